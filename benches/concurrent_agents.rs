@@ -60,6 +60,20 @@ struct EnsureProjectResponse {
     slug: String,
 }
 
+#[derive(serde::Deserialize)]
+struct HealthResponse {
+    archive_queue: ArchiveQueueStats,
+}
+
+#[derive(serde::Deserialize)]
+struct ArchiveQueueStats {
+    pending: u64,
+    committed: u64,
+    batches: u64,
+    max_lag_ms: u64,
+    avg_lag_ms: u64,
+}
+
 // --- Stats ---
 
 #[derive(Debug)]
@@ -216,6 +230,28 @@ async fn wait_for_server(client: &Client, base_url: &str) -> Result<()> {
     anyhow::bail!("Server did not become ready at {}", base_url)
 }
 
+/// Wait for the server's archive queue to drain after a message phase.
+///
+/// Returns the drain time and the queue's final stats.
+async fn wait_for_archive_drain(
+    client: &Client,
+    base_url: &str,
+) -> Result<(Duration, ArchiveQueueStats)> {
+    let start = Instant::now();
+    loop {
+        let health: HealthResponse = client
+            .get(format!("{}/health", base_url))
+            .send()
+            .await?
+            .json()
+            .await?;
+        if health.archive_queue.pending == 0 || start.elapsed() > Duration::from_secs(60) {
+            return Ok((start.elapsed(), health.archive_queue));
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+}
+
 /// Write result to file
 fn write_result(file: &mut std::fs::File, stats: &BenchmarkStats) -> std::io::Result<()> {
     writeln!(
@@ -577,6 +613,27 @@ async fn main() -> Result<()> {
         )
         .await?;
         write_result(&mut file, &stats)?;
+
+        // Sends return before their Git commit; report how far commits lag
+        let (drain, queue) = wait_for_archive_drain(&client, &config.base_url).await?;
+        println!(
+            "  Archive: {} commits for {} messages | drain: {}ms | lag avg {}ms, max {}ms",
+            queue.batches,
+            queue.committed,
+            drain.as_millis(),
+            queue.avg_lag_ms,
+            queue.max_lag_ms
+        );
+        writeln!(file)?;
+        writeln!(
+            file,
+            "**Archive commit lag**: avg {}ms, max {}ms; drained {}ms after load ({} messages in {} commits)",
+            queue.avg_lag_ms,
+            queue.max_lag_ms,
+            drain.as_millis(),
+            queue.committed,
+            queue.batches
+        )?;
     } else {
         println!("Skipping Phase 4: Could not create project.");
     }
@@ -1,8 +1,16 @@
-use config::{Config, File};
+use config::builder::DefaultState;
+use config::{Config, ConfigBuilder, ValueKind};
 use serde::{Deserialize, Serialize};
 use std::env;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+mod check;
+
+pub use check::{ConfigIssue, ConfigReport, ConfigSource, EffectiveValue};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct AppConfig {
     pub server: ServerConfig,
     pub mcp: McpConfig,
@@ -10,9 +18,26 @@ pub struct AppConfig {
     pub escalation: EscalationConfig,
     #[serde(default)]
     pub quota: QuotaConfig,
+    #[serde(default)]
+    pub reservations: ReservationConfig,
+    #[serde(default)]
+    pub messages: MessageConfig,
+    #[serde(default)]
+    pub rate_limit: RateLimitConfig,
+    #[serde(default)]
+    pub archive: ArchiveConfig,
+    #[serde(default)]
+    pub database: DatabaseConfig,
+    #[serde(default)]
+    pub projects: ProjectsConfig,
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    #[serde(default)]
+    pub backup: BackupConfig,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct ServerConfig {
     pub host: String,
     pub port: u16,
@@ -20,12 +45,71 @@ pub struct ServerConfig {
     /// Enable serving embedded web UI (when compiled with with-web-ui feature)
     #[serde(default = "default_serve_ui")]
     pub serve_ui: bool,
+    /// Seconds in-flight requests get to finish after a shutdown signal
+    #[serde(default = "default_shutdown_timeout_secs")]
+    pub shutdown_timeout_secs: u64,
+    /// Serve the OpenAPI document at /api/openapi.json and Swagger UI at /api/docs
+    #[serde(default = "default_api_docs")]
+    pub api_docs: bool,
+    /// Cross-origin access for dashboards on other origins; unset sends no
+    /// CORS headers, so browsers only allow same-origin calls
+    #[serde(default)]
+    pub cors: Option<CorsConfig>,
+    /// Externally visible base URL of the web UI (e.g.
+    /// `https://mail.example.com`); when set, message and thread permalinks
+    /// are absolute URLs under it
+    #[serde(default)]
+    pub public_url: Option<String>,
+}
+
+/// Browsers on these origins may call the HTTP API.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CorsConfig {
+    /// Exact origins (`https://dash.example.com`, `http://localhost:3000`),
+    /// or just `"*"` to allow any origin
+    #[serde(default)]
+    pub allowed_origins: Vec<String>,
+    /// Extra request headers callers may send; `authorization` and
+    /// `content-type` are always allowed
+    #[serde(default)]
+    pub allowed_headers: Vec<String>,
+    /// Seconds browsers may cache a preflight response
+    #[serde(default = "default_cors_max_age_secs")]
+    pub max_age_secs: u64,
+    /// Allow cookies and HTTP auth on cross-origin requests
+    /// (`Access-Control-Allow-Credentials`); not allowed with `"*"`
+    #[serde(default)]
+    pub allow_credentials: bool,
+}
+
+fn default_cors_max_age_secs() -> u64 {
+    600
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: Vec::new(),
+            allowed_headers: Vec::new(),
+            max_age_secs: default_cors_max_age_secs(),
+            allow_credentials: false,
+        }
+    }
 }
 
 fn default_serve_ui() -> bool {
     true
 }
 
+fn default_api_docs() -> bool {
+    true
+}
+
+fn default_shutdown_timeout_secs() -> u64 {
+    10
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
 #[serde(rename_all = "snake_case")]
 pub enum EscalationMode {
@@ -36,6 +120,7 @@ pub enum EscalationMode {
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct EscalationConfig {
     #[serde(default)]
     pub ack_ttl_enabled: bool,
@@ -115,9 +200,20 @@ pub enum ProjectIdentityMode {
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct McpConfig {
     pub transport: String,
     pub port: u16,
+    /// Address the HTTP/SSE transport binds to (use 0.0.0.0 for all interfaces)
+    #[serde(default = "default_mcp_host")]
+    pub host: String,
+    /// Externally visible base URL (e.g. behind a TLS-terminating proxy);
+    /// when unset, URLs are derived from X-Forwarded-Proto/Host
+    #[serde(default)]
+    pub public_url: Option<String>,
+    /// Seconds between SSE keep-alive pings; 0 disables them
+    #[serde(default = "default_sse_keep_alive_secs")]
+    pub sse_keep_alive_secs: u64,
     /// Enable worktree-related features (build slots, pre-commit guard)
     #[serde(default)]
     pub worktrees_enabled: bool,
@@ -132,11 +228,20 @@ pub struct McpConfig {
     pub project_identity_remote: String,
 }
 
+fn default_mcp_host() -> String {
+    "127.0.0.1".to_string()
+}
+
+fn default_sse_keep_alive_secs() -> u64 {
+    15
+}
+
 fn default_project_identity_remote() -> String {
     "origin".to_string()
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct QuotaConfig {
     #[serde(default)]
     pub enabled: bool,
@@ -164,6 +269,415 @@ impl Default for QuotaConfig {
     }
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ReservationConfig {
+    /// Longest TTL a file reservation may be renewed for, in seconds
+    #[serde(default = "default_reservation_max_ttl_seconds")]
+    pub max_ttl_seconds: u64,
+    /// How often the server grants queued reservations whose blockers
+    /// expired, in seconds
+    #[serde(default = "default_queue_sweep_interval_seconds")]
+    pub queue_sweep_interval_seconds: u64,
+}
+
+fn default_reservation_max_ttl_seconds() -> u64 {
+    8 * 60 * 60 // 8 hours
+}
+
+fn default_queue_sweep_interval_seconds() -> u64 {
+    15
+}
+
+impl Default for ReservationConfig {
+    fn default() -> Self {
+        Self {
+            max_ttl_seconds: default_reservation_max_ttl_seconds(),
+            queue_sweep_interval_seconds: default_queue_sweep_interval_seconds(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MessageConfig {
+    /// How long after sending a message its sender may still recall it, in seconds
+    #[serde(default = "default_recall_window_seconds")]
+    pub recall_window_seconds: u64,
+    /// How often the server checks for scheduled messages that are due, in seconds
+    #[serde(default = "default_scheduler_interval_seconds")]
+    pub scheduler_interval_seconds: u64,
+    /// How often the server prunes messages past their project's retention
+    /// policy, in seconds
+    #[serde(default = "default_retention_sweep_interval_seconds")]
+    pub retention_sweep_interval_seconds: u64,
+    /// Longest allowed subject, in characters
+    #[serde(default = "default_max_subject_chars")]
+    pub max_subject_chars: usize,
+    /// Largest allowed message body, in bytes
+    #[serde(default = "default_max_body_bytes")]
+    pub max_body_bytes: usize,
+    /// Most recipients (to + cc + bcc) a message may have
+    #[serde(default = "default_max_recipients")]
+    pub max_recipients: usize,
+    /// Whether cc recipients of an `ack_required` message owe an
+    /// acknowledgement too. Direct recipients always do; bcc recipients never.
+    #[serde(default)]
+    pub pending_acks_include_cc: bool,
+}
+
+fn default_recall_window_seconds() -> u64 {
+    5 * 60 // 5 minutes
+}
+
+fn default_scheduler_interval_seconds() -> u64 {
+    1
+}
+
+fn default_retention_sweep_interval_seconds() -> u64 {
+    60 * 60 // 1 hour
+}
+
+fn default_max_subject_chars() -> usize {
+    500
+}
+
+fn default_max_body_bytes() -> usize {
+    256 * 1024 // 256 KB
+}
+
+fn default_max_recipients() -> usize {
+    100
+}
+
+impl Default for MessageConfig {
+    fn default() -> Self {
+        Self {
+            recall_window_seconds: default_recall_window_seconds(),
+            scheduler_interval_seconds: default_scheduler_interval_seconds(),
+            retention_sweep_interval_seconds: default_retention_sweep_interval_seconds(),
+            max_subject_chars: default_max_subject_chars(),
+            max_body_bytes: default_max_body_bytes(),
+            max_recipients: default_max_recipients(),
+            pending_acks_include_cc: false,
+        }
+    }
+}
+
+/// Messaging between projects.
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectsConfig {
+    /// Slugs of projects whose agents may address agents in other projects
+    /// as `slug::agent-name`. Unrestricted (root) contexts may always do so.
+    #[serde(default)]
+    pub allowed_peers: Vec<String>,
+}
+
+impl ProjectsConfig {
+    /// Whether agents of the project with `slug` may message other projects.
+    pub fn allows_peer_messages(&self, slug: &str) -> bool {
+        self.allowed_peers.iter().any(|peer| peer == slug)
+    }
+}
+
+/// Mailbox events a hook can run on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+pub enum HookEvent {
+    #[serde(rename = "message.created")]
+    MessageCreated,
+    #[serde(rename = "message.acked")]
+    MessageAcked,
+    #[serde(rename = "reservation.conflict")]
+    ReservationConflict,
+}
+
+impl HookEvent {
+    /// Every hookable event, in documentation order.
+    pub const ALL: [HookEvent; 3] = [
+        Self::MessageCreated,
+        Self::MessageAcked,
+        Self::ReservationConflict,
+    ];
+
+    /// Wire name, matching the event bus and SSE `event:` field.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MessageCreated => "message.created",
+            Self::MessageAcked => "message.acked",
+            Self::ReservationConflict => "reservation.conflict",
+        }
+    }
+}
+
+impl std::str::FromStr for HookEvent {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|event| event.as_str() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Self::ALL.iter().map(HookEvent::as_str).collect();
+                format!(
+                    "unknown event '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Narrows a hook to some of its event's occurrences; unset fields match
+/// everything.
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct HookFilter {
+    /// Message importance (`low`, `normal`, `high`, `urgent`); events
+    /// without an importance never match
+    #[serde(default)]
+    pub importance: Option<String>,
+    /// Project slug
+    #[serde(default)]
+    pub project: Option<String>,
+}
+
+/// A local command run when a mailbox event fires, like a Git hook.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HookConfig {
+    pub event: HookEvent,
+    /// Program and arguments, run without a shell; the event arrives as
+    /// JSON on stdin
+    pub command: Vec<String>,
+    #[serde(default)]
+    pub filter: HookFilter,
+    /// Seconds the command may run before it is killed
+    #[serde(default = "default_hook_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+/// Commands run on mailbox events (`[[hooks.on]]` entries).
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HooksConfig {
+    /// Most hook commands running at once; further events wait their turn
+    #[serde(default = "default_hook_max_concurrent")]
+    pub max_concurrent: usize,
+    #[serde(default)]
+    pub on: Vec<HookConfig>,
+}
+
+fn default_hook_timeout_secs() -> u64 {
+    10
+}
+
+fn default_hook_max_concurrent() -> usize {
+    4
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: default_hook_max_concurrent(),
+            on: Vec::new(),
+        }
+    }
+}
+
+/// Where project archives live on disk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArchiveLayout {
+    /// Default: every project in one repository at the archive root
+    #[default]
+    Shared,
+    /// One repository per project at `{archive_root}/{slug}/`
+    PerProject,
+}
+
+/// What a message's outbox and inbox files contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum MailboxCopies {
+    /// Default: a full copy of the message; Git stores it as one blob
+    #[default]
+    Full,
+    /// A small frontmatter file naming the canonical message path
+    Pointer,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ArchiveConfig {
+    /// Write and commit message files before `send_message` returns instead of
+    /// handing them to the background archive queue
+    #[serde(default)]
+    pub sync: bool,
+    /// One shared Git repository or one per project
+    #[serde(default)]
+    pub layout: ArchiveLayout,
+    /// Full copies or pointer files in agent outboxes and inboxes
+    #[serde(default)]
+    pub mailbox_copies: MailboxCopies,
+    /// Jobs the archive queue holds before senders wait for the writer
+    #[serde(default = "default_archive_queue_capacity")]
+    pub queue_capacity: usize,
+    /// Most messages the writer puts into a single Git commit
+    #[serde(default = "default_archive_batch_size")]
+    pub batch_size: usize,
+}
+
+fn default_archive_queue_capacity() -> usize {
+    1024
+}
+
+fn default_archive_batch_size() -> usize {
+    64
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            sync: false,
+            layout: ArchiveLayout::default(),
+            mailbox_copies: MailboxCopies::default(),
+            queue_capacity: default_archive_queue_capacity(),
+            batch_size: default_archive_batch_size(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DatabaseConfig {
+    /// Read-only connections queries are spread over; writes always go
+    /// through a single writer connection. 0 sends reads to the writer too.
+    #[serde(default = "default_read_pool_size")]
+    pub read_pool_size: usize,
+    /// Attempts for a write that keeps failing with "database is locked"
+    /// after `busy_timeout`, including the first one. 1 disables retries.
+    #[serde(default = "default_busy_retry_attempts")]
+    pub busy_retry_attempts: u32,
+    /// Backoff before the first retry; doubles per attempt, with jitter
+    #[serde(default = "default_busy_retry_base_ms")]
+    pub busy_retry_base_ms: u64,
+    /// Upper bound for a single backoff
+    #[serde(default = "default_busy_retry_max_ms")]
+    pub busy_retry_max_ms: u64,
+}
+
+fn default_read_pool_size() -> usize {
+    4
+}
+
+fn default_busy_retry_attempts() -> u32 {
+    5
+}
+
+fn default_busy_retry_base_ms() -> u64 {
+    50
+}
+
+fn default_busy_retry_max_ms() -> u64 {
+    2000
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            read_pool_size: default_read_pool_size(),
+            busy_retry_attempts: default_busy_retry_attempts(),
+            busy_retry_base_ms: default_busy_retry_base_ms(),
+            busy_retry_max_ms: default_busy_retry_max_ms(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitConfig {
+    #[serde(default = "default_rate_limit_enabled")]
+    pub enabled: bool,
+    /// Sustained requests per second allowed per agent (or client IP)
+    #[serde(default = "default_rate_limit_rps")]
+    pub requests_per_second: u32,
+    /// Requests allowed in a burst before throttling kicks in
+    #[serde(default = "default_rate_limit_burst")]
+    pub burst: u32,
+}
+
+fn default_rate_limit_enabled() -> bool {
+    true
+}
+
+fn default_rate_limit_rps() -> u32 {
+    1000
+}
+
+fn default_rate_limit_burst() -> u32 {
+    2000
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_rate_limit_enabled(),
+            requests_per_second: default_rate_limit_rps(),
+            burst: default_rate_limit_burst(),
+        }
+    }
+}
+
+/// Scheduled database snapshots.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BackupConfig {
+    /// Seconds between snapshots; 0 (the default) takes none on a schedule
+    #[serde(default)]
+    pub interval_seconds: u64,
+    /// Where snapshots are written; unset uses `backups` beside the database
+    /// file
+    #[serde(default)]
+    pub directory: Option<PathBuf>,
+    /// Snapshots kept in `directory`, newest first; 0 keeps them all
+    #[serde(default = "default_backup_retention_count")]
+    pub retention_count: usize,
+}
+
+fn default_backup_retention_count() -> usize {
+    7
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            interval_seconds: 0,
+            directory: None,
+            retention_count: default_backup_retention_count(),
+        }
+    }
+}
+
+impl RateLimitConfig {
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        Self {
+            enabled: std::env::var("RATE_LIMIT_ENABLED")
+                .map(|v| v == "true")
+                .unwrap_or(defaults.enabled),
+            requests_per_second: std::env::var("RATE_LIMIT_RPS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.requests_per_second),
+            burst: std::env::var("RATE_LIMIT_BURST")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(defaults.burst),
+        }
+    }
+}
+
 impl McpConfig {
     /// Check if worktree features should be active
     /// Returns true if either WORKTREES_ENABLED or GIT_IDENTITY_ENABLED is set
@@ -179,6 +693,12 @@ impl McpConfig {
                 .ok()
                 .and_then(|p| p.parse().ok())
                 .unwrap_or(3000),
+            host: std::env::var("MOUCHAK_MCP__HOST").unwrap_or_else(|_| default_mcp_host()),
+            public_url: std::env::var("MOUCHAK_MCP__PUBLIC_URL").ok(),
+            sse_keep_alive_secs: std::env::var("MOUCHAK_MCP__SSE_KEEP_ALIVE_SECS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or_else(default_sse_keep_alive_secs),
             worktrees_enabled: parse_bool_env("WORKTREES_ENABLED"),
             git_identity_enabled: parse_bool_env("GIT_IDENTITY_ENABLED"),
             project_identity_mode: std::env::var("PROJECT_IDENTITY_MODE")
@@ -199,9 +719,14 @@ impl McpConfig {
 
 /// Parse boolean environment variable with truthy value detection
 fn parse_bool_env(key: &str) -> bool {
-    std::env::var(key)
-        .map(|v| matches!(v.to_lowercase().as_str(), "1" | "true" | "yes" | "t" | "y"))
-        .unwrap_or(false)
+    std::env::var(key).is_ok_and(|v| parse_bool(&v))
+}
+
+fn parse_bool(value: &str) -> bool {
+    matches!(
+        value.to_lowercase().as_str(),
+        "1" | "true" | "yes" | "t" | "y"
+    )
 }
 
 impl Default for AppConfig {
@@ -212,10 +737,17 @@ impl Default for AppConfig {
                 port: 8765,
                 auth_hmac: None,
                 serve_ui: true,
+                shutdown_timeout_secs: default_shutdown_timeout_secs(),
+                api_docs: default_api_docs(),
+                cors: None,
+                public_url: None,
             },
             mcp: McpConfig {
                 transport: "stdio".to_string(),
                 port: 3000,
+                host: default_mcp_host(),
+                public_url: None,
+                sse_keep_alive_secs: default_sse_keep_alive_secs(),
                 worktrees_enabled: false,
                 git_identity_enabled: false,
                 project_identity_mode: ProjectIdentityMode::default(),
@@ -223,6 +755,14 @@ impl Default for AppConfig {
             },
             escalation: EscalationConfig::default(),
             quota: QuotaConfig::default(),
+            reservations: ReservationConfig::default(),
+            messages: MessageConfig::default(),
+            rate_limit: RateLimitConfig::default(),
+            archive: ArchiveConfig::default(),
+            database: DatabaseConfig::default(),
+            projects: ProjectsConfig::default(),
+            hooks: HooksConfig::default(),
+            backup: BackupConfig::default(),
         }
     }
 }
@@ -234,92 +774,241 @@ impl AppConfig {
     /// 1. `PORT` / `HOST` env vars (12-factor standard)
     /// 2. Config files (`config/default.toml`, `config/{run_mode}.toml`)
     /// 3. Hardcoded defaults (port 8765)
+    ///
+    /// Unknown keys and mistyped values are errors; the message lists every
+    /// problem with the file or env var it came from. Use [`AppConfig::check`]
+    /// for the full report.
     pub fn load() -> Result<Self, config::ConfigError> {
-        let run_mode = env::var("RUN_MODE").unwrap_or_else(|_| "development".into());
+        let report = Self::check(None);
+        if report.is_ok() {
+            Ok(report.config)
+        } else {
+            Err(report.error())
+        }
+    }
 
-        let mut builder = Config::builder()
-            // Start with defaults
-            .set_default("server.host", "0.0.0.0")?
-            .set_default("server.port", 8765)?
-            .set_default("server.serve_ui", true)?
-            .set_default("mcp.transport", "stdio")?
-            .set_default("mcp.port", 3000)?
-            .set_default("mcp.worktrees_enabled", false)?
-            .set_default("mcp.git_identity_enabled", false)?
-            .set_default("mcp.project_identity_mode", "dir")?
-            .set_default("mcp.project_identity_remote", "origin")?
-            .set_default("escalation.ack_ttl_enabled", false)?
-            .set_default("escalation.ack_ttl_seconds", 1800_i64)?
-            .set_default("escalation.escalation_enabled", false)?
-            .set_default("escalation.escalation_mode", "log")?
-            .set_default("escalation.scan_interval_seconds", 300_i64)?
-            // Merge in config files
-            .add_source(File::with_name("config/default").required(false))
-            .add_source(File::with_name(&format!("config/{}", run_mode)).required(false));
+    /// Validate the merged configuration (files + env overrides) without
+    /// failing.
+    ///
+    /// `file` replaces the usual config file search. The report's `config`
+    /// is usable even when there are issues: offending keys are dropped and
+    /// their defaults apply.
+    pub fn check(file: Option<&Path>) -> ConfigReport {
+        let lookup = |var: &str| env::var(var).ok();
+        let files = match file {
+            Some(path) => vec![path.to_path_buf()],
+            None => config_files(&lookup),
+        };
+        check::run(files, &lookup)
+    }
+}
 
-        // Add user config file from ~/.mouchak-mail/config.toml
-        if let Ok(home) = env::var("HOME") {
-            let path = std::path::Path::new(&home)
-                .join(".mouchak-mail")
-                .join("config.toml");
-            builder = builder.add_source(File::from(path).required(false));
-        }
+/// Extensions `File::with_name` would try, in the order we pick them.
+const CONFIG_EXTENSIONS: &[&str] = &["toml", "json", "yaml", "yml", "ini", "ron", "json5"];
 
-        // 12-factor app standard: PORT and HOST env vars
-        if let Ok(port) = env::var("PORT") {
-            if let Ok(p) = port.parse::<i64>() {
-                builder = builder.set_override("server.port", p)?;
-            }
-        }
-        if let Ok(host) = env::var("HOST") {
-            builder = builder.set_override("server.host", host)?;
-        }
+/// Config files that exist, lowest priority first.
+fn config_files(lookup: &dyn Fn(&str) -> Option<String>) -> Vec<PathBuf> {
+    let run_mode = lookup("RUN_MODE").unwrap_or_else(|| "development".into());
+    let mut files: Vec<PathBuf> = ["config/default".to_string(), format!("config/{}", run_mode)]
+        .iter()
+        .filter_map(|stem| {
+            CONFIG_EXTENSIONS
+                .iter()
+                .map(|ext| Path::new(stem).with_extension(ext))
+                .find(|path| path.is_file())
+        })
+        .collect();
 
-        if parse_bool_env("ACK_TTL_ENABLED") {
-            builder = builder.set_override("escalation.ack_ttl_enabled", true)?;
-        }
-        if let Ok(ttl) = env::var("ACK_TTL_SECONDS") {
-            if let Ok(secs) = ttl.parse::<i64>() {
-                builder = builder.set_override("escalation.ack_ttl_seconds", secs)?;
-            }
-        }
-        if parse_bool_env("ACK_ESCALATION_ENABLED") {
-            builder = builder.set_override("escalation.escalation_enabled", true)?;
-        }
-        if let Ok(mode) = env::var("ACK_ESCALATION_MODE") {
-            builder = builder.set_override("escalation.escalation_mode", mode)?;
-        }
-        if let Ok(interval) = env::var("ACK_SCAN_INTERVAL_SECONDS") {
-            if let Ok(secs) = interval.parse::<i64>() {
-                builder = builder.set_override("escalation.scan_interval_seconds", secs)?;
-            }
+    // User config file from ~/.mouchak-mail/config.toml
+    if let Some(home) = lookup("HOME") {
+        let path = Path::new(&home).join(".mouchak-mail").join("config.toml");
+        if path.is_file() {
+            files.push(path);
         }
+    }
+    files
+}
 
-        if parse_bool_env("QUOTA_ENABLED") {
-            builder = builder.set_override("quota.enabled", true)?;
-        }
-        if let Ok(bytes) = env::var("QUOTA_ATTACHMENTS_LIMIT_BYTES") {
-            if let Ok(limit) = bytes.parse::<u64>() {
-                builder = builder.set_override("quota.attachments_limit_bytes", limit)?;
-            }
+/// Built-in values for keys the structs have no serde default for.
+fn config_defaults() -> Result<ConfigBuilder<DefaultState>, config::ConfigError> {
+    Config::builder()
+        .set_default("server.host", "0.0.0.0")?
+        .set_default("server.port", 8765)?
+        .set_default("server.serve_ui", true)?
+        .set_default("server.shutdown_timeout_secs", 10_i64)?
+        .set_default("server.api_docs", true)?
+        .set_default("mcp.transport", "stdio")?
+        .set_default("mcp.port", 3000)?
+        .set_default("mcp.host", "127.0.0.1")?
+        .set_default("mcp.sse_keep_alive_secs", 15_i64)?
+        .set_default("mcp.worktrees_enabled", false)?
+        .set_default("mcp.git_identity_enabled", false)?
+        .set_default("mcp.project_identity_mode", "dir")?
+        .set_default("mcp.project_identity_remote", "origin")?
+        .set_default("escalation.ack_ttl_enabled", false)?
+        .set_default("escalation.ack_ttl_seconds", 1800_i64)?
+        .set_default("escalation.escalation_enabled", false)?
+        .set_default("escalation.escalation_mode", "log")?
+        .set_default("escalation.scan_interval_seconds", 300_i64)
+}
+
+/// A config key set from an environment variable.
+struct EnvOverride {
+    var: &'static str,
+    key: &'static str,
+    value: ValueKind,
+}
+
+/// Collects the env overrides that are set, remembering which variable
+/// supplied each key.
+struct EnvOverrides<'a> {
+    lookup: &'a dyn Fn(&str) -> Option<String>,
+    set: Vec<EnvOverride>,
+}
+
+impl EnvOverrides<'_> {
+    fn push(&mut self, var: &'static str, key: &'static str, value: impl Into<ValueKind>) {
+        self.set.push(EnvOverride {
+            var,
+            key,
+            value: value.into(),
+        });
+    }
+
+    fn string(&mut self, var: &'static str, key: &'static str) {
+        if let Some(value) = (self.lookup)(var) {
+            self.push(var, key, value);
         }
-        if let Ok(count) = env::var("QUOTA_INBOX_LIMIT_COUNT") {
-            if let Ok(limit) = count.parse::<u64>() {
-                builder = builder.set_override("quota.inbox_limit_count", limit)?;
-            }
+    }
+
+    /// Ignored when the value does not parse
+    fn parsed<T: FromStr + Into<ValueKind>>(&mut self, var: &'static str, key: &'static str) {
+        if let Some(value) = (self.lookup)(var).and_then(|v| v.parse::<T>().ok()) {
+            self.push(var, key, value);
         }
+    }
 
-        if let Ok(mode) = env::var("PROJECT_IDENTITY_MODE") {
-            builder = builder.set_override("mcp.project_identity_mode", mode)?;
+    /// Truthy values turn the setting on; anything else leaves it alone
+    fn flag(&mut self, var: &'static str, key: &'static str) {
+        if (self.lookup)(var).is_some_and(|v| parse_bool(&v)) {
+            self.push(var, key, true);
         }
-        if let Ok(remote) = env::var("PROJECT_IDENTITY_REMOTE") {
-            builder = builder.set_override("mcp.project_identity_remote", remote)?;
+    }
+
+    /// `"true"` turns the setting on, any other value turns it off
+    fn switch(&mut self, var: &'static str, key: &'static str) {
+        if let Some(value) = (self.lookup)(var) {
+            self.push(var, key, value == "true");
         }
+    }
 
-        builder.build()?.try_deserialize()
+    /// Comma-separated list
+    fn list(&mut self, var: &'static str, key: &'static str) {
+        if let Some(value) = (self.lookup)(var) {
+            let items: Vec<String> = value
+                .split(',')
+                .map(str::trim)
+                .filter(|item| !item.is_empty())
+                .map(str::to_string)
+                .collect();
+            self.push(var, key, items);
+        }
     }
 }
 
+/// Env overrides that are set, in application order.
+fn env_overrides(lookup: &dyn Fn(&str) -> Option<String>) -> Vec<EnvOverride> {
+    let mut env = EnvOverrides {
+        lookup,
+        set: Vec::new(),
+    };
+
+    // 12-factor app standard: PORT and HOST env vars
+    env.parsed::<i64>("PORT", "server.port");
+    env.string("HOST", "server.host");
+    env.parsed::<u64>("SHUTDOWN_TIMEOUT_SECS", "server.shutdown_timeout_secs");
+    env.switch("API_DOCS_ENABLED", "server.api_docs");
+    env.list("CORS_ALLOWED_ORIGINS", "server.cors.allowed_origins");
+    env.string("PUBLIC_URL", "server.public_url");
+
+    env.flag("ACK_TTL_ENABLED", "escalation.ack_ttl_enabled");
+    env.parsed::<i64>("ACK_TTL_SECONDS", "escalation.ack_ttl_seconds");
+    env.flag("ACK_ESCALATION_ENABLED", "escalation.escalation_enabled");
+    env.string("ACK_ESCALATION_MODE", "escalation.escalation_mode");
+    env.parsed::<i64>(
+        "ACK_SCAN_INTERVAL_SECONDS",
+        "escalation.scan_interval_seconds",
+    );
+
+    env.flag("QUOTA_ENABLED", "quota.enabled");
+    env.parsed::<u64>(
+        "QUOTA_ATTACHMENTS_LIMIT_BYTES",
+        "quota.attachments_limit_bytes",
+    );
+    env.parsed::<u64>("QUOTA_INBOX_LIMIT_COUNT", "quota.inbox_limit_count");
+
+    env.parsed::<u64>(
+        "RESERVATION_MAX_TTL_SECONDS",
+        "reservations.max_ttl_seconds",
+    );
+    env.parsed::<u64>(
+        "RESERVATION_QUEUE_SWEEP_INTERVAL_SECONDS",
+        "reservations.queue_sweep_interval_seconds",
+    );
+
+    env.parsed::<u64>(
+        "MESSAGE_RECALL_WINDOW_SECONDS",
+        "messages.recall_window_seconds",
+    );
+    env.parsed::<u64>(
+        "MESSAGE_SCHEDULER_INTERVAL_SECONDS",
+        "messages.scheduler_interval_seconds",
+    );
+    env.parsed::<u64>(
+        "MESSAGE_RETENTION_SWEEP_INTERVAL_SECONDS",
+        "messages.retention_sweep_interval_seconds",
+    );
+    env.parsed::<u64>("MESSAGE_MAX_SUBJECT_CHARS", "messages.max_subject_chars");
+    env.parsed::<u64>("MESSAGE_MAX_BODY_BYTES", "messages.max_body_bytes");
+    env.parsed::<u64>("MESSAGE_MAX_RECIPIENTS", "messages.max_recipients");
+    env.flag(
+        "MESSAGE_PENDING_ACKS_INCLUDE_CC",
+        "messages.pending_acks_include_cc",
+    );
+
+    env.list("PROJECTS_ALLOWED_PEERS", "projects.allowed_peers");
+
+    env.flag("ARCHIVE_SYNC", "archive.sync");
+    env.string("ARCHIVE_LAYOUT", "archive.layout");
+    env.string("ARCHIVE_MAILBOX_COPIES", "archive.mailbox_copies");
+
+    env.parsed::<u64>("DATABASE_READ_POOL_SIZE", "database.read_pool_size");
+    env.parsed::<u64>(
+        "DATABASE_BUSY_RETRY_ATTEMPTS",
+        "database.busy_retry_attempts",
+    );
+
+    env.parsed::<u64>("BACKUP_INTERVAL_SECONDS", "backup.interval_seconds");
+    env.string("BACKUP_DIRECTORY", "backup.directory");
+    env.parsed::<u64>("BACKUP_RETENTION_COUNT", "backup.retention_count");
+
+    env.switch("RATE_LIMIT_ENABLED", "rate_limit.enabled");
+    env.parsed::<u64>("RATE_LIMIT_RPS", "rate_limit.requests_per_second");
+    env.parsed::<u64>("RATE_LIMIT_BURST", "rate_limit.burst");
+
+    env.string("MOUCHAK_MCP__HOST", "mcp.host");
+    env.string("MOUCHAK_MCP__PUBLIC_URL", "mcp.public_url");
+    env.parsed::<u64>(
+        "MOUCHAK_MCP__SSE_KEEP_ALIVE_SECS",
+        "mcp.sse_keep_alive_secs",
+    );
+
+    env.string("PROJECT_IDENTITY_MODE", "mcp.project_identity_mode");
+    env.string("PROJECT_IDENTITY_REMOTE", "mcp.project_identity_remote");
+
+    env.set
+}
+
 #[cfg(test)]
 #[allow(unsafe_code)]
 mod tests {
@@ -368,6 +1057,9 @@ mod tests {
         let config = McpConfig {
             transport: "stdio".into(),
             port: 3000,
+            host: "127.0.0.1".into(),
+            public_url: None,
+            sse_keep_alive_secs: 15,
             worktrees_enabled: false,
             git_identity_enabled: false,
             project_identity_mode: ProjectIdentityMode::default(),
@@ -397,6 +1089,37 @@ mod tests {
         assert!(config.worktrees_active());
     }
 
+    #[test]
+    fn test_mcp_transport_config_defaults() {
+        let config = AppConfig::default().mcp;
+        assert_eq!(config.host, "127.0.0.1");
+        assert_eq!(config.public_url, None);
+        assert_eq!(config.sse_keep_alive_secs, 15);
+
+        // Configs written before host binding existed still load
+        let parsed: Result<McpConfig, _> = serde_json::from_value(serde_json::json!({
+            "transport": "sse", "port": 3000
+        }));
+        assert!(matches!(
+            parsed,
+            Ok(ref c) if c.host == "127.0.0.1" && c.sse_keep_alive_secs == 15
+        ));
+
+        let parsed: Result<McpConfig, _> = serde_json::from_value(serde_json::json!({
+            "transport": "sse",
+            "port": 3000,
+            "host": "0.0.0.0",
+            "public_url": "https://mail.example.com",
+            "sse_keep_alive_secs": 0
+        }));
+        assert!(matches!(
+            parsed,
+            Ok(ref c) if c.host == "0.0.0.0"
+                && c.public_url.as_deref() == Some("https://mail.example.com")
+                && c.sse_keep_alive_secs == 0
+        ));
+    }
+
     #[test]
     fn test_escalation_config_defaults() {
         let config = EscalationConfig::default();
@@ -407,6 +1130,134 @@ mod tests {
         assert_eq!(config.scan_interval_seconds, 300);
     }
 
+    #[test]
+    fn test_reservation_config_defaults() {
+        let config = ReservationConfig::default();
+        assert_eq!(config.max_ttl_seconds, 8 * 60 * 60);
+
+        // Configs written before the reservations section existed still load
+        let parsed: Result<AppConfig, _> = serde_json::from_value(serde_json::json!({
+            "server": { "host": "0.0.0.0", "port": 8765, "auth_hmac": null },
+            "mcp": { "transport": "stdio", "port": 3000 }
+        }));
+        assert!(matches!(
+            parsed,
+            Ok(ref c) if c.reservations.max_ttl_seconds == 8 * 60 * 60
+        ));
+    }
+
+    #[test]
+    fn test_message_config_defaults() {
+        let config = MessageConfig::default();
+        assert_eq!(config.recall_window_seconds, 300);
+        assert_eq!(config.scheduler_interval_seconds, 1);
+        assert_eq!(config.retention_sweep_interval_seconds, 3600);
+        assert_eq!(config.max_subject_chars, 500);
+        assert_eq!(config.max_body_bytes, 256 * 1024);
+        assert_eq!(config.max_recipients, 100);
+        assert!(!config.pending_acks_include_cc);
+        assert_eq!(AppConfig::default().messages.recall_window_seconds, 300);
+
+        // Configs written before the size limits existed still load
+        let parsed: Result<MessageConfig, _> =
+            serde_json::from_value(serde_json::json!({ "recall_window_seconds": 60 }));
+        assert!(matches!(
+            parsed,
+            Ok(ref c) if c.max_subject_chars == 500 && c.max_recipients == 100
+        ));
+    }
+
+    #[test]
+    fn test_projects_config_peers() {
+        assert!(AppConfig::default().projects.allowed_peers.is_empty());
+
+        let parsed: Result<ProjectsConfig, _> =
+            serde_json::from_value(serde_json::json!({ "allowed_peers": ["frontend", "backend"] }));
+        let config = parsed.unwrap_or_default();
+        assert!(config.allows_peer_messages("backend"));
+        assert!(!config.allows_peer_messages("infra"));
+    }
+
+    #[test]
+    fn test_hooks_config_parses_toml() {
+        assert!(AppConfig::default().hooks.on.is_empty());
+
+        let parsed: HooksConfig = Config::builder()
+            .add_source(config::File::from_str(
+                r#"
+            [[on]]
+            event = "message.created"
+            command = ["tmux", "display-message", "urgent mail"]
+            filter = { importance = "urgent" }
+
+            [[on]]
+            event = "reservation.conflict"
+            command = ["notify-send", "conflict"]
+            timeout_secs = 2
+            "#,
+                config::FileFormat::Toml,
+            ))
+            .build()
+            .and_then(Config::try_deserialize)
+            .unwrap_or_default();
+        assert_eq!(parsed.max_concurrent, 4);
+        assert_eq!(parsed.on.len(), 2);
+        assert_eq!(parsed.on[0].event, HookEvent::MessageCreated);
+        assert_eq!(parsed.on[0].filter.importance.as_deref(), Some("urgent"));
+        assert_eq!(parsed.on[0].timeout_secs, 10);
+        assert_eq!(parsed.on[1].event, HookEvent::ReservationConflict);
+        assert_eq!(parsed.on[1].timeout_secs, 2);
+
+        assert_eq!(
+            "message.acked".parse::<HookEvent>(),
+            Ok(HookEvent::MessageAcked)
+        );
+        assert!("message.read".parse::<HookEvent>().is_err());
+    }
+
+    #[test]
+    fn test_rate_limit_config_defaults() {
+        let config = RateLimitConfig::default();
+        assert!(config.enabled);
+        assert_eq!(config.requests_per_second, 1000);
+        assert_eq!(config.burst, 2000);
+
+        let parsed: Result<RateLimitConfig, _> =
+            serde_json::from_value(serde_json::json!({ "requests_per_second": 5 }));
+        assert!(matches!(
+            parsed,
+            Ok(ref c) if c.enabled && c.requests_per_second == 5 && c.burst == 2000
+        ));
+    }
+
+    #[test]
+    fn test_backup_config_defaults() {
+        let config = AppConfig::default().backup;
+        assert_eq!(config.interval_seconds, 0);
+        assert_eq!(config.directory, None);
+        assert_eq!(config.retention_count, 7);
+
+        let parsed: Result<BackupConfig, _> = serde_json::from_value(serde_json::json!({
+            "interval_seconds": 3600, "directory": "/var/backups/mail"
+        }));
+        assert!(matches!(
+            parsed,
+            Ok(ref c) if c.interval_seconds == 3600
+                && c.directory.as_deref() == Some(Path::new("/var/backups/mail"))
+                && c.retention_count == 7
+        ));
+    }
+
+    #[test]
+    fn test_shutdown_timeout_default() {
+        assert_eq!(AppConfig::default().server.shutdown_timeout_secs, 10);
+
+        let parsed: Result<ServerConfig, _> = serde_json::from_value(serde_json::json!({
+            "host": "0.0.0.0", "port": 8765, "auth_hmac": null
+        }));
+        assert!(matches!(parsed, Ok(ref c) if c.shutdown_timeout_secs == 10));
+    }
+
     #[test]
     fn test_escalation_mode_variants() {
         assert_eq!(EscalationMode::default(), EscalationMode::Log);
@@ -0,0 +1,629 @@
+//! Config validation for `config check` and strict loading.
+//!
+//! Each config file is parsed on its own first so syntax errors name the
+//! file. The merged layers are then deserialized repeatedly: every failure
+//! becomes a [`ConfigIssue`] naming the key and the layer that set it, and
+//! the offending value is dropped (falling back to its default) before the
+//! next attempt, so one run reports every problem.
+
+use super::{AppConfig, EnvOverride, config_defaults, env_overrides};
+use config::{Config, ConfigError, File, Map, Source, Value, ValueKind};
+use serde_path_to_error::Segment;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use strsim::levenshtein;
+
+/// Keys whose values `config check` never prints.
+const SECRET_KEYS: &[&str] = &["server.auth_hmac"];
+
+/// Fixes attempted before giving up on a config that keeps failing.
+const MAX_REPAIRS: usize = 64;
+
+/// Where an effective config value came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigSource {
+    Default,
+    File(PathBuf),
+    /// Environment variable name
+    Env(String),
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::Default => write!(f, "default"),
+            ConfigSource::File(path) => write!(f, "{}", path.display()),
+            ConfigSource::Env(var) => write!(f, "env {}", var),
+        }
+    }
+}
+
+/// One problem found in the configuration.
+#[derive(Debug, Clone)]
+pub struct ConfigIssue {
+    pub source: ConfigSource,
+    /// Dotted key (`server.port`, `hooks.on[0].command`); unset for file
+    /// syntax errors
+    pub key: Option<String>,
+    pub message: String,
+    /// Valid keys or values close to the offending one
+    pub suggestions: Vec<String>,
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: ", self.source)?;
+        if let Some(key) = &self.key {
+            write!(f, "`{}`: ", key)?;
+        }
+        write!(f, "{}", self.message)?;
+        if !self.suggestions.is_empty() {
+            let names: Vec<String> = self
+                .suggestions
+                .iter()
+                .map(|s| format!("`{}`", s))
+                .collect();
+            write!(f, " (did you mean {}?)", names.join(" or "))?;
+        }
+        Ok(())
+    }
+}
+
+/// A config key's final value.
+#[derive(Debug, Clone)]
+pub struct EffectiveValue {
+    pub key: String,
+    /// JSON rendering; secrets are masked
+    pub value: String,
+    pub source: ConfigSource,
+}
+
+/// Result of validating the merged configuration.
+#[derive(Debug, Clone)]
+pub struct ConfigReport {
+    /// Config files that were read, lowest priority first
+    pub files: Vec<PathBuf>,
+    pub issues: Vec<ConfigIssue>,
+    /// Every key of `config`, sorted
+    pub values: Vec<EffectiveValue>,
+    /// Best-effort config: keys with issues are left at their defaults
+    pub config: AppConfig,
+}
+
+impl ConfigReport {
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Every issue in one error, one per line.
+    pub fn error(&self) -> ConfigError {
+        let lines: Vec<String> = self.issues.iter().map(ToString::to_string).collect();
+        ConfigError::Message(format!("invalid configuration:\n  {}", lines.join("\n  ")))
+    }
+
+    /// The config a server may start with.
+    ///
+    /// Issues are fatal once a config file exists; without one they can
+    /// only come from env vars, which are logged and left at their defaults.
+    pub fn for_serve(self) -> Result<AppConfig, ConfigError> {
+        if self.is_ok() {
+            return Ok(self.config);
+        }
+        if !self.files.is_empty() {
+            return Err(self.error());
+        }
+        for issue in &self.issues {
+            tracing::warn!("Ignoring invalid config: {}. Using the default.", issue);
+        }
+        Ok(self.config)
+    }
+}
+
+/// A step in a key path.
+#[derive(Debug, Clone, PartialEq)]
+enum Step {
+    Key(String),
+    Index(usize),
+}
+
+fn dotted(path: &[Step]) -> String {
+    let mut out = String::new();
+    for step in path {
+        match step {
+            Step::Key(key) if out.is_empty() => out.push_str(key),
+            Step::Key(key) => {
+                out.push('.');
+                out.push_str(key);
+            }
+            Step::Index(i) => out.push_str(&format!("[{}]", i)),
+        }
+    }
+    out
+}
+
+fn lookup<'a>(map: &'a Map<String, Value>, path: &[Step]) -> Option<&'a Value> {
+    let (Step::Key(first), rest) = path.split_first()? else {
+        return None;
+    };
+    rest.iter()
+        .try_fold(map.get(first)?, |value, step| match (&value.kind, step) {
+            (ValueKind::Table(table), Step::Key(key)) => table.get(key),
+            (ValueKind::Array(items), Step::Index(i)) => items.get(*i),
+            _ => None,
+        })
+}
+
+/// Replace the value at `path`, or remove it when `replacement` is `None`.
+/// Returns false when there is nothing at `path`.
+fn repair(map: &mut Map<String, Value>, path: &[Step], replacement: Option<Value>) -> bool {
+    let Some((Step::Key(first), rest)) = path.split_first() else {
+        return false;
+    };
+    let Some((last, parents)) = rest.split_last() else {
+        return match replacement {
+            Some(value) => map.insert(first.clone(), value).is_some(),
+            None => map.remove(first).is_some(),
+        };
+    };
+    let mut current = match map.get_mut(first) {
+        Some(value) => value,
+        None => return false,
+    };
+    for step in parents {
+        let next = match (&mut current.kind, step) {
+            (ValueKind::Table(table), Step::Key(key)) => table.get_mut(key),
+            (ValueKind::Array(items), Step::Index(i)) => items.get_mut(*i),
+            _ => None,
+        };
+        current = match next {
+            Some(value) => value,
+            None => return false,
+        };
+    }
+    match (&mut current.kind, last, replacement) {
+        (ValueKind::Table(table), Step::Key(key), Some(value)) => {
+            table.insert(key.clone(), value).is_some()
+        }
+        (ValueKind::Table(table), Step::Key(key), None) => table.remove(key).is_some(),
+        (ValueKind::Array(items), Step::Index(i), Some(value)) if *i < items.len() => {
+            items[*i] = value;
+            true
+        }
+        (ValueKind::Array(items), Step::Index(i), None) if *i < items.len() => {
+            items.remove(*i);
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Parsed layers, lowest priority first.
+struct Layers {
+    defaults: Map<String, Value>,
+    files: Vec<(PathBuf, Map<String, Value>)>,
+    env: Vec<EnvOverride>,
+}
+
+impl Layers {
+    /// The layer that set `path` in the merged config.
+    fn source_of(&self, path: &[Step]) -> ConfigSource {
+        let key = dotted(path);
+        let from_env = self.env.iter().rev().find(|o| {
+            key == o.key
+                || key
+                    .strip_prefix(o.key)
+                    .is_some_and(|rest| rest.starts_with(['.', '[']))
+        });
+        if let Some(o) = from_env {
+            return ConfigSource::Env(o.var.to_string());
+        }
+        self.files
+            .iter()
+            .rev()
+            .find(|(_, map)| lookup(map, path).is_some())
+            .map(|(file, _)| ConfigSource::File(file.clone()))
+            .unwrap_or(ConfigSource::Default)
+    }
+}
+
+fn convert_path(path: &serde_path_to_error::Path) -> Option<Vec<Step>> {
+    path.iter()
+        .map(|segment| match segment {
+            Segment::Map { key } => Some(Step::Key(key.clone())),
+            Segment::Seq { index } => Some(Step::Index(*index)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Message and suggestions for a deserialization error at `path`.
+fn describe(error: &ConfigError, path: &[Step]) -> (String, Vec<String>) {
+    match error {
+        ConfigError::At { error, .. } => describe(error, path),
+        ConfigError::Type {
+            unexpected,
+            expected,
+            ..
+        } => (
+            format!("expected {}, found {}", expected, unexpected),
+            vec![],
+        ),
+        ConfigError::Message(message) => describe_message(message, path),
+        // config-rs joins the field onto its parent without a separator
+        ConfigError::NotFound(key) => {
+            let field = key.rsplit([']', '.']).next().unwrap_or(key);
+            (format!("missing required key `{}`", field), vec![])
+        }
+        other => (other.to_string(), vec![]),
+    }
+}
+
+/// Serde reports unknown names as ``unknown field `x`, expected one of `a`,
+/// `b` ``; turn the candidates into suggestions.
+fn describe_message(message: &str, path: &[Step]) -> (String, Vec<String>) {
+    // config-rs's own wording for unit enum values read from strings
+    if let Some(value) = message
+        .strip_prefix("enum ")
+        .and_then(|rest| rest.split_once(" does not have variant constructor "))
+        .map(|(_, value)| value)
+    {
+        return (format!("unknown value `{}`", value), vec![]);
+    }
+    let kind = if message.starts_with("unknown field") {
+        "key"
+    } else if message.starts_with("unknown variant") {
+        "value"
+    } else {
+        return (message.to_string(), vec![]);
+    };
+    let mut names = message.split('`').skip(1).step_by(2);
+    let Some(unknown) = names.next() else {
+        return (message.to_string(), vec![]);
+    };
+    let candidates: Vec<&str> = names.collect();
+    let mut close: Vec<(&str, usize)> = candidates
+        .iter()
+        .map(|c| (*c, levenshtein(unknown, c)))
+        .filter(|(_, d)| *d <= 2)
+        .collect();
+    close.sort_by_key(|(_, d)| *d);
+
+    let parent = match path.split_last() {
+        Some((_, parent)) if kind == "key" && !parent.is_empty() => format!("{}.", dotted(parent)),
+        _ => String::new(),
+    };
+    let suggestions = close
+        .into_iter()
+        .take(3)
+        .map(|(c, _)| format!("{}{}", parent, c))
+        .collect();
+    let message = if kind == "key" {
+        "unknown key".to_string()
+    } else {
+        format!("unknown value `{}`", unknown)
+    };
+    (message, suggestions)
+}
+
+fn collect(config: Config) -> Result<Map<String, Value>, ConfigError> {
+    config.collect()
+}
+
+/// Flatten a JSON object into sorted `(dotted key, path, rendered value)`.
+fn flatten(
+    value: &serde_json::Value,
+    path: &mut Vec<Step>,
+    out: &mut Vec<(String, Vec<Step>, String)>,
+) {
+    match value {
+        serde_json::Value::Object(fields) => {
+            for (key, field) in fields {
+                path.push(Step::Key(key.clone()));
+                flatten(field, path, out);
+                path.pop();
+            }
+        }
+        leaf => out.push((dotted(path), path.clone(), leaf.to_string())),
+    }
+}
+
+/// Validate `files` merged with the env overrides `env` reports.
+pub(super) fn run(files: Vec<PathBuf>, env: &dyn Fn(&str) -> Option<String>) -> ConfigReport {
+    let mut issues = Vec::new();
+
+    let defaults = match config_defaults().and_then(|b| b.build()).and_then(collect) {
+        Ok(map) => map,
+        Err(e) => {
+            issues.push(ConfigIssue {
+                source: ConfigSource::Default,
+                key: None,
+                message: e.to_string(),
+                suggestions: vec![],
+            });
+            Map::new()
+        }
+    };
+
+    let mut parsed = Vec::new();
+    for file in &files {
+        let map = Config::builder()
+            .add_source(File::from(file.as_path()))
+            .build()
+            .and_then(collect);
+        match map {
+            Ok(map) => parsed.push((file.clone(), map)),
+            Err(e) => issues.push(ConfigIssue {
+                source: ConfigSource::File(file.clone()),
+                key: None,
+                message: e.to_string(),
+                suggestions: vec![],
+            }),
+        }
+    }
+
+    let layers = Layers {
+        defaults,
+        files: parsed,
+        env: env_overrides(env),
+    };
+
+    let mut merged = merge(&layers).unwrap_or_else(|e| {
+        issues.push(ConfigIssue {
+            source: ConfigSource::Default,
+            key: None,
+            message: e.to_string(),
+            suggestions: vec![],
+        });
+        Map::new()
+    });
+
+    let mut repaired = Vec::new();
+    let mut config = None;
+    for _ in 0..MAX_REPAIRS {
+        let root = Value::new(None, ValueKind::Table(merged.clone()));
+        match serde_path_to_error::deserialize::<_, AppConfig>(root) {
+            Ok(c) => {
+                config = Some(c);
+                break;
+            }
+            Err(e) => {
+                let path = convert_path(e.path()).unwrap_or_default();
+                let key = dotted(&path);
+                // Dropping a bad key can leave its table incomplete; that is
+                // the same mistake, so the table goes without a second issue
+                let follows_earlier = !key.is_empty()
+                    && repaired.iter().any(|r: &String| {
+                        r.strip_prefix(key.as_str())
+                            .is_some_and(|rest| rest.starts_with(['.', '[']))
+                    });
+                if !follows_earlier {
+                    let (message, suggestions) = describe(e.inner(), &path);
+                    issues.push(ConfigIssue {
+                        source: layers.source_of(&path),
+                        key: (!path.is_empty()).then(|| key.clone()),
+                        message,
+                        suggestions,
+                    });
+                }
+                let default = lookup(&layers.defaults, &path).cloned();
+                if !repair(&mut merged, &path, default) {
+                    break;
+                }
+                repaired.push(key);
+            }
+        }
+    }
+
+    let config = config.unwrap_or_default();
+    let values = effective_values(&config, &layers, &repaired);
+    ConfigReport {
+        files,
+        issues,
+        values,
+        config,
+    }
+}
+
+fn merge(layers: &Layers) -> Result<Map<String, Value>, ConfigError> {
+    let mut builder = config_defaults()?;
+    for (file, _) in &layers.files {
+        builder = builder.add_source(File::from(Path::new(file)));
+    }
+    for o in &layers.env {
+        builder = builder.set_override(o.key, o.value.clone())?;
+    }
+    collect(builder.build()?)
+}
+
+fn effective_values(
+    config: &AppConfig,
+    layers: &Layers,
+    repaired: &[String],
+) -> Vec<EffectiveValue> {
+    let Ok(json) = serde_json::to_value(config) else {
+        return vec![];
+    };
+    let mut leaves = Vec::new();
+    flatten(&json, &mut Vec::new(), &mut leaves);
+    leaves.sort_by(|a, b| a.0.cmp(&b.0));
+    leaves
+        .into_iter()
+        .map(|(key, path, value)| {
+            let source = if repaired.contains(&key) {
+                ConfigSource::Default
+            } else {
+                layers.source_of(&path)
+            };
+            let value = if SECRET_KEYS.contains(&key.as_str()) && value != "null" {
+                "\"********\"".to_string()
+            } else {
+                value
+            };
+            EffectiveValue { key, value, source }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+    use std::collections::HashMap;
+
+    /// Writes `contents` to a fresh file named after the test.
+    fn config_file(name: &str, contents: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mouchak-config-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(format!("{}.toml", name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    fn check(files: Vec<PathBuf>, env: &[(&str, &str)]) -> ConfigReport {
+        let env: HashMap<String, String> = env
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        run(files, &|var| env.get(var).cloned())
+    }
+
+    fn source_of<'a>(report: &'a ConfigReport, key: &str) -> &'a ConfigSource {
+        &report.values.iter().find(|v| v.key == key).unwrap().source
+    }
+
+    #[test]
+    fn test_unknown_keys_suggest_nearby_keys() {
+        let file = config_file(
+            "unknown",
+            "[server]\nprot = 9000\n\n[[hooks.on]]\nevent = \"message.created\"\ncomand = [\"notify\"]\n",
+        );
+        let report = check(vec![file.clone()], &[]);
+
+        let mut issues = report.issues.clone();
+        issues.sort_by(|a, b| a.key.cmp(&b.key));
+        assert_eq!(issues.len(), 2, "{:?}", issues);
+        assert_eq!(issues[0].key.as_deref(), Some("hooks.on[0].comand"));
+        assert_eq!(issues[0].suggestions, vec!["hooks.on[0].command"]);
+        assert_eq!(issues[1].key.as_deref(), Some("server.prot"));
+        assert_eq!(issues[1].source, ConfigSource::File(file.clone()));
+        assert_eq!(issues[1].message, "unknown key");
+        assert_eq!(issues[1].suggestions, vec!["server.port"]);
+        assert_eq!(
+            issues[1].to_string(),
+            format!(
+                "{}: `server.prot`: unknown key (did you mean `server.port`?)",
+                file.display()
+            )
+        );
+
+        // The typo does not leak into the effective config
+        assert_eq!(report.config.server.port, 8765);
+        assert!(report.error().to_string().contains("server.prot"));
+        assert!(report.for_serve().is_err());
+    }
+
+    #[test]
+    fn test_type_mismatches_fall_back_to_defaults() {
+        let file = config_file(
+            "types",
+            "[server]\nport = \"eighty\"\n\n[messages]\nmax_recipients = 50\nrecall_window_seconds = \"soon\"\n",
+        );
+        let report = check(vec![file.clone()], &[]);
+
+        let mut issues = report.issues.clone();
+        issues.sort_by(|a, b| a.key.cmp(&b.key));
+        assert_eq!(issues.len(), 2, "{:?}", issues);
+        assert_eq!(
+            issues[0].key.as_deref(),
+            Some("messages.recall_window_seconds")
+        );
+        assert!(
+            issues[0].message.contains("found string \"soon\""),
+            "{}",
+            issues[0].message
+        );
+        assert_eq!(issues[1].key.as_deref(), Some("server.port"));
+        assert_eq!(issues[1].source, ConfigSource::File(file.clone()));
+        assert!(
+            issues[1].message.starts_with("expected an integer"),
+            "{}",
+            issues[1].message
+        );
+
+        // Good keys from the same file still apply; bad ones use defaults
+        assert_eq!(report.config.messages.max_recipients, 50);
+        assert_eq!(report.config.server.port, 8765);
+        assert_eq!(source_of(&report, "server.port"), &ConfigSource::Default);
+        assert_eq!(
+            source_of(&report, "messages.max_recipients"),
+            &ConfigSource::File(file)
+        );
+    }
+
+    #[test]
+    fn test_syntax_errors_name_the_file() {
+        let file = config_file("syntax", "[server\nport = 1\n");
+        let report = check(vec![file.clone()], &[]);
+
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].source, ConfigSource::File(file));
+        assert_eq!(report.issues[0].key, None);
+        assert_eq!(report.config.server.port, 8765);
+    }
+
+    #[test]
+    fn test_env_overrides_take_precedence() {
+        let base = config_file(
+            "env-base",
+            "[server]\nport = 9000\nhost = \"10.0.0.1\"\nauth_hmac = \"s3cret\"\n",
+        );
+        let local = config_file("env-local", "[server]\nhost = \"10.0.0.2\"\n");
+        let report = check(
+            vec![base.clone(), local.clone()],
+            &[("PORT", "9100"), ("ARCHIVE_SYNC", "yes")],
+        );
+
+        assert!(report.is_ok(), "{:?}", report.issues);
+        assert_eq!(report.config.server.port, 9100);
+        assert_eq!(report.config.server.host, "10.0.0.2");
+        assert!(report.config.archive.sync);
+        assert_eq!(
+            source_of(&report, "server.port"),
+            &ConfigSource::Env("PORT".into())
+        );
+        assert_eq!(
+            source_of(&report, "archive.sync"),
+            &ConfigSource::Env("ARCHIVE_SYNC".into())
+        );
+        // Later files win over earlier ones
+        assert_eq!(
+            source_of(&report, "server.host"),
+            &ConfigSource::File(local)
+        );
+        assert_eq!(source_of(&report, "mcp.port"), &ConfigSource::Default);
+
+        let secret = report
+            .values
+            .iter()
+            .find(|v| v.key == "server.auth_hmac")
+            .unwrap();
+        assert_eq!(secret.source, ConfigSource::File(base));
+        assert!(!secret.value.contains("s3cret"));
+    }
+
+    #[test]
+    fn test_env_issues_without_config_file_only_warn() {
+        let report = check(vec![], &[("ARCHIVE_LAYOUT", "sideways")]);
+
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(
+            report.issues[0].source,
+            ConfigSource::Env("ARCHIVE_LAYOUT".into())
+        );
+        assert_eq!(report.issues[0].key.as_deref(), Some("archive.layout"));
+        assert_eq!(report.issues[0].message, "unknown value `sideways`");
+        let config = report.for_serve().unwrap();
+        assert_eq!(config.archive.layout, crate::config::ArchiveLayout::Shared);
+    }
+}
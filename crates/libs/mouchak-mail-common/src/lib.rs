@@ -1,5 +1,7 @@
 pub mod config;
 pub mod error;
+pub mod output;
+pub mod permalink;
 pub mod robot;
 pub mod tracing;
 
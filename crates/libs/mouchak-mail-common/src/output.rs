@@ -0,0 +1,147 @@
+//! Output mode for CLI commands.
+//!
+//! Handlers build a serializable result and hand it to [`OutputMode::emit`];
+//! the mode picked with `--output` (or the `AM_OUTPUT` env var) decides
+//! whether it is printed as human-readable text or as a single JSON object.
+
+use serde::Serialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// Environment variable selecting the output mode.
+pub const OUTPUT_ENV: &str = "AM_OUTPUT";
+
+/// How command results and errors are printed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputMode {
+    /// Human-oriented text (default)
+    #[default]
+    Human,
+    /// One JSON object on stdout; errors as JSON on stderr
+    Json,
+}
+
+impl FromStr for OutputMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "human" | "text" => Ok(Self::Human),
+            "json" => Ok(Self::Json),
+            other => Err(format!(
+                "invalid output mode '{}': expected human or json",
+                other
+            )),
+        }
+    }
+}
+
+impl fmt::Display for OutputMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Human => "human",
+            Self::Json => "json",
+        })
+    }
+}
+
+/// A command result that can be printed in either mode.
+pub trait CommandOutput: Serialize {
+    /// Text printed in [`OutputMode::Human`].
+    fn human(&self) -> String;
+}
+
+/// Error body printed to stderr in [`OutputMode::Json`].
+#[derive(Debug, Serialize)]
+struct ErrorOutput {
+    error: String,
+}
+
+impl OutputMode {
+    /// Render a result for this mode.
+    pub fn render<T: CommandOutput>(self, value: &T) -> serde_json::Result<String> {
+        match self {
+            Self::Human => Ok(value.human()),
+            Self::Json => serde_json::to_string(value),
+        }
+    }
+
+    /// Print a result to stdout.
+    pub fn emit<T: CommandOutput>(self, value: &T) -> serde_json::Result<()> {
+        println!("{}", self.render(value)?);
+        Ok(())
+    }
+
+    /// Render an error, including its cause chain.
+    pub fn render_error(self, err: &anyhow::Error) -> String {
+        match self {
+            Self::Human => format!("Error: {:#}", err),
+            Self::Json => serde_json::to_string(&ErrorOutput {
+                error: format!("{:#}", err),
+            })
+            .unwrap_or_else(|_| r#"{"error":"unknown error"}"#.to_string()),
+        }
+    }
+
+    /// Print an error to stderr and exit with status 1.
+    pub fn exit_with_error(self, err: &anyhow::Error) -> ! {
+        eprintln!("{}", self.render_error(err));
+        std::process::exit(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::unwrap_used)]
+
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Created {
+        id: i64,
+        slug: String,
+    }
+
+    impl CommandOutput for Created {
+        fn human(&self) -> String {
+            format!("Created project '{}' with ID {}", self.slug, self.id)
+        }
+    }
+
+    #[test]
+    fn test_parse_output_mode() {
+        assert_eq!("json".parse::<OutputMode>().unwrap(), OutputMode::Json);
+        assert_eq!("JSON".parse::<OutputMode>().unwrap(), OutputMode::Json);
+        assert_eq!("human".parse::<OutputMode>().unwrap(), OutputMode::Human);
+        assert!("yaml".parse::<OutputMode>().is_err());
+    }
+
+    #[test]
+    fn test_render_both_modes() {
+        let created = Created {
+            id: 7,
+            slug: "demo".to_string(),
+        };
+        assert_eq!(
+            OutputMode::Human.render(&created).unwrap(),
+            "Created project 'demo' with ID 7"
+        );
+        assert_eq!(
+            OutputMode::Json.render(&created).unwrap(),
+            r#"{"id":7,"slug":"demo"}"#
+        );
+    }
+
+    #[test]
+    fn test_render_error() {
+        let err = anyhow::anyhow!("not found").context("loading project");
+        assert_eq!(
+            OutputMode::Human.render_error(&err),
+            "Error: loading project: not found"
+        );
+        assert_eq!(
+            OutputMode::Json.render_error(&err),
+            r#"{"error":"loading project: not found"}"#
+        );
+    }
+}
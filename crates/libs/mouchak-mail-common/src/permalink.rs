@@ -0,0 +1,90 @@
+//! Canonical links into the web UI
+//!
+//! A message lives at `/p/{slug}/m/{id}` and a thread at
+//! `/p/{slug}/t/{thread_id}`; the web UI router serves both. Links are
+//! absolute under `server.public_url` when it is configured and relative
+//! paths otherwise, since only the operator knows where the UI is reachable.
+
+/// Path of a message's permalink.
+pub fn message_path(project_slug: &str, message_id: i64) -> String {
+    format!("/p/{}/m/{}", encode_segment(project_slug), message_id)
+}
+
+/// Path of a thread's permalink.
+pub fn thread_path(project_slug: &str, thread_id: &str) -> String {
+    format!(
+        "/p/{}/t/{}",
+        encode_segment(project_slug),
+        encode_segment(thread_id)
+    )
+}
+
+/// Path of a project's page.
+pub fn project_path(project_slug: &str) -> String {
+    format!("/projects/{}", encode_segment(project_slug))
+}
+
+/// `path` under `public_url`, or `path` itself without one.
+pub fn resolve(public_url: Option<&str>, path: &str) -> String {
+    absolute(public_url, path).unwrap_or_else(|| path.to_string())
+}
+
+/// `path` under `public_url`; `None` when no public URL is configured.
+pub fn absolute(public_url: Option<&str>, path: &str) -> Option<String> {
+    let base = public_url
+        .map(|url| url.trim().trim_end_matches('/'))
+        .filter(|url| !url.is_empty())?;
+    Some(format!("{}{}", base, path))
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters, so thread
+/// IDs containing `/`, `?` or spaces stay one path segment.
+fn encode_segment(segment: &str) -> String {
+    let mut encoded = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(message_path("backend", 42), "/p/backend/m/42");
+        assert_eq!(thread_path("backend", "TKT-1"), "/p/backend/t/TKT-1");
+        assert_eq!(
+            thread_path("backend", "review/auth flow?"),
+            "/p/backend/t/review%2Fauth%20flow%3F"
+        );
+        assert_eq!(project_path("backend"), "/projects/backend");
+    }
+
+    #[test]
+    fn test_with_public_url() {
+        let path = message_path("backend", 7);
+        assert_eq!(
+            resolve(Some("https://mail.example.com/"), &path),
+            "https://mail.example.com/p/backend/m/7"
+        );
+        assert_eq!(
+            absolute(Some("https://example.com/mail"), &path).as_deref(),
+            Some("https://example.com/mail/p/backend/m/7")
+        );
+    }
+
+    #[test]
+    fn test_without_public_url() {
+        let path = thread_path("backend", "TKT-1");
+        assert_eq!(resolve(None, &path), "/p/backend/t/TKT-1");
+        assert_eq!(resolve(Some("  "), &path), "/p/backend/t/TKT-1");
+        assert_eq!(absolute(None, &path), None);
+        assert_eq!(absolute(Some(""), &path), None);
+    }
+}
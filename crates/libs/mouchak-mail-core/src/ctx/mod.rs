@@ -2,13 +2,23 @@
 //!
 //! The [`Ctx`] struct provides request-scoped context for identifying
 //! the user making a request. This is used for audit logging and
-//! future RBAC (Role-Based Access Control) implementation.
+//! project-level authorization: a context built from a scoped token only
+//! reaches the projects its claims allow.
+//!
+//! A context may also name an [`Actor`], the agent or user a write is
+//! attributed to. Archive commits use it as the Git author.
+//!
+//! A context built from an agent token ([`Ctx::for_agent`]) is bound to that
+//! agent: [`Ctx::ensure_acting_as`] rejects sends and reservations made
+//! under any other agent's name.
+
+use crate::types::AgentId;
 
 /// Request context containing user identification.
 ///
 /// `Ctx` is passed to all BMC methods to identify the user making
-/// the request. Currently used for audit logging, with future plans
-/// for role-based access control.
+/// the request. Project-scoped BMC methods check
+/// [`Ctx::can_access_project`] and fail with `Error::Forbidden` otherwise.
 ///
 /// # Examples
 ///
@@ -26,6 +36,70 @@
 #[derive(Clone, Debug)]
 pub struct Ctx {
     user_id: i64,
+    agent_name: Option<String>,
+    allowed_projects: Vec<String>,
+    actor: Option<Actor>,
+    authenticated_agent: Option<AgentId>,
+}
+
+/// Project scope that grants access to every project.
+pub const ALL_PROJECTS: &str = "*";
+
+/// Who caused a write, as shown in the archive's `git log`.
+///
+/// # Examples
+///
+/// ```
+/// use mouchak_mail_core::ctx::Actor;
+///
+/// let actor = Actor::agent("BlueLake", "backend-api");
+/// assert_eq!(actor.to_string(), "BlueLake <bluelake@backend-api.local>");
+///
+/// let user = Actor::user("alice@example.com");
+/// assert_eq!(user.email(), "alice@example.com");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Actor {
+    name: String,
+    email: String,
+}
+
+impl Actor {
+    /// An agent writing to one of its projects.
+    pub fn agent(name: &str, project_slug: &str) -> Self {
+        Actor {
+            name: name.to_string(),
+            email: format!("{}@{}.local", name.to_lowercase(), project_slug),
+        }
+    }
+
+    /// An authenticated caller, identified by e.g. a JWT subject. Identities
+    /// that are not email addresses get a placeholder domain.
+    pub fn user(identity: &str) -> Self {
+        let email = if identity.contains('@') {
+            identity.to_string()
+        } else {
+            format!("{}@users.local", identity)
+        };
+        Actor {
+            name: identity.to_string(),
+            email,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+}
+
+impl std::fmt::Display for Actor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} <{}>", self.name, self.email)
+    }
 }
 
 impl Ctx {
@@ -44,7 +118,7 @@ impl Ctx {
     /// assert_eq!(ctx.user_id(), 0);
     /// ```
     pub fn root_ctx() -> Self {
-        Ctx { user_id: 0 }
+        Self::new(0)
     }
 
     /// Creates a new context for a specific user.
@@ -62,7 +136,84 @@ impl Ctx {
     /// assert_eq!(ctx.user_id(), 123);
     /// ```
     pub fn new(user_id: i64) -> Self {
-        Ctx { user_id }
+        Ctx {
+            user_id,
+            agent_name: None,
+            allowed_projects: vec![ALL_PROJECTS.to_string()],
+            actor: None,
+            authenticated_agent: None,
+        }
+    }
+
+    /// Creates a context restricted to the given project slugs.
+    ///
+    /// Pass [`ALL_PROJECTS`] (`"*"`) in `allowed_projects` to allow every
+    /// project. An empty list allows none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mouchak_mail_core::ctx::Ctx;
+    ///
+    /// let ctx = Ctx::scoped(0, Some("BlueLake".into()), vec!["project-a".into()]);
+    /// assert!(ctx.can_access_project("project-a"));
+    /// assert!(!ctx.can_access_project("project-b"));
+    /// assert_eq!(ctx.agent_name(), Some("BlueLake"));
+    /// ```
+    pub fn scoped(user_id: i64, agent_name: Option<String>, allowed_projects: Vec<String>) -> Self {
+        Ctx {
+            user_id,
+            agent_name,
+            allowed_projects,
+            actor: None,
+            authenticated_agent: None,
+        }
+    }
+
+    /// Creates a context authenticated as one agent, scoped to its project.
+    ///
+    /// Writes are attributed to the agent, and [`Ctx::ensure_acting_as`]
+    /// only accepts that agent.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mouchak_mail_core::ctx::Ctx;
+    /// use mouchak_mail_core::types::AgentId;
+    ///
+    /// let ctx = Ctx::for_agent(AgentId::new(7), "BlueLake", "backend-api");
+    /// assert_eq!(ctx.authenticated_agent(), Some(AgentId::new(7)));
+    /// assert!(ctx.can_access_project("backend-api"));
+    /// assert!(!ctx.can_access_project("frontend"));
+    /// assert!(ctx.ensure_acting_as(AgentId::new(7), "BlueLake").is_ok());
+    /// assert!(ctx.ensure_acting_as(AgentId::new(8), "GreenCastle").is_err());
+    /// ```
+    pub fn for_agent(agent_id: AgentId, agent_name: &str, project_slug: &str) -> Self {
+        Ctx {
+            user_id: 0,
+            agent_name: Some(agent_name.to_string()),
+            allowed_projects: vec![project_slug.to_string()],
+            actor: Some(Actor::agent(agent_name, project_slug)),
+            authenticated_agent: Some(agent_id),
+        }
+    }
+
+    /// Returns this context with writes attributed to `actor`.
+    ///
+    /// Access scope is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use mouchak_mail_core::ctx::{Actor, Ctx};
+    ///
+    /// let ctx = Ctx::root_ctx().with_actor(Actor::agent("BlueLake", "backend-api"));
+    /// assert_eq!(ctx.actor().map(|a| a.name()), Some("BlueLake"));
+    /// assert!(ctx.is_unrestricted());
+    /// ```
+    pub fn with_actor(mut self, actor: Actor) -> Self {
+        self.actor = Some(actor);
+        self
     }
 
     /// Returns the user ID associated with this context.
@@ -73,4 +224,55 @@ impl Ctx {
     pub fn user_id(&self) -> i64 {
         self.user_id
     }
+
+    /// Returns the agent identity carried by this context, if any.
+    pub fn agent_name(&self) -> Option<&str> {
+        self.agent_name.as_deref()
+    }
+
+    /// Returns the agent this context was authenticated as by an agent
+    /// token, if any.
+    pub fn authenticated_agent(&self) -> Option<AgentId> {
+        self.authenticated_agent
+    }
+
+    /// Checks that this context may act as the agent `agent_id`, named
+    /// `agent_name`.
+    ///
+    /// Contexts not bound to an agent (root, admin tokens, unauthenticated
+    /// local use) may act as anyone.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::AgentIdentityMismatch` if the context is bound to a
+    /// different agent.
+    pub fn ensure_acting_as(&self, agent_id: AgentId, agent_name: &str) -> crate::Result<()> {
+        match self.authenticated_agent {
+            Some(bound) if bound != agent_id => Err(crate::Error::AgentIdentityMismatch {
+                authenticated: self.agent_name.clone().unwrap_or_default(),
+                claimed: agent_name.to_string(),
+            }),
+            _ => Ok(()),
+        }
+    }
+
+    /// Returns who writes made with this context are attributed to, if known.
+    pub fn actor(&self) -> Option<&Actor> {
+        self.actor.as_ref()
+    }
+
+    /// Returns the project slugs this context may access.
+    pub fn allowed_projects(&self) -> &[String] {
+        &self.allowed_projects
+    }
+
+    /// Returns true if this context may access every project.
+    pub fn is_unrestricted(&self) -> bool {
+        self.allowed_projects.iter().any(|p| p == ALL_PROJECTS)
+    }
+
+    /// Returns true if this context may access the project with `slug`.
+    pub fn can_access_project(&self, slug: &str) -> bool {
+        self.is_unrestricted() || self.allowed_projects.iter().any(|p| p == slug)
+    }
 }
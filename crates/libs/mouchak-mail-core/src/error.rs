@@ -42,25 +42,44 @@ use thiserror::Error;
 /// ## External Errors
 /// Errors from external dependencies are automatically converted using `#[from]`:
 /// - [`Error::Libsql`] - Database errors from libsql
+/// - [`Error::DatabaseBusy`] - Write still locked out after every retry
 /// - [`Error::Git2`] - Git repository errors
 /// - [`Error::SerdeJson`] - JSON serialization/deserialization errors
 /// - [`Error::Io`] - Standard I/O errors
+/// - [`Error::ArchiveWrite`] - I/O error writing a named archive file
 ///
 /// ## Internal Errors
 /// Generic errors for common failure scenarios:
 /// - [`Error::NotFound`] - Generic entity not found
 /// - [`Error::InvalidInput`] - Validation failures
 /// - [`Error::AuthError`] - Authentication failures
+/// - [`Error::Forbidden`] - Project outside the caller's scope
+/// - [`Error::CrossProjectForbidden`] - Sender's project may not message other projects
+/// - [`Error::AgentIdentityMismatch`] - Agent token used to act as another agent
 ///
 /// ## Model-Specific Errors
 /// Entity-specific not-found errors with identifiers:
 /// - [`Error::ProjectNotFound`] - Project lookup failed
+/// - [`Error::SlugConflict`] - Project slug already taken
 /// - [`Error::AgentNotFound`] - Agent lookup failed
 /// - [`Error::MessageNotFound`] - Message lookup failed
+/// - [`Error::MessagePruned`] - Message was removed by the retention policy
+/// - [`Error::ThreadNotFound`] - Thread has no messages
+/// - [`Error::NotMessageSender`] - Recall attempted by someone other than the sender
+/// - [`Error::RecallWindowExpired`] - Recall attempted after the recall window
+/// - [`Error::MessageRecalled`] - Action on a message its sender recalled
 /// - [`Error::FileReservationNotFound`] - File reservation lookup failed
+/// - [`Error::FileReservationInactive`] - File reservation released or expired
 /// - [`Error::ProductNotFound`] - Product lookup failed
 /// - [`Error::MacroNotFound`] - Macro lookup failed
 /// - [`Error::BuildSlotNotFound`] - Build slot lookup failed
+/// - [`Error::TemplateNotFound`] - Message template lookup failed
+/// - [`Error::TemplateNameTaken`] - Template name already used in the project
+/// - [`Error::LabelNotFound`] - Label lookup failed
+/// - [`Error::LabelNameTaken`] - Label name already used in the project
+/// - [`Error::GroupNotFound`] - Agent group lookup failed
+/// - [`Error::GroupNameTaken`] - Group name already used by a group or agent in the project
+/// - [`Error::GroupEmpty`] - Message addressed to a group with no members
 #[derive(Debug, Error, AsRefStr)]
 pub enum Error {
     // -- External errors from dependencies
@@ -70,6 +89,17 @@ pub enum Error {
     #[error("Libsql Error: {0}")]
     Libsql(#[from] libsql::Error),
 
+    /// Database stayed busy/locked through every retry.
+    ///
+    /// Returned by [`crate::store::retry::execute_with_retry`]; `source` is
+    /// the error from the last attempt.
+    #[error("Database busy after {attempts} attempts")]
+    DatabaseBusy {
+        attempts: u32,
+        #[source]
+        source: libsql::Error,
+    },
+
     /// Git repository error.
     ///
     /// Automatically converted from [`git2::Error`] via `From`.
@@ -88,6 +118,17 @@ pub enum Error {
     #[error("IO Error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// Writing one archive file failed.
+    ///
+    /// `path` is relative to the archive repository, so the caller can tell
+    /// which recipient's copy was not written.
+    #[error("Failed to write archive file {path}: {source}")]
+    ArchiveWrite {
+        path: String,
+        #[source]
+        source: std::io::Error,
+    },
+
     /// Age encryption error.
     #[error("Encryption Error: {0}")]
     EncryptionError(String),
@@ -126,6 +167,33 @@ pub enum Error {
     #[error("Authentication failed")]
     AuthError,
 
+    /// Authorization failure.
+    ///
+    /// Returned when the request context is not allowed to access the
+    /// project. The contained string is the project slug.
+    #[error("Access to project denied: {0}")]
+    Forbidden(String),
+
+    /// Cross-project message from a project not allowed to send one.
+    ///
+    /// Returned when a message addresses an agent in another project and the
+    /// sender's project is not in `projects.allowed_peers`. The contained
+    /// string is the sender's project slug.
+    #[error("Project '{0}' may not message agents in other projects")]
+    CrossProjectForbidden(String),
+
+    /// Request authenticated as one agent but acting as another.
+    ///
+    /// Returned when a context bound to an agent token sends or reserves
+    /// files under a different agent's name.
+    #[error("Authenticated as agent '{authenticated}' but acting as '{claimed}'")]
+    AgentIdentityMismatch {
+        /// Agent the token belongs to
+        authenticated: String,
+        /// Agent named in the request
+        claimed: String,
+    },
+
     // -- Model-specific not-found errors
     /// Project not found by slug.
     ///
@@ -136,6 +204,13 @@ pub enum Error {
         suggestions: Vec<String>,
     },
 
+    /// Project slug already taken.
+    ///
+    /// Returned by strict project creation when another project already
+    /// uses the slug; the contained string is the slug.
+    #[error("Project slug '{0}' is already taken")]
+    SlugConflict(String),
+
     /// Agent not found by name.
     ///
     /// Includes optional suggestions for similar agent names.
@@ -151,12 +226,53 @@ pub enum Error {
     #[error("Message not found: {0}")]
     MessageNotFound(i64),
 
+    /// Message removed from the database by the project's retention policy.
+    ///
+    /// The contained i64 is the message ID; the message is still in the Git
+    /// archive.
+    #[error("Message {0} was pruned by the retention policy; it remains in the Git archive")]
+    MessagePruned(i64),
+
+    /// Thread not found in a project.
+    ///
+    /// The contained string is the thread ID that has no messages.
+    #[error("Thread not found: {0}")]
+    ThreadNotFound(String),
+
+    /// Message action restricted to the original sender.
+    ///
+    /// The contained i64 is the message ID.
+    #[error("Only the sender can recall message {0}")]
+    NotMessageSender(i64),
+
+    /// Message recall attempted after the configured recall window.
+    ///
+    /// See `messages.recall_window_seconds` in the app config.
+    #[error("Recall window of {window_seconds}s has passed for message {message_id}")]
+    RecallWindowExpired {
+        message_id: i64,
+        window_seconds: u64,
+    },
+
+    /// Message was recalled by its sender and can no longer be forwarded.
+    ///
+    /// The contained i64 is the message ID.
+    #[error("Message {0} was recalled by its sender")]
+    MessageRecalled(i64),
+
     /// File reservation not found.
     ///
     /// The contained string is the file path that was not found.
     #[error("FileReservation not found: {0}")]
     FileReservationNotFound(String),
 
+    /// File reservation exists but is no longer active.
+    ///
+    /// Returned when renewing a reservation that was released or has
+    /// already expired; the holder must re-reserve the path instead.
+    #[error("FileReservation not active: {0}")]
+    FileReservationInactive(String),
+
     /// Product not found by slug.
     ///
     /// The contained string is the product slug that was not found.
@@ -175,6 +291,54 @@ pub enum Error {
     #[error("Build slot not found: {0}")]
     BuildSlotNotFound(i64),
 
+    /// Message template not found by ID.
+    ///
+    /// The contained i64 is the template ID that was not found.
+    #[error("Template not found: {0}")]
+    TemplateNotFound(i64),
+
+    /// Message template name already used in the project.
+    ///
+    /// Template names are unique per project; the contained string is the
+    /// conflicting name.
+    #[error("A template named '{0}' already exists in this project")]
+    TemplateNameTaken(String),
+
+    /// Label not found in the project.
+    ///
+    /// The contained string is the label ID or name that was looked up.
+    #[error("Label not found: {0}")]
+    LabelNotFound(String),
+
+    /// Label name already used in the project.
+    ///
+    /// Label names are unique per project ignoring case; the contained
+    /// string is the conflicting name.
+    #[error("A label named '{0}' already exists in this project")]
+    LabelNameTaken(String),
+
+    /// Agent group not found in the project.
+    ///
+    /// The contained string is the group name that was looked up.
+    #[error("Group not found: {0}")]
+    GroupNotFound(String),
+
+    /// Agent group name already used in the project.
+    ///
+    /// Group names are unique per project ignoring case and may not shadow
+    /// an agent's name; `taken_by` says which ("a group" or "an agent").
+    #[error("'{name}' is already the name of {taken_by} in this project")]
+    GroupNameTaken {
+        name: String,
+        taken_by: &'static str,
+    },
+
+    /// A message was addressed to a group that has no members.
+    ///
+    /// The contained string is the group name.
+    #[error("Group '{0}' has no members")]
+    GroupEmpty(String),
+
     /// Lock acquisition timeout.
     ///
     /// Returned when a file lock cannot be acquired within the timeout period.
@@ -0,0 +1,187 @@
+//! # Live Event Bus
+//!
+//! In-process publish/subscribe channel for mailbox changes. BMCs publish
+//! [`MailEvent`]s after a write commits, and transports (e.g. the SSE
+//! endpoint in lib-server) subscribe to forward them to clients.
+//!
+//! The bus keeps a bounded history of recent events so a reconnecting
+//! client can replay whatever it missed via `Last-Event-ID`.
+//!
+//! ## Example
+//!
+//! ```
+//! use mouchak_mail_core::events::{EventBus, MailEventKind};
+//!
+//! let bus = EventBus::new(16);
+//! let mut rx = bus.subscribe();
+//! let id = bus.publish(MailEventKind::MessageCreated, "my-project", serde_json::json!({"id": 1}));
+//! assert_eq!(rx.try_recv().unwrap().id, id);
+//! ```
+
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use tokio::sync::{broadcast, watch};
+
+/// Default number of events retained for replay (and broadcast buffer size).
+pub const DEFAULT_EVENT_CAPACITY: usize = 256;
+
+/// Kind of mailbox change carried by a [`MailEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MailEventKind {
+    #[serde(rename = "message.created")]
+    MessageCreated,
+    #[serde(rename = "message.read")]
+    MessageRead,
+    #[serde(rename = "message.recalled")]
+    MessageRecalled,
+    #[serde(rename = "message.acked")]
+    MessageAcked,
+    #[serde(rename = "reservation.created")]
+    ReservationCreated,
+    #[serde(rename = "reservation.released")]
+    ReservationReleased,
+    #[serde(rename = "reservation.queued")]
+    ReservationQueued,
+    #[serde(rename = "reservation.granted")]
+    ReservationGranted,
+    #[serde(rename = "reservation.transferred")]
+    ReservationTransferred,
+    /// A reservation was granted over paths another agent holds (advisory).
+    #[serde(rename = "reservation.conflict")]
+    ReservationConflict,
+}
+
+impl MailEventKind {
+    /// Wire name, also used as the SSE `event:` field.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::MessageCreated => "message.created",
+            Self::MessageRead => "message.read",
+            Self::MessageRecalled => "message.recalled",
+            Self::MessageAcked => "message.acked",
+            Self::ReservationCreated => "reservation.created",
+            Self::ReservationReleased => "reservation.released",
+            Self::ReservationQueued => "reservation.queued",
+            Self::ReservationGranted => "reservation.granted",
+            Self::ReservationTransferred => "reservation.transferred",
+            Self::ReservationConflict => "reservation.conflict",
+        }
+    }
+}
+
+/// A single mailbox change.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MailEvent {
+    /// Monotonic sequence number, unique for the lifetime of the bus.
+    pub id: u64,
+    #[serde(rename = "type")]
+    pub kind: MailEventKind,
+    /// Project the change belongs to, so clients can filter.
+    pub project_slug: String,
+    /// Event-specific payload.
+    pub data: serde_json::Value,
+    /// RFC3339 publish time.
+    pub ts: String,
+}
+
+/// Broadcast channel plus bounded replay history.
+///
+/// Receivers are plain [`broadcast::Receiver`]s; dropping one unsubscribes
+/// it, so disconnected clients never leak channel slots.
+#[derive(Debug)]
+pub struct EventBus {
+    sender: broadcast::Sender<MailEvent>,
+    history: Mutex<VecDeque<MailEvent>>,
+    next_id: AtomicU64,
+    capacity: usize,
+    /// Flipped on shutdown so long-lived subscribers (SSE streams) can end.
+    closed: watch::Sender<bool>,
+}
+
+impl EventBus {
+    /// Creates a bus retaining up to `capacity` events for replay.
+    pub fn new(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let (sender, _) = broadcast::channel(capacity);
+        Self {
+            sender,
+            history: Mutex::new(VecDeque::with_capacity(capacity)),
+            next_id: AtomicU64::new(1),
+            capacity,
+            closed: watch::Sender::new(false),
+        }
+    }
+
+    /// Publishes an event to all current subscribers and the replay history.
+    ///
+    /// Returns the assigned event ID. Publishing with no subscribers is not
+    /// an error.
+    pub fn publish(&self, kind: MailEventKind, project_slug: &str, data: serde_json::Value) -> u64 {
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+
+        // Assign the ID under the history lock so history stays ordered.
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let event = MailEvent {
+            id,
+            kind,
+            project_slug: project_slug.to_string(),
+            data,
+            ts: chrono::Utc::now().to_rfc3339(),
+        };
+
+        if history.len() == self.capacity {
+            history.pop_front();
+        }
+        history.push_back(event.clone());
+
+        let _ = self.sender.send(event);
+        id
+    }
+
+    /// Subscribes to events published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<MailEvent> {
+        self.sender.subscribe()
+    }
+
+    /// Returns retained events with an ID greater than `last_id`, oldest first.
+    pub fn replay_since(&self, last_id: u64) -> Vec<MailEvent> {
+        let history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        history.iter().filter(|e| e.id > last_id).cloned().collect()
+    }
+
+    /// ID of the most recently published event (0 if none).
+    pub fn latest_id(&self) -> u64 {
+        self.next_id.load(Ordering::SeqCst).saturating_sub(1)
+    }
+
+    /// Number of live subscribers.
+    pub fn receiver_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+
+    /// Signals subscribers that the server is shutting down.
+    ///
+    /// Publishing still works afterwards; this only wakes [`closed`](Self::closed).
+    pub fn close(&self) {
+        self.closed.send_replace(true);
+    }
+
+    /// Whether [`close`](Self::close) has been called.
+    pub fn is_closed(&self) -> bool {
+        *self.closed.borrow()
+    }
+
+    /// Resolves once the bus is closed.
+    pub async fn closed(&self) {
+        let mut rx = self.closed.subscribe();
+        let _ = rx.wait_for(|closed| *closed).await;
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_CAPACITY)
+    }
+}
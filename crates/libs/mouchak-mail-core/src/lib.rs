@@ -17,6 +17,9 @@
 //! - [`model`]: All BMC controllers and data models
 //! - [`store`]: Low-level database and Git operations
 //! - [`ctx`]: Request context for RBAC
+//! - [`events`]: Live event bus for mailbox changes
+//! - `testing`: Fixture builder and temp-dir `ModelManager` for tests, behind
+//!   the `test-utils` feature (dev-dependencies only)
 //!
 //! ## Example
 //!
@@ -42,12 +45,19 @@ pub mod ctx;
 /// Error types and Result alias for lib-core operations.
 pub mod error;
 
+/// Live event bus for streaming mailbox changes to clients.
+pub mod events;
+
 /// Backend Model Controllers (BMC) and data models for all entities.
 pub mod model;
 
 /// Low-level storage operations for database and Git.
 pub mod store;
 
+/// Test fixtures for this crate and downstream crates' tests.
+#[cfg(feature = "test-utils")]
+pub mod testing;
+
 /// Strong newtypes for domain identifiers.
 pub mod types;
 
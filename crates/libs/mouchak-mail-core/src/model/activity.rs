@@ -32,8 +32,16 @@
 use crate::Result;
 use crate::ctx::Ctx;
 use crate::model::ModelManager;
+use crate::model::agent::{Agent, AgentBmc};
+use crate::model::agent_capabilities::{AgentCapability, AgentCapabilityBmc};
+use crate::model::file_reservation::{FileReservation, FileReservationBmc};
+use crate::model::message::{MessageBmc, PendingAck};
+use crate::types::ProjectId;
+use crate::utils::{parse_timestamp, parse_timestamp_opt};
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use utoipa::ToSchema;
 
 /// A single activity item in the unified feed.
 ///
@@ -50,7 +58,7 @@ use serde_json::Value;
 /// - `description` - Optional detail text
 /// - `metadata` - Optional JSON metadata
 /// - `created_at` - ISO 8601 timestamp string
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ActivityItem {
     /// Unique identifier with type prefix.
     pub id: String,
@@ -65,11 +73,52 @@ pub struct ActivityItem {
     /// Optional detail text.
     pub description: Option<String>,
     /// Optional JSON metadata.
+    #[schema(value_type = Option<Object>)]
     pub metadata: Option<Value>,
     /// ISO 8601 timestamp string.
     pub created_at: String,
 }
 
+/// Whether an agent sent or received a timeline message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageDirection {
+    Sent,
+    Received,
+}
+
+/// A message in an agent's timeline.
+///
+/// A message the agent sent to itself appears twice, once per direction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentTimelineEntry {
+    pub message_id: i64,
+    pub direction: MessageDirection,
+    pub thread_id: Option<String>,
+    pub subject: String,
+    pub importance: String,
+    /// Sender of a received message, or comma-separated recipients of a sent one.
+    pub counterpart: String,
+    pub created_ts: NaiveDateTime,
+    /// When the agent read a received message.
+    pub read_ts: Option<NaiveDateTime>,
+}
+
+/// An agent's profile and recent activity, as shown on its detail page.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentActivity {
+    pub agent: Agent,
+    /// Non-expired capabilities.
+    pub capabilities: Vec<AgentCapability>,
+    /// Active file reservations, soonest expiry first.
+    pub file_reservations: Vec<FileReservation>,
+    /// Received messages still waiting on the agent's acknowledgement,
+    /// oldest first.
+    pub pending_acks: Vec<PendingAck>,
+    /// Sent and received messages, newest first.
+    pub timeline: Vec<AgentTimelineEntry>,
+}
+
 /// Backend Model Controller for Activity Feed operations.
 ///
 /// Provides a unified activity feed combining messages, tool usage,
@@ -115,7 +164,7 @@ impl ActivityBmc {
 
         let sql_msg = r#"
             SELECT id, project_id, sender_id, subject, body_md, created_ts 
-            FROM messages 
+            FROM visible_messages 
             WHERE project_id = ? 
             ORDER BY created_ts DESC 
             LIMIT ?
@@ -208,4 +257,90 @@ impl ActivityBmc {
 
         Ok(items)
     }
+
+    /// Profile, capabilities, active reservations, pending acknowledgements
+    /// and message timeline of one agent.
+    ///
+    /// The timeline interleaves messages the agent sent and received in a
+    /// single query, newest first, limited to `limit` entries. Recalled
+    /// messages keep their tombstone; scheduled ones are not shown yet.
+    ///
+    /// # Errors
+    /// Returns `AgentNotFound` if the agent does not exist in the project.
+    pub async fn for_agent(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        agent_name: &str,
+        limit: i64,
+    ) -> Result<AgentActivity> {
+        let agent = AgentBmc::get_by_name(ctx, mm, project_id, agent_name).await?;
+        let capabilities = AgentCapabilityBmc::list_for_agent(ctx, mm, agent.id.get()).await?;
+        let file_reservations =
+            FileReservationBmc::list_active_for_agent(ctx, mm, project_id, agent.id).await?;
+        let pending_acks =
+            MessageBmc::list_pending_acks_for_agent(ctx, mm, project_id.get(), agent.id.get())
+                .await?;
+
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT message_id, direction, thread_id, subject, importance, counterpart, created_ts, read_ts
+            FROM (
+                SELECT m.id AS message_id, 'sent' AS direction, m.thread_id, m.subject, m.importance,
+                       COALESCE((
+                           SELECT group_concat(a.name, ', ')
+                           FROM message_recipients AS mr
+                           JOIN agents AS a ON a.id = mr.agent_id
+                           WHERE mr.message_id = m.id
+                       ), '') AS counterpart,
+                       m.created_ts, NULL AS read_ts
+                FROM visible_messages AS m
+                WHERE m.project_id = ?1 AND m.sender_id = ?2
+                UNION ALL
+                SELECT m.id, 'received', m.thread_id, m.subject, m.importance,
+                       s.name, m.created_ts, mr.read_ts
+                FROM visible_messages AS m
+                JOIN message_recipients AS mr ON mr.message_id = m.id
+                JOIN agents AS s ON s.id = m.sender_id
+                WHERE m.project_id = ?1 AND mr.agent_id = ?2
+            )
+            ORDER BY created_ts DESC, message_id DESC, direction ASC
+            LIMIT ?3
+            "#,
+            )
+            .await?;
+        let mut rows = stmt
+            .query((project_id.get(), agent.id.get(), limit))
+            .await?;
+
+        let mut timeline = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let direction: String = row.get(1)?;
+            let created_ts: String = row.get(6)?;
+            timeline.push(AgentTimelineEntry {
+                message_id: row.get(0)?,
+                direction: if direction == "sent" {
+                    MessageDirection::Sent
+                } else {
+                    MessageDirection::Received
+                },
+                thread_id: row.get(2)?,
+                subject: row.get(3)?,
+                importance: row.get(4)?,
+                counterpart: row.get(5)?,
+                created_ts: parse_timestamp(&created_ts, "timeline.created_ts"),
+                read_ts: parse_timestamp_opt(row.get(7)?, "timeline.read_ts"),
+            });
+        }
+
+        Ok(AgentActivity {
+            agent,
+            capabilities,
+            file_reservations,
+            pending_acks,
+            timeline,
+        })
+    }
 }
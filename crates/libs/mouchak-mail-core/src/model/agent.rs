@@ -8,6 +8,11 @@
 //! - **Agent**: The main entity representing a registered AI agent
 //! - **AgentForCreate**: Input data for agent registration
 //! - **AgentProfileUpdate**: Partial update for agent profile fields
+//! - **AgentForUpdate**: Rename or change model, program and task
+//! - **DndPolicy**: "Do not disturb" window and importance threshold
+//!
+//! Agents can also be issued API tokens ([`AgentBmc::issue_token`]); a
+//! request authenticated with one acts as that agent only.
 //!
 //! # Example
 //!
@@ -41,13 +46,21 @@
 use crate::Result;
 use crate::ctx::Ctx;
 use crate::model::ModelManager;
+use crate::model::audit::{AuditAction, AuditBmc};
+use crate::model::project_version::ProjectVersionBmc;
 use crate::store::git_store;
+use crate::store::retry::execute_with_retry;
 use crate::types::{AgentId, ProjectId};
 use crate::utils::mistake_detection::suggest_similar;
 use crate::utils::parse_timestamp;
+use crate::utils::validation::{ValidationError, validate_agent_name};
 use chrono::NaiveDateTime;
+use rand_core::{OsRng, RngCore};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::path::PathBuf;
+use utoipa::ToSchema;
 
 /// A registered AI coding agent.
 ///
@@ -67,6 +80,7 @@ use std::path::PathBuf;
 /// - `last_active_ts` - Last activity timestamp
 /// - `attachments_policy` - How agent handles file attachments
 /// - `contact_policy` - Agent communication preferences
+/// - `retired_ts` - When the agent was retired (`None` while active)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Agent {
     pub id: AgentId,
@@ -79,6 +93,66 @@ pub struct Agent {
     pub last_active_ts: NaiveDateTime,
     pub attachments_policy: String,
     pub contact_policy: String,
+    #[serde(default)]
+    pub retired_ts: Option<NaiveDateTime>,
+}
+
+/// Separator in cross-project addresses such as `backend::worker-1`.
+pub const ADDRESS_SEPARATOR: &str = "::";
+
+/// Importance levels accepted as a DND threshold, lowest first.
+const IMPORTANCE_LEVELS: &[&str] = &["low", "normal", "high", "urgent"];
+
+/// An agent's "do not disturb" preference.
+///
+/// While `dnd_until` is in the future, messages sent to the agent with an
+/// importance below `min_importance` are stored but deferred: they stay out
+/// of the agent's inbox until `dnd_until`. Urgent and ack_required messages
+/// are always delivered immediately.
+///
+/// # Fields
+///
+/// - `dnd_until` - End of the DND window (UTC); `None` means DND is off
+/// - `min_importance` - Lowest importance delivered during DND; `None`
+///   means "urgent", so everything else is deferred
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct DndPolicy {
+    pub dnd_until: Option<NaiveDateTime>,
+    pub min_importance: Option<String>,
+}
+
+impl DndPolicy {
+    /// Whether the DND window is still open at `now`.
+    pub fn is_active(&self, now: NaiveDateTime) -> bool {
+        self.dnd_until.is_some_and(|until| until > now)
+    }
+
+    /// Whether a message with this importance and ack flag would be deferred
+    /// at `now`.
+    pub fn defers(&self, now: NaiveDateTime, importance: &str, ack_required: bool) -> bool {
+        if !self.is_active(now) || ack_required || importance == "urgent" {
+            return false;
+        }
+        let threshold = self.min_importance.as_deref().unwrap_or("urgent");
+        importance_rank(importance) < importance_rank(threshold)
+    }
+}
+
+/// Position of an importance level in [`IMPORTANCE_LEVELS`]; unknown values
+/// rank as "normal".
+/// Prefix of agent token secrets, so a leaked one is easy to recognize.
+pub const AGENT_TOKEN_PREFIX: &str = "mma_";
+
+/// Hex SHA-256 of a token secret; only this is stored.
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn importance_rank(importance: &str) -> usize {
+    IMPORTANCE_LEVELS
+        .iter()
+        .position(|level| *level == importance)
+        .unwrap_or(1)
 }
 
 /// Input data for creating a new agent.
@@ -106,6 +180,15 @@ pub struct AgentForCreate {
     pub task_description: String,
 }
 
+/// Outcome of [`AgentBmc::register`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AgentRegistration {
+    /// Database ID of the agent.
+    pub id: AgentId,
+    /// `false` when an existing agent with the same name was updated.
+    pub created: bool,
+}
+
 /// Backend Model Controller for Agent operations.
 ///
 /// Provides stateless methods for agent lifecycle management including
@@ -113,24 +196,29 @@ pub struct AgentForCreate {
 pub struct AgentBmc;
 
 impl AgentBmc {
-    /// Creates a new agent in the specified project.
+    /// Registers an agent in the specified project, reusing an existing one.
     ///
     /// This method:
-    /// 1. Inserts the agent into the database
-    /// 2. Creates a profile.json file in the Git archive
+    /// 1. Inserts the agent into the database, or, if the name is already
+    ///    registered, refreshes its program, model, task and `last_active_ts`
+    /// 2. Writes its profile.json file in the Git archive
+    ///
+    /// Registering is idempotent so restarting agents can call it again; use
+    /// [`AgentBmc::register`] with `strict` to get the conflict error instead.
     ///
     /// # Arguments
-    /// * `_ctx` - Request context (unused currently)
+    /// * `ctx` - Request context (checked for project access)
     /// * `mm` - ModelManager providing database and Git access
     /// * `agent_c` - Agent creation data
     ///
     /// # Returns
-    /// The created agent's database ID
+    /// The created (or existing) agent's database ID
     ///
     /// # Errors
     /// Returns an error if:
-    /// - Agent name already exists in the project
     /// - Project ID is invalid
+    /// - The name breaks the naming rules (see
+    ///   [`validate_agent_name`]) or differs only in case from another agent
     /// - Git operations fail
     ///
     /// # Example
@@ -151,35 +239,98 @@ impl AgentBmc {
     /// let id = AgentBmc::create(&ctx, mm, agent).await.unwrap();
     /// # }
     /// ```
-    pub async fn create(_ctx: &Ctx, mm: &ModelManager, agent_c: AgentForCreate) -> Result<AgentId> {
+    pub async fn create(ctx: &Ctx, mm: &ModelManager, agent_c: AgentForCreate) -> Result<AgentId> {
+        Ok(Self::register(ctx, mm, agent_c, false).await?.id)
+    }
+
+    /// Registers an agent, reporting whether a new row was created.
+    ///
+    /// With `strict` set, an existing `(project_id, name)` fails with the
+    /// database's unique-constraint error instead of being updated.
+    /// Re-registering a retired agent brings it back into listings.
+    /// Surrounding whitespace is trimmed from the name before validation.
+    pub async fn register(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        mut agent_c: AgentForCreate,
+        strict: bool,
+    ) -> Result<AgentRegistration> {
+        super::project::ProjectBmc::ensure_access(ctx, mm, agent_c.project_id).await?;
+
+        agent_c.name = agent_c.name.trim().to_string();
+        validate_agent_name(&agent_c.name)?;
+
         let db = mm.db();
 
-        // 1. Insert into DB
+        if let Some(existing) =
+            Self::case_variant(db, agent_c.project_id, &agent_c.name, None).await?
+        {
+            return Err(ValidationError::AgentNameTaken {
+                provided: agent_c.name,
+                existing,
+            }
+            .into());
+        }
+
         let stmt = db
-            .prepare(
-                r#"
-            INSERT INTO agents (project_id, name, program, model, task_description)
-            VALUES (?, ?, ?, ?, ?)
-            RETURNING id
-            "#,
-            )
+            .prepare("SELECT id FROM agents WHERE project_id = ? AND name = ?")
             .await?;
-
         let mut rows = stmt
-            .query((
-                agent_c.project_id.get(),
-                agent_c.name.as_str(),
-                agent_c.program.as_str(),
-                agent_c.model.as_str(),
-                agent_c.task_description.as_str(),
-            ))
+            .query((agent_c.project_id.get(), agent_c.name.as_str()))
             .await?;
+        let existing = rows.next().await?.is_some();
 
-        let id: AgentId = if let Some(row) = rows.next().await? {
-            AgentId::new(row.get::<i64>(0)?)
+        // 1. Insert into DB (the upsert also covers a concurrent registration)
+        let sql = if strict {
+            r#"
+            INSERT INTO agents (project_id, name, program, model, task_description)
+            VALUES (?, ?, ?, ?, ?)
+            RETURNING id
+            "#
         } else {
-            return Err(crate::Error::InvalidInput("Failed to create agent".into()));
+            r#"
+            INSERT INTO agents (project_id, name, program, model, task_description)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT (project_id, name) DO UPDATE SET
+                program = excluded.program,
+                model = excluded.model,
+                task_description = excluded.task_description,
+                last_active_ts = CURRENT_TIMESTAMP
+            RETURNING id
+            "#
         };
+        // A single statement: a busy attempt applied nothing, so it can rerun
+        let agent_c = &agent_c;
+        let id: AgentId = execute_with_retry(&mm.busy_retry(), || async move {
+            let stmt = db.prepare(sql).await?;
+            let mut rows = stmt
+                .query((
+                    agent_c.project_id.get(),
+                    agent_c.name.as_str(),
+                    agent_c.program.as_str(),
+                    agent_c.model.as_str(),
+                    agent_c.task_description.as_str(),
+                ))
+                .await?;
+
+            match rows.next().await? {
+                Some(row) => Ok(AgentId::new(row.get::<i64>(0)?)),
+                None => Err(crate::Error::InvalidInput("Failed to create agent".into())),
+            }
+        })
+        .await?;
+
+        if existing {
+            execute_with_retry(&mm.busy_retry(), || async move {
+                db.execute(
+                    "DELETE FROM agent_retirements WHERE agent_id = ?",
+                    [id.get()],
+                )
+                .await?;
+                Ok(())
+            })
+            .await?;
+        }
 
         // 2. Write profile to Git
         let stmt = db.prepare("SELECT slug FROM projects WHERE id = ?").await?;
@@ -198,7 +349,7 @@ impl AgentBmc {
         let _git_guard = mm.git_lock.lock().await;
 
         // Use cached repository to prevent FD exhaustion
-        let repo_arc = mm.get_repo().await?;
+        let repo_arc = mm.get_project_repo(&project_slug).await?;
         let repo = repo_arc.lock().await;
 
         let agent_dir = PathBuf::from("projects")
@@ -210,16 +361,20 @@ impl AgentBmc {
         let profile_rel_path = agent_dir.join("profile.json");
         let profile_json = serde_json::to_string_pretty(&agent_c)?;
 
+        let (author_name, author_email) = git_store::author_of(ctx.actor());
         git_store::commit_file(
             &repo,
             &profile_rel_path,
             &profile_json,
             &format!("agent: profile {}", agent_c.name),
-            "mcp-bot",
-            "mcp-bot@localhost",
+            author_name,
+            author_email,
         )?;
 
-        Ok(id)
+        Ok(AgentRegistration {
+            id,
+            created: !existing,
+        })
     }
 
     /// Retrieves an agent by its database ID.
@@ -235,11 +390,12 @@ impl AgentBmc {
     /// # Errors
     /// Returns `Error::AgentNotFound` if the ID doesn't exist
     pub async fn get(_ctx: &Ctx, mm: &ModelManager, id: AgentId) -> Result<Agent> {
-        let db = mm.db();
+        let db = mm.db_read();
         let stmt = db.prepare(
             r#"
-            SELECT id, project_id, name, program, model, task_description, inception_ts, last_active_ts, attachments_policy, contact_policy
-            FROM agents WHERE id = ?
+            SELECT a.id, a.project_id, a.name, a.program, a.model, a.task_description, a.inception_ts, a.last_active_ts, a.attachments_policy, a.contact_policy, r.retired_ts
+            FROM agents AS a LEFT JOIN agent_retirements AS r ON r.agent_id = a.id
+            WHERE a.id = ?
             "#
         ).await?;
         let mut rows = stmt.query([id.get()]).await?;
@@ -247,7 +403,7 @@ impl AgentBmc {
         if let Some(row) = rows.next().await? {
             // Column indices: 0=id, 1=project_id, 2=name, 3=program, 4=model,
             //                 5=task_description, 6=inception_ts, 7=last_active_ts,
-            //                 8=attachments_policy, 9=contact_policy
+            //                 8=attachments_policy, 9=contact_policy, 10=retired_ts
             let inception_ts_str: String = row.get(6)?;
             let inception_ts = parse_timestamp(&inception_ts_str, "agent.inception_ts");
             let last_active_ts_str: String = row.get(7)?;
@@ -264,6 +420,9 @@ impl AgentBmc {
                 last_active_ts,
                 attachments_policy: row.get(8)?,
                 contact_policy: row.get(9)?,
+                retired_ts: row
+                    .get::<Option<String>>(10)?
+                    .map(|ts| parse_timestamp(&ts, "agent.retired_ts")),
             })
         } else {
             Err(crate::Error::agent_not_found(format!("ID: {}", id)))
@@ -273,7 +432,7 @@ impl AgentBmc {
     /// Retrieves an agent by name within a project.
     ///
     /// # Arguments
-    /// * `_ctx` - Request context
+    /// * `ctx` - Request context
     /// * `mm` - ModelManager providing database access
     /// * `project_id` - Project database ID
     /// * `name` - Agent name (unique within project)
@@ -284,16 +443,19 @@ impl AgentBmc {
     /// # Errors
     /// Returns `Error::AgentNotFound` if no agent with that name exists in the project
     pub async fn get_by_name(
-        _ctx: &Ctx,
+        ctx: &Ctx,
         mm: &ModelManager,
         project_id: ProjectId,
         name: &str,
     ) -> Result<Agent> {
-        let db = mm.db();
+        super::project::ProjectBmc::ensure_access(ctx, mm, project_id).await?;
+
+        let db = mm.db_read();
         let stmt = db.prepare(
             r#"
-            SELECT id, project_id, name, program, model, task_description, inception_ts, last_active_ts, attachments_policy, contact_policy
-            FROM agents WHERE project_id = ? AND name = ?
+            SELECT a.id, a.project_id, a.name, a.program, a.model, a.task_description, a.inception_ts, a.last_active_ts, a.attachments_policy, a.contact_policy, r.retired_ts
+            FROM agents AS a LEFT JOIN agent_retirements AS r ON r.agent_id = a.id
+            WHERE a.project_id = ? AND a.name = ?
             "#
         ).await?;
         let mut rows = stmt.query((project_id.get(), name)).await?;
@@ -301,7 +463,7 @@ impl AgentBmc {
         if let Some(row) = rows.next().await? {
             // Column indices: 0=id, 1=project_id, 2=name, 3=program, 4=model,
             //                 5=task_description, 6=inception_ts, 7=last_active_ts,
-            //                 8=attachments_policy, 9=contact_policy
+            //                 8=attachments_policy, 9=contact_policy, 10=retired_ts
             let inception_ts_str: String = row.get(6)?;
             let inception_ts = parse_timestamp(&inception_ts_str, "agent.inception_ts");
             let last_active_ts_str: String = row.get(7)?;
@@ -318,6 +480,9 @@ impl AgentBmc {
                 last_active_ts,
                 attachments_policy: row.get(8)?,
                 contact_policy: row.get(9)?,
+                retired_ts: row
+                    .get::<Option<String>>(10)?
+                    .map(|ts| parse_timestamp(&ts, "agent.retired_ts")),
             })
         } else {
             // Fetch all agent names in this project for suggestions
@@ -342,6 +507,56 @@ impl AgentBmc {
         }
     }
 
+    /// Resolves a recipient address relative to the sender's project.
+    ///
+    /// A plain name is looked up in `project_id`. An address of the form
+    /// `slug::agent-name` is looked up in the project with that slug; when
+    /// the slug names another project, the sender's project must be allowed
+    /// to message peers (see [`Self::ensure_peer_messaging`]).
+    ///
+    /// # Errors
+    /// Returns `Error::CrossProjectForbidden` if peer messaging is not
+    /// allowed, `Error::ProjectNotFound` for an unknown slug, or
+    /// `Error::AgentNotFound` if the agent doesn't exist
+    pub async fn resolve_address(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        address: &str,
+    ) -> Result<Agent> {
+        let Some((slug, name)) = address.split_once(ADDRESS_SEPARATOR) else {
+            return Self::get_by_name(ctx, mm, project_id, address).await;
+        };
+
+        let home = super::project::ProjectBmc::get(ctx, mm, project_id).await?;
+        if slug == home.slug {
+            return Self::get_by_name(ctx, mm, project_id, name).await;
+        }
+        Self::ensure_peer_messaging(ctx, mm, &home.slug)?;
+
+        // The peer rule replaces the caller's project scope for the target
+        let root = Ctx::root_ctx();
+        let target = super::project::ProjectBmc::get_by_slug(&root, mm, slug).await?;
+        Self::get_by_name(&root, mm, target.id, name).await
+    }
+
+    /// Checks that `project_slug` may message agents in other projects.
+    ///
+    /// Unrestricted contexts always may; otherwise the project must be listed
+    /// in `projects.allowed_peers`.
+    ///
+    /// # Errors
+    /// Returns `Error::CrossProjectForbidden` if the project is not allowed
+    pub fn ensure_peer_messaging(ctx: &Ctx, mm: &ModelManager, project_slug: &str) -> Result<()> {
+        if ctx.is_unrestricted() || mm.app_config.projects.allows_peer_messages(project_slug) {
+            Ok(())
+        } else {
+            Err(crate::Error::CrossProjectForbidden(
+                project_slug.to_string(),
+            ))
+        }
+    }
+
     /// Check if an active reviewer agent exists for a project.
     ///
     /// Used by workers to determine if they should send \[COMPLETION\] to a reviewer
@@ -365,11 +580,12 @@ impl AgentBmc {
         project_id: ProjectId,
         stale_threshold: Option<std::time::Duration>,
     ) -> Result<Option<Agent>> {
-        let db = mm.db();
+        let db = mm.db_read();
         let stmt = db.prepare(
             r#"
-            SELECT id, project_id, name, program, model, task_description, inception_ts, last_active_ts, attachments_policy, contact_policy
-            FROM agents WHERE project_id = ? AND LOWER(name) = 'reviewer'
+            SELECT a.id, a.project_id, a.name, a.program, a.model, a.task_description, a.inception_ts, a.last_active_ts, a.attachments_policy, a.contact_policy, r.retired_ts
+            FROM agents AS a LEFT JOIN agent_retirements AS r ON r.agent_id = a.id
+            WHERE a.project_id = ? AND LOWER(a.name) = 'reviewer' AND r.agent_id IS NULL
             "#
         ).await?;
         let mut rows = stmt.query([project_id.get()]).await?;
@@ -403,40 +619,69 @@ impl AgentBmc {
                 last_active_ts,
                 attachments_policy: row.get(8)?,
                 contact_policy: row.get(9)?,
+                retired_ts: row
+                    .get::<Option<String>>(10)?
+                    .map(|ts| parse_timestamp(&ts, "agent.retired_ts")),
             }))
         } else {
             Ok(None)
         }
     }
 
-    /// Lists all agents in a project, ordered by name.
+    /// Lists the active agents in a project, ordered by name.
+    ///
+    /// Retired agents are left out; see [`Self::list_all_including_retired`].
     ///
     /// # Arguments
-    /// * `_ctx` - Request context
+    /// * `ctx` - Request context
     /// * `mm` - ModelManager providing database access
     /// * `project_id` - Project database ID
     ///
     /// # Returns
-    /// Vector of all agents in the project (may be empty)
+    /// Vector of active agents in the project (may be empty)
     pub async fn list_all_for_project(
-        _ctx: &Ctx,
+        ctx: &Ctx,
         mm: &ModelManager,
         project_id: ProjectId,
     ) -> Result<Vec<Agent>> {
-        let db = mm.db();
+        Self::list_for_project(ctx, mm, project_id, false).await
+    }
+
+    /// Lists every agent in a project, retired ones included, ordered by name.
+    ///
+    /// Used where history matters (archive snapshots, adopt, delete).
+    pub async fn list_all_including_retired(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+    ) -> Result<Vec<Agent>> {
+        Self::list_for_project(ctx, mm, project_id, true).await
+    }
+
+    async fn list_for_project(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        include_retired: bool,
+    ) -> Result<Vec<Agent>> {
+        super::project::ProjectBmc::ensure_access(ctx, mm, project_id).await?;
+
+        let db = mm.db_read();
         let stmt = db.prepare(
             r#"
-            SELECT id, project_id, name, program, model, task_description, inception_ts, last_active_ts, attachments_policy, contact_policy
-            FROM agents WHERE project_id = ? ORDER BY name ASC
+            SELECT a.id, a.project_id, a.name, a.program, a.model, a.task_description, a.inception_ts, a.last_active_ts, a.attachments_policy, a.contact_policy, r.retired_ts
+            FROM agents AS a LEFT JOIN agent_retirements AS r ON r.agent_id = a.id
+            WHERE a.project_id = ? AND (? OR r.agent_id IS NULL)
+            ORDER BY a.name ASC
             "#
         ).await?;
-        let mut rows = stmt.query([project_id.get()]).await?;
+        let mut rows = stmt.query((project_id.get(), include_retired)).await?;
 
         let mut agents = Vec::new();
         while let Some(row) = rows.next().await? {
             // Column indices: 0=id, 1=project_id, 2=name, 3=program, 4=model,
             //                 5=task_description, 6=inception_ts, 7=last_active_ts,
-            //                 8=attachments_policy, 9=contact_policy
+            //                 8=attachments_policy, 9=contact_policy, 10=retired_ts
             let inception_ts_str: String = row.get(6)?;
             let inception_ts = parse_timestamp(&inception_ts_str, "agent.inception_ts");
             let last_active_ts_str: String = row.get(7)?;
@@ -453,6 +698,9 @@ impl AgentBmc {
                 last_active_ts,
                 attachments_policy: row.get(8)?,
                 contact_policy: row.get(9)?,
+                retired_ts: row
+                    .get::<Option<String>>(10)?
+                    .map(|ts| parse_timestamp(&ts, "agent.retired_ts")),
             });
         }
         Ok(agents)
@@ -472,7 +720,7 @@ impl AgentBmc {
         mm: &ModelManager,
         agent_id: AgentId,
     ) -> Result<i64> {
-        let db = mm.db();
+        let db = mm.db_read();
         let stmt = db
             .prepare("SELECT COUNT(*) FROM messages WHERE sender_id = ?")
             .await?;
@@ -498,7 +746,7 @@ impl AgentBmc {
         mm: &ModelManager,
         agent_id: AgentId,
     ) -> Result<i64> {
-        let db = mm.db();
+        let db = mm.db_read();
         let stmt = db
             .prepare("SELECT COUNT(*) FROM message_recipients WHERE agent_id = ?")
             .await?;
@@ -563,17 +811,437 @@ impl AgentBmc {
         Ok(())
     }
 
+    /// Name of another agent in the project that matches `name` ignoring
+    /// case but not exactly, skipping `except`.
+    async fn case_variant(
+        db: &crate::store::Db,
+        project_id: ProjectId,
+        name: &str,
+        except: Option<AgentId>,
+    ) -> Result<Option<String>> {
+        let stmt = db
+            .prepare(
+                "SELECT name FROM agents
+                 WHERE project_id = ? AND name = ? COLLATE NOCASE AND name <> ? AND id <> ?",
+            )
+            .await?;
+        let mut rows = stmt
+            .query((
+                project_id.get(),
+                name,
+                name,
+                except.map_or(0, |id| id.get()),
+            ))
+            .await?;
+        match rows.next().await? {
+            Some(row) => Ok(Some(row.get(0)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Updates an agent's identity and task fields.
+    ///
+    /// Only non-None fields are changed. A new name must be unique within the
+    /// project. After a rename, messages the agent sends are archived under
+    /// its new name; files already in the archive keep the old one.
+    ///
+    /// # Arguments
+    /// * `ctx` - Request context (checked for project access)
+    /// * `mm` - ModelManager providing database and Git access
+    /// * `agent_id` - Agent database ID
+    /// * `update` - Fields to change
+    ///
+    /// # Returns
+    /// The updated agent
+    ///
+    /// # Errors
+    /// Returns an error if the agent doesn't exist, or the new name breaks
+    /// the naming rules or is taken (ignoring case) by another agent
+    pub async fn update(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        agent_id: AgentId,
+        update: AgentForUpdate,
+    ) -> Result<Agent> {
+        let agent = Self::get(ctx, mm, agent_id).await?;
+        super::project::ProjectBmc::ensure_access(ctx, mm, agent.project_id).await?;
+
+        let db = mm.db();
+
+        if let Some(name) = update.name.as_deref()
+            && name != agent.name
+        {
+            validate_agent_name(name)?;
+            let stmt = db
+                .prepare("SELECT id FROM agents WHERE project_id = ? AND name = ?")
+                .await?;
+            let mut rows = stmt.query((agent.project_id.get(), name)).await?;
+            if rows.next().await?.is_some() {
+                return Err(crate::Error::InvalidInput(format!(
+                    "Agent name '{}' already exists in project",
+                    name
+                )));
+            }
+            if let Some(existing) =
+                Self::case_variant(db, agent.project_id, name, Some(agent_id)).await?
+            {
+                return Err(ValidationError::AgentNameTaken {
+                    provided: name.to_string(),
+                    existing,
+                }
+                .into());
+            }
+        }
+
+        let stmt = db
+            .prepare(
+                r#"
+            UPDATE agents SET
+                name = COALESCE(?, name),
+                task_description = COALESCE(?, task_description),
+                model = COALESCE(?, model),
+                program = COALESCE(?, program)
+            WHERE id = ?
+            "#,
+            )
+            .await?;
+        stmt.execute((
+            update.name.clone(),
+            update.task_description,
+            update.model,
+            update.program,
+            agent_id.get(),
+        ))
+        .await?;
+
+        let updated = Self::get(ctx, mm, agent_id).await?;
+        let project = super::project::ProjectBmc::get(ctx, mm, updated.project_id).await?;
+        if updated.name != agent.name {
+            ProjectVersionBmc::bump(mm, &project.slug).await?;
+        }
+
+        // Git Operations - serialized to prevent lock contention
+        let _git_guard = mm.git_lock.lock().await;
+        let repo_arc = mm.get_project_repo(&project.slug).await?;
+        let repo = repo_arc.lock().await;
+
+        let profile_rel_path = PathBuf::from("projects")
+            .join(&project.slug)
+            .join("agents")
+            .join(&updated.name)
+            .join("profile.json");
+        let profile = AgentForCreate {
+            project_id: updated.project_id,
+            name: updated.name.clone(),
+            program: updated.program.clone(),
+            model: updated.model.clone(),
+            task_description: updated.task_description.clone(),
+        };
+        let commit_msg = if updated.name != agent.name {
+            format!("agent: rename {} -> {}", agent.name, updated.name)
+        } else {
+            format!("agent: profile {}", updated.name)
+        };
+        let (author_name, author_email) = git_store::author_of(ctx.actor());
+        git_store::commit_file(
+            &repo,
+            &profile_rel_path,
+            &serde_json::to_string_pretty(&profile)?,
+            &commit_msg,
+            author_name,
+            author_email,
+        )?;
+
+        Ok(updated)
+    }
+
+    /// Retires an agent.
+    ///
+    /// The agent row and its messages are kept, so history stays attributed,
+    /// but the agent no longer appears in [`Self::list_all_for_project`],
+    /// broadcast fan-out or capability checks. Retiring twice keeps the
+    /// original timestamp. The first retirement is recorded in the audit log.
+    ///
+    /// # Arguments
+    /// * `ctx` - Request context (checked for project access)
+    /// * `mm` - ModelManager providing database access
+    /// * `agent_id` - Agent database ID
+    ///
+    /// # Returns
+    /// The retired agent
+    ///
+    /// # Errors
+    /// Returns an error if the agent doesn't exist
+    pub async fn retire(ctx: &Ctx, mm: &ModelManager, agent_id: AgentId) -> Result<Agent> {
+        let agent = Self::get(ctx, mm, agent_id).await?;
+        let project = super::project::ProjectBmc::get(ctx, mm, agent.project_id).await?;
+
+        {
+            let (_tx_guard, tx) = mm.begin_tx().await?;
+            let stmt = tx
+                .prepare("INSERT OR IGNORE INTO agent_retirements (agent_id) VALUES (?)")
+                .await?;
+            if stmt.execute([agent_id.get()]).await? > 0 {
+                AuditBmc::record_in(
+                    &tx,
+                    ctx,
+                    agent.project_id,
+                    AuditAction::AgentRetire,
+                    agent_id.get(),
+                    serde_json::json!({
+                        "before": { "name": agent.name, "retired": false },
+                        "after": { "retired": true },
+                    }),
+                )
+                .await?;
+                ProjectVersionBmc::bump_in(&tx, &project.slug).await?;
+            }
+            tx.commit().await?;
+        }
+
+        Self::get(ctx, mm, agent_id).await
+    }
+
+    /// Gets an agent's DND policy; agents that never set one get the
+    /// default (DND off).
+    pub async fn get_dnd_policy(
+        _ctx: &Ctx,
+        mm: &ModelManager,
+        agent_id: AgentId,
+    ) -> Result<DndPolicy> {
+        let db = mm.db_read();
+        let stmt = db
+            .prepare("SELECT dnd_until, min_importance FROM agent_settings WHERE agent_id = ?")
+            .await?;
+        let mut rows = stmt.query([agent_id.get()]).await?;
+
+        match rows.next().await? {
+            Some(row) => Ok(DndPolicy {
+                dnd_until: crate::utils::parse_timestamp_opt(row.get(0)?, "agent.dnd_until"),
+                min_importance: row.get(1)?,
+            }),
+            None => Ok(DndPolicy::default()),
+        }
+    }
+
+    /// Sets an agent's DND policy, replacing the previous one.
+    ///
+    /// Messages already deferred follow the new window: they are held until
+    /// the new `dnd_until`, or released right away when DND is turned off.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidInput` for an unknown `min_importance`, and
+    /// `Error::AgentNotFound` if the agent doesn't exist
+    pub async fn set_dnd_policy(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        agent_id: AgentId,
+        policy: DndPolicy,
+    ) -> Result<DndPolicy> {
+        if let Some(level) = policy.min_importance.as_deref()
+            && !IMPORTANCE_LEVELS.contains(&level)
+        {
+            return Err(crate::Error::InvalidInput(format!(
+                "Invalid min_importance '{}': expected one of {}",
+                level,
+                IMPORTANCE_LEVELS.join(", ")
+            )));
+        }
+
+        let agent = Self::get(ctx, mm, agent_id).await?;
+        super::project::ProjectBmc::ensure_access(ctx, mm, agent.project_id).await?;
+
+        let dnd_until = policy
+            .dnd_until
+            .map(|ts| ts.format("%Y-%m-%d %H:%M:%S").to_string());
+
+        let (_tx_guard, tx) = mm.begin_tx().await?;
+
+        let stmt = tx
+            .prepare(
+                r#"
+                INSERT INTO agent_settings (agent_id, dnd_until, min_importance, updated_ts)
+                VALUES (?, ?, ?, CURRENT_TIMESTAMP)
+                ON CONFLICT(agent_id) DO UPDATE SET
+                    dnd_until = excluded.dnd_until,
+                    min_importance = excluded.min_importance,
+                    updated_ts = excluded.updated_ts
+                "#,
+            )
+            .await?;
+        stmt.execute((
+            agent_id.get(),
+            dnd_until
+                .clone()
+                .map_or(libsql::Value::Null, libsql::Value::Text),
+            policy
+                .min_importance
+                .clone()
+                .map_or(libsql::Value::Null, libsql::Value::Text),
+        ))
+        .await?;
+
+        match dnd_until {
+            Some(until) => {
+                let stmt = tx
+                    .prepare(
+                        "UPDATE message_deferrals SET deferred_until = ? WHERE agent_id = ? AND deferred_until > CURRENT_TIMESTAMP",
+                    )
+                    .await?;
+                stmt.execute((until, agent_id.get())).await?;
+            }
+            None => {
+                let stmt = tx
+                    .prepare("DELETE FROM message_deferrals WHERE agent_id = ?")
+                    .await?;
+                stmt.execute([agent_id.get()]).await?;
+            }
+        }
+
+        tx.commit().await?;
+
+        Ok(policy)
+    }
+
+    /// Lists the DND policies currently in effect in a project, keyed by
+    /// agent. Agents without an open DND window are left out.
+    pub async fn list_active_dnd_policies(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+    ) -> Result<HashMap<AgentId, DndPolicy>> {
+        super::project::ProjectBmc::ensure_access(ctx, mm, project_id).await?;
+
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+                SELECT s.agent_id, s.dnd_until, s.min_importance
+                FROM agent_settings AS s
+                JOIN agents AS a ON a.id = s.agent_id
+                WHERE a.project_id = ? AND s.dnd_until > CURRENT_TIMESTAMP
+                "#,
+            )
+            .await?;
+        let mut rows = stmt.query([project_id.get()]).await?;
+
+        let mut policies = HashMap::new();
+        while let Some(row) = rows.next().await? {
+            policies.insert(
+                AgentId::new(row.get(0)?),
+                DndPolicy {
+                    dnd_until: crate::utils::parse_timestamp_opt(row.get(1)?, "agent.dnd_until"),
+                    min_importance: row.get(2)?,
+                },
+            );
+        }
+        Ok(policies)
+    }
+
+    /// Issues a new API token for an agent.
+    ///
+    /// The secret is returned once and only its hash is stored. Requests
+    /// sending it as `Authorization: AgentKey <token>` act as this agent.
+    /// Earlier tokens stay valid until revoked.
+    ///
+    /// # Errors
+    /// Returns `Error::AgentNotFound` if the agent doesn't exist, or
+    /// `Error::Forbidden` if its project is outside the caller's scope
+    pub async fn issue_token(ctx: &Ctx, mm: &ModelManager, agent_id: AgentId) -> Result<String> {
+        let agent = Self::get(ctx, mm, agent_id).await?;
+        super::project::ProjectBmc::ensure_access(ctx, mm, agent.project_id).await?;
+
+        let mut secret = [0u8; 32];
+        OsRng.fill_bytes(&mut secret);
+        let token = format!("{}{}", AGENT_TOKEN_PREFIX, hex::encode(secret));
+
+        let db = mm.db();
+        let stmt = db
+            .prepare("INSERT INTO agent_tokens (agent_id, token_hash) VALUES (?, ?)")
+            .await?;
+        stmt.execute((agent_id.get(), hash_token(&token))).await?;
+
+        Ok(token)
+    }
+
+    /// Revokes every active token of an agent.
+    ///
+    /// # Returns
+    /// The number of tokens revoked
+    ///
+    /// # Errors
+    /// Returns `Error::AgentNotFound` if the agent doesn't exist, or
+    /// `Error::Forbidden` if its project is outside the caller's scope
+    pub async fn revoke_token(ctx: &Ctx, mm: &ModelManager, agent_id: AgentId) -> Result<usize> {
+        let agent = Self::get(ctx, mm, agent_id).await?;
+        super::project::ProjectBmc::ensure_access(ctx, mm, agent.project_id).await?;
+
+        let db = mm.db();
+        let stmt = db
+            .prepare(
+                "UPDATE agent_tokens SET revoked_ts = CURRENT_TIMESTAMP WHERE agent_id = ? AND revoked_ts IS NULL",
+            )
+            .await?;
+        Ok(stmt.execute([agent_id.get()]).await?)
+    }
+
+    /// Resolves a token secret to its agent and the agent's project slug.
+    ///
+    /// Returns `None` for unknown or revoked tokens and for tokens of
+    /// retired agents. Records the token's last use.
+    pub async fn authenticate_token(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        token: &str,
+    ) -> Result<Option<(Agent, String)>> {
+        let token_hash = hash_token(token);
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+                SELECT t.agent_id, p.slug
+                FROM agent_tokens AS t
+                JOIN agents AS a ON a.id = t.agent_id
+                JOIN projects AS p ON p.id = a.project_id
+                WHERE t.token_hash = ? AND t.revoked_ts IS NULL
+                "#,
+            )
+            .await?;
+        let mut rows = stmt.query([token_hash.as_str()]).await?;
+        let Some(row) = rows.next().await? else {
+            return Ok(None);
+        };
+        let agent_id = AgentId::new(row.get(0)?);
+        let project_slug: String = row.get(1)?;
+
+        let agent = Self::get(ctx, mm, agent_id).await?;
+        if agent.retired_ts.is_some() {
+            return Ok(None);
+        }
+
+        let stmt = mm
+            .db()
+            .prepare(
+                "UPDATE agent_tokens SET last_used_ts = CURRENT_TIMESTAMP WHERE token_hash = ?",
+            )
+            .await?;
+        stmt.execute([token_hash]).await?;
+
+        Ok(Some((agent, project_slug)))
+    }
+
     /// Deletes an agent and all related data (cascade delete).
     ///
     /// Deletion order for FK constraint satisfaction:
     /// 1. message_recipients (references agent_id)
     /// 2. messages (where sender_id = agent_id)
-    /// 3. file_reservations, build_slots
+    /// 3. file_reservations, reservation_queue, build_slots
     /// 4. agent_links (both sides)
     /// 5. overseer_messages
     /// 6. agent itself
     ///
-    /// Also removes the agent directory from the Git archive.
+    /// These deletes and an audit log entry commit in one transaction. Also
+    /// removes the agent directory from the Git archive.
     ///
     /// # Arguments
     /// * `ctx` - Request context
@@ -598,49 +1266,171 @@ impl AgentBmc {
             .ok_or_else(|| crate::Error::project_not_found(format!("ID: {}", agent.project_id)))?
             .get(0)?;
 
+        // 1-7 run in one transaction with the audit entry
+        let (tx_guard, tx) = mm.begin_tx().await?;
+
         // 1. Delete message_recipients for this agent
-        let stmt = db
+        let stmt = tx
             .prepare("DELETE FROM message_recipients WHERE agent_id = ?")
             .await?;
         stmt.execute([agent_id.get()]).await?;
 
+        let stmt = tx
+            .prepare(
+                "DELETE FROM message_deferrals WHERE agent_id = ? OR message_id IN (SELECT id FROM messages WHERE sender_id = ?)",
+            )
+            .await?;
+        stmt.execute([agent_id.get(), agent_id.get()]).await?;
+
+        let stmt = tx
+            .prepare(
+                "DELETE FROM message_snoozes WHERE agent_id = ? OR message_id IN (SELECT id FROM messages WHERE sender_id = ?)",
+            )
+            .await?;
+        stmt.execute([agent_id.get(), agent_id.get()]).await?;
+
+        let stmt = tx
+            .prepare("DELETE FROM thread_mutes WHERE agent_id = ?")
+            .await?;
+        stmt.execute([agent_id.get()]).await?;
+
+        let stmt = tx
+            .prepare("DELETE FROM agent_group_members WHERE agent_id = ?")
+            .await?;
+        stmt.execute([agent_id.get()]).await?;
+
+        let stmt = tx
+            .prepare(
+                "DELETE FROM message_group_expansions WHERE agent_id = ? OR message_id IN (SELECT id FROM messages WHERE sender_id = ?)",
+            )
+            .await?;
+        stmt.execute([agent_id.get(), agent_id.get()]).await?;
+
+        let stmt = tx
+            .prepare(
+                "DELETE FROM cross_project_recipients WHERE agent_id = ? OR message_id IN (SELECT id FROM messages WHERE sender_id = ?)",
+            )
+            .await?;
+        stmt.execute([agent_id.get(), agent_id.get()]).await?;
+
+        let stmt = tx
+            .prepare(
+                "DELETE FROM message_recalls WHERE message_id IN (SELECT id FROM messages WHERE sender_id = ?)",
+            )
+            .await?;
+        stmt.execute([agent_id.get()]).await?;
+
+        let stmt = tx
+            .prepare(
+                "DELETE FROM message_schedules WHERE message_id IN (SELECT id FROM messages WHERE sender_id = ?)",
+            )
+            .await?;
+        stmt.execute([agent_id.get()]).await?;
+
+        let stmt = tx
+            .prepare(
+                "DELETE FROM message_broadcasts WHERE message_id IN (SELECT id FROM messages WHERE sender_id = ?)",
+            )
+            .await?;
+        stmt.execute([agent_id.get()]).await?;
+
+        let stmt = tx
+            .prepare(
+                "DELETE FROM message_labels WHERE message_id IN (SELECT id FROM messages WHERE sender_id = ?)",
+            )
+            .await?;
+        stmt.execute([agent_id.get()]).await?;
+
+        let stmt = tx
+            .prepare(
+                "DELETE FROM message_thread_seqs WHERE message_id IN (SELECT id FROM messages WHERE sender_id = ?)",
+            )
+            .await?;
+        stmt.execute([agent_id.get()]).await?;
+
+        let stmt = tx
+            .prepare(
+                "DELETE FROM message_forwards WHERE message_id IN (SELECT id FROM messages WHERE sender_id = ?)",
+            )
+            .await?;
+        stmt.execute([agent_id.get()]).await?;
+
         // 2. Delete messages where this agent is sender (FTS5 trigger handles messages_fts)
-        let stmt = db
+        let stmt = tx
             .prepare("DELETE FROM messages WHERE sender_id = ?")
             .await?;
         stmt.execute([agent_id.get()]).await?;
 
         // 3. Delete file_reservations
-        let stmt = db
+        let stmt = tx
             .prepare("DELETE FROM file_reservations WHERE agent_id = ?")
             .await?;
         stmt.execute([agent_id.get()]).await?;
 
+        let stmt = tx
+            .prepare("DELETE FROM reservation_queue WHERE agent_id = ?")
+            .await?;
+        stmt.execute([agent_id.get()]).await?;
+
         // 4. Delete build_slots
-        let stmt = db
+        let stmt = tx
             .prepare("DELETE FROM build_slots WHERE agent_id = ?")
             .await?;
         stmt.execute([agent_id.get()]).await?;
 
         // 5. Delete agent_links (both sides)
-        let stmt = db
+        let stmt = tx
             .prepare("DELETE FROM agent_links WHERE a_agent_id = ? OR b_agent_id = ?")
             .await?;
         stmt.execute([agent_id.get(), agent_id.get()]).await?;
 
         // 6. Delete overseer_messages
-        let stmt = db
+        let stmt = tx
             .prepare("DELETE FROM overseer_messages WHERE sender_id = ?")
             .await?;
         stmt.execute([agent_id.get()]).await?;
 
+        let stmt = tx
+            .prepare("DELETE FROM agent_retirements WHERE agent_id = ?")
+            .await?;
+        stmt.execute([agent_id.get()]).await?;
+
+        let stmt = tx
+            .prepare("DELETE FROM agent_settings WHERE agent_id = ?")
+            .await?;
+        stmt.execute([agent_id.get()]).await?;
+
+        let stmt = tx
+            .prepare("DELETE FROM agent_tokens WHERE agent_id = ?")
+            .await?;
+        stmt.execute([agent_id.get()]).await?;
+
         // 7. Delete the agent
-        let stmt = db.prepare("DELETE FROM agents WHERE id = ?").await?;
+        let stmt = tx.prepare("DELETE FROM agents WHERE id = ?").await?;
         stmt.execute([agent_id.get()]).await?;
 
+        AuditBmc::record_in(
+            &tx,
+            ctx,
+            agent.project_id,
+            AuditAction::AgentDelete,
+            agent_id.get(),
+            serde_json::json!({
+                "before": {
+                    "name": agent.name,
+                    "program": agent.program,
+                    "model": agent.model,
+                },
+            }),
+        )
+        .await?;
+        ProjectVersionBmc::bump_in(&tx, &project_slug).await?;
+        tx.commit().await?;
+        drop(tx_guard);
+
         // 8. Clean up Git archive
         let agent_dir = mm
-            .repo_root
+            .project_repo_root(&project_slug)
             .join("projects")
             .join(&project_slug)
             .join("agents")
@@ -650,19 +1440,20 @@ impl AgentBmc {
             std::fs::remove_dir_all(&agent_dir)?;
 
             let _git_guard = mm.git_lock.lock().await;
-            let repo_arc = mm.get_repo().await?;
+            let repo_arc = mm.get_project_repo(&project_slug).await?;
             let repo = repo_arc.lock().await;
 
             let relative_path = PathBuf::from("projects")
                 .join(&project_slug)
                 .join("agents")
                 .join(&agent.name);
+            let (author_name, author_email) = git_store::author_of(ctx.actor());
             git_store::commit_deletion(
                 &repo,
                 &relative_path,
                 &format!("chore: delete agent {}", agent.name),
-                "mcp-bot",
-                "mcp-bot@localhost",
+                author_name,
+                author_email,
             )?;
         }
 
@@ -695,3 +1486,30 @@ pub struct AgentProfileUpdate {
     /// New contact policy (if updating).
     pub contact_policy: Option<String>,
 }
+
+/// Partial update for an agent's identity and task fields.
+///
+/// Only non-None fields will be updated.
+///
+/// # Example
+///
+/// ```
+/// use mouchak_mail_core::model::agent::AgentForUpdate;
+///
+/// // Rename an agent
+/// let update = AgentForUpdate {
+///     name: Some("worker-2".to_string()),
+///     ..Default::default()
+/// };
+/// ```
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+pub struct AgentForUpdate {
+    /// New name, unique within the project (if renaming).
+    pub name: Option<String>,
+    /// New task description (if updating).
+    pub task_description: Option<String>,
+    /// New AI model identifier (if updating).
+    pub model: Option<String>,
+    /// New runtime program (if updating).
+    pub program: Option<String>,
+}
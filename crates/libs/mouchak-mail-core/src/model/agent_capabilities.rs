@@ -163,7 +163,9 @@ impl AgentCapabilityBmc {
         Ok(())
     }
 
-    /// Check if an agent has a specific capability (non-expired)
+    /// Check if an agent has a specific capability (non-expired).
+    ///
+    /// Retired agents hold no capabilities.
     pub async fn check(
         _ctx: &Ctx,
         mm: &ModelManager,
@@ -177,7 +179,8 @@ impl AgentCapabilityBmc {
             .prepare(
                 r#"SELECT COUNT(*) FROM agent_capabilities
                    WHERE agent_id = ? AND capability = ?
-                   AND (expires_at IS NULL OR expires_at > ?)"#,
+                   AND (expires_at IS NULL OR expires_at > ?)
+                   AND agent_id NOT IN (SELECT agent_id FROM agent_retirements)"#,
             )
             .await?;
         let mut rows = stmt.query((agent_id, capability, now_str)).await?;
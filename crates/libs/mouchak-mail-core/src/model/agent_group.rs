@@ -0,0 +1,372 @@
+//! Named agent groups for addressing teams.
+//!
+//! A group such as "frontend" or "reviewers" is defined per project and
+//! holds any number of the project's agents. Sending to `group:frontend`
+//! delivers to the group's members at send time; the expansion is recorded
+//! with the message, so adding or removing members later never changes who
+//! an earlier message went to.
+//!
+//! Group names follow the agent naming rules, are unique per project
+//! ignoring case, and may not shadow an agent's name.
+
+use crate::Result;
+use crate::ctx::Ctx;
+use crate::model::ModelManager;
+use crate::model::agent::AgentBmc;
+use crate::model::project::ProjectBmc;
+use crate::types::ProjectId;
+use crate::utils::validation::{ValidationError, validate_agent_name};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Prefix that marks a recipient address as a group, as in `group:frontend`.
+pub const GROUP_ADDRESS_PREFIX: &str = "group:";
+
+/// A project's agent group.
+///
+/// # Fields
+///
+/// - `id` - Database primary key
+/// - `project_id` - Owning project
+/// - `name` - Group name, unique within the project ignoring case
+/// - `members` - Member agent names, ordered by name
+/// - `created_ts` - Creation timestamp
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct AgentGroup {
+    pub id: i64,
+    pub project_id: i64,
+    pub name: String,
+    pub members: Vec<String>,
+    pub created_ts: NaiveDateTime,
+}
+
+/// Who a `group:<name>` address reached when a message was sent.
+///
+/// # Fields
+///
+/// - `group` - Group name as stored
+/// - `agent_ids` - Member agent IDs the address expanded to
+/// - `members` - The same members' names
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct GroupExpansion {
+    pub group: String,
+    pub agent_ids: Vec<i64>,
+    pub members: Vec<String>,
+}
+
+/// Backend Model Controller for agent groups.
+pub struct AgentGroupBmc;
+
+impl AgentGroupBmc {
+    /// Creates an empty group.
+    ///
+    /// # Returns
+    /// The created group's database ID
+    ///
+    /// # Errors
+    /// - [`crate::Error::InvalidInput`] if the name breaks the agent naming
+    ///   rules
+    /// - [`crate::Error::GroupNameTaken`] if the project already has a group
+    ///   or an agent with this name, ignoring case
+    pub async fn create(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        name: &str,
+    ) -> Result<i64> {
+        ProjectBmc::ensure_access(ctx, mm, project_id).await?;
+
+        let name = name.trim();
+        if let Err(ValidationError::InvalidAgentName { rule, .. }) = validate_agent_name(name) {
+            return Err(crate::Error::InvalidInput(format!(
+                "Invalid group name '{}': {}",
+                name, rule
+            )));
+        }
+
+        // A group may not hide an agent of the same name from the picker
+        let db = mm.db();
+        let stmt = db
+            .prepare("SELECT name FROM agents WHERE project_id = ? AND name = ? COLLATE NOCASE")
+            .await?;
+        let mut rows = stmt.query((project_id.get(), name)).await?;
+        if let Some(row) = rows.next().await? {
+            return Err(crate::Error::GroupNameTaken {
+                name: row.get(0)?,
+                taken_by: "an agent",
+            });
+        }
+
+        let stmt = db
+            .prepare("INSERT INTO agent_groups (project_id, name) VALUES (?, ?) RETURNING id")
+            .await?;
+        // The column is COLLATE NOCASE, so UNIQUE (project_id, name) already
+        // rejects names differing only in case
+        let row = match stmt.query((project_id.get(), name)).await {
+            Ok(mut rows) => rows.next().await,
+            Err(e) => Err(e),
+        };
+        match row {
+            Ok(Some(row)) => Ok(row.get::<i64>(0)?),
+            Ok(None) => Err(crate::Error::InvalidInput("Failed to create group".into())),
+            Err(e) if e.to_string().contains("UNIQUE constraint failed") => {
+                Err(crate::Error::GroupNameTaken {
+                    name: name.to_string(),
+                    taken_by: "a group",
+                })
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Lists a project's groups with their members, ordered by name.
+    pub async fn list_for_project(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+    ) -> Result<Vec<AgentGroup>> {
+        ProjectBmc::ensure_access(ctx, mm, project_id).await?;
+
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT g.id, g.project_id, g.name, g.created_ts, a.name
+            FROM agent_groups AS g
+            LEFT JOIN agent_group_members AS m ON m.group_id = g.id
+            LEFT JOIN agents AS a ON a.id = m.agent_id
+            WHERE g.project_id = ?
+            ORDER BY g.name ASC, a.name ASC
+            "#,
+            )
+            .await?;
+
+        let mut rows = stmt.query([project_id.get()]).await?;
+        let mut groups: Vec<AgentGroup> = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let id: i64 = row.get(0)?;
+            if groups.last().is_none_or(|g| g.id != id) {
+                let created_ts: String = row.get(3)?;
+                groups.push(AgentGroup {
+                    id,
+                    project_id: row.get(1)?,
+                    name: row.get(2)?,
+                    members: Vec::new(),
+                    created_ts: crate::utils::parse_timestamp(
+                        &created_ts,
+                        "agent_group.created_ts",
+                    ),
+                });
+            }
+            if let (Some(member), Some(group)) = (row.get::<Option<String>>(4)?, groups.last_mut())
+            {
+                group.members.push(member);
+            }
+        }
+        Ok(groups)
+    }
+
+    /// Gets a group and its members by name, ignoring case.
+    ///
+    /// # Errors
+    /// Returns [`crate::Error::GroupNotFound`] if the project has no group
+    /// with this name.
+    pub async fn get_by_name(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        name: &str,
+    ) -> Result<AgentGroup> {
+        let name = name.trim();
+        Self::list_for_project(ctx, mm, project_id)
+            .await?
+            .into_iter()
+            .find(|g| g.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| crate::Error::GroupNotFound(name.to_string()))
+    }
+
+    /// Deletes a group. Messages already sent to it keep their recipients
+    /// and their recorded expansion.
+    ///
+    /// # Errors
+    /// Returns [`crate::Error::GroupNotFound`] if the project has no group
+    /// with this name.
+    pub async fn delete(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        name: &str,
+    ) -> Result<()> {
+        // Also checks access and existence
+        let group = Self::get_by_name(ctx, mm, project_id, name).await?;
+
+        let db = mm.db();
+        // Foreign keys may be off, so the cascade is done by hand
+        let stmt = db
+            .prepare("DELETE FROM agent_group_members WHERE group_id = ?")
+            .await?;
+        stmt.execute([group.id]).await?;
+
+        let stmt = db.prepare("DELETE FROM agent_groups WHERE id = ?").await?;
+        stmt.execute([group.id]).await?;
+        Ok(())
+    }
+
+    /// Adds a project agent to a group.
+    ///
+    /// # Returns
+    /// `false` if the agent was already a member
+    ///
+    /// # Errors
+    /// - [`crate::Error::GroupNotFound`] if the project has no such group
+    /// - [`crate::Error::AgentNotFound`] if the project has no such agent
+    pub async fn add_member(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        group_name: &str,
+        agent_name: &str,
+    ) -> Result<bool> {
+        let group = Self::get_by_name(ctx, mm, project_id, group_name).await?;
+        let agent = AgentBmc::get_by_name(ctx, mm, project_id, agent_name.trim()).await?;
+
+        let db = mm.db();
+        let stmt = db
+            .prepare("INSERT OR IGNORE INTO agent_group_members (group_id, agent_id) VALUES (?, ?)")
+            .await?;
+        let inserted = stmt.execute((group.id, agent.id.get())).await?;
+        Ok(inserted > 0)
+    }
+
+    /// Removes an agent from a group. Messages the agent already received
+    /// through the group are unaffected.
+    ///
+    /// # Returns
+    /// `false` if the agent was not a member
+    ///
+    /// # Errors
+    /// - [`crate::Error::GroupNotFound`] if the project has no such group
+    /// - [`crate::Error::AgentNotFound`] if the project has no such agent
+    pub async fn remove_member(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        group_name: &str,
+        agent_name: &str,
+    ) -> Result<bool> {
+        let group = Self::get_by_name(ctx, mm, project_id, group_name).await?;
+        let agent = AgentBmc::get_by_name(ctx, mm, project_id, agent_name.trim()).await?;
+
+        let db = mm.db();
+        let stmt = db
+            .prepare("DELETE FROM agent_group_members WHERE group_id = ? AND agent_id = ?")
+            .await?;
+        let removed = stmt.execute((group.id, agent.id.get())).await?;
+        Ok(removed > 0)
+    }
+
+    /// Expands a group to its current non-retired members, for a send.
+    ///
+    /// # Errors
+    /// - [`crate::Error::GroupNotFound`] if the project has no such group
+    /// - [`crate::Error::GroupEmpty`] if no non-retired agent is a member
+    pub async fn expand(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        name: &str,
+    ) -> Result<GroupExpansion> {
+        let group = Self::get_by_name(ctx, mm, project_id, name).await?;
+
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT a.id, a.name
+            FROM agent_group_members AS m
+            JOIN agents AS a ON a.id = m.agent_id
+            LEFT JOIN agent_retirements AS r ON r.agent_id = a.id
+            WHERE m.group_id = ? AND r.agent_id IS NULL
+            ORDER BY a.name ASC
+            "#,
+            )
+            .await?;
+        let mut rows = stmt.query([group.id]).await?;
+        let mut expansion = GroupExpansion {
+            group: group.name,
+            agent_ids: Vec::new(),
+            members: Vec::new(),
+        };
+        while let Some(row) = rows.next().await? {
+            expansion.agent_ids.push(row.get(0)?);
+            expansion.members.push(row.get(1)?);
+        }
+        if expansion.agent_ids.is_empty() {
+            return Err(crate::Error::GroupEmpty(expansion.group));
+        }
+        Ok(expansion)
+    }
+
+    /// Records the group expansions a message was sent with.
+    pub async fn record_expansions(
+        mm: &ModelManager,
+        message_id: i64,
+        expansions: &[GroupExpansion],
+    ) -> Result<()> {
+        if expansions.is_empty() {
+            return Ok(());
+        }
+
+        let db = mm.db();
+        let stmt = db
+            .prepare(
+                "INSERT OR IGNORE INTO message_group_expansions (message_id, group_name, agent_id) VALUES (?, ?, ?)",
+            )
+            .await?;
+        for expansion in expansions {
+            for agent_id in &expansion.agent_ids {
+                stmt.reset();
+                stmt.execute((message_id, expansion.group.as_str(), *agent_id))
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The group expansions recorded for a message, ordered by group name.
+    pub async fn expansions_for_message(
+        mm: &ModelManager,
+        message_id: i64,
+    ) -> Result<Vec<GroupExpansion>> {
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT e.group_name, e.agent_id, a.name
+            FROM message_group_expansions AS e
+            JOIN agents AS a ON a.id = e.agent_id
+            WHERE e.message_id = ?
+            ORDER BY e.group_name ASC, a.name ASC
+            "#,
+            )
+            .await?;
+        let mut rows = stmt.query([message_id]).await?;
+        let mut expansions: Vec<GroupExpansion> = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let group: String = row.get(0)?;
+            if expansions.last().is_none_or(|e| e.group != group) {
+                expansions.push(GroupExpansion {
+                    group,
+                    agent_ids: Vec::new(),
+                    members: Vec::new(),
+                });
+            }
+            if let Some(expansion) = expansions.last_mut() {
+                expansion.agent_ids.push(row.get(1)?);
+                expansion.members.push(row.get(2)?);
+            }
+        }
+        Ok(expansions)
+    }
+}
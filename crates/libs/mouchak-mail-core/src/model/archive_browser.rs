@@ -29,9 +29,10 @@ use crate::{Ctx, Error, Result};
 use chrono::{DateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use utoipa::ToSchema;
 
 /// Summary information about a commit.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CommitSummary {
     /// Short commit SHA (7 chars)
     pub short_sha: String,
@@ -50,7 +51,7 @@ pub struct CommitSummary {
 }
 
 /// Detailed information about a commit.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct CommitDetails {
     /// Full commit SHA
     pub sha: String,
@@ -73,7 +74,7 @@ pub struct CommitDetails {
 }
 
 /// A file/directory entry at a specific commit.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct FileEntry {
     /// File or directory name
     pub name: String,
@@ -86,7 +87,7 @@ pub struct FileEntry {
 }
 
 /// File content at a specific commit.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct FileContent {
     /// File path
     pub path: String,
@@ -114,7 +115,7 @@ pub struct FileHistoryEntry {
 }
 
 /// Activity summary for a time period.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ActivitySummary {
     /// Start of the period
     pub period_start: DateTime<Utc>,
@@ -126,7 +127,8 @@ pub struct ActivitySummary {
     pub commits_by_day: HashMap<String, usize>,
     /// Commits per author
     pub commits_by_author: HashMap<String, usize>,
-    /// Most active files
+    /// Most active files, as `[path, commit_count]` pairs
+    #[schema(value_type = Vec<Vec<Object>>)]
     pub most_changed_files: Vec<(String, usize)>,
 }
 
@@ -802,7 +804,7 @@ impl ArchiveBrowserBmc {
 
         // Get top 10 most changed files
         let mut file_counts: Vec<_> = file_change_counts.into_iter().collect();
-        file_counts.sort_by(|a, b| b.1.cmp(&a.1));
+        file_counts.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
         let most_changed_files: Vec<_> = file_counts.into_iter().take(10).collect();
 
         Ok(ActivitySummary {
@@ -0,0 +1,491 @@
+//! Archive Integrity - DB vs Git archive consistency checks
+//!
+//! Compares the canonical message files under
+//! `projects/{slug}/messages/{YYYY}/{MM}/` with the `messages` table:
+//! - **Missing**: a delivered message has no canonical file
+//! - **Orphan**: a file whose id is unknown, unparseable or duplicated
+//! - **Mismatched**: a file whose content hash differs from what the DB implies
+//!
+//! Files are looked up in both archive layouts (shared repository and
+//! `{slug}/` per-project repository); reported paths are relative to the
+//! configured `repo_root`.
+//!
+//! Repair re-writes missing messages from the DB (canonical, outbox and inbox
+//! copies, as set by `archive.mailbox_copies`) into the project's current
+//! repository and commits them in a single commit. Running it twice is a no-op.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use mouchak_mail_core::model::archive_integrity::ArchiveIntegrityBmc;
+//!
+//! let report = ArchiveIntegrityBmc::verify_archive(&ctx, &mm, "my-app").await?;
+//! if !report.is_clean() {
+//!     ArchiveIntegrityBmc::repair_archive(&ctx, &mm, "my-app").await?;
+//! }
+//! ```
+
+use crate::model::ModelManager;
+use crate::model::message::{
+    build_message_paths, format_message_content, mailbox_copy_content, write_archive_file,
+};
+use crate::model::project::ProjectBmc;
+use crate::store::git_store::{self, StagingArea};
+use crate::{Ctx, Error, Result};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sha1::Digest;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
+
+/// Maximum number of paths listed per category in a report.
+pub const REPORT_SAMPLE_LIMIT: usize = 50;
+
+/// Result of an archive integrity check (and optional repair).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct ArchiveReport {
+    /// Project that was checked
+    pub project_slug: String,
+    /// Delivered messages found in the DB
+    pub messages_checked: usize,
+    /// Canonical message files found on disk
+    pub files_scanned: usize,
+    /// Messages with no canonical file
+    pub missing_count: usize,
+    /// Files that don't correspond to exactly one DB message
+    pub orphan_count: usize,
+    /// Files whose content hash differs from the DB
+    pub mismatched_count: usize,
+    /// Messages re-written by repair (0 for a plain verify)
+    pub repaired_count: usize,
+    /// Expected paths of missing messages (bounded sample)
+    pub missing: Vec<String>,
+    /// Orphan file paths (bounded sample)
+    pub orphans: Vec<String>,
+    /// Mismatched file paths (bounded sample)
+    pub mismatched: Vec<String>,
+}
+
+impl ArchiveReport {
+    /// True when no missing, orphan or mismatched files were found.
+    pub fn is_clean(&self) -> bool {
+        self.missing_count == 0 && self.orphan_count == 0 && self.mismatched_count == 0
+    }
+
+    fn sample(list: &mut Vec<String>, path: &Path) {
+        if list.len() < REPORT_SAMPLE_LIMIT {
+            list.push(path.to_string_lossy().to_string());
+        }
+    }
+}
+
+/// A message as the archive should contain it.
+struct ExpectedMessage {
+    id: i64,
+    sender_name: String,
+    /// "To" recipients, named in the frontmatter
+    recipient_names: Vec<String>,
+    /// Every recipient, each with an inbox copy
+    mailbox_names: Vec<String>,
+    subject: String,
+    body_md: String,
+    thread_id: String,
+    importance: String,
+    created_ts: NaiveDateTime,
+}
+
+impl ExpectedMessage {
+    /// Render archive content with the given frontmatter `created` value.
+    fn render(&self, project_slug: &str, created_iso: &str) -> Result<String> {
+        format_message_content(
+            self.id,
+            project_slug,
+            &self.sender_name,
+            &self.recipient_names,
+            &self.subject,
+            &self.body_md,
+            &self.thread_id,
+            &self.importance,
+            created_iso,
+        )
+    }
+}
+
+/// Backend Model Controller for archive integrity checks.
+pub struct ArchiveIntegrityBmc;
+
+impl ArchiveIntegrityBmc {
+    /// Report missing, orphan and mismatched message files for a project.
+    pub async fn verify_archive(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_slug: &str,
+    ) -> Result<ArchiveReport> {
+        let expected = Self::load_expected(ctx, mm, project_slug).await?;
+        let (report, _) = Self::scan(mm, project_slug, &expected)?;
+        Ok(report)
+    }
+
+    /// Verify, then re-write missing messages from the DB and commit them.
+    ///
+    /// Only missing files are written; orphans and mismatches are reported but
+    /// left alone. The returned report describes the state before repair.
+    pub async fn repair_archive(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_slug: &str,
+    ) -> Result<ArchiveReport> {
+        let expected = Self::load_expected(ctx, mm, project_slug).await?;
+
+        let cached_repo = mm.get_project_repo(project_slug).await?;
+        let _git_guard = mm.git_lock.lock().await;
+        let repo = cached_repo.lock().await;
+
+        let (mut report, missing_ids) = Self::scan(mm, project_slug, &expected)?;
+        if missing_ids.is_empty() {
+            return Ok(report);
+        }
+
+        let workdir = repo
+            .workdir()
+            .ok_or(Error::InvalidInput("No workdir".into()))?
+            .to_path_buf();
+        let staging = StagingArea::new(&repo)?;
+        let copies = mm.app_config.archive.mailbox_copies;
+
+        let mut written: Vec<PathBuf> = Vec::new();
+        for id in &missing_ids {
+            let Some(msg) = expected.get(id) else {
+                continue;
+            };
+            let y_dir = msg.created_ts.format("%Y").to_string();
+            let m_dir = msg.created_ts.format("%m").to_string();
+            let created_iso = msg.created_ts.format("%Y-%m-%dT%H-%M-%SZ").to_string();
+            let filename = format!(
+                "{}__{}__{}.md",
+                created_iso,
+                slug::slugify(&msg.subject),
+                msg.id
+            );
+            let paths = build_message_paths(
+                project_slug,
+                &msg.sender_name,
+                &msg.mailbox_names,
+                &filename,
+                &y_dir,
+                &m_dir,
+            );
+            let content = msg.render(project_slug, &created_iso)?;
+
+            let mailbox = mailbox_copy_content(msg.id, &paths.canonical, &content, copies)?;
+
+            write_archive_file(staging.root(), &paths.canonical, &content)?;
+            written.push(paths.canonical);
+            // Mailbox copies may have survived; don't duplicate them
+            for copy in std::iter::once(&paths.outbox).chain(&paths.inboxes) {
+                if !has_message_file(&workdir.join(copy), msg.id) {
+                    write_archive_file(staging.root(), copy, &mailbox)?;
+                    written.push(copy.clone());
+                }
+            }
+        }
+
+        let (author_name, author_email) = git_store::author_of(ctx.actor());
+        staging.commit(
+            &repo,
+            &written,
+            &format!(
+                "archive: repair {} message(s) in {}",
+                missing_ids.len(),
+                project_slug
+            ),
+            author_name,
+            author_email,
+        )?;
+
+        report.repaired_count = missing_ids.len();
+        Ok(report)
+    }
+
+    /// Load delivered messages (scheduled-but-pending ones are not archived yet).
+    async fn load_expected(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_slug: &str,
+    ) -> Result<HashMap<i64, ExpectedMessage>> {
+        let project = ProjectBmc::get_by_slug(ctx, mm, project_slug).await?;
+        // Queued messages aren't on disk yet; don't report them as missing
+        mm.archive_queue.flush().await;
+        let db = mm.db();
+
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT m.id, ag.name, m.subject, b.body_md, m.thread_id, m.importance, m.created_ts
+            FROM messages AS m
+            JOIN message_bodies AS b ON b.message_id = m.id
+            JOIN agents AS ag ON m.sender_id = ag.id
+            LEFT JOIN message_schedules AS s ON s.message_id = m.id
+            WHERE m.project_id = ? AND (s.message_id IS NULL OR s.status = 'delivered')
+            "#,
+            )
+            .await?;
+        let mut rows = stmt.query([project.id.get()]).await?;
+        let mut expected = HashMap::new();
+        while let Some(row) = rows.next().await? {
+            let id: i64 = row.get(0)?;
+            let created_ts: String = row.get(6)?;
+            expected.insert(
+                id,
+                ExpectedMessage {
+                    id,
+                    sender_name: row.get(1)?,
+                    recipient_names: Vec::new(),
+                    mailbox_names: Vec::new(),
+                    subject: row.get(2)?,
+                    body_md: row.get(3)?,
+                    thread_id: row.get::<Option<String>>(4)?.unwrap_or_default(),
+                    importance: row.get(5)?,
+                    created_ts: NaiveDateTime::parse_from_str(&created_ts, "%Y-%m-%d %H:%M:%S")
+                        .unwrap_or_default(),
+                },
+            );
+        }
+
+        // Only "to" recipients are named in the content, in insertion order
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT mr.message_id, ag.name, mr.recipient_type
+            FROM message_recipients AS mr
+            JOIN messages AS m ON mr.message_id = m.id
+            JOIN agents AS ag ON mr.agent_id = ag.id
+            WHERE m.project_id = ?
+            ORDER BY mr.rowid
+            "#,
+            )
+            .await?;
+        let mut rows = stmt.query([project.id.get()]).await?;
+        while let Some(row) = rows.next().await? {
+            let id: i64 = row.get(0)?;
+            if let Some(msg) = expected.get_mut(&id) {
+                let name: String = row.get(1)?;
+                if row.get::<String>(2)? == "to" {
+                    msg.recipient_names.push(name.clone());
+                }
+                msg.mailbox_names.push(name);
+            }
+        }
+
+        Ok(expected)
+    }
+
+    /// Walk the canonical message tree and compare it against `expected`.
+    ///
+    /// Returns the report plus the ids of messages with no canonical file.
+    fn scan(
+        mm: &ModelManager,
+        project_slug: &str,
+        expected: &HashMap<i64, ExpectedMessage>,
+    ) -> Result<(ArchiveReport, Vec<i64>)> {
+        let rel_root = PathBuf::from("projects")
+            .join(project_slug)
+            .join("messages");
+        // Both layouts are scanned: history written before a switch to
+        // per-project repos stays in the shared one
+        let mut files = list_canonical_files(&mm.repo_root, &rel_root)?;
+        files.extend(list_canonical_files(
+            &mm.repo_root,
+            &Path::new(project_slug).join(&rel_root),
+        )?);
+        let missing_root = mm
+            .project_repo_root(project_slug)
+            .strip_prefix(&mm.repo_root)
+            .map(|prefix| prefix.join(&rel_root))
+            .unwrap_or_else(|_| rel_root.clone());
+
+        let mut report = ArchiveReport {
+            project_slug: project_slug.to_string(),
+            messages_checked: expected.len(),
+            files_scanned: files.len(),
+            ..Default::default()
+        };
+
+        let mut seen: HashSet<i64> = HashSet::new();
+        for rel in &files {
+            let id = rel
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(message_id_from_filename);
+            let msg = match id.and_then(|id| expected.get(&id)) {
+                Some(msg) if seen.insert(msg.id) => msg,
+                _ => {
+                    report.orphan_count += 1;
+                    ArchiveReport::sample(&mut report.orphans, rel);
+                    continue;
+                }
+            };
+
+            let content = std::fs::read_to_string(mm.repo_root.join(rel)).unwrap_or_default();
+            let matches = match frontmatter_created(&content) {
+                Some(created_iso) => {
+                    let rendered = msg.render(project_slug, &created_iso)?;
+                    content_hash(&content) == content_hash(&rendered)
+                }
+                None => false,
+            };
+            if !matches {
+                report.mismatched_count += 1;
+                ArchiveReport::sample(&mut report.mismatched, rel);
+            }
+        }
+
+        let mut missing_ids: Vec<i64> = expected
+            .keys()
+            .filter(|id| !seen.contains(id))
+            .copied()
+            .collect();
+        missing_ids.sort_unstable();
+        report.missing_count = missing_ids.len();
+        for id in &missing_ids {
+            if let Some(msg) = expected.get(id) {
+                let path = missing_root
+                    .join(msg.created_ts.format("%Y").to_string())
+                    .join(msg.created_ts.format("%m").to_string())
+                    .join(format!(
+                        "{}__{}__{}.md",
+                        msg.created_ts.format("%Y-%m-%dT%H-%M-%SZ"),
+                        slug::slugify(&msg.subject),
+                        msg.id
+                    ));
+                ArchiveReport::sample(&mut report.missing, &path);
+            }
+        }
+
+        Ok((report, missing_ids))
+    }
+}
+
+/// Ids of the messages with a canonical file in either archive layout.
+pub(crate) fn archived_message_ids(mm: &ModelManager, project_slug: &str) -> Result<HashSet<i64>> {
+    let rel_root = PathBuf::from("projects")
+        .join(project_slug)
+        .join("messages");
+    let mut files = list_canonical_files(&mm.repo_root, &rel_root)?;
+    files.extend(list_canonical_files(
+        &mm.repo_root,
+        &Path::new(project_slug).join(&rel_root),
+    )?);
+    Ok(files
+        .iter()
+        .filter_map(|rel| {
+            rel.file_name()
+                .and_then(|n| n.to_str())
+                .and_then(message_id_from_filename)
+        })
+        .collect())
+}
+
+/// List `{YYYY}/{MM}/*.md` files under `rel_root`, relative to `repo_root`.
+///
+/// Other entries (e.g. the `recalls/` tombstones) are not message files.
+fn list_canonical_files(repo_root: &Path, rel_root: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let root = repo_root.join(rel_root);
+    if !root.is_dir() {
+        return Ok(files);
+    }
+    for year in sorted_subdirs(&root)? {
+        for month in sorted_subdirs(&root.join(&year))? {
+            let dir = root.join(&year).join(&month);
+            let mut names: Vec<String> = std::fs::read_dir(&dir)?
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().is_file())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .filter(|n| n.ends_with(".md"))
+                .collect();
+            names.sort();
+            files.extend(
+                names
+                    .into_iter()
+                    .map(|n| rel_root.join(&year).join(&month).join(n)),
+            );
+        }
+    }
+    Ok(files)
+}
+
+/// Numeric subdirectories, sorted (year and month dirs).
+fn sorted_subdirs(dir: &Path) -> Result<Vec<String>> {
+    let mut names: Vec<String> = std::fs::read_dir(dir)?
+        .filter_map(|e| e.ok())
+        .filter(|e| e.path().is_dir())
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .filter(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        .collect();
+    names.sort();
+    Ok(names)
+}
+
+/// Extract the message id from `{created}__{subject}__{id}.md`.
+fn message_id_from_filename(name: &str) -> Option<i64> {
+    let stem = name.strip_suffix(".md")?;
+    let (_, id) = stem.rsplit_once("__")?;
+    id.parse().ok()
+}
+
+/// True if `path`'s directory already holds a file for message `id`.
+fn has_message_file(path: &Path, id: i64) -> bool {
+    let Some(dir) = path.parent() else {
+        return false;
+    };
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return false;
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .any(|e| message_id_from_filename(&e.file_name().to_string_lossy()) == Some(id))
+}
+
+/// Read the `created` value from a `---json` frontmatter block.
+fn frontmatter_created(content: &str) -> Option<String> {
+    let rest = content.strip_prefix("---json\n")?;
+    let (json, _) = rest.split_once("\n---\n")?;
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    value.get("created")?.as_str().map(str::to_string)
+}
+
+fn content_hash(content: &str) -> String {
+    hex::encode(sha1::Sha1::digest(content.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_id_from_filename() {
+        assert_eq!(
+            message_id_from_filename("2025-01-01T10-00-00Z__hello__42.md"),
+            Some(42)
+        );
+        assert_eq!(
+            message_id_from_filename("2025-01-01T10-00-00Z__a__b__7.md"),
+            Some(7)
+        );
+        assert_eq!(message_id_from_filename("notes.md"), None);
+        assert_eq!(message_id_from_filename("x__abc.md"), None);
+        assert_eq!(message_id_from_filename("x__1.txt"), None);
+    }
+
+    #[test]
+    fn test_frontmatter_created() {
+        let content = "---json\n{\n  \"created\": \"2025-01-01T10-00-00Z\"\n}\n---\n\nbody";
+        assert_eq!(
+            frontmatter_created(content),
+            Some("2025-01-01T10-00-00Z".to_string())
+        );
+        assert_eq!(frontmatter_created("no frontmatter"), None);
+    }
+}
@@ -0,0 +1,334 @@
+//! Write-behind Archive Queue
+//!
+//! `MessageBmc::create` commits the DB rows and returns; the message files are
+//! handed to a single writer task over a bounded channel. The writer drains up
+//! to `batch_size` jobs at a time and records them in one commit, so senders
+//! never wait on `git_lock`.
+//!
+//! With `archive.sync = true` jobs are committed before [`ArchiveQueue::submit`]
+//! returns instead.
+//!
+//! Every file of a batch (canonical, outbox and each recipient's inbox) is
+//! written to a [`StagingArea`] first. If any write fails nothing is committed;
+//! the batch is then retried one job per commit, and only the jobs that fail
+//! again are dropped, with the failing path in [`ArchiveQueueStats::last_error`].
+
+use crate::ctx::Actor;
+use crate::error::Result;
+use crate::model::message::{MessageArchivePaths, stage_message_files};
+use crate::model::open_archive_repo;
+use crate::store::git_store::{self, StagingArea};
+use crate::store::repo_cache::RepoCache;
+use mouchak_mail_common::config::{ArchiveConfig, MailboxCopies};
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
+use tokio::sync::{Mutex, mpsc, oneshot};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+/// Files to write for one message, committed together with other queued jobs.
+pub struct ArchiveJob {
+    pub(crate) message_id: i64,
+    /// Working directory of the project's archive repository
+    pub(crate) repo_root: PathBuf,
+    pub(crate) paths: MessageArchivePaths,
+    pub(crate) content: String,
+    /// One-line commit message used when the job is committed on its own
+    pub(crate) summary: String,
+    /// Commit author; `None` commits as the service identity
+    pub(crate) author: Option<Actor>,
+    pub(crate) enqueued_at: Instant,
+}
+
+/// Queue counters, including how far commits lag behind sends.
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct ArchiveQueueStats {
+    /// `true` when jobs are committed inline (`archive.sync`)
+    pub sync: bool,
+    /// Jobs submitted but not yet committed
+    pub pending: u64,
+    /// Jobs committed to the archive
+    pub committed: u64,
+    /// Commits created by the writer
+    pub batches: u64,
+    /// Jobs whose files could not be written or committed
+    pub failed: u64,
+    /// Longest time a committed job waited between submit and commit
+    pub max_lag_ms: u64,
+    /// Mean time a committed job waited between submit and commit
+    pub avg_lag_ms: u64,
+    /// Most recent write or commit failure, naming the file when a write failed
+    pub last_error: Option<String>,
+}
+
+enum QueueItem {
+    Job(Box<ArchiveJob>),
+    Flush(oneshot::Sender<()>),
+}
+
+#[derive(Default)]
+struct Counters {
+    pending: AtomicU64,
+    committed: AtomicU64,
+    batches: AtomicU64,
+    failed: AtomicU64,
+    total_lag_ms: AtomicU64,
+    max_lag_ms: AtomicU64,
+    last_error: std::sync::Mutex<Option<String>>,
+}
+
+/// What the writer needs to reach the repository.
+#[derive(Clone)]
+struct Writer {
+    repo_cache: Arc<RepoCache>,
+    git_lock: Arc<Mutex<()>>,
+    counters: Arc<Counters>,
+    mailbox_copies: MailboxCopies,
+}
+
+/// Bounded write-behind queue for message archive commits.
+pub struct ArchiveQueue {
+    writer: Writer,
+    config: ArchiveConfig,
+    /// Started on first submit so the writer runs on the caller's runtime
+    sender: OnceLock<mpsc::Sender<QueueItem>>,
+}
+
+impl ArchiveQueue {
+    pub fn new(
+        repo_cache: Arc<RepoCache>,
+        git_lock: Arc<Mutex<()>>,
+        config: ArchiveConfig,
+    ) -> Self {
+        Self {
+            writer: Writer {
+                repo_cache,
+                git_lock,
+                counters: Arc::new(Counters::default()),
+                mailbox_copies: config.mailbox_copies,
+            },
+            config,
+            sender: OnceLock::new(),
+        }
+    }
+
+    /// True when jobs are committed before `submit` returns.
+    pub fn is_sync(&self) -> bool {
+        self.config.sync
+    }
+
+    /// Archive a message; returns `true` if the commit is still pending.
+    ///
+    /// Waits only when the queue is full. Failures are logged, never returned:
+    /// the DB write has already succeeded.
+    pub async fn submit(&self, job: ArchiveJob) -> bool {
+        let counters = &self.writer.counters;
+        counters.pending.fetch_add(1, Ordering::Relaxed);
+
+        if self.config.sync {
+            self.writer.commit_batch(vec![job]).await;
+            return false;
+        }
+
+        let message_id = job.message_id;
+        if self
+            .sender()
+            .send(QueueItem::Job(Box::new(job)))
+            .await
+            .is_err()
+        {
+            counters.pending.fetch_sub(1, Ordering::Relaxed);
+            counters.failed.fetch_add(1, Ordering::Relaxed);
+            warn!(
+                "Archive writer stopped; message {} not archived",
+                message_id
+            );
+            return false;
+        }
+        true
+    }
+
+    /// Wait until every job submitted so far has been committed.
+    pub async fn flush(&self) {
+        let Some(sender) = self.sender.get() else {
+            return;
+        };
+        let (tx, rx) = oneshot::channel();
+        if sender.send(QueueItem::Flush(tx)).await.is_ok() {
+            let _ = rx.await;
+        }
+    }
+
+    pub fn stats(&self) -> ArchiveQueueStats {
+        let c = &self.writer.counters;
+        let committed = c.committed.load(Ordering::Relaxed);
+        ArchiveQueueStats {
+            sync: self.config.sync,
+            pending: c.pending.load(Ordering::Relaxed),
+            committed,
+            batches: c.batches.load(Ordering::Relaxed),
+            failed: c.failed.load(Ordering::Relaxed),
+            max_lag_ms: c.max_lag_ms.load(Ordering::Relaxed),
+            avg_lag_ms: c
+                .total_lag_ms
+                .load(Ordering::Relaxed)
+                .checked_div(committed)
+                .unwrap_or(0),
+            last_error: c
+                .last_error
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .clone(),
+        }
+    }
+
+    fn sender(&self) -> &mpsc::Sender<QueueItem> {
+        self.sender.get_or_init(|| {
+            let (tx, rx) = mpsc::channel(self.config.queue_capacity.max(1));
+            let writer = self.writer.clone();
+            let batch_size = self.config.batch_size.max(1);
+            tokio::spawn(writer.run(rx, batch_size));
+            tx
+        })
+    }
+}
+
+impl Writer {
+    /// Drain the channel until every sender is dropped.
+    async fn run(self, mut rx: mpsc::Receiver<QueueItem>, batch_size: usize) {
+        while let Some(item) = rx.recv().await {
+            let mut jobs = Vec::new();
+            let mut flushes = Vec::new();
+            let mut next = Some(item);
+            while let Some(item) = next.take() {
+                match item {
+                    QueueItem::Job(job) => jobs.push(*job),
+                    QueueItem::Flush(tx) => flushes.push(tx),
+                }
+                if jobs.len() < batch_size {
+                    next = rx.try_recv().ok();
+                }
+            }
+
+            // One commit per repository and author (several with the
+            // per-project layout or when different agents sent)
+            let mut by_repo: Vec<(PathBuf, Option<Actor>, Vec<ArchiveJob>)> = Vec::new();
+            for job in jobs {
+                match by_repo
+                    .iter_mut()
+                    .find(|(root, author, _)| *root == job.repo_root && *author == job.author)
+                {
+                    Some((_, _, group)) => group.push(job),
+                    None => by_repo.push((job.repo_root.clone(), job.author.clone(), vec![job])),
+                }
+            }
+            for (_, _, group) in by_repo {
+                self.commit_batch(group).await;
+            }
+            for tx in flushes {
+                let _ = tx.send(());
+            }
+        }
+    }
+
+    /// Write every job's files and record them in one commit.
+    ///
+    /// All jobs must target the same repository and share an author. If the
+    /// batch fails each job is retried in a commit of its own, so one bad
+    /// message doesn't keep the rest out of the archive.
+    async fn commit_batch(&self, jobs: Vec<ArchiveJob>) {
+        if jobs.len() > 1 {
+            match self.write_and_commit(&jobs).await {
+                Ok(()) => {
+                    self.record_committed(&jobs);
+                    info!("Archived {} message(s) in one commit", jobs.len());
+                    return;
+                }
+                Err(e) => warn!(
+                    "Archive commit of {} messages failed, retrying one by one: {}",
+                    jobs.len(),
+                    e
+                ),
+            }
+        }
+
+        for job in jobs {
+            let job = std::slice::from_ref(&job);
+            match self.write_and_commit(job).await {
+                Ok(()) => self.record_committed(job),
+                Err(e) => self.record_failed(&job[0], e.to_string()),
+            }
+        }
+    }
+
+    /// Count `jobs` as archived by one commit.
+    fn record_committed(&self, jobs: &[ArchiveJob]) {
+        let counters = &self.counters;
+        let count = jobs.len() as u64;
+        for job in jobs {
+            let lag = job.enqueued_at.elapsed().as_millis() as u64;
+            counters.total_lag_ms.fetch_add(lag, Ordering::Relaxed);
+            counters.max_lag_ms.fetch_max(lag, Ordering::Relaxed);
+        }
+        counters.batches.fetch_add(1, Ordering::Relaxed);
+        counters.committed.fetch_add(count, Ordering::Relaxed);
+        counters.pending.fetch_sub(count, Ordering::Relaxed);
+    }
+
+    /// Count `job` as dropped after its own commit failed.
+    fn record_failed(&self, job: &ArchiveJob, error: String) {
+        let counters = &self.counters;
+        warn!(
+            "Archive commit failed for message {}: {}",
+            job.message_id, error
+        );
+        counters.failed.fetch_add(1, Ordering::Relaxed);
+        counters.pending.fetch_sub(1, Ordering::Relaxed);
+        *counters
+            .last_error
+            .lock()
+            .unwrap_or_else(|e| e.into_inner()) = Some(error);
+    }
+
+    /// Stage every job's files, then commit them all at once.
+    async fn write_and_commit(&self, jobs: &[ArchiveJob]) -> Result<()> {
+        let Some(first) = jobs.first() else {
+            return Ok(());
+        };
+        let cached_repo = open_archive_repo(&self.repo_cache, &first.repo_root).await?;
+        let _git_guard = self.git_lock.lock().await;
+        let repo = cached_repo.lock().await;
+
+        let staging = StagingArea::new(&repo)?;
+        let mut paths: Vec<PathBuf> = Vec::new();
+        for job in jobs {
+            paths.extend(stage_message_files(
+                staging.root(),
+                job.message_id,
+                &job.paths,
+                &job.content,
+                self.mailbox_copies,
+            )?);
+        }
+
+        let message = match jobs {
+            [job] => job.summary.clone(),
+            _ => format!(
+                "mail: archive {} messages\n\n{}",
+                jobs.len(),
+                jobs.iter()
+                    .map(|j| j.summary.as_str())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            ),
+        };
+        let author = first.author.as_ref();
+        let message = git_store::with_actor_trailer(&message, author);
+        let (author_name, author_email) = git_store::author_of(author);
+        staging.commit(&repo, &paths, &message, author_name, author_email)?;
+        Ok(())
+    }
+}
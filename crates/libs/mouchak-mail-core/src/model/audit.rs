@@ -0,0 +1,278 @@
+//! Audit log of administrative actions.
+//!
+//! Force-releasing a reservation, adopting or deleting a project, deleting or
+//! retiring an agent, recalling a message and pruning old messages each
+//! append an [`AuditEntry`] recording who did it (the [`Ctx`] actor) and a
+//! before/after summary. Entries are written with [`AuditBmc::record_in`]
+//! inside the action's own transaction where the action has one, so an
+//! action can't commit without its entry.
+//!
+//! Entries have no foreign keys and are kept when the project or agent they
+//! describe is deleted.
+
+use crate::Result;
+use crate::ctx::Ctx;
+use crate::model::ModelManager;
+use crate::model::project::ProjectBmc;
+use crate::types::ProjectId;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::str::FromStr;
+use utoipa::ToSchema;
+
+/// An audited action.
+///
+/// Serialized as `entity.verb`, e.g. `"agent.delete"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub enum AuditAction {
+    #[serde(rename = "reservation.force_release")]
+    ReservationForceRelease,
+    #[serde(rename = "reservation.transfer")]
+    ReservationTransfer,
+    #[serde(rename = "project.adopt")]
+    ProjectAdopt,
+    #[serde(rename = "project.delete")]
+    ProjectDelete,
+    #[serde(rename = "agent.delete")]
+    AgentDelete,
+    #[serde(rename = "agent.retire")]
+    AgentRetire,
+    #[serde(rename = "message.recall")]
+    MessageRecall,
+    #[serde(rename = "retention.prune")]
+    RetentionPrune,
+}
+
+impl AuditAction {
+    pub const ALL: [AuditAction; 8] = [
+        AuditAction::ReservationForceRelease,
+        AuditAction::ReservationTransfer,
+        AuditAction::ProjectAdopt,
+        AuditAction::ProjectDelete,
+        AuditAction::AgentDelete,
+        AuditAction::AgentRetire,
+        AuditAction::MessageRecall,
+        AuditAction::RetentionPrune,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuditAction::ReservationForceRelease => "reservation.force_release",
+            AuditAction::ReservationTransfer => "reservation.transfer",
+            AuditAction::ProjectAdopt => "project.adopt",
+            AuditAction::ProjectDelete => "project.delete",
+            AuditAction::AgentDelete => "agent.delete",
+            AuditAction::AgentRetire => "agent.retire",
+            AuditAction::MessageRecall => "message.recall",
+            AuditAction::RetentionPrune => "retention.prune",
+        }
+    }
+
+    /// Kind of entity the action applies to; `entity_id` is one of its ids.
+    pub fn entity_type(self) -> &'static str {
+        match self {
+            AuditAction::ReservationForceRelease | AuditAction::ReservationTransfer => {
+                "reservation"
+            }
+            AuditAction::ProjectAdopt
+            | AuditAction::ProjectDelete
+            | AuditAction::RetentionPrune => "project",
+            AuditAction::AgentDelete | AuditAction::AgentRetire => "agent",
+            AuditAction::MessageRecall => "message",
+        }
+    }
+}
+
+impl std::fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|a| a.as_str() == s)
+            .ok_or_else(|| {
+                let known: Vec<&str> = Self::ALL.iter().map(|a| a.as_str()).collect();
+                crate::Error::InvalidInput(format!(
+                    "Unknown audit action '{}' (expected one of: {})",
+                    s,
+                    known.join(", ")
+                ))
+            })
+    }
+}
+
+/// One audit log entry.
+///
+/// # Fields
+///
+/// - `project_id` - Project the action happened in
+/// - `action` - What was done
+/// - `entity_type` / `entity_id` - What it was done to
+/// - `actor` - Who did it, as `Name <email>`, if the request named one
+/// - `user_id` - Authenticated user id (0 for system tasks)
+/// - `detail` - JSON summary, usually `before` and `after` objects
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub project_id: i64,
+    pub action: AuditAction,
+    pub entity_type: String,
+    pub entity_id: i64,
+    pub actor: Option<String>,
+    pub user_id: i64,
+    pub detail: Value,
+    pub created_ts: NaiveDateTime,
+}
+
+/// Filters for [`AuditBmc::list`]; unset fields match everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditFilter {
+    pub action: Option<AuditAction>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<i64>,
+}
+
+/// One page of [`AuditBmc::list`] results.
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct AuditPage {
+    /// Entries, newest first
+    pub items: Vec<AuditEntry>,
+    /// Pass back as `cursor` for the next page; `None` on the last page
+    pub next_cursor: Option<i64>,
+}
+
+/// Backend Model Controller for the audit log.
+pub struct AuditBmc;
+
+impl AuditBmc {
+    /// Append an entry on the writer connection.
+    ///
+    /// Use [`Self::record_in`] instead when the action runs in a transaction.
+    pub async fn record(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        action: AuditAction,
+        entity_id: i64,
+        detail: Value,
+    ) -> Result<()> {
+        Self::record_in(mm.db(), ctx, project_id, action, entity_id, detail).await
+    }
+
+    /// Append an entry on `conn`, typically the action's open transaction.
+    pub async fn record_in(
+        conn: &libsql::Connection,
+        ctx: &Ctx,
+        project_id: ProjectId,
+        action: AuditAction,
+        entity_id: i64,
+        detail: Value,
+    ) -> Result<()> {
+        let actor = ctx
+            .actor()
+            .map(ToString::to_string)
+            .or_else(|| ctx.agent_name().map(str::to_string));
+        let stmt = conn
+            .prepare(
+                r#"
+            INSERT INTO audit_log (project_id, action, entity_type, entity_id, actor, user_id, detail)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            "#,
+            )
+            .await?;
+        stmt.execute((
+            project_id.get(),
+            action.as_str(),
+            action.entity_type(),
+            entity_id,
+            actor.map_or(libsql::Value::Null, libsql::Value::Text),
+            ctx.user_id(),
+            detail.to_string(),
+        ))
+        .await?;
+        Ok(())
+    }
+
+    /// List a project's entries matching `filter`, newest first.
+    ///
+    /// Pass the page's `next_cursor` as `cursor` to fetch the next (older)
+    /// page.
+    pub async fn list(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        filter: &AuditFilter,
+        limit: i64,
+        cursor: Option<i64>,
+    ) -> Result<AuditPage> {
+        ProjectBmc::ensure_access(ctx, mm, project_id).await?;
+
+        let mut sql = String::from(
+            r#"
+            SELECT id, project_id, action, entity_type, entity_id, actor, user_id, detail, created_ts
+            FROM audit_log
+            WHERE project_id = ?
+            "#,
+        );
+        let mut params = vec![libsql::Value::Integer(project_id.get())];
+        if let Some(action) = filter.action {
+            sql.push_str(" AND action = ?");
+            params.push(libsql::Value::Text(action.as_str().to_string()));
+        }
+        if let Some(entity_type) = &filter.entity_type {
+            sql.push_str(" AND entity_type = ?");
+            params.push(libsql::Value::Text(entity_type.clone()));
+        }
+        if let Some(entity_id) = filter.entity_id {
+            sql.push_str(" AND entity_id = ?");
+            params.push(libsql::Value::Integer(entity_id));
+        }
+        if let Some(cursor) = cursor {
+            sql.push_str(" AND id < ?");
+            params.push(libsql::Value::Integer(cursor));
+        }
+        sql.push_str(" ORDER BY id DESC LIMIT ?");
+        params.push(libsql::Value::Integer(limit));
+
+        let db = mm.db_read();
+        let stmt = db.prepare(&sql).await?;
+        let mut rows = stmt
+            .query(libsql::params::Params::Positional(params))
+            .await?;
+        let mut items = Vec::new();
+        while let Some(row) = rows.next().await? {
+            items.push(Self::from_row(&row)?);
+        }
+
+        let next_cursor = if items.len() as i64 == limit {
+            items.last().map(|e| e.id)
+        } else {
+            None
+        };
+        Ok(AuditPage { items, next_cursor })
+    }
+
+    fn from_row(row: &libsql::Row) -> Result<AuditEntry> {
+        let action: String = row.get(2)?;
+        let detail: String = row.get(7)?;
+        let created_ts: String = row.get(8)?;
+        Ok(AuditEntry {
+            id: row.get(0)?,
+            project_id: row.get(1)?,
+            action: action.parse()?,
+            entity_type: row.get(3)?,
+            entity_id: row.get(4)?,
+            actor: row.get(5)?,
+            user_id: row.get(6)?,
+            detail: serde_json::from_str(&detail).unwrap_or(Value::Null),
+            created_ts: crate::utils::parse_timestamp(&created_ts, "audit_log.created_ts"),
+        })
+    }
+}
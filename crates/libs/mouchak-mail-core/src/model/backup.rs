@@ -0,0 +1,166 @@
+//! Database backups: consistent SQLite snapshots taken while the server runs.
+//!
+//! The Git archive keeps messages but not agents, reservations,
+//! acknowledgements or read state; a database snapshot has all of them.
+//! [`ModelManager::backup_to`] writes one with `VACUUM INTO`, which copies a
+//! single read transaction, so a snapshot never holds half of a write.
+//!
+//! [`BackupBmc::create`] names snapshots after the time they were taken and
+//! keeps the newest `backup.retention_count` in the backup directory. The
+//! server runs it every `backup.interval_seconds` (off by default);
+//! `POST /api/admin/backup` and `mouchak-mail backup now` take one on demand.
+//!
+//! Restoring is copying a snapshot over the database file while the server is
+//! stopped.
+
+use crate::Result;
+use crate::ctx::{ALL_PROJECTS, Ctx};
+use crate::model::ModelManager;
+use crate::store::resolve_db_path;
+use chrono::{NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tracing::info;
+use utoipa::ToSchema;
+
+/// File name prefix of snapshots in the backup directory.
+pub const BACKUP_PREFIX: &str = "mouchak_mail-";
+
+const BACKUP_EXTENSION: &str = "db";
+
+/// UTC, sortable, and fine enough that back-to-back snapshots get their own
+/// files.
+const STAMP_FORMAT: &str = "%Y%m%dT%H%M%S%3fZ";
+
+/// A snapshot on disk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BackupInfo {
+    pub path: String,
+    pub size_bytes: u64,
+    /// When the snapshot was taken (UTC)
+    pub created_ts: NaiveDateTime,
+}
+
+/// Backend Model Controller for database backups.
+pub struct BackupBmc;
+
+impl BackupBmc {
+    /// Where snapshots go: `backup.directory`, else `backups` beside the
+    /// database file from [`resolve_db_path`], so the server and the CLI
+    /// agree whichever directory they start in.
+    pub fn directory(mm: &ModelManager) -> Result<PathBuf> {
+        match &mm.app_config.backup.directory {
+            Some(dir) => Ok(dir.clone()),
+            None => Ok(resolve_db_path()
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_default()
+                .join("backups")),
+        }
+    }
+
+    /// Take a snapshot into the backup directory, then delete the oldest
+    /// ones beyond `backup.retention_count`.
+    ///
+    /// Backups copy every project, so `ctx` must be unrestricted.
+    pub async fn create(ctx: &Ctx, mm: &ModelManager) -> Result<BackupInfo> {
+        ensure_unrestricted(ctx)?;
+        let dir = Self::directory(mm)?;
+        let created_ts = Utc::now().naive_utc();
+        let path = dir.join(format!(
+            "{}{}.{}",
+            BACKUP_PREFIX,
+            created_ts.format(STAMP_FORMAT),
+            BACKUP_EXTENSION
+        ));
+
+        let size_bytes = mm.backup_to(&path).await?;
+        let removed = Self::rotate(&dir, mm.app_config.backup.retention_count)?;
+        info!(
+            path = %path.display(),
+            size_bytes,
+            removed,
+            "Database backup written"
+        );
+
+        Ok(BackupInfo {
+            path: path.display().to_string(),
+            size_bytes,
+            created_ts,
+        })
+    }
+
+    /// Take a snapshot to `path`, outside the rotation.
+    pub async fn create_at(ctx: &Ctx, mm: &ModelManager, path: &Path) -> Result<BackupInfo> {
+        ensure_unrestricted(ctx)?;
+        let created_ts = Utc::now().naive_utc();
+        let size_bytes = mm.backup_to(path).await?;
+        info!(path = %path.display(), size_bytes, "Database backup written");
+
+        Ok(BackupInfo {
+            path: path.display().to_string(),
+            size_bytes,
+            created_ts,
+        })
+    }
+
+    /// Snapshots in the backup directory, newest first.
+    pub fn list(ctx: &Ctx, mm: &ModelManager) -> Result<Vec<BackupInfo>> {
+        ensure_unrestricted(ctx)?;
+        list_in(&Self::directory(mm)?)
+    }
+
+    /// Delete all but the newest `keep` snapshots in `dir`; 0 keeps them all.
+    /// Returns how many were deleted.
+    fn rotate(dir: &Path, keep: usize) -> Result<usize> {
+        if keep == 0 {
+            return Ok(0);
+        }
+        let stale = list_in(dir)?.into_iter().skip(keep);
+        let mut removed = 0;
+        for backup in stale {
+            std::fs::remove_file(&backup.path)?;
+            removed += 1;
+        }
+        Ok(removed)
+    }
+}
+
+fn ensure_unrestricted(ctx: &Ctx) -> Result<()> {
+    if ctx.is_unrestricted() {
+        Ok(())
+    } else {
+        Err(crate::Error::Forbidden(ALL_PROJECTS.to_string()))
+    }
+}
+
+/// Snapshots named by [`BackupBmc::create`] in `dir`, newest first; other
+/// files are ignored.
+fn list_in(dir: &Path) -> Result<Vec<BackupInfo>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut backups = Vec::new();
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name();
+        let Some(stamp) = name
+            .to_str()
+            .and_then(|n| n.strip_prefix(BACKUP_PREFIX))
+            .and_then(|n| n.strip_suffix(BACKUP_EXTENSION))
+            .and_then(|n| n.strip_suffix('.'))
+        else {
+            continue;
+        };
+        let Ok(created_ts) = NaiveDateTime::parse_from_str(stamp, STAMP_FORMAT) else {
+            continue;
+        };
+        backups.push(BackupInfo {
+            path: entry.path().display().to_string(),
+            size_bytes: entry.metadata()?.len(),
+            created_ts,
+        });
+    }
+    backups.sort_by_key(|b| std::cmp::Reverse(b.created_ts));
+    Ok(backups)
+}
@@ -264,6 +264,9 @@ impl EscalationBmc {
             thread_id: None,
             importance: Some("high".to_string()),
             ack_required: true,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
         };
 
         MessageBmc::create(ctx, mm, reminder).await
@@ -1,13 +1,20 @@
 //! Export functionality for mailbox data
 //!
-//! Supports exporting messages in HTML, JSON, and Markdown formats.
+//! Supports exporting messages in HTML, JSON, Markdown, CSV, TSV, NDJSON, and mbox formats.
 
 use crate::Result;
 use crate::ctx::Ctx;
 use crate::model::ModelManager;
-use crate::model::message::MessageBmc;
+use crate::model::message::{
+    MAX_BATCH_SIZE, Message, MessageBmc, OutboxRecipient, assign_thread_seq, body_hash,
+    insert_body, snippet,
+};
 use crate::model::project::ProjectBmc;
+use crate::store::git_store;
+use chrono::{NaiveDate, NaiveDateTime};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
 
 /// Export format options
 /// Export format options.
@@ -15,14 +22,41 @@ use serde::{Deserialize, Serialize};
 /// Supported formats for mailbox export.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ExportFormat {
-    /// Render as a standalone HTML page
+    /// Render as a standalone HTML page, threaded with a table of contents
     Html,
+    /// The HTML layout split into one page per thread, zipped.
+    ///
+    /// [`ExportedMailbox::content`] holds the zip base64-encoded; use
+    /// [`ExportedMailbox::content_bytes`] to get the archive itself.
+    HtmlZip,
     /// Raw message data in JSON
     Json,
     /// Markdown document (suitable for LLM contexts)
     Markdown,
     /// Comma-separated values
     Csv,
+    /// Tab-separated values, same columns as CSV
+    Tsv,
+    /// Newline-delimited JSON, one message object per line
+    Ndjson,
+    /// RFC 4155 mbox, readable by mutt, Thunderbird and other mail clients
+    Mbox,
+}
+
+impl ExportFormat {
+    /// Canonical format name
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Html => "html",
+            Self::HtmlZip => "html-zip",
+            Self::Json => "json",
+            Self::Markdown => "markdown",
+            Self::Csv => "csv",
+            Self::Tsv => "tsv",
+            Self::Ndjson => "ndjson",
+            Self::Mbox => "mbox",
+        }
+    }
 }
 
 impl std::str::FromStr for ExportFormat {
@@ -31,13 +65,117 @@ impl std::str::FromStr for ExportFormat {
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         Ok(match s.to_lowercase().as_str() {
             "html" => Self::Html,
+            "html-zip" | "htmlzip" => Self::HtmlZip,
             "md" | "markdown" => Self::Markdown,
             "csv" => Self::Csv,
+            "tsv" => Self::Tsv,
+            "ndjson" | "jsonl" => Self::Ndjson,
+            "mbox" => Self::Mbox,
             _ => Self::Json, // default
         })
     }
 }
 
+/// Compression applied to export content.
+///
+/// Compression wraps the rendered export and is independent of its format: a
+/// manifest hashes and signs the uncompressed content, so it verifies the same
+/// whether the export was stored or sent compressed or not.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportCompression {
+    /// Content is stored as rendered
+    #[default]
+    None,
+    /// gzip (RFC 1952)
+    Gzip,
+    /// Zstandard
+    Zstd,
+}
+
+impl ExportCompression {
+    /// zstd level: the library default, a good size/speed balance for text
+    const ZSTD_LEVEL: i32 = 3;
+
+    /// Stable lowercase name, also the HTTP `Content-Encoding` token
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Gzip => "gzip",
+            Self::Zstd => "zstd",
+        }
+    }
+
+    /// Suffix appended to the file name of a compressed export, e.g. `.gz`
+    pub fn file_suffix(&self) -> &'static str {
+        match self {
+            Self::None => "",
+            Self::Gzip => ".gz",
+            Self::Zstd => ".zst",
+        }
+    }
+
+    /// Returns true if content is left uncompressed
+    pub fn is_none(&self) -> bool {
+        *self == Self::None
+    }
+
+    /// Compress `data`.
+    pub fn compress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        use std::io::Write;
+
+        match self {
+            Self::None => Ok(data.to_vec()),
+            Self::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                Ok(encoder.finish()?)
+            }
+            Self::Zstd => Ok(zstd::encode_all(data, Self::ZSTD_LEVEL)?),
+        }
+    }
+
+    /// Decompress `data` produced by [`Self::compress`].
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidInput` if `data` isn't valid for this compression
+    pub fn decompress(&self, data: &[u8]) -> Result<Vec<u8>> {
+        use std::io::Read;
+
+        let invalid = |e: std::io::Error| {
+            crate::Error::InvalidInput(format!("Invalid {} content: {}", self.as_str(), e))
+        };
+        match self {
+            Self::None => Ok(data.to_vec()),
+            Self::Gzip => {
+                let mut out = Vec::new();
+                flate2::read::GzDecoder::new(data)
+                    .read_to_end(&mut out)
+                    .map_err(invalid)?;
+                Ok(out)
+            }
+            Self::Zstd => zstd::decode_all(data).map_err(invalid),
+        }
+    }
+}
+
+impl std::str::FromStr for ExportCompression {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "" | "none" | "identity" => Ok(Self::None),
+            "gzip" | "gz" => Ok(Self::Gzip),
+            "zstd" | "zst" => Ok(Self::Zstd),
+            other => Err(crate::Error::InvalidInput(format!(
+                "Unsupported compression '{}', expected none, gzip or zstd",
+                other
+            ))),
+        }
+    }
+}
+
 /// Exported mailbox data
 /// Exported mailbox data container.
 ///
@@ -50,6 +188,153 @@ pub struct ExportedMailbox {
     pub exported_at: String,
     pub content: String,
     pub format: String,
+    /// Filter applied to the export, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<ExportFilter>,
+    /// Scrub mode applied to subjects and bodies
+    #[serde(default, skip_serializing_if = "ScrubMode::is_none")]
+    pub scrub_mode: ScrubMode,
+    /// Compression of `content`; when set, `content` holds the compressed
+    /// bytes base64-encoded
+    #[serde(default, skip_serializing_if = "ExportCompression::is_none")]
+    pub compression: ExportCompression,
+    /// Size in bytes of the uncompressed export, set when `compression` is
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_size: Option<usize>,
+}
+
+impl ExportedMailbox {
+    /// The export as written to a file: the compressed bytes if compressed,
+    /// otherwise the zip archive for `html-zip` and the UTF-8 text for every
+    /// other format.
+    pub fn content_bytes(&self) -> Result<Vec<u8>> {
+        if !self.compression.is_none() || self.format == ExportFormat::HtmlZip.as_str() {
+            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &self.content)
+                .map_err(|e| crate::Error::InvalidInput(format!("Invalid html-zip content: {}", e)))
+        } else {
+            Ok(self.content.as_bytes().to_vec())
+        }
+    }
+
+    /// Compress the export, recording its original size.
+    ///
+    /// An already compressed export is decompressed first.
+    pub fn compress(self, compression: ExportCompression) -> Result<Self> {
+        let plain = self.decompress()?;
+        if compression.is_none() {
+            return Ok(plain);
+        }
+        let bytes = plain.content_bytes()?;
+        let compressed = compression.compress(&bytes)?;
+        Ok(Self {
+            content: base64::Engine::encode(&base64::engine::general_purpose::STANDARD, compressed),
+            compression,
+            original_size: Some(bytes.len()),
+            ..plain
+        })
+    }
+
+    /// Undo [`Self::compress`], restoring `content` exactly as rendered.
+    ///
+    /// This is the content a manifest hashes, so verify a compressed export
+    /// after decompressing it.
+    pub fn decompress(self) -> Result<Self> {
+        if self.compression.is_none() {
+            return Ok(self);
+        }
+        let bytes = self.compression.decompress(&self.content_bytes()?)?;
+        let content = if self.format == ExportFormat::HtmlZip.as_str() {
+            base64::Engine::encode(&base64::engine::general_purpose::STANDARD, bytes)
+        } else {
+            String::from_utf8(bytes).map_err(|e| {
+                crate::Error::InvalidInput(format!("Decompressed export is not UTF-8: {}", e))
+            })?
+        };
+        Ok(Self {
+            content,
+            compression: ExportCompression::None,
+            original_size: None,
+            ..self
+        })
+    }
+}
+
+/// Maximum number of messages included in a Markdown, CSV/TSV or mbox
+/// export. HTML exports take every matching message so threads stay whole,
+/// and JSON ones so [`ExportBmc::import_mailbox`] can restore them.
+const EXPORT_MESSAGE_LIMIT: i64 = 100;
+
+/// `project_slug` of an [`ExportBmc::export_messages`] export spanning projects.
+pub const SELECTION_EXPORT_SLUG: &str = "selection";
+
+/// Export filter options.
+///
+/// Narrows an export to a date range, agent, thread, or importance level.
+/// All criteria are applied in the database query and combined with AND.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportFilter {
+    /// Only include messages created at or after this timestamp (UTC)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<NaiveDateTime>,
+    /// Only include messages created at or before this timestamp (UTC)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<NaiveDateTime>,
+    /// Only include messages sent or received by this agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub agent_name: Option<String>,
+    /// Only include messages in this thread
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+    /// Only include messages with this importance ("normal" or "high")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub importance: Option<String>,
+}
+
+impl ExportFilter {
+    /// Returns true if no filter criteria are set
+    pub fn is_empty(&self) -> bool {
+        self.since.is_none()
+            && self.until.is_none()
+            && self.agent_name.is_none()
+            && self.thread_id.is_none()
+            && self.importance.is_none()
+    }
+
+    /// Validate the filter (e.g. `since` must not be after `until`)
+    pub fn validate(&self) -> Result<()> {
+        match (self.since, self.until) {
+            (Some(since), Some(until)) if since > until => {
+                Err(crate::Error::InvalidInput(format!(
+                    "Export filter 'since' ({}) is after 'until' ({})",
+                    since, until
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Parse a date bound given as RFC3339 or `YYYY-MM-DD`.
+    ///
+    /// Plain dates resolve to the start of the day, or to the last second
+    /// of the day when `end_of_day` is set (used for `until`).
+    pub fn parse_date_bound(s: &str, end_of_day: bool) -> Result<NaiveDateTime> {
+        let s = s.trim();
+        if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(s) {
+            return Ok(dt.naive_utc());
+        }
+        let date = NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| {
+            crate::Error::InvalidInput(format!(
+                "Invalid date '{}': expected RFC3339 or YYYY-MM-DD",
+                s
+            ))
+        })?;
+        let time = if end_of_day {
+            date.and_hms_opt(23, 59, 59)
+        } else {
+            date.and_hms_opt(0, 0, 0)
+        };
+        time.ok_or_else(|| crate::Error::InvalidInput(format!("Invalid date '{}'", s)))
+    }
 }
 
 use lazy_static::lazy_static;
@@ -59,7 +344,8 @@ use regex::Regex;
 /// Scrubbing mode for privacy protection.
 ///
 /// Controls how sensitive data is redacted from exports.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ScrubMode {
     /// No scrubbing (full fidelity)
     #[default]
@@ -68,6 +354,31 @@ pub enum ScrubMode {
     Standard,
     /// Scrub PII, secrets, and financial info (CC, SSN)
     Aggressive,
+    /// Redact email addresses only, as `[REDACTED:email]`
+    Emails,
+    /// Redact credentials only (AWS keys, GitHub tokens, bearer tokens, private keys)
+    Secrets,
+    /// Redact both emails and secrets
+    All,
+}
+
+impl ScrubMode {
+    /// Stable lowercase name, as recorded in export manifests
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Standard => "standard",
+            Self::Aggressive => "aggressive",
+            Self::Emails => "emails",
+            Self::Secrets => "secrets",
+            Self::All => "all",
+        }
+    }
+
+    /// Returns true if this mode leaves content untouched
+    pub fn is_none(&self) -> bool {
+        *self == Self::None
+    }
 }
 
 impl std::str::FromStr for ScrubMode {
@@ -77,11 +388,56 @@ impl std::str::FromStr for ScrubMode {
         Ok(match s.to_lowercase().as_str() {
             "standard" => Self::Standard,
             "aggressive" | "strict" => Self::Aggressive,
+            "emails" | "email" => Self::Emails,
+            "secrets" => Self::Secrets,
+            "all" => Self::All,
             _ => Self::None,
         })
     }
 }
 
+/// Replace email addresses with `[REDACTED:email]`
+#[allow(clippy::expect_used)]
+fn redact_emails(text: &str) -> String {
+    lazy_static! {
+        static ref REDACT_EMAIL_RE: Regex =
+            Regex::new(r"(?i)\b[A-Z0-9._%+-]+@[A-Z0-9.-]+\.[A-Z]{2,}\b")
+                .expect("valid email regex");
+    }
+
+    REDACT_EMAIL_RE
+        .replace_all(text, "[REDACTED:email]")
+        .into_owned()
+}
+
+/// Replace credentials with `[REDACTED:<kind>]` placeholders
+#[allow(clippy::expect_used)]
+fn redact_secrets(text: &str) -> String {
+    lazy_static! {
+        // Whole PEM blocks; the only secret pattern allowed to span lines
+        static ref REDACT_PRIVATE_KEY_RE: Regex = Regex::new(
+            r"(?s)-----BEGIN [A-Z0-9 ]*PRIVATE KEY-----.*?-----END [A-Z0-9 ]*PRIVATE KEY-----"
+        )
+        .expect("valid private key regex");
+        static ref REDACT_AWS_KEY_RE: Regex =
+            Regex::new(r"\b(?:AKIA|ASIA)[A-Z0-9]{16}\b").expect("valid aws key regex");
+        static ref REDACT_GITHUB_TOKEN_RE: Regex =
+            Regex::new(r"\b(?:gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{20,})\b")
+                .expect("valid github token regex");
+        // Horizontal whitespace only, so "Bearer" at a line end never eats the next line
+        static ref REDACT_BEARER_RE: Regex =
+            Regex::new(r"(?i)\b(bearer)[ \t]+[A-Za-z0-9._~+/-]{20,}=*")
+                .expect("valid bearer regex");
+    }
+
+    let cleaned = REDACT_PRIVATE_KEY_RE.replace_all(text, "[REDACTED:private-key]");
+    let cleaned = REDACT_AWS_KEY_RE.replace_all(&cleaned, "[REDACTED:aws-key]");
+    let cleaned = REDACT_GITHUB_TOKEN_RE.replace_all(&cleaned, "[REDACTED:github-token]");
+    REDACT_BEARER_RE
+        .replace_all(&cleaned, "$1 [REDACTED:bearer-token]")
+        .into_owned()
+}
+
 /// Service for redacting sensitive information from text.
 ///
 /// Uses regex patterns to identify and replace PII and secrets.
@@ -96,8 +452,12 @@ impl Scrubber {
 
     #[allow(clippy::expect_used)]
     pub fn scrub(&self, text: &str) -> String {
-        if self.mode == ScrubMode::None {
-            return text.to_string();
+        match self.mode {
+            ScrubMode::None => return text.to_string(),
+            ScrubMode::Emails => return redact_emails(text),
+            ScrubMode::Secrets => return redact_secrets(text),
+            ScrubMode::All => return redact_emails(&redact_secrets(text)),
+            ScrubMode::Standard | ScrubMode::Aggressive => {}
         }
 
         let mut cleaned = text.to_string();
@@ -194,6 +554,10 @@ pub struct ExportBmc;
 
 impl ExportBmc {
     /// Export a project's mailbox to the specified format
+    ///
+    /// With a `compression` other than `None`, `content` holds the compressed
+    /// bytes base64-encoded and `original_size` the uncompressed size; see
+    /// [`ExportedMailbox::content_bytes`] and [`ExportedMailbox::decompress`].
     pub async fn export_mailbox(
         ctx: &Ctx,
         mm: &ModelManager,
@@ -201,12 +565,37 @@ impl ExportBmc {
         format: ExportFormat,
         scrub_mode: ScrubMode,
         _include_attachments: bool,
+        filter: &ExportFilter,
+        compression: ExportCompression,
     ) -> Result<ExportedMailbox> {
+        filter.validate()?;
+
+        // NDJSON is rendered line by line straight from the database cursor
+        if format == ExportFormat::Ndjson {
+            let mut stream =
+                Self::export_ndjson_stream(ctx, mm, project_slug, scrub_mode, filter).await?;
+            let mut content = String::new();
+            while let Some(line) = stream.next_line().await? {
+                content.push_str(&line);
+            }
+            return stream.into_exported(content).compress(compression);
+        }
+
         // Get project
         let project = ProjectBmc::get_by_slug(ctx, mm, project_slug).await?;
 
-        // Get recent messages (limit to 100 for export)
-        let messages = MessageBmc::list_recent(ctx, mm, project.id, 100).await?;
+        // Get recent messages matching the filter; HTML groups them into
+        // threads and JSON is an import source, so both take the full set
+        // rather than the latest 100
+        let limit = match format {
+            ExportFormat::Html | ExportFormat::HtmlZip | ExportFormat::Json => None,
+            _ => Some(EXPORT_MESSAGE_LIMIT),
+        };
+        let mut rows = Self::query_messages(mm, project.id.get(), filter, limit).await?;
+        let mut messages = Vec::new();
+        while let Some(row) = rows.next().await? {
+            messages.push(message_from_row(&row)?);
+        }
 
         let exported_at = chrono::Utc::now()
             .format("%Y-%m-%d %H:%M:%S UTC")
@@ -214,84 +603,315 @@ impl ExportBmc {
         let message_count = messages.len();
 
         let scrubber = Scrubber::new(scrub_mode);
-
         let content = match format {
-            ExportFormat::Html => Self::render_html(&project.slug, &messages, &scrubber),
-            ExportFormat::Json => Self::render_json(&messages, &scrubber)?,
-            ExportFormat::Markdown => Self::render_markdown(&project.slug, &messages, &scrubber),
-            ExportFormat::Csv => Self::render_csv(&messages, &scrubber)?,
-        };
-
-        let format_str = match format {
-            ExportFormat::Html => "html",
-            ExportFormat::Json => "json",
-            ExportFormat::Markdown => "markdown",
-            ExportFormat::Csv => "csv",
+            ExportFormat::Mbox => {
+                let slugs = HashMap::from([(project.id.get(), project.slug.clone())]);
+                Self::render_mbox(mm, &messages, &slugs, &scrubber).await?
+            }
+            ExportFormat::Csv | ExportFormat::Tsv => {
+                Self::render_csv(mm, &messages, &scrubber, csv_delimiter(format)).await?
+            }
+            ExportFormat::Html | ExportFormat::HtmlZip => {
+                Self::render_html(mm, format, &project.slug, &messages, &scrubber).await?
+            }
+            _ => Self::render(format, &project.slug, &messages, &scrubber)?,
         };
 
-        Ok(ExportedMailbox {
+        ExportedMailbox {
             project_slug: project.slug.clone(),
             project_name: project.human_key.clone(),
             message_count,
             exported_at,
             content,
-            format: format_str.to_string(),
+            format: format.as_str().to_string(),
+            filter: (!filter.is_empty()).then(|| filter.clone()),
+            scrub_mode,
+            compression: ExportCompression::None,
+            original_size: None,
+        }
+        .compress(compression)
+    }
+
+    /// Export specific messages, e.g. a selection from the unified inbox.
+    ///
+    /// Messages may come from several projects; the export is then titled
+    /// [`SELECTION_EXPORT_SLUG`]. Unknown ids and undelivered scheduled
+    /// messages are skipped, recalled ones show their recall reason, and the
+    /// list is capped at [`MAX_BATCH_SIZE`].
+    ///
+    /// # Errors
+    /// [`crate::Error::Forbidden`] when a message is outside `ctx`'s projects.
+    pub async fn export_messages(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        message_ids: &[i64],
+        format: ExportFormat,
+        scrub_mode: ScrubMode,
+    ) -> Result<ExportedMailbox> {
+        if message_ids.is_empty() {
+            return Err(crate::Error::InvalidInput(
+                "No message ids to export".to_string(),
+            ));
+        }
+        if message_ids.len() > MAX_BATCH_SIZE {
+            return Err(crate::Error::InvalidInput(format!(
+                "Too many message ids ({}), maximum is {}",
+                message_ids.len(),
+                MAX_BATCH_SIZE
+            )));
+        }
+        MessageBmc::ensure_messages_access(ctx, mm, message_ids).await?;
+
+        let db = mm.db_read();
+        let placeholders = vec!["?"; message_ids.len()].join(", ");
+        let stmt = db
+            .prepare(&format!(
+                r#"
+            SELECT
+                m.id, m.project_id, m.sender_id, ag.name as sender_name, m.thread_id, m.subject, m.body_md,
+                m.importance, m.ack_required, m.created_ts, m.attachments, m.thread_seq,
+                p.slug, p.human_key
+            FROM visible_messages AS m
+            JOIN agents AS ag ON m.sender_id = ag.id
+            JOIN projects AS p ON m.project_id = p.id
+            WHERE m.id IN ({})
+            ORDER BY m.created_ts DESC, m.thread_seq DESC, m.id DESC
+            "#,
+                placeholders
+            ))
+            .await?;
+        let params: Vec<libsql::Value> = message_ids.iter().map(|id| (*id).into()).collect();
+        let mut rows = stmt
+            .query(libsql::params::Params::Positional(params))
+            .await?;
+
+        let mut messages = Vec::new();
+        let mut projects: Vec<(String, String)> = Vec::new();
+        let mut slugs = HashMap::new();
+        while let Some(row) = rows.next().await? {
+            let msg = message_from_row(&row)?;
+            let project = (row.get::<String>(12)?, row.get::<String>(13)?);
+            slugs.insert(msg.project_id, project.0.clone());
+            messages.push(msg);
+            if !projects.contains(&project) {
+                projects.push(project);
+            }
+        }
+
+        let (project_slug, project_name) = match projects.as_slice() {
+            [single] => single.clone(),
+            _ => (
+                SELECTION_EXPORT_SLUG.to_string(),
+                "Selected messages".to_string(),
+            ),
+        };
+
+        let scrubber = Scrubber::new(scrub_mode);
+        let content = if format == ExportFormat::Ndjson {
+            let mut content = String::new();
+            for msg in &messages {
+                content.push_str(&ndjson_line(msg, &scrubber)?);
+            }
+            content
+        } else if format == ExportFormat::Mbox {
+            Self::render_mbox(mm, &messages, &slugs, &scrubber).await?
+        } else if matches!(format, ExportFormat::Csv | ExportFormat::Tsv) {
+            Self::render_csv(mm, &messages, &scrubber, csv_delimiter(format)).await?
+        } else if matches!(format, ExportFormat::Html | ExportFormat::HtmlZip) {
+            Self::render_html(mm, format, &project_slug, &messages, &scrubber).await?
+        } else {
+            Self::render(format, &project_slug, &messages, &scrubber)?
+        };
+
+        Ok(ExportedMailbox {
+            project_slug,
+            project_name,
+            message_count: messages.len(),
+            exported_at: chrono::Utc::now()
+                .format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string(),
+            content,
+            format: format.as_str().to_string(),
+            filter: None,
+            scrub_mode,
+            compression: ExportCompression::None,
+            original_size: None,
         })
     }
 
-    fn render_html(
+    /// Open a streaming NDJSON export of a project's mailbox.
+    ///
+    /// Rows are read from the database cursor one at a time, so the full
+    /// mailbox is never held in memory. Unlike the buffered formats, the
+    /// stream is not capped at the export message limit.
+    pub async fn export_ndjson_stream(
+        ctx: &Ctx,
+        mm: &ModelManager,
         project_slug: &str,
-        messages: &[crate::model::message::Message],
-        scrubber: &Scrubber,
-    ) -> String {
-        let mut html = String::new();
-        html.push_str("<!DOCTYPE html>\n<html>\n<head>\n");
-        html.push_str(&format!(
-            "<title>Mailbox Export - {}</title>\n",
-            project_slug
-        ));
-        html.push_str(
-            "<style>
-body { font-family: system-ui, sans-serif; max-width: 800px; margin: 0 auto; padding: 20px; }
-.message { border: 1px solid #ddd; padding: 15px; margin: 10px 0; border-radius: 8px; }
-.subject { font-weight: bold; font-size: 1.1em; }
-.meta { color: #666; font-size: 0.9em; margin: 5px 0; }
-.body { margin-top: 10px; white-space: pre-wrap; }
-</style>\n</head>\n<body>\n",
+        scrub_mode: ScrubMode,
+        filter: &ExportFilter,
+    ) -> Result<NdjsonExportStream> {
+        filter.validate()?;
+
+        let project = ProjectBmc::get_by_slug(ctx, mm, project_slug).await?;
+        let rows = Self::query_messages(mm, project.id.get(), filter, None).await?;
+
+        Ok(NdjsonExportStream {
+            rows,
+            scrubber: Scrubber::new(scrub_mode),
+            project_slug: project.slug,
+            project_name: project.human_key,
+            exported_at: chrono::Utc::now()
+                .format("%Y-%m-%d %H:%M:%S UTC")
+                .to_string(),
+            filter: (!filter.is_empty()).then(|| filter.clone()),
+            message_count: 0,
+        })
+    }
+
+    /// Query project messages matching an export filter, newest first.
+    ///
+    /// Reads `visible_messages`, as the inbox does: recalled messages carry
+    /// their recall reason, and scheduled ones are left out until delivered.
+    async fn query_messages(
+        mm: &ModelManager,
+        project_id: i64,
+        filter: &ExportFilter,
+        limit: Option<i64>,
+    ) -> Result<libsql::Rows> {
+        let db = mm.db_read();
+
+        let mut query = String::from(
+            r#"
+            SELECT
+                m.id, m.project_id, m.sender_id, ag.name as sender_name, m.thread_id, m.subject, m.body_md,
+                m.importance, m.ack_required, m.created_ts, m.attachments, m.thread_seq
+            FROM visible_messages AS m
+            JOIN agents AS ag ON m.sender_id = ag.id
+            WHERE m.project_id = ?
+            "#,
         );
-        html.push_str(&format!("<h1>Mailbox Export: {}</h1>\n", project_slug));
-        html.push_str(&format!("<p>Total messages: {}</p>\n", messages.len()));
+        let mut params: Vec<libsql::Value> = vec![project_id.into()];
 
-        for msg in messages {
-            let scrubbed_subject = scrubber.scrub(&msg.subject);
-            let scrubbed_body = scrubber.scrub_body(&msg.body_md);
-            let scrubbed_sender = scrubber.scrub_name(&msg.sender_name);
+        if let Some(since) = filter.since {
+            query.push_str(" AND m.created_ts >= ?");
+            params.push(since.format("%Y-%m-%d %H:%M:%S").to_string().into());
+        }
+        if let Some(until) = filter.until {
+            query.push_str(" AND m.created_ts <= ?");
+            params.push(until.format("%Y-%m-%d %H:%M:%S").to_string().into());
+        }
+        if let Some(agent_name) = &filter.agent_name {
+            query.push_str(
+                r#" AND (ag.name = ? OR EXISTS (
+                    SELECT 1 FROM message_recipients mr
+                    JOIN agents ra ON mr.agent_id = ra.id
+                    WHERE mr.message_id = m.id AND ra.name = ?
+                ))"#,
+            );
+            params.push(agent_name.clone().into());
+            params.push(agent_name.clone().into());
+        }
+        if let Some(thread_id) = &filter.thread_id {
+            query.push_str(" AND m.thread_id = ?");
+            params.push(thread_id.clone().into());
+        }
+        if let Some(importance) = &filter.importance {
+            query.push_str(" AND m.importance = ?");
+            params.push(importance.clone().into());
+        }
 
-            html.push_str("<div class=\"message\">\n");
-            html.push_str(&format!(
-                "<div class=\"subject\">{}</div>\n",
-                html_escape(&scrubbed_subject)
-            ));
-            html.push_str(&format!(
-                "<div class=\"meta\">From: {} | {}</div>\n",
-                html_escape(&scrubbed_sender),
-                msg.created_ts.format("%Y-%m-%d %H:%M")
-            ));
-            html.push_str(&format!(
-                "<div class=\"body\">{}</div>\n",
-                html_escape(&scrubbed_body)
-            ));
-            html.push_str("</div>\n");
+        query.push_str(" ORDER BY m.created_ts DESC, m.thread_seq DESC, m.id DESC");
+        if let Some(limit) = limit {
+            query.push_str(" LIMIT ?");
+            params.push(limit.into());
         }
 
-        html.push_str("</body>\n</html>");
-        html
+        let stmt = db.prepare(&query).await?;
+        Ok(stmt
+            .query(libsql::params::Params::Positional(params))
+            .await?)
+    }
+
+    /// Render buffered messages in one of the non-streaming formats
+    fn render(
+        format: ExportFormat,
+        title: &str,
+        messages: &[Message],
+        scrubber: &Scrubber,
+    ) -> Result<String> {
+        Ok(match format {
+            ExportFormat::Json => Self::render_json(messages, scrubber)?,
+            ExportFormat::Markdown => Self::render_markdown(title, messages, scrubber),
+            ExportFormat::Csv | ExportFormat::Tsv => {
+                unreachable!("CSV needs recipients, see render_csv")
+            }
+            ExportFormat::Html | ExportFormat::HtmlZip => {
+                unreachable!("HTML needs recipients, see render_html")
+            }
+            ExportFormat::Ndjson => unreachable!("NDJSON is rendered line by line"),
+            ExportFormat::Mbox => unreachable!("mbox needs recipients, see render_mbox"),
+        })
+    }
+
+    /// Render messages as an mbox file, oldest first.
+    ///
+    /// `slugs` maps each message's project id to its slug, which becomes the
+    /// domain of the synthesized `agent@slug.local` addresses.
+    async fn render_mbox(
+        mm: &ModelManager,
+        messages: &[Message],
+        slugs: &HashMap<i64, String>,
+        scrubber: &Scrubber,
+    ) -> Result<String> {
+        let recipients = MessageBmc::list_recipients(mm, messages).await?;
+
+        let mut mbox = String::new();
+        // Last Message-ID seen per thread, so replies point at their predecessor
+        let mut thread_tails: HashMap<(i64, &str), String> = HashMap::new();
+        for msg in messages.iter().rev() {
+            let domain = format!(
+                "{}.local",
+                slugs.get(&msg.project_id).map_or("unknown", String::as_str)
+            );
+            let parent = msg.thread_id.as_deref().map(|thread| {
+                let tail = thread_tails.insert(
+                    (msg.project_id, thread),
+                    mbox_message_id(&msg.id.to_string(), &domain),
+                );
+                (
+                    mbox_message_id(&format!("thread.{}", thread), &domain),
+                    tail,
+                )
+            });
+            let msg_recipients = recipients.get(&msg.id).map_or(&[][..], Vec::as_slice);
+            mbox.push_str(&mbox_entry(msg, &domain, msg_recipients, parent, scrubber));
+        }
+        Ok(mbox)
     }
 
-    fn render_json(
-        messages: &[crate::model::message::Message],
+    /// Render messages as a threaded HTML page, or for
+    /// [`ExportFormat::HtmlZip`] as a base64-encoded zip of per-thread pages.
+    async fn render_html(
+        mm: &ModelManager,
+        format: ExportFormat,
+        title: &str,
+        messages: &[Message],
         scrubber: &Scrubber,
     ) -> Result<String> {
+        let recipients = MessageBmc::list_recipients(mm, messages).await?;
+        if format == ExportFormat::HtmlZip {
+            let zip = html::render_zip(title, messages, &recipients, scrubber)?;
+            Ok(base64::Engine::encode(
+                &base64::engine::general_purpose::STANDARD,
+                zip,
+            ))
+        } else {
+            Ok(html::render_page(title, messages, &recipients, scrubber))
+        }
+    }
+
+    fn render_json(messages: &[Message], scrubber: &Scrubber) -> Result<String> {
         // For JSON, we might want to clone and scrub fields.
         // Or create a scrubbed struct.
         // Easiest is to convert to Value, walk it? Or just map to a new Vec.
@@ -327,11 +947,7 @@ body { font-family: system-ui, sans-serif; max-width: 800px; margin: 0 auto; pad
         Ok(serde_json::to_string_pretty(&vals)?)
     }
 
-    fn render_markdown(
-        project_slug: &str,
-        messages: &[crate::model::message::Message],
-        scrubber: &Scrubber,
-    ) -> String {
+    fn render_markdown(project_slug: &str, messages: &[Message], scrubber: &Scrubber) -> String {
         let mut md = String::new();
         md.push_str(&format!("# Mailbox Export: {}\n\n", project_slug));
         md.push_str(&format!("Total messages: {}\n\n---\n\n", messages.len()));
@@ -353,24 +969,46 @@ body { font-family: system-ui, sans-serif; max-width: 800px; margin: 0 auto; pad
         md
     }
 
-    fn render_csv(
-        messages: &[crate::model::message::Message],
+    /// Render messages as delimited text, one row per message.
+    ///
+    /// Columns follow [`CSV_HEADER`]; new columns are only ever appended so
+    /// spreadsheets and scripts keyed on position keep working. `recipients`
+    /// joins the to/cc names with `;`, leaving bcc out as mbox does.
+    async fn render_csv(
+        mm: &ModelManager,
+        messages: &[Message],
         scrubber: &Scrubber,
+        delimiter: u8,
     ) -> Result<String> {
-        let mut wtr = csv::Writer::from_writer(vec![]);
+        let recipients = MessageBmc::list_recipients(mm, messages).await?;
+        let mut wtr = csv::WriterBuilder::new()
+            .delimiter(delimiter)
+            .from_writer(vec![]);
 
         // Header
-        wtr.write_record(["id", "created_at", "sender", "subject", "body"])
+        wtr.write_record(CSV_HEADER)
             .map_err(|e| crate::Error::InvalidInput(format!("CSV Error: {}", e)))?;
 
         // Rows
         for msg in messages {
+            let recipient_names = recipients
+                .get(&msg.id)
+                .map_or(&[][..], Vec::as_slice)
+                .iter()
+                .filter(|r| r.recipient_type != "bcc")
+                .map(|r| scrubber.scrub_name(&r.name))
+                .collect::<Vec<_>>()
+                .join(";");
             wtr.write_record(&[
                 msg.id.to_string(),
                 msg.created_ts.format("%Y-%m-%d %H:%M:%S").to_string(),
                 scrubber.scrub_name(&msg.sender_name),
                 scrubber.scrub(&msg.subject),
                 scrubber.scrub_body(&msg.body_md),
+                msg.thread_id.clone().unwrap_or_default(),
+                msg.importance.clone(),
+                msg.ack_required.to_string(),
+                recipient_names,
             ])
             .map_err(|e| crate::Error::InvalidInput(format!("CSV Error: {}", e)))?;
         }
@@ -383,11 +1021,16 @@ body { font-family: system-ui, sans-serif; max-width: 800px; margin: 0 auto; pad
 }
 
 impl ExportBmc {
+    /// Export the mailbox as Markdown and commit it under `mailboxes/`.
+    ///
+    /// A compressed snapshot is stored with the matching suffix, e.g.
+    /// `{slug}_{timestamp}.md.zst`.
     pub async fn commit_archive(
         ctx: &Ctx,
         mm: &ModelManager,
         project_slug: &str,
         message: &str,
+        compression: ExportCompression,
     ) -> Result<String> {
         // 1. Export in Markdown (default for archive)
         let exported = Self::export_mailbox(
@@ -397,12 +1040,20 @@ impl ExportBmc {
             ExportFormat::Markdown,
             ScrubMode::None,
             true,
+            &ExportFilter::default(),
+            compression,
         )
         .await?;
+        let bytes = exported.content_bytes()?;
 
         // 2. Determine file path in repo
         let now = chrono::Utc::now();
-        let filename = format!("{}_{}.md", project_slug, now.format("%Y%m%d_%H%M%S"));
+        let filename = format!(
+            "{}_{}.md{}",
+            project_slug,
+            now.format("%Y%m%d_%H%M%S"),
+            compression.file_suffix()
+        );
         let rel_path = std::path::Path::new("mailboxes")
             .join(project_slug)
             .join(&filename);
@@ -411,23 +1062,338 @@ impl ExportBmc {
         let _git_guard = mm.git_lock.lock().await;
 
         // Use cached repository to prevent FD exhaustion
-        let repo_arc = mm.get_repo().await?;
+        let repo_arc = mm.get_project_repo(project_slug).await?;
         let repo = repo_arc.lock().await;
 
-        // 4. Commit
-        let oid = crate::store::git_store::commit_file(
+        // 4. Write and commit, authored by the caller when known
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| crate::Error::InvalidInput("Archive has no working directory".into()))?;
+        let full_path = workdir.join(&rel_path);
+        if let Some(parent) = full_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&full_path, &bytes)?;
+
+        let (author_name, author_email) = git_store::author_of(ctx.actor());
+        let oid = git_store::commit_paths(
             &repo,
-            &rel_path,
-            &exported.content,
-            message,
-            "Mouchak Mail",         // Committer name
-            "mcp@generic-agent.ai", // Committer email
+            &[&rel_path],
+            &git_store::with_actor_trailer(message, ctx.actor()),
+            author_name,
+            author_email,
         )?;
 
         Ok(oid.to_string())
     }
 }
 
+/// Map an export query row to a Message
+fn message_from_row(row: &libsql::Row) -> Result<Message> {
+    let created_ts_str: String = row.get(9)?;
+    let created_ts =
+        NaiveDateTime::parse_from_str(&created_ts_str, "%Y-%m-%d %H:%M:%S").unwrap_or_default();
+    let attachments_str: String = row.get(10)?;
+    let attachments: Vec<Value> = serde_json::from_str(&attachments_str)?;
+
+    Ok(Message {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        sender_id: row.get(2)?,
+        sender_name: row.get(3)?,
+        thread_id: row.get(4)?,
+        subject: row.get(5)?,
+        body_md: row.get(6)?,
+        snippet: String::new(),
+        importance: row.get(7)?,
+        ack_required: row.get(8)?,
+        created_ts,
+        attachments,
+        project_slug: None,
+        thread_seq: row.get(11)?,
+        forwarded_from_id: None,
+        returned_from_snooze: false,
+        needs_ack: false,
+    })
+}
+
+/// One NDJSON export line. Field order here is the serialized field order.
+#[derive(Serialize)]
+struct NdjsonRecord<'a> {
+    id: i64,
+    project_id: i64,
+    thread_id: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thread_seq: Option<i64>,
+    created_ts: String,
+    sender_id: i64,
+    sender_name: String,
+    importance: &'a str,
+    ack_required: bool,
+    subject: String,
+    body_md: String,
+    attachments: &'a [Value],
+}
+
+/// Column order of CSV and TSV exports.
+const CSV_HEADER: [&str; 9] = [
+    "id",
+    "created_at",
+    "sender",
+    "subject",
+    "body",
+    "thread_id",
+    "importance",
+    "ack_required",
+    "recipients",
+];
+
+/// Field delimiter for the delimited-text formats.
+fn csv_delimiter(format: ExportFormat) -> u8 {
+    if format == ExportFormat::Tsv {
+        b'\t'
+    } else {
+        b','
+    }
+}
+
+/// Render one mbox entry: "From " separator, headers, blank line, body.
+///
+/// `parent` carries the synthesized thread root id and the previous message
+/// in the thread, if any, for `In-Reply-To`/`References`.
+fn mbox_entry(
+    msg: &Message,
+    domain: &str,
+    recipients: &[OutboxRecipient],
+    parent: Option<(String, Option<String>)>,
+    scrubber: &Scrubber,
+) -> String {
+    let sender = scrubber.scrub_name(&msg.sender_name);
+    let sender_addr = mbox_address(&sender, domain);
+    let date = msg.created_ts.and_utc();
+    let address_list = |kind: &str| {
+        recipients
+            .iter()
+            .filter(|r| r.recipient_type == kind)
+            .map(|r| mbox_mailbox(&scrubber.scrub_name(&r.name), domain))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut entry = format!(
+        "From {} {}\n",
+        sender_addr,
+        date.format("%a %b %e %H:%M:%S %Y")
+    );
+    entry.push_str(&format!(
+        "Message-ID: {}\n",
+        mbox_message_id(&msg.id.to_string(), domain)
+    ));
+    entry.push_str(&format!("Date: {}\n", date.to_rfc2822()));
+    entry.push_str(&format!("From: {}\n", mbox_mailbox(&sender, domain)));
+    // Bcc recipients are left out, as a delivered message would
+    for (header, kind) in [("To", "to"), ("Cc", "cc")] {
+        let list = address_list(kind);
+        if !list.is_empty() {
+            entry.push_str(&format!("{}: {}\n", header, list));
+        }
+    }
+    entry.push_str(&format!(
+        "Subject: {}\n",
+        mbox_header_text(&scrubber.scrub(&msg.subject))
+    ));
+    if let Some((root, previous)) = parent {
+        let in_reply_to = previous.unwrap_or_else(|| root.clone());
+        let references = if in_reply_to == root {
+            root
+        } else {
+            format!("{} {}", root, in_reply_to)
+        };
+        entry.push_str(&format!("In-Reply-To: {}\n", in_reply_to));
+        entry.push_str(&format!("References: {}\n", references));
+    }
+    if let Some(thread) = &msg.thread_id {
+        entry.push_str(&format!("X-Thread-ID: {}\n", mbox_header_text(thread)));
+    }
+    entry.push_str(&format!("X-Importance: {}\n", msg.importance));
+    entry.push_str("MIME-Version: 1.0\n");
+    entry.push_str("Content-Type: text/plain; charset=utf-8\n");
+    entry.push_str("Content-Transfer-Encoding: 8bit\n\n");
+
+    let body = scrubber.scrub_body(&msg.body_md).replace("\r\n", "\n");
+    for line in body.lines() {
+        // mboxrd quoting: any run of '>' before "From " gains one more '>'
+        if line.trim_start_matches('>').starts_with("From ") {
+            entry.push('>');
+        }
+        entry.push_str(line);
+        entry.push('\n');
+    }
+    // Entries are separated by an empty line
+    entry.push('\n');
+    entry
+}
+
+/// `agent-name@project-slug.local` address for an agent
+fn mbox_address(name: &str, domain: &str) -> String {
+    let local: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let local = local.trim_matches(|c| matches!(c, '-' | '.'));
+    format!(
+        "{}@{}",
+        if local.is_empty() { "agent" } else { local },
+        domain
+    )
+}
+
+/// `Display Name <address>` mailbox, quoting the name when needed
+fn mbox_mailbox(name: &str, domain: &str) -> String {
+    let address = mbox_address(name, domain);
+    if name
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ' '))
+    {
+        format!("{} <{}>", name, address)
+    } else if !name.is_ascii() {
+        format!("{} <{}>", mbox_header_text(name), address)
+    } else {
+        let escaped = name.replace('\\', "\\\\").replace('"', "\\\"");
+        format!("\"{}\" <{}>", mbox_header_text(&escaped), address)
+    }
+}
+
+/// `<id@domain>` Message-ID, with characters unsafe in a msg-id replaced
+fn mbox_message_id(id: &str, domain: &str) -> String {
+    let id: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("<{}@{}>", id, domain)
+}
+
+/// Header value on a single line; non-ASCII text becomes RFC 2047 encoded words
+fn mbox_header_text(value: &str) -> String {
+    use base64::Engine;
+
+    let value = value.replace(['\r', '\n'], " ");
+    if value.is_ascii() {
+        return value;
+    }
+
+    // Keep each encoded word under the 75 character limit
+    const MAX_CHUNK_BYTES: usize = 45;
+    let mut words = Vec::new();
+    let mut chunk = String::new();
+    for c in value.chars() {
+        if chunk.len() + c.len_utf8() > MAX_CHUNK_BYTES {
+            words.push(std::mem::take(&mut chunk));
+        }
+        chunk.push(c);
+    }
+    words.push(chunk);
+
+    words
+        .iter()
+        .map(|w| {
+            format!(
+                "=?UTF-8?B?{}?=",
+                base64::engine::general_purpose::STANDARD.encode(w)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n ")
+}
+
+/// Serialize one message as a newline-terminated NDJSON line
+fn ndjson_line(msg: &Message, scrubber: &Scrubber) -> Result<String> {
+    let record = NdjsonRecord {
+        id: msg.id,
+        project_id: msg.project_id,
+        thread_id: msg.thread_id.as_deref(),
+        thread_seq: msg.thread_seq,
+        created_ts: msg.created_ts.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        sender_id: msg.sender_id,
+        sender_name: scrubber.scrub_name(&msg.sender_name),
+        importance: &msg.importance,
+        ack_required: msg.ack_required,
+        subject: scrubber.scrub(&msg.subject),
+        body_md: scrubber.scrub_body(&msg.body_md),
+        attachments: &msg.attachments,
+    };
+
+    let mut line = serde_json::to_string(&record)?;
+    line.push('\n');
+    Ok(line)
+}
+
+/// Streaming NDJSON export backed by a database cursor.
+///
+/// Created by [`ExportBmc::export_ndjson_stream`]. Each call to
+/// [`next_line`](Self::next_line) reads one row and returns it as a single
+/// newline-terminated JSON object.
+pub struct NdjsonExportStream {
+    rows: libsql::Rows,
+    scrubber: Scrubber,
+    project_slug: String,
+    project_name: String,
+    exported_at: String,
+    filter: Option<ExportFilter>,
+    message_count: usize,
+}
+
+impl NdjsonExportStream {
+    /// Read the next message as a newline-terminated JSON line
+    pub async fn next_line(&mut self) -> Result<Option<String>> {
+        let Some(row) = self.rows.next().await? else {
+            return Ok(None);
+        };
+        let msg = message_from_row(&row)?;
+        let line = ndjson_line(&msg, &self.scrubber)?;
+        self.message_count += 1;
+        Ok(Some(line))
+    }
+
+    /// Number of messages emitted so far
+    pub fn message_count(&self) -> usize {
+        self.message_count
+    }
+
+    /// Project slug being exported
+    pub fn project_slug(&self) -> &str {
+        &self.project_slug
+    }
+
+    /// Wrap fully drained content into an [`ExportedMailbox`]
+    pub fn into_exported(self, content: String) -> ExportedMailbox {
+        ExportedMailbox {
+            project_slug: self.project_slug,
+            project_name: self.project_name,
+            message_count: self.message_count,
+            exported_at: self.exported_at,
+            content,
+            format: ExportFormat::Ndjson.as_str().to_string(),
+            filter: self.filter,
+            scrub_mode: self.scrubber.mode,
+            compression: ExportCompression::None,
+            original_size: None,
+        }
+    }
+}
+
 fn html_escape(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
@@ -458,6 +1424,12 @@ pub struct ExportManifest {
     pub content_hash: String,
     /// Export format used
     pub format: String,
+    /// Filter applied to the export, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter: Option<ExportFilter>,
+    /// Scrub mode applied to the exported content
+    #[serde(default, skip_serializing_if = "ScrubMode::is_none")]
+    pub scrub_mode: ScrubMode,
     /// Ed25519 signature (base64, optional)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub signature: Option<String>,
@@ -468,13 +1440,18 @@ pub struct ExportManifest {
 
 impl ExportManifest {
     /// Create a new unsigned manifest
+    ///
+    /// The hash covers the uncompressed content. A compressed export that
+    /// fails to decompress gets a hash of its raw content, which no valid
+    /// export will match.
     pub fn new(exported: &ExportedMailbox) -> Self {
-        // Compute SHA-256 hash of content
-        let content_hash = {
-            use sha1::Digest;
-            let mut hasher = sha1::Sha1::new();
-            hasher.update(exported.content.as_bytes());
-            hex::encode(hasher.finalize())
+        let content_hash = if exported.compression.is_none() {
+            Self::content_hash_of(&exported.content)
+        } else {
+            match exported.clone().decompress() {
+                Ok(plain) => Self::content_hash_of(&plain.content),
+                Err(_) => Self::content_hash_of(&exported.content),
+            }
         };
 
         Self {
@@ -484,14 +1461,29 @@ impl ExportManifest {
             message_count: exported.message_count,
             content_hash,
             format: exported.format.clone(),
+            filter: exported.filter.clone(),
+            scrub_mode: exported.scrub_mode,
             signature: None,
             public_key: None,
         }
     }
 
+    /// Compute the content hash recorded in a manifest for the given export content
+    pub fn content_hash_of(content: &str) -> String {
+        use sha1::Digest;
+        let mut hasher = sha1::Sha1::new();
+        hasher.update(content.as_bytes());
+        hex::encode(hasher.finalize())
+    }
+
+    /// Check whether export content matches the hash recorded in this manifest
+    pub fn matches_content(&self, content: &str) -> bool {
+        Self::content_hash_of(content) == self.content_hash
+    }
+
     /// Get the bytes to be signed (everything except signature and public_key)
     fn signing_payload(&self) -> Vec<u8> {
-        format!(
+        let mut payload = format!(
             "{}:{}:{}:{}:{}:{}",
             self.version,
             self.project_slug,
@@ -499,8 +1491,17 @@ impl ExportManifest {
             self.message_count,
             self.content_hash,
             self.format
-        )
-        .into_bytes()
+        );
+        // Unfiltered, unscrubbed manifests keep the original payload so older signatures still verify
+        if let Some(filter) = &self.filter {
+            payload.push(':');
+            payload.push_str(&serde_json::to_string(filter).unwrap_or_default());
+        }
+        if !self.scrub_mode.is_none() {
+            payload.push_str(":scrub=");
+            payload.push_str(self.scrub_mode.as_str());
+        }
+        payload.into_bytes()
     }
 
     /// Sign the manifest with an Ed25519 signing key
@@ -645,6 +1646,7 @@ impl ExportBmc {
         format: ExportFormat,
         scrub_mode: ScrubMode,
         include_attachments: bool,
+        filter: &ExportFilter,
         signing_key: Option<&SigningKey>,
     ) -> Result<(ExportedMailbox, ExportManifest)> {
         // Export the mailbox
@@ -655,6 +1657,8 @@ impl ExportBmc {
             format,
             scrub_mode,
             include_attachments,
+            filter,
+            ExportCompression::None,
         )
         .await?;
 
@@ -670,16 +1674,16 @@ impl ExportBmc {
     }
 
     /// Verify an export against its manifest
+    ///
+    /// A compressed export is decompressed first: the manifest describes the
+    /// content, not its encoding.
     pub fn verify_export(exported: &ExportedMailbox, manifest: &ExportManifest) -> Result<bool> {
         // First verify content hash matches
-        let content_hash = {
-            use sha1::Digest;
-            let mut hasher = sha1::Sha1::new();
-            hasher.update(exported.content.as_bytes());
-            hex::encode(hasher.finalize())
+        let content = match exported.compression {
+            ExportCompression::None => std::borrow::Cow::Borrowed(&exported.content),
+            _ => std::borrow::Cow::Owned(exported.clone().decompress()?.content),
         };
-
-        if content_hash != manifest.content_hash {
+        if !manifest.matches_content(&content) {
             return Ok(false);
         }
 
@@ -693,6 +1697,266 @@ impl ExportBmc {
     }
 }
 
+// =============================================================================
+// Import
+// =============================================================================
+
+/// `program` recorded for agents created by [`ExportBmc::import_mailbox`].
+pub const IMPORTED_AGENT_PROGRAM: &str = "imported";
+
+/// Outcome of [`ExportBmc::import_mailbox`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct ImportReport {
+    pub project_slug: String,
+    pub format: String,
+    /// Messages written to the project
+    pub imported: usize,
+    /// Messages already present in the project
+    pub skipped: usize,
+    /// Records that could not be parsed or written
+    pub failed: usize,
+    /// Agents created because the export referenced them by name
+    pub agents_created: Vec<String>,
+    /// One entry per failed record
+    pub errors: Vec<String>,
+    /// Manifest check result; `None` when no manifest was provided
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest_verified: Option<bool>,
+}
+
+/// One message as written by the JSON and NDJSON exports.
+#[derive(Debug, Deserialize)]
+struct ImportRecord {
+    sender_name: String,
+    #[serde(default)]
+    thread_id: Option<String>,
+    subject: String,
+    body_md: String,
+    #[serde(default)]
+    importance: Option<String>,
+    #[serde(default)]
+    ack_required: bool,
+    created_ts: NaiveDateTime,
+    #[serde(default)]
+    attachments: Vec<Value>,
+}
+
+impl ExportBmc {
+    /// Import messages from a JSON or NDJSON export into a project.
+    ///
+    /// Senders missing from the project are created by name with
+    /// [`IMPORTED_AGENT_PROGRAM`] as their program. Original timestamps and
+    /// thread ids are kept. A message already in the project (same sender,
+    /// timestamp, subject and body) is skipped, so re-running an import is
+    /// harmless. Exports do not record recipients, so imported messages show
+    /// up in threads, search and later exports but in no inbox.
+    ///
+    /// When a `manifest` is given it is verified first; a content hash
+    /// mismatch or invalid signature aborts the import.
+    pub async fn import_mailbox(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_slug: &str,
+        content: &str,
+        format: ExportFormat,
+        manifest: Option<&ExportManifest>,
+    ) -> Result<ImportReport> {
+        let manifest_verified = match manifest {
+            Some(manifest) => {
+                let verified = manifest.matches_content(content)
+                    && (manifest.signature.is_none() || manifest.verify()?);
+                if !verified {
+                    return Err(crate::Error::InvalidInput(
+                        "Manifest verification failed: content hash or signature does not match"
+                            .to_string(),
+                    ));
+                }
+                Some(true)
+            }
+            None => None,
+        };
+
+        let records: Vec<(String, std::result::Result<ImportRecord, String>)> = match format {
+            ExportFormat::Json => {
+                let values: Vec<Value> = serde_json::from_str(content).map_err(|e| {
+                    crate::Error::InvalidInput(format!("Invalid JSON export: {}", e))
+                })?;
+                values
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, v)| {
+                        let record = serde_json::from_value(v).map_err(|e| e.to_string());
+                        (format!("message {}", i + 1), record)
+                    })
+                    .collect()
+            }
+            ExportFormat::Ndjson => content
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(i, line)| {
+                    let record = serde_json::from_str(line).map_err(|e| e.to_string());
+                    (format!("line {}", i + 1), record)
+                })
+                .collect(),
+            other => {
+                return Err(crate::Error::InvalidInput(format!(
+                    "Import supports json and ndjson exports, not {}",
+                    other.as_str()
+                )));
+            }
+        };
+
+        let project = ProjectBmc::get_by_slug(ctx, mm, project_slug).await?;
+        ProjectBmc::ensure_access(ctx, mm, project.id).await?;
+
+        let mut report = ImportReport {
+            project_slug: project.slug.clone(),
+            format: format.as_str().to_string(),
+            manifest_verified,
+            ..Default::default()
+        };
+        let mut agent_ids: HashMap<String, i64> = HashMap::new();
+
+        // Exports list newest first; importing oldest first keeps the original id order
+        for (label, record) in records.into_iter().rev() {
+            let outcome = match record {
+                Ok(record) => {
+                    Self::import_record(ctx, mm, project.id, &record, &mut agent_ids, &mut report)
+                        .await
+                        .map_err(|e| e.to_string())
+                }
+                Err(e) => Err(e),
+            };
+            match outcome {
+                Ok(true) => report.imported += 1,
+                Ok(false) => report.skipped += 1,
+                Err(e) => {
+                    report.failed += 1;
+                    report.errors.push(format!("{}: {}", label, e));
+                }
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Write one imported message; returns false if it already exists
+    async fn import_record(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: crate::types::ProjectId,
+        record: &ImportRecord,
+        agent_ids: &mut HashMap<String, i64>,
+        report: &mut ImportReport,
+    ) -> Result<bool> {
+        use crate::model::agent::{AgentBmc, AgentForCreate};
+
+        let sender_id = match agent_ids.get(&record.sender_name) {
+            Some(id) => *id,
+            None => {
+                let id = match AgentBmc::get_by_name(ctx, mm, project_id, &record.sender_name).await
+                {
+                    Ok(agent) => agent.id.get(),
+                    Err(crate::Error::AgentNotFound { .. }) => {
+                        let agent_c = AgentForCreate {
+                            project_id,
+                            name: record.sender_name.clone(),
+                            program: IMPORTED_AGENT_PROGRAM.to_string(),
+                            model: "unknown".to_string(),
+                            task_description: "Imported from a mailbox export".to_string(),
+                        };
+                        let id = AgentBmc::create(ctx, mm, agent_c).await?.get();
+                        report.agents_created.push(record.sender_name.clone());
+                        id
+                    }
+                    Err(e) => return Err(e),
+                };
+                agent_ids.insert(record.sender_name.clone(), id);
+                id
+            }
+        };
+
+        let created_ts = record.created_ts.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        // Check for the message in the transaction that inserts it, so two
+        // imports of the same export cannot both write it
+        let (_tx_guard, tx) = mm.begin_tx().await?;
+        let exists = {
+            let stmt = tx
+                .prepare(
+                    r#"
+                SELECT 1 FROM messages AS m
+                JOIN message_bodies AS b ON b.message_id = m.id
+                WHERE m.project_id = ? AND m.sender_id = ? AND m.created_ts = ? AND m.subject = ?
+                  AND b.body_hash = ?
+                LIMIT 1
+                "#,
+                )
+                .await?;
+            let mut rows = stmt
+                .query((
+                    project_id.get(),
+                    sender_id,
+                    created_ts.as_str(),
+                    record.subject.as_str(),
+                    body_hash(&record.body_md),
+                ))
+                .await?;
+            rows.next().await?.is_some()
+        };
+        if exists {
+            return Ok(false);
+        }
+
+        // Imported messages are numbered after the thread's existing ones
+        let params: Vec<libsql::Value> = vec![
+            project_id.get().into(),
+            sender_id.into(),
+            record
+                .thread_id
+                .clone()
+                .map_or(libsql::Value::Null, libsql::Value::Text),
+            record.subject.clone().into(),
+            snippet(&record.body_md).into(),
+            record.importance.as_deref().unwrap_or("normal").into(),
+            serde_json::to_string(&record.attachments)?.into(),
+            i64::from(record.ack_required).into(),
+            created_ts.into(),
+        ];
+        // Scoped so the RETURNING statement is finalized before COMMIT
+        let id = {
+            let stmt = tx
+                .prepare(
+                    r#"
+                INSERT INTO messages (project_id, sender_id, thread_id, subject, snippet, importance, attachments, ack_required, created_ts)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                RETURNING id
+                "#,
+                )
+                .await?;
+            let mut rows = stmt
+                .query(libsql::params::Params::Positional(params))
+                .await?;
+            match rows.next().await? {
+                Some(row) => row.get::<i64>(0)?,
+                None => {
+                    return Err(crate::Error::InvalidInput(
+                        "Failed to import message".into(),
+                    ));
+                }
+            }
+        };
+        insert_body(&tx, id, &record.body_md).await?;
+        if let Some(thread_id) = &record.thread_id {
+            assign_thread_seq(&tx, project_id.get(), id, thread_id).await?;
+        }
+        tx.commit().await?;
+
+        Ok(true)
+    }
+}
+
 // =============================================================================
 // Age Encryption Support
 // =============================================================================
@@ -710,6 +1974,84 @@ pub fn generate_age_identity() -> (String, String) {
     )
 }
 
+/// Parse age recipient strings, reporting the first invalid one
+fn parse_age_recipients(recipients: &[String]) -> Result<Vec<age::x25519::Recipient>> {
+    recipients
+        .iter()
+        .map(|r| {
+            r.trim().parse::<age::x25519::Recipient>().map_err(|e| {
+                crate::Error::InvalidInput(format!("Invalid age recipient '{}': {}", r, e))
+            })
+        })
+        .collect()
+}
+
+/// How an encrypted export is protected
+#[derive(Clone, PartialEq, Eq)]
+pub enum ExportEncryption {
+    /// Encrypt to one or more age recipients (`age1...`)
+    Recipients(Vec<String>),
+    /// Encrypt with an age (scrypt) passphrase
+    Passphrase(String),
+}
+
+impl std::fmt::Debug for ExportEncryption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Recipients(r) => f.debug_tuple("Recipients").field(r).finish(),
+            Self::Passphrase(_) => f.write_str("Passphrase([REDACTED])"),
+        }
+    }
+}
+
+impl ExportEncryption {
+    /// Build from optional recipients and passphrase; exactly one must be given
+    pub fn from_parts(recipients: Vec<String>, passphrase: Option<String>) -> Result<Self> {
+        let encryption = match (recipients.is_empty(), passphrase) {
+            (false, Some(_)) => {
+                return Err(crate::Error::InvalidInput(
+                    "Use either age recipients or a passphrase, not both".to_string(),
+                ));
+            }
+            (false, None) => Self::Recipients(recipients),
+            (true, Some(p)) => Self::Passphrase(p),
+            (true, None) => {
+                return Err(crate::Error::InvalidInput(
+                    "Encryption requires at least one age recipient or a passphrase".to_string(),
+                ));
+            }
+        };
+        encryption.validate()?;
+        Ok(encryption)
+    }
+
+    /// Check recipients parse and the passphrase is non-empty
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            Self::Recipients(recipients) => {
+                if recipients.is_empty() {
+                    return Err(crate::Error::InvalidInput(
+                        "At least one recipient required for encryption".to_string(),
+                    ));
+                }
+                parse_age_recipients(recipients).map(|_| ())
+            }
+            Self::Passphrase(p) if p.is_empty() => Err(crate::Error::InvalidInput(
+                "Passphrase must not be empty".to_string(),
+            )),
+            Self::Passphrase(_) => Ok(()),
+        }
+    }
+
+    /// Encrypt data to armored age output
+    pub fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Self::Recipients(recipients) => encrypt_with_age(data, recipients),
+            Self::Passphrase(passphrase) => encrypt_with_passphrase(data, passphrase),
+        }
+    }
+}
+
 /// Encrypt data using age with one or more recipients
 ///
 /// Recipients are age public keys (bech32-encoded strings starting with "age1...")
@@ -721,16 +2063,10 @@ pub fn encrypt_with_age(data: &[u8], recipients: &[String]) -> Result<Vec<u8>> {
     }
 
     // Parse recipients and box them as trait objects
-    let parsed_recipients: Vec<Box<dyn age::Recipient + Send>> = recipients
-        .iter()
-        .map(|r| {
-            r.parse::<age::x25519::Recipient>()
-                .map(|rec| Box::new(rec) as Box<dyn age::Recipient + Send>)
-                .map_err(|e| {
-                    crate::Error::InvalidInput(format!("Invalid age recipient '{}': {}", r, e))
-                })
-        })
-        .collect::<Result<Vec<_>>>()?;
+    let parsed_recipients: Vec<Box<dyn age::Recipient + Send>> = parse_age_recipients(recipients)?
+        .into_iter()
+        .map(|rec| Box::new(rec) as Box<dyn age::Recipient + Send>)
+        .collect();
 
     // Create encryptor
     let encryptor = age::Encryptor::with_recipients(
@@ -850,7 +2186,10 @@ pub fn decrypt_with_passphrase(encrypted: &[u8], passphrase: &str) -> Result<Vec
 
 /// Encrypt an exported mailbox for secure sharing
 impl ExportBmc {
-    /// Export and encrypt a mailbox for one or more age recipients
+    /// Export and encrypt a mailbox to armored age output
+    ///
+    /// The manifest, content, and project info are bundled and encrypted together;
+    /// the returned manifest is the unencrypted copy describing that payload.
     pub async fn export_mailbox_encrypted(
         ctx: &Ctx,
         mm: &ModelManager,
@@ -858,9 +2197,12 @@ impl ExportBmc {
         format: ExportFormat,
         scrub_mode: ScrubMode,
         include_attachments: bool,
-        recipients: &[String],
+        encryption: &ExportEncryption,
         signing_key: Option<&SigningKey>,
     ) -> Result<(Vec<u8>, ExportManifest)> {
+        // Reject bad recipients before doing any export work
+        encryption.validate()?;
+
         // Export and optionally sign
         let (exported, manifest) = Self::export_mailbox_signed(
             ctx,
@@ -869,6 +2211,7 @@ impl ExportBmc {
             format,
             scrub_mode,
             include_attachments,
+            &ExportFilter::default(),
             signing_key,
         )
         .await?;
@@ -886,7 +2229,7 @@ impl ExportBmc {
         })?;
 
         // Encrypt the bundle
-        let encrypted = encrypt_with_age(&bundle_bytes, recipients)?;
+        let encrypted = encryption.encrypt(&bundle_bytes)?;
 
         Ok((encrypted, manifest))
     }
@@ -902,34 +2245,17 @@ impl ExportBmc {
         passphrase: &str,
         signing_key: Option<&SigningKey>,
     ) -> Result<(Vec<u8>, ExportManifest)> {
-        // Export and optionally sign
-        let (exported, manifest) = Self::export_mailbox_signed(
+        Self::export_mailbox_encrypted(
             ctx,
             mm,
             project_slug,
             format,
             scrub_mode,
             include_attachments,
+            &ExportEncryption::Passphrase(passphrase.to_string()),
             signing_key,
         )
-        .await?;
-
-        // Serialize manifest, exported data, and project info together
-        let bundle = serde_json::json!({
-            "manifest": manifest,
-            "content": exported.content,
-            "format": exported.format.as_str(),
-            "project_slug": exported.project_slug,
-            "project_name": exported.project_name,
-        });
-        let bundle_bytes = serde_json::to_vec_pretty(&bundle).map_err(|e| {
-            crate::Error::InvalidInput(format!("Failed to serialize bundle: {}", e))
-        })?;
-
-        // Encrypt the bundle
-        let encrypted = encrypt_with_passphrase(&bundle_bytes, passphrase)?;
-
-        Ok((encrypted, manifest))
+        .await
     }
 
     /// Decrypt and verify an encrypted export
@@ -975,9 +2301,13 @@ impl ExportBmc {
 
         let format_str = match format {
             ExportFormat::Html => "html",
+            ExportFormat::HtmlZip => "html-zip",
             ExportFormat::Json => "json",
             ExportFormat::Markdown => "markdown",
             ExportFormat::Csv => "csv",
+            ExportFormat::Tsv => "tsv",
+            ExportFormat::Ndjson => "ndjson",
+            ExportFormat::Mbox => "mbox",
         };
 
         let exported = ExportedMailbox {
@@ -987,6 +2317,10 @@ impl ExportBmc {
             format: format_str.to_string(),
             message_count: manifest.message_count,
             exported_at: manifest.exported_at.clone(),
+            filter: manifest.filter.clone(),
+            scrub_mode: manifest.scrub_mode,
+            compression: ExportCompression::None,
+            original_size: None,
         };
 
         Ok((exported, manifest))
@@ -1035,9 +2369,13 @@ impl ExportBmc {
 
         let format_str = match format {
             ExportFormat::Html => "html",
+            ExportFormat::HtmlZip => "html-zip",
             ExportFormat::Json => "json",
             ExportFormat::Markdown => "markdown",
             ExportFormat::Csv => "csv",
+            ExportFormat::Tsv => "tsv",
+            ExportFormat::Ndjson => "ndjson",
+            ExportFormat::Mbox => "mbox",
         };
 
         let exported = ExportedMailbox {
@@ -1047,10 +2385,17 @@ impl ExportBmc {
             format: format_str.to_string(),
             message_count: manifest.message_count,
             exported_at: manifest.exported_at.clone(),
+            filter: manifest.filter.clone(),
+            scrub_mode: manifest.scrub_mode,
+            compression: ExportCompression::None,
+            original_size: None,
         };
 
         Ok((exported, manifest))
     }
 }
+mod html;
+
 #[cfg(test)]
+#[allow(clippy::unwrap_used)]
 mod export_scrub_tests;
@@ -0,0 +1,359 @@
+//! Threaded HTML rendering for mailbox exports.
+//!
+//! The page is self-contained so it can be opened offline or attached to a
+//! ticket: a table of contents grouped by thread, one section per message,
+//! Markdown bodies rendered to HTML and inline CSS. Bodies are sanitized
+//! the same way the web UI does it: raw HTML is shown as text and link
+//! targets are limited to http(s), mailto and relative URLs.
+//!
+//! [`render_zip`] splits the same layout into one page per thread.
+
+use super::{Scrubber, html_escape};
+use crate::model::message::{Message, OutboxRecipient};
+use pulldown_cmark::{Event, LinkType, Options, Parser, Tag, TagEnd, html};
+use std::collections::HashMap;
+use std::io::Write;
+
+const STYLE: &str = "<style>
+body { font-family: system-ui, sans-serif; max-width: 860px; margin: 0 auto; padding: 20px; color: #222; }
+a { color: #b45309; }
+nav.toc { border: 1px solid #ddd; border-radius: 8px; padding: 10px 20px; margin-bottom: 20px; }
+nav.toc li { margin: 4px 0; }
+nav.toc .count { color: #666; font-size: 0.9em; }
+section.thread { margin-top: 30px; }
+section.thread > h2 { border-bottom: 2px solid #eee; padding-bottom: 5px; }
+article.message { border: 1px solid #ddd; padding: 15px; margin: 10px 0; border-radius: 8px; }
+.subject { font-weight: bold; font-size: 1.1em; }
+.meta { color: #666; font-size: 0.9em; margin: 5px 0; }
+.meta .badge { background: #fef3c7; border-radius: 4px; padding: 0 4px; }
+.body { margin-top: 10px; overflow-wrap: anywhere; }
+.body pre { background: #f5f5f4; padding: 10px; border-radius: 6px; overflow-x: auto; }
+.body code { font-family: ui-monospace, monospace; font-size: 0.9em; }
+.body table { border-collapse: collapse; }
+.body th, .body td { border: 1px solid #ddd; padding: 4px 8px; }
+.back { font-size: 0.85em; }
+</style>\n";
+
+/// Messages sharing a thread, oldest first.
+struct HtmlThread<'a> {
+    anchor: String,
+    subject: String,
+    messages: Vec<&'a Message>,
+}
+
+/// Render `messages` (newest first) as one threaded page.
+pub(super) fn render_page(
+    title: &str,
+    messages: &[Message],
+    recipients: &HashMap<i64, Vec<OutboxRecipient>>,
+    scrubber: &Scrubber,
+) -> String {
+    let threads = group_threads(messages, scrubber);
+
+    let mut page = page_header(&format!("Mailbox Export - {}", title));
+    page.push_str(&format!(
+        "<h1>Mailbox Export: {}</h1>\n",
+        html_escape(title)
+    ));
+    page.push_str(&format!(
+        "<p>Total messages: {} in {} thread(s)</p>\n",
+        messages.len(),
+        threads.len()
+    ));
+    page.push_str(&toc(&threads, |thread| format!("#{}", thread.anchor)));
+    for thread in &threads {
+        page.push_str(&thread_section(thread, recipients, scrubber, true));
+    }
+    page.push_str("</body>\n</html>");
+    page
+}
+
+/// Render `messages` as a zip of `index.html` plus `threads/<anchor>.html`
+/// for each thread.
+pub(super) fn render_zip(
+    title: &str,
+    messages: &[Message],
+    recipients: &HashMap<i64, Vec<OutboxRecipient>>,
+    scrubber: &Scrubber,
+) -> crate::Result<Vec<u8>> {
+    let threads = group_threads(messages, scrubber);
+
+    let mut index = page_header(&format!("Mailbox Export - {}", title));
+    index.push_str(&format!(
+        "<h1>Mailbox Export: {}</h1>\n",
+        html_escape(title)
+    ));
+    index.push_str(&format!(
+        "<p>Total messages: {} in {} thread(s)</p>\n",
+        messages.len(),
+        threads.len()
+    ));
+    index.push_str(&toc(&threads, |thread| {
+        format!("threads/{}.html", thread.anchor)
+    }));
+    index.push_str("</body>\n</html>");
+
+    let mut files = vec![("index.html".to_string(), index)];
+    for thread in &threads {
+        let mut page = page_header(&format!("{} - {}", thread.subject, title));
+        page.push_str("<p class=\"back\"><a href=\"../index.html\">&larr; All threads</a></p>\n");
+        page.push_str(&thread_section(thread, recipients, scrubber, false));
+        page.push_str("</body>\n</html>");
+        files.push((format!("threads/{}.html", thread.anchor), page));
+    }
+
+    let zip_err = |e: zip::result::ZipError| crate::Error::Io(std::io::Error::other(e));
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    for (name, content) in files {
+        zip.start_file(name, options).map_err(zip_err)?;
+        zip.write_all(content.as_bytes())?;
+    }
+    Ok(zip.finish().map_err(zip_err)?.into_inner())
+}
+
+/// Anchor for a thread: `thread-` plus the lowercased thread key with
+/// everything but ASCII letters and digits collapsed to `-`.
+pub(super) fn thread_anchor(key: &str) -> String {
+    let mut anchor = String::from("thread");
+    for part in key
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+    {
+        anchor.push('-');
+        anchor.push_str(&part.to_ascii_lowercase());
+    }
+    anchor
+}
+
+/// Group messages by thread. Threads are ordered by latest activity;
+/// messages without a thread get one of their own. Anchors that collide
+/// after normalization get a numeric suffix.
+fn group_threads<'a>(messages: &'a [Message], scrubber: &Scrubber) -> Vec<HtmlThread<'a>> {
+    let mut threads: Vec<HtmlThread<'a>> = Vec::new();
+    let mut by_key: HashMap<String, usize> = HashMap::new();
+    let mut anchors: HashMap<String, usize> = HashMap::new();
+
+    // Newest first, so the first message seen decides the thread's position
+    for msg in messages {
+        let key = match &msg.thread_id {
+            Some(thread_id) => thread_id.clone(),
+            None => format!("msg-{}", msg.id),
+        };
+        let idx = *by_key.entry(key.clone()).or_insert_with(|| {
+            let base = thread_anchor(&key);
+            let seen = anchors.entry(base.clone()).or_insert(0);
+            *seen += 1;
+            let anchor = if *seen == 1 {
+                base
+            } else {
+                format!("{}-{}", base, seen)
+            };
+            threads.push(HtmlThread {
+                anchor,
+                subject: String::new(),
+                messages: Vec::new(),
+            });
+            threads.len() - 1
+        });
+        threads[idx].messages.push(msg);
+    }
+
+    for thread in &mut threads {
+        thread.messages.reverse();
+        thread.subject = thread
+            .messages
+            .first()
+            .map(|msg| scrubber.scrub(&msg.subject))
+            .unwrap_or_default();
+    }
+    threads
+}
+
+fn page_header(title: &str) -> String {
+    let mut page = String::from("<!DOCTYPE html>\n<html>\n<head>\n");
+    page.push_str("<meta charset=\"utf-8\">\n");
+    page.push_str(&format!("<title>{}</title>\n", html_escape(title)));
+    page.push_str(STYLE);
+    page.push_str("</head>\n<body>\n");
+    page
+}
+
+/// Table of contents, one entry per thread linking to `href(thread)`.
+fn toc(threads: &[HtmlThread<'_>], href: impl Fn(&HtmlThread<'_>) -> String) -> String {
+    let mut toc = String::from("<nav class=\"toc\">\n<h2>Threads</h2>\n<ol>\n");
+    for thread in threads {
+        let latest = thread
+            .messages
+            .last()
+            .map(|msg| msg.created_ts.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        toc.push_str(&format!(
+            "<li><a href=\"{}\">{}</a> <span class=\"count\">({} message(s), last {})</span></li>\n",
+            html_escape(&href(thread)),
+            html_escape(&thread.subject),
+            thread.messages.len(),
+            latest
+        ));
+    }
+    toc.push_str("</ol>\n</nav>\n");
+    toc
+}
+
+fn thread_section(
+    thread: &HtmlThread<'_>,
+    recipients: &HashMap<i64, Vec<OutboxRecipient>>,
+    scrubber: &Scrubber,
+    link_to_top: bool,
+) -> String {
+    let mut section = format!(
+        "<section class=\"thread\" id=\"{}\">\n<h2>{}</h2>\n",
+        thread.anchor,
+        html_escape(&thread.subject)
+    );
+    for msg in &thread.messages {
+        let msg_recipients = recipients.get(&msg.id).map_or(&[][..], Vec::as_slice);
+        section.push_str(&message_article(msg, msg_recipients, scrubber));
+    }
+    if link_to_top {
+        section.push_str("<p class=\"back\"><a href=\"#\">&uarr; Top</a></p>\n");
+    }
+    section.push_str("</section>\n");
+    section
+}
+
+fn message_article(msg: &Message, recipients: &[OutboxRecipient], scrubber: &Scrubber) -> String {
+    let mut article = format!("<article class=\"message\" id=\"msg-{}\">\n", msg.id);
+    article.push_str(&format!(
+        "<div class=\"subject\">{}</div>\n",
+        html_escape(&scrubber.scrub(&msg.subject))
+    ));
+    article.push_str(&format!(
+        "<div class=\"meta\">From: {} | <time datetime=\"{}\">{} UTC</time>",
+        html_escape(&scrubber.scrub_name(&msg.sender_name)),
+        msg.created_ts.format("%Y-%m-%dT%H:%M:%SZ"),
+        msg.created_ts.format("%Y-%m-%d %H:%M:%S")
+    ));
+    if msg.importance == "high" {
+        article.push_str(" | <span class=\"badge\">high importance</span>");
+    }
+    if msg.ack_required {
+        article.push_str(" | <span class=\"badge\">ack required</span>");
+    }
+    article.push_str("</div>\n");
+    // Bcc recipients are left out, as in the CSV and mbox exports
+    for (label, kind) in [("To", "to"), ("Cc", "cc")] {
+        let names = recipients
+            .iter()
+            .filter(|r| r.recipient_type == kind)
+            .map(|r| html_escape(&scrubber.scrub_name(&r.name)))
+            .collect::<Vec<_>>();
+        if !names.is_empty() {
+            article.push_str(&format!(
+                "<div class=\"meta\">{}: {}</div>\n",
+                label,
+                names.join(", ")
+            ));
+        }
+    }
+    article.push_str(&format!(
+        "<div class=\"body\">{}</div>\n</article>\n",
+        markdown_to_html(&scrubber.scrub_body(&msg.body_md))
+    ));
+    article
+}
+
+/// Render Markdown to sanitized HTML.
+fn markdown_to_html(md: &str) -> String {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+
+    let mut out = String::with_capacity(md.len() * 3 / 2);
+    html::push_html(&mut out, Parser::new_ext(md, options).map(sanitize));
+    out
+}
+
+/// Replace every event that could emit untrusted markup. Images become
+/// links so the page never loads anything remote.
+fn sanitize(event: Event<'_>) -> Event<'_> {
+    match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            ..
+        })
+        | Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            ..
+        }) => {
+            let href = if link_type == LinkType::Email {
+                format!("mailto:{}", dest_url)
+            } else {
+                dest_url.to_string()
+            };
+            let href = if is_safe_url(&href) { &href } else { "#" };
+            Event::InlineHtml(
+                format!(
+                    "<a href=\"{}\" rel=\"noopener noreferrer\">",
+                    html_escape(href)
+                )
+                .into(),
+            )
+        }
+        Event::End(TagEnd::Link) | Event::End(TagEnd::Image) => Event::InlineHtml("</a>".into()),
+        other => other,
+    }
+}
+
+/// Whether `url` may be used as a link target: http, https, mailto or no
+/// scheme at all. Whitespace and control characters are ignored when
+/// detecting the scheme, as browsers do.
+fn is_safe_url(url: &str) -> bool {
+    let cleaned: String = url
+        .chars()
+        .filter(|c| !c.is_ascii_control() && !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    match cleaned.find(':') {
+        None => true,
+        Some(colon) if cleaned[..colon].contains(['/', '?', '#']) => true,
+        Some(colon) => matches!(&cleaned[..colon], "http" | "https" | "mailto"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_anchor() {
+        assert_eq!(thread_anchor("TKT-42"), "thread-tkt-42");
+        assert_eq!(
+            thread_anchor("feature/Login flow"),
+            "thread-feature-login-flow"
+        );
+        assert_eq!(thread_anchor("msg-7"), "thread-msg-7");
+        assert_eq!(thread_anchor("\"><script>"), "thread-script");
+        assert_eq!(thread_anchor("???"), "thread");
+    }
+
+    #[test]
+    fn test_markdown_is_sanitized() {
+        let html = markdown_to_html(
+            "**bold** <script>alert(1)</script>\n\n[x](javascript:alert(1)) [ok](https://example.com) ![img](http://tracker/p.png)",
+        );
+        assert!(html.contains("<strong>bold</strong>"));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+        assert!(!html.contains("javascript:"));
+        assert!(
+            html.contains("<a href=\"https://example.com\" rel=\"noopener noreferrer\">ok</a>")
+        );
+        assert!(!html.contains("<img"));
+    }
+}
@@ -1,10 +1,17 @@
 use crate::Result;
+use crate::events::MailEventKind;
 use crate::model::ModelManager;
+use crate::model::audit::{AuditAction, AuditBmc};
+use crate::model::message::{MessageBmc, MessageForCreate};
+use crate::model::reservation_queue::ReservationQueueBmc;
 use crate::store::git_store;
+use crate::store::retry::execute_with_retry;
 use crate::types::{AgentId, ProjectId};
+use crate::utils::validation::{ValidationError, validate_ttl};
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use sha1::{Digest, Sha1};
+use tracing::warn;
 
 /// A file reservation (lock) for coordinating agent work.
 ///
@@ -22,6 +29,9 @@ use sha1::{Digest, Sha1};
 /// - `created_ts` - When the lock was acquired
 /// - `expires_ts` - When the lock auto-releases (TTL)
 /// - `released_ts` - When it was manually released (if applicable)
+/// - `transferred_from` - Previous holder, if the reservation was last handed
+///   over with [`FileReservationBmc::transfer`]
+/// - `transfer_reason` - Why it was handed over
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileReservation {
     pub id: i64,
@@ -33,6 +43,10 @@ pub struct FileReservation {
     pub created_ts: NaiveDateTime,
     pub expires_ts: NaiveDateTime,
     pub released_ts: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub transferred_from: Option<AgentId>,
+    #[serde(default)]
+    pub transfer_reason: Option<String>,
 }
 
 /// Input data to request a file reservation.
@@ -69,7 +83,7 @@ impl FileReservationBmc {
     /// 2. Archives reservation to Git
     ///
     /// # Arguments
-    /// * `_ctx` - Request context
+    /// * `ctx` - Request context
     /// * `mm` - ModelManager
     /// * `fr_c` - Reservation data (path pattern, exclusive flag, TTL)
     ///
@@ -101,41 +115,129 @@ impl FileReservationBmc {
     /// # }
     /// ```
     pub async fn create(
-        _ctx: &crate::Ctx,
+        ctx: &crate::Ctx,
         mm: &ModelManager,
         fr_c: FileReservationForCreate,
     ) -> Result<i64> {
-        let db = mm.db();
+        super::project::ProjectBmc::ensure_access(ctx, mm, fr_c.project_id).await?;
 
-        let stmt = db.prepare(
-            r#"
-            INSERT INTO file_reservations (project_id, agent_id, path_pattern, exclusive, reason, expires_ts)
-            VALUES (?, ?, ?, ?, ?, ?)
-            RETURNING id
-            "#
-        ).await?;
+        let db = mm.db();
 
         // Format datetime as string for SQLite
         let expires_ts_str = fr_c.expires_ts.format("%Y-%m-%d %H:%M:%S").to_string();
 
-        let mut rows = stmt
-            .query((
-                fr_c.project_id.get(),
-                fr_c.agent_id.get(),
-                fr_c.path_pattern.as_str(),
-                fr_c.exclusive,
-                fr_c.reason.as_str(),
-                expires_ts_str,
-            ))
+        // A single INSERT: a busy attempt applied nothing, so it can rerun
+        let (fr, expires_ts_str) = (&fr_c, expires_ts_str.as_str());
+        let id = execute_with_retry(&mm.busy_retry(), || async move {
+            let stmt = db.prepare(
+                r#"
+                INSERT INTO file_reservations (project_id, agent_id, path_pattern, exclusive, reason, expires_ts)
+                VALUES (?, ?, ?, ?, ?, ?)
+                RETURNING id
+                "#
+            ).await?;
+
+            let mut rows = stmt
+                .query((
+                    fr.project_id.get(),
+                    fr.agent_id.get(),
+                    fr.path_pattern.as_str(),
+                    fr.exclusive,
+                    fr.reason.as_str(),
+                    expires_ts_str,
+                ))
+                .await?;
+
+            match rows.next().await? {
+                Some(row) => Ok(row.get::<i64>(0)?),
+                None => Err(crate::Error::InvalidInput(
+                    "Failed to create file reservation".into(),
+                )),
+            }
+        })
+        .await?;
+
+        Self::announce_created(ctx, mm, id, &fr_c).await?;
+        Self::announce_conflicts(mm, id, &fr_c).await?;
+        Ok(id)
+    }
+
+    /// Publishes `reservation.conflict` when a just-granted reservation
+    /// overlaps paths other agents hold. Reservations are advisory, so the
+    /// grant stands; the event lets watchers react.
+    async fn announce_conflicts(
+        mm: &ModelManager,
+        id: i64,
+        fr_c: &FileReservationForCreate,
+    ) -> Result<()> {
+        let now = chrono::Utc::now().naive_utc();
+        let mut rows = mm
+            .db()
+            .query(
+                r#"
+                SELECT fr.id, fr.path_pattern, fr.exclusive, fr.expires_ts, a.name, p.slug, me.name
+                FROM file_reservations fr
+                JOIN agents a ON a.id = fr.agent_id
+                JOIN projects p ON p.id = fr.project_id
+                JOIN agents me ON me.id = ?
+                WHERE fr.project_id = ? AND fr.agent_id <> ? AND fr.id <> ?
+                  AND fr.released_ts IS NULL AND fr.expires_ts > ?
+                  AND (fr.exclusive OR ?)
+                ORDER BY fr.id
+                "#,
+                (
+                    fr_c.agent_id.get(),
+                    fr_c.project_id.get(),
+                    fr_c.agent_id.get(),
+                    id,
+                    now.format("%Y-%m-%d %H:%M:%S").to_string(),
+                    fr_c.exclusive,
+                ),
+            )
             .await?;
 
-        let id = if let Some(row) = rows.next().await? {
-            row.get::<i64>(0)?
-        } else {
-            return Err(crate::Error::InvalidInput(
-                "Failed to create file reservation".into(),
-            ));
-        };
+        let mut names = None;
+        let mut held = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let path_pattern: String = row.get(1)?;
+            if !crate::utils::pathspec::paths_conflict(&path_pattern, &fr_c.path_pattern) {
+                continue;
+            }
+            names.get_or_insert((row.get::<String>(5)?, row.get::<String>(6)?));
+            held.push(serde_json::json!({
+                "id": row.get::<i64>(0)?,
+                "agent_name": row.get::<String>(4)?,
+                "path_pattern": path_pattern,
+                "exclusive": row.get::<bool>(2)?,
+                "expires_ts": row.get::<String>(3)?,
+            }));
+        }
+
+        if let Some((project_slug, agent_name)) = names {
+            mm.events.publish(
+                MailEventKind::ReservationConflict,
+                &project_slug,
+                serde_json::json!({
+                    "id": id,
+                    "agent_name": agent_name,
+                    "path_pattern": fr_c.path_pattern,
+                    "exclusive": fr_c.exclusive,
+                    "conflicts": held,
+                }),
+            );
+        }
+        Ok(())
+    }
+
+    /// Archives a just-inserted reservation to Git and publishes
+    /// `reservation.created`.
+    pub(in crate::model) async fn announce_created(
+        ctx: &crate::Ctx,
+        mm: &ModelManager,
+        id: i64,
+        fr_c: &FileReservationForCreate,
+    ) -> Result<()> {
+        let db = mm.db();
 
         // Write to Git
         let stmt = db.prepare("SELECT slug FROM projects WHERE id = ?").await?;
@@ -161,7 +263,7 @@ impl FileReservationBmc {
         let _git_guard = mm.git_lock.lock().await;
 
         // Use cached repository to prevent FD exhaustion
-        let repo_arc = mm.get_repo().await?;
+        let repo_arc = mm.get_project_repo(&project_slug).await?;
         let repo = repo_arc.lock().await;
 
         // Hash path_pattern
@@ -188,29 +290,46 @@ impl FileReservationBmc {
 
         let content = serde_json::to_string_pretty(&payload)?;
 
+        let (author_name, author_email) = git_store::author_of(ctx.actor());
         git_store::commit_file(
             &repo,
             &rel_path,
             &content,
             &format!("file_reservation: {} {}", agent_name, fr_c.path_pattern),
-            "mcp-bot",
-            "mcp-bot@localhost",
+            author_name,
+            author_email,
         )?;
 
-        Ok(id)
+        mm.events.publish(
+            MailEventKind::ReservationCreated,
+            &project_slug,
+            serde_json::json!({
+                "id": id,
+                "agent_name": agent_name,
+                "path_pattern": fr_c.path_pattern,
+                "exclusive": fr_c.exclusive,
+                "expires_ts": fr_c.expires_ts.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            }),
+        );
+
+        Ok(())
     }
 
     pub async fn list_active_for_project(
-        _ctx: &crate::Ctx,
+        ctx: &crate::Ctx,
         mm: &ModelManager,
         project_id: ProjectId,
     ) -> Result<Vec<FileReservation>> {
-        let db = mm.db();
+        super::project::ProjectBmc::ensure_access(ctx, mm, project_id).await?;
+
+        let db = mm.db_read();
         // Select active (not released). Checking expiry is better done in app logic or filter
         let stmt = db.prepare(
             r#"
-            SELECT id, project_id, agent_id, path_pattern, exclusive, reason, created_ts, expires_ts, released_ts
-            FROM file_reservations 
+            SELECT id, project_id, agent_id, path_pattern, exclusive, reason, created_ts, expires_ts, released_ts,
+                   transferred_from, transfer_reason
+            FROM file_reservations
+            LEFT JOIN reservation_transfers ON reservation_transfers.reservation_id = file_reservations.id
             WHERE project_id = ? AND released_ts IS NULL
             ORDER BY created_ts DESC
             "#
@@ -224,6 +343,49 @@ impl FileReservationBmc {
         Ok(reservations)
     }
 
+    /// Lists an agent's unreleased, unexpired reservations in a project.
+    ///
+    /// # Arguments
+    /// * `_ctx` - Request context
+    /// * `mm` - ModelManager
+    /// * `project_id` - Project context
+    /// * `agent_id` - Agent holding the reservations
+    ///
+    /// # Returns
+    /// Active reservations ordered by soonest expiry first
+    pub async fn list_active_for_agent(
+        _ctx: &crate::Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        agent_id: AgentId,
+    ) -> Result<Vec<FileReservation>> {
+        let db = mm.db_read();
+        let now_str = chrono::Utc::now()
+            .naive_utc()
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+
+        let stmt = db.prepare(
+            r#"
+            SELECT id, project_id, agent_id, path_pattern, exclusive, reason, created_ts, expires_ts, released_ts,
+                   transferred_from, transfer_reason
+            FROM file_reservations
+            LEFT JOIN reservation_transfers ON reservation_transfers.reservation_id = file_reservations.id
+            WHERE project_id = ? AND agent_id = ? AND released_ts IS NULL AND expires_ts > ?
+            ORDER BY expires_ts ASC
+            "#
+        ).await?;
+        let mut rows = stmt
+            .query((project_id.get(), agent_id.get(), now_str))
+            .await?;
+
+        let mut reservations = Vec::new();
+        while let Some(row) = rows.next().await? {
+            reservations.push(Self::from_row(row)?);
+        }
+        Ok(reservations)
+    }
+
     /// Lists all active file reservations across all projects.
     ///
     /// Used by the `/mail/api/locks` endpoint and web UI dashboard.
@@ -238,11 +400,13 @@ impl FileReservationBmc {
         _ctx: &crate::Ctx,
         mm: &ModelManager,
     ) -> Result<Vec<FileReservation>> {
-        let db = mm.db();
+        let db = mm.db_read();
         let stmt = db.prepare(
             r#"
-            SELECT id, project_id, agent_id, path_pattern, exclusive, reason, created_ts, expires_ts, released_ts
-            FROM file_reservations 
+            SELECT id, project_id, agent_id, path_pattern, exclusive, reason, created_ts, expires_ts, released_ts,
+                   transferred_from, transfer_reason
+            FROM file_reservations
+            LEFT JOIN reservation_transfers ON reservation_transfers.reservation_id = file_reservations.id
             WHERE released_ts IS NULL
             ORDER BY created_ts DESC
             "#
@@ -269,11 +433,13 @@ impl FileReservationBmc {
     /// # Errors
     /// Returns `Error::FileReservationNotFound` if ID doesn't exist
     pub async fn get(_ctx: &crate::Ctx, mm: &ModelManager, id: i64) -> Result<FileReservation> {
-        let db = mm.db();
+        let db = mm.db_read();
         let stmt = db.prepare(
             r#"
-            SELECT id, project_id, agent_id, path_pattern, exclusive, reason, created_ts, expires_ts, released_ts
-            FROM file_reservations 
+            SELECT id, project_id, agent_id, path_pattern, exclusive, reason, created_ts, expires_ts, released_ts,
+                   transferred_from, transfer_reason
+            FROM file_reservations
+            LEFT JOIN reservation_transfers ON reservation_transfers.reservation_id = file_reservations.id
             WHERE id = ?
             "#
         ).await?;
@@ -288,14 +454,16 @@ impl FileReservationBmc {
 
     /// Releases a file reservation by marking it as released.
     ///
+    /// Queued requests the reservation was blocking are granted right away.
+    ///
     /// # Arguments
-    /// * `_ctx` - Request context
+    /// * `ctx` - Request context
     /// * `mm` - ModelManager
     /// * `id` - Reservation ID to release
     ///
     /// # Errors
     /// Returns an error if the reservation doesn't exist
-    pub async fn release(_ctx: &crate::Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+    pub async fn release(ctx: &crate::Ctx, mm: &ModelManager, id: i64) -> Result<()> {
         let db = mm.db();
         let now = chrono::Utc::now().naive_utc();
         let now_str = now.format("%Y-%m-%d %H:%M:%S").to_string();
@@ -308,20 +476,31 @@ impl FileReservationBmc {
             )
             .await?;
 
-        stmt.execute((now_str, id)).await?;
+        if stmt.execute((now_str, id)).await? > 0 {
+            Self::after_release(ctx, mm, id).await?;
+        }
         Ok(())
     }
 
     pub async fn list_all_for_project(
-        _ctx: &crate::Ctx,
+        ctx: &crate::Ctx,
         mm: &ModelManager,
         project_id: i64,
     ) -> Result<Vec<FileReservation>> {
-        let db = mm.db();
+        super::project::ProjectBmc::ensure_access(
+            ctx,
+            mm,
+            crate::types::ProjectId::new(project_id),
+        )
+        .await?;
+
+        let db = mm.db_read();
         let stmt = db.prepare(
             r#"
-            SELECT id, project_id, agent_id, path_pattern, exclusive, reason, created_ts, expires_ts, released_ts
+            SELECT id, project_id, agent_id, path_pattern, exclusive, reason, created_ts, expires_ts, released_ts,
+                   transferred_from, transfer_reason
             FROM file_reservations
+            LEFT JOIN reservation_transfers ON reservation_transfers.reservation_id = file_reservations.id
             WHERE project_id = ?
             ORDER BY created_ts DESC
             "#
@@ -336,7 +515,7 @@ impl FileReservationBmc {
     }
 
     pub async fn release_by_path(
-        _ctx: &crate::Ctx,
+        ctx: &crate::Ctx,
         mm: &ModelManager,
         project_id: i64,
         agent_id: i64,
@@ -369,6 +548,7 @@ impl FileReservationBmc {
                 )
                 .await?;
             stmt.execute((now_str, id)).await?;
+            Self::after_release(ctx, mm, id).await?;
 
             Ok(Some(id))
         } else {
@@ -377,23 +557,69 @@ impl FileReservationBmc {
     }
 
     /// Force release a reservation by ID (any agent can call this for emergencies)
+    ///
+    /// The release is recorded in the audit log in the same transaction.
     pub async fn force_release(
-        _ctx: &crate::Ctx,
+        ctx: &crate::Ctx,
         mm: &ModelManager,
         reservation_id: i64,
     ) -> Result<()> {
-        let db = mm.db();
         let now = chrono::Utc::now().naive_utc();
         let now_str = now.format("%Y-%m-%d %H:%M:%S").to_string();
 
-        let stmt = db
-            .prepare(
-                r#"
-            UPDATE file_reservations SET released_ts = ? WHERE id = ? AND released_ts IS NULL
-            "#,
-            )
-            .await?;
-        stmt.execute((now_str, reservation_id)).await?;
+        let released = {
+            let (_tx_guard, tx) = mm.begin_tx().await?;
+            let stmt = tx
+                .prepare(
+                    r#"
+                SELECT fr.project_id, a.name, fr.path_pattern, fr.exclusive, fr.expires_ts
+                FROM file_reservations fr
+                JOIN agents a ON a.id = fr.agent_id
+                WHERE fr.id = ? AND fr.released_ts IS NULL
+                "#,
+                )
+                .await?;
+            let mut rows = stmt.query([reservation_id]).await?;
+            let Some(row) = rows.next().await? else {
+                return Ok(());
+            };
+            let project_id = ProjectId::new(row.get(0)?);
+            let before = serde_json::json!({
+                "agent_name": row.get::<String>(1)?,
+                "path_pattern": row.get::<String>(2)?,
+                "exclusive": row.get::<bool>(3)?,
+                "expires_ts": row.get::<String>(4)?,
+            });
+
+            let stmt = tx
+                .prepare(
+                    r#"
+                UPDATE file_reservations SET released_ts = ? WHERE id = ? AND released_ts IS NULL
+                "#,
+                )
+                .await?;
+            let released = stmt.execute((now_str.as_str(), reservation_id)).await? > 0;
+            if released {
+                AuditBmc::record_in(
+                    &tx,
+                    ctx,
+                    project_id,
+                    AuditAction::ReservationForceRelease,
+                    reservation_id,
+                    serde_json::json!({
+                        "before": before,
+                        "after": { "released_ts": now_str },
+                    }),
+                )
+                .await?;
+            }
+            tx.commit().await?;
+            released
+        };
+
+        if released {
+            Self::after_release(ctx, mm, reservation_id).await?;
+        }
         Ok(())
     }
 
@@ -418,6 +644,319 @@ impl FileReservationBmc {
         Ok(())
     }
 
+    /// Gets the most recent reservation an agent took on an exact path pattern.
+    ///
+    /// Includes released and expired reservations so callers can tell a
+    /// lapsed lock apart from one that never existed.
+    pub async fn get_latest_by_path(
+        _ctx: &crate::Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        agent_id: AgentId,
+        path_pattern: &str,
+    ) -> Result<Option<FileReservation>> {
+        let db = mm.db_read();
+        let stmt = db.prepare(
+            r#"
+            SELECT id, project_id, agent_id, path_pattern, exclusive, reason, created_ts, expires_ts, released_ts,
+                   transferred_from, transfer_reason
+            FROM file_reservations
+            LEFT JOIN reservation_transfers ON reservation_transfers.reservation_id = file_reservations.id
+            WHERE project_id = ? AND agent_id = ? AND path_pattern = ?
+            ORDER BY created_ts DESC, id DESC
+            LIMIT 1
+            "#
+        ).await?;
+        let mut rows = stmt
+            .query((project_id.get(), agent_id.get(), path_pattern))
+            .await?;
+
+        match rows.next().await? {
+            Some(row) => Ok(Some(Self::from_row(row)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Renews a live reservation so it expires `ttl_seconds` from now.
+    ///
+    /// Unlike [`Self::renew`], the new expiry is always counted from now (never
+    /// stacked onto the old one) and the TTL is capped by
+    /// `reservations.max_ttl_seconds` in the app config.
+    ///
+    /// # Returns
+    /// The reservation with its updated expiry
+    ///
+    /// # Errors
+    /// - `FileReservationNotFound` if the ID doesn't exist
+    /// - `FileReservationInactive` if it was released or has already expired
+    /// - `Validation` if the TTL is outside the allowed range
+    pub async fn renew_active(
+        ctx: &crate::Ctx,
+        mm: &ModelManager,
+        reservation_id: i64,
+        ttl_seconds: i64,
+    ) -> Result<FileReservation> {
+        let max_ttl = mm.app_config.reservations.max_ttl_seconds;
+        let provided = u64::try_from(ttl_seconds).unwrap_or(0);
+        validate_ttl(provided)?;
+        if provided > max_ttl {
+            return Err(ValidationError::InvalidTtl {
+                provided,
+                min: 60,
+                max: max_ttl,
+                suggestion: max_ttl,
+            }
+            .into());
+        }
+
+        let mut reservation = Self::get(ctx, mm, reservation_id).await?;
+        let now = chrono::Utc::now().naive_utc();
+        Self::ensure_active(&reservation, now)?;
+
+        let new_expires = now + chrono::Duration::seconds(ttl_seconds);
+        Self::renew(ctx, mm, reservation_id, new_expires).await?;
+        reservation.expires_ts = new_expires;
+        Ok(reservation)
+    }
+
+    /// Hands a live reservation to another agent in the same project, e.g.
+    /// when its holder crashed mid-task and the work is being picked up.
+    ///
+    /// The reassignment, the transfer record and the audit entry are written
+    /// in one transaction, so a failed transfer leaves the reservation with
+    /// its holder. Both agents then get a system message and a
+    /// `reservation.transferred` event is published. Transferring to the
+    /// current holder changes nothing.
+    ///
+    /// # Returns
+    /// The reservation as held after the call
+    ///
+    /// # Errors
+    /// - `FileReservationNotFound` if the ID doesn't exist
+    /// - `FileReservationInactive` if it was released or has already expired
+    /// - `AgentNotFound` if the target agent isn't in the reservation's project
+    pub async fn transfer(
+        ctx: &crate::Ctx,
+        mm: &ModelManager,
+        reservation_id: i64,
+        to_agent_id: AgentId,
+        reason: &str,
+    ) -> Result<FileReservation> {
+        let mut reservation = Self::get(ctx, mm, reservation_id).await?;
+        super::project::ProjectBmc::ensure_access(ctx, mm, reservation.project_id).await?;
+
+        let now = chrono::Utc::now().naive_utc();
+        Self::ensure_active(&reservation, now)?;
+        if reservation.agent_id == to_agent_id {
+            return Ok(reservation);
+        }
+
+        let from_agent_id = reservation.agent_id;
+        let now_str = now.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        let (project_slug, from_name, to_name) = {
+            let (_tx_guard, tx) = mm.begin_tx().await?;
+
+            let mut rows = tx
+                .query(
+                    "SELECT name FROM agents WHERE id = ? AND project_id = ?",
+                    (to_agent_id.get(), reservation.project_id.get()),
+                )
+                .await?;
+            let Some(row) = rows.next().await? else {
+                return Err(crate::Error::agent_not_found(format!("{}", to_agent_id)));
+            };
+            let to_name: String = row.get(0)?;
+
+            let mut rows = tx
+                .query(
+                    r#"
+                SELECT a.name, p.slug
+                FROM agents a
+                JOIN projects p ON p.id = ?
+                WHERE a.id = ?
+                "#,
+                    (reservation.project_id.get(), from_agent_id.get()),
+                )
+                .await?;
+            let (from_name, project_slug): (String, String) = match rows.next().await? {
+                Some(row) => (row.get(0)?, row.get(1)?),
+                None => return Err(crate::Error::agent_not_found(format!("{}", from_agent_id))),
+            };
+
+            // Guarded on the holder so a concurrent release or transfer wins
+            let moved = tx
+                .execute(
+                    r#"
+                UPDATE file_reservations SET agent_id = ?
+                WHERE id = ? AND agent_id = ? AND released_ts IS NULL
+                "#,
+                    (to_agent_id.get(), reservation_id, from_agent_id.get()),
+                )
+                .await?;
+            if moved == 0 {
+                return Err(crate::Error::FileReservationInactive(format!(
+                    "{} ({}) was released or transferred concurrently",
+                    reservation_id, reservation.path_pattern
+                )));
+            }
+
+            tx.execute(
+                r#"
+                INSERT OR REPLACE INTO reservation_transfers
+                    (reservation_id, transferred_from, transfer_reason, transferred_ts)
+                VALUES (?, ?, ?, ?)
+                "#,
+                (
+                    reservation_id,
+                    from_agent_id.get(),
+                    reason,
+                    now_str.as_str(),
+                ),
+            )
+            .await?;
+
+            AuditBmc::record_in(
+                &tx,
+                ctx,
+                reservation.project_id,
+                AuditAction::ReservationTransfer,
+                reservation_id,
+                serde_json::json!({
+                    "before": { "agent_name": from_name },
+                    "after": { "agent_name": to_name, "reason": reason },
+                    "path_pattern": reservation.path_pattern,
+                }),
+            )
+            .await?;
+            tx.commit().await?;
+            (project_slug, from_name, to_name)
+        };
+
+        reservation.agent_id = to_agent_id;
+        reservation.transferred_from = Some(from_agent_id);
+        reservation.transfer_reason = Some(reason.to_string());
+
+        mm.events.publish(
+            MailEventKind::ReservationTransferred,
+            &project_slug,
+            serde_json::json!({
+                "id": reservation_id,
+                "from_agent": from_name,
+                "to_agent": to_name,
+                "path_pattern": reservation.path_pattern,
+            }),
+        );
+        Self::notify_transferred(ctx, mm, &reservation, &from_name, &to_name).await;
+
+        Ok(reservation)
+    }
+
+    /// Sends both sides of a transfer a system message, from the previous
+    /// holder to both. Failures are logged only; the transfer already happened.
+    async fn notify_transferred(
+        ctx: &crate::Ctx,
+        mm: &ModelManager,
+        reservation: &FileReservation,
+        from_name: &str,
+        to_name: &str,
+    ) {
+        let Some(from_agent_id) = reservation.transferred_from else {
+            return;
+        };
+        let reason = match reservation.transfer_reason.as_deref() {
+            Some(reason) if !reason.trim().is_empty() => reason,
+            _ => "(none given)",
+        };
+        let notice = MessageForCreate {
+            project_id: reservation.project_id.get(),
+            sender_id: from_agent_id.get(),
+            recipient_ids: vec![from_agent_id.get(), reservation.agent_id.get()],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: format!("Reservation transferred: {}", reservation.path_pattern),
+            body_md: format!(
+                "[System] Reservation {} for `{}` was transferred from {} to {}.\n\n\
+                 Reason: {}\nExclusive: {}\nExpires at: {}",
+                reservation.id,
+                reservation.path_pattern,
+                from_name,
+                to_name,
+                reason,
+                reservation.exclusive,
+                reservation.expires_ts
+            ),
+            thread_id: None,
+            importance: None,
+            ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
+        };
+        if let Err(e) = MessageBmc::create(ctx, mm, notice).await {
+            warn!(reservation_id = reservation.id, error = %e, "Failed to notify agents of reservation transfer");
+        }
+    }
+
+    /// Fails with `FileReservationInactive` if the reservation was released
+    /// or has expired by `now`.
+    fn ensure_active(reservation: &FileReservation, now: NaiveDateTime) -> Result<()> {
+        if let Some(released_ts) = reservation.released_ts {
+            return Err(crate::Error::FileReservationInactive(format!(
+                "{} ({}) was released at {}",
+                reservation.id, reservation.path_pattern, released_ts
+            )));
+        }
+        if reservation.expires_ts <= now {
+            return Err(crate::Error::FileReservationInactive(format!(
+                "{} ({}) expired at {}",
+                reservation.id, reservation.path_pattern, reservation.expires_ts
+            )));
+        }
+        Ok(())
+    }
+
+    /// Publishes a `reservation.released` event for a just-released
+    /// reservation, then grants queued requests it was blocking.
+    ///
+    /// The release itself has already happened, so a failed promotion is
+    /// only logged; the queue sweeper retries it.
+    async fn after_release(ctx: &crate::Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+        let db = mm.db();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT p.slug, a.name, fr.path_pattern, fr.project_id
+            FROM file_reservations fr
+            JOIN projects p ON p.id = fr.project_id
+            JOIN agents a ON a.id = fr.agent_id
+            WHERE fr.id = ?
+            "#,
+            )
+            .await?;
+        let mut rows = stmt.query([id]).await?;
+        if let Some(row) = rows.next().await? {
+            let project_slug: String = row.get(0)?;
+            let agent_name: String = row.get(1)?;
+            let path_pattern: String = row.get(2)?;
+            let project_id = ProjectId::new(row.get(3)?);
+            mm.events.publish(
+                MailEventKind::ReservationReleased,
+                &project_slug,
+                serde_json::json!({
+                    "id": id,
+                    "agent_name": agent_name,
+                    "path_pattern": path_pattern,
+                }),
+            );
+
+            if let Err(e) = ReservationQueueBmc::promote(ctx, mm, project_id).await {
+                warn!(reservation_id = id, error = %e, "Failed to promote queued reservations");
+            }
+        }
+        Ok(())
+    }
+
     fn from_row(row: libsql::Row) -> Result<FileReservation> {
         let created_ts_str: String = row.get(6).unwrap_or_default();
         let expires_ts_str: String = row.get(7).unwrap_or_default();
@@ -444,6 +983,8 @@ impl FileReservationBmc {
             created_ts,
             expires_ts,
             released_ts,
+            transferred_from: row.get::<Option<i64>>(9)?.map(AgentId::new),
+            transfer_reason: row.get(10)?,
         })
     }
 }
@@ -0,0 +1,476 @@
+//! Message labels for triage.
+//!
+//! Labels such as "needs-review", "blocked" or "done" are defined per
+//! project with a display color and applied to any number of messages in
+//! that project. Names are unique per project ignoring case, so
+//! "Blocked" and "blocked" are the same label.
+//!
+//! Deleting a label removes it from every message; the messages stay.
+
+use crate::Result;
+use crate::ctx::Ctx;
+use crate::model::ModelManager;
+use crate::model::message::{Message, MessageBmc};
+use crate::model::project::ProjectBmc;
+use crate::types::ProjectId;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use utoipa::ToSchema;
+
+/// Color used when a label is created without one (neutral gray).
+pub const DEFAULT_LABEL_COLOR: &str = "#6b7280";
+
+/// Longest accepted label name, in characters.
+const MAX_LABEL_NAME_LEN: usize = 50;
+
+/// A project label.
+///
+/// # Fields
+///
+/// - `id` - Database primary key
+/// - `project_id` - Owning project
+/// - `name` - Label name, unique within the project ignoring case
+/// - `color` - Display color as `#rrggbb`
+/// - `created_ts` - Creation timestamp
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Label {
+    pub id: i64,
+    pub project_id: i64,
+    pub name: String,
+    pub color: String,
+    pub created_ts: NaiveDateTime,
+}
+
+/// Input data for creating a label.
+///
+/// # Fields
+///
+/// - `project_id` - Project to define the label in
+/// - `name` - Label name (unique in the project, case-insensitive)
+/// - `color` - `#rrggbb` color, [`DEFAULT_LABEL_COLOR`] if omitted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LabelForCreate {
+    pub project_id: ProjectId,
+    pub name: String,
+    pub color: Option<String>,
+}
+
+/// Backend Model Controller for message labels.
+pub struct LabelBmc;
+
+impl LabelBmc {
+    /// Creates a label.
+    ///
+    /// # Returns
+    /// The created label's database ID
+    ///
+    /// # Errors
+    /// - [`crate::Error::InvalidInput`] for an empty or overlong name, or a
+    ///   color that is not `#rrggbb`
+    /// - [`crate::Error::LabelNameTaken`] if the project already has a label
+    ///   with this name, ignoring case
+    pub async fn create(ctx: &Ctx, mm: &ModelManager, label_c: LabelForCreate) -> Result<i64> {
+        ProjectBmc::ensure_access(ctx, mm, label_c.project_id).await?;
+
+        let name = label_c.name.trim();
+        if name.is_empty() {
+            return Err(crate::Error::InvalidInput(
+                "Label name cannot be empty".into(),
+            ));
+        }
+        if name.chars().count() > MAX_LABEL_NAME_LEN {
+            return Err(crate::Error::InvalidInput(format!(
+                "Label name is longer than {} characters",
+                MAX_LABEL_NAME_LEN
+            )));
+        }
+        let color = label_c
+            .color
+            .as_deref()
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .unwrap_or(DEFAULT_LABEL_COLOR)
+            .to_ascii_lowercase();
+        if !is_hex_color(&color) {
+            return Err(crate::Error::InvalidInput(format!(
+                "Invalid label color '{}': expected #rrggbb",
+                color
+            )));
+        }
+
+        let db = mm.db();
+        let stmt = db
+            .prepare(
+                r#"
+            INSERT INTO labels (project_id, name, color)
+            VALUES (?, ?, ?)
+            RETURNING id
+            "#,
+            )
+            .await?;
+
+        // The column is COLLATE NOCASE, so UNIQUE (project_id, name) already
+        // rejects names differing only in case. With RETURNING, the violation
+        // surfaces on the first row.
+        let row = match stmt
+            .query((label_c.project_id.get(), name, color.as_str()))
+            .await
+        {
+            Ok(mut rows) => rows.next().await,
+            Err(e) => Err(e),
+        };
+        match row {
+            Ok(Some(row)) => Ok(row.get::<i64>(0)?),
+            Ok(None) => Err(crate::Error::InvalidInput("Failed to create label".into())),
+            Err(e) if e.to_string().contains("UNIQUE constraint failed") => {
+                Err(crate::Error::LabelNameTaken(name.to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Lists a project's labels, ordered by name.
+    pub async fn list_for_project(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+    ) -> Result<Vec<Label>> {
+        ProjectBmc::ensure_access(ctx, mm, project_id).await?;
+
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT id, project_id, name, color, created_ts
+            FROM labels
+            WHERE project_id = ?
+            ORDER BY name ASC
+            "#,
+            )
+            .await?;
+
+        let mut rows = stmt.query([project_id.get()]).await?;
+        let mut labels = Vec::new();
+        while let Some(row) = rows.next().await? {
+            labels.push(Self::from_row(row)?);
+        }
+        Ok(labels)
+    }
+
+    /// Gets a label by ID within a project.
+    ///
+    /// # Errors
+    /// Returns [`crate::Error::LabelNotFound`] if the project has no label
+    /// with this ID.
+    pub async fn get(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        label_id: i64,
+    ) -> Result<Label> {
+        ProjectBmc::ensure_access(ctx, mm, project_id).await?;
+
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT id, project_id, name, color, created_ts
+            FROM labels
+            WHERE project_id = ? AND id = ?
+            "#,
+            )
+            .await?;
+
+        let mut rows = stmt.query((project_id.get(), label_id)).await?;
+        match rows.next().await? {
+            Some(row) => Self::from_row(row),
+            None => Err(crate::Error::LabelNotFound(label_id.to_string())),
+        }
+    }
+
+    /// Gets a label by name within a project, ignoring case.
+    ///
+    /// # Errors
+    /// Returns [`crate::Error::LabelNotFound`] if the project has no label
+    /// with this name.
+    pub async fn get_by_name(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        name: &str,
+    ) -> Result<Label> {
+        ProjectBmc::ensure_access(ctx, mm, project_id).await?;
+
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT id, project_id, name, color, created_ts
+            FROM labels
+            WHERE project_id = ? AND name = ?
+            "#,
+            )
+            .await?;
+
+        let mut rows = stmt.query((project_id.get(), name.trim())).await?;
+        match rows.next().await? {
+            Some(row) => Self::from_row(row),
+            None => Err(crate::Error::LabelNotFound(name.trim().to_string())),
+        }
+    }
+
+    /// Deletes a label and removes it from every message. The messages
+    /// themselves are kept.
+    ///
+    /// # Errors
+    /// Returns [`crate::Error::LabelNotFound`] if the project has no label
+    /// with this ID.
+    pub async fn delete(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        label_id: i64,
+    ) -> Result<()> {
+        // Also checks access and existence
+        Self::get(ctx, mm, project_id, label_id).await?;
+
+        let (tx_guard, tx) = mm.begin_tx().await?;
+        // Foreign keys may be off, so the cascade is done by hand
+        let stmt = tx
+            .prepare("DELETE FROM message_labels WHERE label_id = ?")
+            .await?;
+        stmt.execute([label_id]).await?;
+
+        let stmt = tx
+            .prepare("DELETE FROM labels WHERE project_id = ? AND id = ?")
+            .await?;
+        stmt.execute((project_id.get(), label_id)).await?;
+        tx.commit().await?;
+        drop(tx_guard);
+        Ok(())
+    }
+
+    /// Applies a label to a message.
+    ///
+    /// # Returns
+    /// `false` if the message already had the label
+    ///
+    /// # Errors
+    /// - [`crate::Error::MessageNotFound`] if the message does not exist
+    /// - [`crate::Error::LabelNotFound`] if the message's project has no
+    ///   label with this ID
+    pub async fn apply(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        message_id: i64,
+        label_id: i64,
+    ) -> Result<bool> {
+        let project_id = Self::message_project(ctx, mm, message_id).await?;
+        Self::get(ctx, mm, project_id, label_id).await?;
+
+        let db = mm.db();
+        let stmt = db
+            .prepare("INSERT OR IGNORE INTO message_labels (message_id, label_id) VALUES (?, ?)")
+            .await?;
+        let inserted = stmt.execute((message_id, label_id)).await?;
+        Ok(inserted > 0)
+    }
+
+    /// Removes a label from a message.
+    ///
+    /// # Returns
+    /// `false` if the message did not have the label
+    ///
+    /// # Errors
+    /// - [`crate::Error::MessageNotFound`] if the message does not exist
+    /// - [`crate::Error::LabelNotFound`] if the message's project has no
+    ///   label with this ID
+    pub async fn remove(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        message_id: i64,
+        label_id: i64,
+    ) -> Result<bool> {
+        let project_id = Self::message_project(ctx, mm, message_id).await?;
+        Self::get(ctx, mm, project_id, label_id).await?;
+
+        let db = mm.db();
+        let stmt = db
+            .prepare("DELETE FROM message_labels WHERE message_id = ? AND label_id = ?")
+            .await?;
+        let removed = stmt.execute((message_id, label_id)).await?;
+        Ok(removed > 0)
+    }
+
+    /// Adds and removes labels on a message by name.
+    ///
+    /// Names are matched ignoring case. With `create_missing`, names in `add`
+    /// that the project does not have yet are created with the default
+    /// color; otherwise they are an error. Removals run after additions, so
+    /// a name in both lists ends up removed.
+    ///
+    /// # Returns
+    /// The message's labels after the change
+    ///
+    /// # Errors
+    /// - [`crate::Error::MessageNotFound`] if the message does not exist
+    /// - [`crate::Error::LabelNotFound`] for an unknown name in `remove`, or
+    ///   in `add` without `create_missing`
+    pub async fn update_message_labels(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        message_id: i64,
+        add: &[String],
+        remove: &[String],
+        create_missing: bool,
+    ) -> Result<Vec<Label>> {
+        let project_id = Self::message_project(ctx, mm, message_id).await?;
+
+        for name in add {
+            let label = match Self::get_by_name(ctx, mm, project_id, name).await {
+                Ok(label) => label,
+                Err(crate::Error::LabelNotFound(_)) if create_missing => {
+                    let label_id = Self::create(
+                        ctx,
+                        mm,
+                        LabelForCreate {
+                            project_id,
+                            name: name.clone(),
+                            color: None,
+                        },
+                    )
+                    .await?;
+                    Self::get(ctx, mm, project_id, label_id).await?
+                }
+                Err(e) => return Err(e),
+            };
+            Self::apply(ctx, mm, message_id, label.id).await?;
+        }
+        for name in remove {
+            let label = Self::get_by_name(ctx, mm, project_id, name).await?;
+            Self::remove(ctx, mm, message_id, label.id).await?;
+        }
+
+        Self::list_for_message(ctx, mm, message_id).await
+    }
+
+    /// Lists the labels applied to a message, ordered by name.
+    pub async fn list_for_message(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        message_id: i64,
+    ) -> Result<Vec<Label>> {
+        Self::message_project(ctx, mm, message_id).await?;
+
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT l.id, l.project_id, l.name, l.color, l.created_ts
+            FROM labels AS l
+            JOIN message_labels AS ml ON ml.label_id = l.id
+            WHERE ml.message_id = ?
+            ORDER BY l.name ASC
+            "#,
+            )
+            .await?;
+
+        let mut rows = stmt.query([message_id]).await?;
+        let mut labels = Vec::new();
+        while let Some(row) = rows.next().await? {
+            labels.push(Self::from_row(row)?);
+        }
+        Ok(labels)
+    }
+
+    /// Lists the project's messages carrying a label, newest first.
+    /// Recalled messages are left out.
+    pub async fn list_messages(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        label_id: i64,
+        limit: i64,
+    ) -> Result<Vec<Message>> {
+        Self::get(ctx, mm, project_id, label_id).await?;
+
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT
+                m.id, m.project_id, m.sender_id, ag.name as sender_name, m.thread_id, m.subject, m.body_md,
+                m.importance, m.ack_required, m.created_ts, m.attachments, m.thread_seq,
+                m.forwarded_from_id
+            FROM visible_messages AS m
+            JOIN message_labels AS ml ON ml.message_id = m.id
+            JOIN agents AS ag ON m.sender_id = ag.id
+            WHERE ml.label_id = ? AND m.project_id = ?
+            ORDER BY m.created_ts DESC, m.id DESC
+            LIMIT ?
+            "#,
+            )
+            .await?;
+
+        let mut rows = stmt.query((label_id, project_id.get(), limit)).await?;
+        let mut messages = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let created_ts_str: String = row.get(9)?;
+            let created_ts = NaiveDateTime::parse_from_str(&created_ts_str, "%Y-%m-%d %H:%M:%S")
+                .unwrap_or_default();
+            let attachments_str: String = row.get(10)?;
+            let attachments: Vec<Value> = serde_json::from_str(&attachments_str)?;
+
+            messages.push(Message {
+                id: row.get(0)?,
+                project_id: row.get(1)?,
+                sender_id: row.get(2)?,
+                sender_name: row.get(3)?,
+                thread_id: row.get(4)?,
+                subject: row.get(5)?,
+                body_md: row.get(6)?,
+                snippet: String::new(),
+                importance: row.get(7)?,
+                ack_required: row.get(8)?,
+                created_ts,
+                attachments,
+                project_slug: None,
+                thread_seq: row.get(11)?,
+                forwarded_from_id: row.get(12)?,
+                returned_from_snooze: false,
+                needs_ack: false,
+            });
+        }
+        Ok(messages)
+    }
+
+    /// Project of a message, after checking the caller may access it.
+    async fn message_project(ctx: &Ctx, mm: &ModelManager, message_id: i64) -> Result<ProjectId> {
+        let message = MessageBmc::get(ctx, mm, message_id).await?;
+        let project_id = ProjectId::new(message.project_id);
+        ProjectBmc::ensure_access(ctx, mm, project_id).await?;
+        Ok(project_id)
+    }
+
+    fn from_row(row: libsql::Row) -> Result<Label> {
+        let created_ts_str: String = row.get(4).unwrap_or_default();
+        let created_ts =
+            NaiveDateTime::parse_from_str(&created_ts_str, "%Y-%m-%d %H:%M:%S").unwrap_or_default();
+
+        Ok(Label {
+            id: row.get(0)?,
+            project_id: row.get(1)?,
+            name: row.get(2)?,
+            color: row.get(3)?,
+            created_ts,
+        })
+    }
+}
+
+/// Whether `color` is `#` followed by six hex digits.
+fn is_hex_color(color: &str) -> bool {
+    color
+        .strip_prefix('#')
+        .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()))
+}
@@ -6,11 +6,16 @@
 //!
 //! # Features
 //!
-//! - **Threading**: Messages can be grouped into conversation threads
+//! - **Threading**: Messages can be grouped into conversation threads; a
+//!   `thread_id` must name an existing thread unless the sender opts into
+//!   starting one (see [`normalize_thread_id`])
 //! - **Importance**: High/Normal priority levels for triage
 //! - **Recipients**: To/CC/BCC support with delivery tracking
 //! - **Full-text search**: FTS5-powered message search
 //! - **Git archival**: Automatic commit to audit log
+//! - **Scheduled delivery**: `deliver_at` holds a message until the server scheduler releases it
+//! - **Forwarding**: [`MessageBmc::forward`] quotes a message to new recipients and links back to it
+//! - **Sorting**: Inboxes list in any [`InboxSort`] order, paged by [`InboxCursor`]
 //!
 //! # Example
 //!
@@ -35,6 +40,9 @@
 //!     thread_id: None,
 //!     importance: Some("high".to_string()),
 //!     ack_required: false,
+//!     deliver_at: None,
+//!     broadcast: false,
+//!     allow_new_thread: false,
 //! };
 //! let id = MessageBmc::create(&ctx, &mm, msg).await?;
 //! # Ok(())
@@ -42,19 +50,98 @@
 //! ```
 
 use crate::Result;
-use crate::ctx::Ctx;
+use crate::ctx::{Actor, Ctx};
+use crate::events::MailEventKind;
 use crate::model::ModelManager;
+use crate::model::archive_queue::ArchiveJob;
+use crate::model::audit::{AuditAction, AuditBmc};
 use crate::store::git_store;
+use crate::store::retry::execute_with_retry;
 use crate::types::ProjectId;
+use crate::utils::search_query::SearchQuery;
 use chrono::NaiveDateTime;
+use mouchak_mail_common::config::MailboxCopies;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::Arc;
-use tokio::sync::Mutex;
 use tracing::{info, warn};
+use utoipa::ToSchema;
 use uuid::Uuid;
 
+/// Maximum number of message ids accepted by batch operations.
+pub const MAX_BATCH_SIZE: usize = 500;
+
+/// Longest a single [`MessageBmc::wait_for_inbox`] call may block.
+pub const MAX_INBOX_WAIT_SECS: u64 = 120;
+
+/// Most messages returned by one [`MessageBmc::wait_for_inbox`] wake-up.
+const INBOX_WAIT_BATCH: i64 = 100;
+
+/// Longest thread ID kept; longer IDs are cut to this many characters.
+pub const MAX_THREAD_ID_CHARS: usize = 128;
+
+/// Characters of body kept in a message's snippet.
+pub const SNIPPET_CHARS: usize = 200;
+
+/// The snippet stored with a message: the first [`SNIPPET_CHARS`]
+/// characters of its body.
+///
+/// Counts characters rather than bytes, so a multi-byte character is never
+/// cut in half. Matches SQLite's `substr`, which migration 032 used to fill
+/// in snippets for existing messages.
+pub fn snippet(body_md: &str) -> String {
+    body_md.chars().take(SNIPPET_CHARS).collect()
+}
+
+/// Hex SHA-256 of a message body, as stored in `message_bodies.body_hash`.
+pub fn body_hash(body_md: &str) -> String {
+    hex::encode(Sha256::digest(body_md.as_bytes()))
+}
+
+/// Normalizes a caller-supplied thread ID.
+///
+/// Surrounding whitespace is trimmed and the ID is cut to
+/// [`MAX_THREAD_ID_CHARS`] characters. Case is kept: thread IDs compare
+/// case-sensitively, so `TKT-1` and `tkt-1` are different threads.
+///
+/// # Errors
+/// Returns [`crate::Error::InvalidInput`] if the ID is blank or contains
+/// control characters
+pub fn normalize_thread_id(thread_id: &str) -> Result<String> {
+    let trimmed = thread_id.trim();
+    if trimmed.is_empty() {
+        return Err(crate::Error::InvalidInput(
+            "thread_id must not be blank".to_string(),
+        ));
+    }
+    if trimmed.chars().any(char::is_control) {
+        return Err(crate::Error::InvalidInput(format!(
+            "thread_id {:?} contains control characters",
+            trimmed
+        )));
+    }
+    Ok(trimmed.chars().take(MAX_THREAD_ID_CHARS).collect())
+}
+
+/// Subject with any `Re:` / `Fwd:` / `Fw:` prefixes removed.
+fn base_subject(subject: &str) -> &str {
+    let mut rest = subject.trim();
+    loop {
+        let stripped = ["re:", "fwd:", "fw:"].iter().find_map(|prefix| {
+            rest.get(..prefix.len())
+                .filter(|head| head.eq_ignore_ascii_case(prefix))
+                .map(|_| rest[prefix.len()..].trim_start())
+        });
+        match stripped {
+            Some(next) => rest = next,
+            None => return rest,
+        }
+    }
+}
+
 /// Filter type for importance query - strong type, not primitive String
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ImportanceFilter {
@@ -77,6 +164,158 @@ impl ImportanceFilter {
     }
 }
 
+/// Order of an inbox listing.
+///
+/// Every order ends with the message ID, so ties (same second, same
+/// importance) always come back in the same order and [`InboxCursor`]s
+/// never skip or repeat a message.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InboxSort {
+    /// Newest first
+    #[default]
+    CreatedDesc,
+    /// Oldest first
+    CreatedAsc,
+    /// Urgent, high, normal, then low; newest first within each level
+    ImportanceDesc,
+    /// Messages still waiting on an acknowledgement first, then newest first
+    AckPendingFirst,
+}
+
+impl InboxSort {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CreatedDesc => "created_desc",
+            Self::CreatedAsc => "created_asc",
+            Self::ImportanceDesc => "importance_desc",
+            Self::AckPendingFirst => "ack_pending_first",
+        }
+    }
+
+    /// Sort key of the message aliased `m`, most significant first.
+    ///
+    /// `recipient` is an SQL expression for the agent whose acknowledgement
+    /// counts as pending; without one, any recipient's does.
+    fn keys(&self, m: &str, recipient: Option<&str>) -> Vec<String> {
+        let lead = match self {
+            Self::CreatedDesc | Self::CreatedAsc => None,
+            Self::ImportanceDesc => Some(format!(
+                "CASE {m}.importance WHEN 'urgent' THEN 3 WHEN 'high' THEN 2 \
+                 WHEN 'normal' THEN 1 ELSE 0 END"
+            )),
+            Self::AckPendingFirst => Some(format!(
+                "({m}.ack_required AND EXISTS (SELECT 1 FROM message_recipients AS pr \
+                 WHERE pr.message_id = {m}.id AND pr.ack_ts IS NULL{}))",
+                recipient.map_or(String::new(), |r| format!(" AND pr.agent_id = {r}"))
+            )),
+        };
+        lead.into_iter()
+            .chain([format!("{m}.created_ts"), format!("{m}.id")])
+            .collect()
+    }
+
+    /// `ORDER BY` terms for the message aliased `m`.
+    fn order_by(&self, recipient: Option<&str>) -> String {
+        let direction = if *self == Self::CreatedAsc {
+            "ASC"
+        } else {
+            "DESC"
+        };
+        self.keys("m", recipient)
+            .iter()
+            .map(|key| format!("{} {}", key, direction))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// Condition keeping messages after the cursor message, whose ID is
+    /// bound to `placeholder`.
+    fn after_cursor(&self, recipient: Option<&str>, placeholder: &str) -> String {
+        let op = if *self == Self::CreatedAsc { ">" } else { "<" };
+        format!(
+            "({}) {} (SELECT {} FROM messages AS c WHERE c.id = {})",
+            self.keys("m", recipient).join(", "),
+            op,
+            self.keys("c", recipient).join(", "),
+            placeholder
+        )
+    }
+}
+
+impl std::fmt::Display for InboxSort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for InboxSort {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "created_desc" => Ok(Self::CreatedDesc),
+            "created_asc" => Ok(Self::CreatedAsc),
+            "importance_desc" => Ok(Self::ImportanceDesc),
+            "ack_pending_first" => Ok(Self::AckPendingFirst),
+            _ => Err(crate::Error::InvalidInput(format!(
+                "Invalid sort '{}': expected created_desc, created_asc, importance_desc or ack_pending_first",
+                s
+            ))),
+        }
+    }
+}
+
+/// Keyset position in an inbox listing: the sort it was issued under and
+/// the last message on the page.
+///
+/// Encoded as `<sort>:<message_id>`. A cursor only continues the sort it
+/// came from, so one carried over after switching sorts is rejected instead
+/// of returning the wrong page. A bare message ID is read as a
+/// `created_desc` cursor, as issued before sorting existed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InboxCursor {
+    pub sort: InboxSort,
+    pub message_id: i64,
+}
+
+impl InboxCursor {
+    pub fn new(sort: InboxSort, message_id: i64) -> Self {
+        Self { sort, message_id }
+    }
+
+    /// Message ID to continue after, once the cursor is checked against the
+    /// listing's `sort`.
+    fn position(&self, sort: InboxSort) -> Result<i64> {
+        if self.sort != sort {
+            return Err(crate::Error::InvalidInput(format!(
+                "Cursor was issued for sort '{}', not '{}'; restart from the first page",
+                self.sort, sort
+            )));
+        }
+        Ok(self.message_id)
+    }
+}
+
+impl std::fmt::Display for InboxCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.sort, self.message_id)
+    }
+}
+
+impl std::str::FromStr for InboxCursor {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || crate::Error::InvalidInput(format!("Invalid inbox cursor: {}", s));
+        let (sort, id) = match s.split_once(':') {
+            Some((sort, id)) => (sort.parse().map_err(|_| invalid())?, id),
+            None => (InboxSort::CreatedDesc, s),
+        };
+        let message_id = id.parse().map_err(|_| invalid())?;
+        Ok(Self::new(sort, message_id))
+    }
+}
+
 /// A stored message in the system.
 ///
 /// Messages are the primary communication unit between agents. They support
@@ -89,13 +328,20 @@ impl ImportanceFilter {
 /// - `sender_id` - Sending agent ID
 /// - `thread_id` - Conversation thread UUID
 /// - `subject` - Message subject line
-/// - `body_md` - Message body in Markdown
+/// - `body_md` - Message body in Markdown; empty in inbox and search
+///   listings until [`MessageBmc::load_bodies`] fills it in
+/// - `snippet` - Start of the body; only set by inbox and search listings
 /// - `importance` - "normal" or "high"
 /// - `ack_required` - If true, recipients must acknowledge receipt
 /// - `created_ts` - Creation timestamp
 /// - `attachments` - Attached file metadata
 /// - `sender_name` - Denormalized sender name for UI query optimization
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// - `project_slug` - Sender's project when it differs from the reader's
+/// - `thread_seq` - Position in the thread, counting from 1
+/// - `forwarded_from_id` - Message this one forwards, if it is a forward
+/// - `returned_from_snooze` - Back in the reader's inbox after a snooze
+/// - `needs_ack` - The reader still owes an acknowledgement
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct Message {
     pub id: i64,
     pub project_id: i64,
@@ -103,11 +349,43 @@ pub struct Message {
     pub thread_id: Option<String>,
     pub subject: String,
     pub body_md: String,
+    /// The body's [`snippet`]; only set by inbox and search listings
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub snippet: String,
     pub importance: String,
     pub ack_required: bool,
     pub created_ts: NaiveDateTime,
     pub attachments: Vec<Value>, // Use Vec<Value> for attachments
     pub sender_name: String,     // Added sender_name for inbox display
+    /// Originating project, set on messages delivered from another project
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_slug: Option<String>,
+    /// Position in the thread, counting from 1; orders messages created in
+    /// the same second
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thread_seq: Option<i64>,
+    /// Message this one forwards; set on messages from [`MessageBmc::forward`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forwarded_from_id: Option<i64>,
+    /// The reader snoozed this message and the snooze has run out; only set
+    /// by inbox listings
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub returned_from_snooze: bool,
+    /// The reader has yet to acknowledge this message, as counted by
+    /// [`MessageBmc::list_pending_acks_for_agent`]; only set by inbox listings
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub needs_ack: bool,
+}
+
+/// One page of [`MessageBmc::search_page`] results.
+#[derive(Debug, Clone, Default, Serialize, ToSchema)]
+pub struct SearchPage {
+    /// Matches, newest first, carrying their [`snippet`] in place of the body
+    pub items: Vec<Message>,
+    /// Pass back as `cursor` for the next page; `None` on the last page
+    pub next_cursor: Option<i64>,
+    /// Total number of matches across all pages
+    pub total_estimate: i64,
 }
 
 /// Unified inbox item with project slug for display.
@@ -122,13 +400,89 @@ pub struct UnifiedInboxItem {
     pub sender_id: i64,
     pub sender_name: String,
     pub thread_id: Option<String>,
+    /// Position in the thread, counting from 1
+    pub thread_seq: Option<i64>,
     pub subject: String,
-    pub body_md: String,
+    /// The body's [`snippet`]; [`MessageBmc::get`] has the full body
+    pub snippet: String,
+    /// The snippet cut back to a word boundary, with "…" if the body was
+    /// longer
     pub excerpt: String,
     pub importance: String,
+    pub ack_required: bool,
+    /// A recipient has muted this message's thread
+    pub muted: bool,
     pub created_ts: NaiveDateTime,
 }
 
+/// Server-side filters for the unified inbox.
+///
+/// Empty `projects` means every project; `None` fields are not filtered on.
+#[derive(Debug, Clone)]
+pub struct UnifiedInboxFilter {
+    /// Project slugs to include.
+    pub projects: Vec<String>,
+    /// Sender agent name.
+    pub sender: Option<String>,
+    /// Importance level.
+    pub importance: ImportanceFilter,
+    /// Case-insensitive subject substring.
+    pub query: Option<String>,
+    /// Only messages created at or after this time.
+    pub since: Option<NaiveDateTime>,
+    /// Only messages created before this time.
+    pub until: Option<NaiveDateTime>,
+    /// Maximum number of messages to return.
+    pub limit: i64,
+    /// Position after the previous page.
+    pub cursor: Option<InboxCursor>,
+    /// Label name (case-insensitive) the message must carry.
+    pub label: Option<String>,
+    /// Listing order.
+    pub sort: InboxSort,
+}
+
+impl Default for UnifiedInboxFilter {
+    fn default() -> Self {
+        Self {
+            projects: Vec::new(),
+            sender: None,
+            importance: ImportanceFilter::All,
+            query: None,
+            since: None,
+            until: None,
+            limit: 50,
+            cursor: None,
+            label: None,
+            sort: InboxSort::CreatedDesc,
+        }
+    }
+}
+
+/// Options for [`MessageBmc::list_inbox_for_agent_sorted`].
+#[derive(Debug, Clone)]
+pub struct InboxQuery {
+    /// Label name (case-insensitive) the message must carry.
+    pub label: Option<String>,
+    /// Listing order.
+    pub sort: InboxSort,
+    /// Position after the previous page.
+    pub cursor: Option<InboxCursor>,
+    /// Maximum number of messages to return.
+    pub limit: i64,
+}
+
+impl Default for InboxQuery {
+    fn default() -> Self {
+        Self {
+            label: None,
+            sort: InboxSort::CreatedDesc,
+            cursor: None,
+            limit: 50,
+        }
+    }
+}
+
 /// Input data for creating a new message.
 ///
 /// # Fields
@@ -140,9 +494,13 @@ pub struct UnifiedInboxItem {
 /// - `bcc_ids` - Blind carbon copy recipients
 /// - `subject` - Subject line
 /// - `body_md` - Body in Markdown
-/// - `thread_id` - Optional thread ID (generates new UUID if None)
+/// - `thread_id` - Optional thread ID (generates new UUID if None); must name an
+///   existing thread in the project unless `allow_new_thread` is set
 /// - `importance` - "normal" (default) or "high"
 /// - `ack_required` - Request read receipt
+/// - `deliver_at` - Hold the message until this UTC time (delivered immediately if None)
+/// - `broadcast` - Send to every active agent in the project instead of `recipient_ids`
+/// - `allow_new_thread` - Start a thread named `thread_id` if none exists yet
 #[derive(Deserialize, Serialize)]
 pub struct MessageForCreate {
     pub project_id: i64,
@@ -157,6 +515,69 @@ pub struct MessageForCreate {
     /// Whether recipients must acknowledge this message (default: false)
     #[serde(default)]
     pub ack_required: bool,
+    /// Scheduled delivery time; the message stays hidden until then
+    #[serde(default)]
+    pub deliver_at: Option<NaiveDateTime>,
+    /// Address every non-retired agent except the sender, resolved at send time
+    #[serde(default)]
+    pub broadcast: bool,
+    /// Start a new thread when `thread_id` names none in the project
+    #[serde(default)]
+    pub allow_new_thread: bool,
+}
+
+/// A recipient address that could not be resolved.
+///
+/// `code` is the error code the send would have failed with:
+/// `AGENT_NOT_FOUND`, `PROJECT_NOT_FOUND` or `FORBIDDEN_CROSS_PROJECT`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RecipientFailure {
+    pub name: String,
+    pub code: String,
+}
+
+/// Recipient addresses resolved by [`MessageBmc::resolve_recipients`].
+///
+/// # Fields
+///
+/// - `recipient_ids` / `cc_ids` / `bcc_ids` - Agent IDs of the addresses that resolved
+/// - `delivered` - Addresses that resolved, in request order
+/// - `failed` - Addresses that did not, with their error code
+/// - `first_error` - Error of the first failed address, for callers that reject the send
+/// - `groups` - What each `group:<name>` address expanded to, for
+///   [`AgentGroupBmc::record_expansions`](super::agent_group::AgentGroupBmc::record_expansions)
+#[derive(Debug, Default)]
+pub struct ResolvedRecipients {
+    pub recipient_ids: Vec<i64>,
+    pub cc_ids: Option<Vec<i64>>,
+    pub bcc_ids: Option<Vec<i64>>,
+    pub delivered: Vec<String>,
+    pub failed: Vec<RecipientFailure>,
+    pub first_error: Option<crate::Error>,
+    pub groups: Vec<super::agent_group::GroupExpansion>,
+}
+
+impl ResolvedRecipients {
+    /// Whether the message may be sent.
+    ///
+    /// Always when every address resolved; with `allow_partial`, as long as
+    /// at least one did.
+    pub fn can_send(&self, allow_partial: bool) -> bool {
+        self.failed.is_empty() || (allow_partial && !self.delivered.is_empty())
+    }
+}
+
+/// Error code reported for a recipient address that failed with `err`, or
+/// `None` if the error is not about the address itself.
+fn recipient_error_code(err: &crate::Error) -> Option<&'static str> {
+    match err {
+        crate::Error::AgentNotFound { .. } => Some("AGENT_NOT_FOUND"),
+        crate::Error::ProjectNotFound { .. } => Some("PROJECT_NOT_FOUND"),
+        crate::Error::CrossProjectForbidden(_) => Some("FORBIDDEN_CROSS_PROJECT"),
+        crate::Error::GroupNotFound(_) => Some("GROUP_NOT_FOUND"),
+        crate::Error::GroupEmpty(_) => Some("GROUP_EMPTY"),
+        _ => None,
+    }
 }
 
 /// Raw row from list_pending_reviews query with all nested data.
@@ -201,6 +622,30 @@ pub struct OverdueMessage {
     pub created_ts: NaiveDateTime,
 }
 
+/// A received message still waiting on the reader's acknowledgement.
+///
+/// Returned by [`MessageBmc::list_pending_acks_for_agent`].
+///
+/// # Fields
+///
+/// - `project_slug` - Sender's project when it differs from the reader's
+/// - `recipient_type` - How the reader was addressed: "to" or "cc"
+/// - `days_outstanding` - Whole days since the message was sent
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct PendingAck {
+    pub message_id: i64,
+    pub project_id: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_slug: Option<String>,
+    pub thread_id: Option<String>,
+    pub subject: String,
+    pub sender_name: String,
+    pub importance: String,
+    pub recipient_type: String,
+    pub created_ts: NaiveDateTime,
+    pub days_outstanding: i64,
+}
+
 impl MessageBmc {
     /// List messages that require acknowledgement but haven't received one within the threshold
     pub async fn list_overdue_acks(
@@ -208,7 +653,7 @@ impl MessageBmc {
         mm: &ModelManager,
         threshold_hours: i64,
     ) -> Result<Vec<OverdueMessage>> {
-        let db = mm.db();
+        let db = mm.db_read();
         // SQLite: datetime('now', '-N hours')
         let time_modifier = format!("-{} hours", threshold_hours);
 
@@ -220,13 +665,14 @@ impl MessageBmc {
             r#"
             SELECT 
                 m.id, m.project_id, m.sender_id, m.subject, ag_sender.name, mr.agent_id, ag_recipient.name, m.created_ts
-            FROM messages AS m
+            FROM visible_messages AS m
             JOIN message_recipients AS mr ON m.id = mr.message_id
             JOIN agents AS ag_sender ON m.sender_id = ag_sender.id
             JOIN agents AS ag_recipient ON mr.agent_id = ag_recipient.id
             WHERE 
                 m.ack_required = 1 
                 AND mr.ack_ts IS NULL
+                AND m.recalled_ts IS NULL
                 AND m.created_ts < datetime('now', ?)
             ORDER BY m.created_ts ASC
             "#
@@ -261,8 +707,76 @@ impl MessageBmc {
         Ok(overdue)
     }
 
+    /// List messages the agent still has to acknowledge, oldest first.
+    ///
+    /// Covers `ack_required` messages addressed to the agent directly, and
+    /// as cc when `messages.pending_acks_include_cc` is set. Recalled
+    /// messages are left out. Unlike the inbox, deferred, muted and snoozed
+    /// messages still count.
+    pub async fn list_pending_acks_for_agent(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: i64,
+        agent_id: i64,
+    ) -> Result<Vec<PendingAck>> {
+        super::project::ProjectBmc::ensure_access(
+            ctx,
+            mm,
+            crate::types::ProjectId::new(project_id),
+        )
+        .await?;
+
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+                SELECT m.id, m.project_id, CASE WHEN m.project_id != ?2 THEN sp.slug END,
+                       m.thread_id, m.subject, ag.name, m.importance, mr.recipient_type,
+                       m.created_ts
+                FROM message_recipients AS mr
+                JOIN visible_messages AS m ON m.id = mr.message_id
+                JOIN agents AS ag ON ag.id = m.sender_id
+                JOIN projects AS sp ON sp.id = m.project_id
+                WHERE mr.agent_id = ?1
+                  AND (m.project_id = ?2 OR EXISTS (
+                      SELECT 1 FROM cross_project_recipients AS cpr
+                      WHERE cpr.message_id = m.id AND cpr.agent_id = mr.agent_id
+                        AND cpr.project_id = ?2
+                  ))
+                  AND m.ack_required = 1
+                  AND mr.ack_ts IS NULL
+                  AND m.recalled_ts IS NULL
+                  AND (mr.recipient_type = 'to' OR (?3 AND mr.recipient_type = 'cc'))
+                ORDER BY m.created_ts ASC, m.id ASC
+                "#,
+            )
+            .await?;
+        let include_cc = mm.app_config.messages.pending_acks_include_cc;
+        let mut rows = stmt.query((agent_id, project_id, include_cc)).await?;
+
+        let now = chrono::Utc::now().naive_utc();
+        let mut pending = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let created_ts: String = row.get(8)?;
+            let created_ts = crate::utils::parse_timestamp(&created_ts, "messages.created_ts");
+            pending.push(PendingAck {
+                message_id: row.get(0)?,
+                project_id: row.get(1)?,
+                project_slug: row.get(2)?,
+                thread_id: row.get(3)?,
+                subject: row.get(4)?,
+                sender_name: row.get(5)?,
+                importance: row.get(6)?,
+                recipient_type: row.get(7)?,
+                created_ts,
+                days_outstanding: (now - created_ts).num_days().max(0),
+            });
+        }
+        Ok(pending)
+    }
+
     pub async fn get_inbox_count(_ctx: &Ctx, mm: &ModelManager, agent_id: i64) -> Result<i64> {
-        let db = mm.db();
+        let db = mm.db_read();
         let stmt = db
             .prepare("SELECT COUNT(*) FROM message_recipients WHERE agent_id = ?")
             .await?;
@@ -274,8 +788,85 @@ impl MessageBmc {
         }
     }
 
+    /// Recipients of a broadcast: every non-retired agent except the sender.
+    async fn broadcast_recipients(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        msg_c: &MessageForCreate,
+    ) -> Result<Vec<i64>> {
+        let has_explicit = !msg_c.recipient_ids.is_empty()
+            || msg_c.cc_ids.as_ref().is_some_and(|ids| !ids.is_empty())
+            || msg_c.bcc_ids.as_ref().is_some_and(|ids| !ids.is_empty());
+        if has_explicit {
+            return Err(crate::Error::InvalidInput(
+                "A broadcast goes to every agent; leave recipients, cc and bcc empty".to_string(),
+            ));
+        }
+
+        let agents =
+            super::agent::AgentBmc::list_all_for_project(ctx, mm, ProjectId::new(msg_c.project_id))
+                .await?;
+        let ids: Vec<i64> = agents
+            .iter()
+            .map(|a| a.id.get())
+            .filter(|id| *id != msg_c.sender_id)
+            .collect();
+        if ids.is_empty() {
+            return Err(crate::Error::InvalidInput(
+                "Cannot broadcast: the project has no other active agents".to_string(),
+            ));
+        }
+        Ok(ids)
+    }
+
+    /// Recipients whose DND policy defers this message, with the time each
+    /// deferral ends.
+    async fn dnd_deferrals(
+        mm: &ModelManager,
+        agent_ids: &[i64],
+        importance: &str,
+        ack_required: bool,
+    ) -> Result<Vec<(i64, NaiveDateTime)>> {
+        // Urgent and ack_required messages always go straight through
+        if agent_ids.is_empty() || ack_required || importance == "urgent" {
+            return Ok(Vec::new());
+        }
+
+        let mut unique_ids = agent_ids.to_vec();
+        unique_ids.sort_unstable();
+        unique_ids.dedup();
+
+        let placeholders = unique_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let query = format!(
+            "SELECT agent_id, dnd_until, min_importance FROM agent_settings WHERE agent_id IN ({}) AND dnd_until IS NOT NULL",
+            placeholders
+        );
+        let params: Vec<libsql::Value> = unique_ids.iter().map(|&id| id.into()).collect();
+
+        let db = mm.db_read();
+        let stmt = db.prepare(&query).await?;
+        let mut rows = stmt
+            .query(libsql::params::Params::Positional(params))
+            .await?;
+
+        let now = chrono::Utc::now().naive_utc();
+        let mut deferrals = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let policy = super::agent::DndPolicy {
+                dnd_until: crate::utils::parse_timestamp_opt(row.get(1)?, "agent.dnd_until"),
+                min_importance: row.get(2)?,
+            };
+            if let Some(until) = policy.dnd_until
+                && policy.defers(now, importance, ack_required)
+            {
+                deferrals.push((row.get::<i64>(0)?, until));
+            }
+        }
+        Ok(deferrals)
+    }
+
     async fn check_inbox_quotas(mm: &ModelManager, agent_ids: &[i64], limit: i64) -> Result<()> {
-        let db = mm.db();
+        let db = mm.db_read();
         if agent_ids.is_empty() {
             return Ok(());
         }
@@ -309,6 +900,91 @@ impl MessageBmc {
         Ok(())
     }
 
+    /// Resolves every to/cc/bcc address before a send.
+    ///
+    /// Addresses are resolved with [`AgentBmc::resolve_address`](super::agent::AgentBmc::resolve_address),
+    /// except `group:<name>`, which expands to the group's current members
+    /// (see [`AgentGroupBmc::expand`](super::agent_group::AgentGroupBmc::expand)).
+    /// Unlike a send, one unknown name does not stop the others from being
+    /// checked, so the caller can report all of them at once. An agent
+    /// reached twice within one list is delivered once.
+    ///
+    /// # Errors
+    /// Only errors unrelated to the addresses, such as database failures;
+    /// unknown agents and projects and forbidden cross-project addresses end
+    /// up in [`ResolvedRecipients::failed`].
+    pub async fn resolve_recipients(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        to: &[String],
+        cc: Option<&[String]>,
+        bcc: Option<&[String]>,
+    ) -> Result<ResolvedRecipients> {
+        let mut resolved = ResolvedRecipients::default();
+        resolved.recipient_ids =
+            Self::resolve_address_list(ctx, mm, project_id, to, &mut resolved).await?;
+        if let Some(cc) = cc {
+            resolved.cc_ids =
+                Some(Self::resolve_address_list(ctx, mm, project_id, cc, &mut resolved).await?);
+        }
+        if let Some(bcc) = bcc {
+            resolved.bcc_ids =
+                Some(Self::resolve_address_list(ctx, mm, project_id, bcc, &mut resolved).await?);
+        }
+        Ok(resolved)
+    }
+
+    /// Resolves one recipient list, recording each address in `resolved`.
+    async fn resolve_address_list(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        names: &[String],
+        resolved: &mut ResolvedRecipients,
+    ) -> Result<Vec<i64>> {
+        let mut ids = Vec::new();
+        for name in names {
+            let outcome = match name.strip_prefix(super::agent_group::GROUP_ADDRESS_PREFIX) {
+                Some(group) => {
+                    super::agent_group::AgentGroupBmc::expand(ctx, mm, project_id, group)
+                        .await
+                        .map(|expansion| {
+                            let agent_ids = expansion.agent_ids.clone();
+                            resolved.groups.push(expansion);
+                            agent_ids
+                        })
+                }
+                None => super::agent::AgentBmc::resolve_address(ctx, mm, project_id, name)
+                    .await
+                    .map(|agent| vec![agent.id.get()]),
+            };
+            match outcome {
+                Ok(agent_ids) => {
+                    let new: Vec<i64> = agent_ids
+                        .into_iter()
+                        .filter(|id| !ids.contains(id))
+                        .collect();
+                    if !new.is_empty() {
+                        ids.extend(new);
+                        resolved.delivered.push(name.clone());
+                    }
+                }
+                Err(err) => {
+                    let Some(code) = recipient_error_code(&err) else {
+                        return Err(err);
+                    };
+                    resolved.failed.push(RecipientFailure {
+                        name: name.clone(),
+                        code: code.to_string(),
+                    });
+                    resolved.first_error.get_or_insert(err);
+                }
+            }
+        }
+        Ok(ids)
+    }
+
     /// Creates a new message and sends it to one or more recipients.
     ///
     /// This method:
@@ -317,7 +993,7 @@ impl MessageBmc {
     /// 3. Archives message to Git (async, doesn't block response)
     ///
     /// # Arguments
-    /// * `_ctx` - Request context
+    /// * `ctx` - Request context
     /// * `mm` - ModelManager providing database and Git access
     /// * `msg_c` - Message creation data including recipients
     ///
@@ -325,7 +1001,14 @@ impl MessageBmc {
     /// The created message's database ID
     ///
     /// # Errors
-    /// Returns an error if sender or any recipient doesn't exist
+    /// Returns an error if sender or any recipient doesn't exist, or
+    /// [`crate::Error::Validation`] if the subject, body or recipient count is
+    /// over the limits in [`MessageConfig`](mouchak_mail_common::config::MessageConfig).
+    /// A broadcast fails with [`crate::Error::InvalidInput`] if it also names
+    /// recipients, or if the project has no other active agents.
+    /// A `thread_id` that [`normalize_thread_id`] rejects fails with
+    /// [`crate::Error::InvalidInput`], and one naming no thread in the project
+    /// with [`crate::Error::ThreadNotFound`] unless `allow_new_thread` is set.
     ///
     /// # Example
     /// ```no_run
@@ -345,11 +1028,49 @@ impl MessageBmc {
     ///     thread_id: None,
     ///     importance: None,
     ///     ack_required: false,
+    ///     deliver_at: None,
+    ///     broadcast: false,
+    ///     allow_new_thread: false,
     /// };
     /// let id = MessageBmc::create(&ctx, mm, msg).await.unwrap();
     /// # }
     /// ```
-    pub async fn create(_ctx: &Ctx, mm: &ModelManager, msg_c: MessageForCreate) -> Result<i64> {
+    pub async fn create(ctx: &Ctx, mm: &ModelManager, mut msg_c: MessageForCreate) -> Result<i64> {
+        super::project::ProjectBmc::ensure_access(
+            ctx,
+            mm,
+            crate::types::ProjectId::new(msg_c.project_id),
+        )
+        .await?;
+
+        // Reject oversized messages before they reach the database or archive
+        let recipient_count = msg_c.recipient_ids.len()
+            + msg_c.cc_ids.as_ref().map_or(0, Vec::len)
+            + msg_c.bcc_ids.as_ref().map_or(0, Vec::len);
+        crate::utils::validation::validate_message_limits(
+            &msg_c.subject,
+            &msg_c.body_md,
+            recipient_count,
+            &mm.app_config.messages,
+        )?;
+
+        // A thread ID must continue an existing thread unless the sender asks
+        // for a new one, so a typo doesn't silently fork the conversation
+        if let Some(thread_id) = msg_c.thread_id.take() {
+            let thread_id = normalize_thread_id(&thread_id)?;
+            if !msg_c.allow_new_thread
+                && !Self::thread_exists(mm, msg_c.project_id, &thread_id).await?
+            {
+                return Err(crate::Error::ThreadNotFound(thread_id));
+            }
+            msg_c.thread_id = Some(thread_id);
+        }
+
+        // Resolve a broadcast to the project's current agents
+        if msg_c.broadcast {
+            msg_c.recipient_ids = Self::broadcast_recipients(ctx, mm, &msg_c).await?;
+        }
+
         // Enforce Quota
         if mm.app_config.quota.enabled {
             let limit = mm.app_config.quota.inbox_limit_count as i64;
@@ -365,47 +1086,8 @@ impl MessageBmc {
             }
         }
 
-        let db = mm.db();
-
-        // 1. Insert into DB
-        let thread_id = msg_c
-            .thread_id
-            .unwrap_or_else(|| Uuid::new_v4().to_string());
-        let importance = msg_c.importance.unwrap_or("normal".to_string());
-
-        // Helper to serialize attachments (empty for now)
-        let attachments_json = "[]";
-
-        let stmt = db.prepare(
-            r#"
-            INSERT INTO messages (project_id, sender_id, thread_id, subject, body_md, importance, attachments, ack_required)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-            RETURNING id
-            "#
-        ).await?;
-
-        let mut rows = stmt
-            .query((
-                msg_c.project_id,
-                msg_c.sender_id,
-                thread_id.as_str(),
-                msg_c.subject.as_str(),
-                msg_c.body_md.as_str(),
-                importance.as_str(),
-                attachments_json,
-                msg_c.ack_required,
-            ))
-            .await?;
-
-        let id = if let Some(row) = rows.next().await? {
-            row.get::<i64>(0)?
-        } else {
-            return Err(crate::Error::InvalidInput(
-                "Failed to create message".into(),
-            ));
-        };
-
-        // 2. Insert Recipients with recipient_type (BATCHED)
+        // 1. Validate everything up front, in one round trip: project slug,
+        // sender and recipient names. Nothing is written if any id is unknown.
         let mut recipient_tuples = Vec::new();
         for rid in &msg_c.recipient_ids {
             recipient_tuples.push((*rid, "to"));
@@ -421,136 +1103,436 @@ impl MessageBmc {
             }
         }
 
-        if !recipient_tuples.is_empty() {
-            // Constuct batch insert query: ... VALUES (?, ?, ?), (?, ?, ?)
-            let mut query = String::from(
-                "INSERT INTO message_recipients (message_id, agent_id, recipient_type) VALUES ",
-            );
-            let mut params: Vec<libsql::Value> = Vec::with_capacity(recipient_tuples.len() * 3);
-
-            for (i, (rid, rtype)) in recipient_tuples.iter().enumerate() {
-                if i > 0 {
-                    query.push_str(", ");
-                }
-                query.push_str("(?, ?, ?)");
-                params.push(id.into());
-                params.push((*rid).into());
-                params.push((*rtype).to_string().into());
-            }
-
-            let stmt = db.prepare(&query).await?;
-            stmt.execute(libsql::params::Params::Positional(params))
-                .await?;
-        }
-
-        // 3. Git Operations - DEFERRED to background task for low latency
-        // Collect data needed for background git commit
-        let stmt = db.prepare("SELECT slug FROM projects WHERE id = ?").await?;
-        let mut rows = stmt.query([msg_c.project_id]).await?;
-        let project_slug: String = if let Some(row) = rows.next().await? {
-            row.get(0)?
-        } else {
-            return Err(crate::Error::project_not_found(format!(
-                "ID: {}",
-                msg_c.project_id
-            )));
-        };
-
-        // Batch fetch sender and recipient names
         let mut needed_ids = vec![msg_c.sender_id];
-        needed_ids.extend_from_slice(&msg_c.recipient_ids);
-
+        needed_ids.extend(recipient_tuples.iter().map(|(rid, _)| *rid));
         let placeholders = needed_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
-        let query = format!("SELECT id, name FROM agents WHERE id IN ({})", placeholders);
+        let query = format!(
+            "SELECT p.slug, a.id, a.name, a.project_id FROM projects AS p LEFT JOIN agents AS a ON a.id IN ({}) WHERE p.id = ?",
+            placeholders
+        );
+        let mut params: Vec<libsql::Value> = needed_ids.iter().map(|&id| id.into()).collect();
+        params.push(msg_c.project_id.into());
 
+        let db = mm.db();
         let stmt = db.prepare(&query).await?;
-        let params: Vec<libsql::Value> = needed_ids.iter().map(|&id| id.into()).collect();
         let mut rows = stmt
             .query(libsql::params::Params::Positional(params))
             .await?;
 
+        let mut project_slug = None;
         let mut agent_map = std::collections::HashMap::new();
+        let mut agent_projects = std::collections::HashMap::new();
         while let Some(row) = rows.next().await? {
-            let aid: i64 = row.get(0)?;
-            let name: String = row.get(1)?;
-            agent_map.insert(aid, name);
+            project_slug = Some(row.get::<String>(0)?);
+            if let Some(aid) = row.get::<Option<i64>>(1)? {
+                agent_map.insert(aid, row.get::<String>(2)?);
+                agent_projects.insert(aid, row.get::<i64>(3)?);
+            }
+        }
+        let project_slug = project_slug
+            .ok_or_else(|| crate::Error::project_not_found(format!("ID: {}", msg_c.project_id)))?;
+        if let Some(unknown) = needed_ids.iter().find(|id| !agent_map.contains_key(id)) {
+            return Err(crate::Error::agent_not_found(format!("ID: {}", unknown)));
+        }
+
+        // Recipients registered in another project, with that project
+        let cross_project: Vec<(i64, i64)> = recipient_tuples
+            .iter()
+            .filter_map(|(rid, _)| {
+                agent_projects
+                    .get(rid)
+                    .filter(|&&pid| pid != msg_c.project_id)
+                    .map(|&pid| (*rid, pid))
+            })
+            .collect();
+        if !cross_project.is_empty() {
+            super::agent::AgentBmc::ensure_peer_messaging(ctx, mm, &project_slug)?;
+        }
+
+        // 2. Insert message, schedule and recipients in one transaction
+        let thread_id = msg_c
+            .thread_id
+            .clone()
+            .unwrap_or_else(|| Uuid::new_v4().to_string());
+        let importance = msg_c
+            .importance
+            .clone()
+            .unwrap_or_else(|| "normal".to_string());
+
+        // Recipients in "do not disturb" get the message, but deferred
+        let recipient_ids: Vec<i64> = recipient_tuples.iter().map(|(rid, _)| *rid).collect();
+        let deferrals =
+            Self::dnd_deferrals(mm, &recipient_ids, &importance, msg_c.ack_required).await?;
+
+        // The whole transaction is re-run if SQLite stays busy; a failed
+        // attempt is rolled back when its transaction is dropped
+        let id = execute_with_retry(&mm.busy_retry(), || {
+            Self::insert_message_rows(
+                mm,
+                &msg_c,
+                &thread_id,
+                &importance,
+                &recipient_tuples,
+                &cross_project,
+                &deferrals,
+            )
+        })
+        .await?;
+
+        // Event and archive commit happen at delivery time for scheduled messages
+        if msg_c.deliver_at.is_some() {
+            return Ok(id);
         }
 
+        // 3. Git Operations - DEFERRED to the archive queue, after commit
         let sender_name = agent_map
-            .remove(&msg_c.sender_id)
+            .get(&msg_c.sender_id)
+            .cloned()
             .ok_or_else(|| crate::Error::agent_not_found(format!("ID: {}", msg_c.sender_id)))?;
+        let recipient_names = msg_c
+            .recipient_ids
+            .iter()
+            .filter_map(|rid| agent_map.get(rid).cloned())
+            .collect();
+        let mailbox_names = recipient_tuples
+            .iter()
+            .filter_map(|(rid, _)| agent_map.get(rid).cloned())
+            .collect();
+
+        let author = ctx
+            .actor()
+            .cloned()
+            .unwrap_or_else(|| Actor::agent(&sender_name, &project_slug));
+        announce_message(
+            mm,
+            MessageAnnouncement {
+                id,
+                project_id: msg_c.project_id,
+                project_slug,
+                author,
+                sender_id: msg_c.sender_id,
+                sender_name,
+                recipient_names,
+                mailbox_names,
+                subject: msg_c.subject,
+                body_md: msg_c.body_md,
+                thread_id,
+                importance,
+                ack_required: msg_c.ack_required,
+            },
+        )
+        .await;
 
-        let mut recipient_names = Vec::new();
-        for recipient_id in &msg_c.recipient_ids {
-            if let Some(name) = agent_map.get(recipient_id) {
-                recipient_names.push(name.clone());
-            } else {
-                warn!("Recipient Name not found for ID: {}", recipient_id);
-                recipient_names.push(format!("Unknown-{}", recipient_id));
-            }
+        Ok(id)
+    }
+
+    /// Whether any message in the project belongs to `thread_id`.
+    async fn thread_exists(mm: &ModelManager, project_id: i64, thread_id: &str) -> Result<bool> {
+        let db = mm.db();
+        let stmt = db
+            .prepare("SELECT 1 FROM messages WHERE project_id = ? AND thread_id = ? LIMIT 1")
+            .await?;
+        let mut rows = stmt.query((project_id, thread_id)).await?;
+        Ok(rows.next().await?.is_some())
+    }
+
+    /// Thread ID for a conversation about `subject`.
+    ///
+    /// `Re:` / `Fwd:` prefixes are ignored, so a reply finds the thread its
+    /// first message started. That is the thread of the earliest message in
+    /// the project with the same subject; if there is none yet, the ID is
+    /// derived from the project and subject, so every caller asking about the
+    /// same subject in the project gets the same ID. Send the first message with
+    /// `allow_new_thread` set to start the thread.
+    ///
+    /// # Errors
+    /// Returns [`crate::Error::Forbidden`] if `ctx` is scoped to other
+    /// projects, or a database error
+    pub async fn ensure_thread(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: i64,
+        subject: &str,
+    ) -> Result<String> {
+        super::project::ProjectBmc::ensure_access(
+            ctx,
+            mm,
+            crate::types::ProjectId::new(project_id),
+        )
+        .await?;
+
+        let subject = base_subject(subject);
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+                SELECT thread_id FROM messages
+                WHERE project_id = ? AND subject = ? AND thread_id IS NOT NULL
+                ORDER BY id ASC
+                LIMIT 1
+                "#,
+            )
+            .await?;
+        let mut rows = stmt.query((project_id, subject)).await?;
+        if let Some(row) = rows.next().await? {
+            return Ok(row.get(0)?);
         }
 
-        // Spawn background task for git operations (non-blocking)
-        // Get cached repository before spawning to ensure it's in the cache
-        let cached_repo = match mm.get_repo().await {
-            Ok(repo) => repo,
-            Err(e) => {
-                warn!("Failed to get cached repo for message {}: {}", id, e);
-                return Ok(id); // Return success since DB write succeeded
+        let digest = Sha256::digest(format!("{}\n{}", project_id, subject).as_bytes());
+        Ok(format!("subject-{}", hex::encode(&digest[..8])))
+    }
+
+    /// Insert a message with its body, schedule, broadcast, recipient,
+    /// cross-project and deferral rows in one transaction. Returns the message ID.
+    async fn insert_message_rows(
+        mm: &ModelManager,
+        msg_c: &MessageForCreate,
+        thread_id: &str,
+        importance: &str,
+        recipient_tuples: &[(i64, &str)],
+        cross_project: &[(i64, i64)],
+        deferrals: &[(i64, NaiveDateTime)],
+    ) -> Result<i64> {
+        // Helper to serialize attachments (empty for now)
+        let attachments_json = "[]";
+
+        let (_tx_guard, tx) = mm.begin_tx().await?;
+
+        // Scoped so the RETURNING statement is finalized before COMMIT
+        let id = {
+            let stmt = tx.prepare(
+                r#"
+                INSERT INTO messages (project_id, sender_id, thread_id, subject, snippet, importance, attachments, ack_required)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+                RETURNING id
+                "#
+            ).await?;
+
+            let mut rows = stmt
+                .query((
+                    msg_c.project_id,
+                    msg_c.sender_id,
+                    thread_id,
+                    msg_c.subject.as_str(),
+                    snippet(&msg_c.body_md),
+                    importance,
+                    attachments_json,
+                    msg_c.ack_required,
+                ))
+                .await?;
+
+            if let Some(row) = rows.next().await? {
+                row.get::<i64>(0)?
+            } else {
+                return Err(crate::Error::InvalidInput(
+                    "Failed to create message".into(),
+                ));
             }
         };
 
-        let git_lock = mm.git_lock.clone();
-        let subject = msg_c.subject.clone();
-        let body_md = msg_c.body_md.clone();
-        let thread_id_clone = thread_id.clone();
-        let importance_clone = importance.clone();
+        insert_body(&tx, id, &msg_c.body_md).await?;
+        assign_thread_seq(&tx, msg_c.project_id, id, thread_id).await?;
 
-        tokio::spawn(async move {
-            if let Err(e) = commit_message_to_git(
-                git_lock,
-                cached_repo,
-                id,
-                &project_slug,
-                &sender_name,
-                &recipient_names,
-                &subject,
-                &body_md,
-                &thread_id_clone,
-                &importance_clone,
-            )
-            .await
-            {
-                warn!("Background git commit failed for message {}: {}", id, e);
+        // Scheduled messages get their schedule row before any recipient row,
+        // so they never surface in an inbox ahead of deliver_at.
+        if let Some(deliver_at) = msg_c.deliver_at {
+            let stmt = tx
+                .prepare("INSERT INTO message_schedules (message_id, deliver_at) VALUES (?, ?)")
+                .await?;
+            stmt.execute((id, deliver_at.format("%Y-%m-%d %H:%M:%S").to_string()))
+                .await?;
+        }
+
+        if msg_c.broadcast {
+            let stmt = tx
+                .prepare("INSERT INTO message_broadcasts (message_id) VALUES (?)")
+                .await?;
+            stmt.execute([id]).await?;
+        }
+
+        if !recipient_tuples.is_empty() {
+            // Constuct batch insert query: ... VALUES (?, ?, ?), (?, ?, ?)
+            let mut query = String::from(
+                "INSERT INTO message_recipients (message_id, agent_id, recipient_type) VALUES ",
+            );
+            let mut params: Vec<libsql::Value> = Vec::with_capacity(recipient_tuples.len() * 3);
+
+            for (i, (rid, rtype)) in recipient_tuples.iter().enumerate() {
+                if i > 0 {
+                    query.push_str(", ");
+                }
+                query.push_str("(?, ?, ?)");
+                params.push(id.into());
+                params.push((*rid).into());
+                params.push((*rtype).to_string().into());
             }
-        });
+
+            let stmt = tx.prepare(&query).await?;
+            stmt.execute(libsql::params::Params::Positional(params))
+                .await?;
+        }
+
+        for (agent_id, project_id) in cross_project {
+            let stmt = tx
+                .prepare(
+                    "INSERT OR IGNORE INTO cross_project_recipients (message_id, agent_id, project_id) VALUES (?, ?, ?)",
+                )
+                .await?;
+            stmt.execute((id, *agent_id, *project_id)).await?;
+        }
+
+        for (agent_id, until) in deferrals {
+            let stmt = tx
+                .prepare(
+                    "INSERT OR IGNORE INTO message_deferrals (message_id, agent_id, deferred_until) VALUES (?, ?, ?)",
+                )
+                .await?;
+            stmt.execute((id, *agent_id, until.format("%Y-%m-%d %H:%M:%S").to_string()))
+                .await?;
+        }
+
+        tx.commit().await?;
 
         Ok(id)
     }
 
+    /// List an agent's inbox, newest first.
+    ///
+    /// Messages deferred by the agent's DND policy are left out until their
+    /// deferral ends. Messages in threads the agent muted are left out too,
+    /// unless they are urgent or @-mention the agent in the subject.
+    /// Messages the agent snoozed are left out until the snooze runs out,
+    /// then come back with `returned_from_snooze` set.
+    /// Messages the agent still has to acknowledge have `needs_ack` set.
+    /// Messages sent from another project carry that project's slug.
+    /// Messages carry their [`snippet`] and an empty `body_md`; see
+    /// [`Self::load_bodies`].
     pub async fn list_inbox_for_agent(
-        _ctx: &Ctx,
+        ctx: &Ctx,
         mm: &ModelManager,
         project_id: i64,
         agent_id: i64,
         limit: i64,
     ) -> Result<Vec<Message>> {
-        let db = mm.db();
-        let stmt = db.prepare(
+        Self::list_inbox_for_agent_labeled(ctx, mm, project_id, agent_id, None, limit).await
+    }
+
+    /// List an agent's inbox, newest first, keeping only messages carrying
+    /// `label` (case-insensitive) when one is given.
+    ///
+    /// Deferred, muted and snoozed messages are left out as in
+    /// [`Self::list_inbox_for_agent`].
+    pub async fn list_inbox_for_agent_labeled(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: i64,
+        agent_id: i64,
+        label: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<Message>> {
+        let query = InboxQuery {
+            label: label.map(str::to_string),
+            limit,
+            ..Default::default()
+        };
+        Self::list_inbox_for_agent_sorted(ctx, mm, project_id, agent_id, &query).await
+    }
+
+    /// List one page of an agent's inbox in `query.sort` order.
+    ///
+    /// Deferred, muted and snoozed messages are left out, and bodies left to
+    /// their snippet, as in [`Self::list_inbox_for_agent`]. A pending
+    /// acknowledgement is this agent's own. Continue with [`InboxCursor::new`] on the last message.
+    ///
+    /// # Errors
+    /// [`crate::Error::InvalidInput`] when `query.cursor` was issued for
+    /// another sort.
+    pub async fn list_inbox_for_agent_sorted(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: i64,
+        agent_id: i64,
+        query: &InboxQuery,
+    ) -> Result<Vec<Message>> {
+        let cursor = query.cursor.map(|c| c.position(query.sort)).transpose()?;
+
+        super::project::ProjectBmc::ensure_access(
+            ctx,
+            mm,
+            crate::types::ProjectId::new(project_id),
+        )
+        .await?;
+
+        let db = mm.db_read();
+        let stmt = db.prepare(&format!(
             r#"
             SELECT
-                m.id, m.project_id, m.sender_id, ag.name as sender_name, m.thread_id, m.subject, m.body_md,
-                m.importance, m.ack_required, m.created_ts, m.attachments
-            FROM messages AS m
+                m.id, m.project_id, m.sender_id, ag.name as sender_name, m.thread_id, m.subject, m.snippet,
+                m.importance, m.ack_required, m.created_ts, m.attachments,
+                CASE WHEN m.project_id != ?2 THEN sp.slug END, m.thread_seq, m.forwarded_from_id,
+                EXISTS (
+                    SELECT 1 FROM message_snoozes AS sn
+                    WHERE sn.message_id = m.id AND sn.agent_id = mr.agent_id
+                ),
+                m.ack_required = 1 AND mr.ack_ts IS NULL AND m.recalled_ts IS NULL
+                    AND (mr.recipient_type = 'to' OR (?6 AND mr.recipient_type = 'cc'))
+            FROM visible_messages AS m
             JOIN message_recipients AS mr ON m.id = mr.message_id
             JOIN agents AS ag ON m.sender_id = ag.id
-            WHERE mr.agent_id = ? AND m.project_id = ?
-            ORDER BY m.created_ts DESC
-            LIMIT ?
-            "#
-        ).await?;
-
-        let mut rows = stmt.query((agent_id, project_id, limit)).await?;
+            JOIN projects AS sp ON sp.id = m.project_id
+            WHERE mr.agent_id = ?1
+              AND (m.project_id = ?2 OR EXISTS (
+                  SELECT 1 FROM cross_project_recipients AS cpr
+                  WHERE cpr.message_id = m.id AND cpr.agent_id = mr.agent_id
+                    AND cpr.project_id = ?2
+              ))
+              AND NOT EXISTS (
+                  SELECT 1 FROM message_deferrals AS d
+                  WHERE d.message_id = m.id AND d.agent_id = mr.agent_id
+                    AND d.deferred_until > CURRENT_TIMESTAMP
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM message_snoozes AS sn
+                  WHERE sn.message_id = m.id AND sn.agent_id = mr.agent_id
+                    AND sn.snoozed_until > CURRENT_TIMESTAMP
+              )
+              AND NOT EXISTS (
+                  SELECT 1 FROM thread_mutes AS tm
+                  JOIN agents AS me ON me.id = tm.agent_id
+                  WHERE tm.agent_id = mr.agent_id AND tm.project_id = m.project_id
+                    AND tm.thread_id = m.thread_id
+                    AND m.importance != 'urgent'
+                    AND (lower(m.subject) || ' ') NOT GLOB ('*@' || lower(me.name) || '[^a-z0-9_-]*')
+              )
+              AND (?3 IS NULL OR EXISTS (
+                  SELECT 1 FROM message_labels AS ml
+                  JOIN labels AS l ON l.id = ml.label_id
+                  WHERE ml.message_id = m.id AND l.name = ?3
+              ))
+              AND (?5 IS NULL OR {})
+            ORDER BY {}
+            LIMIT ?4
+            "#,
+            query.sort.after_cursor(Some("?1"), "?5"),
+            query.sort.order_by(Some("?1"))
+        )).await?;
+
+        let label: libsql::Value = query
+            .label
+            .as_deref()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map_or(libsql::Value::Null, |l| l.to_string().into());
+        let mut rows = stmt
+            .query((
+                agent_id,
+                project_id,
+                label,
+                query.limit,
+                cursor,
+                mm.app_config.messages.pending_acks_include_cc,
+            ))
+            .await?;
         let mut messages = Vec::new();
 
         while let Some(row) = rows.next().await? {
@@ -560,7 +1542,7 @@ impl MessageBmc {
             let sender_name: String = row.get(3)?;
             let thread_id: Option<String> = row.get(4)?;
             let subject: String = row.get(5)?;
-            let body_md: String = row.get(6)?;
+            let snippet: String = row.get(6)?;
             let importance: String = row.get(7)?;
             let ack_required: bool = row.get(8)?;
             let created_ts_str: String = row.get(9)?;
@@ -569,6 +1551,8 @@ impl MessageBmc {
 
             let attachments_str: String = row.get(10)?;
             let attachments: Vec<Value> = serde_json::from_str(&attachments_str)?;
+            let project_slug: Option<String> = row.get(11)?;
+            let thread_seq: Option<i64> = row.get(12)?;
 
             messages.push(Message {
                 id,
@@ -577,39 +1561,135 @@ impl MessageBmc {
                 sender_name,
                 thread_id,
                 subject,
-                body_md,
+                body_md: String::new(),
+                snippet,
                 importance,
                 ack_required,
                 created_ts,
                 attachments,
+                project_slug,
+                thread_seq,
+                forwarded_from_id: row.get(13)?,
+                returned_from_snooze: row.get(14)?,
+                needs_ack: row.get(15)?,
             });
         }
         Ok(messages)
     }
 
-    /// List outbox messages SENT BY an agent
+    /// Wait until the agent's inbox has messages newer than
+    /// `since_message_id`, newest first.
+    ///
+    /// Returns immediately when such messages already exist. Otherwise the
+    /// call parks on the event bus and re-checks the inbox whenever a message
+    /// is created in the project, returning an empty list once `timeout`
+    /// (capped at [`MAX_INBOX_WAIT_SECS`]) elapses or the server shuts down.
+    /// Without `since_message_id` only messages sent after the call starts
+    /// count.
+    pub async fn wait_for_inbox(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: i64,
+        agent_id: i64,
+        since_message_id: Option<i64>,
+        timeout: std::time::Duration,
+    ) -> Result<Vec<Message>> {
+        let project = super::project::ProjectBmc::get(ctx, mm, ProjectId::new(project_id)).await?;
+        let deadline = tokio::time::Instant::now()
+            + timeout.min(std::time::Duration::from_secs(MAX_INBOX_WAIT_SECS));
+
+        // Subscribe before the first check so a message created in between
+        // still wakes us.
+        let mut events = mm.events.subscribe();
+        let since = match since_message_id {
+            Some(id) => id,
+            None => {
+                let db = mm.db_read();
+                let mut rows = db
+                    .query(
+                        "SELECT COALESCE(MAX(id), 0) FROM messages WHERE project_id = ?",
+                        [project_id],
+                    )
+                    .await?;
+                match rows.next().await? {
+                    Some(row) => row.get(0)?,
+                    None => 0,
+                }
+            }
+        };
+
+        loop {
+            let mut messages =
+                Self::list_inbox_for_agent(ctx, mm, project_id, agent_id, INBOX_WAIT_BATCH).await?;
+            messages.retain(|m| m.id > since);
+            if !messages.is_empty() {
+                return Ok(messages);
+            }
+
+            // Park until a message lands in this project
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep_until(deadline) => return Ok(Vec::new()),
+                    _ = mm.events.closed() => return Ok(Vec::new()),
+                    event = events.recv() => match event {
+                        Ok(event)
+                            if event.kind == MailEventKind::MessageCreated
+                                && event.project_slug == project.slug =>
+                        {
+                            break;
+                        }
+                        Ok(_) => {}
+                        // Missed events may include ours; re-check
+                        Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => break,
+                        Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                            return Ok(Vec::new());
+                        }
+                    },
+                }
+            }
+        }
+    }
+
+    /// List outbox messages SENT BY an agent, newest first.
+    ///
+    /// Each message carries its recipients with read and ack timestamps.
+    /// Pass the `id` of the last message of a page as `cursor` to fetch the
+    /// next (older) page.
     pub async fn list_outbox_for_agent(
-        _ctx: &Ctx,
+        ctx: &Ctx,
         mm: &ModelManager,
         project_id: i64,
         agent_id: i64,
         limit: i64,
-    ) -> Result<Vec<Message>> {
-        let db = mm.db();
+        cursor: Option<i64>,
+    ) -> Result<Vec<OutboxMessage>> {
+        super::project::ProjectBmc::ensure_access(
+            ctx,
+            mm,
+            crate::types::ProjectId::new(project_id),
+        )
+        .await?;
+
+        let db = mm.db_read();
+        // Keyset pagination on (created_ts, id) so pages stay stable while
+        // the agent keeps sending.
         let stmt = db.prepare(
             r#"
             SELECT
                 m.id, m.project_id, m.sender_id, ag.name as sender_name, m.thread_id, m.subject, m.body_md,
-                m.importance, m.ack_required, m.created_ts, m.attachments
-            FROM messages AS m
+                m.importance, m.ack_required, m.created_ts, m.attachments, m.thread_seq,
+                m.forwarded_from_id
+            FROM visible_messages AS m
             JOIN agents AS ag ON m.sender_id = ag.id
-            WHERE m.sender_id = ? AND m.project_id = ?
-            ORDER BY m.created_ts DESC
-            LIMIT ?
+            WHERE m.project_id = ?1 AND m.sender_id = ?2
+              AND (?3 IS NULL OR (m.created_ts, m.id) < (SELECT created_ts, id FROM messages WHERE id = ?3))
+            ORDER BY m.created_ts DESC, m.id DESC
+            LIMIT ?4
             "#
         ).await?;
 
-        let mut rows = stmt.query((agent_id, project_id, limit)).await?;
+        let cursor: libsql::Value = cursor.map_or(libsql::Value::Null, libsql::Value::Integer);
+        let mut rows = stmt.query((project_id, agent_id, cursor, limit)).await?;
         let mut messages = Vec::new();
 
         while let Some(row) = rows.next().await? {
@@ -623,8 +1703,7 @@ impl MessageBmc {
             let importance: String = row.get(7)?;
             let ack_required: bool = row.get(8)?;
             let created_ts_str: String = row.get(9)?;
-            let created_ts = NaiveDateTime::parse_from_str(&created_ts_str, "%Y-%m-%d %H:%M:%S")
-                .unwrap_or_default();
+            let created_ts = parse_ts(&created_ts_str);
 
             let attachments_str: String = row.get(10)?;
             let attachments: Vec<Value> = serde_json::from_str(&attachments_str)?;
@@ -637,23 +1716,115 @@ impl MessageBmc {
                 thread_id,
                 subject,
                 body_md,
+                snippet: String::new(),
                 importance,
                 ack_required,
                 created_ts,
                 attachments,
+                project_slug: None,
+                thread_seq: row.get(11)?,
+                forwarded_from_id: row.get(12)?,
+                returned_from_snooze: false,
+                needs_ack: false,
             });
         }
-        Ok(messages)
+
+        let mut recipients = Self::list_recipients(mm, &messages).await?;
+        Ok(messages
+            .into_iter()
+            .map(|message| OutboxMessage {
+                recipients: recipients.remove(&message.id).unwrap_or_default(),
+                message,
+            })
+            .collect())
+    }
+
+    /// Batch fetch recipients (with read/ack state) for a page of messages.
+    pub async fn list_recipients(
+        mm: &ModelManager,
+        messages: &[Message],
+    ) -> Result<HashMap<i64, Vec<OutboxRecipient>>> {
+        let mut by_message: HashMap<i64, Vec<OutboxRecipient>> = HashMap::new();
+        if messages.is_empty() {
+            return Ok(by_message);
+        }
+
+        let placeholders = messages.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let query = format!(
+            r#"
+            SELECT mr.message_id, ag.name, mr.recipient_type, mr.read_ts, mr.ack_ts
+            FROM message_recipients AS mr
+            JOIN agents AS ag ON mr.agent_id = ag.id
+            WHERE mr.message_id IN ({})
+            ORDER BY mr.message_id, mr.recipient_type DESC, ag.name
+            "#,
+            placeholders
+        );
+
+        let db = mm.db_read();
+        let stmt = db.prepare(&query).await?;
+        let params: Vec<libsql::Value> = messages.iter().map(|m| m.id.into()).collect();
+        let mut rows = stmt
+            .query(libsql::params::Params::Positional(params))
+            .await?;
+
+        while let Some(row) = rows.next().await? {
+            let message_id: i64 = row.get(0)?;
+            let read_ts: Option<String> = row.get(3)?;
+            let ack_ts: Option<String> = row.get(4)?;
+            by_message
+                .entry(message_id)
+                .or_default()
+                .push(OutboxRecipient {
+                    name: row.get(1)?,
+                    recipient_type: row.get(2)?,
+                    read_ts: read_ts.as_deref().map(parse_ts),
+                    ack_ts: ack_ts.as_deref().map(parse_ts),
+                });
+        }
+        Ok(by_message)
+    }
+
+    /// Fill in the full bodies of a page of listed messages. Recalled messages get their recall reason, as in
+    /// [`Self::get`].
+    pub async fn load_bodies(mm: &ModelManager, messages: &mut [Message]) -> Result<()> {
+        if messages.is_empty() {
+            return Ok(());
+        }
+
+        let placeholders = messages.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let query = format!(
+            "SELECT id, body_md FROM visible_messages WHERE id IN ({})",
+            placeholders
+        );
+
+        let db = mm.db_read();
+        let stmt = db.prepare(&query).await?;
+        let params: Vec<libsql::Value> = messages.iter().map(|m| m.id.into()).collect();
+        let mut rows = stmt
+            .query(libsql::params::Params::Positional(params))
+            .await?;
+        let mut bodies: HashMap<i64, String> = HashMap::new();
+        while let Some(row) = rows.next().await? {
+            bodies.insert(row.get(0)?, row.get(1)?);
+        }
+        for message in messages.iter_mut() {
+            if let Some(body) = bodies.remove(&message.id) {
+                message.body_md = body;
+            }
+        }
+        Ok(())
     }
 
     pub async fn get(_ctx: &Ctx, mm: &ModelManager, message_id: i64) -> Result<Message> {
-        let db = mm.db();
+        let db = mm.db_read();
         let stmt = db.prepare(
             r#"
             SELECT
                 m.id, m.project_id, m.sender_id, ag.name as sender_name, m.thread_id, m.subject, m.body_md,
-                m.importance, m.ack_required, m.created_ts, m.attachments
-            FROM messages AS m
+                m.importance, m.ack_required, m.created_ts, m.attachments, m.thread_seq,
+                m.forwarded_from_id
+            FROM visible_messages AS m
             JOIN agents AS ag ON m.sender_id = ag.id
             WHERE m.id = ?
             "#
@@ -686,29 +1857,42 @@ impl MessageBmc {
                 thread_id,
                 subject,
                 body_md,
+                snippet: String::new(),
                 importance,
                 ack_required,
                 created_ts,
                 attachments,
+                project_slug: None,
+                thread_seq: row.get(11)?,
+                forwarded_from_id: row.get(12)?,
+                returned_from_snooze: false,
+                needs_ack: false,
             })
+        } else if crate::model::retention::RetentionBmc::is_pruned(mm, message_id).await? {
+            Err(crate::Error::MessagePruned(message_id))
         } else {
             Err(crate::Error::MessageNotFound(message_id))
         }
     }
 
     /// Get recipient names for a message
+    ///
+    /// Recipients in another project are returned as `slug::agent-name`.
     pub async fn get_recipients(
         _ctx: &Ctx,
         mm: &ModelManager,
         message_id: i64,
     ) -> Result<Vec<String>> {
-        let db = mm.db();
+        let db = mm.db_read();
         let stmt = db
             .prepare(
                 r#"
-            SELECT a.name
+            SELECT COALESCE(p.slug || '::' || a.name, a.name)
             FROM message_recipients mr
             JOIN agents a ON mr.agent_id = a.id
+            LEFT JOIN cross_project_recipients cpr
+                ON cpr.message_id = mr.message_id AND cpr.agent_id = mr.agent_id
+            LEFT JOIN projects p ON p.id = cpr.project_id
             WHERE mr.message_id = ?
             ORDER BY mr.recipient_type, a.name
             "#,
@@ -726,22 +1910,32 @@ impl MessageBmc {
         Ok(recipients)
     }
 
+    /// List a thread's messages, oldest first.
+    ///
+    /// Includes messages of the thread sent from other projects to agents of
+    /// this one; those carry their project's slug.
     pub async fn list_by_thread(
         _ctx: &Ctx,
         mm: &ModelManager,
         project_id: i64,
         thread_id: &str,
     ) -> Result<Vec<Message>> {
-        let db = mm.db();
+        let db = mm.db_read();
         let stmt = db.prepare(
             r#"
             SELECT
                 m.id, m.project_id, m.sender_id, ag.name as sender_name, m.thread_id, m.subject, m.body_md,
-                m.importance, m.ack_required, m.created_ts, m.attachments
-            FROM messages AS m
+                m.importance, m.ack_required, m.created_ts, m.attachments,
+                CASE WHEN m.project_id != ?1 THEN sp.slug END, m.thread_seq, m.forwarded_from_id
+            FROM visible_messages AS m
             JOIN agents AS ag ON m.sender_id = ag.id
-            WHERE m.project_id = ? AND m.thread_id = ?
-            ORDER BY m.created_ts ASC, m.id ASC
+            JOIN projects AS sp ON sp.id = m.project_id
+            WHERE m.thread_id = ?2
+              AND (m.project_id = ?1 OR EXISTS (
+                  SELECT 1 FROM cross_project_recipients AS cpr
+                  WHERE cpr.message_id = m.id AND cpr.project_id = ?1
+              ))
+            ORDER BY m.created_ts ASC, m.thread_seq ASC, m.id ASC
             "#
         ).await?;
 
@@ -764,6 +1958,8 @@ impl MessageBmc {
 
             let attachments_str: String = row.get(10)?;
             let attachments: Vec<Value> = serde_json::from_str(&attachments_str)?;
+            let project_slug: Option<String> = row.get(11)?;
+            let thread_seq: Option<i64> = row.get(12)?;
 
             messages.push(Message {
                 id,
@@ -773,94 +1969,164 @@ impl MessageBmc {
                 thread_id,
                 subject,
                 body_md,
+                snippet: String::new(),
                 importance,
                 ack_required,
                 created_ts,
                 attachments,
+                project_slug,
+                thread_seq,
+                forwarded_from_id: row.get(13)?,
+                returned_from_snooze: false,
+                needs_ack: false,
             });
         }
         Ok(messages)
     }
 
     /// Full-text search messages using FTS5
+    ///
+    /// `query` may use the field syntax of [`SearchQuery`]; returns the first
+    /// `limit` matches, newest first. See [`Self::search_page`] for paging.
     pub async fn search(
-        _ctx: &Ctx,
+        ctx: &Ctx,
         mm: &ModelManager,
         project_id: i64,
         query: &str,
         limit: i64,
     ) -> Result<Vec<Message>> {
-        let db = mm.db();
+        let query = SearchQuery::parse(query);
+        Ok(Self::search_page(ctx, mm, project_id, &query, limit, None)
+            .await?
+            .items)
+    }
+
+    /// Search messages matching every part of `query`, newest first.
+    ///
+    /// Free-text terms go to the FTS5 body index; the other fields become SQL
+    /// conditions. Matches carry their [`snippet`] and an empty `body_md`. Pass the page's `next_cursor` as `cursor` to fetch the next
+    /// (older) page. A malformed FTS expression yields an empty page rather
+    /// than an error.
+    pub async fn search_page(
+        _ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: i64,
+        query: &SearchQuery,
+        limit: i64,
+        cursor: Option<i64>,
+    ) -> Result<SearchPage> {
+        let db = mm.db_read();
 
-        // FTS5 Unsearchable patterns (return empty to avoid errors or heavy meaningless queries)
+        // FTS5 Unsearchable patterns (skip them to avoid errors or heavy meaningless queries)
         // Python equivalent: _FTS5_UNSEARCHABLE_PATTERNS
-        let trimmed = query.trim();
-        if matches!(
+        let text = query.text();
+        let trimmed = text.trim();
+        let fts_query = if matches!(
             trimmed,
             "" | "*" | "**" | "***" | "." | ".." | "..." | "?" | "??" | "???"
         ) {
-            info!("Search query '{}' is in blocklist, returning empty", query);
-            return Ok(Vec::new());
+            if !trimmed.is_empty() {
+                info!("Search terms '{}' are in blocklist, ignoring them", text);
+            }
+            None
+        } else {
+            Some(fts_match_expression(&text))
+        };
+        if fts_query.is_none() && !query.has_filters() {
+            return Ok(SearchPage::default());
         }
 
-        // Logic for handling raw vs literal queries:
-        // 1. If query contains explicit FTS operators (AND, OR, NOT) or wildcards (*), pass raw
-        // 2. If query has balanced quotes (phrase search), pass raw
-        // 3. Otherwise, quote each word to prevent hyphens being treated as NOT operator
-        //    e.g., "full-text search" -> FTS5 interprets as "full AND NOT text AND search"
-        //    Fix: Quote words containing hyphens: "\"full-text\" search"
-        let quote_count = query.chars().filter(|c| *c == '"').count();
-        let has_fts_operators = query.contains(" AND ")
-            || query.contains(" OR ")
-            || query.contains(" NOT ")
-            || query.contains('*');
-
-        let fts_query = if quote_count % 2 != 0 {
-            // Unbalanced quotes: Treat as literal string search
-            // This satisfies PORT-5.2 (Error Handling) for obviously malformed inputs
-            format!("\"{}\"", query.replace('"', "\"\""))
-        } else if has_fts_operators || query.starts_with('"') {
-            // Has explicit FTS operators or is a phrase search - pass raw
-            query.to_string()
-        } else {
-            // Simple search: quote words containing hyphens to prevent FTS5 misinterpretation
-            // "full-text search" -> "\"full-text\" search"
-            query
-                .split_whitespace()
-                .map(|word| {
-                    if word.contains('-') && !word.starts_with('"') {
-                        format!("\"{}\"", word)
-                    } else {
-                        word.to_string()
-                    }
-                })
-                .collect::<Vec<_>>()
-                .join(" ")
+        let mut conditions = String::from("m.project_id = ? AND m.recalled_ts IS NULL");
+        let mut params: Vec<libsql::Value> = vec![project_id.into()];
+        if let Some(fts_query) = &fts_query {
+            conditions.push_str(
+                " AND m.id IN (SELECT rowid FROM messages_fts WHERE messages_fts MATCH ?)",
+            );
+            params.push(fts_query.clone().into());
+        }
+        if let Some(from) = &query.from {
+            conditions.push_str(" AND lower(ag.name) = lower(?)");
+            params.push(from.clone().into());
+        }
+        for term in &query.subject_terms {
+            conditions.push_str(" AND instr(lower(m.subject), lower(?)) > 0");
+            params.push(term.clone().into());
+        }
+        if let Some(after) = query.after {
+            conditions.push_str(" AND m.created_ts >= ?");
+            params.push(after.format("%Y-%m-%d %H:%M:%S").to_string().into());
+        }
+        if let Some(before) = query.before {
+            conditions.push_str(" AND m.created_ts < ?");
+            params.push(before.format("%Y-%m-%d %H:%M:%S").to_string().into());
+        }
+        if let Some(importance) = &query.importance {
+            conditions.push_str(" AND m.importance = ?");
+            params.push(importance.clone().into());
+        }
+        if let Some(thread) = &query.thread {
+            conditions.push_str(" AND m.thread_id = ?");
+            params.push(thread.clone().into());
+        }
+
+        let count_sql = format!(
+            "SELECT COUNT(*) FROM visible_messages AS m JOIN agents AS ag ON m.sender_id = ag.id WHERE {}",
+            conditions
+        );
+        let total_estimate = match db.prepare(&count_sql).await {
+            Ok(stmt) => match stmt
+                .query(libsql::params::Params::Positional(params.clone()))
+                .await
+            {
+                Ok(mut rows) => match rows.next().await {
+                    Ok(Some(row)) => row.get::<i64>(0)?,
+                    _ => 0,
+                },
+                Err(e) => {
+                    info!(
+                        "FTS Search failed for query '{}' (likely syntax): {}. Returning empty.",
+                        text, e
+                    );
+                    return Ok(SearchPage::default());
+                }
+            },
+            Err(e) => return Err(e.into()),
         };
 
-        let stmt = db.prepare(
-            r#"
+        // Keyset pagination on (created_ts, id), as for the outbox
+        if let Some(cursor) = cursor {
+            conditions.push_str(
+                " AND (m.created_ts, m.id) < (SELECT created_ts, id FROM messages WHERE id = ?)",
+            );
+            params.push(cursor.into());
+        }
+        params.push(limit.into());
+
+        let stmt = db
+            .prepare(&format!(
+                r#"
             SELECT
-                m.id, m.project_id, m.sender_id, ag.name as sender_name, m.thread_id, m.subject, m.body_md,
-                m.importance, m.ack_required, m.created_ts, m.attachments
-            FROM messages AS m
+                m.id, m.project_id, m.sender_id, ag.name as sender_name, m.thread_id, m.subject, m.snippet,
+                m.importance, m.ack_required, m.created_ts, m.attachments, m.thread_seq,
+                m.forwarded_from_id
+            FROM visible_messages AS m
             JOIN agents AS ag ON m.sender_id = ag.id
-            WHERE m.project_id = ? AND m.id IN (
-                SELECT rowid FROM messages_fts WHERE messages_fts MATCH ?
-            )
-            ORDER BY m.created_ts DESC
+            WHERE {}
+            ORDER BY m.created_ts DESC, m.id DESC
             LIMIT ?
-            "#
-        ).await?;
+            "#,
+                conditions
+            ))
+            .await?;
 
-        let mut rows = match stmt.query((project_id, fts_query.as_str(), limit)).await {
+        let mut rows = match stmt.query(libsql::params::Params::Positional(params)).await {
             Ok(rows) => rows,
             Err(e) => {
                 info!(
                     "FTS Search failed for query '{}' (likely syntax): {}. Returning empty.",
-                    query, e
+                    text, e
                 );
-                return Ok(Vec::new());
+                return Ok(SearchPage::default());
             }
         };
 
@@ -874,7 +2140,7 @@ impl MessageBmc {
                 Err(e) => {
                     info!(
                         "FTS Row iteration failed for query '{}': {}. Returning partial/empty.",
-                        query, e
+                        text, e
                     );
                     // If this is the first row and it failed, likely syntax error.
                     // We stop iteration and return what we have (or empty).
@@ -888,7 +2154,7 @@ impl MessageBmc {
             let sender_name: String = row.get(3)?;
             let thread_id: Option<String> = row.get(4)?;
             let subject: String = row.get(5)?;
-            let body_md: String = row.get(6)?;
+            let snippet: String = row.get(6)?;
             let importance: String = row.get(7)?;
             let ack_required: bool = row.get(8)?;
             let created_ts_str: String = row.get(9)?;
@@ -905,17 +2171,34 @@ impl MessageBmc {
                 sender_name,
                 thread_id,
                 subject,
-                body_md,
+                body_md: String::new(),
+                snippet,
                 importance,
                 ack_required,
                 created_ts,
                 attachments,
+                project_slug: None,
+                thread_seq: row.get(11)?,
+                forwarded_from_id: row.get(12)?,
+                returned_from_snooze: false,
+                needs_ack: false,
             });
         }
-        Ok(messages)
+
+        let next_cursor = if messages.len() as i64 == limit {
+            messages.last().map(|m| m.id)
+        } else {
+            None
+        };
+        Ok(SearchPage {
+            items: messages,
+            next_cursor,
+            total_estimate,
+        })
     }
 
-    /// Mark a message as read by a recipient
+    /// Mark a message as read by a recipient, clearing any snooze the
+    /// recipient set on it
     pub async fn mark_read(
         _ctx: &Ctx,
         mm: &ModelManager,
@@ -931,11 +2214,218 @@ impl MessageBmc {
             UPDATE message_recipients SET read_ts = ? WHERE message_id = ? AND agent_id = ? AND read_ts IS NULL
             "#
         ).await?;
-        stmt.execute((now_str, message_id, agent_id)).await?;
+        let changed = stmt.execute((now_str, message_id, agent_id)).await?;
+
+        let stmt = db
+            .prepare("DELETE FROM message_snoozes WHERE message_id = ? AND agent_id = ?")
+            .await?;
+        stmt.execute((message_id, agent_id)).await?;
+
+        if changed > 0 {
+            let stmt = db
+                .prepare(
+                    r#"
+                SELECT p.slug, a.name
+                FROM messages m
+                JOIN projects p ON p.id = m.project_id
+                JOIN agents a ON a.id = ?
+                WHERE m.id = ?
+                "#,
+                )
+                .await?;
+            let mut rows = stmt.query((agent_id, message_id)).await?;
+            if let Some(row) = rows.next().await? {
+                let project_slug: String = row.get(0)?;
+                let agent_name: String = row.get(1)?;
+                mm.events.publish(
+                    MailEventKind::MessageRead,
+                    &project_slug,
+                    serde_json::json!({
+                        "message_id": message_id,
+                        "agent_id": agent_id,
+                        "agent_name": agent_name,
+                    }),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that `ctx` may access the project of every message in
+    /// `message_ids`. Unknown ids are ignored.
+    ///
+    /// # Errors
+    /// [`crate::Error::Forbidden`] naming the first project outside `ctx`.
+    pub(crate) async fn ensure_messages_access(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        message_ids: &[i64],
+    ) -> Result<()> {
+        if ctx.is_unrestricted() || message_ids.is_empty() {
+            return Ok(());
+        }
+        let db = mm.db_read();
+        let placeholders = vec!["?"; message_ids.len()].join(", ");
+        let stmt = db
+            .prepare(&format!(
+                r#"
+            SELECT DISTINCT p.slug
+            FROM messages m
+            JOIN projects p ON p.id = m.project_id
+            WHERE m.id IN ({})
+            "#,
+                placeholders
+            ))
+            .await?;
+        let params: Vec<libsql::Value> = message_ids.iter().map(|id| (*id).into()).collect();
+        let mut rows = stmt
+            .query(libsql::params::Params::Positional(params))
+            .await?;
+        while let Some(row) = rows.next().await? {
+            let slug: String = row.get(0)?;
+            if !ctx.can_access_project(&slug) {
+                return Err(crate::Error::Forbidden(slug));
+            }
+        }
         Ok(())
     }
 
-    /// Acknowledge a message by a recipient
+    /// Mark several messages as read in one statement.
+    ///
+    /// With `agent_id`, only that recipient's rows are updated; without it,
+    /// every recipient of each message is marked (overseer triage from the
+    /// unified inbox). Rows that are already read are left untouched, and a
+    /// `MessageRead` event is published for each newly read row. Snoozes on
+    /// the newly read rows are cleared.
+    ///
+    /// Only an unrestricted context not bound to an agent may omit
+    /// `agent_id`, and an agent-bound context may only mark its own rows.
+    ///
+    /// # Returns
+    /// Ids of the messages that had at least one row newly marked read.
+    ///
+    /// # Errors
+    /// - [`crate::Error::InvalidInput`] for too many ids, or no `agent_id`
+    ///   from a restricted context
+    /// - [`crate::Error::AgentIdentityMismatch`] when `agent_id` is not the
+    ///   context's agent
+    /// - [`crate::Error::Forbidden`] when a message is outside `ctx`'s projects
+    pub async fn mark_read_batch(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        message_ids: &[i64],
+        agent_id: Option<i64>,
+    ) -> Result<Vec<i64>> {
+        if message_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        if message_ids.len() > MAX_BATCH_SIZE {
+            return Err(crate::Error::InvalidInput(format!(
+                "Too many message ids ({}), maximum is {}",
+                message_ids.len(),
+                MAX_BATCH_SIZE
+            )));
+        }
+        match agent_id {
+            Some(agent_id) if ctx.authenticated_agent().is_some() => {
+                let agent =
+                    super::agent::AgentBmc::get(ctx, mm, crate::types::AgentId::new(agent_id))
+                        .await?;
+                ctx.ensure_acting_as(agent.id, &agent.name)?;
+            }
+            Some(_) => {}
+            None if !ctx.is_unrestricted() || ctx.authenticated_agent().is_some() => {
+                return Err(crate::Error::InvalidInput(
+                    "An agent is required to mark messages read".to_string(),
+                ));
+            }
+            None => {}
+        }
+        Self::ensure_messages_access(ctx, mm, message_ids).await?;
+
+        let db = mm.db();
+        let now_str = chrono::Utc::now()
+            .naive_utc()
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        let placeholders = vec!["?"; message_ids.len()].join(", ");
+        let mut filter = format!("mr.read_ts IS NULL AND mr.message_id IN ({})", placeholders);
+        let mut params: Vec<libsql::Value> = message_ids.iter().map(|id| (*id).into()).collect();
+        if let Some(agent_id) = agent_id {
+            filter.push_str(" AND mr.agent_id = ?");
+            params.push(agent_id.into());
+        }
+
+        // Collect the unread rows first so events carry agent and project names
+        let stmt = db
+            .prepare(&format!(
+                r#"
+            SELECT mr.message_id, mr.agent_id, a.name, p.slug
+            FROM message_recipients mr
+            JOIN agents a ON a.id = mr.agent_id
+            JOIN messages m ON m.id = mr.message_id
+            JOIN projects p ON p.id = m.project_id
+            WHERE {}
+            ORDER BY mr.message_id, mr.agent_id
+            "#,
+                filter
+            ))
+            .await?;
+        let mut rows = stmt
+            .query(libsql::params::Params::Positional(params.clone()))
+            .await?;
+        let mut unread = Vec::new();
+        while let Some(row) = rows.next().await? {
+            unread.push((
+                row.get::<i64>(0)?,
+                row.get::<i64>(1)?,
+                row.get::<String>(2)?,
+                row.get::<String>(3)?,
+            ));
+        }
+        if unread.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut update_params: Vec<libsql::Value> = vec![now_str.into()];
+        update_params.extend(params);
+        let stmt = db
+            .prepare(&format!(
+                "UPDATE message_recipients AS mr SET read_ts = ? WHERE {}",
+                filter
+            ))
+            .await?;
+        stmt.execute(libsql::params::Params::Positional(update_params))
+            .await?;
+
+        let stmt = db
+            .prepare("DELETE FROM message_snoozes WHERE message_id = ? AND agent_id = ?")
+            .await?;
+        for (message_id, agent_id, _, _) in &unread {
+            stmt.execute((*message_id, *agent_id)).await?;
+            stmt.reset();
+        }
+
+        let mut marked = Vec::new();
+        for (message_id, agent_id, agent_name, project_slug) in unread {
+            mm.events.publish(
+                MailEventKind::MessageRead,
+                &project_slug,
+                serde_json::json!({
+                    "message_id": message_id,
+                    "agent_id": agent_id,
+                    "agent_name": agent_name,
+                }),
+            );
+            if marked.last() != Some(&message_id) {
+                marked.push(message_id);
+            }
+        }
+        Ok(marked)
+    }
+
+    /// Acknowledge a message by a recipient, which also marks it read, and
+    /// publish `message.acked`
     pub async fn acknowledge(
         _ctx: &Ctx,
         mm: &ModelManager,
@@ -956,59 +2446,852 @@ impl MessageBmc {
             "#,
             )
             .await?;
-        stmt.execute((now_str.as_str(), now_str.as_str(), message_id, agent_id))
+        let acked = stmt
+            .execute((now_str.as_str(), now_str.as_str(), message_id, agent_id))
+            .await?;
+
+        let stmt = db
+            .prepare("DELETE FROM message_snoozes WHERE message_id = ? AND agent_id = ?")
             .await?;
+        stmt.execute((message_id, agent_id)).await?;
+
+        if acked > 0 {
+            let stmt = db
+                .prepare(
+                    r#"
+                SELECT p.slug, a.name, m.subject, m.importance, m.thread_id
+                FROM messages m
+                JOIN projects p ON p.id = m.project_id
+                JOIN agents a ON a.id = ?
+                WHERE m.id = ?
+                "#,
+                )
+                .await?;
+            let mut rows = stmt.query((agent_id, message_id)).await?;
+            if let Some(row) = rows.next().await? {
+                let project_slug: String = row.get(0)?;
+                mm.events.publish(
+                    MailEventKind::MessageAcked,
+                    &project_slug,
+                    serde_json::json!({
+                        "message_id": message_id,
+                        "agent_id": agent_id,
+                        "agent_name": row.get::<String>(1)?,
+                        "subject": row.get::<String>(2)?,
+                        "importance": row.get::<String>(3)?,
+                        "thread_id": row.get::<Option<String>>(4)?,
+                    }),
+                );
+            }
+        }
         Ok(())
     }
 
-    /// List distinct threads for a project
-    pub async fn list_threads(
+    /// Snooze a message in one recipient's inbox until `until`.
+    ///
+    /// The message leaves the agent's inbox and comes back flagged
+    /// `returned_from_snooze` once `until` passes. Snoozing again moves the
+    /// time. Other recipients and the thread view are unaffected, and
+    /// reading the message clears the snooze.
+    ///
+    /// # Errors
+    /// [`crate::Error::InvalidInput`] when `until` is not in the future;
+    /// [`crate::Error::MessageNotFound`] when the agent is not a recipient.
+    pub async fn snooze(
         _ctx: &Ctx,
         mm: &ModelManager,
+        message_id: i64,
+        agent_id: i64,
+        until: NaiveDateTime,
+    ) -> Result<()> {
+        if until <= chrono::Utc::now().naive_utc() {
+            return Err(crate::Error::InvalidInput(format!(
+                "Snooze time {} is not in the future",
+                until.format("%Y-%m-%dT%H:%M:%SZ")
+            )));
+        }
+
+        let db = mm.db();
+        let stmt = db
+            .prepare(
+                r#"
+            INSERT INTO message_snoozes (message_id, agent_id, snoozed_until)
+            SELECT message_id, agent_id, ? FROM message_recipients
+            WHERE message_id = ? AND agent_id = ?
+            ON CONFLICT (message_id, agent_id) DO UPDATE
+            SET snoozed_until = excluded.snoozed_until, snoozed_ts = CURRENT_TIMESTAMP
+            "#,
+            )
+            .await?;
+        let changed = stmt
+            .execute((
+                until.format("%Y-%m-%d %H:%M:%S").to_string(),
+                message_id,
+                agent_id,
+            ))
+            .await?;
+        if changed == 0 {
+            return Err(crate::Error::MessageNotFound(message_id));
+        }
+        Ok(())
+    }
+
+    /// Put a snoozed message back in the agent's inbox now.
+    ///
+    /// # Returns
+    /// Whether the message was snoozed.
+    pub async fn unsnooze(
+        _ctx: &Ctx,
+        mm: &ModelManager,
+        message_id: i64,
+        agent_id: i64,
+    ) -> Result<bool> {
+        let db = mm.db();
+        let stmt = db
+            .prepare("DELETE FROM message_snoozes WHERE message_id = ? AND agent_id = ?")
+            .await?;
+        Ok(stmt.execute((message_id, agent_id)).await? > 0)
+    }
+
+    /// List the messages an agent has snoozed that are still hidden,
+    /// soonest to return first.
+    pub async fn list_snoozed(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: i64,
+        agent_id: i64,
+    ) -> Result<Vec<SnoozedMessage>> {
+        super::project::ProjectBmc::ensure_access(ctx, mm, ProjectId::new(project_id)).await?;
+
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT
+                m.id, m.project_id, m.sender_id, ag.name as sender_name, m.thread_id, m.subject, m.body_md,
+                m.importance, m.ack_required, m.created_ts, m.attachments,
+                CASE WHEN m.project_id != ?2 THEN sp.slug END, m.thread_seq, m.forwarded_from_id,
+                sn.snoozed_until
+            FROM message_snoozes AS sn
+            JOIN visible_messages AS m ON m.id = sn.message_id
+            JOIN agents AS ag ON m.sender_id = ag.id
+            JOIN projects AS sp ON sp.id = m.project_id
+            WHERE sn.agent_id = ?1 AND sn.snoozed_until > CURRENT_TIMESTAMP
+            ORDER BY sn.snoozed_until ASC, m.id ASC
+            "#,
+            )
+            .await?;
+        let mut rows = stmt.query((agent_id, project_id)).await?;
+        let mut snoozed = Vec::new();
+
+        while let Some(row) = rows.next().await? {
+            let attachments_str: String = row.get(10)?;
+            snoozed.push(SnoozedMessage {
+                message: Message {
+                    id: row.get(0)?,
+                    project_id: row.get(1)?,
+                    sender_id: row.get(2)?,
+                    sender_name: row.get(3)?,
+                    thread_id: row.get(4)?,
+                    subject: row.get(5)?,
+                    body_md: row.get(6)?,
+                    snippet: String::new(),
+                    importance: row.get(7)?,
+                    ack_required: row.get(8)?,
+                    created_ts: parse_ts(&row.get::<String>(9)?),
+                    attachments: serde_json::from_str(&attachments_str)?,
+                    project_slug: row.get(11)?,
+                    thread_seq: row.get(12)?,
+                    forwarded_from_id: row.get(13)?,
+                    returned_from_snooze: false,
+                    needs_ack: false,
+                },
+                snoozed_until: parse_ts(&row.get::<String>(14)?),
+            });
+        }
+        Ok(snoozed)
+    }
+
+    /// Recall a sent message.
+    ///
+    /// Only the original sender may recall, and only within
+    /// `messages.recall_window_seconds` of sending. The stored row is kept;
+    /// reads afterwards show a `[Recalled]` subject and the reason in place
+    /// of the body. A tombstone file is committed to the Git archive so the
+    /// history is appended to, never rewritten.
+    pub async fn recall(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        message_id: i64,
+        sender_id: i64,
+        reason: &str,
+    ) -> Result<MessageRecall> {
+        let reason = reason.trim();
+        if reason.is_empty() {
+            return Err(crate::Error::InvalidInput(
+                "Recall reason cannot be empty".into(),
+            ));
+        }
+
+        let db = mm.db();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT m.project_id, m.sender_id, m.subject, m.created_ts, p.slug, ag.name,
+                   (SELECT 1 FROM message_recalls r WHERE r.message_id = m.id)
+            FROM messages AS m
+            JOIN projects AS p ON m.project_id = p.id
+            JOIN agents AS ag ON m.sender_id = ag.id
+            WHERE m.id = ?
+            "#,
+            )
+            .await?;
+        let mut rows = stmt.query([message_id]).await?;
+        let row = rows
+            .next()
+            .await?
+            .ok_or(crate::Error::MessageNotFound(message_id))?;
+        let project_id: i64 = row.get(0)?;
+        let message_sender_id: i64 = row.get(1)?;
+        let subject: String = row.get(2)?;
+        let created_ts = parse_ts(&row.get::<String>(3)?);
+        let project_slug: String = row.get(4)?;
+        let sender_name: String = row.get(5)?;
+        let already_recalled: Option<i64> = row.get(6)?;
+
+        super::project::ProjectBmc::ensure_access(ctx, mm, ProjectId::new(project_id)).await?;
+
+        if message_sender_id != sender_id {
+            return Err(crate::Error::NotMessageSender(message_id));
+        }
+        if already_recalled.is_some() {
+            return Err(crate::Error::InvalidInput(format!(
+                "Message {} is already recalled",
+                message_id
+            )));
+        }
+
+        let window_seconds = mm.app_config.messages.recall_window_seconds;
+        let now = chrono::Utc::now().naive_utc();
+        let window = chrono::Duration::seconds(i64::try_from(window_seconds).unwrap_or(i64::MAX));
+        if now.signed_duration_since(created_ts) > window {
+            return Err(crate::Error::RecallWindowExpired {
+                message_id,
+                window_seconds,
+            });
+        }
+
+        let recalled_ts_str = now.format("%Y-%m-%d %H:%M:%S").to_string();
+        {
+            let (_tx_guard, tx) = mm.begin_tx().await?;
+            let stmt = tx
+                .prepare(
+                    "INSERT INTO message_recalls (message_id, recalled_ts, recall_reason) VALUES (?, ?, ?)",
+                )
+                .await?;
+            stmt.execute((message_id, recalled_ts_str.as_str(), reason))
+                .await?;
+            AuditBmc::record_in(
+                &tx,
+                ctx,
+                ProjectId::new(project_id),
+                AuditAction::MessageRecall,
+                message_id,
+                serde_json::json!({
+                    "before": { "sender_name": sender_name, "subject": subject },
+                    "after": { "recalled_ts": recalled_ts_str, "reason": reason },
+                }),
+            )
+            .await?;
+            tx.commit().await?;
+        }
+
+        let recall = MessageRecall {
+            message_id,
+            recalled_ts: parse_ts(&recalled_ts_str),
+            recall_reason: reason.to_string(),
+        };
+
+        mm.events.publish(
+            MailEventKind::MessageRecalled,
+            &project_slug,
+            serde_json::json!({
+                "message_id": message_id,
+                "sender_name": sender_name,
+                "reason": reason,
+            }),
+        );
+
+        // The DB is the source of truth; a failed tombstone commit is logged only
+        let author = ctx
+            .actor()
+            .cloned()
+            .unwrap_or_else(|| Actor::agent(&sender_name, &project_slug));
+        if let Err(e) =
+            commit_recall_to_git(mm, &project_slug, &sender_name, &author, &subject, &recall).await
+        {
+            warn!(
+                "Recall tombstone commit failed for message {}: {}",
+                message_id, e
+            );
+        }
+
+        Ok(recall)
+    }
+
+    /// Returns the recall record for a message, if it was recalled.
+    pub async fn get_recall(
+        _ctx: &Ctx,
+        mm: &ModelManager,
+        message_id: i64,
+    ) -> Result<Option<MessageRecall>> {
+        let db = mm.db_read();
+        let stmt = db
+            .prepare("SELECT recalled_ts, recall_reason FROM message_recalls WHERE message_id = ?")
+            .await?;
+        let mut rows = stmt.query([message_id]).await?;
+        match rows.next().await? {
+            Some(row) => Ok(Some(MessageRecall {
+                message_id,
+                recalled_ts: parse_ts(&row.get::<String>(0)?),
+                recall_reason: row.get(1)?,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    /// Forward a message to agents that did not receive it.
+    ///
+    /// Sends a new message from the forwarder, in a new thread, whose body is
+    /// the optional note followed by the original quoted as Markdown under
+    /// its sender, date, To/Cc recipients and subject. BCC recipients are
+    /// never listed. The new message's `forwarded_from_id` points at the
+    /// original, so forwarding a forward builds a chain back to the first
+    /// message.
+    ///
+    /// The forwarder must be the original's sender or one of its recipients;
+    /// to anyone else the message is not found. Recalled messages can't be
+    /// forwarded ([`crate::Error::MessageRecalled`]).
+    ///
+    /// # Returns
+    /// The new message's ID
+    pub async fn forward(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        message_id: i64,
+        forwarder_agent_id: i64,
+        recipient_ids: Vec<i64>,
+        note: Option<&str>,
+    ) -> Result<i64> {
+        if recipient_ids.is_empty() {
+            return Err(crate::Error::InvalidInput(
+                "Forward needs at least one recipient".into(),
+            ));
+        }
+
+        let original = Self::get(ctx, mm, message_id).await?;
+        let recipients = Self::list_recipients(mm, std::slice::from_ref(&original))
+            .await?
+            .remove(&message_id)
+            .unwrap_or_default();
+        let forwarder =
+            super::agent::AgentBmc::get(ctx, mm, crate::types::AgentId::new(forwarder_agent_id))
+                .await?;
+        let can_see = original.sender_id == forwarder_agent_id || {
+            let db = mm.db_read();
+            let stmt = db
+                .prepare("SELECT 1 FROM message_recipients WHERE message_id = ? AND agent_id = ?")
+                .await?;
+            let mut rows = stmt.query((message_id, forwarder_agent_id)).await?;
+            rows.next().await?.is_some()
+        };
+        if !can_see {
+            return Err(crate::Error::MessageNotFound(message_id));
+        }
+        if Self::get_recall(ctx, mm, message_id).await?.is_some() {
+            return Err(crate::Error::MessageRecalled(message_id));
+        }
+
+        let subject = if original.subject.starts_with("Fwd: ") {
+            original.subject.clone()
+        } else {
+            format!("Fwd: {}", original.subject)
+        };
+
+        let msg_c = MessageForCreate {
+            project_id: forwarder.project_id.get(),
+            sender_id: forwarder_agent_id,
+            recipient_ids,
+            cc_ids: None,
+            bcc_ids: None,
+            subject,
+            body_md: forward_body(&original, &recipients, note),
+            thread_id: None,
+            importance: None,
+            ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
+        };
+        let id = Self::create(ctx, mm, msg_c).await?;
+
+        let db = mm.db();
+        let stmt = db
+            .prepare("INSERT INTO message_forwards (message_id, forwarded_from_id) VALUES (?, ?)")
+            .await?;
+        stmt.execute((id, message_id)).await?;
+
+        Ok(id)
+    }
+
+    /// List an agent's messages still waiting for their `deliver_at`, soonest first.
+    pub async fn list_scheduled(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: i64,
+        sender_id: i64,
+    ) -> Result<Vec<ScheduledMessage>> {
+        super::project::ProjectBmc::ensure_access(ctx, mm, ProjectId::new(project_id)).await?;
+
+        let db = mm.db_read();
+        let stmt = db.prepare(
+            r#"
+            SELECT
+                m.id, m.project_id, m.sender_id, ag.name as sender_name, m.thread_id, m.subject, b.body_md,
+                m.importance, m.ack_required, m.created_ts, m.attachments, s.deliver_at, ts.seq
+            FROM message_schedules AS s
+            JOIN messages AS m ON m.id = s.message_id
+            JOIN message_bodies AS b ON b.message_id = m.id
+            LEFT JOIN message_thread_seqs AS ts ON ts.message_id = m.id
+            JOIN agents AS ag ON m.sender_id = ag.id
+            WHERE m.project_id = ? AND m.sender_id = ? AND s.status = 'scheduled'
+            ORDER BY s.deliver_at ASC, m.id ASC
+            "#
+        ).await?;
+
+        let mut rows = stmt.query((project_id, sender_id)).await?;
+        let mut messages = Vec::new();
+        let mut deliver_ats = HashMap::new();
+
+        while let Some(row) = rows.next().await? {
+            let id: i64 = row.get(0)?;
+            let attachments_str: String = row.get(10)?;
+            deliver_ats.insert(id, parse_ts(&row.get::<String>(11)?));
+            messages.push(Message {
+                id,
+                project_id: row.get(1)?,
+                sender_id: row.get(2)?,
+                sender_name: row.get(3)?,
+                thread_id: row.get(4)?,
+                subject: row.get(5)?,
+                body_md: row.get(6)?,
+                snippet: String::new(),
+                importance: row.get(7)?,
+                ack_required: row.get(8)?,
+                created_ts: parse_ts(&row.get::<String>(9)?),
+                attachments: serde_json::from_str(&attachments_str)?,
+                project_slug: None,
+                thread_seq: row.get(12)?,
+                forwarded_from_id: None,
+                returned_from_snooze: false,
+                needs_ack: false,
+            });
+        }
+
+        let mut recipients = Self::list_recipients(mm, &messages).await?;
+        Ok(messages
+            .into_iter()
+            .map(|message| ScheduledMessage {
+                deliver_at: deliver_ats.remove(&message.id).unwrap_or_default(),
+                recipients: recipients.remove(&message.id).unwrap_or_default(),
+                message,
+            })
+            .collect())
+    }
+
+    /// Cancel a scheduled message before it is delivered.
+    ///
+    /// Only the sender may cancel. The row is kept (hidden) for the audit
+    /// trail; nothing reaches the Git archive since it was never delivered.
+    pub async fn cancel_scheduled(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        message_id: i64,
+        sender_id: i64,
+    ) -> Result<()> {
+        let db = mm.db();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT m.project_id, m.sender_id, s.status
+            FROM message_schedules AS s
+            JOIN messages AS m ON m.id = s.message_id
+            WHERE s.message_id = ?
+            "#,
+            )
+            .await?;
+        let mut rows = stmt.query([message_id]).await?;
+        let row = rows
+            .next()
+            .await?
+            .ok_or(crate::Error::MessageNotFound(message_id))?;
+        let project_id: i64 = row.get(0)?;
+        let message_sender_id: i64 = row.get(1)?;
+        let status: String = row.get(2)?;
+
+        super::project::ProjectBmc::ensure_access(ctx, mm, ProjectId::new(project_id)).await?;
+
+        if message_sender_id != sender_id {
+            return Err(crate::Error::NotMessageSender(message_id));
+        }
+
+        // Guard on status so a concurrent delivery wins cleanly
+        let stmt = db
+            .prepare(
+                "UPDATE message_schedules SET status = 'cancelled' WHERE message_id = ? AND status = 'scheduled'",
+            )
+            .await?;
+        if stmt.execute([message_id]).await? == 0 {
+            let status = if status == "scheduled" {
+                "delivered"
+            } else {
+                status.as_str()
+            };
+            return Err(crate::Error::InvalidInput(format!(
+                "Message {} is already {}",
+                message_id, status
+            )));
+        }
+
+        info!("Cancelled scheduled message {}", message_id);
+        Ok(())
+    }
+
+    /// Deliver every scheduled message whose `deliver_at` has passed.
+    ///
+    /// Called periodically by the server's scheduler; past-due messages left
+    /// over from a restart are picked up on the first run. Claiming rows with
+    /// a single `UPDATE ... RETURNING` keeps concurrent runs from delivering
+    /// the same message twice. Delivered messages take the delivery time as
+    /// their `created_ts` so they sort as new mail.
+    ///
+    /// # Returns
+    /// IDs of the messages delivered by this call.
+    pub async fn deliver_due_scheduled(_ctx: &Ctx, mm: &ModelManager) -> Result<Vec<i64>> {
+        let db = mm.db();
+        let now = chrono::Utc::now()
+            .naive_utc()
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+
+        let stmt = db
+            .prepare(
+                r#"
+            UPDATE message_schedules SET status = 'delivered', delivered_ts = ?1
+            WHERE status = 'scheduled' AND deliver_at <= ?1
+            RETURNING message_id
+            "#,
+            )
+            .await?;
+        let mut rows = stmt.query([now.as_str()]).await?;
+        let mut delivered = Vec::new();
+        while let Some(row) = rows.next().await? {
+            delivered.push(row.get::<i64>(0)?);
+        }
+
+        for &id in &delivered {
+            let stmt = db
+                .prepare("UPDATE messages SET created_ts = ? WHERE id = ?")
+                .await?;
+            stmt.execute((now.as_str(), id)).await?;
+
+            match Self::load_announcement(mm, id).await {
+                Ok(msg) => announce_message(mm, msg).await,
+                Err(e) => warn!("Failed to announce scheduled message {}: {}", id, e),
+            }
+        }
+
+        Ok(delivered)
+    }
+
+    /// Rebuild the announcement for a stored message (scheduled delivery path).
+    async fn load_announcement(mm: &ModelManager, message_id: i64) -> Result<MessageAnnouncement> {
+        let db = mm.db();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT m.project_id, p.slug, m.sender_id, ag.name, m.subject, b.body_md,
+                   m.thread_id, m.importance, m.ack_required
+            FROM messages AS m
+            JOIN message_bodies AS b ON b.message_id = m.id
+            JOIN projects AS p ON m.project_id = p.id
+            JOIN agents AS ag ON m.sender_id = ag.id
+            WHERE m.id = ?
+            "#,
+            )
+            .await?;
+        let mut rows = stmt.query([message_id]).await?;
+        let row = rows
+            .next()
+            .await?
+            .ok_or(crate::Error::MessageNotFound(message_id))?;
+
+        // Only "to" recipients are named, matching the immediate-send path;
+        // everyone gets an inbox copy
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT ag.name, mr.recipient_type
+            FROM message_recipients AS mr
+            JOIN agents AS ag ON mr.agent_id = ag.id
+            WHERE mr.message_id = ?
+            ORDER BY mr.rowid
+            "#,
+            )
+            .await?;
+        let mut name_rows = stmt.query([message_id]).await?;
+        let mut recipient_names = Vec::new();
+        let mut mailbox_names = Vec::new();
+        while let Some(name_row) = name_rows.next().await? {
+            let name = name_row.get::<String>(0)?;
+            if name_row.get::<String>(1)? == "to" {
+                recipient_names.push(name.clone());
+            }
+            mailbox_names.push(name);
+        }
+
+        let project_slug: String = row.get(1)?;
+        let sender_name: String = row.get(3)?;
+        Ok(MessageAnnouncement {
+            id: message_id,
+            project_id: row.get(0)?,
+            author: Actor::agent(&sender_name, &project_slug),
+            project_slug,
+            sender_id: row.get(2)?,
+            sender_name,
+            subject: row.get(4)?,
+            body_md: row.get(5)?,
+            thread_id: row.get::<Option<String>>(6)?.unwrap_or_default(),
+            importance: row.get(7)?,
+            ack_required: row.get(8)?,
+            recipient_names,
+            mailbox_names,
+        })
+    }
+
+    /// List a project's threads, most recently active first.
+    ///
+    /// One row per thread from a single GROUP BY over the
+    /// `(project_id, thread_id, created_ts)` index; the first subject and
+    /// last sender are looked up only for the rows on the returned page.
+    /// Pass the cursor of the last thread on a page (see
+    /// [`ThreadSummary::cursor`]) to fetch the next one.
+    pub async fn list_threads(
+        ctx: &Ctx,
+        mm: &ModelManager,
         project_id: i64,
         limit: i64,
+        cursor: Option<&ThreadCursor>,
     ) -> Result<Vec<ThreadSummary>> {
-        let db = mm.db();
+        super::project::ProjectBmc::ensure_access(ctx, mm, ProjectId::new(project_id)).await?;
 
+        let db = mm.db_read();
+
+        // Keyset pagination on (last activity, thread_id) so pages stay
+        // stable while new messages arrive.
         let stmt = db
             .prepare(
                 r#"
+            WITH threads AS (
+                SELECT
+                    m.thread_id,
+                    COUNT(*) AS message_count,
+                    COUNT(DISTINCT m.sender_id) AS participant_count,
+                    MAX(m.created_ts) AS last_message_ts
+                FROM visible_messages AS m
+                WHERE m.project_id = ?1 AND m.thread_id IS NOT NULL
+                GROUP BY m.thread_id
+            ),
+            page AS (
+                SELECT * FROM threads
+                WHERE ?2 IS NULL OR (last_message_ts, thread_id) < (?2, ?3)
+                ORDER BY last_message_ts DESC, thread_id DESC
+                LIMIT ?4
+            )
             SELECT
-                m.thread_id,
-                MIN(m.subject) as subject,
-                COUNT(*) as message_count,
-                MAX(m.created_ts) as last_message_ts
-            FROM messages AS m
-            WHERE m.project_id = ? AND m.thread_id IS NOT NULL
-            GROUP BY m.thread_id
-            ORDER BY last_message_ts DESC
-            LIMIT ?
+                p.thread_id,
+                (
+                    SELECT f.subject FROM visible_messages AS f
+                    WHERE f.project_id = ?1 AND f.thread_id = p.thread_id
+                    ORDER BY f.created_ts ASC, f.id ASC
+                    LIMIT 1
+                ) AS subject,
+                p.message_count,
+                p.participant_count,
+                p.last_message_ts,
+                (
+                    SELECT ag.name FROM visible_messages AS l
+                    JOIN agents AS ag ON l.sender_id = ag.id
+                    WHERE l.project_id = ?1 AND l.thread_id = p.thread_id
+                    ORDER BY l.created_ts DESC, l.id DESC
+                    LIMIT 1
+                ) AS last_sender_name
+            FROM page AS p
+            ORDER BY p.last_message_ts DESC, p.thread_id DESC
             "#,
             )
             .await?;
 
-        let mut rows = stmt.query((project_id, limit)).await?;
+        let (cursor_ts, cursor_thread) = match cursor {
+            Some(c) => (
+                libsql::Value::Text(c.last_message_ts.format("%Y-%m-%d %H:%M:%S").to_string()),
+                libsql::Value::Text(c.thread_id.clone()),
+            ),
+            None => (libsql::Value::Null, libsql::Value::Null),
+        };
+        let mut rows = stmt
+            .query((project_id, cursor_ts, cursor_thread, limit))
+            .await?;
         let mut threads = Vec::new();
 
         while let Some(row) = rows.next().await? {
-            let thread_id: String = row.get(0)?;
-            let subject: String = row.get(1)?;
             let message_count: i64 = row.get(2)?;
-            let last_message_ts_str: String = row.get(3)?;
-            let last_message_ts =
-                NaiveDateTime::parse_from_str(&last_message_ts_str, "%Y-%m-%d %H:%M:%S")
-                    .unwrap_or_default();
-
+            let participant_count: i64 = row.get(3)?;
             threads.push(ThreadSummary {
-                thread_id,
-                subject,
+                thread_id: row.get(0)?,
+                subject: row.get::<Option<String>>(1)?.unwrap_or_default(),
                 message_count: message_count as usize,
-                last_message_ts,
+                participant_count: participant_count as usize,
+                last_message_ts: parse_ts(&row.get::<String>(4)?),
+                last_sender_name: row.get::<Option<String>>(5)?.unwrap_or_default(),
             });
         }
         Ok(threads)
     }
 
+    /// Structured statistics for one thread, without loading every message.
+    ///
+    /// Per-sender counts, timestamps and ack totals come from a single
+    /// GROUP BY; the subject and the `recent` newest snippets are two more
+    /// indexed lookups.
+    ///
+    /// # Errors
+    /// Returns `Error::ThreadNotFound` if the thread has no messages in the project
+    pub async fn thread_summary(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: i64,
+        thread_id: &str,
+        recent: i64,
+    ) -> Result<ThreadStats> {
+        super::project::ProjectBmc::ensure_access(ctx, mm, ProjectId::new(project_id)).await?;
+
+        let db = mm.db_read();
+
+        // 1. Per-sender aggregates; thread totals are folded from these rows
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT
+                ag.name,
+                COUNT(*) AS message_count,
+                MIN(m.created_ts),
+                MAX(m.created_ts),
+                SUM(CASE WHEN m.ack_required THEN 1 ELSE 0 END),
+                SUM(CASE WHEN m.ack_required AND NOT EXISTS (
+                    SELECT 1 FROM message_recipients mr
+                    WHERE mr.message_id = m.id AND mr.ack_ts IS NULL
+                ) THEN 1 ELSE 0 END)
+            FROM visible_messages AS m
+            JOIN agents AS ag ON m.sender_id = ag.id
+            WHERE m.project_id = ? AND m.thread_id = ?
+            GROUP BY m.sender_id
+            ORDER BY message_count DESC, ag.name ASC
+            "#,
+            )
+            .await?;
+        let mut rows = stmt.query((project_id, thread_id)).await?;
+
+        let mut participants = Vec::new();
+        let mut first_ts: Option<NaiveDateTime> = None;
+        let mut last_ts: Option<NaiveDateTime> = None;
+        let (mut message_count, mut ack_required_count, mut acked_count) = (0, 0, 0);
+        while let Some(row) = rows.next().await? {
+            let count: i64 = row.get(1)?;
+            let first = parse_ts(&row.get::<String>(2)?);
+            let last = parse_ts(&row.get::<String>(3)?);
+            first_ts = Some(first_ts.map_or(first, |ts| ts.min(first)));
+            last_ts = Some(last_ts.map_or(last, |ts| ts.max(last)));
+            message_count += count;
+            ack_required_count += row.get::<i64>(4)?;
+            acked_count += row.get::<i64>(5)?;
+            participants.push(ThreadParticipant {
+                name: row.get(0)?,
+                message_count: count,
+            });
+        }
+        let (Some(first_ts), Some(last_ts)) = (first_ts, last_ts) else {
+            return Err(crate::Error::ThreadNotFound(thread_id.to_string()));
+        };
+
+        // 2. Subject of the first message
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT subject FROM visible_messages
+            WHERE project_id = ? AND thread_id = ?
+            ORDER BY created_ts ASC, thread_seq ASC, id ASC
+            LIMIT 1
+            "#,
+            )
+            .await?;
+        let mut rows = stmt.query((project_id, thread_id)).await?;
+        let subject = match rows.next().await? {
+            Some(row) => row.get(0)?,
+            None => String::new(),
+        };
+
+        // 3. Newest snippets, returned oldest first
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT m.id, ag.name, m.created_ts, substr(m.body_md, 1, ?), m.thread_seq
+            FROM visible_messages AS m
+            JOIN agents AS ag ON m.sender_id = ag.id
+            WHERE m.project_id = ? AND m.thread_id = ?
+            ORDER BY m.created_ts DESC, m.thread_seq DESC, m.id DESC
+            LIMIT ?
+            "#,
+            )
+            .await?;
+        let mut rows = stmt
+            .query((THREAD_SNIPPET_CHARS, project_id, thread_id, recent.max(0)))
+            .await?;
+        let mut recent_messages = Vec::new();
+        while let Some(row) = rows.next().await? {
+            recent_messages.push(ThreadSnippet {
+                message_id: row.get(0)?,
+                thread_seq: row.get(4)?,
+                sender_name: row.get(1)?,
+                created_ts: parse_ts(&row.get::<String>(2)?),
+                snippet: row.get(3)?,
+            });
+        }
+        recent_messages.reverse();
+
+        Ok(ThreadStats {
+            thread_id: thread_id.to_string(),
+            subject,
+            participants,
+            message_count,
+            first_ts,
+            last_ts,
+            ack_required_count,
+            acked_count,
+            recent: recent_messages,
+        })
+    }
+
     /// List recent messages for a project
     pub async fn list_recent(
         _ctx: &Ctx,
@@ -1016,16 +3299,17 @@ impl MessageBmc {
         project_id: ProjectId,
         limit: i64,
     ) -> Result<Vec<Message>> {
-        let db = mm.db();
+        let db = mm.db_read();
         let stmt = db.prepare(
             r#"
             SELECT
                 m.id, m.project_id, m.sender_id, ag.name as sender_name, m.thread_id, m.subject, m.body_md,
-                m.importance, m.ack_required, m.created_ts, m.attachments
-            FROM messages AS m
+                m.importance, m.ack_required, m.created_ts, m.attachments, m.thread_seq,
+                m.forwarded_from_id
+            FROM visible_messages AS m
             JOIN agents AS ag ON m.sender_id = ag.id
             WHERE m.project_id = ?
-            ORDER BY m.created_ts DESC
+            ORDER BY m.created_ts DESC, m.thread_seq DESC
             LIMIT ?
             "#
         ).await?;
@@ -1058,10 +3342,78 @@ impl MessageBmc {
                 thread_id,
                 subject,
                 body_md,
+                snippet: String::new(),
                 importance,
                 ack_required,
                 created_ts,
                 attachments,
+                project_slug: None,
+                thread_seq: row.get(11)?,
+                forwarded_from_id: row.get(12)?,
+                returned_from_snooze: false,
+                needs_ack: false,
+            });
+        }
+        Ok(messages)
+    }
+
+    /// List a project's broadcast messages, oldest first.
+    ///
+    /// Lets an agent that registered after a broadcast catch up on it; only
+    /// agents active at send time received it in their inbox. With `since`,
+    /// only broadcasts created at or after that time are returned.
+    pub async fn list_broadcasts(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        since: Option<NaiveDateTime>,
+    ) -> Result<Vec<Message>> {
+        super::project::ProjectBmc::ensure_access(ctx, mm, project_id).await?;
+
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT
+                m.id, m.project_id, m.sender_id, ag.name as sender_name, m.thread_id, m.subject, m.body_md,
+                m.importance, m.ack_required, m.created_ts, m.attachments, m.thread_seq,
+                m.forwarded_from_id
+            FROM visible_messages AS m
+            JOIN message_broadcasts AS b ON b.message_id = m.id
+            JOIN agents AS ag ON m.sender_id = ag.id
+            WHERE m.project_id = ? AND (? IS NULL OR m.created_ts >= ?)
+            ORDER BY m.created_ts ASC, m.id ASC
+            "#,
+            )
+            .await?;
+
+        let since: libsql::Value = since.map_or(libsql::Value::Null, |ts| {
+            libsql::Value::Text(ts.format("%Y-%m-%d %H:%M:%S").to_string())
+        });
+        let mut rows = stmt.query((project_id.get(), since.clone(), since)).await?;
+        let mut messages = Vec::new();
+
+        while let Some(row) = rows.next().await? {
+            let created_ts_str: String = row.get(9)?;
+            let attachments_str: String = row.get(10)?;
+            messages.push(Message {
+                id: row.get(0)?,
+                project_id: row.get(1)?,
+                sender_id: row.get(2)?,
+                sender_name: row.get(3)?,
+                thread_id: row.get(4)?,
+                subject: row.get(5)?,
+                body_md: row.get(6)?,
+                snippet: String::new(),
+                importance: row.get(7)?,
+                ack_required: row.get(8)?,
+                created_ts: parse_ts(&created_ts_str),
+                attachments: serde_json::from_str(&attachments_str)?,
+                project_slug: None,
+                thread_seq: row.get(11)?,
+                forwarded_from_id: row.get(12)?,
+                returned_from_snooze: false,
+                needs_ack: false,
             });
         }
         Ok(messages)
@@ -1089,7 +3441,7 @@ impl MessageBmc {
         sender_id: Option<i64>,
         limit: i64,
     ) -> Result<Vec<PendingReviewRow>> {
-        let db = mm.db();
+        let db = mm.db_read();
         let limit_clamped = limit.clamp(1, 50);
 
         // Build query with optional filters
@@ -1108,7 +3460,7 @@ impl MessageBmc {
                 p.id as project_id,
                 p.slug as project_slug,
                 p.human_key as project_name,
-                (SELECT COUNT(*) FROM messages m2
+                (SELECT COUNT(*) FROM visible_messages m2
                  WHERE m2.thread_id = m.thread_id AND m.thread_id IS NOT NULL) as thread_count,
                 (
                     SELECT json_group_array(json_object(
@@ -1122,7 +3474,7 @@ impl MessageBmc {
                     JOIN agents a ON mr.agent_id = a.id
                     WHERE mr.message_id = m.id
                 ) as recipients_json
-            FROM messages m
+            FROM visible_messages m
             JOIN agents sender ON m.sender_id = sender.id
             JOIN projects p ON m.project_id = p.id
             WHERE
@@ -1194,13 +3546,79 @@ impl MessageBmc {
         Ok(results)
     }
 
+    /// Count unread messages per recipient agent in a project.
+    ///
+    /// A message is unread for an agent until [`Self::mark_read`] (or
+    /// acknowledge) sets its `read_ts`. Computed in a single GROUP BY over the
+    /// partial `idx_message_recipients_unread` index.
+    ///
+    /// # Returns
+    /// Map of agent_id to unread count; agents with nothing unread are omitted.
+    pub async fn unread_counts(
+        _ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+    ) -> Result<HashMap<i64, i64>> {
+        let db = mm.db();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT mr.agent_id, COUNT(*)
+            FROM message_recipients mr
+            JOIN visible_messages m ON m.id = mr.message_id
+            WHERE mr.read_ts IS NULL AND m.project_id = ?
+            GROUP BY mr.agent_id
+            "#,
+            )
+            .await?;
+        let mut rows = stmt.query([project_id.get()]).await?;
+
+        let mut counts = HashMap::new();
+        while let Some(row) = rows.next().await? {
+            counts.insert(row.get::<i64>(0)?, row.get::<i64>(1)?);
+        }
+        Ok(counts)
+    }
+
+    /// Count unread messages per agent across all projects.
+    ///
+    /// # Returns
+    /// Map of project_id to (agent_id to unread count).
+    pub async fn unread_counts_all(
+        _ctx: &Ctx,
+        mm: &ModelManager,
+    ) -> Result<HashMap<i64, HashMap<i64, i64>>> {
+        let db = mm.db();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT m.project_id, mr.agent_id, COUNT(*)
+            FROM message_recipients mr
+            JOIN visible_messages m ON m.id = mr.message_id
+            WHERE mr.read_ts IS NULL
+            GROUP BY m.project_id, mr.agent_id
+            "#,
+            )
+            .await?;
+        let mut rows = stmt.query(()).await?;
+
+        let mut counts: HashMap<i64, HashMap<i64, i64>> = HashMap::new();
+        while let Some(row) = rows.next().await? {
+            counts
+                .entry(row.get::<i64>(0)?)
+                .or_default()
+                .insert(row.get::<i64>(1)?, row.get::<i64>(2)?);
+        }
+        Ok(counts)
+    }
+
     /// List messages across ALL projects (unified inbox)
     ///
     /// Returns messages from all projects, optionally filtered by importance.
     /// This provides a Gmail-style unified view of all agent communications.
     ///
     /// # Arguments
-    /// * `_ctx` - Request context
+    /// * `ctx` - Request context
     /// * `mm` - ModelManager providing database access
     /// * `importance` - Filter by importance level (High, Normal, or All)
     /// * `limit` - Maximum number of messages to return
@@ -1208,58 +3626,133 @@ impl MessageBmc {
     /// # Returns
     /// Vector of unified inbox items ordered by created_ts DESC (newest first)
     pub async fn list_unified_inbox(
-        _ctx: &Ctx,
+        ctx: &Ctx,
         mm: &ModelManager,
         importance: ImportanceFilter,
         limit: i32,
     ) -> Result<Vec<UnifiedInboxItem>> {
-        let db = mm.db();
+        let filter = UnifiedInboxFilter {
+            importance,
+            limit: limit as i64,
+            ..Default::default()
+        };
+        Self::list_unified_inbox_filtered(ctx, mm, &filter).await
+    }
 
-        // Build query based on importance filter - joins with projects for slug
-        let (query, params): (String, Vec<libsql::Value>) = match importance {
-            ImportanceFilter::High => {
-                let q = r#"
-                    SELECT
-                        m.id, m.project_id, p.slug as project_slug, m.sender_id, ag.name as sender_name,
-                        m.thread_id, m.subject, m.body_md, m.importance, m.created_ts
-                    FROM messages AS m
-                    JOIN agents AS ag ON m.sender_id = ag.id
-                    JOIN projects AS p ON m.project_id = p.id
-                    WHERE m.importance = 'high'
-                    ORDER BY m.created_ts DESC
-                    LIMIT ?
-                "#.to_string();
-                (q, vec![(limit as i64).into()])
-            }
-            ImportanceFilter::Normal => {
-                let q = r#"
-                    SELECT
-                        m.id, m.project_id, p.slug as project_slug, m.sender_id, ag.name as sender_name,
-                        m.thread_id, m.subject, m.body_md, m.importance, m.created_ts
-                    FROM messages AS m
-                    JOIN agents AS ag ON m.sender_id = ag.id
-                    JOIN projects AS p ON m.project_id = p.id
-                    WHERE m.importance = 'normal'
-                    ORDER BY m.created_ts DESC
-                    LIMIT ?
-                "#.to_string();
-                (q, vec![(limit as i64).into()])
-            }
-            ImportanceFilter::All => {
-                let q = r#"
-                    SELECT
-                        m.id, m.project_id, p.slug as project_slug, m.sender_id, ag.name as sender_name,
-                        m.thread_id, m.subject, m.body_md, m.importance, m.created_ts
-                    FROM messages AS m
-                    JOIN agents AS ag ON m.sender_id = ag.id
-                    JOIN projects AS p ON m.project_id = p.id
-                    ORDER BY m.created_ts DESC
-                    LIMIT ?
-                "#.to_string();
-                (q, vec![(limit as i64).into()])
+    /// SQL and parameters for [`Self::list_unified_inbox_filtered`].
+    pub(crate) fn unified_inbox_query(
+        filter: &UnifiedInboxFilter,
+    ) -> Result<(String, Vec<libsql::Value>)> {
+        // Joins with projects for slug
+        let mut query = String::from(
+            r#"
+            SELECT
+                m.id, m.project_id, p.slug as project_slug, m.sender_id, ag.name as sender_name,
+                m.thread_id, m.subject, m.snippet, m.importance, m.created_ts, m.ack_required,
+                EXISTS (
+                    SELECT 1 FROM thread_mutes AS tm
+                    JOIN message_recipients AS mr ON mr.agent_id = tm.agent_id
+                    WHERE mr.message_id = m.id AND tm.project_id = m.project_id
+                      AND tm.thread_id = m.thread_id
+                ) AS muted,
+                m.thread_seq
+            FROM visible_messages AS m
+            JOIN agents AS ag ON m.sender_id = ag.id
+            JOIN projects AS p ON m.project_id = p.id
+            WHERE 1 = 1
+            "#,
+        );
+        let mut params: Vec<libsql::Value> = Vec::new();
+
+        if !filter.projects.is_empty() {
+            let placeholders = vec!["?"; filter.projects.len()].join(", ");
+            query.push_str(&format!(" AND p.slug IN ({})", placeholders));
+            params.extend(filter.projects.iter().map(|slug| slug.clone().into()));
+        }
+        if let Some(sender) = &filter.sender {
+            query.push_str(" AND ag.name = ?");
+            params.push(sender.clone().into());
+        }
+        match filter.importance {
+            ImportanceFilter::High => query.push_str(" AND m.importance = 'high'"),
+            ImportanceFilter::Normal => query.push_str(" AND m.importance = 'normal'"),
+            ImportanceFilter::All => {}
+        }
+        if let Some(q) = &filter.query {
+            query.push_str(" AND instr(lower(m.subject), lower(?)) > 0");
+            params.push(q.clone().into());
+        }
+        if let Some(since) = filter.since {
+            query.push_str(" AND m.created_ts >= ?");
+            params.push(since.format("%Y-%m-%d %H:%M:%S").to_string().into());
+        }
+        if let Some(until) = filter.until {
+            query.push_str(" AND m.created_ts < ?");
+            params.push(until.format("%Y-%m-%d %H:%M:%S").to_string().into());
+        }
+        if let Some(label) = &filter.label {
+            query.push_str(
+                " AND EXISTS (SELECT 1 FROM message_labels AS ml JOIN labels AS l ON l.id = ml.label_id WHERE ml.message_id = m.id AND l.name = ?)",
+            );
+            params.push(label.clone().into());
+        }
+        // Keyset pagination on the sort key, ending in id
+        if let Some(cursor) = &filter.cursor {
+            let id = cursor.position(filter.sort)?;
+            query.push_str(&format!(" AND {}", filter.sort.after_cursor(None, "?")));
+            params.push(id.into());
+        }
+
+        query.push_str(&format!(" ORDER BY {} LIMIT ?", filter.sort.order_by(None)));
+        params.push(filter.limit.into());
+        Ok((query, params))
+    }
+
+    /// List unified inbox messages matching `filter`, in `filter.sort` order.
+    ///
+    /// Every filter is applied in SQL, so a narrow filter still returns up to
+    /// `limit` matches instead of whatever was in the latest page. Pass an
+    /// [`InboxCursor`] on the last item as `cursor` to fetch the next page.
+    /// A pending acknowledgement is any recipient's.
+    ///
+    /// A `ctx` limited to some projects only sees those: `filter.projects`
+    /// is narrowed to them, and an empty list means all of them.
+    ///
+    /// # Errors
+    /// [`crate::Error::InvalidInput`] when `filter.cursor` was issued for
+    /// another sort.
+    pub async fn list_unified_inbox_filtered(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        filter: &UnifiedInboxFilter,
+    ) -> Result<Vec<UnifiedInboxItem>> {
+        let scoped;
+        let filter = if ctx.is_unrestricted() {
+            filter
+        } else {
+            let projects: Vec<String> = if filter.projects.is_empty() {
+                ctx.allowed_projects().to_vec()
+            } else {
+                filter
+                    .projects
+                    .iter()
+                    .filter(|slug| ctx.can_access_project(slug))
+                    .cloned()
+                    .collect()
+            };
+            if projects.is_empty() {
+                return Ok(Vec::new());
             }
+            scoped = UnifiedInboxFilter {
+                projects,
+                ..filter.clone()
+            };
+            &scoped
         };
 
+        let db = mm.db_read();
+        let (query, params) = Self::unified_inbox_query(filter)?;
+
         let stmt = db.prepare(&query).await?;
         let mut rows = stmt
             .query(libsql::params::Params::Positional(params))
@@ -1274,20 +3767,23 @@ impl MessageBmc {
             let sender_name: String = row.get(4)?;
             let thread_id: Option<String> = row.get(5)?;
             let subject: String = row.get(6)?;
-            let body_md: String = row.get(7)?;
+            let snippet: String = row.get(7)?;
             let importance: String = row.get(8)?;
             let created_ts_str: String = row.get(9)?;
             let created_ts = NaiveDateTime::parse_from_str(&created_ts_str, "%Y-%m-%d %H:%M:%S")
                 .unwrap_or_default();
-
-            // Generate excerpt: first 200 chars, truncated at word boundary
-            let excerpt = if body_md.len() <= 200 {
-                body_md.clone()
+            let ack_required: bool = row.get(10)?;
+            let muted: bool = row.get(11)?;
+            let thread_seq: Option<i64> = row.get(12)?;
+
+            // Excerpt: the snippet, cut back to a word boundary when the
+            // body was longer
+            let excerpt = if snippet.chars().count() < SNIPPET_CHARS {
+                snippet.clone()
             } else {
-                let truncated = &body_md[..200];
-                match truncated.rfind(char::is_whitespace) {
-                    Some(pos) if pos > 100 => format!("{}…", &truncated[..pos]),
-                    _ => format!("{}…", truncated),
+                match snippet.rfind(char::is_whitespace) {
+                    Some(pos) if pos > 100 => format!("{}…", &snippet[..pos]),
+                    _ => format!("{}…", snippet),
                 }
             };
 
@@ -1298,10 +3794,13 @@ impl MessageBmc {
                 sender_id,
                 sender_name,
                 thread_id,
+                thread_seq,
                 subject,
-                body_md,
+                snippet,
                 excerpt,
                 importance,
+                ack_required,
+                muted,
                 created_ts,
             });
         }
@@ -1309,23 +3808,303 @@ impl MessageBmc {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// One row of [`MessageBmc::list_threads`].
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ThreadSummary {
     pub thread_id: String,
+    /// Subject of the first message in the thread
     pub subject: String,
     pub message_count: usize,
+    /// Distinct senders in the thread
+    pub participant_count: usize,
+    pub last_message_ts: NaiveDateTime,
+    /// Sender of the most recent message
+    pub last_sender_name: String,
+}
+
+impl ThreadSummary {
+    /// Cursor that continues a [`MessageBmc::list_threads`] listing after
+    /// this thread.
+    pub fn cursor(&self) -> ThreadCursor {
+        ThreadCursor {
+            last_message_ts: self.last_message_ts,
+            thread_id: self.thread_id.clone(),
+        }
+    }
+}
+
+/// Keyset position in a thread listing: last activity, then thread ID.
+///
+/// Encoded as `<last_message_ts>|<thread_id>` (timestamp as
+/// `%Y-%m-%dT%H:%M:%S`) so it can travel as an opaque query parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ThreadCursor {
     pub last_message_ts: NaiveDateTime,
+    pub thread_id: String,
+}
+
+impl std::fmt::Display for ThreadCursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}|{}",
+            self.last_message_ts.format("%Y-%m-%dT%H:%M:%S"),
+            self.thread_id
+        )
+    }
+}
+
+impl std::str::FromStr for ThreadCursor {
+    type Err = crate::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || crate::Error::InvalidInput(format!("Invalid thread cursor: {}", s));
+        let (ts, thread_id) = s.split_once('|').ok_or_else(invalid)?;
+        let last_message_ts =
+            NaiveDateTime::parse_from_str(ts, "%Y-%m-%dT%H:%M:%S").map_err(|_| invalid())?;
+        if thread_id.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            last_message_ts,
+            thread_id: thread_id.to_string(),
+        })
+    }
+}
+
+/// Maximum characters of body kept in a [`ThreadSnippet`].
+const THREAD_SNIPPET_CHARS: i64 = 200;
+
+/// Per-thread statistics returned by [`MessageBmc::thread_summary`].
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ThreadStats {
+    pub thread_id: String,
+    /// Subject of the first message in the thread
+    pub subject: String,
+    /// Senders ordered by message count, most active first
+    pub participants: Vec<ThreadParticipant>,
+    pub message_count: i64,
+    pub first_ts: NaiveDateTime,
+    pub last_ts: NaiveDateTime,
+    /// Messages sent with `ack_required`
+    pub ack_required_count: i64,
+    /// Ack-required messages acknowledged by every recipient
+    pub acked_count: i64,
+    /// Newest messages, oldest first
+    pub recent: Vec<ThreadSnippet>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ThreadParticipant {
+    pub name: String,
+    pub message_count: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ThreadSnippet {
+    pub message_id: i64,
+    /// Position in the thread, counting from 1
+    pub thread_seq: Option<i64>,
+    pub sender_name: String,
+    pub created_ts: NaiveDateTime,
+    /// First 200 characters of the body
+    pub snippet: String,
+}
+
+/// A sent message with its delivery state, returned by
+/// [`MessageBmc::list_outbox_for_agent`].
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OutboxMessage {
+    #[serde(flatten)]
+    pub message: Message,
+    /// To recipients first, then CC, then BCC
+    pub recipients: Vec<OutboxRecipient>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OutboxRecipient {
+    pub name: String,
+    /// `to`, `cc` or `bcc`
+    pub recipient_type: String,
+    pub read_ts: Option<NaiveDateTime>,
+    pub ack_ts: Option<NaiveDateTime>,
+}
+
+/// Body of a forward: the note, then the original quoted under its headers.
+///
+/// Only `to` and `cc` recipients are listed, so a forward never reveals who
+/// was BCC'd. A forwarded body that is itself a forward nests its quote.
+fn forward_body(original: &Message, recipients: &[OutboxRecipient], note: Option<&str>) -> String {
+    let names = |kind: &str| {
+        recipients
+            .iter()
+            .filter(|r| r.recipient_type == kind)
+            .map(|r| r.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let mut body = String::new();
+    if let Some(note) = note.map(str::trim).filter(|n| !n.is_empty()) {
+        body.push_str(note);
+        body.push_str("\n\n");
+    }
+    body.push_str("> ---------- Forwarded message ----------\n");
+    body.push_str(&format!("> **From:** {}\n", original.sender_name));
+    body.push_str(&format!(
+        "> **Date:** {} UTC\n",
+        original.created_ts.format("%Y-%m-%d %H:%M:%S")
+    ));
+    let to = names("to");
+    if !to.is_empty() {
+        body.push_str(&format!("> **To:** {}\n", to));
+    }
+    let cc = names("cc");
+    if !cc.is_empty() {
+        body.push_str(&format!("> **Cc:** {}\n", cc));
+    }
+    body.push_str(&format!("> **Subject:** {}\n>\n", original.subject));
+    for line in original.body_md.lines() {
+        if line.is_empty() {
+            body.push_str(">\n");
+        } else {
+            body.push_str("> ");
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    body
+}
+
+/// Tombstone for a recalled message, returned by [`MessageBmc::recall`].
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MessageRecall {
+    pub message_id: i64,
+    pub recalled_ts: NaiveDateTime,
+    pub recall_reason: String,
+}
+
+/// A message held for delivery, returned by [`MessageBmc::list_scheduled`].
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ScheduledMessage {
+    #[serde(flatten)]
+    pub message: Message,
+    pub deliver_at: NaiveDateTime,
+    /// To recipients first, then CC, then BCC
+    pub recipients: Vec<OutboxRecipient>,
+}
+
+/// A message hidden from its reader's inbox, returned by
+/// [`MessageBmc::list_snoozed`].
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SnoozedMessage {
+    #[serde(flatten)]
+    pub message: Message,
+    /// When the message comes back to the inbox
+    pub snoozed_until: NaiveDateTime,
+}
+
+/// Turn free-text search terms into an FTS5 MATCH expression.
+///
+/// 1. Unbalanced quotes: the whole text is one literal phrase
+/// 2. Explicit operators (AND, OR, NOT, `*`) or a leading phrase: passed raw
+/// 3. Otherwise words containing `-` or `:` are quoted, so FTS5 does not read
+///    "full-text" as "full NOT text" or "a:b" as a column filter
+fn fts_match_expression(text: &str) -> String {
+    let quote_count = text.chars().filter(|c| *c == '"').count();
+    let has_fts_operators = text.contains(" AND ")
+        || text.contains(" OR ")
+        || text.contains(" NOT ")
+        || text.contains('*');
+    if quote_count % 2 != 0 {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else if has_fts_operators || text.starts_with('"') {
+        text.to_string()
+    } else {
+        text.split_whitespace()
+            .map(|word| {
+                if (word.contains('-') || word.contains(':')) && !word.starts_with('"') {
+                    format!("\"{}\"", word)
+                } else {
+                    word.to_string()
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+fn parse_ts(ts: &str) -> NaiveDateTime {
+    NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S").unwrap_or_default()
+}
+
+/// Give a new message the next sequence number of its thread in
+/// `project_id`.
+///
+/// Call it in the transaction inserting the message: the counter is bumped
+/// and read in one statement, so concurrent senders never share or skip a
+/// number.
+pub(crate) async fn assign_thread_seq(
+    conn: &libsql::Connection,
+    project_id: i64,
+    message_id: i64,
+    thread_id: &str,
+) -> Result<i64> {
+    // Scoped so the RETURNING statement is finalized before COMMIT
+    let seq = {
+        let stmt = conn
+            .prepare(
+                r#"
+            INSERT INTO thread_counters (project_id, thread_id, last_seq) VALUES (?, ?, 1)
+            ON CONFLICT(project_id, thread_id) DO UPDATE SET last_seq = last_seq + 1
+            RETURNING last_seq
+            "#,
+            )
+            .await?;
+        let mut rows = stmt.query((project_id, thread_id)).await?;
+        match rows.next().await? {
+            Some(row) => row.get::<i64>(0)?,
+            None => {
+                return Err(crate::Error::InvalidInput(
+                    "Failed to number message in thread".into(),
+                ));
+            }
+        }
+    };
+
+    let stmt = conn
+        .prepare("INSERT INTO message_thread_seqs (message_id, seq) VALUES (?, ?)")
+        .await?;
+    stmt.execute((message_id, seq)).await?;
+    Ok(seq)
+}
+
+/// Store a new message's full body in `message_bodies`.
+///
+/// Call it in the transaction inserting the message, which carries the
+/// body's [`snippet`].
+pub(crate) async fn insert_body(
+    conn: &libsql::Connection,
+    message_id: i64,
+    body_md: &str,
+) -> Result<()> {
+    let stmt = conn
+        .prepare("INSERT INTO message_bodies (message_id, body_md, body_hash) VALUES (?, ?, ?)")
+        .await?;
+    stmt.execute((message_id, body_md, body_hash(body_md)))
+        .await?;
+    Ok(())
 }
 
 /// Paths for git archival of a message
-struct MessageArchivePaths {
-    canonical: PathBuf,
-    outbox: PathBuf,
-    inboxes: Vec<PathBuf>,
+pub(crate) struct MessageArchivePaths {
+    pub(crate) canonical: PathBuf,
+    pub(crate) outbox: PathBuf,
+    pub(crate) inboxes: Vec<PathBuf>,
 }
 
 /// Build all file paths for message archival
-fn build_message_paths(
+pub(crate) fn build_message_paths(
     project_slug: &str,
     sender_name: &str,
     recipient_names: &[String],
@@ -1370,7 +4149,7 @@ fn build_message_paths(
 }
 
 /// Format message content with JSON frontmatter
-fn format_message_content(
+pub(crate) fn format_message_content(
     id: i64,
     project_slug: &str,
     sender_name: &str,
@@ -1399,104 +4178,217 @@ fn format_message_content(
 }
 
 /// Write content to a path, creating parent directories as needed
-fn write_archive_file(root: &std::path::Path, rel: &std::path::Path, content: &str) -> Result<()> {
+///
+/// Fails with [`crate::Error::ArchiveWrite`] naming `rel`.
+pub(crate) fn write_archive_file(
+    root: &std::path::Path,
+    rel: &std::path::Path,
+    content: &str,
+) -> Result<()> {
     let full = root.join(rel);
-    if let Some(p) = full.parent() {
-        std::fs::create_dir_all(p)?;
-    }
-    std::fs::write(full, content)?;
-    Ok(())
+    let write = || {
+        if let Some(p) = full.parent() {
+            std::fs::create_dir_all(p)?;
+        }
+        std::fs::write(&full, content)
+    };
+    write().map_err(|source| crate::Error::ArchiveWrite {
+        path: rel.display().to_string(),
+        source,
+    })
+}
+
+/// Format a mailbox pointer: frontmatter naming the canonical message file
+pub(crate) fn format_mailbox_pointer(id: i64, canonical: &std::path::Path) -> Result<String> {
+    let frontmatter = serde_json::json!({
+        "id": id,
+        "canonical": canonical
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/"),
+    });
+    Ok(format!(
+        "---json\n{}\n---\n",
+        serde_json::to_string_pretty(&frontmatter)?
+    ))
+}
+
+/// What outbox and inbox files of a message contain under `copies`
+pub(crate) fn mailbox_copy_content<'a>(
+    id: i64,
+    canonical: &std::path::Path,
+    content: &'a str,
+    copies: MailboxCopies,
+) -> Result<Cow<'a, str>> {
+    Ok(match copies {
+        MailboxCopies::Full => Cow::Borrowed(content),
+        MailboxCopies::Pointer => Cow::Owned(format_mailbox_pointer(id, canonical)?),
+    })
 }
 
-/// Write message to all archive paths (canonical, outbox, inboxes)
-fn write_message_to_archive(
-    workdir: &std::path::Path,
+/// Write a message's canonical, outbox and inbox files under `root`
+///
+/// Stops at the first failed write. Returns every path written, canonical
+/// first.
+pub(crate) fn stage_message_files(
+    root: &std::path::Path,
+    id: i64,
     paths: &MessageArchivePaths,
     content: &str,
-) -> Result<()> {
-    write_archive_file(workdir, &paths.canonical, content)?;
-    write_archive_file(workdir, &paths.outbox, content)?;
-    for inbox_path in &paths.inboxes {
-        write_archive_file(workdir, inbox_path, content)?;
+    copies: MailboxCopies,
+) -> Result<Vec<PathBuf>> {
+    write_archive_file(root, &paths.canonical, content)?;
+    let mailbox = mailbox_copy_content(id, &paths.canonical, content, copies)?;
+
+    let mut written = vec![paths.canonical.clone()];
+    for path in std::iter::once(&paths.outbox).chain(&paths.inboxes) {
+        write_archive_file(root, path, &mailbox)?;
+        written.push(path.clone());
     }
-    Ok(())
+    Ok(written)
 }
 
-/// Background git commit for message archival
-/// This runs async after the DB commit returns, keeping API latency low
-#[allow(clippy::too_many_arguments)]
-async fn commit_message_to_git(
-    git_lock: Arc<Mutex<()>>,
-    cached_repo: Arc<Mutex<git2::Repository>>,
+/// What a message looks like at the moment it becomes visible to recipients.
+struct MessageAnnouncement {
     id: i64,
-    project_slug: &str,
-    sender_name: &str,
-    recipient_names: &[String],
-    subject: &str,
-    body_md: &str,
-    thread_id: &str,
-    importance: &str,
-) -> Result<()> {
-    // Build timestamp-based paths
+    project_id: i64,
+    project_slug: String,
+    /// Git author of the archive commit
+    author: Actor,
+    sender_id: i64,
+    sender_name: String,
+    /// "To" recipients, as named in the frontmatter
+    recipient_names: Vec<String>,
+    /// Every recipient (to, cc and bcc); each gets an inbox copy
+    mailbox_names: Vec<String>,
+    subject: String,
+    body_md: String,
+    thread_id: String,
+    importance: String,
+    ack_required: bool,
+}
+
+/// Publish `message.created` and hand the message to the archive queue.
+///
+/// Shared by immediate sends and scheduled deliveries. Archive failures are
+/// logged only; the DB write has already succeeded.
+async fn announce_message(mm: &ModelManager, msg: MessageAnnouncement) {
+    let id = msg.id;
+    mm.events.publish(
+        MailEventKind::MessageCreated,
+        &msg.project_slug,
+        serde_json::json!({
+            "id": id,
+            "project_id": msg.project_id,
+            "project_slug": msg.project_slug,
+            "sender_id": msg.sender_id,
+            "sender_name": msg.sender_name,
+            "recipients": msg.recipient_names,
+            "subject": msg.subject,
+            "importance": msg.importance,
+            "thread_id": msg.thread_id,
+            "ack_required": msg.ack_required,
+            "created_ts": chrono::Utc::now().naive_utc().format("%Y-%m-%dT%H:%M:%S").to_string(),
+        }),
+    );
+
+    match build_archive_job(mm.project_repo_root(&msg.project_slug), &msg) {
+        Ok(job) => {
+            mm.archive_queue.submit(job).await;
+        }
+        Err(e) => warn!("Failed to prepare archive for message {}: {}", id, e),
+    }
+}
+
+/// Build the archive files for a message, stamped with the current time.
+fn build_archive_job(repo_root: PathBuf, msg: &MessageAnnouncement) -> Result<ArchiveJob> {
     let now = chrono::Utc::now();
     let y_dir = now.format("%Y").to_string();
     let m_dir = now.format("%m").to_string();
     let created_iso = now.format("%Y-%m-%dT%H-%M-%SZ").to_string();
-    let filename = format!("{}__{}__{}.md", created_iso, slug::slugify(subject), id);
+    let filename = format!(
+        "{}__{}__{}.md",
+        created_iso,
+        slug::slugify(&msg.subject),
+        msg.id
+    );
 
     let paths = build_message_paths(
-        project_slug,
-        sender_name,
-        recipient_names,
+        &msg.project_slug,
+        &msg.sender_name,
+        &msg.mailbox_names,
         &filename,
         &y_dir,
         &m_dir,
     );
 
     let content = format_message_content(
-        id,
-        project_slug,
-        sender_name,
-        recipient_names,
-        subject,
-        body_md,
-        thread_id,
-        importance,
+        msg.id,
+        &msg.project_slug,
+        &msg.sender_name,
+        &msg.recipient_names,
+        &msg.subject,
+        &msg.body_md,
+        &msg.thread_id,
+        &msg.importance,
         &created_iso,
     )?;
 
-    // Git operations - serialized to prevent lock contention
-    // Use pre-fetched cached repository to prevent FD exhaustion
-    let _git_guard = git_lock.lock().await;
-    let repo = cached_repo.lock().await;
-    let workdir = repo
-        .workdir()
-        .ok_or(crate::Error::InvalidInput("No workdir".into()))?;
-
-    write_message_to_archive(workdir, &paths, &content)?;
-
-    // Commit all paths
-    let all_paths: Vec<PathBuf> = std::iter::once(paths.canonical.clone())
-        .chain(std::iter::once(paths.outbox.clone()))
-        .chain(paths.inboxes.iter().cloned())
-        .collect();
-    let all_paths_ref: Vec<&std::path::Path> = all_paths.iter().map(|p| p.as_path()).collect();
+    Ok(ArchiveJob {
+        message_id: msg.id,
+        repo_root,
+        paths,
+        content,
+        summary: format!(
+            "mail: {} -> {} | {}",
+            msg.sender_name,
+            msg.recipient_names.join(", "),
+            msg.subject
+        ),
+        author: Some(msg.author.clone()),
+        enqueued_at: std::time::Instant::now(),
+    })
+}
 
-    let commit_msg = format!(
-        "mail: {} -> {} | {}",
-        sender_name,
-        recipient_names.join(", "),
-        subject
-    );
-    git_store::commit_paths(
+/// Commit a recall tombstone next to the archived message.
+///
+/// Written to `projects/{slug}/messages/recalls/{id}.json` in its own commit;
+/// the original message files are left untouched.
+async fn commit_recall_to_git(
+    mm: &ModelManager,
+    project_slug: &str,
+    sender_name: &str,
+    author: &Actor,
+    subject: &str,
+    recall: &MessageRecall,
+) -> Result<()> {
+    let path = PathBuf::from("projects")
+        .join(project_slug)
+        .join("messages")
+        .join("recalls")
+        .join(format!("{}.json", recall.message_id));
+    let content = serde_json::to_string_pretty(&serde_json::json!({
+        "message_id": recall.message_id,
+        "sender": sender_name,
+        "recalled_ts": recall.recalled_ts.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        "reason": recall.recall_reason,
+    }))?;
+
+    let cached_repo = mm.get_project_repo(project_slug).await?;
+    let _git_guard = mm.git_lock.lock().await;
+    let repo = cached_repo.lock().await;
+    git_store::commit_file(
         &repo,
-        &all_paths_ref,
-        &commit_msg,
-        "mcp-bot",
-        "mcp-bot@localhost",
+        &path,
+        &content,
+        &git_store::with_actor_trailer(
+            &format!("recall: {} | {}", sender_name, subject),
+            Some(author),
+        ),
+        author.name(),
+        author.email(),
     )?;
-
-    info!("Background git commit succeeded for message {}", id);
     Ok(())
 }
 
@@ -1721,39 +4613,88 @@ mod tests {
         assert_eq!(std::fs::read_to_string(&full_path).unwrap(), "");
     }
 
+    #[test]
+    fn test_write_archive_file_error_names_path() {
+        let temp_dir = TempDir::new().unwrap();
+        // A file where a directory should be
+        std::fs::write(temp_dir.path().join("agents"), "").unwrap();
+        let rel_path = PathBuf::from("agents/bob/inbox/msg.md");
+
+        let err = write_archive_file(temp_dir.path(), &rel_path, "content").unwrap_err();
+
+        assert!(
+            matches!(err, crate::Error::ArchiveWrite { ref path, .. } if path == "agents/bob/inbox/msg.md")
+        );
+        assert!(err.to_string().contains("agents/bob/inbox/msg.md"));
+    }
+
     // ============================================================================
-    // TDD Tests for write_message_to_archive
+    // TDD Tests for stage_message_files
     // ============================================================================
 
-    #[test]
-    fn test_write_message_to_archive_creates_all_files() {
-        let temp_dir = TempDir::new().unwrap();
-        let paths = MessageArchivePaths {
+    fn two_inbox_paths() -> MessageArchivePaths {
+        MessageArchivePaths {
             canonical: PathBuf::from("messages/2025/01/msg.md"),
             outbox: PathBuf::from("agents/sender/outbox/2025/01/msg.md"),
             inboxes: vec![
                 PathBuf::from("agents/alice/inbox/2025/01/msg.md"),
                 PathBuf::from("agents/bob/inbox/2025/01/msg.md"),
             ],
-        };
+        }
+    }
 
-        write_message_to_archive(temp_dir.path(), &paths, "test content").unwrap();
+    #[test]
+    fn test_stage_message_files_full_copies() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = two_inbox_paths();
+
+        let written = stage_message_files(
+            temp_dir.path(),
+            7,
+            &paths,
+            "test content",
+            MailboxCopies::Full,
+        )
+        .unwrap();
 
-        // All 4 files should exist with same content
-        assert!(temp_dir.path().join(&paths.canonical).exists());
-        assert!(temp_dir.path().join(&paths.outbox).exists());
-        assert!(temp_dir.path().join(&paths.inboxes[0]).exists());
-        assert!(temp_dir.path().join(&paths.inboxes[1]).exists());
+        // All 4 files should exist with same content, canonical first
+        assert_eq!(written.len(), 4);
+        assert_eq!(written[0], paths.canonical);
+        for path in &written {
+            assert_eq!(
+                std::fs::read_to_string(temp_dir.path().join(path)).unwrap(),
+                "test content"
+            );
+        }
+    }
+
+    #[test]
+    fn test_stage_message_files_pointer_copies() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = two_inbox_paths();
+
+        stage_message_files(
+            temp_dir.path(),
+            7,
+            &paths,
+            "test content",
+            MailboxCopies::Pointer,
+        )
+        .unwrap();
 
-        // Verify content
         assert_eq!(
             std::fs::read_to_string(temp_dir.path().join(&paths.canonical)).unwrap(),
             "test content"
         );
+        let pointer = std::fs::read_to_string(temp_dir.path().join(&paths.inboxes[1])).unwrap();
+        assert!(pointer.starts_with("---json\n"));
+        assert!(pointer.contains("\"id\": 7"));
+        assert!(pointer.contains("\"canonical\": \"messages/2025/01/msg.md\""));
+        assert!(!pointer.contains("test content"));
     }
 
     #[test]
-    fn test_write_message_to_archive_empty_inboxes() {
+    fn test_stage_message_files_empty_inboxes() {
         let temp_dir = TempDir::new().unwrap();
         let paths = MessageArchivePaths {
             canonical: PathBuf::from("msg.md"),
@@ -1762,72 +4703,73 @@ mod tests {
         };
 
         // Should succeed even with no inboxes
-        write_message_to_archive(temp_dir.path(), &paths, "content").unwrap();
+        let written =
+            stage_message_files(temp_dir.path(), 1, &paths, "content", MailboxCopies::Full)
+                .unwrap();
 
+        assert_eq!(written.len(), 2);
         assert!(temp_dir.path().join(&paths.canonical).exists());
         assert!(temp_dir.path().join(&paths.outbox).exists());
     }
 
+    #[test]
+    fn test_stage_message_files_stops_at_failed_inbox() {
+        let temp_dir = TempDir::new().unwrap();
+        let paths = two_inbox_paths();
+        std::fs::create_dir_all(temp_dir.path().join(&paths.inboxes[0])).unwrap();
+
+        let err = stage_message_files(temp_dir.path(), 1, &paths, "content", MailboxCopies::Full)
+            .unwrap_err();
+
+        assert!(
+            err.to_string()
+                .contains("agents/alice/inbox/2025/01/msg.md")
+        );
+        assert!(!temp_dir.path().join(&paths.inboxes[1]).exists());
+    }
+
     // ============================================================================
     // FTS Query Escaping Tests
     // ============================================================================
 
-    /// Helper to simulate FTS query escaping logic from MessageBmc::search
-    fn escape_fts_query(query: &str) -> String {
-        let quote_count = query.chars().filter(|c| *c == '"').count();
-        let has_fts_operators = query.contains(" AND ")
-            || query.contains(" OR ")
-            || query.contains(" NOT ")
-            || query.contains('*');
-
-        if quote_count % 2 != 0 {
-            format!("\"{}\"", query.replace('"', "\"\""))
-        } else if has_fts_operators || query.starts_with('"') {
-            query.to_string()
-        } else {
-            query
-                .split_whitespace()
-                .map(|word| {
-                    if word.contains('-') && !word.starts_with('"') {
-                        format!("\"{}\"", word)
-                    } else {
-                        word.to_string()
-                    }
-                })
-                .collect::<Vec<_>>()
-                .join(" ")
-        }
-    }
-
     #[test]
     fn test_fts_query_escapes_hyphens() {
         // "full-text search" should NOT be interpreted as "full AND NOT text AND search"
         // FTS5 treats hyphen as NOT operator by default
-        let escaped = escape_fts_query("full-text search");
+        let escaped = fts_match_expression("full-text search");
         assert_eq!(escaped, "\"full-text\" search");
 
         // Multiple hyphenated words
-        let escaped2 = escape_fts_query("real-time data-driven");
+        let escaped2 = fts_match_expression("real-time data-driven");
         assert_eq!(escaped2, "\"real-time\" \"data-driven\"");
     }
 
+    #[test]
+    fn test_fts_query_escapes_colons() {
+        // Unknown `field:value` tokens reach FTS5 as plain terms, not column filters
+        assert_eq!(
+            fts_match_expression("label:ops deploy"),
+            "\"label:ops\" deploy"
+        );
+    }
+
     #[test]
     fn test_fts_query_preserves_operators() {
         // Explicit FTS operators should be preserved
-        assert_eq!(escape_fts_query("full AND text"), "full AND text");
-        assert_eq!(escape_fts_query("search*"), "search*");
+        assert_eq!(fts_match_expression("full AND text"), "full AND text");
+        assert_eq!(fts_match_expression("search*"), "search*");
     }
 
     #[test]
     fn test_fts_query_handles_phrases() {
         // Quoted phrases should be preserved
-        assert_eq!(escape_fts_query("\"exact phrase\""), "\"exact phrase\"");
+        assert_eq!(fts_match_expression("\"exact phrase\""), "\"exact phrase\"");
     }
 
     #[test]
     fn test_fts_query_escapes_unbalanced_quotes() {
         // Unbalanced quotes should be escaped
-        let escaped = escape_fts_query("\"unclosed phrase");
+        let escaped = fts_match_expression("\"unclosed phrase");
         assert!(escaped.starts_with('"') && escaped.ends_with('"'));
     }
 
@@ -15,15 +15,22 @@
 //! | BMC | Description |
 //! |-----|-------------|
 //! | `agent::AgentBmc` | AI agent registration and profiles |
+//! | `agent_group::AgentGroupBmc` | Named agent groups for `group:<name>` addressing |
 //! | `message::MessageBmc` | Inter-agent messaging |
 //! | `project::ProjectBmc` | Project management |
 //! | `file_reservation::FileReservationBmc` | File locking coordination |
+//! | `reservation_queue::ReservationQueueBmc` | Waiting list for contended reservations |
 //! | `build_slot::BuildSlotBmc` | CI/CD slot management |
 //! | `macro_def::MacroDefBmc` | Workflow macro definitions |
 //! | `attachment::AttachmentBmc` | File attachments |
 //! | `activity::ActivityBmc` | Unified activity feed |
 //! | `tool_metric::ToolMetricBmc` | Tool usage analytics |
 //! | `overseer_message::OverseerMessageBmc` | Human escalation messages |
+//! | `archive_integrity::ArchiveIntegrityBmc` | Archive vs DB consistency checks |
+//! | `template::TemplateBmc` | Canned message templates |
+//! | `project_version::ProjectVersionBmc` | Change counters for client cache invalidation |
+//! | `server_info::ServerInfoBmc` | Enabled features and enforced limits |
+//! | `backup::BackupBmc` | Database snapshots and their rotation |
 //!
 //! ## ModelManager
 //!
@@ -31,39 +38,58 @@
 //! - Database connections (libSQL)
 //! - Git repository operations
 //! - Concurrency control via `git_lock`
+//! - Live change notifications via `events`
+//! - Batched message archive commits via `archive_queue`
 
 pub mod activity;
 pub mod agent;
 pub mod agent_capabilities;
+pub mod agent_group;
 pub mod agent_link;
 pub mod archive_browser;
+pub mod archive_integrity;
+pub mod archive_queue;
 pub mod attachment;
+pub mod audit;
+pub mod backup;
 pub mod build_slot;
 pub mod escalation;
 pub mod export;
 pub mod file_reservation;
 pub mod identity;
+pub mod label;
 pub mod macro_def;
 pub mod message;
 pub mod message_recipient;
+pub mod notification;
 pub mod orchestration;
 pub mod overseer_message;
 pub mod precommit_guard;
 pub mod product;
 pub mod project;
 pub mod project_sibling_suggestion;
+pub mod project_version;
+pub mod reservation_queue;
+pub mod retention;
+pub mod server_info;
+pub mod template;
+pub mod thread_mute;
 pub mod time_travel;
 pub mod tool_metric;
 
 use crate::Result;
+use crate::events::EventBus;
+use crate::model::archive_queue::ArchiveQueue;
 use crate::store::archive_lock::{ArchiveLock, LockGuard};
+use crate::store::db_pool::DbPool;
 use crate::store::repo_cache::RepoCache;
+use crate::store::retry::RetryPolicy;
 use crate::store::{self, Db};
 use git2::Repository;
-use mouchak_mail_common::config::AppConfig;
-use std::path::PathBuf;
+use mouchak_mail_common::config::{AppConfig, ArchiveLayout};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, MutexGuard};
 use tracing::info;
 
 /// Default LRU cache capacity for git repositories.
@@ -75,7 +101,11 @@ const DEFAULT_ARCHIVE_LOCK_TIMEOUT_SECS: u64 = 30;
 
 #[derive(Clone)]
 pub struct ModelManager {
-    pub(crate) db: Db,
+    /// Writer connection plus read-only readers.
+    pool: Arc<DbPool>,
+    /// Serializes explicit transactions: every BMC shares one connection and
+    /// SQLite rejects a nested `BEGIN`.
+    tx_lock: Arc<Mutex<()>>,
     pub repo_root: PathBuf,
     /// Mutex to serialize git operations - git2's index locking doesn't handle
     /// high concurrency well, so we serialize commits at the application level.
@@ -90,14 +120,17 @@ pub struct ModelManager {
     archive_lock: Arc<ArchiveLock>,
     /// Application configuration.
     pub app_config: Arc<AppConfig>,
+    /// Live event bus; BMCs publish mailbox changes here after writes.
+    pub events: Arc<EventBus>,
+    /// Write-behind queue that batches message archive commits.
+    pub archive_queue: Arc<ArchiveQueue>,
 }
 
 impl ModelManager {
     /// Constructor
     pub async fn new(app_config: Arc<AppConfig>) -> Result<Self> {
-        let db = store::new_db_pool().await?;
-        // Default to "data/archive" for now, similar to Python's default or configurable
-        let repo_root = std::env::current_dir()?.join("data").join("archive");
+        let pool = Arc::new(store::new_db_pool(app_config.database.read_pool_size).await?);
+        let repo_root = Self::default_repo_root()?;
         std::fs::create_dir_all(&repo_root)?;
 
         // Auto-initialize git repository if not exists
@@ -113,27 +146,54 @@ impl ModelManager {
         let archive_lock = Arc::new(ArchiveLock::new(&repo_root));
         Self::cleanup_stale_locks(&archive_lock).await;
 
+        let git_lock = Arc::new(Mutex::new(()));
+        let repo_cache = Arc::new(RepoCache::new(cache_size));
+        let archive_queue = Arc::new(ArchiveQueue::new(
+            repo_cache.clone(),
+            git_lock.clone(),
+            app_config.archive.clone(),
+        ));
+
         Ok(ModelManager {
-            db,
+            pool,
+            tx_lock: Arc::new(Mutex::new(())),
             repo_root,
-            git_lock: Arc::new(Mutex::new(())),
-            repo_cache: Arc::new(RepoCache::new(cache_size)),
+            git_lock,
+            repo_cache,
             archive_lock,
             app_config,
+            events: Arc::new(EventBus::default()),
+            archive_queue,
         })
     }
 
+    /// Archive root used by [`Self::new`]: `data/archive` under the current
+    /// directory.
+    pub fn default_repo_root() -> Result<PathBuf> {
+        Ok(std::env::current_dir()?.join("data").join("archive"))
+    }
+
     /// Constructor for testing with custom db connection and paths
     /// This is public so integration tests can use it
     pub fn new_for_test(db: Db, repo_root: PathBuf, app_config: Arc<AppConfig>) -> Self {
         let archive_lock = Arc::new(ArchiveLock::new(&repo_root));
+        let git_lock = Arc::new(Mutex::new(()));
+        let repo_cache = Arc::new(RepoCache::default());
+        let archive_queue = Arc::new(ArchiveQueue::new(
+            repo_cache.clone(),
+            git_lock.clone(),
+            app_config.archive.clone(),
+        ));
         ModelManager {
-            db,
-            repo_root: repo_root.clone(),
-            git_lock: Arc::new(Mutex::new(())),
-            repo_cache: Arc::new(RepoCache::default()),
+            pool: Arc::new(DbPool::single(db)),
+            tx_lock: Arc::new(Mutex::new(())),
+            repo_root,
+            git_lock,
+            repo_cache,
             archive_lock,
             app_config,
+            events: Arc::new(EventBus::default()),
+            archive_queue,
         }
     }
 
@@ -166,6 +226,50 @@ impl ModelManager {
         }
     }
 
+    /// Release resources before the process exits.
+    ///
+    /// Flushes the archive queue, closes the event bus so streaming subscribers
+    /// finish, then checkpoints the WAL into the main database file so no
+    /// `-wal` data is left behind.
+    pub async fn shutdown(&self) -> Result<()> {
+        self.archive_queue.flush().await;
+        self.events.close();
+        let mut rows = self
+            .db()
+            .query("PRAGMA wal_checkpoint(TRUNCATE)", ())
+            .await?;
+        while rows.next().await?.is_some() {}
+        info!("Database WAL checkpointed on shutdown");
+        Ok(())
+    }
+
+    /// Write a consistent snapshot of the database to `path` and return its
+    /// size in bytes.
+    ///
+    /// Uses `VACUUM INTO`, which copies one read transaction: writes issued
+    /// meanwhile wait for the copy and land in the next snapshot, never half
+    /// in this one. The snapshot is compacted and needs no `-wal` file.
+    /// Fails if `path` already exists.
+    pub async fn backup_to(&self, path: &Path) -> Result<u64> {
+        if path.exists() {
+            return Err(crate::Error::InvalidInput(format!(
+                "backup file already exists: {}",
+                path.display()
+            )));
+        }
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        // VACUUM fails inside a transaction; holding the lock keeps explicit
+        // transactions off the writer until the copy is done
+        let _guard = self.tx_lock.lock().await;
+        self.db()
+            .execute("VACUUM INTO ?1", [path.display().to_string()])
+            .await?;
+        Ok(std::fs::metadata(path)?.len())
+    }
+
     /// Get a cached repository handle for the repo_root.
     ///
     /// Uses LRU cache to prevent file descriptor exhaustion.
@@ -181,6 +285,42 @@ impl ModelManager {
         self.repo_cache.get(&self.repo_root).await
     }
 
+    /// Working directory of the repository that holds `slug`'s archive.
+    ///
+    /// `repo_root` in the shared layout, `repo_root/{slug}` in the per-project
+    /// layout. Paths inside either repository are the same
+    /// (`projects/{slug}/...`), so a per-project repo can be handed over as is.
+    pub fn project_repo_root(&self, slug: &str) -> PathBuf {
+        match self.app_config.archive.layout {
+            ArchiveLayout::Shared => self.repo_root.clone(),
+            ArchiveLayout::PerProject => self.repo_root.join(slug),
+        }
+    }
+
+    /// Repository to read a project's history from, without creating one.
+    ///
+    /// Falls back to the shared repository when the project has no
+    /// per-project repository yet (e.g. history written before the switch).
+    pub async fn get_project_history_repo(&self, slug: &str) -> Result<Arc<Mutex<Repository>>> {
+        let root = self.project_repo_root(slug);
+        if root != self.repo_root && root.join(".git").exists() {
+            return self.repo_cache.get(&root).await;
+        }
+        self.get_repo().await
+    }
+
+    /// Get a cached repository handle for a project's archive.
+    ///
+    /// In the per-project layout the repository is created on first use.
+    pub async fn get_project_repo(&self, slug: &str) -> Result<Arc<Mutex<Repository>>> {
+        match self.app_config.archive.layout {
+            ArchiveLayout::Shared => self.get_repo().await,
+            ArchiveLayout::PerProject => {
+                open_archive_repo(&self.repo_cache, &self.project_repo_root(slug)).await
+            }
+        }
+    }
+
     /// Acquire advisory file lock for longer archive operations.
     ///
     /// The returned guard automatically releases the lock when dropped.
@@ -201,22 +341,79 @@ impl ModelManager {
         self.archive_lock.acquire(agent, timeout).await
     }
 
-    /// Returns the sqlx db pool reference.
+    /// Begin an explicit transaction on the shared connection.
+    ///
+    /// Hold the returned guard until the transaction is committed or dropped
+    /// (which rolls it back).
+    pub(in crate::model) async fn begin_tx(
+        &self,
+    ) -> Result<(MutexGuard<'_, ()>, libsql::Transaction)> {
+        let guard = self.tx_lock.lock().await;
+        let tx = self.db().transaction().await?;
+        Ok((guard, tx))
+    }
+
+    /// Returns the writer connection: use it for writes and transactions.
     /// (Only for the model layer)
     pub(in crate::model) fn db(&self) -> &Db {
-        &self.db
+        self.pool.writer()
+    }
+
+    /// Returns a read-only pooled connection for plain SELECTs.
+    /// (Only for the model layer)
+    pub(in crate::model) fn db_read(&self) -> &Db {
+        self.pool.reader()
+    }
+
+    /// Backoff for writes that hit "database is locked", from
+    /// `database.busy_retry_*`. (Only for the model layer)
+    pub(in crate::model) fn busy_retry(&self) -> RetryPolicy {
+        RetryPolicy::from_config(&self.app_config.database)
     }
 
     /// Returns the db connection for integration tests
     /// This should only be used in test code
     pub fn db_for_test(&self) -> &Db {
-        &self.db
+        self.pool.writer()
     }
 
     /// Health check - verify database connectivity
     pub async fn health_check(&self) -> Result<bool> {
-        let stmt = self.db.prepare("SELECT 1").await?;
+        let stmt = self.db().prepare("SELECT 1").await?;
         let mut rows = stmt.query(()).await?;
         Ok(rows.next().await?.is_some())
     }
+
+    /// Checks that `repo_root` exists and opens as a Git repository.
+    pub fn archive_health_check(&self) -> Result<()> {
+        if !self.repo_root.is_dir() {
+            return Err(crate::Error::Io(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("archive root {} is missing", self.repo_root.display()),
+            )));
+        }
+        crate::store::git_store::open_repo(&self.repo_root).map(|_| ())
+    }
+}
+
+/// Open (creating if needed) the archive repository at `root` through the cache.
+///
+/// New repositories get the same `.gitattributes` commit as the shared archive.
+pub(crate) async fn open_archive_repo(
+    repo_cache: &RepoCache,
+    root: &Path,
+) -> Result<Arc<Mutex<Repository>>> {
+    if !root.join(".git").exists() {
+        std::fs::create_dir_all(root)?;
+        let repo = store::git_store::init_or_open_repo(root)?;
+        store::git_store::commit_file(
+            &repo,
+            Path::new(".gitattributes"),
+            "*.json text\n*.md text\n",
+            "chore: initialize archive",
+            store::git_store::SERVICE_NAME,
+            store::git_store::SERVICE_EMAIL,
+        )?;
+    }
+    repo_cache.get(root).await
 }
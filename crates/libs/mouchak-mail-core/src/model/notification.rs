@@ -0,0 +1,378 @@
+//! Notification center for the web UI.
+//!
+//! Notifications are not stored; [`NotificationBmc::list_for_actor`] derives
+//! them on every call from four things that need the caller's attention:
+//!
+//! - messages the actor sent with `ack_required` that not every recipient
+//!   has acknowledged yet
+//! - messages to the actor that it has yet to acknowledge, as listed by
+//!   [`MessageBmc::list_pending_acks_for_agent`]
+//! - unread high or urgent messages addressed to the actor
+//! - active file reservations of the actor that overlap someone else's
+//!   reservation or have agents queued behind them
+//!
+//! The actor is matched by agent name in every accessible project. Each
+//! notification has a stable `key`; dismissing it records the key in
+//! `notifications_read` for that actor only, and leaves the underlying
+//! message or reservation untouched.
+
+use crate::Result;
+use crate::ctx::Ctx;
+use crate::model::ModelManager;
+use crate::model::message::MessageBmc;
+use crate::utils::pathspec::paths_conflict;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use utoipa::ToSchema;
+
+/// Actor used when the request context names none, e.g. the web UI with
+/// auth disabled. Matches the sender the overseer composer uses.
+pub const DEFAULT_NOTIFICATION_ACTOR: &str = "Overseer";
+
+/// Maximum notifications returned per source.
+const MAX_PER_SOURCE: i64 = 50;
+
+/// What a notification is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    /// A message the actor sent still waits for acknowledgements.
+    AckPending,
+    /// A message to the actor waits for the actor's acknowledgement.
+    AckRequested,
+    /// A high or urgent message to the actor is unread.
+    UrgentUnread,
+    /// One of the actor's reservations overlaps another agent's.
+    ReservationConflict,
+}
+
+/// A single entry in the notification center.
+///
+/// # Fields
+///
+/// - `key` - Stable identifier, used for dismissal
+/// - `kind` - Source of the notification
+/// - `project_slug` - Project the message or reservation belongs to
+/// - `title` - One-line summary
+/// - `detail` - Subject line or reservation path
+/// - `message_id` / `thread_id` - Message to link to, if any
+/// - `reservation_id` - The actor's reservation, for conflicts
+/// - `created_ts` - When the underlying message or reservation was created
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct Notification {
+    pub key: String,
+    pub kind: NotificationKind,
+    pub project_slug: String,
+    pub title: String,
+    pub detail: String,
+    pub message_id: Option<i64>,
+    pub thread_id: Option<String>,
+    pub reservation_id: Option<i64>,
+    pub created_ts: NaiveDateTime,
+}
+
+/// Backend Model Controller for the notification center.
+pub struct NotificationBmc;
+
+impl NotificationBmc {
+    /// Name notifications are computed and dismissed for.
+    ///
+    /// The context's agent name if it has one, then its actor, then
+    /// [`DEFAULT_NOTIFICATION_ACTOR`].
+    pub fn actor_name(ctx: &Ctx) -> String {
+        ctx.agent_name()
+            .or_else(|| ctx.actor().map(|actor| actor.name()))
+            .unwrap_or(DEFAULT_NOTIFICATION_ACTOR)
+            .to_string()
+    }
+
+    /// Lists the actor's undismissed notifications, newest first.
+    ///
+    /// Projects outside the context's scope are skipped.
+    pub async fn list_for_actor(ctx: &Ctx, mm: &ModelManager) -> Result<Vec<Notification>> {
+        let actor = Self::actor_name(ctx);
+        let dismissed = Self::dismissed_keys(mm, &actor).await?;
+
+        let mut notifications = Self::pending_acks(mm, &actor).await?;
+        notifications.extend(Self::acks_requested(ctx, mm, &actor).await?);
+        notifications.extend(Self::urgent_unread(mm, &actor).await?);
+        notifications.extend(Self::reservation_conflicts(mm, &actor).await?);
+
+        notifications
+            .retain(|n| ctx.can_access_project(&n.project_slug) && !dismissed.contains(&n.key));
+        notifications.sort_by(|a, b| b.created_ts.cmp(&a.created_ts).then(b.key.cmp(&a.key)));
+        Ok(notifications)
+    }
+
+    /// Hides notifications for the actor.
+    ///
+    /// # Returns
+    /// Number of keys newly dismissed; keys dismissed before are ignored.
+    pub async fn dismiss(ctx: &Ctx, mm: &ModelManager, keys: &[String]) -> Result<usize> {
+        let actor = Self::actor_name(ctx);
+        let db = mm.db();
+        let mut dismissed = 0;
+        for key in keys {
+            dismissed += db
+                .execute(
+                    "INSERT OR IGNORE INTO notifications_read (actor, notification_key) VALUES (?, ?)",
+                    (actor.as_str(), key.as_str()),
+                )
+                .await?;
+        }
+        Ok(dismissed as usize)
+    }
+
+    async fn dismissed_keys(mm: &ModelManager, actor: &str) -> Result<HashSet<String>> {
+        let db = mm.db_read();
+        let stmt = db
+            .prepare("SELECT notification_key FROM notifications_read WHERE actor = ?")
+            .await?;
+        let mut rows = stmt.query([actor]).await?;
+        let mut keys = HashSet::new();
+        while let Some(row) = rows.next().await? {
+            keys.insert(row.get::<String>(0)?);
+        }
+        Ok(keys)
+    }
+
+    async fn pending_acks(mm: &ModelManager, actor: &str) -> Result<Vec<Notification>> {
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+                SELECT m.id, p.slug, m.thread_id, m.subject, m.created_ts,
+                       COUNT(*) AS total,
+                       SUM(CASE WHEN mr.ack_ts IS NULL THEN 1 ELSE 0 END) AS pending
+                FROM visible_messages AS m
+                JOIN agents AS a ON a.id = m.sender_id
+                JOIN projects AS p ON p.id = m.project_id
+                JOIN message_recipients AS mr ON mr.message_id = m.id
+                WHERE a.name = ? AND m.ack_required = 1
+                GROUP BY m.id
+                HAVING pending > 0
+                ORDER BY m.created_ts DESC
+                LIMIT ?
+                "#,
+            )
+            .await?;
+        let mut rows = stmt.query((actor, MAX_PER_SOURCE)).await?;
+
+        let mut notifications = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let message_id: i64 = row.get(0)?;
+            let created_ts: String = row.get(4)?;
+            let total: i64 = row.get(5)?;
+            let pending: i64 = row.get(6)?;
+            notifications.push(Notification {
+                key: format!("ack:{}", message_id),
+                kind: NotificationKind::AckPending,
+                project_slug: row.get(1)?,
+                title: format!("{} of {} acknowledgements pending", pending, total),
+                detail: row.get(3)?,
+                message_id: Some(message_id),
+                thread_id: row.get(2)?,
+                reservation_id: None,
+                created_ts: crate::utils::parse_timestamp(&created_ts, "messages.created_ts"),
+            });
+        }
+        Ok(notifications)
+    }
+
+    /// Acknowledgements the actor owes, in every accessible project where an
+    /// agent has the actor's name. Each carries the reader's project, whose
+    /// inbox holds the message. Keeps the oldest when there are too many.
+    async fn acks_requested(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        actor: &str,
+    ) -> Result<Vec<Notification>> {
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+                SELECT a.id, a.project_id, p.slug
+                FROM agents AS a
+                JOIN projects AS p ON p.id = a.project_id
+                WHERE a.name = ?
+                "#,
+            )
+            .await?;
+        let mut rows = stmt.query([actor]).await?;
+        let mut agents = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let slug: String = row.get(2)?;
+            if ctx.can_access_project(&slug) {
+                agents.push((row.get::<i64>(0)?, row.get::<i64>(1)?, slug));
+            }
+        }
+
+        let mut notifications = Vec::new();
+        for (agent_id, project_id, slug) in agents {
+            let pending =
+                MessageBmc::list_pending_acks_for_agent(ctx, mm, project_id, agent_id).await?;
+            for ack in pending {
+                notifications.push(Notification {
+                    key: format!("ack_requested:{}:{}", ack.message_id, agent_id),
+                    kind: NotificationKind::AckRequested,
+                    project_slug: slug.clone(),
+                    title: format!("{} asked for your acknowledgement", ack.sender_name),
+                    detail: ack.subject,
+                    message_id: Some(ack.message_id),
+                    thread_id: ack.thread_id,
+                    reservation_id: None,
+                    created_ts: ack.created_ts,
+                });
+            }
+        }
+        notifications.truncate(MAX_PER_SOURCE as usize);
+        Ok(notifications)
+    }
+
+    async fn urgent_unread(mm: &ModelManager, actor: &str) -> Result<Vec<Notification>> {
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+                SELECT m.id, p.slug, m.thread_id, m.subject, m.created_ts, m.importance,
+                       sender.name, mr.agent_id
+                FROM message_recipients AS mr
+                JOIN agents AS me ON me.id = mr.agent_id
+                JOIN visible_messages AS m ON m.id = mr.message_id
+                JOIN agents AS sender ON sender.id = m.sender_id
+                JOIN projects AS p ON p.id = m.project_id
+                WHERE me.name = ? AND mr.read_ts IS NULL
+                  AND m.importance IN ('high', 'urgent')
+                ORDER BY m.created_ts DESC
+                LIMIT ?
+                "#,
+            )
+            .await?;
+        let mut rows = stmt.query((actor, MAX_PER_SOURCE)).await?;
+
+        let mut notifications = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let message_id: i64 = row.get(0)?;
+            let created_ts: String = row.get(4)?;
+            let importance: String = row.get(5)?;
+            let sender: String = row.get(6)?;
+            let agent_id: i64 = row.get(7)?;
+            notifications.push(Notification {
+                key: format!("unread:{}:{}", message_id, agent_id),
+                kind: NotificationKind::UrgentUnread,
+                project_slug: row.get(1)?,
+                title: format!("Unread {} message from {}", importance, sender),
+                detail: row.get(3)?,
+                message_id: Some(message_id),
+                thread_id: row.get(2)?,
+                reservation_id: None,
+                created_ts: crate::utils::parse_timestamp(&created_ts, "messages.created_ts"),
+            });
+        }
+        Ok(notifications)
+    }
+
+    /// Overlaps between the actor's active reservations and other agents'
+    /// active or queued ones. Overlap only matters when either side is
+    /// exclusive, as for [`crate::model::reservation_queue`].
+    async fn reservation_conflicts(mm: &ModelManager, actor: &str) -> Result<Vec<Notification>> {
+        let now = chrono::Utc::now()
+            .naive_utc()
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        let db = mm.db_read();
+
+        // The actor's reservations plus every other claim in the same projects
+        let stmt = db
+            .prepare(
+                r#"
+                SELECT 'held', fr.id, fr.project_id, p.slug, a.name, fr.path_pattern,
+                       fr.exclusive, fr.created_ts
+                FROM file_reservations AS fr
+                JOIN agents AS a ON a.id = fr.agent_id
+                JOIN projects AS p ON p.id = fr.project_id
+                WHERE fr.released_ts IS NULL AND fr.expires_ts > ?1
+                  AND fr.project_id IN (
+                      SELECT r.project_id FROM file_reservations AS r
+                      JOIN agents AS ra ON ra.id = r.agent_id
+                      WHERE ra.name = ?2 AND r.released_ts IS NULL AND r.expires_ts > ?1
+                  )
+                UNION ALL
+                SELECT 'queued', q.id, q.project_id, p.slug, a.name, q.path_pattern,
+                       q.exclusive, q.created_ts
+                FROM reservation_queue AS q
+                JOIN agents AS a ON a.id = q.agent_id
+                JOIN projects AS p ON p.id = q.project_id
+                WHERE q.wait_until > ?1
+                "#,
+            )
+            .await?;
+        let mut rows = stmt.query((now, actor)).await?;
+
+        struct Claim {
+            queued: bool,
+            id: i64,
+            project_id: i64,
+            project_slug: String,
+            agent_name: String,
+            path_pattern: String,
+            exclusive: bool,
+            created_ts: NaiveDateTime,
+        }
+        let mut claims = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let source: String = row.get(0)?;
+            let created_ts: String = row.get(7)?;
+            claims.push(Claim {
+                queued: source == "queued",
+                id: row.get(1)?,
+                project_id: row.get(2)?,
+                project_slug: row.get(3)?,
+                agent_name: row.get(4)?,
+                path_pattern: row.get(5)?,
+                exclusive: row.get::<i64>(6)? != 0,
+                created_ts: crate::utils::parse_timestamp(&created_ts, "created_ts"),
+            });
+        }
+
+        let mut notifications = Vec::new();
+        for mine in claims.iter().filter(|c| !c.queued && c.agent_name == actor) {
+            for other in &claims {
+                if other.agent_name == actor
+                    || other.project_id != mine.project_id
+                    || !(mine.exclusive || other.exclusive)
+                    || !paths_conflict(&mine.path_pattern, &other.path_pattern)
+                {
+                    continue;
+                }
+                let (key, title) = if other.queued {
+                    (
+                        format!("queued:{}:{}", mine.id, other.id),
+                        format!("{} is waiting for your reservation", other.agent_name),
+                    )
+                } else {
+                    (
+                        format!("conflict:{}:{}", mine.id, other.id),
+                        format!(
+                            "{} holds an overlapping reservation on {}",
+                            other.agent_name, other.path_pattern
+                        ),
+                    )
+                };
+                notifications.push(Notification {
+                    key,
+                    kind: NotificationKind::ReservationConflict,
+                    project_slug: mine.project_slug.clone(),
+                    title,
+                    detail: mine.path_pattern.clone(),
+                    message_id: None,
+                    thread_id: None,
+                    reservation_id: Some(mine.id),
+                    created_ts: mine.created_ts.max(other.created_ts),
+                });
+            }
+        }
+        Ok(notifications)
+    }
+}
@@ -764,7 +764,7 @@ impl OrchestrationBmc {
             chrono::Utc::now() - chrono::Duration::from_std(stale_threshold).unwrap_or_default();
 
         // Get all threads for the project
-        let threads = MessageBmc::list_threads(ctx, mm, project_id, 100).await?;
+        let threads = MessageBmc::list_threads(ctx, mm, project_id, 100, None).await?;
 
         for thread in threads {
             let messages =
@@ -819,7 +819,7 @@ impl OrchestrationBmc {
         let cutoff =
             chrono::Utc::now() - chrono::Duration::from_std(stale_threshold).unwrap_or_default();
 
-        let threads = MessageBmc::list_threads(ctx, mm, project_id, 100).await?;
+        let threads = MessageBmc::list_threads(ctx, mm, project_id, 100, None).await?;
 
         for thread in threads {
             let messages =
@@ -894,12 +894,18 @@ mod tests {
             thread_id: Some("test-thread".to_string()),
             subject: subject.to_string(),
             body_md: "Test body".to_string(),
+            snippet: String::new(),
             importance: "normal".to_string(),
             ack_required: false,
             created_ts: NaiveDateTime::parse_from_str("2024-01-01 00:00:00", "%Y-%m-%d %H:%M:%S")
                 .unwrap(),
             attachments: vec![],
             sender_name: "test-sender".to_string(),
+            project_slug: None,
+            thread_seq: None,
+            forwarded_from_id: None,
+            returned_from_snooze: false,
+            needs_ack: false,
         }
     }
 
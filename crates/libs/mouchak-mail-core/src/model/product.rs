@@ -104,7 +104,7 @@ impl ProductBmc {
 
     /// Get product by UID
     pub async fn get_by_uid(_ctx: &Ctx, mm: &ModelManager, product_uid: &str) -> Result<Product> {
-        let db = mm.db();
+        let db = mm.db_read();
         let stmt = db
             .prepare("SELECT id, product_uid, name, created_at FROM products WHERE product_uid = ?")
             .await?;
@@ -128,7 +128,7 @@ impl ProductBmc {
 
     /// List all products with their linked project IDs
     pub async fn list_all(_ctx: &Ctx, mm: &ModelManager) -> Result<Vec<ProductWithProjects>> {
-        let db = mm.db();
+        let db = mm.db_read();
         let stmt = db
             .prepare(
                 "SELECT id, product_uid, name, created_at FROM products ORDER BY created_at DESC",
@@ -219,7 +219,7 @@ impl ProductBmc {
         mm: &ModelManager,
         product_id: i64,
     ) -> Result<Vec<i64>> {
-        let db = mm.db();
+        let db = mm.db_read();
         let stmt = db
             .prepare("SELECT project_id FROM product_project_links WHERE product_id = ?")
             .await?;
@@ -239,7 +239,7 @@ impl ProductBmc {
         mm: &ModelManager,
         project_id: i64,
     ) -> Result<Vec<Product>> {
-        let db = mm.db();
+        let db = mm.db_read();
         // Join products and product_project_links
         let stmt = db
             .prepare(
@@ -39,12 +39,17 @@
 
 use crate::Result;
 use crate::model::ModelManager;
+use crate::model::audit::{AuditAction, AuditBmc};
+use crate::model::project_version::ProjectVersionBmc;
 use crate::store::git_store;
 use crate::types::ProjectId;
 use crate::utils::mistake_detection::suggest_similar;
 use chrono::NaiveDateTime;
+use mouchak_mail_common::config::ArchiveLayout;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use utoipa::ToSchema;
 
 /// A project workspace for AI agents.
 ///
@@ -69,6 +74,91 @@ pub struct Project {
     pub created_at: NaiveDateTime,
 }
 
+/// Insert attempts [`ProjectBmc::create_with_unique_slug`] makes before
+/// giving up on concurrent creates.
+const MAX_SLUG_ATTEMPTS: usize = 16;
+
+/// Activity figures for a project, for dashboards.
+///
+/// # Fields
+///
+/// - `project_id` - Project the figures belong to
+/// - `agent_count` - Registered agents
+/// - `message_count` - Delivered messages
+/// - `messages_last_24h` / `messages_last_7d` - Messages sent in those windows
+/// - `active_reservations` - Unreleased, unexpired file reservations
+/// - `thread_count` - Distinct message threads
+/// - `last_activity_ts` - Latest message or agent activity, if any
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ProjectStats {
+    #[schema(value_type = i64)]
+    pub project_id: ProjectId,
+    pub agent_count: i64,
+    pub message_count: i64,
+    pub messages_last_24h: i64,
+    pub messages_last_7d: i64,
+    pub active_reservations: i64,
+    pub thread_count: i64,
+    pub last_activity_ts: Option<NaiveDateTime>,
+}
+
+impl ProjectStats {
+    fn empty(project_id: ProjectId) -> Self {
+        Self {
+            project_id,
+            agent_count: 0,
+            message_count: 0,
+            messages_last_24h: 0,
+            messages_last_7d: 0,
+            active_reservations: 0,
+            thread_count: 0,
+            last_activity_ts: None,
+        }
+    }
+
+    fn touch(&mut self, ts: Option<NaiveDateTime>) {
+        if ts > self.last_activity_ts {
+            self.last_activity_ts = ts;
+        }
+    }
+}
+
+/// How long a project keeps messages in the database.
+///
+/// Older messages are pruned by
+/// [`RetentionBmc::prune`](crate::model::retention::RetentionBmc::prune) once
+/// their archive file is confirmed; the Git archive keeps them for good.
+///
+/// # Fields
+///
+/// - `retention_days` - Age in days after which a message may be pruned
+/// - `keep_unread` - Keep messages some recipient hasn't read yet
+/// - `keep_ack_pending` - Keep ack-required messages some recipient hasn't
+///   acknowledged yet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct RetentionPolicy {
+    pub retention_days: i64,
+    #[serde(default = "default_keep")]
+    pub keep_unread: bool,
+    #[serde(default = "default_keep")]
+    pub keep_ack_pending: bool,
+}
+
+fn default_keep() -> bool {
+    true
+}
+
+impl RetentionPolicy {
+    /// Prune after `retention_days`, keeping unread and ack-pending messages.
+    pub fn days(retention_days: i64) -> Self {
+        Self {
+            retention_days,
+            keep_unread: true,
+            keep_ack_pending: true,
+        }
+    }
+}
+
 /// Backend Model Controller for Project operations.
 ///
 /// Manages projects which are the top-level organizational unit for agents and messages.
@@ -92,7 +182,9 @@ impl ProjectBmc {
     /// The created project's database ID
     ///
     /// # Errors
-    /// Returns an error if project slug already exists
+    /// Returns `Error::SlugConflict` if the slug is already taken; use
+    /// [`create_with_unique_slug`](Self::create_with_unique_slug) to pick a
+    /// free one instead
     ///
     /// # Example
     /// ```no_run
@@ -110,20 +202,32 @@ impl ProjectBmc {
         slug: &str,
         human_key: &str,
     ) -> Result<ProjectId> {
+        if !ctx.can_access_project(slug) {
+            return Err(crate::Error::Forbidden(slug.to_string()));
+        }
+
         let db = mm.db();
 
         // Execute insert
         let stmt = db
             .prepare("INSERT INTO projects (slug, human_key) VALUES (?, ?) RETURNING id")
             .await?;
-        let mut rows = stmt.query([slug, human_key]).await?;
-
-        let id: i64 = if let Some(row) = rows.next().await? {
-            row.get::<i64>(0)?
-        } else {
-            return Err(crate::Error::InvalidInput(
-                "Failed to create project".into(),
-            ));
+        // With RETURNING, a constraint violation surfaces on the first row
+        let row = match stmt.query([slug, human_key]).await {
+            Ok(mut rows) => rows.next().await,
+            Err(e) => Err(e),
+        };
+        let id: i64 = match row {
+            Ok(Some(row)) => row.get::<i64>(0)?,
+            Ok(None) => {
+                return Err(crate::Error::InvalidInput(
+                    "Failed to create project".into(),
+                ));
+            }
+            Err(e) if e.to_string().contains("UNIQUE constraint failed") => {
+                return Err(crate::Error::SlugConflict(slug.to_string()));
+            }
+            Err(e) => return Err(e.into()),
         };
 
         Self::ensure_archive(mm, slug).await?;
@@ -134,16 +238,73 @@ impl ProjectBmc {
         Ok(ProjectId::new(id))
     }
 
+    /// Creates a project, suffixing the slug if another project has it.
+    ///
+    /// Different human keys can slugify to the same string (e.g.
+    /// `/work/My Repo` and `/work/my-repo`). The first free slug of `slug`,
+    /// `slug-2`, `slug-3`, ... is used, so the choice only depends on which
+    /// projects already exist.
+    ///
+    /// # Returns
+    /// The created project's ID and the slug it got
+    pub async fn create_with_unique_slug(
+        ctx: &crate::Ctx,
+        mm: &ModelManager,
+        slug: &str,
+        human_key: &str,
+    ) -> Result<(ProjectId, String)> {
+        let taken = Self::slugs_with_prefix(mm, slug).await?;
+        let mut candidates = std::iter::once(slug.to_string())
+            .chain((2..).map(|n| format!("{}-{}", slug, n)))
+            .filter(|candidate| !taken.contains(candidate));
+
+        // A concurrent create can take the candidate between the lookup and
+        // the insert; move on to the next one
+        for _ in 0..MAX_SLUG_ATTEMPTS {
+            let Some(candidate) = candidates.next() else {
+                break;
+            };
+            match Self::create(ctx, mm, &candidate, human_key).await {
+                Ok(id) => return Ok((id, candidate)),
+                Err(crate::Error::SlugConflict(_)) => continue,
+                Err(e) => return Err(e),
+            }
+        }
+        Err(crate::Error::SlugConflict(slug.to_string()))
+    }
+
+    /// Slugs equal to `slug` or starting with `slug-`.
+    async fn slugs_with_prefix(mm: &ModelManager, slug: &str) -> Result<HashSet<String>> {
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT slug FROM projects
+            WHERE slug = ?1 OR substr(slug, 1, length(?1) + 1) = ?1 || '-'
+            "#,
+            )
+            .await?;
+        let mut rows = stmt.query([slug]).await?;
+
+        let mut slugs = HashSet::new();
+        while let Some(row) = rows.next().await? {
+            slugs.insert(row.get::<String>(0)?);
+        }
+        Ok(slugs)
+    }
+
     /// Lists all projects ordered by creation time (newest first).
     ///
+    /// Projects outside the context's scope are omitted.
+    ///
     /// # Arguments
-    /// * `_ctx` - Request context
+    /// * `ctx` - Request context
     /// * `mm` - ModelManager providing database access
     ///
     /// # Returns
-    /// Vector of all projects (may be empty)
-    pub async fn list_all(_ctx: &crate::Ctx, mm: &ModelManager) -> Result<Vec<Project>> {
-        let db = mm.db();
+    /// Vector of projects the context may access (may be empty)
+    pub async fn list_all(ctx: &crate::Ctx, mm: &ModelManager) -> Result<Vec<Project>> {
+        let db = mm.db_read();
         let stmt = db
             .prepare(
                 "SELECT id, slug, human_key, created_at FROM projects ORDER BY created_at DESC",
@@ -157,9 +318,14 @@ impl ProjectBmc {
             let created_at = NaiveDateTime::parse_from_str(&created_at_str, "%Y-%m-%d %H:%M:%S")
                 .unwrap_or_default();
 
+            let slug: String = row.get(1)?;
+            if !ctx.can_access_project(&slug) {
+                continue;
+            }
+
             projects.push(Project {
                 id: ProjectId::new(row.get(0)?),
-                slug: row.get(1)?,
+                slug,
                 human_key: row.get(2)?,
                 created_at,
             });
@@ -170,7 +336,7 @@ impl ProjectBmc {
     /// Retrieves a project by its slug (URL-safe identifier).
     ///
     /// # Arguments
-    /// * `_ctx` - Request context  
+    /// * `ctx` - Request context
     /// * `mm` - ModelManager
     /// * `slug` - Project slug (e.g., "my-project")
     ///
@@ -178,9 +344,10 @@ impl ProjectBmc {
     /// The project data
     ///
     /// # Errors
-    /// Returns `Error::ProjectNotFound` if slug doesn't exist
-    pub async fn get_by_slug(_ctx: &crate::Ctx, mm: &ModelManager, slug: &str) -> Result<Project> {
-        let db = mm.db();
+    /// Returns `Error::ProjectNotFound` if slug doesn't exist, or
+    /// `Error::Forbidden` if it is outside the context's scope
+    pub async fn get_by_slug(ctx: &crate::Ctx, mm: &ModelManager, slug: &str) -> Result<Project> {
+        let db = mm.db_read();
         // Note: We are mapping manually because libsql doesn't have FromRow like sqlx yet
         let stmt = db
             .prepare("SELECT id, slug, human_key, created_at FROM projects WHERE slug = ?")
@@ -194,12 +361,15 @@ impl ProjectBmc {
             let created_at = NaiveDateTime::parse_from_str(&created_at_str, "%Y-%m-%d %H:%M:%S")
                 .unwrap_or_default(); // Simplification for MVP
 
-            Ok(Project {
-                id: ProjectId::new(row.get(0)?),
-                slug: row.get(1)?,
-                human_key: row.get(2)?,
-                created_at,
-            })
+            Self::check_access(
+                ctx,
+                Project {
+                    id: ProjectId::new(row.get(0)?),
+                    slug: row.get(1)?,
+                    human_key: row.get(2)?,
+                    created_at,
+                },
+            )
         } else {
             // Fetch all project slugs for suggestions
             let stmt = db.prepare("SELECT slug FROM projects").await?;
@@ -223,7 +393,7 @@ impl ProjectBmc {
     /// Retrieves a project by its human-readable key.
     ///
     /// # Arguments
-    /// * `_ctx` - Request context
+    /// * `ctx` - Request context
     /// * `mm` - ModelManager
     /// * `human_key` - Human-readable project name
     ///
@@ -231,13 +401,14 @@ impl ProjectBmc {
     /// The project data
     ///
     /// # Errors
-    /// Returns `Error::ProjectNotFound` if human_key doesn't exist
+    /// Returns `Error::ProjectNotFound` if human_key doesn't exist, or
+    /// `Error::Forbidden` if it is outside the context's scope
     pub async fn get_by_human_key(
-        _ctx: &crate::Ctx,
+        ctx: &crate::Ctx,
         mm: &ModelManager,
         human_key: &str,
     ) -> Result<Project> {
-        let db = mm.db();
+        let db = mm.db_read();
         let stmt = db
             .prepare("SELECT id, slug, human_key, created_at FROM projects WHERE human_key = ?")
             .await?;
@@ -248,12 +419,15 @@ impl ProjectBmc {
             let created_at = NaiveDateTime::parse_from_str(&created_at_str, "%Y-%m-%d %H:%M:%S")
                 .unwrap_or_default();
 
-            Ok(Project {
-                id: ProjectId::new(row.get(0)?),
-                slug: row.get(1)?,
-                human_key: row.get(2)?,
-                created_at,
-            })
+            Self::check_access(
+                ctx,
+                Project {
+                    id: ProjectId::new(row.get(0)?),
+                    slug: row.get(1)?,
+                    human_key: row.get(2)?,
+                    created_at,
+                },
+            )
         } else {
             // Fetch all human_keys for suggestions
             let stmt = db.prepare("SELECT human_key FROM projects").await?;
@@ -294,24 +468,32 @@ impl ProjectBmc {
         mm: &ModelManager,
         identifier: &str,
     ) -> Result<Project> {
+        // A match outside the context's scope is reported as Forbidden rather
+        // than falling through to the next lookup.
         // First try by slug
-        if let Ok(project) = Self::get_by_slug(ctx, mm, identifier).await {
-            return Ok(project);
+        match Self::get_by_slug(ctx, mm, identifier).await {
+            Ok(project) => return Ok(project),
+            Err(e @ crate::Error::Forbidden(_)) => return Err(e),
+            Err(_) => {}
         }
 
         // Then try by human_key
-        if let Ok(project) = Self::get_by_human_key(ctx, mm, identifier).await {
-            return Ok(project);
+        match Self::get_by_human_key(ctx, mm, identifier).await {
+            Ok(project) => return Ok(project),
+            Err(e @ crate::Error::Forbidden(_)) => return Err(e),
+            Err(_) => {}
         }
 
         // Finally, try slugified version of the identifier as slug
         let slugified = crate::utils::slugify(identifier);
-        if let Ok(project) = Self::get_by_slug(ctx, mm, &slugified).await {
-            return Ok(project);
+        match Self::get_by_slug(ctx, mm, &slugified).await {
+            Ok(project) => return Ok(project),
+            Err(e @ crate::Error::Forbidden(_)) => return Err(e),
+            Err(_) => {}
         }
 
         // Fetch both slugs and human_keys for suggestions
-        let db = mm.db();
+        let db = mm.db_read();
         let stmt = db.prepare("SELECT slug, human_key FROM projects").await?;
         let mut rows = stmt.query(()).await?;
         let mut all_identifiers: Vec<String> = Vec::new();
@@ -335,12 +517,17 @@ impl ProjectBmc {
     /// Ensures the Git archive directory structure exists for a project.
     ///
     /// Creates the project directory and initializes `.gitattributes` if needed.
-    /// This is called automatically during project creation.
+    /// This is called automatically during project creation. With the
+    /// per-project layout nothing is created until the first archive write.
     ///
     /// # Arguments
     /// * `mm` - ModelManager providing Git access
     /// * `slug` - Project slug for directory naming
     pub async fn ensure_archive(mm: &ModelManager, slug: &str) -> Result<()> {
+        if mm.app_config.archive.layout == ArchiveLayout::PerProject {
+            return Ok(());
+        }
+
         let repo_root = &mm.repo_root;
         let project_root = repo_root.join("projects").join(slug);
 
@@ -366,8 +553,8 @@ impl ProjectBmc {
                 Path::new(attributes_path),
                 "*.json text\n*.md text\n",
                 "chore: initialize archive",
-                "mcp-bot",
-                "mcp-bot@localhost",
+                git_store::SERVICE_NAME,
+                git_store::SERVICE_EMAIL,
             )?;
         }
 
@@ -388,7 +575,7 @@ impl ProjectBmc {
         mm: &ModelManager,
         project_id: ProjectId,
     ) -> Result<i64> {
-        let db = mm.db();
+        let db = mm.db_read();
         let stmt = db
             .prepare("SELECT COUNT(*) FROM messages WHERE project_id = ?")
             .await?;
@@ -400,10 +587,245 @@ impl ProjectBmc {
         }
     }
 
+    /// Returns the project's retention policy, if one is set.
+    pub async fn get_retention(
+        _ctx: &crate::Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+    ) -> Result<Option<RetentionPolicy>> {
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                "SELECT retention_days, keep_unread, keep_ack_pending FROM project_retention WHERE project_id = ?",
+            )
+            .await?;
+        let mut rows = stmt.query([project_id.get()]).await?;
+        match rows.next().await? {
+            Some(row) => Ok(Some(RetentionPolicy {
+                retention_days: row.get(0)?,
+                keep_unread: row.get(1)?,
+                keep_ack_pending: row.get(2)?,
+            })),
+            None => Ok(None),
+        }
+    }
+
+    /// Sets (or replaces) the project's retention policy.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidInput` if `retention_days` is less than 1, or
+    /// `Error::ProjectNotFound` / `Error::Forbidden` for an unknown project
+    pub async fn set_retention(
+        ctx: &crate::Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        policy: RetentionPolicy,
+    ) -> Result<()> {
+        if policy.retention_days < 1 {
+            return Err(crate::Error::InvalidInput(
+                "retention_days must be at least 1".into(),
+            ));
+        }
+        Self::get(ctx, mm, project_id).await?;
+
+        let db = mm.db();
+        let stmt = db
+            .prepare(
+                r#"
+                INSERT INTO project_retention (project_id, retention_days, keep_unread, keep_ack_pending)
+                VALUES (?, ?, ?, ?)
+                ON CONFLICT(project_id) DO UPDATE SET
+                    retention_days = excluded.retention_days,
+                    keep_unread = excluded.keep_unread,
+                    keep_ack_pending = excluded.keep_ack_pending,
+                    updated_ts = CURRENT_TIMESTAMP
+                "#,
+            )
+            .await?;
+        stmt.execute((
+            project_id.get(),
+            policy.retention_days,
+            policy.keep_unread,
+            policy.keep_ack_pending,
+        ))
+        .await?;
+        Ok(())
+    }
+
+    /// Removes the project's retention policy; its messages are kept forever.
+    pub async fn clear_retention(
+        ctx: &crate::Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+    ) -> Result<()> {
+        Self::ensure_access(ctx, mm, project_id).await?;
+        let db = mm.db();
+        let stmt = db
+            .prepare("DELETE FROM project_retention WHERE project_id = ?")
+            .await?;
+        stmt.execute([project_id.get()]).await?;
+        Ok(())
+    }
+
+    /// Computes activity figures for one project.
+    ///
+    /// # Errors
+    /// Returns `Error::Forbidden` / `Error::ProjectNotFound` if the project
+    /// is out of reach
+    pub async fn stats(
+        ctx: &crate::Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+    ) -> Result<ProjectStats> {
+        Self::ensure_access(ctx, mm, project_id).await?;
+
+        let mut stats = Self::load_stats(mm, Some(project_id)).await?;
+        Ok(stats
+            .remove(&project_id.get())
+            .unwrap_or_else(|| ProjectStats::empty(project_id)))
+    }
+
+    /// Computes activity figures for every project the context may access.
+    ///
+    /// Uses the same grouped queries as [`stats`](Self::stats), so the cost
+    /// doesn't grow with the number of projects.
+    ///
+    /// # Returns
+    /// Stats in [`list_all`](Self::list_all) order
+    pub async fn stats_all(ctx: &crate::Ctx, mm: &ModelManager) -> Result<Vec<ProjectStats>> {
+        let projects = Self::list_all(ctx, mm).await?;
+        let mut stats = Self::load_stats(mm, None).await?;
+        Ok(projects
+            .into_iter()
+            .map(|p| {
+                stats
+                    .remove(&p.id.get())
+                    .unwrap_or_else(|| ProjectStats::empty(p.id))
+            })
+            .collect())
+    }
+
+    /// Runs the stats aggregates for one project, or all when `None`.
+    ///
+    /// Projects with no agents, messages or reservations are absent.
+    async fn load_stats(
+        mm: &ModelManager,
+        project_id: Option<ProjectId>,
+    ) -> Result<HashMap<i64, ProjectStats>> {
+        const TS_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+        let parse_ts =
+            |s: Option<String>| s.and_then(|s| NaiveDateTime::parse_from_str(&s, TS_FORMAT).ok());
+
+        let db = mm.db_read();
+        let now = chrono::Utc::now().naive_utc();
+        let filter = match project_id {
+            Some(id) => libsql::Value::Integer(id.get()),
+            None => libsql::Value::Null,
+        };
+        let mut stats: HashMap<i64, ProjectStats> = HashMap::new();
+
+        let mut rows = db
+            .query(
+                r#"
+                SELECT project_id, COUNT(*), MAX(last_active_ts)
+                FROM agents
+                WHERE ?1 IS NULL OR project_id = ?1
+                GROUP BY project_id
+                "#,
+                [filter.clone()],
+            )
+            .await?;
+        while let Some(row) = rows.next().await? {
+            let id: i64 = row.get(0)?;
+            let entry = stats
+                .entry(id)
+                .or_insert_with(|| ProjectStats::empty(ProjectId::new(id)));
+            entry.agent_count = row.get(1)?;
+            entry.touch(parse_ts(row.get(2)?));
+        }
+
+        let day_ago = (now - chrono::Duration::hours(24))
+            .format(TS_FORMAT)
+            .to_string();
+        let week_ago = (now - chrono::Duration::days(7))
+            .format(TS_FORMAT)
+            .to_string();
+        let mut rows = db
+            .query(
+                r#"
+                SELECT
+                    project_id,
+                    COUNT(*),
+                    SUM(CASE WHEN created_ts >= ?2 THEN 1 ELSE 0 END),
+                    SUM(CASE WHEN created_ts >= ?3 THEN 1 ELSE 0 END),
+                    COUNT(DISTINCT thread_id),
+                    MAX(created_ts)
+                FROM visible_messages
+                WHERE ?1 IS NULL OR project_id = ?1
+                GROUP BY project_id
+                "#,
+                (filter.clone(), day_ago, week_ago),
+            )
+            .await?;
+        while let Some(row) = rows.next().await? {
+            let id: i64 = row.get(0)?;
+            let entry = stats
+                .entry(id)
+                .or_insert_with(|| ProjectStats::empty(ProjectId::new(id)));
+            entry.message_count = row.get(1)?;
+            entry.messages_last_24h = row.get(2)?;
+            entry.messages_last_7d = row.get(3)?;
+            entry.thread_count = row.get(4)?;
+            entry.touch(parse_ts(row.get(5)?));
+        }
+
+        let mut rows = db
+            .query(
+                r#"
+                SELECT project_id, COUNT(*)
+                FROM file_reservations
+                WHERE (?1 IS NULL OR project_id = ?1)
+                  AND released_ts IS NULL AND expires_ts > ?2
+                GROUP BY project_id
+                "#,
+                (filter, now.format(TS_FORMAT).to_string()),
+            )
+            .await?;
+        while let Some(row) = rows.next().await? {
+            let id: i64 = row.get(0)?;
+            stats
+                .entry(id)
+                .or_insert_with(|| ProjectStats::empty(ProjectId::new(id)))
+                .active_reservations = row.get(1)?;
+        }
+
+        Ok(stats)
+    }
+
+    /// Resolves the project for a working directory from its marker files.
+    ///
+    /// Walks up from `dir` using
+    /// [`discover_project_identity`](crate::utils::discover_project_identity),
+    /// then looks the discovered identifier up like
+    /// [`get_by_identifier`](Self::get_by_identifier).
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidInput` listing the searched directories if no
+    /// marker is found, or `Error::ProjectNotFound` if the marker names an
+    /// unknown project
+    pub async fn resolve_from_cwd(
+        ctx: &crate::Ctx,
+        mm: &ModelManager,
+        dir: &Path,
+    ) -> Result<Project> {
+        let identifier = crate::utils::discover_project_identity(dir)?;
+        Self::get_by_identifier(ctx, mm, &identifier).await
+    }
+
     /// Retrieves a project by its database ID.
     ///
     /// # Arguments
-    /// * `_ctx` - Request context
+    /// * `ctx` - Request context
     /// * `mm` - ModelManager
     /// * `id` - Project database ID
     ///
@@ -411,9 +833,10 @@ impl ProjectBmc {
     /// The project data
     ///
     /// # Errors
-    /// Returns `Error::ProjectNotFound` if ID doesn't exist
-    pub async fn get(_ctx: &crate::Ctx, mm: &ModelManager, id: ProjectId) -> Result<Project> {
-        let db = mm.db();
+    /// Returns `Error::ProjectNotFound` if ID doesn't exist, or
+    /// `Error::Forbidden` if it is outside the context's scope
+    pub async fn get(ctx: &crate::Ctx, mm: &ModelManager, id: ProjectId) -> Result<Project> {
+        let db = mm.db_read();
         let stmt = db
             .prepare("SELECT id, slug, human_key, created_at FROM projects WHERE id = ?")
             .await?;
@@ -424,17 +847,46 @@ impl ProjectBmc {
             let created_at = NaiveDateTime::parse_from_str(&created_at_str, "%Y-%m-%d %H:%M:%S")
                 .unwrap_or_default();
 
-            Ok(Project {
-                id: ProjectId::new(row.get(0)?),
-                slug: row.get(1)?,
-                human_key: row.get(2)?,
-                created_at,
-            })
+            Self::check_access(
+                ctx,
+                Project {
+                    id: ProjectId::new(row.get(0)?),
+                    slug: row.get(1)?,
+                    human_key: row.get(2)?,
+                    created_at,
+                },
+            )
         } else {
             Err(crate::Error::project_not_found(format!("ID: {}", id.get())))
         }
     }
 
+    /// Ensures the context may access the project with the given ID.
+    ///
+    /// Unrestricted contexts (root, none-auth) pass without a query.
+    ///
+    /// # Errors
+    /// Returns `Error::Forbidden` if the project is outside the context's
+    /// scope, or `Error::ProjectNotFound` if the ID doesn't exist
+    pub async fn ensure_access(
+        ctx: &crate::Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+    ) -> Result<()> {
+        if ctx.is_unrestricted() {
+            return Ok(());
+        }
+        Self::get(ctx, mm, project_id).await.map(|_| ())
+    }
+
+    fn check_access(ctx: &crate::Ctx, project: Project) -> Result<Project> {
+        if ctx.can_access_project(&project.slug) {
+            Ok(project)
+        } else {
+            Err(crate::Error::Forbidden(project.slug))
+        }
+    }
+
     /// List sibling projects (projects sharing at least one product)
     pub async fn list_siblings(
         ctx: &crate::Ctx,
@@ -445,7 +897,7 @@ impl ProjectBmc {
         let products =
             crate::model::product::ProductBmc::list_for_project(ctx, mm, project_id.get()).await?;
 
-        let mut sibling_ids = std::collections::HashSet::new();
+        let mut sibling_ids = HashSet::new();
 
         // 2. Get all projects for these products
         for product in products {
@@ -485,56 +937,69 @@ impl ProjectBmc {
     ) -> Result<String> {
         // 1. Get project
         let project = Self::get(ctx, mm, project_id).await?;
-        let repo_root = &mm.repo_root;
-        let project_root = repo_root.join("projects").join(&project.slug);
 
-        // Ensure directory exists (it should, but just in case)
-        if !project_root.exists() {
-            std::fs::create_dir_all(&project_root)?;
-        }
-
-        // 2. Export Mailbox (JSON)
-        // reuse ExportBmc logic but specifically for archive
-        let messages =
-            crate::model::message::MessageBmc::list_recent(ctx, mm, project_id, 1000).await?; // reasonable limit for archive?
-        let mailbox_json = serde_json::to_string_pretty(&messages)?;
-        let mailbox_path = project_root.join("mailbox.json");
-        std::fs::write(&mailbox_path, mailbox_json)?;
-
-        // 3. Export Agents (JSON)
-        let agents =
-            crate::model::agent::AgentBmc::list_all_for_project(ctx, mm, project_id).await?;
-        let agents_json = serde_json::to_string_pretty(&agents)?;
-        let agents_path = project_root.join("agents.json");
-        std::fs::write(&agents_path, agents_json)?;
+        // 2-3. Export mailbox and agents
+        let paths = Self::write_archive_snapshot(ctx, mm, &project).await?;
 
         // 4. Commit using git_store - serialized to prevent lock contention
         let _git_guard = mm.git_lock.lock().await;
 
-        // We need to pass paths relative to the repo root
-        // Since we are inside repo_root, relative path is `projects/{slug}/mailbox.json`
-        let relative_mailbox = Path::new("projects")
-            .join(&project.slug)
-            .join("mailbox.json");
-        let relative_agents = Path::new("projects")
-            .join(&project.slug)
-            .join("agents.json");
-
         // Use cached repository to prevent FD exhaustion
-        let repo_arc = mm.get_repo().await?;
+        let repo_arc = mm.get_project_repo(&project.slug).await?;
         let repo = repo_arc.lock().await;
 
+        let (author_name, author_email) = git_store::author_of(ctx.actor());
         let oid = git_store::commit_paths(
             &repo,
-            &[relative_mailbox, relative_agents],
-            message,
-            "mcp-bot",
-            "mcp-bot@localhost",
+            &paths,
+            &git_store::with_actor_trailer(message, ctx.actor()),
+            author_name,
+            author_email,
         )?;
 
         Ok(oid.to_string())
     }
 
+    /// Writes `mailbox.json` and `agents.json` for a project into the archive
+    /// working tree without committing.
+    ///
+    /// Returns the written paths relative to the repo root.
+    async fn write_archive_snapshot(
+        ctx: &crate::Ctx,
+        mm: &ModelManager,
+        project: &Project,
+    ) -> Result<Vec<PathBuf>> {
+        let project_root = mm
+            .project_repo_root(&project.slug)
+            .join("projects")
+            .join(&project.slug);
+
+        // Ensure directory exists (it should, but just in case)
+        if !project_root.exists() {
+            std::fs::create_dir_all(&project_root)?;
+        }
+
+        // Export Mailbox (JSON)
+        // reuse ExportBmc logic but specifically for archive
+        let messages =
+            crate::model::message::MessageBmc::list_recent(ctx, mm, project.id, 1000).await?; // reasonable limit for archive?
+        let mailbox_json = serde_json::to_string_pretty(&messages)?;
+        std::fs::write(project_root.join("mailbox.json"), mailbox_json)?;
+
+        // Export Agents (JSON)
+        let agents =
+            crate::model::agent::AgentBmc::list_all_including_retired(ctx, mm, project.id).await?;
+        let agents_json = serde_json::to_string_pretty(&agents)?;
+        std::fs::write(project_root.join("agents.json"), agents_json)?;
+
+        // Paths relative to the repo root: `projects/{slug}/...`
+        let relative_root = Path::new("projects").join(&project.slug);
+        Ok(vec![
+            relative_root.join("mailbox.json"),
+            relative_root.join("agents.json"),
+        ])
+    }
+
     /// Deletes a project and all related data (cascade delete).
     ///
     /// This method performs a cascading delete in the correct order to respect
@@ -544,14 +1009,16 @@ impl ProjectBmc {
     /// 1. message_recipients (references messages and agents)
     /// 2. messages_fts (FTS5 virtual table, synced with messages)
     /// 3. messages (references project and sender agent)
-    /// 4. file_reservations, build_slots, macros, overseer_messages
+    /// 4. file_reservations, reservation_queue, build_slots, macros, overseer_messages
     /// 5. agent_links (references agents)
     /// 6. project_sibling_suggestions
     /// 7. agents (references project)
     /// 8. product_project_links
     /// 9. project itself
     ///
-    /// Also removes the project directory from the Git archive.
+    /// These deletes and an audit log entry commit in one transaction; the
+    /// entry outlives the project. Also removes the project directory from
+    /// the Git archive.
     ///
     /// # Arguments
     /// * `ctx` - Request context
@@ -578,7 +1045,6 @@ impl ProjectBmc {
     /// # }
     /// ```
     pub async fn delete(ctx: &crate::Ctx, mm: &ModelManager, project_id: ProjectId) -> Result<()> {
-        let db = mm.db();
         let pid = project_id.get();
 
         // First, verify project exists and get slug for git cleanup
@@ -586,12 +1052,16 @@ impl ProjectBmc {
         let project_slug = project.slug.clone();
 
         // Get all agent IDs for this project (needed for message_recipients cleanup)
-        let agents = super::agent::AgentBmc::list_all_for_project(ctx, mm, project_id).await?;
+        let agents =
+            super::agent::AgentBmc::list_all_including_retired(ctx, mm, project_id).await?;
         let agent_ids: Vec<i64> = agents.iter().map(|a| a.id.get()).collect();
 
+        // 1-11 run in one transaction with the audit entry
+        let (tx_guard, tx) = mm.begin_tx().await?;
+
         // 1. Delete message_recipients for messages in this project
         // (message_recipients references both messages and agents)
-        let stmt = db
+        let stmt = tx
             .prepare(
                 r#"
                 DELETE FROM message_recipients
@@ -601,36 +1071,201 @@ impl ProjectBmc {
             .await?;
         stmt.execute([pid]).await?;
 
+        // Deliveries from other projects to this project's agents go too
+        let stmt = tx
+            .prepare(
+                r#"
+                DELETE FROM message_recipients
+                WHERE (message_id, agent_id) IN (
+                    SELECT message_id, agent_id FROM cross_project_recipients WHERE project_id = ?
+                )
+                "#,
+            )
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
+            .prepare(
+                r#"
+                DELETE FROM cross_project_recipients
+                WHERE project_id = ?1
+                   OR message_id IN (SELECT id FROM messages WHERE project_id = ?1)
+                "#,
+            )
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
+            .prepare(
+                r#"
+                DELETE FROM message_recalls
+                WHERE message_id IN (SELECT id FROM messages WHERE project_id = ?)
+                "#,
+            )
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
+            .prepare(
+                r#"
+                DELETE FROM message_schedules
+                WHERE message_id IN (SELECT id FROM messages WHERE project_id = ?)
+                "#,
+            )
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
+            .prepare(
+                r#"
+                DELETE FROM message_broadcasts
+                WHERE message_id IN (SELECT id FROM messages WHERE project_id = ?)
+                "#,
+            )
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
+            .prepare(
+                r#"
+                DELETE FROM message_deferrals
+                WHERE message_id IN (SELECT id FROM messages WHERE project_id = ?)
+                "#,
+            )
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
+            .prepare(
+                r#"
+                DELETE FROM message_snoozes
+                WHERE message_id IN (SELECT id FROM messages WHERE project_id = ?)
+                "#,
+            )
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
+            .prepare(
+                r#"
+                DELETE FROM message_labels
+                WHERE message_id IN (SELECT id FROM messages WHERE project_id = ?)
+                "#,
+            )
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
+            .prepare(
+                r#"
+                DELETE FROM message_group_expansions
+                WHERE message_id IN (SELECT id FROM messages WHERE project_id = ?)
+                "#,
+            )
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
+            .prepare(
+                r#"
+                DELETE FROM message_thread_seqs
+                WHERE message_id IN (SELECT id FROM messages WHERE project_id = ?)
+                "#,
+            )
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
+            .prepare(
+                r#"
+                DELETE FROM message_forwards
+                WHERE message_id IN (SELECT id FROM messages WHERE project_id = ?)
+                "#,
+            )
+            .await?;
+        stmt.execute([pid]).await?;
+
         // 2. Delete messages (FTS5 trigger handles messages_fts automatically)
-        let stmt = db
+        let stmt = tx
             .prepare("DELETE FROM messages WHERE project_id = ?")
             .await?;
         stmt.execute([pid]).await?;
 
+        // Its thread counters
+        let stmt = tx
+            .prepare("DELETE FROM thread_counters WHERE project_id = ?")
+            .await?;
+        stmt.execute([pid]).await?;
+
         // 3. Delete file_reservations
-        let stmt = db
+        let stmt = tx
             .prepare("DELETE FROM file_reservations WHERE project_id = ?")
             .await?;
         stmt.execute([pid]).await?;
 
+        let stmt = tx
+            .prepare("DELETE FROM reservation_queue WHERE project_id = ?")
+            .await?;
+        stmt.execute([pid]).await?;
+
         // 4. Delete build_slots
-        let stmt = db
+        let stmt = tx
             .prepare("DELETE FROM build_slots WHERE project_id = ?")
             .await?;
         stmt.execute([pid]).await?;
 
         // 5. Delete macros
-        let stmt = db
+        let stmt = tx
             .prepare("DELETE FROM macros WHERE project_id = ?")
             .await?;
         stmt.execute([pid]).await?;
 
         // 6. Delete overseer_messages
-        let stmt = db
+        let stmt = tx
             .prepare("DELETE FROM overseer_messages WHERE project_id = ?")
             .await?;
         stmt.execute([pid]).await?;
 
+        let stmt = tx
+            .prepare("DELETE FROM message_templates WHERE project_id = ?")
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
+            .prepare("DELETE FROM labels WHERE project_id = ?")
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
+            .prepare(
+                r#"
+                DELETE FROM agent_group_members
+                WHERE group_id IN (SELECT id FROM agent_groups WHERE project_id = ?)
+                "#,
+            )
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
+            .prepare("DELETE FROM agent_groups WHERE project_id = ?")
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
+            .prepare("DELETE FROM thread_mutes WHERE project_id = ?")
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
+            .prepare("DELETE FROM project_retention WHERE project_id = ?")
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
+            .prepare("DELETE FROM pruned_messages WHERE project_id = ?")
+            .await?;
+        stmt.execute([pid]).await?;
+
         // 7. Delete agent_links
         if !agent_ids.is_empty() {
             let placeholders = agent_ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
@@ -638,7 +1273,7 @@ impl ProjectBmc {
                 "DELETE FROM agent_links WHERE a_agent_id IN ({}) OR b_agent_id IN ({})",
                 placeholders, placeholders
             );
-            let stmt = db.prepare(&sql).await?;
+            let stmt = tx.prepare(&sql).await?;
             let params: Vec<libsql::Value> = agent_ids
                 .iter()
                 .chain(agent_ids.iter())
@@ -648,78 +1283,168 @@ impl ProjectBmc {
         }
 
         // 8. Delete project_sibling_suggestions
-        let stmt = db
+        let stmt = tx
             .prepare("DELETE FROM project_sibling_suggestions WHERE project_a_id = ? OR project_b_id = ?")
             .await?;
         stmt.execute([pid, pid]).await?;
 
-        // 9. Delete agents
-        let stmt = db
+        // 9. Delete agents (and their retirement markers)
+        let stmt = tx
+            .prepare(
+                "DELETE FROM agent_retirements WHERE agent_id IN (SELECT id FROM agents WHERE project_id = ?)",
+            )
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
+            .prepare(
+                "DELETE FROM agent_settings WHERE agent_id IN (SELECT id FROM agents WHERE project_id = ?)",
+            )
+            .await?;
+        stmt.execute([pid]).await?;
+
+        let stmt = tx
             .prepare("DELETE FROM agents WHERE project_id = ?")
             .await?;
         stmt.execute([pid]).await?;
 
         // 10. Delete product_project_links
-        let stmt = db
+        let stmt = tx
             .prepare("DELETE FROM product_project_links WHERE project_id = ?")
             .await?;
         stmt.execute([pid]).await?;
 
         // 11. Delete the project itself
-        let stmt = db.prepare("DELETE FROM projects WHERE id = ?").await?;
+        let stmt = tx.prepare("DELETE FROM projects WHERE id = ?").await?;
         stmt.execute([pid]).await?;
 
+        // Kept after the project is gone: audit_log has no foreign keys
+        AuditBmc::record_in(
+            &tx,
+            ctx,
+            project_id,
+            AuditAction::ProjectDelete,
+            pid,
+            serde_json::json!({
+                "before": {
+                    "slug": project.slug,
+                    "human_key": project.human_key,
+                    "agents": agent_ids.len(),
+                },
+            }),
+        )
+        .await?;
+        ProjectVersionBmc::bump_in(&tx, &project_slug).await?;
+        tx.commit().await?;
+        drop(tx_guard);
+
         // 12. Clean up Git archive
-        let project_dir = mm.repo_root.join("projects").join(&project_slug);
+        let project_dir = mm
+            .project_repo_root(&project_slug)
+            .join("projects")
+            .join(&project_slug);
         if project_dir.exists() {
             std::fs::remove_dir_all(&project_dir)?;
 
             let _git_guard = mm.git_lock.lock().await;
-            let repo_arc = mm.get_repo().await?;
+            let repo_arc = mm.get_project_repo(&project_slug).await?;
             let repo = repo_arc.lock().await;
 
             let relative_path = Path::new("projects").join(&project_slug);
+            let (author_name, author_email) = git_store::author_of(ctx.actor());
             git_store::commit_deletion(
                 &repo,
                 &relative_path,
-                &format!("chore: delete project {}", project_slug),
-                "mcp-bot",
-                "mcp-bot@localhost",
+                &git_store::with_actor_trailer(
+                    &format!("chore: delete project {}", project_slug),
+                    ctx.actor(),
+                ),
+                author_name,
+                author_email,
             )?;
         }
 
         Ok(())
     }
 
+    /// Computes what [`adopt`](Self::adopt) would do, without writing anything.
+    ///
+    /// Agent names present in both projects are resolved according to
+    /// `policy`; anything that can't be resolved is listed in
+    /// [`AdoptReport::conflicts`].
+    pub async fn plan_adopt(
+        ctx: &crate::Ctx,
+        mm: &ModelManager,
+        from_project_id: ProjectId,
+        to_project_id: ProjectId,
+        policy: &AgentConflictPolicy,
+    ) -> Result<AdoptReport> {
+        Ok(
+            Self::build_adopt_plan(ctx, mm, from_project_id, to_project_id, policy)
+                .await?
+                .report,
+        )
+    }
+
     /// Adopts (merges) artifacts from one project to another.
     ///
-    /// Moves all agents and messages from `from_project_id` to `to_project_id`.
-    /// Note: This may fail if there are naming conflicts (e.g. agent name exists in dest).
+    /// Moves agents, messages, file reservations and build slots from
+    /// `from_project_id` to `to_project_id`, merges the source project's
+    /// Git archive directory into the destination's, and records the whole
+    /// adoption as a single archive commit.
+    ///
+    /// Agents whose name already exists in the destination are handled per
+    /// `policy`. Unresolved conflicts abort the adoption before any change is
+    /// made; use [`plan_adopt`](Self::plan_adopt) to inspect them first.
+    ///
+    /// The adoption is recorded in the destination project's audit log.
+    ///
+    /// # Errors
+    /// - [`crate::Error::InvalidInput`] if both IDs are the same project or
+    ///   conflicts remain unresolved
     pub async fn adopt(
         ctx: &crate::Ctx,
         mm: &ModelManager,
         from_project_id: ProjectId,
         to_project_id: ProjectId,
-    ) -> Result<()> {
+        policy: &AgentConflictPolicy,
+    ) -> Result<AdoptReport> {
+        let plan = Self::build_adopt_plan(ctx, mm, from_project_id, to_project_id, policy).await?;
+        if !plan.report.conflicts.is_empty() {
+            return Err(crate::Error::InvalidInput(format!(
+                "Adopt blocked by conflicts: {}",
+                plan.report.conflicts.join("; ")
+            )));
+        }
+
         let db = mm.db();
         let from_pid = from_project_id.get();
         let to_pid = to_project_id.get();
 
-        // 1. Move Agents
-        // We use execute directly
-        // Note: Using prepare_cached or prepare
+        // 1. Resolve name collisions before moving agents (UNIQUE(project_id, name))
+        for (src_id, dest_id) in &plan.merges {
+            Self::reattribute_agent(mm, *src_id, *dest_id).await?;
+        }
+        for (src_id, new_name) in &plan.renames {
+            let stmt = db
+                .prepare("UPDATE agents SET name = ? WHERE id = ?")
+                .await?;
+            stmt.execute((new_name.as_str(), *src_id)).await?;
+        }
+
+        // 2. Move Agents
         let stmt = db
             .prepare("UPDATE agents SET project_id = ? WHERE project_id = ?")
             .await?;
         stmt.execute([to_pid, from_pid]).await?;
 
-        // 2. Move Messages
+        // 3. Move Messages
         let stmt = db
             .prepare("UPDATE messages SET project_id = ? WHERE project_id = ?")
             .await?;
         stmt.execute([to_pid, from_pid]).await?;
 
-        // 3. Move other entities (Optional but recommended for full adopt)
+        // 4. Move other entities
         // File Reservations
         let stmt = db
             .prepare("UPDATE file_reservations SET project_id = ? WHERE project_id = ?")
@@ -732,9 +1457,402 @@ impl ProjectBmc {
             .await?;
         stmt.execute([to_pid, from_pid]).await?;
 
-        // 4. Sync destination to archive
-        Self::sync_to_archive(ctx, mm, to_project_id, "Adopted artifacts").await?;
+        // Thread mutes follow their agents and threads
+        let stmt = db
+            .prepare("UPDATE OR IGNORE thread_mutes SET project_id = ? WHERE project_id = ?")
+            .await?;
+        stmt.execute([to_pid, from_pid]).await?;
+
+        // Cross-project deliveries follow their agents; those now within one
+        // project are ordinary deliveries
+        let stmt = db
+            .prepare("UPDATE cross_project_recipients SET project_id = ? WHERE project_id = ?")
+            .await?;
+        stmt.execute([to_pid, from_pid]).await?;
+        let stmt = db
+            .prepare(
+                "DELETE FROM cross_project_recipients WHERE project_id = (SELECT project_id FROM messages WHERE id = message_id)",
+            )
+            .await?;
+        stmt.execute(()).await?;
+
+        // Reattribution spans several helpers, so the entry follows the moves
+        // rather than sharing a transaction with them
+        AuditBmc::record(
+            ctx,
+            mm,
+            to_project_id,
+            AuditAction::ProjectAdopt,
+            from_pid,
+            serde_json::json!({
+                "before": { "from_project": plan.from.slug, "to_project": plan.to.slug },
+                "after": plan.report,
+            }),
+        )
+        .await?;
+        ProjectVersionBmc::bump(mm, &plan.from.slug).await?;
+        ProjectVersionBmc::bump(mm, &plan.to.slug).await?;
+
+        // 5. Merge archive directories and commit once
+        Self::merge_archive_dirs(ctx, mm, &plan).await?;
+
+        Ok(plan.report)
+    }
+
+    /// Shared planning step for [`plan_adopt`](Self::plan_adopt) and [`adopt`](Self::adopt).
+    async fn build_adopt_plan(
+        ctx: &crate::Ctx,
+        mm: &ModelManager,
+        from_project_id: ProjectId,
+        to_project_id: ProjectId,
+        policy: &AgentConflictPolicy,
+    ) -> Result<AdoptPlan> {
+        if from_project_id == to_project_id {
+            return Err(crate::Error::InvalidInput(
+                "Cannot adopt a project into itself".to_string(),
+            ));
+        }
+
+        let from = Self::get(ctx, mm, from_project_id).await?;
+        let to = Self::get(ctx, mm, to_project_id).await?;
+
+        let src_agents =
+            crate::model::agent::AgentBmc::list_all_including_retired(ctx, mm, from_project_id)
+                .await?;
+        let dest_agents =
+            crate::model::agent::AgentBmc::list_all_including_retired(ctx, mm, to_project_id)
+                .await?;
+        let dest_by_name: std::collections::HashMap<&str, i64> = dest_agents
+            .iter()
+            .map(|a| (a.name.as_str(), a.id.get()))
+            .collect();
+        let src_names: HashSet<&str> = src_agents.iter().map(|a| a.name.as_str()).collect();
+
+        let mut report = AdoptReport::default();
+        let mut merges = Vec::new();
+        let mut renames = Vec::new();
+        let mut agent_dir_renames = Vec::new();
+
+        for agent in &src_agents {
+            let Some(&dest_id) = dest_by_name.get(agent.name.as_str()) else {
+                report.agents_moved += 1;
+                continue;
+            };
+            match policy {
+                AgentConflictPolicy::Report => report
+                    .conflicts
+                    .push(format!("agent '{}' exists in both projects", agent.name)),
+                AgentConflictPolicy::Merge => {
+                    merges.push((agent.id.get(), dest_id));
+                    report.agents_merged.push(agent.name.clone());
+                }
+                AgentConflictPolicy::RenameSuffix(suffix) => {
+                    let new_name = format!("{}{}", agent.name, suffix);
+                    if suffix.is_empty()
+                        || dest_by_name.contains_key(new_name.as_str())
+                        || src_names.contains(new_name.as_str())
+                    {
+                        report.conflicts.push(format!(
+                            "agent '{}' cannot be renamed to '{}': name already taken",
+                            agent.name, new_name
+                        ));
+                    } else {
+                        renames.push((agent.id.get(), new_name.clone()));
+                        agent_dir_renames.push((agent.name.clone(), new_name.clone()));
+                        report.agents_moved += 1;
+                        report.agents_renamed.push((agent.name.clone(), new_name));
+                    }
+                }
+            }
+        }
+
+        report.messages_moved =
+            Self::count_for_project(mm, "messages", from_project_id).await? as usize;
+        report.reservations_moved =
+            Self::count_for_project(mm, "file_reservations", from_project_id).await? as usize;
+
+        let src_dir = mm
+            .project_repo_root(&from.slug)
+            .join("projects")
+            .join(&from.slug);
+        report.archive_files_moved = archive_files(&src_dir)?.len();
+
+        Ok(AdoptPlan {
+            from,
+            to,
+            merges,
+            renames,
+            agent_dir_renames,
+            report,
+        })
+    }
+
+    /// Counts rows in `table` belonging to a project (table name is internal, never user input).
+    async fn count_for_project(
+        mm: &ModelManager,
+        table: &'static str,
+        project_id: ProjectId,
+    ) -> Result<i64> {
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(&format!(
+                "SELECT COUNT(*) FROM {} WHERE project_id = ?",
+                table
+            ))
+            .await?;
+        let mut rows = stmt.query([project_id.get()]).await?;
+        if let Some(row) = rows.next().await? {
+            Ok(row.get(0)?)
+        } else {
+            Ok(0)
+        }
+    }
+
+    /// Reattributes everything owned by `src_id` to `dest_id`, then deletes `src_id`.
+    ///
+    /// Rows protected by a uniqueness constraint (e.g. an agent that was both
+    /// recipients of one message) keep the destination row and drop the duplicate.
+    async fn reattribute_agent(mm: &ModelManager, src_id: i64, dest_id: i64) -> Result<()> {
+        let db = mm.db();
+        let updates: &[&str] = &[
+            "UPDATE messages SET sender_id = ? WHERE sender_id = ?",
+            "UPDATE OR IGNORE message_recipients SET agent_id = ? WHERE agent_id = ?",
+            "UPDATE file_reservations SET agent_id = ? WHERE agent_id = ?",
+            "UPDATE build_slots SET agent_id = ? WHERE agent_id = ?",
+            "UPDATE overseer_messages SET sender_id = ? WHERE sender_id = ?",
+            "UPDATE OR IGNORE agent_links SET a_agent_id = ? WHERE a_agent_id = ?",
+            "UPDATE OR IGNORE agent_links SET b_agent_id = ? WHERE b_agent_id = ?",
+            "UPDATE OR IGNORE agent_capabilities SET agent_id = ? WHERE agent_id = ?",
+            "UPDATE agent_capabilities SET granted_by = ? WHERE granted_by = ?",
+            "UPDATE tool_metrics SET agent_id = ? WHERE agent_id = ?",
+            "UPDATE attachments SET agent_id = ? WHERE agent_id = ?",
+            "UPDATE OR IGNORE message_deferrals SET agent_id = ? WHERE agent_id = ?",
+            "UPDATE OR IGNORE message_snoozes SET agent_id = ? WHERE agent_id = ?",
+            "UPDATE OR IGNORE thread_mutes SET agent_id = ? WHERE agent_id = ?",
+            "UPDATE OR IGNORE agent_group_members SET agent_id = ? WHERE agent_id = ?",
+            "UPDATE OR IGNORE message_group_expansions SET agent_id = ? WHERE agent_id = ?",
+            "UPDATE OR IGNORE cross_project_recipients SET agent_id = ? WHERE agent_id = ?",
+        ];
+        for sql in updates {
+            let stmt = db.prepare(sql).await?;
+            stmt.execute([dest_id, src_id]).await?;
+        }
 
+        // Leftovers are duplicates the destination agent already has
+        let cleanups: &[&str] = &[
+            "DELETE FROM message_recipients WHERE agent_id = ?",
+            "DELETE FROM agent_links WHERE a_agent_id = ? OR b_agent_id = ?",
+            "DELETE FROM agent_capabilities WHERE agent_id = ?",
+            "DELETE FROM agent_retirements WHERE agent_id = ?",
+            "DELETE FROM message_deferrals WHERE agent_id = ?",
+            "DELETE FROM message_snoozes WHERE agent_id = ?",
+            "DELETE FROM thread_mutes WHERE agent_id = ?",
+            "DELETE FROM agent_group_members WHERE agent_id = ?",
+            "DELETE FROM message_group_expansions WHERE agent_id = ?",
+            "DELETE FROM cross_project_recipients WHERE agent_id = ?",
+            "DELETE FROM agent_settings WHERE agent_id = ?",
+            "DELETE FROM agents WHERE id = ?",
+        ];
+        for sql in cleanups {
+            let stmt = db.prepare(sql).await?;
+            if sql.contains(" OR ") {
+                stmt.execute([src_id, src_id]).await?;
+            } else {
+                stmt.execute([src_id]).await?;
+            }
+        }
         Ok(())
     }
+
+    /// Moves the source project's archive files into the destination
+    /// directory and commits the move plus refreshed exports as one commit.
+    ///
+    /// Files that already exist at the destination are kept (the
+    /// destination's copy wins); renamed agents' directories follow the new name.
+    ///
+    /// With the per-project layout the files cross repositories: the
+    /// destination commit records the source repository's HEAD as provenance
+    /// and the source repository gets its own removal commit, keeping its
+    /// history intact.
+    async fn merge_archive_dirs(
+        ctx: &crate::Ctx,
+        mm: &ModelManager,
+        plan: &AdoptPlan,
+    ) -> Result<()> {
+        // Queued message files must land before the directory moves
+        mm.archive_queue.flush().await;
+        let _git_guard = mm.git_lock.lock().await;
+
+        let src_root = mm.project_repo_root(&plan.from.slug);
+        let dest_root = mm.project_repo_root(&plan.to.slug);
+        let src_rel = Path::new("projects").join(&plan.from.slug);
+        let dest_rel = Path::new("projects").join(&plan.to.slug);
+        let src_dir = src_root.join(&src_rel);
+
+        let mut added = Vec::new();
+        for rel in archive_files(&src_dir)? {
+            let target_rel = rename_agent_dir(&rel, &plan.agent_dir_renames);
+            let target = dest_root.join(&dest_rel).join(&target_rel);
+            if target.exists() {
+                tracing::warn!(
+                    path = %target.display(),
+                    "Adopt: keeping destination copy of archive file"
+                );
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::rename(src_dir.join(&rel), &target)?;
+            added.push(dest_rel.join(target_rel));
+        }
+        if src_dir.exists() {
+            std::fs::remove_dir_all(&src_dir)?;
+        }
+
+        added.extend(Self::write_archive_snapshot(ctx, mm, &plan.to).await?);
+
+        let r = &plan.report;
+        let mut message = format!(
+            "chore: adopt project {} into {}\n\nagents moved: {}, agents merged: {}, agents renamed: {}, messages moved: {}, reservations moved: {}",
+            plan.from.slug,
+            plan.to.slug,
+            r.agents_moved,
+            r.agents_merged.len(),
+            r.agents_renamed.len(),
+            r.messages_moved,
+            r.reservations_moved
+        );
+
+        let actor = ctx.actor();
+        let (author_name, author_email) = git_store::author_of(actor);
+        if src_root == dest_root {
+            let repo_arc = mm.get_project_repo(&plan.to.slug).await?;
+            let repo = repo_arc.lock().await;
+            git_store::commit_changes(
+                &repo,
+                &added,
+                &[src_rel],
+                &git_store::with_actor_trailer(&message, actor),
+                author_name,
+                author_email,
+            )?;
+            return Ok(());
+        }
+
+        // Separate repositories: drop the files from the source first so its
+        // HEAD (recorded below) is the commit that handed them over
+        if src_root.join(".git").exists() {
+            let repo_arc = mm.get_project_repo(&plan.from.slug).await?;
+            let repo = repo_arc.lock().await;
+            let oid = git_store::commit_deletion(
+                &repo,
+                &src_rel,
+                &git_store::with_actor_trailer(
+                    &format!("chore: adopted into project {}", plan.to.slug),
+                    actor,
+                ),
+                author_name,
+                author_email,
+            )?;
+            message.push_str(&format!(
+                "\nadopted from repository {} at {}",
+                src_root.display(),
+                oid
+            ));
+        }
+
+        let repo_arc = mm.get_project_repo(&plan.to.slug).await?;
+        let repo = repo_arc.lock().await;
+        git_store::commit_changes(
+            &repo,
+            &added,
+            &[] as &[PathBuf],
+            &git_store::with_actor_trailer(&message, actor),
+            author_name,
+            author_email,
+        )?;
+        Ok(())
+    }
+}
+
+/// How [`ProjectBmc::adopt`] handles an agent name present in both projects.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AgentConflictPolicy {
+    /// Report the collision as a conflict and refuse to adopt.
+    #[default]
+    Report,
+    /// Merge into the destination agent: messages, recipients, reservations
+    /// and capabilities are reattributed and the source agent is removed.
+    Merge,
+    /// Rename the source agent by appending this suffix.
+    RenameSuffix(String),
+}
+
+/// What an adoption moved (or, from [`ProjectBmc::plan_adopt`], would move).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct AdoptReport {
+    /// Agents moved to the destination (including renamed ones).
+    pub agents_moved: usize,
+    /// Source agents merged into a same-named destination agent.
+    pub agents_merged: Vec<String>,
+    /// Source agents renamed to avoid a collision, as `(old, new)`.
+    pub agents_renamed: Vec<(String, String)>,
+    pub messages_moved: usize,
+    pub reservations_moved: usize,
+    /// Files in the source project's archive directory.
+    pub archive_files_moved: usize,
+    /// Unresolved problems; adoption refuses to run while this is non-empty.
+    pub conflicts: Vec<String>,
+}
+
+/// Internal adoption plan: the report plus the concrete steps behind it.
+struct AdoptPlan {
+    from: Project,
+    to: Project,
+    /// `(source agent id, destination agent id)` pairs to merge.
+    merges: Vec<(i64, i64)>,
+    /// `(source agent id, new name)` pairs to rename.
+    renames: Vec<(i64, String)>,
+    /// `(old name, new name)` for moving renamed agents' archive directories.
+    agent_dir_renames: Vec<(String, String)>,
+    report: AdoptReport,
+}
+
+/// Lists files under `dir` (recursively) as paths relative to `dir`.
+fn archive_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+    let mut pending = vec![PathBuf::new()];
+    while let Some(rel) = pending.pop() {
+        for entry in std::fs::read_dir(dir.join(&rel))? {
+            let entry = entry?;
+            let child = rel.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                pending.push(child);
+            } else {
+                files.push(child);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// Maps `agents/{old}/...` to `agents/{new}/...` for renamed agents.
+fn rename_agent_dir(rel: &Path, renames: &[(String, String)]) -> PathBuf {
+    let mut components = rel.components();
+    if let (Some(first), Some(second)) = (components.next(), components.next())
+        && first.as_os_str() == "agents"
+        && let Some((_, new_name)) = renames
+            .iter()
+            .find(|(old, _)| second.as_os_str() == old.as_str())
+    {
+        return Path::new("agents")
+            .join(new_name)
+            .join(components.as_path());
+    }
+    rel.to_path_buf()
 }
@@ -0,0 +1,70 @@
+//! Per-project structure versions.
+//!
+//! Clients such as the web UI cache agent and message lists per project.
+//! Operations that reshape a project — adopting or deleting it, renaming or
+//! retiring an agent, pruning old messages — bump its version, and a client
+//! that sees a different version from the one it cached refetches.
+//!
+//! Versions are keyed by slug rather than project id, so deleting a project
+//! still bumps a version its clients can read. A project that was never
+//! changed is at version 0.
+
+use crate::Result;
+use crate::ctx::Ctx;
+use crate::model::ModelManager;
+
+/// Backend Model Controller for project structure versions.
+pub struct ProjectVersionBmc;
+
+impl ProjectVersionBmc {
+    /// Current version of the project with `slug`.
+    ///
+    /// Unknown slugs are at version 0, so this doesn't fail for a project
+    /// that was just deleted.
+    ///
+    /// # Errors
+    /// Returns [`crate::Error::Forbidden`] if `ctx` is scoped to other projects
+    pub async fn get(ctx: &Ctx, mm: &ModelManager, slug: &str) -> Result<i64> {
+        if !ctx.can_access_project(slug) {
+            return Err(crate::Error::Forbidden(slug.to_string()));
+        }
+
+        let db = mm.db_read();
+        let stmt = db
+            .prepare("SELECT version FROM project_versions WHERE slug = ?")
+            .await?;
+        let mut rows = stmt.query([slug]).await?;
+        match rows.next().await? {
+            Some(row) => Ok(row.get(0)?),
+            None => Ok(0),
+        }
+    }
+
+    /// Bumps the project's version and returns the new one.
+    pub async fn bump(mm: &ModelManager, slug: &str) -> Result<i64> {
+        Self::bump_in(mm.db(), slug).await
+    }
+
+    /// Bumps the version on `conn`, typically the change's open transaction.
+    pub async fn bump_in(conn: &libsql::Connection, slug: &str) -> Result<i64> {
+        let stmt = conn
+            .prepare(
+                r#"
+                INSERT INTO project_versions (slug, version) VALUES (?, 1)
+                ON CONFLICT(slug) DO UPDATE SET
+                    version = version + 1,
+                    updated_ts = CURRENT_TIMESTAMP
+                RETURNING version
+                "#,
+            )
+            .await?;
+        let mut rows = stmt.query([slug]).await?;
+        match rows.next().await? {
+            Some(row) => Ok(row.get(0)?),
+            None => Err(crate::Error::InvalidInput(format!(
+                "Could not bump version of project '{}'",
+                slug
+            ))),
+        }
+    }
+}
@@ -0,0 +1,548 @@
+//! Waiting list for contended file reservations.
+//!
+//! Reservations are advisory: by default an overlapping request is granted
+//! anyway and the caller is told about the conflict. An agent can instead
+//! ask to wait, in which case a request that overlaps another agent's
+//! reservation (with either side exclusive) is queued here.
+//!
+//! Waiters are granted first come first served. [`ReservationQueueBmc::promote`]
+//! runs whenever a reservation is released and from the server's periodic
+//! sweep, which also catches blockers that simply expired. A promoted agent
+//! gets a system message and a `reservation.granted` event. Requests still
+//! queued when their wait runs out are dropped.
+
+use crate::Result;
+use crate::ctx::{Actor, Ctx};
+use crate::events::MailEventKind;
+use crate::model::ModelManager;
+use crate::model::file_reservation::{FileReservationBmc, FileReservationForCreate};
+use crate::model::message::{MessageBmc, MessageForCreate};
+use crate::model::project::ProjectBmc;
+use crate::types::{AgentId, ProjectId};
+use crate::utils::pathspec::paths_conflict;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+/// How long a request waits in the queue when no timeout is given, in seconds.
+pub const DEFAULT_QUEUE_WAIT_SECONDS: i64 = 600;
+
+const TS_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// A reservation request waiting for overlapping reservations to go away.
+///
+/// # Fields
+///
+/// - `id` - Queue entry ID
+/// - `project_id` - Project context
+/// - `agent_id` / `agent_name` - Waiting agent
+/// - `path_pattern` - Requested glob pattern
+/// - `exclusive` - Whether the reservation will be exclusive once granted
+/// - `reason` - Why the reservation is wanted
+/// - `ttl_seconds` - Reservation TTL, counted from when it is granted
+/// - `created_ts` - When the request was queued
+/// - `wait_until` - When the request is dropped if still waiting
+/// - `position` - 1-based place among waiters for overlapping paths
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct QueuedReservation {
+    pub id: i64,
+    #[schema(value_type = i64)]
+    pub project_id: ProjectId,
+    #[schema(value_type = i64)]
+    pub agent_id: AgentId,
+    pub agent_name: String,
+    pub path_pattern: String,
+    pub exclusive: bool,
+    pub reason: String,
+    pub ttl_seconds: i64,
+    pub created_ts: NaiveDateTime,
+    pub wait_until: NaiveDateTime,
+    pub position: i64,
+}
+
+/// A reservation request that may wait for its path.
+///
+/// # Fields
+///
+/// - `project_id` - Project context
+/// - `agent_id` - Requesting agent
+/// - `path_pattern` - Files to lock (glob)
+/// - `exclusive` - True for write access
+/// - `reason` - Justification for the lock
+/// - `ttl_seconds` - Reservation TTL, counted from when it is granted
+/// - `wait_timeout_seconds` - How long to stay queued, capped at
+///   `reservations.max_ttl_seconds`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReservationQueueForCreate {
+    pub project_id: ProjectId,
+    pub agent_id: AgentId,
+    pub path_pattern: String,
+    pub exclusive: bool,
+    pub reason: String,
+    pub ttl_seconds: i64,
+    pub wait_timeout_seconds: i64,
+}
+
+/// What happened to a [`ReservationQueueBmc::reserve_or_enqueue`] request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ReservationRequestOutcome {
+    /// Nothing was in the way; the reservation was created.
+    Granted { reservation_id: i64 },
+    /// The request is waiting in the queue.
+    Queued(QueuedReservation),
+}
+
+/// A live reservation that can block a waiter.
+struct Hold {
+    agent_id: i64,
+    path_pattern: String,
+    exclusive: bool,
+}
+
+/// Backend Model Controller for the reservation wait queue.
+pub struct ReservationQueueBmc;
+
+impl ReservationQueueBmc {
+    /// Grants the reservation if nothing overlaps it, otherwise queues it.
+    ///
+    /// The request waits if it overlaps another agent's live reservation, or
+    /// another agent's earlier queued request, with either side exclusive.
+    /// Queueing behind earlier waiters keeps promotion first come first
+    /// served.
+    ///
+    /// # Errors
+    /// `Forbidden` / `ProjectNotFound` if the project is out of reach.
+    pub async fn reserve_or_enqueue(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        req: ReservationQueueForCreate,
+    ) -> Result<ReservationRequestOutcome> {
+        let project = ProjectBmc::get(ctx, mm, req.project_id).await?;
+        let now = chrono::Utc::now().naive_utc();
+        let max_wait =
+            i64::try_from(mm.app_config.reservations.max_ttl_seconds).unwrap_or(i64::MAX);
+        let wait_until =
+            now + chrono::Duration::seconds(req.wait_timeout_seconds.clamp(1, max_wait));
+
+        let queued_id = {
+            // Checked and queued under the transaction lock, so a concurrent
+            // promotion can't slip between the two
+            let (_tx_guard, tx) = mm.begin_tx().await?;
+            let holds = Self::load_holds(&tx, req.project_id, now).await?;
+            let waiters = Self::load_waiters(&tx, req.project_id, now).await?;
+
+            let agent_id = req.agent_id.get();
+            let blocked = holds.iter().any(|h| {
+                h.agent_id != agent_id
+                    && overlaps(
+                        &h.path_pattern,
+                        h.exclusive,
+                        &req.path_pattern,
+                        req.exclusive,
+                    )
+            }) || waiters.iter().any(|w| {
+                w.agent_id != req.agent_id
+                    && overlaps(
+                        &w.path_pattern,
+                        w.exclusive,
+                        &req.path_pattern,
+                        req.exclusive,
+                    )
+            });
+            if !blocked {
+                None
+            } else {
+                let id = {
+                    let stmt = tx
+                        .prepare(
+                            r#"
+                            INSERT INTO reservation_queue
+                                (project_id, agent_id, path_pattern, exclusive, reason, ttl_seconds, wait_until)
+                            VALUES (?, ?, ?, ?, ?, ?, ?)
+                            RETURNING id
+                            "#,
+                        )
+                        .await?;
+                    let mut rows = stmt
+                        .query((
+                            req.project_id.get(),
+                            agent_id,
+                            req.path_pattern.as_str(),
+                            req.exclusive,
+                            req.reason.as_str(),
+                            req.ttl_seconds,
+                            wait_until.format(TS_FORMAT).to_string(),
+                        ))
+                        .await?;
+                    match rows.next().await? {
+                        Some(row) => row.get::<i64>(0)?,
+                        None => {
+                            return Err(crate::Error::InvalidInput(
+                                "Failed to queue file reservation".into(),
+                            ));
+                        }
+                    }
+                };
+                tx.commit().await?;
+                Some(id)
+            }
+        };
+
+        let Some(queued_id) = queued_id else {
+            let reservation_id = FileReservationBmc::create(
+                ctx,
+                mm,
+                FileReservationForCreate {
+                    project_id: req.project_id,
+                    agent_id: req.agent_id,
+                    path_pattern: req.path_pattern,
+                    exclusive: req.exclusive,
+                    reason: req.reason,
+                    expires_ts: now + chrono::Duration::seconds(req.ttl_seconds),
+                },
+            )
+            .await?;
+            return Ok(ReservationRequestOutcome::Granted { reservation_id });
+        };
+
+        let entry = Self::list_for_project(ctx, mm, req.project_id)
+            .await?
+            .into_iter()
+            .find(|w| w.id == queued_id)
+            .ok_or_else(|| crate::Error::FileReservationNotFound(format!("queue {}", queued_id)))?;
+
+        mm.events.publish(
+            MailEventKind::ReservationQueued,
+            &project.slug,
+            serde_json::json!({
+                "queue_id": entry.id,
+                "agent_name": entry.agent_name,
+                "path_pattern": entry.path_pattern,
+                "exclusive": entry.exclusive,
+                "position": entry.position,
+                "wait_until": entry.wait_until.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            }),
+        );
+
+        Ok(ReservationRequestOutcome::Queued(entry))
+    }
+
+    /// Lists a project's waiting requests in arrival order, with positions.
+    ///
+    /// Requests whose wait has run out are left out even before the sweep
+    /// removes them.
+    pub async fn list_for_project(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+    ) -> Result<Vec<QueuedReservation>> {
+        ProjectBmc::ensure_access(ctx, mm, project_id).await?;
+
+        let now = chrono::Utc::now().naive_utc();
+        Self::load_waiters(mm.db_read(), project_id, now).await
+    }
+
+    /// Drops timed-out requests and grants every waiter nothing blocks any
+    /// more, in arrival order.
+    ///
+    /// Runs in one transaction, so a reservation is created exactly when its
+    /// queue entry is removed. Each promoted agent is then sent a system
+    /// message and a `reservation.granted` event is published.
+    ///
+    /// # Returns
+    /// IDs of the reservations created
+    pub async fn promote(ctx: &Ctx, mm: &ModelManager, project_id: ProjectId) -> Result<Vec<i64>> {
+        let project = ProjectBmc::get(ctx, mm, project_id).await?;
+        let now = chrono::Utc::now().naive_utc();
+        let now_str = now.format(TS_FORMAT).to_string();
+
+        let mut granted = Vec::new();
+        {
+            let (_tx_guard, tx) = mm.begin_tx().await?;
+
+            let dropped = tx
+                .execute(
+                    "DELETE FROM reservation_queue WHERE project_id = ? AND wait_until <= ?",
+                    (project_id.get(), now_str.as_str()),
+                )
+                .await?;
+            if dropped > 0 {
+                info!(
+                    project = %project.slug,
+                    dropped, "Dropped queued reservations that waited too long"
+                );
+            }
+
+            let mut holds = Self::load_holds(&tx, project_id, now).await?;
+            let waiters = Self::load_waiters(&tx, project_id, now).await?;
+            let mut still_waiting: Vec<&QueuedReservation> = Vec::new();
+
+            for waiter in &waiters {
+                let agent_id = waiter.agent_id.get();
+                let blocked = holds.iter().any(|h| {
+                    h.agent_id != agent_id
+                        && overlaps(
+                            &h.path_pattern,
+                            h.exclusive,
+                            &waiter.path_pattern,
+                            waiter.exclusive,
+                        )
+                }) || still_waiting.iter().any(|w| {
+                    w.agent_id != waiter.agent_id
+                        && overlaps(
+                            &w.path_pattern,
+                            w.exclusive,
+                            &waiter.path_pattern,
+                            waiter.exclusive,
+                        )
+                });
+                if blocked {
+                    still_waiting.push(waiter);
+                    continue;
+                }
+
+                let fr_c = FileReservationForCreate {
+                    project_id,
+                    agent_id: waiter.agent_id,
+                    path_pattern: waiter.path_pattern.clone(),
+                    exclusive: waiter.exclusive,
+                    reason: waiter.reason.clone(),
+                    expires_ts: now + chrono::Duration::seconds(waiter.ttl_seconds),
+                };
+                let reservation_id = {
+                    let stmt = tx
+                        .prepare(
+                            r#"
+                            INSERT INTO file_reservations (project_id, agent_id, path_pattern, exclusive, reason, expires_ts)
+                            VALUES (?, ?, ?, ?, ?, ?)
+                            RETURNING id
+                            "#,
+                        )
+                        .await?;
+                    let mut rows = stmt
+                        .query((
+                            project_id.get(),
+                            agent_id,
+                            fr_c.path_pattern.as_str(),
+                            fr_c.exclusive,
+                            fr_c.reason.as_str(),
+                            fr_c.expires_ts.format(TS_FORMAT).to_string(),
+                        ))
+                        .await?;
+                    match rows.next().await? {
+                        Some(row) => row.get::<i64>(0)?,
+                        None => {
+                            return Err(crate::Error::InvalidInput(
+                                "Failed to create file reservation".into(),
+                            ));
+                        }
+                    }
+                };
+                tx.execute("DELETE FROM reservation_queue WHERE id = ?", [waiter.id])
+                    .await?;
+
+                holds.push(Hold {
+                    agent_id,
+                    path_pattern: fr_c.path_pattern.clone(),
+                    exclusive: fr_c.exclusive,
+                });
+                granted.push((reservation_id, fr_c, waiter.clone()));
+            }
+
+            tx.commit().await?;
+        }
+
+        let mut ids = Vec::with_capacity(granted.len());
+        for (reservation_id, fr_c, waiter) in granted {
+            // The archive commit is the waiting agent's
+            let agent_ctx = ctx
+                .clone()
+                .with_actor(Actor::agent(&waiter.agent_name, &project.slug));
+            FileReservationBmc::announce_created(&agent_ctx, mm, reservation_id, &fr_c).await?;
+            Self::notify_granted(&agent_ctx, mm, reservation_id, &fr_c, &waiter).await;
+
+            mm.events.publish(
+                MailEventKind::ReservationGranted,
+                &project.slug,
+                serde_json::json!({
+                    "id": reservation_id,
+                    "queue_id": waiter.id,
+                    "agent_name": waiter.agent_name,
+                    "path_pattern": fr_c.path_pattern,
+                    "waited_seconds": (now - waiter.created_ts).num_seconds().max(0),
+                    "expires_ts": fr_c.expires_ts.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                }),
+            );
+            ids.push(reservation_id);
+        }
+        Ok(ids)
+    }
+
+    /// Promotes waiters in every project that has any.
+    ///
+    /// Releases promote on their own; this catches blockers that expired
+    /// and requests that timed out. Called periodically by the server.
+    ///
+    /// # Returns
+    /// IDs of the reservations created
+    pub async fn sweep(ctx: &Ctx, mm: &ModelManager) -> Result<Vec<i64>> {
+        let db = mm.db_read();
+        let mut rows = db
+            .query("SELECT DISTINCT project_id FROM reservation_queue", ())
+            .await?;
+        let mut project_ids = Vec::new();
+        while let Some(row) = rows.next().await? {
+            project_ids.push(ProjectId::new(row.get(0)?));
+        }
+
+        let mut granted = Vec::new();
+        for project_id in project_ids {
+            granted.extend(Self::promote(ctx, mm, project_id).await?);
+        }
+        Ok(granted)
+    }
+
+    /// Sends the promoted agent a note that its reservation is live.
+    ///
+    /// Addressed from the agent to itself, since no other agent granted it.
+    /// Failures are logged only; the reservation already exists.
+    async fn notify_granted(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        reservation_id: i64,
+        fr_c: &FileReservationForCreate,
+        waiter: &QueuedReservation,
+    ) {
+        let notice = MessageForCreate {
+            project_id: fr_c.project_id.get(),
+            sender_id: waiter.agent_id.get(),
+            recipient_ids: vec![waiter.agent_id.get()],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: format!("Reservation granted: {}", fr_c.path_pattern),
+            body_md: format!(
+                "[System] Your queued reservation for `{}` was granted.\n\n\
+                 Reservation ID: {}\nQueued at: {}\nExpires at: {}",
+                fr_c.path_pattern, reservation_id, waiter.created_ts, fr_c.expires_ts
+            ),
+            thread_id: None,
+            importance: None,
+            ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
+        };
+        if let Err(e) = MessageBmc::create(ctx, mm, notice).await {
+            warn!(reservation_id, error = %e, "Failed to notify agent of granted reservation");
+        }
+    }
+
+    /// Unreleased, unexpired reservations in the project.
+    async fn load_holds(
+        conn: &libsql::Connection,
+        project_id: ProjectId,
+        now: NaiveDateTime,
+    ) -> Result<Vec<Hold>> {
+        let mut rows = conn
+            .query(
+                r#"
+                SELECT agent_id, path_pattern, exclusive
+                FROM file_reservations
+                WHERE project_id = ? AND released_ts IS NULL AND expires_ts > ?
+                "#,
+                (project_id.get(), now.format(TS_FORMAT).to_string()),
+            )
+            .await?;
+        let mut holds = Vec::new();
+        while let Some(row) = rows.next().await? {
+            holds.push(Hold {
+                agent_id: row.get(0)?,
+                path_pattern: row.get(1)?,
+                exclusive: row.get(2)?,
+            });
+        }
+        Ok(holds)
+    }
+
+    /// Requests still waiting in the project, oldest first, with positions.
+    async fn load_waiters(
+        conn: &libsql::Connection,
+        project_id: ProjectId,
+        now: NaiveDateTime,
+    ) -> Result<Vec<QueuedReservation>> {
+        let mut rows = conn
+            .query(
+                r#"
+                SELECT q.id, q.project_id, q.agent_id, a.name, q.path_pattern, q.exclusive,
+                       q.reason, q.ttl_seconds, q.created_ts, q.wait_until
+                FROM reservation_queue AS q
+                JOIN agents AS a ON a.id = q.agent_id
+                WHERE q.project_id = ? AND q.wait_until > ?
+                ORDER BY q.id
+                "#,
+                (project_id.get(), now.format(TS_FORMAT).to_string()),
+            )
+            .await?;
+        let mut waiters = Vec::new();
+        while let Some(row) = rows.next().await? {
+            waiters.push(Self::from_row(row)?);
+        }
+        assign_positions(&mut waiters);
+        Ok(waiters)
+    }
+
+    fn from_row(row: libsql::Row) -> Result<QueuedReservation> {
+        let created_ts_str: String = row.get(8).unwrap_or_default();
+        let wait_until_str: String = row.get(9).unwrap_or_default();
+
+        Ok(QueuedReservation {
+            id: row.get(0)?,
+            project_id: ProjectId::new(row.get(1)?),
+            agent_id: AgentId::new(row.get(2)?),
+            agent_name: row.get(3)?,
+            path_pattern: row.get(4)?,
+            exclusive: row.get(5)?,
+            reason: row.get(6)?,
+            ttl_seconds: row.get(7)?,
+            created_ts: NaiveDateTime::parse_from_str(&created_ts_str, TS_FORMAT)
+                .unwrap_or_default(),
+            wait_until: NaiveDateTime::parse_from_str(&wait_until_str, TS_FORMAT)
+                .unwrap_or_default(),
+            position: 0,
+        })
+    }
+}
+
+/// Whether two reservations get in each other's way.
+fn overlaps(path_a: &str, exclusive_a: bool, path_b: &str, exclusive_b: bool) -> bool {
+    (exclusive_a || exclusive_b) && paths_conflict(path_a, path_b)
+}
+
+/// Numbers each waiter by how many earlier waiters of other agents it is
+/// queued behind, plus one.
+fn assign_positions(waiters: &mut [QueuedReservation]) {
+    let positions: Vec<i64> = waiters
+        .iter()
+        .enumerate()
+        .map(|(i, waiter)| {
+            let ahead = waiters[..i]
+                .iter()
+                .filter(|w| {
+                    w.agent_id != waiter.agent_id
+                        && overlaps(
+                            &w.path_pattern,
+                            w.exclusive,
+                            &waiter.path_pattern,
+                            waiter.exclusive,
+                        )
+                })
+                .count();
+            ahead as i64 + 1
+        })
+        .collect();
+    for (waiter, position) in waiters.iter_mut().zip(positions) {
+        waiter.position = position;
+    }
+}
@@ -0,0 +1,318 @@
+//! Message retention: pruning old messages from the database.
+//!
+//! A project with a [`RetentionPolicy`] keeps messages in SQLite for
+//! `retention_days`; after that [`RetentionBmc::prune`] deletes the message
+//! and recipient rows, leaving the Git archive as the permanent record. A
+//! message is only deleted once its canonical archive file is confirmed on
+//! disk; messages without one are skipped and reported.
+//!
+//! Each pruned message leaves a tombstone so [`MessageBmc::get`] can return
+//! [`Error::MessagePruned`](crate::Error::MessagePruned) instead of a plain
+//! not-found, and each deleted batch gets an entry in the project's audit log.
+//!
+//! The server runs [`RetentionBmc::prune_all`] every
+//! `messages.retention_sweep_interval_seconds`; the CLI runs one project with
+//! `projects prune <slug> [--dry-run]`.
+//!
+//! [`MessageBmc::get`]: crate::model::message::MessageBmc::get
+
+use crate::Result;
+use crate::ctx::Ctx;
+use crate::model::ModelManager;
+use crate::model::archive_integrity::archived_message_ids;
+use crate::model::audit::{AuditAction, AuditBmc};
+use crate::model::project::{ProjectBmc, RetentionPolicy};
+use crate::model::project_version::ProjectVersionBmc;
+use crate::types::ProjectId;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use utoipa::ToSchema;
+
+/// Maximum number of skipped message ids listed in a report.
+pub const REPORT_SAMPLE_LIMIT: usize = 50;
+
+/// Messages deleted per transaction, keeping `IN (...)` lists well under
+/// SQLite's bound-parameter limit.
+const PRUNE_BATCH_SIZE: usize = 500;
+
+const TS_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Tables holding per-message rows, cleared before the message itself.
+const MESSAGE_CHILD_TABLES: [&str; 11] = [
+    "message_recipients",
+    "cross_project_recipients",
+    "message_recalls",
+    "message_schedules",
+    "message_broadcasts",
+    "message_deferrals",
+    "message_snoozes",
+    "message_labels",
+    "message_group_expansions",
+    "message_thread_seqs",
+    "message_forwards",
+];
+
+/// Outcome of pruning one project.
+///
+/// In a dry run the counts describe what would have been deleted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PruneReport {
+    /// Project that was pruned
+    pub project_slug: String,
+    /// True if nothing was deleted
+    pub dry_run: bool,
+    /// Messages created before this instant were considered
+    pub cutoff: NaiveDateTime,
+    /// Messages old enough that the policy allows pruning them
+    pub eligible: usize,
+    /// Messages deleted
+    pub pruned: usize,
+    /// Recipient rows deleted along with them
+    pub recipients_pruned: usize,
+    /// Eligible messages kept because their archive file is missing
+    pub missing_archive: usize,
+    /// Ids of those messages (bounded sample)
+    pub missing_archive_ids: Vec<i64>,
+}
+
+/// Backend Model Controller for message retention.
+pub struct RetentionBmc;
+
+impl RetentionBmc {
+    /// Prune a project's messages older than its retention cutoff.
+    ///
+    /// Each eligible message is deleted only if its canonical file exists in
+    /// the archive; the rest are counted in `missing_archive`.
+    ///
+    /// # Errors
+    /// Returns `Error::InvalidInput` if the project has no retention policy,
+    /// or `Error::ProjectNotFound` / `Error::Forbidden` for an unknown project
+    pub async fn prune(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        dry_run: bool,
+    ) -> Result<PruneReport> {
+        let project = ProjectBmc::get(ctx, mm, project_id).await?;
+        let policy = ProjectBmc::get_retention(ctx, mm, project_id)
+            .await?
+            .ok_or_else(|| {
+                crate::Error::InvalidInput(format!(
+                    "Project '{}' has no retention policy",
+                    project.slug
+                ))
+            })?;
+
+        let cutoff = chrono::Utc::now().naive_utc() - chrono::Duration::days(policy.retention_days);
+        let eligible = Self::load_eligible(mm, project_id, cutoff, policy).await?;
+
+        // Queued archive writes aren't on disk yet
+        mm.archive_queue.flush().await;
+        let archived = archived_message_ids(mm, &project.slug)?;
+        let (verified, missing): (Vec<i64>, Vec<i64>) =
+            eligible.iter().partition(|id| archived.contains(id));
+
+        let mut report = PruneReport {
+            project_slug: project.slug.clone(),
+            dry_run,
+            cutoff,
+            eligible: eligible.len(),
+            pruned: 0,
+            recipients_pruned: 0,
+            missing_archive: missing.len(),
+            missing_archive_ids: missing.iter().take(REPORT_SAMPLE_LIMIT).copied().collect(),
+        };
+        if !missing.is_empty() {
+            warn!(
+                "Retention: {} message(s) in '{}' have no archive file and were kept",
+                missing.len(),
+                project.slug
+            );
+        }
+
+        for batch in verified.chunks(PRUNE_BATCH_SIZE) {
+            let (messages, recipients) = if dry_run {
+                (batch.len(), Self::count_recipients(mm, batch).await?)
+            } else {
+                Self::delete_batch(ctx, mm, project_id, cutoff, batch).await?
+            };
+            report.pruned += messages;
+            report.recipients_pruned += recipients;
+        }
+
+        if !dry_run && report.pruned > 0 {
+            ProjectVersionBmc::bump(mm, &project.slug).await?;
+            info!(
+                "Retention: pruned {} message(s) from '{}'",
+                report.pruned, project.slug
+            );
+        }
+        Ok(report)
+    }
+
+    /// Prune every project that has a retention policy.
+    ///
+    /// A project that fails is logged and skipped so the others still run.
+    pub async fn prune_all(ctx: &Ctx, mm: &ModelManager) -> Result<Vec<PruneReport>> {
+        let db = mm.db_read();
+        let stmt = db
+            .prepare("SELECT project_id FROM project_retention ORDER BY project_id")
+            .await?;
+        let mut rows = stmt.query(()).await?;
+        let mut project_ids = Vec::new();
+        while let Some(row) = rows.next().await? {
+            project_ids.push(ProjectId::new(row.get(0)?));
+        }
+
+        let mut reports = Vec::new();
+        for project_id in project_ids {
+            match Self::prune(ctx, mm, project_id, false).await {
+                Ok(report) => reports.push(report),
+                Err(e) => warn!("Retention: project {} failed: {}", project_id.get(), e),
+            }
+        }
+        Ok(reports)
+    }
+
+    /// True if the message was removed by a retention prune.
+    pub async fn is_pruned(mm: &ModelManager, message_id: i64) -> Result<bool> {
+        let db = mm.db_read();
+        let stmt = db
+            .prepare("SELECT 1 FROM pruned_messages WHERE message_id = ?")
+            .await?;
+        let mut rows = stmt.query([message_id]).await?;
+        Ok(rows.next().await?.is_some())
+    }
+
+    /// Ids of delivered messages created before `cutoff` that `policy` lets go.
+    async fn load_eligible(
+        mm: &ModelManager,
+        project_id: ProjectId,
+        cutoff: NaiveDateTime,
+        policy: RetentionPolicy,
+    ) -> Result<Vec<i64>> {
+        let mut sql = String::from(
+            r#"
+            SELECT m.id
+            FROM messages AS m
+            LEFT JOIN message_schedules AS s ON s.message_id = m.id
+            WHERE m.project_id = ? AND m.created_ts < ?
+              AND (s.message_id IS NULL OR s.status = 'delivered')
+            "#,
+        );
+        if policy.keep_unread {
+            sql.push_str(
+                " AND NOT EXISTS (SELECT 1 FROM message_recipients AS r WHERE r.message_id = m.id AND r.read_ts IS NULL)",
+            );
+        }
+        if policy.keep_ack_pending {
+            sql.push_str(
+                " AND NOT (m.ack_required = 1 AND EXISTS (SELECT 1 FROM message_recipients AS r WHERE r.message_id = m.id AND r.ack_ts IS NULL))",
+            );
+        }
+        sql.push_str(" ORDER BY m.id");
+
+        let db = mm.db_read();
+        let stmt = db.prepare(&sql).await?;
+        let mut rows = stmt
+            .query((project_id.get(), cutoff.format(TS_FORMAT).to_string()))
+            .await?;
+        let mut ids = Vec::new();
+        while let Some(row) = rows.next().await? {
+            ids.push(row.get(0)?);
+        }
+        Ok(ids)
+    }
+
+    async fn count_recipients(mm: &ModelManager, ids: &[i64]) -> Result<usize> {
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(&format!(
+                "SELECT COUNT(*) FROM message_recipients WHERE message_id IN ({})",
+                placeholders(ids.len())
+            ))
+            .await?;
+        let mut rows = stmt.query(id_params(ids)).await?;
+        match rows.next().await? {
+            Some(row) => Ok(usize::try_from(row.get::<i64>(0)?).unwrap_or_default()),
+            None => Ok(0),
+        }
+    }
+
+    /// Tombstone and delete one batch of messages in a single transaction,
+    /// together with its audit log entry.
+    ///
+    /// Returns the number of message and recipient rows deleted.
+    async fn delete_batch(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        cutoff: NaiveDateTime,
+        ids: &[i64],
+    ) -> Result<(usize, usize)> {
+        let in_list = placeholders(ids.len());
+        let (_tx_guard, tx) = mm.begin_tx().await?;
+
+        for id in ids {
+            tx.execute(
+                "INSERT OR IGNORE INTO pruned_messages (message_id, project_id) VALUES (?, ?)",
+                (*id, project_id.get()),
+            )
+            .await?;
+        }
+
+        let mut recipients = 0;
+        for table in MESSAGE_CHILD_TABLES {
+            let deleted = tx
+                .execute(
+                    &format!("DELETE FROM {} WHERE message_id IN ({})", table, in_list),
+                    id_params(ids),
+                )
+                .await?;
+            if table == "message_recipients" {
+                recipients = deleted;
+            }
+        }
+
+        // The FTS5 delete trigger keeps messages_fts in step
+        let messages = tx
+            .execute(
+                &format!("DELETE FROM messages WHERE id IN ({})", in_list),
+                id_params(ids),
+            )
+            .await?;
+
+        AuditBmc::record_in(
+            &tx,
+            ctx,
+            project_id,
+            AuditAction::RetentionPrune,
+            project_id.get(),
+            serde_json::json!({
+                "before": {
+                    "cutoff": cutoff.format(TS_FORMAT).to_string(),
+                    "first_message_id": ids.first(),
+                    "last_message_id": ids.last(),
+                },
+                "after": { "messages_pruned": messages, "recipients_pruned": recipients },
+            }),
+        )
+        .await?;
+        tx.commit().await?;
+
+        Ok((
+            usize::try_from(messages).unwrap_or_default(),
+            usize::try_from(recipients).unwrap_or_default(),
+        ))
+    }
+}
+
+/// `?, ?, ...` for an `IN` list of `n` values.
+fn placeholders(n: usize) -> String {
+    vec!["?"; n].join(", ")
+}
+
+fn id_params(ids: &[i64]) -> libsql::params::Params {
+    libsql::params::Params::Positional(ids.iter().map(|id| libsql::Value::Integer(*id)).collect())
+}
@@ -0,0 +1,119 @@
+//! Server capabilities and limits.
+//!
+//! Agents read these to learn which optional features are switched on and
+//! how large a message or reservation may be, rather than finding out by
+//! trial and error. Everything comes from the loaded [`AppConfig`] except the
+//! schema version, which is one `PRAGMA` read; configuration is fixed for the
+//! life of the process, so clients may cache the result.
+//!
+//! [`AppConfig`]: mouchak_mail_common::config::AppConfig
+
+use crate::Result;
+use crate::ctx::Ctx;
+use crate::model::ModelManager;
+use crate::store;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// What this server is running and how it is configured.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ServerInfo {
+    /// Server version
+    pub version: String,
+    /// Schema version of the database (`PRAGMA user_version`)
+    pub schema_version: i64,
+    /// Schema version this build migrates databases to
+    pub expected_schema_version: i64,
+    pub features: ServerFeatures,
+    pub limits: ServerLimits,
+}
+
+/// Optional features and whether each is on.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ServerFeatures {
+    /// Build slot tools (`WORKTREES_ENABLED` or `GIT_IDENTITY_ENABLED`)
+    pub worktrees: bool,
+    /// Project identity derived from git remotes
+    pub git_identity: bool,
+    /// Per-recipient read and acknowledgement tracking
+    pub read_tracking: bool,
+    /// Attachment and inbox quotas
+    pub quota: bool,
+    /// Request rate limiting
+    pub rate_limit: bool,
+    /// Overdue acknowledgements are flagged
+    pub ack_ttl: bool,
+    /// Overdue acknowledgements are escalated
+    pub escalation: bool,
+    /// Messages are written to the git archive before the send returns
+    pub archive_sync: bool,
+    /// OpenAPI document and Swagger UI are served
+    pub api_docs: bool,
+    /// The web UI is served
+    pub web_ui: bool,
+}
+
+/// Size and time limits the server enforces.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ServerLimits {
+    /// Longest subject, in characters
+    pub max_subject_chars: usize,
+    /// Largest message body, in bytes
+    pub max_body_bytes: usize,
+    /// Most recipients (to + cc + bcc) per message
+    pub max_recipients: usize,
+    /// Longest TTL a file reservation may be renewed for, in seconds
+    pub max_reservation_ttl_seconds: u64,
+    /// How long a sender may recall a message, in seconds
+    pub recall_window_seconds: u64,
+    /// Attachment bytes allowed per project; `None` without quotas
+    pub max_attachment_bytes: Option<u64>,
+    /// Messages allowed per inbox; `None` without quotas
+    pub max_inbox_messages: Option<u64>,
+    /// Sustained requests per second; `None` without rate limiting
+    pub rate_limit_rps: Option<u32>,
+    /// Requests allowed in a burst; `None` without rate limiting
+    pub rate_limit_burst: Option<u32>,
+}
+
+/// Backend Model Controller for server introspection.
+pub struct ServerInfoBmc;
+
+impl ServerInfoBmc {
+    /// Describes the running server.
+    pub async fn get(_ctx: &Ctx, mm: &ModelManager) -> Result<ServerInfo> {
+        let config = &mm.app_config;
+        let schema_version = store::schema_version(mm.db_read()).await?;
+
+        let quota = config.quota.enabled;
+        let rate_limit = config.rate_limit.enabled;
+        Ok(ServerInfo {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            schema_version,
+            expected_schema_version: store::SCHEMA_VERSION,
+            features: ServerFeatures {
+                worktrees: config.mcp.worktrees_active(),
+                git_identity: config.mcp.git_identity_enabled,
+                read_tracking: true,
+                quota,
+                rate_limit,
+                ack_ttl: config.escalation.ack_ttl_enabled,
+                escalation: config.escalation.escalation_enabled,
+                archive_sync: config.archive.sync,
+                api_docs: config.server.api_docs,
+                web_ui: config.server.serve_ui,
+            },
+            limits: ServerLimits {
+                max_subject_chars: config.messages.max_subject_chars,
+                max_body_bytes: config.messages.max_body_bytes,
+                max_recipients: config.messages.max_recipients,
+                max_reservation_ttl_seconds: config.reservations.max_ttl_seconds,
+                recall_window_seconds: config.messages.recall_window_seconds,
+                max_attachment_bytes: quota.then_some(config.quota.attachments_limit_bytes),
+                max_inbox_messages: quota.then_some(config.quota.inbox_limit_count),
+                rate_limit_rps: rate_limit.then_some(config.rate_limit.requests_per_second),
+                rate_limit_burst: rate_limit.then_some(config.rate_limit.burst),
+            },
+        })
+    }
+}
@@ -0,0 +1,272 @@
+//! Message templates (canned directives).
+//!
+//! Overseers send the same handful of directives (STOP, STATUS REPORT,
+//! SWITCH BRANCH, ...) many times a day. A template stores the subject,
+//! body and flags once per project so the composer can pre-fill them.
+//!
+//! Bodies and subjects may contain `{{agent_name}}` and `{{date}}`
+//! placeholders. They are stored verbatim; substitution happens in the
+//! client right before sending.
+
+use crate::Result;
+use crate::ctx::Ctx;
+use crate::model::ModelManager;
+use crate::model::project::ProjectBmc;
+use crate::types::ProjectId;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Importance levels a template may carry, matching `send_message`.
+const VALID_IMPORTANCE: &[&str] = &["low", "normal", "high", "urgent"];
+
+/// A reusable message template stored per project.
+///
+/// # Fields
+///
+/// - `id` - Database primary key
+/// - `project_id` - Owning project
+/// - `name` - Unique template name within the project
+/// - `subject` - Subject line, may contain placeholders
+/// - `body_md` - Markdown body, may contain placeholders
+/// - `importance` - Importance applied when the template is used
+/// - `ack_required` - Whether messages from this template request an ack
+/// - `created_ts` - Creation timestamp
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MessageTemplate {
+    pub id: i64,
+    pub project_id: i64,
+    pub name: String,
+    pub subject: String,
+    pub body_md: String,
+    pub importance: String,
+    pub ack_required: bool,
+    pub created_ts: NaiveDateTime,
+}
+
+/// Input data for creating a template.
+///
+/// # Fields
+///
+/// - `project_id` - Project to attach the template to
+/// - `name` - Template name (must be unique in the project)
+/// - `subject` - Subject line
+/// - `body_md` - Markdown body
+/// - `importance` - "low", "normal" (default), "high" or "urgent"
+/// - `ack_required` - Request acknowledgment (default false)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateForCreate {
+    pub project_id: ProjectId,
+    pub name: String,
+    pub subject: String,
+    pub body_md: String,
+    pub importance: Option<String>,
+    #[serde(default)]
+    pub ack_required: bool,
+}
+
+/// Backend Model Controller for message templates.
+pub struct TemplateBmc;
+
+impl TemplateBmc {
+    /// Creates a template.
+    ///
+    /// # Returns
+    /// The created template's database ID
+    ///
+    /// # Errors
+    /// - [`crate::Error::InvalidInput`] for an empty name or subject, or an
+    ///   unknown importance
+    /// - [`crate::Error::TemplateNameTaken`] if the project already has a
+    ///   template with this name
+    pub async fn create(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        template_c: TemplateForCreate,
+    ) -> Result<i64> {
+        ProjectBmc::ensure_access(ctx, mm, template_c.project_id).await?;
+
+        let name = template_c.name.trim();
+        if name.is_empty() {
+            return Err(crate::Error::InvalidInput(
+                "Template name cannot be empty".into(),
+            ));
+        }
+        if template_c.subject.trim().is_empty() {
+            return Err(crate::Error::InvalidInput(
+                "Template subject cannot be empty".into(),
+            ));
+        }
+        let importance = template_c.importance.as_deref().unwrap_or("normal");
+        if !VALID_IMPORTANCE.contains(&importance) {
+            return Err(crate::Error::InvalidInput(format!(
+                "Invalid importance '{}': expected one of {}",
+                importance,
+                VALID_IMPORTANCE.join(", ")
+            )));
+        }
+
+        if Self::get_by_name(ctx, mm, template_c.project_id, name)
+            .await?
+            .is_some()
+        {
+            return Err(crate::Error::TemplateNameTaken(name.to_string()));
+        }
+
+        let db = mm.db();
+        let stmt = db
+            .prepare(
+                r#"
+            INSERT INTO message_templates (project_id, name, subject, body_md, importance, ack_required)
+            VALUES (?, ?, ?, ?, ?, ?)
+            RETURNING id
+            "#,
+            )
+            .await?;
+
+        // A concurrent create can still slip past the lookup above; the
+        // UNIQUE (project_id, name) constraint reports it the same way, on
+        // the first RETURNING row.
+        let row = match stmt
+            .query((
+                template_c.project_id.get(),
+                name,
+                template_c.subject.as_str(),
+                template_c.body_md.as_str(),
+                importance,
+                template_c.ack_required,
+            ))
+            .await
+        {
+            Ok(mut rows) => rows.next().await,
+            Err(e) => Err(e),
+        };
+        match row {
+            Ok(Some(row)) => Ok(row.get::<i64>(0)?),
+            Ok(None) => Err(crate::Error::InvalidInput(
+                "Failed to create template".into(),
+            )),
+            Err(e) if e.to_string().contains("UNIQUE constraint failed") => {
+                Err(crate::Error::TemplateNameTaken(name.to_string()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Lists a project's templates, ordered by name.
+    pub async fn list_for_project(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+    ) -> Result<Vec<MessageTemplate>> {
+        ProjectBmc::ensure_access(ctx, mm, project_id).await?;
+
+        let db = mm.db();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT id, project_id, name, subject, body_md, importance, ack_required, created_ts
+            FROM message_templates
+            WHERE project_id = ?
+            ORDER BY name ASC
+            "#,
+            )
+            .await?;
+
+        let mut rows = stmt.query([project_id.get()]).await?;
+        let mut templates = Vec::new();
+        while let Some(row) = rows.next().await? {
+            templates.push(Self::from_row(row)?);
+        }
+        Ok(templates)
+    }
+
+    /// Gets a template by ID within a project.
+    ///
+    /// # Errors
+    /// Returns [`crate::Error::TemplateNotFound`] if the project has no
+    /// template with this ID.
+    pub async fn get(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        template_id: i64,
+    ) -> Result<MessageTemplate> {
+        ProjectBmc::ensure_access(ctx, mm, project_id).await?;
+
+        let db = mm.db();
+        let stmt = db
+            .prepare(
+                r#"
+            SELECT id, project_id, name, subject, body_md, importance, ack_required, created_ts
+            FROM message_templates
+            WHERE project_id = ? AND id = ?
+            "#,
+            )
+            .await?;
+
+        let mut rows = stmt.query((project_id.get(), template_id)).await?;
+        match rows.next().await? {
+            Some(row) => Self::from_row(row),
+            None => Err(crate::Error::TemplateNotFound(template_id)),
+        }
+    }
+
+    /// Deletes a template.
+    ///
+    /// # Errors
+    /// Returns [`crate::Error::TemplateNotFound`] if the project has no
+    /// template with this ID.
+    pub async fn delete(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        template_id: i64,
+    ) -> Result<()> {
+        ProjectBmc::ensure_access(ctx, mm, project_id).await?;
+
+        let db = mm.db();
+        let stmt = db
+            .prepare("DELETE FROM message_templates WHERE project_id = ? AND id = ?")
+            .await?;
+        let affected = stmt.execute((project_id.get(), template_id)).await?;
+        if affected == 0 {
+            return Err(crate::Error::TemplateNotFound(template_id));
+        }
+        Ok(())
+    }
+
+    async fn get_by_name(
+        _ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        name: &str,
+    ) -> Result<Option<i64>> {
+        let db = mm.db();
+        let stmt = db
+            .prepare("SELECT id FROM message_templates WHERE project_id = ? AND name = ?")
+            .await?;
+        let mut rows = stmt.query((project_id.get(), name)).await?;
+        match rows.next().await? {
+            Some(row) => Ok(Some(row.get::<i64>(0)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn from_row(row: libsql::Row) -> Result<MessageTemplate> {
+        let created_ts_str: String = row.get(7).unwrap_or_default();
+        let created_ts =
+            NaiveDateTime::parse_from_str(&created_ts_str, "%Y-%m-%d %H:%M:%S").unwrap_or_default();
+
+        Ok(MessageTemplate {
+            id: row.get(0)?,
+            project_id: row.get(1)?,
+            name: row.get(2)?,
+            subject: row.get(3)?,
+            body_md: row.get(4)?,
+            importance: row.get(5)?,
+            ack_required: row.get(6)?,
+            created_ts,
+        })
+    }
+}
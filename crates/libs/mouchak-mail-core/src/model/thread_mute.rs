@@ -0,0 +1,156 @@
+//! Per-agent thread mutes.
+//!
+//! An agent stuck on a noisy thread can mute it instead of turning on DND.
+//! [`MessageBmc::list_inbox_for_agent`](crate::model::message::MessageBmc::list_inbox_for_agent)
+//! then leaves the thread's messages out of that agent's inbox unless they
+//! are urgent or @-mention the agent in the subject. Messages are still
+//! delivered and stay visible in the thread view, so unmuting brings back
+//! everything sent in the meantime.
+
+use crate::Result;
+use crate::ctx::Ctx;
+use crate::model::ModelManager;
+use crate::model::agent::AgentBmc;
+use crate::model::project::ProjectBmc;
+use crate::types::{AgentId, ProjectId};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// A thread muted by one agent.
+///
+/// # Fields
+///
+/// - `project_id` - Project the thread belongs to
+/// - `thread_id` - Muted thread
+/// - `agent_id` / `agent_name` - Agent that muted it
+/// - `muted_ts` - When it was muted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct ThreadMute {
+    pub project_id: i64,
+    pub thread_id: String,
+    pub agent_id: i64,
+    pub agent_name: String,
+    pub muted_ts: NaiveDateTime,
+}
+
+/// Backend Model Controller for thread mutes.
+pub struct ThreadMuteBmc;
+
+impl ThreadMuteBmc {
+    /// Mutes a thread for an agent.
+    ///
+    /// # Returns
+    /// `false` if the agent had already muted the thread
+    ///
+    /// # Errors
+    /// - [`crate::Error::AgentNotFound`] if the agent does not exist
+    /// - [`crate::Error::ThreadNotFound`] if the thread has no messages in
+    ///   the agent's project
+    pub async fn mute(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        agent_id: AgentId,
+        thread_id: &str,
+    ) -> Result<bool> {
+        let project_id = Self::agent_project(ctx, mm, agent_id).await?;
+        Self::ensure_thread(mm, project_id, thread_id).await?;
+
+        let db = mm.db();
+        let stmt = db
+            .prepare(
+                "INSERT OR IGNORE INTO thread_mutes (project_id, thread_id, agent_id) VALUES (?, ?, ?)",
+            )
+            .await?;
+        let inserted = stmt
+            .execute((project_id.get(), thread_id, agent_id.get()))
+            .await?;
+        Ok(inserted > 0)
+    }
+
+    /// Unmutes a thread for an agent.
+    ///
+    /// # Returns
+    /// `false` if the agent had not muted the thread
+    ///
+    /// # Errors
+    /// Returns [`crate::Error::AgentNotFound`] if the agent does not exist.
+    pub async fn unmute(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        agent_id: AgentId,
+        thread_id: &str,
+    ) -> Result<bool> {
+        let project_id = Self::agent_project(ctx, mm, agent_id).await?;
+
+        let db = mm.db();
+        let stmt = db
+            .prepare(
+                "DELETE FROM thread_mutes WHERE project_id = ? AND thread_id = ? AND agent_id = ?",
+            )
+            .await?;
+        let removed = stmt
+            .execute((project_id.get(), thread_id, agent_id.get()))
+            .await?;
+        Ok(removed > 0)
+    }
+
+    /// Lists the agents that muted a thread, ordered by name.
+    pub async fn list_for_thread(
+        ctx: &Ctx,
+        mm: &ModelManager,
+        project_id: ProjectId,
+        thread_id: &str,
+    ) -> Result<Vec<ThreadMute>> {
+        ProjectBmc::ensure_access(ctx, mm, project_id).await?;
+
+        let db = mm.db_read();
+        let stmt = db
+            .prepare(
+                r#"
+                SELECT tm.project_id, tm.thread_id, tm.agent_id, a.name, tm.muted_ts
+                FROM thread_mutes AS tm
+                JOIN agents AS a ON a.id = tm.agent_id
+                WHERE tm.project_id = ? AND tm.thread_id = ?
+                ORDER BY a.name
+                "#,
+            )
+            .await?;
+        let mut rows = stmt.query((project_id.get(), thread_id)).await?;
+        let mut mutes = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let muted_ts: String = row.get(4)?;
+            mutes.push(ThreadMute {
+                project_id: row.get(0)?,
+                thread_id: row.get(1)?,
+                agent_id: row.get(2)?,
+                agent_name: row.get(3)?,
+                muted_ts: crate::utils::parse_timestamp(&muted_ts, "thread_mutes.muted_ts"),
+            });
+        }
+        Ok(mutes)
+    }
+
+    /// Project of `agent_id`, after checking the caller may access it.
+    async fn agent_project(ctx: &Ctx, mm: &ModelManager, agent_id: AgentId) -> Result<ProjectId> {
+        let agent = AgentBmc::get(ctx, mm, agent_id).await?;
+        ProjectBmc::ensure_access(ctx, mm, agent.project_id).await?;
+        Ok(agent.project_id)
+    }
+
+    async fn ensure_thread(
+        mm: &ModelManager,
+        project_id: ProjectId,
+        thread_id: &str,
+    ) -> Result<()> {
+        let db = mm.db_read();
+        let stmt = db
+            .prepare("SELECT 1 FROM messages WHERE project_id = ? AND thread_id = ? LIMIT 1")
+            .await?;
+        let mut rows = stmt.query((project_id.get(), thread_id)).await?;
+        if rows.next().await?.is_none() {
+            return Err(crate::Error::ThreadNotFound(thread_id.to_string()));
+        }
+        Ok(())
+    }
+}
@@ -147,7 +147,7 @@ impl TimeTravelBmc {
         Self::validate_slug(project_slug)?;
         Self::validate_agent_name(agent_name)?;
 
-        let repo = mm.get_repo().await?;
+        let repo = mm.get_project_history_repo(project_slug).await?;
         let repo_guard = repo.lock().await;
 
         // Find commit at or before the requested time
@@ -8,7 +8,8 @@
 //!
 //! - **Performance monitoring**: Track tool duration and identify slow tools
 //! - **Error tracking**: Monitor error rates and error codes
-//! - **Usage analytics**: See which tools are used most frequently
+//! - **Usage analytics**: See which tools are used most frequently, with
+//!   latency percentiles over a window ([`ToolMetricBmc::usage`])
 //! - **Debugging**: Review tool arguments for failed invocations
 //!
 //! # Example
@@ -41,6 +42,16 @@ use crate::Ctx;
 use crate::Result;
 use crate::model::ModelManager;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Most tools a [`ToolUsageReport`] lists; the rest only count in its totals.
+pub const MAX_USAGE_TOOLS: i64 = 50;
+
+/// Number of time buckets a usage window is split into.
+pub const USAGE_BUCKETS: i64 = 24;
+
+/// Shortest usage bucket, in seconds.
+const MIN_BUCKET_SECONDS: i64 = 60;
 
 /// A recorded MCP tool invocation metric.
 ///
@@ -57,7 +68,7 @@ use serde::{Deserialize, Serialize};
 /// - `error_code` - Error code if status is "error"
 /// - `duration_ms` - Execution time in milliseconds
 /// - `created_at` - Timestamp of invocation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ToolMetric {
     /// Database primary key.
     pub id: i64,
@@ -259,6 +270,214 @@ impl ToolMetricBmc {
         Ok(stats)
     }
 
+    /// Summarizes tool usage since a point in time.
+    ///
+    /// Backs the metrics dashboard: overall totals plus per-tool statistics
+    /// for every invocation recorded at or after `since`.
+    ///
+    /// # Arguments
+    /// * `_ctx` - Request context
+    /// * `mm` - ModelManager
+    /// * `since` - Start of the window (UTC)
+    ///
+    /// # Returns
+    /// Totals and per-tool statistics (most used first)
+    pub async fn summary(
+        _ctx: &Ctx,
+        mm: &ModelManager,
+        since: chrono::NaiveDateTime,
+    ) -> Result<ToolMetricsSummary> {
+        let db = mm.db();
+        let since_str = since.format("%Y-%m-%d %H:%M:%S").to_string();
+
+        let sql = r#"
+            SELECT
+                tool_name,
+                COUNT(*) as count,
+                AVG(duration_ms) as avg_duration_ms,
+                SUM(CASE WHEN status = 'error' THEN 1 ELSE 0 END) as error_count
+            FROM tool_metrics
+            WHERE created_at >= ?
+            GROUP BY tool_name ORDER BY count DESC
+        "#;
+        let stmt = db.prepare(sql).await?;
+        let mut rows = stmt.query([since_str.clone()]).await?;
+
+        let mut tools = Vec::new();
+        while let Some(row) = rows.next().await? {
+            tools.push(Self::row_to_stat(&row)?);
+        }
+
+        let total_calls: i64 = tools.iter().map(|t| t.count).sum();
+        let error_count: i64 = tools.iter().map(|t| t.error_count).sum();
+        let avg_duration_ms = if total_calls > 0 {
+            tools
+                .iter()
+                .map(|t| t.avg_duration_ms * t.count as f64)
+                .sum::<f64>()
+                / total_calls as f64
+        } else {
+            0.0
+        };
+
+        Ok(ToolMetricsSummary {
+            since: since_str,
+            total_calls,
+            error_count,
+            avg_duration_ms,
+            tools,
+        })
+    }
+
+    /// Per-tool usage since a point in time, aggregated in SQL.
+    ///
+    /// Backs the stats page: calls, errors and nearest-rank latency
+    /// percentiles per tool, and each tool's calls per time bucket. The
+    /// window up to now is split into [`USAGE_BUCKETS`] buckets of at least
+    /// a minute. At most [`MAX_USAGE_TOOLS`] tools are listed, busiest
+    /// first; totals cover every tool.
+    ///
+    /// # Arguments
+    /// * `_ctx` - Request context
+    /// * `mm` - ModelManager
+    /// * `since` - Start of the window (UTC)
+    ///
+    /// # Returns
+    /// Totals and per-tool usage (most used first)
+    pub async fn usage(
+        _ctx: &Ctx,
+        mm: &ModelManager,
+        since: chrono::NaiveDateTime,
+    ) -> Result<ToolUsageReport> {
+        let db = mm.db();
+        let since_str = since.format("%Y-%m-%d %H:%M:%S").to_string();
+        let since_epoch = since.and_utc().timestamp();
+        let window_seconds = (chrono::Utc::now().timestamp() - since_epoch).max(0);
+        let bucket_seconds =
+            ((window_seconds + USAGE_BUCKETS - 1) / USAGE_BUCKETS).max(MIN_BUCKET_SECONDS);
+        let bucket_count = ((window_seconds + bucket_seconds - 1) / bucket_seconds).max(1);
+
+        let stmt = db
+            .prepare(
+                r#"
+                SELECT
+                    COUNT(*),
+                    COALESCE(SUM(CASE WHEN status = 'error' THEN 1 ELSE 0 END), 0),
+                    COUNT(DISTINCT tool_name)
+                FROM tool_metrics
+                WHERE created_at >= ?
+                "#,
+            )
+            .await?;
+        let mut rows = stmt.query([since_str.clone()]).await?;
+        let (total_calls, total_errors, tool_count): (i64, i64, i64) = match rows.next().await? {
+            Some(row) => (row.get(0)?, row.get(1)?, row.get(2)?),
+            None => (0, 0, 0),
+        };
+
+        // Nearest-rank percentile p is the smallest duration whose rank
+        // within its tool reaches p% of the tool's calls
+        let stmt = db
+            .prepare(
+                r#"
+                WITH ranked AS (
+                    SELECT
+                        tool_name,
+                        status,
+                        duration_ms,
+                        ROW_NUMBER() OVER (PARTITION BY tool_name ORDER BY duration_ms) AS rn,
+                        COUNT(*) OVER (PARTITION BY tool_name) AS n
+                    FROM tool_metrics
+                    WHERE created_at >= ?1
+                )
+                SELECT
+                    tool_name,
+                    COUNT(*) AS calls,
+                    SUM(CASE WHEN status = 'error' THEN 1 ELSE 0 END) AS errors,
+                    AVG(duration_ms),
+                    MIN(CASE WHEN rn * 100 >= n * 50 THEN duration_ms END),
+                    MIN(CASE WHEN rn * 100 >= n * 95 THEN duration_ms END),
+                    MIN(CASE WHEN rn * 100 >= n * 99 THEN duration_ms END)
+                FROM ranked
+                GROUP BY tool_name
+                ORDER BY calls DESC, tool_name
+                LIMIT ?2
+                "#,
+            )
+            .await?;
+        let mut rows = stmt.query((since_str.clone(), MAX_USAGE_TOOLS)).await?;
+        let mut tools = Vec::new();
+        while let Some(row) = rows.next().await? {
+            let calls: i64 = row.get(1)?;
+            let errors: i64 = row.get(2)?;
+            tools.push(ToolUsage {
+                tool_name: row.get(0)?,
+                calls,
+                errors,
+                error_rate: errors as f64 / calls as f64,
+                avg_duration_ms: row.get(3)?,
+                p50_ms: row.get(4)?,
+                p95_ms: row.get(5)?,
+                p99_ms: row.get(6)?,
+                buckets: vec![0; bucket_count as usize],
+            });
+        }
+
+        let stmt = db
+            .prepare(
+                r#"
+                SELECT
+                    tool_name,
+                    MIN((CAST(strftime('%s', created_at) AS INTEGER) - ?2) / ?3, ?4) AS bucket,
+                    COUNT(*)
+                FROM tool_metrics
+                WHERE created_at >= ?1 AND tool_name IN (
+                    SELECT tool_name FROM tool_metrics
+                    WHERE created_at >= ?1
+                    GROUP BY tool_name
+                    ORDER BY COUNT(*) DESC, tool_name
+                    LIMIT ?5
+                )
+                GROUP BY tool_name, bucket
+                "#,
+            )
+            .await?;
+        let mut rows = stmt
+            .query((
+                since_str.clone(),
+                since_epoch,
+                bucket_seconds,
+                bucket_count - 1,
+                MAX_USAGE_TOOLS,
+            ))
+            .await?;
+        while let Some(row) = rows.next().await? {
+            let tool_name: String = row.get(0)?;
+            let bucket: i64 = row.get(1)?;
+            let calls: i64 = row.get(2)?;
+            if let Some(tool) = tools.iter_mut().find(|t| t.tool_name == tool_name)
+                && let Some(slot) = tool.buckets.get_mut(bucket.max(0) as usize)
+            {
+                *slot += calls;
+            }
+        }
+
+        Ok(ToolUsageReport {
+            since: since_str,
+            bucket_seconds,
+            total_calls,
+            total_errors,
+            error_rate: if total_calls > 0 {
+                total_errors as f64 / total_calls as f64
+            } else {
+                0.0
+            },
+            tool_count,
+            truncated: tool_count > tools.len() as i64,
+            tools,
+        })
+    }
+
     fn row_to_stat(row: &libsql::Row) -> Result<ToolStat> {
         Ok(ToolStat {
             tool_name: row.get(0)?,
@@ -273,7 +492,7 @@ impl ToolMetricBmc {
 ///
 /// Provides summary metrics including usage count, average duration,
 /// and error rate for a specific tool.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct ToolStat {
     /// Tool name.
     pub tool_name: String,
@@ -284,3 +503,62 @@ pub struct ToolStat {
     /// Count of failed invocations.
     pub error_count: i64,
 }
+
+/// Tool usage summary over a time window.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ToolMetricsSummary {
+    /// Start of the window (UTC, `%Y-%m-%d %H:%M:%S`).
+    pub since: String,
+    /// Total invocations in the window.
+    pub total_calls: i64,
+    /// Failed invocations in the window.
+    pub error_count: i64,
+    /// Average execution duration across all tools in milliseconds.
+    pub avg_duration_ms: f64,
+    /// Per-tool statistics, most used first.
+    pub tools: Vec<ToolStat>,
+}
+
+/// One tool's usage over a window.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ToolUsage {
+    /// Tool name.
+    pub tool_name: String,
+    /// Invocations in the window.
+    pub calls: i64,
+    /// Failed invocations in the window.
+    pub errors: i64,
+    /// `errors / calls`, from 0.0 to 1.0.
+    pub error_rate: f64,
+    /// Average execution duration in milliseconds.
+    pub avg_duration_ms: f64,
+    /// Median execution duration in milliseconds (nearest rank).
+    pub p50_ms: i64,
+    /// 95th percentile execution duration in milliseconds (nearest rank).
+    pub p95_ms: i64,
+    /// 99th percentile execution duration in milliseconds (nearest rank).
+    pub p99_ms: i64,
+    /// Invocations per time bucket, oldest first.
+    pub buckets: Vec<i64>,
+}
+
+/// Per-tool usage over a time window, from [`ToolMetricBmc::usage`].
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ToolUsageReport {
+    /// Start of the window and of the first bucket (UTC, `%Y-%m-%d %H:%M:%S`).
+    pub since: String,
+    /// Length of each bucket in seconds.
+    pub bucket_seconds: i64,
+    /// Invocations of every tool in the window.
+    pub total_calls: i64,
+    /// Failed invocations of every tool in the window.
+    pub total_errors: i64,
+    /// `total_errors / total_calls`, or 0.0 for an empty window.
+    pub error_rate: f64,
+    /// Distinct tools called in the window.
+    pub tool_count: i64,
+    /// True if more tools were called than are listed.
+    pub truncated: bool,
+    /// Per-tool usage, most used first, at most [`MAX_USAGE_TOOLS`].
+    pub tools: Vec<ToolUsage>,
+}
@@ -0,0 +1,105 @@
+//! SQLite connection pool: one writer, several read-only readers.
+//!
+//! A libsql [`Connection`] serializes every statement issued through it, so
+//! sharing one connection makes concurrent reads queue behind each other even
+//! though WAL mode lets separate connections read in parallel. The pool keeps
+//! all writes on a single writer connection (preserving SQLite's one-writer
+//! semantics) and spreads reads round-robin over `read_pool_size` readers.
+//!
+//! Readers are opened with `PRAGMA query_only`, so a write sent to the wrong
+//! handle fails instead of racing the writer.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use mouchak_mail_core::store::db_pool::DbPool;
+//!
+//! # async fn example() -> mouchak_mail_core::Result<()> {
+//! let pool = DbPool::open(std::path::Path::new("data/mouchak_mail.db"), 4).await?;
+//! let mut rows = pool.reader().query("SELECT COUNT(*) FROM projects", ()).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::Result;
+use crate::store::Db;
+use libsql::{Builder, Connection};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// One writer connection plus a round-robin set of read-only connections.
+pub struct DbPool {
+    writer: Db,
+    readers: Vec<Db>,
+    next_reader: AtomicUsize,
+}
+
+impl DbPool {
+    /// Open `read_pool_size` readers and one writer on the database at `path`.
+    ///
+    /// PRAGMAs are applied to every connection; migrations are not run.
+    pub async fn open(path: &Path, read_pool_size: usize) -> Result<Self> {
+        let db = Builder::new_local(path).build().await?;
+
+        let writer = db.connect()?;
+        configure_connection(&writer).await;
+
+        let mut readers = Vec::with_capacity(read_pool_size);
+        for _ in 0..read_pool_size {
+            let reader = db.connect()?;
+            configure_connection(&reader).await;
+            let _ = reader.execute("PRAGMA query_only=ON;", ()).await;
+            readers.push(reader);
+        }
+
+        Ok(Self {
+            writer,
+            readers,
+            next_reader: AtomicUsize::new(0),
+        })
+    }
+
+    /// Wrap a single connection used for both reads and writes.
+    pub fn single(conn: Db) -> Self {
+        Self {
+            writer: conn,
+            readers: Vec::new(),
+            next_reader: AtomicUsize::new(0),
+        }
+    }
+
+    /// The connection for INSERT/UPDATE/DELETE and transactions.
+    pub fn writer(&self) -> &Db {
+        &self.writer
+    }
+
+    /// Next read-only connection, or the writer when the pool has no readers.
+    pub fn reader(&self) -> &Db {
+        if self.readers.is_empty() {
+            return &self.writer;
+        }
+        let i = self.next_reader.fetch_add(1, Ordering::Relaxed) % self.readers.len();
+        &self.readers[i]
+    }
+
+    /// Number of dedicated read connections.
+    pub fn reader_count(&self) -> usize {
+        self.readers.len()
+    }
+}
+
+/// Apply the per-connection concurrency PRAGMAs.
+///
+/// `busy_timeout` and `cache_size` are connection-scoped in SQLite, so every
+/// pooled connection needs them, not just the first.
+async fn configure_connection(conn: &Connection) {
+    // WAL mode: enables concurrent reads during writes
+    let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
+    // busy_timeout: wait up to 30 seconds when database is locked (instead of failing immediately)
+    // This is critical for 100+ concurrent agents writing simultaneously
+    let _ = conn.execute("PRAGMA busy_timeout=30000;", ()).await;
+    // synchronous=NORMAL: good balance of safety and performance with WAL
+    let _ = conn.execute("PRAGMA synchronous=NORMAL;", ()).await;
+    // cache_size: increase cache to reduce disk I/O (negative = KB, so -64000 = 64MB)
+    let _ = conn.execute("PRAGMA cache_size=-64000;", ()).await;
+}
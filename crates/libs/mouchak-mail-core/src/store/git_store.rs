@@ -11,6 +11,10 @@
 //! - Entity data is serialized to JSON
 //! - Each change creates a Git commit with author attribution
 //!
+//! The commit author is whoever caused the change (see
+//! [`Actor`](crate::ctx::Actor)); the committer is always the service
+//! identity, [`SERVICE_NAME`] / [`SERVICE_EMAIL`].
+//!
 //! # Example
 //!
 //! ```no_run
@@ -25,8 +29,47 @@
 //! ```
 
 use crate::Result;
-use git2::{Error as GitError, Oid, Repository, Signature, Tree};
-use std::path::Path;
+use crate::ctx::Actor;
+use git2::{Error as GitError, IndexEntry, IndexTime, Oid, Repository, Signature, Tree};
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Committer name for every archive commit.
+pub const SERVICE_NAME: &str = "mcp-bot";
+
+/// Committer email for every archive commit.
+pub const SERVICE_EMAIL: &str = "mcp-bot@localhost";
+
+/// Author name and email for a change made by `actor`, falling back to the
+/// service identity when the actor is unknown.
+pub fn author_of(actor: Option<&Actor>) -> (&str, &str) {
+    actor.map_or((SERVICE_NAME, SERVICE_EMAIL), |a| (a.name(), a.email()))
+}
+
+/// Appends an `Actor:` trailer naming `actor` to a commit message.
+///
+/// Returns the message unchanged when the actor is unknown.
+///
+/// # Example
+///
+/// ```
+/// use mouchak_mail_core::ctx::Actor;
+/// use mouchak_mail_core::store::git_store::with_actor_trailer;
+///
+/// let actor = Actor::agent("BlueLake", "backend-api");
+/// assert_eq!(
+///     with_actor_trailer("mail: hi", Some(&actor)),
+///     "mail: hi\n\nActor: BlueLake <bluelake@backend-api.local>"
+/// );
+/// assert_eq!(with_actor_trailer("mail: hi", None), "mail: hi");
+/// ```
+pub fn with_actor_trailer(message: &str, actor: Option<&Actor>) -> String {
+    match actor {
+        Some(actor) => format!("{}\n\nActor: {}", message, actor),
+        None => message.to_string(),
+    }
+}
 
 /// Initializes or opens a Git repository at the given path.
 ///
@@ -82,19 +125,16 @@ pub fn open_repo<P: AsRef<Path>>(path: P) -> Result<Repository> {
     Repository::open(path).map_err(crate::Error::from)
 }
 
-/// Creates a commit with the given tree and signature
-fn create_commit(
-    repo: &Repository,
-    tree: &Tree,
-    signature: &Signature,
-    message: &str,
-) -> Result<Oid> {
+/// Creates a commit with the given tree and author, committed by the
+/// service identity
+fn create_commit(repo: &Repository, tree: &Tree, author: &Signature, message: &str) -> Result<Oid> {
+    let committer = Signature::now(SERVICE_NAME, SERVICE_EMAIL)?;
     let parent_commit_opt = find_last_commit(repo)?;
     let commit_oid = match parent_commit_opt {
         Some(ref parent) => {
-            repo.commit(Some("HEAD"), signature, signature, message, tree, &[parent])?
+            repo.commit(Some("HEAD"), author, &committer, message, tree, &[parent])?
         }
-        None => repo.commit(Some("HEAD"), signature, signature, message, tree, &[])?,
+        None => repo.commit(Some("HEAD"), author, &committer, message, tree, &[])?,
     };
     Ok(commit_oid)
 }
@@ -110,7 +150,7 @@ fn create_commit(
 /// * `file_path` - Relative path within the repository
 /// * `content` - File content to write
 /// * `message` - Commit message
-/// * `author_name` - Git author name
+/// * `author_name` - Git author name (the committer is always the service)
 /// * `author_email` - Git author email
 ///
 /// # Returns
@@ -156,9 +196,9 @@ pub fn commit_file<P: AsRef<Path>>(
     let mut index = repo.index()?;
     index.add_path(file_path.as_ref())?;
     let tree = repo.find_tree(index.write_tree()?)?;
-    let signature = Signature::now(author_name, author_email)?;
+    let author = Signature::now(author_name, author_email)?;
 
-    create_commit(repo, &tree, &signature, message)
+    create_commit(repo, &tree, &author, message)
 }
 
 /// Commits multiple existing files to the repository in a single commit.
@@ -171,7 +211,7 @@ pub fn commit_file<P: AsRef<Path>>(
 /// * `repo` - The Git repository
 /// * `paths` - Slice of relative paths to commit
 /// * `message` - Commit message
-/// * `author_name` - Git author name
+/// * `author_name` - Git author name (the committer is always the service)
 /// * `author_email` - Git author email
 ///
 /// # Returns
@@ -189,9 +229,177 @@ pub fn commit_paths<P: AsRef<Path>>(
         index.add_path(path.as_ref())?;
     }
     let tree = repo.find_tree(index.write_tree()?)?;
-    let signature = Signature::now(author_name, author_email)?;
+    let author = Signature::now(author_name, author_email)?;
+
+    create_commit(repo, &tree, &author, message)
+}
+
+/// Stages additions and directory removals together and commits them once.
+///
+/// Used when a change both moves and deletes archive content (e.g. project
+/// adoption), so history shows a single commit instead of a delete/add pair.
+///
+/// # Arguments
+///
+/// * `repo` - The Git repository
+/// * `added` - Relative paths of files that exist on disk to stage
+/// * `removed_dirs` - Relative directories to drop from the index
+/// * `message` - Commit message
+/// * `author_name` - Git author name (the committer is always the service)
+/// * `author_email` - Git author email
+///
+/// # Returns
+///
+/// The OID of the created commit.
+pub fn commit_changes<P: AsRef<Path>, Q: AsRef<Path>>(
+    repo: &Repository,
+    added: &[P],
+    removed_dirs: &[Q],
+    message: &str,
+    author_name: &str,
+    author_email: &str,
+) -> Result<Oid> {
+    let mut index = repo.index()?;
+    for dir in removed_dirs {
+        index.remove_dir(dir.as_ref(), 0)?;
+    }
+    for path in added {
+        index.add_path(path.as_ref())?;
+    }
+    index.write()?;
+
+    let tree = repo.find_tree(index.write_tree()?)?;
+    let author = Signature::now(author_name, author_email)?;
+
+    create_commit(repo, &tree, &author, message)
+}
+
+/// Scratch directory inside `.git` where a commit's files are written before
+/// any of them reach the working tree.
+///
+/// [`StagingArea::commit`] hashes the staged files straight into a commit on
+/// top of HEAD, so a write that fails half way leaves both HEAD and the
+/// working tree untouched. The directory is removed when dropped.
+///
+/// # Example
+///
+/// ```no_run
+/// use mouchak_mail_core::store::git_store::{StagingArea, init_or_open_repo};
+///
+/// # fn example() -> mouchak_mail_core::Result<()> {
+/// let repo = init_or_open_repo("data/audit")?;
+/// let staging = StagingArea::new(&repo)?;
+/// std::fs::create_dir_all(staging.root().join("agents"))?;
+/// std::fs::write(staging.root().join("agents/1.json"), "{}")?;
+/// staging.commit(&repo, &["agents/1.json"], "Create agent", "system", "system@local")?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct StagingArea {
+    root: PathBuf,
+}
+
+impl StagingArea {
+    /// Creates an empty staging directory under the repository's `.git`.
+    pub fn new(repo: &Repository) -> Result<Self> {
+        static NEXT: AtomicU64 = AtomicU64::new(0);
+        let root = repo.path().join("mouchak-staging").join(format!(
+            "{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+
+    /// Directory to write files into, laid out like the working tree.
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Commits the staged `paths` on top of HEAD in a single commit, then
+    /// moves them into the working tree and the repository index.
+    ///
+    /// Identical files share one blob, so copying a file to several paths
+    /// costs no extra space in the repository.
+    ///
+    /// # Arguments
+    ///
+    /// * `repo` - The Git repository the staging area was created for
+    /// * `paths` - Relative paths of staged files, as they appear in the tree
+    /// * `message` - Commit message
+    /// * `author_name` - Git author name (the committer is always the service)
+    /// * `author_email` - Git author email
+    ///
+    /// # Returns
+    ///
+    /// The OID of the created commit.
+    pub fn commit<P: AsRef<Path>>(
+        self,
+        repo: &Repository,
+        paths: &[P],
+        message: &str,
+        author_name: &str,
+        author_email: &str,
+    ) -> Result<Oid> {
+        let workdir = repo
+            .workdir()
+            .ok_or_else(|| GitError::from_str("Repository has no working directory"))?;
+        let paths: BTreeSet<&Path> = paths.iter().map(AsRef::as_ref).collect();
+
+        // Build the tree in a private index so nothing is half-staged on error
+        let mut index = git2::Index::new()?;
+        if let Some(head) = find_last_commit(repo)? {
+            index.read_tree(&head.tree()?)?;
+        }
+        for &rel in &paths {
+            let staged = self.root.join(rel);
+            let entry = IndexEntry {
+                ctime: IndexTime::new(0, 0),
+                mtime: IndexTime::new(0, 0),
+                dev: 0,
+                ino: 0,
+                mode: 0o100644,
+                uid: 0,
+                gid: 0,
+                file_size: std::fs::metadata(&staged)?.len() as u32,
+                id: repo.blob_path(&staged)?,
+                flags: 0,
+                flags_extended: 0,
+                path: index_path(rel).into_bytes(),
+            };
+            index.add(&entry)?;
+        }
+        let tree = repo.find_tree(index.write_tree_to(repo)?)?;
+        let author = Signature::now(author_name, author_email)?;
+        let oid = create_commit(repo, &tree, &author, message)?;
+
+        let mut repo_index = repo.index()?;
+        for rel in paths {
+            let target = workdir.join(rel);
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::rename(self.root.join(rel), &target)?;
+            repo_index.add_path(rel)?;
+        }
+        repo_index.write()?;
+        Ok(oid)
+    }
+}
+
+impl Drop for StagingArea {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
 
-    create_commit(repo, &tree, &signature, message)
+/// A relative path as Git stores it: `/`-separated, whatever the platform.
+fn index_path(rel: &Path) -> String {
+    rel.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
 }
 
 /// Finds the last commit in the repository, returns None if no commits exist.
@@ -393,7 +601,7 @@ pub fn get_commit_time(repo: &Repository, commit_oid: Oid) -> Result<i64> {
 /// * `repo` - The Git repository
 /// * `path` - Relative path to remove from the index
 /// * `message` - Commit message
-/// * `author_name` - Git author name
+/// * `author_name` - Git author name (the committer is always the service)
 /// * `author_email` - Git author email
 ///
 /// # Returns
@@ -412,7 +620,7 @@ pub fn commit_deletion<P: AsRef<Path>>(
 
     let tree_oid = index.write_tree()?;
     let tree = repo.find_tree(tree_oid)?;
-    let signature = Signature::now(author_name, author_email)?;
+    let author = Signature::now(author_name, author_email)?;
 
-    create_commit(repo, &tree, &signature, message)
+    create_commit(repo, &tree, &author, message)
 }
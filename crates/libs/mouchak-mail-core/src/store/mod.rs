@@ -2,7 +2,8 @@
 //!
 //! This module provides the storage layer for lib-core, handling:
 //!
-//! - **Database connections**: SQLite via libsql with optimized settings
+//! - **Database connections**: SQLite via libsql with optimized settings,
+//!   pooled as one writer plus read-only readers (`db_pool` submodule)
 //! - **Git storage**: Audit trail for entities via git2
 //!
 //! # Architecture
@@ -28,6 +29,7 @@
 //! - WAL mode for concurrent reads during writes
 //! - 30-second busy timeout for lock contention
 //! - 64MB cache for reduced I/O
+//! - Reads spread over `database.read_pool_size` read-only connections
 //!
 //! # Example
 //!
@@ -35,14 +37,17 @@
 //! use mouchak_mail_core::store::new_db_pool;
 //!
 //! async fn setup() -> mouchak_mail_core::Result<()> {
-//!     let db = new_db_pool().await?;
+//!     let pool = new_db_pool(4).await?;
 //!     // Database is ready with migrations applied
 //!     Ok(())
 //! }
 //! ```
 
 use crate::Result;
+use crate::store::db_pool::DbPool;
+use crate::utils::validation::validate_agent_name;
 use libsql::{Builder, Connection};
+use serde::Serialize;
 use std::path::PathBuf;
 
 /// Resolves the database path, ensuring consistency regardless of CWD.
@@ -52,7 +57,7 @@ use std::path::PathBuf;
 /// 2. `CARGO_WORKSPACE_DIR` env var + "data/mouchak_mail.db"
 /// 3. Walk up directories to find workspace root (contains Cargo.toml with [workspace])
 /// 4. Fall back to CWD + "data/mouchak_mail.db"
-fn resolve_db_path() -> PathBuf {
+pub fn resolve_db_path() -> PathBuf {
     // 1. Check for explicit DATABASE_PATH
     if let Ok(path) = std::env::var("DATABASE_PATH") {
         let p = PathBuf::from(&path);
@@ -105,6 +110,9 @@ fn resolve_db_path() -> PathBuf {
 /// Uses libsql's [`Connection`] for SQLite access.
 pub type Db = Connection;
 
+/// Writer plus read-only connection pool.
+pub mod db_pool;
+
 /// Git storage operations for audit logging.
 pub mod git_store;
 
@@ -117,17 +125,139 @@ pub mod archive_lock;
 /// File handle safety patterns documentation (PORT-2.3).
 pub mod file_safety;
 
+/// Retry helper for writes that hit SQLITE_BUSY.
+pub mod retry;
+
+/// Embedded migrations, applied in order. A database runs each one once.
+const MIGRATIONS: &[&str] = &[
+    include_str!("../../../../../migrations/001_initial_schema.sql"),
+    include_str!("../../../../../migrations/002_agent_capabilities.sql"),
+    include_str!("../../../../../migrations/003_tool_metrics.sql"),
+    include_str!("../../../../../migrations/004_attachments.sql"),
+    include_str!("../../../../../migrations/005_attachments_agent.sql"),
+    include_str!("../../../../../migrations/006_query_indexes.sql"),
+    include_str!("../../../../../migrations/007_unread_counts_index.sql"),
+    include_str!("../../../../../migrations/008_outbox_index.sql"),
+    include_str!("../../../../../migrations/009_message_recalls.sql"),
+    include_str!("../../../../../migrations/010_scheduled_messages.sql"),
+    include_str!("../../../../../migrations/011_agent_retirements.sql"),
+    include_str!("../../../../../migrations/012_unified_inbox_indexes.sql"),
+    include_str!("../../../../../migrations/013_message_broadcasts.sql"),
+    include_str!("../../../../../migrations/014_message_templates.sql"),
+    include_str!("../../../../../migrations/015_thread_listing_index.sql"),
+    include_str!("../../../../../migrations/016_agent_dnd.sql"),
+    include_str!("../../../../../migrations/017_message_labels.sql"),
+    include_str!("../../../../../migrations/018_reservation_queue.sql"),
+    include_str!("../../../../../migrations/019_message_retention.sql"),
+    include_str!("../../../../../migrations/020_audit_log.sql"),
+    include_str!("../../../../../migrations/021_thread_mutes.sql"),
+    include_str!("../../../../../migrations/022_cross_project_recipients.sql"),
+    include_str!("../../../../../migrations/023_thread_sequences.sql"),
+    include_str!("../../../../../migrations/024_notifications_read.sql"),
+    include_str!("../../../../../migrations/025_project_versions.sql"),
+    include_str!("../../../../../migrations/026_agent_tokens.sql"),
+    include_str!("../../../../../migrations/027_message_forwards.sql"),
+    include_str!("../../../../../migrations/028_reservation_transfers.sql"),
+    include_str!("../../../../../migrations/029_unified_inbox_sort_indexes.sql"),
+    include_str!("../../../../../migrations/030_message_snoozes.sql"),
+    include_str!("../../../../../migrations/031_agent_groups.sql"),
+    include_str!("../../../../../migrations/032_message_bodies.sql"),
+    include_str!("../../../../../migrations/033_thread_counters_by_project.sql"),
+];
+
+/// Schema version of a database with every embedded migration applied.
+///
+/// Recorded in SQLite's `user_version`; databases created before versioning
+/// report 0.
+pub const SCHEMA_VERSION: i64 = MIGRATIONS.len() as i64;
+
+/// Reads the schema version recorded in the database (`PRAGMA user_version`).
+pub async fn schema_version(conn: &Connection) -> Result<i64> {
+    let mut rows = conn.query("PRAGMA user_version", ()).await?;
+    match rows.next().await? {
+        Some(row) => Ok(row.get(0)?),
+        None => Ok(0),
+    }
+}
+
+/// Reads the schema version of the database file at `path` without
+/// migrating it.
+pub async fn read_schema_version(path: &std::path::Path) -> Result<i64> {
+    let db = Builder::new_local(path).build().await?;
+    let conn = db.connect()?;
+    schema_version(&conn).await
+}
+
+/// An existing agent whose name breaks the naming rules enforced at
+/// registration.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct AgentNameIssue {
+    pub project_slug: String,
+    pub name: String,
+    /// The rule broken, or the name it collides with ignoring case
+    pub problem: String,
+}
+
+/// Lists agents registered before name validation whose names are invalid
+/// or differ only in case from another agent in the same project.
+///
+/// Reports only; offending agents are left as they are so their mail keeps
+/// flowing until they are renamed.
+pub async fn agent_name_issues(conn: &Connection) -> Result<Vec<AgentNameIssue>> {
+    let mut rows = conn
+        .query(
+            "SELECT p.slug, a.name,
+                    (SELECT MIN(b.name) FROM agents b
+                     WHERE b.project_id = a.project_id
+                       AND b.name = a.name COLLATE NOCASE AND b.name <> a.name)
+             FROM agents a JOIN projects p ON p.id = a.project_id
+             ORDER BY p.slug, a.name",
+            (),
+        )
+        .await?;
+
+    let mut issues = Vec::new();
+    while let Some(row) = rows.next().await? {
+        let project_slug: String = row.get(0)?;
+        let name: String = row.get(1)?;
+        let clash: Option<String> = row.get(2)?;
+        let problem = match validate_agent_name(&name) {
+            Err(e) => e.to_string(),
+            Ok(()) => match clash {
+                Some(other) => format!("differs only in case from '{}'", other),
+                None => continue,
+            },
+        };
+        issues.push(AgentNameIssue {
+            project_slug,
+            name,
+            problem,
+        });
+    }
+    Ok(issues)
+}
+
+/// Runs [`agent_name_issues`] against the database file at `path` without
+/// migrating it.
+pub async fn read_agent_name_issues(path: &std::path::Path) -> Result<Vec<AgentNameIssue>> {
+    let db = Builder::new_local(path).build().await?;
+    let conn = db.connect()?;
+    agent_name_issues(&conn).await
+}
+
 /// Creates a new database connection pool with migrations applied.
 ///
 /// This function:
 /// 1. Creates the `data/` directory if needed
 /// 2. Opens or creates the SQLite database
-/// 3. Applies concurrency optimizations (WAL, timeouts, cache)
-/// 4. Runs all migrations
+/// 3. Opens one writer and `read_pool_size` read-only connections, applying
+///    concurrency optimizations (WAL, timeouts, cache) to each
+/// 4. Runs pending migrations on the writer and records [`SCHEMA_VERSION`]
+/// 5. Logs a warning for each agent name flagged by [`agent_name_issues`]
 ///
 /// # Returns
 ///
-/// A configured database connection ready for use.
+/// A configured connection pool ready for use.
 ///
 /// # Errors
 ///
@@ -142,11 +272,11 @@ pub mod file_safety;
 /// use mouchak_mail_core::store::new_db_pool;
 ///
 /// # async fn example() -> mouchak_mail_core::Result<()> {
-/// let db = new_db_pool().await?;
+/// let pool = new_db_pool(4).await?;
 /// # Ok(())
 /// # }
 /// ```
-pub async fn new_db_pool() -> Result<Db> {
+pub async fn new_db_pool(read_pool_size: usize) -> Result<DbPool> {
     // Resolve database path (handles CWD-independence)
     let db_path = resolve_db_path();
 
@@ -155,37 +285,95 @@ pub async fn new_db_pool() -> Result<Db> {
         std::fs::create_dir_all(parent)?;
     }
 
-    tracing::info!("Opening database at: {}", db_path.display());
-    let db = Builder::new_local(&db_path).build().await?;
-    let conn = db.connect()?;
+    tracing::info!(
+        "Opening database at: {} ({} read connections)",
+        db_path.display(),
+        read_pool_size
+    );
+    let pool = DbPool::open(&db_path, read_pool_size).await?;
+    apply_migrations(pool.writer()).await?;
 
-    // SQLite concurrency optimizations for high-load scenarios
-    // WAL mode: enables concurrent reads during writes
-    let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
-    // busy_timeout: wait up to 30 seconds when database is locked (instead of failing immediately)
-    // This is critical for 100+ concurrent agents writing simultaneously
-    let _ = conn.execute("PRAGMA busy_timeout=30000;", ()).await;
-    // synchronous=NORMAL: good balance of safety and performance with WAL
-    let _ = conn.execute("PRAGMA synchronous=NORMAL;", ()).await;
-    // cache_size: increase cache to reduce disk I/O (negative = KB, so -64000 = 64MB)
-    let _ = conn.execute("PRAGMA cache_size=-64000;", ()).await;
-
-    // Apply all migrations in order
-    // Note: SQLite's IF NOT EXISTS makes this idempotent for table creation
-    let migrations = [
-        include_str!("../../../../../migrations/001_initial_schema.sql"),
-        include_str!("../../../../../migrations/002_agent_capabilities.sql"),
-        include_str!("../../../../../migrations/003_tool_metrics.sql"),
-        include_str!("../../../../../migrations/004_attachments.sql"),
-        include_str!("../../../../../migrations/005_attachments_agent.sql"),
-        include_str!("../../../../../migrations/006_query_indexes.sql"),
-    ];
-
-    for migration in &migrations {
-        conn.execute_batch(migration).await?;
+    for issue in agent_name_issues(pool.writer()).await? {
+        tracing::warn!(
+            project = %issue.project_slug,
+            agent = %issue.name,
+            "Agent name needs renaming: {}",
+            issue.problem
+        );
     }
 
-    Ok(conn)
+    Ok(pool)
+}
+
+/// Applies the embedded migrations `conn` hasn't recorded yet, in order,
+/// and records [`SCHEMA_VERSION`].
+///
+/// Each migration runs in its own transaction together with the
+/// `user_version` bump to its number, so a failure leaves the database at
+/// the last migration that completed, never with one half applied.
+/// Migrations at or below the recorded version are skipped, so a migration
+/// may make one-time changes such as `ALTER TABLE`. Databases from before
+/// versioning report 0 and run every migration; those up to 031 use
+/// `IF NOT EXISTS` and are safe to repeat. A version written by a newer
+/// binary is never lowered.
+pub async fn apply_migrations(conn: &Connection) -> Result<()> {
+    migrate_to(conn, SCHEMA_VERSION).await
+}
+
+/// Applies pending migrations up to and including `target`.
+pub(crate) async fn migrate_to(conn: &Connection, target: i64) -> Result<()> {
+    let current = schema_version(conn).await?;
+    if current >= target {
+        return Ok(());
+    }
+
+    let pending = MIGRATIONS
+        .iter()
+        .enumerate()
+        .take(target as usize)
+        .skip(current.max(0) as usize);
+    for (index, migration) in pending {
+        let version = index as i64 + 1;
+        let tx = conn.transaction().await?;
+        tx.execute_batch(migration).await?;
+        if version == BODY_HASH_MIGRATION {
+            backfill_body_hashes(&tx).await?;
+        }
+        tx.execute(&format!("PRAGMA user_version = {}", version), ())
+            .await?;
+        tx.commit().await?;
+    }
+
+    Ok(())
+}
+
+/// Migration that moves bodies into `message_bodies` (032).
+const BODY_HASH_MIGRATION: i64 = 32;
+
+/// Fills in `message_bodies.body_hash` for bodies moved there by migration
+/// 032, which can't hash in SQL. Runs inside that migration's transaction.
+async fn backfill_body_hashes(conn: &Connection) -> Result<()> {
+    let mut rows = conn
+        .query(
+            "SELECT message_id, body_md FROM message_bodies WHERE body_hash = ''",
+            (),
+        )
+        .await?;
+    let mut hashes = Vec::new();
+    while let Some(row) = rows.next().await? {
+        let body: String = row.get(1)?;
+        hashes.push((row.get::<i64>(0)?, crate::model::message::body_hash(&body)));
+    }
+    drop(rows);
+
+    let stmt = conn
+        .prepare("UPDATE message_bodies SET body_hash = ? WHERE message_id = ?")
+        .await?;
+    for (message_id, hash) in hashes {
+        stmt.execute((hash, message_id)).await?;
+        stmt.reset();
+    }
+    Ok(())
 }
 
 /// Gets a database connection for executing queries.
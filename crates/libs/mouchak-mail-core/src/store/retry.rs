@@ -0,0 +1,285 @@
+//! Retrying writes that fail with `SQLITE_BUSY` / `SQLITE_LOCKED`.
+//!
+//! `busy_timeout` makes SQLite wait for the write lock, but under heavy
+//! contention the timeout can still elapse and "database is locked" reaches
+//! the caller. [`execute_with_retry`] re-runs the operation with jittered
+//! exponential backoff instead.
+//!
+//! Only wrap operations that are safe to run again: a single statement (a
+//! busy statement applies nothing) or a whole transaction, which is rolled
+//! back when its [`libsql::Transaction`] is dropped on error. Never wrap a
+//! sequence of autocommit statements where an early one may already have
+//! been applied.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use mouchak_mail_core::store::retry::{RetryPolicy, execute_with_retry};
+//!
+//! # async fn example(db: &libsql::Connection) -> mouchak_mail_core::Result<()> {
+//! execute_with_retry(&RetryPolicy::default(), || async {
+//!     db.execute("UPDATE agents SET last_active_ts = CURRENT_TIMESTAMP WHERE id = 1", ())
+//!         .await?;
+//!     Ok(())
+//! })
+//! .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Error, Result};
+use mouchak_mail_common::config::DatabaseConfig;
+use rand::Rng;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tracing::warn;
+
+/// Primary result codes for "database is busy" and "table is locked".
+const SQLITE_BUSY: i32 = 5;
+const SQLITE_LOCKED: i32 = 6;
+
+/// Retries performed by [`execute_with_retry`] since startup.
+static BUSY_RETRIES: AtomicU64 = AtomicU64::new(0);
+
+/// Number of busy retries performed process-wide, for metrics.
+pub fn busy_retry_count() -> u64 {
+    BUSY_RETRIES.load(Ordering::Relaxed)
+}
+
+/// How often and how long to back off before giving up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total attempts including the first; at least 1
+    pub max_attempts: u32,
+    /// Backoff ceiling before the first retry
+    pub base_delay: Duration,
+    /// Backoff ceiling for any retry
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn from_config(config: &DatabaseConfig) -> Self {
+        Self {
+            max_attempts: config.busy_retry_attempts.max(1),
+            base_delay: Duration::from_millis(config.busy_retry_base_ms),
+            max_delay: Duration::from_millis(config.busy_retry_max_ms),
+        }
+    }
+
+    /// Upper bound of the wait after failed attempt `attempt` (1-based):
+    /// `base_delay * 2^(attempt - 1)`, capped at `max_delay`.
+    pub fn backoff_ceiling(&self, attempt: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.base_delay.saturating_mul(factor).min(self.max_delay)
+    }
+
+    /// Wait after failed attempt `attempt`: a random point in the upper half
+    /// of [`backoff_ceiling`](Self::backoff_ceiling), so contending writers
+    /// spread out instead of retrying in lockstep.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let ceiling = self.backoff_ceiling(attempt);
+        let half = ceiling / 2;
+        half + (ceiling - half).mul_f64(rand::thread_rng().r#gen::<f64>())
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::from_config(&DatabaseConfig::default())
+    }
+}
+
+/// Whether `err` is a busy/locked failure worth retrying.
+pub fn is_busy_error(err: &libsql::Error) -> bool {
+    if let libsql::Error::SqliteFailure(code, _) = err
+        && matches!(code & 0xff, SQLITE_BUSY | SQLITE_LOCKED)
+    {
+        return true;
+    }
+    let msg = err.to_string().to_lowercase();
+    msg.contains("database is locked")
+        || msg.contains("database table is locked")
+        || msg.contains("database is busy")
+}
+
+/// Run `op`, re-running it while it fails with a busy/locked database error.
+///
+/// Other errors are returned immediately. When every attempt was busy the
+/// result is [`Error::DatabaseBusy`], with the last database error as its
+/// source.
+pub async fn execute_with_retry<T, F, Fut>(policy: &RetryPolicy, op: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    retry_with_sleep(policy, op, tokio::time::sleep).await
+}
+
+/// [`execute_with_retry`] with the sleep injected, so tests can observe the
+/// backoff without waiting for it.
+async fn retry_with_sleep<T, F, Fut, S, SFut>(
+    policy: &RetryPolicy,
+    mut op: F,
+    mut sleep: S,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+    S: FnMut(Duration) -> SFut,
+    SFut: Future<Output = ()>,
+{
+    let max_attempts = policy.max_attempts.max(1);
+    let mut attempt = 1;
+    loop {
+        match op().await {
+            Err(Error::Libsql(e)) if is_busy_error(&e) => {
+                if attempt >= max_attempts {
+                    return Err(Error::DatabaseBusy {
+                        attempts: attempt,
+                        source: e,
+                    });
+                }
+                let delay = policy.backoff(attempt);
+                BUSY_RETRIES.fetch_add(1, Ordering::Relaxed);
+                warn!(
+                    attempt,
+                    delay_ms = delay.as_millis() as u64,
+                    "Database busy, retrying write"
+                );
+                sleep(delay).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+    use std::sync::Mutex;
+
+    fn policy(max_attempts: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(350),
+        }
+    }
+
+    fn busy() -> Error {
+        Error::Libsql(libsql::Error::SqliteFailure(
+            SQLITE_BUSY,
+            "database is locked".to_string(),
+        ))
+    }
+
+    /// Runs `op` through the retry loop, recording the requested sleeps.
+    async fn run<T>(
+        policy: &RetryPolicy,
+        failures: u32,
+        fail: impl Fn() -> Error,
+        value: T,
+    ) -> (Result<T>, u32, Vec<Duration>)
+    where
+        T: Clone,
+    {
+        let calls = Mutex::new(0u32);
+        let sleeps = Mutex::new(Vec::new());
+        let result = retry_with_sleep(
+            policy,
+            || {
+                let mut calls = calls.lock().unwrap();
+                *calls += 1;
+                let outcome = if *calls <= failures {
+                    Err(fail())
+                } else {
+                    Ok(value.clone())
+                };
+                async move { outcome }
+            },
+            |delay| {
+                sleeps.lock().unwrap().push(delay);
+                async {}
+            },
+        )
+        .await;
+        let calls = *calls.lock().unwrap();
+        (result, calls, sleeps.into_inner().unwrap())
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_max() {
+        let policy = policy(5);
+        let ceilings: Vec<u128> = (1..=4)
+            .map(|a| policy.backoff_ceiling(a).as_millis())
+            .collect();
+        assert_eq!(ceilings, vec![100, 200, 350, 350]);
+        assert_eq!(policy.backoff_ceiling(u32::MAX), policy.max_delay);
+    }
+
+    #[tokio::test]
+    async fn test_retries_busy_until_success() {
+        let before = busy_retry_count();
+        let (result, calls, sleeps) = run(&policy(5), 3, busy, 42).await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(calls, 4);
+        assert_eq!(sleeps.len(), 3);
+        for (i, delay) in sleeps.iter().enumerate() {
+            let ceiling = policy(5).backoff_ceiling(i as u32 + 1);
+            assert!(*delay >= ceiling / 2 && *delay <= ceiling, "{:?}", delay);
+        }
+        // Other tests may retry concurrently; ours are counted at least
+        assert!(busy_retry_count() >= before + 3);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_with_source() {
+        let (result, calls, sleeps) = run(&policy(3), u32::MAX, busy, ()).await;
+
+        assert_eq!(calls, 3);
+        assert_eq!(sleeps.len(), 2);
+        let err = result.unwrap_err();
+        assert!(matches!(err, Error::DatabaseBusy { attempts: 3, .. }));
+        let source = err.source().expect("original error kept as source");
+        assert!(source.to_string().contains("database is locked"));
+    }
+
+    #[tokio::test]
+    async fn test_other_errors_are_not_retried() {
+        let (result, calls, sleeps) = run(
+            &policy(5),
+            u32::MAX,
+            || Error::InvalidInput("bad".to_string()),
+            (),
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::InvalidInput(_))));
+        assert_eq!(calls, 1);
+        assert!(sleeps.is_empty());
+
+        let constraint = libsql::Error::SqliteFailure(19, "UNIQUE constraint failed".to_string());
+        assert!(!is_busy_error(&constraint));
+        let locked = libsql::Error::SqliteFailure(262, "database table is locked".to_string());
+        assert!(is_busy_error(&locked));
+    }
+
+    #[tokio::test]
+    async fn test_single_attempt_disables_retries() {
+        let (result, calls, sleeps) = run(&policy(1), u32::MAX, busy, ()).await;
+
+        assert!(matches!(
+            result,
+            Err(Error::DatabaseBusy { attempts: 1, .. })
+        ));
+        assert_eq!(calls, 1);
+        assert!(sleeps.is_empty());
+    }
+}
@@ -0,0 +1,445 @@
+//! Test fixtures for this crate and the crates built on it.
+//!
+//! Only compiled with the `test-utils` feature. Enable it from a crate's
+//! `[dev-dependencies]` so it never reaches a release build:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! mouchak-mail-core = { path = "../mouchak-mail-core", features = ["test-utils"] }
+//! ```
+//!
+//! [`TestEnv`] is a [`ModelManager`] over a fresh, fully migrated database
+//! and archive in a temp dir. [`FixtureBuilder`] populates it with a
+//! project, agents, messages and reservations in one call, with
+//! deterministic names and content:
+//!
+//! ```no_run
+//! # async fn example() -> mouchak_mail_core::Result<()> {
+//! use mouchak_mail_core::testing::TestEnv;
+//!
+//! let env = TestEnv::new().await?;
+//! let fixture = env
+//!     .fixtures()
+//!     .project("/test/repo")
+//!     .agents(["sender-agent", "recipient-agent"])
+//!     .messages(3, Some("TASK-1"), Some("high"))
+//!     .reservations(["src/**"])
+//!     .build()
+//!     .await?;
+//!
+//! assert_eq!(fixture.message_ids.len(), 3);
+//! let sender = fixture.agent_id("sender-agent");
+//! # let _ = sender;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::model::ModelManager;
+use crate::model::agent::{AgentBmc, AgentForCreate};
+use crate::model::file_reservation::{FileReservationBmc, FileReservationForCreate};
+use crate::model::message::{MessageBmc, MessageForCreate, UnifiedInboxFilter, body_hash, snippet};
+use crate::model::project::ProjectBmc;
+use crate::types::{AgentId, ProjectId};
+use crate::utils::slugify;
+use crate::{Ctx, Error, Result};
+use mouchak_mail_common::config::AppConfig;
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+
+/// Project used when [`FixtureBuilder::project`] isn't called.
+pub const DEFAULT_HUMAN_KEY: &str = "/test/fixture";
+
+/// How long fixture reservations last.
+const RESERVATION_TTL_HOURS: i64 = 1;
+
+/// Text seeded message bodies repeat.
+const SEED_BODY_TEXT: &str = "Seeded message body. ";
+
+/// Approximate length of a [`TestEnv::seed_messages`] body.
+const SEED_BODY_LEN: usize = 252;
+
+/// Migrates `conn` to schema `version` rather than the latest, e.g. to
+/// stand in for a database an earlier release left behind.
+pub async fn migrate_to(conn: &libsql::Connection, version: i64) -> Result<()> {
+    crate::store::migrate_to(conn, version).await
+}
+
+/// A [`ModelManager`] over its own migrated database and archive, removed
+/// when dropped.
+pub struct TestEnv {
+    pub mm: ModelManager,
+    pub ctx: Ctx,
+    temp_dir: TempDir,
+}
+
+impl TestEnv {
+    /// Environment with the default configuration.
+    pub async fn new() -> Result<Self> {
+        Self::with_config(AppConfig::default()).await
+    }
+
+    /// Environment with `config`.
+    pub async fn with_config(config: AppConfig) -> Result<Self> {
+        let temp_dir = TempDir::new()?;
+        let archive_root = temp_dir.path().join("archive");
+        std::fs::create_dir_all(&archive_root)?;
+
+        let db = libsql::Builder::new_local(temp_dir.path().join("test.db"))
+            .build()
+            .await?;
+        let conn = db.connect()?;
+        let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
+        crate::store::apply_migrations(&conn).await?;
+
+        let mm = ModelManager::new_for_test(conn, archive_root, Arc::new(config));
+        Ok(Self {
+            mm,
+            ctx: Ctx::root_ctx(),
+            temp_dir,
+        })
+    }
+
+    /// Directory holding the database and archive.
+    pub fn path(&self) -> &Path {
+        self.temp_dir.path()
+    }
+
+    /// Builder for fixtures in this environment.
+    pub fn fixtures(&self) -> FixtureBuilder<'_> {
+        FixtureBuilder::new(&self.ctx, &self.mm)
+    }
+
+    /// `EXPLAIN QUERY PLAN` details of the unified inbox query for `filter`,
+    /// one line per plan step.
+    pub async fn explain_unified_inbox(&self, filter: &UnifiedInboxFilter) -> Result<Vec<String>> {
+        let (query, params) = MessageBmc::unified_inbox_query(filter)?;
+        let mut rows = self
+            .mm
+            .db_for_test()
+            .query(
+                &format!("EXPLAIN QUERY PLAN {}", query),
+                libsql::params::Params::Positional(params),
+            )
+            .await?;
+        let mut plan = Vec::new();
+        while let Some(row) = rows.next().await? {
+            plan.push(row.get::<String>(3)?);
+        }
+        Ok(plan)
+    }
+
+    /// Inserts `count` messages into `fixture`'s project directly in the
+    /// database, for tests that need volume rather than realism.
+    ///
+    /// Senders rotate through the fixture's agents, each message going to
+    /// the next one. Threads, importance and acknowledgement requests
+    /// rotate too, and timestamps run a second apart up to now. Nothing is
+    /// written to the archive and no events are published.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidInput`] if the fixture has no agents, or any
+    /// database error
+    pub async fn seed_messages(&self, fixture: &Fixture, count: usize) -> Result<()> {
+        self.seed_messages_with_body_len(fixture, count, SEED_BODY_LEN)
+            .await
+    }
+
+    /// [`TestEnv::seed_messages`] with bodies of about `body_len` bytes,
+    /// for measuring how body size affects queries.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidInput`] if the fixture has no agents, or any
+    /// database error
+    pub async fn seed_messages_with_body_len(
+        &self,
+        fixture: &Fixture,
+        count: usize,
+        body_len: usize,
+    ) -> Result<()> {
+        const IMPORTANCE: [&str; 6] = ["normal", "normal", "normal", "high", "urgent", "low"];
+        const THREADS: usize = 500;
+
+        if fixture.agents.is_empty() {
+            return Err(Error::InvalidInput(
+                "Seeded messages need at least one agent".to_string(),
+            ));
+        }
+        let agent_ids: Vec<i64> = fixture.agents.iter().map(|(_, id)| id.get()).collect();
+        let start = chrono::Utc::now().naive_utc() - chrono::Duration::seconds(count as i64);
+        let body = SEED_BODY_TEXT.repeat(body_len.div_ceil(SEED_BODY_TEXT.len()));
+
+        let tx = self.mm.db_for_test().transaction().await?;
+        let insert_message = tx
+            .prepare(
+                "INSERT INTO messages \
+                 (project_id, sender_id, thread_id, subject, snippet, importance, ack_required, created_ts) \
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .await?;
+        let insert_body = tx
+            .prepare("INSERT INTO message_bodies (message_id, body_md, body_hash) VALUES (?, ?, ?)")
+            .await?;
+        let insert_recipient = tx
+            .prepare(
+                "INSERT INTO message_recipients (message_id, agent_id, recipient_type) VALUES (?, ?, 'to')",
+            )
+            .await?;
+        for i in 0..count {
+            let created_ts = start + chrono::Duration::seconds(i as i64 + 1);
+            let body_md = format!("{}{}", body, i + 1);
+            insert_message
+                .execute(libsql::params![
+                    fixture.project_id.get(),
+                    agent_ids[i % agent_ids.len()],
+                    format!("SEED-{}", i % THREADS),
+                    format!("Seeded message {}", i + 1),
+                    snippet(&body_md),
+                    IMPORTANCE[i % IMPORTANCE.len()],
+                    i % 10 == 0,
+                    created_ts.format("%Y-%m-%d %H:%M:%S").to_string()
+                ])
+                .await?;
+            insert_message.reset();
+            let message_id = tx.last_insert_rowid();
+            let hash = body_hash(&body_md);
+            insert_body
+                .execute(libsql::params![message_id, body_md, hash])
+                .await?;
+            insert_body.reset();
+            insert_recipient
+                .execute(libsql::params![
+                    message_id,
+                    agent_ids[(i + 1) % agent_ids.len()]
+                ])
+                .await?;
+            insert_recipient.reset();
+        }
+        drop(insert_message);
+        drop(insert_body);
+        drop(insert_recipient);
+        tx.commit().await?;
+        Ok(())
+    }
+}
+
+/// Agent names for [`FixtureBuilder::agents`]: a count, which names agents
+/// `agent-1`, `agent-2`, ..., or the names themselves.
+pub trait AgentNames {
+    fn into_names(self) -> Vec<String>;
+}
+
+impl AgentNames for usize {
+    fn into_names(self) -> Vec<String> {
+        (1..=self).map(|i| format!("agent-{}", i)).collect()
+    }
+}
+
+impl<S: Into<String>, const N: usize> AgentNames for [S; N] {
+    fn into_names(self) -> Vec<String> {
+        self.into_iter().map(Into::into).collect()
+    }
+}
+
+impl<S: Into<String>> AgentNames for Vec<S> {
+    fn into_names(self) -> Vec<String> {
+        self.into_iter().map(Into::into).collect()
+    }
+}
+
+/// Creates a project and, optionally, agents, messages and reservations.
+///
+/// Messages are sent by the first agent to all the others (to itself if it
+/// is the only one), with subjects `Test Message 1`, `Test Message 2`, ...
+/// Reservations are exclusive, held by the first agent for an hour.
+pub struct FixtureBuilder<'a> {
+    ctx: &'a Ctx,
+    mm: &'a ModelManager,
+    human_key: String,
+    agents: Vec<String>,
+    message_count: usize,
+    thread_id: Option<String>,
+    importance: Option<String>,
+    reservations: Vec<String>,
+}
+
+impl<'a> FixtureBuilder<'a> {
+    pub fn new(ctx: &'a Ctx, mm: &'a ModelManager) -> Self {
+        Self {
+            ctx,
+            mm,
+            human_key: DEFAULT_HUMAN_KEY.to_string(),
+            agents: Vec::new(),
+            message_count: 0,
+            thread_id: None,
+            importance: None,
+            reservations: Vec::new(),
+        }
+    }
+
+    /// Project to create, by human key; its slug is derived from it.
+    pub fn project(mut self, human_key: impl Into<String>) -> Self {
+        self.human_key = human_key.into();
+        self
+    }
+
+    /// Agents to register, as a count or a list of names.
+    pub fn agents(mut self, agents: impl AgentNames) -> Self {
+        self.agents = agents.into_names();
+        self
+    }
+
+    /// `count` messages, all in `thread_id` and with `importance` if given.
+    pub fn messages(
+        mut self,
+        count: usize,
+        thread_id: Option<&str>,
+        importance: Option<&str>,
+    ) -> Self {
+        self.message_count = count;
+        self.thread_id = thread_id.map(str::to_string);
+        self.importance = importance.map(str::to_string);
+        self
+    }
+
+    /// Path patterns for the first agent to reserve.
+    pub fn reservations<S: Into<String>>(mut self, paths: impl IntoIterator<Item = S>) -> Self {
+        self.reservations = paths.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Creates everything in order.
+    ///
+    /// # Errors
+    /// Returns [`Error::InvalidInput`] if messages or reservations are
+    /// requested without agents, or any creation error
+    pub async fn build(self) -> Result<Fixture> {
+        let (ctx, mm) = (self.ctx, self.mm);
+        if self.agents.is_empty() && (self.message_count > 0 || !self.reservations.is_empty()) {
+            return Err(Error::InvalidInput(
+                "Fixture messages and reservations need at least one agent".to_string(),
+            ));
+        }
+
+        let project_slug = slugify(&self.human_key);
+        let project_id = ProjectBmc::create(ctx, mm, &project_slug, &self.human_key).await?;
+
+        let mut agents = Vec::with_capacity(self.agents.len());
+        for name in self.agents {
+            let id = AgentBmc::create(
+                ctx,
+                mm,
+                AgentForCreate {
+                    project_id,
+                    name: name.clone(),
+                    program: "fixture".to_string(),
+                    model: "fixture-model".to_string(),
+                    task_description: format!("Fixture agent {}", name),
+                },
+            )
+            .await?;
+            agents.push((name, id));
+        }
+
+        let mut message_ids = Vec::with_capacity(self.message_count);
+        if let Some((_, sender_id)) = agents.first() {
+            let recipient_ids: Vec<i64> = match &agents[1..] {
+                [] => vec![sender_id.get()],
+                others => others.iter().map(|(_, id)| id.get()).collect(),
+            };
+            for i in 1..=self.message_count {
+                let id = MessageBmc::create(
+                    ctx,
+                    mm,
+                    MessageForCreate {
+                        project_id: project_id.get(),
+                        sender_id: sender_id.get(),
+                        recipient_ids: recipient_ids.clone(),
+                        cc_ids: None,
+                        bcc_ids: None,
+                        subject: format!("Test Message {}", i),
+                        body_md: format!("This is the body of message {}.", i),
+                        thread_id: self.thread_id.clone(),
+                        importance: self.importance.clone(),
+                        ack_required: false,
+                        deliver_at: None,
+                        broadcast: false,
+                        allow_new_thread: true,
+                    },
+                )
+                .await?;
+                message_ids.push(id);
+            }
+        }
+
+        let mut reservation_ids = Vec::with_capacity(self.reservations.len());
+        if let Some((_, holder_id)) = agents.first() {
+            let expires_ts =
+                chrono::Utc::now().naive_utc() + chrono::Duration::hours(RESERVATION_TTL_HOURS);
+            for path_pattern in self.reservations {
+                let id = FileReservationBmc::create(
+                    ctx,
+                    mm,
+                    FileReservationForCreate {
+                        project_id,
+                        agent_id: *holder_id,
+                        path_pattern,
+                        exclusive: true,
+                        reason: "Fixture reservation".to_string(),
+                        expires_ts,
+                    },
+                )
+                .await?;
+                reservation_ids.push(id);
+            }
+        }
+
+        Ok(Fixture {
+            project_id,
+            project_slug,
+            human_key: self.human_key,
+            agents,
+            message_ids,
+            reservation_ids,
+        })
+    }
+}
+
+/// What a [`FixtureBuilder`] created.
+#[derive(Debug, Clone)]
+pub struct Fixture {
+    pub project_id: ProjectId,
+    pub project_slug: String,
+    pub human_key: String,
+    /// Agent names and IDs in creation order
+    pub agents: Vec<(String, AgentId)>,
+    /// Message IDs in creation order
+    pub message_ids: Vec<i64>,
+    /// Reservation IDs in creation order
+    pub reservation_ids: Vec<i64>,
+}
+
+impl Fixture {
+    /// ID of the agent called `name`.
+    ///
+    /// # Panics
+    /// If the fixture has no such agent
+    pub fn agent_id(&self, name: &str) -> AgentId {
+        self.agents
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, id)| *id)
+            .unwrap_or_else(|| panic!("fixture has no agent named '{}'", name))
+    }
+
+    /// The first agent, which sends the messages and holds the
+    /// reservations.
+    pub fn sender(&self) -> Option<(&str, AgentId)> {
+        self.agents.first().map(|(name, id)| (name.as_str(), *id))
+    }
+
+    /// Names of all agents, in creation order.
+    pub fn agent_names(&self) -> Vec<&str> {
+        self.agents.iter().map(|(name, _)| name.as_str()).collect()
+    }
+}
@@ -6,6 +6,7 @@
 //!
 //! - `slugify` - Convert text to URL-safe slugs
 //! - `parse_timestamp` - Parse timestamp with warning on failure
+//! - `sanitize_agent_name` - Strip characters agent names may not contain
 
 use chrono::NaiveDateTime;
 use slug;
@@ -93,6 +94,8 @@ pub mod image_processing;
 pub mod mistake_detection;
 pub mod pathspec;
 pub mod project_identity;
+pub mod search_query;
 pub mod validation;
 
-pub use project_identity::compute_project_slug;
+pub use project_identity::{compute_project_slug, discover_project_identity};
+pub use validation::sanitize_agent_name;
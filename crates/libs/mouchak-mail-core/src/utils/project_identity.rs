@@ -3,10 +3,20 @@
 //! All modes now generate privacy-safe slugs that don't leak filesystem paths
 //! or usernames. The format is `{project-name}-{hash}` where hash is derived
 //! from the full path to ensure uniqueness.
+//!
+//! [`discover_project_identity`] resolves a project slug from marker files
+//! in a working tree, so tools can omit the project when run inside a repo.
 
 use mouchak_mail_common::config::ProjectIdentityMode;
 use sha1::{Digest, Sha1};
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+/// Marker file written by `projects mark-identity`.
+pub const PROJECT_ID_MARKER: &str = ".mouchak-mail-project-id";
+/// Marker file name used by the Python implementation.
+pub const LEGACY_PROJECT_ID_MARKER: &str = ".agent-mail-project-id";
+/// Discovery config written by `projects discovery-init`.
+pub const DISCOVERY_FILE: &str = "discovery.yaml";
 
 pub fn compute_project_slug(
     human_key: &str,
@@ -118,6 +128,56 @@ fn compute_git_common_dir_slug(path: &str) -> Option<String> {
     Some(format!("repo-{hash}"))
 }
 
+/// Walks up from `start` looking for a project marker and returns the slug.
+///
+/// In each directory the markers are checked in order: [`PROJECT_ID_MARKER`],
+/// [`LEGACY_PROJECT_ID_MARKER`], then a `project:` entry in [`DISCOVERY_FILE`].
+/// The nearest directory with a usable marker wins.
+///
+/// # Errors
+/// Returns [`crate::Error::InvalidInput`] listing every directory searched
+/// when no marker is found.
+pub fn discover_project_identity(start: &Path) -> crate::Result<String> {
+    let mut searched: Vec<PathBuf> = Vec::new();
+
+    for dir in start.ancestors() {
+        searched.push(dir.to_path_buf());
+
+        for marker in [PROJECT_ID_MARKER, LEGACY_PROJECT_ID_MARKER] {
+            if let Ok(content) = std::fs::read_to_string(dir.join(marker)) {
+                let slug = content.trim();
+                if !slug.is_empty() {
+                    return Ok(slug.to_string());
+                }
+            }
+        }
+
+        if let Ok(content) = std::fs::read_to_string(dir.join(DISCOVERY_FILE))
+            && let Some(slug) = parse_discovery_project(&content)
+        {
+            return Ok(slug);
+        }
+    }
+
+    let dirs: Vec<String> = searched.iter().map(|d| d.display().to_string()).collect();
+    Err(crate::Error::InvalidInput(format!(
+        "No project marker found (looked for {}, {} or a 'project:' key in {}) in: {}",
+        PROJECT_ID_MARKER,
+        LEGACY_PROJECT_ID_MARKER,
+        DISCOVERY_FILE,
+        dirs.join(", ")
+    )))
+}
+
+/// Reads the top-level `project:` key from a discovery.yaml document.
+fn parse_discovery_project(content: &str) -> Option<String> {
+    content.lines().find_map(|line| {
+        let value = line.strip_prefix("project:")?.trim();
+        let value = value.trim_matches(|c| c == '"' || c == '\'');
+        (!value.is_empty()).then(|| value.to_string())
+    })
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
@@ -548,4 +608,75 @@ mod tests {
             );
         }
     }
+
+    // =========================================================================
+    // Marker discovery
+    // =========================================================================
+
+    #[test]
+    fn test_discover_marker_in_parent_dir() {
+        let root = tempfile::tempdir().unwrap();
+        let nested = root.path().join("src").join("deep").join("module");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(root.path().join(PROJECT_ID_MARKER), "my-project\n").unwrap();
+
+        assert_eq!(discover_project_identity(&nested).unwrap(), "my-project");
+    }
+
+    #[test]
+    fn test_discover_nearest_marker_wins() {
+        let root = tempfile::tempdir().unwrap();
+        let nested = root.path().join("packages").join("inner");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(root.path().join(PROJECT_ID_MARKER), "outer").unwrap();
+        std::fs::write(
+            root.path().join("packages").join(LEGACY_PROJECT_ID_MARKER),
+            "inner",
+        )
+        .unwrap();
+
+        assert_eq!(discover_project_identity(&nested).unwrap(), "inner");
+    }
+
+    #[test]
+    fn test_discover_from_discovery_yaml() {
+        let root = tempfile::tempdir().unwrap();
+        let nested = root.path().join("a").join("b");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(
+            root.path().join(DISCOVERY_FILE),
+            "product: tools\nproject: \"yaml-project\"\nprojects: []\n",
+        )
+        .unwrap();
+
+        assert_eq!(discover_project_identity(&nested).unwrap(), "yaml-project");
+    }
+
+    #[test]
+    fn test_discover_skips_empty_marker_and_discovery_without_project() {
+        let root = tempfile::tempdir().unwrap();
+        let nested = root.path().join("child");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(nested.join(PROJECT_ID_MARKER), "  \n").unwrap();
+        std::fs::write(nested.join(DISCOVERY_FILE), "product: x\nprojects: []\n").unwrap();
+        std::fs::write(root.path().join(PROJECT_ID_MARKER), "parent").unwrap();
+
+        assert_eq!(discover_project_identity(&nested).unwrap(), "parent");
+    }
+
+    #[test]
+    fn test_discover_failure_lists_searched_dirs() {
+        let root = tempfile::tempdir().unwrap();
+        let nested = root.path().join("x").join("y");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        // Ancestors above the tempdir may carry markers on a dev machine, so
+        // only check the error shape when nothing is found.
+        if let Err(e) = discover_project_identity(&nested) {
+            let msg = e.to_string();
+            assert!(msg.contains(PROJECT_ID_MARKER));
+            assert!(msg.contains(&nested.display().to_string()));
+            assert!(msg.contains(&root.path().display().to_string()));
+        }
+    }
 }
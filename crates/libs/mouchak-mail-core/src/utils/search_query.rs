@@ -0,0 +1,300 @@
+//! Field-scoped message search queries.
+//!
+//! Parses queries such as `from:BlueStone subject:deploy after:2025-01-01 urgent`
+//! into a [`SearchQuery`] that [`MessageBmc::search_page`] turns into SQL and
+//! FTS5 conditions.
+//!
+//! # Syntax
+//!
+//! | Field | Matches |
+//! |-------|---------|
+//! | `from:NAME` | Sender name (case-insensitive) |
+//! | `subject:WORD` / `subject:"two words"` | Subject contains the text |
+//! | `after:DATE` | Created on or after `DATE` |
+//! | `before:DATE` | Created before `DATE` |
+//! | `importance:LEVEL` | `low`, `normal`, `high` or `urgent` |
+//! | `thread:ID` | Thread ID |
+//!
+//! Dates are `YYYY-MM-DD` (midnight UTC) or `YYYY-MM-DDTHH:MM[:SS]`. Anything
+//! else, including unknown fields and unparseable dates, is a plain term
+//! searched in message bodies, so a query never fails to parse.
+//!
+//! [`MessageBmc::search_page`]: crate::model::message::MessageBmc::search_page
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+/// A parsed search query.
+///
+/// # Example
+///
+/// ```
+/// use mouchak_mail_core::utils::search_query::SearchQuery;
+///
+/// let q = SearchQuery::parse(r#"from:BlueStone subject:"release plan" urgent"#);
+/// assert_eq!(q.from.as_deref(), Some("BlueStone"));
+/// assert_eq!(q.subject_terms, vec!["release plan"]);
+/// assert_eq!(q.terms, vec!["urgent"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    /// Free-text words and `"quoted phrases"` (quotes kept), in query order,
+    /// matched against message bodies
+    pub terms: Vec<String>,
+    /// Sender name
+    pub from: Option<String>,
+    /// Texts the subject must all contain
+    pub subject_terms: Vec<String>,
+    /// Inclusive lower bound on `created_ts`
+    pub after: Option<NaiveDateTime>,
+    /// Exclusive upper bound on `created_ts`
+    pub before: Option<NaiveDateTime>,
+    /// Importance level, lowercased
+    pub importance: Option<String>,
+    /// Thread ID
+    pub thread: Option<String>,
+}
+
+/// One whitespace-separated piece of the input.
+struct Token {
+    /// `name` of a `name:value` token
+    field: Option<String>,
+    value: String,
+    /// The original text, used when the token falls back to a plain term
+    raw: String,
+}
+
+impl SearchQuery {
+    /// Parse `input`. Later occurrences of single-valued fields win.
+    pub fn parse(input: &str) -> Self {
+        let mut query = Self::default();
+        for token in tokenize(input) {
+            if !query.apply_field(&token) {
+                query.terms.push(token.raw);
+            }
+        }
+        query
+    }
+
+    /// True when the query matches nothing in particular.
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty() && !self.has_filters()
+    }
+
+    /// True when any field other than the free-text terms is set.
+    pub fn has_filters(&self) -> bool {
+        self.from.is_some()
+            || !self.subject_terms.is_empty()
+            || self.after.is_some()
+            || self.before.is_some()
+            || self.importance.is_some()
+            || self.thread.is_some()
+    }
+
+    /// The free-text terms as one query string.
+    pub fn text(&self) -> String {
+        self.terms.join(" ")
+    }
+
+    /// Record a `field:value` token; returns `false` for a plain term.
+    fn apply_field(&mut self, token: &Token) -> bool {
+        let Some(field) = token.field.as_deref() else {
+            return false;
+        };
+        let value = token.value.clone();
+        match field.to_ascii_lowercase().as_str() {
+            "from" => self.from = Some(value),
+            "subject" => self.subject_terms.push(value),
+            "importance" => self.importance = Some(value.to_lowercase()),
+            "thread" => self.thread = Some(value),
+            "after" => match parse_date(&value) {
+                Some(ts) => self.after = Some(ts),
+                None => return false,
+            },
+            "before" => match parse_date(&value) {
+                Some(ts) => self.before = Some(ts),
+                None => return false,
+            },
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Parse `YYYY-MM-DD` or `YYYY-MM-DDTHH:MM[:SS]`.
+fn parse_date(value: &str) -> Option<NaiveDateTime> {
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0);
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+        .ok()
+}
+
+/// Split on whitespace, keeping `"quoted phrases"` and `field:"quoted values"`
+/// together. An unclosed quote runs to the end of the input.
+fn tokenize(input: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        let Some(&first) = chars.peek() else {
+            break;
+        };
+
+        let mut raw = String::new();
+        if first == '"' {
+            read_quoted(&mut chars, &mut raw);
+            tokens.push(Token {
+                field: None,
+                value: raw.clone(),
+                raw,
+            });
+            continue;
+        }
+
+        while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+            raw.push(c);
+            if c == ':' && chars.peek() == Some(&'"') && !raw[..raw.len() - 1].contains(':') {
+                read_quoted(&mut chars, &mut raw);
+                break;
+            }
+        }
+
+        let (field, value) = match raw.split_once(':') {
+            Some((name, value))
+                if !name.is_empty() && !name.contains('"') && !unquote(value).trim().is_empty() =>
+            {
+                (Some(name.to_string()), unquote(value).to_string())
+            }
+            _ => (None, raw.clone()),
+        };
+        tokens.push(Token { field, value, raw });
+    }
+    tokens
+}
+
+/// Append a `"..."` run, quotes included, to `out`.
+fn read_quoted(chars: &mut std::iter::Peekable<std::str::Chars<'_>>, out: &mut String) {
+    if let Some(open) = chars.next() {
+        out.push(open);
+    }
+    for c in chars.by_ref() {
+        out.push(c);
+        if c == '"' {
+            break;
+        }
+    }
+}
+
+/// Strip one pair of surrounding double quotes.
+fn unquote(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .map(|v| v.strip_suffix('"').unwrap_or(v))
+        .unwrap_or(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap_or_default()
+    }
+
+    #[test]
+    fn test_plain_words_are_terms() {
+        let q = SearchQuery::parse("  deploy   failed ");
+        assert_eq!(q.terms, vec!["deploy", "failed"]);
+        assert!(!q.has_filters());
+        assert_eq!(q.text(), "deploy failed");
+    }
+
+    #[test]
+    fn test_empty_query() {
+        assert!(SearchQuery::parse("").is_empty());
+        assert!(SearchQuery::parse("   ").is_empty());
+        assert!(!SearchQuery::parse("from:BlueStone").is_empty());
+    }
+
+    #[test]
+    fn test_quoted_phrase_is_one_term() {
+        let q = SearchQuery::parse(r#"urgent "release plan" notes"#);
+        assert_eq!(q.terms, vec!["urgent", "\"release plan\"", "notes"]);
+    }
+
+    #[test]
+    fn test_unclosed_quote_runs_to_end() {
+        let q = SearchQuery::parse(r#""unclosed phrase"#);
+        assert_eq!(q.terms, vec!["\"unclosed phrase"]);
+    }
+
+    #[test]
+    fn test_quoted_field_value() {
+        let q = SearchQuery::parse(r#"subject:"release plan" from:"BlueStone""#);
+        assert_eq!(q.subject_terms, vec!["release plan"]);
+        assert_eq!(q.from.as_deref(), Some("BlueStone"));
+        assert!(q.terms.is_empty());
+    }
+
+    #[test]
+    fn test_mixed_fields_and_terms() {
+        let q = SearchQuery::parse(
+            "from:BlueStone subject:deploy after:2025-01-01 urgent importance:HIGH thread:T-42 rollback",
+        );
+        assert_eq!(q.from.as_deref(), Some("BlueStone"));
+        assert_eq!(q.subject_terms, vec!["deploy"]);
+        assert_eq!(q.after, Some(ts("2025-01-01 00:00:00")));
+        assert_eq!(q.before, None);
+        assert_eq!(q.importance.as_deref(), Some("high"));
+        assert_eq!(q.thread.as_deref(), Some("T-42"));
+        assert_eq!(q.terms, vec!["urgent", "rollback"]);
+    }
+
+    #[test]
+    fn test_repeated_fields() {
+        let q = SearchQuery::parse("subject:deploy subject:prod from:A from:B");
+        assert_eq!(q.subject_terms, vec!["deploy", "prod"]);
+        assert_eq!(q.from.as_deref(), Some("B"));
+    }
+
+    #[test]
+    fn test_field_names_are_case_insensitive() {
+        let q = SearchQuery::parse("From:BlueStone SUBJECT:deploy");
+        assert_eq!(q.from.as_deref(), Some("BlueStone"));
+        assert_eq!(q.subject_terms, vec!["deploy"]);
+    }
+
+    #[test]
+    fn test_date_formats() {
+        let q = SearchQuery::parse("after:2025-03-04T05:06:07 before:2025-03-05T10:30");
+        assert_eq!(q.after, Some(ts("2025-03-04 05:06:07")));
+        assert_eq!(q.before, Some(ts("2025-03-05 10:30:00")));
+    }
+
+    #[test]
+    fn test_invalid_date_is_a_term() {
+        let q = SearchQuery::parse("after:yesterday before:2025-13-01");
+        assert_eq!(q.after, None);
+        assert_eq!(q.before, None);
+        assert_eq!(q.terms, vec!["after:yesterday", "before:2025-13-01"]);
+    }
+
+    #[test]
+    fn test_unknown_field_is_a_term() {
+        let q = SearchQuery::parse("label:ops to:BlueStone https://example.com");
+        assert!(!q.has_filters());
+        assert_eq!(
+            q.terms,
+            vec!["label:ops", "to:BlueStone", "https://example.com"]
+        );
+    }
+
+    #[test]
+    fn test_empty_field_value_is_a_term() {
+        let q = SearchQuery::parse(r#"from: subject:"""#);
+        assert_eq!(q.from, None);
+        assert!(q.subject_terms.is_empty());
+        assert_eq!(q.terms, vec!["from:", "subject:\"\""]);
+    }
+}
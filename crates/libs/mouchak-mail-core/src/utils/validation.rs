@@ -2,10 +2,11 @@
 //!
 //! This module provides validation functions for common input types:
 //!
-//! - **Agent names**: Alphanumeric + underscore + hyphen, 1-64 characters
+//! - **Agent names**: ASCII letters, digits, underscore and hyphen, 2-64 characters
 //! - **Project keys**: Absolute paths or human-readable keys
 //! - **File paths**: Must be relative (no leading `/`)
 //! - **TTL values**: Between 60 seconds and 7 days
+//! - **Message size**: Subject, body and recipient limits from [`MessageConfig`]
 //!
 //! All validation functions return actionable error messages with
 //! suggestions for fixing invalid input.
@@ -32,13 +33,18 @@
 #![allow(clippy::expect_used)]
 
 use lazy_static::lazy_static;
+use mouchak_mail_common::config::MessageConfig;
 use regex::Regex;
 use serde::Serialize;
 
+/// Shortest allowed agent name, in characters.
+pub const AGENT_NAME_MIN_LEN: usize = 2;
+/// Longest allowed agent name, in characters.
+pub const AGENT_NAME_MAX_LEN: usize = 64;
+/// Pattern every agent name must match, for API schemas.
+pub const AGENT_NAME_PATTERN: &str = "^[A-Za-z0-9_-]{2,64}$";
+
 lazy_static! {
-    /// Regex pattern for valid agent names: alphanumeric + underscore + hyphen, 1-64 chars.
-    static ref AGENT_NAME_RE: Regex =
-        Regex::new(r"^[a-zA-Z0-9_-]{1,64}$").expect("valid regex pattern");
     /// Regex pattern for valid human keys: alphanumeric + underscore + hyphen, 1-64 chars.
     static ref HUMAN_KEY_RE: Regex =
         Regex::new(r"^[a-zA-Z0-9_-]{1,64}$").expect("valid regex pattern");
@@ -81,7 +87,7 @@ pub struct ValidationFailure {
 ///
 /// match validate_agent_name("invalid-name") {
 ///     Ok(()) => println!("Valid"),
-///     Err(ValidationError::InvalidAgentName { provided, suggestion }) => {
+///     Err(ValidationError::InvalidAgentName { provided, suggestion, .. }) => {
 ///         println!("Invalid: {}, try: {}", provided, suggestion);
 ///     }
 ///     Err(e) => println!("Other error: {}", e),
@@ -111,15 +117,28 @@ pub enum ValidationError {
         suggestion: String,
     },
 
-    /// Agent name doesn't match the required pattern.
-    #[error("Agent name must match ^[a-zA-Z0-9_-]{{1,64}}$, got: {provided}")]
+    /// Agent name breaks one of the naming rules.
+    #[error("Invalid agent name '{provided}': {rule}")]
     InvalidAgentName {
         /// The invalid agent name.
         provided: String,
+        /// The rule the name breaks.
+        rule: String,
         /// Sanitized version as suggestion.
         suggestion: String,
     },
 
+    /// Agent name differs only in case from one already in the project.
+    #[error(
+        "Agent name '{provided}' is taken by '{existing}' (names are unique per project, ignoring case)"
+    )]
+    AgentNameTaken {
+        /// The requested name.
+        provided: String,
+        /// The existing agent's name.
+        existing: String,
+    },
+
     /// File path is absolute when it should be relative.
     #[error("File path must be relative (no leading /), got: {provided}")]
     AbsolutePathNotAllowed {
@@ -142,6 +161,19 @@ pub enum ValidationError {
         suggestion: u64,
     },
 
+    /// Value is over a configured size limit.
+    #[error("{field} exceeds the limit of {limit} {unit}, got {provided}")]
+    LimitExceeded {
+        /// Field that is too large.
+        field: String,
+        /// Size that was provided.
+        provided: usize,
+        /// Configured maximum.
+        limit: usize,
+        /// Unit of both sizes (e.g. "characters", "bytes").
+        unit: String,
+    },
+
     /// Entity not found with similar name suggestions.
     #[error("Entity not found: {entity_type} with {identifier}")]
     NotFound {
@@ -168,11 +200,15 @@ impl ValidationError {
     }
 }
 
-/// Validates an agent name against the allowed pattern.
+/// Validates an agent name against the naming rules.
+///
+/// Agent names become archive paths (`agents/<name>/...`) and URL segments,
+/// so they must:
+/// - Be 2-64 characters long
+/// - Contain only ASCII letters, digits, underscores and hyphens
 ///
-/// Agent names must:
-/// - Be 1-64 characters long
-/// - Contain only alphanumeric characters and underscores
+/// Case is preserved; uniqueness within a project ignores case and is
+/// checked at registration.
 ///
 /// # Arguments
 ///
@@ -180,8 +216,8 @@ impl ValidationError {
 ///
 /// # Returns
 ///
-/// `Ok(())` if valid, or `Err(ValidationError::InvalidAgentName)` with
-/// a sanitized suggestion.
+/// `Ok(())` if valid, or `Err(ValidationError::InvalidAgentName)` naming
+/// the broken rule, with a sanitized suggestion.
 ///
 /// # Examples
 ///
@@ -194,25 +230,60 @@ impl ValidationError {
 ///
 /// // Invalid names (special chars) return suggestions
 /// let err = validate_agent_name("agent@invalid").unwrap_err();
+/// assert!(err.to_string().contains("'@'"));
 /// ```
 pub fn validate_agent_name(name: &str) -> Result<(), ValidationError> {
-    if AGENT_NAME_RE.is_match(name) {
-        return Ok(());
+    match agent_name_rule_broken(name) {
+        None => Ok(()),
+        Some(rule) => Err(ValidationError::InvalidAgentName {
+            provided: name.to_string(),
+            rule,
+            suggestion: sanitize_agent_name(name),
+        }),
     }
+}
 
-    Err(ValidationError::InvalidAgentName {
-        provided: name.to_string(),
-        suggestion: sanitize_agent_name(name),
-    })
+/// The first naming rule `name` breaks, if any.
+fn agent_name_rule_broken(name: &str) -> Option<String> {
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '-'))
+    {
+        let shown = if c.is_ascii_graphic() || c == ' ' {
+            format!("'{}'", c)
+        } else {
+            format!("U+{:04X}", c as u32)
+        };
+        return Some(format!(
+            "{} is not allowed; use only ASCII letters, digits, '_' and '-'",
+            shown
+        ));
+    }
+    // Only ASCII is left, so bytes are characters
+    if name.len() < AGENT_NAME_MIN_LEN {
+        return Some(format!(
+            "must be at least {} characters long",
+            AGENT_NAME_MIN_LEN
+        ));
+    }
+    if name.len() > AGENT_NAME_MAX_LEN {
+        return Some(format!(
+            "must be at most {} characters long, got {}",
+            AGENT_NAME_MAX_LEN,
+            name.len()
+        ));
+    }
+    None
 }
 
 /// Sanitizes an agent name by removing invalid characters.
 ///
 /// This is used to generate suggestions when validation fails.
 /// The sanitized name:
-/// - Contains only alphanumeric characters, underscores, and hyphens
+/// - Contains only ASCII letters, digits, underscores, and hyphens, so
+///   Unicode lookalikes such as a Cyrillic `а` are dropped
 /// - Is truncated to 64 characters
-/// - Is lowercased for consistency
+/// - Keeps the input's case
 ///
 /// # Arguments
 ///
@@ -228,15 +299,15 @@ pub fn validate_agent_name(name: &str) -> Result<(), ValidationError> {
 /// use mouchak_mail_core::utils::validation::sanitize_agent_name;
 ///
 /// assert_eq!(sanitize_agent_name("my-agent!"), "my-agent");
-/// assert_eq!(sanitize_agent_name("Claude_1"), "claude_1");
+/// assert_eq!(sanitize_agent_name("Claude_1"), "Claude_1");
+/// assert_eq!(sanitize_agent_name("../../etc"), "etc");
 /// ```
 pub fn sanitize_agent_name(input: &str) -> String {
     input
         .chars()
-        .filter(|c| c.is_alphanumeric() || *c == '_' || *c == '-')
-        .take(64)
-        .collect::<String>()
-        .to_lowercase()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+        .take(AGENT_NAME_MAX_LEN)
+        .collect()
 }
 
 /// Validates a project key.
@@ -367,6 +438,55 @@ pub fn validate_ttl(ttl_seconds: u64) -> Result<(), ValidationError> {
     })
 }
 
+/// Validates a message against the configured size limits.
+///
+/// The subject is measured in characters, the body in bytes, and the
+/// recipient count includes cc and bcc. Values equal to a limit are allowed.
+///
+/// # Examples
+///
+/// ```
+/// use mouchak_mail_common::config::MessageConfig;
+/// use mouchak_mail_core::utils::validation::validate_message_limits;
+///
+/// let limits = MessageConfig::default();
+/// assert!(validate_message_limits("Hello", "Body", 1, &limits).is_ok());
+/// assert!(validate_message_limits("Hello", "Body", 101, &limits).is_err());
+/// ```
+pub fn validate_message_limits(
+    subject: &str,
+    body_md: &str,
+    recipient_count: usize,
+    limits: &MessageConfig,
+) -> Result<(), ValidationError> {
+    let checks = [
+        (
+            "subject",
+            subject.chars().count(),
+            limits.max_subject_chars,
+            "characters",
+        ),
+        ("body_md", body_md.len(), limits.max_body_bytes, "bytes"),
+        (
+            "recipients",
+            recipient_count,
+            limits.max_recipients,
+            "recipients",
+        ),
+    ];
+    for (field, provided, limit, unit) in checks {
+        if provided > limit {
+            return Err(ValidationError::LimitExceeded {
+                field: field.to_string(),
+                provided,
+                limit,
+                unit: unit.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 #[allow(
     clippy::unwrap_used,
@@ -380,7 +500,6 @@ mod tests {
     fn test_valid_agent_names() {
         assert!(validate_agent_name("claude_1").is_ok());
         assert!(validate_agent_name("AGENT123").is_ok());
-        assert!(validate_agent_name("a").is_ok());
         assert!(validate_agent_name("cc-1").is_ok());
         assert!(validate_agent_name("cod-2").is_ok());
         assert!(validate_agent_name("agent-name-with-hyphens").is_ok());
@@ -399,6 +518,60 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_agent_name_errors_name_the_rule() {
+        let rule = |name: &str| match validate_agent_name(name).unwrap_err() {
+            ValidationError::InvalidAgentName { rule, .. } => rule,
+            other => panic!("unexpected error: {}", other),
+        };
+
+        assert!(rule("a").contains("at least 2 characters"));
+        assert!(validate_agent_name("ab").is_ok());
+        assert!(validate_agent_name(&"x".repeat(64)).is_ok());
+        assert!(rule(&"x".repeat(65)).contains("at most 64 characters long, got 65"));
+        assert!(rule("").contains("at least 2"));
+        assert!(rule("my agent").starts_with("' ' is not allowed"));
+        assert!(rule("tab\there").starts_with("U+0009 is not allowed"));
+        assert!(rule("team/lead").starts_with("'/' is not allowed"));
+    }
+
+    #[test]
+    fn test_agent_name_rejects_path_traversal() {
+        for name in [
+            "../../etc",
+            "..",
+            "a/../b",
+            "agent\\..\\x",
+            "./agent",
+            "agent\0",
+        ] {
+            let err = validate_agent_name(name).unwrap_err();
+            assert!(
+                matches!(err, ValidationError::InvalidAgentName { .. }),
+                "{} should be rejected",
+                name
+            );
+        }
+        assert_eq!(sanitize_agent_name("../../etc"), "etc");
+    }
+
+    #[test]
+    fn test_agent_name_rejects_unicode_lookalikes() {
+        // Cyrillic а (U+0430) and Greek Ο (U+039F) look like Latin letters
+        let err = validate_agent_name("B\u{0430}ckend").unwrap_err();
+        assert!(err.to_string().contains("U+0430"));
+        assert!(validate_agent_name("\u{039F}verseer").is_err());
+        // Fullwidth letters are not ASCII either
+        assert!(validate_agent_name("ＡＢＣ").is_err());
+        assert_eq!(sanitize_agent_name("B\u{0430}ckend"), "Bckend");
+        assert!(
+            validate_agent_name("é")
+                .unwrap_err()
+                .to_string()
+                .contains("U+00E9")
+        );
+    }
+
     #[test]
     fn test_absolute_path_rejection() {
         let err = validate_reservation_path("/src/lib.rs").unwrap_err();
@@ -416,4 +589,34 @@ mod tests {
             assert_eq!(suggestion, 60);
         }
     }
+
+    #[test]
+    fn test_message_limits_at_boundaries() {
+        let limits = MessageConfig::default();
+        let subject = "é".repeat(500); // characters, not bytes
+        let body = "x".repeat(256 * 1024);
+        assert!(validate_message_limits(&subject, &body, 100, &limits).is_ok());
+
+        let err = validate_message_limits(&"é".repeat(501), "", 1, &limits).unwrap_err();
+        assert!(matches!(
+            err,
+            ValidationError::LimitExceeded { ref field, provided: 501, limit: 500, .. } if field == "subject"
+        ));
+        assert_eq!(
+            err.to_string(),
+            "subject exceeds the limit of 500 characters, got 501"
+        );
+
+        let err = validate_message_limits("", &"x".repeat(256 * 1024 + 1), 1, &limits).unwrap_err();
+        assert!(matches!(
+            err,
+            ValidationError::LimitExceeded { ref field, limit: 262_144, .. } if field == "body_md"
+        ));
+
+        let err = validate_message_limits("", "", 101, &limits).unwrap_err();
+        assert!(matches!(
+            err,
+            ValidationError::LimitExceeded { ref field, limit: 100, .. } if field == "recipients"
+        ));
+    }
 }
@@ -13,12 +13,13 @@
 mod common;
 
 use crate::common::TestContext;
-use mouchak_mail_core::model::activity::ActivityBmc;
+use mouchak_mail_core::model::activity::{ActivityBmc, MessageDirection};
 use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
+use mouchak_mail_core::model::file_reservation::{FileReservationBmc, FileReservationForCreate};
 use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
 use mouchak_mail_core::model::project::ProjectBmc;
 use mouchak_mail_core::model::tool_metric::{ToolMetricBmc, ToolMetricForCreate};
-use mouchak_mail_core::types::ProjectId;
+use mouchak_mail_core::types::{AgentId, ProjectId};
 use mouchak_mail_core::utils::slugify;
 
 /// Helper to set up a project with agent
@@ -80,6 +81,9 @@ async fn test_list_activity_with_messages() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
 
     MessageBmc::create(&tc.ctx, &tc.mm, msg)
@@ -310,3 +314,104 @@ async fn test_activity_sorted_by_time() {
         );
     }
 }
+
+/// Test the per-agent timeline interleaves sent and received messages
+#[tokio::test]
+async fn test_agent_activity_timeline() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+
+    let (project_id, agent_id) = setup_project_and_agent(&tc).await;
+    let peer = AgentForCreate {
+        project_id: ProjectId(project_id),
+        name: "peer-agent".to_string(),
+        program: "cursor".to_string(),
+        model: "gpt-4".to_string(),
+        task_description: "Talking back".to_string(),
+    };
+    let peer_id: i64 = AgentBmc::create(&tc.ctx, &tc.mm, peer)
+        .await
+        .expect("Failed to create peer")
+        .into();
+
+    let send = |sender_id: i64, recipient_id: i64, subject: &str| MessageForCreate {
+        project_id,
+        sender_id,
+        recipient_ids: vec![recipient_id],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: subject.to_string(),
+        body_md: "body".to_string(),
+        thread_id: Some("timeline".to_string()),
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
+    };
+    let outgoing = MessageBmc::create(&tc.ctx, &tc.mm, send(agent_id, peer_id, "Outgoing"))
+        .await
+        .expect("Failed to send");
+    let incoming = MessageBmc::create(&tc.ctx, &tc.mm, send(peer_id, agent_id, "Incoming"))
+        .await
+        .expect("Failed to send");
+    MessageBmc::mark_read(&tc.ctx, &tc.mm, incoming, agent_id)
+        .await
+        .expect("Failed to mark read");
+
+    FileReservationBmc::create(
+        &tc.ctx,
+        &tc.mm,
+        FileReservationForCreate {
+            project_id: ProjectId(project_id),
+            agent_id: AgentId(agent_id),
+            path_pattern: "src/**".to_string(),
+            exclusive: true,
+            reason: "timeline test".to_string(),
+            expires_ts: chrono::Utc::now().naive_utc() + chrono::Duration::hours(1),
+        },
+    )
+    .await
+    .expect("Failed to reserve");
+
+    let activity =
+        ActivityBmc::for_agent(&tc.ctx, &tc.mm, ProjectId(project_id), "activity-agent", 10)
+            .await
+            .expect("Failed to load agent activity");
+
+    assert_eq!(activity.agent.name, "activity-agent");
+    assert_eq!(activity.file_reservations.len(), 1);
+    assert_eq!(activity.timeline.len(), 2);
+
+    let sent = activity
+        .timeline
+        .iter()
+        .find(|e| e.message_id == outgoing)
+        .expect("Sent message missing");
+    assert_eq!(sent.direction, MessageDirection::Sent);
+    assert_eq!(sent.counterpart, "peer-agent");
+    assert!(sent.read_ts.is_none());
+
+    let received = activity
+        .timeline
+        .iter()
+        .find(|e| e.message_id == incoming)
+        .expect("Received message missing");
+    assert_eq!(received.direction, MessageDirection::Received);
+    assert_eq!(received.counterpart, "peer-agent");
+    assert!(received.read_ts.is_some());
+
+    // Limit applies to the merged timeline
+    let limited =
+        ActivityBmc::for_agent(&tc.ctx, &tc.mm, ProjectId(project_id), "activity-agent", 1)
+            .await
+            .expect("Failed to load agent activity");
+    assert_eq!(limited.timeline.len(), 1);
+
+    assert!(
+        ActivityBmc::for_agent(&tc.ctx, &tc.mm, ProjectId(project_id), "nobody", 10)
+            .await
+            .is_err()
+    );
+}
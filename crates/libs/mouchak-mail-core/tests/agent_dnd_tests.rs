@@ -0,0 +1,178 @@
+//! Agent "do not disturb" tests
+//!
+//! Messages below an agent's DND threshold are stored but kept out of its
+//! inbox until the DND window ends.
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use crate::common::TestContext;
+use chrono::{Duration, Utc};
+use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate, DndPolicy};
+use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
+use mouchak_mail_core::model::project::ProjectBmc;
+use mouchak_mail_core::types::{AgentId, ProjectId};
+
+mod common;
+
+/// Project with a sender and a recipient; returns (project_id, sender_id, recipient_id).
+async fn setup(tc: &TestContext, slug: &str) -> (i64, i64, AgentId) {
+    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, slug, &format!("/dnd/{}", slug))
+        .await
+        .unwrap();
+
+    let mut ids = Vec::new();
+    for name in ["Sender", "Focused"] {
+        let agent_c = AgentForCreate {
+            project_id,
+            name: name.to_string(),
+            program: "test".to_string(),
+            model: "test".to_string(),
+            task_description: String::new(),
+        };
+        ids.push(AgentBmc::create(&tc.ctx, &tc.mm, agent_c).await.unwrap());
+    }
+
+    (project_id.get(), ids[0].get(), ids[1])
+}
+
+async fn send(
+    tc: &TestContext,
+    project_id: i64,
+    sender_id: i64,
+    recipient: AgentId,
+    subject: &str,
+    importance: &str,
+    ack_required: bool,
+) -> i64 {
+    let msg_c = MessageForCreate {
+        project_id,
+        sender_id,
+        recipient_ids: vec![recipient.get()],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: subject.to_string(),
+        body_md: "body".to_string(),
+        thread_id: None,
+        importance: Some(importance.to_string()),
+        ack_required,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap()
+}
+
+async fn inbox_subjects(tc: &TestContext, project_id: i64, agent: AgentId) -> Vec<String> {
+    MessageBmc::list_inbox_for_agent(&tc.ctx, &tc.mm, project_id, agent.get(), 50)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|m| m.subject)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_dnd_defers_normal_until_expiry() {
+    let tc = TestContext::new().await.unwrap();
+    let (project_id, sender_id, focused) = setup(&tc, "dnd-expiry").await;
+
+    let policy = DndPolicy {
+        dnd_until: Some(Utc::now().naive_utc() + Duration::seconds(2)),
+        min_importance: None,
+    };
+    AgentBmc::set_dnd_policy(&tc.ctx, &tc.mm, focused, policy)
+        .await
+        .unwrap();
+
+    let chatter_id = send(
+        &tc, project_id, sender_id, focused, "chatter", "normal", false,
+    )
+    .await;
+    send(&tc, project_id, sender_id, focused, "fire", "urgent", false).await;
+    send(
+        &tc,
+        project_id,
+        sender_id,
+        focused,
+        "please ack",
+        "normal",
+        true,
+    )
+    .await;
+
+    // Urgent and ack_required get through; the normal message is held back
+    let mut subjects = inbox_subjects(&tc, project_id, focused).await;
+    subjects.sort();
+    assert_eq!(subjects, vec!["fire", "please ack"]);
+
+    // ...but it is stored
+    let stored = MessageBmc::get(&tc.ctx, &tc.mm, chatter_id).await.unwrap();
+    assert_eq!(stored.subject, "chatter");
+
+    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+
+    let subjects = inbox_subjects(&tc, project_id, focused).await;
+    assert!(subjects.contains(&"chatter".to_string()));
+    assert_eq!(subjects.len(), 3);
+}
+
+#[tokio::test]
+async fn test_dnd_min_importance_and_turning_off() {
+    let tc = TestContext::new().await.unwrap();
+    let (project_id, sender_id, focused) = setup(&tc, "dnd-threshold").await;
+
+    let policy = DndPolicy {
+        dnd_until: Some(Utc::now().naive_utc() + Duration::hours(1)),
+        min_importance: Some("high".to_string()),
+    };
+    AgentBmc::set_dnd_policy(&tc.ctx, &tc.mm, focused, policy)
+        .await
+        .unwrap();
+
+    let stored = AgentBmc::get_dnd_policy(&tc.ctx, &tc.mm, focused)
+        .await
+        .unwrap();
+    assert_eq!(stored.min_importance.as_deref(), Some("high"));
+    assert!(stored.is_active(Utc::now().naive_utc()));
+
+    let active = AgentBmc::list_active_dnd_policies(&tc.ctx, &tc.mm, ProjectId::new(project_id))
+        .await
+        .unwrap();
+    assert_eq!(active.len(), 1);
+    assert!(active.contains_key(&focused));
+
+    send(&tc, project_id, sender_id, focused, "fyi", "low", false).await;
+    send(&tc, project_id, sender_id, focused, "review", "high", false).await;
+    assert_eq!(
+        inbox_subjects(&tc, project_id, focused).await,
+        vec!["review"]
+    );
+
+    // Turning DND off releases what was deferred
+    AgentBmc::set_dnd_policy(&tc.ctx, &tc.mm, focused, DndPolicy::default())
+        .await
+        .unwrap();
+    assert_eq!(inbox_subjects(&tc, project_id, focused).await.len(), 2);
+    assert!(
+        AgentBmc::list_active_dnd_policies(&tc.ctx, &tc.mm, ProjectId::new(project_id))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_dnd_rejects_unknown_importance() {
+    let tc = TestContext::new().await.unwrap();
+    let (_project_id, _sender_id, focused) = setup(&tc, "dnd-invalid").await;
+
+    let policy = DndPolicy {
+        dnd_until: Some(Utc::now().naive_utc() + Duration::hours(1)),
+        min_importance: Some("critical".to_string()),
+    };
+    assert!(matches!(
+        AgentBmc::set_dnd_policy(&tc.ctx, &tc.mm, focused, policy).await,
+        Err(mouchak_mail_core::Error::InvalidInput(_))
+    ));
+}
@@ -0,0 +1,256 @@
+//! Agent group tests
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use mouchak_mail_core::Error;
+use mouchak_mail_core::model::agent::AgentBmc;
+use mouchak_mail_core::model::agent_group::{AgentGroupBmc, GroupExpansion};
+use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
+use mouchak_mail_core::testing::TestEnv;
+use mouchak_mail_core::types::{AgentId, ProjectId};
+
+/// Project with the named agents; returns the project and the agent IDs
+async fn setup(tc: &TestEnv, slug: &str, agents: &[&str]) -> (ProjectId, Vec<AgentId>) {
+    let fixture = tc
+        .fixtures()
+        .project(format!("/group/{}", slug))
+        .agents(agents.to_vec())
+        .build()
+        .await
+        .unwrap();
+    let ids = fixture.agents.iter().map(|(_, id)| *id).collect();
+    (fixture.project_id, ids)
+}
+
+fn addresses(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[tokio::test]
+async fn test_group_crud() {
+    let tc = TestEnv::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+    let (project_id, _) = setup(&tc, "group-crud", &["BlueLake", "GreenCastle"]).await;
+
+    AgentGroupBmc::create(ctx, mm, project_id, " frontend ")
+        .await
+        .unwrap();
+    AgentGroupBmc::create(ctx, mm, project_id, "reviewers")
+        .await
+        .unwrap();
+    assert!(
+        AgentGroupBmc::add_member(ctx, mm, project_id, "FRONTEND", "GreenCastle")
+            .await
+            .unwrap()
+    );
+    assert!(
+        AgentGroupBmc::add_member(ctx, mm, project_id, "frontend", "BlueLake")
+            .await
+            .unwrap()
+    );
+    assert!(
+        !AgentGroupBmc::add_member(ctx, mm, project_id, "frontend", "BlueLake")
+            .await
+            .unwrap()
+    );
+
+    let groups = AgentGroupBmc::list_for_project(ctx, mm, project_id)
+        .await
+        .unwrap();
+    assert_eq!(groups.len(), 2);
+    assert_eq!(groups[0].name, "frontend");
+    assert_eq!(groups[0].members, vec!["BlueLake", "GreenCastle"]);
+    assert!(groups[1].members.is_empty());
+
+    let err = AgentGroupBmc::add_member(ctx, mm, project_id, "frontend", "Ghost")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::AgentNotFound { .. }), "{err:?}");
+
+    AgentGroupBmc::delete(ctx, mm, project_id, "Reviewers")
+        .await
+        .unwrap();
+    let err = AgentGroupBmc::get_by_name(ctx, mm, project_id, "reviewers")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::GroupNotFound(name) if name == "reviewers"));
+}
+
+#[tokio::test]
+async fn test_group_name_rules() {
+    let tc = TestEnv::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+    let (project_id, _) = setup(&tc, "group-names", &["BlueLake"]).await;
+    let (other_project, _) = setup(&tc, "group-names-other", &[]).await;
+
+    // A group may not shadow an agent, whatever the case
+    let err = AgentGroupBmc::create(ctx, mm, project_id, "bluelake")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::GroupNameTaken { name, taken_by } if name == "BlueLake" && *taken_by == "an agent"),
+        "{err:?}"
+    );
+
+    AgentGroupBmc::create(ctx, mm, project_id, "frontend")
+        .await
+        .unwrap();
+    let err = AgentGroupBmc::create(ctx, mm, project_id, "Frontend")
+        .await
+        .unwrap_err();
+    assert!(
+        matches!(&err, Error::GroupNameTaken { taken_by, .. } if *taken_by == "a group"),
+        "{err:?}"
+    );
+    // Names are per project
+    AgentGroupBmc::create(ctx, mm, other_project, "frontend")
+        .await
+        .unwrap();
+
+    for bad in ["x", "front end", "group:frontend"] {
+        let err = AgentGroupBmc::create(ctx, mm, project_id, bad)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidInput(_)), "{bad}: {err:?}");
+    }
+}
+
+#[tokio::test]
+async fn test_group_expansion_is_snapshotted_at_send() {
+    let tc = TestEnv::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+    let (project_id, ids) = setup(
+        &tc,
+        "group-snapshot",
+        &["Sender", "BlueLake", "GreenCastle", "RedStone"],
+    )
+    .await;
+
+    AgentGroupBmc::create(ctx, mm, project_id, "frontend")
+        .await
+        .unwrap();
+    for name in ["BlueLake", "GreenCastle", "RedStone"] {
+        AgentGroupBmc::add_member(ctx, mm, project_id, "frontend", name)
+            .await
+            .unwrap();
+    }
+    // Retired members are not addressed
+    AgentBmc::retire(ctx, mm, ids[3]).await.unwrap();
+
+    // A group and one of its members named directly reach each agent once
+    let resolved = MessageBmc::resolve_recipients(
+        ctx,
+        mm,
+        project_id,
+        &addresses(&["group:Frontend", "BlueLake"]),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    assert!(resolved.failed.is_empty());
+    assert_eq!(
+        resolved.recipient_ids,
+        vec![ids[1].get(), ids[2].get()],
+        "group members then direct recipients, without duplicates"
+    );
+    let expected = vec![GroupExpansion {
+        group: "frontend".to_string(),
+        agent_ids: vec![ids[1].get(), ids[2].get()],
+        members: addresses(&["BlueLake", "GreenCastle"]),
+    }];
+    assert_eq!(resolved.groups, expected);
+
+    let message_id = MessageBmc::create(
+        ctx,
+        mm,
+        MessageForCreate {
+            project_id: project_id.get(),
+            sender_id: ids[0].get(),
+            recipient_ids: resolved.recipient_ids.clone(),
+            cc_ids: None,
+            bcc_ids: None,
+            subject: "Standup".to_string(),
+            body_md: "Body".to_string(),
+            thread_id: None,
+            importance: None,
+            ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
+        },
+    )
+    .await
+    .unwrap();
+    AgentGroupBmc::record_expansions(mm, message_id, &resolved.groups)
+        .await
+        .unwrap();
+
+    // Later membership changes and deleting the group leave the message alone
+    AgentGroupBmc::remove_member(ctx, mm, project_id, "frontend", "GreenCastle")
+        .await
+        .unwrap();
+    AgentGroupBmc::delete(ctx, mm, project_id, "frontend")
+        .await
+        .unwrap();
+
+    assert_eq!(
+        AgentGroupBmc::expansions_for_message(mm, message_id)
+            .await
+            .unwrap(),
+        expected
+    );
+    let mut recipients = MessageBmc::get_recipients(ctx, mm, message_id)
+        .await
+        .unwrap();
+    recipients.sort();
+    assert_eq!(recipients, addresses(&["BlueLake", "GreenCastle"]));
+    let inbox = MessageBmc::list_inbox_for_agent(ctx, mm, project_id.get(), ids[2].get(), 10)
+        .await
+        .unwrap();
+    assert_eq!(inbox.len(), 1);
+}
+
+#[tokio::test]
+async fn test_unknown_and_empty_groups_fail_to_resolve() {
+    let tc = TestEnv::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+    let (project_id, _) = setup(&tc, "group-empty", &["BlueLake"]).await;
+    AgentGroupBmc::create(ctx, mm, project_id, "idle")
+        .await
+        .unwrap();
+
+    let err = AgentGroupBmc::expand(ctx, mm, project_id, "idle")
+        .await
+        .unwrap_err();
+    assert!(matches!(err, Error::GroupEmpty(name) if name == "idle"));
+
+    let resolved = MessageBmc::resolve_recipients(
+        ctx,
+        mm,
+        project_id,
+        &addresses(&["group:idle", "group:backend", "BlueLake"]),
+        None,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(resolved.delivered, addresses(&["BlueLake"]));
+    let codes: Vec<(&str, &str)> = resolved
+        .failed
+        .iter()
+        .map(|f| (f.name.as_str(), f.code.as_str()))
+        .collect();
+    assert_eq!(
+        codes,
+        vec![
+            ("group:idle", "GROUP_EMPTY"),
+            ("group:backend", "GROUP_NOT_FOUND")
+        ]
+    );
+    assert!(matches!(resolved.first_error, Some(Error::GroupEmpty(_))));
+    assert!(resolved.groups.is_empty());
+    assert!(resolved.can_send(true));
+    assert!(!resolved.can_send(false));
+}
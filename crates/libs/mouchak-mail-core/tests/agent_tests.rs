@@ -13,10 +13,14 @@
 mod common;
 
 use crate::common::TestContext;
-use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
+use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate, AgentForUpdate};
+use mouchak_mail_core::model::agent_capabilities::AgentCapabilityBmc;
+use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
 use mouchak_mail_core::model::project::ProjectBmc;
+use mouchak_mail_core::store;
 use mouchak_mail_core::utils::slugify;
-use mouchak_mail_core::{AgentId, ProjectId};
+use mouchak_mail_core::utils::validation::ValidationError;
+use mouchak_mail_core::{AgentId, Error, ProjectId};
 
 /// Helper to create a test project
 async fn create_test_project(tc: &TestContext, name: &str) -> ProjectId {
@@ -181,6 +185,9 @@ async fn test_delete_agent_cascade() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     mouchak_mail_core::model::message::MessageBmc::create(&tc.ctx, &tc.mm, msg)
         .await
@@ -315,6 +322,9 @@ async fn test_count_messages_sent() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     mouchak_mail_core::model::message::MessageBmc::create(&tc.ctx, &tc.mm, msg)
         .await
@@ -368,6 +378,9 @@ async fn test_count_messages_received() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     mouchak_mail_core::model::message::MessageBmc::create(&tc.ctx, &tc.mm, msg)
         .await
@@ -477,3 +490,356 @@ async fn test_get_by_name_with_suggestions() {
         "Error should contain suggestions"
     );
 }
+
+/// Helper to register an agent with default fields
+async fn create_named_agent(tc: &TestContext, project_id: ProjectId, name: &str) -> AgentId {
+    let agent = AgentForCreate {
+        project_id,
+        name: name.to_string(),
+        program: "test".to_string(),
+        model: "test".to_string(),
+        task_description: "Initial task".to_string(),
+    };
+    AgentBmc::create(&tc.ctx, &tc.mm, agent).await.unwrap()
+}
+
+/// Test renaming an agent and changing its task
+#[tokio::test]
+async fn test_update_agent_rename() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let project_id = create_test_project(&tc, "update").await;
+    let agent_id = create_named_agent(&tc, project_id, "OldName").await;
+
+    let update = AgentForUpdate {
+        name: Some("NewName".to_string()),
+        task_description: Some("New task".to_string()),
+        ..Default::default()
+    };
+    let agent = AgentBmc::update(&tc.ctx, &tc.mm, agent_id, update)
+        .await
+        .unwrap();
+    assert_eq!(agent.name, "NewName");
+    assert_eq!(agent.task_description, "New task");
+    assert_eq!(agent.model, "test");
+
+    assert!(
+        AgentBmc::get_by_name(&tc.ctx, &tc.mm, project_id, "OldName")
+            .await
+            .is_err()
+    );
+
+    // New profile under the new name; the old directory is left alone
+    let agents_dir = tc
+        .repo_root()
+        .join("projects")
+        .join(slugify("/test/agents/update"))
+        .join("agents");
+    assert!(agents_dir.join("NewName").join("profile.json").exists());
+    assert!(agents_dir.join("OldName").join("profile.json").exists());
+}
+
+/// Test that a rename cannot take another agent's name
+#[tokio::test]
+async fn test_update_agent_duplicate_name() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let project_id = create_test_project(&tc, "update-dup").await;
+    let agent_id = create_named_agent(&tc, project_id, "First").await;
+    create_named_agent(&tc, project_id, "Second").await;
+
+    let update = AgentForUpdate {
+        name: Some("Second".to_string()),
+        ..Default::default()
+    };
+    let result = AgentBmc::update(&tc.ctx, &tc.mm, agent_id, update).await;
+    assert!(result.is_err(), "Duplicate name should be rejected");
+
+    let agent = AgentBmc::get(&tc.ctx, &tc.mm, agent_id).await.unwrap();
+    assert_eq!(agent.name, "First");
+}
+
+/// Test registration rejects names that would escape the archive or
+/// impersonate another agent
+#[tokio::test]
+async fn test_register_rejects_invalid_names() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let project_id = create_test_project(&tc, "name-rules").await;
+
+    for bad in [
+        "../../etc/passwd",
+        "..",
+        "agents/Other",
+        "C:\\Windows",
+        "Bl\u{0435}eStone", // Cyrillic 'е'
+        "\u{039F}verseer",  // Greek capital omicron
+        "Ｗorker",
+        "x",
+        "",
+    ] {
+        let agent_c = AgentForCreate {
+            project_id,
+            name: bad.to_string(),
+            program: "test".to_string(),
+            model: "test".to_string(),
+            task_description: String::new(),
+        };
+        let result = AgentBmc::create(&tc.ctx, &tc.mm, agent_c).await;
+        assert!(
+            matches!(
+                result,
+                Err(Error::Validation(ValidationError::InvalidAgentName { .. }))
+            ),
+            "{:?} should be rejected, got {:?}",
+            bad,
+            result
+        );
+    }
+
+    assert!(
+        AgentBmc::list_all_for_project(&tc.ctx, &tc.mm, project_id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let agents_dir = tc
+        .repo_root()
+        .join("projects")
+        .join(slugify("/test/agents/name-rules"))
+        .join("agents");
+    assert!(!agents_dir.exists());
+}
+
+/// Test names are trimmed, keep their case, and are unique ignoring case
+#[tokio::test]
+async fn test_register_names_unique_ignoring_case() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let project_id = create_test_project(&tc, "name-case").await;
+    let other_project = create_test_project(&tc, "name-case-other").await;
+
+    let agent_id = create_named_agent(&tc, project_id, "  BlueStone\t").await;
+    let agent = AgentBmc::get(&tc.ctx, &tc.mm, agent_id).await.unwrap();
+    assert_eq!(agent.name, "BlueStone");
+
+    let agent_c = AgentForCreate {
+        project_id,
+        name: "bluestone".to_string(),
+        program: "test".to_string(),
+        model: "test".to_string(),
+        task_description: String::new(),
+    };
+    let result = AgentBmc::create(&tc.ctx, &tc.mm, agent_c).await;
+    match result {
+        Err(Error::Validation(ValidationError::AgentNameTaken { provided, existing })) => {
+            assert_eq!(provided, "bluestone");
+            assert_eq!(existing, "BlueStone");
+        }
+        other => panic!("expected AgentNameTaken, got {:?}", other),
+    }
+
+    // Re-registering the exact name still upserts, other projects are separate
+    create_named_agent(&tc, project_id, "BlueStone").await;
+    create_named_agent(&tc, other_project, "bluestone").await;
+
+    // Renaming may change an agent's own case but not take a case variant
+    let second = create_named_agent(&tc, project_id, "GreenCastle").await;
+    let update = AgentForUpdate {
+        name: Some("BLUESTONE".to_string()),
+        ..Default::default()
+    };
+    let result = AgentBmc::update(&tc.ctx, &tc.mm, second, update).await;
+    assert!(matches!(
+        result,
+        Err(Error::Validation(ValidationError::AgentNameTaken { .. }))
+    ));
+    let update = AgentForUpdate {
+        name: Some("greencastle".to_string()),
+        ..Default::default()
+    };
+    let renamed = AgentBmc::update(&tc.ctx, &tc.mm, second, update)
+        .await
+        .unwrap();
+    assert_eq!(renamed.name, "greencastle");
+}
+
+/// Test names stored before validation are reported, not changed
+#[tokio::test]
+async fn test_agent_name_issues_report_legacy_names() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let project_id = create_test_project(&tc, "legacy-names").await;
+    create_named_agent(&tc, project_id, "Worker").await;
+
+    let db = tc.mm.db_for_test();
+    assert!(store::agent_name_issues(db).await.unwrap().is_empty());
+
+    for name in ["worker", "my agent", "Fine_Name"] {
+        db.execute(
+            "INSERT INTO agents (project_id, name, program, model, task_description, inception_ts, last_active_ts)
+             VALUES (?, ?, 'test', 'test', '', datetime('now'), datetime('now'))",
+            (project_id.get(), name),
+        )
+        .await
+        .unwrap();
+    }
+
+    let issues = store::agent_name_issues(db).await.unwrap();
+    let names: Vec<&str> = issues.iter().map(|i| i.name.as_str()).collect();
+    assert_eq!(names, ["Worker", "my agent", "worker"]);
+    assert!(issues[0].problem.contains("'worker'"));
+    assert!(issues[1].problem.contains("' ' is not allowed"));
+    assert!(issues[2].problem.contains("'Worker'"));
+    assert!(
+        issues
+            .iter()
+            .all(|i| i.project_slug == slugify("/test/agents/legacy-names"))
+    );
+
+    // Still reachable under their stored names
+    AgentBmc::get_by_name(&tc.ctx, &tc.mm, project_id, "my agent")
+        .await
+        .unwrap();
+}
+
+/// Test retiring an agent hides it but keeps its history
+#[tokio::test]
+async fn test_retire_agent() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let project_id = create_test_project(&tc, "retire").await;
+    let retiree = create_named_agent(&tc, project_id, "Retiree").await;
+    let peer = create_named_agent(&tc, project_id, "Peer").await;
+    AgentCapabilityBmc::grant_defaults(&tc.ctx, &tc.mm, retiree.get())
+        .await
+        .unwrap();
+
+    let msg = MessageForCreate {
+        project_id: project_id.get(),
+        sender_id: retiree.get(),
+        recipient_ids: vec![peer.get()],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: "Before retirement".into(),
+        body_md: "Body".into(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg).await.unwrap();
+
+    let agent = AgentBmc::retire(&tc.ctx, &tc.mm, retiree).await.unwrap();
+    let retired_ts = agent.retired_ts.expect("retired_ts should be set");
+
+    // Retiring again keeps the first timestamp
+    let again = AgentBmc::retire(&tc.ctx, &tc.mm, retiree).await.unwrap();
+    assert_eq!(again.retired_ts, Some(retired_ts));
+
+    let active = AgentBmc::list_all_for_project(&tc.ctx, &tc.mm, project_id)
+        .await
+        .unwrap();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].name, "Peer");
+    let all = AgentBmc::list_all_including_retired(&tc.ctx, &tc.mm, project_id)
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 2);
+
+    assert!(
+        !AgentCapabilityBmc::check(&tc.ctx, &tc.mm, retiree.get(), "send_message")
+            .await
+            .unwrap()
+    );
+
+    let message = MessageBmc::get(&tc.ctx, &tc.mm, msg_id).await.unwrap();
+    assert_eq!(message.sender_name, "Retiree");
+}
+
+/// Test registering an existing name updates it unless strict
+#[tokio::test]
+async fn test_register_existing_agent_upserts() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let project_id = create_test_project(&tc, "upsert").await;
+    let agent_id = create_named_agent(&tc, project_id, "Restarter").await;
+    AgentBmc::retire(&tc.ctx, &tc.mm, agent_id).await.unwrap();
+
+    let again = AgentForCreate {
+        project_id,
+        name: "Restarter".to_string(),
+        program: "new-program".to_string(),
+        model: "new-model".to_string(),
+        task_description: "Resumed task".to_string(),
+    };
+    let registration = AgentBmc::register(&tc.ctx, &tc.mm, again.clone(), false)
+        .await
+        .unwrap();
+    assert_eq!(registration.id, agent_id);
+    assert!(!registration.created);
+
+    let agent = AgentBmc::get(&tc.ctx, &tc.mm, agent_id).await.unwrap();
+    assert_eq!(agent.model, "new-model");
+    assert_eq!(agent.task_description, "Resumed task");
+    assert!(
+        agent.retired_ts.is_none(),
+        "Re-registering should un-retire"
+    );
+
+    let result = AgentBmc::register(&tc.ctx, &tc.mm, again, true).await;
+    assert!(result.is_err(), "Strict registration should conflict");
+}
+
+/// Test issuing, resolving and revoking agent tokens
+#[tokio::test]
+async fn test_agent_token_lifecycle() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let project_id = create_test_project(&tc, "tokens").await;
+    let agent_id = create_named_agent(&tc, project_id, "KeyHolder").await;
+
+    let token = AgentBmc::issue_token(&tc.ctx, &tc.mm, agent_id)
+        .await
+        .unwrap();
+    assert!(token.starts_with(mouchak_mail_core::model::agent::AGENT_TOKEN_PREFIX));
+
+    let (agent, project_slug) = AgentBmc::authenticate_token(&tc.ctx, &tc.mm, &token)
+        .await
+        .unwrap()
+        .expect("Issued token should resolve");
+    assert_eq!(agent.id, agent_id);
+    assert_eq!(project_slug, slugify("/test/agents/tokens"));
+
+    let ctx = mouchak_mail_core::Ctx::for_agent(agent.id, &agent.name, &project_slug);
+    assert!(ctx.ensure_acting_as(agent_id, "KeyHolder").is_ok());
+    let other = create_named_agent(&tc, project_id, "Impostor").await;
+    assert!(matches!(
+        ctx.ensure_acting_as(other, "Impostor"),
+        Err(mouchak_mail_core::Error::AgentIdentityMismatch { .. })
+    ));
+
+    assert_eq!(
+        AgentBmc::revoke_token(&tc.ctx, &tc.mm, agent_id)
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(
+        AgentBmc::authenticate_token(&tc.ctx, &tc.mm, &token)
+            .await
+            .unwrap()
+            .is_none(),
+        "Revoked token should not resolve"
+    );
+}
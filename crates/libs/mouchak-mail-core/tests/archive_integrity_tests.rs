@@ -0,0 +1,210 @@
+//! Archive integrity tests
+//!
+//! Tests for verifying the Git message archive against the DB and repairing it.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+#[path = "common/mod.rs"]
+mod common;
+
+use crate::common::TestContext;
+use mouchak_mail_common::config::{AppConfig, ArchiveLayout};
+use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
+use mouchak_mail_core::model::archive_integrity::{ArchiveIntegrityBmc, ArchiveReport};
+use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
+use mouchak_mail_core::model::project::ProjectBmc;
+use std::path::PathBuf;
+
+const SLUG: &str = "integrity-project";
+
+/// Create a project with two agents and send `count` messages between them.
+async fn setup_archive(tc: &TestContext, count: usize) -> Vec<i64> {
+    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, SLUG, "/integrity/project")
+        .await
+        .unwrap();
+
+    let mut agent_ids = Vec::new();
+    for name in ["Sender", "Recipient"] {
+        let agent_c = AgentForCreate {
+            project_id,
+            name: name.to_string(),
+            program: "test".to_string(),
+            model: "test".to_string(),
+            task_description: "Integrity agent".to_string(),
+        };
+        agent_ids.push(AgentBmc::create(&tc.ctx, &tc.mm, agent_c).await.unwrap());
+    }
+
+    let mut ids = Vec::new();
+    for i in 0..count {
+        let msg_c = MessageForCreate {
+            project_id: project_id.get(),
+            sender_id: agent_ids[0].into(),
+            recipient_ids: vec![agent_ids[1].into()],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: format!("Integrity {}", i),
+            body_md: format!("Body {}", i),
+            thread_id: None,
+            importance: None,
+            ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
+        };
+        ids.push(MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap());
+    }
+    ids
+}
+
+/// Messages are archived in the background; wait until every file exists.
+async fn wait_for_archive(tc: &TestContext) -> ArchiveReport {
+    let mut report = ArchiveReport::default();
+    for _ in 0..50 {
+        report = ArchiveIntegrityBmc::verify_archive(&tc.ctx, &tc.mm, SLUG)
+            .await
+            .unwrap();
+        if report.missing_count == 0 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    report
+}
+
+fn canonical_file(tc: &TestContext, message_id: i64) -> PathBuf {
+    canonical_file_in(&tc.repo_root(), message_id)
+}
+
+fn canonical_file_in(repo_dir: &std::path::Path, message_id: i64) -> PathBuf {
+    let suffix = format!("__{}.md", message_id);
+    let messages_dir = repo_dir.join("projects").join(SLUG).join("messages");
+    for year in std::fs::read_dir(&messages_dir).unwrap() {
+        let year = year.unwrap().path();
+        if !year.is_dir() {
+            continue;
+        }
+        for month in std::fs::read_dir(&year).unwrap() {
+            for file in std::fs::read_dir(month.unwrap().path()).unwrap() {
+                let path = file.unwrap().path();
+                if path.to_string_lossy().ends_with(&suffix) {
+                    return path;
+                }
+            }
+        }
+    }
+    panic!("No canonical file for message {}", message_id);
+}
+
+#[tokio::test]
+async fn test_verify_clean_archive() {
+    let tc = TestContext::new().await.unwrap();
+    setup_archive(&tc, 2).await;
+
+    let report = wait_for_archive(&tc).await;
+    assert!(report.is_clean(), "Unexpected report: {:?}", report);
+    assert_eq!(report.messages_checked, 2);
+    assert_eq!(report.files_scanned, 2);
+}
+
+#[tokio::test]
+async fn test_verify_detects_missing_orphan_and_mismatch() {
+    let tc = TestContext::new().await.unwrap();
+    let ids = setup_archive(&tc, 3).await;
+    wait_for_archive(&tc).await;
+
+    std::fs::remove_file(canonical_file(&tc, ids[0])).unwrap();
+
+    let edited = canonical_file(&tc, ids[1]);
+    let content = std::fs::read_to_string(&edited).unwrap();
+    std::fs::write(&edited, content.replace("Body 1", "Tampered")).unwrap();
+
+    let stray = edited.with_file_name("2025-01-01T00-00-00Z__stray__99999.md");
+    std::fs::write(&stray, "not a message").unwrap();
+
+    let report = ArchiveIntegrityBmc::verify_archive(&tc.ctx, &tc.mm, SLUG)
+        .await
+        .unwrap();
+    assert_eq!(report.missing_count, 1);
+    assert_eq!(report.mismatched_count, 1);
+    assert_eq!(report.orphan_count, 1);
+    assert_eq!(report.repaired_count, 0);
+    assert!(report.missing[0].ends_with(&format!("__{}.md", ids[0])));
+    assert!(report.orphans[0].ends_with("__99999.md"));
+}
+
+#[tokio::test]
+async fn test_repair_rewrites_missing_and_is_idempotent() {
+    let tc = TestContext::new().await.unwrap();
+    let ids = setup_archive(&tc, 2).await;
+    wait_for_archive(&tc).await;
+
+    std::fs::remove_file(canonical_file(&tc, ids[1])).unwrap();
+
+    let report = ArchiveIntegrityBmc::repair_archive(&tc.ctx, &tc.mm, SLUG)
+        .await
+        .unwrap();
+    assert_eq!(report.missing_count, 1);
+    assert_eq!(report.repaired_count, 1);
+
+    let after = ArchiveIntegrityBmc::verify_archive(&tc.ctx, &tc.mm, SLUG)
+        .await
+        .unwrap();
+    assert!(after.is_clean(), "Unexpected report: {:?}", after);
+
+    // Second repair finds nothing to do
+    let again = ArchiveIntegrityBmc::repair_archive(&tc.ctx, &tc.mm, SLUG)
+        .await
+        .unwrap();
+    assert_eq!(again.repaired_count, 0);
+    assert!(again.is_clean());
+}
+
+#[tokio::test]
+async fn test_verify_unknown_project() {
+    let tc = TestContext::new().await.unwrap();
+    assert!(
+        ArchiveIntegrityBmc::verify_archive(&tc.ctx, &tc.mm, "no-such-project")
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn test_verify_per_project_layout() {
+    let mut config = AppConfig::default();
+    config.archive.layout = ArchiveLayout::PerProject;
+    let tc = TestContext::new_with_config(config).await.unwrap();
+    let ids = setup_archive(&tc, 2).await;
+    tc.mm.archive_queue.flush().await;
+
+    let project_repo = tc.repo_root().join(SLUG);
+    assert!(project_repo.join(".git").exists());
+    let report = ArchiveIntegrityBmc::verify_archive(&tc.ctx, &tc.mm, SLUG)
+        .await
+        .unwrap();
+    assert!(report.is_clean(), "Unexpected report: {:?}", report);
+    assert_eq!(report.files_scanned, 2);
+
+    // History left in the shared repository still counts
+    let per_project = canonical_file_in(&project_repo, ids[0]);
+    let shared = tc
+        .repo_root()
+        .join(per_project.strip_prefix(&project_repo).unwrap());
+    std::fs::create_dir_all(shared.parent().unwrap()).unwrap();
+    std::fs::rename(&per_project, &shared).unwrap();
+
+    let report = ArchiveIntegrityBmc::verify_archive(&tc.ctx, &tc.mm, SLUG)
+        .await
+        .unwrap();
+    assert!(report.is_clean(), "Unexpected report: {:?}", report);
+
+    // Missing files are reported and repaired in the per-project repository
+    std::fs::remove_file(canonical_file_in(&project_repo, ids[1])).unwrap();
+    let report = ArchiveIntegrityBmc::repair_archive(&tc.ctx, &tc.mm, SLUG)
+        .await
+        .unwrap();
+    assert_eq!(report.repaired_count, 1);
+    assert!(report.missing[0].starts_with(SLUG));
+    assert!(canonical_file_in(&project_repo, ids[1]).exists());
+}
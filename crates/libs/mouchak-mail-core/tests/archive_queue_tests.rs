@@ -0,0 +1,427 @@
+//! Archive queue tests
+//!
+//! Tests for the write-behind archive queue: batching, flush, shutdown and
+//! the `archive.sync` escape hatch.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+#[path = "common/mod.rs"]
+mod common;
+
+use crate::common::TestContext;
+use mouchak_mail_common::config::{AppConfig, MailboxCopies};
+use mouchak_mail_core::Ctx;
+use mouchak_mail_core::ctx::Actor;
+use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
+use mouchak_mail_core::model::archive_integrity::ArchiveIntegrityBmc;
+use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
+use mouchak_mail_core::model::project::ProjectBmc;
+
+const SLUG: &str = "archive-queue-project";
+
+/// Create a project with two agents and send `count` messages concurrently.
+async fn send_messages(tc: &TestContext, count: usize) -> Vec<i64> {
+    send_messages_as(tc, &tc.ctx, count).await
+}
+
+/// Like [`send_messages`], but sends with `ctx`.
+async fn send_messages_as(tc: &TestContext, ctx: &Ctx, count: usize) -> Vec<i64> {
+    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, SLUG, "/archive/queue")
+        .await
+        .unwrap();
+
+    let mut agent_ids = Vec::new();
+    for name in ["Sender", "Recipient"] {
+        let agent_c = AgentForCreate {
+            project_id,
+            name: name.to_string(),
+            program: "test".to_string(),
+            model: "test".to_string(),
+            task_description: "Queue agent".to_string(),
+        };
+        agent_ids.push(AgentBmc::create(&tc.ctx, &tc.mm, agent_c).await.unwrap());
+    }
+
+    let sends = (0..count).map(|i| {
+        let msg_c = MessageForCreate {
+            project_id: project_id.get(),
+            sender_id: agent_ids[0].into(),
+            recipient_ids: vec![agent_ids[1].into()],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: format!("Queued {}", i),
+            body_md: format!("Body {}", i),
+            thread_id: None,
+            importance: None,
+            ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
+        };
+        MessageBmc::create(ctx, &tc.mm, msg_c)
+    });
+    futures::future::join_all(sends)
+        .await
+        .into_iter()
+        .map(|r| r.unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn test_flush_commits_every_queued_message() {
+    let tc = TestContext::new().await.unwrap();
+    send_messages(&tc, 20).await;
+
+    tc.mm.archive_queue.flush().await;
+
+    let stats = tc.mm.archive_queue.stats();
+    assert!(!stats.sync);
+    assert_eq!(stats.pending, 0);
+    assert_eq!(stats.committed, 20);
+    assert_eq!(stats.failed, 0);
+    assert!(stats.batches >= 1 && stats.batches <= 20);
+
+    let report = ArchiveIntegrityBmc::verify_archive(&tc.ctx, &tc.mm, SLUG)
+        .await
+        .unwrap();
+    assert!(report.is_clean(), "Unexpected report: {:?}", report);
+    assert_eq!(report.files_scanned, 20);
+}
+
+#[tokio::test]
+async fn test_shutdown_flushes_queue() {
+    let tc = TestContext::new().await.unwrap();
+    send_messages(&tc, 10).await;
+
+    tc.mm.shutdown().await.unwrap();
+
+    assert_eq!(tc.mm.archive_queue.stats().pending, 0);
+    let report = ArchiveIntegrityBmc::verify_archive(&tc.ctx, &tc.mm, SLUG)
+        .await
+        .unwrap();
+    assert_eq!(report.missing_count, 0);
+    assert_eq!(report.files_scanned, 10);
+}
+
+#[tokio::test]
+async fn test_flush_without_messages_returns() {
+    let tc = TestContext::new().await.unwrap();
+    tc.mm.archive_queue.flush().await;
+    assert_eq!(tc.mm.archive_queue.stats().committed, 0);
+}
+
+#[tokio::test]
+async fn test_sync_mode_commits_before_returning() {
+    let mut config = AppConfig::default();
+    config.archive.sync = true;
+    let tc = TestContext::new_with_config(config).await.unwrap();
+    send_messages(&tc, 3).await;
+
+    // No flush: every file must already be on disk
+    let stats = tc.mm.archive_queue.stats();
+    assert!(stats.sync);
+    assert_eq!(stats.pending, 0);
+    assert_eq!(stats.committed, 3);
+
+    let report = ArchiveIntegrityBmc::verify_archive(&tc.ctx, &tc.mm, SLUG)
+        .await
+        .unwrap();
+    assert!(report.is_clean(), "Unexpected report: {:?}", report);
+}
+
+/// Author, author email, committer and message of the archive's HEAD commit.
+fn head_commit(tc: &TestContext) -> (String, String, String, String) {
+    let repo = git2::Repository::open(tc.mm.project_repo_root(SLUG)).unwrap();
+    let commit = repo.head().unwrap().peel_to_commit().unwrap();
+    (
+        commit.author().name().unwrap().to_string(),
+        commit.author().email().unwrap().to_string(),
+        commit.committer().name().unwrap().to_string(),
+        commit.message().unwrap().to_string(),
+    )
+}
+
+#[tokio::test]
+async fn test_commit_author_is_sender_agent() {
+    let tc = TestContext::new().await.unwrap();
+    send_messages(&tc, 1).await;
+    tc.mm.archive_queue.flush().await;
+
+    let (author, email, committer, message) = head_commit(&tc);
+    assert_eq!(author, "Sender");
+    assert_eq!(email, "sender@archive-queue-project.local");
+    assert_eq!(committer, "mcp-bot");
+    assert!(
+        message.ends_with("Actor: Sender <sender@archive-queue-project.local>"),
+        "Unexpected message: {}",
+        message
+    );
+}
+
+#[tokio::test]
+async fn test_commit_author_follows_ctx_actor() {
+    let tc = TestContext::new().await.unwrap();
+    let ctx = Ctx::root_ctx().with_actor(Actor::user("alice@example.com"));
+    send_messages_as(&tc, &ctx, 1).await;
+    tc.mm.archive_queue.flush().await;
+
+    let (author, email, committer, _) = head_commit(&tc);
+    assert_eq!(author, "alice@example.com");
+    assert_eq!(email, "alice@example.com");
+    assert_eq!(committer, "mcp-bot");
+}
+
+/// Create Sender and three recipients; returns the project id and agent ids.
+async fn create_fan_out_agents(tc: &TestContext) -> (i64, Vec<i64>) {
+    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, SLUG, "/archive/queue")
+        .await
+        .unwrap();
+
+    let mut agent_ids = Vec::new();
+    for name in ["Sender", "RedFox", "BlueLake", "GreenCastle"] {
+        let agent_c = AgentForCreate {
+            project_id,
+            name: name.to_string(),
+            program: "test".to_string(),
+            model: "test".to_string(),
+            task_description: "Queue agent".to_string(),
+        };
+        agent_ids.push(
+            AgentBmc::create(&tc.ctx, &tc.mm, agent_c)
+                .await
+                .unwrap()
+                .get(),
+        );
+    }
+    (project_id.get(), agent_ids)
+}
+
+/// A message from Sender to two recipients with the third on CC.
+fn fan_out(project_id: i64, agent_ids: &[i64], subject: &str) -> MessageForCreate {
+    MessageForCreate {
+        project_id,
+        sender_id: agent_ids[0],
+        recipient_ids: vec![agent_ids[1], agent_ids[2]],
+        cc_ids: Some(vec![agent_ids[3]]),
+        bcc_ids: None,
+        subject: subject.to_string(),
+        body_md: "Three recipients".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    }
+}
+
+/// Send one message to three recipients; returns its id.
+async fn send_to_three(tc: &TestContext) -> i64 {
+    let (project_id, agent_ids) = create_fan_out_agents(tc).await;
+    MessageBmc::create(&tc.ctx, &tc.mm, fan_out(project_id, &agent_ids, "Fan out"))
+        .await
+        .unwrap()
+}
+
+/// Files added by the HEAD commit, with their blob ids, sorted by path.
+fn head_commit_files(repo: &git2::Repository) -> Vec<(String, git2::Oid)> {
+    let head = repo.head().unwrap().peel_to_commit().unwrap();
+    let parent_tree = head.parent(0).unwrap().tree().unwrap();
+    let diff = repo
+        .diff_tree_to_tree(Some(&parent_tree), Some(&head.tree().unwrap()), None)
+        .unwrap();
+    let mut files: Vec<(String, git2::Oid)> = diff
+        .deltas()
+        .map(|d| {
+            assert_eq!(d.status(), git2::Delta::Added);
+            let file = d.new_file();
+            (
+                file.path().unwrap().to_string_lossy().to_string(),
+                file.id(),
+            )
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// Files under `projects/` that differ from HEAD or are untracked.
+fn dirty_project_files(repo: &git2::Repository) -> Vec<String> {
+    let mut opts = git2::StatusOptions::new();
+    opts.include_untracked(true).recurse_untracked_dirs(true);
+    repo.statuses(Some(&mut opts))
+        .unwrap()
+        .iter()
+        .filter_map(|s| s.path().map(str::to_string))
+        .filter(|path| path.starts_with("projects/"))
+        .collect()
+}
+
+fn blob_text(repo: &git2::Repository, oid: git2::Oid) -> String {
+    String::from_utf8(repo.find_blob(oid).unwrap().content().to_vec()).unwrap()
+}
+
+/// Asserts `files` are the canonical, outbox and three inbox paths of `id`.
+fn assert_message_paths(files: &[(String, git2::Oid)], id: i64) -> String {
+    assert_eq!(files.len(), 5, "Unexpected files: {:?}", files);
+    let suffix = format!("__{}.md", id);
+    assert!(files.iter().all(|(path, _)| path.ends_with(&suffix)));
+
+    let dir_of = |agent: &str, mailbox: &str| {
+        files
+            .iter()
+            .filter(|(path, _)| {
+                path.starts_with(&format!("projects/{}/agents/{}/{}/", SLUG, agent, mailbox))
+            })
+            .count()
+    };
+    assert_eq!(dir_of("Sender", "outbox"), 1);
+    for recipient in ["RedFox", "BlueLake", "GreenCastle"] {
+        assert_eq!(
+            dir_of(recipient, "inbox"),
+            1,
+            "No inbox copy for {}",
+            recipient
+        );
+    }
+
+    let canonical: Vec<_> = files
+        .iter()
+        .filter(|(path, _)| path.starts_with(&format!("projects/{}/messages/", SLUG)))
+        .collect();
+    assert_eq!(canonical.len(), 1);
+    canonical[0].0.clone()
+}
+
+#[tokio::test]
+async fn test_three_recipient_message_is_one_commit_of_one_blob() {
+    let tc = TestContext::new().await.unwrap();
+    let id = send_to_three(&tc).await;
+    tc.mm.archive_queue.flush().await;
+
+    let repo = git2::Repository::open(tc.mm.project_repo_root(SLUG)).unwrap();
+    let files = head_commit_files(&repo);
+    let canonical = assert_message_paths(&files, id);
+
+    // Full copies: every path points at the canonical blob
+    let canonical_oid = files.iter().find(|(p, _)| *p == canonical).unwrap().1;
+    assert!(files.iter().all(|(_, oid)| *oid == canonical_oid));
+    assert!(blob_text(&repo, canonical_oid).contains("Three recipients"));
+
+    // The working tree matches the commit and nothing is left staged
+    assert_eq!(dirty_project_files(&repo), Vec::<String>::new());
+    let staging = repo.path().join("mouchak-staging");
+    assert!(!staging.exists() || std::fs::read_dir(staging).unwrap().next().is_none());
+}
+
+#[tokio::test]
+async fn test_pointer_mailbox_copies_name_canonical_path() {
+    let mut config = AppConfig::default();
+    config.archive.mailbox_copies = MailboxCopies::Pointer;
+    let tc = TestContext::new_with_config(config).await.unwrap();
+    let id = send_to_three(&tc).await;
+    tc.mm.archive_queue.flush().await;
+
+    let repo = git2::Repository::open(tc.mm.project_repo_root(SLUG)).unwrap();
+    let files = head_commit_files(&repo);
+    let canonical = assert_message_paths(&files, id);
+
+    let canonical_oid = files.iter().find(|(p, _)| *p == canonical).unwrap().1;
+    assert!(blob_text(&repo, canonical_oid).contains("Three recipients"));
+
+    // Outbox and inboxes share one small pointer blob
+    let pointers: Vec<_> = files.iter().filter(|(p, _)| *p != canonical).collect();
+    assert_eq!(pointers.len(), 4);
+    assert!(pointers.iter().all(|(_, oid)| *oid == pointers[0].1));
+    let pointer = blob_text(&repo, pointers[0].1);
+    assert!(pointer.contains(&format!("\"id\": {}", id)));
+    assert!(pointer.contains(&format!("\"canonical\": \"{}\"", canonical)));
+    assert!(!pointer.contains("Three recipients"));
+
+    let report = ArchiveIntegrityBmc::verify_archive(&tc.ctx, &tc.mm, SLUG)
+        .await
+        .unwrap();
+    assert!(report.is_clean(), "Unexpected report: {:?}", report);
+}
+
+#[tokio::test]
+async fn test_failed_write_commits_nothing() {
+    let tc = TestContext::new().await.unwrap();
+    let (project_id, agent_ids) = create_fan_out_agents(&tc).await;
+    let msg_c = fan_out(project_id, &agent_ids, "Warm up");
+    MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
+    tc.mm.archive_queue.flush().await;
+    let repo = git2::Repository::open(tc.mm.project_repo_root(SLUG)).unwrap();
+    let head_before = repo.head().unwrap().target().unwrap();
+
+    // The slugified subject makes a file name longer than the filesystem allows
+    let msg_c = fan_out(project_id, &agent_ids, &"x".repeat(300));
+    MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
+    tc.mm.archive_queue.flush().await;
+
+    assert_eq!(repo.head().unwrap().target().unwrap(), head_before);
+    let stats = tc.mm.archive_queue.stats();
+    assert_eq!(stats.failed, 1);
+    let error = stats.last_error.unwrap();
+    assert!(
+        error.starts_with(&format!(
+            "Failed to write archive file projects/{}/messages/",
+            SLUG
+        )),
+        "Unexpected error: {}",
+        error
+    );
+    assert_eq!(dirty_project_files(&repo), Vec::<String>::new());
+}
+
+#[tokio::test]
+async fn test_failed_job_does_not_drop_its_batch() {
+    let tc = TestContext::new().await.unwrap();
+    let (project_id, agent_ids) = create_fan_out_agents(&tc).await;
+
+    // Hold the writer on the first message so the rest queue up into one batch
+    let git_guard = tc.mm.git_lock.lock().await;
+    let msg_c = fan_out(project_id, &agent_ids, "Warm up");
+    MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
+
+    let subjects: Vec<String> = (0..6)
+        .map(|i| match i {
+            // The slugified subject makes a file name longer than the filesystem allows
+            3 => "x".repeat(300),
+            _ => format!("Batched {}", i),
+        })
+        .collect();
+    let sends = subjects.iter().map(|subject| {
+        MessageBmc::create(&tc.ctx, &tc.mm, fan_out(project_id, &agent_ids, subject))
+    });
+    for result in futures::future::join_all(sends).await {
+        result.unwrap();
+    }
+    drop(git_guard);
+    tc.mm.archive_queue.flush().await;
+
+    let stats = tc.mm.archive_queue.stats();
+    assert_eq!(stats.pending, 0);
+    assert_eq!(stats.committed, 6);
+    assert_eq!(stats.failed, 1);
+    let error = stats.last_error.unwrap();
+    assert!(
+        error.starts_with(&format!(
+            "Failed to write archive file projects/{}/messages/",
+            SLUG
+        )),
+        "Unexpected error: {}",
+        error
+    );
+
+    let report = ArchiveIntegrityBmc::verify_archive(&tc.ctx, &tc.mm, SLUG)
+        .await
+        .unwrap();
+    assert_eq!(report.messages_checked, 7);
+    assert_eq!(report.files_scanned, 6);
+    assert_eq!(report.missing_count, 1);
+    assert_eq!(report.orphan_count, 0);
+
+    let repo = git2::Repository::open(tc.mm.project_repo_root(SLUG)).unwrap();
+    assert_eq!(dirty_project_files(&repo), Vec::<String>::new());
+}
@@ -0,0 +1,306 @@
+//! Audit log tests
+//!
+//! Tests that administrative actions leave audit log entries, and that the
+//! log can be filtered and paged.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+#[path = "common/mod.rs"]
+mod common;
+
+use crate::common::TestContext;
+use mouchak_mail_core::Error;
+use mouchak_mail_core::ctx::{Actor, Ctx};
+use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
+use mouchak_mail_core::model::audit::{AuditAction, AuditBmc, AuditFilter};
+use mouchak_mail_core::model::file_reservation::{FileReservationBmc, FileReservationForCreate};
+use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
+use mouchak_mail_core::model::project::ProjectBmc;
+use mouchak_mail_core::types::{AgentId, ProjectId};
+
+struct Fixture {
+    project_id: ProjectId,
+    sender: AgentId,
+    recipient: AgentId,
+}
+
+async fn setup(tc: &TestContext) -> Fixture {
+    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, "audit-project", "/audit/project")
+        .await
+        .unwrap();
+
+    let mut ids = Vec::new();
+    for name in ["Sender", "Recipient"] {
+        let agent_c = AgentForCreate {
+            project_id,
+            name: name.to_string(),
+            program: "test".to_string(),
+            model: "test".to_string(),
+            task_description: "Audit agent".to_string(),
+        };
+        ids.push(AgentBmc::create(&tc.ctx, &tc.mm, agent_c).await.unwrap());
+    }
+
+    Fixture {
+        project_id,
+        sender: ids[0],
+        recipient: ids[1],
+    }
+}
+
+async fn send(tc: &TestContext, fx: &Fixture, subject: &str) -> i64 {
+    let msg_c = MessageForCreate {
+        project_id: fx.project_id.get(),
+        sender_id: fx.sender.get(),
+        recipient_ids: vec![fx.recipient.get()],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: subject.to_string(),
+        body_md: "Body".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap()
+}
+
+async fn reserve(tc: &TestContext, fx: &Fixture, path: &str) -> i64 {
+    let fr_c = FileReservationForCreate {
+        project_id: fx.project_id,
+        agent_id: fx.sender,
+        path_pattern: path.to_string(),
+        exclusive: true,
+        reason: "Editing".to_string(),
+        expires_ts: chrono::Utc::now().naive_utc() + chrono::Duration::hours(1),
+    };
+    FileReservationBmc::create(&tc.ctx, &tc.mm, fr_c)
+        .await
+        .unwrap()
+}
+
+async fn entries(
+    tc: &TestContext,
+    fx: &Fixture,
+    filter: AuditFilter,
+) -> Vec<mouchak_mail_core::model::audit::AuditEntry> {
+    AuditBmc::list(&tc.ctx, &tc.mm, fx.project_id, &filter, 100, None)
+        .await
+        .unwrap()
+        .items
+}
+
+#[tokio::test]
+async fn test_admin_actions_are_audited() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc).await;
+    let admin = Ctx::root_ctx().with_actor(Actor::user("alice@example.com"));
+
+    let reservation_id = reserve(&tc, &fx, "src/lib.rs").await;
+    FileReservationBmc::force_release(&admin, &tc.mm, reservation_id)
+        .await
+        .unwrap();
+
+    let message_id = send(&tc, &fx, "Oops").await;
+    MessageBmc::recall(&admin, &tc.mm, message_id, fx.sender.get(), "Wrong thread")
+        .await
+        .unwrap();
+
+    AgentBmc::delete(&admin, &tc.mm, fx.recipient)
+        .await
+        .unwrap();
+
+    let log = entries(&tc, &fx, AuditFilter::default()).await;
+    let actions: Vec<AuditAction> = log.iter().map(|e| e.action).collect();
+    assert_eq!(
+        actions,
+        vec![
+            AuditAction::AgentDelete,
+            AuditAction::MessageRecall,
+            AuditAction::ReservationForceRelease,
+        ]
+    );
+
+    for entry in &log {
+        assert_eq!(entry.project_id, fx.project_id.get());
+        assert_eq!(
+            entry.actor.as_deref(),
+            Some("alice@example.com <alice@example.com>")
+        );
+    }
+
+    let delete = &log[0];
+    assert_eq!(delete.entity_type, "agent");
+    assert_eq!(delete.entity_id, fx.recipient.get());
+    assert_eq!(delete.detail["before"]["name"], "Recipient");
+
+    let recall = &log[1];
+    assert_eq!(recall.entity_type, "message");
+    assert_eq!(recall.entity_id, message_id);
+    assert_eq!(recall.detail["before"]["subject"], "Oops");
+    assert_eq!(recall.detail["after"]["reason"], "Wrong thread");
+
+    let release = &log[2];
+    assert_eq!(release.entity_type, "reservation");
+    assert_eq!(release.entity_id, reservation_id);
+    assert_eq!(release.detail["before"]["path_pattern"], "src/lib.rs");
+    assert!(release.detail["after"]["released_ts"].is_string());
+}
+
+#[tokio::test]
+async fn test_noop_actions_are_not_audited() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc).await;
+
+    // Releasing twice, or an unknown reservation, changes nothing
+    let reservation_id = reserve(&tc, &fx, "src/main.rs").await;
+    for id in [reservation_id, reservation_id, 999_999] {
+        FileReservationBmc::force_release(&tc.ctx, &tc.mm, id)
+            .await
+            .unwrap();
+    }
+
+    // Retiring twice keeps the first retirement
+    AgentBmc::retire(&tc.ctx, &tc.mm, fx.recipient)
+        .await
+        .unwrap();
+    AgentBmc::retire(&tc.ctx, &tc.mm, fx.recipient)
+        .await
+        .unwrap();
+
+    // A rejected recall writes nothing
+    let message_id = send(&tc, &fx, "Keep").await;
+    let result = MessageBmc::recall(&tc.ctx, &tc.mm, message_id, fx.recipient.get(), "No").await;
+    assert!(matches!(result, Err(Error::NotMessageSender(_))));
+
+    let log = entries(&tc, &fx, AuditFilter::default()).await;
+    let actions: Vec<AuditAction> = log.iter().map(|e| e.action).collect();
+    assert_eq!(
+        actions,
+        vec![
+            AuditAction::AgentRetire,
+            AuditAction::ReservationForceRelease
+        ]
+    );
+    // Root context with no actor
+    assert_eq!(log[0].actor, None);
+    assert_eq!(log[0].user_id, 0);
+}
+
+#[tokio::test]
+async fn test_filter_and_paginate() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc).await;
+
+    let mut reservation_ids = Vec::new();
+    for i in 0..5 {
+        let id = reserve(&tc, &fx, &format!("src/file_{}.rs", i)).await;
+        FileReservationBmc::force_release(&tc.ctx, &tc.mm, id)
+            .await
+            .unwrap();
+        reservation_ids.push(id);
+    }
+    AgentBmc::retire(&tc.ctx, &tc.mm, fx.recipient)
+        .await
+        .unwrap();
+
+    let released = entries(
+        &tc,
+        &fx,
+        AuditFilter {
+            action: Some(AuditAction::ReservationForceRelease),
+            ..Default::default()
+        },
+    )
+    .await;
+    assert_eq!(released.len(), 5);
+
+    let one = entries(
+        &tc,
+        &fx,
+        AuditFilter {
+            entity_type: Some("reservation".to_string()),
+            entity_id: Some(reservation_ids[2]),
+            ..Default::default()
+        },
+    )
+    .await;
+    assert_eq!(one.len(), 1);
+    assert_eq!(one[0].entity_id, reservation_ids[2]);
+
+    let agents = entries(
+        &tc,
+        &fx,
+        AuditFilter {
+            entity_type: Some("agent".to_string()),
+            ..Default::default()
+        },
+    )
+    .await;
+    assert_eq!(agents.len(), 1);
+    assert_eq!(agents[0].action, AuditAction::AgentRetire);
+
+    // Page through all six, newest first
+    let mut seen = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = AuditBmc::list(
+            &tc.ctx,
+            &tc.mm,
+            fx.project_id,
+            &AuditFilter::default(),
+            4,
+            cursor,
+        )
+        .await
+        .unwrap();
+        seen.extend(page.items.iter().map(|e| e.id));
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(seen.len(), 6);
+    assert!(seen.windows(2).all(|w| w[0] > w[1]));
+}
+
+#[tokio::test]
+async fn test_project_delete_entry_outlives_project() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc).await;
+
+    ProjectBmc::delete(&tc.ctx, &tc.mm, fx.project_id)
+        .await
+        .unwrap();
+
+    // The project is gone, so read the row directly
+    let mut rows = tc
+        .mm
+        .db_for_test()
+        .query(
+            "SELECT action, entity_id FROM audit_log WHERE project_id = ?",
+            [fx.project_id.get()],
+        )
+        .await
+        .unwrap();
+    let row = rows.next().await.unwrap().expect("audit entry");
+    assert_eq!(row.get::<String>(0).unwrap(), "project.delete");
+    assert_eq!(row.get::<i64>(1).unwrap(), fx.project_id.get());
+}
+
+#[test]
+fn test_action_names_round_trip() {
+    for action in AuditAction::ALL {
+        assert_eq!(action.as_str().parse::<AuditAction>().unwrap(), action);
+        assert_eq!(
+            serde_json::to_value(action).unwrap(),
+            serde_json::json!(action.as_str())
+        );
+    }
+    assert!(matches!(
+        "agent.explode".parse::<AuditAction>(),
+        Err(Error::InvalidInput(_))
+    ));
+}
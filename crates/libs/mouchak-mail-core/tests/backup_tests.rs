@@ -0,0 +1,213 @@
+//! Database backup tests
+//!
+//! A snapshot taken while messages are being written must be a consistent
+//! point-in-time copy: each writer's messages form a prefix of what it wrote,
+//! and every message in the copy has its body and recipient rows.
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use libsql::{Builder, OpenFlags};
+use mouchak_mail_common::config::AppConfig;
+use mouchak_mail_core::ctx::Ctx;
+use mouchak_mail_core::model::backup::BackupBmc;
+use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
+use mouchak_mail_core::testing::TestEnv;
+use std::collections::BTreeMap;
+use tempfile::TempDir;
+
+const WRITERS: usize = 4;
+const MESSAGES_PER_WRITER: usize = 40;
+
+async fn context_backing_up_to(dir: &TempDir, retention_count: usize) -> TestEnv {
+    let mut config = AppConfig::default();
+    config.backup.directory = Some(dir.path().to_path_buf());
+    config.backup.retention_count = retention_count;
+    TestEnv::with_config(config).await.unwrap()
+}
+
+/// Project with a sender and a reader; returns (project_id, sender, reader).
+async fn setup(tc: &TestEnv) -> (i64, i64, i64) {
+    let fixture = tc
+        .fixtures()
+        .project("/backup")
+        .agents(["Writer", "Reader"])
+        .build()
+        .await
+        .unwrap();
+    (
+        fixture.project_id.get(),
+        fixture.agent_id("Writer").get(),
+        fixture.agent_id("Reader").get(),
+    )
+}
+
+async fn message_count(tc: &TestEnv) -> i64 {
+    let mut rows = tc
+        .mm
+        .db_for_test()
+        .query("SELECT COUNT(*) FROM messages", ())
+        .await
+        .unwrap();
+    rows.next().await.unwrap().unwrap().get(0).unwrap()
+}
+
+#[tokio::test]
+async fn test_backup_during_writes_is_consistent() {
+    let dir = TempDir::new().unwrap();
+    let tc = context_backing_up_to(&dir, 0).await;
+    let (project_id, sender_id, reader_id) = setup(&tc).await;
+
+    let mut writers = Vec::new();
+    for w in 0..WRITERS {
+        let mm = tc.mm.clone();
+        writers.push(tokio::spawn(async move {
+            let ctx = Ctx::root_ctx();
+            for i in 0..MESSAGES_PER_WRITER {
+                let msg_c = MessageForCreate {
+                    project_id,
+                    sender_id,
+                    recipient_ids: vec![reader_id],
+                    cc_ids: None,
+                    bcc_ids: None,
+                    subject: format!("w{}-{:03}", w, i),
+                    body_md: format!("body {} {}", w, i),
+                    thread_id: None,
+                    importance: None,
+                    ack_required: false,
+                    deliver_at: None,
+                    broadcast: false,
+                    allow_new_thread: false,
+                };
+                MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
+            }
+        }));
+    }
+
+    // Back up once the writers are well under way
+    while message_count(&tc).await < 20 {
+        tokio::time::sleep(std::time::Duration::from_millis(5)).await;
+    }
+    let backup = BackupBmc::create(&tc.ctx, &tc.mm).await.unwrap();
+    for writer in writers {
+        writer.await.unwrap();
+    }
+    assert!(backup.size_bytes > 0);
+    assert!(backup.path.starts_with(&dir.path().display().to_string()));
+
+    let snapshot = Builder::new_local(&backup.path)
+        .flags(OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .build()
+        .await
+        .unwrap()
+        .connect()
+        .unwrap();
+
+    let mut rows = snapshot.query("PRAGMA integrity_check", ()).await.unwrap();
+    let integrity: String = rows.next().await.unwrap().unwrap().get(0).unwrap();
+    assert_eq!(integrity, "ok");
+    let mut rows = snapshot
+        .query("PRAGMA foreign_key_check", ())
+        .await
+        .unwrap();
+    assert!(
+        rows.next().await.unwrap().is_none(),
+        "dangling foreign keys"
+    );
+
+    // Each writer's messages are a prefix of what it wrote
+    let mut by_writer: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    let mut rows = snapshot
+        .query("SELECT subject FROM messages ORDER BY id", ())
+        .await
+        .unwrap();
+    while let Some(row) = rows.next().await.unwrap() {
+        let subject: String = row.get(0).unwrap();
+        let (w, i) = subject[1..].split_once('-').unwrap();
+        by_writer
+            .entry(w.parse().unwrap())
+            .or_default()
+            .push(i.parse().unwrap());
+    }
+    let copied: usize = by_writer.values().map(Vec::len).sum();
+    assert!(copied >= 20, "only {} messages copied", copied);
+    for (w, indexes) in &by_writer {
+        assert_eq!(
+            indexes,
+            &(0..indexes.len()).collect::<Vec<_>>(),
+            "writer {} is not a prefix",
+            w
+        );
+    }
+
+    // No message is missing the rows written with it
+    let mut rows = snapshot
+        .query(
+            "SELECT COUNT(*) FROM messages m
+             WHERE NOT EXISTS (SELECT 1 FROM message_bodies b WHERE b.message_id = m.id)
+                OR (SELECT COUNT(*) FROM message_recipients r WHERE r.message_id = m.id) != 1",
+            (),
+        )
+        .await
+        .unwrap();
+    let incomplete: i64 = rows.next().await.unwrap().unwrap().get(0).unwrap();
+    assert_eq!(incomplete, 0);
+
+    // Agents come along too, and the copy is read-only
+    let mut rows = snapshot
+        .query("SELECT COUNT(*) FROM agents", ())
+        .await
+        .unwrap();
+    let agents: i64 = rows.next().await.unwrap().unwrap().get(0).unwrap();
+    assert_eq!(agents, 2);
+    assert!(snapshot.execute("DELETE FROM messages", ()).await.is_err());
+
+    assert_eq!(
+        message_count(&tc).await,
+        (WRITERS * MESSAGES_PER_WRITER) as i64
+    );
+}
+
+#[tokio::test]
+async fn test_rotation_keeps_newest_snapshots() {
+    let dir = TempDir::new().unwrap();
+    let tc = context_backing_up_to(&dir, 2).await;
+    setup(&tc).await;
+
+    let mut taken = Vec::new();
+    for _ in 0..3 {
+        taken.push(BackupBmc::create(&tc.ctx, &tc.mm).await.unwrap());
+    }
+    // Files that are not snapshots are left alone
+    std::fs::write(dir.path().join("notes.txt"), "keep me").unwrap();
+
+    let listed = BackupBmc::list(&tc.ctx, &tc.mm).unwrap();
+    assert_eq!(
+        listed.iter().map(|b| &b.path).collect::<Vec<_>>(),
+        vec![&taken[2].path, &taken[1].path]
+    );
+    assert!(!std::path::Path::new(&taken[0].path).exists());
+    assert!(dir.path().join("notes.txt").exists());
+
+    // Explicit paths are outside the rotation and never overwritten
+    let elsewhere = TempDir::new().unwrap();
+    let path = elsewhere.path().join("manual.db");
+    let manual = BackupBmc::create_at(&tc.ctx, &tc.mm, &path).await.unwrap();
+    assert_eq!(manual.path, path.display().to_string());
+    assert_eq!(BackupBmc::list(&tc.ctx, &tc.mm).unwrap().len(), 2);
+    assert!(BackupBmc::create_at(&tc.ctx, &tc.mm, &path).await.is_err());
+}
+
+#[tokio::test]
+async fn test_backups_need_an_unrestricted_context() {
+    let dir = TempDir::new().unwrap();
+    let tc = context_backing_up_to(&dir, 0).await;
+    let scoped = Ctx::scoped(1, None, vec!["backup".to_string()]);
+
+    assert!(matches!(
+        BackupBmc::create(&scoped, &tc.mm).await,
+        Err(mouchak_mail_core::Error::Forbidden(_))
+    ));
+    assert!(BackupBmc::list(&scoped, &tc.mm).is_err());
+    assert!(BackupBmc::list(&tc.ctx, &tc.mm).unwrap().is_empty());
+}
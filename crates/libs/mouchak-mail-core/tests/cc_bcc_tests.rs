@@ -125,6 +125,9 @@ async fn test_send_message_with_cc() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
 
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c)
@@ -181,6 +184,9 @@ async fn test_send_message_with_bcc() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
 
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c)
@@ -237,6 +243,9 @@ async fn test_send_message_with_cc_and_bcc() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
 
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c)
@@ -304,6 +313,9 @@ async fn test_cc_recipient_can_see_message() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
 
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
@@ -351,6 +363,9 @@ async fn test_bcc_recipient_can_see_message() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
 
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
@@ -425,6 +440,9 @@ async fn test_multiple_cc_recipients() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
 
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
@@ -494,6 +512,9 @@ async fn test_multiple_bcc_recipients() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
 
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
@@ -551,6 +572,9 @@ async fn test_cc_recipient_mark_as_read() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
 
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
@@ -583,6 +607,9 @@ async fn test_bcc_recipient_acknowledge() {
         thread_id: None,
         importance: Some("high".to_string()),
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
 
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
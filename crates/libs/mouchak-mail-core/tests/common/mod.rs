@@ -96,26 +96,10 @@ async fn create_test_db(db_path: &std::path::Path) -> Result<mouchak_mail_core::
     // Apply migrations
     let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
 
-    // Read schema from migrations directory (same path as store/mod.rs uses)
-    let schema = include_str!("../../../../../migrations/001_initial_schema.sql");
-    conn.execute_batch(schema).await?;
-    let schema002 = include_str!("../../../../../migrations/002_agent_capabilities.sql");
-    conn.execute_batch(schema002).await?;
-    let schema003 = include_str!("../../../../../migrations/003_tool_metrics.sql");
-    conn.execute_batch(schema003).await?;
-    let schema004 = include_str!("../../../../../migrations/004_attachments.sql");
-    conn.execute_batch(schema004).await?;
-    let schema005 = include_str!("../../../../../migrations/005_attachments_agent.sql");
-    conn.execute_batch(schema005).await?;
-    let schema006 = include_str!("../../../../../migrations/006_query_indexes.sql");
-    conn.execute_batch(schema006).await?;
-
-    // Verify idempotency: running migrations again should not fail
-    conn.execute_batch(schema).await?;
-    conn.execute_batch(schema002).await?;
-    conn.execute_batch(schema003).await?;
-    conn.execute_batch(schema004).await?;
-    conn.execute_batch(schema006).await?;
+    mouchak_mail_core::store::apply_migrations(&conn).await?;
+
+    // Verify idempotency: applying again should be a no-op
+    mouchak_mail_core::store::apply_migrations(&conn).await?;
 
     Ok(conn)
 }
@@ -31,16 +31,9 @@ async fn create_test_mm() -> (ModelManager, TempDir) {
     let db = Builder::new_local(&db_path).build().await.unwrap();
     let conn = db.connect().unwrap();
 
-    // Run migrations inline (same as store/mod.rs)
-    let migrations = [
-        include_str!("../../../../migrations/001_initial_schema.sql"),
-        include_str!("../../../../migrations/002_agent_capabilities.sql"),
-        include_str!("../../../../migrations/003_tool_metrics.sql"),
-        include_str!("../../../../migrations/004_attachments.sql"),
-    ];
-    for migration in &migrations {
-        conn.execute_batch(migration).await.expect("run migration");
-    }
+    mouchak_mail_core::store::apply_migrations(&conn)
+        .await
+        .expect("run migrations");
 
     let app_config = Arc::new(AppConfig::default());
     let mm = ModelManager::new_for_test(conn, temp_dir.path().to_path_buf(), app_config);
@@ -93,6 +86,9 @@ fn make_message(
         thread_id,
         importance: Some("normal".to_string()),
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     }
 }
 
@@ -673,3 +669,67 @@ async fn test_concurrent_message_bundle_writes() {
         assert!(count >= 1, "Agent {} bundle should have some successes", i);
     }
 }
+
+// ============================================================================
+// TEST 13: Thread Sequence Numbers Under Concurrent Sends (50 parallel)
+// ============================================================================
+
+#[tokio::test]
+async fn test_concurrent_thread_sequence_numbers() {
+    let (mm, _temp) = create_test_mm().await;
+    let mm = Arc::new(mm);
+
+    let (project_id, agent_ids) = setup_test_project(&mm).await;
+    let thread_id = "numbered-thread";
+
+    let handles: Vec<_> = (0..50)
+        .map(|i| {
+            let mm = Arc::clone(&mm);
+            let sender_id = agent_ids[i % agent_ids.len()];
+            let recipient_id = agent_ids[(i + 1) % agent_ids.len()];
+
+            tokio::spawn(async move {
+                let ctx = Ctx::root_ctx();
+                let msg = make_message(
+                    project_id,
+                    sender_id,
+                    vec![recipient_id],
+                    format!("Numbered message {}", i),
+                    "Sequence test".to_string(),
+                    Some(thread_id.to_string()),
+                );
+                MessageBmc::create(&ctx, &mm, msg).await.unwrap()
+            })
+        })
+        .collect();
+    for handle in join_all(handles).await {
+        handle.unwrap();
+    }
+
+    let ctx = Ctx::root_ctx();
+    let thread = MessageBmc::list_by_thread(&ctx, &mm, project_id.get(), thread_id)
+        .await
+        .unwrap();
+
+    // Gapless, returned in sequence order, and numbered in insertion order
+    let seqs: Vec<i64> = thread.iter().map(|m| m.thread_seq.unwrap()).collect();
+    assert_eq!(seqs, (1..=50).collect::<Vec<i64>>());
+    assert!(thread.windows(2).all(|pair| pair[0].id < pair[1].id));
+
+    let next = MessageBmc::create(
+        &ctx,
+        &mm,
+        make_message(
+            project_id,
+            agent_ids[0],
+            vec![agent_ids[1]],
+            "After the burst".to_string(),
+            "Sequence test".to_string(),
+            Some(thread_id.to_string()),
+        ),
+    )
+    .await
+    .unwrap();
+    let next = MessageBmc::get(&ctx, &mm, next).await.unwrap();
+    assert_eq!(next.thread_seq, Some(51));
+}
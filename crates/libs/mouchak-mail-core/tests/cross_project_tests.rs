@@ -0,0 +1,244 @@
+//! Cross-project messaging tests
+//!
+//! Recipients addressed as `slug::agent-name` live in another project. The
+//! message stays in the sender's project and shows up in the recipient's
+//! inbox and thread views with the sender's project slug.
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use crate::common::TestContext;
+use mouchak_mail_common::config::AppConfig;
+use mouchak_mail_core::model::agent::{Agent, AgentBmc, AgentForCreate};
+use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate, RecipientFailure};
+use mouchak_mail_core::model::project::ProjectBmc;
+use mouchak_mail_core::types::ProjectId;
+use mouchak_mail_core::{Ctx, Error};
+
+mod common;
+
+async fn create_agent(tc: &TestContext, slug: &str, name: &str) -> (ProjectId, Agent) {
+    let project_id = match ProjectBmc::get_by_slug(&tc.ctx, &tc.mm, slug).await {
+        Ok(project) => project.id,
+        Err(_) => ProjectBmc::create(&tc.ctx, &tc.mm, slug, &format!("/peers/{}", slug))
+            .await
+            .unwrap(),
+    };
+    let agent_c = AgentForCreate {
+        project_id,
+        name: name.to_string(),
+        program: "test".to_string(),
+        model: "test".to_string(),
+        task_description: String::new(),
+    };
+    let agent_id = AgentBmc::create(&tc.ctx, &tc.mm, agent_c).await.unwrap();
+    let agent = AgentBmc::get(&tc.ctx, &tc.mm, agent_id).await.unwrap();
+    (project_id, agent)
+}
+
+fn message(
+    project_id: ProjectId,
+    sender: &Agent,
+    recipient: &Agent,
+    subject: &str,
+    thread: &str,
+) -> MessageForCreate {
+    MessageForCreate {
+        project_id: project_id.get(),
+        sender_id: sender.id.get(),
+        recipient_ids: vec![recipient.id.get()],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: subject.to_string(),
+        body_md: "body".to_string(),
+        thread_id: Some(thread.to_string()),
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
+    }
+}
+
+fn peers_config(peers: &[&str]) -> AppConfig {
+    let mut config = AppConfig::default();
+    config.projects.allowed_peers = peers.iter().map(|p| p.to_string()).collect();
+    config
+}
+
+#[tokio::test]
+async fn test_resolve_address_in_other_project() {
+    let tc = TestContext::new().await.unwrap();
+    let (frontend, _) = create_agent(&tc, "frontend", "Designer").await;
+    let (backend, worker) = create_agent(&tc, "backend", "Worker").await;
+
+    // Root may address any project
+    let agent = AgentBmc::resolve_address(&tc.ctx, &tc.mm, frontend, "backend::Worker")
+        .await
+        .unwrap();
+    assert_eq!(agent.id, worker.id);
+    assert_eq!(agent.project_id, backend);
+
+    // The home project's own slug resolves locally; plain names too
+    let local = AgentBmc::resolve_address(&tc.ctx, &tc.mm, backend, "backend::Worker")
+        .await
+        .unwrap();
+    assert_eq!(local.id, worker.id);
+    let plain = AgentBmc::resolve_address(&tc.ctx, &tc.mm, backend, "Worker")
+        .await
+        .unwrap();
+    assert_eq!(plain.id, worker.id);
+
+    let unknown = AgentBmc::resolve_address(&tc.ctx, &tc.mm, frontend, "nowhere::Worker").await;
+    assert!(matches!(unknown, Err(Error::ProjectNotFound { .. })));
+    let missing = AgentBmc::resolve_address(&tc.ctx, &tc.mm, frontend, "backend::Ghost").await;
+    assert!(matches!(missing, Err(Error::AgentNotFound { .. })));
+}
+
+#[tokio::test]
+async fn test_cross_project_denied_without_allowed_peer() {
+    let tc = TestContext::new_with_config(peers_config(&["ops"]))
+        .await
+        .unwrap();
+    let (frontend, designer) = create_agent(&tc, "frontend", "Designer").await;
+    let (_, worker) = create_agent(&tc, "backend", "Worker").await;
+    let scoped = Ctx::scoped(0, None, vec!["frontend".to_string()]);
+
+    let resolved = AgentBmc::resolve_address(&scoped, &tc.mm, frontend, "backend::Worker").await;
+    assert!(matches!(resolved, Err(Error::CrossProjectForbidden(ref slug)) if slug == "frontend"));
+
+    // Agent ids from elsewhere are rejected at send time as well
+    let sent = MessageBmc::create(
+        &scoped,
+        &tc.mm,
+        message(frontend, &designer, &worker, "hello", "t-denied"),
+    )
+    .await;
+    assert!(matches!(sent, Err(Error::CrossProjectForbidden(_))));
+    let inbox = MessageBmc::list_inbox_for_agent(
+        &tc.ctx,
+        &tc.mm,
+        worker.project_id.get(),
+        worker.id.get(),
+        10,
+    )
+    .await
+    .unwrap();
+    assert!(inbox.is_empty());
+}
+
+#[tokio::test]
+async fn test_resolve_recipients_reports_every_failure() {
+    let tc = TestContext::new_with_config(peers_config(&["ops"]))
+        .await
+        .unwrap();
+    let (frontend, _) = create_agent(&tc, "frontend", "Designer").await;
+    let (_, reviewer) = create_agent(&tc, "frontend", "Reviewer").await;
+    create_agent(&tc, "backend", "Worker").await;
+    let scoped = Ctx::scoped(0, None, vec!["frontend".to_string()]);
+
+    let to = vec!["Reviewer".to_string(), "Ghost".to_string()];
+    let cc = vec!["backend::Worker".to_string()];
+    let resolved = MessageBmc::resolve_recipients(&scoped, &tc.mm, frontend, &to, Some(&cc), None)
+        .await
+        .unwrap();
+
+    assert_eq!(resolved.recipient_ids, vec![reviewer.id.get()]);
+    assert_eq!(resolved.cc_ids, Some(vec![]));
+    assert_eq!(resolved.bcc_ids, None);
+    assert_eq!(resolved.delivered, vec!["Reviewer".to_string()]);
+    assert_eq!(
+        resolved.failed,
+        vec![
+            RecipientFailure {
+                name: "Ghost".to_string(),
+                code: "AGENT_NOT_FOUND".to_string(),
+            },
+            RecipientFailure {
+                name: "backend::Worker".to_string(),
+                code: "FORBIDDEN_CROSS_PROJECT".to_string(),
+            },
+        ]
+    );
+    assert!(matches!(
+        resolved.first_error,
+        Some(Error::AgentNotFound { .. })
+    ));
+    assert!(!resolved.can_send(false));
+    assert!(resolved.can_send(true));
+}
+
+#[tokio::test]
+async fn test_cross_project_message_in_receiver_inbox() {
+    let tc = TestContext::new_with_config(peers_config(&["frontend"]))
+        .await
+        .unwrap();
+    let (frontend, designer) = create_agent(&tc, "frontend", "Designer").await;
+    let (backend, worker) = create_agent(&tc, "backend", "Worker").await;
+    let scoped = Ctx::scoped(0, None, vec!["frontend".to_string()]);
+
+    let recipient = AgentBmc::resolve_address(&scoped, &tc.mm, frontend, "backend::Worker")
+        .await
+        .unwrap();
+    let id = MessageBmc::create(
+        &scoped,
+        &tc.mm,
+        message(frontend, &designer, &recipient, "API shape?", "t-api"),
+    )
+    .await
+    .unwrap();
+
+    let inbox =
+        MessageBmc::list_inbox_for_agent(&tc.ctx, &tc.mm, backend.get(), worker.id.get(), 10)
+            .await
+            .unwrap();
+    assert_eq!(inbox.len(), 1);
+    assert_eq!(inbox[0].id, id);
+    assert_eq!(inbox[0].project_id, frontend.get());
+    assert_eq!(inbox[0].project_slug.as_deref(), Some("frontend"));
+
+    let recipients = MessageBmc::get_recipients(&tc.ctx, &tc.mm, id)
+        .await
+        .unwrap();
+    assert_eq!(recipients, vec!["backend::Worker"]);
+
+    // The sender's outbox is unchanged and carries no foreign slug
+    let sent = MessageBmc::list_by_thread(&tc.ctx, &tc.mm, frontend.get(), "t-api")
+        .await
+        .unwrap();
+    assert_eq!(sent.len(), 1);
+    assert!(sent[0].project_slug.is_none());
+}
+
+#[tokio::test]
+async fn test_thread_spanning_projects() {
+    let tc = TestContext::new().await.unwrap();
+    let (frontend, designer) = create_agent(&tc, "frontend", "Designer").await;
+    let (backend, worker) = create_agent(&tc, "backend", "Worker").await;
+
+    let question = MessageBmc::create(
+        &tc.ctx,
+        &tc.mm,
+        message(frontend, &designer, &worker, "API shape?", "t-span"),
+    )
+    .await
+    .unwrap();
+    let answer = MessageBmc::create(
+        &tc.ctx,
+        &tc.mm,
+        message(backend, &worker, &designer, "Re: API shape?", "t-span"),
+    )
+    .await
+    .unwrap();
+
+    for (project, foreign) in [(frontend, answer), (backend, question)] {
+        let thread = MessageBmc::list_by_thread(&tc.ctx, &tc.mm, project.get(), "t-span")
+            .await
+            .unwrap();
+        let ids: Vec<i64> = thread.iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec![question, answer]);
+        for m in &thread {
+            assert_eq!(m.project_slug.is_some(), m.id == foreign);
+        }
+    }
+}
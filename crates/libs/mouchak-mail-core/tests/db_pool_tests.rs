@@ -0,0 +1,99 @@
+//! Database pool tests
+//!
+//! Tests for the writer + read-only reader connection pool.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use mouchak_mail_core::store::db_pool::DbPool;
+use std::sync::Arc;
+use std::time::Instant;
+use tempfile::TempDir;
+
+/// Counts to a few million in SQL: long enough to overlap with another query.
+const LONG_READ: &str = "WITH RECURSIVE c(x) AS (SELECT 1 UNION ALL SELECT x + 1 FROM c WHERE x < 5000000) SELECT COUNT(*) FROM c";
+
+async fn open_pool(temp_dir: &TempDir, readers: usize) -> DbPool {
+    let pool = DbPool::open(&temp_dir.path().join("pool.db"), readers)
+        .await
+        .unwrap();
+    pool.writer()
+        .execute("CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT)", ())
+        .await
+        .unwrap();
+    pool
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_concurrent_long_reads_do_not_block() {
+    let temp_dir = TempDir::new().unwrap();
+    let pool = Arc::new(open_pool(&temp_dir, 2).await);
+
+    let long_pool = pool.clone();
+    let long_read = tokio::spawn(async move {
+        let mut rows = long_pool.reader().query(LONG_READ, ()).await.unwrap();
+        rows.next().await.unwrap();
+        Instant::now()
+    });
+
+    // Let the long read start, then issue a second read on the other reader
+    tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+    let short_pool = pool.clone();
+    let short_read = tokio::spawn(async move {
+        let mut rows = short_pool
+            .reader()
+            .query("SELECT COUNT(*) FROM items", ())
+            .await
+            .unwrap();
+        rows.next().await.unwrap();
+        Instant::now()
+    });
+
+    let long_done = long_read.await.unwrap();
+    let short_done = short_read.await.unwrap();
+    assert!(
+        short_done < long_done,
+        "Second read waited for the first one to finish"
+    );
+}
+
+#[tokio::test]
+async fn test_readers_see_committed_writes_and_reject_writes() {
+    let temp_dir = TempDir::new().unwrap();
+    let pool = open_pool(&temp_dir, 2).await;
+    assert_eq!(pool.reader_count(), 2);
+
+    pool.writer()
+        .execute("INSERT INTO items (name) VALUES ('a')", ())
+        .await
+        .unwrap();
+    for _ in 0..pool.reader_count() {
+        let mut rows = pool
+            .reader()
+            .query("SELECT COUNT(*) FROM items", ())
+            .await
+            .unwrap();
+        let count: i64 = rows.next().await.unwrap().unwrap().get(0).unwrap();
+        assert_eq!(count, 1);
+    }
+
+    let result = pool
+        .reader()
+        .execute("INSERT INTO items (name) VALUES ('b')", ())
+        .await;
+    assert!(result.is_err(), "Readers must be read-only");
+}
+
+#[tokio::test]
+async fn test_empty_pool_reads_from_writer() {
+    let temp_dir = TempDir::new().unwrap();
+    let pool = open_pool(&temp_dir, 0).await;
+    assert_eq!(pool.reader_count(), 0);
+
+    let mut rows = pool
+        .reader()
+        .query("SELECT COUNT(*) FROM items", ())
+        .await
+        .unwrap();
+    let count: i64 = rows.next().await.unwrap().unwrap().get(0).unwrap();
+    assert_eq!(count, 0);
+}
@@ -58,6 +58,9 @@ async fn test_list_overdue_acks() -> mouchak_mail_core::Result<()> {
         thread_id: None,
         importance: None,
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let overdue_msg_id = MessageBmc::create(ctx, mm, msg_c).await?;
 
@@ -81,6 +84,9 @@ async fn test_list_overdue_acks() -> mouchak_mail_core::Result<()> {
         thread_id: None,
         importance: None,
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let _recent_msg_id = MessageBmc::create(ctx, mm, msg_recent).await?;
 
@@ -96,6 +102,9 @@ async fn test_list_overdue_acks() -> mouchak_mail_core::Result<()> {
         thread_id: None,
         importance: None,
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let acked_msg_id = MessageBmc::create(ctx, mm, msg_acked).await?;
     // Backdate
@@ -119,6 +128,9 @@ async fn test_list_overdue_acks() -> mouchak_mail_core::Result<()> {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let no_ack_msg_id = MessageBmc::create(ctx, mm, msg_no_ack).await?;
     db.execute(
@@ -191,6 +203,9 @@ async fn test_escalate_overdue_log_mode_dry_run() -> mouchak_mail_core::Result<(
         thread_id: None,
         importance: None,
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(ctx, mm, msg_c).await?;
 
@@ -259,6 +274,9 @@ async fn test_escalate_overdue_log_mode_real() -> mouchak_mail_core::Result<()>
         thread_id: None,
         importance: None,
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(ctx, mm, msg_c).await?;
 
@@ -334,6 +352,9 @@ async fn test_escalate_overdue_no_overdue_messages() -> mouchak_mail_core::Resul
         thread_id: None,
         importance: None,
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let _msg_id = MessageBmc::create(ctx, mm, msg_c).await?;
 
@@ -392,6 +413,9 @@ async fn test_escalate_overdue_overseer_mode_dry_run() -> mouchak_mail_core::Res
         thread_id: None,
         importance: None,
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(ctx, mm, msg_c).await?;
 
@@ -460,6 +484,9 @@ async fn test_escalate_overdue_overseer_mode_real() -> mouchak_mail_core::Result
         thread_id: None,
         importance: None,
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(ctx, mm, msg_c).await?;
 
@@ -535,6 +562,9 @@ async fn test_escalate_overdue_file_reservation_dry_run() -> mouchak_mail_core::
         thread_id: None,
         importance: None,
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(ctx, mm, msg_c).await?;
 
@@ -610,6 +640,9 @@ async fn test_escalate_overdue_file_reservation_real() -> mouchak_mail_core::Res
         thread_id: None,
         importance: None,
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(ctx, mm, msg_c).await?;
 
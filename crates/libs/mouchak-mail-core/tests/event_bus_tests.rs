@@ -0,0 +1,403 @@
+//! Event bus tests
+//!
+//! Tests for live event publishing, replay, and BMC integration.
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::inefficient_to_string
+)]
+
+#[path = "common/mod.rs"]
+mod common;
+
+use crate::common::TestContext;
+use chrono::{Duration, Utc};
+use mouchak_mail_core::events::{EventBus, MailEventKind};
+use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
+use mouchak_mail_core::model::file_reservation::{FileReservationBmc, FileReservationForCreate};
+use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
+use mouchak_mail_core::model::project::ProjectBmc;
+
+/// Test replay returns only events after the given ID and history is bounded
+#[test]
+fn test_replay_since_and_capacity() {
+    let bus = EventBus::new(3);
+    for i in 0..5 {
+        bus.publish(
+            MailEventKind::MessageCreated,
+            "proj",
+            serde_json::json!({ "n": i }),
+        );
+    }
+
+    assert_eq!(bus.latest_id(), 5);
+
+    // Only the last 3 events are retained
+    let all: Vec<u64> = bus.replay_since(0).iter().map(|e| e.id).collect();
+    assert_eq!(all, vec![3, 4, 5]);
+
+    let after: Vec<u64> = bus.replay_since(4).iter().map(|e| e.id).collect();
+    assert_eq!(after, vec![5]);
+}
+
+/// Test dropped receivers are released so reconnects don't leak subscribers
+#[test]
+fn test_receivers_released_on_drop() {
+    let bus = EventBus::default();
+    assert_eq!(bus.receiver_count(), 0);
+
+    let first = bus.subscribe();
+    let second = bus.subscribe();
+    assert_eq!(bus.receiver_count(), 2);
+
+    drop(first);
+    drop(second);
+    assert_eq!(bus.receiver_count(), 0);
+
+    // Publishing without subscribers is fine
+    bus.publish(MailEventKind::MessageRead, "proj", serde_json::json!({}));
+}
+
+/// Test event kinds serialize to their wire names
+#[test]
+fn test_event_serialization() {
+    let bus = EventBus::default();
+    bus.publish(
+        MailEventKind::ReservationReleased,
+        "proj",
+        serde_json::json!({ "id": 7 }),
+    );
+    let event = bus.replay_since(0).pop().unwrap();
+
+    let value = serde_json::to_value(&event).unwrap();
+    assert_eq!(value["type"], "reservation.released");
+    assert_eq!(value["project_slug"], "proj");
+    assert_eq!(value["data"]["id"], 7);
+}
+
+/// Test message and reservation BMCs publish events with the project slug
+#[tokio::test]
+async fn test_bmc_writes_publish_events() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+
+    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, "events-proj", "/events/proj")
+        .await
+        .unwrap();
+
+    let mut agent_ids = Vec::new();
+    for name in ["Alpha", "Beta"] {
+        let agent_c = AgentForCreate {
+            project_id,
+            name: name.to_string(),
+            program: "test".to_string(),
+            model: "test".to_string(),
+            task_description: "Event agent".to_string(),
+        };
+        agent_ids.push(AgentBmc::create(&tc.ctx, &tc.mm, agent_c).await.unwrap());
+    }
+
+    let mut rx = tc.mm.events.subscribe();
+
+    let msg_c = MessageForCreate {
+        project_id: project_id.get(),
+        sender_id: agent_ids[0].get(),
+        recipient_ids: vec![agent_ids[1].get()],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: "Live".to_string(),
+        body_md: "Hello".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    let message_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
+
+    let created = rx.recv().await.unwrap();
+    assert_eq!(created.kind, MailEventKind::MessageCreated);
+    assert_eq!(created.project_slug, "events-proj");
+    assert_eq!(created.data["id"], message_id);
+    assert_eq!(created.data["sender_name"], "Alpha");
+
+    MessageBmc::mark_read(&tc.ctx, &tc.mm, message_id, agent_ids[1].get())
+        .await
+        .unwrap();
+    let read = rx.recv().await.unwrap();
+    assert_eq!(read.kind, MailEventKind::MessageRead);
+    assert_eq!(read.data["agent_name"], "Beta");
+
+    // Marking read again changes nothing and publishes nothing
+    MessageBmc::mark_read(&tc.ctx, &tc.mm, message_id, agent_ids[1].get())
+        .await
+        .unwrap();
+
+    let reservation_id = FileReservationBmc::create(
+        &tc.ctx,
+        &tc.mm,
+        FileReservationForCreate {
+            project_id,
+            agent_id: agent_ids[0],
+            path_pattern: "src/live.rs".to_string(),
+            exclusive: true,
+            reason: "Events".to_string(),
+            expires_ts: Utc::now().naive_utc() + Duration::hours(1),
+        },
+    )
+    .await
+    .unwrap();
+    let reserved = rx.recv().await.unwrap();
+    assert_eq!(reserved.kind, MailEventKind::ReservationCreated);
+    assert_eq!(reserved.data["id"], reservation_id);
+
+    FileReservationBmc::release(&tc.ctx, &tc.mm, reservation_id)
+        .await
+        .unwrap();
+    let released = rx.recv().await.unwrap();
+    assert_eq!(released.kind, MailEventKind::ReservationReleased);
+    assert_eq!(released.data["path_pattern"], "src/live.rs");
+
+    assert!(rx.try_recv().is_err(), "No further events expected");
+}
+
+/// Test acks and overlapping reservations publish their hookable events
+#[tokio::test]
+async fn test_ack_and_conflict_publish_events() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (project_id, alpha, beta) = setup_pair(&tc, "events-hooks").await;
+
+    let mut msg_c = message(project_id, alpha, beta, "Deploy?");
+    msg_c.importance = Some("urgent".to_string());
+    let message_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
+
+    let mut rx = tc.mm.events.subscribe();
+    MessageBmc::acknowledge(&tc.ctx, &tc.mm, message_id, beta)
+        .await
+        .unwrap();
+    let acked = rx.recv().await.unwrap();
+    assert_eq!(acked.kind, MailEventKind::MessageAcked);
+    assert_eq!(acked.project_slug, "events-hooks");
+    assert_eq!(acked.data["message_id"], message_id);
+    assert_eq!(acked.data["agent_name"], "Beta");
+    assert_eq!(acked.data["importance"], "urgent");
+
+    // Acking a message the agent never got publishes nothing
+    MessageBmc::acknowledge(&tc.ctx, &tc.mm, message_id, alpha)
+        .await
+        .unwrap();
+    assert!(rx.try_recv().is_err());
+
+    let reserve = |agent_id: i64, path: &str, exclusive: bool| FileReservationForCreate {
+        project_id: project_id.into(),
+        agent_id: agent_id.into(),
+        path_pattern: path.to_string(),
+        exclusive,
+        reason: "Events".to_string(),
+        expires_ts: Utc::now().naive_utc() + Duration::hours(1),
+    };
+    let held = FileReservationBmc::create(&tc.ctx, &tc.mm, reserve(alpha, "src/**", true))
+        .await
+        .unwrap();
+    assert_eq!(
+        rx.recv().await.unwrap().kind,
+        MailEventKind::ReservationCreated
+    );
+
+    let contested = FileReservationBmc::create(&tc.ctx, &tc.mm, reserve(beta, "src/lib.rs", false))
+        .await
+        .unwrap();
+    assert_eq!(
+        rx.recv().await.unwrap().kind,
+        MailEventKind::ReservationCreated
+    );
+    let conflict = rx.recv().await.unwrap();
+    assert_eq!(conflict.kind, MailEventKind::ReservationConflict);
+    assert_eq!(conflict.data["id"], contested);
+    assert_eq!(conflict.data["agent_name"], "Beta");
+    assert_eq!(conflict.data["conflicts"][0]["id"], held);
+    assert_eq!(conflict.data["conflicts"][0]["agent_name"], "Alpha");
+
+    // Shared reservations on different paths don't conflict
+    FileReservationBmc::create(&tc.ctx, &tc.mm, reserve(beta, "docs/**", false))
+        .await
+        .unwrap();
+    assert_eq!(
+        rx.recv().await.unwrap().kind,
+        MailEventKind::ReservationCreated
+    );
+    assert!(rx.try_recv().is_err(), "No conflict expected");
+}
+
+/// Test closing the bus wakes waiters, including ones that subscribe late
+#[tokio::test]
+async fn test_close_wakes_waiters() {
+    let bus = std::sync::Arc::new(EventBus::default());
+    assert!(!bus.is_closed());
+
+    let waiter = {
+        let bus = bus.clone();
+        tokio::spawn(async move { bus.closed().await })
+    };
+
+    bus.close();
+    tokio::time::timeout(std::time::Duration::from_secs(1), waiter)
+        .await
+        .expect("waiter should wake on close")
+        .unwrap();
+
+    assert!(bus.is_closed());
+    // Already closed: resolves immediately
+    bus.closed().await;
+}
+
+/// Test ModelManager::shutdown closes the event bus and checkpoints the database
+#[tokio::test]
+async fn test_model_manager_shutdown() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+
+    ProjectBmc::create(&tc.ctx, &tc.mm, "shutdown-proj", "/shutdown/proj")
+        .await
+        .unwrap();
+
+    tc.mm.shutdown().await.expect("shutdown should succeed");
+    assert!(tc.mm.events.is_closed());
+
+    // The connection stays usable for anything still holding the manager
+    assert!(tc.mm.health_check().await.unwrap());
+}
+
+/// Creates a project with agents "Alpha" and "Beta"; returns (project, alpha, beta)
+async fn setup_pair(tc: &TestContext, slug: &str) -> (i64, i64, i64) {
+    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, slug, &format!("/events/{}", slug))
+        .await
+        .unwrap();
+    let mut ids = Vec::new();
+    for name in ["Alpha", "Beta"] {
+        let agent_c = AgentForCreate {
+            project_id,
+            name: name.to_string(),
+            program: "test".to_string(),
+            model: "test".to_string(),
+            task_description: "Event agent".to_string(),
+        };
+        ids.push(
+            AgentBmc::create(&tc.ctx, &tc.mm, agent_c)
+                .await
+                .unwrap()
+                .get(),
+        );
+    }
+    (project_id.get(), ids[0], ids[1])
+}
+
+fn message(project_id: i64, from: i64, to: i64, subject: &str) -> MessageForCreate {
+    MessageForCreate {
+        project_id,
+        sender_id: from,
+        recipient_ids: vec![to],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: subject.to_string(),
+        body_md: "Hello".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    }
+}
+
+/// Test a concurrent send wakes a pending inbox wait
+#[tokio::test]
+async fn test_send_wakes_inbox_wait() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (project_id, alpha, beta) = setup_pair(&tc, "wait-wake").await;
+
+    // Already-delivered mail doesn't satisfy a wait without a cursor
+    MessageBmc::create(&tc.ctx, &tc.mm, message(project_id, alpha, beta, "Old"))
+        .await
+        .unwrap();
+
+    let started = std::time::Instant::now();
+    let (waited, sent) = tokio::join!(
+        MessageBmc::wait_for_inbox(
+            &tc.ctx,
+            &tc.mm,
+            project_id,
+            beta,
+            None,
+            std::time::Duration::from_secs(30),
+        ),
+        async {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            // Mail for someone else doesn't end the wait
+            MessageBmc::create(&tc.ctx, &tc.mm, message(project_id, beta, alpha, "Other"))
+                .await
+                .unwrap();
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            MessageBmc::create(&tc.ctx, &tc.mm, message(project_id, alpha, beta, "New"))
+                .await
+                .unwrap()
+        }
+    );
+
+    let waited = waited.unwrap();
+    assert_eq!(waited.len(), 1);
+    assert_eq!(waited[0].id, sent);
+    assert_eq!(waited[0].subject, "New");
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+
+    // With a cursor, existing newer mail is returned immediately
+    let pending = MessageBmc::wait_for_inbox(
+        &tc.ctx,
+        &tc.mm,
+        project_id,
+        beta,
+        Some(0),
+        std::time::Duration::from_secs(30),
+    )
+    .await
+    .unwrap();
+    let mut subjects: Vec<&str> = pending.iter().map(|m| m.subject.as_str()).collect();
+    subjects.sort_unstable();
+    assert_eq!(subjects, vec!["New", "Old"]);
+}
+
+/// Test an inbox wait with no new mail returns empty at the timeout
+#[tokio::test]
+async fn test_inbox_wait_times_out_empty() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (project_id, alpha, beta) = setup_pair(&tc, "wait-timeout").await;
+    let old = MessageBmc::create(&tc.ctx, &tc.mm, message(project_id, alpha, beta, "Old"))
+        .await
+        .unwrap();
+
+    let started = std::time::Instant::now();
+    let waited = MessageBmc::wait_for_inbox(
+        &tc.ctx,
+        &tc.mm,
+        project_id,
+        beta,
+        Some(old),
+        std::time::Duration::from_millis(200),
+    )
+    .await
+    .unwrap();
+
+    assert!(waited.is_empty());
+    assert!(started.elapsed() >= std::time::Duration::from_millis(200));
+}
@@ -3,7 +3,9 @@
 use mouchak_mail_core::ctx::Ctx;
 use mouchak_mail_core::model::ModelManager;
 use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
-use mouchak_mail_core::model::export::{ExportBmc, ExportFormat, ScrubMode};
+use mouchak_mail_core::model::export::{
+    ExportBmc, ExportCompression, ExportFilter, ExportFormat, ScrubMode,
+};
 use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
 use mouchak_mail_core::model::project::ProjectBmc;
 use uuid::Uuid;
@@ -63,6 +65,9 @@ async fn test_export_scrubbing() -> mouchak_mail_core::Result<()> {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&ctx, &mm, msg).await?;
 
@@ -74,6 +79,8 @@ async fn test_export_scrubbing() -> mouchak_mail_core::Result<()> {
         ExportFormat::Markdown,
         ScrubMode::Standard,
         false,
+        &ExportFilter::default(),
+        ExportCompression::None,
     )
     .await?;
 
@@ -93,6 +100,8 @@ async fn test_export_scrubbing() -> mouchak_mail_core::Result<()> {
         ExportFormat::Markdown,
         ScrubMode::Aggressive,
         false,
+        &ExportFilter::default(),
+        ExportCompression::None,
     )
     .await?;
 
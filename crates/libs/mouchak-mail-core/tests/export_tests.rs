@@ -9,210 +9,1191 @@
     clippy::inefficient_to_string
 )]
 
-#[path = "common/mod.rs"]
-mod common;
-
-use crate::common::TestContext;
-use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
-use mouchak_mail_core::model::export::{ExportBmc, ExportFormat, ScrubMode};
+use mouchak_mail_core::model::agent::AgentBmc;
+use mouchak_mail_core::model::export::{
+    ExportBmc, ExportCompression, ExportFilter, ExportFormat, SELECTION_EXPORT_SLUG, ScrubMode,
+};
 use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
-use mouchak_mail_core::model::project::ProjectBmc;
+use mouchak_mail_core::testing::TestEnv;
 use mouchak_mail_core::types::ProjectId;
-use mouchak_mail_core::utils::slugify;
 
 /// Helper to set up a project with messages for export tests
-async fn setup_project_with_messages(tc: &TestContext, suffix: &str) -> (ProjectId, String) {
-    let human_key = format!("/test/export-repo-{}", suffix);
-    let slug = slugify(&human_key);
+async fn setup_project_with_messages(tc: &TestEnv, suffix: &str) -> (ProjectId, String) {
+    let fixture = tc
+        .fixtures()
+        .project(format!("/test/export-repo-{}", suffix))
+        .agents(["sender-agent", "recipient-agent"])
+        .messages(3, None, None)
+        .build()
+        .await
+        .expect("Failed to build fixture");
+
+    (fixture.project_id, fixture.project_slug)
+}
+
+/// Test exporting mailbox in JSON format
+#[tokio::test]
+async fn test_export_json() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (_, slug) = setup_project_with_messages(&tc, "json").await;
+
+    let exported = ExportBmc::export_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ExportFormat::Json,
+        ScrubMode::None,
+        false,
+        &ExportFilter::default(),
+        ExportCompression::None,
+    )
+    .await
+    .expect("Failed to export mailbox");
+
+    assert_eq!(exported.project_slug, slug);
+    assert_eq!(exported.format, "json");
+    assert_eq!(exported.message_count, 3);
+    assert!(exported.content.contains("Test Message"));
+    assert!(
+        exported.content.starts_with('['),
+        "JSON should start with array"
+    );
+}
+
+/// Test exporting mailbox in HTML format
+#[tokio::test]
+async fn test_export_html() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (_, slug) = setup_project_with_messages(&tc, "html").await;
+
+    let exported = ExportBmc::export_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ExportFormat::Html,
+        ScrubMode::None,
+        false,
+        &ExportFilter::default(),
+        ExportCompression::None,
+    )
+    .await
+    .expect("Failed to export mailbox");
+
+    assert_eq!(exported.format, "html");
+    assert!(exported.content.contains("<!DOCTYPE html>"));
+    assert!(exported.content.contains("<title>"));
+    assert!(exported.content.contains("Test Message"));
+}
 
-    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, &slug, &human_key)
+/// Add a two-message thread "TKT-42/Login flow" to a project from
+/// `setup_project_with_messages`
+async fn add_login_thread(tc: &TestEnv, project_id: ProjectId) {
+    let sender = AgentBmc::get_by_name(&tc.ctx, &tc.mm, project_id, "sender-agent")
         .await
-        .expect("Failed to create project");
-
-    // Create sender agent
-    let sender = AgentForCreate {
-        project_id,
-        name: "sender-agent".to_string(),
-        program: "claude-code".to_string(),
-        model: "claude-3".to_string(),
-        task_description: "Sending messages".to_string(),
-    };
-    let sender_id = AgentBmc::create(&tc.ctx, &tc.mm, sender)
+        .expect("Failed to get sender");
+    let recipient = AgentBmc::get_by_name(&tc.ctx, &tc.mm, project_id, "recipient-agent")
+        .await
+        .expect("Failed to get recipient");
+
+    for (from, to, subject, body) in [
+        (
+            &sender,
+            &recipient,
+            "Login flow",
+            "Please review **the login flow**.\n\n<img src=x onerror=alert(1)>",
+        ),
+        (
+            &recipient,
+            &sender,
+            "Re: Login flow",
+            "Looks good, see [the PR](https://example.com/pr/1).",
+        ),
+    ] {
+        MessageBmc::create(
+            &tc.ctx,
+            &tc.mm,
+            MessageForCreate {
+                project_id: project_id.get(),
+                sender_id: from.id.get(),
+                recipient_ids: vec![to.id.get()],
+                cc_ids: None,
+                bcc_ids: None,
+                subject: subject.to_string(),
+                body_md: body.to_string(),
+                thread_id: Some("TKT-42/Login flow".to_string()),
+                importance: Some("high".to_string()),
+                ack_required: false,
+                deliver_at: None,
+                broadcast: false,
+                allow_new_thread: true,
+            },
+        )
         .await
-        .expect("Failed to create sender");
-
-    // Create recipient agent
-    let recipient = AgentForCreate {
-        project_id,
-        name: "recipient-agent".to_string(),
-        program: "cursor".to_string(),
-        model: "gpt-4".to_string(),
-        task_description: "Receiving messages".to_string(),
+        .expect("Failed to create message");
+    }
+}
+
+/// HTML export groups messages by thread behind a table of contents
+#[tokio::test]
+async fn test_export_html_thread_anchors() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (project_id, slug) = setup_project_with_messages(&tc, "html-threads").await;
+    add_login_thread(&tc, project_id).await;
+
+    let html = ExportBmc::export_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ExportFormat::Html,
+        ScrubMode::None,
+        false,
+        &ExportFilter::default(),
+        ExportCompression::None,
+    )
+    .await
+    .expect("Failed to export mailbox")
+    .content;
+
+    // Thread ids are normalized into anchors the TOC links to
+    assert!(html.contains("<section class=\"thread\" id=\"thread-tkt-42-login-flow\">"));
+    assert!(html.contains("<a href=\"#thread-tkt-42-login-flow\">Login flow</a>"));
+    assert!(html.contains("(2 message(s), last "));
+    // Unthreaded messages get a thread of their own
+    assert_eq!(html.matches("<section class=\"thread\"").count(), 4);
+    assert_eq!(
+        html.matches("<article class=\"message\" id=\"msg-").count(),
+        5
+    );
+
+    // Within a thread the conversation reads oldest first
+    let first = html.find("Please review").unwrap();
+    let reply = html.find("Looks good").unwrap();
+    assert!(first < reply);
+
+    // Recipients, timestamps and rendered, sanitized Markdown
+    assert!(html.contains("<div class=\"meta\">To: recipient-agent</div>"));
+    assert!(html.contains("<time datetime=\""));
+    assert!(html.contains("<strong>the login flow</strong>"));
+    assert!(html.contains("<a href=\"https://example.com/pr/1\" rel=\"noopener noreferrer\">"));
+    assert!(!html.contains("<img"));
+    assert!(html.contains("high importance"));
+    // Styles are inline, nothing is loaded from elsewhere
+    assert!(html.contains("<style>"));
+    assert!(!html.contains("<link"));
+}
+
+/// html-zip splits the export into one page per thread
+#[tokio::test]
+async fn test_export_html_zip() {
+    use std::io::Read;
+
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (project_id, slug) = setup_project_with_messages(&tc, "html-zip").await;
+    add_login_thread(&tc, project_id).await;
+
+    let exported = ExportBmc::export_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ExportFormat::HtmlZip,
+        ScrubMode::None,
+        false,
+        &ExportFilter::default(),
+        ExportCompression::None,
+    )
+    .await
+    .expect("Failed to export mailbox");
+    assert_eq!(exported.format, "html-zip");
+    assert_eq!(exported.message_count, 5);
+
+    let bytes = exported.content_bytes().expect("Invalid zip content");
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).expect("Invalid zip");
+    let mut names: Vec<String> = archive.file_names().map(str::to_string).collect();
+    names.sort();
+    assert_eq!(names.len(), 5);
+    assert_eq!(names[0], "index.html");
+    assert!(names.contains(&"threads/thread-tkt-42-login-flow.html".to_string()));
+    // The other three messages each start a thread of their own
+    assert_eq!(
+        names
+            .iter()
+            .filter(|name| name.starts_with("threads/")
+                && name.as_str() != "threads/thread-tkt-42-login-flow.html")
+            .count(),
+        3
+    );
+
+    let mut index = String::new();
+    archive
+        .by_name("index.html")
+        .unwrap()
+        .read_to_string(&mut index)
+        .unwrap();
+    assert!(index.contains("<!DOCTYPE html>"));
+    assert!(index.contains("<a href=\"threads/thread-tkt-42-login-flow.html\">Login flow</a>"));
+
+    let mut thread = String::new();
+    archive
+        .by_name("threads/thread-tkt-42-login-flow.html")
+        .unwrap()
+        .read_to_string(&mut thread)
+        .unwrap();
+    assert!(thread.contains("<a href=\"../index.html\">"));
+    assert!(thread.contains("Please review") && thread.contains("Looks good"));
+    assert!(!thread.contains("Test Message"));
+}
+
+/// HTML exports keep every message of a long thread, past the buffered
+/// export limit
+#[tokio::test]
+async fn test_export_html_includes_all_messages() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let fixture = tc
+        .fixtures()
+        .project("/test/export-repo-html-long")
+        .agents(["sender-agent", "recipient-agent"])
+        .messages(120, Some("TKT-7"), None)
+        .build()
+        .await
+        .expect("Failed to build fixture");
+
+    for format in [ExportFormat::Html, ExportFormat::HtmlZip] {
+        let exported = ExportBmc::export_mailbox(
+            &tc.ctx,
+            &tc.mm,
+            &fixture.project_slug,
+            format,
+            ScrubMode::None,
+            false,
+            &ExportFilter::default(),
+            ExportCompression::None,
+        )
+        .await
+        .expect("Failed to export mailbox");
+        assert_eq!(exported.message_count, 120, "{}", format.as_str());
+    }
+
+    let markdown = ExportBmc::export_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &fixture.project_slug,
+        ExportFormat::Markdown,
+        ScrubMode::None,
+        false,
+        &ExportFilter::default(),
+        ExportCompression::None,
+    )
+    .await
+    .expect("Failed to export mailbox");
+    assert_eq!(markdown.message_count, 100);
+}
+
+/// Test exporting mailbox in Markdown format
+#[tokio::test]
+async fn test_export_markdown() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (_, slug) = setup_project_with_messages(&tc, "md").await;
+
+    let exported = ExportBmc::export_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ExportFormat::Markdown,
+        ScrubMode::None,
+        false,
+        &ExportFilter::default(),
+        ExportCompression::None,
+    )
+    .await
+    .expect("Failed to export mailbox");
+
+    assert_eq!(exported.format, "markdown");
+    assert!(exported.content.contains("# Mailbox Export"));
+    assert!(exported.content.contains("## Test Message"));
+    assert!(exported.content.contains("**From:**"));
+}
+
+/// Test exporting mailbox in CSV format
+#[tokio::test]
+async fn test_export_csv() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (_, slug) = setup_project_with_messages(&tc, "csv").await;
+
+    let exported = ExportBmc::export_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ExportFormat::Csv,
+        ScrubMode::None,
+        false,
+        &ExportFilter::default(),
+        ExportCompression::None,
+    )
+    .await
+    .expect("Failed to export mailbox");
+
+    assert_eq!(exported.format, "csv");
+    assert!(exported.content.starts_with(
+        "id,created_at,sender,subject,body,thread_id,importance,ack_required,recipients\n"
+    ));
+    assert!(exported.content.contains("Test Message"));
+
+    let rows = parse_delimited(&exported.content, b',');
+    assert_eq!(rows.len(), 3);
+    for row in &rows {
+        assert_eq!(row.len(), 9);
+        assert_eq!(row[2], "sender-agent");
+        assert_eq!(row[6], "normal");
+        assert_eq!(row[7], "false");
+        assert_eq!(row[8], "recipient-agent");
+    }
+}
+
+/// Parse a CSV/TSV export back into records, skipping the header
+fn parse_delimited(content: &str, delimiter: u8) -> Vec<Vec<String>> {
+    csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .from_reader(content.as_bytes())
+        .records()
+        .map(|r| r.unwrap().iter().map(str::to_string).collect())
+        .collect()
+}
+
+/// Test CSV and TSV exports round-trip awkward field contents
+#[tokio::test]
+async fn test_export_csv_escaping_and_tsv() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let fixture = tc
+        .fixtures()
+        .project("/test/export-repo-csv-escaping")
+        .agents(["sender-agent", "to-agent", "cc-agent", "bcc-agent"])
+        .build()
+        .await
+        .expect("Failed to build fixture");
+    let (project_id, slug) = (fixture.project_id, fixture.project_slug.clone());
+    let ids: Vec<_> = fixture.agents.iter().map(|(_, id)| *id).collect();
+
+    let subject = "Re: \"quoted\", commas,\ttabs — naïve 日本語 🚀";
+    let body = "line one, with comma\nline \"two\"\r\nline\tthree";
+    let msg = MessageForCreate {
+        project_id: project_id.get(),
+        sender_id: ids[0].into(),
+        recipient_ids: vec![ids[1].into()],
+        cc_ids: Some(vec![ids[2].into()]),
+        bcc_ids: Some(vec![ids[3].into()]),
+        subject: subject.to_string(),
+        body_md: body.to_string(),
+        thread_id: Some("TKT-1, \"escalated\"".to_string()),
+        importance: Some("high".to_string()),
+        ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
-    let recipient_id = AgentBmc::create(&tc.ctx, &tc.mm, recipient)
+    MessageBmc::create(&tc.ctx, &tc.mm, msg).await.unwrap();
+
+    for (format, delimiter) in [(ExportFormat::Csv, b','), (ExportFormat::Tsv, b'\t')] {
+        let exported = ExportBmc::export_mailbox(
+            &tc.ctx,
+            &tc.mm,
+            &slug,
+            format,
+            ScrubMode::None,
+            false,
+            &ExportFilter::default(),
+            ExportCompression::None,
+        )
+        .await
+        .expect("Failed to export mailbox");
+        assert_eq!(exported.format, format.as_str());
+
+        let rows = parse_delimited(&exported.content, delimiter);
+        assert_eq!(rows.len(), 1, "{:?}: {}", format, exported.content);
+        let row = &rows[0];
+        assert_eq!(row[2], "sender-agent");
+        assert_eq!(row[3], subject);
+        assert_eq!(row[4], body);
+        assert_eq!(row[5], "TKT-1, \"escalated\"");
+        assert_eq!(row[6], "high");
+        assert_eq!(row[7], "true");
+        // Bcc recipients stay out of the export
+        let mut recipients: Vec<&str> = row[8].split(';').collect();
+        recipients.sort_unstable();
+        assert_eq!(recipients, vec!["cc-agent", "to-agent"]);
+    }
+
+    let tsv = ExportBmc::export_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ExportFormat::Tsv,
+        ScrubMode::None,
+        false,
+        &ExportFilter::default(),
+        ExportCompression::None,
+    )
+    .await
+    .unwrap();
+    assert!(tsv.content.starts_with("id\tcreated_at\tsender\t"));
+}
+
+/// Test exporting empty mailbox
+#[tokio::test]
+async fn test_export_empty_mailbox() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let slug = tc
+        .fixtures()
+        .project("/test/empty-export-repo")
+        .build()
         .await
-        .expect("Failed to create recipient");
+        .expect("Failed to build fixture")
+        .project_slug;
 
-    // Create some messages
-    for i in 1..=3 {
+    let exported = ExportBmc::export_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ExportFormat::Json,
+        ScrubMode::None,
+        false,
+        &ExportFilter::default(),
+        ExportCompression::None,
+    )
+    .await
+    .expect("Failed to export mailbox");
+
+    assert_eq!(exported.message_count, 0);
+    assert_eq!(exported.content, "[]"); // Empty JSON array
+}
+
+/// Test exporting mailbox in NDJSON format
+#[tokio::test]
+async fn test_export_ndjson() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (_, slug) = setup_project_with_messages(&tc, "ndjson").await;
+
+    let exported = ExportBmc::export_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ExportFormat::Ndjson,
+        ScrubMode::None,
+        false,
+        &ExportFilter::default(),
+        ExportCompression::None,
+    )
+    .await
+    .expect("Failed to export mailbox");
+
+    assert_eq!(exported.format, "ndjson");
+    assert_eq!(exported.message_count, 3);
+    assert!(exported.content.ends_with('\n'));
+
+    let lines: Vec<&str> = exported.content.lines().collect();
+    assert_eq!(lines.len(), exported.message_count);
+    for line in &lines {
+        let value: serde_json::Value = serde_json::from_str(line).expect("Line should be JSON");
+        assert!(value.is_object());
+        // Stable field order: id first, attachments last
+        assert!(line.starts_with("{\"id\":"));
+        assert!(line.contains("\"attachments\":["));
+        assert!(line.find("\"subject\"") < line.find("\"body_md\""));
+    }
+}
+
+/// Test NDJSON export of an empty mailbox produces an empty body
+#[tokio::test]
+async fn test_export_ndjson_empty_mailbox() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let slug = tc
+        .fixtures()
+        .project("/test/empty-ndjson-repo")
+        .build()
+        .await
+        .expect("Failed to build fixture")
+        .project_slug;
+
+    let exported = ExportBmc::export_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ExportFormat::Ndjson,
+        ScrubMode::None,
+        false,
+        &ExportFilter::default(),
+        ExportCompression::None,
+    )
+    .await
+    .expect("Failed to export mailbox");
+
+    assert_eq!(exported.message_count, 0);
+    assert_eq!(exported.content, "");
+}
+
+/// Test exporting a thread as mbox with threading headers and From-line escaping
+#[tokio::test]
+async fn test_export_mbox() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (project_id, slug) = setup_project_with_messages(&tc, "mbox").await;
+    let sender = AgentBmc::get_by_name(&tc.ctx, &tc.mm, project_id, "sender-agent")
+        .await
+        .unwrap();
+    let recipient = AgentBmc::get_by_name(&tc.ctx, &tc.mm, project_id, "recipient-agent")
+        .await
+        .unwrap();
+
+    for (from, to, body) in [
+        (
+            &sender,
+            &recipient,
+            "From the logs:\n>From earlier\nFrom: not a header",
+        ),
+        (&recipient, &sender, "Looks good"),
+    ] {
         let msg = MessageForCreate {
             project_id: project_id.get(),
-            sender_id: sender_id.into(),
-            recipient_ids: vec![recipient_id.into()],
+            sender_id: from.id.into(),
+            recipient_ids: vec![to.id.into()],
             cc_ids: None,
             bcc_ids: None,
-            subject: format!("Test Message {}", i),
-            body_md: format!("This is the body of message {}.", i),
-            thread_id: None,
+            subject: "Review findings".to_string(),
+            body_md: body.to_string(),
+            thread_id: Some("REVIEW-1".to_string()),
             importance: None,
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: true,
         };
         MessageBmc::create(&tc.ctx, &tc.mm, msg)
             .await
             .expect("Failed to create message");
     }
 
-    (project_id, slug)
+    let exported = ExportBmc::export_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ExportFormat::Mbox,
+        ScrubMode::None,
+        false,
+        &ExportFilter {
+            thread_id: Some("REVIEW-1".to_string()),
+            ..Default::default()
+        },
+        ExportCompression::None,
+    )
+    .await
+    .expect("Failed to export mailbox");
+
+    assert_eq!(exported.format, "mbox");
+    assert_eq!(exported.message_count, 2);
+
+    let content = &exported.content;
+    let domain = format!("{}.local", slug);
+    let separators: Vec<&str> = content.lines().filter(|l| l.starts_with("From ")).collect();
+    assert_eq!(separators.len(), 2, "only separator lines start with From");
+    assert!(separators[0].starts_with(&format!("From sender-agent@{} ", domain)));
+
+    assert!(content.contains(&format!("From: sender-agent <sender-agent@{}>", domain)));
+    assert!(content.contains(&format!("To: recipient-agent <recipient-agent@{}>", domain)));
+    assert!(content.contains("Subject: Review findings\n"));
+    assert!(content.contains("Content-Type: text/plain; charset=utf-8\n"));
+
+    // Body lines starting with "From " (after any '>') are quoted
+    assert!(content.contains("\n>From the logs:\n>>From earlier\nFrom: not a header\n"));
+
+    // Reply points at the first message; both reference the thread root
+    let first_id = content
+        .lines()
+        .find_map(|l| l.strip_prefix("Message-ID: "))
+        .unwrap();
+    assert!(content.contains(&format!("In-Reply-To: {}\n", first_id)));
+    assert!(content.contains(&format!(
+        "References: <thread.REVIEW-1@{}> {}\n",
+        domain, first_id
+    )));
+    assert!(content.contains(&format!("In-Reply-To: <thread.REVIEW-1@{}>\n", domain)));
+    assert!(content.ends_with("\n\n"));
 }
 
-/// Test exporting mailbox in JSON format
+/// Test NDJSON stream counts messages and feeds an accurate manifest
 #[tokio::test]
-async fn test_export_json() {
-    let tc = TestContext::new()
+async fn test_export_ndjson_stream_manifest() {
+    use mouchak_mail_core::model::export::ExportManifest;
+
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (_, slug) = setup_project_with_messages(&tc, "ndjson-stream").await;
+
+    let mut stream = ExportBmc::export_ndjson_stream(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ScrubMode::None,
+        &ExportFilter::default(),
+    )
+    .await
+    .expect("Failed to open stream");
+
+    let mut content = String::new();
+    while let Some(line) = stream.next_line().await.expect("Failed to read line") {
+        content.push_str(&line);
+    }
+    assert_eq!(stream.message_count(), 3);
+
+    let exported = stream.into_exported(content);
+    let manifest = ExportManifest::new(&exported);
+    assert_eq!(manifest.message_count, 3);
+    assert_eq!(manifest.format, "ndjson");
+}
+
+/// Test export filtered by thread and agent
+#[tokio::test]
+async fn test_export_filter_by_thread_and_agent() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (project_id, slug) = setup_project_with_messages(&tc, "filter-thread").await;
+    let sender = AgentBmc::get_by_name(&tc.ctx, &tc.mm, project_id, "sender-agent")
+        .await
+        .expect("Failed to get sender");
+    let recipient = AgentBmc::get_by_name(&tc.ctx, &tc.mm, project_id, "recipient-agent")
+        .await
+        .expect("Failed to get recipient");
+
+    let msg = MessageForCreate {
+        project_id: project_id.get(),
+        sender_id: sender.id.into(),
+        recipient_ids: vec![recipient.id.into()],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: "Threaded Message".to_string(),
+        body_md: "Part of a specific thread.".to_string(),
+        thread_id: Some("THREAD-FILTER-1".to_string()),
+        importance: Some("high".to_string()),
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
+    };
+    MessageBmc::create(&tc.ctx, &tc.mm, msg)
+        .await
+        .expect("Failed to create message");
+
+    let filter = ExportFilter {
+        thread_id: Some("THREAD-FILTER-1".to_string()),
+        ..Default::default()
+    };
+    let exported = ExportBmc::export_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ExportFormat::Json,
+        ScrubMode::None,
+        false,
+        &filter,
+        ExportCompression::None,
+    )
+    .await
+    .expect("Failed to export mailbox");
+
+    assert_eq!(exported.message_count, 1);
+    assert!(exported.content.contains("Threaded Message"));
+    assert_eq!(exported.filter, Some(filter));
+
+    // Recipient-side agent filter matches every message they received
+    let agent_filter = ExportFilter {
+        agent_name: Some("recipient-agent".to_string()),
+        ..Default::default()
+    };
+    let exported = ExportBmc::export_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ExportFormat::Json,
+        ScrubMode::None,
+        false,
+        &agent_filter,
+        ExportCompression::None,
+    )
+    .await
+    .expect("Failed to export mailbox");
+    assert_eq!(exported.message_count, 4);
+
+    let unknown_agent = ExportFilter {
+        agent_name: Some("nobody".to_string()),
+        ..Default::default()
+    };
+    let exported = ExportBmc::export_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ExportFormat::Json,
+        ScrubMode::None,
+        false,
+        &unknown_agent,
+        ExportCompression::None,
+    )
+    .await
+    .expect("Failed to export mailbox");
+    assert_eq!(exported.message_count, 0);
+}
+
+/// Test export filter with since after until is rejected
+#[tokio::test]
+async fn test_export_filter_since_after_until() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (_, slug) = setup_project_with_messages(&tc, "filter-range").await;
+
+    let filter = ExportFilter {
+        since: Some(ExportFilter::parse_date_bound("2025-06-02", false).unwrap()),
+        until: Some(ExportFilter::parse_date_bound("2025-06-01", true).unwrap()),
+        ..Default::default()
+    };
+    let result = ExportBmc::export_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ExportFormat::Json,
+        ScrubMode::None,
+        false,
+        &filter,
+        ExportCompression::None,
+    )
+    .await;
+
+    assert!(matches!(
+        result,
+        Err(mouchak_mail_core::Error::InvalidInput(_))
+    ));
+}
+
+/// Test filtered export with no matches is valid in every format
+#[tokio::test]
+async fn test_export_filter_empty_result_all_formats() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (_, slug) = setup_project_with_messages(&tc, "filter-empty").await;
+
+    // All test messages are created "now", so a range in the past matches nothing
+    let filter = ExportFilter {
+        until: Some(ExportFilter::parse_date_bound("2000-01-01", true).unwrap()),
+        ..Default::default()
+    };
+
+    for format in [
+        ExportFormat::Json,
+        ExportFormat::Html,
+        ExportFormat::Markdown,
+        ExportFormat::Csv,
+        ExportFormat::Mbox,
+    ] {
+        let exported = ExportBmc::export_mailbox(
+            &tc.ctx,
+            &tc.mm,
+            &slug,
+            format,
+            ScrubMode::None,
+            false,
+            &filter,
+            ExportCompression::None,
+        )
         .await
-        .expect("Failed to create test context");
+        .expect("Failed to export mailbox");
+
+        assert_eq!(exported.message_count, 0);
+        match format {
+            ExportFormat::Json => assert_eq!(exported.content, "[]"),
+            ExportFormat::Mbox => assert_eq!(exported.content, ""),
+            ExportFormat::Csv => assert_eq!(
+                exported.content.trim(),
+                "id,created_at,sender,subject,body,thread_id,importance,ack_required,recipients"
+            ),
+            _ => assert!(exported.content.contains("Total messages: 0")),
+        }
+    }
+}
+
+/// Test signed export records the filter in its manifest
+#[tokio::test]
+async fn test_export_filter_recorded_in_signed_manifest() {
+    use mouchak_mail_core::model::export::generate_signing_keypair;
+
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (_, slug) = setup_project_with_messages(&tc, "filter-signed").await;
+    let (signing_key, _) = generate_signing_keypair();
+
+    let filter = ExportFilter {
+        since: Some(ExportFilter::parse_date_bound("2000-01-01T00:00:00Z", false).unwrap()),
+        agent_name: Some("sender-agent".to_string()),
+        ..Default::default()
+    };
+    let (exported, mut manifest) = ExportBmc::export_mailbox_signed(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ExportFormat::Json,
+        ScrubMode::None,
+        false,
+        &filter,
+        Some(&signing_key),
+    )
+    .await
+    .expect("Failed to export signed mailbox");
+
+    assert_eq!(manifest.filter, Some(filter));
+    assert!(ExportBmc::verify_export(&exported, &manifest).unwrap());
+
+    // The filter is covered by the signature
+    manifest.filter = None;
+    assert!(!manifest.verify().unwrap());
+}
+
+/// Test export date bound parsing
+#[test]
+fn test_export_filter_parse_date_bound() {
+    let start = ExportFilter::parse_date_bound("2025-03-04", false).unwrap();
+    assert_eq!(start.to_string(), "2025-03-04 00:00:00");
+
+    let end = ExportFilter::parse_date_bound("2025-03-04", true).unwrap();
+    assert_eq!(end.to_string(), "2025-03-04 23:59:59");
+
+    let rfc = ExportFilter::parse_date_bound("2025-03-04T10:00:00+02:00", false).unwrap();
+    assert_eq!(rfc.to_string(), "2025-03-04 08:00:00");
+
+    assert!(ExportFilter::parse_date_bound("yesterday", false).is_err());
+}
+
+/// Test a JSON export restores into another project and re-importing skips it
+#[tokio::test]
+async fn test_import_json_round_trip() {
+    use mouchak_mail_core::model::export::IMPORTED_AGENT_PROGRAM;
 
-    let (_, slug) = setup_project_with_messages(&tc, "json").await;
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
+    let (_, source_slug) = setup_project_with_messages(&tc, "import-src").await;
     let exported = ExportBmc::export_mailbox(
         &tc.ctx,
         &tc.mm,
-        &slug,
+        &source_slug,
         ExportFormat::Json,
         ScrubMode::None,
         false,
+        &ExportFilter::default(),
+        ExportCompression::None,
     )
     .await
     .expect("Failed to export mailbox");
 
-    assert_eq!(exported.project_slug, slug);
-    assert_eq!(exported.format, "json");
-    assert_eq!(exported.message_count, 3);
-    assert!(exported.content.contains("Test Message"));
-    assert!(
-        exported.content.starts_with('['),
-        "JSON should start with array"
-    );
-}
-
-/// Test exporting mailbox in HTML format
-#[tokio::test]
-async fn test_export_html() {
-    let tc = TestContext::new()
+    let target = tc
+        .fixtures()
+        .project("/test/import-target")
+        .build()
         .await
-        .expect("Failed to create test context");
+        .expect("Failed to build fixture");
+    let (target_id, target_slug) = (target.project_id, target.project_slug);
 
-    let (_, slug) = setup_project_with_messages(&tc, "html").await;
-
-    let exported = ExportBmc::export_mailbox(
+    let report = ExportBmc::import_mailbox(
         &tc.ctx,
         &tc.mm,
-        &slug,
-        ExportFormat::Html,
-        ScrubMode::None,
-        false,
+        &target_slug,
+        &exported.content,
+        ExportFormat::Json,
+        None,
     )
     .await
-    .expect("Failed to export mailbox");
+    .expect("Failed to import");
 
-    assert_eq!(exported.format, "html");
-    assert!(exported.content.contains("<!DOCTYPE html>"));
-    assert!(exported.content.contains("<title>"));
-    assert!(exported.content.contains("Test Message"));
-}
+    assert_eq!(report.imported, 3);
+    assert_eq!(report.skipped, 0);
+    assert_eq!(report.failed, 0);
+    assert_eq!(report.agents_created, vec!["sender-agent".to_string()]);
+    assert_eq!(report.manifest_verified, None);
 
-/// Test exporting mailbox in Markdown format
-#[tokio::test]
-async fn test_export_markdown() {
-    let tc = TestContext::new()
+    let agent = AgentBmc::get_by_name(&tc.ctx, &tc.mm, target_id, "sender-agent")
         .await
-        .expect("Failed to create test context");
-
-    let (_, slug) = setup_project_with_messages(&tc, "md").await;
+        .unwrap();
+    assert_eq!(agent.program, IMPORTED_AGENT_PROGRAM);
 
-    let exported = ExportBmc::export_mailbox(
+    // Timestamps and thread ids survive the round trip
+    let restored = ExportBmc::export_mailbox(
         &tc.ctx,
         &tc.mm,
-        &slug,
-        ExportFormat::Markdown,
+        &target_slug,
+        ExportFormat::Json,
         ScrubMode::None,
         false,
+        &ExportFilter::default(),
+        ExportCompression::None,
     )
     .await
-    .expect("Failed to export mailbox");
+    .expect("Failed to export restored mailbox");
+    let original: Vec<serde_json::Value> = serde_json::from_str(&exported.content).unwrap();
+    let restored: Vec<serde_json::Value> = serde_json::from_str(&restored.content).unwrap();
+    assert_eq!(restored.len(), 3);
+    for (a, b) in original.iter().zip(&restored) {
+        for field in [
+            "created_ts",
+            "thread_id",
+            "subject",
+            "body_md",
+            "sender_name",
+        ] {
+            assert_eq!(a[field], b[field], "{} differs", field);
+        }
+    }
 
-    assert_eq!(exported.format, "markdown");
-    assert!(exported.content.contains("# Mailbox Export"));
-    assert!(exported.content.contains("## Test Message"));
-    assert!(exported.content.contains("**From:**"));
+    let again = ExportBmc::import_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &target_slug,
+        &exported.content,
+        ExportFormat::Json,
+        None,
+    )
+    .await
+    .expect("Failed to re-import");
+    assert_eq!(again.imported, 0);
+    assert_eq!(again.skipped, 3);
+    assert!(again.agents_created.is_empty());
 }
 
-/// Test exporting mailbox in CSV format
+/// JSON exports hold the whole mailbox, and two imports of one export
+/// running at once write each message only once
 #[tokio::test]
-async fn test_export_csv() {
-    let tc = TestContext::new()
+async fn test_import_json_full_mailbox_concurrently() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let source_slug = tc
+        .fixtures()
+        .project("/test/import-large-src")
+        .agents(["sender-agent", "recipient-agent"])
+        .messages(120, None, None)
+        .build()
         .await
-        .expect("Failed to create test context");
-
-    let (_, slug) = setup_project_with_messages(&tc, "csv").await;
-
+        .expect("Failed to build fixture")
+        .project_slug;
     let exported = ExportBmc::export_mailbox(
         &tc.ctx,
         &tc.mm,
-        &slug,
-        ExportFormat::Csv,
+        &source_slug,
+        ExportFormat::Json,
         ScrubMode::None,
         false,
+        &ExportFilter::default(),
+        ExportCompression::None,
     )
     .await
     .expect("Failed to export mailbox");
+    assert_eq!(exported.message_count, 120);
 
-    assert_eq!(exported.format, "csv");
-    assert!(
-        exported
-            .content
-            .contains("id,created_at,sender,subject,body")
+    let target_slug = tc
+        .fixtures()
+        .project("/test/import-large-target")
+        .agents(["sender-agent"])
+        .build()
+        .await
+        .expect("Failed to build fixture")
+        .project_slug;
+
+    let import = || {
+        ExportBmc::import_mailbox(
+            &tc.ctx,
+            &tc.mm,
+            &target_slug,
+            &exported.content,
+            ExportFormat::Json,
+            None,
+        )
+    };
+    let (first, second) = tokio::join!(import(), import());
+    let (first, second) = (
+        first.expect("Import failed"),
+        second.expect("Import failed"),
     );
-    assert!(exported.content.contains("Test Message"));
+    assert_eq!(first.failed + second.failed, 0);
+    assert_eq!(first.imported + second.imported, 120);
+    assert_eq!(first.skipped + second.skipped, 120);
+
+    let restored = ExportBmc::export_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &target_slug,
+        ExportFormat::Json,
+        ScrubMode::None,
+        false,
+        &ExportFilter::default(),
+        ExportCompression::None,
+    )
+    .await
+    .expect("Failed to export restored mailbox");
+    assert_eq!(restored.message_count, 120);
 }
 
-/// Test exporting empty mailbox
+/// Exports show what readers see: recalled bodies are replaced by the
+/// recall reason and scheduled messages stay out until delivered
 #[tokio::test]
-async fn test_export_empty_mailbox() {
-    let tc = TestContext::new()
+async fn test_export_hides_recalled_and_scheduled_messages() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let fixture = tc
+        .fixtures()
+        .project("/test/export-repo-visible")
+        .agents(["sender-agent", "recipient-agent"])
+        .messages(2, None, None)
+        .build()
         .await
-        .expect("Failed to create test context");
-
-    let human_key = "/test/empty-export-repo";
-    let slug = slugify(human_key);
-
-    ProjectBmc::create(&tc.ctx, &tc.mm, &slug, human_key)
+        .expect("Failed to build fixture");
+    let sender_id = fixture.agent_id("sender-agent").get();
+    let recalled_id = fixture.message_ids[0];
+    MessageBmc::recall(&tc.ctx, &tc.mm, recalled_id, sender_id, "Wrong numbers")
+        .await
+        .expect("Failed to recall");
+    let scheduled_id = MessageBmc::create(
+        &tc.ctx,
+        &tc.mm,
+        MessageForCreate {
+            project_id: fixture.project_id.get(),
+            sender_id,
+            recipient_ids: vec![fixture.agent_id("recipient-agent").get()],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: "Scheduled note".to_string(),
+            body_md: "Not out yet".to_string(),
+            thread_id: None,
+            importance: None,
+            ack_required: false,
+            deliver_at: Some(chrono::Utc::now().naive_utc() + chrono::Duration::days(1)),
+            broadcast: false,
+            allow_new_thread: false,
+        },
+    )
+    .await
+    .expect("Failed to schedule message");
+
+    for format in [ExportFormat::Json, ExportFormat::Ndjson] {
+        let exported = ExportBmc::export_mailbox(
+            &tc.ctx,
+            &tc.mm,
+            &fixture.project_slug,
+            format,
+            ScrubMode::None,
+            false,
+            &ExportFilter::default(),
+            ExportCompression::None,
+        )
         .await
-        .expect("Failed to create project");
+        .expect("Failed to export mailbox");
+        assert_eq!(exported.message_count, 2, "{}", format.as_str());
+        assert!(!exported.content.contains("body of message 1"));
+        assert!(exported.content.contains("Wrong numbers"));
+        assert!(!exported.content.contains("Not out yet"));
+    }
 
-    let exported = ExportBmc::export_mailbox(
+    let selected = ExportBmc::export_messages(
         &tc.ctx,
         &tc.mm,
-        &slug,
+        &[recalled_id, scheduled_id],
         ExportFormat::Json,
         ScrubMode::None,
+    )
+    .await
+    .expect("Failed to export selection");
+    assert_eq!(selected.message_count, 1);
+    assert!(selected.content.contains("Wrong numbers"));
+    assert!(!selected.content.contains("Not out yet"));
+}
+
+/// Test NDJSON import verifies the manifest and reports bad lines
+#[tokio::test]
+async fn test_import_ndjson_with_manifest() {
+    use mouchak_mail_core::model::export::generate_signing_keypair;
+
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (_, source_slug) = setup_project_with_messages(&tc, "import-ndjson").await;
+    let (signing_key, _) = generate_signing_keypair();
+    let (exported, manifest) = ExportBmc::export_mailbox_signed(
+        &tc.ctx,
+        &tc.mm,
+        &source_slug,
+        ExportFormat::Ndjson,
+        ScrubMode::None,
         false,
+        &ExportFilter::default(),
+        Some(&signing_key),
     )
     .await
     .expect("Failed to export mailbox");
 
-    assert_eq!(exported.message_count, 0);
-    assert_eq!(exported.content, "[]"); // Empty JSON array
+    let target_slug = tc
+        .fixtures()
+        .project("/test/import-ndjson-target")
+        .build()
+        .await
+        .expect("Failed to build fixture")
+        .project_slug;
+
+    // Tampered content is rejected before anything is written
+    let tampered = exported.content.replace("message 1", "message 9");
+    let result = ExportBmc::import_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &target_slug,
+        &tampered,
+        ExportFormat::Ndjson,
+        Some(&manifest),
+    )
+    .await;
+    assert!(result.is_err());
+
+    let report = ExportBmc::import_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &target_slug,
+        &exported.content,
+        ExportFormat::Ndjson,
+        Some(&manifest),
+    )
+    .await
+    .expect("Failed to import");
+    assert_eq!(report.manifest_verified, Some(true));
+    assert_eq!(report.imported, 3);
+
+    // Unparseable lines are counted, not fatal
+    let content = format!("{}{{\"subject\": \"no sender\"}}\n", exported.content);
+    let report = ExportBmc::import_mailbox(
+        &tc.ctx,
+        &tc.mm,
+        &target_slug,
+        &content,
+        ExportFormat::Ndjson,
+        None,
+    )
+    .await
+    .expect("Failed to import");
+    assert_eq!(report.skipped, 3);
+    assert_eq!(report.failed, 1);
+    assert!(report.errors[0].starts_with("line 4:"));
+
+    // Only formats the importer can read back are accepted
+    let result =
+        ExportBmc::import_mailbox(&tc.ctx, &tc.mm, &target_slug, "", ExportFormat::Csv, None).await;
+    assert!(result.is_err());
 }
 
 /// Test export format parsing
@@ -222,6 +1203,10 @@ async fn test_export_format_parsing() {
 
     assert_eq!(ExportFormat::from_str("html").unwrap(), ExportFormat::Html);
     assert_eq!(ExportFormat::from_str("HTML").unwrap(), ExportFormat::Html);
+    assert_eq!(
+        ExportFormat::from_str("html-zip").unwrap(),
+        ExportFormat::HtmlZip
+    );
     assert_eq!(ExportFormat::from_str("json").unwrap(), ExportFormat::Json);
     assert_eq!(
         ExportFormat::from_str("md").unwrap(),
@@ -232,6 +1217,17 @@ async fn test_export_format_parsing() {
         ExportFormat::Markdown
     );
     assert_eq!(ExportFormat::from_str("csv").unwrap(), ExportFormat::Csv);
+    assert_eq!(ExportFormat::from_str("tsv").unwrap(), ExportFormat::Tsv);
+    assert_eq!(
+        ExportFormat::from_str("ndjson").unwrap(),
+        ExportFormat::Ndjson
+    );
+    assert_eq!(
+        ExportFormat::from_str("jsonl").unwrap(),
+        ExportFormat::Ndjson
+    );
+    assert_eq!(ExportFormat::from_str("mbox").unwrap(), ExportFormat::Mbox);
+    assert_eq!(ExportFormat::from_str("MBOX").unwrap(), ExportFormat::Mbox);
     // Unknown defaults to JSON
     assert_eq!(
         ExportFormat::from_str("unknown").unwrap(),
@@ -242,9 +1238,7 @@ async fn test_export_format_parsing() {
 /// Test export for nonexistent project
 #[tokio::test]
 async fn test_export_nonexistent_project() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     let result = ExportBmc::export_mailbox(
         &tc.ctx,
@@ -253,6 +1247,8 @@ async fn test_export_nonexistent_project() {
         ExportFormat::Json,
         ScrubMode::None,
         false,
+        &ExportFilter::default(),
+        ExportCompression::None,
     )
     .await;
 
@@ -262,9 +1258,7 @@ async fn test_export_nonexistent_project() {
 /// Test exported_at timestamp is set
 #[tokio::test]
 async fn test_export_timestamp() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     let (_, slug) = setup_project_with_messages(&tc, "timestamp").await;
 
@@ -275,6 +1269,8 @@ async fn test_export_timestamp() {
         ExportFormat::Json,
         ScrubMode::None,
         false,
+        &ExportFilter::default(),
+        ExportCompression::None,
     )
     .await
     .expect("Failed to export mailbox");
@@ -286,16 +1282,20 @@ async fn test_export_timestamp() {
 /// Test commit_archive creates a git commit with exported mailbox
 #[tokio::test]
 async fn test_commit_archive() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     let (_, slug) = setup_project_with_messages(&tc, "archive").await;
 
     let commit_message = "Archive mailbox for testing";
-    let oid = ExportBmc::commit_archive(&tc.ctx, &tc.mm, &slug, commit_message)
-        .await
-        .expect("Failed to commit archive");
+    let oid = ExportBmc::commit_archive(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        commit_message,
+        ExportCompression::None,
+    )
+    .await
+    .expect("Failed to commit archive");
 
     // Verify OID is a valid git hash (40 hex characters)
     assert_eq!(oid.len(), 40, "Git OID should be 40 characters");
@@ -305,23 +1305,70 @@ async fn test_commit_archive() {
     );
 }
 
-/// Test commit_archive for empty mailbox
+/// Test commit_archive stores a compressed snapshot with a matching suffix
 #[tokio::test]
-async fn test_commit_archive_empty_mailbox() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+async fn test_commit_archive_compressed() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
-    let human_key = "/test/empty-archive-repo";
-    let slug = slugify(human_key);
+    let (_, slug) = setup_project_with_messages(&tc, "archive-zstd").await;
+
+    ExportBmc::commit_archive(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        "Archive compressed mailbox",
+        ExportCompression::Zstd,
+    )
+    .await
+    .expect("Failed to commit archive");
 
-    ProjectBmc::create(&tc.ctx, &tc.mm, &slug, human_key)
+    let repo = tc.mm.get_project_repo(&slug).await.unwrap();
+    let dir = repo
+        .lock()
         .await
-        .expect("Failed to create project");
+        .workdir()
+        .unwrap()
+        .join("mailboxes")
+        .join(&slug);
+    let files: Vec<_> = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .collect();
+    assert_eq!(files.len(), 1);
+    assert!(files[0].to_string_lossy().ends_with(".md.zst"));
+
+    let markdown = ExportCompression::Zstd
+        .decompress(&std::fs::read(&files[0]).unwrap())
+        .unwrap();
+    assert!(
+        String::from_utf8(markdown)
+            .unwrap()
+            .contains("Test Message 1")
+    );
+}
+
+/// Test commit_archive for empty mailbox
+#[tokio::test]
+async fn test_commit_archive_empty_mailbox() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
-    let oid = ExportBmc::commit_archive(&tc.ctx, &tc.mm, &slug, "Archive empty mailbox")
+    let slug = tc
+        .fixtures()
+        .project("/test/empty-archive-repo")
+        .build()
         .await
-        .expect("Failed to commit empty archive");
+        .expect("Failed to build fixture")
+        .project_slug;
+
+    let oid = ExportBmc::commit_archive(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        "Archive empty mailbox",
+        ExportCompression::None,
+    )
+    .await
+    .expect("Failed to commit empty archive");
 
     // Should still create a valid commit even with no messages
     assert_eq!(oid.len(), 40, "Git OID should be 40 characters");
@@ -330,12 +1377,16 @@ async fn test_commit_archive_empty_mailbox() {
 /// Test commit_archive for nonexistent project fails
 #[tokio::test]
 async fn test_commit_archive_nonexistent_project() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
-    let result =
-        ExportBmc::commit_archive(&tc.ctx, &tc.mm, "nonexistent-archive-slug", "Should fail").await;
+    let result = ExportBmc::commit_archive(
+        &tc.ctx,
+        &tc.mm,
+        "nonexistent-archive-slug",
+        "Should fail",
+        ExportCompression::None,
+    )
+    .await;
 
     assert!(
         result.is_err(),
@@ -372,6 +1423,10 @@ async fn test_export_manifest_signing() {
         exported_at: "2025-12-20T00:00:00Z".to_string(),
         content: "test content".to_string(),
         format: "json".to_string(),
+        filter: None,
+        scrub_mode: ScrubMode::None,
+        compression: ExportCompression::None,
+        original_size: None,
     };
 
     let (signing_key, _) = generate_signing_keypair();
@@ -399,6 +1454,10 @@ async fn test_export_manifest_verification() {
         exported_at: "2025-12-20T00:00:00Z".to_string(),
         content: "test content".to_string(),
         format: "json".to_string(),
+        filter: None,
+        scrub_mode: ScrubMode::None,
+        compression: ExportCompression::None,
+        original_size: None,
     };
 
     let (signing_key, _) = generate_signing_keypair();
@@ -422,6 +1481,10 @@ async fn test_export_manifest_tamper_detection() {
         exported_at: "2025-12-20T00:00:00Z".to_string(),
         content: "test content".to_string(),
         format: "json".to_string(),
+        filter: None,
+        scrub_mode: ScrubMode::None,
+        compression: ExportCompression::None,
+        original_size: None,
     };
 
     let (signing_key, _) = generate_signing_keypair();
@@ -447,6 +1510,10 @@ async fn test_export_verify_with_external_key() {
         exported_at: "2025-12-20T00:00:00Z".to_string(),
         content: "test content".to_string(),
         format: "json".to_string(),
+        filter: None,
+        scrub_mode: ScrubMode::None,
+        compression: ExportCompression::None,
+        original_size: None,
     };
 
     let (signing_key, verifying_key) = generate_signing_keypair();
@@ -474,6 +1541,10 @@ async fn test_export_verify_wrong_key_fails() {
         exported_at: "2025-12-20T00:00:00Z".to_string(),
         content: "test content".to_string(),
         format: "json".to_string(),
+        filter: None,
+        scrub_mode: ScrubMode::None,
+        compression: ExportCompression::None,
+        original_size: None,
     };
 
     let (signing_key, _) = generate_signing_keypair();
@@ -491,9 +1562,7 @@ async fn test_export_verify_wrong_key_fails() {
 
 #[tokio::test]
 async fn test_export_mailbox_signed() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     use mouchak_mail_core::model::export::generate_signing_keypair;
 
@@ -507,6 +1576,7 @@ async fn test_export_mailbox_signed() {
         ExportFormat::Json,
         ScrubMode::None,
         false,
+        &ExportFilter::default(),
         Some(&signing_key),
     )
     .await
@@ -671,6 +1741,10 @@ async fn test_manifest_content_hash_integrity() {
         exported_at: "2025-12-20T00:00:00Z".to_string(),
         content: content.to_string(),
         format: "json".to_string(),
+        filter: None,
+        scrub_mode: ScrubMode::None,
+        compression: ExportCompression::None,
+        original_size: None,
     };
 
     let manifest = ExportManifest::new(&exported);
@@ -698,6 +1772,10 @@ async fn test_manifest_structure_completeness() {
         exported_at: "2025-12-20T12:00:00Z".to_string(),
         content: "content".to_string(),
         format: "markdown".to_string(),
+        filter: None,
+        scrub_mode: ScrubMode::None,
+        compression: ExportCompression::None,
+        original_size: None,
     };
 
     let manifest = ExportManifest::new(&exported);
@@ -795,12 +1873,10 @@ async fn test_scrub_mode_parsing() {
 /// Test encrypted export with identity roundtrip
 #[tokio::test]
 async fn test_encrypted_export_identity_roundtrip() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     use mouchak_mail_core::model::export::{
-        ExportBmc, generate_age_identity, generate_signing_keypair,
+        ExportBmc, ExportEncryption, generate_age_identity, generate_signing_keypair,
     };
 
     let (_, slug) = setup_project_with_messages(&tc, "enc-roundtrip").await;
@@ -815,7 +1891,7 @@ async fn test_encrypted_export_identity_roundtrip() {
         ExportFormat::Json,
         ScrubMode::None,
         false,
-        &[recipient],
+        &ExportEncryption::Recipients(vec![recipient]),
         Some(&signing_key),
     )
     .await
@@ -836,9 +1912,7 @@ async fn test_encrypted_export_identity_roundtrip() {
 /// Test encrypted export with passphrase roundtrip
 #[tokio::test]
 async fn test_encrypted_export_passphrase_roundtrip() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     use mouchak_mail_core::model::export::{ExportBmc, generate_signing_keypair};
 
@@ -869,12 +1943,74 @@ async fn test_encrypted_export_passphrase_roundtrip() {
     assert!(dec_manifest.verify().expect("Verification should succeed"));
 }
 
+/// Test encryption options reject mixed, missing, and invalid inputs
+#[test]
+fn test_export_encryption_from_parts() {
+    use mouchak_mail_core::model::export::{ExportEncryption, generate_age_identity};
+
+    let (_, recipient) = generate_age_identity();
+
+    let mixed = ExportEncryption::from_parts(vec![recipient.clone()], Some("pw".to_string()));
+    assert!(matches!(
+        mixed,
+        Err(mouchak_mail_core::Error::InvalidInput(_))
+    ));
+
+    let missing = ExportEncryption::from_parts(vec![], None);
+    assert!(matches!(
+        missing,
+        Err(mouchak_mail_core::Error::InvalidInput(_))
+    ));
+
+    let invalid = ExportEncryption::from_parts(vec!["age1notarealkey".to_string()], None);
+    match invalid {
+        Err(mouchak_mail_core::Error::InvalidInput(msg)) => {
+            assert!(msg.contains("age1notarealkey"));
+        }
+        other => panic!("Expected InvalidInput, got {:?}", other),
+    }
+
+    assert_eq!(
+        ExportEncryption::from_parts(vec![recipient.clone()], None).unwrap(),
+        ExportEncryption::Recipients(vec![recipient])
+    );
+    assert_eq!(
+        ExportEncryption::from_parts(vec![], Some("pw".to_string())).unwrap(),
+        ExportEncryption::Passphrase("pw".to_string())
+    );
+}
+
+/// Test an invalid recipient fails before the export is rendered
+#[tokio::test]
+async fn test_encrypted_export_invalid_recipient() {
+    use mouchak_mail_core::model::export::ExportEncryption;
+
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (_, slug) = setup_project_with_messages(&tc, "enc-invalid").await;
+
+    let result = ExportBmc::export_mailbox_encrypted(
+        &tc.ctx,
+        &tc.mm,
+        &slug,
+        ExportFormat::Json,
+        ScrubMode::None,
+        false,
+        &ExportEncryption::Recipients(vec!["not-a-recipient".to_string()]),
+        None,
+    )
+    .await;
+
+    assert!(matches!(
+        result,
+        Err(mouchak_mail_core::Error::InvalidInput(_))
+    ));
+}
+
 /// Test bundle without signature verifies by content hash only
 #[tokio::test]
 async fn test_verify_bundle_without_signature() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     let (_, slug) = setup_project_with_messages(&tc, "no-sig").await;
 
@@ -886,6 +2022,7 @@ async fn test_verify_bundle_without_signature() {
         ExportFormat::Json,
         ScrubMode::None,
         false,
+        &ExportFilter::default(),
         None, // No signing key
     )
     .await
@@ -903,9 +2040,7 @@ async fn test_verify_bundle_without_signature() {
 /// Test verification fails when content is tampered
 #[tokio::test]
 async fn test_verify_bundle_content_tampered() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     use mouchak_mail_core::model::export::generate_signing_keypair;
 
@@ -919,6 +2054,7 @@ async fn test_verify_bundle_content_tampered() {
         ExportFormat::Json,
         ScrubMode::None,
         false,
+        &ExportFilter::default(),
         Some(&signing_key),
     )
     .await
@@ -963,32 +2099,17 @@ async fn test_age_multi_recipient_encryption() {
 /// Test export includes HTML escaping for XSS prevention
 #[tokio::test]
 async fn test_export_html_xss_prevention() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
-    use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
-    use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
-    use mouchak_mail_core::model::project::ProjectBmc;
-
-    let slug = mouchak_mail_core::utils::slugify("/test/xss-export");
-    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, &slug, "/test/xss-export")
+    let fixture = tc
+        .fixtures()
+        .project("/test/xss-export")
+        .agents(["sender"])
+        .build()
         .await
-        .expect("Create project");
-
-    let sender_id = AgentBmc::create(
-        &tc.ctx,
-        &tc.mm,
-        AgentForCreate {
-            project_id,
-            name: "sender".into(),
-            program: "test".into(),
-            model: "test".into(),
-            task_description: "test".into(),
-        },
-    )
-    .await
-    .expect("Create sender");
+        .expect("Build fixture");
+    let (project_id, slug) = (fixture.project_id, fixture.project_slug.clone());
+    let sender_id = fixture.agent_id("sender");
 
     // Create message with XSS payload
     let xss_payload = "<script>alert('XSS')</script>";
@@ -1006,6 +2127,9 @@ async fn test_export_html_xss_prevention() {
             thread_id: None,
             importance: None,
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
         },
     )
     .await
@@ -1019,6 +2143,8 @@ async fn test_export_html_xss_prevention() {
         ExportFormat::Html,
         ScrubMode::None,
         false,
+        &ExportFilter::default(),
+        ExportCompression::None,
     )
     .await
     .expect("Export should succeed");
@@ -1037,32 +2163,17 @@ async fn test_export_html_xss_prevention() {
 /// Test export with unicode content
 #[tokio::test]
 async fn test_export_unicode_content() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
-    use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
-    use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
-    use mouchak_mail_core::model::project::ProjectBmc;
-
-    let slug = mouchak_mail_core::utils::slugify("/test/unicode-export");
-    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, &slug, "/test/unicode-export")
+    let fixture = tc
+        .fixtures()
+        .project("/test/unicode-export")
+        .agents(["Sender"]) // Agent names are ASCII; the content is not
+        .build()
         .await
-        .expect("Create project");
-
-    let sender_id = AgentBmc::create(
-        &tc.ctx,
-        &tc.mm,
-        AgentForCreate {
-            project_id,
-            name: "送信者".into(), // Japanese "sender"
-            program: "test".into(),
-            model: "test".into(),
-            task_description: "test".into(),
-        },
-    )
-    .await
-    .expect("Create sender");
+        .expect("Build fixture");
+    let (project_id, slug) = (fixture.project_id, fixture.project_slug.clone());
+    let sender_id = fixture.agent_id("Sender");
 
     // Create message with unicode
     MessageBmc::create(
@@ -1079,6 +2190,9 @@ async fn test_export_unicode_content() {
             thread_id: None,
             importance: None,
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
         },
     )
     .await
@@ -1092,6 +2206,8 @@ async fn test_export_unicode_content() {
         ExportFormat::Json,
         ScrubMode::None,
         false,
+        &ExportFilter::default(),
+        ExportCompression::None,
     )
     .await
     .expect("Export should succeed");
@@ -1159,3 +2275,193 @@ async fn test_export_format_default() {
     let format2 = ExportFormat::from_str("pdf").unwrap();
     assert_eq!(format2, ExportFormat::Json);
 }
+
+/// Test exporting a selection of message ids
+#[tokio::test]
+async fn test_export_selected_messages() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (project_a, slug_a) = setup_project_with_messages(&tc, "selection-a").await;
+    let (project_b, _) = setup_project_with_messages(&tc, "selection-b").await;
+    let recent_a = MessageBmc::list_recent(&tc.ctx, &tc.mm, project_a, 10)
+        .await
+        .unwrap();
+    let recent_b = MessageBmc::list_recent(&tc.ctx, &tc.mm, project_b, 10)
+        .await
+        .unwrap();
+
+    // Single project keeps its slug; only the chosen ids are exported
+    let ids = vec![recent_a[0].id, recent_a[2].id];
+    let exported = ExportBmc::export_messages(
+        &tc.ctx,
+        &tc.mm,
+        &ids,
+        ExportFormat::Markdown,
+        ScrubMode::None,
+    )
+    .await
+    .expect("Failed to export selection");
+    assert_eq!(exported.project_slug, slug_a);
+    assert_eq!(exported.format, "markdown");
+    assert_eq!(exported.message_count, 2);
+    assert!(exported.content.contains(&recent_a[0].subject));
+    assert!(!exported.content.contains(&recent_a[1].subject));
+
+    // Across projects, NDJSON has one line per message; unknown ids are skipped
+    let ids = vec![recent_a[0].id, recent_b[0].id, 999_999];
+    let exported =
+        ExportBmc::export_messages(&tc.ctx, &tc.mm, &ids, ExportFormat::Ndjson, ScrubMode::None)
+            .await
+            .expect("Failed to export selection");
+    assert_eq!(exported.project_slug, SELECTION_EXPORT_SLUG);
+    assert_eq!(exported.message_count, 2);
+    assert_eq!(exported.content.lines().count(), 2);
+
+    // An empty selection is rejected
+    let empty =
+        ExportBmc::export_messages(&tc.ctx, &tc.mm, &[], ExportFormat::Json, ScrubMode::None).await;
+    assert!(matches!(
+        empty,
+        Err(mouchak_mail_core::Error::InvalidInput(_))
+    ));
+
+    // A context limited to project A cannot export project B's messages
+    let scoped = mouchak_mail_core::Ctx::scoped(1, None, vec![slug_a.clone()]);
+    let ids = vec![recent_a[0].id, recent_b[0].id];
+    let denied =
+        ExportBmc::export_messages(&scoped, &tc.mm, &ids, ExportFormat::Json, ScrubMode::None)
+            .await;
+    assert!(matches!(
+        denied,
+        Err(mouchak_mail_core::Error::Forbidden(_))
+    ));
+    let own = ExportBmc::export_messages(
+        &scoped,
+        &tc.mm,
+        &ids[..1],
+        ExportFormat::Json,
+        ScrubMode::None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(own.message_count, 1);
+}
+
+// =============================================================================
+// Compression
+// =============================================================================
+
+#[tokio::test]
+async fn test_export_compression_round_trip() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+    let (_, slug) = setup_project_with_messages(&tc, "compress").await;
+
+    let filter = ExportFilter::default();
+    let export = |compression| {
+        ExportBmc::export_mailbox(
+            &tc.ctx,
+            &tc.mm,
+            &slug,
+            ExportFormat::Json,
+            ScrubMode::None,
+            false,
+            &filter,
+            compression,
+        )
+    };
+    let plain = export(ExportCompression::None).await.unwrap();
+    assert_eq!(plain.original_size, None);
+
+    for (compression, magic) in [
+        (ExportCompression::Gzip, &[0x1f, 0x8b][..]),
+        (ExportCompression::Zstd, &[0x28, 0xb5, 0x2f, 0xfd][..]),
+    ] {
+        let compressed = export(compression).await.unwrap();
+        assert_eq!(compressed.compression, compression);
+        assert_eq!(compressed.original_size, Some(plain.content.len()));
+
+        let bytes = compressed.content_bytes().unwrap();
+        assert!(bytes.starts_with(magic), "{:?} magic bytes", compression);
+
+        let restored = compressed.decompress().unwrap();
+        assert_eq!(restored.compression, ExportCompression::None);
+        assert_eq!(restored.original_size, None);
+        assert_eq!(restored.content, plain.content);
+    }
+}
+
+#[test]
+fn test_export_compression_parse() {
+    assert_eq!(
+        "".parse::<ExportCompression>().unwrap(),
+        ExportCompression::None
+    );
+    assert_eq!(
+        "GZIP".parse::<ExportCompression>().unwrap(),
+        ExportCompression::Gzip
+    );
+    assert_eq!(
+        "zst".parse::<ExportCompression>().unwrap(),
+        ExportCompression::Zstd
+    );
+    assert!("brotli".parse::<ExportCompression>().is_err());
+    assert!(ExportCompression::Gzip.decompress(b"not gzip").is_err());
+}
+
+/// Signatures cover the uncompressed content, so a compressed export
+/// verifies against the same manifest as the original, before and after
+/// decompression and after a JSON round trip.
+#[tokio::test]
+async fn test_signature_independent_of_compression() {
+    use mouchak_mail_core::model::export::{
+        ExportManifest, ExportedMailbox, generate_signing_keypair,
+    };
+
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+    let (_, slug) = setup_project_with_messages(&tc, "compress-sign").await;
+    let (signing_key, _) = generate_signing_keypair();
+
+    for format in [ExportFormat::Markdown, ExportFormat::HtmlZip] {
+        let (exported, manifest) = ExportBmc::export_mailbox_signed(
+            &tc.ctx,
+            &tc.mm,
+            &slug,
+            format,
+            ScrubMode::None,
+            false,
+            &ExportFilter::default(),
+            Some(&signing_key),
+        )
+        .await
+        .unwrap();
+
+        for compression in [ExportCompression::Gzip, ExportCompression::Zstd] {
+            let compressed = exported.clone().compress(compression).unwrap();
+            assert_ne!(compressed.content, exported.content);
+
+            // The manifest of a compressed export is the manifest of its content
+            assert_eq!(
+                ExportManifest::new(&compressed).content_hash,
+                manifest.content_hash
+            );
+            assert!(ExportBmc::verify_export(&compressed, &manifest).unwrap());
+
+            // Over the wire and back, then decompressed by the receiver
+            let received: ExportedMailbox =
+                serde_json::from_str(&serde_json::to_string(&compressed).unwrap()).unwrap();
+            let decompressed = received.decompress().unwrap();
+            assert_eq!(decompressed.content, exported.content);
+            assert!(ExportBmc::verify_export(&decompressed, &manifest).unwrap());
+
+            // Tampering with the content is still caught (valid base64, so
+            // html-zip content still decodes)
+            let tampered = ExportedMailbox {
+                content: "dGFtcGVyZWQ=".to_string(),
+                ..decompressed
+            }
+            .compress(compression)
+            .unwrap();
+            assert!(!ExportBmc::verify_export(&tampered, &manifest).unwrap());
+        }
+    }
+}
@@ -9,47 +9,31 @@
     clippy::inefficient_to_string
 )]
 
-#[path = "common/mod.rs"]
-mod common;
-
-use crate::common::TestContext;
 use chrono::{Duration, Utc};
+use mouchak_mail_core::Error;
 use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
 use mouchak_mail_core::model::file_reservation::{FileReservationBmc, FileReservationForCreate};
-use mouchak_mail_core::model::project::ProjectBmc;
+use mouchak_mail_core::testing::TestEnv;
 use mouchak_mail_core::types::{AgentId, ProjectId};
 use mouchak_mail_core::utils::slugify;
 
 /// Helper to set up a project and agent for file reservation tests
-async fn setup_project_and_agent(tc: &TestContext) -> (ProjectId, i64) {
-    let human_key = "/test/repo";
-    let slug = slugify(human_key);
-
-    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, &slug, human_key)
-        .await
-        .expect("Failed to create project");
-
-    let agent = AgentForCreate {
-        project_id,
-        name: "test-agent".to_string(),
-        program: "claude-code".to_string(),
-        model: "claude-3".to_string(),
-        task_description: "Testing file reservations".to_string(),
-    };
-
-    let agent_id = AgentBmc::create(&tc.ctx, &tc.mm, agent)
+async fn setup_project_and_agent(tc: &TestEnv) -> (ProjectId, i64) {
+    let fixture = tc
+        .fixtures()
+        .project("/test/repo")
+        .agents(["test-agent"])
+        .build()
         .await
-        .expect("Failed to create agent");
+        .expect("Failed to build fixture");
 
-    (project_id, agent_id.into())
+    (fixture.project_id, fixture.agent_id("test-agent").into())
 }
 
 /// Test creating a file reservation
 #[tokio::test]
 async fn test_create_file_reservation() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     let (project_id, agent_id) = setup_project_and_agent(&tc).await;
 
@@ -73,9 +57,7 @@ async fn test_create_file_reservation() {
 /// Test getting a file reservation by ID
 #[tokio::test]
 async fn test_get_file_reservation() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     let (project_id, agent_id) = setup_project_and_agent(&tc).await;
 
@@ -109,9 +91,7 @@ async fn test_get_file_reservation() {
 /// Test listing active file reservations for a project
 #[tokio::test]
 async fn test_list_active_file_reservations() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     let (project_id, agent_id) = setup_project_and_agent(&tc).await;
 
@@ -141,9 +121,7 @@ async fn test_list_active_file_reservations() {
 /// Test releasing a file reservation
 #[tokio::test]
 async fn test_release_file_reservation() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     let (project_id, agent_id) = setup_project_and_agent(&tc).await;
 
@@ -191,9 +169,7 @@ async fn test_release_file_reservation() {
 /// Test releasing a file reservation by path
 #[tokio::test]
 async fn test_release_by_path() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     let (project_id, agent_id) = setup_project_and_agent(&tc).await;
 
@@ -230,12 +206,76 @@ async fn test_release_by_path() {
     );
 }
 
+/// Test listing an agent's active reservations skips released, expired, and other agents' locks
+#[tokio::test]
+async fn test_list_active_for_agent() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (project_id, agent_id) = setup_project_and_agent(&tc).await;
+
+    let other = AgentForCreate {
+        project_id,
+        name: "other-agent".to_string(),
+        program: "claude-code".to_string(),
+        model: "claude-3".to_string(),
+        task_description: "Holding other locks".to_string(),
+    };
+    let other_id = AgentBmc::create(&tc.ctx, &tc.mm, other)
+        .await
+        .expect("Failed to create agent");
+
+    let now = Utc::now().naive_utc();
+    let reservations = [
+        (AgentId(agent_id), "src/active.rs", now + Duration::hours(1)),
+        (
+            AgentId(agent_id),
+            "src/released.rs",
+            now + Duration::hours(1),
+        ),
+        (
+            AgentId(agent_id),
+            "src/expired.rs",
+            now - Duration::hours(1),
+        ),
+        (other_id, "src/other.rs", now + Duration::hours(1)),
+    ];
+    for (owner, path, expires_ts) in reservations {
+        let fr_c = FileReservationForCreate {
+            project_id,
+            agent_id: owner,
+            path_pattern: path.to_string(),
+            exclusive: true,
+            reason: "Testing".to_string(),
+            expires_ts,
+        };
+        FileReservationBmc::create(&tc.ctx, &tc.mm, fr_c)
+            .await
+            .expect("Failed to create reservation");
+    }
+
+    FileReservationBmc::release_by_path(
+        &tc.ctx,
+        &tc.mm,
+        project_id.get(),
+        agent_id,
+        "src/released.rs",
+    )
+    .await
+    .expect("Failed to release by path");
+
+    let active =
+        FileReservationBmc::list_active_for_agent(&tc.ctx, &tc.mm, project_id, AgentId(agent_id))
+            .await
+            .expect("Failed to list agent reservations");
+
+    let paths: Vec<&str> = active.iter().map(|r| r.path_pattern.as_str()).collect();
+    assert_eq!(paths, vec!["src/active.rs"]);
+}
+
 /// Test force releasing a file reservation
 #[tokio::test]
 async fn test_force_release() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     let (project_id, agent_id) = setup_project_and_agent(&tc).await;
 
@@ -272,9 +312,7 @@ async fn test_force_release() {
 /// Test renewing a file reservation
 #[tokio::test]
 async fn test_renew_file_reservation() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     let (project_id, agent_id) = setup_project_and_agent(&tc).await;
 
@@ -310,12 +348,140 @@ async fn test_renew_file_reservation() {
     );
 }
 
+/// Test renew_active counts the new expiry from now rather than stacking
+#[tokio::test]
+async fn test_renew_active_counts_from_now() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (project_id, agent_id) = setup_project_and_agent(&tc).await;
+
+    let fr_c = FileReservationForCreate {
+        project_id,
+        agent_id: AgentId(agent_id),
+        path_pattern: "src/renew.rs".to_string(),
+        exclusive: true,
+        reason: "Renewal".to_string(),
+        expires_ts: Utc::now().naive_utc() + Duration::hours(2),
+    };
+    FileReservationBmc::create(&tc.ctx, &tc.mm, fr_c)
+        .await
+        .expect("Failed to create reservation");
+
+    let found = FileReservationBmc::get_latest_by_path(
+        &tc.ctx,
+        &tc.mm,
+        project_id,
+        AgentId(agent_id),
+        "src/renew.rs",
+    )
+    .await
+    .expect("Failed to look up reservation")
+    .expect("Reservation should exist");
+
+    let renewed = FileReservationBmc::renew_active(&tc.ctx, &tc.mm, found.id, 600)
+        .await
+        .expect("Failed to renew reservation");
+
+    // 10 minutes from now is earlier than the original 2 hour expiry
+    assert!(renewed.expires_ts < found.expires_ts);
+    assert!(renewed.expires_ts > Utc::now().naive_utc() + Duration::seconds(590));
+
+    let stored = FileReservationBmc::get(&tc.ctx, &tc.mm, found.id)
+        .await
+        .expect("Failed to get reservation");
+    assert_eq!(
+        stored.expires_ts.and_utc().timestamp(),
+        renewed.expires_ts.and_utc().timestamp()
+    );
+}
+
+/// Test renew_active rejects released and expired reservations
+#[tokio::test]
+async fn test_renew_active_rejects_inactive() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (project_id, agent_id) = setup_project_and_agent(&tc).await;
+    let now = Utc::now().naive_utc();
+
+    let released_id = FileReservationBmc::create(
+        &tc.ctx,
+        &tc.mm,
+        FileReservationForCreate {
+            project_id,
+            agent_id: AgentId(agent_id),
+            path_pattern: "src/released.rs".to_string(),
+            exclusive: true,
+            reason: "Released".to_string(),
+            expires_ts: now + Duration::hours(1),
+        },
+    )
+    .await
+    .expect("Failed to create reservation");
+    FileReservationBmc::release(&tc.ctx, &tc.mm, released_id)
+        .await
+        .expect("Failed to release reservation");
+
+    let expired_id = FileReservationBmc::create(
+        &tc.ctx,
+        &tc.mm,
+        FileReservationForCreate {
+            project_id,
+            agent_id: AgentId(agent_id),
+            path_pattern: "src/expired.rs".to_string(),
+            exclusive: true,
+            reason: "Expired".to_string(),
+            expires_ts: now - Duration::hours(1),
+        },
+    )
+    .await
+    .expect("Failed to create reservation");
+
+    for id in [released_id, expired_id] {
+        let result = FileReservationBmc::renew_active(&tc.ctx, &tc.mm, id, 600).await;
+        assert!(
+            matches!(result, Err(Error::FileReservationInactive(_))),
+            "Inactive reservation {} must not be renewed",
+            id
+        );
+    }
+}
+
+/// Test renew_active enforces the configured max TTL
+#[tokio::test]
+async fn test_renew_active_ttl_cap() {
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    let (project_id, agent_id) = setup_project_and_agent(&tc).await;
+
+    let reservation_id = FileReservationBmc::create(
+        &tc.ctx,
+        &tc.mm,
+        FileReservationForCreate {
+            project_id,
+            agent_id: AgentId(agent_id),
+            path_pattern: "src/capped.rs".to_string(),
+            exclusive: true,
+            reason: "Capped".to_string(),
+            expires_ts: Utc::now().naive_utc() + Duration::hours(1),
+        },
+    )
+    .await
+    .expect("Failed to create reservation");
+
+    let max_ttl = tc.mm.app_config.reservations.max_ttl_seconds as i64;
+    let result =
+        FileReservationBmc::renew_active(&tc.ctx, &tc.mm, reservation_id, max_ttl + 1).await;
+    assert!(matches!(result, Err(Error::Validation(_))));
+
+    FileReservationBmc::renew_active(&tc.ctx, &tc.mm, reservation_id, max_ttl)
+        .await
+        .expect("Renewal at the cap should succeed");
+}
+
 /// Test listing all reservations (including released)
 #[tokio::test]
 async fn test_list_all_for_project() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     let (project_id, agent_id) = setup_project_and_agent(&tc).await;
 
@@ -372,9 +538,7 @@ async fn test_list_all_for_project() {
 /// Test file reservation not found error
 #[tokio::test]
 async fn test_file_reservation_not_found() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     let result = FileReservationBmc::get(&tc.ctx, &tc.mm, 99999).await;
 
@@ -387,75 +551,26 @@ async fn test_file_reservation_not_found() {
 /// Test listing all active reservations across all projects
 #[tokio::test]
 async fn test_list_all_active() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
-
-    // Create first project with reservations
-    let human_key1 = "/test/project1";
-    let slug1 = slugify(human_key1);
-    let project1_id = ProjectBmc::create(&tc.ctx, &tc.mm, &slug1, human_key1)
-        .await
-        .expect("Failed to create project 1");
-
-    let agent1 = AgentForCreate {
-        project_id: project1_id,
-        name: "agent-one".to_string(),
-        program: "claude".to_string(),
-        model: "claude-3".to_string(),
-        task_description: "Agent one".to_string(),
-    };
-    let agent1_id: i64 = AgentBmc::create(&tc.ctx, &tc.mm, agent1)
-        .await
-        .expect("Failed to create agent 1")
-        .into();
-
-    // Create second project with reservations
-    let human_key2 = "/test/project2";
-    let slug2 = slugify(human_key2);
-    let project2_id = ProjectBmc::create(&tc.ctx, &tc.mm, &slug2, human_key2)
-        .await
-        .expect("Failed to create project 2");
-
-    let agent2 = AgentForCreate {
-        project_id: project2_id,
-        name: "agent-two".to_string(),
-        program: "claude".to_string(),
-        model: "claude-3".to_string(),
-        task_description: "Agent two".to_string(),
-    };
-    let agent2_id: i64 = AgentBmc::create(&tc.ctx, &tc.mm, agent2)
-        .await
-        .expect("Failed to create agent 2")
-        .into();
-
-    let expires_ts = Utc::now().naive_utc() + Duration::hours(1);
-
-    // Create reservations in project 1
-    let fr1 = FileReservationForCreate {
-        project_id: project1_id,
-        agent_id: AgentId(agent1_id),
-        path_pattern: "src/*.rs".to_string(),
-        exclusive: true,
-        reason: "Project 1 work".to_string(),
-        expires_ts,
-    };
-    FileReservationBmc::create(&tc.ctx, &tc.mm, fr1)
-        .await
-        .expect("Failed to create reservation 1");
-
-    // Create reservations in project 2
-    let fr2 = FileReservationForCreate {
-        project_id: project2_id,
-        agent_id: AgentId(agent2_id),
-        path_pattern: "lib/*.rs".to_string(),
-        exclusive: true,
-        reason: "Project 2 work".to_string(),
-        expires_ts,
-    };
-    FileReservationBmc::create(&tc.ctx, &tc.mm, fr2)
-        .await
-        .expect("Failed to create reservation 2");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
+
+    // One project per agent, each holding one reservation
+    let project1 = tc
+        .fixtures()
+        .project("/test/project1")
+        .agents(["agent-one"])
+        .reservations(["src/*.rs"])
+        .build()
+        .await
+        .expect("Failed to build project 1");
+    let project2 = tc
+        .fixtures()
+        .project("/test/project2")
+        .agents(["agent-two"])
+        .reservations(["lib/*.rs"])
+        .build()
+        .await
+        .expect("Failed to build project 2");
+    let (project1_id, project2_id) = (project1.project_id, project2.project_id);
 
     // List all active across all projects
     let all_active = FileReservationBmc::list_all_active(&tc.ctx, &tc.mm)
@@ -489,7 +604,7 @@ use serial_test::serial;
 use temp_env::async_with_vars;
 
 /// Helper to create a second agent for conflict testing
-async fn create_second_agent(tc: &TestContext, project_id: ProjectId) -> i64 {
+async fn create_second_agent(tc: &TestEnv, project_id: ProjectId) -> i64 {
     let agent = AgentForCreate {
         project_id,
         name: "other-agent".to_string(),
@@ -507,9 +622,7 @@ async fn create_second_agent(tc: &TestContext, project_id: ProjectId) -> i64 {
 #[tokio::test]
 #[serial]
 async fn test_guard_detects_reservation_conflict() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     // Create project and two agents
     let (project_id, agent1_id) = setup_project_and_agent(&tc).await;
@@ -570,9 +683,7 @@ async fn test_guard_detects_reservation_conflict() {
 #[tokio::test]
 #[serial]
 async fn test_guard_allows_own_reservations() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     let (project_id, agent_id) = setup_project_and_agent(&tc).await;
 
@@ -623,9 +734,7 @@ async fn test_guard_allows_own_reservations() {
 #[tokio::test]
 #[serial]
 async fn test_guard_ignores_expired_reservations() {
-    let tc = TestContext::new()
-        .await
-        .expect("Failed to create test context");
+    let tc = TestEnv::new().await.expect("Failed to create test context");
 
     let (project_id, agent1_id) = setup_project_and_agent(&tc).await;
     let _agent2_id = create_second_agent(&tc, project_id).await;
@@ -0,0 +1,113 @@
+//! Fixture builder tests
+//!
+//! Tests for the `testing` module shared with downstream crates' tests.
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use mouchak_mail_core::Error;
+use mouchak_mail_core::model::file_reservation::FileReservationBmc;
+use mouchak_mail_core::model::message::{MessageBmc, UnifiedInboxFilter};
+use mouchak_mail_core::testing::{DEFAULT_HUMAN_KEY, TestEnv};
+use mouchak_mail_core::utils::slugify;
+
+/// Test the builder creates everything it was asked for, deterministically
+#[tokio::test]
+async fn test_fixture_builder_populates_project() {
+    let env = TestEnv::new().await.expect("Failed to create test env");
+
+    let fixture = env
+        .fixtures()
+        .project("/test/fixture-builder")
+        .agents(3)
+        .messages(2, Some("TASK-7"), Some("high"))
+        .reservations(["src/**", "docs/*.md"])
+        .build()
+        .await
+        .expect("Failed to build fixture");
+
+    assert_eq!(fixture.project_slug, slugify("/test/fixture-builder"));
+    assert_eq!(fixture.agent_names(), ["agent-1", "agent-2", "agent-3"]);
+    assert_eq!(fixture.sender().map(|(name, _)| name), Some("agent-1"));
+
+    assert_eq!(fixture.message_ids.len(), 2);
+    for (i, id) in fixture.message_ids.iter().enumerate() {
+        let msg = MessageBmc::get(&env.ctx, &env.mm, *id).await.unwrap();
+        assert_eq!(msg.subject, format!("Test Message {}", i + 1));
+        assert_eq!(msg.thread_id.as_deref(), Some("TASK-7"));
+        assert_eq!(msg.importance, "high");
+        assert_eq!(msg.sender_id, fixture.agent_id("agent-1").get());
+    }
+
+    let active = FileReservationBmc::list_active_for_project(&env.ctx, &env.mm, fixture.project_id)
+        .await
+        .unwrap();
+    assert_eq!(active.len(), 2);
+    assert!(
+        active
+            .iter()
+            .all(|r| r.agent_id == fixture.agent_id("agent-1") && r.exclusive)
+    );
+}
+
+/// Test defaults and the agent requirement for messages
+#[tokio::test]
+async fn test_fixture_builder_defaults_and_validation() {
+    let env = TestEnv::new().await.expect("Failed to create test env");
+
+    let fixture = env
+        .fixtures()
+        .build()
+        .await
+        .expect("Failed to build fixture");
+    assert_eq!(fixture.human_key, DEFAULT_HUMAN_KEY);
+    assert!(fixture.agents.is_empty());
+    assert!(fixture.sender().is_none());
+
+    let result = env
+        .fixtures()
+        .project("/test/no-agents")
+        .messages(1, None, None)
+        .build()
+        .await;
+    assert!(matches!(result, Err(Error::InvalidInput(_))));
+}
+
+/// Test seeded messages rotate senders and importance, newest last
+#[tokio::test]
+async fn test_seed_messages() {
+    let env = TestEnv::new().await.expect("Failed to create test env");
+    let fixture = env
+        .fixtures()
+        .project("/test/seeded")
+        .agents(2)
+        .build()
+        .await
+        .expect("Failed to build fixture");
+
+    env.seed_messages(&fixture, 12).await.unwrap();
+
+    let filter = UnifiedInboxFilter {
+        limit: 20,
+        ..Default::default()
+    };
+    let items = MessageBmc::list_unified_inbox_filtered(&env.ctx, &env.mm, &filter)
+        .await
+        .unwrap();
+    assert_eq!(items.len(), 12);
+    assert_eq!(items[0].subject, "Seeded message 12");
+    assert_eq!(items[0].sender_name, "agent-2");
+    assert_eq!(items[1].sender_name, "agent-1");
+    assert!(items.iter().any(|m| m.importance == "urgent"));
+    assert!(items.iter().any(|m| m.ack_required));
+    assert!(items.windows(2).all(|w| w[0].created_ts > w[1].created_ts));
+
+    let empty = env
+        .fixtures()
+        .project("/test/seeded-empty")
+        .build()
+        .await
+        .unwrap();
+    let result = env.seed_messages(&empty, 1).await;
+    assert!(matches!(result, Err(Error::InvalidInput(_))));
+}
@@ -0,0 +1,184 @@
+//! Message forwarding tests
+//!
+//! A forward is a new message quoting the original, linked back to it by
+//! `forwarded_from_id`.
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use crate::common::TestContext;
+use mouchak_mail_core::Error;
+use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
+use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
+use mouchak_mail_core::model::project::ProjectBmc;
+
+mod common;
+
+struct Fixture {
+    project_id: i64,
+    alice: i64,
+    bob: i64,
+    carol: i64,
+    dave: i64,
+    erin: i64,
+}
+
+async fn setup(tc: &TestContext, slug: &str) -> Fixture {
+    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, slug, &format!("/forward/{}", slug))
+        .await
+        .unwrap();
+
+    let mut ids = Vec::new();
+    for name in ["Alice", "Bob", "Carol", "Dave", "Erin"] {
+        let agent_c = AgentForCreate {
+            project_id,
+            name: name.to_string(),
+            program: "test".to_string(),
+            model: "test".to_string(),
+            task_description: String::new(),
+        };
+        ids.push(
+            AgentBmc::create(&tc.ctx, &tc.mm, agent_c)
+                .await
+                .unwrap()
+                .get(),
+        );
+    }
+
+    Fixture {
+        project_id: project_id.get(),
+        alice: ids[0],
+        bob: ids[1],
+        carol: ids[2],
+        dave: ids[3],
+        erin: ids[4],
+    }
+}
+
+/// Alice sends to Bob, cc Carol, bcc Dave
+async fn send_original(tc: &TestContext, fx: &Fixture) -> i64 {
+    let msg_c = MessageForCreate {
+        project_id: fx.project_id,
+        sender_id: fx.alice,
+        recipient_ids: vec![fx.bob],
+        cc_ids: Some(vec![fx.carol]),
+        bcc_ids: Some(vec![fx.dave]),
+        subject: "Schema change".to_string(),
+        body_md: "Dropping the legacy table.\n\nShout if you still use it.".to_string(),
+        thread_id: Some("SCHEMA-1".to_string()),
+        importance: Some("high".to_string()),
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
+    };
+    MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap()
+}
+
+/// Test a forward quotes the original and links back through a double forward
+#[tokio::test]
+async fn test_forward_provenance_chain() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "forward-chain").await;
+    let original_id = send_original(&tc, &fx).await;
+
+    let first_id = MessageBmc::forward(
+        &tc.ctx,
+        &tc.mm,
+        original_id,
+        fx.bob,
+        vec![fx.erin],
+        Some("FYI, this affects your migration"),
+    )
+    .await
+    .unwrap();
+    let first = MessageBmc::get(&tc.ctx, &tc.mm, first_id).await.unwrap();
+    assert_eq!(first.sender_id, fx.bob);
+    assert_eq!(first.subject, "Fwd: Schema change");
+    assert_eq!(first.forwarded_from_id, Some(original_id));
+    assert_ne!(first.thread_id.as_deref(), Some("SCHEMA-1"));
+    assert!(
+        first
+            .body_md
+            .starts_with("FYI, this affects your migration\n\n")
+    );
+    assert!(first.body_md.contains("> **From:** Alice\n"));
+    assert!(first.body_md.contains("> **To:** Bob\n"));
+    assert!(first.body_md.contains("> **Cc:** Carol\n"));
+    assert!(
+        first
+            .body_md
+            .contains("> Dropping the legacy table.\n>\n> Shout")
+    );
+    assert_eq!(
+        MessageBmc::get_recipients(&tc.ctx, &tc.mm, first_id)
+            .await
+            .unwrap(),
+        ["Erin"]
+    );
+
+    // Erin forwards the forward: the quote nests and the chain leads back
+    let second_id = MessageBmc::forward(&tc.ctx, &tc.mm, first_id, fx.erin, vec![fx.alice], None)
+        .await
+        .unwrap();
+    let second = MessageBmc::get(&tc.ctx, &tc.mm, second_id).await.unwrap();
+    assert_eq!(second.subject, "Fwd: Schema change");
+    assert!(second.body_md.starts_with("> ---------- Forwarded message"));
+    assert!(second.body_md.contains("> **From:** Bob\n"));
+    assert!(second.body_md.contains("> > **From:** Alice\n"));
+
+    let mut chain = vec![second_id];
+    let mut current = second;
+    while let Some(from) = current.forwarded_from_id {
+        chain.push(from);
+        current = MessageBmc::get(&tc.ctx, &tc.mm, from).await.unwrap();
+    }
+    assert_eq!(chain, [second_id, first_id, original_id]);
+
+    // The forward shows up in the recipient's inbox with its link
+    let inbox = MessageBmc::list_inbox_for_agent(&tc.ctx, &tc.mm, fx.project_id, fx.alice, 10)
+        .await
+        .unwrap();
+    let delivered = inbox.iter().find(|m| m.id == second_id).unwrap();
+    assert_eq!(delivered.forwarded_from_id, Some(first_id));
+}
+
+/// Test forwarding never reveals BCC recipients, even when a BCC forwards
+#[tokio::test]
+async fn test_forward_hides_bcc() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "forward-bcc").await;
+    let original_id = send_original(&tc, &fx).await;
+
+    for forwarder in [fx.alice, fx.dave] {
+        let id = MessageBmc::forward(&tc.ctx, &tc.mm, original_id, forwarder, vec![fx.erin], None)
+            .await
+            .unwrap();
+        let forward = MessageBmc::get(&tc.ctx, &tc.mm, id).await.unwrap();
+        assert!(!forward.body_md.contains("Dave"), "{}", forward.body_md);
+        assert!(!forward.body_md.to_lowercase().contains("bcc"));
+    }
+}
+
+/// Test recalled messages and outsiders can't forward
+#[tokio::test]
+async fn test_forward_rejections() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "forward-reject").await;
+    let original_id = send_original(&tc, &fx).await;
+
+    // Erin never received the message
+    let result =
+        MessageBmc::forward(&tc.ctx, &tc.mm, original_id, fx.erin, vec![fx.bob], None).await;
+    assert!(matches!(result, Err(Error::MessageNotFound(id)) if id == original_id));
+
+    let result = MessageBmc::forward(&tc.ctx, &tc.mm, original_id, fx.bob, vec![], None).await;
+    assert!(matches!(result, Err(Error::InvalidInput(_))));
+
+    MessageBmc::recall(&tc.ctx, &tc.mm, original_id, fx.alice, "Wrong table")
+        .await
+        .unwrap();
+    let result =
+        MessageBmc::forward(&tc.ctx, &tc.mm, original_id, fx.bob, vec![fx.erin], None).await;
+    assert!(matches!(result, Err(Error::MessageRecalled(id)) if id == original_id));
+}
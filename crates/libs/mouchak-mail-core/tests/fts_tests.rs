@@ -12,6 +12,7 @@ use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
 use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
 use mouchak_mail_core::model::project::ProjectBmc;
 use mouchak_mail_core::types::ProjectId;
+use mouchak_mail_core::utils::search_query::SearchQuery;
 use serial_test::serial;
 
 // --- Test Setup Helper ---
@@ -74,6 +75,9 @@ async fn test_fts_wildcard_search() -> Result<()> {
             thread_id: None,
             importance: None,
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
         },
     )
     .await?;
@@ -118,3 +122,171 @@ async fn test_fts_malformed_query_graceful() -> Result<()> {
 
     Ok(())
 }
+
+async fn send(
+    mm: &ModelManager,
+    p_id: ProjectId,
+    sender_id: i64,
+    subject: &str,
+    body: &str,
+    importance: Option<&str>,
+    thread_id: Option<&str>,
+) -> i64 {
+    MessageBmc::create(
+        &Ctx::root_ctx(),
+        mm,
+        MessageForCreate {
+            project_id: p_id.into(),
+            sender_id,
+            recipient_ids: vec![],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: subject.to_string(),
+            body_md: body.to_string(),
+            thread_id: thread_id.map(str::to_string),
+            importance: importance.map(str::to_string),
+            ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: true,
+        },
+    )
+    .await
+    .unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn test_search_field_filters() -> Result<()> {
+    let mm = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+    let (p_id, blue) = setup_project_and_agent(&ctx, &mm, "fields").await;
+    let red: i64 = AgentBmc::create(
+        &ctx,
+        &mm,
+        AgentForCreate {
+            project_id: p_id,
+            name: "RedFox".into(),
+            program: "test".into(),
+            model: "test".into(),
+            task_description: "test".into(),
+        },
+    )
+    .await
+    .unwrap()
+    .into();
+
+    let deploy = send(
+        &mm,
+        p_id,
+        blue,
+        "Deploy release plan",
+        "urgent rollout",
+        Some("urgent"),
+        Some("T-1"),
+    )
+    .await;
+    send(
+        &mm,
+        p_id,
+        blue,
+        "Weekly notes",
+        "urgent rollout",
+        None,
+        None,
+    )
+    .await;
+    let red_deploy = send(
+        &mm,
+        p_id,
+        red,
+        "Deploy hotfix",
+        "calm rollout",
+        None,
+        Some("T-2"),
+    )
+    .await;
+
+    let ids = |query: &str| {
+        let mm = &mm;
+        let ctx = &ctx;
+        let query = SearchQuery::parse(query);
+        async move {
+            MessageBmc::search_page(ctx, mm, p_id.into(), &query, 10, None)
+                .await
+                .unwrap()
+                .items
+                .into_iter()
+                .map(|m| m.id)
+                .collect::<Vec<_>>()
+        }
+    };
+
+    assert_eq!(
+        ids("from:agent-fields subject:deploy urgent").await,
+        vec![deploy]
+    );
+    assert_eq!(ids("from:REDFOX").await, vec![red_deploy]);
+    assert_eq!(
+        ids(r#"subject:"release plan""#).await,
+        vec![deploy],
+        "quoted subject matches as one phrase"
+    );
+    assert_eq!(ids("subject:deploy").await, vec![red_deploy, deploy]);
+    assert_eq!(ids("importance:URGENT").await, vec![deploy]);
+    assert_eq!(ids("thread:T-2 rollout").await, vec![red_deploy]);
+    assert_eq!(ids("after:2000-01-01 subject:deploy").await.len(), 2);
+    assert!(ids("before:2000-01-01 subject:deploy").await.is_empty());
+    // Unknown fields are body terms, so nothing matches rather than erroring
+    assert!(ids("label:ops").await.is_empty());
+
+    Ok(())
+}
+
+#[tokio::test]
+#[serial]
+async fn test_search_page_cursor() -> Result<()> {
+    let mm = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+    let (p_id, a_id) = setup_project_and_agent(&ctx, &mm, "pages").await;
+
+    let mut sent = Vec::new();
+    for i in 0..5 {
+        sent.push(
+            send(
+                &mm,
+                p_id,
+                a_id,
+                &format!("Status {}", i),
+                "paged body",
+                None,
+                None,
+            )
+            .await,
+        );
+    }
+    sent.reverse();
+
+    let query = SearchQuery::parse("paged");
+    let mut seen = Vec::new();
+    let mut cursor = None;
+    loop {
+        let page = MessageBmc::search_page(&ctx, &mm, p_id.into(), &query, 2, cursor).await?;
+        assert_eq!(page.total_estimate, 5);
+        seen.extend(page.items.iter().map(|m| m.id));
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(seen, sent, "pages cover every match once, newest first");
+
+    // A blocklisted query with no fields matches nothing
+    let empty =
+        MessageBmc::search_page(&ctx, &mm, p_id.into(), &SearchQuery::parse("*"), 2, None).await?;
+    assert!(empty.items.is_empty());
+    assert_eq!(empty.total_estimate, 0);
+    assert_eq!(empty.next_cursor, None);
+
+    Ok(())
+}
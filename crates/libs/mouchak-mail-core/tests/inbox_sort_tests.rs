@@ -0,0 +1,241 @@
+//! Inbox sort order tests
+//!
+//! Every sort is checked on a fixture with ties on both timestamp and
+//! importance, listed whole and paged two at a time.
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use crate::common::TestContext;
+use mouchak_mail_core::Error;
+use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
+use mouchak_mail_core::model::message::{
+    InboxCursor, InboxQuery, InboxSort, MessageBmc, MessageForCreate, UnifiedInboxFilter,
+};
+use mouchak_mail_core::model::project::ProjectBmc;
+
+mod common;
+
+struct Fixture {
+    project_id: i64,
+    bob: i64,
+    /// Message IDs in creation order
+    ids: Vec<i64>,
+}
+
+/// Alice sends Bob six messages:
+///
+/// | # | importance | ack      | created             |
+/// |---|------------|----------|---------------------|
+/// | 0 | low        |          | 2026-03-01 07:00:00 |
+/// | 1 | urgent     | acked    | 2026-03-01 08:00:00 |
+/// | 2 | high       | pending  | 2026-03-01 09:00:00 |
+/// | 3 | high       |          | 2026-03-01 09:00:00 |
+/// | 4 | normal     | pending  | 2026-03-01 10:00:00 |
+/// | 5 | normal     |          | 2026-03-01 10:00:00 |
+async fn setup(tc: &TestContext) -> Fixture {
+    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, "inbox-sort", "/inbox/sort")
+        .await
+        .unwrap();
+
+    let mut agents = Vec::new();
+    for name in ["Alice", "Bob"] {
+        let agent_c = AgentForCreate {
+            project_id,
+            name: name.to_string(),
+            program: "test".to_string(),
+            model: "test".to_string(),
+            task_description: String::new(),
+        };
+        agents.push(
+            AgentBmc::create(&tc.ctx, &tc.mm, agent_c)
+                .await
+                .unwrap()
+                .get(),
+        );
+    }
+    let (alice, bob) = (agents[0], agents[1]);
+
+    let rows = [
+        ("low", false, "2026-03-01 07:00:00"),
+        ("urgent", true, "2026-03-01 08:00:00"),
+        ("high", true, "2026-03-01 09:00:00"),
+        ("high", false, "2026-03-01 09:00:00"),
+        ("normal", true, "2026-03-01 10:00:00"),
+        ("normal", false, "2026-03-01 10:00:00"),
+    ];
+    let db = tc.mm.db_for_test();
+    let mut ids = Vec::new();
+    for (i, (importance, ack_required, created_ts)) in rows.into_iter().enumerate() {
+        let msg_c = MessageForCreate {
+            project_id: project_id.get(),
+            sender_id: alice,
+            recipient_ids: vec![bob],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: format!("Message {}", i),
+            body_md: "Body".to_string(),
+            thread_id: None,
+            importance: Some(importance.to_string()),
+            ack_required,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
+        };
+        let id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
+        db.execute(
+            "UPDATE messages SET created_ts = ? WHERE id = ?",
+            (created_ts, id),
+        )
+        .await
+        .unwrap();
+        ids.push(id);
+    }
+    MessageBmc::acknowledge(&tc.ctx, &tc.mm, ids[1], bob)
+        .await
+        .unwrap();
+
+    Fixture {
+        project_id: project_id.get(),
+        bob,
+        ids,
+    }
+}
+
+/// Fixture indexes in the expected order for each sort
+const EXPECTED: [(InboxSort, [usize; 6]); 4] = [
+    (InboxSort::CreatedDesc, [5, 4, 3, 2, 1, 0]),
+    (InboxSort::CreatedAsc, [0, 1, 2, 3, 4, 5]),
+    (InboxSort::ImportanceDesc, [1, 3, 2, 5, 4, 0]),
+    (InboxSort::AckPendingFirst, [4, 2, 5, 3, 1, 0]),
+];
+
+/// List Bob's inbox in `sort` order, `limit` at a time, following cursors
+async fn bob_inbox(tc: &TestContext, fx: &Fixture, sort: InboxSort, limit: i64) -> Vec<i64> {
+    let mut query = InboxQuery {
+        sort,
+        limit,
+        ..Default::default()
+    };
+    let mut seen = Vec::new();
+    loop {
+        let page =
+            MessageBmc::list_inbox_for_agent_sorted(&tc.ctx, &tc.mm, fx.project_id, fx.bob, &query)
+                .await
+                .unwrap();
+        let Some(last) = page.last() else {
+            return seen;
+        };
+        query.cursor = Some(InboxCursor::new(sort, last.id));
+        seen.extend(page.iter().map(|m| m.id));
+    }
+}
+
+/// Test each sort order for an agent's inbox, whole and paged
+#[tokio::test]
+async fn test_agent_inbox_sort_orders() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc).await;
+
+    for (sort, order) in EXPECTED {
+        let expected: Vec<i64> = order.iter().map(|&i| fx.ids[i]).collect();
+        assert_eq!(bob_inbox(&tc, &fx, sort, 50).await, expected, "{}", sort);
+        assert_eq!(
+            bob_inbox(&tc, &fx, sort, 2).await,
+            expected,
+            "{} paged",
+            sort
+        );
+    }
+
+    // The default is newest first
+    let inbox = MessageBmc::list_inbox_for_agent(&tc.ctx, &tc.mm, fx.project_id, fx.bob, 50)
+        .await
+        .unwrap();
+    let ids: Vec<i64> = inbox.iter().map(|m| m.id).collect();
+    assert_eq!(ids, bob_inbox(&tc, &fx, InboxSort::CreatedDesc, 50).await);
+}
+
+/// Test each sort order for the unified inbox, paged across ties
+#[tokio::test]
+async fn test_unified_inbox_sort_orders() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc).await;
+
+    for (sort, order) in EXPECTED {
+        let mut filter = UnifiedInboxFilter {
+            sort,
+            limit: 2,
+            ..Default::default()
+        };
+        let mut seen = Vec::new();
+        loop {
+            let page = MessageBmc::list_unified_inbox_filtered(&tc.ctx, &tc.mm, &filter)
+                .await
+                .unwrap();
+            let Some(last) = page.last() else {
+                break;
+            };
+            filter.cursor = Some(InboxCursor::new(sort, last.id));
+            seen.extend(page.iter().map(|m| m.id));
+        }
+        let expected: Vec<i64> = order.iter().map(|&i| fx.ids[i]).collect();
+        assert_eq!(seen, expected, "{}", sort);
+    }
+}
+
+/// Test a cursor from one sort is rejected by another
+#[tokio::test]
+async fn test_cursor_from_other_sort_rejected() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc).await;
+
+    let query = InboxQuery {
+        sort: InboxSort::ImportanceDesc,
+        cursor: Some(InboxCursor::new(InboxSort::CreatedDesc, fx.ids[3])),
+        ..Default::default()
+    };
+    let result =
+        MessageBmc::list_inbox_for_agent_sorted(&tc.ctx, &tc.mm, fx.project_id, fx.bob, &query)
+            .await;
+    assert!(matches!(result, Err(Error::InvalidInput(ref m)) if m.contains("created_desc")));
+
+    let filter = UnifiedInboxFilter {
+        sort: InboxSort::CreatedAsc,
+        cursor: Some(InboxCursor::new(InboxSort::AckPendingFirst, fx.ids[3])),
+        ..Default::default()
+    };
+    let result = MessageBmc::list_unified_inbox_filtered(&tc.ctx, &tc.mm, &filter).await;
+    assert!(matches!(result, Err(Error::InvalidInput(_))));
+}
+
+/// Test sort and cursor text forms
+#[test]
+fn test_sort_and_cursor_parsing() {
+    for (sort, _) in EXPECTED {
+        assert_eq!(sort.as_str().parse::<InboxSort>().unwrap(), sort);
+    }
+    assert_eq!(InboxSort::default(), InboxSort::CreatedDesc);
+    assert!(matches!(
+        "newest".parse::<InboxSort>(),
+        Err(Error::InvalidInput(_))
+    ));
+
+    let cursor = InboxCursor::new(InboxSort::ImportanceDesc, 42);
+    assert_eq!(cursor.to_string(), "importance_desc:42");
+    assert_eq!("importance_desc:42".parse::<InboxCursor>().unwrap(), cursor);
+
+    // Bare IDs are newest-first cursors
+    assert_eq!(
+        "42".parse::<InboxCursor>().unwrap(),
+        InboxCursor::new(InboxSort::CreatedDesc, 42)
+    );
+
+    for bad in ["", "importance_desc:", "newest:42", "created_asc:x", "x"] {
+        assert!(
+            matches!(bad.parse::<InboxCursor>(), Err(Error::InvalidInput(_))),
+            "{:?} should be rejected",
+            bad
+        );
+    }
+}
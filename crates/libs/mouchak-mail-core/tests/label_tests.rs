@@ -0,0 +1,289 @@
+//! Message label tests
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use crate::common::TestContext;
+use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
+use mouchak_mail_core::model::label::{DEFAULT_LABEL_COLOR, LabelBmc, LabelForCreate};
+use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate, UnifiedInboxFilter};
+use mouchak_mail_core::model::project::ProjectBmc;
+use mouchak_mail_core::types::ProjectId;
+
+mod common;
+
+fn label(project_id: ProjectId, name: &str, color: Option<&str>) -> LabelForCreate {
+    LabelForCreate {
+        project_id,
+        name: name.to_string(),
+        color: color.map(String::from),
+    }
+}
+
+/// Project with a sender and a recipient; returns (project, sender, recipient)
+async fn setup(tc: &TestContext, slug: &str) -> (ProjectId, i64, i64) {
+    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, slug, &format!("/label/{}", slug))
+        .await
+        .unwrap();
+    let mut ids = Vec::new();
+    for name in ["Sender", "Recipient"] {
+        let agent_c = AgentForCreate {
+            project_id,
+            name: name.to_string(),
+            program: "test".to_string(),
+            model: "test".to_string(),
+            task_description: "test".to_string(),
+        };
+        let id: i64 = AgentBmc::create(&tc.ctx, &tc.mm, agent_c)
+            .await
+            .unwrap()
+            .into();
+        ids.push(id);
+    }
+    (project_id, ids[0], ids[1])
+}
+
+async fn send(tc: &TestContext, project_id: ProjectId, from: i64, to: i64, subject: &str) -> i64 {
+    let msg_c = MessageForCreate {
+        project_id: project_id.get(),
+        sender_id: from,
+        recipient_ids: vec![to],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: subject.to_string(),
+        body_md: "Body".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_label_crud_and_validation() {
+    let tc = TestContext::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+    let (project_id, _, _) = setup(&tc, "label-crud").await;
+
+    let blocked_id = LabelBmc::create(ctx, mm, label(project_id, " blocked ", Some("#DC2626")))
+        .await
+        .unwrap();
+    LabelBmc::create(ctx, mm, label(project_id, "Needs-Review", None))
+        .await
+        .unwrap();
+
+    let blocked = LabelBmc::get(ctx, mm, project_id, blocked_id)
+        .await
+        .unwrap();
+    assert_eq!(blocked.name, "blocked");
+    assert_eq!(blocked.color, "#dc2626");
+
+    let listed = LabelBmc::list_for_project(ctx, mm, project_id)
+        .await
+        .unwrap();
+    let names: Vec<&str> = listed.iter().map(|l| l.name.as_str()).collect();
+    assert_eq!(names, vec!["blocked", "Needs-Review"]);
+    assert_eq!(listed[1].color, DEFAULT_LABEL_COLOR);
+
+    // Names are unique per project ignoring case
+    assert!(matches!(
+        LabelBmc::create(ctx, mm, label(project_id, "BLOCKED", None)).await,
+        Err(mouchak_mail_core::Error::LabelNameTaken(name)) if name == "BLOCKED"
+    ));
+    let found = LabelBmc::get_by_name(ctx, mm, project_id, "needs-review")
+        .await
+        .unwrap();
+    assert_eq!(found.name, "Needs-Review");
+
+    // ... but the same name is fine in another project
+    let (other_id, _, _) = setup(&tc, "label-other").await;
+    LabelBmc::create(ctx, mm, label(other_id, "blocked", None))
+        .await
+        .unwrap();
+
+    for (name, color) in [("  ", None), ("ok", Some("red")), ("ok", Some("#12345g"))] {
+        assert!(matches!(
+            LabelBmc::create(ctx, mm, label(project_id, name, color)).await,
+            Err(mouchak_mail_core::Error::InvalidInput(_))
+        ));
+    }
+    assert!(matches!(
+        LabelBmc::get_by_name(ctx, mm, project_id, "done").await,
+        Err(mouchak_mail_core::Error::LabelNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_apply_remove_and_delete_cascade() {
+    let tc = TestContext::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+    let (project_id, sender, recipient) = setup(&tc, "label-apply").await;
+    let first = send(&tc, project_id, sender, recipient, "First").await;
+    let second = send(&tc, project_id, sender, recipient, "Second").await;
+
+    let blocked_id = LabelBmc::create(ctx, mm, label(project_id, "blocked", None))
+        .await
+        .unwrap();
+    assert!(LabelBmc::apply(ctx, mm, first, blocked_id).await.unwrap());
+    assert!(!LabelBmc::apply(ctx, mm, first, blocked_id).await.unwrap());
+    LabelBmc::apply(ctx, mm, second, blocked_id).await.unwrap();
+
+    let labelled = LabelBmc::list_messages(ctx, mm, project_id, blocked_id, 10)
+        .await
+        .unwrap();
+    let subjects: Vec<&str> = labelled.iter().map(|m| m.subject.as_str()).collect();
+    assert_eq!(subjects, vec!["Second", "First"]);
+
+    assert!(LabelBmc::remove(ctx, mm, second, blocked_id).await.unwrap());
+    assert!(!LabelBmc::remove(ctx, mm, second, blocked_id).await.unwrap());
+
+    // A label from another project can't be applied
+    let (other_id, _, _) = setup(&tc, "label-apply-other").await;
+    let foreign_id = LabelBmc::create(ctx, mm, label(other_id, "blocked", None))
+        .await
+        .unwrap();
+    assert!(matches!(
+        LabelBmc::apply(ctx, mm, first, foreign_id).await,
+        Err(mouchak_mail_core::Error::LabelNotFound(_))
+    ));
+
+    // Deleting the label drops the join rows but keeps the messages
+    LabelBmc::delete(ctx, mm, project_id, blocked_id)
+        .await
+        .unwrap();
+    assert!(
+        LabelBmc::list_for_message(ctx, mm, first)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        MessageBmc::get(ctx, mm, first).await.unwrap().subject,
+        "First"
+    );
+    assert!(matches!(
+        LabelBmc::delete(ctx, mm, project_id, blocked_id).await,
+        Err(mouchak_mail_core::Error::LabelNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_update_message_labels_by_name() {
+    let tc = TestContext::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+    let (project_id, sender, recipient) = setup(&tc, "label-names").await;
+    let message_id = send(&tc, project_id, sender, recipient, "Triage me").await;
+
+    let names = |labels: Vec<mouchak_mail_core::model::label::Label>| -> Vec<String> {
+        labels.into_iter().map(|l| l.name).collect()
+    };
+
+    assert!(matches!(
+        LabelBmc::update_message_labels(ctx, mm, message_id, &["done".to_string()], &[], false)
+            .await,
+        Err(mouchak_mail_core::Error::LabelNotFound(name)) if name == "done"
+    ));
+
+    let labels = LabelBmc::update_message_labels(
+        ctx,
+        mm,
+        message_id,
+        &["needs-review".to_string(), "blocked".to_string()],
+        &[],
+        true,
+    )
+    .await
+    .unwrap();
+    assert_eq!(names(labels), vec!["blocked", "needs-review"]);
+
+    let labels = LabelBmc::update_message_labels(
+        ctx,
+        mm,
+        message_id,
+        &["Blocked".to_string()],
+        &["NEEDS-REVIEW".to_string()],
+        false,
+    )
+    .await
+    .unwrap();
+    assert_eq!(names(labels), vec!["blocked"]);
+}
+
+#[tokio::test]
+async fn test_inbox_label_filters() {
+    let tc = TestContext::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+    let (project_id, sender, recipient) = setup(&tc, "label-filter").await;
+    let tagged = send(&tc, project_id, sender, recipient, "Tagged").await;
+    send(&tc, project_id, sender, recipient, "Untagged").await;
+
+    LabelBmc::update_message_labels(ctx, mm, tagged, &["blocked".to_string()], &[], true)
+        .await
+        .unwrap();
+
+    let inbox = MessageBmc::list_inbox_for_agent_labeled(
+        ctx,
+        mm,
+        project_id.get(),
+        recipient,
+        Some("BLOCKED"),
+        10,
+    )
+    .await
+    .unwrap();
+    assert_eq!(inbox.len(), 1);
+    assert_eq!(inbox[0].id, tagged);
+
+    let inbox =
+        MessageBmc::list_inbox_for_agent_labeled(ctx, mm, project_id.get(), recipient, None, 10)
+            .await
+            .unwrap();
+    assert_eq!(inbox.len(), 2);
+
+    let filter = UnifiedInboxFilter {
+        label: Some("blocked".to_string()),
+        ..Default::default()
+    };
+    let unified = MessageBmc::list_unified_inbox_filtered(ctx, mm, &filter)
+        .await
+        .unwrap();
+    assert_eq!(unified.len(), 1);
+    assert_eq!(unified[0].subject, "Tagged");
+
+    let filter = UnifiedInboxFilter {
+        label: Some("done".to_string()),
+        ..Default::default()
+    };
+    assert!(
+        MessageBmc::list_unified_inbox_filtered(ctx, mm, &filter)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_project_delete_removes_labels() {
+    let tc = TestContext::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+    let (project_id, sender, recipient) = setup(&tc, "label-project-delete").await;
+    let message_id = send(&tc, project_id, sender, recipient, "Going away").await;
+    LabelBmc::update_message_labels(ctx, mm, message_id, &["done".to_string()], &[], true)
+        .await
+        .unwrap();
+
+    ProjectBmc::delete(ctx, mm, project_id).await.unwrap();
+
+    let db = mm.db_for_test();
+    for table in ["labels", "message_labels"] {
+        let mut rows = db
+            .query(&format!("SELECT COUNT(*) FROM {}", table), ())
+            .await
+            .unwrap();
+        let count: i64 = rows.next().await.unwrap().unwrap().get(0).unwrap();
+        assert_eq!(count, 0, "{} should be empty", table);
+    }
+}
@@ -0,0 +1,135 @@
+//! Message listing performance with large bodies
+//!
+//! The plan test always runs. The timing test seeds 50k messages with
+//! 8 KiB bodies and times the inbox, unified inbox and search listings,
+//! which read only the snippet. It is ignored by default; run it with
+//! `cargo test -p mouchak-mail-core --test message_body_perf_tests -- --ignored --nocapture`.
+
+// Tests are allowed to use unwrap()/expect()
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use mouchak_mail_core::model::message::{MessageBmc, UnifiedInboxFilter};
+use mouchak_mail_core::testing::TestEnv;
+use std::time::{Duration, Instant};
+
+/// Messages seeded for the timing test
+const SEEDED: usize = 50_000;
+
+/// Body size of each seeded message
+const BODY_LEN: usize = 8 * 1024;
+
+/// Runs per listing; the fastest is reported, to leave out cache warm-up
+const RUNS: usize = 5;
+
+/// Slowest acceptable listing. The agent inbox took 361ms here while
+/// bodies were stored in messages, and 169ms with snippets.
+const LIST_BUDGET: Duration = Duration::from_millis(250);
+
+/// Whether a plan looks up message_bodies, which visible_messages joins as `b`
+fn reads_bodies(plan: &[String]) -> bool {
+    plan.iter()
+        .any(|step| step.starts_with("SEARCH b ") || step.contains("message_bodies"))
+}
+
+/// Test listings never read message_bodies, only the snippet on messages
+#[tokio::test]
+async fn test_listing_plans_skip_message_bodies() {
+    let env = TestEnv::new().await.unwrap();
+    let plan = env
+        .explain_unified_inbox(&UnifiedInboxFilter::default())
+        .await
+        .unwrap();
+    assert!(
+        !reads_bodies(&plan),
+        "unified inbox should not read bodies: {:#?}",
+        plan
+    );
+
+    for (column, expected) in [("snippet", false), ("body_md", true)] {
+        let mut rows = env
+            .mm
+            .db_for_test()
+            .query(
+                &format!(
+                    "EXPLAIN QUERY PLAN SELECT id, {} FROM visible_messages",
+                    column
+                ),
+                (),
+            )
+            .await
+            .unwrap();
+        let mut plan = Vec::new();
+        while let Some(row) = rows.next().await.unwrap() {
+            plan.push(row.get::<String>(3).unwrap());
+        }
+        assert_eq!(reads_bodies(&plan), expected, "{}: {:#?}", column, plan);
+    }
+}
+
+/// Test inbox, unified inbox and search pages stay fast when bodies are large
+#[tokio::test]
+#[ignore = "seeds 50k messages with 8 KiB bodies; run with --ignored"]
+async fn test_listings_with_large_bodies() {
+    let env = TestEnv::new().await.unwrap();
+    let fixture = env
+        .fixtures()
+        .project("/perf/bodies")
+        .agents(4)
+        .build()
+        .await
+        .unwrap();
+    env.seed_messages_with_body_len(&fixture, SEEDED, BODY_LEN)
+        .await
+        .unwrap();
+    let project_id = fixture.project_id.get();
+    let agent_id = fixture.agent_id("agent-2").get();
+    let (ctx, mm) = (&env.ctx, &env.mm);
+
+    let mut timings = Vec::new();
+
+    let mut best = Duration::MAX;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        let page = MessageBmc::list_inbox_for_agent(ctx, mm, project_id, agent_id, 50)
+            .await
+            .unwrap();
+        best = best.min(start.elapsed());
+        assert_eq!(page.len(), 50);
+    }
+    timings.push(("agent inbox", best));
+
+    let mut best = Duration::MAX;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        let page = MessageBmc::list_unified_inbox_filtered(ctx, mm, &UnifiedInboxFilter::default())
+            .await
+            .unwrap();
+        best = best.min(start.elapsed());
+        assert_eq!(page.len(), 50);
+    }
+    timings.push(("unified inbox", best));
+
+    let mut best = Duration::MAX;
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        let page = MessageBmc::search(ctx, mm, project_id, "4999", 50)
+            .await
+            .unwrap();
+        best = best.min(start.elapsed());
+        assert!(!page.is_empty());
+    }
+    timings.push(("search", best));
+
+    for (name, elapsed) in &timings {
+        println!("{:<14} {:?}", name, elapsed);
+    }
+    for (name, elapsed) in timings {
+        assert!(
+            elapsed < LIST_BUDGET,
+            "{} took {:?}, budget is {:?}",
+            name,
+            elapsed,
+            LIST_BUDGET
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! Message body storage tests
+//!
+//! Full bodies live in message_bodies; messages keeps a 200-character
+//! snippet for listings. Covers snippets of multi-byte bodies, written at
+//! insert time and backfilled by migration 032.
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use crate::common::TestContext;
+use libsql::Builder;
+use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
+use mouchak_mail_core::model::message::{
+    MessageBmc, MessageForCreate, SNIPPET_CHARS, UnifiedInboxFilter, body_hash, snippet,
+};
+use mouchak_mail_core::model::project::ProjectBmc;
+use mouchak_mail_core::{store, testing};
+use tempfile::TempDir;
+
+mod common;
+
+/// 2-, 3- and 4-byte characters, 3 of each per repeat
+fn multibyte_body(repeats: usize) -> String {
+    "ééé中中中🦀🦀🦀 ".repeat(repeats)
+}
+
+#[test]
+fn test_snippet_never_splits_a_character() {
+    let body = multibyte_body(40);
+    let cut = snippet(&body);
+    assert_eq!(cut.chars().count(), SNIPPET_CHARS);
+    assert!(body.starts_with(&cut));
+    // A byte-based cut at 200 would land inside a character
+    assert!(!body.is_char_boundary(201));
+
+    assert_eq!(snippet("短い本文"), "短い本文");
+    assert_eq!(snippet(""), "");
+}
+
+#[tokio::test]
+async fn test_snippet_written_at_insert_for_multibyte_body() {
+    let tc = TestContext::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+    let project_id = ProjectBmc::create(ctx, mm, "bodies", "/bodies/insert")
+        .await
+        .unwrap();
+    let mut agent_ids = Vec::new();
+    for name in ["Sender", "Reader"] {
+        let agent_c = AgentForCreate {
+            project_id,
+            name: name.to_string(),
+            program: "test".to_string(),
+            model: "test".to_string(),
+            task_description: "test".to_string(),
+        };
+        agent_ids.push(AgentBmc::create(ctx, mm, agent_c).await.unwrap().get());
+    }
+
+    let body = format!("needle {}", multibyte_body(40));
+    let message_id = MessageBmc::create(
+        ctx,
+        mm,
+        MessageForCreate {
+            project_id: project_id.get(),
+            sender_id: agent_ids[0],
+            recipient_ids: vec![agent_ids[1]],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: "Unicode".to_string(),
+            body_md: body.clone(),
+            thread_id: None,
+            importance: None,
+            ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
+        },
+    )
+    .await
+    .unwrap();
+    let expected = snippet(&body);
+
+    // Listings carry the snippet and leave the body empty
+    let mut inbox = MessageBmc::list_inbox_for_agent(ctx, mm, project_id.get(), agent_ids[1], 10)
+        .await
+        .unwrap();
+    assert_eq!(inbox[0].snippet, expected);
+    assert!(inbox[0].body_md.is_empty());
+    let found = MessageBmc::search(ctx, mm, project_id.get(), "needle", 10)
+        .await
+        .unwrap();
+    assert_eq!(found[0].snippet, expected);
+    assert!(found[0].body_md.is_empty());
+    let unified = MessageBmc::list_unified_inbox_filtered(
+        ctx,
+        mm,
+        &UnifiedInboxFilter {
+            projects: vec!["bodies".to_string()],
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(unified[0].snippet, expected);
+    assert!(unified[0].excerpt.ends_with('…'));
+    assert!(body.starts_with(unified[0].excerpt.trim_end_matches('…')));
+
+    // The full body is one call away
+    MessageBmc::load_bodies(mm, &mut inbox).await.unwrap();
+    assert_eq!(inbox[0].body_md, body);
+    let message = MessageBmc::get(ctx, mm, message_id).await.unwrap();
+    assert_eq!(message.body_md, body);
+    assert!(message.snippet.is_empty());
+
+    let mut rows = mm
+        .db_for_test()
+        .query(
+            "SELECT body_hash FROM message_bodies WHERE message_id = ?",
+            [message_id],
+        )
+        .await
+        .unwrap();
+    let hash: String = rows.next().await.unwrap().unwrap().get(0).unwrap();
+    assert_eq!(hash, body_hash(&body));
+}
+
+#[tokio::test]
+async fn test_migration_backfills_bodies_and_snippets() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Builder::new_local(temp_dir.path().join("old.db"))
+        .build()
+        .await
+        .unwrap();
+    let conn = db.connect().unwrap();
+
+    // A database as the previous release left it
+    testing::migrate_to(&conn, 31).await.unwrap();
+
+    let long = format!("needle {}", multibyte_body(40));
+    let short = "Kurzer Text über Größen".to_string();
+    conn.execute_batch(
+        "INSERT INTO projects (slug, human_key) VALUES ('old', '/old');
+         INSERT INTO agents (project_id, name, program, model) VALUES (1, 'Sender', 'test', 'test');",
+    )
+    .await
+    .unwrap();
+    for body in [&long, &short] {
+        conn.execute(
+            "INSERT INTO messages (project_id, sender_id, subject, body_md) VALUES (1, 1, 'Old', ?)",
+            [body.as_str()],
+        )
+        .await
+        .unwrap();
+    }
+
+    store::apply_migrations(&conn).await.unwrap();
+    assert_eq!(
+        store::schema_version(&conn).await.unwrap(),
+        store::SCHEMA_VERSION
+    );
+
+    let mut rows = conn
+        .query(
+            "SELECT m.snippet, b.body_md, b.body_hash
+             FROM messages AS m JOIN message_bodies AS b ON b.message_id = m.id
+             ORDER BY m.id",
+            (),
+        )
+        .await
+        .unwrap();
+    for body in [&long, &short] {
+        let row = rows.next().await.unwrap().unwrap();
+        assert_eq!(row.get::<String>(0).unwrap(), snippet(body));
+        assert_eq!(&row.get::<String>(1).unwrap(), body);
+        assert_eq!(row.get::<String>(2).unwrap(), body_hash(body));
+    }
+    drop(rows);
+
+    // The full-text index now reads message_bodies
+    let mut rows = conn
+        .query(
+            "SELECT rowid FROM messages_fts WHERE messages_fts MATCH 'needle'",
+            (),
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        rows.next().await.unwrap().unwrap().get::<i64>(0).unwrap(),
+        1
+    );
+    drop(rows);
+
+    // Already recorded: running again changes nothing
+    store::apply_migrations(&conn).await.unwrap();
+}
+
+/// A migration that fails partway leaves the previous version recorded and
+/// none of its changes, so the next start can run it again
+#[tokio::test]
+async fn test_failed_migration_rolls_back() {
+    let temp_dir = TempDir::new().unwrap();
+    let db = Builder::new_local(temp_dir.path().join("old.db"))
+        .build()
+        .await
+        .unwrap();
+    let conn = db.connect().unwrap();
+    testing::migrate_to(&conn, 31).await.unwrap();
+
+    // Clashes with a trigger 032 creates after it has altered messages
+    conn.execute_batch(
+        "CREATE TRIGGER messages_body_ad AFTER DELETE ON projects BEGIN SELECT 1; END;",
+    )
+    .await
+    .unwrap();
+    assert!(store::apply_migrations(&conn).await.is_err());
+    assert_eq!(store::schema_version(&conn).await.unwrap(), 31);
+    conn.query("SELECT body_md FROM messages", ())
+        .await
+        .expect("032's DROP COLUMN was rolled back");
+
+    conn.execute_batch("DROP TRIGGER messages_body_ad;")
+        .await
+        .unwrap();
+    store::apply_migrations(&conn).await.unwrap();
+    assert_eq!(
+        store::schema_version(&conn).await.unwrap(),
+        store::SCHEMA_VERSION
+    );
+}
@@ -13,9 +13,14 @@
 mod common;
 
 use crate::common::TestContext;
+use mouchak_mail_common::config::AppConfig;
+use mouchak_mail_core::Ctx;
 use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
-use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
+use mouchak_mail_core::model::message::{
+    MAX_BATCH_SIZE, MessageBmc, MessageForCreate, ThreadCursor,
+};
 use mouchak_mail_core::model::project::ProjectBmc;
+use mouchak_mail_core::types::{AgentId, ProjectId};
 use mouchak_mail_core::utils::slugify;
 
 /// Helper to set up project and agents for message tests
@@ -74,6 +79,9 @@ async fn test_send_message() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
 
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c)
@@ -83,6 +91,64 @@ async fn test_send_message() {
     assert!(msg_id > 0, "Message should have valid ID");
 }
 
+/// A create with an unknown recipient id must not leave a partial message behind
+#[tokio::test]
+async fn test_send_message_invalid_recipient_writes_nothing() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (project_id, sender_id, recipient_id) = setup_messaging(&tc).await;
+
+    let msg_c = MessageForCreate {
+        project_id,
+        sender_id,
+        recipient_ids: vec![recipient_id],
+        cc_ids: Some(vec![999_999]),
+        bcc_ids: None,
+        subject: "Half sent".to_string(),
+        body_md: "Should not be stored".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    let result = MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await;
+    assert!(result.is_err(), "Unknown recipient should be rejected");
+
+    let db = tc.mm.db_for_test();
+    for table in ["messages", "message_recipients"] {
+        let stmt = db
+            .prepare(&format!("SELECT COUNT(*) FROM {}", table))
+            .await
+            .unwrap();
+        let mut rows = stmt.query(()).await.unwrap();
+        let count: i64 = rows.next().await.unwrap().unwrap().get(0).unwrap();
+        assert_eq!(count, 0, "{} should be empty", table);
+    }
+
+    // The connection is left usable for the next send
+    let msg_c = MessageForCreate {
+        project_id,
+        sender_id,
+        recipient_ids: vec![recipient_id],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: "Fully sent".to_string(),
+        body_md: "Stored".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    MessageBmc::create(&tc.ctx, &tc.mm, msg_c)
+        .await
+        .expect("Valid send after a rejected one");
+}
+
 /// Test getting a specific message
 #[tokio::test]
 async fn test_get_message() {
@@ -102,6 +168,9 @@ async fn test_get_message() {
         thread_id: None,
         importance: Some("high".to_string()),
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
 
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
@@ -134,6 +203,9 @@ async fn test_message_threading() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let initial_id = MessageBmc::create(&tc.ctx, &tc.mm, initial_msg_c)
         .await
@@ -154,6 +226,9 @@ async fn test_message_threading() {
         thread_id: initial.thread_id.clone(),
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
     let reply_id = MessageBmc::create(&tc.ctx, &tc.mm, reply_msg_c)
         .await
@@ -187,6 +262,9 @@ async fn test_search_messages() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg1_c).await.unwrap();
 
@@ -201,6 +279,9 @@ async fn test_search_messages() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg2_c).await.unwrap();
 
@@ -215,6 +296,9 @@ async fn test_search_messages() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg3_c).await.unwrap();
 
@@ -256,6 +340,9 @@ async fn test_mark_message_read() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
 
@@ -288,6 +375,9 @@ async fn test_acknowledge_message() {
         thread_id: None,
         importance: Some("high".to_string()),
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
 
@@ -320,6 +410,9 @@ async fn test_list_threads() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg1_id = MessageBmc::create(&tc.ctx, &tc.mm, msg1_c).await.unwrap();
     let msg1 = MessageBmc::get(&tc.ctx, &tc.mm, msg1_id).await.unwrap();
@@ -336,6 +429,9 @@ async fn test_list_threads() {
         thread_id: msg1.thread_id.clone(),
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, reply_c).await.unwrap();
 
@@ -351,11 +447,14 @@ async fn test_list_threads() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg2_c).await.unwrap();
 
     // List threads
-    let threads = MessageBmc::list_threads(&tc.ctx, &tc.mm, project_id, 10)
+    let threads = MessageBmc::list_threads(&tc.ctx, &tc.mm, project_id, 10, None)
         .await
         .expect("Should list threads");
 
@@ -368,6 +467,190 @@ async fn test_list_threads() {
     }
 }
 
+/// Test thread listing details and keyset pagination
+#[tokio::test]
+async fn test_list_threads_pagination() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (project_id, sender_id, recipient_id) = setup_messaging(&tc).await;
+
+    let send = |sender: i64, recipient: i64, subject: &str, thread: &str| MessageForCreate {
+        project_id,
+        sender_id: sender,
+        recipient_ids: vec![recipient],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: subject.to_string(),
+        body_md: "body".to_string(),
+        thread_id: Some(thread.to_string()),
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
+    };
+
+    for thread in ["T-1", "T-2", "T-3"] {
+        MessageBmc::create(
+            &tc.ctx,
+            &tc.mm,
+            send(
+                sender_id,
+                recipient_id,
+                &format!("Start {}", thread),
+                thread,
+            ),
+        )
+        .await
+        .unwrap();
+    }
+    MessageBmc::create(
+        &tc.ctx,
+        &tc.mm,
+        send(recipient_id, sender_id, "Re: Start T-2", "T-2"),
+    )
+    .await
+    .unwrap();
+
+    let all = MessageBmc::list_threads(&tc.ctx, &tc.mm, project_id, 10, None)
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 3);
+    for pair in all.windows(2) {
+        assert!(
+            (pair[0].last_message_ts, &pair[0].thread_id)
+                > (pair[1].last_message_ts, &pair[1].thread_id),
+            "threads should be ordered by last activity, then thread id"
+        );
+    }
+
+    let t2 = all.iter().find(|t| t.thread_id == "T-2").unwrap();
+    assert_eq!(
+        t2.subject, "Start T-2",
+        "subject comes from the first message"
+    );
+    assert_eq!(t2.message_count, 2);
+    assert_eq!(t2.participant_count, 2);
+    assert_eq!(t2.last_sender_name, "Recipient");
+    let t1 = all.iter().find(|t| t.thread_id == "T-1").unwrap();
+    assert_eq!(t1.participant_count, 1);
+    assert_eq!(t1.last_sender_name, "Sender");
+
+    // Walk the listing two at a time
+    let first = MessageBmc::list_threads(&tc.ctx, &tc.mm, project_id, 2, None)
+        .await
+        .unwrap();
+    assert_eq!(first.len(), 2);
+    let cursor: ThreadCursor = first[1].cursor().to_string().parse().unwrap();
+    assert_eq!(cursor, first[1].cursor());
+    let second = MessageBmc::list_threads(&tc.ctx, &tc.mm, project_id, 2, Some(&cursor))
+        .await
+        .unwrap();
+    assert_eq!(second.len(), 1);
+
+    let paged: Vec<&str> = first
+        .iter()
+        .chain(second.iter())
+        .map(|t| t.thread_id.as_str())
+        .collect();
+    let expected: Vec<&str> = all.iter().map(|t| t.thread_id.as_str()).collect();
+    assert_eq!(paged, expected);
+
+    assert!("not-a-cursor".parse::<ThreadCursor>().is_err());
+}
+
+/// Test thread summary stats (participants, acks, snippets)
+#[tokio::test]
+async fn test_thread_summary() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (project_id, sender_id, recipient_id) = setup_messaging(&tc).await;
+
+    let start_c = MessageForCreate {
+        project_id,
+        sender_id,
+        recipient_ids: vec![recipient_id],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: "Design review".to_string(),
+        body_md: "Please review the design".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    let start_id = MessageBmc::create(&tc.ctx, &tc.mm, start_c).await.unwrap();
+    let thread_id = MessageBmc::get(&tc.ctx, &tc.mm, start_id)
+        .await
+        .unwrap()
+        .thread_id
+        .unwrap();
+
+    for (from, to, body, ack) in [
+        (recipient_id, sender_id, "Looks good", false),
+        (sender_id, recipient_id, "Second pass", true),
+        (sender_id, recipient_id, "Final version", false),
+    ] {
+        let msg_c = MessageForCreate {
+            project_id,
+            sender_id: from,
+            recipient_ids: vec![to],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: "Re: Design review".to_string(),
+            body_md: body.to_string(),
+            thread_id: Some(thread_id.clone()),
+            importance: None,
+            ack_required: ack,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: true,
+        };
+        MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
+    }
+    MessageBmc::acknowledge(&tc.ctx, &tc.mm, start_id, recipient_id)
+        .await
+        .unwrap();
+
+    let summary = MessageBmc::thread_summary(&tc.ctx, &tc.mm, project_id, &thread_id, 2)
+        .await
+        .expect("Should summarize thread");
+
+    assert_eq!(summary.subject, "Design review");
+    assert_eq!(summary.message_count, 4);
+    assert_eq!(summary.ack_required_count, 2);
+    assert_eq!(summary.acked_count, 1);
+    assert!(summary.first_ts <= summary.last_ts);
+
+    assert_eq!(summary.participants.len(), 2);
+    assert_eq!(summary.participants[0].name, "Sender");
+    assert_eq!(summary.participants[0].message_count, 3);
+    assert_eq!(summary.participants[1].name, "Recipient");
+    assert_eq!(summary.participants[1].message_count, 1);
+
+    let snippets: Vec<&str> = summary.recent.iter().map(|r| r.snippet.as_str()).collect();
+    assert_eq!(snippets, vec!["Second pass", "Final version"]);
+}
+
+/// Test that an unknown thread is reported as not found
+#[tokio::test]
+async fn test_thread_summary_unknown_thread() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (project_id, _, _) = setup_messaging(&tc).await;
+
+    let result = MessageBmc::thread_summary(&tc.ctx, &tc.mm, project_id, "no-such-thread", 5).await;
+    assert!(matches!(
+        result,
+        Err(mouchak_mail_core::Error::ThreadNotFound(_))
+    ));
+}
+
 /// Test listing outbox messages for an agent
 #[tokio::test]
 async fn test_list_outbox() {
@@ -388,6 +671,9 @@ async fn test_list_outbox() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg1_c).await.unwrap();
 
@@ -402,27 +688,32 @@ async fn test_list_outbox() {
         thread_id: None,
         importance: Some("high".to_string()),
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg2_c).await.unwrap();
 
     // List outbox for sender
-    let outbox = MessageBmc::list_outbox_for_agent(&tc.ctx, &tc.mm, project_id, sender_id, 10)
-        .await
-        .expect("Should list outbox messages");
+    let outbox =
+        MessageBmc::list_outbox_for_agent(&tc.ctx, &tc.mm, project_id, sender_id, 10, None)
+            .await
+            .expect("Should list outbox messages");
 
     // Verify we got both messages
     assert_eq!(outbox.len(), 2, "Should have 2 messages in outbox");
 
     // Verify messages are ordered by created_ts DESC (newest first)
     assert!(
-        outbox[0].subject == "Outbox Test 2" || outbox[0].subject == "Outbox Test 1",
+        outbox[0].message.subject == "Outbox Test 2"
+            || outbox[0].message.subject == "Outbox Test 1",
         "Should contain expected subjects"
     );
 
     // Verify sender_id is correct
     for msg in &outbox {
         assert_eq!(
-            msg.sender_id, sender_id,
+            msg.message.sender_id, sender_id,
             "All messages should be from sender"
         );
     }
@@ -503,6 +794,9 @@ async fn test_outbox_project_filtering() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg1_c).await.unwrap();
 
@@ -518,6 +812,9 @@ async fn test_outbox_project_filtering() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg2_c).await.unwrap();
 
@@ -528,6 +825,7 @@ async fn test_outbox_project_filtering() {
         project1.id.into(),
         sender1_id.into(),
         10,
+        None,
     )
     .await
     .expect("Should list outbox for project 1");
@@ -539,6 +837,7 @@ async fn test_outbox_project_filtering() {
         project2.id.into(),
         sender2_id.into(),
         10,
+        None,
     )
     .await
     .expect("Should list outbox for project 2");
@@ -554,8 +853,8 @@ async fn test_outbox_project_filtering() {
         1,
         "Should have 1 message in project 2 outbox"
     );
-    assert_eq!(outbox1[0].subject, "Project 1 Message");
-    assert_eq!(outbox2[0].subject, "Project 2 Message");
+    assert_eq!(outbox1[0].message.subject, "Project 1 Message");
+    assert_eq!(outbox2[0].message.subject, "Project 2 Message");
 }
 
 /// Test outbox pagination with limit
@@ -579,13 +878,16 @@ async fn test_outbox_pagination() {
             thread_id: None,
             importance: None,
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
         };
         MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
     }
 
     // List with limit of 3
     let outbox_limited =
-        MessageBmc::list_outbox_for_agent(&tc.ctx, &tc.mm, project_id, sender_id, 3)
+        MessageBmc::list_outbox_for_agent(&tc.ctx, &tc.mm, project_id, sender_id, 3, None)
             .await
             .expect("Should list outbox with limit");
 
@@ -593,11 +895,28 @@ async fn test_outbox_pagination() {
     assert_eq!(outbox_limited.len(), 3, "Should return exactly 3 messages");
 
     // List with limit of 10 (should return all 5)
-    let outbox_all = MessageBmc::list_outbox_for_agent(&tc.ctx, &tc.mm, project_id, sender_id, 10)
-        .await
-        .expect("Should list all outbox messages");
+    let outbox_all =
+        MessageBmc::list_outbox_for_agent(&tc.ctx, &tc.mm, project_id, sender_id, 10, None)
+            .await
+            .expect("Should list all outbox messages");
 
     assert_eq!(outbox_all.len(), 5, "Should return all 5 messages");
+
+    // Next page resumes after the last message of the first page
+    let cursor = outbox_limited.last().map(|m| m.message.id);
+    let next_page =
+        MessageBmc::list_outbox_for_agent(&tc.ctx, &tc.mm, project_id, sender_id, 3, cursor)
+            .await
+            .expect("Should list next outbox page");
+
+    assert_eq!(next_page.len(), 2, "Second page should hold the rest");
+    let paged: Vec<i64> = outbox_limited
+        .iter()
+        .chain(next_page.iter())
+        .map(|m| m.message.id)
+        .collect();
+    let all: Vec<i64> = outbox_all.iter().map(|m| m.message.id).collect();
+    assert_eq!(paged, all, "Pages should match the unpaged order");
 }
 
 /// Test outbox with multiple recipients (including CC and BCC)
@@ -669,18 +988,66 @@ async fn test_outbox_with_multiple_recipients() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
 
     // Check outbox for sender
-    let outbox =
-        MessageBmc::list_outbox_for_agent(&tc.ctx, &tc.mm, project.id.into(), sender_id.into(), 10)
-            .await
-            .expect("Should list outbox");
+    let outbox = MessageBmc::list_outbox_for_agent(
+        &tc.ctx,
+        &tc.mm,
+        project.id.into(),
+        sender_id.into(),
+        10,
+        None,
+    )
+    .await
+    .expect("Should list outbox");
 
     // Should have exactly 1 message
     assert_eq!(outbox.len(), 1, "Should have 1 message in outbox");
-    assert_eq!(outbox[0].subject, "Multi-recipient Message");
+    assert_eq!(outbox[0].message.subject, "Multi-recipient Message");
+
+    // Recipients are listed to, cc, bcc with no delivery state yet
+    let recipients: Vec<(&str, &str)> = outbox[0]
+        .recipients
+        .iter()
+        .map(|r| (r.name.as_str(), r.recipient_type.as_str()))
+        .collect();
+    assert_eq!(
+        recipients,
+        vec![
+            ("Recipient1", "to"),
+            ("Recipient2", "cc"),
+            ("Recipient3", "bcc")
+        ]
+    );
+    assert!(
+        outbox[0]
+            .recipients
+            .iter()
+            .all(|r| r.read_ts.is_none() && r.ack_ts.is_none())
+    );
+
+    // Acknowledging shows up on the sender's outbox
+    MessageBmc::acknowledge(&tc.ctx, &tc.mm, outbox[0].message.id, recipient2_id.into())
+        .await
+        .unwrap();
+    let outbox = MessageBmc::list_outbox_for_agent(
+        &tc.ctx,
+        &tc.mm,
+        project.id.into(),
+        sender_id.into(),
+        10,
+        None,
+    )
+    .await
+    .expect("Should list outbox");
+    let cc = &outbox[0].recipients[1];
+    assert!(cc.ack_ts.is_some() && cc.read_ts.is_some());
+    assert!(outbox[0].recipients[0].ack_ts.is_none());
 
     // Verify each recipient got the message in their inbox
     let inbox1 = MessageBmc::list_inbox_for_agent(
@@ -734,9 +1101,10 @@ async fn test_empty_outbox() {
     let (project_id, sender_id, _recipient_id) = setup_messaging(&tc).await;
 
     // List outbox without sending any messages
-    let outbox = MessageBmc::list_outbox_for_agent(&tc.ctx, &tc.mm, project_id, sender_id, 10)
-        .await
-        .expect("Should list empty outbox");
+    let outbox =
+        MessageBmc::list_outbox_for_agent(&tc.ctx, &tc.mm, project_id, sender_id, 10, None)
+            .await
+            .expect("Should list empty outbox");
 
     assert_eq!(outbox.len(), 0, "Outbox should be empty");
 }
@@ -798,6 +1166,9 @@ async fn test_multiple_to_recipients() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c)
         .await
@@ -823,10 +1194,16 @@ async fn test_multiple_to_recipients() {
     }
 
     // Verify sender's outbox has exactly 1 message
-    let outbox =
-        MessageBmc::list_outbox_for_agent(&tc.ctx, &tc.mm, project.id.into(), sender_id.into(), 10)
-            .await
-            .expect("Should list outbox");
+    let outbox = MessageBmc::list_outbox_for_agent(
+        &tc.ctx,
+        &tc.mm,
+        project.id.into(),
+        sender_id.into(),
+        10,
+        None,
+    )
+    .await
+    .expect("Should list outbox");
     assert_eq!(outbox.len(), 1, "Sender should have 1 message in outbox");
 }
 
@@ -854,6 +1231,9 @@ async fn test_ack_required_persisted() {
         thread_id: None,
         importance: Some("high".to_string()),
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
 
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c)
@@ -888,6 +1268,9 @@ async fn test_ack_required_defaults_false() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
 
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c)
@@ -921,6 +1304,9 @@ async fn test_list_pending_reviews_returns_ack_required_messages() {
         thread_id: None,
         importance: Some("high".to_string()),
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c)
         .await
@@ -957,6 +1343,9 @@ async fn test_list_pending_reviews_excludes_non_ack_required() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg_c)
         .await
@@ -991,6 +1380,9 @@ async fn test_list_pending_reviews_excludes_acknowledged() {
         thread_id: None,
         importance: Some("high".to_string()),
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c)
         .await
@@ -1074,6 +1466,9 @@ async fn test_list_pending_reviews_partial_ack() {
         thread_id: None,
         importance: Some("high".to_string()),
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c)
         .await
@@ -1136,6 +1531,9 @@ async fn test_list_pending_reviews_empty_when_all_acked() {
             thread_id: None,
             importance: Some("high".to_string()),
             ack_required: true,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
         };
         let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
 
@@ -1205,6 +1603,9 @@ async fn test_list_pending_reviews_full_context_present() {
         thread_id: None,
         importance: Some("high".to_string()),
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
 
@@ -1316,6 +1717,9 @@ async fn test_list_pending_reviews_filter_by_project() {
         thread_id: None,
         importance: Some("high".to_string()),
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg1_c).await.unwrap();
 
@@ -1331,6 +1735,9 @@ async fn test_list_pending_reviews_filter_by_project() {
         thread_id: None,
         importance: Some("high".to_string()),
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg2_c).await.unwrap();
 
@@ -1422,6 +1829,9 @@ async fn test_list_pending_reviews_filter_by_sender() {
         thread_id: None,
         importance: Some("high".to_string()),
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg1_c).await.unwrap();
 
@@ -1437,6 +1847,9 @@ async fn test_list_pending_reviews_filter_by_sender() {
         thread_id: None,
         importance: Some("high".to_string()),
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg2_c).await.unwrap();
 
@@ -1532,6 +1945,9 @@ async fn test_list_pending_reviews_limit_clamped() {
             thread_id: None,
             importance: Some("high".to_string()),
             ack_required: true,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
         };
         MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
     }
@@ -1658,6 +2074,9 @@ async fn test_batch_mixed_recipient_types() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c)
         .await
@@ -1695,9 +2114,641 @@ async fn test_batch_mixed_recipient_types() {
     }
 
     // Verify sender has exactly 1 message in outbox
-    let outbox =
-        MessageBmc::list_outbox_for_agent(&tc.ctx, &tc.mm, project.id.into(), sender_id.into(), 10)
+    let outbox = MessageBmc::list_outbox_for_agent(
+        &tc.ctx,
+        &tc.mm,
+        project.id.into(),
+        sender_id.into(),
+        10,
+        None,
+    )
+    .await
+    .unwrap();
+    assert_eq!(outbox.len(), 1, "Sender should have 1 outbox message");
+}
+
+/// Test per-agent unread counts drop as messages are read
+#[tokio::test]
+async fn test_unread_counts() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (project_id, sender_id, recipient_id) = setup_messaging(&tc).await;
+
+    let mut message_ids = Vec::new();
+    for subject in ["First", "Second", "Third"] {
+        let msg_c = MessageForCreate {
+            project_id,
+            sender_id,
+            recipient_ids: vec![recipient_id],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: subject.to_string(),
+            body_md: "Unread".to_string(),
+            thread_id: None,
+            importance: None,
+            ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
+        };
+        message_ids.push(MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap());
+    }
+
+    MessageBmc::mark_read(&tc.ctx, &tc.mm, message_ids[0], recipient_id)
+        .await
+        .unwrap();
+
+    let counts = MessageBmc::unread_counts(&tc.ctx, &tc.mm, ProjectId::new(project_id))
+        .await
+        .unwrap();
+    assert_eq!(counts.get(&recipient_id), Some(&2));
+    assert!(
+        !counts.contains_key(&sender_id),
+        "Sender has nothing unread"
+    );
+
+    let all = MessageBmc::unread_counts_all(&tc.ctx, &tc.mm)
+        .await
+        .unwrap();
+    assert_eq!(all[&project_id][&recipient_id], 2);
+}
+
+/// Helper to send a simple message from sender to recipient
+async fn send_simple(tc: &TestContext, project_id: i64, sender_id: i64, recipient_id: i64) -> i64 {
+    let msg_c = MessageForCreate {
+        project_id,
+        sender_id,
+        recipient_ids: vec![recipient_id],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: "Deploy now".to_string(),
+        body_md: "Wrong directive".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap()
+}
+
+/// Test that a recalled message is hidden from subsequent reads
+#[tokio::test]
+async fn test_recall_hides_message() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (project_id, sender_id, recipient_id) = setup_messaging(&tc).await;
+    let message_id = send_simple(&tc, project_id, sender_id, recipient_id).await;
+
+    let recall = MessageBmc::recall(&tc.ctx, &tc.mm, message_id, sender_id, "Sent to wrong team")
+        .await
+        .expect("Sender should be able to recall");
+    assert_eq!(recall.recall_reason, "Sent to wrong team");
+
+    let msg = MessageBmc::get(&tc.ctx, &tc.mm, message_id).await.unwrap();
+    assert_eq!(msg.subject, "[Recalled] Deploy now");
+    assert_eq!(msg.body_md, "Sent to wrong team");
+
+    let inbox = MessageBmc::list_inbox_for_agent(&tc.ctx, &tc.mm, project_id, recipient_id, 10)
+        .await
+        .unwrap();
+    assert_eq!(inbox[0].subject, "[Recalled] Deploy now");
+    assert!(!inbox[0].snippet.contains("Wrong directive"));
+
+    let hits = MessageBmc::search(&tc.ctx, &tc.mm, project_id, "directive", 10)
+        .await
+        .unwrap();
+    assert!(hits.is_empty(), "Recalled body should not be searchable");
+
+    let stored = MessageBmc::get_recall(&tc.ctx, &tc.mm, message_id)
+        .await
+        .unwrap();
+    assert!(stored.is_some());
+
+    // Tombstone appended to the archive
+    let project = ProjectBmc::get(&tc.ctx, &tc.mm, ProjectId::new(project_id))
+        .await
+        .unwrap();
+    let tombstone = tc
+        .mm
+        .repo_root
+        .join("projects")
+        .join(&project.slug)
+        .join("messages")
+        .join("recalls")
+        .join(format!("{}.json", message_id));
+    assert!(tombstone.exists(), "Recall tombstone should be archived");
+
+    // A second recall is rejected
+    let again = MessageBmc::recall(&tc.ctx, &tc.mm, message_id, sender_id, "Again").await;
+    assert!(matches!(
+        again,
+        Err(mouchak_mail_core::Error::InvalidInput(_))
+    ));
+}
+
+/// Test that only the sender can recall, and only within the window
+#[tokio::test]
+async fn test_recall_rejections() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (project_id, sender_id, recipient_id) = setup_messaging(&tc).await;
+    let message_id = send_simple(&tc, project_id, sender_id, recipient_id).await;
+
+    let by_recipient =
+        MessageBmc::recall(&tc.ctx, &tc.mm, message_id, recipient_id, "Not mine").await;
+    assert!(matches!(
+        by_recipient,
+        Err(mouchak_mail_core::Error::NotMessageSender(id)) if id == message_id
+    ));
+
+    // Push the message outside the default 5 minute window
+    tc.mm
+        .db_for_test()
+        .execute(
+            "UPDATE messages SET created_ts = datetime('now', '-10 minutes') WHERE id = ?",
+            [message_id],
+        )
+        .await
+        .unwrap();
+
+    let too_late = MessageBmc::recall(&tc.ctx, &tc.mm, message_id, sender_id, "Too late").await;
+    assert!(matches!(
+        too_late,
+        Err(mouchak_mail_core::Error::RecallWindowExpired {
+            window_seconds: 300,
+            ..
+        })
+    ));
+
+    let msg = MessageBmc::get(&tc.ctx, &tc.mm, message_id).await.unwrap();
+    assert_eq!(
+        msg.subject, "Deploy now",
+        "Failed recall leaves message intact"
+    );
+}
+
+/// Helper to schedule a message for `deliver_at`
+async fn send_scheduled(
+    tc: &TestContext,
+    project_id: i64,
+    sender_id: i64,
+    recipient_id: i64,
+    deliver_at: chrono::NaiveDateTime,
+) -> i64 {
+    let msg_c = MessageForCreate {
+        project_id,
+        sender_id,
+        recipient_ids: vec![recipient_id],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: "Reminder".to_string(),
+        body_md: "Check the build".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: Some(deliver_at),
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap()
+}
+
+/// Test that a scheduled message stays hidden until the scheduler delivers it
+#[tokio::test]
+async fn test_scheduled_message_delivery() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (project_id, sender_id, recipient_id) = setup_messaging(&tc).await;
+
+    let deliver_at = chrono::Utc::now().naive_utc() + chrono::Duration::seconds(1);
+    let message_id = send_scheduled(&tc, project_id, sender_id, recipient_id, deliver_at).await;
+
+    let inbox = MessageBmc::list_inbox_for_agent(&tc.ctx, &tc.mm, project_id, recipient_id, 10)
+        .await
+        .unwrap();
+    assert!(inbox.is_empty(), "Scheduled message must not be in inbox");
+    assert!(MessageBmc::get(&tc.ctx, &tc.mm, message_id).await.is_err());
+
+    let scheduled = MessageBmc::list_scheduled(&tc.ctx, &tc.mm, project_id, sender_id)
+        .await
+        .unwrap();
+    assert_eq!(scheduled.len(), 1);
+    assert_eq!(scheduled[0].message.id, message_id);
+    assert_eq!(scheduled[0].recipients[0].recipient_type, "to");
+
+    // Poll the way the server's scheduler does
+    let mut delivered = Vec::new();
+    for _ in 0..30 {
+        delivered = MessageBmc::deliver_due_scheduled(&tc.ctx, &tc.mm)
             .await
             .unwrap();
-    assert_eq!(outbox.len(), 1, "Sender should have 1 outbox message");
+        if !delivered.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(200)).await;
+    }
+    assert_eq!(delivered, vec![message_id]);
+
+    let inbox = MessageBmc::list_inbox_for_agent(&tc.ctx, &tc.mm, project_id, recipient_id, 10)
+        .await
+        .unwrap();
+    assert_eq!(inbox.len(), 1);
+    assert_eq!(inbox[0].subject, "Reminder");
+    assert!(
+        MessageBmc::list_scheduled(&tc.ctx, &tc.mm, project_id, sender_id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    // Already delivered; a second run is a no-op
+    let again = MessageBmc::deliver_due_scheduled(&tc.ctx, &tc.mm)
+        .await
+        .unwrap();
+    assert!(again.is_empty());
+}
+
+/// Test that past-due messages (e.g. left over from a restart) deliver on the next run
+#[tokio::test]
+async fn test_scheduled_message_past_due() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (project_id, sender_id, recipient_id) = setup_messaging(&tc).await;
+
+    let deliver_at = chrono::Utc::now().naive_utc() - chrono::Duration::minutes(5);
+    let message_id = send_scheduled(&tc, project_id, sender_id, recipient_id, deliver_at).await;
+
+    let delivered = MessageBmc::deliver_due_scheduled(&tc.ctx, &tc.mm)
+        .await
+        .unwrap();
+    assert_eq!(delivered, vec![message_id]);
+    assert!(MessageBmc::get(&tc.ctx, &tc.mm, message_id).await.is_ok());
+}
+
+/// Test cancelling a scheduled message
+#[tokio::test]
+async fn test_cancel_scheduled() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (project_id, sender_id, recipient_id) = setup_messaging(&tc).await;
+
+    let deliver_at = chrono::Utc::now().naive_utc() - chrono::Duration::seconds(1);
+    let message_id = send_scheduled(&tc, project_id, sender_id, recipient_id, deliver_at).await;
+
+    let by_recipient =
+        MessageBmc::cancel_scheduled(&tc.ctx, &tc.mm, message_id, recipient_id).await;
+    assert!(matches!(
+        by_recipient,
+        Err(mouchak_mail_core::Error::NotMessageSender(id)) if id == message_id
+    ));
+
+    MessageBmc::cancel_scheduled(&tc.ctx, &tc.mm, message_id, sender_id)
+        .await
+        .expect("Sender should be able to cancel");
+
+    let again = MessageBmc::cancel_scheduled(&tc.ctx, &tc.mm, message_id, sender_id).await;
+    assert!(matches!(
+        again,
+        Err(mouchak_mail_core::Error::InvalidInput(_))
+    ));
+
+    // Cancelled messages are never delivered, even when past due
+    let delivered = MessageBmc::deliver_due_scheduled(&tc.ctx, &tc.mm)
+        .await
+        .unwrap();
+    assert!(delivered.is_empty());
+    let inbox = MessageBmc::list_inbox_for_agent(&tc.ctx, &tc.mm, project_id, recipient_id, 10)
+        .await
+        .unwrap();
+    assert!(inbox.is_empty());
+
+    // Unscheduled messages cannot be cancelled
+    let plain_id = send_simple(&tc, project_id, sender_id, recipient_id).await;
+    let plain = MessageBmc::cancel_scheduled(&tc.ctx, &tc.mm, plain_id, sender_id).await;
+    assert!(matches!(
+        plain,
+        Err(mouchak_mail_core::Error::MessageNotFound(_))
+    ));
+}
+
+/// Test batch mark-read for one agent and for every recipient
+#[tokio::test]
+async fn test_mark_read_batch() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (project_id, sender_id, recipient_id) = setup_messaging(&tc).await;
+    let project = ProjectBmc::get(&tc.ctx, &tc.mm, ProjectId::new(project_id))
+        .await
+        .unwrap();
+    let other_c = AgentForCreate {
+        project_id: project.id,
+        name: "Observer".to_string(),
+        program: "test".to_string(),
+        model: "test".to_string(),
+        task_description: "Second recipient".to_string(),
+    };
+    let other_id: i64 = AgentBmc::create(&tc.ctx, &tc.mm, other_c)
+        .await
+        .unwrap()
+        .into();
+
+    let mut message_ids = Vec::new();
+    for subject in ["First", "Second", "Third"] {
+        let msg_c = MessageForCreate {
+            project_id,
+            sender_id,
+            recipient_ids: vec![recipient_id, other_id],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: subject.to_string(),
+            body_md: "Batch".to_string(),
+            thread_id: None,
+            importance: None,
+            ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
+        };
+        message_ids.push(MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap());
+    }
+
+    // One agent, two messages
+    let marked =
+        MessageBmc::mark_read_batch(&tc.ctx, &tc.mm, &message_ids[..2], Some(recipient_id))
+            .await
+            .unwrap();
+    assert_eq!(marked, vec![message_ids[0], message_ids[1]]);
+    let counts = MessageBmc::unread_counts(&tc.ctx, &tc.mm, ProjectId::new(project_id))
+        .await
+        .unwrap();
+    assert_eq!(counts.get(&recipient_id), Some(&1));
+    assert_eq!(counts.get(&other_id), Some(&3));
+
+    // Already-read rows are not reported again
+    let again = MessageBmc::mark_read_batch(&tc.ctx, &tc.mm, &message_ids[..2], Some(recipient_id))
+        .await
+        .unwrap();
+    assert!(again.is_empty());
+
+    // No agent: every recipient; unknown ids are ignored
+    let mut ids = message_ids.clone();
+    ids.push(999_999);
+    let marked = MessageBmc::mark_read_batch(&tc.ctx, &tc.mm, &ids, None)
+        .await
+        .unwrap();
+    assert_eq!(marked, message_ids);
+    let counts = MessageBmc::unread_counts(&tc.ctx, &tc.mm, ProjectId::new(project_id))
+        .await
+        .unwrap();
+    assert!(counts.is_empty());
+
+    // Empty and oversized batches
+    assert!(
+        MessageBmc::mark_read_batch(&tc.ctx, &tc.mm, &[], None)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    let too_many: Vec<i64> = (0..=MAX_BATCH_SIZE as i64).collect();
+    assert!(matches!(
+        MessageBmc::mark_read_batch(&tc.ctx, &tc.mm, &too_many, None).await,
+        Err(mouchak_mail_core::Error::InvalidInput(_))
+    ));
+}
+
+/// Test that batch mark-read is held to the caller's agent and projects
+#[tokio::test]
+async fn test_mark_read_batch_scoped_ctx() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (project_id, sender_id, recipient_id) = setup_messaging(&tc).await;
+    let project = ProjectBmc::get(&tc.ctx, &tc.mm, ProjectId::new(project_id))
+        .await
+        .unwrap();
+
+    let msg_c = MessageForCreate {
+        project_id,
+        sender_id,
+        recipient_ids: vec![recipient_id],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: "Scoped".to_string(),
+        body_md: "Batch".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    let ids = vec![MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap()];
+
+    // An agent key cannot mark another agent's rows, or every recipient's
+    let sender_ctx = Ctx::for_agent(AgentId::new(sender_id), "Sender", &project.slug);
+    assert!(matches!(
+        MessageBmc::mark_read_batch(&sender_ctx, &tc.mm, &ids, Some(recipient_id)).await,
+        Err(mouchak_mail_core::Error::AgentIdentityMismatch { .. })
+    ));
+    assert!(matches!(
+        MessageBmc::mark_read_batch(&sender_ctx, &tc.mm, &ids, None).await,
+        Err(mouchak_mail_core::Error::InvalidInput(_))
+    ));
+
+    // A context for another project cannot touch these messages
+    let other_ctx = Ctx::scoped(1, None, vec!["other-project".to_string()]);
+    assert!(matches!(
+        MessageBmc::mark_read_batch(&other_ctx, &tc.mm, &ids, Some(recipient_id)).await,
+        Err(mouchak_mail_core::Error::Forbidden(_))
+    ));
+
+    let recipient_ctx = Ctx::for_agent(AgentId::new(recipient_id), "Recipient", &project.slug);
+    let marked = MessageBmc::mark_read_batch(&recipient_ctx, &tc.mm, &ids, Some(recipient_id))
+        .await
+        .unwrap();
+    assert_eq!(marked, ids);
+}
+
+/// Test subject, body and recipient limits exactly at and just past each boundary
+#[tokio::test]
+async fn test_create_enforces_size_limits() {
+    let mut config = AppConfig::default();
+    config.messages.max_recipients = 2;
+    let tc = TestContext::new_with_config(config)
+        .await
+        .expect("Failed to create test context");
+    let (project_id, sender_id, recipient_id) = setup_messaging(&tc).await;
+
+    let msg = |subject: String, body_md: String, cc_ids: Option<Vec<i64>>| MessageForCreate {
+        project_id,
+        sender_id,
+        recipient_ids: vec![recipient_id],
+        cc_ids,
+        bcc_ids: None,
+        subject,
+        body_md,
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    let assert_limit = |result: mouchak_mail_core::Result<i64>, expected: &str| match result {
+        Err(mouchak_mail_core::Error::Validation(ve)) => {
+            assert!(ve.to_string().contains(expected), "{}", ve)
+        }
+        other => panic!("expected a validation error, got {:?}", other),
+    };
+
+    // Exactly at every limit is accepted
+    MessageBmc::create(
+        &tc.ctx,
+        &tc.mm,
+        msg(
+            "s".repeat(500),
+            "b".repeat(256 * 1024),
+            Some(vec![sender_id]),
+        ),
+    )
+    .await
+    .unwrap();
+
+    assert_limit(
+        MessageBmc::create(&tc.ctx, &tc.mm, msg("s".repeat(501), String::new(), None)).await,
+        "subject exceeds the limit of 500 characters",
+    );
+    assert_limit(
+        MessageBmc::create(
+            &tc.ctx,
+            &tc.mm,
+            msg("Big".to_string(), "b".repeat(256 * 1024 + 1), None),
+        )
+        .await,
+        "body_md exceeds the limit of 262144 bytes",
+    );
+    assert_limit(
+        MessageBmc::create(
+            &tc.ctx,
+            &tc.mm,
+            msg(
+                "Crowded".to_string(),
+                String::new(),
+                Some(vec![sender_id, recipient_id]),
+            ),
+        )
+        .await,
+        "recipients exceeds the limit of 2 recipients",
+    );
+}
+
+/// Test a broadcast reaches every active agent but the sender and is listed afterwards
+#[tokio::test]
+async fn test_broadcast_message() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (project_id, sender_id, recipient_id) = setup_messaging(&tc).await;
+
+    let retired_c = AgentForCreate {
+        project_id: ProjectId::new(project_id),
+        name: "Retired".to_string(),
+        program: "test".to_string(),
+        model: "test".to_string(),
+        task_description: "Retired agent".to_string(),
+    };
+    let retired_id = AgentBmc::create(&tc.ctx, &tc.mm, retired_c).await.unwrap();
+    AgentBmc::retire(&tc.ctx, &tc.mm, retired_id).await.unwrap();
+
+    let broadcast = |recipient_ids: Vec<i64>| MessageForCreate {
+        project_id,
+        sender_id,
+        recipient_ids,
+        cc_ids: None,
+        bcc_ids: None,
+        subject: "All hands".to_string(),
+        body_md: "Freeze merges until the release is cut.".to_string(),
+        thread_id: None,
+        importance: Some("high".to_string()),
+        ack_required: false,
+        deliver_at: None,
+        broadcast: true,
+        allow_new_thread: false,
+    };
+
+    let msg_id = MessageBmc::create(&tc.ctx, &tc.mm, broadcast(vec![]))
+        .await
+        .expect("Failed to broadcast");
+    let recipients = MessageBmc::get_recipients(&tc.ctx, &tc.mm, msg_id)
+        .await
+        .unwrap();
+    assert_eq!(recipients, vec!["Recipient".to_string()]);
+
+    // Explicit recipients and broadcast are mutually exclusive
+    let result = MessageBmc::create(&tc.ctx, &tc.mm, broadcast(vec![recipient_id])).await;
+    assert!(result.is_err());
+
+    // Agents registering later can catch up on earlier broadcasts
+    let broadcasts = MessageBmc::list_broadcasts(&tc.ctx, &tc.mm, ProjectId::new(project_id), None)
+        .await
+        .unwrap();
+    assert_eq!(broadcasts.len(), 1);
+    assert_eq!(broadcasts[0].id, msg_id);
+
+    let future = chrono::Utc::now().naive_utc() + chrono::Duration::hours(1);
+    let broadcasts =
+        MessageBmc::list_broadcasts(&tc.ctx, &tc.mm, ProjectId::new(project_id), Some(future))
+            .await
+            .unwrap();
+    assert!(broadcasts.is_empty());
+}
+
+/// Test a broadcast with nobody to receive it is rejected
+#[tokio::test]
+async fn test_broadcast_rejected_without_agents() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+
+    let human_key = "/messaging/lonely";
+    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, &slugify(human_key), human_key)
+        .await
+        .unwrap();
+    let sender_c = AgentForCreate {
+        project_id,
+        name: "Solo".to_string(),
+        program: "test".to_string(),
+        model: "test".to_string(),
+        task_description: "Only agent".to_string(),
+    };
+    let sender_id = AgentBmc::create(&tc.ctx, &tc.mm, sender_c).await.unwrap();
+
+    let msg_c = MessageForCreate {
+        project_id: project_id.get(),
+        sender_id: sender_id.into(),
+        recipient_ids: vec![],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: "Anyone?".to_string(),
+        body_md: "Hello".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: true,
+        allow_new_thread: false,
+    };
+    let err = MessageBmc::create(&tc.ctx, &tc.mm, msg_c)
+        .await
+        .expect_err("Broadcast without agents should fail");
+    assert!(err.to_string().contains("no other active agents"));
 }
@@ -0,0 +1,313 @@
+//! Notification center tests
+//!
+//! Notifications are derived from pending acks, acks owed by the actor,
+//! unread urgent mail and reservation overlaps, and dismissed per actor.
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use crate::common::TestContext;
+use mouchak_mail_core::Ctx;
+use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
+use mouchak_mail_core::model::file_reservation::{FileReservationBmc, FileReservationForCreate};
+use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
+use mouchak_mail_core::model::notification::{
+    DEFAULT_NOTIFICATION_ACTOR, NotificationBmc, NotificationKind,
+};
+use mouchak_mail_core::model::project::ProjectBmc;
+use mouchak_mail_core::types::{AgentId, ProjectId};
+
+mod common;
+
+struct Fixture {
+    project_id: ProjectId,
+    overseer: AgentId,
+    worker: AgentId,
+    other: AgentId,
+}
+
+async fn setup(tc: &TestContext, slug: &str) -> Fixture {
+    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, slug, &format!("/notify/{}", slug))
+        .await
+        .unwrap();
+
+    let mut ids = Vec::new();
+    for name in [DEFAULT_NOTIFICATION_ACTOR, "Worker", "Other"] {
+        let agent_c = AgentForCreate {
+            project_id,
+            name: name.to_string(),
+            program: "test".to_string(),
+            model: "test".to_string(),
+            task_description: String::new(),
+        };
+        ids.push(AgentBmc::create(&tc.ctx, &tc.mm, agent_c).await.unwrap());
+    }
+
+    Fixture {
+        project_id,
+        overseer: ids[0],
+        worker: ids[1],
+        other: ids[2],
+    }
+}
+
+async fn send(
+    tc: &TestContext,
+    fx: &Fixture,
+    sender: AgentId,
+    recipients: &[AgentId],
+    importance: &str,
+    ack_required: bool,
+) -> i64 {
+    let msg_c = MessageForCreate {
+        project_id: fx.project_id.get(),
+        sender_id: sender.get(),
+        recipient_ids: recipients.iter().map(|id| id.get()).collect(),
+        cc_ids: None,
+        bcc_ids: None,
+        subject: format!("{} directive", importance),
+        body_md: "body".to_string(),
+        thread_id: None,
+        importance: Some(importance.to_string()),
+        ack_required,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap()
+}
+
+async fn reserve(tc: &TestContext, fx: &Fixture, agent: AgentId, path: &str) -> i64 {
+    let fr_c = FileReservationForCreate {
+        project_id: fx.project_id,
+        agent_id: agent,
+        path_pattern: path.to_string(),
+        exclusive: true,
+        reason: "editing".to_string(),
+        expires_ts: chrono::Utc::now().naive_utc() + chrono::Duration::hours(1),
+    };
+    FileReservationBmc::create(&tc.ctx, &tc.mm, fr_c)
+        .await
+        .unwrap()
+}
+
+fn agent_ctx(name: &str) -> Ctx {
+    Ctx::scoped(0, Some(name.to_string()), vec!["*".to_string()])
+}
+
+#[tokio::test]
+async fn test_pending_acks_until_everyone_acknowledges() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "acks").await;
+
+    let msg_id = send(
+        &tc,
+        &fx,
+        fx.overseer,
+        &[fx.worker, fx.other],
+        "normal",
+        true,
+    )
+    .await;
+    // Without ack_required there is nothing to wait for
+    send(&tc, &fx, fx.overseer, &[fx.worker], "normal", false).await;
+
+    let notifications = NotificationBmc::list_for_actor(&tc.ctx, &tc.mm)
+        .await
+        .unwrap();
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].kind, NotificationKind::AckPending);
+    assert_eq!(notifications[0].message_id, Some(msg_id));
+    assert_eq!(notifications[0].project_slug, "acks");
+    assert_eq!(notifications[0].title, "2 of 2 acknowledgements pending");
+
+    MessageBmc::acknowledge(&tc.ctx, &tc.mm, msg_id, fx.worker.get())
+        .await
+        .unwrap();
+    let notifications = NotificationBmc::list_for_actor(&tc.ctx, &tc.mm)
+        .await
+        .unwrap();
+    assert_eq!(notifications[0].title, "1 of 2 acknowledgements pending");
+
+    MessageBmc::acknowledge(&tc.ctx, &tc.mm, msg_id, fx.other.get())
+        .await
+        .unwrap();
+    assert!(
+        NotificationBmc::list_for_actor(&tc.ctx, &tc.mm)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_acks_requested_of_actor() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "owed").await;
+
+    let msg_id = send(&tc, &fx, fx.worker, &[fx.overseer], "normal", true).await;
+
+    let notifications = NotificationBmc::list_for_actor(&tc.ctx, &tc.mm)
+        .await
+        .unwrap();
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].kind, NotificationKind::AckRequested);
+    assert_eq!(notifications[0].message_id, Some(msg_id));
+    assert_eq!(notifications[0].project_slug, "owed");
+    assert_eq!(
+        notifications[0].title,
+        "Worker asked for your acknowledgement"
+    );
+
+    MessageBmc::acknowledge(&tc.ctx, &tc.mm, msg_id, fx.overseer.get())
+        .await
+        .unwrap();
+    assert!(
+        NotificationBmc::list_for_actor(&tc.ctx, &tc.mm)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_unread_high_importance_to_actor() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "urgent").await;
+
+    let urgent = send(&tc, &fx, fx.worker, &[fx.overseer], "urgent", false).await;
+    send(&tc, &fx, fx.worker, &[fx.overseer], "normal", false).await;
+    // High importance, but for someone else
+    send(&tc, &fx, fx.worker, &[fx.other], "high", false).await;
+
+    let notifications = NotificationBmc::list_for_actor(&tc.ctx, &tc.mm)
+        .await
+        .unwrap();
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].kind, NotificationKind::UrgentUnread);
+    assert_eq!(notifications[0].message_id, Some(urgent));
+    assert_eq!(notifications[0].title, "Unread urgent message from Worker");
+
+    MessageBmc::mark_read(&tc.ctx, &tc.mm, urgent, fx.overseer.get())
+        .await
+        .unwrap();
+    assert!(
+        NotificationBmc::list_for_actor(&tc.ctx, &tc.mm)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_reservation_conflicts_seen_by_both_holders() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "locks").await;
+
+    let mine = reserve(&tc, &fx, fx.overseer, "src/**").await;
+    let theirs = reserve(&tc, &fx, fx.worker, "src/api/**").await;
+    // Disjoint paths do not conflict
+    reserve(&tc, &fx, fx.other, "docs/**").await;
+
+    let notifications = NotificationBmc::list_for_actor(&tc.ctx, &tc.mm)
+        .await
+        .unwrap();
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].kind, NotificationKind::ReservationConflict);
+    assert_eq!(notifications[0].reservation_id, Some(mine));
+    assert_eq!(
+        notifications[0].key,
+        format!("conflict:{}:{}", mine, theirs)
+    );
+
+    let worker = NotificationBmc::list_for_actor(&agent_ctx("Worker"), &tc.mm)
+        .await
+        .unwrap();
+    assert_eq!(worker.len(), 1);
+    assert_eq!(worker[0].reservation_id, Some(theirs));
+
+    FileReservationBmc::release(&tc.ctx, &tc.mm, theirs)
+        .await
+        .unwrap();
+    assert!(
+        NotificationBmc::list_for_actor(&tc.ctx, &tc.mm)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_dismiss_is_per_actor() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "dismiss").await;
+
+    send(&tc, &fx, fx.worker, &[fx.overseer], "high", false).await;
+    let ack = send(&tc, &fx, fx.overseer, &[fx.worker], "normal", true).await;
+
+    let notifications = NotificationBmc::list_for_actor(&tc.ctx, &tc.mm)
+        .await
+        .unwrap();
+    assert_eq!(notifications.len(), 2);
+
+    let key = format!("ack:{}", ack);
+    assert_eq!(
+        NotificationBmc::dismiss(&tc.ctx, &tc.mm, std::slice::from_ref(&key))
+            .await
+            .unwrap(),
+        1
+    );
+    // Dismissing again is a no-op
+    assert_eq!(
+        NotificationBmc::dismiss(&tc.ctx, &tc.mm, std::slice::from_ref(&key))
+            .await
+            .unwrap(),
+        0
+    );
+
+    let notifications = NotificationBmc::list_for_actor(&tc.ctx, &tc.mm)
+        .await
+        .unwrap();
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].kind, NotificationKind::UrgentUnread);
+
+    // A dismissal by one actor does not hide anything for another
+    let worker = agent_ctx("Worker");
+    NotificationBmc::dismiss(&worker, &tc.mm, &[notifications[0].key.clone()])
+        .await
+        .unwrap();
+    assert_eq!(
+        NotificationBmc::list_for_actor(&tc.ctx, &tc.mm)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_scoped_context_skips_other_projects() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "visible").await;
+    let hidden = setup(&tc, "hidden").await;
+
+    send(&tc, &fx, fx.worker, &[fx.overseer], "high", false).await;
+    send(
+        &tc,
+        &hidden,
+        hidden.worker,
+        &[hidden.overseer],
+        "high",
+        false,
+    )
+    .await;
+
+    let ctx = Ctx::scoped(
+        0,
+        Some(DEFAULT_NOTIFICATION_ACTOR.to_string()),
+        vec!["visible".to_string()],
+    );
+    let notifications = NotificationBmc::list_for_actor(&ctx, &tc.mm).await.unwrap();
+    assert_eq!(notifications.len(), 1);
+    assert_eq!(notifications[0].project_slug, "visible");
+}
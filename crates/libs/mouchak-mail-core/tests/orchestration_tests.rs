@@ -61,6 +61,9 @@ async fn send_message(
         thread_id,
         importance: Some("high".to_string()),
         ack_required,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg)
         .await
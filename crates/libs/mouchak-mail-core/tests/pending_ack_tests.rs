@@ -0,0 +1,193 @@
+//! Pending acknowledgement tests
+//!
+//! An agent's pending acks are the ack_required messages addressed to it
+//! that it has not acknowledged yet, oldest first.
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use mouchak_mail_common::config::AppConfig;
+use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
+use mouchak_mail_core::testing::TestEnv;
+
+/// Project with a sender, a reader and a bystander; returns (project_id, agent ids).
+async fn setup(tc: &TestEnv, slug: &str) -> (i64, Vec<i64>) {
+    let fixture = tc
+        .fixtures()
+        .project(format!("/acks/{}", slug))
+        .agents(["Sender", "Reader", "Bystander"])
+        .build()
+        .await
+        .unwrap();
+    let ids = fixture.agents.iter().map(|(_, id)| id.get()).collect();
+    (fixture.project_id.get(), ids)
+}
+
+async fn send(
+    tc: &TestEnv,
+    project_id: i64,
+    sender_id: i64,
+    to: Vec<i64>,
+    cc: Vec<i64>,
+    bcc: Vec<i64>,
+    ack_required: bool,
+) -> i64 {
+    let msg_c = MessageForCreate {
+        project_id,
+        sender_id,
+        recipient_ids: to,
+        cc_ids: Some(cc),
+        bcc_ids: Some(bcc),
+        subject: "Please confirm".to_string(),
+        body_md: "body".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap()
+}
+
+async fn pending_ids(tc: &TestEnv, project_id: i64, agent_id: i64) -> Vec<i64> {
+    MessageBmc::list_pending_acks_for_agent(&tc.ctx, &tc.mm, project_id, agent_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|ack| ack.message_id)
+        .collect()
+}
+
+#[tokio::test]
+async fn test_acked_messages_drop_out() {
+    let tc = TestEnv::new().await.unwrap();
+    let (project_id, ids) = setup(&tc, "acked").await;
+    let (sender, reader) = (ids[0], ids[1]);
+
+    let older = send(&tc, project_id, sender, vec![reader], vec![], vec![], true).await;
+    let newer = send(&tc, project_id, sender, vec![reader], vec![], vec![], true).await;
+    let no_ack = send(&tc, project_id, sender, vec![reader], vec![], vec![], false).await;
+    tc.mm
+        .db_for_test()
+        .execute(
+            "UPDATE messages SET created_ts = datetime('now', '-3 days') WHERE id = ?",
+            [older],
+        )
+        .await
+        .unwrap();
+
+    let pending = MessageBmc::list_pending_acks_for_agent(&tc.ctx, &tc.mm, project_id, reader)
+        .await
+        .unwrap();
+    assert_eq!(
+        pending.iter().map(|a| a.message_id).collect::<Vec<_>>(),
+        vec![older, newer]
+    );
+    assert_eq!(pending[0].days_outstanding, 3);
+    assert_eq!(pending[1].days_outstanding, 0);
+    assert_eq!(pending[0].sender_name, "Sender");
+    assert_eq!(pending[0].recipient_type, "to");
+    // The sender owes nothing
+    assert!(pending_ids(&tc, project_id, sender).await.is_empty());
+
+    let inbox = MessageBmc::list_inbox_for_agent(&tc.ctx, &tc.mm, project_id, reader, 10)
+        .await
+        .unwrap();
+    let needs_ack = |id: i64| inbox.iter().find(|m| m.id == id).unwrap().needs_ack;
+    assert!(needs_ack(older) && needs_ack(newer));
+    assert!(!needs_ack(no_ack));
+
+    MessageBmc::acknowledge(&tc.ctx, &tc.mm, older, reader)
+        .await
+        .unwrap();
+    assert_eq!(pending_ids(&tc, project_id, reader).await, vec![newer]);
+    let inbox = MessageBmc::list_inbox_for_agent(&tc.ctx, &tc.mm, project_id, reader, 10)
+        .await
+        .unwrap();
+    assert!(!inbox.iter().find(|m| m.id == older).unwrap().needs_ack);
+}
+
+#[tokio::test]
+async fn test_recalled_messages_drop_out() {
+    let tc = TestEnv::new().await.unwrap();
+    let (project_id, ids) = setup(&tc, "recalled").await;
+    let (sender, reader) = (ids[0], ids[1]);
+
+    let recalled = send(&tc, project_id, sender, vec![reader], vec![], vec![], true).await;
+    let kept = send(&tc, project_id, sender, vec![reader], vec![], vec![], true).await;
+    MessageBmc::recall(&tc.ctx, &tc.mm, recalled, sender, "sent too early")
+        .await
+        .unwrap();
+
+    assert_eq!(pending_ids(&tc, project_id, reader).await, vec![kept]);
+    let inbox = MessageBmc::list_inbox_for_agent(&tc.ctx, &tc.mm, project_id, reader, 10)
+        .await
+        .unwrap();
+    assert!(!inbox.iter().find(|m| m.id == recalled).unwrap().needs_ack);
+}
+
+#[tokio::test]
+async fn test_cc_recipients_only_when_configured() {
+    let tc = TestEnv::new().await.unwrap();
+    let (project_id, ids) = setup(&tc, "cc-off").await;
+    let (sender, reader, bystander) = (ids[0], ids[1], ids[2]);
+
+    send(
+        &tc,
+        project_id,
+        sender,
+        vec![bystander],
+        vec![reader],
+        vec![],
+        true,
+    )
+    .await;
+    assert!(pending_ids(&tc, project_id, reader).await.is_empty());
+
+    let mut config = AppConfig::default();
+    config.messages.pending_acks_include_cc = true;
+    let tc = TestEnv::with_config(config).await.unwrap();
+    let (project_id, ids) = setup(&tc, "cc-on").await;
+    let (sender, reader, bystander) = (ids[0], ids[1], ids[2]);
+
+    let cc = send(
+        &tc,
+        project_id,
+        sender,
+        vec![bystander],
+        vec![reader],
+        vec![],
+        true,
+    )
+    .await;
+    // bcc recipients never owe an acknowledgement
+    send(
+        &tc,
+        project_id,
+        sender,
+        vec![bystander],
+        vec![],
+        vec![reader],
+        true,
+    )
+    .await;
+
+    let pending = MessageBmc::list_pending_acks_for_agent(&tc.ctx, &tc.mm, project_id, reader)
+        .await
+        .unwrap();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].message_id, cc);
+    assert_eq!(pending[0].recipient_type, "cc");
+    let inbox = MessageBmc::list_inbox_for_agent(&tc.ctx, &tc.mm, project_id, reader, 10)
+        .await
+        .unwrap();
+    assert_eq!(
+        inbox
+            .iter()
+            .filter(|m| m.needs_ack)
+            .map(|m| m.id)
+            .collect::<Vec<_>>(),
+        vec![cc]
+    );
+}
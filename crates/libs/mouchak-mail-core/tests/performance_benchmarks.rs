@@ -26,7 +26,9 @@ use mouchak_mail_common::config::AppConfig;
 use mouchak_mail_core::ctx::Ctx;
 use mouchak_mail_core::model::ModelManager;
 use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
-use mouchak_mail_core::model::export::{ExportBmc, ExportFormat, ScrubMode};
+use mouchak_mail_core::model::export::{
+    ExportBmc, ExportCompression, ExportFilter, ExportFormat, ScrubMode,
+};
 use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
 use mouchak_mail_core::model::project::ProjectBmc;
 use std::sync::Arc;
@@ -48,16 +50,9 @@ async fn create_test_mm() -> (ModelManager, TempDir) {
     let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
     let _ = conn.execute("PRAGMA synchronous=NORMAL;", ()).await;
 
-    // Run migrations inline (same as store/mod.rs)
-    let migrations = [
-        include_str!("../../../../migrations/001_initial_schema.sql"),
-        include_str!("../../../../migrations/002_agent_capabilities.sql"),
-        include_str!("../../../../migrations/003_tool_metrics.sql"),
-        include_str!("../../../../migrations/004_attachments.sql"),
-    ];
-    for migration in &migrations {
-        conn.execute_batch(migration).await.expect("run migration");
-    }
+    mouchak_mail_core::store::apply_migrations(&conn)
+        .await
+        .expect("run migrations");
 
     let app_config = Arc::new(AppConfig::default());
     let mm = ModelManager::new_for_test(conn, temp_dir.path().to_path_buf(), app_config);
@@ -121,6 +116,9 @@ fn make_message(
         thread_id: None,
         importance: Some("normal".to_string()),
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     }
 }
 
@@ -174,6 +172,8 @@ async fn test_small_bundle_export_performance() {
         ExportFormat::Markdown,
         ScrubMode::None,
         false,
+        &ExportFilter::default(),
+        ExportCompression::None,
     )
     .await
     .expect("Export should succeed");
@@ -216,6 +216,8 @@ async fn test_medium_bundle_export_performance() {
         ExportFormat::Json,
         ScrubMode::None,
         false,
+        &ExportFilter::default(),
+        ExportCompression::None,
     )
     .await
     .expect("Export should succeed");
@@ -238,14 +240,14 @@ async fn test_medium_bundle_export_performance() {
     );
 }
 
-/// Test 3: Large bundle export performance (100 messages, capped by ExportBmc)
+/// Test 3: Large bundle export performance (100 messages)
 #[tokio::test]
 async fn test_large_bundle_export_performance() {
     let (mm, _temp) = create_test_mm().await;
     let ctx = Ctx::root_ctx();
     let (project_id, sender_id, recipient_id) = setup_test_project(&mm).await;
 
-    // Create large message bundle (ExportBmc caps at 100)
+    // Create large message bundle (the Markdown and CSV export cap)
     create_messages(&ctx, &mm, project_id, sender_id, recipient_id, 100).await;
 
     // Measure export time for all formats
@@ -258,10 +260,18 @@ async fn test_large_bundle_export_performance() {
 
     for (format, name) in formats {
         let start = Instant::now();
-        let result =
-            ExportBmc::export_mailbox(&ctx, &mm, "perf-test", format, ScrubMode::None, false)
-                .await
-                .expect("Export should succeed");
+        let result = ExportBmc::export_mailbox(
+            &ctx,
+            &mm,
+            "perf-test",
+            format,
+            ScrubMode::None,
+            false,
+            &ExportFilter::default(),
+            ExportCompression::None,
+        )
+        .await
+        .expect("Export should succeed");
         let duration = start.elapsed();
 
         // Performance check: large export should be under 1 second
@@ -315,6 +325,8 @@ async fn test_database_compressibility() {
         ExportFormat::Json,
         ScrubMode::None,
         false,
+        &ExportFilter::default(),
+        ExportCompression::None,
     )
     .await
     .expect("Export should succeed");
@@ -378,6 +390,8 @@ async fn test_chunk_size_validation() {
         ExportFormat::Markdown,
         ScrubMode::None,
         false,
+        &ExportFilter::default(),
+        ExportCompression::None,
     )
     .await
     .expect("Export should succeed");
@@ -416,6 +430,8 @@ async fn test_vacuum_improves_locality() {
         ExportFormat::Json,
         ScrubMode::None,
         false,
+        &ExportFilter::default(),
+        ExportCompression::None,
     )
     .await
     .expect("Export should succeed");
@@ -430,6 +446,8 @@ async fn test_vacuum_improves_locality() {
         ExportFormat::Json,
         ScrubMode::None,
         false,
+        &ExportFilter::default(),
+        ExportCompression::None,
     )
     .await
     .expect("Export should succeed");
@@ -536,6 +554,8 @@ async fn test_export_scales_linearly() {
             ExportFormat::Json,
             ScrubMode::None,
             false,
+            &ExportFilter::default(),
+            ExportCompression::None,
         )
         .await
         .expect("Export should succeed");
@@ -13,8 +13,9 @@
 mod common;
 
 use crate::common::TestContext;
+use mouchak_mail_common::config::{AppConfig, ArchiveLayout};
 use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
-use mouchak_mail_core::model::project::ProjectBmc;
+use mouchak_mail_core::model::project::{AgentConflictPolicy, ProjectBmc};
 use mouchak_mail_core::utils::slugify;
 
 /// Test creating a new project
@@ -151,6 +152,9 @@ async fn test_adopt_project() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = mouchak_mail_core::model::message::MessageBmc::create(&tc.ctx, &tc.mm, msg_c)
         .await
@@ -164,9 +168,18 @@ async fn test_adopt_project() {
         .unwrap();
 
     // 5. Perform Adopt (src -> dest)
-    ProjectBmc::adopt(&tc.ctx, &tc.mm, src_id, dest_id)
-        .await
-        .expect("Adopt failed");
+    let report = ProjectBmc::adopt(
+        &tc.ctx,
+        &tc.mm,
+        src_id,
+        dest_id,
+        &AgentConflictPolicy::Report,
+    )
+    .await
+    .expect("Adopt failed");
+    assert_eq!(report.agents_moved, 1);
+    assert_eq!(report.messages_moved, 1);
+    assert!(report.conflicts.is_empty());
 
     // 6. Verify Artifacts Moved
     // Check Agent
@@ -187,6 +200,192 @@ async fn test_adopt_project() {
     );
 }
 
+/// Creates source and destination projects that both have an agent named `shared`.
+async fn setup_conflicting_projects(
+    tc: &TestContext,
+) -> (
+    mouchak_mail_core::types::ProjectId,
+    mouchak_mail_core::types::ProjectId,
+    mouchak_mail_core::types::AgentId,
+    mouchak_mail_core::types::AgentId,
+) {
+    let src_id = ProjectBmc::create(&tc.ctx, &tc.mm, "adopt-src", "/adopt/src")
+        .await
+        .unwrap();
+    let dest_id = ProjectBmc::create(&tc.ctx, &tc.mm, "adopt-dest", "/adopt/dest")
+        .await
+        .unwrap();
+
+    let mut ids = Vec::new();
+    for project_id in [src_id, dest_id] {
+        let agent = AgentForCreate {
+            project_id,
+            name: "shared".into(),
+            program: "test".into(),
+            model: "test".into(),
+            task_description: "test".into(),
+        };
+        ids.push(AgentBmc::create(&tc.ctx, &tc.mm, agent).await.unwrap());
+    }
+
+    let msg_c = mouchak_mail_core::model::message::MessageForCreate {
+        project_id: src_id.get(),
+        sender_id: ids[0].into(),
+        recipient_ids: vec![ids[0].into()],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: "Before adopt".into(),
+        body_md: "Content".into(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    mouchak_mail_core::model::message::MessageBmc::create(&tc.ctx, &tc.mm, msg_c)
+        .await
+        .unwrap();
+
+    (src_id, dest_id, ids[0], ids[1])
+}
+
+#[tokio::test]
+async fn test_plan_adopt_reports_conflicts_without_writing() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (src_id, dest_id, src_agent, _) = setup_conflicting_projects(&tc).await;
+
+    let report = ProjectBmc::plan_adopt(
+        &tc.ctx,
+        &tc.mm,
+        src_id,
+        dest_id,
+        &AgentConflictPolicy::Report,
+    )
+    .await
+    .unwrap();
+    assert_eq!(report.messages_moved, 1);
+    assert_eq!(report.conflicts.len(), 1);
+    assert!(report.conflicts[0].contains("shared"));
+
+    // Nothing moved
+    let agent = AgentBmc::get(&tc.ctx, &tc.mm, src_agent).await.unwrap();
+    assert_eq!(agent.project_id, src_id);
+
+    // adopt refuses while conflicts remain
+    let result = ProjectBmc::adopt(
+        &tc.ctx,
+        &tc.mm,
+        src_id,
+        dest_id,
+        &AgentConflictPolicy::Report,
+    )
+    .await;
+    assert!(matches!(
+        result,
+        Err(mouchak_mail_core::Error::InvalidInput(_))
+    ));
+    let agent = AgentBmc::get(&tc.ctx, &tc.mm, src_agent).await.unwrap();
+    assert_eq!(agent.project_id, src_id);
+}
+
+#[tokio::test]
+async fn test_adopt_merges_conflicting_agents() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (src_id, dest_id, src_agent, dest_agent) = setup_conflicting_projects(&tc).await;
+
+    let report = ProjectBmc::adopt(
+        &tc.ctx,
+        &tc.mm,
+        src_id,
+        dest_id,
+        &AgentConflictPolicy::Merge,
+    )
+    .await
+    .unwrap();
+    assert_eq!(report.agents_merged, vec!["shared".to_string()]);
+    assert_eq!(report.agents_moved, 0);
+
+    // Source agent is gone; its message now belongs to the destination agent
+    assert!(AgentBmc::get(&tc.ctx, &tc.mm, src_agent).await.is_err());
+    let inbox = mouchak_mail_core::model::message::MessageBmc::list_inbox_for_agent(
+        &tc.ctx,
+        &tc.mm,
+        dest_id.get(),
+        dest_agent.get(),
+        10,
+    )
+    .await
+    .unwrap();
+    assert_eq!(inbox.len(), 1);
+}
+
+#[tokio::test]
+async fn test_adopt_renames_conflicting_agents() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (src_id, dest_id, src_agent, _) = setup_conflicting_projects(&tc).await;
+
+    let policy = AgentConflictPolicy::RenameSuffix("-src".into());
+    let report = ProjectBmc::adopt(&tc.ctx, &tc.mm, src_id, dest_id, &policy)
+        .await
+        .unwrap();
+    assert_eq!(
+        report.agents_renamed,
+        vec![("shared".to_string(), "shared-src".to_string())]
+    );
+
+    let agent = AgentBmc::get(&tc.ctx, &tc.mm, src_agent).await.unwrap();
+    assert_eq!(agent.name, "shared-src");
+    assert_eq!(agent.project_id, dest_id);
+    assert!(!tc.mm.repo_root.join("projects").join("adopt-src").exists());
+}
+
+#[tokio::test]
+async fn test_adopt_across_per_project_repos() {
+    let mut config = AppConfig::default();
+    config.archive.layout = ArchiveLayout::PerProject;
+    let tc = TestContext::new_with_config(config)
+        .await
+        .expect("Failed to create test context");
+    let (src_id, dest_id, _, _) = setup_conflicting_projects(&tc).await;
+
+    let policy = AgentConflictPolicy::RenameSuffix("-src".into());
+    ProjectBmc::adopt(&tc.ctx, &tc.mm, src_id, dest_id, &policy)
+        .await
+        .unwrap();
+
+    let src_repo = tc.repo_root().join("adopt-src");
+    let dest_repo = tc.repo_root().join("adopt-dest");
+    assert!(!src_repo.join("projects").join("adopt-src").exists());
+    assert!(
+        dest_repo
+            .join("projects")
+            .join("adopt-dest")
+            .join("messages")
+            .exists()
+    );
+
+    // The source repository keeps its history; the destination records where
+    // the files came from
+    let src_head = git2::Repository::open(&src_repo)
+        .unwrap()
+        .head()
+        .unwrap()
+        .target()
+        .unwrap();
+    let dest = git2::Repository::open(&dest_repo).unwrap();
+    let dest_head = dest.head().unwrap().peel_to_commit().unwrap();
+    let message = dest_head.message().unwrap();
+    assert!(message.contains("adopt project adopt-src into adopt-dest"));
+    assert!(message.contains(&src_head.to_string()));
+}
+
 #[tokio::test]
 async fn test_delete_project_cascade() {
     let tc = TestContext::new()
@@ -221,6 +420,9 @@ async fn test_delete_project_cascade() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     mouchak_mail_core::model::message::MessageBmc::create(&tc.ctx, &tc.mm, msg)
         .await
@@ -250,3 +452,209 @@ async fn test_delete_nonexistent_project() {
         ProjectBmc::delete(&tc.ctx, &tc.mm, mouchak_mail_core::types::ProjectId(99999)).await;
     assert!(result.is_err(), "Deleting nonexistent project should fail");
 }
+
+/// Test that a project-scoped context cannot see or touch other projects
+#[tokio::test]
+async fn test_scoped_ctx_forbids_other_projects() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+
+    let a_id = ProjectBmc::create(&tc.ctx, &tc.mm, "project-a", "/project/a")
+        .await
+        .expect("Failed to create project a");
+    let b_id = ProjectBmc::create(&tc.ctx, &tc.mm, "project-b", "/project/b")
+        .await
+        .expect("Failed to create project b");
+
+    let scoped = mouchak_mail_core::Ctx::scoped(1, None, vec!["project-a".to_string()]);
+
+    let project = ProjectBmc::get(&scoped, &tc.mm, a_id)
+        .await
+        .expect("Scoped ctx should read its own project");
+    assert_eq!(project.slug, "project-a");
+
+    let result = ProjectBmc::get_by_slug(&scoped, &tc.mm, "project-b").await;
+    assert!(matches!(
+        result,
+        Err(mouchak_mail_core::Error::Forbidden(_))
+    ));
+
+    let result = AgentBmc::list_all_for_project(&scoped, &tc.mm, b_id).await;
+    assert!(matches!(
+        result,
+        Err(mouchak_mail_core::Error::Forbidden(_))
+    ));
+
+    let visible = ProjectBmc::list_all(&scoped, &tc.mm)
+        .await
+        .expect("Failed to list projects");
+    assert_eq!(visible.len(), 1);
+    assert_eq!(visible[0].slug, "project-a");
+}
+
+#[tokio::test]
+async fn test_resolve_from_cwd_uses_marker_in_parent_dir() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, "marked-project", "/marked/project")
+        .await
+        .unwrap();
+
+    let workdir = tempfile::tempdir().unwrap();
+    let nested = workdir.path().join("crates").join("inner").join("src");
+    std::fs::create_dir_all(&nested).unwrap();
+    std::fs::write(
+        workdir.path().join(".mouchak-mail-project-id"),
+        "marked-project\n",
+    )
+    .unwrap();
+
+    let project = ProjectBmc::resolve_from_cwd(&tc.ctx, &tc.mm, &nested)
+        .await
+        .unwrap();
+    assert_eq!(project.id, project_id);
+}
+
+/// Test that stats aggregate agents, messages, threads and reservations
+#[tokio::test]
+async fn test_project_stats() {
+    use mouchak_mail_core::model::file_reservation::{
+        FileReservationBmc, FileReservationForCreate,
+    };
+    use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
+
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+
+    let project_id = ProjectBmc::create(ctx, mm, "stats-busy", "/stats/busy")
+        .await
+        .unwrap();
+    let idle_id = ProjectBmc::create(ctx, mm, "stats-idle", "/stats/idle")
+        .await
+        .unwrap();
+
+    let mut agent_ids = Vec::new();
+    for name in ["stats-sender", "stats-recipient"] {
+        let agent = AgentForCreate {
+            project_id,
+            name: name.into(),
+            program: "test".into(),
+            model: "test".into(),
+            task_description: "Stats".into(),
+        };
+        agent_ids.push(AgentBmc::create(ctx, mm, agent).await.unwrap());
+    }
+
+    for thread_id in [Some("t-1"), Some("t-1"), None] {
+        let msg = MessageForCreate {
+            project_id: project_id.get(),
+            sender_id: agent_ids[0].into(),
+            recipient_ids: vec![agent_ids[1].into()],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: "Stats".into(),
+            body_md: "Body".into(),
+            thread_id: thread_id.map(String::from),
+            importance: None,
+            ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: true,
+        };
+        MessageBmc::create(ctx, mm, msg).await.unwrap();
+    }
+    // One message from earlier in the week
+    mm.db_for_test()
+        .execute(
+            "UPDATE messages SET created_ts = datetime('now', '-3 days') WHERE thread_id IS NOT 't-1'",
+            (),
+        )
+        .await
+        .unwrap();
+
+    let mut reservation_ids = Vec::new();
+    for path in ["src/lib.rs", "src/main.rs"] {
+        let fr_c = FileReservationForCreate {
+            project_id,
+            agent_id: agent_ids[0],
+            path_pattern: path.into(),
+            exclusive: true,
+            reason: "Stats".into(),
+            expires_ts: chrono::Utc::now().naive_utc() + chrono::Duration::hours(1),
+        };
+        reservation_ids.push(FileReservationBmc::create(ctx, mm, fr_c).await.unwrap());
+    }
+    FileReservationBmc::release(ctx, mm, reservation_ids[1])
+        .await
+        .unwrap();
+
+    let stats = ProjectBmc::stats(ctx, mm, project_id).await.unwrap();
+    assert_eq!(stats.agent_count, 2);
+    assert_eq!(stats.message_count, 3);
+    assert_eq!(stats.messages_last_24h, 2);
+    assert_eq!(stats.messages_last_7d, 3);
+    assert_eq!(stats.thread_count, 2);
+    assert_eq!(stats.active_reservations, 1);
+    assert!(stats.last_activity_ts.is_some());
+
+    let all = ProjectBmc::stats_all(ctx, mm).await.unwrap();
+    assert_eq!(all.len(), 2);
+    let idle = all.iter().find(|s| s.project_id == idle_id).unwrap();
+    assert_eq!(idle.message_count, 0);
+    assert_eq!(idle.last_activity_ts, None);
+    assert_eq!(
+        all.iter().find(|s| s.project_id == project_id).unwrap(),
+        &stats
+    );
+
+    let scoped = mouchak_mail_core::Ctx::scoped(1, None, vec!["stats-idle".to_string()]);
+    let visible = ProjectBmc::stats_all(&scoped, mm).await.unwrap();
+    assert_eq!(visible.len(), 1);
+    assert_eq!(visible[0].project_id, idle_id);
+    assert!(matches!(
+        ProjectBmc::stats(&scoped, mm, project_id).await,
+        Err(mouchak_mail_core::Error::Forbidden(_))
+    ));
+}
+
+/// Test that colliding human keys get suffixed slugs
+#[tokio::test]
+async fn test_create_with_unique_slug_suffixes_collisions() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+
+    let human_keys = ["/work/My Repo", "/work/my-repo", "/work/my_repo"];
+    let mut slugs = Vec::new();
+    for human_key in human_keys {
+        let slug = slugify(human_key);
+        assert_eq!(slug, "work-my-repo");
+        let (_, chosen) = ProjectBmc::create_with_unique_slug(ctx, mm, &slug, human_key)
+            .await
+            .expect("Failed to create project");
+        slugs.push(chosen);
+    }
+    assert_eq!(
+        slugs,
+        vec!["work-my-repo", "work-my-repo-2", "work-my-repo-3"]
+    );
+
+    for (human_key, slug) in human_keys.iter().zip(&slugs) {
+        let project = ProjectBmc::get_by_human_key(ctx, mm, human_key)
+            .await
+            .expect("Failed to resolve human key");
+        assert_eq!(&project.slug, slug);
+    }
+
+    // Strict creation reports the collision instead
+    let result = ProjectBmc::create(ctx, mm, "work-my-repo", "/work/MY REPO").await;
+    assert!(matches!(
+        result,
+        Err(mouchak_mail_core::Error::SlugConflict(ref slug)) if slug == "work-my-repo"
+    ));
+}
@@ -59,6 +59,9 @@ async fn setup_data(tc: &TestContext) -> (ProjectId, i64) {
                 thread_id: Some("thread-1".into()),
                 importance: None,
                 ack_required: false,
+                deliver_at: None,
+                broadcast: false,
+                allow_new_thread: true,
             },
         )
         .await
@@ -114,9 +117,10 @@ async fn test_get_thread_messages_all_query_uses_index() -> Result<()> {
     // list_by_thread(p_id, "thread-1")
     let sql = r#"
         SELECT
-            m.id, m.project_id, m.sender_id, ag.name as sender_name, m.thread_id, m.subject, m.body_md,
+            m.id, m.project_id, m.sender_id, ag.name as sender_name, m.thread_id, m.subject, b.body_md,
             m.importance, m.ack_required, m.created_ts, m.attachments
         FROM messages AS m
+        JOIN message_bodies AS b ON b.message_id = m.id
         JOIN agents AS ag ON m.sender_id = ag.id
         WHERE m.project_id = ? AND m.thread_id = ?
         ORDER BY m.created_ts ASC
@@ -236,7 +240,7 @@ async fn test_get_thread_messages_specific_thread_query_uses_index() -> Result<(
 
     // Query for specific thread messages should use thread_id index
     let sql = r#"
-        SELECT id, subject, body_md, created_ts
+        SELECT id, subject, snippet, created_ts
         FROM messages
         WHERE thread_id = ?
         ORDER BY created_ts ASC
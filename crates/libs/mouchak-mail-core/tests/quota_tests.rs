@@ -122,6 +122,9 @@ async fn test_quota_inbox_limit_exceeded() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg1)
         .await
@@ -139,6 +142,9 @@ async fn test_quota_inbox_limit_exceeded() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg2)
         .await
@@ -156,6 +162,9 @@ async fn test_quota_inbox_limit_exceeded() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
 
     let res = MessageBmc::create(&tc.ctx, &tc.mm, msg3).await;
@@ -0,0 +1,220 @@
+//! Reservation wait queue tests
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use crate::common::TestContext;
+use mouchak_mail_core::events::MailEventKind;
+use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
+use mouchak_mail_core::model::file_reservation::FileReservationBmc;
+use mouchak_mail_core::model::message::MessageBmc;
+use mouchak_mail_core::model::project::ProjectBmc;
+use mouchak_mail_core::model::reservation_queue::{
+    ReservationQueueBmc, ReservationQueueForCreate, ReservationRequestOutcome,
+};
+use mouchak_mail_core::types::{AgentId, ProjectId};
+
+mod common;
+
+/// Project with one agent per name; returns (project, agent ids)
+async fn setup(tc: &TestContext, slug: &str, names: &[&str]) -> (ProjectId, Vec<AgentId>) {
+    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, slug, &format!("/queue/{}", slug))
+        .await
+        .unwrap();
+    let mut ids = Vec::new();
+    for name in names {
+        let agent_c = AgentForCreate {
+            project_id,
+            name: name.to_string(),
+            program: "test".to_string(),
+            model: "test".to_string(),
+            task_description: "test".to_string(),
+        };
+        ids.push(AgentBmc::create(&tc.ctx, &tc.mm, agent_c).await.unwrap());
+    }
+    (project_id, ids)
+}
+
+async fn request(
+    tc: &TestContext,
+    project_id: ProjectId,
+    agent_id: AgentId,
+    path: &str,
+) -> ReservationRequestOutcome {
+    let req = ReservationQueueForCreate {
+        project_id,
+        agent_id,
+        path_pattern: path.to_string(),
+        exclusive: true,
+        reason: "edit".to_string(),
+        ttl_seconds: 3600,
+        wait_timeout_seconds: 600,
+    };
+    ReservationQueueBmc::reserve_or_enqueue(&tc.ctx, &tc.mm, req)
+        .await
+        .unwrap()
+}
+
+fn granted_id(outcome: ReservationRequestOutcome) -> i64 {
+    match outcome {
+        ReservationRequestOutcome::Granted { reservation_id } => reservation_id,
+        other => panic!("expected a grant, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_waiters_are_granted_in_order() {
+    let tc = TestContext::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+    let (project_id, agents) = setup(&tc, "queue-fifo", &["Holder", "First", "Second"]).await;
+
+    let held = granted_id(request(&tc, project_id, agents[0], "src/**").await);
+
+    let ReservationRequestOutcome::Queued(first) =
+        request(&tc, project_id, agents[1], "src/lib.rs").await
+    else {
+        panic!("first waiter should be queued");
+    };
+    assert_eq!(first.position, 1);
+    let ReservationRequestOutcome::Queued(second) =
+        request(&tc, project_id, agents[2], "src/lib.rs").await
+    else {
+        panic!("second waiter should be queued");
+    };
+    assert_eq!(second.position, 2);
+
+    // Unrelated paths are not held up by the queue
+    granted_id(request(&tc, project_id, agents[2], "docs/README.md").await);
+
+    let mut events = mm.events.subscribe();
+    FileReservationBmc::release(ctx, mm, held).await.unwrap();
+
+    // Only the first waiter gets the path; the second now waits on it
+    let active = FileReservationBmc::list_active_for_project(ctx, mm, project_id)
+        .await
+        .unwrap();
+    let holder = active
+        .iter()
+        .find(|r| r.path_pattern == "src/lib.rs")
+        .expect("first waiter granted");
+    assert_eq!(holder.agent_id, agents[1]);
+
+    let queue = ReservationQueueBmc::list_for_project(ctx, mm, project_id)
+        .await
+        .unwrap();
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue[0].agent_name, "Second");
+    assert_eq!(queue[0].position, 1);
+
+    let mut granted_to = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if event.kind == MailEventKind::ReservationGranted {
+            granted_to.push(event.data["agent_name"].as_str().unwrap().to_string());
+        }
+    }
+    assert_eq!(granted_to, vec!["First"]);
+
+    let inbox = MessageBmc::list_inbox_for_agent(ctx, mm, project_id.get(), agents[1].get(), 10)
+        .await
+        .unwrap();
+    assert!(
+        inbox
+            .iter()
+            .any(|m| m.subject == "Reservation granted: src/lib.rs")
+    );
+
+    FileReservationBmc::release(ctx, mm, holder.id)
+        .await
+        .unwrap();
+    assert!(
+        ReservationQueueBmc::list_for_project(ctx, mm, project_id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_timed_out_waiters_are_dropped() {
+    let tc = TestContext::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+    let (project_id, agents) = setup(&tc, "queue-timeout", &["Holder", "Waiter"]).await;
+
+    let held = granted_id(request(&tc, project_id, agents[0], "Cargo.toml").await);
+    assert!(matches!(
+        request(&tc, project_id, agents[1], "Cargo.toml").await,
+        ReservationRequestOutcome::Queued(_)
+    ));
+
+    mm.db_for_test()
+        .execute(
+            "UPDATE reservation_queue SET wait_until = datetime('now', '-1 minute')",
+            (),
+        )
+        .await
+        .unwrap();
+    assert!(
+        ReservationQueueBmc::list_for_project(ctx, mm, project_id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    // The blocker going away no longer grants anything
+    FileReservationBmc::release(ctx, mm, held).await.unwrap();
+    assert!(
+        ReservationQueueBmc::sweep(ctx, mm)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        FileReservationBmc::list_active_for_project(ctx, mm, project_id)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let mut rows = mm
+        .db_for_test()
+        .query("SELECT COUNT(*) FROM reservation_queue", ())
+        .await
+        .unwrap();
+    let count: i64 = rows.next().await.unwrap().unwrap().get(0).unwrap();
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+async fn test_sweep_promotes_past_expired_holds() {
+    let tc = TestContext::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+    let (project_id, agents) = setup(&tc, "queue-expiry", &["Holder", "Waiter"]).await;
+
+    granted_id(request(&tc, project_id, agents[0], "build.rs").await);
+    assert!(matches!(
+        request(&tc, project_id, agents[1], "build.rs").await,
+        ReservationRequestOutcome::Queued(_)
+    ));
+
+    // Nothing to grant while the hold is live
+    assert!(
+        ReservationQueueBmc::sweep(ctx, mm)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    mm.db_for_test()
+        .execute(
+            "UPDATE file_reservations SET expires_ts = datetime('now', '-1 minute')",
+            (),
+        )
+        .await
+        .unwrap();
+    let granted = ReservationQueueBmc::sweep(ctx, mm).await.unwrap();
+    assert_eq!(granted.len(), 1);
+
+    let reservation = FileReservationBmc::get(ctx, mm, granted[0]).await.unwrap();
+    assert_eq!(reservation.agent_id, agents[1]);
+    assert_eq!(reservation.path_pattern, "build.rs");
+}
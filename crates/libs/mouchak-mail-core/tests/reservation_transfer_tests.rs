@@ -0,0 +1,189 @@
+//! Reservation ownership transfer tests
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use mouchak_mail_core::Error;
+use mouchak_mail_core::events::MailEventKind;
+use mouchak_mail_core::model::audit::{AuditAction, AuditBmc, AuditFilter};
+use mouchak_mail_core::model::file_reservation::{FileReservationBmc, FileReservationForCreate};
+use mouchak_mail_core::model::message::MessageBmc;
+use mouchak_mail_core::testing::TestEnv;
+use mouchak_mail_core::types::{AgentId, ProjectId};
+
+/// Project with one agent per name; returns (project, agent ids)
+async fn setup(tc: &TestEnv, slug: &str, names: &[&str]) -> (ProjectId, Vec<AgentId>) {
+    let fixture = tc
+        .fixtures()
+        .project(format!("/transfer/{}", slug))
+        .agents(names.to_vec())
+        .build()
+        .await
+        .unwrap();
+    let ids = fixture.agents.iter().map(|(_, id)| *id).collect();
+    (fixture.project_id, ids)
+}
+
+async fn reserve(
+    tc: &TestEnv,
+    project_id: ProjectId,
+    agent_id: AgentId,
+    path: &str,
+    ttl_seconds: i64,
+) -> i64 {
+    let fr_c = FileReservationForCreate {
+        project_id,
+        agent_id,
+        path_pattern: path.to_string(),
+        exclusive: true,
+        reason: "edit".to_string(),
+        expires_ts: chrono::Utc::now().naive_utc() + chrono::Duration::seconds(ttl_seconds),
+    };
+    FileReservationBmc::create(&tc.ctx, &tc.mm, fr_c)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_transfer_reassigns_and_notifies() {
+    let tc = TestEnv::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+    let (project_id, agents) = setup(&tc, "transfer-ok", &["Crashed", "Rescuer"]).await;
+    let id = reserve(&tc, project_id, agents[0], "src/auth/**", 3600).await;
+
+    let mut events = mm.events.subscribe();
+    let moved = FileReservationBmc::transfer(ctx, mm, id, agents[1], "Crashed mid-refactor")
+        .await
+        .unwrap();
+    assert_eq!(moved.agent_id, agents[1]);
+    assert_eq!(moved.transferred_from, Some(agents[0]));
+
+    // The row and the transfer record are persisted
+    let stored = FileReservationBmc::get(ctx, mm, id).await.unwrap();
+    assert_eq!(stored.agent_id, agents[1]);
+    assert_eq!(stored.transferred_from, Some(agents[0]));
+    assert_eq!(
+        stored.transfer_reason.as_deref(),
+        Some("Crashed mid-refactor")
+    );
+    assert!(stored.released_ts.is_none());
+    assert_eq!(stored.expires_ts, moved.expires_ts);
+
+    let log = AuditBmc::list(
+        ctx,
+        mm,
+        project_id,
+        &AuditFilter {
+            action: Some(AuditAction::ReservationTransfer),
+            ..Default::default()
+        },
+        10,
+        None,
+    )
+    .await
+    .unwrap()
+    .items;
+    assert_eq!(log.len(), 1);
+    assert_eq!(log[0].entity_id, id);
+    assert_eq!(log[0].detail["before"]["agent_name"], "Crashed");
+    assert_eq!(log[0].detail["after"]["agent_name"], "Rescuer");
+
+    let mut transferred = Vec::new();
+    while let Ok(event) = events.try_recv() {
+        if event.kind == MailEventKind::ReservationTransferred {
+            transferred.push(event.data["to_agent"].as_str().unwrap().to_string());
+        }
+    }
+    assert_eq!(transferred, vec!["Rescuer"]);
+
+    for agent in &agents {
+        let inbox = MessageBmc::list_inbox_for_agent(ctx, mm, project_id.get(), agent.get(), 10)
+            .await
+            .unwrap();
+        assert!(
+            inbox
+                .iter()
+                .any(|m| m.subject == "Reservation transferred: src/auth/**"),
+            "agent {} should be told about the transfer",
+            agent
+        );
+    }
+}
+
+/// Test a missing target agent leaves nothing half-done
+#[tokio::test]
+async fn test_transfer_to_unknown_agent_is_atomic() {
+    let tc = TestEnv::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+    let (project_id, agents) = setup(&tc, "transfer-missing", &["Holder"]).await;
+    let (_, outsiders) = setup(&tc, "transfer-elsewhere", &["Outsider"]).await;
+    let id = reserve(&tc, project_id, agents[0], "src/lib.rs", 3600).await;
+
+    for target in [AgentId::new(9_999), outsiders[0]] {
+        let result = FileReservationBmc::transfer(ctx, mm, id, target, "handover").await;
+        assert!(
+            matches!(result, Err(Error::AgentNotFound { .. })),
+            "expected AgentNotFound, got {:?}",
+            result
+        );
+    }
+
+    let stored = FileReservationBmc::get(ctx, mm, id).await.unwrap();
+    assert_eq!(stored.agent_id, agents[0]);
+    assert_eq!(stored.transferred_from, None);
+    assert_eq!(stored.transfer_reason, None);
+
+    let log = AuditBmc::list(ctx, mm, project_id, &AuditFilter::default(), 10, None)
+        .await
+        .unwrap()
+        .items;
+    assert!(log.is_empty(), "a failed transfer must not be audited");
+}
+
+#[tokio::test]
+async fn test_transfer_rejects_inactive_reservations() {
+    let tc = TestEnv::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+    let (project_id, agents) = setup(&tc, "transfer-inactive", &["Holder", "Other"]).await;
+
+    let released = reserve(&tc, project_id, agents[0], "docs/**", 3600).await;
+    FileReservationBmc::release(ctx, mm, released)
+        .await
+        .unwrap();
+    let result = FileReservationBmc::transfer(ctx, mm, released, agents[1], "late").await;
+    assert!(matches!(result, Err(Error::FileReservationInactive(_))));
+
+    let expired = reserve(&tc, project_id, agents[0], "build/**", -60).await;
+    let result = FileReservationBmc::transfer(ctx, mm, expired, agents[1], "late").await;
+    assert!(matches!(result, Err(Error::FileReservationInactive(_))));
+    assert_eq!(
+        FileReservationBmc::get(ctx, mm, expired)
+            .await
+            .unwrap()
+            .agent_id,
+        agents[0]
+    );
+
+    let result = FileReservationBmc::transfer(ctx, mm, 424_242, agents[1], "late").await;
+    assert!(matches!(result, Err(Error::FileReservationNotFound(_))));
+}
+
+#[tokio::test]
+async fn test_transfer_to_holder_is_noop() {
+    let tc = TestEnv::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+    let (project_id, agents) = setup(&tc, "transfer-noop", &["Holder"]).await;
+    let id = reserve(&tc, project_id, agents[0], "src/main.rs", 3600).await;
+
+    let same = FileReservationBmc::transfer(ctx, mm, id, agents[0], "no change")
+        .await
+        .unwrap();
+    assert_eq!(same.agent_id, agents[0]);
+    assert_eq!(same.transferred_from, None);
+
+    let log = AuditBmc::list(ctx, mm, project_id, &AuditFilter::default(), 10, None)
+        .await
+        .unwrap()
+        .items;
+    assert!(log.is_empty());
+}
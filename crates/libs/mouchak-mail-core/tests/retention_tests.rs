@@ -0,0 +1,302 @@
+//! Message retention tests
+//!
+//! Tests for pruning old messages once their Git archive files are confirmed.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+#[path = "common/mod.rs"]
+mod common;
+
+use crate::common::TestContext;
+use mouchak_mail_core::Error;
+use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
+use mouchak_mail_core::model::archive_integrity::ArchiveIntegrityBmc;
+use mouchak_mail_core::model::audit::{AuditAction, AuditBmc, AuditFilter};
+use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
+use mouchak_mail_core::model::project::{ProjectBmc, RetentionPolicy};
+use mouchak_mail_core::model::retention::RetentionBmc;
+use mouchak_mail_core::types::ProjectId;
+use std::path::PathBuf;
+
+const SLUG: &str = "retention-project";
+
+struct Fixture {
+    project_id: ProjectId,
+    recipient_id: i64,
+    message_ids: Vec<i64>,
+}
+
+/// Create a project and send `count` messages, all backdated to 2020.
+async fn setup(tc: &TestContext, count: usize) -> Fixture {
+    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, SLUG, "/retention/project")
+        .await
+        .unwrap();
+
+    let mut agent_ids = Vec::new();
+    for name in ["Sender", "Recipient"] {
+        let agent_c = AgentForCreate {
+            project_id,
+            name: name.to_string(),
+            program: "test".to_string(),
+            model: "test".to_string(),
+            task_description: "Retention agent".to_string(),
+        };
+        agent_ids.push(AgentBmc::create(&tc.ctx, &tc.mm, agent_c).await.unwrap());
+    }
+
+    let mut message_ids = Vec::new();
+    for i in 0..count {
+        let msg_c = MessageForCreate {
+            project_id: project_id.get(),
+            sender_id: agent_ids[0].into(),
+            recipient_ids: vec![agent_ids[1].into()],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: format!("Retention {}", i),
+            body_md: format!("Body {}", i),
+            thread_id: None,
+            importance: None,
+            ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
+        };
+        message_ids.push(MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap());
+    }
+
+    // Archive files are written in the background
+    for _ in 0..50 {
+        let report = ArchiveIntegrityBmc::verify_archive(&tc.ctx, &tc.mm, SLUG)
+            .await
+            .unwrap();
+        if report.missing_count == 0 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+
+    tc.mm
+        .db_for_test()
+        .execute(
+            "UPDATE messages SET created_ts = '2020-01-01 00:00:00' WHERE project_id = ?",
+            [project_id.get()],
+        )
+        .await
+        .unwrap();
+
+    Fixture {
+        project_id,
+        recipient_id: agent_ids[1].get(),
+        message_ids,
+    }
+}
+
+/// Mark every message read so `keep_unread` doesn't hold them back.
+async fn read_all(tc: &TestContext, fx: &Fixture) {
+    for id in &fx.message_ids {
+        MessageBmc::mark_read(&tc.ctx, &tc.mm, *id, fx.recipient_id)
+            .await
+            .unwrap();
+    }
+}
+
+fn canonical_file(tc: &TestContext, message_id: i64) -> PathBuf {
+    let suffix = format!("__{}.md", message_id);
+    let messages_dir = tc.repo_root().join("projects").join(SLUG).join("messages");
+    for year in std::fs::read_dir(&messages_dir).unwrap() {
+        let year = year.unwrap().path();
+        if !year.is_dir() {
+            continue;
+        }
+        for month in std::fs::read_dir(&year).unwrap() {
+            for file in std::fs::read_dir(month.unwrap().path()).unwrap() {
+                let path = file.unwrap().path();
+                if path.to_string_lossy().ends_with(&suffix) {
+                    return path;
+                }
+            }
+        }
+    }
+    panic!("No canonical file for message {}", message_id);
+}
+
+#[tokio::test]
+async fn test_prune_without_policy_is_rejected() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, 1).await;
+
+    let result = RetentionBmc::prune(&tc.ctx, &tc.mm, fx.project_id, false).await;
+    assert!(matches!(result, Err(Error::InvalidInput(_))));
+}
+
+#[tokio::test]
+async fn test_set_retention_validates_days() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, 0).await;
+
+    let result =
+        ProjectBmc::set_retention(&tc.ctx, &tc.mm, fx.project_id, RetentionPolicy::days(0)).await;
+    assert!(matches!(result, Err(Error::InvalidInput(_))));
+
+    let policy = RetentionPolicy {
+        retention_days: 30,
+        keep_unread: false,
+        keep_ack_pending: true,
+    };
+    ProjectBmc::set_retention(&tc.ctx, &tc.mm, fx.project_id, policy)
+        .await
+        .unwrap();
+    let stored = ProjectBmc::get_retention(&tc.ctx, &tc.mm, fx.project_id)
+        .await
+        .unwrap();
+    assert_eq!(stored, Some(policy));
+
+    ProjectBmc::clear_retention(&tc.ctx, &tc.mm, fx.project_id)
+        .await
+        .unwrap();
+    let cleared = ProjectBmc::get_retention(&tc.ctx, &tc.mm, fx.project_id)
+        .await
+        .unwrap();
+    assert_eq!(cleared, None);
+}
+
+#[tokio::test]
+async fn test_dry_run_deletes_nothing() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, 3).await;
+    read_all(&tc, &fx).await;
+    ProjectBmc::set_retention(&tc.ctx, &tc.mm, fx.project_id, RetentionPolicy::days(30))
+        .await
+        .unwrap();
+
+    let report = RetentionBmc::prune(&tc.ctx, &tc.mm, fx.project_id, true)
+        .await
+        .unwrap();
+    assert!(report.dry_run);
+    assert_eq!(report.eligible, 3);
+    assert_eq!(report.pruned, 3);
+    assert_eq!(report.recipients_pruned, 3);
+    assert_eq!(report.missing_archive, 0);
+
+    for id in &fx.message_ids {
+        MessageBmc::get(&tc.ctx, &tc.mm, *id).await.unwrap();
+    }
+    let audit = AuditBmc::list(
+        &tc.ctx,
+        &tc.mm,
+        fx.project_id,
+        &AuditFilter::default(),
+        10,
+        None,
+    )
+    .await
+    .unwrap();
+    assert!(audit.items.is_empty());
+}
+
+#[tokio::test]
+async fn test_prune_skips_messages_without_archive_file() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, 3).await;
+    read_all(&tc, &fx).await;
+    ProjectBmc::set_retention(&tc.ctx, &tc.mm, fx.project_id, RetentionPolicy::days(30))
+        .await
+        .unwrap();
+
+    let kept = fx.message_ids[1];
+    std::fs::remove_file(canonical_file(&tc, kept)).unwrap();
+
+    let report = RetentionBmc::prune(&tc.ctx, &tc.mm, fx.project_id, false)
+        .await
+        .unwrap();
+    assert!(!report.dry_run);
+    assert_eq!(report.eligible, 3);
+    assert_eq!(report.pruned, 2);
+    assert_eq!(report.recipients_pruned, 2);
+    assert_eq!(report.missing_archive, 1);
+    assert_eq!(report.missing_archive_ids, vec![kept]);
+
+    MessageBmc::get(&tc.ctx, &tc.mm, kept).await.unwrap();
+    for id in [fx.message_ids[0], fx.message_ids[2]] {
+        let result = MessageBmc::get(&tc.ctx, &tc.mm, id).await;
+        assert!(
+            matches!(result, Err(Error::MessagePruned(pruned)) if pruned == id),
+            "Expected MessagePruned, got {:?}",
+            result
+        );
+    }
+
+    // A message that never existed is still a plain not-found
+    let result = MessageBmc::get(&tc.ctx, &tc.mm, 999_999).await;
+    assert!(matches!(result, Err(Error::MessageNotFound(_))));
+
+    let filter = AuditFilter {
+        action: Some(AuditAction::RetentionPrune),
+        ..Default::default()
+    };
+    let audit = AuditBmc::list(&tc.ctx, &tc.mm, fx.project_id, &filter, 10, None)
+        .await
+        .unwrap();
+    assert_eq!(audit.items.len(), 1);
+    assert_eq!(audit.items[0].detail["after"]["messages_pruned"], 2);
+}
+
+#[tokio::test]
+async fn test_keep_unread_and_recent_messages() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, 3).await;
+    ProjectBmc::set_retention(&tc.ctx, &tc.mm, fx.project_id, RetentionPolicy::days(30))
+        .await
+        .unwrap();
+
+    // Only the first is read; the last is recent
+    MessageBmc::mark_read(&tc.ctx, &tc.mm, fx.message_ids[0], fx.recipient_id)
+        .await
+        .unwrap();
+    MessageBmc::mark_read(&tc.ctx, &tc.mm, fx.message_ids[2], fx.recipient_id)
+        .await
+        .unwrap();
+    tc.mm
+        .db_for_test()
+        .execute(
+            "UPDATE messages SET created_ts = CURRENT_TIMESTAMP WHERE id = ?",
+            [fx.message_ids[2]],
+        )
+        .await
+        .unwrap();
+
+    let report = RetentionBmc::prune(&tc.ctx, &tc.mm, fx.project_id, false)
+        .await
+        .unwrap();
+    assert_eq!(report.eligible, 1);
+    assert_eq!(report.pruned, 1);
+    assert!(
+        RetentionBmc::is_pruned(&tc.mm, fx.message_ids[0])
+            .await
+            .unwrap()
+    );
+    MessageBmc::get(&tc.ctx, &tc.mm, fx.message_ids[1])
+        .await
+        .unwrap();
+    MessageBmc::get(&tc.ctx, &tc.mm, fx.message_ids[2])
+        .await
+        .unwrap();
+
+    // Dropping keep_unread lets the unread one go too
+    let policy = RetentionPolicy {
+        keep_unread: false,
+        ..RetentionPolicy::days(30)
+    };
+    ProjectBmc::set_retention(&tc.ctx, &tc.mm, fx.project_id, policy)
+        .await
+        .unwrap();
+    let report = RetentionBmc::prune(&tc.ctx, &tc.mm, fx.project_id, false)
+        .await
+        .unwrap();
+    assert_eq!(report.pruned, 1);
+    assert!(
+        RetentionBmc::is_pruned(&tc.mm, fx.message_ids[1])
+            .await
+            .unwrap()
+    );
+}
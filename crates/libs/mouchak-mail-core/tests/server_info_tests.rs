@@ -0,0 +1,79 @@
+//! Server info tests
+//!
+//! The reported features and limits follow the loaded configuration.
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use crate::common::TestContext;
+use mouchak_mail_common::config::AppConfig;
+use mouchak_mail_core::model::server_info::ServerInfoBmc;
+use mouchak_mail_core::store::SCHEMA_VERSION;
+
+mod common;
+
+/// Test the defaults: optional features off, no quotas, rate limited
+#[tokio::test]
+async fn test_server_info_defaults() {
+    let tc = TestContext::new().await.unwrap();
+    // Test databases are built without the migration runner, which records this
+    tc.mm
+        .db_for_test()
+        .execute(&format!("PRAGMA user_version = {}", SCHEMA_VERSION), ())
+        .await
+        .unwrap();
+    let info = ServerInfoBmc::get(&tc.ctx, &tc.mm).await.unwrap();
+
+    assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+    assert_eq!(info.schema_version, SCHEMA_VERSION);
+    assert_eq!(info.expected_schema_version, SCHEMA_VERSION);
+
+    let config = AppConfig::default();
+    assert!(!info.features.worktrees);
+    assert!(!info.features.quota);
+    assert!(info.features.read_tracking);
+    assert_eq!(info.limits.max_body_bytes, config.messages.max_body_bytes);
+    assert_eq!(info.limits.max_recipients, config.messages.max_recipients);
+    assert_eq!(
+        info.limits.max_reservation_ttl_seconds,
+        config.reservations.max_ttl_seconds
+    );
+    assert_eq!(info.limits.max_attachment_bytes, None);
+    assert_eq!(info.limits.max_inbox_messages, None);
+    assert!(info.features.rate_limit);
+    assert_eq!(
+        info.limits.rate_limit_rps,
+        Some(config.rate_limit.requests_per_second)
+    );
+}
+
+/// Test toggled features and changed limits show up
+#[tokio::test]
+async fn test_server_info_reflects_config() {
+    let mut config = AppConfig::default();
+    config.mcp.worktrees_enabled = true;
+    config.quota.enabled = true;
+    config.quota.attachments_limit_bytes = 2048;
+    config.quota.inbox_limit_count = 7;
+    config.rate_limit.enabled = false;
+    config.escalation.ack_ttl_enabled = true;
+    config.messages.max_body_bytes = 1000;
+    config.messages.max_recipients = 4;
+    config.reservations.max_ttl_seconds = 600;
+    let tc = TestContext::new_with_config(config).await.unwrap();
+
+    let info = ServerInfoBmc::get(&tc.ctx, &tc.mm).await.unwrap();
+
+    assert!(info.features.worktrees);
+    assert!(!info.features.git_identity);
+    assert!(info.features.quota);
+    assert!(!info.features.rate_limit);
+    assert!(info.features.ack_ttl);
+    assert_eq!(info.limits.max_body_bytes, 1000);
+    assert_eq!(info.limits.max_recipients, 4);
+    assert_eq!(info.limits.max_reservation_ttl_seconds, 600);
+    assert_eq!(info.limits.max_attachment_bytes, Some(2048));
+    assert_eq!(info.limits.max_inbox_messages, Some(7));
+    assert_eq!(info.limits.rate_limit_rps, None);
+    assert_eq!(info.limits.rate_limit_burst, None);
+}
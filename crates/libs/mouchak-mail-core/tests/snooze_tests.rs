@@ -0,0 +1,183 @@
+//! Inbox snooze tests
+//!
+//! A snoozed message is hidden from one recipient's inbox until the snooze
+//! runs out, then comes back flagged.
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use chrono::{Duration, Timelike, Utc};
+use mouchak_mail_core::Error;
+use mouchak_mail_core::model::message::{Message, MessageBmc, MessageForCreate};
+use mouchak_mail_core::testing::TestEnv;
+
+/// Project with a sender and two recipients; returns (project_id, agent ids).
+async fn setup(tc: &TestEnv, slug: &str) -> (i64, Vec<i64>) {
+    let fixture = tc
+        .fixtures()
+        .project(format!("/snooze/{}", slug))
+        .agents(["Sender", "Triager", "Bystander"])
+        .build()
+        .await
+        .unwrap();
+    let ids = fixture.agents.iter().map(|(_, id)| id.get()).collect();
+    (fixture.project_id.get(), ids)
+}
+
+async fn send(tc: &TestEnv, project_id: i64, agents: &[i64], subject: &str) -> i64 {
+    let msg_c = MessageForCreate {
+        project_id,
+        sender_id: agents[0],
+        recipient_ids: agents[1..].to_vec(),
+        cc_ids: None,
+        bcc_ids: None,
+        subject: subject.to_string(),
+        body_md: "body".to_string(),
+        thread_id: Some("DEPLOY-1".to_string()),
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
+    };
+    MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap()
+}
+
+async fn inbox(tc: &TestEnv, project_id: i64, agent_id: i64) -> Vec<Message> {
+    MessageBmc::list_inbox_for_agent(&tc.ctx, &tc.mm, project_id, agent_id, 50)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_snoozed_message_returns_after_expiry() {
+    let tc = TestEnv::new().await.unwrap();
+    let (project_id, agents) = setup(&tc, "snooze-expiry").await;
+    let (triager, bystander) = (agents[1], agents[2]);
+    let snoozed_id = send(&tc, project_id, &agents, "after the deploy").await;
+    send(&tc, project_id, &agents, "right now").await;
+
+    let until = Utc::now().naive_utc() + Duration::seconds(2);
+    MessageBmc::snooze(&tc.ctx, &tc.mm, snoozed_id, triager, until)
+        .await
+        .unwrap();
+
+    let subjects: Vec<String> = inbox(&tc, project_id, triager)
+        .await
+        .into_iter()
+        .map(|m| m.subject)
+        .collect();
+    assert_eq!(subjects, vec!["right now"]);
+
+    let snoozed = MessageBmc::list_snoozed(&tc.ctx, &tc.mm, project_id, triager)
+        .await
+        .unwrap();
+    assert_eq!(snoozed.len(), 1);
+    assert_eq!(snoozed[0].message.id, snoozed_id);
+    assert_eq!(snoozed[0].snoozed_until, until.with_nanosecond(0).unwrap());
+
+    // Other recipients and the thread are unaffected
+    assert_eq!(inbox(&tc, project_id, bystander).await.len(), 2);
+    let thread = MessageBmc::list_by_thread(&tc.ctx, &tc.mm, project_id, "DEPLOY-1")
+        .await
+        .unwrap();
+    assert_eq!(thread.len(), 2);
+
+    tokio::time::sleep(std::time::Duration::from_secs(3)).await;
+
+    let returned = inbox(&tc, project_id, triager).await;
+    assert_eq!(returned.len(), 2);
+    let back = returned.iter().find(|m| m.id == snoozed_id).unwrap();
+    assert!(back.returned_from_snooze);
+    assert!(
+        returned
+            .iter()
+            .all(|m| m.id == snoozed_id || !m.returned_from_snooze)
+    );
+    assert!(
+        MessageBmc::list_snoozed(&tc.ctx, &tc.mm, project_id, triager)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert!(
+        inbox(&tc, project_id, bystander)
+            .await
+            .iter()
+            .all(|m| !m.returned_from_snooze)
+    );
+}
+
+#[tokio::test]
+async fn test_marking_read_clears_snooze() {
+    let tc = TestEnv::new().await.unwrap();
+    let (project_id, agents) = setup(&tc, "snooze-read").await;
+    let triager = agents[1];
+    let first = send(&tc, project_id, &agents, "first").await;
+    let second = send(&tc, project_id, &agents, "second").await;
+    let later = Utc::now().naive_utc() + Duration::hours(4);
+
+    MessageBmc::snooze(&tc.ctx, &tc.mm, first, triager, later)
+        .await
+        .unwrap();
+    MessageBmc::mark_read(&tc.ctx, &tc.mm, first, triager)
+        .await
+        .unwrap();
+
+    MessageBmc::snooze(&tc.ctx, &tc.mm, second, triager, later)
+        .await
+        .unwrap();
+    MessageBmc::mark_read_batch(&tc.ctx, &tc.mm, &[second], Some(triager))
+        .await
+        .unwrap();
+
+    let back = inbox(&tc, project_id, triager).await;
+    assert_eq!(back.len(), 2);
+    assert!(back.iter().all(|m| !m.returned_from_snooze));
+    assert!(
+        MessageBmc::list_snoozed(&tc.ctx, &tc.mm, project_id, triager)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[tokio::test]
+async fn test_unsnooze_and_validation() {
+    let tc = TestEnv::new().await.unwrap();
+    let (project_id, agents) = setup(&tc, "snooze-undo").await;
+    let (sender, triager) = (agents[0], agents[1]);
+    let id = send(&tc, project_id, &agents, "undo me").await;
+    let later = Utc::now().naive_utc() + Duration::hours(1);
+
+    MessageBmc::snooze(&tc.ctx, &tc.mm, id, triager, later)
+        .await
+        .unwrap();
+    // Snoozing again moves the time instead of failing
+    MessageBmc::snooze(&tc.ctx, &tc.mm, id, triager, later + Duration::hours(1))
+        .await
+        .unwrap();
+    assert!(inbox(&tc, project_id, triager).await.is_empty());
+
+    assert!(
+        MessageBmc::unsnooze(&tc.ctx, &tc.mm, id, triager)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !MessageBmc::unsnooze(&tc.ctx, &tc.mm, id, triager)
+            .await
+            .unwrap()
+    );
+    let back = inbox(&tc, project_id, triager).await;
+    assert_eq!(back.len(), 1);
+    assert!(!back[0].returned_from_snooze);
+
+    let past = Utc::now().naive_utc() - Duration::minutes(1);
+    let result = MessageBmc::snooze(&tc.ctx, &tc.mm, id, triager, past).await;
+    assert!(matches!(result, Err(Error::InvalidInput(_))));
+
+    // Only recipients can snooze
+    let result = MessageBmc::snooze(&tc.ctx, &tc.mm, id, sender, later).await;
+    assert!(matches!(result, Err(Error::MessageNotFound(_))));
+}
@@ -0,0 +1,149 @@
+//! Message template tests
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use crate::common::TestContext;
+use mouchak_mail_core::model::project::ProjectBmc;
+use mouchak_mail_core::model::template::{TemplateBmc, TemplateForCreate};
+use mouchak_mail_core::types::ProjectId;
+
+mod common;
+
+fn template(project_id: ProjectId, name: &str) -> TemplateForCreate {
+    TemplateForCreate {
+        project_id,
+        name: name.to_string(),
+        subject: "STATUS REPORT for {{agent_name}}".to_string(),
+        body_md: "Reply with your progress as of {{date}}.".to_string(),
+        importance: Some("high".to_string()),
+        ack_required: true,
+    }
+}
+
+#[tokio::test]
+async fn test_template_crud() {
+    let tc = TestContext::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+
+    let project_id = ProjectBmc::create(ctx, mm, "template-crud", "/template/crud")
+        .await
+        .unwrap();
+
+    let status_id = TemplateBmc::create(ctx, mm, template(project_id, "status"))
+        .await
+        .unwrap();
+    let mut stop = template(project_id, "stop");
+    stop.subject = "STOP".to_string();
+    stop.importance = None;
+    stop.ack_required = false;
+    TemplateBmc::create(ctx, mm, stop).await.unwrap();
+
+    // Placeholders are stored verbatim
+    let status = TemplateBmc::get(ctx, mm, project_id, status_id)
+        .await
+        .unwrap();
+    assert_eq!(status.name, "status");
+    assert_eq!(status.subject, "STATUS REPORT for {{agent_name}}");
+    assert_eq!(status.body_md, "Reply with your progress as of {{date}}.");
+    assert_eq!(status.importance, "high");
+    assert!(status.ack_required);
+
+    let listed = TemplateBmc::list_for_project(ctx, mm, project_id)
+        .await
+        .unwrap();
+    let names: Vec<&str> = listed.iter().map(|t| t.name.as_str()).collect();
+    assert_eq!(names, vec!["status", "stop"]);
+    assert_eq!(listed[1].importance, "normal");
+    assert!(!listed[1].ack_required);
+
+    TemplateBmc::delete(ctx, mm, project_id, status_id)
+        .await
+        .unwrap();
+    assert!(matches!(
+        TemplateBmc::get(ctx, mm, project_id, status_id).await,
+        Err(mouchak_mail_core::Error::TemplateNotFound(id)) if id == status_id
+    ));
+    assert!(matches!(
+        TemplateBmc::delete(ctx, mm, project_id, status_id).await,
+        Err(mouchak_mail_core::Error::TemplateNotFound(_))
+    ));
+    assert_eq!(
+        TemplateBmc::list_for_project(ctx, mm, project_id)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn test_template_name_unique_per_project() {
+    let tc = TestContext::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+
+    let project_a = ProjectBmc::create(ctx, mm, "template-a", "/template/a")
+        .await
+        .unwrap();
+    let project_b = ProjectBmc::create(ctx, mm, "template-b", "/template/b")
+        .await
+        .unwrap();
+
+    TemplateBmc::create(ctx, mm, template(project_a, "status"))
+        .await
+        .unwrap();
+
+    let err = TemplateBmc::create(ctx, mm, template(project_a, "status"))
+        .await
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        mouchak_mail_core::Error::TemplateNameTaken(ref name) if name == "status"
+    ));
+    assert!(err.to_string().contains("already exists"));
+
+    // The same name is fine in another project
+    TemplateBmc::create(ctx, mm, template(project_b, "status"))
+        .await
+        .unwrap();
+
+    // A template from another project is not reachable by ID
+    let b_templates = TemplateBmc::list_for_project(ctx, mm, project_b)
+        .await
+        .unwrap();
+    assert!(matches!(
+        TemplateBmc::get(ctx, mm, project_a, b_templates[0].id).await,
+        Err(mouchak_mail_core::Error::TemplateNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn test_template_validation() {
+    let tc = TestContext::new().await.unwrap();
+    let (ctx, mm) = (&tc.ctx, &tc.mm);
+
+    let project_id = ProjectBmc::create(ctx, mm, "template-validation", "/template/validation")
+        .await
+        .unwrap();
+
+    let mut blank_name = template(project_id, "  ");
+    blank_name.subject = "STOP".to_string();
+    assert!(matches!(
+        TemplateBmc::create(ctx, mm, blank_name).await,
+        Err(mouchak_mail_core::Error::InvalidInput(_))
+    ));
+
+    let mut blank_subject = template(project_id, "stop");
+    blank_subject.subject = String::new();
+    assert!(matches!(
+        TemplateBmc::create(ctx, mm, blank_subject).await,
+        Err(mouchak_mail_core::Error::InvalidInput(_))
+    ));
+
+    let mut bad_importance = template(project_id, "stop");
+    bad_importance.importance = Some("critical".to_string());
+    assert!(matches!(
+        TemplateBmc::create(ctx, mm, bad_importance).await,
+        Err(mouchak_mail_core::Error::InvalidInput(_))
+    ));
+}
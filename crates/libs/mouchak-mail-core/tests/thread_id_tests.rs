@@ -0,0 +1,259 @@
+//! Thread ID validation tests
+//!
+//! Sending into a thread that does not exist is refused unless the caller
+//! asks for a new thread, so a mistyped ID cannot silently fork a
+//! conversation.
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use crate::common::TestContext;
+use mouchak_mail_core::Error;
+use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
+use mouchak_mail_core::model::message::{
+    MAX_THREAD_ID_CHARS, MessageBmc, MessageForCreate, normalize_thread_id,
+};
+use mouchak_mail_core::model::project::ProjectBmc;
+
+mod common;
+
+struct Fixture {
+    project_id: i64,
+    alice: i64,
+    bob: i64,
+}
+
+async fn setup(tc: &TestContext, slug: &str) -> Fixture {
+    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, slug, &format!("/threads/{}", slug))
+        .await
+        .unwrap();
+
+    let mut ids = Vec::new();
+    for name in ["Alice", "Bob"] {
+        let agent_c = AgentForCreate {
+            project_id,
+            name: name.to_string(),
+            program: "test".to_string(),
+            model: "test".to_string(),
+            task_description: String::new(),
+        };
+        ids.push(
+            AgentBmc::create(&tc.ctx, &tc.mm, agent_c)
+                .await
+                .unwrap()
+                .get(),
+        );
+    }
+
+    Fixture {
+        project_id: project_id.get(),
+        alice: ids[0],
+        bob: ids[1],
+    }
+}
+
+async fn send(
+    tc: &TestContext,
+    fx: &Fixture,
+    subject: &str,
+    thread_id: Option<&str>,
+    allow_new_thread: bool,
+) -> mouchak_mail_core::Result<i64> {
+    let msg_c = MessageForCreate {
+        project_id: fx.project_id,
+        sender_id: fx.alice,
+        recipient_ids: vec![fx.bob],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: subject.to_string(),
+        body_md: "Body".to_string(),
+        thread_id: thread_id.map(str::to_string),
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread,
+    };
+    MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await
+}
+
+/// Test a mistyped thread ID is rejected instead of starting a thread
+#[tokio::test]
+async fn test_unknown_thread_id_rejected() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "thread-typo").await;
+
+    send(&tc, &fx, "Deploy plan", Some("DEPLOY-42"), true)
+        .await
+        .unwrap();
+    send(&tc, &fx, "Re: Deploy plan", Some("DEPLOY-42"), false)
+        .await
+        .unwrap();
+
+    let result = send(&tc, &fx, "Re: Deploy plan", Some("DEPLOY-24"), false).await;
+    assert!(
+        matches!(result, Err(Error::ThreadNotFound(ref id)) if id == "DEPLOY-24"),
+        "expected ThreadNotFound, got {:?}",
+        result
+    );
+
+    let thread = MessageBmc::list_by_thread(&tc.ctx, &tc.mm, fx.project_id, "DEPLOY-24")
+        .await
+        .unwrap();
+    assert!(thread.is_empty(), "nothing should be sent on rejection");
+}
+
+/// Test allow_new_thread starts a thread that later sends can join
+#[tokio::test]
+async fn test_allow_new_thread_creates_thread() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "thread-new").await;
+
+    let first = send(&tc, &fx, "Kickoff", Some("  KICKOFF-1  "), true)
+        .await
+        .unwrap();
+    let second = send(&tc, &fx, "Re: Kickoff", Some("KICKOFF-1"), false)
+        .await
+        .unwrap();
+
+    let thread = MessageBmc::list_by_thread(&tc.ctx, &tc.mm, fx.project_id, "KICKOFF-1")
+        .await
+        .unwrap();
+    let ids: Vec<i64> = thread.iter().map(|m| m.id).collect();
+    assert_eq!(
+        ids,
+        vec![first, second],
+        "trimmed ID should join the thread"
+    );
+}
+
+/// Test thread IDs compare case-sensitively
+#[tokio::test]
+async fn test_thread_id_case_sensitive() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "thread-case").await;
+
+    send(&tc, &fx, "Ticket", Some("TKT-1"), true).await.unwrap();
+
+    let result = send(&tc, &fx, "Ticket", Some("tkt-1"), false).await;
+    assert!(matches!(result, Err(Error::ThreadNotFound(_))));
+
+    send(&tc, &fx, "Ticket", Some("tkt-1"), true).await.unwrap();
+    let upper = MessageBmc::list_by_thread(&tc.ctx, &tc.mm, fx.project_id, "TKT-1")
+        .await
+        .unwrap();
+    let lower = MessageBmc::list_by_thread(&tc.ctx, &tc.mm, fx.project_id, "tkt-1")
+        .await
+        .unwrap();
+    assert_eq!(upper.len(), 1);
+    assert_eq!(lower.len(), 1);
+}
+
+/// Test a thread in another project does not count
+#[tokio::test]
+async fn test_thread_id_scoped_to_project() {
+    let tc = TestContext::new().await.unwrap();
+    let first = setup(&tc, "thread-scope-a").await;
+    let second = setup(&tc, "thread-scope-b").await;
+
+    send(&tc, &first, "Shared", Some("SHARED-1"), true)
+        .await
+        .unwrap();
+
+    let result = send(&tc, &second, "Shared", Some("SHARED-1"), false).await;
+    assert!(matches!(result, Err(Error::ThreadNotFound(_))));
+}
+
+/// Test blank, control-character and overlong IDs
+#[test]
+fn test_normalize_thread_id() {
+    assert_eq!(normalize_thread_id(" TKT-1\t").unwrap(), "TKT-1");
+    assert!(matches!(
+        normalize_thread_id("   "),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(matches!(
+        normalize_thread_id("TKT\n1"),
+        Err(Error::InvalidInput(_))
+    ));
+    assert!(matches!(
+        normalize_thread_id("TKT\u{7}1"),
+        Err(Error::InvalidInput(_))
+    ));
+
+    let long = "x".repeat(MAX_THREAD_ID_CHARS + 20);
+    assert_eq!(
+        normalize_thread_id(&long).unwrap().chars().count(),
+        MAX_THREAD_ID_CHARS
+    );
+}
+
+/// Test a control character is rejected on send, even for a new thread
+#[tokio::test]
+async fn test_control_characters_rejected_on_send() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "thread-control").await;
+
+    let result = send(&tc, &fx, "Bad", Some("BAD\r\nID"), true).await;
+    assert!(matches!(result, Err(Error::InvalidInput(_))));
+}
+
+/// Test ensure_thread is stable and finds the thread a subject started
+#[tokio::test]
+async fn test_ensure_thread() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "thread-ensure").await;
+
+    let fresh = MessageBmc::ensure_thread(&tc.ctx, &tc.mm, fx.project_id, "Release notes")
+        .await
+        .unwrap();
+    let again = MessageBmc::ensure_thread(&tc.ctx, &tc.mm, fx.project_id, "Re: Release notes")
+        .await
+        .unwrap();
+    assert_eq!(fresh, again, "Re: prefix should not change the ID");
+    assert!(fresh.len() <= MAX_THREAD_ID_CHARS);
+
+    send(&tc, &fx, "Release notes", Some("REL-7"), true)
+        .await
+        .unwrap();
+    let found = MessageBmc::ensure_thread(&tc.ctx, &tc.mm, fx.project_id, "Fwd: Re: Release notes")
+        .await
+        .unwrap();
+    assert_eq!(found, "REL-7");
+}
+
+/// Test the same subject in two projects gets two threads, each numbered
+/// from 1
+#[tokio::test]
+async fn test_ensure_thread_per_project() {
+    let tc = TestContext::new().await.unwrap();
+    let first = setup(&tc, "thread-project-a").await;
+    let second = setup(&tc, "thread-project-b").await;
+
+    let first_id = MessageBmc::ensure_thread(&tc.ctx, &tc.mm, first.project_id, "Weekly sync")
+        .await
+        .unwrap();
+    let second_id = MessageBmc::ensure_thread(&tc.ctx, &tc.mm, second.project_id, "Weekly sync")
+        .await
+        .unwrap();
+    assert_ne!(first_id, second_id);
+
+    // A thread ID both projects use is numbered separately in each
+    for fx in [&first, &second] {
+        for _ in 0..2 {
+            send(&tc, fx, "Weekly sync", Some("SYNC-1"), true)
+                .await
+                .unwrap();
+        }
+    }
+    for fx in [&first, &second] {
+        let seqs: Vec<Option<i64>> =
+            MessageBmc::list_by_thread(&tc.ctx, &tc.mm, fx.project_id, "SYNC-1")
+                .await
+                .unwrap()
+                .iter()
+                .map(|m| m.thread_seq)
+                .collect();
+        assert_eq!(seqs, vec![Some(1), Some(2)]);
+    }
+}
@@ -0,0 +1,227 @@
+//! Thread mute tests
+//!
+//! A muted thread's messages are delivered but kept out of the muting
+//! agent's inbox, except urgent ones and ones that @-mention the agent.
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use crate::common::TestContext;
+use mouchak_mail_core::Error;
+use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
+use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate, UnifiedInboxFilter};
+use mouchak_mail_core::model::project::ProjectBmc;
+use mouchak_mail_core::model::thread_mute::ThreadMuteBmc;
+use mouchak_mail_core::types::{AgentId, ProjectId};
+
+mod common;
+
+struct Fixture {
+    project_id: i64,
+    sender: AgentId,
+    listener: AgentId,
+    bystander: AgentId,
+}
+
+async fn setup(tc: &TestContext, slug: &str) -> Fixture {
+    let project_id = ProjectBmc::create(&tc.ctx, &tc.mm, slug, &format!("/mute/{}", slug))
+        .await
+        .unwrap();
+
+    let mut ids = Vec::new();
+    for name in ["Sender", "Listener", "Bystander"] {
+        let agent_c = AgentForCreate {
+            project_id,
+            name: name.to_string(),
+            program: "test".to_string(),
+            model: "test".to_string(),
+            task_description: String::new(),
+        };
+        ids.push(AgentBmc::create(&tc.ctx, &tc.mm, agent_c).await.unwrap());
+    }
+
+    Fixture {
+        project_id: project_id.get(),
+        sender: ids[0],
+        listener: ids[1],
+        bystander: ids[2],
+    }
+}
+
+async fn send(
+    tc: &TestContext,
+    fx: &Fixture,
+    thread: &str,
+    subject: &str,
+    importance: &str,
+) -> i64 {
+    let msg_c = MessageForCreate {
+        project_id: fx.project_id,
+        sender_id: fx.sender.get(),
+        recipient_ids: vec![fx.listener.get(), fx.bystander.get()],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: subject.to_string(),
+        body_md: "body".to_string(),
+        thread_id: Some(thread.to_string()),
+        importance: Some(importance.to_string()),
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
+    };
+    MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap()
+}
+
+/// Inbox subjects, sorted (messages sent in the same second tie on created_ts).
+async fn inbox_subjects(tc: &TestContext, fx: &Fixture, agent: AgentId) -> Vec<String> {
+    let mut subjects: Vec<String> =
+        MessageBmc::list_inbox_for_agent(&tc.ctx, &tc.mm, fx.project_id, agent.get(), 50)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|m| m.subject)
+            .collect();
+    subjects.sort();
+    subjects
+}
+
+#[tokio::test]
+async fn test_muted_thread_hidden_from_inbox_but_not_thread() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "mute-hide").await;
+
+    send(&tc, &fx, "noisy", "first", "normal").await;
+    send(&tc, &fx, "quiet", "elsewhere", "normal").await;
+    assert!(
+        ThreadMuteBmc::mute(&tc.ctx, &tc.mm, fx.listener, "noisy")
+            .await
+            .unwrap()
+    );
+    send(&tc, &fx, "noisy", "second", "high").await;
+
+    // Only the muting agent loses the thread, including older messages
+    assert_eq!(
+        inbox_subjects(&tc, &fx, fx.listener).await,
+        vec!["elsewhere"]
+    );
+    assert_eq!(inbox_subjects(&tc, &fx, fx.bystander).await.len(), 3);
+
+    let thread: Vec<String> = MessageBmc::list_by_thread(&tc.ctx, &tc.mm, fx.project_id, "noisy")
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|m| m.subject)
+        .collect();
+    assert_eq!(thread, vec!["first", "second"]);
+}
+
+#[tokio::test]
+async fn test_unmute_restores_messages_sent_while_muted() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "mute-restore").await;
+
+    send(&tc, &fx, "noisy", "before", "normal").await;
+    ThreadMuteBmc::mute(&tc.ctx, &tc.mm, fx.listener, "noisy")
+        .await
+        .unwrap();
+    send(&tc, &fx, "noisy", "during", "normal").await;
+    assert!(inbox_subjects(&tc, &fx, fx.listener).await.is_empty());
+
+    assert!(
+        ThreadMuteBmc::unmute(&tc.ctx, &tc.mm, fx.listener, "noisy")
+            .await
+            .unwrap()
+    );
+    assert_eq!(
+        inbox_subjects(&tc, &fx, fx.listener).await,
+        vec!["before", "during"]
+    );
+
+    // Unmuting again is a no-op
+    assert!(
+        !ThreadMuteBmc::unmute(&tc.ctx, &tc.mm, fx.listener, "noisy")
+            .await
+            .unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_urgent_and_mentions_get_through() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "mute-bypass").await;
+
+    send(&tc, &fx, "noisy", "chatter", "normal").await;
+    ThreadMuteBmc::mute(&tc.ctx, &tc.mm, fx.listener, "noisy")
+        .await
+        .unwrap();
+    send(&tc, &fx, "noisy", "prod is down", "urgent").await;
+    send(&tc, &fx, "noisy", "@listener can you look?", "normal").await;
+    send(&tc, &fx, "noisy", "ping @ListenerBot", "normal").await;
+    send(&tc, &fx, "noisy", "listener without the at", "normal").await;
+
+    assert_eq!(
+        inbox_subjects(&tc, &fx, fx.listener).await,
+        vec!["@listener can you look?", "prod is down"]
+    );
+}
+
+#[tokio::test]
+async fn test_mute_state_and_unified_inbox_flag() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "mute-flag").await;
+
+    send(&tc, &fx, "noisy", "muted one", "normal").await;
+    send(&tc, &fx, "quiet", "plain one", "normal").await;
+
+    assert!(
+        ThreadMuteBmc::mute(&tc.ctx, &tc.mm, fx.listener, "noisy")
+            .await
+            .unwrap()
+    );
+    // Muting twice keeps the first mute
+    assert!(
+        !ThreadMuteBmc::mute(&tc.ctx, &tc.mm, fx.listener, "noisy")
+            .await
+            .unwrap()
+    );
+
+    let project_id = ProjectId::new(fx.project_id);
+    let mutes = ThreadMuteBmc::list_for_thread(&tc.ctx, &tc.mm, project_id, "noisy")
+        .await
+        .unwrap();
+    assert_eq!(mutes.len(), 1);
+    assert_eq!(mutes[0].agent_name, "Listener");
+
+    let items =
+        MessageBmc::list_unified_inbox_filtered(&tc.ctx, &tc.mm, &UnifiedInboxFilter::default())
+            .await
+            .unwrap();
+    for item in items {
+        assert_eq!(item.muted, item.subject == "muted one", "{}", item.subject);
+    }
+
+    // Threads must exist to be muted
+    let result = ThreadMuteBmc::mute(&tc.ctx, &tc.mm, fx.listener, "no-such-thread").await;
+    assert!(matches!(result, Err(Error::ThreadNotFound(_))));
+}
+
+#[tokio::test]
+async fn test_agent_delete_removes_mutes() {
+    let tc = TestContext::new().await.unwrap();
+    let fx = setup(&tc, "mute-delete").await;
+
+    send(&tc, &fx, "noisy", "muted one", "normal").await;
+    ThreadMuteBmc::mute(&tc.ctx, &tc.mm, fx.listener, "noisy")
+        .await
+        .unwrap();
+    AgentBmc::delete(&tc.ctx, &tc.mm, fx.listener)
+        .await
+        .unwrap();
+
+    let project_id = ProjectId::new(fx.project_id);
+    let mutes = ThreadMuteBmc::list_for_thread(&tc.ctx, &tc.mm, project_id, "noisy")
+        .await
+        .unwrap();
+    assert!(mutes.is_empty());
+}
@@ -15,7 +15,7 @@ mod common;
 use crate::common::TestContext;
 use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
 use mouchak_mail_core::model::project::ProjectBmc;
-use mouchak_mail_core::model::tool_metric::{ToolMetricBmc, ToolMetricForCreate};
+use mouchak_mail_core::model::tool_metric::{MAX_USAGE_TOOLS, ToolMetricBmc, ToolMetricForCreate};
 use mouchak_mail_core::types::ProjectId;
 use mouchak_mail_core::utils::slugify;
 
@@ -331,3 +331,178 @@ async fn test_tool_metric_with_agent() {
     assert_eq!(metrics[0].agent_id, Some(agent_id.into()));
     assert_eq!(metrics[0].tool_name, "reserve_file");
 }
+
+/// Test summary totals and the since-window cutoff
+#[tokio::test]
+async fn test_summary_since() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+
+    for (tool_name, status, duration_ms) in [
+        ("send_message", "success", 10),
+        ("send_message", "error", 30),
+        ("list_inbox", "success", 20),
+    ] {
+        let metric_c = ToolMetricForCreate {
+            project_id: None,
+            agent_id: None,
+            tool_name: tool_name.to_string(),
+            args_json: None,
+            status: status.to_string(),
+            error_code: (status == "error").then(|| "NOT_FOUND".to_string()),
+            duration_ms,
+        };
+        ToolMetricBmc::create(&tc.ctx, &tc.mm, metric_c)
+            .await
+            .expect("Failed to create metric");
+    }
+
+    let since = chrono::Utc::now().naive_utc() - chrono::Duration::hours(1);
+    let summary = ToolMetricBmc::summary(&tc.ctx, &tc.mm, since)
+        .await
+        .expect("Failed to get summary");
+
+    assert_eq!(summary.total_calls, 3);
+    assert_eq!(summary.error_count, 1);
+    assert!((summary.avg_duration_ms - 20.0).abs() < f64::EPSILON);
+    assert_eq!(summary.tools[0].tool_name, "send_message");
+    assert_eq!(summary.tools[0].count, 2);
+
+    // Nothing recorded after a future cutoff
+    let future = chrono::Utc::now().naive_utc() + chrono::Duration::hours(1);
+    let empty = ToolMetricBmc::summary(&tc.ctx, &tc.mm, future)
+        .await
+        .expect("Failed to get summary");
+    assert_eq!(empty.total_calls, 0);
+    assert!(empty.tools.is_empty());
+}
+
+/// Helper to record one invocation, returning its ID
+async fn record(tc: &TestContext, tool_name: &str, status: &str, duration_ms: i64) -> i64 {
+    let metric_c = ToolMetricForCreate {
+        project_id: None,
+        agent_id: None,
+        tool_name: tool_name.to_string(),
+        args_json: None,
+        status: status.to_string(),
+        error_code: (status == "error").then(|| "NOT_FOUND".to_string()),
+        duration_ms,
+    };
+    ToolMetricBmc::create(&tc.ctx, &tc.mm, metric_c)
+        .await
+        .expect("Failed to create metric")
+}
+
+/// Helper to move an invocation to another time
+async fn backdate(tc: &TestContext, id: i64, at: chrono::NaiveDateTime) {
+    tc.mm
+        .db_for_test()
+        .execute(
+            "UPDATE tool_metrics SET created_at = ? WHERE id = ?",
+            (at.format("%Y-%m-%d %H:%M:%S").to_string(), id),
+        )
+        .await
+        .expect("Failed to backdate metric");
+}
+
+/// Test usage error rates and nearest-rank latency percentiles
+#[tokio::test]
+async fn test_tool_usage_percentiles_and_error_rates() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+
+    // send_message: 10, 20, ..., 100 ms, two of them failed
+    for i in 1..=10 {
+        let status = if i <= 2 { "error" } else { "success" };
+        record(&tc, "send_message", status, i * 10).await;
+    }
+    record(&tc, "list_inbox", "success", 7).await;
+
+    let since = chrono::Utc::now().naive_utc() - chrono::Duration::hours(1);
+    let report = ToolMetricBmc::usage(&tc.ctx, &tc.mm, since)
+        .await
+        .expect("Failed to get usage");
+
+    assert_eq!(report.total_calls, 11);
+    assert_eq!(report.total_errors, 2);
+    assert!((report.error_rate - 2.0 / 11.0).abs() < 1e-9);
+    assert_eq!(report.tool_count, 2);
+    assert!(!report.truncated);
+
+    let send = &report.tools[0];
+    assert_eq!(send.tool_name, "send_message");
+    assert_eq!((send.calls, send.errors), (10, 2));
+    assert!((send.error_rate - 0.2).abs() < 1e-9);
+    assert!((send.avg_duration_ms - 55.0).abs() < 1e-9);
+    assert_eq!((send.p50_ms, send.p95_ms, send.p99_ms), (50, 100, 100));
+
+    let inbox = &report.tools[1];
+    assert_eq!(inbox.tool_name, "list_inbox");
+    assert_eq!(inbox.error_rate, 0.0);
+    assert_eq!((inbox.p50_ms, inbox.p95_ms, inbox.p99_ms), (7, 7, 7));
+}
+
+/// Test invocations land in the right time bucket and old ones are left out
+#[tokio::test]
+async fn test_tool_usage_buckets() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+
+    let since = chrono::Utc::now().naive_utc() - chrono::Duration::hours(1);
+    let early = record(&tc, "send_message", "success", 10).await;
+    backdate(&tc, early, since + chrono::Duration::seconds(630)).await;
+    let old = record(&tc, "send_message", "success", 10).await;
+    backdate(&tc, old, since - chrono::Duration::minutes(5)).await;
+    record(&tc, "send_message", "success", 10).await;
+
+    let report = ToolMetricBmc::usage(&tc.ctx, &tc.mm, since)
+        .await
+        .expect("Failed to get usage");
+
+    // An hour in 24 buckets of 150 seconds
+    assert_eq!(report.bucket_seconds, 150);
+    assert_eq!(report.total_calls, 2);
+    let buckets = &report.tools[0].buckets;
+    assert_eq!(buckets.len(), 24);
+    assert_eq!(buckets[4], 1);
+    assert_eq!(buckets[23], 1);
+    assert_eq!(buckets.iter().sum::<i64>(), 2);
+}
+
+/// Test an empty window and the cap on listed tools
+#[tokio::test]
+async fn test_tool_usage_empty_window_and_cap() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+
+    let since = chrono::Utc::now().naive_utc() - chrono::Duration::hours(1);
+    let empty = ToolMetricBmc::usage(&tc.ctx, &tc.mm, since)
+        .await
+        .expect("Failed to get usage");
+    assert_eq!((empty.total_calls, empty.total_errors), (0, 0));
+    assert_eq!(empty.error_rate, 0.0);
+    assert_eq!(empty.tool_count, 0);
+    assert!(empty.tools.is_empty());
+    assert!(!empty.truncated);
+
+    for i in 0..=MAX_USAGE_TOOLS {
+        record(&tc, &format!("tool_{:02}", i), "success", 5).await;
+    }
+    record(&tc, "tool_50", "success", 5).await;
+
+    let report = ToolMetricBmc::usage(&tc.ctx, &tc.mm, since)
+        .await
+        .expect("Failed to get usage");
+    assert_eq!(report.total_calls, MAX_USAGE_TOOLS + 2);
+    assert_eq!(report.tool_count, MAX_USAGE_TOOLS + 1);
+    assert_eq!(report.tools.len() as i64, MAX_USAGE_TOOLS);
+    assert!(report.truncated);
+    // Busiest first, then by name; the last tool by name is cut
+    assert_eq!(report.tools[0].tool_name, "tool_50");
+    assert_eq!(report.tools[1].tool_name, "tool_00");
+    assert!(report.tools.iter().all(|t| t.tool_name != "tool_49"));
+}
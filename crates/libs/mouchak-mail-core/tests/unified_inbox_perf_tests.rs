@@ -0,0 +1,148 @@
+//! Unified inbox query performance tests
+//!
+//! The query plan test always runs. The timing test seeds 200k messages and
+//! is ignored by default; run it with
+//! `cargo test -p mouchak-mail-core --test unified_inbox_perf_tests -- --ignored --nocapture`.
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use mouchak_mail_core::model::message::{
+    ImportanceFilter, InboxCursor, InboxSort, MessageBmc, UnifiedInboxFilter,
+};
+use mouchak_mail_core::testing::TestEnv;
+use std::time::{Duration, Instant};
+
+/// Messages seeded per project for the timing test
+const SEEDED_PER_PROJECT: usize = 100_000;
+
+/// Slowest acceptable page at 200k messages
+const PAGE_BUDGET: Duration = Duration::from_millis(150);
+
+/// Test each listing reads an index in sort order instead of sorting every
+/// matching message
+#[tokio::test]
+async fn test_unified_inbox_plans_use_sort_indexes() {
+    let env = TestEnv::new().await.unwrap();
+    let fixture = env
+        .fixtures()
+        .project("/perf/plan")
+        .agents(2)
+        .build()
+        .await
+        .unwrap();
+    env.seed_messages(&fixture, 100).await.unwrap();
+
+    let cases = [
+        ("idx_messages_created", UnifiedInboxFilter::default()),
+        (
+            "idx_messages_project_created_id",
+            UnifiedInboxFilter {
+                projects: vec![fixture.project_slug.clone()],
+                ..Default::default()
+            },
+        ),
+        (
+            "idx_messages_importance_rank",
+            UnifiedInboxFilter {
+                sort: InboxSort::ImportanceDesc,
+                ..Default::default()
+            },
+        ),
+    ];
+    for (index, filter) in cases {
+        let plan = env.explain_unified_inbox(&filter).await.unwrap();
+        assert!(
+            plan.iter().any(|step| step.contains(index)),
+            "expected {} in plan: {:#?}",
+            index,
+            plan
+        );
+        assert!(
+            !plan.iter().any(|step| step.contains("TEMP B-TREE")),
+            "{} plan should not sort: {:#?}",
+            index,
+            plan
+        );
+    }
+}
+
+/// Test every indexed inbox page stays fast at 200k messages
+///
+/// `ack_pending_first` is left out: whether a message is still pending
+/// depends on its recipients, so that sort checks every message.
+#[tokio::test]
+#[ignore = "seeds 200k messages; run with --ignored"]
+async fn test_unified_inbox_pages_at_200k_messages() {
+    let env = TestEnv::new().await.unwrap();
+    let mut slugs = Vec::new();
+    for key in ["/perf/alpha", "/perf/beta"] {
+        let fixture = env.fixtures().project(key).agents(4).build().await.unwrap();
+        env.seed_messages(&fixture, SEEDED_PER_PROJECT)
+            .await
+            .unwrap();
+        slugs.push(fixture.project_slug);
+    }
+
+    let first =
+        MessageBmc::list_unified_inbox_filtered(&env.ctx, &env.mm, &UnifiedInboxFilter::default())
+            .await
+            .unwrap();
+    let cursor = InboxCursor::new(InboxSort::CreatedDesc, first.last().unwrap().id);
+
+    let cases = [
+        ("all projects", UnifiedInboxFilter::default()),
+        (
+            "one project",
+            UnifiedInboxFilter {
+                projects: vec![slugs[0].clone()],
+                ..Default::default()
+            },
+        ),
+        (
+            "high importance",
+            UnifiedInboxFilter {
+                importance: ImportanceFilter::High,
+                ..Default::default()
+            },
+        ),
+        (
+            "sender",
+            UnifiedInboxFilter {
+                sender: Some("agent-2".to_string()),
+                ..Default::default()
+            },
+        ),
+        (
+            "by importance",
+            UnifiedInboxFilter {
+                sort: InboxSort::ImportanceDesc,
+                ..Default::default()
+            },
+        ),
+        (
+            "second page",
+            UnifiedInboxFilter {
+                cursor: Some(cursor),
+                ..Default::default()
+            },
+        ),
+    ];
+
+    for (name, filter) in cases {
+        let start = Instant::now();
+        let items = MessageBmc::list_unified_inbox_filtered(&env.ctx, &env.mm, &filter)
+            .await
+            .unwrap();
+        let elapsed = start.elapsed();
+        println!("{:<16} {:>4} items in {:?}", name, items.len(), elapsed);
+        assert_eq!(items.len(), 50, "{} should fill a page", name);
+        assert!(
+            elapsed < PAGE_BUDGET,
+            "{} took {:?}, budget is {:?}",
+            name,
+            elapsed,
+            PAGE_BUDGET
+        );
+    }
+}
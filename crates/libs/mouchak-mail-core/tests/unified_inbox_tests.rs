@@ -14,8 +14,11 @@
 mod common;
 
 use crate::common::TestContext;
+use mouchak_mail_core::Ctx;
 use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
-use mouchak_mail_core::model::message::{ImportanceFilter, MessageBmc, MessageForCreate};
+use mouchak_mail_core::model::message::{
+    ImportanceFilter, InboxCursor, MessageBmc, MessageForCreate, UnifiedInboxFilter,
+};
 use mouchak_mail_core::model::project::ProjectBmc;
 use mouchak_mail_core::utils::slugify;
 
@@ -83,6 +86,9 @@ async fn test_list_unified_inbox_returns_all_projects() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg1_c).await.unwrap();
 
@@ -98,6 +104,9 @@ async fn test_list_unified_inbox_returns_all_projects() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, msg2_c).await.unwrap();
 
@@ -139,6 +148,9 @@ async fn test_list_unified_inbox_filters_by_high_importance() {
         thread_id: None,
         importance: Some("high".to_string()),
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, high_msg).await.unwrap();
 
@@ -154,6 +166,9 @@ async fn test_list_unified_inbox_filters_by_high_importance() {
         thread_id: None,
         importance: Some("normal".to_string()),
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&tc.ctx, &tc.mm, normal_msg)
         .await
@@ -195,6 +210,9 @@ async fn test_list_unified_inbox_respects_limit() {
             thread_id: None,
             importance: None,
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
         };
         MessageBmc::create(&tc.ctx, &tc.mm, msg).await.unwrap();
     }
@@ -221,3 +239,252 @@ async fn test_list_unified_inbox_empty() {
 
     assert!(messages.is_empty(), "Empty inbox should return empty vec");
 }
+
+/// Test server-side project, sender and subject filters plus cursor paging
+#[tokio::test]
+async fn test_list_unified_inbox_filtered() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+
+    let (busy_id, busy_sender, busy_recipient) =
+        setup_project_with_agents(&tc, "/unified/busy").await;
+    let (quiet_id, quiet_sender, quiet_recipient) =
+        setup_project_with_agents(&tc, "/unified/quiet").await;
+
+    // The quiet project's message is buried under a busy project's traffic
+    let send = |project_id, sender_id, recipient_id, subject: String| MessageForCreate {
+        project_id,
+        sender_id,
+        recipient_ids: vec![recipient_id],
+        cc_ids: None,
+        bcc_ids: None,
+        subject,
+        body_md: "Body".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    MessageBmc::create(
+        &tc.ctx,
+        &tc.mm,
+        send(
+            quiet_id,
+            quiet_sender,
+            quiet_recipient,
+            "Quiet Deploy".into(),
+        ),
+    )
+    .await
+    .unwrap();
+    for i in 0..5 {
+        MessageBmc::create(
+            &tc.ctx,
+            &tc.mm,
+            send(busy_id, busy_sender, busy_recipient, format!("Busy {}", i)),
+        )
+        .await
+        .unwrap();
+    }
+
+    let quiet_slug = slugify("/unified/quiet");
+    let filter = UnifiedInboxFilter {
+        projects: vec![quiet_slug.clone()],
+        limit: 3,
+        ..Default::default()
+    };
+    let quiet = MessageBmc::list_unified_inbox_filtered(&tc.ctx, &tc.mm, &filter)
+        .await
+        .unwrap();
+    assert_eq!(quiet.len(), 1);
+    assert_eq!(quiet[0].project_slug, quiet_slug);
+
+    let filter = UnifiedInboxFilter {
+        query: Some("deploy".to_string()),
+        ..Default::default()
+    };
+    let by_subject = MessageBmc::list_unified_inbox_filtered(&tc.ctx, &tc.mm, &filter)
+        .await
+        .unwrap();
+    assert_eq!(by_subject.len(), 1);
+    assert_eq!(by_subject[0].subject, "Quiet Deploy");
+
+    let filter = UnifiedInboxFilter {
+        sender: Some("Nobody".to_string()),
+        ..Default::default()
+    };
+    assert!(
+        MessageBmc::list_unified_inbox_filtered(&tc.ctx, &tc.mm, &filter)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    // Page through the busy project two at a time without repeats
+    let busy_slug = slugify("/unified/busy");
+    let mut filter = UnifiedInboxFilter {
+        projects: vec![busy_slug],
+        limit: 2,
+        ..Default::default()
+    };
+    let mut seen = Vec::new();
+    loop {
+        let page = MessageBmc::list_unified_inbox_filtered(&tc.ctx, &tc.mm, &filter)
+            .await
+            .unwrap();
+        if page.is_empty() {
+            break;
+        }
+        filter.cursor = page.last().map(|m| InboxCursor::new(filter.sort, m.id));
+        seen.extend(page.into_iter().map(|m| m.id));
+    }
+    let mut unique = seen.clone();
+    unique.sort();
+    unique.dedup();
+    assert_eq!(seen.len(), 5);
+    assert_eq!(unique.len(), 5);
+}
+
+/// Test that a project-scoped context only sees its own projects
+#[tokio::test]
+async fn test_list_unified_inbox_scoped_ctx() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+
+    for key in ["/unified/mine", "/unified/theirs"] {
+        let (project_id, sender_id, recipient_id) = setup_project_with_agents(&tc, key).await;
+        let msg = MessageForCreate {
+            project_id,
+            sender_id,
+            recipient_ids: vec![recipient_id],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: format!("Message in {}", key),
+            body_md: "Body".to_string(),
+            thread_id: None,
+            importance: None,
+            ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
+        };
+        MessageBmc::create(&tc.ctx, &tc.mm, msg).await.unwrap();
+    }
+
+    let mine = slugify("/unified/mine");
+    let theirs = slugify("/unified/theirs");
+    let ctx = Ctx::scoped(1, None, vec![mine.clone()]);
+
+    let all = MessageBmc::list_unified_inbox_filtered(&ctx, &tc.mm, &UnifiedInboxFilter::default())
+        .await
+        .unwrap();
+    assert_eq!(all.len(), 1);
+    assert_eq!(all[0].project_slug, mine);
+
+    // Asking for another project explicitly yields nothing
+    let filter = UnifiedInboxFilter {
+        projects: vec![theirs.clone()],
+        ..Default::default()
+    };
+    assert!(
+        MessageBmc::list_unified_inbox_filtered(&ctx, &tc.mm, &filter)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let filter = UnifiedInboxFilter {
+        projects: vec![mine.clone(), theirs],
+        ..Default::default()
+    };
+    let both = MessageBmc::list_unified_inbox_filtered(&ctx, &tc.mm, &filter)
+        .await
+        .unwrap();
+    assert_eq!(both.len(), 1);
+    assert_eq!(both[0].project_slug, mine);
+}
+
+/// Test the created-time window: `since` is inclusive, `until` exclusive
+#[tokio::test]
+async fn test_list_unified_inbox_date_range_boundaries() {
+    let tc = TestContext::new()
+        .await
+        .expect("Failed to create test context");
+
+    let (project_id, sender_id, recipient_id) =
+        setup_project_with_agents(&tc, "/unified/dated").await;
+
+    let stamps = [
+        ("Before", "2026-03-01 09:59:59"),
+        ("At since", "2026-03-01 10:00:00"),
+        ("Inside", "2026-03-01 15:30:00"),
+        ("At until", "2026-03-02 10:00:00"),
+    ];
+    for (subject, created_ts) in stamps {
+        let msg_c = MessageForCreate {
+            project_id,
+            sender_id,
+            recipient_ids: vec![recipient_id],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: subject.to_string(),
+            body_md: "Body".to_string(),
+            thread_id: None,
+            importance: None,
+            ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
+        };
+        let id = MessageBmc::create(&tc.ctx, &tc.mm, msg_c).await.unwrap();
+        tc.mm
+            .db_for_test()
+            .execute(
+                "UPDATE messages SET created_ts = ? WHERE id = ?",
+                (created_ts, id),
+            )
+            .await
+            .unwrap();
+    }
+
+    let at =
+        |ts: &str| Some(chrono::NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M:%S").unwrap());
+    let subjects = |filter: UnifiedInboxFilter| {
+        let tc = &tc;
+        async move {
+            MessageBmc::list_unified_inbox_filtered(&tc.ctx, &tc.mm, &filter)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|m| m.subject)
+                .collect::<Vec<_>>()
+        }
+    };
+
+    let window = subjects(UnifiedInboxFilter {
+        since: at("2026-03-01 10:00:00"),
+        until: at("2026-03-02 10:00:00"),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(window, vec!["Inside", "At since"]);
+
+    let open_ended = subjects(UnifiedInboxFilter {
+        until: at("2026-03-01 10:00:00"),
+        ..Default::default()
+    })
+    .await;
+    assert_eq!(open_ended, vec!["Before"]);
+
+    let empty = subjects(UnifiedInboxFilter {
+        since: at("2026-03-01 15:30:00"),
+        until: at("2026-03-01 15:30:00"),
+        ..Default::default()
+    })
+    .await;
+    assert!(empty.is_empty());
+}
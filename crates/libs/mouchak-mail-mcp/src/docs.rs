@@ -1,7 +1,27 @@
-use crate::tools::ToolSchema;
+use crate::tools::prompts::{PromptSchema, get_prompt_schemas};
+use crate::tools::{ParameterSchema, ToolSchema, get_tool_schemas};
 
-/// Generate Markdown documentation for tools
-pub fn generate_markdown_docs(schemas: &[ToolSchema]) -> String {
+fn push_parameter_table(md: &mut String, heading: &str, params: &[ParameterSchema]) {
+    if params.is_empty() {
+        return;
+    }
+    md.push_str(&format!("### {}\n\n", heading));
+    md.push_str("| Name | Type | Required | Description |\n");
+    md.push_str("|------|------|----------|-------------|\n");
+    for param in params {
+        md.push_str(&format!(
+            "| `{}` | {} | {} | {} |\n",
+            param.name,
+            param.param_type,
+            if param.required { "Yes" } else { "No" },
+            param.description
+        ));
+    }
+}
+
+/// Generate Markdown documentation for tools, followed by a Prompts section
+/// when `prompts` is non-empty
+pub fn generate_markdown_docs(schemas: &[ToolSchema], prompts: &[PromptSchema]) -> String {
     let mut md = String::from("# Mouchak Mail - Tool Reference\n\n");
     md.push_str(&format!("Total tools: {}\n\n", schemas.len()));
     md.push_str("## Table of Contents\n\n");
@@ -20,20 +40,65 @@ pub fn generate_markdown_docs(schemas: &[ToolSchema]) -> String {
         md.push_str(&format!("## {}\n\n", schema.name));
         md.push_str(&format!("{}\n\n", schema.description));
 
-        if !schema.parameters.is_empty() {
-            md.push_str("### Parameters\n\n");
-            md.push_str("| Name | Type | Required | Description |\n");
-            md.push_str("|------|------|----------|-------------|\n");
-            for param in &schema.parameters {
-                md.push_str(&format!(
-                    "| `{}` | {} | {} | {} |\n",
-                    param.name,
-                    param.param_type,
-                    if param.required { "Yes" } else { "No" },
-                    param.description
-                ));
-            }
+        push_parameter_table(&mut md, "Parameters", &schema.parameters);
+    }
+
+    if !prompts.is_empty() {
+        md.push_str("\n---\n\n# Prompts\n\n");
+        md.push_str(&format!("Total prompts: {}\n\n", prompts.len()));
+        for prompt in prompts {
+            md.push_str(&format!("## {}\n\n", prompt.name));
+            md.push_str(&format!("{}\n\n", prompt.description));
+            push_parameter_table(&mut md, "Arguments", &prompt.arguments);
         }
     }
     md
 }
+
+/// Render the tool schemas for the `schema` subcommand.
+///
+/// `format` is `markdown` (or `md`) for Markdown docs; anything else gives
+/// pretty-printed JSON. Only enabled tools are included, so build slot tools
+/// appear only when `worktrees_enabled` is set, matching what the server
+/// exposes.
+pub fn render_schema(format: &str, worktrees_enabled: bool) -> serde_json::Result<String> {
+    let schemas: Vec<ToolSchema> = get_tool_schemas(worktrees_enabled)
+        .into_iter()
+        .filter(|schema| schema.enabled)
+        .collect();
+    if format == "markdown" || format == "md" {
+        Ok(generate_markdown_docs(&schemas, &get_prompt_schemas()))
+    } else {
+        serde_json::to_string_pretty(&schemas)
+    }
+}
+
+/// Render the tool table for the `tools` subcommand.
+///
+/// Enabled tools come first; tools the current configuration disables follow
+/// in their own table with the reason.
+pub fn render_tool_list(worktrees_enabled: bool) -> String {
+    let (enabled, disabled): (Vec<ToolSchema>, Vec<ToolSchema>) =
+        get_tool_schemas(worktrees_enabled)
+            .into_iter()
+            .partition(|schema| schema.enabled);
+    let mut out = format!("Mouchak Mail Tools ({} total)\n\n", enabled.len());
+    out.push_str(&format!("{:<30} DESCRIPTION\n", "TOOL"));
+    out.push_str(&format!("{}\n", "-".repeat(80)));
+    for schema in enabled {
+        out.push_str(&format!("{:<30} {}\n", schema.name, schema.description));
+    }
+    if !disabled.is_empty() {
+        out.push_str(&format!("\nDisabled ({})\n\n", disabled.len()));
+        out.push_str(&format!("{:<30} REASON\n", "TOOL"));
+        out.push_str(&format!("{}\n", "-".repeat(80)));
+        for schema in disabled {
+            out.push_str(&format!(
+                "{:<30} {}\n",
+                schema.name,
+                schema.disabled_reason.unwrap_or_default()
+            ));
+        }
+    }
+    out
+}
@@ -5,6 +5,7 @@ use tokio::io::{stdin, stdout};
 
 pub mod docs;
 pub mod tools;
+pub mod transport;
 pub use tools::{
     InvokeMacroParams, ListMacrosParams, MouchakMailService, RegisterMacroParams,
     UnregisterMacroParams,
@@ -27,13 +28,23 @@ pub async fn run_stdio(config: AppConfig) -> Result<()> {
 
     // Run over stdio
     let transport = (stdin(), stdout());
-    let server = service.serve(transport).await?;
+    let server = service.clone().serve(transport).await?;
 
     tracing::info!("MCP server initialized, waiting for requests...");
 
+    // A signal cancels the service; stdin closing ends it on its own.
+    let cancel = server.cancellation_token();
+    let signal_task = tokio::spawn(async move {
+        shutdown_signal().await;
+        cancel.cancel();
+    });
+
     // Wait for shutdown
     let quit_reason = server.waiting().await?;
     tracing::info!("Server shutting down: {:?}", quit_reason);
+    signal_task.abort();
+
+    service.shutdown().await?;
 
     Ok(())
 }
@@ -43,10 +54,13 @@ pub async fn run_sse(config: AppConfig) -> Result<()> {
         session::local::LocalSessionManager,
         tower::{StreamableHttpServerConfig, StreamableHttpService},
     };
-    use std::net::SocketAddr;
     use std::sync::Arc;
+    use transport::{MCP_PATH, keep_alive_interval, mcp_endpoint_url};
 
-    let addr: SocketAddr = format!("0.0.0.0:{}", config.mcp.port).parse()?;
+    let listener =
+        tokio::net::TcpListener::bind((config.mcp.host.as_str(), config.mcp.port)).await?;
+    let addr = listener.local_addr()?.to_string();
+    let drain_timeout = std::time::Duration::from_secs(config.server.shutdown_timeout_secs);
     tracing::info!(
         "Starting Mouchak Mail server (HTTP/SSE mode) on http://{}",
         addr
@@ -61,8 +75,10 @@ pub async fn run_sse(config: AppConfig) -> Result<()> {
 
     let server_config = StreamableHttpServerConfig {
         stateful_mode,
+        sse_keep_alive: keep_alive_interval(&config.mcp),
         ..Default::default()
     };
+    let mcp_config = config.mcp.clone();
 
     // Create a service factory that creates a new MouchakMailService for each connection
     let service_factory = move || {
@@ -79,18 +95,44 @@ pub async fn run_sse(config: AppConfig) -> Result<()> {
     // Create the StreamableHttpService (tower-compatible)
     let mcp_service = StreamableHttpService::new(service_factory, session_manager, server_config);
 
+    let endpoint = mcp_endpoint_url(&mcp_config, &axum::http::HeaderMap::new(), &addr);
     tracing::info!("HTTP/SSE MCP endpoints:");
-    tracing::info!("  - POST http://{}/mcp (for tool calls)", addr);
-    tracing::info!("  - GET  http://{}/mcp (for SSE stream)", addr);
+    tracing::info!("  - POST {} (for tool calls)", endpoint);
+    tracing::info!("  - GET  {} (for SSE stream)", endpoint);
+
+    // Create an Axum app with the MCP service, plus a discovery route that
+    // reports the endpoint's absolute URL as seen through any reverse proxy
+    let app = axum::Router::new()
+        .route(MCP_PATH, axum::routing::any_service(mcp_service))
+        .route(
+            "/mcp/endpoint",
+            axum::routing::get(move |headers: axum::http::HeaderMap| {
+                let url = mcp_endpoint_url(&mcp_config, &headers, &addr);
+                async move { axum::Json(serde_json::json!({ "url": url })) }
+            }),
+        );
+
+    // Run the server; on a signal stop accepting and drain for a bounded time
+    let (drain_tx, drain_rx) = tokio::sync::oneshot::channel::<()>();
+    let server = axum::serve(listener, app).with_graceful_shutdown(async move {
+        let _ = drain_rx.await;
+    });
+    let mut server = tokio::spawn(async move { server.await });
 
-    // Create an Axum app with the MCP service
-    let app = axum::Router::new().route("/mcp", axum::routing::any_service(mcp_service));
+    tokio::select! {
+        result = &mut server => return Ok(result??),
+        _ = shutdown_signal() => {
+            let _ = drain_tx.send(());
+        }
+    }
 
-    // Run the server
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await?;
+    match tokio::time::timeout(drain_timeout, &mut server).await {
+        Ok(result) => result??,
+        Err(_) => {
+            tracing::warn!("Drain timeout elapsed; dropping remaining connections");
+            server.abort();
+        }
+    }
 
     Ok(())
 }
@@ -6,36 +6,33 @@ use mouchak_mail_core::{
     ctx::Ctx,
     model::{
         ModelManager,
-        agent::{AgentBmc, AgentForCreate, AgentProfileUpdate},
+        agent::{AgentBmc, AgentForCreate, AgentForUpdate, AgentProfileUpdate},
         agent_capabilities::AgentCapabilityBmc,
+        agent_group::{AgentGroup, AgentGroupBmc},
         file_reservation::FileReservationBmc,
     },
     utils::mistake_detection::detect_unix_username_as_agent,
-    utils::validation::{validate_agent_name, validate_project_key},
+    utils::validation::validate_agent_name,
 };
 use rmcp::{ErrorData as McpError, model::CallToolResult, model::Content};
 use std::sync::Arc;
 
 use super::helpers;
 use super::{
-    CreateAgentIdentityParams, GetAgentProfileParams, ListAgentsParams, RegisterAgentParams,
-    UpdateAgentProfileParams, WhoisParams,
+    CreateAgentIdentityParams, GetAgentProfileParams, GroupAction, ListAgentsParams,
+    ManageAgentGroupParams, RegisterAgentParams, UpdateAgentParams, UpdateAgentProfileParams,
+    WhoisParams,
 };
+use crate::tools::errors::{ErrorCode, mcp_err};
 
 /// Register an agent in a project.
 pub async fn register_agent_impl(
     ctx: &Ctx,
     mm: &Arc<ModelManager>,
-    params: RegisterAgentParams,
+    mut params: RegisterAgentParams,
 ) -> Result<CallToolResult, McpError> {
     // Validate inputs
-    validate_project_key(&params.project_slug).map_err(|e| {
-        McpError::invalid_params(
-            format!("{}", e),
-            Some(serde_json::json!({ "details": e.context() })),
-        )
-    })?;
-
+    params.name = params.name.trim().to_string();
     validate_agent_name(&params.name).map_err(|e| {
         McpError::invalid_params(
             format!("{}", e),
@@ -46,45 +43,62 @@ pub async fn register_agent_impl(
     // Get project
     let project = helpers::resolve_project(ctx, mm, &params.project_slug).await?;
 
-    // Check if agent exists
-    match AgentBmc::get_by_name(ctx, mm, project.id, &params.name).await {
-        Ok(agent) => {
-            let msg = format!(
+    // Strict callers want the conflict instead of an update
+    if params.strict
+        && let Ok(agent) = AgentBmc::get_by_name(ctx, mm, project.id, &params.name).await
+    {
+        return Err(McpError::invalid_params(
+            format!(
                 "Agent '{}' already exists (id: {}, program: {})",
                 agent.name, agent.id, agent.program
-            );
-            Ok(CallToolResult::success(vec![Content::text(msg)]))
-        }
-        Err(_) => {
-            let agent_c = AgentForCreate {
-                project_id: project.id,
-                name: params.name.clone(),
-                program: params.program,
-                model: params.model,
-                task_description: params.task_description,
-            };
-
-            let id = AgentBmc::create(ctx, mm, agent_c)
-                .await
-                .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+            ),
+            None,
+        ));
+    }
 
-            AgentCapabilityBmc::grant_defaults(ctx, mm, id.get())
-                .await
-                .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+    let agent_c = AgentForCreate {
+        project_id: project.id,
+        name: params.name.clone(),
+        program: params.program,
+        model: params.model,
+        task_description: params.task_description,
+    };
 
-            let mut msg = format!(
-                "Registered agent '{}' with id {} (granted default capabilities)",
-                params.name,
-                id.get()
-            );
+    let registration = AgentBmc::register(ctx, mm, agent_c, params.strict)
+        .await
+        .map_err(|e| match e {
+            mouchak_mail_core::Error::Validation(ve) => McpError::invalid_params(
+                ve.to_string(),
+                Some(serde_json::json!({ "details": ve.context() })),
+            ),
+            other => McpError::internal_error(other.to_string(), None),
+        })?;
+    let id = registration.id;
+
+    if !registration.created {
+        let msg = format!(
+            "Agent '{}' already exists (id: {}); updated program, model and task",
+            params.name,
+            id.get()
+        );
+        return Ok(CallToolResult::success(vec![Content::text(msg)]));
+    }
 
-            if let Some(hint) = detect_unix_username_as_agent(&params.name) {
-                msg.push_str(&format!("\n\nHint: {}", hint.suggestion));
-            }
+    AgentCapabilityBmc::grant_defaults(ctx, mm, id.get())
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
 
-            Ok(CallToolResult::success(vec![Content::text(msg)]))
-        }
+    let mut msg = format!(
+        "Registered agent '{}' with id {} (granted default capabilities)",
+        params.name,
+        id.get()
+    );
+
+    if let Some(hint) = detect_unix_username_as_agent(&params.name) {
+        msg.push_str(&format!("\n\nHint: {}", hint.suggestion));
     }
+
+    Ok(CallToolResult::success(vec![Content::text(msg)]))
 }
 
 /// Get information about an agent.
@@ -139,6 +153,46 @@ pub async fn update_agent_profile_impl(
     Ok(CallToolResult::success(vec![Content::text(msg)]))
 }
 
+/// Rename an agent, update its task/model/program, or retire it.
+pub async fn update_agent_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: UpdateAgentParams,
+) -> Result<CallToolResult, McpError> {
+    let project = helpers::resolve_project(ctx, mm, &params.project_slug).await?;
+
+    let agent = AgentBmc::get_by_name(ctx, mm, project.id, &params.agent_name)
+        .await
+        .map_err(|e| McpError::invalid_params(format!("Agent not found: {}", e), None))?;
+
+    if let Some(name) = &params.name {
+        validate_agent_name(name).map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+    }
+
+    let update = AgentForUpdate {
+        name: params.name,
+        task_description: params.task_description,
+        model: params.model,
+        program: params.program,
+    };
+    let mut agent = AgentBmc::update(ctx, mm, agent.id, update)
+        .await
+        .map_err(|e| McpError::invalid_params(e.to_string(), None))?;
+
+    if params.retire {
+        agent = AgentBmc::retire(ctx, mm, agent.id)
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+    }
+
+    let msg = if agent.retired_ts.is_some() {
+        format!("Agent '{}' retired", agent.name)
+    } else {
+        format!("Updated agent '{}'", agent.name)
+    };
+    Ok(CallToolResult::success(vec![Content::text(msg)]))
+}
+
 /// Get detailed profile information for an agent.
 pub async fn get_agent_profile_impl(
     ctx: &Ctx,
@@ -196,21 +250,145 @@ pub async fn list_agents_impl(
         .await
         .map_err(|e| McpError::internal_error(e.to_string(), None))?;
 
-    let mut output = format!(
-        "Agents in '{}' ({}):\n\n",
-        params.project_slug,
-        agents.len()
-    );
+    let dnd = AgentBmc::list_active_dnd_policies(ctx, mm, project.id)
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+    let mut output = format!("Agents in '{}' ({}):\n\n", project.slug, agents.len());
     for a in &agents {
         output.push_str(&format!(
             "- {} (program: {}, model: {})\n  Task: {}\n",
             a.name, a.program, a.model, a.task_description
         ));
+        if let Some(policy) = dnd.get(&a.id) {
+            output.push_str(&format!(
+                "  Do not disturb: {}\n",
+                super::contacts::describe_dnd(policy)
+            ));
+        }
     }
 
     Ok(CallToolResult::success(vec![Content::text(output)]))
 }
 
+/// Maps agent group errors to tool errors.
+fn group_error(project_slug: &str, err: mouchak_mail_core::Error) -> McpError {
+    match err {
+        mouchak_mail_core::Error::GroupNotFound(name) => mcp_err!(
+            ErrorCode::InvalidInput,
+            &format!("Group '{}' does not exist in project '{}'", name, project_slug),
+            {
+                "group": name,
+                "suggestion": "List groups with action 'list' or create it with action 'create'"
+            }
+        ),
+        mouchak_mail_core::Error::AgentNotFound { name, suggestions } => mcp_err!(
+            ErrorCode::AgentNotFound,
+            &format!("Agent '{}' not found in project '{}'", name, project_slug),
+            { "agent_name": name, "suggestions": suggestions }
+        ),
+        e @ (mouchak_mail_core::Error::GroupNameTaken { .. }
+        | mouchak_mail_core::Error::InvalidInput(_)) => {
+            McpError::invalid_params(e.to_string(), None)
+        }
+        other => McpError::internal_error(other.to_string(), None),
+    }
+}
+
+fn describe_group(group: &AgentGroup) -> String {
+    if group.members.is_empty() {
+        format!("- group:{} (no members)\n", group.name)
+    } else {
+        format!(
+            "- group:{} ({}): {}\n",
+            group.name,
+            group.members.len(),
+            group.members.join(", ")
+        )
+    }
+}
+
+/// List, create, change or delete a project's agent groups.
+pub async fn manage_agent_group_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: ManageAgentGroupParams,
+) -> Result<CallToolResult, McpError> {
+    let project = helpers::resolve_project(ctx, mm, &params.project_slug).await?;
+    let to_mcp = |e| group_error(&project.slug, e);
+
+    let group_name = params
+        .group_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty());
+    let agent_names = params
+        .agent_names
+        .as_deref()
+        .map(helpers::split_names)
+        .unwrap_or_default();
+
+    let group_name = match (params.action, group_name) {
+        (GroupAction::List, _) => {
+            let groups = AgentGroupBmc::list_for_project(ctx, mm, project.id)
+                .await
+                .map_err(to_mcp)?;
+            let mut output = format!("Groups in '{}' ({}):\n\n", project.slug, groups.len());
+            for group in &groups {
+                output.push_str(&describe_group(group));
+            }
+            return Ok(CallToolResult::success(vec![Content::text(output)]));
+        }
+        (_, None) => {
+            return Err(McpError::invalid_params(
+                "group_name is required for this action".to_string(),
+                None,
+            ));
+        }
+        (GroupAction::AddMember | GroupAction::RemoveMember, _) if agent_names.is_empty() => {
+            return Err(McpError::invalid_params(
+                "agent_names is required for add_member and remove_member".to_string(),
+                None,
+            ));
+        }
+        (GroupAction::Delete, Some(name)) => {
+            AgentGroupBmc::delete(ctx, mm, project.id, name)
+                .await
+                .map_err(to_mcp)?;
+            return Ok(CallToolResult::success(vec![Content::text(format!(
+                "Deleted group '{}' from '{}'. Messages already sent to it are unchanged.",
+                name, project.slug
+            ))]));
+        }
+        (GroupAction::Create, Some(name)) => {
+            AgentGroupBmc::create(ctx, mm, project.id, name)
+                .await
+                .map_err(to_mcp)?;
+            name
+        }
+        (_, Some(name)) => name,
+    };
+
+    for agent_name in &agent_names {
+        if params.action == GroupAction::RemoveMember {
+            AgentGroupBmc::remove_member(ctx, mm, project.id, group_name, agent_name)
+                .await
+                .map_err(to_mcp)?;
+        } else {
+            AgentGroupBmc::add_member(ctx, mm, project.id, group_name, agent_name)
+                .await
+                .map_err(to_mcp)?;
+        }
+    }
+
+    let group = AgentGroupBmc::get_by_name(ctx, mm, project.id, group_name)
+        .await
+        .map_err(to_mcp)?;
+    Ok(CallToolResult::success(vec![Content::text(
+        describe_group(&group),
+    )]))
+}
+
 const ADJECTIVES: &[&str] = &[
     "Blue", "Green", "Red", "Golden", "Silver", "Crystal", "Dark", "Bright", "Swift", "Calm",
     "Bold", "Wise", "Noble", "Grand", "Mystic", "Ancient", "Lunar", "Solar", "Azure", "Coral",
@@ -36,8 +36,11 @@ pub async fn add_attachment_impl(
     );
 
     // Store attachment in Git
-    let repo = git_store::open_repo(&mm.repo_root)
+    let repo_arc = mm
+        .get_project_repo(&project.slug)
+        .await
         .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+    let repo = repo_arc.lock().await;
 
     let attachment_path = std::path::PathBuf::from("projects")
         .join(&project.slug)
@@ -51,6 +54,7 @@ pub async fn add_attachment_impl(
         .decode(&params.content_base64)
         .map_err(|e| McpError::invalid_params(format!("Invalid base64: {}", e), None))?;
 
+    let (author_name, author_email) = git_store::author_of(ctx.actor());
     git_store::commit_file(
         &repo,
         &attachment_path,
@@ -59,8 +63,8 @@ pub async fn add_attachment_impl(
             "attachment: {} for message {}",
             params.filename, params.message_id
         ),
-        "mcp-bot",
-        "mcp-bot@localhost",
+        author_name,
+        author_email,
     )
     .map_err(|e| McpError::internal_error(e.to_string(), None))?;
 
@@ -79,8 +83,11 @@ pub async fn get_attachment_impl(
 ) -> Result<CallToolResult, McpError> {
     let project = helpers::resolve_project(ctx, mm, &params.project_slug).await?;
 
-    let repo = git_store::open_repo(&mm.repo_root)
+    let repo_arc = mm
+        .get_project_history_repo(&project.slug)
+        .await
         .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+    let repo = repo_arc.lock().await;
 
     let attachment_path = std::path::PathBuf::from("projects")
         .join(&project.slug)
@@ -6,7 +6,7 @@ use mouchak_mail_core::{
     ctx::Ctx,
     model::{
         ModelManager,
-        agent::{AgentBmc, AgentProfileUpdate},
+        agent::{AgentBmc, AgentProfileUpdate, DndPolicy},
         agent_link::{AgentLinkBmc, AgentLinkForCreate},
         project::ProjectBmc,
     },
@@ -16,9 +16,10 @@ use std::sync::Arc;
 
 use super::helpers;
 use super::{
-    ListContactsParams, RequestContactParams, RespondContactByNameParams, RespondContactParams,
-    SetContactPolicyParams,
+    GetContactPolicyParams, ListContactsParams, RequestContactParams, RespondContactByNameParams,
+    RespondContactParams, SetContactPolicyParams,
 };
+use crate::tools::errors::{ErrorCode, mcp_err};
 
 /// Request to add another agent as a contact.
 pub async fn request_contact_impl(
@@ -168,31 +169,119 @@ pub async fn list_contacts_impl(
     Ok(CallToolResult::success(vec![Content::text(output)]))
 }
 
-/// Set an agent's contact acceptance policy.
+/// Set an agent's contact acceptance policy and/or "do not disturb" window.
+///
+/// Omitted fields keep their current value.
 pub async fn set_contact_policy_impl(
     ctx: &Ctx,
     mm: &Arc<ModelManager>,
     params: SetContactPolicyParams,
 ) -> Result<CallToolResult, McpError> {
+    if params.contact_policy.is_none()
+        && params.dnd_until.is_none()
+        && params.min_importance.is_none()
+    {
+        return Err(mcp_err!(
+            ErrorCode::InvalidInput,
+            "Nothing to set",
+            { "suggestion": "Pass contact_policy, dnd_until and/or min_importance" }
+        ));
+    }
+
     let project = helpers::resolve_project(ctx, mm, &params.project_slug).await?;
 
     let agent = AgentBmc::get_by_name(ctx, mm, project.id, &params.agent_name)
         .await
         .map_err(|e| McpError::invalid_params(format!("Agent not found: {}", e), None))?;
 
-    let update = AgentProfileUpdate {
-        task_description: None,
-        attachments_policy: None,
-        contact_policy: Some(params.contact_policy.clone()),
-    };
+    let mut lines = Vec::new();
+
+    if let Some(contact_policy) = params.contact_policy {
+        let update = AgentProfileUpdate {
+            task_description: None,
+            attachments_policy: None,
+            contact_policy: Some(contact_policy.clone()),
+        };
+
+        AgentBmc::update_profile(ctx, mm, agent.id, update)
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+        lines.push(format!(
+            "Contact policy for '{}' set to '{}'",
+            params.agent_name, contact_policy
+        ));
+    }
+
+    if params.dnd_until.is_some() || params.min_importance.is_some() {
+        let mut policy = AgentBmc::get_dnd_policy(ctx, mm, agent.id)
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+        if let Some(value) = params.dnd_until.as_deref().map(str::trim) {
+            policy.dnd_until = if value.is_empty() || value.eq_ignore_ascii_case("off") {
+                None
+            } else {
+                Some(helpers::parse_timestamp_param("dnd_until", value)?)
+            };
+        }
+        if let Some(level) = params.min_importance {
+            policy.min_importance = Some(level).filter(|l| !l.trim().is_empty());
+        }
+
+        let policy = AgentBmc::set_dnd_policy(ctx, mm, agent.id, policy)
+            .await
+            .map_err(|e| match e {
+                mouchak_mail_core::Error::InvalidInput(msg) => {
+                    mcp_err!(ErrorCode::InvalidInput, &msg)
+                }
+                other => McpError::internal_error(other.to_string(), None),
+            })?;
+
+        lines.push(format!(
+            "Do not disturb for '{}': {}",
+            params.agent_name,
+            describe_dnd(&policy)
+        ));
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(
+        lines.join("\n"),
+    )]))
+}
+
+/// Show an agent's contact policy and "do not disturb" window.
+pub async fn get_contact_policy_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: GetContactPolicyParams,
+) -> Result<CallToolResult, McpError> {
+    let (_project, agent) =
+        helpers::resolve_project_and_agent(ctx, mm, &params.project_slug, &params.agent_name)
+            .await?;
 
-    AgentBmc::update_profile(ctx, mm, agent.id, update)
+    let policy = AgentBmc::get_dnd_policy(ctx, mm, agent.id)
         .await
         .map_err(|e| McpError::internal_error(e.to_string(), None))?;
 
-    let msg = format!(
-        "Contact policy for '{}' set to '{}'",
-        params.agent_name, params.contact_policy
+    let output = format!(
+        "Agent: {}\nContact Policy: {}\nDo Not Disturb: {}",
+        agent.name,
+        agent.contact_policy,
+        describe_dnd(&policy)
     );
-    Ok(CallToolResult::success(vec![Content::text(msg)]))
+    Ok(CallToolResult::success(vec![Content::text(output)]))
+}
+
+/// One-line summary of a DND policy, e.g. "until 2025-01-01 09:30:00 UTC
+/// (delivering high and above)".
+pub(crate) fn describe_dnd(policy: &DndPolicy) -> String {
+    match policy.dnd_until {
+        Some(until) if policy.is_active(chrono::Utc::now().naive_utc()) => format!(
+            "until {} UTC (delivering {} and above)",
+            until,
+            policy.min_importance.as_deref().unwrap_or("urgent")
+        ),
+        _ => "off".to_string(),
+    }
 }
@@ -9,13 +9,23 @@ pub enum ErrorCode {
     AgentNotFound,
     AgentAlreadyExists,
     CapabilityDenied,
+    /// An agent token was used to act as a different agent
+    AgentIdentityMismatch,
 
     ProjectNotFound,
     ProjectAlreadyExists,
 
     MessageNotFound,
+    /// The message was removed by the retention policy
+    NotFoundPruned,
     ThreadNotFound,
     InvalidRecipient,
+    /// The sender's project may not message agents in other projects
+    ForbiddenCrossProject,
+    NotMessageSender,
+    RecallWindowExpired,
+    /// The message was recalled by its sender
+    MessageRecalled,
 
     ReservationConflict,
     ReservationNotFound,
@@ -30,6 +40,7 @@ pub enum ErrorCode {
     InvalidAgentName,
     InvalidProjectKey,
     InvalidTtl,
+    ValidationError,
 
     DatabaseError,
     InternalError,
@@ -50,6 +61,7 @@ impl ErrorCode {
             Self::AgentNotFound
             | Self::ProjectNotFound
             | Self::MessageNotFound
+            | Self::NotFoundPruned
             | Self::ThreadNotFound
             | Self::ProductNotFound
             | Self::MacroNotFound
@@ -65,11 +77,17 @@ impl ErrorCode {
             }
 
             Self::CapabilityDenied
+            | Self::AgentIdentityMismatch
             | Self::InvalidRecipient
+            | Self::ForbiddenCrossProject
             | Self::InvalidInput
             | Self::InvalidAgentName
             | Self::InvalidProjectKey
             | Self::InvalidTtl
+            | Self::ValidationError
+            | Self::NotMessageSender
+            | Self::RecallWindowExpired
+            | Self::MessageRecalled
             | Self::ReservationExpired => McpError::invalid_params(message.to_string(), Some(data)),
 
             Self::DatabaseError | Self::InternalError => {
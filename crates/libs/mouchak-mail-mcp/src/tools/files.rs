@@ -9,10 +9,12 @@ use mouchak_mail_core::{
         agent::AgentBmc,
         agent_capabilities::AgentCapabilityBmc,
         file_reservation::{FileReservationBmc, FileReservationForCreate},
+        reservation_queue::{
+            DEFAULT_QUEUE_WAIT_SECONDS, ReservationQueueBmc, ReservationQueueForCreate,
+            ReservationRequestOutcome,
+        },
     },
-    utils::validation::{
-        validate_agent_name, validate_project_key, validate_reservation_path, validate_ttl,
-    },
+    utils::validation::{validate_agent_name, validate_reservation_path, validate_ttl},
 };
 use rmcp::{ErrorData as McpError, model::CallToolResult, model::Content};
 use std::sync::Arc;
@@ -20,9 +22,11 @@ use std::sync::Arc;
 use super::helpers;
 use super::{
     FileReservationParams, FileReservationPathsParams, ForceReleaseReservationParams,
-    ListReservationsParams, ReleaseFileReservationsByAgentParams, ReleaseReservationParams,
-    RenewFileReservationParams, RenewFileReservationsByAgentParams,
+    ListMyReservationsParams, ListReservationQueueParams, ListReservationsParams,
+    ReleaseFileReservationsByAgentParams, ReleaseReservationParams, ReleaseReservationsParams,
+    RenewFileReservationParams, RenewFileReservationsByAgentParams, TransferReservationParams,
 };
+use crate::tools::errors::{ErrorCode, mcp_err};
 
 /// Reserve a file path pattern to prevent conflicts between agents.
 pub async fn reserve_file_impl(
@@ -31,13 +35,6 @@ pub async fn reserve_file_impl(
     params: FileReservationParams,
 ) -> Result<CallToolResult, McpError> {
     // Validate inputs
-    validate_project_key(&params.project_slug).map_err(|e| {
-        McpError::invalid_params(
-            format!("{}", e),
-            Some(serde_json::json!({ "details": e.context() })),
-        )
-    })?;
-
     validate_agent_name(&params.agent_name).map_err(|e| {
         McpError::invalid_params(
             format!("{}", e),
@@ -66,6 +63,7 @@ pub async fn reserve_file_impl(
     let agent = AgentBmc::get_by_name(ctx, mm, project.id, &params.agent_name)
         .await
         .map_err(|e| McpError::invalid_params(format!("Agent not found: {}", e), None))?;
+    helpers::ensure_acting_as(ctx, &agent)?;
 
     if !AgentCapabilityBmc::check(ctx, mm, agent.id.get(), "file_reservation_paths")
         .await
@@ -119,7 +117,7 @@ pub async fn list_reservations_impl(
 
     let mut output = format!(
         "Active reservations in '{}' ({}):\n\n",
-        params.project_slug,
+        project.slug,
         reservations.len()
     );
     for r in &reservations {
@@ -132,6 +130,78 @@ pub async fn list_reservations_impl(
     Ok(CallToolResult::success(vec![Content::text(output)]))
 }
 
+/// List queued reservation requests and what each one waits on.
+pub async fn list_reservation_queue_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: ListReservationQueueParams,
+) -> Result<CallToolResult, McpError> {
+    let project = helpers::resolve_project(ctx, mm, &params.project_slug).await?;
+
+    let mut queue = ReservationQueueBmc::list_for_project(ctx, mm, project.id)
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+    if let Some(filter) = &params.path_pattern {
+        queue.retain(|q| {
+            mouchak_mail_core::utils::pathspec::paths_conflict(&q.path_pattern, filter)
+        });
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    let active: Vec<_> = FileReservationBmc::list_active_for_project(ctx, mm, project.id)
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?
+        .into_iter()
+        .filter(|r| r.expires_ts > now)
+        .collect();
+
+    let entries: Vec<serde_json::Value> = queue
+        .iter()
+        .map(|q| {
+            let blocked_by: Vec<serde_json::Value> = active
+                .iter()
+                .filter(|r| {
+                    r.agent_id != q.agent_id
+                        && (r.exclusive || q.exclusive)
+                        && mouchak_mail_core::utils::pathspec::paths_conflict(
+                            &r.path_pattern,
+                            &q.path_pattern,
+                        )
+                })
+                .map(|r| {
+                    serde_json::json!({
+                        "reservation_id": r.id,
+                        "agent_id": r.agent_id,
+                        "path_pattern": r.path_pattern,
+                        "expires_ts": r.expires_ts.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "queue_id": q.id,
+                "agent_name": q.agent_name,
+                "path_pattern": q.path_pattern,
+                "exclusive": q.exclusive,
+                "position": q.position,
+                "queued_ts": q.created_ts.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                "wait_until": q.wait_until.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                "blocked_by": blocked_by,
+            })
+        })
+        .collect();
+
+    let output = serde_json::json!({
+        "project_slug": project.slug,
+        "count": entries.len(),
+        "queue": entries,
+    });
+    let json_text = serde_json::to_string_pretty(&output).map_err(|e| {
+        McpError::internal_error(format!("Failed to serialize response: {}", e), None)
+    })?;
+
+    Ok(CallToolResult::success(vec![Content::text(json_text)]))
+}
+
 /// Release a file reservation by ID.
 pub async fn release_reservation_impl(
     ctx: &Ctx,
@@ -160,22 +230,141 @@ pub async fn force_release_reservation_impl(
     Ok(CallToolResult::success(vec![Content::text(msg)]))
 }
 
-/// Extend the TTL of a file reservation.
+/// Hand a live reservation to another agent in the same project.
+///
+/// Both agents get a system message; transferring to the current holder
+/// changes nothing.
+pub async fn transfer_reservation_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: TransferReservationParams,
+) -> Result<CallToolResult, McpError> {
+    let (project, to_agent) =
+        helpers::resolve_project_and_agent(ctx, mm, &params.project_slug, &params.to_agent).await?;
+
+    let not_found = || {
+        mcp_err!(
+            ErrorCode::ReservationNotFound,
+            &format!(
+                "Reservation {} not found in project '{}'",
+                params.reservation_id, project.slug
+            ),
+            {
+                "reservation_id": params.reservation_id,
+                "suggestion": "Check active reservations with list_file_reservations"
+            }
+        )
+    };
+    let reservation = FileReservationBmc::get(ctx, mm, params.reservation_id)
+        .await
+        .ok()
+        .filter(|r| r.project_id == project.id)
+        .ok_or_else(not_found)?;
+    let from_agent = AgentBmc::get(ctx, mm, reservation.agent_id)
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+    let transferred =
+        FileReservationBmc::transfer(ctx, mm, reservation.id, to_agent.id, &params.reason)
+            .await
+            .map_err(|e| match e {
+                mouchak_mail_core::Error::FileReservationNotFound(_) => not_found(),
+                mouchak_mail_core::Error::FileReservationInactive(msg) => mcp_err!(
+                    ErrorCode::ReservationExpired,
+                    &format!("Reservation no longer active: {}", msg),
+                    {
+                        "reservation_id": reservation.id,
+                        "path_pattern": reservation.path_pattern,
+                        "suggestion": "Reserve the path directly with file_reservation_paths"
+                    }
+                ),
+                other => McpError::internal_error(other.to_string(), None),
+            })?;
+
+    let output = serde_json::json!({
+        "reservation_id": transferred.id,
+        "path_pattern": transferred.path_pattern,
+        "from_agent": from_agent.name,
+        "to_agent": to_agent.name,
+        "transferred": from_agent.id != to_agent.id,
+        "expires_ts": transferred.expires_ts.format("%Y-%m-%dT%H:%M:%S").to_string(),
+    });
+    let json_text = serde_json::to_string_pretty(&output).map_err(|e| {
+        McpError::internal_error(format!("Failed to serialize response: {}", e), None)
+    })?;
+
+    Ok(CallToolResult::success(vec![Content::text(json_text)]))
+}
+
+/// Renew a live file reservation so it expires `ttl_seconds` from now.
+///
+/// The reservation is looked up by ID or by path pattern and must belong to
+/// the calling agent. Released or expired reservations cannot be renewed.
 pub async fn renew_file_reservation_impl(
     ctx: &Ctx,
     mm: &Arc<ModelManager>,
     params: RenewFileReservationParams,
 ) -> Result<CallToolResult, McpError> {
-    let ttl = params.ttl_seconds.unwrap_or(3600);
-    let new_expires = chrono::Utc::now().naive_utc() + chrono::Duration::seconds(ttl);
+    let (project, agent) =
+        helpers::resolve_project_and_agent(ctx, mm, &params.project_slug, &params.agent_name)
+            .await?;
+    helpers::ensure_acting_as(ctx, &agent)?;
+
+    let reservation = match (params.reservation_id, params.path_pattern.as_deref()) {
+        (Some(id), _) => FileReservationBmc::get(ctx, mm, id).await.ok(),
+        (None, Some(path)) => {
+            FileReservationBmc::get_latest_by_path(ctx, mm, project.id, agent.id, path)
+                .await
+                .map_err(|e| McpError::internal_error(e.to_string(), None))?
+        }
+        (None, None) => {
+            return Err(McpError::invalid_params(
+                "Provide either reservation_id or path_pattern".to_string(),
+                None,
+            ));
+        }
+    };
 
-    FileReservationBmc::renew(ctx, mm, params.reservation_id, new_expires)
+    let reservation = reservation
+        .filter(|r| r.project_id == project.id && r.agent_id == agent.id)
+        .ok_or_else(|| {
+            mcp_err!(
+                ErrorCode::ReservationNotFound,
+                &format!("No reservation held by '{}' matches the request", agent.name),
+                {
+                    "reservation_id": params.reservation_id,
+                    "path_pattern": params.path_pattern,
+                    "suggestion": "Check held reservations with list_my_reservations"
+                }
+            )
+        })?;
+
+    let ttl = params.ttl_seconds.unwrap_or(3600);
+    let renewed = FileReservationBmc::renew_active(ctx, mm, reservation.id, ttl)
         .await
-        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        .map_err(|e| match e {
+            mouchak_mail_core::Error::FileReservationInactive(msg) => mcp_err!(
+                ErrorCode::ReservationExpired,
+                &format!("Reservation no longer active: {}", msg),
+                {
+                    "reservation_id": reservation.id,
+                    "path_pattern": reservation.path_pattern,
+                    "suggestion": "Re-reserve the path with file_reservation_paths"
+                }
+            ),
+            mouchak_mail_core::Error::Validation(ve) => mcp_err!(
+                ErrorCode::InvalidTtl,
+                &ve.to_string(),
+                { "details": ve.context() }
+            ),
+            other => McpError::internal_error(other.to_string(), None),
+        })?;
 
     let msg = format!(
-        "Renewed reservation {} until {}",
-        params.reservation_id, new_expires
+        "Renewed reservation {} ({}) until {}",
+        renewed.id,
+        renewed.path_pattern,
+        renewed.expires_ts.and_utc().to_rfc3339()
     );
     Ok(CallToolResult::success(vec![Content::text(msg)]))
 }
@@ -187,13 +376,6 @@ pub async fn file_reservation_paths_impl(
     params: FileReservationPathsParams,
 ) -> Result<CallToolResult, McpError> {
     // Validate inputs
-    validate_project_key(&params.project_slug).map_err(|e| {
-        McpError::invalid_params(
-            format!("{}", e),
-            Some(serde_json::json!({ "details": e.context() })),
-        )
-    })?;
-
     validate_agent_name(&params.agent_name).map_err(|e| {
         McpError::invalid_params(
             format!("{}", e),
@@ -225,6 +407,7 @@ pub async fn file_reservation_paths_impl(
     let agent = AgentBmc::get_by_name(ctx, mm, project.id, &params.agent_name)
         .await
         .map_err(|e| McpError::invalid_params(format!("Agent not found: {}", e), None))?;
+    helpers::ensure_acting_as(ctx, &agent)?;
 
     let active_reservations = FileReservationBmc::list_active_for_project(ctx, mm, project.id)
         .await
@@ -237,6 +420,55 @@ pub async fn file_reservation_paths_impl(
     let mut granted = Vec::new();
     let mut conflicts = Vec::new();
 
+    if params.wait.unwrap_or(false) {
+        let mut queued = Vec::new();
+        for path in params.paths {
+            let outcome = ReservationQueueBmc::reserve_or_enqueue(
+                ctx,
+                mm,
+                ReservationQueueForCreate {
+                    project_id: project.id,
+                    agent_id: agent.id,
+                    path_pattern: path.clone(),
+                    exclusive: params.exclusive,
+                    reason: params.reason.clone().unwrap_or_default(),
+                    ttl_seconds: ttl,
+                    wait_timeout_seconds: params
+                        .wait_timeout_seconds
+                        .unwrap_or(DEFAULT_QUEUE_WAIT_SECONDS),
+                },
+            )
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+            match outcome {
+                ReservationRequestOutcome::Granted { reservation_id } => granted.push(format!(
+                    "Granted: {} (id: {}, expires: {})",
+                    path, reservation_id, expires_ts
+                )),
+                ReservationRequestOutcome::Queued(entry) => queued.push(format!(
+                    "Queued: {} (queue id: {}, position: {}, waits until: {})",
+                    path, entry.id, entry.position, entry.wait_until
+                )),
+            }
+        }
+
+        let mut output = format!(
+            "Granted {} reservations, queued {}\n\n",
+            granted.len(),
+            queued.len()
+        );
+        for line in granted.iter().chain(&queued) {
+            output.push_str(&format!("  {}\n", line));
+        }
+        if !queued.is_empty() {
+            output.push_str(
+                "\nQueued paths are granted in order as they free up; you will get a message when each one is.\n",
+            );
+        }
+        return Ok(CallToolResult::success(vec![Content::text(output)]));
+    }
+
     for path in params.paths {
         // Check conflicts using glob pattern matching
         for res in &active_reservations {
@@ -291,13 +523,6 @@ pub async fn release_file_reservations_by_path_impl(
     mm: &Arc<ModelManager>,
     params: ReleaseFileReservationsByAgentParams,
 ) -> Result<CallToolResult, McpError> {
-    validate_project_key(&params.project_slug).map_err(|e| {
-        McpError::invalid_params(
-            format!("{}", e),
-            Some(serde_json::json!({ "details": e.context() })),
-        )
-    })?;
-
     validate_agent_name(&params.agent_name).map_err(|e| {
         McpError::invalid_params(
             format!("{}", e),
@@ -310,6 +535,7 @@ pub async fn release_file_reservations_by_path_impl(
     let agent = AgentBmc::get_by_name(ctx, mm, project.id, &params.agent_name)
         .await
         .map_err(|e| McpError::invalid_params(format!("Agent not found: {}", e), None))?;
+    helpers::ensure_acting_as(ctx, &agent)?;
 
     let mut released_ids = Vec::new();
 
@@ -338,7 +564,7 @@ pub async fn release_file_reservations_by_path_impl(
         "released_count": released_ids.len(),
         "released_ids": released_ids,
         "agent_name": params.agent_name,
-        "project_slug": params.project_slug
+        "project_slug": project.slug
     });
 
     let json_text = serde_json::to_string_pretty(&output).map_err(|e| {
@@ -353,13 +579,6 @@ pub async fn renew_file_reservations_by_agent_impl(
     mm: &Arc<ModelManager>,
     params: RenewFileReservationsByAgentParams,
 ) -> Result<CallToolResult, McpError> {
-    validate_project_key(&params.project_slug).map_err(|e| {
-        McpError::invalid_params(
-            format!("{}", e),
-            Some(serde_json::json!({ "details": e.context() })),
-        )
-    })?;
-
     validate_agent_name(&params.agent_name).map_err(|e| {
         McpError::invalid_params(
             format!("{}", e),
@@ -372,6 +591,7 @@ pub async fn renew_file_reservations_by_agent_impl(
     let agent = AgentBmc::get_by_name(ctx, mm, project.id, &params.agent_name)
         .await
         .map_err(|e| McpError::invalid_params(format!("Agent not found: {}", e), None))?;
+    helpers::ensure_acting_as(ctx, &agent)?;
 
     let all_reservations = FileReservationBmc::list_active_for_project(ctx, mm, project.id)
         .await
@@ -407,10 +627,153 @@ pub async fn renew_file_reservations_by_agent_impl(
         "renewed_ids": renewed_ids,
         "new_expires_ts": new_expires.format("%Y-%m-%dT%H:%M:%S").to_string(),
         "agent_name": params.agent_name,
-        "project_slug": params.project_slug
+        "project_slug": project.slug
     });
 
     Ok(CallToolResult::success(vec![Content::text(
         serde_json::to_string_pretty(&output).unwrap_or_default(),
     )]))
 }
+
+/// List the active reservations held by the calling agent.
+pub async fn list_my_reservations_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: ListMyReservationsParams,
+) -> Result<CallToolResult, McpError> {
+    validate_agent_name(&params.agent_name).map_err(|e| {
+        McpError::invalid_params(
+            format!("{}", e),
+            Some(serde_json::json!({ "details": e.context() })),
+        )
+    })?;
+
+    let project = helpers::resolve_project(ctx, mm, &params.project_slug).await?;
+
+    let agent = AgentBmc::get_by_name(ctx, mm, project.id, &params.agent_name)
+        .await
+        .map_err(|e| McpError::invalid_params(format!("Agent not found: {}", e), None))?;
+
+    let reservations = FileReservationBmc::list_active_for_agent(ctx, mm, project.id, agent.id)
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+    let items: Vec<_> = reservations
+        .iter()
+        .map(|r| {
+            serde_json::json!({
+                "id": r.id,
+                "path_pattern": r.path_pattern,
+                "exclusive": r.exclusive,
+                "reason": r.reason,
+                "created_ts": r.created_ts.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                "expires_ts": r.expires_ts.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            })
+        })
+        .collect();
+
+    let output = serde_json::json!({
+        "count": items.len(),
+        "reservations": items,
+        "agent_name": params.agent_name,
+        "project_slug": project.slug
+    });
+
+    let json_text = serde_json::to_string_pretty(&output).map_err(|e| {
+        McpError::internal_error(format!("Failed to serialize response: {}", e), None)
+    })?;
+
+    Ok(CallToolResult::success(vec![Content::text(json_text)]))
+}
+
+/// Release the calling agent's reservations by path, or all of them.
+///
+/// Paths the agent does not hold are reported as `not_found`; paths held only
+/// by other agents are reported as `refused` and left untouched.
+pub async fn release_reservations_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: ReleaseReservationsParams,
+) -> Result<CallToolResult, McpError> {
+    validate_agent_name(&params.agent_name).map_err(|e| {
+        McpError::invalid_params(
+            format!("{}", e),
+            Some(serde_json::json!({ "details": e.context() })),
+        )
+    })?;
+
+    let release_all = params.all.unwrap_or(false);
+    if !release_all && params.paths.as_ref().is_none_or(|p| p.is_empty()) {
+        return Err(McpError::invalid_params(
+            "Provide 'paths' to release or set 'all' to true".to_string(),
+            None,
+        ));
+    }
+
+    let project = helpers::resolve_project(ctx, mm, &params.project_slug).await?;
+
+    let agent = AgentBmc::get_by_name(ctx, mm, project.id, &params.agent_name)
+        .await
+        .map_err(|e| McpError::invalid_params(format!("Agent not found: {}", e), None))?;
+    helpers::ensure_acting_as(ctx, &agent)?;
+
+    let paths: Vec<String> = if release_all {
+        FileReservationBmc::list_active_for_agent(ctx, mm, project.id, agent.id)
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?
+            .into_iter()
+            .map(|r| r.path_pattern)
+            .collect()
+    } else {
+        params.paths.clone().unwrap_or_default()
+    };
+
+    let active = FileReservationBmc::list_active_for_project(ctx, mm, project.id)
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+    let mut released = Vec::new();
+    let mut not_found = Vec::new();
+    let mut refused = Vec::new();
+
+    for path in paths {
+        let released_id =
+            FileReservationBmc::release_by_path(ctx, mm, project.id.get(), agent.id.get(), &path)
+                .await
+                .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+        if let Some(id) = released_id {
+            released.push(serde_json::json!({ "path": path, "id": id }));
+            continue;
+        }
+
+        let holder = active
+            .iter()
+            .find(|r| r.path_pattern == path && r.agent_id != agent.id);
+        match holder {
+            Some(r) => {
+                let holder_name = AgentBmc::get(ctx, mm, r.agent_id)
+                    .await
+                    .map(|a| a.name)
+                    .unwrap_or_else(|_| r.agent_id.to_string());
+                refused.push(serde_json::json!({ "path": path, "held_by": holder_name }));
+            }
+            None => not_found.push(path),
+        }
+    }
+
+    let output = serde_json::json!({
+        "released_count": released.len(),
+        "released": released,
+        "not_found": not_found,
+        "refused": refused,
+        "agent_name": params.agent_name,
+        "project_slug": project.slug
+    });
+
+    let json_text = serde_json::to_string_pretty(&output).map_err(|e| {
+        McpError::internal_error(format!("Failed to serialize response: {}", e), None)
+    })?;
+
+    Ok(CallToolResult::success(vec![Content::text(json_text)]))
+}
@@ -6,10 +6,16 @@ use mouchak_mail_core::{
     ctx::Ctx,
     model::{
         ModelManager,
-        agent::{Agent, AgentBmc},
+        agent::{ADDRESS_SEPARATOR, Agent, AgentBmc},
+        agent_group::{AgentGroupBmc, GROUP_ADDRESS_PREFIX, GroupExpansion},
         project::{Project, ProjectBmc},
     },
-    utils::validation::{validate_agent_name, validate_project_key},
+    utils::{
+        discover_project_identity,
+        validation::{
+            AGENT_NAME_MIN_LEN, sanitize_agent_name, validate_agent_name, validate_project_key,
+        },
+    },
 };
 use rmcp::ErrorData as McpError;
 use std::sync::Arc;
@@ -18,6 +24,8 @@ use crate::tools::errors::{ErrorCode, mcp_err};
 
 /// Resolve a project by slug or human_key.
 ///
+/// An empty slug falls back to discovering the project from marker files
+/// above the server's working directory (see [`discover_project`]).
 /// Validates input format before querying database.
 /// Returns the project or an McpError with structured error code and suggestion.
 pub async fn resolve_project(
@@ -25,6 +33,14 @@ pub async fn resolve_project(
     mm: &Arc<ModelManager>,
     slug: &str,
 ) -> Result<Project, McpError> {
+    let discovered;
+    let slug = if slug.trim().is_empty() {
+        discovered = discover_project()?;
+        discovered.as_str()
+    } else {
+        slug
+    };
+
     // Validate input format first
     if let Err(e) = validate_project_key(slug) {
         return Err(mcp_err!(
@@ -46,6 +62,32 @@ pub async fn resolve_project(
     })
 }
 
+/// Discover the project slug from the server's working directory.
+///
+/// The stdio server is normally launched inside the repository, so the
+/// `.mouchak-mail-project-id` marker (or `discovery.yaml`) written by the CLI
+/// identifies the project when a tool call omits `project_slug`.
+pub fn discover_project() -> Result<String, McpError> {
+    let cwd = std::env::current_dir().map_err(|e| {
+        mcp_err!(
+            ErrorCode::ProjectNotFound,
+            &format!("project_slug omitted and working directory unavailable: {e}"),
+            { "suggestion": "Pass project_slug explicitly" }
+        )
+    })?;
+
+    discover_project_identity(&cwd).map_err(|e| {
+        mcp_err!(
+            ErrorCode::ProjectNotFound,
+            &format!("project_slug omitted and discovery failed: {e}"),
+            {
+                "cwd": cwd.display().to_string(),
+                "suggestion": "Pass project_slug explicitly or run `mouchak-mail-cli projects mark-identity <slug>` in the repository"
+            }
+        )
+    })
+}
+
 /// Resolve an agent by name within a project.
 ///
 /// Validates input format before querying database.
@@ -57,13 +99,9 @@ pub async fn resolve_agent(
     agent_name: &str,
 ) -> Result<Agent, McpError> {
     if let Err(e) = validate_agent_name(agent_name) {
-        let sanitized_name: String = agent_name
-            .chars()
-            .filter(|c| c.is_alphanumeric() || *c == '_' || *c == '-')
-            .take(64)
-            .collect();
-        let suggestion = if sanitized_name.is_empty() {
-            "Use alphanumeric characters, underscores, and hyphens (1-64 chars)".to_string()
+        let sanitized_name = sanitize_agent_name(agent_name);
+        let suggestion = if sanitized_name.len() < AGENT_NAME_MIN_LEN {
+            "Use ASCII letters, digits, underscores, and hyphens (2-64 chars)".to_string()
         } else {
             sanitized_name
         };
@@ -94,6 +132,53 @@ pub async fn resolve_agent(
     })
 }
 
+/// Resolve a recipient address relative to the sender's project.
+///
+/// Plain names go through [`resolve_agent`]; `slug::agent-name` addresses an
+/// agent in another project, subject to `projects.allowed_peers`.
+pub async fn resolve_address(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    project_id: i64,
+    address: &str,
+) -> Result<Agent, McpError> {
+    if !address.contains(ADDRESS_SEPARATOR) {
+        return resolve_agent(ctx, mm, project_id, address).await;
+    }
+
+    AgentBmc::resolve_address(
+        ctx,
+        mm,
+        mouchak_mail_core::types::ProjectId::new(project_id),
+        address,
+    )
+    .await
+    .map_err(|e| match e {
+        mouchak_mail_core::Error::CrossProjectForbidden(slug) => mcp_err!(
+            ErrorCode::ForbiddenCrossProject,
+            &format!("Project '{}' may not message agents in other projects", slug),
+            {
+                "address": address,
+                "project_slug": slug,
+                "suggestion": "Add the project to projects.allowed_peers"
+            }
+        ),
+        mouchak_mail_core::Error::ProjectNotFound { .. } => mcp_err!(
+            ErrorCode::ProjectNotFound,
+            &format!("Project in address '{}' not found", address),
+            { "address": address, "suggestion": "Check project slugs with list_projects" }
+        ),
+        _ => mcp_err!(
+            ErrorCode::AgentNotFound,
+            &format!("Agent '{}' not found", address),
+            {
+                "address": address,
+                "suggestion": "Check agent names with list_agents in the addressed project"
+            }
+        ),
+    })
+}
+
 /// Resolve project and agent in one call.
 ///
 /// Common pattern: look up project by slug, then agent by name.
@@ -108,22 +193,49 @@ pub async fn resolve_project_and_agent(
     Ok((project, agent))
 }
 
+/// Check that the caller may act as `agent`.
+///
+/// Calls made with an agent token are bound to that agent; see
+/// [`Ctx::ensure_acting_as`].
+pub fn ensure_acting_as(ctx: &Ctx, agent: &Agent) -> Result<(), McpError> {
+    ctx.ensure_acting_as(agent.id, &agent.name).map_err(|e| {
+        mcp_err!(
+            ErrorCode::AgentIdentityMismatch,
+            &format!("{e}"),
+            {
+                "agent_name": agent.name,
+                "suggestion": "Agent keys can only send and reserve as their own agent"
+            }
+        )
+    })
+}
+
+/// Split a comma-separated list of agent names, dropping blanks.
+pub fn split_names(names_csv: &str) -> Vec<String> {
+    names_csv
+        .split(',')
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
 /// Parse comma-separated agent names and resolve them to IDs.
 ///
-/// Supports special keyword "broadcast" to resolve to all agents in the project.
+/// Supports special keyword "broadcast" to resolve to all agents in the project,
+/// `slug::agent-name` for agents in other projects, and `group:name` for the
+/// current members of an agent group; each group's expansion is appended to
+/// `groups` so the caller can record it with the message.
 /// Returns Vec of agent IDs or error if any agent not found.
 pub async fn resolve_agent_names(
     ctx: &Ctx,
     mm: &Arc<ModelManager>,
     project_id: i64,
     names_csv: &str,
+    groups: &mut Vec<GroupExpansion>,
 ) -> Result<Vec<i64>, McpError> {
     let mut ids = Vec::new();
-    for name in names_csv
-        .split(',')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty())
-    {
+    for name in &split_names(names_csv) {
         if name.eq_ignore_ascii_case("broadcast") {
             let all_agents = AgentBmc::list_all_for_project(
                 ctx,
@@ -143,8 +255,16 @@ pub async fn resolve_agent_names(
                     ids.push(agent.id.get());
                 }
             }
+        } else if let Some(group) = name.strip_prefix(GROUP_ADDRESS_PREFIX) {
+            let expansion = resolve_group(ctx, mm, project_id, group).await?;
+            for id in &expansion.agent_ids {
+                if !ids.contains(id) {
+                    ids.push(*id);
+                }
+            }
+            groups.push(expansion);
         } else {
-            let agent = resolve_agent(ctx, mm, project_id, name).await?;
+            let agent = resolve_address(ctx, mm, project_id, name).await?;
             if !ids.contains(&agent.id.get()) {
                 ids.push(agent.id.get());
             }
@@ -153,6 +273,35 @@ pub async fn resolve_agent_names(
     Ok(ids)
 }
 
+/// Expand a `group:name` recipient to the group's current members.
+async fn resolve_group(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    project_id: i64,
+    group: &str,
+) -> Result<GroupExpansion, McpError> {
+    AgentGroupBmc::expand(
+        ctx,
+        mm,
+        mouchak_mail_core::types::ProjectId::new(project_id),
+        group,
+    )
+    .await
+    .map_err(|e| match e {
+        mouchak_mail_core::Error::GroupNotFound(_) => mcp_err!(
+            ErrorCode::InvalidRecipient,
+            &format!("Group '{}' not found", group),
+            { "group": group, "suggestion": "List groups with manage_agent_group action 'list'" }
+        ),
+        mouchak_mail_core::Error::GroupEmpty(_) => mcp_err!(
+            ErrorCode::InvalidRecipient,
+            &format!("Group '{}' has no members", group),
+            { "group": group, "suggestion": "Add agents with manage_agent_group action 'add_member'" }
+        ),
+        other => McpError::internal_error(other.to_string(), None),
+    })
+}
+
 /// Parse optional comma-separated agent names and resolve them to IDs.
 ///
 /// Returns None if input is None or empty, otherwise Vec of agent IDs.
@@ -161,11 +310,57 @@ pub async fn resolve_optional_agent_names(
     mm: &Arc<ModelManager>,
     project_id: i64,
     names_csv: Option<&str>,
+    groups: &mut Vec<GroupExpansion>,
 ) -> Result<Option<Vec<i64>>, McpError> {
     match names_csv {
-        Some(names) if !names.trim().is_empty() => {
-            Ok(Some(resolve_agent_names(ctx, mm, project_id, names).await?))
-        }
+        Some(names) if !names.trim().is_empty() => Ok(Some(
+            resolve_agent_names(ctx, mm, project_id, names, groups).await?,
+        )),
         _ => Ok(None),
     }
 }
+
+/// Map a `MessageBmc::create` failure to an MCP error.
+///
+/// Size limit violations keep their structured context under the
+/// `VALIDATION_ERROR` code so agents can shorten the message and retry;
+/// rejected input such as an empty broadcast maps to `INVALID_INPUT`.
+pub fn message_create_error(err: mouchak_mail_core::Error) -> McpError {
+    match err {
+        mouchak_mail_core::Error::Validation(ve) => mcp_err!(
+            ErrorCode::ValidationError,
+            &ve.to_string(),
+            { "details": ve.context() }
+        ),
+        mouchak_mail_core::Error::InvalidInput(msg) => mcp_err!(ErrorCode::InvalidInput, &msg),
+        mouchak_mail_core::Error::ThreadNotFound(thread_id) => mcp_err!(
+            ErrorCode::ThreadNotFound,
+            &format!("Thread '{}' not found", thread_id),
+            {
+                "thread_id": thread_id,
+                "suggestion": "Check thread IDs with list_threads (they are case-sensitive), or pass allow_new_thread=true to start a new thread"
+            }
+        ),
+        mouchak_mail_core::Error::CrossProjectForbidden(slug) => mcp_err!(
+            ErrorCode::ForbiddenCrossProject,
+            &format!("Project '{}' may not message agents in other projects", slug),
+            { "project_slug": slug, "suggestion": "Add the project to projects.allowed_peers" }
+        ),
+        other => McpError::internal_error(other.to_string(), None),
+    }
+}
+
+/// Parse a timestamp parameter: RFC 3339, or a naive ISO 8601 time taken as UTC.
+pub fn parse_timestamp_param(field: &str, value: &str) -> Result<chrono::NaiveDateTime, McpError> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.naive_utc())
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S"))
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S"))
+        .map_err(|_| {
+            mcp_err!(
+                ErrorCode::InvalidInput,
+                &format!("Invalid {} timestamp: '{}'", field, value),
+                { "suggestion": "Use ISO 8601, e.g. 2025-01-01T09:30:00Z" }
+            )
+        })
+}
@@ -37,11 +37,7 @@ pub async fn list_macros_impl(
         .await
         .map_err(|e| McpError::internal_error(e.to_string(), None))?;
 
-    let mut output = format!(
-        "Macros in '{}' ({}):\n\n",
-        params.project_slug,
-        macros.len()
-    );
+    let mut output = format!("Macros in '{}' ({}):\n\n", project.slug, macros.len());
     for m in &macros {
         output.push_str(&format!(
             "- {} ({} steps): {}\n",
@@ -173,11 +169,14 @@ pub async fn quick_standup_workflow_impl(
         thread_id: Some("STANDUP".to_string()),
         importance: Some("normal".to_string()),
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
 
     let msg_id = MessageBmc::create(ctx, mm, msg_c)
         .await
-        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        .map_err(helpers::message_create_error)?;
 
     let msg = format!(
         "Standup request sent to {} agents (message id: {})",
@@ -219,11 +218,14 @@ pub async fn quick_handoff_workflow_impl(
         )),
         importance: Some("high".to_string()),
         ack_required: true, // Handoffs should be acknowledged
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
 
     let msg_id = MessageBmc::create(ctx, mm, msg_c)
         .await
-        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        .map_err(helpers::message_create_error)?;
 
     let msg = format!(
         "Handoff message sent from '{}' to '{}' (id: {})",
@@ -275,11 +277,14 @@ pub async fn quick_review_workflow_impl(
         thread_id: Some("CODE-REVIEW".to_string()),
         importance: Some("normal".to_string()),
         ack_required: true, // Review requests should be acknowledged
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
 
     let msg_id = MessageBmc::create(ctx, mm, msg_c)
         .await
-        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        .map_err(helpers::message_create_error)?;
 
     let msg = format!(
         "Review request sent to '{}'. Reserved {} files for review (id: {})",
@@ -501,7 +506,7 @@ pub async fn macro_prepare_thread_impl(
     };
 
     // Fetch inbox
-    let inbox_messages = MessageBmc::list_inbox_for_agent(
+    let mut inbox_messages = MessageBmc::list_inbox_for_agent(
         ctx,
         mm,
         project.id.get(),
@@ -510,6 +515,11 @@ pub async fn macro_prepare_thread_impl(
     )
     .await
     .unwrap_or_default();
+    if params.include_inbox_bodies {
+        MessageBmc::load_bodies(mm, &mut inbox_messages)
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+    }
 
     let inbox_items: Vec<serde_json::Value> = inbox_messages
         .iter()
@@ -751,6 +761,9 @@ pub async fn macro_contact_handshake_impl(
                 thread_id: params.thread_id,
                 importance: Some("normal".to_string()),
                 ack_required: false,
+                deliver_at: None,
+                broadcast: false,
+                allow_new_thread: true,
             };
             match MessageBmc::create(ctx, mm, msg_c).await {
                 Ok(msg_id) => Some(serde_json::json!({
@@ -3,22 +3,33 @@
 //! Handles sending, receiving, threading, and searching messages.
 
 use mouchak_mail_core::{
-    ctx::Ctx,
+    ctx::{Actor, Ctx},
     model::{
         ModelManager,
+        agent::ADDRESS_SEPARATOR,
         agent_capabilities::AgentCapabilityBmc,
-        message::{MessageBmc, MessageForCreate},
+        agent_group::AgentGroupBmc,
+        label::LabelBmc,
+        message::{
+            InboxQuery, InboxSort, MAX_INBOX_WAIT_SECS, Message, MessageBmc, MessageForCreate,
+        },
+        thread_mute::ThreadMuteBmc,
     },
 };
 use rmcp::{ErrorData as McpError, model::CallToolResult, model::Content};
 use std::sync::Arc;
+use std::time::Duration;
 
 use super::helpers;
 use super::{
-    AcknowledgeMessageParams, GetMessageParams, GetThreadParams, ListInboxParams,
-    ListThreadsParams, MarkMessageReadParams, ReplyMessageParams, SearchMessagesParams,
-    SendMessageParams,
+    AcknowledgeMessageParams, CancelScheduledParams, ForwardMessageParams, GetMessageParams,
+    GetThreadParams, LabelMessageParams, ListInboxParams, ListPendingAcksParams,
+    ListScheduledParams, ListSnoozedParams, ListThreadsParams, MarkMessageReadParams,
+    MuteThreadParams, RecallMessageParams, ReplyMessageParams, SearchMessagesParams,
+    SendMessageParams, SnoozeMessageParams, SummarizeThreadParams, ThreadIdInput,
+    ThreadStatsResult, ThreadSummaryError, WaitForMessagesParams,
 };
+use crate::tools::errors::{ErrorCode, mcp_err};
 
 /// Send a message from one agent to others.
 pub async fn send_message_impl(
@@ -29,6 +40,11 @@ pub async fn send_message_impl(
     let (project, sender) =
         helpers::resolve_project_and_agent(ctx, mm, &params.project_slug, &params.sender_name)
             .await?;
+    helpers::ensure_acting_as(ctx, &sender)?;
+    // Archive commits are authored by the sender
+    let ctx = &ctx
+        .clone()
+        .with_actor(Actor::agent(&sender.name, &project.slug));
 
     if !AgentCapabilityBmc::check(ctx, mm, sender.id.get(), "send_message")
         .await
@@ -43,15 +59,93 @@ pub async fn send_message_impl(
         ));
     }
 
-    let recipient_ids = helpers::resolve_agent_names(ctx, mm, project.id.get(), &params.to).await?;
+    let broadcast = params.broadcast.unwrap_or(false);
+    if broadcast && !params.to.trim().is_empty() {
+        return Err(mcp_err!(
+            ErrorCode::InvalidInput,
+            "broadcast and 'to' are mutually exclusive",
+            { "suggestion": "Omit 'to' to send to every agent in the project" }
+        ));
+    }
 
-    let cc_ids =
-        helpers::resolve_optional_agent_names(ctx, mm, project.id.get(), params.cc.as_deref())
-            .await?;
+    // Atomic sends fail on the first unknown recipient with its full error;
+    // partial sends resolve everything first and skip what doesn't resolve
+    let (recipient_ids, cc_ids, bcc_ids, delivered, failed, groups) = if params
+        .allow_partial
+        .unwrap_or(false)
+    {
+        let cc = params.cc.as_deref().map(helpers::split_names);
+        let bcc = params.bcc.as_deref().map(helpers::split_names);
+        let resolved = MessageBmc::resolve_recipients(
+            ctx,
+            mm,
+            project.id,
+            &helpers::split_names(&params.to),
+            cc.as_deref(),
+            bcc.as_deref(),
+        )
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        if !resolved.can_send(true) {
+            return Err(mcp_err!(
+                ErrorCode::InvalidRecipient,
+                "None of the recipients could be resolved",
+                { "failed": resolved.failed, "suggestion": "Check agent names with list_agents" }
+            ));
+        }
+        (
+            resolved.recipient_ids,
+            resolved.cc_ids,
+            resolved.bcc_ids,
+            resolved.delivered,
+            resolved.failed,
+            resolved.groups,
+        )
+    } else {
+        let mut groups = Vec::new();
+        let recipient_ids =
+            helpers::resolve_agent_names(ctx, mm, project.id.get(), &params.to, &mut groups)
+                .await?;
+        let cc_ids = helpers::resolve_optional_agent_names(
+            ctx,
+            mm,
+            project.id.get(),
+            params.cc.as_deref(),
+            &mut groups,
+        )
+        .await?;
+        let bcc_ids = helpers::resolve_optional_agent_names(
+            ctx,
+            mm,
+            project.id.get(),
+            params.bcc.as_deref(),
+            &mut groups,
+        )
+        .await?;
+        let delivered = [
+            Some(params.to.as_str()),
+            params.cc.as_deref(),
+            params.bcc.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .flat_map(helpers::split_names)
+        .collect();
+        (
+            recipient_ids,
+            cc_ids,
+            bcc_ids,
+            delivered,
+            Vec::new(),
+            groups,
+        )
+    };
 
-    let bcc_ids =
-        helpers::resolve_optional_agent_names(ctx, mm, project.id.get(), params.bcc.as_deref())
-            .await?;
+    let deliver_at = params
+        .deliver_at
+        .as_deref()
+        .map(|value| helpers::parse_timestamp_param("deliver_at", value))
+        .transpose()?;
 
     let msg_c = MessageForCreate {
         project_id: project.id.get(),
@@ -64,17 +158,58 @@ pub async fn send_message_impl(
         thread_id: params.thread_id,
         importance: params.importance,
         ack_required: params.ack_required.unwrap_or(false),
+        deliver_at,
+        broadcast,
+        allow_new_thread: params.allow_new_thread.unwrap_or(false),
     };
 
     let msg_id = MessageBmc::create(ctx, mm, msg_c)
+        .await
+        .map_err(helpers::message_create_error)?;
+    AgentGroupBmc::record_expansions(mm, msg_id, &groups)
         .await
         .map_err(|e| McpError::internal_error(e.to_string(), None))?;
 
-    let msg = format!(
-        "Message sent (id: {}) from '{}' to '{}' with subject '{}'",
-        msg_id, params.sender_name, params.to, params.subject
-    );
-    Ok(CallToolResult::success(vec![Content::text(msg)]))
+    let delivered = if broadcast {
+        MessageBmc::get_recipients(ctx, mm, msg_id)
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?
+    } else {
+        delivered
+    };
+    let to = if broadcast {
+        "all agents"
+    } else {
+        params.to.as_str()
+    };
+    let mut msg = match deliver_at {
+        Some(at) => format!(
+            "Message scheduled (id: {}) from '{}' to '{}' with subject '{}', delivering at {} UTC",
+            msg_id, params.sender_name, to, params.subject, at
+        ),
+        None => format!(
+            "Message sent (id: {}) from '{}' to '{}' with subject '{}'",
+            msg_id, params.sender_name, to, params.subject
+        ),
+    };
+    for failure in &failed {
+        msg.push_str(&format!(
+            "\nNot delivered to '{}': {}",
+            failure.name, failure.code
+        ));
+    }
+
+    let result = serde_json::json!({
+        "message_id": msg_id,
+        "delivered": delivered,
+        "partial_success": !failed.is_empty(),
+        "failed": failed,
+        "groups": groups,
+    });
+    Ok(CallToolResult::success(vec![
+        Content::text(msg),
+        Content::text(result.to_string()),
+    ]))
 }
 
 /// List messages in an agent's inbox.
@@ -100,25 +235,129 @@ pub async fn list_inbox_impl(
         ));
     }
 
-    let messages = MessageBmc::list_inbox_for_agent(
+    let sort = params
+        .sort
+        .as_deref()
+        .filter(|s| !s.is_empty())
+        .map(str::parse::<InboxSort>)
+        .transpose()
+        .map_err(|e| McpError::invalid_params(e.to_string(), None))?
+        .unwrap_or_default();
+    let query = InboxQuery {
+        label: params.label.clone(),
+        sort,
+        cursor: None,
+        limit: params.limit.unwrap_or(50),
+    };
+    let messages =
+        MessageBmc::list_inbox_for_agent_sorted(ctx, mm, project.id.get(), agent.id.get(), &query)
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+    let mut output = format!(
+        "Inbox for '{}' ({} messages):\n\n",
+        params.agent_name,
+        messages.len()
+    );
+    for m in &messages {
+        output.push_str(&format!(
+            "- [{}] {} (from: {}, thread: {:?}, {}{}{})\n",
+            m.id,
+            m.subject,
+            sender_address(m),
+            m.thread_id,
+            m.importance,
+            if m.ack_required { ", ack required" } else { "" },
+            if m.returned_from_snooze {
+                ", back from snooze"
+            } else {
+                ""
+            }
+        ));
+    }
+
+    Ok(CallToolResult::success(vec![Content::text(output)]))
+}
+
+/// Sender name, prefixed with its project for messages from another project.
+fn sender_address(m: &Message) -> String {
+    match &m.project_slug {
+        Some(slug) => format!("{}{}{}", slug, ADDRESS_SEPARATOR, m.sender_name),
+        None => m.sender_name.clone(),
+    }
+}
+
+/// " (#7 in thread)" for a numbered message, empty otherwise.
+fn thread_position(m: &Message) -> String {
+    m.thread_seq
+        .map(|seq| format!(" (#{} in thread)", seq))
+        .unwrap_or_default()
+}
+
+/// Block until new messages reach an agent's inbox or the timeout passes.
+pub async fn wait_for_messages_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: WaitForMessagesParams,
+) -> Result<CallToolResult, McpError> {
+    let (project, agent) =
+        helpers::resolve_project_and_agent(ctx, mm, &params.project_slug, &params.agent_name)
+            .await?;
+
+    if !AgentCapabilityBmc::check(ctx, mm, agent.id.get(), "fetch_inbox")
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?
+    {
+        return Err(McpError::invalid_params(
+            format!(
+                "Agent '{}' does not have 'fetch_inbox' capability",
+                params.agent_name
+            ),
+            None,
+        ));
+    }
+
+    let timeout_seconds = params
+        .timeout_seconds
+        .unwrap_or(30)
+        .min(MAX_INBOX_WAIT_SECS);
+    let messages = MessageBmc::wait_for_inbox(
         ctx,
         mm,
         project.id.get(),
         agent.id.get(),
-        params.limit.unwrap_or(50),
+        params.since_message_id,
+        Duration::from_secs(timeout_seconds),
     )
     .await
     .map_err(|e| McpError::internal_error(e.to_string(), None))?;
 
+    if messages.is_empty() {
+        return Ok(CallToolResult::success(vec![Content::text(format!(
+            "No new messages for '{}' within {}s",
+            params.agent_name, timeout_seconds
+        ))]));
+    }
+
     let mut output = format!(
-        "Inbox for '{}' ({} messages):\n\n",
+        "New messages for '{}' ({} messages):\n\n",
         params.agent_name,
         messages.len()
     );
     for m in &messages {
         output.push_str(&format!(
-            "- [{}] {} (from: {}, thread: {:?}, {})\n",
-            m.id, m.subject, m.sender_name, m.thread_id, m.importance
+            "- [{}] {} (from: {}, thread: {:?}, {}{}{})\n",
+            m.id,
+            m.subject,
+            sender_address(m),
+            m.thread_id,
+            m.importance,
+            if m.ack_required { ", ack required" } else { "" },
+            if m.returned_from_snooze {
+                ", back from snooze"
+            } else {
+                ""
+            }
         ));
     }
 
@@ -133,15 +372,26 @@ pub async fn get_message_impl(
 ) -> Result<CallToolResult, McpError> {
     let message = MessageBmc::get(ctx, mm, params.message_id)
         .await
-        .map_err(|e| McpError::invalid_params(format!("Message not found: {}", e), None))?;
+        .map_err(|e| match e {
+            mouchak_mail_core::Error::MessagePruned(id) => {
+                let msg = e.to_string();
+                mcp_err!(ErrorCode::NotFoundPruned, &msg, {
+                    "message_id": id,
+                    "suggestion": "Read it from the project's Git archive"
+                })
+            }
+            e => McpError::invalid_params(format!("Message not found: {}", e), None),
+        })?;
 
     let output = format!(
-        "Message ID: {}\nFrom: {}\nSubject: {}\nThread: {:?}\nImportance: {}\nCreated: {}\n\n---\n{}",
+        "Message ID: {}\nFrom: {}\nSubject: {}\nThread: {:?}{}\nImportance: {}\nAck required: {}\nCreated: {}\n\n---\n{}",
         message.id,
         message.sender_name,
         message.subject,
         message.thread_id,
+        thread_position(&message),
         message.importance,
+        if message.ack_required { "yes" } else { "no" },
         message.created_ts,
         message.body_md
     );
@@ -201,8 +451,13 @@ pub async fn get_thread_impl(
     );
     for m in &messages {
         output.push_str(&format!(
-            "---\n[{}] From: {} | {}\nSubject: {}\n\n{}\n\n",
-            m.id, m.sender_name, m.created_ts, m.subject, m.body_md
+            "---\n[{}] From: {} | {}{}\nSubject: {}\n\n{}\n\n",
+            m.id,
+            sender_address(m),
+            m.created_ts,
+            thread_position(m),
+            m.subject,
+            m.body_md
         ));
     }
 
@@ -218,6 +473,11 @@ pub async fn reply_message_impl(
     let (project, sender) =
         helpers::resolve_project_and_agent(ctx, mm, &params.project_slug, &params.sender_name)
             .await?;
+    helpers::ensure_acting_as(ctx, &sender)?;
+    // Archive commits are authored by the sender
+    let ctx = &ctx
+        .clone()
+        .with_actor(Actor::agent(&sender.name, &project.slug));
 
     if !AgentCapabilityBmc::check(ctx, mm, sender.id.get(), "send_message")
         .await
@@ -242,6 +502,15 @@ pub async fn reply_message_impl(
         format!("Re: {}", original_msg.subject)
     };
 
+    // Messages from before threads were always assigned continue the thread
+    // their subject started
+    let thread_id = match original_msg.thread_id.clone() {
+        Some(thread_id) => thread_id,
+        None => MessageBmc::ensure_thread(ctx, mm, project.id.get(), &original_msg.subject)
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?,
+    };
+
     let msg_c = MessageForCreate {
         project_id: project.id.get(),
         sender_id: sender.id.get(),
@@ -250,19 +519,96 @@ pub async fn reply_message_impl(
         bcc_ids: None,
         subject: subject.clone(),
         body_md: params.body_md,
-        thread_id: original_msg.thread_id.clone(),
+        thread_id: Some(thread_id),
         importance: params.importance,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        // The thread may live in the original sender's project
+        allow_new_thread: true,
     };
 
     let msg_id = MessageBmc::create(ctx, mm, msg_c)
         .await
-        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        .map_err(helpers::message_create_error)?;
 
     let msg = format!("Reply sent (id: {}) with subject '{}'", msg_id, subject);
     Ok(CallToolResult::success(vec![Content::text(msg)]))
 }
 
+/// Forward a message to agents who were not on it, quoting the original.
+pub async fn forward_message_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: ForwardMessageParams,
+) -> Result<CallToolResult, McpError> {
+    let (project, sender) =
+        helpers::resolve_project_and_agent(ctx, mm, &params.project_slug, &params.sender_name)
+            .await?;
+    helpers::ensure_acting_as(ctx, &sender)?;
+    // Archive commits are authored by the forwarder
+    let ctx = &ctx
+        .clone()
+        .with_actor(Actor::agent(&sender.name, &project.slug));
+
+    if !AgentCapabilityBmc::check(ctx, mm, sender.id.get(), "send_message")
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?
+    {
+        return Err(McpError::invalid_params(
+            format!(
+                "Agent '{}' does not have 'send_message' capability",
+                params.sender_name
+            ),
+            None,
+        ));
+    }
+
+    let mut groups = Vec::new();
+    let recipient_ids =
+        helpers::resolve_agent_names(ctx, mm, project.id.get(), &params.to, &mut groups).await?;
+
+    let msg_id = MessageBmc::forward(
+        ctx,
+        mm,
+        params.message_id,
+        sender.id.get(),
+        recipient_ids,
+        params.note.as_deref(),
+    )
+    .await
+    .map_err(|e| match e {
+        mouchak_mail_core::Error::MessageNotFound(id) => mcp_err!(
+            ErrorCode::MessageNotFound,
+            &format!("Message {} not found for agent '{}'", id, params.sender_name),
+            {
+                "message_id": id,
+                "suggestion": "Only the sender or a recipient can forward a message"
+            }
+        ),
+        mouchak_mail_core::Error::MessagePruned(id) => mcp_err!(
+            ErrorCode::NotFoundPruned,
+            &format!("Message {} was pruned by the retention policy", id),
+            { "message_id": id }
+        ),
+        mouchak_mail_core::Error::MessageRecalled(id) => mcp_err!(
+            ErrorCode::MessageRecalled,
+            &format!("Message {} was recalled by its sender and can't be forwarded", id),
+            { "message_id": id }
+        ),
+        other => helpers::message_create_error(other),
+    })?;
+    AgentGroupBmc::record_expansions(mm, msg_id, &groups)
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+    let msg = format!(
+        "Message {} forwarded (id: {}) to {}",
+        params.message_id, msg_id, params.to
+    );
+    Ok(CallToolResult::success(vec![Content::text(msg)]))
+}
+
 /// Mark a message as read.
 pub async fn mark_message_read_impl(
     ctx: &Ctx,
@@ -318,23 +664,387 @@ pub async fn acknowledge_message_impl(
     Ok(CallToolResult::success(vec![Content::text(msg)]))
 }
 
-/// List all conversation threads in a project.
-pub async fn list_threads_impl(
+/// Add and remove labels on a message so agents can triage their own mail.
+pub async fn label_message_impl(
     ctx: &Ctx,
     mm: &Arc<ModelManager>,
-    params: ListThreadsParams,
+    params: LabelMessageParams,
 ) -> Result<CallToolResult, McpError> {
-    let project = helpers::resolve_project(ctx, mm, &params.project_slug).await?;
+    let (project, _agent) =
+        helpers::resolve_project_and_agent(ctx, mm, &params.project_slug, &params.agent_name)
+            .await?;
+
+    if params.add.is_empty() && params.remove.is_empty() {
+        return Err(McpError::invalid_params(
+            "Provide at least one label name in 'add' or 'remove'".to_string(),
+            None,
+        ));
+    }
+
+    let message_not_found = || {
+        mcp_err!(
+            ErrorCode::MessageNotFound,
+            &format!(
+                "Message {} not found in project '{}'",
+                params.message_id, project.slug
+            ),
+            { "message_id": params.message_id, "project_slug": project.slug }
+        )
+    };
+    let message = MessageBmc::get(ctx, mm, params.message_id)
+        .await
+        .map_err(|_| message_not_found())?;
+    if message.project_id != project.id.get() {
+        return Err(message_not_found());
+    }
+
+    let labels = LabelBmc::update_message_labels(
+        ctx,
+        mm,
+        params.message_id,
+        &params.add,
+        &params.remove,
+        params.create_missing.unwrap_or(true),
+    )
+    .await
+    .map_err(|e| match e {
+        mouchak_mail_core::Error::LabelNotFound(name) => mcp_err!(
+            ErrorCode::InvalidInput,
+            &format!("Label '{}' does not exist in project '{}'", name, project.slug),
+            {
+                "label": name,
+                "suggestion": "Set create_missing to true to create labels on first use"
+            }
+        ),
+        mouchak_mail_core::Error::InvalidInput(msg) => McpError::invalid_params(msg, None),
+        other => McpError::internal_error(other.to_string(), None),
+    })?;
+
+    let names: Vec<&str> = labels.iter().map(|l| l.name.as_str()).collect();
+    let msg = if names.is_empty() {
+        format!("Message {} has no labels", params.message_id)
+    } else {
+        format!("Message {} labels: {}", params.message_id, names.join(", "))
+    };
+    Ok(CallToolResult::success(vec![Content::text(msg)]))
+}
+
+/// Recall a message the agent sent, replacing it with a tombstone.
+pub async fn recall_message_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: RecallMessageParams,
+) -> Result<CallToolResult, McpError> {
+    let (project, agent) =
+        helpers::resolve_project_and_agent(ctx, mm, &params.project_slug, &params.agent_name)
+            .await?;
+    let ctx = &ctx
+        .clone()
+        .with_actor(Actor::agent(&agent.name, &project.slug));
+
+    let recall = MessageBmc::recall(ctx, mm, params.message_id, agent.id.get(), &params.reason)
+        .await
+        .map_err(|e| match e {
+            mouchak_mail_core::Error::MessageNotFound(id) => mcp_err!(
+                ErrorCode::MessageNotFound,
+                &format!("Message {} not found", id),
+                { "message_id": id }
+            ),
+            mouchak_mail_core::Error::NotMessageSender(id) => mcp_err!(
+                ErrorCode::NotMessageSender,
+                &format!("Only the sender of message {} can recall it", id),
+                {
+                    "message_id": id,
+                    "agent_name": params.agent_name,
+                    "suggestion": "Ask the original sender to recall the message"
+                }
+            ),
+            mouchak_mail_core::Error::RecallWindowExpired {
+                message_id,
+                window_seconds,
+            } => mcp_err!(
+                ErrorCode::RecallWindowExpired,
+                &format!(
+                    "Message {} is older than the {}s recall window",
+                    message_id, window_seconds
+                ),
+                {
+                    "message_id": message_id,
+                    "window_seconds": window_seconds,
+                    "suggestion": "Send a follow-up reply correcting the message instead"
+                }
+            ),
+            mouchak_mail_core::Error::InvalidInput(msg) => McpError::invalid_params(msg, None),
+            other => McpError::internal_error(other.to_string(), None),
+        })?;
+
+    let msg = format!(
+        "Message {} recalled by '{}' at {}: {}",
+        recall.message_id, params.agent_name, recall.recalled_ts, recall.recall_reason
+    );
+    Ok(CallToolResult::success(vec![Content::text(msg)]))
+}
+
+/// Mute a thread so its messages stay out of the agent's inbox.
+pub async fn mute_thread_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: MuteThreadParams,
+) -> Result<CallToolResult, McpError> {
+    let (project, agent) =
+        helpers::resolve_project_and_agent(ctx, mm, &params.project_slug, &params.agent_name)
+            .await?;
+
+    let newly_muted = ThreadMuteBmc::mute(ctx, mm, agent.id, &params.thread_id)
+        .await
+        .map_err(|e| match e {
+            mouchak_mail_core::Error::ThreadNotFound(thread_id) => mcp_err!(
+                ErrorCode::ThreadNotFound,
+                &format!("Thread '{}' not found", thread_id),
+                {
+                    "thread_id": thread_id,
+                    "project_slug": project.slug,
+                    "suggestion": "Check thread IDs with list_threads"
+                }
+            ),
+            other => McpError::internal_error(other.to_string(), None),
+        })?;
+
+    let msg = if newly_muted {
+        format!(
+            "Thread '{}' muted for '{}'. Its messages stay out of the inbox unless urgent or @{} is in the subject.",
+            params.thread_id, agent.name, agent.name
+        )
+    } else {
+        format!(
+            "Thread '{}' was already muted for '{}'",
+            params.thread_id, agent.name
+        )
+    };
+    Ok(CallToolResult::success(vec![Content::text(msg)]))
+}
+
+/// Unmute a thread, bringing its messages back into the agent's inbox.
+pub async fn unmute_thread_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: MuteThreadParams,
+) -> Result<CallToolResult, McpError> {
+    let (_project, agent) =
+        helpers::resolve_project_and_agent(ctx, mm, &params.project_slug, &params.agent_name)
+            .await?;
+
+    let was_muted = ThreadMuteBmc::unmute(ctx, mm, agent.id, &params.thread_id)
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+    let msg = if was_muted {
+        format!("Thread '{}' unmuted for '{}'", params.thread_id, agent.name)
+    } else {
+        format!(
+            "Thread '{}' was not muted for '{}'",
+            params.thread_id, agent.name
+        )
+    };
+    Ok(CallToolResult::success(vec![Content::text(msg)]))
+}
+
+/// List an agent's scheduled messages that have not been delivered yet.
+pub async fn list_scheduled_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: ListScheduledParams,
+) -> Result<CallToolResult, McpError> {
+    let (project, agent) =
+        helpers::resolve_project_and_agent(ctx, mm, &params.project_slug, &params.agent_name)
+            .await?;
+
+    let scheduled = MessageBmc::list_scheduled(ctx, mm, project.id.get(), agent.id.get())
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+    let mut output = format!(
+        "Scheduled messages for '{}' ({}):\n\n",
+        params.agent_name,
+        scheduled.len()
+    );
+    for s in &scheduled {
+        let recipients: Vec<&str> = s.recipients.iter().map(|r| r.name.as_str()).collect();
+        output.push_str(&format!(
+            "- [{}] {} (to: {}, deliver at: {} UTC)\n",
+            s.message.id,
+            s.message.subject,
+            recipients.join(", "),
+            s.deliver_at
+        ));
+    }
+    Ok(CallToolResult::success(vec![Content::text(output)]))
+}
+
+/// Cancel a scheduled message before delivery.
+pub async fn cancel_scheduled_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: CancelScheduledParams,
+) -> Result<CallToolResult, McpError> {
+    let (_project, agent) =
+        helpers::resolve_project_and_agent(ctx, mm, &params.project_slug, &params.agent_name)
+            .await?;
+
+    MessageBmc::cancel_scheduled(ctx, mm, params.message_id, agent.id.get())
+        .await
+        .map_err(|e| match e {
+            mouchak_mail_core::Error::MessageNotFound(id) => mcp_err!(
+                ErrorCode::MessageNotFound,
+                &format!("No scheduled message with id {}", id),
+                {
+                    "message_id": id,
+                    "suggestion": "Check pending messages with list_scheduled"
+                }
+            ),
+            mouchak_mail_core::Error::NotMessageSender(id) => mcp_err!(
+                ErrorCode::NotMessageSender,
+                &format!("Only the sender of message {} can cancel it", id),
+                { "message_id": id, "agent_name": params.agent_name }
+            ),
+            mouchak_mail_core::Error::InvalidInput(msg) => McpError::invalid_params(msg, None),
+            other => McpError::internal_error(other.to_string(), None),
+        })?;
+
+    let msg = format!(
+        "Scheduled message {} cancelled by '{}'",
+        params.message_id, params.agent_name
+    );
+    Ok(CallToolResult::success(vec![Content::text(msg)]))
+}
+
+/// Snooze a message for one recipient, or unsnooze it when no time is given.
+pub async fn snooze_message_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: SnoozeMessageParams,
+) -> Result<CallToolResult, McpError> {
+    let (_project, agent) =
+        helpers::resolve_project_and_agent(ctx, mm, &params.project_slug, &params.agent_name)
+            .await?;
+
+    let Some(until) = params.until.as_deref() else {
+        let was_snoozed = MessageBmc::unsnooze(ctx, mm, params.message_id, agent.id.get())
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        let msg = if was_snoozed {
+            format!(
+                "Message {} unsnoozed for '{}'",
+                params.message_id, agent.name
+            )
+        } else {
+            format!(
+                "Message {} was not snoozed for '{}'",
+                params.message_id, agent.name
+            )
+        };
+        return Ok(CallToolResult::success(vec![Content::text(msg)]));
+    };
+
+    let until = helpers::parse_timestamp_param("until", until)?;
+    MessageBmc::snooze(ctx, mm, params.message_id, agent.id.get(), until)
+        .await
+        .map_err(|e| match e {
+            mouchak_mail_core::Error::MessageNotFound(id) => mcp_err!(
+                ErrorCode::MessageNotFound,
+                &format!("Message {} was not sent to '{}'", id, agent.name),
+                {
+                    "message_id": id,
+                    "agent_name": agent.name,
+                    "suggestion": "Only recipients can snooze a message; check fetch_inbox"
+                }
+            ),
+            mouchak_mail_core::Error::InvalidInput(msg) => McpError::invalid_params(msg, None),
+            other => McpError::internal_error(other.to_string(), None),
+        })?;
+
+    let msg = format!(
+        "Message {} snoozed for '{}' until {} UTC",
+        params.message_id, agent.name, until
+    );
+    Ok(CallToolResult::success(vec![Content::text(msg)]))
+}
 
-    let threads = MessageBmc::list_threads(ctx, mm, project.id.get(), params.limit.unwrap_or(50))
+/// List an agent's active snoozes, soonest to return first.
+pub async fn list_snoozed_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: ListSnoozedParams,
+) -> Result<CallToolResult, McpError> {
+    let (project, agent) =
+        helpers::resolve_project_and_agent(ctx, mm, &params.project_slug, &params.agent_name)
+            .await?;
+
+    let snoozed = MessageBmc::list_snoozed(ctx, mm, project.id.get(), agent.id.get())
         .await
         .map_err(|e| McpError::internal_error(e.to_string(), None))?;
 
     let mut output = format!(
-        "Threads in '{}' ({}):\n\n",
-        params.project_slug,
-        threads.len()
+        "Snoozed messages for '{}' ({}):\n\n",
+        agent.name,
+        snoozed.len()
     );
+    for s in &snoozed {
+        output.push_str(&format!(
+            "- [{}] {} (from: {}, back at: {} UTC)\n",
+            s.message.id, s.message.subject, s.message.sender_name, s.snoozed_until
+        ));
+    }
+    Ok(CallToolResult::success(vec![Content::text(output)]))
+}
+
+/// List messages an agent still has to acknowledge, oldest first.
+pub async fn list_pending_acks_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: ListPendingAcksParams,
+) -> Result<CallToolResult, McpError> {
+    let (project, agent) =
+        helpers::resolve_project_and_agent(ctx, mm, &params.project_slug, &params.agent_name)
+            .await?;
+
+    let pending =
+        MessageBmc::list_pending_acks_for_agent(ctx, mm, project.id.get(), agent.id.get())
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+    let mut output = format!(
+        "Pending acknowledgements for '{}' ({}):\n\n",
+        agent.name,
+        pending.len()
+    );
+    for p in &pending {
+        let sender = match &p.project_slug {
+            Some(slug) => format!("{}{}{}", slug, ADDRESS_SEPARATOR, p.sender_name),
+            None => p.sender_name.clone(),
+        };
+        output.push_str(&format!(
+            "- [{}] {} (from: {}, {}, {} day(s) outstanding)\n",
+            p.message_id, p.subject, sender, p.importance, p.days_outstanding
+        ));
+    }
+    Ok(CallToolResult::success(vec![Content::text(output)]))
+}
+
+/// List all conversation threads in a project.
+pub async fn list_threads_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: ListThreadsParams,
+) -> Result<CallToolResult, McpError> {
+    let project = helpers::resolve_project(ctx, mm, &params.project_slug).await?;
+
+    let threads =
+        MessageBmc::list_threads(ctx, mm, project.id.get(), params.limit.unwrap_or(50), None)
+            .await
+            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+    let mut output = format!("Threads in '{}' ({}):\n\n", project.slug, threads.len());
     for t in &threads {
         output.push_str(&format!(
             "- {} | {} ({} msgs, last: {})\n",
@@ -343,3 +1053,57 @@ pub async fn list_threads_impl(
     }
     Ok(CallToolResult::success(vec![Content::text(output)]))
 }
+
+/// Default number of recent snippets per summarized thread.
+const DEFAULT_SUMMARY_RECENT: i64 = 3;
+/// Upper bound on requested snippets per thread.
+const MAX_SUMMARY_RECENT: i64 = 50;
+
+/// Summarize one or more threads with structured statistics (no LLM).
+pub async fn summarize_thread_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    params: SummarizeThreadParams,
+) -> Result<CallToolResult, McpError> {
+    let project = helpers::resolve_project(ctx, mm, &params.project_slug).await?;
+
+    let recent = if params.include_examples == Some(false) {
+        0
+    } else {
+        params
+            .recent
+            .unwrap_or(DEFAULT_SUMMARY_RECENT)
+            .clamp(0, MAX_SUMMARY_RECENT)
+    };
+    let single = matches!(params.thread_id, ThreadIdInput::Single(_));
+    let thread_ids: Vec<String> = params.thread_id.into();
+
+    let mut summaries = Vec::new();
+    let mut errors = Vec::new();
+    for thread_id in thread_ids {
+        match MessageBmc::thread_summary(ctx, mm, project.id.get(), &thread_id, recent).await {
+            Ok(stats) => summaries.push(stats),
+            Err(mouchak_mail_core::Error::ThreadNotFound(_)) if single => {
+                return Err(mcp_err!(
+                    ErrorCode::ThreadNotFound,
+                    &format!("Thread '{}' not found", thread_id),
+                    {
+                        "thread_id": thread_id,
+                        "project_slug": project.slug,
+                        "suggestion": "Check thread IDs with list_threads"
+                    }
+                ));
+            }
+            Err(e) => errors.push(ThreadSummaryError {
+                thread_id,
+                error: e.to_string(),
+            }),
+        }
+    }
+
+    let result = ThreadStatsResult { summaries, errors };
+    let json = serde_json::to_string_pretty(&result)
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+    Ok(CallToolResult::success(vec![Content::text(json)]))
+}
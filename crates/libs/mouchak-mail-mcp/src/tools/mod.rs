@@ -3,23 +3,28 @@
 //! This module defines all MCP tools that wrap the lib-core functionality.
 
 use anyhow::Result;
-use mouchak_mail_common::config::AppConfig;
+use mouchak_mail_common::config::{AppConfig, MessageConfig};
 use rmcp::{
     ErrorData as McpError,
     handler::server::{ServerHandler, tool::ToolRouter, wrapper::Parameters},
     model::{
-        CallToolRequestParam, CallToolResult, Content, Implementation, ListResourcesResult,
-        ListToolsResult, PaginatedRequestParam, ReadResourceRequestParam, ReadResourceResult,
-        ResourcesCapability, ServerCapabilities, ServerInfo, ToolsCapability,
+        CallToolRequestParam, CallToolResult, Content, GetPromptRequestParam, GetPromptResult,
+        Implementation, ListPromptsResult, ListResourceTemplatesResult, ListResourcesResult,
+        ListToolsResult, Meta, PaginatedRequestParam, PromptsCapability, ReadResourceRequestParam,
+        ReadResourceResult, ResourcesCapability, ServerCapabilities, ServerInfo,
+        SubscribeRequestParam, ToolsCapability, UnsubscribeRequestParam,
     },
     service::{RequestContext, RoleServer},
     tool, tool_router,
 };
 use serde::Serialize;
+use serde_json::json;
 use std::sync::Arc;
 
 use mouchak_mail_core::{ctx::Ctx, model::ModelManager};
 
+use errors::{ErrorCode, mcp_err};
+
 pub mod agent;
 pub mod archive;
 pub mod attachments;
@@ -37,9 +42,11 @@ mod params;
 pub mod precommit;
 pub mod products;
 pub mod project;
+pub mod prompts;
 pub mod resources;
 pub mod reviews;
 mod schema;
+pub mod validation;
 
 pub use params::*;
 pub use schema::schema_from_params;
@@ -53,6 +60,11 @@ pub struct ToolSchema {
     pub name: String,
     pub description: String,
     pub parameters: Vec<ParameterSchema>,
+    /// Whether the server exposes this tool in the current configuration
+    pub enabled: bool,
+    /// Why the tool is disabled; `None` when it is enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disabled_reason: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -70,13 +82,66 @@ const BUILD_SLOT_TOOLS: &[&str] = &[
     "renew_build_slot",
 ];
 
+/// Why build slot tools are unavailable when worktrees are disabled
+const BUILD_SLOT_DISABLED_REASON: &str = "Build slot tools require WORKTREES_ENABLED=true.";
+
+/// Tools whose messages are subject to the size limits in `MessageConfig`
+const MESSAGE_SENDING_TOOLS: &[&str] = &["send_message", "reply_message", "forward_message"];
+
+/// Tools that act as an agent, with the argument naming that agent. A call
+/// made with an agent key may only name the key's own agent.
+const ACTING_AGENT_TOOLS: &[(&str, &str)] = &[
+    ("send_message", "sender_name"),
+    ("reply_message", "sender_name"),
+    ("forward_message", "sender_name"),
+    ("reserve_file", "agent_name"),
+    ("file_reservation_paths", "agent_name"),
+    ("renew_file_reservation", "agent_name"),
+    ("release_file_reservations_by_path", "agent_name"),
+    ("renew_file_reservations_by_agent", "agent_name"),
+    ("release_reservations", "agent_name"),
+];
+
+/// The `(project_slug, agent_name)` a `tools/call` acts as, if the tool is
+/// one that sends or reserves on an agent's behalf.
+///
+/// Tool aliases are resolved first.
+pub fn acting_agent(
+    tool_name: &str,
+    args: Option<&rmcp::model::JsonObject>,
+) -> Option<(String, String)> {
+    let tool_name = MouchakMailService::resolve_tool_alias(tool_name).unwrap_or(tool_name);
+    let (_, field) = ACTING_AGENT_TOOLS
+        .iter()
+        .find(|(name, _)| *name == tool_name)?;
+    let args = args?;
+    let project_slug = args.get("project_slug")?.as_str()?;
+    let agent_name = args.get(*field)?.as_str()?;
+    Some((project_slug.to_string(), agent_name.to_string()))
+}
+
+/// Size limits as a sentence for tool descriptions.
+pub fn message_limits_note(limits: &MessageConfig) -> String {
+    format!(
+        "Limits: subject at most {} characters, body_md at most {} bytes, at most {} recipients (to + cc + bcc).",
+        limits.max_subject_chars, limits.max_body_bytes, limits.max_recipients
+    )
+}
+
 /// Get schema information for all tools
 ///
-/// When `worktrees_enabled` is false, build slot tools are excluded from the list.
+/// Every tool is listed. When `worktrees_enabled` is false, build slot tools
+/// are marked disabled, with the reason in `disabled_reason`.
 pub fn get_tool_schemas(worktrees_enabled: bool) -> Vec<ToolSchema> {
     get_all_tool_schemas()
         .into_iter()
-        .filter(|schema| worktrees_enabled || !BUILD_SLOT_TOOLS.contains(&schema.name.as_str()))
+        .map(|mut schema| {
+            if !worktrees_enabled && BUILD_SLOT_TOOLS.contains(&schema.name.as_str()) {
+                schema.enabled = false;
+                schema.disabled_reason = Some(BUILD_SLOT_DISABLED_REASON.to_string());
+            }
+            schema
+        })
         .collect()
 }
 
@@ -105,13 +170,17 @@ fn get_all_tool_schemas() -> Vec<ToolSchema> {
         // Agent Management
         schema_from_params::<RegisterAgentParams>(
             "register_agent",
-            "Register a new agent in a project.",
+            "Register an agent in a project. Idempotent: re-registering an existing name updates its program, model and task unless strict=true.",
         ),
         schema_from_params::<ListAgentsParams>("list_agents", "List all agents in a project."),
         schema_from_params::<ListAgentsParams>(
             "list_project_agents",
             "List all agents in a project. (Alias for list_agents)",
         ),
+        schema_from_params::<ManageAgentGroupParams>(
+            "manage_agent_group",
+            "List, create, change or delete named agent groups addressable as group:<name>.",
+        ),
         schema_from_params::<WhoisParams>("whois", "Look up agent information by name."),
         schema_from_params::<GetAgentProfileParams>(
             "get_agent_profile",
@@ -121,6 +190,10 @@ fn get_all_tool_schemas() -> Vec<ToolSchema> {
             "update_agent_profile",
             "Update agent profile settings.",
         ),
+        schema_from_params::<UpdateAgentParams>(
+            "update_agent",
+            "Rename an agent, update its task, model or program, or retire it.",
+        ),
         schema_from_params::<CreateAgentIdentityParams>(
             "create_agent_identity",
             "Create a unique agent identity with auto-generated name.",
@@ -128,7 +201,7 @@ fn get_all_tool_schemas() -> Vec<ToolSchema> {
         // Messaging
         schema_from_params::<SendMessageParams>(
             "send_message",
-            "Send a message from one agent to others.",
+            "Send a message from one agent to others. An unknown thread_id is rejected unless allow_new_thread is true.",
         ),
         schema_from_params::<ListInboxParams>(
             "check_inbox",
@@ -142,17 +215,64 @@ fn get_all_tool_schemas() -> Vec<ToolSchema> {
             "list_inbox",
             "List an agent's inbox messages. (Alias for check_inbox)",
         ),
+        schema_from_params::<WaitForMessagesParams>(
+            "wait_for_messages",
+            "Wait up to timeout_seconds (max 120) for new messages in an agent's inbox.",
+        ),
         schema_from_params::<ReplyMessageParams>(
             "reply_message",
             "Reply to an existing message in a thread.",
         ),
+        schema_from_params::<ForwardMessageParams>(
+            "forward_message",
+            "Forward a message to other agents, quoting it with its original sender and date.",
+        ),
         schema_from_params::<GetMessageParams>("get_message", "Get a specific message by ID."),
-        schema_from_params::<ListOutboxParams>("list_outbox", "List messages sent by an agent."),
+        schema_from_params::<ListOutboxParams>(
+            "list_outbox",
+            "List messages sent by an agent with recipient read/ack status.",
+        ),
         schema_from_params::<MarkMessageReadParams>("mark_message_read", "Mark a message as read."),
         schema_from_params::<AcknowledgeMessageParams>(
             "acknowledge_message",
             "Acknowledge receipt of a message.",
         ),
+        schema_from_params::<LabelMessageParams>(
+            "label_message",
+            "Add or remove labels on a message to triage it (e.g. needs-review, blocked, done).",
+        ),
+        schema_from_params::<MuteThreadParams>(
+            "mute_thread",
+            "Mute a thread for an agent so its messages stay out of the inbox (urgent and @-mentions still get through).",
+        ),
+        schema_from_params::<MuteThreadParams>(
+            "unmute_thread",
+            "Unmute a thread for an agent, restoring its messages to the inbox.",
+        ),
+        schema_from_params::<RecallMessageParams>(
+            "recall_message",
+            "Recall a recently sent message, replacing it with a tombstone (sender only).",
+        ),
+        schema_from_params::<ListScheduledParams>(
+            "list_scheduled",
+            "List an agent's scheduled messages that have not been delivered yet.",
+        ),
+        schema_from_params::<CancelScheduledParams>(
+            "cancel_scheduled",
+            "Cancel a scheduled message before its delivery time (sender only).",
+        ),
+        schema_from_params::<SnoozeMessageParams>(
+            "snooze_message",
+            "Hide a message from an agent's inbox until a given time, or unsnooze it when `until` is omitted.",
+        ),
+        schema_from_params::<ListSnoozedParams>(
+            "list_snoozed",
+            "List an agent's snoozed messages and when each returns to the inbox.",
+        ),
+        schema_from_params::<ListPendingAcksParams>(
+            "list_pending_acks",
+            "List messages an agent still has to acknowledge, oldest first, with days outstanding.",
+        ),
         schema_from_params::<SearchMessagesParams>(
             "search_messages",
             "Search messages using full-text search.",
@@ -197,13 +317,21 @@ fn get_all_tool_schemas() -> Vec<ToolSchema> {
         schema_from_params::<ListContactsParams>("list_contacts", "List agent contacts."),
         schema_from_params::<SetContactPolicyParams>(
             "set_contact_policy",
-            "Set agent contact policy.",
+            "Set agent contact policy and do-not-disturb window.",
+        ),
+        schema_from_params::<GetContactPolicyParams>(
+            "get_contact_policy",
+            "Get agent contact policy and do-not-disturb window.",
         ),
         // File Reservations
         schema_from_params::<FileReservationParams>("reserve_file", "Reserve a file path pattern."),
         schema_from_params::<FileReservationPathsParams>(
             "file_reservation_paths",
-            "Reserve multiple file paths at once.",
+            "Reserve multiple file paths at once. With wait=true, paths held by other agents are queued and granted when released.",
+        ),
+        schema_from_params::<ListReservationQueueParams>(
+            "list_reservation_queue",
+            "List queued reservation requests with their positions and the reservations they wait on.",
         ),
         schema_from_params::<ListReservationsParams>(
             "list_file_reservations",
@@ -217,9 +345,13 @@ fn get_all_tool_schemas() -> Vec<ToolSchema> {
             "force_release_reservation",
             "Force release a reservation (for emergencies).",
         ),
+        schema_from_params::<TransferReservationParams>(
+            "transfer_reservation",
+            "Hand a live reservation to another agent in the same project, e.g. when its holder crashed. Both agents are notified and the transfer is audited.",
+        ),
         schema_from_params::<RenewFileReservationParams>(
             "renew_file_reservation",
-            "Extend a file reservation's TTL by ID.",
+            "Renew a live file reservation by ID or path pattern. The new expiry is counted from now and capped by the server's max TTL.",
         ),
         schema_from_params::<ReleaseFileReservationsByAgentParams>(
             "release_file_reservations_by_path",
@@ -241,6 +373,14 @@ fn get_all_tool_schemas() -> Vec<ToolSchema> {
             "list_file_reservations",
             "List active file reservations. (Alias for list_reservations)",
         ),
+        schema_from_params::<ListMyReservationsParams>(
+            "list_my_reservations",
+            "List the active file reservations held by an agent, with paths, exclusivity, and expiry.",
+        ),
+        schema_from_params::<ReleaseReservationsParams>(
+            "release_reservations",
+            "Release an agent's own file reservations by path, or all of them. Reports released, not found, and refused paths.",
+        ),
         // Build Slots
         schema_from_params::<AcquireBuildSlotParams>(
             "acquire_build_slot",
@@ -340,6 +480,10 @@ fn get_all_tool_schemas() -> Vec<ToolSchema> {
             "list_activity",
             "List recent activity in a project.",
         ),
+        schema_from_params::<GetServerInfoParams>(
+            "get_server_info",
+            "Get the server version, enabled features, limits and database schema version.",
+        ),
         // Overseer
         schema_from_params::<SendOverseerMessageParams>(
             "send_overseer_message",
@@ -361,6 +505,10 @@ pub struct MouchakMailService {
     tool_router: ToolRouter<Self>,
     /// Whether worktrees/build slot tools are enabled
     worktrees_enabled: bool,
+    /// Resource URIs this session's client subscribed to
+    subscriptions: resources::ResourceSubscriptions,
+    /// Context the current request runs under; see [`Self::for_request`]
+    ctx: Ctx,
 }
 
 impl MouchakMailService {
@@ -394,6 +542,8 @@ impl MouchakMailService {
             mm,
             tool_router,
             worktrees_enabled,
+            subscriptions: Default::default(),
+            ctx: Ctx::root_ctx(),
         })
     }
 
@@ -411,6 +561,8 @@ impl MouchakMailService {
             mm,
             tool_router,
             worktrees_enabled,
+            subscriptions: Default::default(),
+            ctx: Ctx::root_ctx(),
         }
     }
 
@@ -419,15 +571,38 @@ impl MouchakMailService {
         self.worktrees_enabled
     }
 
+    /// Flush state before exit (closes the event bus, checkpoints the WAL)
+    pub async fn shutdown(&self) -> Result<()> {
+        Ok(self.mm.shutdown().await?)
+    }
+
     fn ctx(&self) -> Ctx {
-        Ctx::root_ctx()
+        self.ctx.clone()
+    }
+
+    /// This service, running under the [`Ctx`] the HTTP auth layer attached
+    /// to the request.
+    ///
+    /// Requests without one (stdio, or HTTP without scoped credentials) keep
+    /// the root context.
+    fn for_request(&self, context: &RequestContext<RoleServer>) -> Self {
+        let ctx = context
+            .extensions
+            .get::<axum::http::request::Parts>()
+            .and_then(|parts| parts.extensions.get::<Ctx>())
+            .cloned()
+            .unwrap_or_else(Ctx::root_ctx);
+        Self {
+            ctx,
+            ..self.clone()
+        }
     }
 
     pub async fn read_resource_impl(
         &self,
         request: ReadResourceRequestParam,
     ) -> Result<ReadResourceResult, McpError> {
-        resources::read_resource_impl(&self.ctx(), &self.mm, request).await
+        resources::read_resource_impl(&self.ctx(), &self.mm, request, self.worktrees_enabled).await
     }
     pub async fn record_tool_metric(
         &self,
@@ -441,10 +616,24 @@ impl MouchakMailService {
         use mouchak_mail_core::model::tool_metric::{ToolMetricBmc, ToolMetricForCreate};
 
         let (status, error_code) = match result {
+            Ok(r) if r.is_error == Some(true) => {
+                ("error".to_string(), Some("TOOL_ERROR".to_string()))
+            }
             Ok(_) => ("success".to_string(), None),
             Err(e) => ("error".to_string(), Some(format!("{:?}", e.code))),
         };
 
+        // Prometheus series are process-local; the tool_metrics row below
+        // keeps history across restarts.
+        metrics::counter!(
+            "mcp_tool_calls_total",
+            "tool" => tool_name.to_string(),
+            "status" => status.clone()
+        )
+        .increment(1);
+        metrics::histogram!("mcp_tool_duration_seconds", "tool" => tool_name.to_string())
+            .record(duration.as_secs_f64());
+
         // Extract context
         let (project_slug, agent_name) = self.extract_context(args);
 
@@ -507,7 +696,22 @@ impl MouchakMailService {
         &self,
         request: Option<PaginatedRequestParam>,
     ) -> Result<ListResourcesResult, McpError> {
-        resources::list_resources_impl(&self.ctx(), &self.mm, request).await
+        resources::list_resources_impl(&self.ctx(), &self.mm, request, self.worktrees_enabled).await
+    }
+
+    pub fn list_resource_templates_impl(&self) -> ListResourceTemplatesResult {
+        resources::list_resource_templates_impl(self.worktrees_enabled)
+    }
+
+    pub fn list_prompts_impl(&self) -> ListPromptsResult {
+        prompts::list_prompts_impl()
+    }
+
+    pub async fn get_prompt_impl(
+        &self,
+        request: GetPromptRequestParam,
+    ) -> Result<GetPromptResult, McpError> {
+        prompts::get_prompt_impl(&self.ctx(), &self.mm, request).await
     }
 
     /// Public impl method for testing search_messages_product
@@ -546,26 +750,69 @@ impl MouchakMailService {
     pub fn check_build_slot_rejection(&self, tool_name: &str) -> Option<String> {
         if !self.worktrees_enabled && BUILD_SLOT_TOOLS.contains(&tool_name) {
             Some(format!(
-                "Tool '{}' is not available. Build slot tools require WORKTREES_ENABLED=true.",
-                tool_name
+                "Tool '{}' is not available. {}",
+                tool_name, BUILD_SLOT_DISABLED_REASON
             ))
         } else {
             None
         }
     }
 
+    /// Check a call's arguments against the tool's advertised input schema.
+    ///
+    /// Every type mismatch and missing required field is reported in one
+    /// `VALIDATION_ERROR`. Unknown fields are not an error; they come back in
+    /// the report so the caller can warn about them. Unknown tools pass, and
+    /// the router rejects them.
+    /// Public for testing argument validation.
+    pub fn validate_tool_arguments(
+        &self,
+        tool_name: &str,
+        args: Option<&rmcp::model::JsonObject>,
+    ) -> Result<validation::ArgumentReport, McpError> {
+        let Some(route) = self.tool_router.map.get(tool_name) else {
+            return Ok(validation::ArgumentReport::default());
+        };
+        let report = validation::validate_arguments(&route.attr.input_schema, args);
+        if report.is_valid() {
+            return Ok(report);
+        }
+
+        let problems: Vec<String> = report.violations.iter().map(|v| v.describe()).collect();
+        Err(mcp_err!(
+            ErrorCode::ValidationError,
+            &format!(
+                "Invalid arguments for '{}': {}",
+                tool_name,
+                problems.join("; ")
+            ),
+            {
+                "violations": report.violations,
+                "unknown_fields": report.unknown_fields,
+                "suggestion": "Fix the listed fields and retry; see tools/list for the input schema"
+            }
+        ))
+    }
+
     /// List tools with worktree filtering applied.
+    ///
+    /// Tools that send messages have the configured size limits appended to
+    /// their descriptions so agents can stay under them.
     /// Public for testing ServerHandler::list_tools filtering logic.
     pub fn list_tools_filtered(&self) -> Vec<rmcp::model::Tool> {
-        let all_tools = self.tool_router.list_all();
-        if self.worktrees_enabled {
-            all_tools
-        } else {
-            all_tools
-                .into_iter()
-                .filter(|tool| !BUILD_SLOT_TOOLS.contains(&&*tool.name))
-                .collect()
-        }
+        let limits = message_limits_note(&self.mm.app_config.messages);
+        self.tool_router
+            .list_all()
+            .into_iter()
+            .filter(|tool| self.worktrees_enabled || !BUILD_SLOT_TOOLS.contains(&&*tool.name))
+            .map(|mut tool| {
+                if MESSAGE_SENDING_TOOLS.contains(&&*tool.name) {
+                    let description = tool.description.as_deref().unwrap_or_default();
+                    tool.description = Some(format!("{} {}", description, limits).into());
+                }
+                tool
+            })
+            .collect()
     }
 }
 
@@ -576,7 +823,11 @@ impl ServerHandler for MouchakMailService {
             protocol_version: Default::default(),
             capabilities: ServerCapabilities {
                 tools: Some(ToolsCapability::default()),
-                resources: Some(ResourcesCapability::default()),
+                resources: Some(ResourcesCapability {
+                    subscribe: Some(true),
+                    list_changed: None,
+                }),
+                prompts: Some(PromptsCapability::default()),
                 ..Default::default()
             },
             server_info: Implementation {
@@ -585,7 +836,9 @@ impl ServerHandler for MouchakMailService {
                 ..Default::default()
             },
             instructions: Some(
-                "Mouchak Mail MCP Server - Multi-agent messaging and coordination system"
+                "Mouchak Mail MCP Server - Multi-agent messaging and coordination system. \
+                 Inboxes and messages are also readable as resources; see \
+                 resources/templates/list for the URI forms."
                     .to_string(),
             ),
         }
@@ -611,6 +864,7 @@ impl ServerHandler for MouchakMailService {
         context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<CallToolResult, McpError>> + Send + '_ {
         async move {
+            let service = self.for_request(&context);
             let start = std::time::Instant::now();
             let original_name = request.name.clone();
             let args = request.arguments.clone();
@@ -633,31 +887,50 @@ impl ServerHandler for MouchakMailService {
 
             let tool_name = request.name.clone();
 
-            if !self.worktrees_enabled && BUILD_SLOT_TOOLS.contains(&&*tool_name) {
+            let result = if !service.worktrees_enabled && BUILD_SLOT_TOOLS.contains(&&*tool_name) {
                 tracing::warn!(
                     tool = %tool_name,
                     "Attempted to call build slot tool but worktrees are disabled"
                 );
-                return Err(McpError::invalid_request(
+                Err(McpError::invalid_request(
                     format!(
-                        "Tool '{}' is not available. Build slot tools require WORKTREES_ENABLED=true.",
-                        tool_name
+                        "Tool '{}' is not available. {}",
+                        tool_name, BUILD_SLOT_DISABLED_REASON
                     ),
                     None,
-                ));
-            }
-
-            let tool_context =
-                rmcp::handler::server::tool::ToolCallContext::new(self, request, context);
-            let result = self.tool_router.call(tool_context).await;
+                ))
+            } else {
+                match service.validate_tool_arguments(&tool_name, request.arguments.as_ref()) {
+                    Err(e) => Err(e),
+                    Ok(report) => {
+                        let tool_context = rmcp::handler::server::tool::ToolCallContext::new(
+                            &service, request, context,
+                        );
+                        let result = service.tool_router.call(tool_context).await;
+                        // Unknown fields ride along as warnings rather than
+                        // failing, so newer clients work against this server
+                        result.map(|mut result| {
+                            if !report.unknown_fields.is_empty() {
+                                let meta = result.meta.get_or_insert_with(Meta::new);
+                                meta.insert("warnings".to_string(), json!(report.warnings()));
+                                meta.insert(
+                                    "unknown_fields".to_string(),
+                                    json!(report.unknown_fields),
+                                );
+                            }
+                            result
+                        })
+                    }
+                }
+            };
 
             let duration = start.elapsed();
 
-            // Fire and forget metric recording (spawn generic task or just await since we are async)
-            // Awaiting is safer to ensure it's recorded before response?
-            // Better to spawn to avoid latency, but for now await is fine as DB write is fast.
+            // Awaited rather than spawned so the row is visible once the call returns;
+            // the DB write is fast relative to tool execution.
             let args_val = args.map(serde_json::Value::Object);
-            self.record_tool_metric(&tool_name, &args_val, duration, &result)
+            service
+                .record_tool_metric(&tool_name, &args_val, duration, &result)
                 .await;
 
             result
@@ -666,18 +939,69 @@ impl ServerHandler for MouchakMailService {
 
     fn list_resources(
         &self,
-        _request: Option<PaginatedRequestParam>,
-        _context: RequestContext<RoleServer>,
+        request: Option<PaginatedRequestParam>,
+        context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<ListResourcesResult, McpError>> + Send + '_ {
-        async move { self.list_resources_impl(_request).await }
+        async move {
+            self.for_request(&context)
+                .list_resources_impl(request)
+                .await
+        }
     }
 
     fn read_resource(
         &self,
         request: ReadResourceRequestParam,
-        _context: RequestContext<RoleServer>,
+        context: RequestContext<RoleServer>,
     ) -> impl std::future::Future<Output = Result<ReadResourceResult, McpError>> + Send + '_ {
-        self.read_resource_impl(request)
+        async move { self.for_request(&context).read_resource_impl(request).await }
+    }
+
+    fn list_resource_templates(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> impl std::future::Future<Output = Result<ListResourceTemplatesResult, McpError>> + Send + '_
+    {
+        async move { Ok(self.list_resource_templates_impl()) }
+    }
+
+    fn list_prompts(
+        &self,
+        _request: Option<PaginatedRequestParam>,
+        _context: RequestContext<RoleServer>,
+    ) -> impl std::future::Future<Output = Result<ListPromptsResult, McpError>> + Send + '_ {
+        async move { Ok(self.list_prompts_impl()) }
+    }
+
+    fn get_prompt(
+        &self,
+        request: GetPromptRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> impl std::future::Future<Output = Result<GetPromptResult, McpError>> + Send + '_ {
+        async move { self.for_request(&context).get_prompt_impl(request).await }
+    }
+
+    fn subscribe(
+        &self,
+        request: SubscribeRequestParam,
+        context: RequestContext<RoleServer>,
+    ) -> impl std::future::Future<Output = Result<(), McpError>> + Send + '_ {
+        async move {
+            self.subscriptions
+                .subscribe(&self.mm, &request.uri, context.peer)
+        }
+    }
+
+    fn unsubscribe(
+        &self,
+        request: UnsubscribeRequestParam,
+        _context: RequestContext<RoleServer>,
+    ) -> impl std::future::Future<Output = Result<(), McpError>> + Send + '_ {
+        async move {
+            self.subscriptions.unsubscribe(&request.uri);
+            Ok(())
+        }
     }
 }
 
@@ -700,7 +1024,9 @@ impl MouchakMailService {
     }
 
     /// Register a new agent in a project
-    #[tool(description = "Register an agent in a project. Agents can send and receive messages.")]
+    #[tool(
+        description = "Register an agent in a project. Agents can send and receive messages. Idempotent: re-registering an existing name updates its program, model and task unless strict=true."
+    )]
     async fn register_agent(
         &self,
         params: Parameters<RegisterAgentParams>,
@@ -710,7 +1036,7 @@ impl MouchakMailService {
 
     /// Send a message to one or more agents
     #[tool(
-        description = "Send a message from one agent to another. Creates a new thread or continues an existing one."
+        description = "Send a message from one agent to another. Creates a new thread or continues an existing one: an unknown thread_id is rejected with THREAD_NOT_FOUND unless allow_new_thread=true (thread IDs are case-sensitive, at most 128 characters). Recipients may include group:<name> to reach that group's current members (see manage_agent_group). Returns a summary line followed by a JSON delivery report: {message_id, delivered: [names], failed: [{name, code}], groups: [{group, agent_ids, members}], partial_success}. By default nothing is sent unless every recipient resolves; with allow_partial=true the message goes to the recipients that resolve and the rest are listed in failed (code AGENT_NOT_FOUND, PROJECT_NOT_FOUND, FORBIDDEN_CROSS_PROJECT, GROUP_NOT_FOUND or GROUP_EMPTY)."
     )]
     async fn send_message(
         &self,
@@ -728,6 +1054,17 @@ impl MouchakMailService {
         messaging::list_inbox_impl(&self.ctx(), &self.mm, params.0).await
     }
 
+    /// Long-poll an agent's inbox for new messages
+    #[tool(
+        description = "Wait for new messages in an agent's inbox. Returns as soon as one arrives, or an empty result after timeout_seconds (default 30, max 120)."
+    )]
+    async fn wait_for_messages(
+        &self,
+        params: Parameters<WaitForMessagesParams>,
+    ) -> Result<CallToolResult, McpError> {
+        messaging::wait_for_messages_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
     /// Get a specific message by ID
     #[tool(description = "Retrieve a message by its ID, including full body content.")]
     async fn get_message(
@@ -800,6 +1137,17 @@ impl MouchakMailService {
         agent::list_agents_impl(&self.ctx(), &self.mm, params.0).await
     }
 
+    /// Manage named agent groups
+    #[tool(
+        description = "Manage a project's named agent groups. action is one of list, create, add_member, remove_member or delete; agent_names is comma-separated. Send to a group's current members with to=\"group:<name>\". Group names may not match an agent's name."
+    )]
+    async fn manage_agent_group(
+        &self,
+        params: Parameters<ManageAgentGroupParams>,
+    ) -> Result<CallToolResult, McpError> {
+        agent::manage_agent_group_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
     /// Reserve a file path for an agent
     #[tool(description = "Reserve a file path pattern to prevent conflicts between agents.")]
     async fn reserve_file(
@@ -818,6 +1166,17 @@ impl MouchakMailService {
         files::list_reservations_impl(&self.ctx(), &self.mm, params.0).await
     }
 
+    /// List queued reservation requests
+    #[tool(
+        description = "List reservation requests waiting for a path, in queue order, with their positions and the reservations blocking them."
+    )]
+    async fn list_reservation_queue(
+        &self,
+        params: Parameters<ListReservationQueueParams>,
+    ) -> Result<CallToolResult, McpError> {
+        files::list_reservation_queue_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
     /// Release a file reservation
     #[tool(description = "Release a file reservation by ID.")]
     async fn release_reservation(
@@ -838,9 +1197,20 @@ impl MouchakMailService {
         files::force_release_reservation_impl(&self.ctx(), &self.mm, params.0).await
     }
 
+    /// Transfer a file reservation to another agent
+    #[tool(
+        description = "Hand a live reservation to another agent in the same project, e.g. when its holder crashed mid-task. Both agents get a system message and the transfer is audited. Released or expired reservations cannot be transferred."
+    )]
+    async fn transfer_reservation(
+        &self,
+        params: Parameters<TransferReservationParams>,
+    ) -> Result<CallToolResult, McpError> {
+        files::transfer_reservation_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
     /// Renew a file reservation TTL
     #[tool(
-        description = "Extend the TTL of a file reservation. Keeps the lock active for more work."
+        description = "Renew a live file reservation by ID or path pattern. The new expiry is counted from now (not stacked) and capped by the server's max TTL."
     )]
     async fn renew_file_reservation(
         &self,
@@ -869,6 +1239,28 @@ impl MouchakMailService {
         files::renew_file_reservations_by_agent_impl(&self.ctx(), &self.mm, params.0).await
     }
 
+    /// List reservations held by an agent
+    #[tool(
+        description = "List the active file reservations held by an agent, with paths, exclusivity, and expiry."
+    )]
+    async fn list_my_reservations(
+        &self,
+        params: Parameters<ListMyReservationsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        files::list_my_reservations_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
+    /// Release an agent's own reservations
+    #[tool(
+        description = "Release your own file reservations by path, or all of them with all=true. Paths you don't hold are reported, not errors; reservations held by other agents are never released."
+    )]
+    async fn release_reservations(
+        &self,
+        params: Parameters<ReleaseReservationsParams>,
+    ) -> Result<CallToolResult, McpError> {
+        files::release_reservations_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
     /// Reply to a message
     #[tool(description = "Reply to an existing message in a thread.")]
     async fn reply_message(
@@ -878,6 +1270,17 @@ impl MouchakMailService {
         messaging::reply_message_impl(&self.ctx(), &self.mm, params.0).await
     }
 
+    /// Forward a message
+    #[tool(
+        description = "Forward a message you sent or received to agents who weren't on it. They get a new message quoting the original with its sender, date, To and Cc (never Bcc), linked back via forwarded_from_id. Recalled messages can't be forwarded."
+    )]
+    async fn forward_message(
+        &self,
+        params: Parameters<ForwardMessageParams>,
+    ) -> Result<CallToolResult, McpError> {
+        messaging::forward_message_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
     /// Mark a message as read
     #[tool(description = "Mark a message as read by a specific agent.")]
     async fn mark_message_read(
@@ -896,6 +1299,101 @@ impl MouchakMailService {
         messaging::acknowledge_message_impl(&self.ctx(), &self.mm, params.0).await
     }
 
+    /// Label a message
+    #[tool(
+        description = "Add or remove labels (e.g. needs-review, blocked, done) on a message in your project. Unknown labels are created unless create_missing is false. Returns the message's labels."
+    )]
+    async fn label_message(
+        &self,
+        params: Parameters<LabelMessageParams>,
+    ) -> Result<CallToolResult, McpError> {
+        messaging::label_message_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
+    /// Mute a thread
+    #[tool(
+        description = "Mute a noisy thread for an agent. Its messages stay out of the agent's inbox unless urgent or the subject @-mentions the agent; they remain visible in the thread. Unmuting brings them back."
+    )]
+    async fn mute_thread(
+        &self,
+        params: Parameters<MuteThreadParams>,
+    ) -> Result<CallToolResult, McpError> {
+        messaging::mute_thread_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
+    /// Unmute a thread
+    #[tool(
+        description = "Unmute a thread for an agent. Its messages, including any sent while it was muted, show up in the inbox again."
+    )]
+    async fn unmute_thread(
+        &self,
+        params: Parameters<MuteThreadParams>,
+    ) -> Result<CallToolResult, McpError> {
+        messaging::unmute_thread_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
+    /// Recall a sent message
+    #[tool(
+        description = "Recall a message you sent within the recall window. Recipients see a tombstone with your reason instead of the original content."
+    )]
+    async fn recall_message(
+        &self,
+        params: Parameters<RecallMessageParams>,
+    ) -> Result<CallToolResult, McpError> {
+        messaging::recall_message_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
+    /// List scheduled messages
+    #[tool(
+        description = "List messages an agent has scheduled with deliver_at that are still pending."
+    )]
+    async fn list_scheduled(
+        &self,
+        params: Parameters<ListScheduledParams>,
+    ) -> Result<CallToolResult, McpError> {
+        messaging::list_scheduled_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
+    /// Cancel a scheduled message
+    #[tool(description = "Cancel a message you scheduled with deliver_at before it is delivered.")]
+    async fn cancel_scheduled(
+        &self,
+        params: Parameters<CancelScheduledParams>,
+    ) -> Result<CallToolResult, McpError> {
+        messaging::cancel_scheduled_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
+    /// Snooze or unsnooze a message
+    #[tool(
+        description = "Snooze a message for one recipient: it leaves their inbox until `until`, then comes back flagged returned_from_snooze. Omit `until` to unsnooze now. Reading or acknowledging the message clears the snooze; other recipients and the thread view are unaffected."
+    )]
+    async fn snooze_message(
+        &self,
+        params: Parameters<SnoozeMessageParams>,
+    ) -> Result<CallToolResult, McpError> {
+        messaging::snooze_message_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
+    /// List snoozed messages
+    #[tool(description = "List messages an agent has snoozed and when each returns to the inbox.")]
+    async fn list_snoozed(
+        &self,
+        params: Parameters<ListSnoozedParams>,
+    ) -> Result<CallToolResult, McpError> {
+        messaging::list_snoozed_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
+    /// List pending acknowledgements
+    #[tool(
+        description = "List ack_required messages addressed to an agent that it has not acknowledged yet, oldest first, with how many days each has been outstanding. Recalled messages are left out."
+    )]
+    async fn list_pending_acks(
+        &self,
+        params: Parameters<ListPendingAcksParams>,
+    ) -> Result<CallToolResult, McpError> {
+        messaging::list_pending_acks_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
     /// Generate agent identity names
     #[tool(description = "Generate memorable agent names with collision detection.")]
     async fn create_agent_identity(
@@ -914,6 +1412,15 @@ impl MouchakMailService {
         agent::update_agent_profile_impl(&self.ctx(), &self.mm, params.0).await
     }
 
+    /// Update or retire an agent
+    #[tool(description = "Rename an agent, update its task, model or program, or retire it.")]
+    async fn update_agent(
+        &self,
+        params: Parameters<UpdateAgentParams>,
+    ) -> Result<CallToolResult, McpError> {
+        agent::update_agent_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
     /// Get project info
     #[tool(description = "Get detailed information about a project.")]
     async fn get_project_info(
@@ -976,7 +1483,9 @@ impl MouchakMailService {
     }
 
     /// Set contact policy
-    #[tool(description = "Set an agent's contact acceptance policy (auto, manual, deny).")]
+    #[tool(
+        description = "Set an agent's contact acceptance policy (auto, manual, deny) and/or do-not-disturb window. While DND is on, messages below min_importance are held back from the inbox until dnd_until; urgent and ack_required messages always get through."
+    )]
     async fn set_contact_policy(
         &self,
         params: Parameters<SetContactPolicyParams>,
@@ -984,6 +1493,15 @@ impl MouchakMailService {
         contacts::set_contact_policy_impl(&self.ctx(), &self.mm, params.0).await
     }
 
+    /// Get contact policy
+    #[tool(description = "Get an agent's contact acceptance policy and do-not-disturb window.")]
+    async fn get_contact_policy(
+        &self,
+        params: Parameters<GetContactPolicyParams>,
+    ) -> Result<CallToolResult, McpError> {
+        contacts::get_contact_policy_impl(&self.ctx(), &self.mm, params.0).await
+    }
+
     /// Acquire build slot
     #[tool(description = "Acquire an exclusive build slot for CI/CD isolation.")]
     async fn acquire_build_slot(
@@ -1168,68 +1686,13 @@ impl MouchakMailService {
     }
 
     #[tool(
-        description = "Summarize one or more conversation threads. Accepts single thread_id (string) or multiple (array). Partial failures are returned in errors array."
+        description = "Summarize one or more conversation threads with structured stats: participants with message counts, first/last timestamps, ack totals and recent snippets (no LLM). Accepts single thread_id (string) or multiple (array). An unknown single thread returns THREAD_NOT_FOUND; batch failures are returned in errors array."
     )]
     async fn summarize_thread(
         &self,
         params: Parameters<SummarizeThreadParams>,
     ) -> Result<CallToolResult, McpError> {
-        use mouchak_mail_core::model::message::MessageBmc;
-
-        let ctx = self.ctx();
-        let p = params.0;
-
-        let project = helpers::resolve_project(&ctx, &self.mm, &p.project_slug).await?;
-
-        let thread_ids: Vec<String> = p.thread_id.into();
-        let mut summaries = Vec::new();
-        let mut errors = Vec::new();
-
-        for thread_id in thread_ids {
-            match MessageBmc::list_by_thread(&ctx, &self.mm, project.id.get(), &thread_id).await {
-                Ok(messages) if !messages.is_empty() => {
-                    let mut participants: Vec<String> =
-                        messages.iter().map(|m| m.sender_name.clone()).collect();
-                    participants.sort();
-                    participants.dedup();
-
-                    let subject = messages
-                        .first()
-                        .map(|m| m.subject.clone())
-                        .unwrap_or_default();
-                    let last_snippet = messages
-                        .last()
-                        .map(|m| m.body_md.chars().take(100).collect::<String>())
-                        .unwrap_or_default();
-
-                    summaries.push(ThreadSummaryItem {
-                        thread_id,
-                        subject,
-                        message_count: messages.len(),
-                        participants,
-                        last_snippet,
-                    });
-                }
-                Ok(_) => {
-                    errors.push(ThreadSummaryError {
-                        thread_id: thread_id.clone(),
-                        error: "Thread not found or empty".to_string(),
-                    });
-                }
-                Err(e) => {
-                    errors.push(ThreadSummaryError {
-                        thread_id: thread_id.clone(),
-                        error: e.to_string(),
-                    });
-                }
-            }
-        }
-
-        let result = SummarizeResult { summaries, errors };
-        let json = serde_json::to_string_pretty(&result)
-            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
-
-        Ok(CallToolResult::success(vec![Content::text(json)]))
+        messaging::summarize_thread_impl(&self.ctx(), &self.mm, params.0).await
     }
 
     /// Ensure product exists
@@ -1348,7 +1811,9 @@ impl MouchakMailService {
     }
 
     /// List messages in an agent's outbox
-    #[tool(description = "Get messages from an agent's outbox (sent messages).")]
+    #[tool(
+        description = "Get messages from an agent's outbox (sent messages) with each recipient's read/ack status. Page with `cursor`."
+    )]
     async fn list_outbox(
         &self,
         params: Parameters<ListOutboxParams>,
@@ -1357,7 +1822,7 @@ impl MouchakMailService {
     }
 
     #[tool(
-        description = "Reserve multiple file paths for exclusive editing with conflict detection."
+        description = "Reserve multiple file paths for exclusive editing with conflict detection. Set wait=true to queue paths held by other agents until they are released."
     )]
     async fn file_reservation_paths(
         &self,
@@ -1418,6 +1883,14 @@ impl MouchakMailService {
         observability::get_tool_stats_impl(&self.ctx(), &self.mm, params.0).await
     }
 
+    /// Get server capabilities and limits
+    #[tool(
+        description = "Get the server version, which optional features are enabled (worktrees, quotas, rate limiting, ...), enforced limits (body size, recipients, reservation TTL) and the database schema version. Fixed until the server restarts, so call it once per session."
+    )]
+    async fn get_server_info(&self) -> Result<CallToolResult, McpError> {
+        observability::get_server_info_impl(&self.ctx(), &self.mm, self.worktrees_enabled).await
+    }
+
     /// List activity for a project
     #[tool(description = "List recent activity for a project.")]
     async fn list_activity(
@@ -1463,14 +1936,9 @@ mod tests {
         let conn = db.connect().unwrap();
         let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
 
-        let schema1 = include_str!("../../../../../migrations/001_initial_schema.sql");
-        conn.execute_batch(schema1).await.unwrap();
-        let schema2 = include_str!("../../../../../migrations/002_agent_capabilities.sql");
-        conn.execute_batch(schema2).await.unwrap();
-        let schema3 = include_str!("../../../../../migrations/003_tool_metrics.sql");
-        conn.execute_batch(schema3).await.unwrap();
-        let schema4 = include_str!("../../../../../migrations/004_attachments.sql");
-        conn.execute_batch(schema4).await.unwrap();
+        mouchak_mail_core::store::apply_migrations(&conn)
+            .await
+            .unwrap();
 
         let app_config = Arc::new(AppConfig::default());
         let mm = ModelManager::new_for_test(conn, archive_root, app_config);
@@ -1509,7 +1977,11 @@ mod tests {
             body_md: "Body".into(),
             importance: None,
             thread_id: None,
+            allow_new_thread: None,
             ack_required: None,
+            deliver_at: None,
+            broadcast: None,
+            allow_partial: None,
         };
 
         // We invoke the handler directly
@@ -1543,7 +2015,11 @@ mod tests {
             body_md: "Body".into(),
             importance: None,
             thread_id: None,
+            allow_new_thread: None,
             ack_required: None,
+            deliver_at: None,
+            broadcast: None,
+            allow_partial: None,
         };
         let result = service.send_message(Parameters(params2)).await;
         assert!(result.is_ok());
@@ -1663,7 +2139,11 @@ mod tests {
             body_md: "Body".into(),
             importance: None,
             thread_id: None,
+            allow_new_thread: None,
             ack_required: None,
+            deliver_at: None,
+            broadcast: None,
+            allow_partial: None,
         };
 
         // Invoke
@@ -1747,6 +2227,9 @@ mod tests {
             thread_id: None,
             importance: None,
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
         };
         MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
 
@@ -1848,24 +2331,33 @@ mod tests {
             names.contains(&"renew_build_slot"),
             "renew_build_slot should be present when worktrees enabled"
         );
+        assert!(schemas.iter().all(|s| s.enabled));
     }
 
     #[test]
-    fn test_get_tool_schemas_worktrees_disabled_excludes_build_slots() {
+    fn test_get_tool_schemas_worktrees_disabled_marks_build_slots() {
         let schemas = get_tool_schemas(false);
-        let names: Vec<&str> = schemas.iter().map(|s| s.name.as_str()).collect();
 
+        for name in BUILD_SLOT_TOOLS {
+            let schema = schemas
+                .iter()
+                .find(|s| s.name == *name)
+                .unwrap_or_else(|| panic!("{} should still be listed", name));
+            assert!(
+                !schema.enabled,
+                "{} should be disabled when worktrees disabled",
+                name
+            );
+            assert_eq!(
+                schema.disabled_reason.as_deref(),
+                Some(BUILD_SLOT_DISABLED_REASON)
+            );
+        }
         assert!(
-            !names.contains(&"acquire_build_slot"),
-            "acquire_build_slot should NOT be present when worktrees disabled"
-        );
-        assert!(
-            !names.contains(&"release_build_slot"),
-            "release_build_slot should NOT be present when worktrees disabled"
-        );
-        assert!(
-            !names.contains(&"renew_build_slot"),
-            "renew_build_slot should NOT be present when worktrees disabled"
+            schemas
+                .iter()
+                .filter(|s| !BUILD_SLOT_TOOLS.contains(&s.name.as_str()))
+                .all(|s| s.enabled && s.disabled_reason.is_none())
         );
     }
 
@@ -1,12 +1,13 @@
 //! Observability tool implementations
 //!
-//! Handles tool metrics, activity tracking, and pending reviews listing.
+//! Handles tool metrics, activity tracking, pending reviews listing and
+//! server introspection.
 
 use mouchak_mail_core::{
     ctx::Ctx,
     model::{
         ModelManager, activity::ActivityBmc, agent::AgentBmc, message::MessageBmc,
-        project::ProjectBmc, tool_metric::ToolMetricBmc,
+        project::ProjectBmc, server_info::ServerInfoBmc, tool_metric::ToolMetricBmc,
     },
 };
 use rmcp::{ErrorData as McpError, model::CallToolResult, model::Content};
@@ -47,6 +48,26 @@ pub async fn get_tool_stats_impl(
     Ok(CallToolResult::success(vec![Content::text(json_str)]))
 }
 
+/// Describe the server: version, enabled features, limits and schema version.
+///
+/// `worktrees_enabled` is what this service exposes, which can differ from
+/// the loaded config when the service was built with an override.
+pub async fn get_server_info_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    worktrees_enabled: bool,
+) -> Result<CallToolResult, McpError> {
+    let mut info = ServerInfoBmc::get(ctx, mm)
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+    info.features.worktrees = worktrees_enabled;
+
+    let json_str = serde_json::to_string_pretty(&info)
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+
+    Ok(CallToolResult::success(vec![Content::text(json_str)]))
+}
+
 /// List recent activity for a project.
 pub async fn list_activity_impl(
     ctx: &Ctx,
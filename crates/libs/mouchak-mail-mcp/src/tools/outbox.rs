@@ -1,10 +1,15 @@
 //! Outbox tool implementations
 //!
-//! Handles listing sent messages for agents.
+//! Handles listing sent messages for agents, with per-recipient read
+//! and ack status.
 
 use mouchak_mail_core::{
     ctx::Ctx,
-    model::{ModelManager, agent::AgentBmc, message::MessageBmc},
+    model::{
+        ModelManager,
+        agent::AgentBmc,
+        message::{MessageBmc, OutboxRecipient},
+    },
 };
 use rmcp::{ErrorData as McpError, model::CallToolResult, model::Content};
 use std::sync::Arc;
@@ -24,12 +29,14 @@ pub async fn list_outbox_impl(
         .await
         .map_err(|e| McpError::invalid_params(format!("Agent not found: {}", e), None))?;
 
+    let limit = params.limit.unwrap_or(50);
     let messages = MessageBmc::list_outbox_for_agent(
         ctx,
         mm,
         project.id.get(),
         agent.id.get(),
-        params.limit.unwrap_or(50),
+        limit,
+        params.cursor,
     )
     .await
     .map_err(|e| McpError::internal_error(e.to_string(), None))?;
@@ -39,12 +46,37 @@ pub async fn list_outbox_impl(
         params.agent_name,
         messages.len()
     );
-    for m in &messages {
+    for sent in &messages {
+        let m = &sent.message;
         output.push_str(&format!(
-            "- [{}] {} (to: {:?}, thread: {:?}, {})\n",
-            m.id, m.subject, m.sender_name, m.thread_id, m.importance
+            "- [{}] {} (thread: {:?}, {})\n",
+            m.id, m.subject, m.thread_id, m.importance
+        ));
+        let recipients: Vec<String> = sent
+            .recipients
+            .iter()
+            .map(|r| format!("{}: {} ({})", r.recipient_type, r.name, delivery_status(r)))
+            .collect();
+        output.push_str(&format!("  {}\n", recipients.join(", ")));
+    }
+    if messages.len() as i64 == limit
+        && let Some(last) = messages.last()
+    {
+        output.push_str(&format!(
+            "\nMore messages may exist; pass cursor={} for the next page.\n",
+            last.message.id
         ));
     }
 
     Ok(CallToolResult::success(vec![Content::text(output)]))
 }
+
+fn delivery_status(recipient: &OutboxRecipient) -> &'static str {
+    if recipient.ack_ts.is_some() {
+        "acked"
+    } else if recipient.read_ts.is_some() {
+        "read"
+    } else {
+        "unread"
+    }
+}
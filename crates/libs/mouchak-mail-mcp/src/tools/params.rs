@@ -2,6 +2,7 @@
 //!
 //! This module contains all parameter and response types for MCP tools.
 
+use mouchak_mail_core::utils::validation::AGENT_NAME_PATTERN;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -53,10 +54,13 @@ pub struct ListProjectsParams {}
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct RegisterAgentParams {
     /// Project slug the agent belongs to
-    #[serde(alias = "project_key")]
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
-    /// Agent's unique name within the project (alias: agent_name)
+    /// Agent's unique name within the project (alias: agent_name).
+    /// 2-64 ASCII letters, digits, '_' or '-'; case is kept, but names that
+    /// differ only in case count as taken.
     #[serde(alias = "agent_name")]
+    #[schemars(regex(pattern = AGENT_NAME_PATTERN))]
     pub name: String,
     /// Agent's program identifier (e.g., "claude-code", "antigravity")
     pub program: String,
@@ -64,16 +68,22 @@ pub struct RegisterAgentParams {
     pub model: String,
     /// Description of the agent's task/responsibilities
     pub task_description: String,
+    /// Fail if the name is already registered instead of updating that agent
+    #[serde(default)]
+    pub strict: bool,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SendMessageParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Sender agent name
     pub sender_name: String,
-    /// Recipient agent names (comma-separated for multiple)
+    /// Recipient agent names (comma-separated for multiple); omit when broadcasting.
+    /// Use `project-slug::agent-name` for an agent in another project and
+    /// `group:name` for the current members of an agent group.
+    #[serde(default)]
     pub to: String,
     /// CC recipient agent names (comma-separated for multiple)
     pub cc: Option<String>,
@@ -85,17 +95,32 @@ pub struct SendMessageParams {
     pub body_md: String,
     /// Message importance (low, normal, high, urgent)
     pub importance: Option<String>,
-    /// Thread ID to continue existing conversation
+    /// Thread ID to continue an existing conversation (case-sensitive,
+    /// at most 128 characters)
     pub thread_id: Option<String>,
+    /// Start a new thread when `thread_id` names none in this project
+    /// (default: false, an unknown `thread_id` is rejected)
+    #[serde(default)]
+    pub allow_new_thread: Option<bool>,
     /// Whether recipients must acknowledge this message (default: false)
     #[serde(default)]
     pub ack_required: Option<bool>,
+    /// Deliver later instead of now (ISO 8601 timestamp, UTC if no offset)
+    #[serde(default)]
+    pub deliver_at: Option<String>,
+    /// Send to every active agent in the project instead of `to`/`cc`/`bcc`
+    #[serde(default)]
+    pub broadcast: Option<bool>,
+    /// Deliver to the recipients that resolve even if others don't
+    /// (default: false, nothing is sent unless every recipient resolves)
+    #[serde(default)]
+    pub allow_partial: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ListInboxParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent name to list inbox for
     pub agent_name: String,
@@ -107,6 +132,26 @@ pub struct ListInboxParams {
     pub since_ts: Option<String>,
     /// Include full message bodies in response (default: false for token efficiency)
     pub include_bodies: Option<bool>,
+    /// Only messages carrying this label (case-insensitive)
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Order: "created_desc" (default), "created_asc", "importance_desc" or "ack_pending_first"
+    #[serde(default)]
+    pub sort: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct WaitForMessagesParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// Agent name whose inbox to wait on
+    pub agent_name: String,
+    /// Seconds to wait for new mail (default: 30, max: 120)
+    pub timeout_seconds: Option<u64>,
+    /// Only messages with an ID greater than this count as new
+    /// (default: messages arriving after the call starts)
+    pub since_message_id: Option<i64>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -118,14 +163,14 @@ pub struct GetMessageParams {
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ListProjectSiblingsParams {
     /// Project slug to find siblings for
-    #[serde(alias = "project_key")]
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct CommitArchiveParams {
     /// Project slug to archive
-    #[serde(alias = "project_key")]
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Commit message
     pub message: String,
@@ -133,8 +178,8 @@ pub struct CommitArchiveParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct WhoisParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent name to look up
     pub agent_name: String,
@@ -144,10 +189,12 @@ pub struct WhoisParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SearchMessagesParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
-    /// Search query (full-text search)
+    /// Search query: body terms plus optional `from:NAME`, `subject:"TEXT"`,
+    /// `after:YYYY-MM-DD`, `before:YYYY-MM-DD`, `importance:LEVEL` and
+    /// `thread:ID` fields
     pub query: String,
     /// Maximum results
     pub limit: Option<i64>,
@@ -155,8 +202,8 @@ pub struct SearchMessagesParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetThreadParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Thread ID
     pub thread_id: String,
@@ -164,8 +211,8 @@ pub struct GetThreadParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetReviewStateParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Thread ID (e.g., TASK-abc123)
     pub thread_id: String,
@@ -173,8 +220,8 @@ pub struct GetReviewStateParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ClaimReviewParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Message ID of the [COMPLETION] message to review
     pub message_id: i64,
@@ -184,15 +231,15 @@ pub struct ClaimReviewParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ListAgentsParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct FileReservationParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent name requesting reservations
     pub agent_name: String,
@@ -208,8 +255,8 @@ pub struct FileReservationParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ListReservationsParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Filter by agent name (optional)
     pub agent_name: Option<String>,
@@ -230,17 +277,37 @@ pub struct ForceReleaseReservationParams {
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
-pub struct RenewFileReservationParams {
-    /// Reservation ID to renew
+pub struct TransferReservationParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// Reservation ID to hand over
     pub reservation_id: i64,
-    /// New TTL in seconds (default 3600)
+    /// Agent taking over the reservation
+    pub to_agent: String,
+    /// Why the reservation changes hands, e.g. the holder crashed
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RenewFileReservationParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// Agent name holding the reservation
+    pub agent_name: String,
+    /// Reservation ID to renew (takes precedence over path_pattern)
+    pub reservation_id: Option<i64>,
+    /// Path pattern of the reservation to renew
+    pub path_pattern: Option<String>,
+    /// New TTL in seconds, counted from now (default 3600, capped by server config)
     pub ttl_seconds: Option<i64>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ReleaseFileReservationsByAgentParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent name whose reservations to release
     pub agent_name: String,
@@ -252,8 +319,8 @@ pub struct ReleaseFileReservationsByAgentParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct RenewFileReservationsByAgentParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent name whose reservations to renew
     pub agent_name: String,
@@ -263,10 +330,32 @@ pub struct RenewFileReservationsByAgentParams {
     pub paths: Option<Vec<String>>,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListMyReservationsParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// Agent name whose active reservations to list
+    pub agent_name: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReleaseReservationsParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// Agent name releasing its own reservations
+    pub agent_name: String,
+    /// Exact path patterns to release (as passed when reserving)
+    pub paths: Option<Vec<String>>,
+    /// Release every active reservation held by this agent (default: false)
+    pub all: Option<bool>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ReplyMessageParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Sender agent name
     pub sender_name: String,
@@ -286,10 +375,25 @@ pub struct ReplyMessageParams {
     pub subject_prefix: Option<String>,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ForwardMessageParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// Agent forwarding the message (must be its sender or a recipient)
+    pub sender_name: String,
+    /// Message ID to forward
+    pub message_id: i64,
+    /// Recipient agent names (comma-separated for multiple; `group:name` for a group)
+    pub to: String,
+    /// Note shown above the quoted message (optional)
+    pub note: Option<String>,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct MarkMessageReadParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent name marking as read
     pub agent_name: String,
@@ -299,8 +403,8 @@ pub struct MarkMessageReadParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct AcknowledgeMessageParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent name acknowledging
     pub agent_name: String,
@@ -308,10 +412,135 @@ pub struct AcknowledgeMessageParams {
     pub message_id: i64,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct LabelMessageParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// Agent name applying the labels
+    pub agent_name: String,
+    /// Message ID to label
+    pub message_id: i64,
+    /// Label names to apply (case-insensitive)
+    #[serde(default)]
+    pub add: Vec<String>,
+    /// Label names to remove (case-insensitive)
+    #[serde(default)]
+    pub remove: Vec<String>,
+    /// Create labels in `add` that the project does not have yet (default: true)
+    pub create_missing: Option<bool>,
+}
+
+/// What [`ManageAgentGroupParams`] does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupAction {
+    /// List the project's groups and their members
+    List,
+    /// Create a group, adding `agent_names` as its first members
+    Create,
+    /// Add `agent_names` to the group
+    AddMember,
+    /// Remove `agent_names` from the group
+    RemoveMember,
+    /// Delete the group
+    Delete,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ManageAgentGroupParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// list, create, add_member, remove_member or delete
+    pub action: GroupAction,
+    /// Group name (case-insensitive); required except for `list`
+    pub group_name: Option<String>,
+    /// Agent names (comma-separated) for create, add_member and remove_member
+    pub agent_names: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct RecallMessageParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// Agent name recalling the message (must be the original sender)
+    #[serde(alias = "sender_name")]
+    pub agent_name: String,
+    /// Message ID to recall
+    pub message_id: i64,
+    /// Reason shown to recipients in place of the original body
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct MuteThreadParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// Agent name muting or unmuting the thread
+    pub agent_name: String,
+    /// Thread ID
+    pub thread_id: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListScheduledParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// Sender whose pending scheduled messages to list
+    pub agent_name: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct CancelScheduledParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// Agent name cancelling the message (must be the original sender)
+    #[serde(alias = "sender_name")]
+    pub agent_name: String,
+    /// Scheduled message ID to cancel
+    pub message_id: i64,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct SnoozeMessageParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// Recipient snoozing the message
+    pub agent_name: String,
+    /// Message ID to snooze
+    pub message_id: i64,
+    /// When the message returns to the inbox (ISO 8601, UTC if no offset); omit to unsnooze
+    pub until: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListSnoozedParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// Agent whose snoozed messages to list
+    pub agent_name: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListPendingAcksParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// Agent whose outstanding acknowledgements to list
+    pub agent_name: String,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct CreateAgentIdentityParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Optional hint for name generation
     #[allow(dead_code)]
@@ -326,8 +555,8 @@ pub struct CreateAgentIdentityParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct UpdateAgentProfileParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent name to update
     pub agent_name: String,
@@ -339,17 +568,39 @@ pub struct UpdateAgentProfileParams {
     pub contact_policy: Option<String>,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct UpdateAgentParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// Current agent name
+    pub agent_name: String,
+    /// New agent name, unique within the project ignoring case (optional).
+    /// 2-64 ASCII letters, digits, '_' or '-'.
+    #[schemars(regex(pattern = AGENT_NAME_PATTERN))]
+    pub name: Option<String>,
+    /// New task description (optional)
+    pub task_description: Option<String>,
+    /// New AI model identifier (optional)
+    pub model: Option<String>,
+    /// New runtime program (optional)
+    pub program: Option<String>,
+    /// Retire the agent: hide it from listings while keeping its history
+    #[serde(default)]
+    pub retire: bool,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetProjectInfoParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetAgentProfileParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent name
     pub agent_name: String,
@@ -357,8 +608,8 @@ pub struct GetAgentProfileParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ListThreadsParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Maximum threads to return
     pub limit: Option<i64>,
@@ -390,8 +641,8 @@ pub struct RespondContactParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct RespondContactByNameParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent name responding to the contact request (the one who received the request)
     pub to_agent: String,
@@ -403,8 +654,8 @@ pub struct RespondContactByNameParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ListContactsParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent name
     pub agent_name: String,
@@ -412,19 +663,36 @@ pub struct ListContactsParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SetContactPolicyParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// Agent name
+    pub agent_name: String,
+    /// Contact policy: auto, manual, or deny (unchanged if omitted)
+    #[serde(default)]
+    pub contact_policy: Option<String>,
+    /// Do not disturb until this time (ISO 8601, UTC); "off" ends DND
+    #[serde(default)]
+    pub dnd_until: Option<String>,
+    /// Lowest importance delivered during DND: low, normal, high or urgent
+    /// (default urgent). Urgent and ack_required messages always get through.
+    #[serde(default)]
+    pub min_importance: Option<String>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetContactPolicyParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent name
     pub agent_name: String,
-    /// Contact policy: auto, manual, or deny
-    pub contact_policy: String,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct AcquireBuildSlotParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent name
     pub agent_name: String,
@@ -450,8 +718,8 @@ pub struct ReleaseBuildSlotParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SendOverseerMessageParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent name receiving the message
     pub agent_name: String,
@@ -465,15 +733,15 @@ pub struct SendOverseerMessageParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ListMacrosParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct RegisterMacroParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Macro name
     pub name: String,
@@ -485,8 +753,8 @@ pub struct RegisterMacroParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct UnregisterMacroParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Macro name to remove
     pub name: String,
@@ -494,8 +762,8 @@ pub struct UnregisterMacroParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct InvokeMacroParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Macro name to invoke
     pub name: String,
@@ -508,8 +776,8 @@ pub struct ListBuiltinWorkflowsParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct QuickStandupWorkflowParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Sender agent name
     pub sender_name: String,
@@ -519,8 +787,8 @@ pub struct QuickStandupWorkflowParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct QuickHandoffWorkflowParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent handing off the task
     pub from_agent: String,
@@ -534,8 +802,8 @@ pub struct QuickHandoffWorkflowParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct QuickReviewWorkflowParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent requesting review
     pub requester: String,
@@ -708,11 +976,13 @@ impl From<ThreadIdInput> for Vec<String> {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct SummarizeThreadParams {
-    #[serde(alias = "project_key")]
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     pub thread_id: ThreadIdInput,
     /// Include example messages in the summary (optional)
     pub include_examples: Option<bool>,
+    /// Number of newest message snippets per thread (default 3, max 50)
+    pub recent: Option<i64>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
@@ -728,7 +998,7 @@ pub struct LinkProjectToProductParams {
     /// Product UID
     pub product_uid: String,
     /// Project slug to link
-    #[serde(alias = "project_key")]
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
 }
 
@@ -737,7 +1007,7 @@ pub struct UnlinkProjectFromProductParams {
     /// Product UID
     pub product_uid: String,
     /// Project slug to unlink
-    #[serde(alias = "project_key")]
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
 }
 
@@ -770,7 +1040,7 @@ pub struct SummarizeThreadProductParams {
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ExportMailboxParams {
     /// Project slug to export
-    #[serde(alias = "project_key")]
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Export format: html, json, or markdown
     pub format: Option<String>,
@@ -781,19 +1051,22 @@ pub struct ExportMailboxParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ListOutboxParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent name to list outbox for
     pub agent_name: String,
     /// Maximum number of messages to return
     pub limit: Option<i64>,
+    /// Message ID to resume after (the last ID from the previous page)
+    #[serde(default)]
+    pub cursor: Option<i64>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct FileReservationPathsParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Agent name requesting reservations
     pub agent_name: String,
@@ -805,12 +1078,30 @@ pub struct FileReservationPathsParams {
     pub reason: Option<String>,
     /// TTL in seconds (default 3600)
     pub ttl_seconds: Option<i64>,
+    /// Queue paths held by other agents instead of granting them with a
+    /// conflict warning (default: false)
+    #[serde(default)]
+    pub wait: Option<bool>,
+    /// How long a queued path may wait before the request is dropped
+    /// (default: 600)
+    #[serde(default)]
+    pub wait_timeout_seconds: Option<i64>,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ListReservationQueueParams {
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
+    pub project_slug: String,
+    /// Only requests overlapping this path pattern
+    #[serde(default)]
+    pub path_pattern: Option<String>,
 }
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct InstallPrecommitGuardParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Target repository path
     pub target_repo_path: String,
@@ -824,8 +1115,8 @@ pub struct UninstallPrecommitGuardParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct AddAttachmentParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Message ID to attach to
     pub message_id: i64,
@@ -837,8 +1128,8 @@ pub struct AddAttachmentParams {
 
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct GetAttachmentParams {
-    /// Project slug
-    #[serde(alias = "project_key")]
+    /// Project slug (discovered from the working directory if omitted)
+    #[serde(alias = "project_key", default)]
     pub project_slug: String,
     /// Attachment ID
     pub attachment_id: String,
@@ -854,6 +1145,10 @@ pub struct ListToolMetricsParams {
     pub limit: Option<i64>,
 }
 
+/// Parameters for get_server_info tool (no parameters required)
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct GetServerInfoParams {}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct ListActivityParams {
     /// Project ID
@@ -912,3 +1207,9 @@ pub struct SummarizeResult {
     pub summaries: Vec<ThreadSummaryItem>,
     pub errors: Vec<ThreadSummaryError>,
 }
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ThreadStatsResult {
+    pub summaries: Vec<mouchak_mail_core::model::message::ThreadStats>,
+    pub errors: Vec<ThreadSummaryError>,
+}
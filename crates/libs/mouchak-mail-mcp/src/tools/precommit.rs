@@ -14,7 +14,7 @@ pub async fn install_precommit_guard_impl(
     params: InstallPrecommitGuardParams,
 ) -> Result<CallToolResult, McpError> {
     // Verify project exists
-    let _project = helpers::resolve_project(ctx, mm, &params.project_slug).await?;
+    let project = helpers::resolve_project(ctx, mm, &params.project_slug).await?;
 
     let target_path = std::path::PathBuf::from(&params.target_repo_path);
     let hooks_dir = target_path.join(".git").join("hooks");
@@ -33,7 +33,7 @@ fi
 echo "Mouchak Mail: Pre-commit guard active"
 exit 0
 "#,
-        params.project_slug
+        project.slug
     );
 
     // Ensure hooks directory exists
@@ -52,7 +52,7 @@ pub async fn link_project_to_product_impl(
 
     let msg = format!(
         "Linked project '{}' to product '{}' (link_id: {})",
-        params.project_slug, params.product_uid, link_id
+        project.slug, params.product_uid, link_id
     );
     Ok(CallToolResult::success(vec![Content::text(msg)]))
 }
@@ -76,12 +76,12 @@ pub async fn unlink_project_from_product_impl(
     let msg = if unlinked {
         format!(
             "Unlinked project '{}' from product '{}'",
-            params.project_slug, params.product_uid
+            project.slug, params.product_uid
         )
     } else {
         format!(
             "Project '{}' was not linked to product '{}'",
-            params.project_slug, params.product_uid
+            project.slug, params.product_uid
         )
     };
     Ok(CallToolResult::success(vec![Content::text(msg)]))
@@ -260,7 +260,7 @@ pub async fn summarize_thread_product_impl(
 
         if !aggregated_messages.is_empty() {
             // Sort by created_ts
-            aggregated_messages.sort_by(|a, b| a.created_ts.cmp(&b.created_ts));
+            aggregated_messages.sort_by_key(|m| m.created_ts);
 
             let mut participants: Vec<String> = aggregated_messages
                 .iter()
@@ -0,0 +1,476 @@
+//! MCP prompts for common coordination workflows
+//!
+//! Each prompt is rendered server-side from current mailbox state, so the
+//! client gets a ready-to-use user message instead of having to call several
+//! tools first.
+
+use mouchak_mail_core::{
+    ctx::Ctx,
+    model::{
+        ModelManager, agent::AgentBmc, file_reservation::FileReservationBmc, message::MessageBmc,
+    },
+    utils::pathspec::paths_conflict,
+};
+use rmcp::{
+    ErrorData as McpError,
+    model::{
+        GetPromptRequestParam, GetPromptResult, JsonObject, ListPromptsResult, Prompt,
+        PromptArgument, PromptMessage, PromptMessageRole,
+    },
+};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::ParameterSchema;
+use super::errors::{ErrorCode, mcp_err};
+use super::helpers::{resolve_project, resolve_project_and_agent};
+
+/// Default number of inbox messages scanned by `check_my_inbox`
+const DEFAULT_INBOX_LIMIT: i64 = 20;
+/// Default reservation TTL suggested by `announce_file_work`
+const DEFAULT_TTL_SECONDS: i64 = 3600;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PromptSchema {
+    pub name: String,
+    pub description: String,
+    pub arguments: Vec<ParameterSchema>,
+}
+
+fn arg(name: &str, required: bool, description: &str) -> ParameterSchema {
+    ParameterSchema {
+        name: name.to_string(),
+        param_type: "string".to_string(),
+        required,
+        description: description.to_string(),
+    }
+}
+
+fn project_arg() -> ParameterSchema {
+    arg(
+        "project_slug",
+        false,
+        "Project slug (discovered from the working directory if omitted)",
+    )
+}
+
+/// Schemas for all prompts, used for `prompts/list` and the docs
+pub fn get_prompt_schemas() -> Vec<PromptSchema> {
+    vec![
+        PromptSchema {
+            name: "check_my_inbox".to_string(),
+            description: "Review your unread messages and decide what needs a reply, an ack or action."
+                .to_string(),
+            arguments: vec![
+                project_arg(),
+                arg("agent_name", true, "Your agent name"),
+                arg(
+                    "limit",
+                    false,
+                    "Most recent inbox messages to scan (default: 20)",
+                ),
+            ],
+        },
+        PromptSchema {
+            name: "announce_file_work".to_string(),
+            description: "Reserve files you are about to edit and tell the other agents, flagging paths already held."
+                .to_string(),
+            arguments: vec![
+                project_arg(),
+                arg("agent_name", true, "Your agent name"),
+                arg(
+                    "paths",
+                    true,
+                    "Paths or glob patterns to work on (comma or newline separated)",
+                ),
+                arg("reason", false, "What the work is for"),
+                arg("ttl_seconds", false, "Reservation TTL in seconds (default: 3600)"),
+            ],
+        },
+        PromptSchema {
+            name: "thread_catchup".to_string(),
+            description: "Summarize a thread: decisions, open questions and action items.".to_string(),
+            arguments: vec![project_arg(), arg("thread_id", true, "Thread to catch up on")],
+        },
+    ]
+}
+
+pub fn list_prompts_impl() -> ListPromptsResult {
+    let prompts = get_prompt_schemas()
+        .into_iter()
+        .map(|schema| {
+            let arguments = schema
+                .arguments
+                .into_iter()
+                .map(|a| PromptArgument {
+                    name: a.name,
+                    title: None,
+                    description: Some(a.description),
+                    required: Some(a.required),
+                })
+                .collect();
+            Prompt::new(schema.name, Some(schema.description), Some(arguments))
+        })
+        .collect();
+
+    ListPromptsResult {
+        prompts,
+        next_cursor: None,
+        meta: None,
+    }
+}
+
+/// Render a prompt with the given arguments
+pub async fn get_prompt_impl(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    request: GetPromptRequestParam,
+) -> Result<GetPromptResult, McpError> {
+    let args = PromptArgs {
+        prompt: &request.name,
+        values: request.arguments.unwrap_or_default(),
+    };
+
+    let (description, text) = match request.name.as_str() {
+        "check_my_inbox" => check_my_inbox(ctx, mm, &args).await?,
+        "announce_file_work" => announce_file_work(ctx, mm, &args).await?,
+        "thread_catchup" => thread_catchup(ctx, mm, &args).await?,
+        other => {
+            return Err(mcp_err!(
+                ErrorCode::InvalidInput,
+                &format!("Unknown prompt '{}'", other),
+                {
+                    "prompt": other,
+                    "suggestion": "List available prompts with prompts/list"
+                }
+            ));
+        }
+    };
+
+    Ok(GetPromptResult {
+        description: Some(description),
+        messages: vec![PromptMessage::new_text(PromptMessageRole::User, text)],
+    })
+}
+
+/// Prompt arguments. Clients send strings, but numbers are accepted too.
+struct PromptArgs<'a> {
+    prompt: &'a str,
+    values: JsonObject,
+}
+
+impl PromptArgs<'_> {
+    fn optional(&self, name: &str) -> Option<String> {
+        let value = match self.values.get(name)? {
+            serde_json::Value::String(s) => s.trim().to_string(),
+            serde_json::Value::Null => return None,
+            other => other.to_string(),
+        };
+        (!value.is_empty()).then_some(value)
+    }
+
+    fn required(&self, name: &str) -> Result<String, McpError> {
+        self.optional(name).ok_or_else(|| {
+            mcp_err!(
+                ErrorCode::InvalidInput,
+                &format!(
+                    "Missing required argument '{}' for prompt '{}'",
+                    name, self.prompt
+                ),
+                { "prompt": self.prompt, "argument": name }
+            )
+        })
+    }
+
+    fn positive_int(&self, name: &str, default: i64) -> Result<i64, McpError> {
+        let Some(value) = self.optional(name) else {
+            return Ok(default);
+        };
+        value.parse::<i64>().ok().filter(|n| *n > 0).ok_or_else(|| {
+            mcp_err!(
+                ErrorCode::InvalidInput,
+                &format!(
+                    "Argument '{}' for prompt '{}' must be a positive integer, got '{}'",
+                    name, self.prompt, value
+                ),
+                { "prompt": self.prompt, "argument": name }
+            )
+        })
+    }
+}
+
+fn internal(e: impl std::fmt::Display) -> McpError {
+    McpError::internal_error(e.to_string(), None)
+}
+
+async fn check_my_inbox(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    args: &PromptArgs<'_>,
+) -> Result<(String, String), McpError> {
+    let agent_name = args.required("agent_name")?;
+    let limit = args.positive_int("limit", DEFAULT_INBOX_LIMIT)?;
+    let project_slug = args.optional("project_slug").unwrap_or_default();
+    let (project, agent) = resolve_project_and_agent(ctx, mm, &project_slug, &agent_name).await?;
+
+    let mut messages =
+        MessageBmc::list_inbox_for_agent(ctx, mm, project.id.get(), agent.id.get(), limit)
+            .await
+            .map_err(internal)?;
+    MessageBmc::load_bodies(mm, &mut messages)
+        .await
+        .map_err(internal)?;
+    let recipients = MessageBmc::list_recipients(mm, &messages)
+        .await
+        .map_err(internal)?;
+    let unread: Vec<_> = messages
+        .iter()
+        .filter(|m| {
+            recipients.get(&m.id).is_some_and(|rs| {
+                rs.iter()
+                    .any(|r| r.name == agent.name && r.read_ts.is_none())
+            })
+        })
+        .collect();
+
+    let mut text = format!("I am agent {} in project {}. ", agent.name, project.slug);
+    if unread.is_empty() {
+        text.push_str(
+            "My inbox has no unread messages. Briefly confirm there is nothing to act on.\n",
+        );
+        return Ok((format!("Inbox of {}: no unread messages", agent.name), text));
+    }
+
+    text.push_str(&format!(
+        "These are my {} unread messages, newest first:\n\n",
+        unread.len()
+    ));
+    for m in &unread {
+        text.push_str(&format!("### #{} {}\n\n", m.id, m.subject));
+        text.push_str(&format!(
+            "From {} · importance {} · sent {}",
+            m.sender_name,
+            m.importance,
+            m.created_ts.format("%Y-%m-%d %H:%M")
+        ));
+        if let Some(thread_id) = &m.thread_id {
+            text.push_str(&format!(" · thread {}", thread_id));
+        }
+        if m.ack_required {
+            text.push_str(" · **ack required**");
+        }
+        text.push_str(&format!("\n\n{}\n\n", m.body_md.trim_end()));
+    }
+    text.push_str(&format!(
+        "For each message, decide whether it needs a reply, an acknowledgement or follow-up work, \
+         most urgent first. Then:\n\
+         - reply with `reply_message` (`sender_name`: \"{agent}\", `message_id`)\n\
+         - acknowledge ack-required messages with `acknowledge_message` (`agent_name`: \"{agent}\", `message_id`)\n\
+         - mark the rest read with `mark_message_read` (`agent_name`: \"{agent}\", `message_id`)\n\
+         Use `project_slug`: \"{project}\" in every call.\n",
+        agent = agent.name,
+        project = project.slug
+    ));
+
+    Ok((
+        format!("Inbox of {}: {} unread messages", agent.name, unread.len()),
+        text,
+    ))
+}
+
+async fn announce_file_work(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    args: &PromptArgs<'_>,
+) -> Result<(String, String), McpError> {
+    let agent_name = args.required("agent_name")?;
+    let paths_arg = args.required("paths")?;
+    let paths: Vec<String> = paths_arg
+        .split([',', '\n'])
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(str::to_string)
+        .collect();
+    if paths.is_empty() {
+        return Err(mcp_err!(
+            ErrorCode::InvalidInput,
+            "Argument 'paths' for prompt 'announce_file_work' must name at least one path",
+            { "prompt": args.prompt, "argument": "paths" }
+        ));
+    }
+    let ttl_seconds = args.positive_int("ttl_seconds", DEFAULT_TTL_SECONDS)?;
+    let reason = args.optional("reason");
+    let project_slug = args.optional("project_slug").unwrap_or_default();
+    let (project, agent) = resolve_project_and_agent(ctx, mm, &project_slug, &agent_name).await?;
+
+    let agents = AgentBmc::list_all_for_project(ctx, mm, project.id)
+        .await
+        .map_err(internal)?;
+    let names: HashMap<_, _> = agents.iter().map(|a| (a.id, a.name.as_str())).collect();
+    let now = chrono::Utc::now().naive_utc();
+    let held: Vec<_> = FileReservationBmc::list_active_for_project(ctx, mm, project.id)
+        .await
+        .map_err(internal)?
+        .into_iter()
+        .filter(|r| r.agent_id != agent.id && r.exclusive && r.expires_ts > now)
+        .collect();
+
+    let mut conflicts = Vec::new();
+    for path in &paths {
+        for r in held
+            .iter()
+            .filter(|r| paths_conflict(path, &r.path_pattern))
+        {
+            let holder = names.get(&r.agent_id).copied().unwrap_or("unknown");
+            conflicts.push((path.as_str(), r, holder));
+        }
+    }
+
+    // Tell the holders of conflicting paths; otherwise everyone else active
+    let mut recipients: Vec<&str> = conflicts.iter().map(|(_, _, holder)| *holder).collect();
+    if recipients.is_empty() {
+        recipients = agents
+            .iter()
+            .filter(|a| a.id != agent.id && a.retired_ts.is_none())
+            .map(|a| a.name.as_str())
+            .collect();
+    }
+    recipients.sort_unstable();
+    recipients.dedup();
+
+    let path_list = paths.join(", ");
+    let mut text = format!(
+        "I am agent {} in project {} and I am about to work on: {}.",
+        agent.name, project.slug, path_list
+    );
+    if let Some(reason) = &reason {
+        text.push_str(&format!(" Reason: {}.", reason));
+    }
+    text.push_str("\n\n");
+
+    if !conflicts.is_empty() {
+        text.push_str("These paths are already held by other agents:\n\n");
+        for (path, r, holder) in &conflicts {
+            text.push_str(&format!(
+                "- `{}` overlaps `{}` held by {} until {} ({})\n",
+                path,
+                r.path_pattern,
+                holder,
+                r.expires_ts.format("%Y-%m-%d %H:%M"),
+                r.reason
+            ));
+        }
+        text.push_str("\nQueue for them instead of editing over the holders.\n\n");
+    }
+
+    let reserve = serde_json::json!({
+        "project_slug": project.slug,
+        "agent_name": agent.name,
+        "paths": paths,
+        "exclusive": true,
+        "reason": reason.clone().unwrap_or_else(|| "Announced file work".to_string()),
+        "ttl_seconds": ttl_seconds,
+        "wait": !conflicts.is_empty(),
+    });
+    text.push_str(&format!(
+        "1. Reserve the paths by calling `file_reservation_paths` with:\n\n```json\n{}\n```\n\n",
+        serde_json::to_string_pretty(&reserve).map_err(internal)?
+    ));
+
+    if recipients.is_empty() {
+        text.push_str(
+            "2. No other agents are registered in the project, so no announcement is needed.\n",
+        );
+    } else {
+        let mut body = format!("I'm starting work on {}.", path_list);
+        if let Some(reason) = &reason {
+            body.push_str(&format!("\n\nReason: {}", reason));
+        }
+        body.push_str(&format!(
+            "\n\nReserved for {} minutes; please avoid these paths until I release them.",
+            ttl_seconds / 60
+        ));
+        let notify = serde_json::json!({
+            "project_slug": project.slug,
+            "sender_name": agent.name,
+            "to": recipients.join(","),
+            "subject": format!("Starting work on {}", path_list),
+            "body_md": body,
+        });
+        text.push_str(&format!(
+            "2. Once the reservation succeeds, announce it by calling `send_message` with:\n\n```json\n{}\n```\n",
+            serde_json::to_string_pretty(&notify).map_err(internal)?
+        ));
+    }
+
+    Ok((
+        format!("Reserve and announce work on {} path(s)", paths.len()),
+        text,
+    ))
+}
+
+async fn thread_catchup(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    args: &PromptArgs<'_>,
+) -> Result<(String, String), McpError> {
+    let thread_id = args.required("thread_id")?;
+    let project_slug = args.optional("project_slug").unwrap_or_default();
+    let project = resolve_project(ctx, mm, &project_slug).await?;
+
+    let messages = MessageBmc::list_by_thread(ctx, mm, project.id.get(), &thread_id)
+        .await
+        .map_err(internal)?;
+    if messages.is_empty() {
+        return Err(mcp_err!(
+            ErrorCode::ThreadNotFound,
+            &format!("Thread '{}' not found", thread_id),
+            { "thread_id": thread_id, "project_slug": project.slug }
+        ));
+    }
+    let stats = MessageBmc::thread_summary(ctx, mm, project.id.get(), &thread_id, 0)
+        .await
+        .map_err(internal)?;
+
+    let participants = stats
+        .participants
+        .iter()
+        .map(|p| format!("{} ({})", p.name, p.message_count))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut text = format!(
+        "Catch me up on thread {} (\"{}\") in project {}: {} messages from {} between {} and {}.",
+        thread_id,
+        stats.subject,
+        project.slug,
+        stats.message_count,
+        participants,
+        stats.first_ts.format("%Y-%m-%d %H:%M"),
+        stats.last_ts.format("%Y-%m-%d %H:%M")
+    );
+    if stats.ack_required_count > 0 {
+        text.push_str(&format!(
+            " {} of {} ack-required messages are fully acknowledged.",
+            stats.acked_count, stats.ack_required_count
+        ));
+    }
+    text.push_str("\n\nTranscript, oldest first:\n\n");
+    for m in &messages {
+        text.push_str(&format!(
+            "**{}** ({}, #{}): {}\n\n{}\n\n",
+            m.sender_name,
+            m.created_ts.format("%Y-%m-%d %H:%M"),
+            m.id,
+            m.subject,
+            m.body_md.trim_end()
+        ));
+    }
+    text.push_str(
+        "Summarize the thread in a few bullets each under: Decisions, Open questions, \
+         and Action items (with owners). Call out anything still waiting on a reply \
+         or an acknowledgement.\n",
+    );
+
+    Ok((format!("Catch up on thread {}", thread_id), text))
+}
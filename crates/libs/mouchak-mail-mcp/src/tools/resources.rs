@@ -1,21 +1,40 @@
 //! MCP Resource implementations for Mouchak Mail
+//!
+//! Resources are addressed as `mouchak-mail://{project}/{type}[/{id}]` or
+//! `resource://{type}[/{id}]?project={project}`. Mailboxes, threads and
+//! listings are JSON; `message/{id}` and inboxes read with
+//! `?format=markdown` are rendered Markdown for loading into context.
+//!
+//! Clients can subscribe to any of these URIs and get
+//! `notifications/resources/updated` when the mailbox event bus reports a
+//! change. This needs a transport that keeps a channel to the client open:
+//! stdio, or the HTTP transport in stateful (SSE) mode.
 
 use mouchak_mail_core::{
     ctx::Ctx,
+    events::{MailEvent, MailEventKind},
     model::{
-        ModelManager, agent::AgentBmc, file_reservation::FileReservationBmc, message::MessageBmc,
-        product::ProductBmc, project::ProjectBmc,
+        ModelManager,
+        agent::AgentBmc,
+        build_slot::BuildSlotBmc,
+        file_reservation::FileReservationBmc,
+        message::{Message, MessageBmc},
+        product::ProductBmc,
+        project::ProjectBmc,
     },
     types::ProjectId,
 };
 use rmcp::{
-    ErrorData as McpError,
+    ErrorData as McpError, Peer, RoleServer,
     model::{
-        ListResourcesResult, PaginatedRequestParam, RawResource, ReadResourceRequestParam,
-        ReadResourceResult, Resource, ResourceContents,
+        ListResourceTemplatesResult, ListResourcesResult, PaginatedRequestParam, RawResource,
+        RawResourceTemplate, ReadResourceRequestParam, ReadResourceResult, Resource,
+        ResourceContents, ResourceTemplate, ResourceUpdatedNotificationParam,
     },
 };
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast::error::RecvError;
 
 #[derive(serde::Serialize)]
 struct ResourceMessage<'a> {
@@ -25,6 +44,8 @@ struct ResourceMessage<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     body_md: Option<&'a str>,
     thread_id: Option<&'a String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thread_seq: Option<i64>,
     importance: &'a str,
     created_ts: chrono::NaiveDateTime,
 }
@@ -163,27 +184,31 @@ async fn handle_agents_resource(
     }
 }
 
-/// Handle inbox/outbox resource type
-async fn handle_mailbox_resource(
+/// Messages in an agent's inbox or outbox, newest first
+async fn mailbox_messages(
     ctx: &Ctx,
     mm: &Arc<ModelManager>,
     project_id: ProjectId,
     agent_name: &str,
     limit: i64,
-    include_bodies: bool,
     is_inbox: bool,
-) -> Result<String, McpError> {
+) -> Result<Vec<Message>, McpError> {
     let agent = AgentBmc::get_by_name(ctx, mm, project_id, agent_name)
         .await
         .map_err(|e| McpError::invalid_params(format!("Agent not found: {}", e), None))?;
 
-    let messages = if is_inbox {
+    if is_inbox {
         MessageBmc::list_inbox_for_agent(ctx, mm, project_id.get(), agent.id.get(), limit).await
     } else {
-        MessageBmc::list_outbox_for_agent(ctx, mm, project_id.get(), agent.id.get(), limit).await
+        MessageBmc::list_outbox_for_agent(ctx, mm, project_id.get(), agent.id.get(), limit, None)
+            .await
+            .map(|sent| sent.into_iter().map(|o| o.message).collect())
     }
-    .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+    .map_err(|e| McpError::internal_error(e.to_string(), None))
+}
 
+/// Render messages as the JSON list used by mailbox and thread resources
+fn messages_json(messages: &[Message], include_bodies: bool) -> Result<String, McpError> {
     let resource_messages: Vec<_> = messages
         .iter()
         .map(|m| ResourceMessage {
@@ -196,6 +221,7 @@ async fn handle_mailbox_resource(
                 None
             },
             thread_id: m.thread_id.as_ref(),
+            thread_seq: m.thread_seq,
             importance: &m.importance,
             created_ts: m.created_ts,
         })
@@ -205,6 +231,103 @@ async fn handle_mailbox_resource(
         .map_err(|e| McpError::internal_error(e.to_string(), None))
 }
 
+/// Render an inbox as Markdown: one line per message, bodies optional
+fn render_inbox_markdown(
+    project_slug: &str,
+    agent_name: &str,
+    messages: &[Message],
+    include_bodies: bool,
+) -> String {
+    let mut out = format!("# Inbox: {} ({})\n\n", agent_name, project_slug);
+    if messages.is_empty() {
+        out.push_str("No messages.\n");
+        return out;
+    }
+    out.push_str(&format!("{} most recent messages.\n\n", messages.len()));
+    for m in messages {
+        let importance = if m.importance == "normal" {
+            String::new()
+        } else {
+            format!(" [{}]", m.importance)
+        };
+        out.push_str(&format!(
+            "- **#{}**{} {} — from {}, {}",
+            m.id,
+            importance,
+            m.subject,
+            m.sender_name,
+            m.created_ts.format("%Y-%m-%d %H:%M")
+        ));
+        if m.ack_required {
+            out.push_str(" (ack required)");
+        }
+        out.push('\n');
+        if include_bodies {
+            for line in m.body_md.lines() {
+                out.push_str(&format!("  > {}\n", line));
+            }
+        }
+    }
+    out
+}
+
+/// Render a message as Markdown: a metadata list followed by the body
+fn render_message_markdown(project_slug: &str, message: &Message, recipients: &[String]) -> String {
+    let mut out = format!("# {}\n\n", message.subject);
+    out.push_str(&format!("- **Message:** #{}\n", message.id));
+    out.push_str(&format!("- **Project:** {}\n", project_slug));
+    out.push_str(&format!("- **From:** {}\n", message.sender_name));
+    if !recipients.is_empty() {
+        out.push_str(&format!("- **To:** {}\n", recipients.join(", ")));
+    }
+    if let Some(thread_id) = &message.thread_id {
+        out.push_str(&format!("- **Thread:** {}\n", thread_id));
+    }
+    out.push_str(&format!("- **Importance:** {}\n", message.importance));
+    if message.ack_required {
+        out.push_str("- **Ack required:** yes\n");
+    }
+    out.push_str(&format!(
+        "- **Sent:** {}\n\n---\n\n",
+        message.created_ts.format("%Y-%m-%d %H:%M:%S")
+    ));
+    out.push_str(&message.body_md);
+    if !message.body_md.ends_with('\n') {
+        out.push('\n');
+    }
+    out
+}
+
+/// Handle message resource type
+async fn handle_message_resource(
+    ctx: &Ctx,
+    mm: &Arc<ModelManager>,
+    project_id: ProjectId,
+    project_slug: &str,
+    message_id: &str,
+) -> Result<String, McpError> {
+    let message_id: i64 = message_id.parse().map_err(|_| {
+        McpError::invalid_params(format!("Invalid message ID: {}", message_id), None)
+    })?;
+    let message = MessageBmc::get(ctx, mm, message_id)
+        .await
+        .ok()
+        .filter(|m| m.project_id == project_id.get())
+        .ok_or_else(|| {
+            McpError::invalid_params(
+                format!(
+                    "Message {} not found in project '{}'",
+                    message_id, project_slug
+                ),
+                None,
+            )
+        })?;
+    let recipients = MessageBmc::get_recipients(ctx, mm, message_id)
+        .await
+        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+    Ok(render_message_markdown(project_slug, &message, &recipients))
+}
+
 /// Handle thread resource type
 async fn handle_thread_resource(
     ctx: &Ctx,
@@ -216,32 +339,16 @@ async fn handle_thread_resource(
     let messages = MessageBmc::list_by_thread(ctx, mm, project_id.get(), thread_id)
         .await
         .map_err(|e| McpError::internal_error(e.to_string(), None))?;
-
-    let resource_messages: Vec<_> = messages
-        .iter()
-        .map(|m| ResourceMessage {
-            id: m.id,
-            sender_name: &m.sender_name,
-            subject: &m.subject,
-            body_md: if include_bodies {
-                Some(&m.body_md)
-            } else {
-                None
-            },
-            thread_id: m.thread_id.as_ref(),
-            importance: &m.importance,
-            created_ts: m.created_ts,
-        })
-        .collect();
-
-    serde_json::to_string_pretty(&resource_messages)
-        .map_err(|e| McpError::internal_error(e.to_string(), None))
+    messages_json(&messages, include_bodies)
 }
 
+/// Read a resource. Build slots are only readable with worktrees enabled,
+/// matching the build slot tools.
 pub async fn read_resource_impl(
     ctx: &Ctx,
     mm: &Arc<ModelManager>,
     request: ReadResourceRequestParam,
+    worktrees_enabled: bool,
 ) -> Result<ReadResourceResult, McpError> {
     let uri_str = request.uri;
     let uri = url::Url::parse(&uri_str)
@@ -257,6 +364,9 @@ pub async fn read_resource_impl(
     let query: std::collections::HashMap<_, _> = uri.query_pairs().into_owned().collect();
     let (project_slug, resource_type, resource_id, limit, include_bodies) =
         parse_resource_uri(&uri, &query)?;
+    let markdown = query
+        .get("format")
+        .is_some_and(|f| f == "markdown" || f == "md");
 
     // Handle identity resource (no project context needed)
     if resource_type == "identity" {
@@ -278,6 +388,7 @@ pub async fn read_resource_impl(
         .map_err(|e| McpError::invalid_params(format!("Project not found: {}", e), None))?;
     let project_id = project.id;
 
+    let mut mime_type = "application/json";
     let content = match resource_type.as_str() {
         "agents" | "agent" => {
             handle_agents_resource(ctx, mm, project_id, resource_id.as_deref()).await?
@@ -294,24 +405,48 @@ pub async fn read_resource_impl(
                 "Missing agent name".to_string(),
                 None,
             ))?;
-            handle_mailbox_resource(ctx, mm, project_id, agent_name, limit, include_bodies, true)
-                .await?
+            let mut messages =
+                mailbox_messages(ctx, mm, project_id, agent_name, limit, true).await?;
+            if include_bodies {
+                MessageBmc::load_bodies(mm, &mut messages)
+                    .await
+                    .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+            }
+            if markdown {
+                mime_type = "text/markdown";
+                render_inbox_markdown(&project_slug, agent_name, &messages, include_bodies)
+            } else {
+                messages_json(&messages, include_bodies)?
+            }
         }
         "outbox" => {
             let agent_name = resource_id.as_deref().ok_or(McpError::invalid_params(
                 "Missing agent name".to_string(),
                 None,
             ))?;
-            handle_mailbox_resource(
-                ctx,
-                mm,
-                project_id,
-                agent_name,
-                limit,
-                include_bodies,
-                false,
-            )
-            .await?
+            let messages = mailbox_messages(ctx, mm, project_id, agent_name, limit, false).await?;
+            messages_json(&messages, include_bodies)?
+        }
+        "message" => {
+            let message_id = resource_id.as_deref().ok_or(McpError::invalid_params(
+                "Missing message ID".to_string(),
+                None,
+            ))?;
+            mime_type = "text/markdown";
+            handle_message_resource(ctx, mm, project_id, &project_slug, message_id).await?
+        }
+        "build_slots" if worktrees_enabled => {
+            let slots = BuildSlotBmc::list_active(ctx, mm, project_id.get())
+                .await
+                .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+            serde_json::to_string_pretty(&slots)
+                .map_err(|e| McpError::internal_error(e.to_string(), None))?
+        }
+        "build_slots" => {
+            return Err(McpError::invalid_params(
+                "Build slot resources require WORKTREES_ENABLED=true".to_string(),
+                None,
+            ));
         }
         "thread" => {
             let thread_id_str = resource_id.as_deref().ok_or(McpError::invalid_params(
@@ -321,7 +456,7 @@ pub async fn read_resource_impl(
             handle_thread_resource(ctx, mm, project_id, thread_id_str, include_bodies).await?
         }
         "threads" => {
-            let threads = MessageBmc::list_threads(ctx, mm, project_id.get(), limit)
+            let threads = MessageBmc::list_threads(ctx, mm, project_id.get(), limit, None)
                 .await
                 .map_err(|e| McpError::internal_error(e.to_string(), None))?;
             serde_json::to_string_pretty(&threads)
@@ -349,17 +484,20 @@ pub async fn read_resource_impl(
     Ok(ReadResourceResult {
         contents: vec![ResourceContents::TextResourceContents {
             uri: uri_str,
-            mime_type: Some("application/json".to_string()),
+            mime_type: Some(mime_type.to_string()),
             text: content,
             meta: None,
         }],
     })
 }
 
+/// List concrete resources for every visible project. Build slots are only
+/// listed with worktrees enabled.
 pub async fn list_resources_impl(
     ctx: &Ctx,
     mm: &Arc<ModelManager>,
     _request: Option<PaginatedRequestParam>,
+    worktrees_enabled: bool,
 ) -> Result<ListResourcesResult, McpError> {
     let projects = ProjectBmc::list_all(ctx, mm)
         .await
@@ -475,6 +613,22 @@ pub async fn list_resources_impl(
             annotations: None,
         });
 
+        if worktrees_enabled {
+            resources.push(Resource {
+                raw: RawResource {
+                    uri: format!("mouchak-mail://{}/build_slots", slug),
+                    name: format!("Build Slots ({})", slug),
+                    description: Some(format!("Active build slots in project '{}'", slug)),
+                    mime_type: Some("application/json".to_string()),
+                    size: None,
+                    icons: None,
+                    meta: None,
+                    title: None,
+                },
+                annotations: None,
+            });
+        }
+
         resources.push(Resource {
             raw: RawResource {
                 uri: format!("resource://agents?project={}", slug),
@@ -533,3 +687,339 @@ pub async fn list_resources_impl(
         meta: None,
     })
 }
+
+/// URI templates for the resource types, in RFC 6570 form
+pub fn list_resource_templates_impl(worktrees_enabled: bool) -> ListResourceTemplatesResult {
+    let mut templates = vec![
+        (
+            "resource://inbox/{agent}{?project,format,limit,include_bodies}",
+            "Agent inbox",
+            "Recent inbox messages; format=markdown renders a readable summary",
+            "application/json",
+        ),
+        (
+            "resource://outbox/{agent}{?project,limit,include_bodies}",
+            "Agent outbox",
+            "Recently sent messages",
+            "application/json",
+        ),
+        (
+            "resource://message/{id}{?project}",
+            "Message",
+            "Message body as Markdown with sender, recipients and thread metadata",
+            "text/markdown",
+        ),
+        (
+            "resource://thread/{thread_id}{?project,include_bodies}",
+            "Thread",
+            "Messages in a conversation thread",
+            "application/json",
+        ),
+        (
+            "resource://threads{?project,limit}",
+            "Threads",
+            "Conversation threads in a project",
+            "application/json",
+        ),
+        (
+            "resource://agents{?project}",
+            "Agents",
+            "Agents registered in a project",
+            "application/json",
+        ),
+        (
+            "resource://file_reservations{?project}",
+            "File reservations",
+            "Active file reservations in a project",
+            "application/json",
+        ),
+        (
+            "resource://product/{product_uid}",
+            "Product",
+            "Product and its linked projects",
+            "application/json",
+        ),
+    ];
+    if worktrees_enabled {
+        templates.push((
+            "resource://build_slots{?project}",
+            "Build slots",
+            "Active build slots in a project",
+            "application/json",
+        ));
+    }
+
+    ListResourceTemplatesResult {
+        resource_templates: templates
+            .into_iter()
+            .map(
+                |(uri_template, name, description, mime_type)| ResourceTemplate {
+                    raw: RawResourceTemplate {
+                        uri_template: uri_template.to_string(),
+                        name: name.to_string(),
+                        title: None,
+                        description: Some(description.to_string()),
+                        mime_type: Some(mime_type.to_string()),
+                    },
+                    annotations: None,
+                },
+            )
+            .collect(),
+        next_cursor: None,
+        meta: None,
+    }
+}
+
+/// What a resource URI points at, ignoring options like `limit`.
+///
+/// A `None` id in a changed key stands for every resource of that kind in
+/// the project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ResourceKey {
+    project_slug: String,
+    kind: String,
+    id: Option<String>,
+}
+
+impl ResourceKey {
+    fn new(project_slug: &str, kind: &str, id: Option<String>) -> Self {
+        Self {
+            project_slug: project_slug.to_string(),
+            kind: kind.to_string(),
+            id,
+        }
+    }
+
+    fn parse(uri: &str) -> Option<Self> {
+        let url = url::Url::parse(uri).ok()?;
+        if url.scheme() != "mouchak-mail" && url.scheme() != "resource" {
+            return None;
+        }
+        let query: HashMap<_, _> = url.query_pairs().into_owned().collect();
+        let (project_slug, kind, id, _, _) = parse_resource_uri(&url, &query).ok()?;
+        Some(Self {
+            project_slug,
+            kind,
+            id,
+        })
+    }
+
+    /// Whether a change to `changed` affects this (subscribed) resource
+    fn affected_by(&self, changed: &ResourceKey) -> bool {
+        self.project_slug == changed.project_slug
+            && self.kind == changed.kind
+            && (changed.id.is_none() || self.id == changed.id)
+    }
+}
+
+/// Resources whose contents an event changes
+fn changed_resources(event: &MailEvent) -> Vec<ResourceKey> {
+    let slug = event.project_slug.as_str();
+    let str_field = |name: &str| event.data[name].as_str().map(str::to_string);
+    let id_field = |name: &str| event.data[name].as_i64().map(|id| id.to_string());
+
+    match event.kind {
+        MailEventKind::MessageCreated => {
+            let mut keys = vec![
+                ResourceKey::new(slug, "outbox", str_field("sender_name")),
+                ResourceKey::new(slug, "threads", None),
+            ];
+            if let Some(thread_id) = str_field("thread_id") {
+                keys.push(ResourceKey::new(slug, "thread", Some(thread_id)));
+            }
+            if let Some(recipients) = event.data["recipients"].as_array() {
+                keys.extend(
+                    recipients
+                        .iter()
+                        .filter_map(|r| r.as_str())
+                        .map(|name| ResourceKey::new(slug, "inbox", Some(name.to_string()))),
+                );
+            }
+            keys
+        }
+        MailEventKind::MessageRead | MailEventKind::MessageAcked => vec![
+            ResourceKey::new(slug, "inbox", str_field("agent_name")),
+            ResourceKey::new(slug, "message", id_field("message_id")),
+        ],
+        // Recipients aren't in the event, so every mailbox may show the recall
+        MailEventKind::MessageRecalled => vec![
+            ResourceKey::new(slug, "message", id_field("message_id")),
+            ResourceKey::new(slug, "inbox", None),
+            ResourceKey::new(slug, "outbox", None),
+            ResourceKey::new(slug, "thread", None),
+        ],
+        MailEventKind::ReservationCreated
+        | MailEventKind::ReservationReleased
+        | MailEventKind::ReservationQueued
+        | MailEventKind::ReservationGranted
+        | MailEventKind::ReservationTransferred => {
+            vec![ResourceKey::new(slug, "file_reservations", None)]
+        }
+        // Sent alongside `reservation.created`, which already covers it
+        MailEventKind::ReservationConflict => Vec::new(),
+    }
+}
+
+#[derive(Default)]
+struct SubscriptionState {
+    /// Subscribed URIs as sent by the client, with what they point at
+    uris: Vec<(String, ResourceKey)>,
+    /// Whether the task forwarding bus events to the client is running
+    forwarding: bool,
+}
+
+/// Resource subscriptions of one MCP session.
+///
+/// The first subscription starts a task that watches the mailbox event bus
+/// and sends `notifications/resources/updated` for subscribed URIs. It ends
+/// when the client goes away or the bus closes.
+#[derive(Clone, Default)]
+pub struct ResourceSubscriptions {
+    state: Arc<Mutex<SubscriptionState>>,
+}
+
+impl ResourceSubscriptions {
+    fn lock(&self) -> std::sync::MutexGuard<'_, SubscriptionState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Subscribe `peer` to updates of `uri`
+    pub fn subscribe(
+        &self,
+        mm: &Arc<ModelManager>,
+        uri: &str,
+        peer: Peer<RoleServer>,
+    ) -> Result<(), McpError> {
+        let key = ResourceKey::parse(uri).ok_or_else(|| {
+            McpError::invalid_params(format!("Invalid resource URI: {}", uri), None)
+        })?;
+
+        let mut state = self.lock();
+        if !state.uris.iter().any(|(u, _)| u == uri) {
+            state.uris.push((uri.to_string(), key));
+        }
+        if !state.forwarding {
+            state.forwarding = true;
+            self.spawn_forwarder(mm, peer);
+        }
+        Ok(())
+    }
+
+    /// Stop sending updates for `uri`
+    pub fn unsubscribe(&self, uri: &str) {
+        self.lock().uris.retain(|(u, _)| u != uri);
+    }
+
+    /// Subscribed URIs affected by `event`
+    fn updated_uris(&self, event: &MailEvent) -> Vec<String> {
+        let changed = changed_resources(event);
+        self.lock()
+            .uris
+            .iter()
+            .filter(|(_, key)| changed.iter().any(|c| key.affected_by(c)))
+            .map(|(uri, _)| uri.clone())
+            .collect()
+    }
+
+    fn all_uris(&self) -> Vec<String> {
+        self.lock()
+            .uris
+            .iter()
+            .map(|(uri, _)| uri.clone())
+            .collect()
+    }
+
+    fn spawn_forwarder(&self, mm: &Arc<ModelManager>, peer: Peer<RoleServer>) {
+        let events = mm.events.clone();
+        let mut rx = events.subscribe();
+        let subscriptions = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let uris = tokio::select! {
+                    _ = events.closed() => break,
+                    received = rx.recv() => match received {
+                        Ok(event) => subscriptions.updated_uris(&event),
+                        // Missed events may have touched anything
+                        Err(RecvError::Lagged(_)) => subscriptions.all_uris(),
+                        Err(RecvError::Closed) => break,
+                    },
+                };
+                for uri in uris {
+                    if peer
+                        .notify_resource_updated(ResourceUpdatedNotificationParam { uri })
+                        .await
+                        .is_err()
+                    {
+                        subscriptions.lock().forwarding = false;
+                        return;
+                    }
+                }
+                if peer.is_transport_closed() {
+                    break;
+                }
+            }
+            subscriptions.lock().forwarding = false;
+        });
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn event(kind: MailEventKind, data: serde_json::Value) -> MailEvent {
+        MailEvent {
+            id: 1,
+            kind,
+            project_slug: "proj".to_string(),
+            data,
+            ts: String::new(),
+        }
+    }
+
+    fn affected(uri: &str, event: &MailEvent) -> bool {
+        let key = ResourceKey::parse(uri).unwrap();
+        changed_resources(event).iter().any(|c| key.affected_by(c))
+    }
+
+    #[test]
+    fn test_resource_key_ignores_options_and_scheme() {
+        let a = ResourceKey::parse("mouchak-mail://proj/inbox/Blue?limit=5&format=markdown");
+        let b = ResourceKey::parse("resource://inbox/Blue?project=proj");
+        assert_eq!(a, b);
+        assert!(a.is_some());
+        assert!(ResourceKey::parse("https://example.com/inbox").is_none());
+    }
+
+    #[test]
+    fn test_new_message_updates_recipient_inboxes() {
+        let created = event(
+            MailEventKind::MessageCreated,
+            serde_json::json!({
+                "id": 7,
+                "sender_name": "Red",
+                "recipients": ["Blue"],
+                "thread_id": "T-1",
+            }),
+        );
+        assert!(affected("mouchak-mail://proj/inbox/Blue", &created));
+        assert!(affected("resource://inbox/Blue?project=proj", &created));
+        assert!(affected("mouchak-mail://proj/outbox/Red", &created));
+        assert!(affected("mouchak-mail://proj/thread/T-1", &created));
+        assert!(!affected("mouchak-mail://proj/inbox/Red", &created));
+        assert!(!affected("mouchak-mail://other/inbox/Blue", &created));
+    }
+
+    #[test]
+    fn test_recall_updates_every_mailbox() {
+        let recalled = event(
+            MailEventKind::MessageRecalled,
+            serde_json::json!({"message_id": 7, "sender_name": "Red"}),
+        );
+        assert!(affected("mouchak-mail://proj/message/7", &recalled));
+        assert!(affected("mouchak-mail://proj/inbox/Anyone", &recalled));
+        assert!(!affected("mouchak-mail://proj/message/8", &recalled));
+    }
+}
@@ -131,11 +131,14 @@ pub async fn claim_review_impl(
         thread_id: Some(thread_id.clone()),
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
 
     MessageBmc::create(ctx, mm, msg)
         .await
-        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        .map_err(helpers::message_create_error)?;
 
     let result = ClaimResult {
         success: true,
@@ -20,7 +20,7 @@ use super::{ParameterSchema, ToolSchema};
 /// Generate a `ToolSchema` from a type implementing `JsonSchema`.
 ///
 /// Extracts parameter names, types, required status, and descriptions from the struct.
-/// `Option<T>` fields become optional parameters; all others are required.
+/// `Option<T>` and `#[serde(default)]` fields become optional parameters; all others are required.
 pub fn schema_from_params<T: JsonSchema>(name: &str, description: &str) -> ToolSchema {
     let schema = schema_for!(T);
     let json_value = serde_json::to_value(schema).unwrap_or(Value::Null);
@@ -61,6 +61,8 @@ pub fn schema_from_params<T: JsonSchema>(name: &str, description: &str) -> ToolS
         name: name.into(),
         description: description.into(),
         parameters,
+        enabled: true,
+        disabled_reason: None,
     }
 }
 
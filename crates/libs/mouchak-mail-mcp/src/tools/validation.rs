@@ -0,0 +1,467 @@
+//! Tool argument validation against the advertised input schemas.
+//!
+//! [`MouchakMailService`](super::MouchakMailService) checks every call's
+//! arguments against the JSON Schema the tool lists in `tools/list` (the same
+//! params structs [`get_tool_schemas`](super::get_tool_schemas) reads) before
+//! dispatching it. A malformed call gets one error listing every bad field,
+//! with the expected type and an example value, rather than the first serde
+//! error. Fields the schema doesn't know are not an error: they are returned
+//! as warnings so newer clients keep working against older servers.
+//!
+//! Only the parts of JSON Schema that schemars emits for the params structs
+//! are enforced: `type`, `nullable`, `enum`, `items`, `anyOf`/`oneOf`,
+//! `$ref` and `required`. Ranges and formats are left to deserialization.
+
+use rmcp::model::JsonObject;
+use serde::Serialize;
+use serde_json::{Value, json};
+
+/// `#[serde(alias)]` names accepted by the params structs, as
+/// `(alias, field)`. Schemas list only the field name.
+const FIELD_ALIASES: &[(&str, &str)] = &[
+    ("project_key", "project_slug"),
+    ("agent_name", "name"),
+    ("sender_name", "agent_name"),
+    ("from_project_key", "from_project_slug"),
+    ("to_project_key", "to_project_slug"),
+];
+
+/// One argument that doesn't match the schema.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Violation {
+    /// Offending field as the caller named it, with `[i]` for array items
+    pub field: String,
+    /// Expected JSON type, e.g. `integer` or `string or array`
+    pub expected: String,
+    /// JSON type received, or `missing`
+    pub received: String,
+    /// A value of the expected type
+    pub example: Value,
+}
+
+impl Violation {
+    /// One-line description for error messages.
+    pub fn describe(&self) -> String {
+        if self.received == "missing" {
+            format!(
+                "'{}' is required ({}, e.g. {})",
+                self.field, self.expected, self.example
+            )
+        } else {
+            format!(
+                "'{}' must be {}, got {} (e.g. {})",
+                self.field, self.expected, self.received, self.example
+            )
+        }
+    }
+}
+
+/// Outcome of validating one call's arguments.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ArgumentReport {
+    pub violations: Vec<Violation>,
+    /// Fields the schema doesn't define; ignored by the tool
+    pub unknown_fields: Vec<String>,
+}
+
+impl ArgumentReport {
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Warnings for the response metadata, one per unknown field.
+    pub fn warnings(&self) -> Vec<String> {
+        self.unknown_fields
+            .iter()
+            .map(|field| format!("Unknown parameter '{}' was ignored", field))
+            .collect()
+    }
+}
+
+/// Validate `args` against a tool's input `schema`, collecting every
+/// violation and unknown field.
+pub fn validate_arguments(schema: &JsonObject, args: Option<&JsonObject>) -> ArgumentReport {
+    let empty = JsonObject::new();
+    let args = args.unwrap_or(&empty);
+    let properties = schema
+        .get("properties")
+        .and_then(Value::as_object)
+        .unwrap_or(&empty);
+    let required: Vec<&str> = schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+
+    let mut report = ArgumentReport::default();
+
+    for (key, value) in args {
+        let field = if properties.contains_key(key) {
+            key.as_str()
+        } else {
+            match canonical_field(key, properties) {
+                Some(field) => field,
+                None => {
+                    report.unknown_fields.push(key.clone());
+                    continue;
+                }
+            }
+        };
+        if value.is_null() && required.contains(&field) {
+            report
+                .violations
+                .push(violation(key, field, &properties[field], schema, value));
+            continue;
+        }
+        check_value(
+            key,
+            field,
+            &properties[field],
+            schema,
+            value,
+            &mut report.violations,
+        );
+    }
+
+    for field in required {
+        let given = args.contains_key(field)
+            || FIELD_ALIASES
+                .iter()
+                .any(|(alias, target)| *target == field && args.contains_key(*alias));
+        if !given {
+            let expected = properties.get(field).unwrap_or(&Value::Null);
+            report.violations.push(Violation {
+                field: field.to_string(),
+                expected: expected_type(expected, schema),
+                received: "missing".to_string(),
+                example: example_value(field, expected, schema),
+            });
+        }
+    }
+
+    report
+}
+
+/// Schema field that `key` is a serde alias for, if the tool has that field.
+fn canonical_field<'a>(key: &str, properties: &'a JsonObject) -> Option<&'a str> {
+    FIELD_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == key)
+        .and_then(|(_, field)| properties.get_key_value(*field))
+        .map(|(field, _)| field.as_str())
+}
+
+/// Check `value` against `property`, recursing into array items.
+fn check_value(
+    path: &str,
+    field: &str,
+    property: &Value,
+    root: &JsonObject,
+    value: &Value,
+    violations: &mut Vec<Violation>,
+) {
+    match matches(property, root, value) {
+        Some(false) => violations.push(violation(path, field, property, root, value)),
+        Some(true) => {
+            let items = resolve(property, root).get("items");
+            if let (Some(items), Some(values)) = (items, value.as_array()) {
+                for (i, item) in values.iter().enumerate() {
+                    check_value(
+                        &format!("{}[{}]", path, i),
+                        field,
+                        items,
+                        root,
+                        item,
+                        violations,
+                    );
+                }
+            }
+        }
+        None => {}
+    }
+}
+
+fn violation(
+    path: &str,
+    field: &str,
+    property: &Value,
+    root: &JsonObject,
+    value: &Value,
+) -> Violation {
+    Violation {
+        field: path.to_string(),
+        expected: expected_type(property, root),
+        received: json_type(value).to_string(),
+        example: example_value(field, property, root),
+    }
+}
+
+/// Follow a `$ref` into the schema's definitions.
+fn resolve<'a>(schema: &'a Value, root: &'a JsonObject) -> &'a Value {
+    let Some(reference) = schema.get("$ref").and_then(Value::as_str) else {
+        return schema;
+    };
+    let name = reference.rsplit('/').next().unwrap_or_default();
+    ["$defs", "definitions"]
+        .iter()
+        .find_map(|defs| root.get(*defs)?.get(name))
+        .unwrap_or(schema)
+}
+
+/// Whether `value` satisfies `schema`; `None` when the schema says nothing
+/// this validator enforces.
+fn matches(schema: &Value, root: &JsonObject, value: &Value) -> Option<bool> {
+    let schema = resolve(schema, root);
+    // schemars marks `Option` fields `nullable` rather than adding "null"
+    if value.is_null() && schema.get("nullable") == Some(&Value::Bool(true)) {
+        return Some(true);
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        return Some(allowed.contains(value));
+    }
+    if let Some(branches) = schema
+        .get("anyOf")
+        .or_else(|| schema.get("oneOf"))
+        .and_then(Value::as_array)
+    {
+        let results: Vec<Option<bool>> = branches
+            .iter()
+            .map(|branch| matches(branch, root, value))
+            .collect();
+        return if results.contains(&Some(true)) || results.contains(&None) {
+            Some(true)
+        } else {
+            Some(false)
+        };
+    }
+    let types = schema_types(schema);
+    if types.is_empty() {
+        return None;
+    }
+    Some(types.iter().any(|t| type_matches(t, value)))
+}
+
+fn schema_types(schema: &Value) -> Vec<&str> {
+    match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn type_matches(expected: &str, value: &Value) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Human-readable expected type, e.g. `integer`, `string or array` or
+/// `one of "low", "high"`.
+fn expected_type(schema: &Value, root: &JsonObject) -> String {
+    let schema = resolve(schema, root);
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        let values: Vec<String> = allowed.iter().map(Value::to_string).collect();
+        return format!("one of {}", values.join(", "));
+    }
+    if let Some(branches) = schema
+        .get("anyOf")
+        .or_else(|| schema.get("oneOf"))
+        .and_then(Value::as_array)
+    {
+        let types: Vec<String> = branches
+            .iter()
+            .filter(|branch| schema_types(resolve(branch, root)) != ["null"])
+            .map(|branch| expected_type(branch, root))
+            .collect();
+        return types.join(" or ");
+    }
+    let types: Vec<&str> = schema_types(schema)
+        .into_iter()
+        .filter(|t| *t != "null")
+        .collect();
+    match types.as_slice() {
+        [] => "any value".to_string(),
+        ["array"] => match schema.get("items") {
+            Some(items) => format!("array of {}", expected_type(items, root)),
+            None => "array".to_string(),
+        },
+        types => types.join(" or "),
+    }
+}
+
+/// A plausible value for `field`, for error messages.
+fn example_value(field: &str, schema: &Value, root: &JsonObject) -> Value {
+    let schema = resolve(schema, root);
+    if let Some(first) = schema
+        .get("enum")
+        .and_then(Value::as_array)
+        .and_then(|allowed| allowed.first())
+    {
+        return first.clone();
+    }
+    if let Some(first) = schema
+        .get("anyOf")
+        .or_else(|| schema.get("oneOf"))
+        .and_then(Value::as_array)
+        .and_then(|branches| {
+            branches
+                .iter()
+                .find(|branch| schema_types(resolve(branch, root)) != ["null"])
+        })
+    {
+        return example_value(field, first, root);
+    }
+
+    let kind = schema_types(schema)
+        .into_iter()
+        .find(|t| *t != "null")
+        .unwrap_or("string");
+    match kind {
+        "string" if field.ends_with("project_slug") || field == "human_key" => {
+            json!("/abs/path/to/repo")
+        }
+        "string" if field == "name" || field.ends_with("_name") || field == "to" => {
+            json!("BlueLake")
+        }
+        "string" if field == "thread_id" => json!("TASK-123"),
+        "string" => json!("text"),
+        "integer" if field.ends_with("_id") || field.ends_with("_ids") => json!(42),
+        "integer" => json!(10),
+        "number" => json!(1.5),
+        "boolean" => json!(true),
+        "array" => match schema.get("items") {
+            Some(items) => json!([example_value(field, items, root)]),
+            None => json!([]),
+        },
+        "object" => json!({}),
+        _ => Value::Null,
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    fn schema() -> JsonObject {
+        json!({
+            "type": "object",
+            "properties": {
+                "project_slug": { "type": "string" },
+                "message_id": { "type": "integer", "format": "int64" },
+                "urgent": { "type": "boolean", "nullable": true },
+                "paths": { "type": ["array", "null"], "items": { "type": "string" } },
+                "thread_id": { "$ref": "#/$defs/ThreadIdInput" },
+                "importance": { "enum": ["low", "normal", "high"] }
+            },
+            "required": ["project_slug", "message_id"],
+            "$defs": {
+                "ThreadIdInput": {
+                    "anyOf": [
+                        { "type": "string" },
+                        { "type": "array", "items": { "type": "string" } }
+                    ]
+                }
+            }
+        })
+        .as_object()
+        .cloned()
+        .unwrap()
+    }
+
+    fn validate(args: Value) -> ArgumentReport {
+        validate_arguments(&schema(), args.as_object())
+    }
+
+    #[test]
+    fn test_valid_arguments_pass() {
+        let report = validate(json!({
+            "project_slug": "/repo",
+            "message_id": 7,
+            "urgent": null,
+            "paths": ["src/**"],
+            "thread_id": ["a", "b"],
+            "importance": "high"
+        }));
+        assert_eq!(report, ArgumentReport::default());
+    }
+
+    #[test]
+    fn test_collects_every_violation() {
+        let report = validate(json!({
+            "message_id": "7",
+            "urgent": "yes",
+            "paths": ["ok", 3],
+            "thread_id": 5,
+            "importance": "extreme"
+        }));
+        let fields: Vec<&str> = report.violations.iter().map(|v| v.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "importance",
+                "message_id",
+                "paths[1]",
+                "thread_id",
+                "urgent",
+                "project_slug"
+            ]
+        );
+
+        assert!(report.violations[0].expected.contains("\"normal\""));
+        let message_id = &report.violations[1];
+        assert_eq!(message_id.expected, "integer");
+        assert_eq!(message_id.received, "string");
+        assert_eq!(message_id.example, json!(42));
+        assert_eq!(report.violations[3].expected, "string or array of string");
+        assert_eq!(report.violations[4].expected, "boolean");
+        assert_eq!(report.violations[5].received, "missing");
+        assert!(report.violations[5].describe().contains("is required"));
+    }
+
+    #[test]
+    fn test_required_field_may_not_be_null() {
+        let report = validate(json!({ "project_slug": "/repo", "message_id": null }));
+        assert_eq!(report.violations.len(), 1);
+        assert_eq!(report.violations[0].received, "null");
+    }
+
+    #[test]
+    fn test_unknown_fields_warn_and_aliases_count() {
+        let report = validate(json!({
+            "project_key": "/repo",
+            "message_id": 1,
+            "colour": "blue"
+        }));
+        assert!(report.is_valid());
+        assert_eq!(report.unknown_fields, vec!["colour".to_string()]);
+        assert_eq!(
+            report.warnings(),
+            vec!["Unknown parameter 'colour' was ignored".to_string()]
+        );
+
+        // The alias is type-checked like the field it stands for
+        let report = validate(json!({ "project_key": 9, "message_id": 1 }));
+        assert_eq!(report.violations[0].field, "project_key");
+        assert_eq!(report.violations[0].example, json!("/abs/path/to/repo"));
+    }
+}
@@ -0,0 +1,122 @@
+//! HTTP/SSE transport helpers
+//!
+//! Keep-alive timing and public URL resolution for `run_sse`, kept apart from
+//! the server loop so they can be unit tested.
+
+use axum::http::HeaderMap;
+use mouchak_mail_common::config::McpConfig;
+use std::time::Duration;
+
+/// Path of the streamable HTTP MCP endpoint (POST for calls, GET for SSE)
+pub const MCP_PATH: &str = "/mcp";
+
+/// Interval between SSE keep-alive pings; `None` when disabled (0 seconds).
+pub fn keep_alive_interval(config: &McpConfig) -> Option<Duration> {
+    (config.sse_keep_alive_secs > 0).then(|| Duration::from_secs(config.sse_keep_alive_secs))
+}
+
+/// Base URL clients should use to reach the server, without a trailing slash.
+///
+/// An explicit `public_url` wins. Otherwise `X-Forwarded-Proto` and
+/// `X-Forwarded-Host` from a TLS-terminating proxy are honored, falling back
+/// to the `Host` header and finally the bind address over plain HTTP.
+pub fn public_base_url(config: &McpConfig, headers: &HeaderMap, bind_addr: &str) -> String {
+    if let Some(url) = config.public_url.as_deref().filter(|u| !u.is_empty()) {
+        return url.trim_end_matches('/').to_string();
+    }
+
+    // Proxies may append to these headers; the first value is the client-facing one
+    let header = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(',').next())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+    let proto = header("x-forwarded-proto").unwrap_or("http");
+    let host = header("x-forwarded-host")
+        .or_else(|| header("host"))
+        .unwrap_or(bind_addr);
+    format!("{}://{}", proto, host)
+}
+
+/// Absolute URL of the MCP message endpoint.
+pub fn mcp_endpoint_url(config: &McpConfig, headers: &HeaderMap, bind_addr: &str) -> String {
+    format!(
+        "{}{}",
+        public_base_url(config, headers, bind_addr),
+        MCP_PATH
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mouchak_mail_common::config::AppConfig;
+
+    fn config() -> McpConfig {
+        AppConfig::default().mcp
+    }
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.insert(*name, axum::http::HeaderValue::from_static(value));
+        }
+        map
+    }
+
+    #[test]
+    fn test_keep_alive_interval() {
+        let mut config = config();
+        assert_eq!(keep_alive_interval(&config), Some(Duration::from_secs(15)));
+
+        config.sse_keep_alive_secs = 30;
+        assert_eq!(keep_alive_interval(&config), Some(Duration::from_secs(30)));
+
+        config.sse_keep_alive_secs = 0;
+        assert_eq!(keep_alive_interval(&config), None);
+    }
+
+    #[test]
+    fn test_url_falls_back_to_bind_address() {
+        assert_eq!(
+            mcp_endpoint_url(&config(), &HeaderMap::new(), "127.0.0.1:3000"),
+            "http://127.0.0.1:3000/mcp"
+        );
+    }
+
+    #[test]
+    fn test_url_uses_host_header() {
+        let headers = headers(&[("host", "mail.internal:3000")]);
+        assert_eq!(
+            mcp_endpoint_url(&config(), &headers, "0.0.0.0:3000"),
+            "http://mail.internal:3000/mcp"
+        );
+    }
+
+    #[test]
+    fn test_url_honors_forwarded_headers() {
+        let headers = headers(&[
+            ("host", "127.0.0.1:3000"),
+            ("x-forwarded-proto", "https"),
+            ("x-forwarded-host", "mail.example.com, proxy.internal"),
+        ]);
+        assert_eq!(
+            mcp_endpoint_url(&config(), &headers, "127.0.0.1:3000"),
+            "https://mail.example.com/mcp"
+        );
+    }
+
+    #[test]
+    fn test_public_url_overrides_headers() {
+        let mut config = config();
+        config.public_url = Some("https://agents.example.com/mail/".to_string());
+        let headers = headers(&[("x-forwarded-host", "other.example.com")]);
+        assert_eq!(
+            mcp_endpoint_url(&config, &headers, "127.0.0.1:3000"),
+            "https://agents.example.com/mail/mcp"
+        );
+    }
+}
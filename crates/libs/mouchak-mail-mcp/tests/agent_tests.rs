@@ -4,11 +4,12 @@ use libsql::Builder;
 use mouchak_mail_common::config::AppConfig;
 use mouchak_mail_core::ctx::Ctx;
 use mouchak_mail_core::model::{ModelManager, agent::AgentBmc, project::ProjectBmc};
-use mouchak_mail_mcp::tools::agent;
 use mouchak_mail_mcp::tools::{
-    CreateAgentIdentityParams, GetAgentProfileParams, ListAgentsParams, RegisterAgentParams,
+    CreateAgentIdentityParams, GetAgentProfileParams, GroupAction, ListAgentsParams,
+    ManageAgentGroupParams, RegisterAgentParams, SendMessageParams, UpdateAgentParams,
     UpdateAgentProfileParams, WhoisParams,
 };
+use mouchak_mail_mcp::tools::{agent, messaging};
 use std::sync::Arc;
 use tempfile::TempDir;
 
@@ -30,14 +31,9 @@ async fn create_test_mm() -> (Arc<ModelManager>, TempDir) {
     let conn = db.connect().unwrap();
     let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
 
-    let schema1 = include_str!("../../../../migrations/001_initial_schema.sql");
-    conn.execute_batch(schema1).await.unwrap();
-    let schema2 = include_str!("../../../../migrations/002_agent_capabilities.sql");
-    conn.execute_batch(schema2).await.unwrap();
-    let schema3 = include_str!("../../../../migrations/003_tool_metrics.sql");
-    conn.execute_batch(schema3).await.unwrap();
-    let schema4 = include_str!("../../../../migrations/004_attachments.sql");
-    conn.execute_batch(schema4).await.unwrap();
+    mouchak_mail_core::store::apply_migrations(&conn)
+        .await
+        .unwrap();
 
     let app_config = Arc::new(AppConfig::default());
     let mm = ModelManager::new_for_test(conn, archive_root, app_config);
@@ -71,6 +67,7 @@ async fn test_register_agent_impl_success() {
         program: "claude_code".to_string(),
         model: "opus".to_string(),
         task_description: "Testing agent registration".to_string(),
+        strict: false,
     };
 
     let result = agent::register_agent_impl(&ctx, &mm, params).await;
@@ -95,6 +92,7 @@ async fn test_register_agent_impl_already_exists() {
         program: "claude_code".to_string(),
         model: "opus".to_string(),
         task_description: "First registration".to_string(),
+        strict: false,
     };
 
     agent::register_agent_impl(&ctx, &mm, params1)
@@ -107,6 +105,7 @@ async fn test_register_agent_impl_already_exists() {
         program: "claude_code".to_string(),
         model: "opus".to_string(),
         task_description: "First registration".to_string(),
+        strict: false,
     };
 
     let result = agent::register_agent_impl(&ctx, &mm, params2).await;
@@ -116,6 +115,73 @@ async fn test_register_agent_impl_already_exists() {
     assert!(output.contains("already exists"));
 }
 
+#[tokio::test]
+async fn test_register_agent_impl_enforces_name_rules() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+
+    let project_slug = setup_project(&mm, "name_rules").await;
+    let register = |name: &str| RegisterAgentParams {
+        project_slug: project_slug.clone(),
+        name: name.to_string(),
+        program: "claude_code".to_string(),
+        model: "opus".to_string(),
+        task_description: String::new(),
+        strict: false,
+    };
+
+    for bad in ["../../etc/passwd", "\u{0430}lice", "x"] {
+        let err = agent::register_agent_impl(&ctx, &mm, register(bad))
+            .await
+            .unwrap_err();
+        assert!(
+            err.message.contains("Invalid agent name"),
+            "{}: {}",
+            bad,
+            err.message
+        );
+    }
+
+    // Surrounding whitespace is trimmed and case is kept
+    agent::register_agent_impl(&ctx, &mm, register("  Alice "))
+        .await
+        .unwrap();
+    let project = ProjectBmc::get_by_identifier(&ctx, &mm, &project_slug)
+        .await
+        .unwrap();
+    AgentBmc::get_by_name(&ctx, &mm, project.id, "Alice")
+        .await
+        .unwrap();
+
+    let err = agent::register_agent_impl(&ctx, &mm, register("alice"))
+        .await
+        .unwrap_err();
+    assert!(err.message.contains("taken by 'Alice'"), "{}", err.message);
+}
+
+#[tokio::test]
+async fn test_register_agent_impl_strict_conflict() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+
+    let project_slug = setup_project(&mm, "strict").await;
+
+    let register = |strict: bool| RegisterAgentParams {
+        project_slug: project_slug.clone(),
+        name: "StrictAgent".to_string(),
+        program: "claude_code".to_string(),
+        model: "opus".to_string(),
+        task_description: "Strict registration".to_string(),
+        strict,
+    };
+
+    agent::register_agent_impl(&ctx, &mm, register(true))
+        .await
+        .unwrap();
+    let result = agent::register_agent_impl(&ctx, &mm, register(true)).await;
+    assert!(result.is_err());
+}
+
 #[tokio::test]
 async fn test_register_agent_impl_invalid_name() {
     let (mm, _temp) = create_test_mm().await;
@@ -129,6 +195,7 @@ async fn test_register_agent_impl_invalid_name() {
         program: "claude_code".to_string(),
         model: "opus".to_string(),
         task_description: "Should fail".to_string(),
+        strict: false,
     };
 
     let result = agent::register_agent_impl(&ctx, &mm, params).await;
@@ -146,6 +213,7 @@ async fn test_register_agent_impl_project_not_found() {
         program: "claude_code".to_string(),
         model: "opus".to_string(),
         task_description: "Should fail".to_string(),
+        strict: false,
     };
 
     let result = agent::register_agent_impl(&ctx, &mm, params).await;
@@ -165,6 +233,7 @@ async fn test_whois_impl_success() {
         program: "claude_code".to_string(),
         model: "sonnet".to_string(),
         task_description: "Agent for whois test".to_string(),
+        strict: false,
     };
     agent::register_agent_impl(&ctx, &mm, register_params)
         .await
@@ -215,6 +284,7 @@ async fn test_update_agent_profile_impl_success() {
         program: "claude_code".to_string(),
         model: "opus".to_string(),
         task_description: "Original task".to_string(),
+        strict: false,
     };
     agent::register_agent_impl(&ctx, &mm, register_params)
         .await
@@ -262,6 +332,63 @@ async fn test_update_agent_profile_impl_agent_not_found() {
     assert!(result.is_err());
 }
 
+#[tokio::test]
+async fn test_update_agent_impl_rename_and_retire() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+
+    let project_slug = setup_project(&mm, "update_agent").await;
+
+    let register_params = RegisterAgentParams {
+        project_slug: project_slug.clone(),
+        name: "OldName".to_string(),
+        program: "claude_code".to_string(),
+        model: "opus".to_string(),
+        task_description: "Original task".to_string(),
+        strict: false,
+    };
+    agent::register_agent_impl(&ctx, &mm, register_params)
+        .await
+        .unwrap();
+
+    let params = UpdateAgentParams {
+        project_slug: project_slug.clone(),
+        agent_name: "OldName".to_string(),
+        name: Some("NewName".to_string()),
+        task_description: Some("Renamed task".to_string()),
+        model: None,
+        program: None,
+        retire: false,
+    };
+    let result = agent::update_agent_impl(&ctx, &mm, params).await.unwrap();
+    assert!(extract_text(&result).contains("Updated agent 'NewName'"));
+
+    let project = ProjectBmc::get_by_identifier(&ctx, &mm, &project_slug)
+        .await
+        .unwrap();
+    let renamed = AgentBmc::get_by_name(&ctx, &mm, project.id, "NewName")
+        .await
+        .unwrap();
+    assert_eq!(renamed.task_description, "Renamed task");
+
+    let params = UpdateAgentParams {
+        project_slug: project_slug.clone(),
+        agent_name: "NewName".to_string(),
+        name: None,
+        task_description: None,
+        model: None,
+        program: None,
+        retire: true,
+    };
+    let result = agent::update_agent_impl(&ctx, &mm, params).await.unwrap();
+    assert!(extract_text(&result).contains("retired"));
+
+    let agents = AgentBmc::list_all_for_project(&ctx, &mm, project.id)
+        .await
+        .unwrap();
+    assert!(agents.is_empty());
+}
+
 #[tokio::test]
 async fn test_get_agent_profile_impl_success() {
     let (mm, _temp) = create_test_mm().await;
@@ -275,6 +402,7 @@ async fn test_get_agent_profile_impl_success() {
         program: "claude_code".to_string(),
         model: "opus".to_string(),
         task_description: "Detailed profile test".to_string(),
+        strict: false,
     };
     agent::register_agent_impl(&ctx, &mm, register_params)
         .await
@@ -344,6 +472,7 @@ async fn test_list_agents_impl_with_agents() {
             program: "claude_code".to_string(),
             model: "opus".to_string(),
             task_description: format!("Task for {}", name),
+            strict: false,
         };
         agent::register_agent_impl(&ctx, &mm, params).await.unwrap();
     }
@@ -420,6 +549,7 @@ async fn test_register_agent_impl_unix_username_hint() {
         program: "claude_code".to_string(),
         model: "opus".to_string(),
         task_description: "Testing unix username hint".to_string(),
+        strict: false,
     };
 
     let result = agent::register_agent_impl(&ctx, &mm, params).await;
@@ -442,6 +572,7 @@ async fn test_register_agent_impl_no_unix_hint_for_proper_name() {
         program: "claude_code".to_string(),
         model: "opus".to_string(),
         task_description: "Testing no unix hint".to_string(),
+        strict: false,
     };
 
     let result = agent::register_agent_impl(&ctx, &mm, params).await;
@@ -488,6 +619,7 @@ async fn test_create_agent_identity_impl_avoids_existing() {
         program: "claude_code".to_string(),
         model: "opus".to_string(),
         task_description: "Existing agent".to_string(),
+        strict: false,
     };
     agent::register_agent_impl(&ctx, &mm, register_params)
         .await
@@ -595,8 +727,120 @@ async fn test_register_agent_impl_invalid_project_slug() {
         program: "claude_code".to_string(),
         model: "opus".to_string(),
         task_description: "Should fail".to_string(),
+        strict: false,
     };
 
     let result = agent::register_agent_impl(&ctx, &mm, params).await;
     assert!(result.is_err());
 }
+
+#[tokio::test]
+async fn test_manage_agent_group_impl_and_group_send() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+    let project_slug = setup_project(&mm, "groups").await;
+    for name in ["LeadAgent", "BlueLake", "GreenCastle"] {
+        let params = RegisterAgentParams {
+            project_slug: project_slug.clone(),
+            name: name.to_string(),
+            program: "claude_code".to_string(),
+            model: "opus".to_string(),
+            task_description: String::new(),
+            strict: false,
+        };
+        agent::register_agent_impl(&ctx, &mm, params).await.unwrap();
+    }
+
+    let manage =
+        |action, group_name: Option<&str>, agent_names: Option<&str>| ManageAgentGroupParams {
+            project_slug: project_slug.clone(),
+            action,
+            group_name: group_name.map(String::from),
+            agent_names: agent_names.map(String::from),
+        };
+
+    let result = agent::manage_agent_group_impl(
+        &ctx,
+        &mm,
+        manage(
+            GroupAction::Create,
+            Some("frontend"),
+            Some("BlueLake, GreenCastle"),
+        ),
+    )
+    .await
+    .unwrap();
+    assert!(extract_text(&result).contains("group:frontend (2): BlueLake, GreenCastle"));
+
+    // Shadowing an agent is rejected
+    let err = agent::manage_agent_group_impl(
+        &ctx,
+        &mm,
+        manage(GroupAction::Create, Some("bluelake"), None),
+    )
+    .await
+    .unwrap_err();
+    assert!(err.message.contains("already the name of an agent"));
+
+    let err = agent::manage_agent_group_impl(
+        &ctx,
+        &mm,
+        manage(GroupAction::AddMember, Some("backend"), Some("BlueLake")),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.data.unwrap()["error_code"], "INVALID_INPUT");
+    assert!(
+        agent::manage_agent_group_impl(&ctx, &mm, manage(GroupAction::Delete, None, None))
+            .await
+            .is_err()
+    );
+
+    let send = || SendMessageParams {
+        project_slug: project_slug.clone(),
+        sender_name: "LeadAgent".to_string(),
+        to: "group:frontend".to_string(),
+        cc: None,
+        bcc: None,
+        subject: "Standup".to_string(),
+        body_md: "Ten minutes".to_string(),
+        thread_id: None,
+        allow_new_thread: None,
+        importance: None,
+        ack_required: None,
+        deliver_at: None,
+        broadcast: None,
+        allow_partial: None,
+    };
+    let result = messaging::send_message_impl(&ctx, &mm, send())
+        .await
+        .unwrap();
+    let report: serde_json::Value =
+        serde_json::from_str(&result.content[1].as_text().unwrap().text).unwrap();
+    assert_eq!(report["groups"][0]["group"], "frontend");
+    assert_eq!(
+        report["groups"][0]["members"],
+        serde_json::json!(["BlueLake", "GreenCastle"])
+    );
+
+    agent::manage_agent_group_impl(
+        &ctx,
+        &mm,
+        manage(
+            GroupAction::RemoveMember,
+            Some("frontend"),
+            Some("BlueLake,GreenCastle"),
+        ),
+    )
+    .await
+    .unwrap();
+    let err = messaging::send_message_impl(&ctx, &mm, send())
+        .await
+        .unwrap_err();
+    assert_eq!(err.data.unwrap()["error_code"], "INVALID_RECIPIENT");
+
+    let result = agent::manage_agent_group_impl(&ctx, &mm, manage(GroupAction::List, None, None))
+        .await
+        .unwrap();
+    assert!(extract_text(&result).contains("group:frontend (no members)"));
+}
@@ -10,8 +10,8 @@ use mouchak_mail_core::model::{
 };
 use mouchak_mail_mcp::tools::contacts;
 use mouchak_mail_mcp::tools::{
-    ListContactsParams, RequestContactParams, RespondContactByNameParams, RespondContactParams,
-    SetContactPolicyParams,
+    GetContactPolicyParams, ListContactsParams, RequestContactParams, RespondContactByNameParams,
+    RespondContactParams, SetContactPolicyParams,
 };
 use std::sync::Arc;
 use tempfile::TempDir;
@@ -34,14 +34,9 @@ async fn create_test_mm() -> (Arc<ModelManager>, TempDir) {
     let conn = db.connect().unwrap();
     let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
 
-    let schema1 = include_str!("../../../../migrations/001_initial_schema.sql");
-    conn.execute_batch(schema1).await.unwrap();
-    let schema2 = include_str!("../../../../migrations/002_agent_capabilities.sql");
-    conn.execute_batch(schema2).await.unwrap();
-    let schema3 = include_str!("../../../../migrations/003_tool_metrics.sql");
-    conn.execute_batch(schema3).await.unwrap();
-    let schema4 = include_str!("../../../../migrations/004_attachments.sql");
-    conn.execute_batch(schema4).await.unwrap();
+    mouchak_mail_core::store::apply_migrations(&conn)
+        .await
+        .unwrap();
 
     let app_config = Arc::new(AppConfig::default());
     let mm = ModelManager::new_for_test(conn, archive_root, app_config);
@@ -315,7 +310,9 @@ async fn test_set_contact_policy_impl_success() {
     let params = SetContactPolicyParams {
         project_slug: project_slug.to_string(),
         agent_name: "policy_agent".to_string(),
-        contact_policy: "auto".to_string(),
+        contact_policy: Some("auto".to_string()),
+        dnd_until: None,
+        min_importance: None,
     };
 
     let result = contacts::set_contact_policy_impl(&ctx, &mm, params).await;
@@ -348,7 +345,9 @@ async fn test_set_contact_policy_impl_manual() {
     let params = SetContactPolicyParams {
         project_slug: project_slug.to_string(),
         agent_name: "manual_agent".to_string(),
-        contact_policy: "manual".to_string(),
+        contact_policy: Some("manual".to_string()),
+        dnd_until: None,
+        min_importance: None,
     };
 
     let result = contacts::set_contact_policy_impl(&ctx, &mm, params).await;
@@ -380,7 +379,9 @@ async fn test_set_contact_policy_impl_deny() {
     let params = SetContactPolicyParams {
         project_slug: project_slug.to_string(),
         agent_name: "deny_agent".to_string(),
-        contact_policy: "deny".to_string(),
+        contact_policy: Some("deny".to_string()),
+        dnd_until: None,
+        min_importance: None,
     };
 
     let result = contacts::set_contact_policy_impl(&ctx, &mm, params).await;
@@ -403,7 +404,9 @@ async fn test_set_contact_policy_impl_agent_not_found() {
     let params = SetContactPolicyParams {
         project_slug: project_slug.to_string(),
         agent_name: "nonexistent".to_string(),
-        contact_policy: "auto".to_string(),
+        contact_policy: Some("auto".to_string()),
+        dnd_until: None,
+        min_importance: None,
     };
 
     let result = contacts::set_contact_policy_impl(&ctx, &mm, params).await;
@@ -412,6 +415,82 @@ async fn test_set_contact_policy_impl_agent_not_found() {
     assert!(err.message.contains("not found"));
 }
 
+#[tokio::test]
+async fn test_set_and_get_dnd_policy() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+
+    let project_slug = "dnd-policy-project";
+    let project_id = ProjectBmc::create(&ctx, &mm, project_slug, "DND Policy Project")
+        .await
+        .unwrap();
+
+    let agent_c = AgentForCreate {
+        project_id,
+        name: "focused_agent".to_string(),
+        program: "claude".to_string(),
+        model: "opus".to_string(),
+        task_description: "Agent heads-down on a refactor".to_string(),
+    };
+    AgentBmc::create(&ctx, &mm, agent_c).await.unwrap();
+
+    let until = (chrono::Utc::now() + chrono::Duration::hours(1)).to_rfc3339();
+    let params = SetContactPolicyParams {
+        project_slug: project_slug.to_string(),
+        agent_name: "focused_agent".to_string(),
+        contact_policy: None,
+        dnd_until: Some(until),
+        min_importance: Some("high".to_string()),
+    };
+    let output = extract_text(
+        &contacts::set_contact_policy_impl(&ctx, &mm, params)
+            .await
+            .unwrap(),
+    );
+    assert!(output.contains("Do not disturb"));
+    assert!(output.contains("high and above"));
+
+    let params = GetContactPolicyParams {
+        project_slug: project_slug.to_string(),
+        agent_name: "focused_agent".to_string(),
+    };
+    let output = extract_text(
+        &contacts::get_contact_policy_impl(&ctx, &mm, params)
+            .await
+            .unwrap(),
+    );
+    assert!(output.contains("Contact Policy: auto"));
+    assert!(output.contains("high and above"));
+
+    // "off" ends DND and leaves the threshold in place for next time
+    let params = SetContactPolicyParams {
+        project_slug: project_slug.to_string(),
+        agent_name: "focused_agent".to_string(),
+        contact_policy: None,
+        dnd_until: Some("off".to_string()),
+        min_importance: None,
+    };
+    let output = extract_text(
+        &contacts::set_contact_policy_impl(&ctx, &mm, params)
+            .await
+            .unwrap(),
+    );
+    assert!(output.contains("Do not disturb for 'focused_agent': off"));
+
+    let params = SetContactPolicyParams {
+        project_slug: project_slug.to_string(),
+        agent_name: "focused_agent".to_string(),
+        contact_policy: None,
+        dnd_until: Some("tomorrow-ish".to_string()),
+        min_importance: None,
+    };
+    assert!(
+        contacts::set_contact_policy_impl(&ctx, &mm, params)
+            .await
+            .is_err()
+    );
+}
+
 async fn setup_single_project_with_two_agents(
     mm: &Arc<ModelManager>,
     suffix: &str,
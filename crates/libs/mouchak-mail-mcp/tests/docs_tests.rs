@@ -1,10 +1,11 @@
-use mouchak_mail_mcp::docs::generate_markdown_docs;
+use mouchak_mail_mcp::docs::{generate_markdown_docs, render_schema};
+use mouchak_mail_mcp::tools::prompts::PromptSchema;
 use mouchak_mail_mcp::tools::{ParameterSchema, ToolSchema};
 
 #[test]
 fn test_generate_markdown_docs_empty_schemas() {
     let schemas: Vec<ToolSchema> = vec![];
-    let result = generate_markdown_docs(&schemas);
+    let result = generate_markdown_docs(&schemas, &[]);
 
     assert!(result.contains("# Mouchak Mail - Tool Reference"));
     assert!(result.contains("Total tools: 0"));
@@ -17,9 +18,11 @@ fn test_generate_markdown_docs_single_tool_no_params() {
         name: "test_tool".to_string(),
         description: "A test tool description".to_string(),
         parameters: vec![],
+        enabled: true,
+        disabled_reason: None,
     }];
 
-    let result = generate_markdown_docs(&schemas);
+    let result = generate_markdown_docs(&schemas, &[]);
 
     assert!(result.contains("# Mouchak Mail - Tool Reference"));
     assert!(result.contains("Total tools: 1"));
@@ -48,9 +51,11 @@ fn test_generate_markdown_docs_with_parameters() {
                 description: "Message priority level".to_string(),
             },
         ],
+        enabled: true,
+        disabled_reason: None,
     }];
 
-    let result = generate_markdown_docs(&schemas);
+    let result = generate_markdown_docs(&schemas, &[]);
 
     assert!(result.contains("### Parameters"));
     assert!(result.contains("| Name | Type | Required | Description |"));
@@ -65,6 +70,8 @@ fn test_generate_markdown_docs_multiple_tools() {
             name: "tool_one".to_string(),
             description: "First tool".to_string(),
             parameters: vec![],
+            enabled: true,
+            disabled_reason: None,
         },
         ToolSchema {
             name: "tool_two".to_string(),
@@ -75,10 +82,12 @@ fn test_generate_markdown_docs_multiple_tools() {
                 required: true,
                 description: "An argument".to_string(),
             }],
+            enabled: true,
+            disabled_reason: None,
         },
     ];
 
-    let result = generate_markdown_docs(&schemas);
+    let result = generate_markdown_docs(&schemas, &[]);
 
     assert!(result.contains("Total tools: 2"));
     assert!(result.contains("- [tool_one](#tool-one)"));
@@ -93,9 +102,11 @@ fn test_generate_markdown_docs_output_not_empty() {
         name: "any_tool".to_string(),
         description: "Any description".to_string(),
         parameters: vec![],
+        enabled: true,
+        disabled_reason: None,
     }];
 
-    let result = generate_markdown_docs(&schemas);
+    let result = generate_markdown_docs(&schemas, &[]);
 
     assert!(!result.is_empty());
     assert!(result.len() > 50);
@@ -107,9 +118,44 @@ fn test_generate_markdown_docs_underscore_to_dash_in_anchor() {
         name: "my_complex_tool_name".to_string(),
         description: "Tool with underscores".to_string(),
         parameters: vec![],
+        enabled: true,
+        disabled_reason: None,
     }];
 
-    let result = generate_markdown_docs(&schemas);
+    let result = generate_markdown_docs(&schemas, &[]);
 
     assert!(result.contains("- [my_complex_tool_name](#my-complex-tool-name)"));
 }
+
+#[test]
+fn test_generate_markdown_docs_prompts_section() {
+    let prompts = vec![PromptSchema {
+        name: "thread_catchup".to_string(),
+        description: "Summarize a thread".to_string(),
+        arguments: vec![ParameterSchema {
+            name: "thread_id".to_string(),
+            param_type: "string".to_string(),
+            required: true,
+            description: "Thread to catch up on".to_string(),
+        }],
+    }];
+
+    let without = generate_markdown_docs(&[], &[]);
+    assert!(!without.contains("# Prompts"));
+
+    let result = generate_markdown_docs(&[], &prompts);
+    assert!(result.contains("# Prompts"));
+    assert!(result.contains("Total prompts: 1"));
+    assert!(result.contains("## thread_catchup"));
+    assert!(result.contains("### Arguments"));
+    assert!(result.contains("| `thread_id` | string | Yes | Thread to catch up on |"));
+}
+
+#[test]
+fn test_render_schema_markdown_lists_prompts() -> serde_json::Result<()> {
+    let markdown = render_schema("markdown", false)?;
+    for prompt in ["check_my_inbox", "announce_file_work", "thread_catchup"] {
+        assert!(markdown.contains(&format!("## {}", prompt)), "{}", prompt);
+    }
+    Ok(())
+}
@@ -7,13 +7,15 @@ use mouchak_mail_core::model::{
     ModelManager,
     agent::{AgentBmc, AgentForCreate},
     agent_capabilities::{AgentCapabilityBmc, AgentCapabilityForCreate},
+    file_reservation::FileReservationBmc,
     project::ProjectBmc,
 };
 use mouchak_mail_mcp::tools::files;
 use mouchak_mail_mcp::tools::{
     FileReservationParams, FileReservationPathsParams, ForceReleaseReservationParams,
-    ListReservationsParams, ReleaseFileReservationsByAgentParams, ReleaseReservationParams,
-    RenewFileReservationParams, RenewFileReservationsByAgentParams,
+    ListMyReservationsParams, ListReservationQueueParams, ListReservationsParams,
+    ReleaseFileReservationsByAgentParams, ReleaseReservationParams, ReleaseReservationsParams,
+    RenewFileReservationParams, RenewFileReservationsByAgentParams, TransferReservationParams,
 };
 use std::sync::Arc;
 use tempfile::TempDir;
@@ -36,14 +38,9 @@ async fn create_test_mm() -> (Arc<ModelManager>, TempDir) {
     let conn = db.connect().unwrap();
     let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
 
-    let schema1 = include_str!("../../../../migrations/001_initial_schema.sql");
-    conn.execute_batch(schema1).await.unwrap();
-    let schema2 = include_str!("../../../../migrations/002_agent_capabilities.sql");
-    conn.execute_batch(schema2).await.unwrap();
-    let schema3 = include_str!("../../../../migrations/003_tool_metrics.sql");
-    conn.execute_batch(schema3).await.unwrap();
-    let schema4 = include_str!("../../../../migrations/004_attachments.sql");
-    conn.execute_batch(schema4).await.unwrap();
+    mouchak_mail_core::store::apply_migrations(&conn)
+        .await
+        .unwrap();
 
     let app_config = Arc::new(AppConfig::default());
     let mm = ModelManager::new_for_test(conn, archive_root, app_config);
@@ -306,7 +303,7 @@ async fn test_renew_file_reservation_impl_success() {
 
     let reserve_params = FileReservationParams {
         project_slug: project_slug.clone(),
-        agent_name,
+        agent_name: agent_name.clone(),
         path_pattern: "renew.rs".to_string(),
         exclusive: Some(true),
         reason: None,
@@ -325,7 +322,10 @@ async fn test_renew_file_reservation_impl_success() {
         .expect("Should extract reservation id");
 
     let params = RenewFileReservationParams {
-        reservation_id,
+        project_slug,
+        agent_name,
+        reservation_id: Some(reservation_id),
+        path_pattern: None,
         ttl_seconds: Some(7200),
     };
 
@@ -350,6 +350,8 @@ async fn test_file_reservation_paths_impl_single_path() {
         exclusive: true,
         reason: Some("Single file reservation".to_string()),
         ttl_seconds: Some(3600),
+        wait: None,
+        wait_timeout_seconds: None,
     };
 
     let result = files::file_reservation_paths_impl(&ctx, &mm, params).await;
@@ -378,6 +380,8 @@ async fn test_file_reservation_paths_impl_multiple_paths() {
         exclusive: true,
         reason: Some("Multiple files".to_string()),
         ttl_seconds: Some(3600),
+        wait: None,
+        wait_timeout_seconds: None,
     };
 
     let result = files::file_reservation_paths_impl(&ctx, &mm, params).await;
@@ -427,6 +431,8 @@ async fn test_file_reservation_paths_impl_conflict_detection() {
         exclusive: true,
         reason: Some("First agent".to_string()),
         ttl_seconds: Some(3600),
+        wait: None,
+        wait_timeout_seconds: None,
     };
     files::file_reservation_paths_impl(&Ctx::root_ctx(), &mm, params1)
         .await
@@ -439,6 +445,8 @@ async fn test_file_reservation_paths_impl_conflict_detection() {
         exclusive: true,
         reason: Some("Second agent conflicting".to_string()),
         ttl_seconds: Some(3600),
+        wait: None,
+        wait_timeout_seconds: None,
     };
 
     let result = files::file_reservation_paths_impl(&Ctx::root_ctx(), &mm, params2).await;
@@ -452,6 +460,69 @@ async fn test_file_reservation_paths_impl_conflict_detection() {
     );
 }
 
+#[tokio::test]
+async fn test_file_reservation_paths_impl_wait_queues_conflict() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+
+    let (project_slug, holder) = setup_project_with_agent(&mm, "wait1").await;
+    let project_id = ProjectBmc::get_by_identifier(&ctx, &mm, &project_slug)
+        .await
+        .unwrap()
+        .id;
+    let agent_c = AgentForCreate {
+        project_id,
+        name: "wait_agent_2".to_string(),
+        program: "claude".to_string(),
+        model: "sonnet".to_string(),
+        task_description: "Waits for the holder".to_string(),
+    };
+    let waiter_id = AgentBmc::create(&ctx, &mm, agent_c).await.unwrap();
+    let cap = AgentCapabilityForCreate {
+        agent_id: waiter_id.into(),
+        capability: "file_reservation_paths".to_string(),
+        granted_by: None,
+        expires_at: None,
+    };
+    AgentCapabilityBmc::create(&ctx, &mm, cap).await.unwrap();
+
+    let reserve = |agent_name: String, wait: Option<bool>| FileReservationPathsParams {
+        project_slug: project_slug.clone(),
+        agent_name,
+        paths: vec!["src/main.rs".to_string()],
+        exclusive: true,
+        reason: None,
+        ttl_seconds: Some(3600),
+        wait,
+        wait_timeout_seconds: Some(300),
+    };
+    files::file_reservation_paths_impl(&ctx, &mm, reserve(holder, None))
+        .await
+        .unwrap();
+
+    let result =
+        files::file_reservation_paths_impl(&ctx, &mm, reserve("wait_agent_2".into(), Some(true)))
+            .await
+            .unwrap();
+    let output = extract_text(&result);
+    assert!(
+        output.contains("Granted 0 reservations, queued 1"),
+        "Should queue: {}",
+        output
+    );
+
+    let params = ListReservationQueueParams {
+        project_slug: project_slug.clone(),
+        path_pattern: Some("src/**".to_string()),
+    };
+    let result = files::list_reservation_queue_impl(&ctx, &mm, params)
+        .await
+        .unwrap();
+    let output = extract_text(&result);
+    assert!(output.contains("wait_agent_2"), "Queue: {}", output);
+    assert!(output.contains("reservation_id"), "Blocker: {}", output);
+}
+
 #[tokio::test]
 async fn test_file_reservation_paths_impl_non_exclusive() {
     let (mm, _temp) = create_test_mm().await;
@@ -466,6 +537,8 @@ async fn test_file_reservation_paths_impl_non_exclusive() {
         exclusive: false,
         reason: Some("Reading docs".to_string()),
         ttl_seconds: Some(1800),
+        wait: None,
+        wait_timeout_seconds: None,
     };
 
     let result = files::file_reservation_paths_impl(&ctx, &mm, params).await;
@@ -487,6 +560,8 @@ async fn test_file_reservation_paths_impl_invalid_project() {
         exclusive: true,
         reason: None,
         ttl_seconds: None,
+        wait: None,
+        wait_timeout_seconds: None,
     };
 
     let result = files::file_reservation_paths_impl(&ctx, &mm, params).await;
@@ -512,6 +587,8 @@ async fn test_file_reservation_paths_impl_invalid_agent() {
         exclusive: true,
         reason: None,
         ttl_seconds: None,
+        wait: None,
+        wait_timeout_seconds: None,
     };
 
     let result = files::file_reservation_paths_impl(&ctx, &mm, params).await;
@@ -534,6 +611,8 @@ async fn test_release_file_reservations_by_path_impl_success() {
         exclusive: true,
         reason: Some("Testing path-based release".to_string()),
         ttl_seconds: Some(3600),
+        wait: None,
+        wait_timeout_seconds: None,
     };
     files::file_reservation_paths_impl(&ctx, &mm, reserve_params)
         .await
@@ -572,6 +651,8 @@ async fn test_release_file_reservations_by_path_impl_multiple_paths() {
         exclusive: true,
         reason: None,
         ttl_seconds: Some(3600),
+        wait: None,
+        wait_timeout_seconds: None,
     };
     files::file_reservation_paths_impl(&ctx, &mm, reserve_params)
         .await
@@ -650,6 +731,8 @@ async fn test_renew_file_reservations_by_agent_impl_success() {
         exclusive: true,
         reason: Some("Testing agent-based renew".to_string()),
         ttl_seconds: Some(1800),
+        wait: None,
+        wait_timeout_seconds: None,
     };
     files::file_reservation_paths_impl(&ctx, &mm, reserve_params)
         .await
@@ -689,6 +772,8 @@ async fn test_renew_file_reservations_by_agent_impl_filtered_paths() {
         exclusive: true,
         reason: None,
         ttl_seconds: Some(1800),
+        wait: None,
+        wait_timeout_seconds: None,
     };
     files::file_reservation_paths_impl(&ctx, &mm, reserve_params)
         .await
@@ -753,3 +838,343 @@ async fn test_renew_file_reservations_by_agent_impl_invalid_agent() {
     let err = result.unwrap_err();
     assert!(err.message.contains("not found"));
 }
+
+async fn reserve_paths(
+    mm: &Arc<ModelManager>,
+    project_slug: &str,
+    agent_name: &str,
+    paths: &[&str],
+) {
+    let params = FileReservationPathsParams {
+        project_slug: project_slug.to_string(),
+        agent_name: agent_name.to_string(),
+        paths: paths.iter().map(|p| p.to_string()).collect(),
+        exclusive: true,
+        reason: None,
+        ttl_seconds: Some(3600),
+        wait: None,
+        wait_timeout_seconds: None,
+    };
+    files::file_reservation_paths_impl(&Ctx::root_ctx(), mm, params)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_list_my_reservations_impl_success() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+
+    let (project_slug, agent_name) = setup_project_with_agent(&mm, "list_mine").await;
+    reserve_paths(&mm, &project_slug, &agent_name, &["src/a.rs", "src/b.rs"]).await;
+
+    let params = ListMyReservationsParams {
+        project_slug,
+        agent_name,
+    };
+
+    let result = files::list_my_reservations_impl(&ctx, &mm, params).await;
+    assert!(result.is_ok());
+
+    let output = extract_text(&result.unwrap());
+    assert!(output.contains("src/a.rs"));
+    assert!(output.contains("src/b.rs"));
+    assert!(output.contains("expires_ts"));
+    assert!(output.contains("exclusive"));
+}
+
+#[tokio::test]
+async fn test_release_reservations_impl_reports_not_found() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+
+    let (project_slug, agent_name) = setup_project_with_agent(&mm, "release_mine").await;
+    reserve_paths(&mm, &project_slug, &agent_name, &["src/held.rs"]).await;
+
+    let params = ReleaseReservationsParams {
+        project_slug: project_slug.clone(),
+        agent_name: agent_name.clone(),
+        paths: Some(vec![
+            "src/held.rs".to_string(),
+            "src/missing.rs".to_string(),
+        ]),
+        all: None,
+    };
+
+    let result = files::release_reservations_impl(&ctx, &mm, params).await;
+    assert!(result.is_ok(), "Unknown paths should not be an error");
+
+    let output = extract_text(&result.unwrap());
+    assert!(output.contains("released_count"));
+    assert!(output.contains("src/held.rs"));
+    assert!(output.contains("src/missing.rs"));
+
+    let list = files::list_my_reservations_impl(
+        &ctx,
+        &mm,
+        ListMyReservationsParams {
+            project_slug,
+            agent_name,
+        },
+    )
+    .await
+    .unwrap();
+    assert!(!extract_text(&list).contains("src/held.rs"));
+}
+
+#[tokio::test]
+async fn test_release_reservations_impl_all() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+
+    let (project_slug, agent_name) = setup_project_with_agent(&mm, "release_all").await;
+    reserve_paths(&mm, &project_slug, &agent_name, &["x.rs", "y.rs", "z.rs"]).await;
+
+    let params = ReleaseReservationsParams {
+        project_slug: project_slug.clone(),
+        agent_name: agent_name.clone(),
+        paths: None,
+        all: Some(true),
+    };
+
+    let result = files::release_reservations_impl(&ctx, &mm, params).await;
+    assert!(result.is_ok());
+    assert!(extract_text(&result.unwrap()).contains("released_count\\\": 3"));
+
+    let list = files::list_my_reservations_impl(
+        &ctx,
+        &mm,
+        ListMyReservationsParams {
+            project_slug,
+            agent_name,
+        },
+    )
+    .await
+    .unwrap();
+    assert!(extract_text(&list).contains("count\\\": 0"));
+}
+
+#[tokio::test]
+async fn test_release_reservations_impl_refuses_other_agent() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+
+    let (project_slug, owner) = setup_project_with_agent(&mm, "release_other").await;
+    reserve_paths(&mm, &project_slug, &owner, &["src/owned.rs"]).await;
+
+    let project = ProjectBmc::get_by_slug(&ctx, &mm, &project_slug)
+        .await
+        .unwrap();
+    let intruder = AgentForCreate {
+        project_id: project.id,
+        name: "intruder_agent".to_string(),
+        program: "claude".to_string(),
+        model: "opus".to_string(),
+        task_description: "Tries to release others' locks".to_string(),
+    };
+    AgentBmc::create(&ctx, &mm, intruder).await.unwrap();
+
+    let params = ReleaseReservationsParams {
+        project_slug: project_slug.clone(),
+        agent_name: "intruder_agent".to_string(),
+        paths: Some(vec!["src/owned.rs".to_string()]),
+        all: None,
+    };
+
+    let result = files::release_reservations_impl(&ctx, &mm, params).await;
+    assert!(result.is_ok());
+    let output = extract_text(&result.unwrap());
+    assert!(output.contains("refused"));
+    assert!(output.contains(&owner));
+
+    let list = files::list_my_reservations_impl(
+        &ctx,
+        &mm,
+        ListMyReservationsParams {
+            project_slug,
+            agent_name: owner,
+        },
+    )
+    .await
+    .unwrap();
+    assert!(
+        extract_text(&list).contains("src/owned.rs"),
+        "Owner's reservation must stay active"
+    );
+}
+
+#[tokio::test]
+async fn test_release_reservations_impl_requires_paths_or_all() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+
+    let (project_slug, agent_name) = setup_project_with_agent(&mm, "release_empty").await;
+
+    let params = ReleaseReservationsParams {
+        project_slug,
+        agent_name,
+        paths: None,
+        all: None,
+    };
+
+    let result = files::release_reservations_impl(&ctx, &mm, params).await;
+    assert!(result.is_err());
+    assert!(result.unwrap_err().message.contains("all"));
+}
+
+#[tokio::test]
+async fn test_renew_file_reservation_impl_by_path() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+
+    let (project_slug, agent_name) = setup_project_with_agent(&mm, "renew_path").await;
+    reserve_paths(&mm, &project_slug, &agent_name, &["src/renew.rs"]).await;
+
+    let params = RenewFileReservationParams {
+        project_slug,
+        agent_name,
+        reservation_id: None,
+        path_pattern: Some("src/renew.rs".to_string()),
+        ttl_seconds: Some(600),
+    };
+
+    let result = files::renew_file_reservation_impl(&ctx, &mm, params).await;
+    let output = extract_text(&result.unwrap());
+    assert!(output.contains("src/renew.rs"));
+    // RFC3339 expiry carries an explicit UTC offset
+    assert!(output.contains("+00:00"));
+}
+
+#[tokio::test]
+async fn test_renew_file_reservation_impl_released_rejected() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+
+    let (project_slug, agent_name) = setup_project_with_agent(&mm, "renew_released").await;
+    reserve_paths(&mm, &project_slug, &agent_name, &["src/gone.rs"]).await;
+
+    files::release_reservations_impl(
+        &ctx,
+        &mm,
+        ReleaseReservationsParams {
+            project_slug: project_slug.clone(),
+            agent_name: agent_name.clone(),
+            paths: None,
+            all: Some(true),
+        },
+    )
+    .await
+    .unwrap();
+
+    let params = RenewFileReservationParams {
+        project_slug,
+        agent_name,
+        reservation_id: None,
+        path_pattern: Some("src/gone.rs".to_string()),
+        ttl_seconds: Some(600),
+    };
+
+    let err = files::renew_file_reservation_impl(&ctx, &mm, params)
+        .await
+        .unwrap_err();
+    let data = err.data.unwrap();
+    assert_eq!(data["error_code"], "RESERVATION_EXPIRED");
+}
+
+#[tokio::test]
+async fn test_renew_file_reservation_impl_ttl_over_cap() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+
+    let (project_slug, agent_name) = setup_project_with_agent(&mm, "renew_cap").await;
+    reserve_paths(&mm, &project_slug, &agent_name, &["src/cap.rs"]).await;
+
+    let max_ttl = AppConfig::default().reservations.max_ttl_seconds as i64;
+    let params = RenewFileReservationParams {
+        project_slug,
+        agent_name,
+        reservation_id: None,
+        path_pattern: Some("src/cap.rs".to_string()),
+        ttl_seconds: Some(max_ttl + 60),
+    };
+
+    let err = files::renew_file_reservation_impl(&ctx, &mm, params)
+        .await
+        .unwrap_err();
+    let data = err.data.unwrap();
+    assert_eq!(data["error_code"], "INVALID_TTL");
+}
+
+#[tokio::test]
+async fn test_renew_file_reservation_impl_unknown_path() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+
+    let (project_slug, agent_name) = setup_project_with_agent(&mm, "renew_unknown").await;
+
+    let params = RenewFileReservationParams {
+        project_slug,
+        agent_name,
+        reservation_id: None,
+        path_pattern: Some("src/never.rs".to_string()),
+        ttl_seconds: None,
+    };
+
+    let err = files::renew_file_reservation_impl(&ctx, &mm, params)
+        .await
+        .unwrap_err();
+    let data = err.data.unwrap();
+    assert_eq!(data["error_code"], "RESERVATION_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn test_transfer_reservation_impl_success() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+
+    let (project_slug, agent_name) = setup_project_with_agent(&mm, "transfer").await;
+    let project = ProjectBmc::get_by_slug(&ctx, &mm, &project_slug)
+        .await
+        .unwrap();
+    let agent_c = AgentForCreate {
+        project_id: project.id,
+        name: "rescuer".to_string(),
+        program: "claude".to_string(),
+        model: "opus".to_string(),
+        task_description: "Picks up abandoned work".to_string(),
+    };
+    AgentBmc::create(&ctx, &mm, agent_c).await.unwrap();
+    reserve_paths(&mm, &project_slug, &agent_name, &["src/stuck.rs"]).await;
+    let reservation = FileReservationBmc::list_active_for_project(&ctx, &mm, project.id)
+        .await
+        .unwrap()
+        .remove(0);
+
+    let params = TransferReservationParams {
+        project_slug: project_slug.clone(),
+        reservation_id: reservation.id,
+        to_agent: "rescuer".to_string(),
+        reason: "Holder crashed".to_string(),
+    };
+    let result = files::transfer_reservation_impl(&ctx, &mm, params)
+        .await
+        .unwrap();
+    let output: serde_json::Value =
+        serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap();
+    assert_eq!(output["from_agent"], agent_name);
+    assert_eq!(output["to_agent"], "rescuer");
+    assert_eq!(output["transferred"], true);
+
+    // Unknown reservations are reported, not passed through as internal errors
+    let params = TransferReservationParams {
+        project_slug,
+        reservation_id: reservation.id + 1_000,
+        to_agent: "rescuer".to_string(),
+        reason: "Holder crashed".to_string(),
+    };
+    let err = files::transfer_reservation_impl(&ctx, &mm, params)
+        .await
+        .unwrap_err();
+    let data = err.data.unwrap();
+    assert_eq!(data["error_code"], "RESERVATION_NOT_FOUND");
+}
@@ -55,6 +55,9 @@ async fn test_mcp_resource_api_schemes() -> anyhow::Result<()> {
             thread_id: Some("T1".to_string()),
             importance: Some("normal".to_string()),
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: true,
         },
     )
     .await?;
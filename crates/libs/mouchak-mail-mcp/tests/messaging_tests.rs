@@ -20,9 +20,11 @@ use mouchak_mail_core::model::{
 };
 use mouchak_mail_mcp::tools::messaging;
 use mouchak_mail_mcp::tools::{
-    AcknowledgeMessageParams, GetMessageParams, GetThreadParams, ListInboxParams,
-    ListThreadsParams, MarkMessageReadParams, ReplyMessageParams, SearchMessagesParams,
-    SendMessageParams,
+    AcknowledgeMessageParams, CancelScheduledParams, ForwardMessageParams, GetMessageParams,
+    GetThreadParams, LabelMessageParams, ListInboxParams, ListPendingAcksParams,
+    ListScheduledParams, ListSnoozedParams, ListThreadsParams, MarkMessageReadParams,
+    MuteThreadParams, RecallMessageParams, ReplyMessageParams, SearchMessagesParams,
+    SendMessageParams, SnoozeMessageParams, WaitForMessagesParams,
 };
 use std::sync::Arc;
 use tempfile::TempDir;
@@ -37,14 +39,9 @@ async fn create_test_mm() -> (Arc<ModelManager>, TempDir) {
     let conn = db.connect().unwrap();
     let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
 
-    let schema1 = include_str!("../../../../migrations/001_initial_schema.sql");
-    conn.execute_batch(schema1).await.unwrap();
-    let schema2 = include_str!("../../../../migrations/002_agent_capabilities.sql");
-    conn.execute_batch(schema2).await.unwrap();
-    let schema3 = include_str!("../../../../migrations/003_tool_metrics.sql");
-    conn.execute_batch(schema3).await.unwrap();
-    let schema4 = include_str!("../../../../migrations/004_attachments.sql");
-    conn.execute_batch(schema4).await.unwrap();
+    mouchak_mail_core::store::apply_migrations(&conn)
+        .await
+        .unwrap();
 
     let app_config = Arc::new(AppConfig::default());
     let mm = ModelManager::new_for_test(conn, archive_root, app_config);
@@ -137,8 +134,12 @@ async fn test_send_message_impl_success() {
         subject: "Test Subject".to_string(),
         body_md: "This is a test message body.".to_string(),
         thread_id: Some("THREAD-001".to_string()),
+        allow_new_thread: Some(true),
         importance: Some("high".to_string()),
         ack_required: Some(true),
+        deliver_at: None,
+        broadcast: None,
+        allow_partial: None,
     };
 
     let result = messaging::send_message_impl(&ctx, &mm, params).await;
@@ -150,6 +151,149 @@ async fn test_send_message_impl_success() {
     assert!(text.contains("Test Subject"));
 }
 
+#[tokio::test]
+async fn test_send_message_impl_agent_key_cannot_impersonate() {
+    let (mm, _temp) = create_test_mm().await;
+    let (project_id, sender_id, receiver_id, project_slug) = setup_project_and_agents(&mm).await;
+
+    // Bound to receiver_agent, as an AgentKey request would be
+    let ctx = Ctx::for_agent(receiver_id.into(), "receiver_agent", &project_slug);
+    let params = |sender: &str, to: &str| SendMessageParams {
+        project_slug: project_slug.clone(),
+        sender_name: sender.to_string(),
+        to: to.to_string(),
+        cc: None,
+        bcc: None,
+        subject: "Test Subject".to_string(),
+        body_md: "Body".to_string(),
+        thread_id: None,
+        allow_new_thread: None,
+        importance: None,
+        ack_required: None,
+        deliver_at: None,
+        broadcast: None,
+        allow_partial: None,
+    };
+
+    let err = messaging::send_message_impl(&ctx, &mm, params("sender_agent", "receiver_agent"))
+        .await
+        .unwrap_err();
+    assert_eq!(err.data.unwrap()["error_code"], "AGENT_IDENTITY_MISMATCH");
+
+    let result =
+        messaging::send_message_impl(&ctx, &mm, params("receiver_agent", "sender_agent")).await;
+    assert!(result.is_ok(), "sending as the bound agent should succeed");
+
+    // The rejected send left nothing in sender_agent's outbox
+    let outbox =
+        MessageBmc::list_outbox_for_agent(&Ctx::root_ctx(), &mm, project_id, sender_id, 10, None)
+            .await
+            .unwrap();
+    assert!(outbox.is_empty());
+}
+
+#[tokio::test]
+async fn test_send_message_impl_scheduled() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+    let (project_id, sender_id, receiver_id, project_slug) = setup_project_and_agents(&mm).await;
+
+    let params = SendMessageParams {
+        project_slug: project_slug.clone(),
+        sender_name: "sender_agent".to_string(),
+        to: "receiver_agent".to_string(),
+        cc: None,
+        bcc: None,
+        subject: "Standup Reminder".to_string(),
+        body_md: "Post your update.".to_string(),
+        thread_id: None,
+        allow_new_thread: None,
+        importance: None,
+        ack_required: None,
+        deliver_at: Some("2099-01-01T09:30:00Z".to_string()),
+        broadcast: None,
+        allow_partial: None,
+    };
+    let result = messaging::send_message_impl(&ctx, &mm, params).await;
+    let text = format!("{:?}", result.unwrap());
+    assert!(text.contains("Message scheduled"));
+    assert!(text.contains("2099-01-01 09:30:00"));
+
+    let inbox = MessageBmc::list_inbox_for_agent(&ctx, &mm, project_id, receiver_id, 10)
+        .await
+        .unwrap();
+    assert!(
+        inbox.is_empty(),
+        "Scheduled message must not be delivered yet"
+    );
+
+    let params = ListScheduledParams {
+        project_slug: project_slug.clone(),
+        agent_name: "sender_agent".to_string(),
+    };
+    let text = format!(
+        "{:?}",
+        messaging::list_scheduled_impl(&ctx, &mm, params)
+            .await
+            .unwrap()
+    );
+    assert!(text.contains("Standup Reminder"));
+    assert!(text.contains("receiver_agent"));
+
+    let scheduled = MessageBmc::list_scheduled(&ctx, &mm, project_id, sender_id)
+        .await
+        .unwrap();
+    let message_id = scheduled[0].message.id;
+
+    let params = CancelScheduledParams {
+        project_slug: project_slug.clone(),
+        agent_name: "receiver_agent".to_string(),
+        message_id,
+    };
+    let result = messaging::cancel_scheduled_impl(&ctx, &mm, params).await;
+    assert!(format!("{:?}", result).contains("NOT_MESSAGE_SENDER"));
+
+    let params = CancelScheduledParams {
+        project_slug: project_slug.clone(),
+        agent_name: "sender_agent".to_string(),
+        message_id,
+    };
+    let text = format!(
+        "{:?}",
+        messaging::cancel_scheduled_impl(&ctx, &mm, params)
+            .await
+            .unwrap()
+    );
+    assert!(text.contains("cancelled"));
+}
+
+#[tokio::test]
+async fn test_send_message_impl_invalid_deliver_at() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+    let (_, _, _, project_slug) = setup_project_and_agents(&mm).await;
+
+    let params = SendMessageParams {
+        project_slug,
+        sender_name: "sender_agent".to_string(),
+        to: "receiver_agent".to_string(),
+        cc: None,
+        bcc: None,
+        subject: "Bad Time".to_string(),
+        body_md: "Body".to_string(),
+        thread_id: None,
+        allow_new_thread: None,
+        importance: None,
+        ack_required: None,
+        deliver_at: Some("in 30 minutes".to_string()),
+        broadcast: None,
+        allow_partial: None,
+    };
+    let result = messaging::send_message_impl(&ctx, &mm, params).await;
+    assert!(result.is_err());
+    assert!(format!("{:?}", result).contains("INVALID_INPUT"));
+}
+
 #[tokio::test]
 async fn test_send_message_impl_with_cc_bcc() {
     let (mm, _temp) = create_test_mm().await;
@@ -183,14 +327,124 @@ async fn test_send_message_impl_with_cc_bcc() {
         subject: "CC/BCC Test".to_string(),
         body_md: "Testing CC and BCC.".to_string(),
         thread_id: None,
+        allow_new_thread: None,
         importance: None,
         ack_required: None,
+        deliver_at: None,
+        broadcast: None,
+        allow_partial: None,
     };
 
     let result = messaging::send_message_impl(&ctx, &mm, params).await;
     assert!(result.is_ok());
 }
 
+#[tokio::test]
+async fn test_send_message_impl_allow_partial() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+    let (project_id, _, receiver_id, project_slug) = setup_project_and_agents(&mm).await;
+
+    let params = |allow_partial: Option<bool>| SendMessageParams {
+        project_slug: project_slug.clone(),
+        sender_name: "sender_agent".to_string(),
+        to: "receiver_agent, ghost_agent".to_string(),
+        cc: Some("nowhere::agent".to_string()),
+        bcc: None,
+        subject: "Partial".to_string(),
+        body_md: "Some of you will get this.".to_string(),
+        thread_id: None,
+        allow_new_thread: None,
+        importance: None,
+        ack_required: None,
+        deliver_at: None,
+        broadcast: None,
+        allow_partial,
+    };
+
+    // Atomic by default: the first unknown recipient fails the send
+    let err = messaging::send_message_impl(&ctx, &mm, params(None))
+        .await
+        .unwrap_err();
+    assert_eq!(err.data.unwrap()["error_code"], "AGENT_NOT_FOUND");
+    let inbox = MessageBmc::list_inbox_for_agent(&ctx, &mm, project_id, receiver_id, 10)
+        .await
+        .unwrap();
+    assert!(inbox.is_empty());
+
+    let result = messaging::send_message_impl(&ctx, &mm, params(Some(true)))
+        .await
+        .unwrap();
+    let report: serde_json::Value =
+        serde_json::from_str(&result.content[1].as_text().unwrap().text).unwrap();
+    assert_eq!(report["delivered"], serde_json::json!(["receiver_agent"]));
+    assert_eq!(report["partial_success"], true);
+    assert_eq!(report["failed"][0]["name"], "ghost_agent");
+    assert_eq!(report["failed"][0]["code"], "AGENT_NOT_FOUND");
+    assert_eq!(report["failed"][1]["name"], "nowhere::agent");
+    assert_eq!(report["failed"][1]["code"], "PROJECT_NOT_FOUND");
+    let inbox = MessageBmc::list_inbox_for_agent(&ctx, &mm, project_id, receiver_id, 10)
+        .await
+        .unwrap();
+    assert_eq!(inbox.len(), 1);
+    assert_eq!(report["message_id"], inbox[0].id);
+
+    // Nothing resolves: rejected even with allow_partial
+    let mut none = params(Some(true));
+    none.to = "ghost_agent".to_string();
+    none.cc = None;
+    let err = messaging::send_message_impl(&ctx, &mm, none)
+        .await
+        .unwrap_err();
+    let data = err.data.unwrap();
+    assert_eq!(data["error_code"], "INVALID_RECIPIENT");
+    assert_eq!(data["failed"][0]["name"], "ghost_agent");
+}
+
+#[tokio::test]
+async fn test_send_message_impl_unknown_thread() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+    let (project_id, _, _, project_slug) = setup_project_and_agents(&mm).await;
+
+    let params = |thread_id: &str, allow_new_thread: Option<bool>| SendMessageParams {
+        project_slug: project_slug.clone(),
+        sender_name: "sender_agent".to_string(),
+        to: "receiver_agent".to_string(),
+        cc: None,
+        bcc: None,
+        subject: "Migration".to_string(),
+        body_md: "Status update.".to_string(),
+        thread_id: Some(thread_id.to_string()),
+        allow_new_thread,
+        importance: None,
+        ack_required: None,
+        deliver_at: None,
+        broadcast: None,
+        allow_partial: None,
+    };
+
+    messaging::send_message_impl(&ctx, &mm, params("MIGRATE-1", Some(true)))
+        .await
+        .unwrap();
+    messaging::send_message_impl(&ctx, &mm, params("MIGRATE-1", None))
+        .await
+        .unwrap();
+
+    // A typo is refused rather than silently starting a new thread
+    let err = messaging::send_message_impl(&ctx, &mm, params("MIGRATE-l", None))
+        .await
+        .unwrap_err();
+    let data = err.data.unwrap();
+    assert_eq!(data["error_code"], "THREAD_NOT_FOUND");
+    assert_eq!(data["thread_id"], "MIGRATE-l");
+
+    let thread = MessageBmc::list_by_thread(&ctx, &mm, project_id, "MIGRATE-1")
+        .await
+        .unwrap();
+    assert_eq!(thread.len(), 2);
+}
+
 #[tokio::test]
 async fn test_send_message_impl_without_capability() {
     let (mm, _temp) = create_test_mm().await;
@@ -215,8 +469,12 @@ async fn test_send_message_impl_without_capability() {
         subject: "Should Fail".to_string(),
         body_md: "This should fail.".to_string(),
         thread_id: None,
+        allow_new_thread: None,
         importance: None,
         ack_required: None,
+        deliver_at: None,
+        broadcast: None,
+        allow_partial: None,
     };
 
     let result = messaging::send_message_impl(&ctx, &mm, params).await;
@@ -242,8 +500,12 @@ async fn test_send_message_impl_invalid_project() {
         subject: "Test".to_string(),
         body_md: "Test".to_string(),
         thread_id: None,
+        allow_new_thread: None,
         importance: None,
         ack_required: None,
+        deliver_at: None,
+        broadcast: None,
+        allow_partial: None,
     };
 
     let result = messaging::send_message_impl(&ctx, &mm, params).await;
@@ -267,6 +529,9 @@ async fn test_list_inbox_impl_success() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
 
@@ -277,6 +542,8 @@ async fn test_list_inbox_impl_success() {
         urgent_only: None,
         since_ts: None,
         include_bodies: None,
+        label: None,
+        sort: None,
     };
 
     let result = messaging::list_inbox_impl(&ctx, &mm, params).await;
@@ -287,6 +554,421 @@ async fn test_list_inbox_impl_success() {
     assert!(text.contains("Inbox Test"));
 }
 
+#[tokio::test]
+async fn test_list_inbox_impl_sort() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+    let (project_id, sender_id, receiver_id, project_slug) = setup_project_and_agents(&mm).await;
+
+    for (subject, importance) in [("Routine", "normal"), ("Fire", "urgent"), ("Later", "low")] {
+        let msg_c = MessageForCreate {
+            project_id,
+            sender_id,
+            recipient_ids: vec![receiver_id],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: subject.to_string(),
+            body_md: "Body".to_string(),
+            thread_id: None,
+            importance: Some(importance.to_string()),
+            ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
+        };
+        MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
+    }
+
+    let params = |sort: &str| ListInboxParams {
+        project_slug: project_slug.clone(),
+        agent_name: "receiver_agent".to_string(),
+        limit: None,
+        urgent_only: None,
+        since_ts: None,
+        include_bodies: None,
+        label: None,
+        sort: Some(sort.to_string()),
+    };
+
+    let result = messaging::list_inbox_impl(&ctx, &mm, params("importance_desc"))
+        .await
+        .unwrap();
+    let text = format!("{:?}", result);
+    let fire = text.find("Fire").unwrap();
+    let routine = text.find("Routine").unwrap();
+    let later = text.find("Later").unwrap();
+    assert!(fire < routine && routine < later, "{}", text);
+
+    let err = messaging::list_inbox_impl(&ctx, &mm, params("loudest"))
+        .await
+        .unwrap_err();
+    assert!(err.message.contains("Invalid sort 'loudest'"));
+}
+
+#[tokio::test]
+async fn test_send_message_impl_cross_project() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+    let (_, _, _, project_slug) = setup_project_and_agents(&mm).await;
+
+    let peer_project = ProjectBmc::create(&ctx, &mm, "peer-project", "Peer Project")
+        .await
+        .unwrap();
+    let peer_c = AgentForCreate {
+        project_id: peer_project,
+        name: "peer_agent".to_string(),
+        program: "claude".to_string(),
+        model: "sonnet".to_string(),
+        task_description: "Peer agent".to_string(),
+    };
+    let peer_id = AgentBmc::create(&ctx, &mm, peer_c).await.unwrap();
+    let cap_inbox = AgentCapabilityForCreate {
+        agent_id: peer_id.into(),
+        capability: "fetch_inbox".to_string(),
+        granted_by: None,
+        expires_at: None,
+    };
+    AgentCapabilityBmc::create(&ctx, &mm, cap_inbox)
+        .await
+        .unwrap();
+
+    let params = || SendMessageParams {
+        project_slug: project_slug.clone(),
+        sender_name: "sender_agent".to_string(),
+        to: "peer-project::peer_agent".to_string(),
+        cc: None,
+        bcc: None,
+        subject: "Cross Project".to_string(),
+        body_md: "Hello, neighbour.".to_string(),
+        thread_id: None,
+        allow_new_thread: None,
+        importance: None,
+        ack_required: None,
+        deliver_at: None,
+        broadcast: None,
+        allow_partial: None,
+    };
+
+    // A context scoped to the sender's project is not in allowed_peers
+    let scoped = Ctx::scoped(0, None, vec![project_slug.clone()]);
+    let err = messaging::send_message_impl(&scoped, &mm, params())
+        .await
+        .unwrap_err();
+    assert_eq!(err.data.unwrap()["error_code"], "FORBIDDEN_CROSS_PROJECT");
+
+    messaging::send_message_impl(&ctx, &mm, params())
+        .await
+        .unwrap();
+
+    let inbox = ListInboxParams {
+        project_slug: "peer-project".to_string(),
+        agent_name: "peer_agent".to_string(),
+        limit: Some(10),
+        urgent_only: None,
+        since_ts: None,
+        include_bodies: None,
+        label: None,
+        sort: None,
+    };
+    let text = format!(
+        "{:?}",
+        messaging::list_inbox_impl(&ctx, &mm, inbox).await.unwrap()
+    );
+    assert!(text.contains("Cross Project"));
+    assert!(text.contains("from: test-messaging-project::sender_agent"));
+}
+
+#[tokio::test]
+async fn test_label_message_impl() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+    let (project_id, sender_id, receiver_id, project_slug) = setup_project_and_agents(&mm).await;
+
+    let msg_c = MessageForCreate {
+        project_id,
+        sender_id,
+        recipient_ids: vec![receiver_id],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: "Label Test".to_string(),
+        body_md: "Please review.".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    let message_id = MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
+
+    let label = |add: &[&str], remove: &[&str], create_missing| LabelMessageParams {
+        project_slug: project_slug.clone(),
+        agent_name: "receiver_agent".to_string(),
+        message_id,
+        add: add.iter().map(|s| s.to_string()).collect(),
+        remove: remove.iter().map(|s| s.to_string()).collect(),
+        create_missing,
+    };
+
+    // Unknown labels are created on first use by default
+    let result = messaging::label_message_impl(&ctx, &mm, label(&["needs-review"], &[], None))
+        .await
+        .unwrap();
+    assert!(format!("{:?}", result).contains("labels: needs-review"));
+
+    let result =
+        messaging::label_message_impl(&ctx, &mm, label(&["blocked"], &[], Some(false))).await;
+    assert!(result.is_err());
+
+    let result = messaging::label_message_impl(&ctx, &mm, label(&[], &["NEEDS-REVIEW"], None))
+        .await
+        .unwrap();
+    assert!(format!("{:?}", result).contains("has no labels"));
+
+    // The label still exists, so the inbox can filter on it once re-applied
+    messaging::label_message_impl(&ctx, &mm, label(&["needs-review"], &[], Some(false)))
+        .await
+        .unwrap();
+    let params = ListInboxParams {
+        project_slug: project_slug.clone(),
+        agent_name: "receiver_agent".to_string(),
+        limit: Some(10),
+        urgent_only: None,
+        since_ts: None,
+        include_bodies: None,
+        label: Some("Needs-Review".to_string()),
+        sort: None,
+    };
+    let text = format!(
+        "{:?}",
+        messaging::list_inbox_impl(&ctx, &mm, params).await.unwrap()
+    );
+    assert!(text.contains("Label Test"));
+}
+
+#[tokio::test]
+async fn test_mute_thread_impl() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+    let (project_id, sender_id, receiver_id, project_slug) = setup_project_and_agents(&mm).await;
+
+    let msg_c = MessageForCreate {
+        project_id,
+        sender_id,
+        recipient_ids: vec![receiver_id],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: "Noisy Update".to_string(),
+        body_md: "More chatter.".to_string(),
+        thread_id: Some("noisy-thread".to_string()),
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
+    };
+    MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
+
+    let mute = |thread_id: &str| MuteThreadParams {
+        project_slug: project_slug.clone(),
+        agent_name: "receiver_agent".to_string(),
+        thread_id: thread_id.to_string(),
+    };
+    let inbox = || ListInboxParams {
+        project_slug: project_slug.clone(),
+        agent_name: "receiver_agent".to_string(),
+        limit: Some(10),
+        urgent_only: None,
+        since_ts: None,
+        include_bodies: None,
+        label: None,
+        sort: None,
+    };
+
+    let result = messaging::mute_thread_impl(&ctx, &mm, mute("noisy-thread"))
+        .await
+        .unwrap();
+    assert!(format!("{:?}", result).contains("muted for 'receiver_agent'"));
+    let text = format!(
+        "{:?}",
+        messaging::list_inbox_impl(&ctx, &mm, inbox())
+            .await
+            .unwrap()
+    );
+    assert!(!text.contains("Noisy Update"));
+
+    let result = messaging::unmute_thread_impl(&ctx, &mm, mute("noisy-thread"))
+        .await
+        .unwrap();
+    assert!(format!("{:?}", result).contains("unmuted"));
+    let text = format!(
+        "{:?}",
+        messaging::list_inbox_impl(&ctx, &mm, inbox())
+            .await
+            .unwrap()
+    );
+    assert!(text.contains("Noisy Update"));
+
+    let err = messaging::mute_thread_impl(&ctx, &mm, mute("missing-thread"))
+        .await
+        .unwrap_err();
+    assert!(err.message.contains("Thread 'missing-thread' not found"));
+}
+
+#[tokio::test]
+async fn test_snooze_message_impl() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+    let (project_id, sender_id, receiver_id, project_slug) = setup_project_and_agents(&mm).await;
+
+    let msg_c = MessageForCreate {
+        project_id,
+        sender_id,
+        recipient_ids: vec![receiver_id],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: "Review After Deploy".to_string(),
+        body_md: "No rush.".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
+    };
+    let message_id = MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
+
+    let snooze = |agent_name: &str, until: Option<&str>| SnoozeMessageParams {
+        project_slug: project_slug.clone(),
+        agent_name: agent_name.to_string(),
+        message_id,
+        until: until.map(str::to_string),
+    };
+    let inbox = || ListInboxParams {
+        project_slug: project_slug.clone(),
+        agent_name: "receiver_agent".to_string(),
+        limit: Some(10),
+        urgent_only: None,
+        since_ts: None,
+        include_bodies: None,
+        label: None,
+        sort: None,
+    };
+
+    let result = messaging::snooze_message_impl(
+        &ctx,
+        &mm,
+        snooze("receiver_agent", Some("2099-01-01T09:00:00Z")),
+    )
+    .await
+    .unwrap();
+    assert!(format!("{:?}", result).contains("until 2099-01-01 09:00:00 UTC"));
+    let text = format!(
+        "{:?}",
+        messaging::list_inbox_impl(&ctx, &mm, inbox())
+            .await
+            .unwrap()
+    );
+    assert!(!text.contains("Review After Deploy"));
+
+    let params = ListSnoozedParams {
+        project_slug: project_slug.clone(),
+        agent_name: "receiver_agent".to_string(),
+    };
+    let text = format!(
+        "{:?}",
+        messaging::list_snoozed_impl(&ctx, &mm, params)
+            .await
+            .unwrap()
+    );
+    assert!(text.contains("Review After Deploy"));
+    assert!(text.contains("back at: 2099-01-01 09:00:00 UTC"));
+
+    let result = messaging::snooze_message_impl(&ctx, &mm, snooze("receiver_agent", None))
+        .await
+        .unwrap();
+    assert!(format!("{:?}", result).contains("unsnoozed"));
+    let text = format!(
+        "{:?}",
+        messaging::list_inbox_impl(&ctx, &mm, inbox())
+            .await
+            .unwrap()
+    );
+    assert!(text.contains("Review After Deploy"));
+
+    let err = messaging::snooze_message_impl(
+        &ctx,
+        &mm,
+        snooze("sender_agent", Some("2099-01-01T09:00:00Z")),
+    )
+    .await
+    .unwrap_err();
+    assert!(format!("{:?}", err).contains("MESSAGE_NOT_FOUND"));
+
+    let err = messaging::snooze_message_impl(&ctx, &mm, snooze("receiver_agent", Some("later")))
+        .await
+        .unwrap_err();
+    assert!(err.message.contains("Invalid until timestamp"));
+}
+
+#[tokio::test]
+async fn test_list_pending_acks_impl() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+    let (project_id, sender_id, receiver_id, project_slug) = setup_project_and_agents(&mm).await;
+
+    let mut ids = Vec::new();
+    for (subject, ack_required) in [("Confirm Schema", true), ("FYI Only", false)] {
+        let msg_c = MessageForCreate {
+            project_id,
+            sender_id,
+            recipient_ids: vec![receiver_id],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: subject.to_string(),
+            body_md: "Please confirm.".to_string(),
+            thread_id: None,
+            importance: None,
+            ack_required,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: true,
+        };
+        ids.push(MessageBmc::create(&ctx, &mm, msg_c).await.unwrap());
+    }
+
+    let pending = || ListPendingAcksParams {
+        project_slug: project_slug.clone(),
+        agent_name: "receiver_agent".to_string(),
+    };
+    let text = format!(
+        "{:?}",
+        messaging::list_pending_acks_impl(&ctx, &mm, pending())
+            .await
+            .unwrap()
+    );
+    assert!(text.contains("Pending acknowledgements for 'receiver_agent' (1)"));
+    assert!(text.contains("Confirm Schema (from: sender_agent, normal, 0 day(s) outstanding)"));
+    assert!(!text.contains("FYI Only"));
+
+    let ack = AcknowledgeMessageParams {
+        project_slug: project_slug.clone(),
+        agent_name: "receiver_agent".to_string(),
+        message_id: ids[0],
+    };
+    messaging::acknowledge_message_impl(&ctx, &mm, ack)
+        .await
+        .unwrap();
+    let text = format!(
+        "{:?}",
+        messaging::list_pending_acks_impl(&ctx, &mm, pending())
+            .await
+            .unwrap()
+    );
+    assert!(text.contains("(0)"));
+    assert!(!text.contains("Confirm Schema"));
+}
+
 #[tokio::test]
 async fn test_list_inbox_impl_empty() {
     let (mm, _temp) = create_test_mm().await;
@@ -300,6 +982,8 @@ async fn test_list_inbox_impl_empty() {
         urgent_only: None,
         since_ts: None,
         include_bodies: None,
+        label: None,
+        sort: None,
     };
 
     let result = messaging::list_inbox_impl(&ctx, &mm, params).await;
@@ -309,6 +993,52 @@ async fn test_list_inbox_impl_empty() {
     assert!(text.contains("0 messages"));
 }
 
+#[tokio::test]
+async fn test_wait_for_messages_impl_wakes_on_send() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+    let (project_id, sender_id, receiver_id, project_slug) = setup_project_and_agents(&mm).await;
+
+    let wait = |timeout_seconds| WaitForMessagesParams {
+        project_slug: project_slug.clone(),
+        agent_name: "receiver_agent".to_string(),
+        timeout_seconds: Some(timeout_seconds),
+        since_message_id: None,
+    };
+
+    let (result, _) = tokio::join!(
+        messaging::wait_for_messages_impl(&ctx, &mm, wait(30)),
+        async {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            let msg_c = MessageForCreate {
+                project_id,
+                sender_id,
+                recipient_ids: vec![receiver_id],
+                cc_ids: None,
+                bcc_ids: None,
+                subject: "Wake Up".to_string(),
+                body_md: "New work for you.".to_string(),
+                thread_id: None,
+                importance: None,
+                ack_required: false,
+                deliver_at: None,
+                broadcast: false,
+                allow_new_thread: false,
+            };
+            MessageBmc::create(&ctx, &mm, msg_c).await.unwrap()
+        }
+    );
+    let text = format!("{:?}", result.unwrap());
+    assert!(text.contains("New messages for 'receiver_agent' (1 messages)"));
+    assert!(text.contains("Wake Up"));
+
+    // Nothing newer arrives: the wait ends empty
+    let result = messaging::wait_for_messages_impl(&ctx, &mm, wait(0))
+        .await
+        .unwrap();
+    assert!(format!("{:?}", result).contains("No new messages for 'receiver_agent'"));
+}
+
 #[tokio::test]
 async fn test_list_inbox_impl_without_capability() {
     let (mm, _temp) = create_test_mm().await;
@@ -322,6 +1052,8 @@ async fn test_list_inbox_impl_without_capability() {
         urgent_only: None,
         since_ts: None,
         include_bodies: None,
+        label: None,
+        sort: None,
     };
 
     let result = messaging::list_inbox_impl(&ctx, &mm, params).await;
@@ -350,6 +1082,9 @@ async fn test_get_message_impl_success() {
         thread_id: Some("THREAD-GET".to_string()),
         importance: Some("normal".to_string()),
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
     let msg_id = MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
 
@@ -394,6 +1129,9 @@ async fn test_search_messages_impl_success() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
 
@@ -447,6 +1185,9 @@ async fn test_get_thread_impl_success() {
             thread_id: Some("THREAD-TEST".to_string()),
             importance: None,
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: true,
         };
         MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
     }
@@ -499,6 +1240,9 @@ async fn test_reply_message_impl_success() {
         thread_id: Some("REPLY-THREAD".to_string()),
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
     let original_msg_id = MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
 
@@ -539,6 +1283,9 @@ async fn test_reply_message_impl_no_thread_id() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let original_msg_id = MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
 
@@ -579,6 +1326,9 @@ async fn test_reply_message_impl_already_has_re_prefix() {
         thread_id: Some("RE-THREAD".to_string()),
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
     let msg_id = MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
 
@@ -643,6 +1393,9 @@ async fn test_mark_message_read_impl_success() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
 
@@ -677,6 +1430,9 @@ async fn test_acknowledge_message_impl_success() {
         thread_id: None,
         importance: None,
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
 
@@ -694,6 +1450,107 @@ async fn test_acknowledge_message_impl_success() {
     assert!(text.contains("receiver_agent"));
 }
 
+#[tokio::test]
+async fn test_recall_message_impl() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+    let (project_id, sender_id, receiver_id, project_slug) = setup_project_and_agents(&mm).await;
+
+    let msg_c = MessageForCreate {
+        project_id,
+        sender_id,
+        recipient_ids: vec![receiver_id],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: "Wrong Directive".to_string(),
+        body_md: "Delete the staging database.".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    let msg_id = MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
+
+    // Recipients cannot recall
+    let params = RecallMessageParams {
+        project_slug: project_slug.clone(),
+        agent_name: "receiver_agent".to_string(),
+        message_id: msg_id,
+        reason: "Not mine".to_string(),
+    };
+    let result = messaging::recall_message_impl(&ctx, &mm, params).await;
+    assert!(result.is_err());
+    assert!(format!("{:?}", result).contains("NOT_MESSAGE_SENDER"));
+
+    let params = RecallMessageParams {
+        project_slug: project_slug.clone(),
+        agent_name: "sender_agent".to_string(),
+        message_id: msg_id,
+        reason: "Sent by mistake".to_string(),
+    };
+    let result = messaging::recall_message_impl(&ctx, &mm, params).await;
+    assert!(result.is_ok());
+    let text = format!("{:?}", result.unwrap());
+    assert!(text.contains("recalled"));
+    assert!(text.contains("Sent by mistake"));
+
+    let msg = MessageBmc::get(&ctx, &mm, msg_id).await.unwrap();
+    assert_eq!(msg.subject, "[Recalled] Wrong Directive");
+    assert_eq!(msg.body_md, "Sent by mistake");
+}
+
+#[tokio::test]
+async fn test_forward_message_impl() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+    let (project_id, sender_id, receiver_id, project_slug) = setup_project_and_agents(&mm).await;
+
+    let msg_c = MessageForCreate {
+        project_id,
+        sender_id,
+        recipient_ids: vec![receiver_id],
+        cc_ids: None,
+        bcc_ids: None,
+        subject: "Deploy Window".to_string(),
+        body_md: "Deploys freeze at 17:00.".to_string(),
+        thread_id: None,
+        importance: None,
+        ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
+    };
+    let msg_id = MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
+
+    let forward = |note: Option<&str>| ForwardMessageParams {
+        project_slug: project_slug.clone(),
+        sender_name: "receiver_agent".to_string(),
+        message_id: msg_id,
+        to: "sender_agent".to_string(),
+        note: note.map(str::to_string),
+    };
+    let result = messaging::forward_message_impl(&ctx, &mm, forward(Some("Heads up"))).await;
+    assert!(result.is_ok());
+    assert!(format!("{:?}", result.unwrap()).contains(&format!("Message {} forwarded", msg_id)));
+
+    let mut inbox = MessageBmc::list_inbox_for_agent(&ctx, &mm, project_id, sender_id, 10)
+        .await
+        .unwrap();
+    MessageBmc::load_bodies(&mm, &mut inbox).await.unwrap();
+    let forwarded = inbox.first().unwrap();
+    assert_eq!(forwarded.subject, "Fwd: Deploy Window");
+    assert_eq!(forwarded.forwarded_from_id, Some(msg_id));
+    assert!(forwarded.body_md.contains("> Deploys freeze at 17:00."));
+
+    MessageBmc::recall(&ctx, &mm, msg_id, sender_id, "Freeze moved")
+        .await
+        .unwrap();
+    let result = messaging::forward_message_impl(&ctx, &mm, forward(None)).await;
+    assert!(format!("{:?}", result.unwrap_err()).contains("MESSAGE_RECALLED"));
+}
+
 #[tokio::test]
 async fn test_acknowledge_message_impl_without_capability() {
     let (mm, _temp) = create_test_mm().await;
@@ -711,6 +1568,9 @@ async fn test_acknowledge_message_impl_without_capability() {
         thread_id: None,
         importance: None,
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
 
@@ -747,6 +1607,9 @@ async fn test_list_threads_impl_success() {
             thread_id: Some(format!("THREAD-{}", i)),
             importance: None,
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: true,
         };
         MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
     }
@@ -807,8 +1670,12 @@ async fn test_send_message_impl_multiple_recipients() {
         subject: "Multi-recipient".to_string(),
         body_md: "Sent to multiple.".to_string(),
         thread_id: None,
+        allow_new_thread: None,
         importance: None,
         ack_required: None,
+        deliver_at: None,
+        broadcast: None,
+        allow_partial: None,
     };
 
     let result = messaging::send_message_impl(&ctx, &mm, params).await;
@@ -843,6 +1710,9 @@ async fn test_reply_message_impl_without_capability() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     let msg_id = MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
 
@@ -880,6 +1750,8 @@ async fn test_list_inbox_impl_invalid_project() {
         urgent_only: None,
         since_ts: None,
         include_bodies: None,
+        label: None,
+        sort: None,
     };
 
     let result = messaging::list_inbox_impl(&ctx, &mm, params).await;
@@ -27,6 +27,10 @@ fn extract_text(result: &rmcp::model::CallToolResult) -> String {
 }
 
 async fn create_test_mm() -> (Arc<ModelManager>, TempDir) {
+    create_test_mm_with_config(AppConfig::default()).await
+}
+
+async fn create_test_mm_with_config(config: AppConfig) -> (Arc<ModelManager>, TempDir) {
     let temp_dir = TempDir::new().expect("Failed to create temp dir");
     let db_path = temp_dir.path().join("test_observability.db");
     let archive_root = temp_dir.path().join("archive");
@@ -36,16 +40,11 @@ async fn create_test_mm() -> (Arc<ModelManager>, TempDir) {
     let conn = db.connect().unwrap();
     let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
 
-    let schema1 = include_str!("../../../../migrations/001_initial_schema.sql");
-    conn.execute_batch(schema1).await.unwrap();
-    let schema2 = include_str!("../../../../migrations/002_agent_capabilities.sql");
-    conn.execute_batch(schema2).await.unwrap();
-    let schema3 = include_str!("../../../../migrations/003_tool_metrics.sql");
-    conn.execute_batch(schema3).await.unwrap();
-    let schema4 = include_str!("../../../../migrations/004_attachments.sql");
-    conn.execute_batch(schema4).await.unwrap();
+    mouchak_mail_core::store::apply_migrations(&conn)
+        .await
+        .unwrap();
 
-    let app_config = Arc::new(AppConfig::default());
+    let app_config = Arc::new(config);
     let mm = ModelManager::new_for_test(conn, archive_root, app_config);
     (Arc::new(mm), temp_dir)
 }
@@ -155,6 +154,52 @@ async fn test_get_tool_stats_impl_with_data() {
     assert!(result.is_ok());
 }
 
+async fn server_info(mm: &Arc<ModelManager>, worktrees_enabled: bool) -> serde_json::Value {
+    let result = observability::get_server_info_impl(&Ctx::root_ctx(), mm, worktrees_enabled)
+        .await
+        .unwrap();
+    serde_json::from_str(&result.content[0].as_text().unwrap().text).unwrap()
+}
+
+#[tokio::test]
+async fn test_get_server_info_impl_defaults() {
+    let (mm, _temp) = create_test_mm().await;
+    let defaults = AppConfig::default();
+
+    let info = server_info(&mm, false).await;
+    assert_eq!(info["version"], env!("CARGO_PKG_VERSION"));
+    assert!(info["schema_version"].is_i64());
+    assert_eq!(info["features"]["worktrees"], false);
+    assert_eq!(info["features"]["quota"], false);
+    assert_eq!(
+        info["limits"]["max_body_bytes"],
+        defaults.messages.max_body_bytes
+    );
+    assert!(info["limits"]["max_attachment_bytes"].is_null());
+}
+
+#[tokio::test]
+async fn test_get_server_info_impl_reflects_config() {
+    let mut config = AppConfig::default();
+    config.quota.enabled = true;
+    config.quota.attachments_limit_bytes = 4096;
+    config.messages.max_body_bytes = 512;
+    config.messages.max_recipients = 3;
+    config.reservations.max_ttl_seconds = 900;
+    config.rate_limit.enabled = false;
+    let (mm, _temp) = create_test_mm_with_config(config).await;
+
+    let info = server_info(&mm, true).await;
+    assert_eq!(info["features"]["worktrees"], true);
+    assert_eq!(info["features"]["quota"], true);
+    assert_eq!(info["features"]["rate_limit"], false);
+    assert_eq!(info["limits"]["max_body_bytes"], 512);
+    assert_eq!(info["limits"]["max_recipients"], 3);
+    assert_eq!(info["limits"]["max_reservation_ttl_seconds"], 900);
+    assert_eq!(info["limits"]["max_attachment_bytes"], 4096);
+    assert!(info["limits"]["rate_limit_rps"].is_null());
+}
+
 #[tokio::test]
 async fn test_list_activity_impl_empty() {
     let (mm, _temp) = create_test_mm().await;
@@ -285,6 +330,9 @@ async fn test_list_pending_reviews_impl_with_ack_required_message() {
         thread_id: None,
         importance: Some("high".to_string()),
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&ctx, &mm, msg).await.unwrap();
 
@@ -352,6 +400,9 @@ async fn test_list_pending_reviews_impl_with_sender_filter() {
         thread_id: None,
         importance: None,
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
     MessageBmc::create(&ctx, &mm, msg).await.unwrap();
 
@@ -364,3 +415,49 @@ async fn test_list_pending_reviews_impl_with_sender_filter() {
     let result = observability::list_pending_reviews_impl(&ctx, &mm, params).await;
     assert!(result.is_ok());
 }
+
+#[tokio::test]
+async fn test_record_tool_metric_failure_status() {
+    let (mm, _temp) = create_test_mm().await;
+    let ctx = Ctx::root_ctx();
+    let service = mouchak_mail_mcp::tools::MouchakMailService::new_with_mm(mm.clone(), false);
+
+    let failed: Result<rmcp::model::CallToolResult, rmcp::ErrorData> =
+        Err(rmcp::ErrorData::invalid_params("Agent not found", None));
+    service
+        .record_tool_metric(
+            "send_message",
+            &None,
+            std::time::Duration::from_millis(5),
+            &failed,
+        )
+        .await;
+
+    // Tool-level errors returned as is_error results count as failures too
+    let tool_error = Ok(rmcp::model::CallToolResult::error(vec![]));
+    service
+        .record_tool_metric(
+            "list_inbox",
+            &None,
+            std::time::Duration::from_millis(5),
+            &tool_error,
+        )
+        .await;
+
+    let metrics = ToolMetricBmc::list_recent(&ctx, &mm, None, 10)
+        .await
+        .unwrap();
+    assert_eq!(metrics.len(), 2);
+    assert!(metrics.iter().all(|m| m.status == "error"));
+
+    let send = metrics
+        .iter()
+        .find(|m| m.tool_name == "send_message")
+        .unwrap();
+    assert!(send.error_code.as_deref().unwrap().contains("32602"));
+    let inbox = metrics
+        .iter()
+        .find(|m| m.tool_name == "list_inbox")
+        .unwrap();
+    assert_eq!(inbox.error_code.as_deref(), Some("TOOL_ERROR"));
+}
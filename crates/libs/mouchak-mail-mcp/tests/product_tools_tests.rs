@@ -43,14 +43,9 @@ async fn create_test_mm() -> (Arc<ModelManager>, TempDir) {
     let conn = db.connect().unwrap();
     let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
 
-    let schema1 = include_str!("../../../../migrations/001_initial_schema.sql");
-    conn.execute_batch(schema1).await.unwrap();
-    let schema2 = include_str!("../../../../migrations/002_agent_capabilities.sql");
-    conn.execute_batch(schema2).await.unwrap();
-    let schema3 = include_str!("../../../../migrations/003_tool_metrics.sql");
-    conn.execute_batch(schema3).await.unwrap();
-    let schema4 = include_str!("../../../../migrations/004_attachments.sql");
-    conn.execute_batch(schema4).await.unwrap();
+    mouchak_mail_core::store::apply_migrations(&conn)
+        .await
+        .unwrap();
 
     let app_config = Arc::new(AppConfig::default());
     let mm = ModelManager::new_for_test(conn, archive_root, app_config);
@@ -294,6 +289,9 @@ async fn test_product_inbox_impl_success() {
             thread_id: None,
             importance: None,
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
         },
     )
     .await
@@ -394,6 +392,9 @@ async fn test_search_messages_product() -> anyhow::Result<()> {
             thread_id: Some("T-SEARCH-1".to_string()),
             importance: Some("normal".to_string()),
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: true,
         },
     )
     .await?;
@@ -412,6 +413,9 @@ async fn test_search_messages_product() -> anyhow::Result<()> {
             thread_id: Some("T-SEARCH-2".to_string()),
             importance: Some("high".to_string()),
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: true,
         },
     )
     .await?;
@@ -516,6 +520,9 @@ async fn test_summarize_thread_product() -> anyhow::Result<()> {
             thread_id: Some(shared_thread_id.clone()),
             importance: Some("normal".to_string()),
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: true,
         },
     )
     .await?;
@@ -534,6 +541,9 @@ async fn test_summarize_thread_product() -> anyhow::Result<()> {
             thread_id: Some(shared_thread_id.clone()),
             importance: Some("normal".to_string()),
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: true,
         },
     )
     .await?;
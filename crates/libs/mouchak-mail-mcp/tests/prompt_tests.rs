@@ -0,0 +1,316 @@
+//! MCP prompt rendering and argument validation
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use mouchak_mail_core::{
+    ctx::Ctx,
+    model::{
+        ModelManager,
+        agent::{AgentBmc, AgentForCreate},
+        file_reservation::{FileReservationBmc, FileReservationForCreate},
+        message::{MessageBmc, MessageForCreate},
+        project::ProjectBmc,
+    },
+    types::ProjectId,
+};
+use mouchak_mail_mcp::tools::MouchakMailService;
+use rmcp::model::{GetPromptRequestParam, GetPromptResult, PromptMessageContent};
+use serde_json::json;
+use std::sync::Arc;
+use uuid::Uuid;
+
+struct Fixture {
+    service: MouchakMailService,
+    mm: Arc<ModelManager>,
+    slug: String,
+    project_id: ProjectId,
+    /// RedFox, BlueLake, GreenCastle
+    agents: Vec<i64>,
+}
+
+impl Fixture {
+    async fn new() -> Self {
+        let mm = Arc::new(
+            ModelManager::new(Arc::new(mouchak_mail_common::config::AppConfig::default()))
+                .await
+                .unwrap(),
+        );
+        let ctx = Ctx::root_ctx();
+        let slug = format!("prompts-{}", Uuid::new_v4());
+        let project_id = ProjectBmc::create(&ctx, &mm, &slug, "/prompts")
+            .await
+            .unwrap();
+
+        let mut agents = Vec::new();
+        for name in ["RedFox", "BlueLake", "GreenCastle"] {
+            let agent = AgentForCreate {
+                project_id,
+                name: name.to_string(),
+                program: "test".to_string(),
+                model: "test".to_string(),
+                task_description: "test".to_string(),
+            };
+            agents.push(AgentBmc::create(&ctx, &mm, agent).await.unwrap().get());
+        }
+
+        Self {
+            service: MouchakMailService::new_with_mm(mm.clone(), false),
+            mm,
+            slug,
+            project_id,
+            agents,
+        }
+    }
+
+    async fn send(&self, from: usize, to: usize, subject: &str, body: &str, thread: &str) -> i64 {
+        MessageBmc::create(
+            &Ctx::root_ctx(),
+            &self.mm,
+            MessageForCreate {
+                project_id: self.project_id.get(),
+                sender_id: self.agents[from],
+                recipient_ids: vec![self.agents[to]],
+                cc_ids: None,
+                bcc_ids: None,
+                subject: subject.to_string(),
+                body_md: body.to_string(),
+                thread_id: Some(thread.to_string()),
+                importance: None,
+                ack_required: from == 0,
+                deliver_at: None,
+                broadcast: false,
+                allow_new_thread: true,
+            },
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn render(&self, name: &str, args: serde_json::Value) -> GetPromptResult {
+        self.service
+            .get_prompt_impl(request(name, args))
+            .await
+            .unwrap()
+    }
+}
+
+fn request(name: &str, args: serde_json::Value) -> GetPromptRequestParam {
+    GetPromptRequestParam {
+        name: name.to_string(),
+        arguments: args.as_object().cloned(),
+    }
+}
+
+fn text(result: &GetPromptResult) -> &str {
+    assert_eq!(result.messages.len(), 1);
+    match &result.messages[0].content {
+        PromptMessageContent::Text { text } => text,
+        other => panic!("expected text content, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_prompts_are_listed_with_arguments() {
+    let fx = Fixture::new().await;
+    let listed = fx.service.list_prompts_impl();
+    let names: Vec<_> = listed.prompts.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(
+        names,
+        vec!["check_my_inbox", "announce_file_work", "thread_catchup"]
+    );
+
+    let catchup = &listed.prompts[2];
+    let args = catchup.arguments.as_ref().unwrap();
+    let thread_arg = args.iter().find(|a| a.name == "thread_id").unwrap();
+    assert_eq!(thread_arg.required, Some(true));
+    let project_arg = args.iter().find(|a| a.name == "project_slug").unwrap();
+    assert_eq!(project_arg.required, Some(false));
+}
+
+#[tokio::test]
+async fn test_check_my_inbox_renders_unread_messages() {
+    let fx = Fixture::new().await;
+    let read_id = fx.send(2, 1, "Old news", "Already seen", "T-old").await;
+    let unread_id = fx
+        .send(
+            0,
+            1,
+            "Review request",
+            "Please review the parser",
+            "T-review",
+        )
+        .await;
+    MessageBmc::mark_read(&Ctx::root_ctx(), &fx.mm, read_id, fx.agents[1])
+        .await
+        .unwrap();
+
+    let result = fx
+        .render(
+            "check_my_inbox",
+            json!({"project_slug": fx.slug, "agent_name": "BlueLake"}),
+        )
+        .await;
+    let rendered = text(&result);
+    assert!(rendered.contains("These are my 1 unread messages"));
+    assert!(rendered.contains(&format!("### #{} Review request", unread_id)));
+    assert!(rendered.contains("From RedFox"));
+    assert!(rendered.contains("**ack required**"));
+    assert!(rendered.contains("Please review the parser"));
+    assert!(!rendered.contains("Already seen"));
+    assert!(rendered.contains("`acknowledge_message`"));
+
+    let empty = fx
+        .render(
+            "check_my_inbox",
+            json!({"project_slug": fx.slug, "agent_name": "GreenCastle"}),
+        )
+        .await;
+    assert!(text(&empty).contains("no unread messages"));
+}
+
+#[tokio::test]
+async fn test_announce_file_work_flags_held_paths() {
+    let fx = Fixture::new().await;
+    FileReservationBmc::create(
+        &Ctx::root_ctx(),
+        &fx.mm,
+        FileReservationForCreate {
+            project_id: fx.project_id,
+            agent_id: fx.agents[2].into(),
+            path_pattern: "src/api/**".to_string(),
+            exclusive: true,
+            reason: "API refactor".to_string(),
+            expires_ts: chrono::Utc::now().naive_utc() + chrono::Duration::hours(1),
+        },
+    )
+    .await
+    .unwrap();
+
+    let result = fx
+        .render(
+            "announce_file_work",
+            json!({
+                "project_slug": fx.slug,
+                "agent_name": "RedFox",
+                "paths": "src/api/routes.rs, docs/api.md",
+                "reason": "New endpoint",
+                "ttl_seconds": 1800,
+            }),
+        )
+        .await;
+    let rendered = text(&result);
+    assert!(rendered.contains("`src/api/routes.rs` overlaps `src/api/**` held by GreenCastle"));
+    assert!(rendered.contains("`file_reservation_paths`"));
+    assert!(rendered.contains("\"wait\": true"));
+    assert!(rendered.contains("\"ttl_seconds\": 1800"));
+    // Only the holder is notified about a conflict
+    assert!(rendered.contains("\"to\": \"GreenCastle\""));
+    assert!(rendered.contains("Reserved for 30 minutes"));
+
+    let clear = fx
+        .render(
+            "announce_file_work",
+            json!({"project_slug": fx.slug, "agent_name": "RedFox", "paths": "README.md"}),
+        )
+        .await;
+    let rendered = text(&clear);
+    assert!(!rendered.contains("already held"));
+    assert!(rendered.contains("\"wait\": false"));
+    assert!(rendered.contains("\"to\": \"BlueLake,GreenCastle\""));
+}
+
+#[tokio::test]
+async fn test_thread_catchup_includes_transcript() {
+    let fx = Fixture::new().await;
+    fx.send(0, 1, "Schema change", "Adding a column", "T-schema")
+        .await;
+    fx.send(1, 0, "Re: Schema change", "Needs a migration", "T-schema")
+        .await;
+
+    let result = fx
+        .render(
+            "thread_catchup",
+            json!({"project_slug": fx.slug, "thread_id": "T-schema"}),
+        )
+        .await;
+    let rendered = text(&result);
+    assert!(rendered.contains("thread T-schema (\"Schema change\")"));
+    assert!(rendered.contains("2 messages"));
+    assert!(rendered.contains("0 of 1 ack-required messages"));
+    let first = rendered.find("Adding a column").unwrap();
+    let second = rendered.find("Needs a migration").unwrap();
+    assert!(first < second);
+    assert!(rendered.contains("Action items"));
+
+    let missing = fx
+        .service
+        .get_prompt_impl(request(
+            "thread_catchup",
+            json!({"project_slug": fx.slug, "thread_id": "T-nope"}),
+        ))
+        .await
+        .unwrap_err();
+    assert!(missing.message.contains("Thread 'T-nope' not found"));
+}
+
+#[tokio::test]
+async fn test_missing_arguments_are_rejected() {
+    let fx = Fixture::new().await;
+    let cases = [
+        (
+            "check_my_inbox",
+            json!({"project_slug": fx.slug}),
+            "agent_name",
+        ),
+        (
+            "announce_file_work",
+            json!({"project_slug": fx.slug, "agent_name": "RedFox"}),
+            "paths",
+        ),
+        (
+            "announce_file_work",
+            json!({"project_slug": fx.slug, "paths": "src/lib.rs"}),
+            "agent_name",
+        ),
+        (
+            "thread_catchup",
+            json!({"project_slug": fx.slug, "thread_id": "  "}),
+            "thread_id",
+        ),
+    ];
+    for (prompt, args, argument) in cases {
+        let err = fx
+            .service
+            .get_prompt_impl(request(prompt, args))
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.message,
+            format!(
+                "Missing required argument '{}' for prompt '{}'",
+                argument, prompt
+            )
+        );
+        let data = err.data.unwrap();
+        assert_eq!(data["error_code"], "INVALID_INPUT");
+        assert_eq!(data["argument"], argument);
+    }
+
+    let err = fx
+        .service
+        .get_prompt_impl(request(
+            "check_my_inbox",
+            json!({"project_slug": fx.slug, "agent_name": "RedFox", "limit": "lots"}),
+        ))
+        .await
+        .unwrap_err();
+    assert!(err.message.contains("must be a positive integer"));
+
+    let err = fx
+        .service
+        .get_prompt_impl(request("no_such_prompt", json!({})))
+        .await
+        .unwrap_err();
+    assert!(err.message.contains("Unknown prompt 'no_such_prompt'"));
+}
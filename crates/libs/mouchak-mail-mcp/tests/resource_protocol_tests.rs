@@ -0,0 +1,332 @@
+//! Resource support over the MCP wire protocol
+//!
+//! Drives the service the way a client does: newline-delimited JSON-RPC over
+//! an in-memory pipe, covering list, templates, read and subscriptions.
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use mouchak_mail_core::model::{
+    ModelManager,
+    agent::{AgentBmc, AgentForCreate},
+    message::{MessageBmc, MessageForCreate},
+    project::ProjectBmc,
+};
+use mouchak_mail_mcp::tools::MouchakMailService;
+use rmcp::ServiceExt;
+use serde_json::{Value, json};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, DuplexStream, ReadHalf, WriteHalf};
+use uuid::Uuid;
+
+/// Minimal MCP client speaking raw JSON-RPC lines
+struct FakeClient {
+    reader: BufReader<ReadHalf<DuplexStream>>,
+    writer: WriteHalf<DuplexStream>,
+    next_id: i64,
+    /// Notifications received while waiting for a response
+    notifications: Vec<Value>,
+}
+
+impl FakeClient {
+    async fn connect(service: MouchakMailService) -> Self {
+        let (server_io, client_io) = tokio::io::duplex(64 * 1024);
+        tokio::spawn(async move {
+            if let Ok(running) = service.serve(server_io).await {
+                let _ = running.waiting().await;
+            }
+        });
+        let (read, writer) = tokio::io::split(client_io);
+        let mut client = Self {
+            reader: BufReader::new(read),
+            writer,
+            next_id: 1,
+            notifications: Vec::new(),
+        };
+
+        let init = client
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": "2025-03-26",
+                    "capabilities": {},
+                    "clientInfo": {"name": "fake-client", "version": "0.0.0"}
+                }),
+            )
+            .await;
+        assert_eq!(init["capabilities"]["resources"]["subscribe"], true);
+        client
+            .send(json!({"jsonrpc": "2.0", "method": "notifications/initialized"}))
+            .await;
+        client
+    }
+
+    async fn send(&mut self, message: Value) {
+        let mut line = serde_json::to_vec(&message).unwrap();
+        line.push(b'\n');
+        self.writer.write_all(&line).await.unwrap();
+        self.writer.flush().await.unwrap();
+    }
+
+    async fn recv(&mut self) -> Value {
+        let mut line = String::new();
+        tokio::time::timeout(Duration::from_secs(10), self.reader.read_line(&mut line))
+            .await
+            .expect("server reply timed out")
+            .unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    /// Send a request and return its `result`, panicking on an error reply
+    async fn request(&mut self, method: &str, params: Value) -> Value {
+        let reply = self.try_request(method, params).await;
+        assert!(reply.get("error").is_none(), "{} failed: {}", method, reply);
+        reply["result"].clone()
+    }
+
+    /// Send a request and return the whole reply
+    async fn try_request(&mut self, method: &str, params: Value) -> Value {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.send(json!({"jsonrpc": "2.0", "id": id, "method": method, "params": params}))
+            .await;
+        loop {
+            let message = self.recv().await;
+            if message["id"] == id {
+                return message;
+            }
+            self.notifications.push(message);
+        }
+    }
+
+    /// Wait for a `resources/updated` notification for `uri`, keeping any
+    /// other messages in `notifications`
+    async fn updated(&mut self, uri: &str) {
+        let is_match = |n: &Value| {
+            n["method"] == "notifications/resources/updated" && n["params"]["uri"] == uri
+        };
+        if let Some(pos) = self.notifications.iter().position(is_match) {
+            self.notifications.remove(pos);
+            return;
+        }
+        loop {
+            let message = self.recv().await;
+            if is_match(&message) {
+                return;
+            }
+            self.notifications.push(message);
+        }
+    }
+
+    /// URIs of `resources/updated` notifications received so far
+    fn updated_uris(&self) -> Vec<&str> {
+        self.notifications
+            .iter()
+            .filter(|n| n["method"] == "notifications/resources/updated")
+            .filter_map(|n| n["params"]["uri"].as_str())
+            .collect()
+    }
+
+    async fn read_text(&mut self, uri: &str) -> (String, String) {
+        let result = self.request("resources/read", json!({"uri": uri})).await;
+        let content = &result["contents"][0];
+        (
+            content["mimeType"].as_str().unwrap().to_string(),
+            content["text"].as_str().unwrap().to_string(),
+        )
+    }
+}
+
+struct Fixture {
+    mm: Arc<ModelManager>,
+    slug: String,
+    project_id: i64,
+    sender_id: i64,
+    recipient_id: i64,
+}
+
+impl Fixture {
+    async fn new() -> Self {
+        let mm = Arc::new(
+            ModelManager::new(Arc::new(mouchak_mail_common::config::AppConfig::default()))
+                .await
+                .unwrap(),
+        );
+        let ctx = mouchak_mail_core::ctx::Ctx::root_ctx();
+        let slug = format!("res-proto-{}", Uuid::new_v4());
+        let project_id = ProjectBmc::create(&ctx, &mm, &slug, "/res/proto")
+            .await
+            .unwrap();
+
+        let mut ids = Vec::new();
+        for name in ["RedFox", "BlueLake"] {
+            let agent = AgentForCreate {
+                project_id,
+                name: name.to_string(),
+                program: "test".to_string(),
+                model: "test".to_string(),
+                task_description: "test".to_string(),
+            };
+            ids.push(AgentBmc::create(&ctx, &mm, agent).await.unwrap().get());
+        }
+
+        Self {
+            mm,
+            slug,
+            project_id: project_id.get(),
+            sender_id: ids[0],
+            recipient_id: ids[1],
+        }
+    }
+
+    async fn send(&self, subject: &str, body: &str) -> i64 {
+        let ctx = mouchak_mail_core::ctx::Ctx::root_ctx();
+        MessageBmc::create(
+            &ctx,
+            &self.mm,
+            MessageForCreate {
+                project_id: self.project_id,
+                sender_id: self.sender_id,
+                recipient_ids: vec![self.recipient_id],
+                cc_ids: None,
+                bcc_ids: None,
+                subject: subject.to_string(),
+                body_md: body.to_string(),
+                thread_id: Some("PLAN-1".to_string()),
+                importance: Some("high".to_string()),
+                ack_required: true,
+                deliver_at: None,
+                broadcast: false,
+                allow_new_thread: true,
+            },
+        )
+        .await
+        .unwrap()
+    }
+}
+
+#[tokio::test]
+async fn test_list_templates_and_read_over_protocol() {
+    let fx = Fixture::new().await;
+    let message_id = fx.send("Migration plan", "Step 1: *back up*").await;
+    let mut client =
+        FakeClient::connect(MouchakMailService::new_with_mm(fx.mm.clone(), false)).await;
+
+    let listed = client.request("resources/list", json!({})).await;
+    let uris: Vec<&str> = listed["resources"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["uri"].as_str().unwrap())
+        .collect();
+    let inbox_uri = format!("mouchak-mail://{}/inbox/BlueLake", fx.slug);
+    assert!(uris.contains(&inbox_uri.as_str()));
+    assert!(!uris.iter().any(|u| u.contains("build_slots")));
+
+    let templates = client.request("resources/templates/list", json!({})).await;
+    let templates: Vec<&str> = templates["resourceTemplates"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|t| t["uriTemplate"].as_str().unwrap())
+        .collect();
+    assert!(templates.contains(&"resource://message/{id}{?project}"));
+    assert!(!templates.iter().any(|t| t.contains("build_slots")));
+
+    let (mime, text) = client
+        .read_text(&format!(
+            "mouchak-mail://{}/message/{}",
+            fx.slug, message_id
+        ))
+        .await;
+    assert_eq!(mime, "text/markdown");
+    assert!(text.starts_with("# Migration plan\n"));
+    assert!(text.contains("- **From:** RedFox"));
+    assert!(text.contains("- **To:** BlueLake"));
+    assert!(text.contains("- **Thread:** PLAN-1"));
+    assert!(text.contains("Step 1: *back up*"));
+
+    let (mime, text) = client
+        .read_text(&format!(
+            "{}?format=markdown&include_bodies=true",
+            inbox_uri
+        ))
+        .await;
+    assert_eq!(mime, "text/markdown");
+    assert!(text.contains(&format!("**#{}** [high] Migration plan", message_id)));
+    assert!(text.contains("> Step 1: *back up*"));
+
+    let (mime, text) = client.read_text(&inbox_uri).await;
+    assert_eq!(mime, "application/json");
+    let inbox: Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(inbox[0]["id"], message_id);
+
+    // Messages are only readable through their own project
+    let other = client
+        .try_request(
+            "resources/read",
+            json!({"uri": format!("mouchak-mail://{}/message/{}", Uuid::new_v4(), message_id)}),
+        )
+        .await;
+    assert!(other.get("error").is_some());
+
+    // Build slots need worktrees
+    let slots = client
+        .try_request(
+            "resources/read",
+            json!({"uri": format!("mouchak-mail://{}/build_slots", fx.slug)}),
+        )
+        .await;
+    assert!(slots.get("error").is_some());
+}
+
+#[tokio::test]
+async fn test_build_slot_resources_with_worktrees() {
+    let fx = Fixture::new().await;
+    let mut client =
+        FakeClient::connect(MouchakMailService::new_with_mm(fx.mm.clone(), true)).await;
+
+    let listed = client.request("resources/list", json!({})).await;
+    let slots_uri = format!("mouchak-mail://{}/build_slots", fx.slug);
+    assert!(
+        listed["resources"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|r| r["uri"] == slots_uri)
+    );
+
+    let (mime, text) = client.read_text(&slots_uri).await;
+    assert_eq!(mime, "application/json");
+    assert_eq!(serde_json::from_str::<Value>(&text).unwrap(), json!([]));
+}
+
+#[tokio::test]
+async fn test_subscribed_inbox_gets_updated_notification() {
+    let fx = Fixture::new().await;
+    let mut client =
+        FakeClient::connect(MouchakMailService::new_with_mm(fx.mm.clone(), false)).await;
+
+    let inbox_uri = format!("mouchak-mail://{}/inbox/BlueLake?format=markdown", fx.slug);
+    let sender_inbox = format!("mouchak-mail://{}/inbox/RedFox", fx.slug);
+    let outbox_uri = format!("mouchak-mail://{}/outbox/RedFox", fx.slug);
+    for uri in [&inbox_uri, &sender_inbox, &outbox_uri] {
+        client
+            .request("resources/subscribe", json!({"uri": uri}))
+            .await;
+    }
+
+    // Updates for one event are sent together, in subscription order
+    fx.send("Heads up", "Deploying now").await;
+    client.updated(&inbox_uri).await;
+    client.updated(&outbox_uri).await;
+    assert!(client.updated_uris().is_empty());
+
+    client
+        .request("resources/unsubscribe", json!({"uri": inbox_uri}))
+        .await;
+    fx.send("Done", "Deployed").await;
+    client.updated(&outbox_uri).await;
+    assert!(client.updated_uris().is_empty());
+}
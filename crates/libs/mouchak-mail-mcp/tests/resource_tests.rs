@@ -55,6 +55,9 @@ async fn test_resources_gap_features() -> anyhow::Result<()> {
             thread_id: Some(thread_id.to_string()),
             importance: Some("normal".to_string()),
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: true,
         },
     )
     .await?;
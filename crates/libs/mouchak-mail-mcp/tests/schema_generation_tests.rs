@@ -59,15 +59,15 @@ fn test_generated_schema_matches_manual_schema() {
 fn test_required_fields_marked_correctly() {
     let schema = schema_from_params::<SendMessageParams>("send_message", "test");
 
-    // project_slug is NOT Option<T>, should be required
+    // project_slug has a serde default (discovered from the working directory)
     let project_slug = schema
         .parameters
         .iter()
         .find(|p| p.name == "project_slug")
         .expect("project_slug parameter not found");
     assert!(
-        project_slug.required,
-        "project_slug should be required (non-Option field)"
+        !project_slug.required,
+        "project_slug should be optional (serde default)"
     );
 
     // sender_name is NOT Option<T>, should be required
@@ -159,42 +159,63 @@ fn test_schema_count_matches_expected() {
     );
 }
 
-/// Test that build slot tools are filtered when worktrees_enabled is false
+/// Test that build slot tools are disabled, not dropped, when worktrees_enabled is false
 #[test]
-fn test_build_slot_tools_filtered_when_disabled() {
+fn test_build_slot_tools_disabled_when_worktrees_off() {
     let schemas_with_build = mouchak_mail_mcp::get_tool_schemas(true);
     let schemas_without_build = mouchak_mail_mcp::get_tool_schemas(false);
 
-    // With worktrees enabled, should have build slot tools
-    let names_with: Vec<&str> = schemas_with_build.iter().map(|s| s.name.as_str()).collect();
-    assert!(
-        names_with.contains(&"acquire_build_slot"),
-        "acquire_build_slot should be present when worktrees_enabled=true"
-    );
+    // Both configurations list every tool
+    assert_eq!(schemas_with_build.len(), schemas_without_build.len());
 
-    // Without worktrees, should NOT have build slot tools
-    let names_without: Vec<&str> = schemas_without_build
-        .iter()
-        .map(|s| s.name.as_str())
-        .collect();
-    assert!(
-        !names_without.contains(&"acquire_build_slot"),
-        "acquire_build_slot should NOT be present when worktrees_enabled=false"
-    );
-    assert!(
-        !names_without.contains(&"release_build_slot"),
-        "release_build_slot should NOT be present when worktrees_enabled=false"
-    );
-    assert!(
-        !names_without.contains(&"renew_build_slot"),
-        "renew_build_slot should NOT be present when worktrees_enabled=false"
-    );
+    let enabled = |schemas: &[mouchak_mail_mcp::tools::ToolSchema], name: &str| {
+        schemas
+            .iter()
+            .find(|s| s.name == name)
+            .map(|s| s.enabled)
+            .unwrap_or_else(|| panic!("{} should be listed", name))
+    };
+    for tool in [
+        "acquire_build_slot",
+        "release_build_slot",
+        "renew_build_slot",
+    ] {
+        assert!(
+            enabled(&schemas_with_build, tool),
+            "{} should be enabled when worktrees_enabled=true",
+            tool
+        );
+        assert!(
+            !enabled(&schemas_without_build, tool),
+            "{} should be disabled when worktrees_enabled=false",
+            tool
+        );
+    }
+    assert!(enabled(&schemas_without_build, "send_message"));
 
-    // Filtered list should have fewer tools
+    // Disabled tools say why, and the reason is left out of enabled ones
+    let json = serde_json::to_value(&schemas_without_build).unwrap();
+    let acquire = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["name"] == "acquire_build_slot")
+        .unwrap();
+    assert_eq!(acquire["enabled"], false);
     assert!(
-        schemas_without_build.len() < schemas_with_build.len(),
-        "Filtered schema count should be less than full count"
+        acquire["disabled_reason"]
+            .as_str()
+            .unwrap()
+            .contains("WORKTREES_ENABLED")
     );
+    let send = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|s| s["name"] == "send_message")
+        .unwrap();
+    assert_eq!(send["enabled"], true);
+    assert!(send.get("disabled_reason").is_none());
 }
 
 /// Test that parameter types are correctly extracted
@@ -238,3 +259,67 @@ fn test_parameters_sorted_alphabetically() {
         "Parameters should be sorted alphabetically for consistent output"
     );
 }
+
+/// Test that project_slug may be omitted so the server can discover it
+#[test]
+fn test_project_slug_may_be_omitted() {
+    let params: SendMessageParams = serde_json::from_value(serde_json::json!({
+        "sender_name": "alice",
+        "to": "bob",
+        "subject": "hi",
+        "body_md": "hello"
+    }))
+    .expect("project_slug should default when omitted");
+    assert!(params.project_slug.is_empty());
+}
+
+/// Test that rendered docs and tool lists follow the worktrees flag
+#[test]
+fn test_rendered_docs_follow_worktrees_flag() {
+    use mouchak_mail_mcp::docs::{render_schema, render_tool_list};
+
+    let with = render_schema("markdown", true).unwrap();
+    let without = render_schema("md", false).unwrap();
+    for tool in [
+        "acquire_build_slot",
+        "release_build_slot",
+        "renew_build_slot",
+    ] {
+        assert!(
+            with.contains(&format!("## {}\n", tool)),
+            "{} should be documented when worktrees are enabled",
+            tool
+        );
+        assert!(
+            !without.contains(tool),
+            "{} should not be documented when worktrees are disabled",
+            tool
+        );
+    }
+    assert!(without.contains("## send_message\n"));
+
+    // One separator row per tool parameter or prompt argument table
+    assert_eq!(
+        with.matches("|------|------|----------|-------------|\n")
+            .count(),
+        with.matches("### Parameters\n").count() + with.matches("### Arguments\n").count()
+    );
+
+    assert!(!render_tool_list(true).contains("Disabled"));
+    let (listed, disabled) = render_tool_list(false)
+        .split_once("\nDisabled (3)\n")
+        .map(|(l, d)| (l.to_string(), d.to_string()))
+        .expect("disabled tools should get their own table");
+    assert!(!listed.contains("acquire_build_slot"));
+    assert!(disabled.contains("acquire_build_slot"));
+    assert!(disabled.contains("WORKTREES_ENABLED=true"));
+
+    let json: serde_json::Value =
+        serde_json::from_str(&render_schema("json", false).unwrap()).unwrap();
+    assert!(
+        json.as_array()
+            .unwrap()
+            .iter()
+            .all(|s| s["name"] != "acquire_build_slot")
+    );
+}
@@ -29,15 +29,9 @@ async fn create_test_mm() -> (Arc<ModelManager>, TempDir) {
     let conn = db.connect().unwrap();
     let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
 
-    // Run migrations
-    let schema1 = include_str!("../../../../migrations/001_initial_schema.sql");
-    conn.execute_batch(schema1).await.unwrap();
-    let schema2 = include_str!("../../../../migrations/002_agent_capabilities.sql");
-    conn.execute_batch(schema2).await.unwrap();
-    let schema3 = include_str!("../../../../migrations/003_tool_metrics.sql");
-    conn.execute_batch(schema3).await.unwrap();
-    let schema4 = include_str!("../../../../migrations/004_attachments.sql");
-    conn.execute_batch(schema4).await.unwrap();
+    mouchak_mail_core::store::apply_migrations(&conn)
+        .await
+        .unwrap();
 
     let app_config = Arc::new(AppConfig::default());
     let mm = ModelManager::new_for_test(conn, archive_root, app_config);
@@ -125,6 +119,9 @@ async fn test_quick_standup_sends_to_all_agents() {
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
 
     let msg_id = MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
@@ -178,6 +175,9 @@ async fn test_quick_handoff_sends_message() {
         thread_id: Some("HANDOFF-FEATURE-X".to_string()),
         importance: Some("high".to_string()),
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
 
     let msg_id = MessageBmc::create(&ctx, &mm, handoff_msg).await.unwrap();
@@ -248,6 +248,9 @@ async fn test_quick_review_reserves_files_and_sends_message() {
         thread_id: Some("REVIEW-MAIN-RS".to_string()),
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
 
     let msg_id = MessageBmc::create(&ctx, &mm, review_msg).await.unwrap();
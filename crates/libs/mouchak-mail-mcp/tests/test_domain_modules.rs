@@ -49,15 +49,9 @@ async fn create_test_mm() -> (Arc<ModelManager>, TempDir) {
     let conn = db.connect().unwrap();
     let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
 
-    // Run migrations
-    let schema1 = include_str!("../../../../migrations/001_initial_schema.sql");
-    conn.execute_batch(schema1).await.unwrap();
-    let schema2 = include_str!("../../../../migrations/002_agent_capabilities.sql");
-    conn.execute_batch(schema2).await.unwrap();
-    let schema3 = include_str!("../../../../migrations/003_tool_metrics.sql");
-    conn.execute_batch(schema3).await.unwrap();
-    let schema4 = include_str!("../../../../migrations/004_attachments.sql");
-    conn.execute_batch(schema4).await.unwrap();
+    mouchak_mail_core::store::apply_migrations(&conn)
+        .await
+        .unwrap();
 
     let app_config = Arc::new(AppConfig::default());
     let mm = ModelManager::new_for_test(conn, archive_root, app_config);
@@ -185,6 +179,7 @@ async fn test_register_agent() {
         program: "claude".to_string(),
         model: "opus".to_string(),
         task_description: "Test task".to_string(),
+        strict: false,
     };
 
     let result = agent::register_agent_impl(&ctx, &mm, params).await;
@@ -360,7 +355,9 @@ async fn test_set_contact_policy() {
     let params = SetContactPolicyParams {
         project_slug: "policy-test".to_string(),
         agent_name: "policy-agent".to_string(),
-        contact_policy: "open".to_string(),
+        contact_policy: Some("open".to_string()),
+        dnd_until: None,
+        min_importance: None,
     };
 
     let result = contacts::set_contact_policy_impl(&ctx, &mm, params).await;
@@ -455,6 +452,9 @@ async fn test_get_review_state_with_completion() {
         thread_id: Some("TASK-123".to_string()),
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
     MessageBmc::create(&ctx, &mm, msg).await.unwrap();
 
@@ -498,6 +498,7 @@ async fn test_list_outbox_empty() {
         project_slug: "outbox-test".to_string(),
         agent_name: "sender".to_string(),
         limit: Some(10),
+        cursor: None,
     };
 
     let result = outbox::list_outbox_impl(&ctx, &mm, params).await;
@@ -545,6 +546,9 @@ async fn test_list_outbox_with_messages() {
             thread_id: None,
             importance: None,
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
         };
         MessageBmc::create(&ctx, &mm, msg).await.unwrap();
     }
@@ -553,6 +557,7 @@ async fn test_list_outbox_with_messages() {
         project_slug: "outbox-full".to_string(),
         agent_name: "sender".to_string(),
         limit: Some(10),
+        cursor: None,
     };
 
     let result = outbox::list_outbox_impl(&ctx, &mm, params).await;
@@ -560,6 +565,7 @@ async fn test_list_outbox_with_messages() {
 
     let content = format!("{:?}", result);
     assert!(content.contains("Test Message"));
+    assert!(content.contains("recipient (unread)"));
 }
 
 // ==============================================================================
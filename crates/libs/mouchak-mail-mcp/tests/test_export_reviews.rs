@@ -31,14 +31,9 @@ async fn create_test_mm() -> (Arc<ModelManager>, TempDir) {
     let conn = db.connect().unwrap();
     let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
 
-    let schema1 = include_str!("../../../../migrations/001_initial_schema.sql");
-    conn.execute_batch(schema1).await.unwrap();
-    let schema2 = include_str!("../../../../migrations/002_agent_capabilities.sql");
-    conn.execute_batch(schema2).await.unwrap();
-    let schema3 = include_str!("../../../../migrations/003_tool_metrics.sql");
-    conn.execute_batch(schema3).await.unwrap();
-    let schema4 = include_str!("../../../../migrations/004_attachments.sql");
-    conn.execute_batch(schema4).await.unwrap();
+    mouchak_mail_core::store::apply_migrations(&conn)
+        .await
+        .unwrap();
 
     let app_config = Arc::new(AppConfig::default());
     let mm = ModelManager::new_for_test(conn, archive_root, app_config);
@@ -82,6 +77,9 @@ async fn setup_project_with_messages(mm: &Arc<ModelManager>) -> (i64, i64, i64,
         thread_id: Some("THREAD-001".to_string()),
         importance: Some("normal".to_string()),
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
     MessageBmc::create(&ctx, mm, msg).await.unwrap();
 
@@ -237,6 +235,9 @@ async fn test_get_review_state_impl_with_completion_message() {
         thread_id: Some("REVIEW-THREAD".to_string()),
         importance: Some("normal".to_string()),
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
     MessageBmc::create(&ctx, &mm, msg).await.unwrap();
 
@@ -288,6 +289,9 @@ async fn test_claim_review_impl_success() {
         thread_id: Some("CLAIM-THREAD".to_string()),
         importance: Some("high".to_string()),
         ack_required: true,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
     let msg_id = MessageBmc::create(&ctx, &mm, msg).await.unwrap();
 
@@ -323,6 +327,9 @@ async fn test_claim_review_impl_already_claimed() {
         thread_id: Some("ALREADY-CLAIMED".to_string()),
         importance: Some("normal".to_string()),
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
     let msg_id = MessageBmc::create(&ctx, &mm, msg).await.unwrap();
 
@@ -384,6 +391,9 @@ async fn test_claim_review_impl_reviewer_not_found() {
         thread_id: Some("TEST-THREAD".to_string()),
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
     let msg_id = MessageBmc::create(&ctx, &mm, msg).await.unwrap();
 
@@ -37,14 +37,9 @@ async fn create_test_mm() -> (Arc<ModelManager>, TempDir) {
     let conn = db.connect().unwrap();
     let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
 
-    let schema1 = include_str!("../../../../migrations/001_initial_schema.sql");
-    conn.execute_batch(schema1).await.unwrap();
-    let schema2 = include_str!("../../../../migrations/002_agent_capabilities.sql");
-    conn.execute_batch(schema2).await.unwrap();
-    let schema3 = include_str!("../../../../migrations/003_tool_metrics.sql");
-    conn.execute_batch(schema3).await.unwrap();
-    let schema4 = include_str!("../../../../migrations/004_attachments.sql");
-    conn.execute_batch(schema4).await.unwrap();
+    mouchak_mail_core::store::apply_migrations(&conn)
+        .await
+        .unwrap();
 
     let app_config = Arc::new(AppConfig::default());
     let mm = ModelManager::new_for_test(conn, archive_root, app_config);
@@ -455,7 +450,10 @@ async fn test_renew_file_reservation_impl_success() {
 
     // Renew with longer TTL
     let params = RenewFileReservationParams {
-        reservation_id: res_id,
+        project_slug,
+        agent_name: "file_agent".to_string(),
+        reservation_id: Some(res_id),
+        path_pattern: None,
         ttl_seconds: Some(3600),
     };
 
@@ -510,6 +508,8 @@ async fn test_reserve_file_paths_impl_success() {
         ttl_seconds: Some(3600),
         exclusive: true, // Not Option<bool>
         reason: Some("Multiple files".to_string()),
+        wait: None,
+        wait_timeout_seconds: None,
     };
 
     let result = files::file_reservation_paths_impl(&ctx, &mm, params).await;
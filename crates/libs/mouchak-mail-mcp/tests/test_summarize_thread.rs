@@ -30,14 +30,9 @@ async fn create_test_mm() -> (Arc<ModelManager>, TempDir) {
     let conn = db.connect().unwrap();
     let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
 
-    let schema1 = include_str!("../../../../migrations/001_initial_schema.sql");
-    conn.execute_batch(schema1).await.unwrap();
-    let schema2 = include_str!("../../../../migrations/002_agent_capabilities.sql");
-    conn.execute_batch(schema2).await.unwrap();
-    let schema3 = include_str!("../../../../migrations/003_tool_metrics.sql");
-    conn.execute_batch(schema3).await.unwrap();
-    let schema4 = include_str!("../../../../migrations/004_attachments.sql");
-    conn.execute_batch(schema4).await.unwrap();
+    mouchak_mail_core::store::apply_migrations(&conn)
+        .await
+        .unwrap();
 
     let app_config = Arc::new(AppConfig::default());
     let mm = ModelManager::new_for_test(conn, archive_root, app_config);
@@ -81,6 +76,9 @@ async fn setup_test_data(mm: &ModelManager) -> (i64, i64, i64) {
         thread_id: Some("THREAD-001".to_string()),
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
     MessageBmc::create(&ctx, mm, msg1).await.unwrap();
 
@@ -95,6 +93,9 @@ async fn setup_test_data(mm: &ModelManager) -> (i64, i64, i64) {
         thread_id: Some("THREAD-001".to_string()),
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
     MessageBmc::create(&ctx, mm, msg2).await.unwrap();
 
@@ -109,6 +110,9 @@ async fn setup_test_data(mm: &ModelManager) -> (i64, i64, i64) {
         thread_id: Some("THREAD-002".to_string()),
         importance: Some("high".to_string()),
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: true,
     };
     MessageBmc::create(&ctx, mm, msg3).await.unwrap();
 
@@ -269,3 +273,51 @@ fn test_thread_id_input_to_vec() {
         vec!["THREAD-001".to_string(), "THREAD-002".to_string()]
     );
 }
+
+#[tokio::test]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+async fn test_summarize_thread_impl_returns_stats() {
+    use mouchak_mail_mcp::tools::{SummarizeThreadParams, ThreadIdInput, messaging};
+
+    let (mm, _temp) = create_test_mm().await;
+    setup_test_data(&mm).await;
+    let ctx = Ctx::root_ctx();
+
+    let params = SummarizeThreadParams {
+        project_slug: "test-project".to_string(),
+        thread_id: ThreadIdInput::Multiple(vec![
+            "THREAD-001".to_string(),
+            "THREAD-NONEXISTENT".to_string(),
+        ]),
+        include_examples: None,
+        recent: Some(1),
+    };
+    let result = messaging::summarize_thread_impl(&ctx, &mm, params)
+        .await
+        .unwrap();
+    let text = result
+        .content
+        .first()
+        .map(|c| format!("{:?}", c))
+        .unwrap_or_default();
+
+    assert!(text.contains("Thread 1 Subject"));
+    assert!(text.contains("alice") && text.contains("bob"));
+    assert!(text.contains("ack_required_count"));
+    // Only the newest snippet was requested
+    assert!(text.contains("Reply in thread 1"));
+    assert!(!text.contains("First message in thread 1"));
+    assert!(text.contains("THREAD-NONEXISTENT"), "Missing error entry");
+
+    // A single unknown thread is a structured THREAD_NOT_FOUND error
+    let params = SummarizeThreadParams {
+        project_slug: "test-project".to_string(),
+        thread_id: ThreadIdInput::Single("THREAD-NONEXISTENT".to_string()),
+        include_examples: None,
+        recent: None,
+    };
+    let err = messaging::summarize_thread_impl(&ctx, &mm, params)
+        .await
+        .unwrap_err();
+    assert_eq!(err.data.unwrap()["error_code"], "THREAD_NOT_FOUND");
+}
@@ -1,7 +1,7 @@
 //! Tests for MCP tool dispatch logic
 //!
-//! Target: Coverage for tool alias resolution and worktree-dependent filtering
-//! in lib-mcp/src/tools/mod.rs
+//! Target: Coverage for tool alias resolution, worktree-dependent filtering
+//! and argument validation in lib-mcp/src/tools/mod.rs
 
 #![allow(clippy::unwrap_used, clippy::expect_used)]
 
@@ -20,16 +20,26 @@ mod get_tool_schemas_filtering {
     use super::*;
 
     #[test]
-    fn excludes_build_slots_when_worktrees_disabled() {
+    fn disables_build_slots_when_worktrees_disabled() {
         let schemas = get_tool_schemas(false);
-        let tool_names: Vec<&str> = schemas.iter().map(|s| s.name.as_str()).collect();
 
         for build_slot_tool in BUILD_SLOT_TOOLS {
+            let schema = schemas
+                .iter()
+                .find(|s| s.name == *build_slot_tool)
+                .unwrap_or_else(|| panic!("Tool '{}' should still be listed", build_slot_tool));
             assert!(
-                !tool_names.contains(build_slot_tool),
-                "Tool '{}' should be excluded when worktrees disabled, but found in: {:?}",
-                build_slot_tool,
-                tool_names
+                !schema.enabled,
+                "Tool '{}' should be disabled when worktrees disabled",
+                build_slot_tool
+            );
+            assert!(
+                schema
+                    .disabled_reason
+                    .as_deref()
+                    .is_some_and(|r| r.contains("WORKTREES_ENABLED")),
+                "Tool '{}' should say why it is disabled",
+                build_slot_tool
             );
         }
     }
@@ -222,6 +232,41 @@ mod server_handler_list_tools {
             );
         }
     }
+    #[tokio::test]
+    async fn message_tools_describe_configured_limits() {
+        let mut config = AppConfig::default();
+        config.messages.max_subject_chars = 120;
+        config.messages.max_body_bytes = 4096;
+        config.messages.max_recipients = 7;
+        let mm = Arc::new(
+            ModelManager::new(Arc::new(config))
+                .await
+                .expect("Failed to create ModelManager"),
+        );
+        let service = MouchakMailService::new_with_mm(mm, false);
+
+        let tools = service.list_tools_filtered();
+        let description = |name: &str| {
+            tools
+                .iter()
+                .find(|t| t.name == name)
+                .and_then(|t| t.description.as_deref())
+                .unwrap_or_default()
+                .to_string()
+        };
+        for name in ["send_message", "reply_message", "forward_message"] {
+            let text = description(name);
+            assert!(
+                text.contains("subject at most 120 characters")
+                    && text.contains("body_md at most 4096 bytes")
+                    && text.contains("at most 7 recipients"),
+                "{} description should state the limits: {}",
+                name,
+                text
+            );
+        }
+        assert!(!description("list_projects").contains("Limits:"));
+    }
 }
 
 mod build_slot_rejection {
@@ -300,3 +345,176 @@ mod build_slot_rejection {
         }
     }
 }
+
+mod argument_validation {
+    use super::*;
+    use serde_json::{Value, json};
+
+    async fn service() -> MouchakMailService {
+        let mm = Arc::new(
+            ModelManager::new(Arc::new(AppConfig::default()))
+                .await
+                .expect("Failed to create ModelManager"),
+        );
+        MouchakMailService::new_with_mm(mm, true)
+    }
+
+    /// A value of the wrong type for `property`, or None if it accepts anything.
+    fn wrong_value(property: &Value) -> Option<Value> {
+        if property.get("$ref").is_some() {
+            // ThreadIdInput and friends are strings or arrays
+            return Some(json!(12345));
+        }
+        match property.get("type")?.as_str()? {
+            "string" => Some(json!(12345)),
+            "integer" | "number" => Some(json!("7")),
+            "boolean" => Some(json!("yes")),
+            "array" => Some(json!("a,b")),
+            "object" => Some(json!(["not", "an", "object"])),
+            _ => None,
+        }
+    }
+
+    #[tokio::test]
+    async fn every_tool_reports_each_malformed_field() {
+        let service = service().await;
+        let mut checked = 0;
+
+        for tool in service.list_tools_filtered() {
+            let properties = tool
+                .input_schema
+                .get("properties")
+                .and_then(Value::as_object)
+                .cloned()
+                .unwrap_or_default();
+            let required: Vec<String> = tool
+                .input_schema
+                .get("required")
+                .and_then(Value::as_array)
+                .map(|r| {
+                    r.iter()
+                        .filter_map(|v| v.as_str().map(String::from))
+                        .collect()
+                })
+                .unwrap_or_default();
+            // Required fields first; tools without any get every typed field
+            let fields: Vec<&String> = if required.is_empty() {
+                properties.keys().collect()
+            } else {
+                properties.keys().filter(|k| required.contains(k)).collect()
+            };
+
+            let args: serde_json::Map<String, Value> = fields
+                .iter()
+                .filter_map(|field| {
+                    wrong_value(&properties[field.as_str()]).map(|v| ((*field).clone(), v))
+                })
+                .collect();
+            if args.is_empty() {
+                continue;
+            }
+
+            let err = service
+                .validate_tool_arguments(&tool.name, Some(&args))
+                .expect_err(&format!("'{}' should reject {:?}", tool.name, args));
+            let data = err.data.as_ref().expect("error should have data");
+            assert_eq!(data["error_code"], "VALIDATION_ERROR", "tool {}", tool.name);
+
+            let violations = data["violations"].as_array().expect("violations");
+            assert_eq!(
+                violations.len(),
+                args.len(),
+                "tool {}: {}",
+                tool.name,
+                err.message
+            );
+            for field in args.keys() {
+                assert!(
+                    err.message.contains(&format!("'{}'", field)),
+                    "Error for '{}' should name '{}', got: {}",
+                    tool.name,
+                    field,
+                    err.message
+                );
+                let violation = violations
+                    .iter()
+                    .find(|v| v["field"] == field.as_str())
+                    .unwrap_or_else(|| panic!("{}: no violation for {}", tool.name, field));
+                assert!(violation["expected"].is_string());
+                assert!(!violation["example"].is_null(), "{}: {}", tool.name, field);
+            }
+            checked += 1;
+        }
+
+        assert!(checked > 50, "only {} tools were checked", checked);
+    }
+
+    #[tokio::test]
+    async fn reports_missing_required_fields_with_examples() {
+        let service = service().await;
+
+        let err = service
+            .validate_tool_arguments("send_message", None)
+            .expect_err("missing fields should fail");
+        for field in ["sender_name", "subject", "body_md"] {
+            assert!(err.message.contains(&format!("'{}' is required", field)));
+        }
+
+        let args = json!({ "sender_name": "BlueLake", "subject": "hi", "body_md": "x", "ack_required": "true" });
+        let err = service
+            .validate_tool_arguments("send_message", args.as_object())
+            .expect_err("string for boolean should fail");
+        assert!(
+            err.message
+                .contains("'ack_required' must be boolean, got string (e.g. true)"),
+            "got: {}",
+            err.message
+        );
+    }
+
+    #[tokio::test]
+    async fn accepts_valid_arguments_aliases_and_nulls() {
+        let service = service().await;
+
+        let args = json!({
+            "project_key": "/repo",
+            "agent_name": "BlueLake",
+            "program": "cli",
+            "model": "m",
+            "task_description": "t"
+        });
+        let report = service
+            .validate_tool_arguments("register_agent", args.as_object())
+            .expect("aliased fields are valid");
+        assert!(report.unknown_fields.is_empty());
+
+        let args = json!({ "thread_id": ["a", "b"], "recent": null });
+        assert!(
+            service
+                .validate_tool_arguments("summarize_thread", args.as_object())
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_fields_warn_without_failing() {
+        let service = service().await;
+
+        let args = json!({ "message_id": 1, "verbose": true });
+        let report = service
+            .validate_tool_arguments("get_message", args.as_object())
+            .expect("unknown fields should not fail");
+        assert_eq!(report.unknown_fields, vec!["verbose".to_string()]);
+        assert_eq!(
+            report.warnings(),
+            vec!["Unknown parameter 'verbose' was ignored".to_string()]
+        );
+
+        // Reported alongside real violations too
+        let args = json!({ "message_id": "1", "verbose": true });
+        let err = service
+            .validate_tool_arguments("get_message", args.as_object())
+            .expect_err("string message_id should fail");
+        assert_eq!(err.data.unwrap()["unknown_fields"], json!(["verbose"]));
+    }
+}
@@ -1,29 +1,155 @@
 use axum::Router;
-use axum::routing::{delete, get, post};
+use axum::routing::{delete, get, patch, post};
 
 use crate::AppState;
 use crate::tools;
 
+pub mod agent_activity;
 pub mod attachments;
+pub mod audit;
+pub mod backup;
+pub mod events;
 pub mod export;
+pub mod groups;
+pub mod inbox_wait;
+pub mod labels;
+pub mod messages;
+pub mod notifications;
+pub mod outbox;
+pub mod pending_acks;
+pub mod project_stats;
+pub mod project_version;
+pub mod reservations;
+pub mod server_info;
+pub mod templates;
+pub mod threads;
 pub mod unified_inbox;
+pub mod unread_counts;
 
 pub fn routes() -> Router<AppState> {
     Router::new()
         // Unified Inbox (Gmail-style cross-project view)
         .route("/api/unified-inbox", get(unified_inbox::unified_inbox_json))
+        .route("/api/unread-counts", get(unread_counts::all_unread_counts))
+        .route("/api/notifications", get(notifications::list_notifications))
+        .route(
+            "/api/notifications/dismiss",
+            post(notifications::dismiss_notifications),
+        )
+        .route(
+            "/api/project/{slug}/unread-counts",
+            get(unread_counts::project_unread_counts),
+        )
+        .route(
+            "/api/project/{slug}/stats",
+            get(project_stats::project_stats),
+        )
+        .route(
+            "/api/project/{slug}/version",
+            get(project_version::project_version),
+        )
+        .route(
+            "/api/project/{slug}/reservations",
+            get(reservations::project_reservations),
+        )
+        .route(
+            "/api/project/{slug}/reservations/{id}",
+            delete(reservations::force_release_project_reservation),
+        )
+        .route(
+            "/api/project/{slug}/reservations/{id}/transfer",
+            post(reservations::transfer_project_reservation),
+        )
+        .route("/api/project/{slug}/threads", get(threads::list_threads))
+        .route(
+            "/api/project/{slug}/thread/{thread_id}/summary",
+            get(threads::thread_summary),
+        )
+        .route(
+            "/api/project/{slug}/thread/{thread_id}/mutes",
+            get(threads::list_thread_mutes).post(threads::mute_thread),
+        )
+        .route(
+            "/api/project/{slug}/thread/{thread_id}/mutes/{agent_name}",
+            delete(threads::unmute_thread),
+        )
+        .route(
+            "/api/project/{slug}/agent/{name}/outbox",
+            get(outbox::agent_outbox),
+        )
+        .route(
+            "/api/project/{slug}/agent/{name}/inbox/wait",
+            get(inbox_wait::wait_for_inbox),
+        )
+        .route(
+            "/api/project/{slug}/agent/{name}/activity",
+            get(agent_activity::agent_activity),
+        )
+        .route(
+            "/api/project/{slug}/agent/{name}/pending-acks",
+            get(pending_acks::pending_acks),
+        )
+        .route("/api/project/{slug}/audit", get(audit::project_audit))
+        // Message templates
+        .route(
+            "/api/project/{slug}/templates",
+            get(templates::list_templates).post(templates::create_template),
+        )
+        .route(
+            "/api/project/{slug}/templates/{id}",
+            get(templates::get_template).delete(templates::delete_template),
+        )
+        // Agent groups
+        .route(
+            "/api/project/{slug}/groups",
+            get(groups::list_groups).post(groups::create_group),
+        )
+        .route(
+            "/api/project/{slug}/groups/{name}",
+            get(groups::get_group).delete(groups::delete_group),
+        )
+        .route(
+            "/api/project/{slug}/groups/{name}/members",
+            post(groups::add_group_member),
+        )
+        .route(
+            "/api/project/{slug}/groups/{name}/members/{agent_name}",
+            delete(groups::remove_group_member),
+        )
+        // Message labels
+        .route(
+            "/api/project/{slug}/labels",
+            get(labels::list_labels).post(labels::create_label),
+        )
+        .route(
+            "/api/project/{slug}/labels/{id}",
+            delete(labels::delete_label),
+        )
+        .route(
+            "/api/message/{id}/labels",
+            get(labels::list_message_labels).post(labels::update_message_labels),
+        )
+        // Live updates (SSE)
+        .route("/api/events", get(events::event_stream))
         // Core
         // ..
         // Export
         .route("/api/export", post(export::export_mailbox))
+        .route("/api/export/messages", post(export::export_messages))
+        .route("/api/export/import", post(export::import_mailbox))
+        .route("/api/project/{slug}/export", get(export::export_project))
         // Attachments
         .route("/api/health", get(tools::health_check))
         .route("/api/health_check", get(tools::health_check)) // Python alias
         .route("/api/ready", get(tools::readiness_check))
+        .route("/api/server-info", get(server_info::server_info))
+        .route("/api/get_server_info", get(server_info::server_info)) // Python alias
+        .route("/api/admin/backup", post(backup::create_backup))
         .route("/api/readiness", get(tools::readiness_check)) // Alias
         .route("/api/project/ensure", post(tools::ensure_project))
         .route("/api/ensure_project", post(tools::ensure_project)) // Python alias
         .route("/api/projects", get(tools::list_all_projects))
+        .route("/api/projects/stats", get(project_stats::all_project_stats))
         .route("/api/list_projects", get(tools::list_all_projects)) // Python alias
         .route("/api/list_all_projects", get(tools::list_all_projects)) // Python alias
         .route(
@@ -40,6 +166,11 @@ pub fn routes() -> Router<AppState> {
             "/api/projects/{project_slug}/agents/{agent_name}",
             delete(tools::delete_agent),
         )
+        // Agent lifecycle: PATCH updates, DELETE retires (history is kept)
+        .route(
+            "/api/project/{project_slug}/agent/{agent_name}",
+            patch(tools::update_agent).delete(tools::retire_agent),
+        )
         // Identity
         .route("/api/agent/register", post(tools::register_agent))
         .route("/api/register_agent", post(tools::register_agent)) // Python alias
@@ -62,7 +193,17 @@ pub fn routes() -> Router<AppState> {
         .route("/api/mark_message_read", post(tools::mark_message_read)) // Python alias
         .route("/api/message/acknowledge", post(tools::acknowledge_message))
         .route("/api/acknowledge_message", post(tools::acknowledge_message)) // Python alias
+        .route("/api/message/recall", post(tools::recall_message))
+        .route("/api/recall_message", post(tools::recall_message)) // Python alias
+        .route("/api/message/scheduled", post(tools::list_scheduled))
+        .route(
+            "/api/message/cancel_scheduled",
+            post(tools::cancel_scheduled),
+        )
+        .route("/api/message/snooze", post(tools::snooze_message))
+        .route("/api/message/snoozed", post(tools::list_snoozed))
         .route("/api/messages/search", post(tools::search_messages))
+        .route("/api/messages/mark-read", post(messages::mark_read_batch))
         .route("/api/search_messages", post(tools::search_messages)) // Python alias
         // Pending Reviews (ack_required messages awaiting acknowledgment)
         .route(
@@ -102,6 +243,10 @@ pub fn routes() -> Router<AppState> {
             post(tools::list_file_reservations),
         ) // Python alias
         .route("/api/reservations", post(tools::list_file_reservations)) // Python alias (short)
+        .route(
+            "/api/file_reservations/queue",
+            post(tools::list_reservation_queue),
+        )
         // File Locks API (cross-project view for web UI dashboard)
         .route("/mail/api/locks", get(tools::list_all_locks))
         .route("/api/locks", get(tools::list_all_locks)) // Alias without /mail prefix
@@ -257,6 +402,10 @@ pub fn routes() -> Router<AppState> {
         .route("/api/metrics/tools", get(tools::list_tool_metrics))
         .route("/api/list_tool_metrics", get(tools::list_tool_metrics)) // Python alias
         .route("/api/metrics/tools/stats", get(tools::get_tool_stats))
+        .route(
+            "/api/metrics/tools/summary",
+            get(tools::get_tool_metrics_summary),
+        )
         .route("/api/get_tool_stats", get(tools::get_tool_stats)) // Python alias
         .route("/api/tool_stats", get(tools::get_tool_stats)) // Python alias (short)
         .route("/api/activity", get(tools::list_activity))
@@ -264,6 +413,8 @@ pub fn routes() -> Router<AppState> {
         // Archive
         .route("/api/archive/commit", post(tools::commit_archive))
         .route("/api/commit_archive", post(tools::commit_archive)) // Python alias
+        .route("/api/archive/verify", post(tools::verify_archive))
+        .route("/api/verify_archive", post(tools::verify_archive)) // Python alias
         // Archive Browser
         .route("/api/archive/commits", get(tools::list_archive_commits))
         .route("/api/archive/commits/{sha}", get(tools::get_archive_commit))
@@ -0,0 +1,62 @@
+//! Agent activity HTTP handler
+//!
+//! Backs the web UI's agent detail page: profile, capabilities, active file
+//! reservations, pending acknowledgements and an interleaved sent/received
+//! message timeline, joined server-side in one request.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+};
+use mouchak_mail_core::model::activity::ActivityBmc;
+use mouchak_mail_core::model::project::ProjectBmc;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+
+/// Default number of timeline entries
+const DEFAULT_LIMIT: i64 = 50;
+/// Upper bound on requested timeline entries
+const MAX_LIMIT: i64 = 200;
+
+/// Query parameters for the agent activity endpoint
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AgentActivityParams {
+    /// Maximum timeline entries (default 50, max 200)
+    pub limit: Option<i64>,
+}
+
+/// GET /api/project/{slug}/agent/{name}/activity
+///
+/// Agent metadata, non-expired capabilities, active file reservations,
+/// pending acknowledgements and recent sent/received messages, newest first.
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/agent/{name}/activity",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ("name" = String, Path, description = "Agent name"),
+        AgentActivityParams
+    ),
+    responses(
+        (status = 200, description = "Agent profile, capabilities, reservations, pending acks and message timeline"),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
+pub async fn agent_activity(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((slug, name)): Path<(String, String)>,
+    Query(params): Query<AgentActivityParams>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let activity = ActivityBmc::for_agent(&ctx, mm, project.id, &name, limit).await?;
+
+    Ok(Json(activity).into_response())
+}
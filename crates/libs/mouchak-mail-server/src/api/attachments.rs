@@ -1,5 +1,5 @@
 use crate::AppState;
-use crate::auth::AuthenticatedUser;
+use crate::auth::{AuthenticatedUser, RequestCtx};
 use axum::http::header;
 use axum::{
     Extension, Json,
@@ -8,7 +8,6 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use base64::Engine;
-use mouchak_mail_core::Ctx;
 use mouchak_mail_core::model::agent::AgentBmc;
 use mouchak_mail_core::model::attachment::{AttachmentBmc, AttachmentForCreate};
 use mouchak_mail_core::model::project::ProjectBmc;
@@ -44,11 +43,11 @@ pub struct AddAttachmentResponse {
     )
 )]
 pub async fn add_attachment(
+    RequestCtx(ctx): RequestCtx,
     State(state): State<AppState>,
     auth_user: Option<Extension<AuthenticatedUser>>,
     Json(payload): Json<AddAttachmentPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &state.mm;
 
     if auth_user.is_none() {
@@ -147,12 +146,11 @@ pub struct ListAttachmentsParams {
     )
 )]
 pub async fn list_attachments(
+    RequestCtx(ctx): RequestCtx,
     State(state): State<AppState>,
     auth_user: Option<Extension<AuthenticatedUser>>,
     Query(params): Query<ListAttachmentsParams>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
-
     if auth_user.is_none() {
         warn!(
             "list_attachments called without authenticated user for project: {}",
@@ -197,12 +195,12 @@ pub struct GetAttachmentParams {
     )
 )]
 pub async fn get_attachment(
+    RequestCtx(ctx): RequestCtx,
     State(state): State<AppState>,
     auth_user: Option<Extension<AuthenticatedUser>>,
     Path(id): Path<i64>,
     Query(params): Query<GetAttachmentParams>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &state.mm;
 
     if auth_user.is_none() {
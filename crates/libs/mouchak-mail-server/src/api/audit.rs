@@ -0,0 +1,77 @@
+//! Audit log HTTP handler
+//!
+//! Lists a project's administrative actions (force releases, adoptions,
+//! deletions, recalls, retention prunes), newest first.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+};
+use mouchak_mail_core::model::audit::{AuditAction, AuditBmc, AuditFilter, AuditPage};
+use mouchak_mail_core::model::project::ProjectBmc;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+
+/// Default page size for the audit endpoint
+const DEFAULT_LIMIT: i64 = 50;
+/// Upper bound on requested page size
+const MAX_LIMIT: i64 = 200;
+
+/// Query parameters for the audit endpoint
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AuditParams {
+    /// Only this action, e.g. `agent.delete`
+    pub action: Option<String>,
+    /// Only this entity type: `project`, `agent`, `message` or `reservation`
+    pub entity_type: Option<String>,
+    /// Only this entity ID
+    pub entity_id: Option<i64>,
+    /// Page size (default 50, max 200)
+    pub limit: Option<i64>,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<i64>,
+}
+
+/// GET /api/project/{slug}/audit
+///
+/// Audit log entries for the project, newest first.
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/audit",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        AuditParams
+    ),
+    responses(
+        (status = 200, description = "One page of audit log entries", body = AuditPage),
+        (status = 400, description = "Unknown action"),
+        (status = 404, description = "Project not found")
+    )
+)]
+pub async fn project_audit(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(params): Query<AuditParams>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let filter = AuditFilter {
+        action: params
+            .action
+            .as_deref()
+            .map(str::parse::<AuditAction>)
+            .transpose()?,
+        entity_type: params.entity_type,
+        entity_id: params.entity_id,
+    };
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let page = AuditBmc::list(&ctx, mm, project.id, &filter, limit, params.cursor).await?;
+
+    Ok(Json(page).into_response())
+}
@@ -0,0 +1,27 @@
+//! Database backup HTTP handler
+//!
+//! POST /api/admin/backup takes a snapshot of the database into the backup
+//! directory, rotating old ones, and reports where it went.
+
+use axum::{Json, extract::State};
+use mouchak_mail_core::model::backup::{BackupBmc, BackupInfo};
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+
+/// POST /api/admin/backup
+#[utoipa::path(
+    post,
+    path = "/api/admin/backup",
+    responses(
+        (status = 200, description = "Snapshot path, size and time", body = BackupInfo),
+        (status = 403, description = "Token is scoped to specific projects")
+    )
+)]
+pub async fn create_backup(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+) -> crate::error::Result<Json<BackupInfo>> {
+    let info = BackupBmc::create(&ctx, &app_state.mm).await?;
+    Ok(Json(info))
+}
@@ -0,0 +1,125 @@
+//! Live event stream HTTP handler
+//!
+//! Streams mailbox changes (`message.created`, `message.read`, `message.recalled`,
+//! `message.acked`, `reservation.created`, `reservation.released`,
+//! `reservation.conflict`, ...) as Server-Sent Events.
+
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use futures::stream::{self, Stream, StreamExt};
+use mouchak_mail_core::events::MailEvent;
+use serde::Deserialize;
+use std::convert::Infallible;
+use tokio::sync::broadcast::error::RecvError;
+use utoipa::IntoParams;
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+
+/// Query parameters for the event stream endpoint
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct EventStreamParams {
+    /// Only forward events for this project slug
+    pub project: Option<String>,
+    /// Replay events after this ID (fallback when the `Last-Event-ID` header can't be set)
+    pub last_event_id: Option<u64>,
+}
+
+/// GET /api/events
+///
+/// Replays events missed since `Last-Event-ID` (when still retained), then
+/// streams new events as they are published. The broadcast receiver lives in
+/// the response stream and is dropped when the client disconnects. Events for
+/// projects outside the caller's token scope are never forwarded.
+#[utoipa::path(
+    get,
+    path = "/api/events",
+    params(EventStreamParams),
+    responses(
+        (status = 200, description = "Server-Sent Events stream of mailbox changes", body = String, content_type = "text/event-stream")
+    )
+)]
+pub async fn event_stream(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Query(params): Query<EventStreamParams>,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let bus = app_state.mm.events.clone();
+
+    // Subscribe before reading history so nothing published in between is lost.
+    let receiver = bus.subscribe();
+
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .or(params.last_event_id)
+        // An ID from before a server restart is meaningless; start fresh.
+        .filter(|id| *id <= bus.latest_id());
+
+    let replay = last_event_id
+        .map(|id| bus.replay_since(id))
+        .unwrap_or_default();
+    let cursor = replay.last().map(|e| e.id).or(last_event_id).unwrap_or(0);
+
+    let project = params.project;
+    let replay_project = project.clone();
+    let replay_ctx = ctx.clone();
+    let replayed = stream::iter(
+        replay
+            .into_iter()
+            .filter(move |e| {
+                replay_ctx.can_access_project(&e.project_slug)
+                    && matches_project(e, replay_project.as_deref())
+            })
+            .map(|e| Ok(to_sse_event(&e))),
+    );
+
+    let live = stream::unfold(
+        (receiver, cursor, project, ctx),
+        |(mut receiver, cursor, project, ctx)| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        // Skip anything already delivered during replay.
+                        if event.id <= cursor
+                            || !ctx.can_access_project(&event.project_slug)
+                            || !matches_project(&event, project.as_deref())
+                        {
+                            continue;
+                        }
+                        let next = event.id;
+                        return Some((Ok(to_sse_event(&event)), (receiver, next, project, ctx)));
+                    }
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "SSE client lagged behind event bus");
+                        // Tell the client its view is stale so it can refetch.
+                        let resync = Event::default().event("resync").data("{}");
+                        return Some((Ok(resync), (receiver, cursor, project, ctx)));
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        },
+    );
+
+    // End the stream on shutdown so the connection doesn't stall draining.
+    let closed = async move { bus.closed().await };
+    Sse::new(replayed.chain(live).take_until(closed)).keep_alive(KeepAlive::default())
+}
+
+fn matches_project(event: &MailEvent, project: Option<&str>) -> bool {
+    project.is_none_or(|slug| event.project_slug == slug)
+}
+
+fn to_sse_event(event: &MailEvent) -> Event {
+    let data = serde_json::to_string(event).unwrap_or_else(|_| "{}".to_string());
+    Event::default()
+        .id(event.id.to_string())
+        .event(event.kind.as_str())
+        .data(data)
+}
@@ -1,19 +1,74 @@
 use crate::AppState;
-use axum::http::header;
+use crate::auth::RequestCtx;
+use axum::body::Body;
+use axum::http::{HeaderMap, header};
 use axum::{
     Json,
-    extract::State,
+    extract::{Path, Query, State},
     response::{IntoResponse, Response},
 };
-use mouchak_mail_core::Ctx;
-use mouchak_mail_core::model::export::{ExportBmc, ExportFormat, ScrubMode};
+use base64::Engine;
+use mouchak_mail_core::model::export::{
+    ExportBmc, ExportCompression, ExportEncryption, ExportFilter, ExportFormat, ExportManifest,
+    ExportedMailbox, ImportReport, NdjsonExportStream, ScrubMode,
+};
 use serde::Deserialize;
-use utoipa::ToSchema;
+use tokio::io::AsyncWriteExt;
+use tokio_util::io::ReaderStream;
+use utoipa::{IntoParams, ToSchema};
+
+/// Header carrying the passphrase for passphrase-encrypted exports (kept out of the URL)
+const EXPORT_PASSPHRASE_HEADER: &str = "x-export-passphrase";
+/// Response header carrying the base64-encoded JSON manifest of an encrypted export
+const EXPORT_MANIFEST_HEADER: &str = "x-export-manifest";
 
 #[derive(Deserialize, ToSchema)]
 pub struct ExportPayload {
     pub project_slug: String,
-    pub format: String, // "json", "html", "md", "csv"
+    pub format: String, // "json", "html", "html-zip", "md", "csv", "tsv", "ndjson", "mbox"
+    /// Only export messages created at or after this date (RFC3339 or YYYY-MM-DD)
+    #[serde(default)]
+    pub since: Option<String>,
+    /// Only export messages created at or before this date (RFC3339 or YYYY-MM-DD)
+    #[serde(default)]
+    pub until: Option<String>,
+    /// Only export messages sent or received by this agent
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// Only export messages in this thread
+    #[serde(default)]
+    pub thread: Option<String>,
+    /// Only export messages with this importance ("normal" or "high")
+    #[serde(default)]
+    pub importance: Option<String>,
+    /// Redaction mode: none, standard, aggressive, emails, secrets, all
+    #[serde(default)]
+    pub scrub: Option<String>,
+    /// Compression: none, gzip, zstd. Sent as-is with `Content-Encoding`
+    /// when the client's `Accept-Encoding` allows it, otherwise decompressed
+    #[serde(default)]
+    pub compression: Option<String>,
+}
+
+impl ExportPayload {
+    /// Build the export filter from the optional payload fields
+    fn filter(&self) -> mouchak_mail_core::Result<ExportFilter> {
+        Ok(ExportFilter {
+            since: self
+                .since
+                .as_deref()
+                .map(|s| ExportFilter::parse_date_bound(s, false))
+                .transpose()?,
+            until: self
+                .until
+                .as_deref()
+                .map(|s| ExportFilter::parse_date_bound(s, true))
+                .transpose()?,
+            agent_name: self.agent.clone(),
+            thread_id: self.thread.clone(),
+            importance: self.importance.clone(),
+        })
+    }
 }
 
 // Note: for now keeping handler signatures simple for utoipa
@@ -26,44 +81,420 @@ pub struct ExportPayload {
     )
 )]
 pub async fn export_mailbox(
+    RequestCtx(ctx): RequestCtx,
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(payload): Json<ExportPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
+    let format = payload
+        .format
+        .parse::<ExportFormat>()
+        .unwrap_or(ExportFormat::Json);
+    let filter = payload.filter()?;
+    let scrub_mode = payload
+        .scrub
+        .as_deref()
+        .and_then(|s| s.parse::<ScrubMode>().ok())
+        .unwrap_or_default();
+    let compression = payload
+        .compression
+        .as_deref()
+        .unwrap_or_default()
+        .parse::<ExportCompression>()?;
+
+    // Determine content type and extension
+    let (content_type, ext) = content_type_and_ext(format);
+
+    let filename = format!("{}_mailbox.{}", payload.project_slug, ext);
+
+    // Compressed NDJSON is buffered like the other formats
+    let (body, encoding) = if format == ExportFormat::Ndjson && compression.is_none() {
+        let stream = ExportBmc::export_ndjson_stream(
+            &ctx,
+            &state.mm,
+            &payload.project_slug,
+            scrub_mode,
+            &filter,
+        )
+        .await?;
+        (ndjson_body(stream), None)
+    } else {
+        let exported = ExportBmc::export_mailbox(
+            &ctx,
+            &state.mm,
+            &payload.project_slug,
+            format,
+            scrub_mode,
+            false,
+            &filter,
+            compression,
+        )
+        .await?;
+        negotiate_encoding(exported, &headers)?
+    };
+
+    build_response(content_type, &filename, body, encoding, None)
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ExportMessagesPayload {
+    /// Messages to export
+    pub message_ids: Vec<i64>,
+    pub format: String, // "json", "html", "html-zip", "md", "csv", "tsv", "ndjson", "mbox"
+    /// Redaction mode: none, standard, aggressive, emails, secrets, all
+    #[serde(default)]
+    pub scrub: Option<String>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/export/messages",
+    request_body = ExportMessagesPayload,
+    responses(
+        (status = 200, description = "Export the selected messages", body = String, content_type = "text/plain"),
+        (status = 400, description = "No ids, or too many ids"),
+        (status = 403, description = "Message outside the caller's projects")
+    )
+)]
+pub async fn export_messages(
+    RequestCtx(ctx): RequestCtx,
+    State(state): State<AppState>,
+    Json(payload): Json<ExportMessagesPayload>,
+) -> crate::error::Result<Response> {
+    let format = payload
+        .format
+        .parse::<ExportFormat>()
+        .unwrap_or(ExportFormat::Json);
+    let scrub_mode = payload
+        .scrub
+        .as_deref()
+        .and_then(|s| s.parse::<ScrubMode>().ok())
+        .unwrap_or_default();
 
+    let exported =
+        ExportBmc::export_messages(&ctx, &state.mm, &payload.message_ids, format, scrub_mode)
+            .await?;
+    let (content_type, ext) = content_type_and_ext(format);
+
+    build_response(
+        content_type,
+        &format!("{}_messages.{}", exported.project_slug, ext),
+        Body::from(exported.content_bytes()?),
+        None,
+        None,
+    )
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ImportPayload {
+    /// Project to import into
+    pub project_slug: String,
+    /// Format of `content`: "json" or "ndjson"
+    pub format: String,
+    /// Export content as produced by /api/export
+    pub content: String,
+    /// Manifest of the export; verified before anything is imported
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub manifest: Option<ExportManifest>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/export/import",
+    request_body = ImportPayload,
+    responses(
+        (status = 200, description = "Import report", body = ImportReport),
+        (status = 400, description = "Unsupported format, unparseable content, or manifest verification failed"),
+        (status = 404, description = "Project not found")
+    )
+)]
+pub async fn import_mailbox(
+    RequestCtx(ctx): RequestCtx,
+    State(state): State<AppState>,
+    Json(payload): Json<ImportPayload>,
+) -> crate::error::Result<Response> {
     let format = payload
         .format
         .parse::<ExportFormat>()
         .unwrap_or(ExportFormat::Json);
 
-    let exported = ExportBmc::export_mailbox(
+    let report = ExportBmc::import_mailbox(
         &ctx,
         &state.mm,
         &payload.project_slug,
+        &payload.content,
+        format,
+        payload.manifest.as_ref(),
+    )
+    .await?;
+
+    Ok(Json(report).into_response())
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct ProjectExportQuery {
+    /// Export format: json, html, html-zip, md, csv, tsv, ndjson, mbox
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Redaction mode: none, standard, aggressive, emails, secrets, all
+    #[serde(default)]
+    pub scrub: Option<String>,
+    /// Encryption scheme; only "age" is supported
+    #[serde(default)]
+    pub encrypt: Option<String>,
+    /// Comma-separated age recipients (age1...)
+    #[serde(default)]
+    pub recipient: Option<String>,
+    /// Compression of unencrypted exports: none, gzip, zstd. Sent as-is with
+    /// `Content-Encoding` when `Accept-Encoding` allows it, otherwise decompressed
+    #[serde(default)]
+    pub compression: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/export",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ProjectExportQuery
+    ),
+    responses(
+        (status = 200, description = "Export mailbox, armored age output when encrypt=age", body = String, content_type = "text/plain"),
+        (status = 400, description = "Invalid encryption options or age recipient")
+    )
+)]
+pub async fn export_project(
+    RequestCtx(ctx): RequestCtx,
+    State(state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(query): Query<ProjectExportQuery>,
+    headers: HeaderMap,
+) -> crate::error::Result<Response> {
+    let format = query
+        .format
+        .as_deref()
+        .unwrap_or("json")
+        .parse::<ExportFormat>()
+        .unwrap_or(ExportFormat::Json);
+    let scrub_mode = query
+        .scrub
+        .as_deref()
+        .and_then(|s| s.parse::<ScrubMode>().ok())
+        .unwrap_or_default();
+
+    let recipients: Vec<String> = query
+        .recipient
+        .as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|r| !r.is_empty())
+        .map(str::to_string)
+        .collect();
+    let passphrase = headers
+        .get(EXPORT_PASSPHRASE_HEADER)
+        .map(|v| {
+            v.to_str().map(str::to_string).map_err(|_| {
+                crate::ServerError::BadRequest(format!(
+                    "{} must be valid ASCII",
+                    EXPORT_PASSPHRASE_HEADER
+                ))
+            })
+        })
+        .transpose()?;
+
+    match query.encrypt.as_deref() {
+        Some("age") => {}
+        Some(other) => {
+            return Err(crate::ServerError::BadRequest(format!(
+                "Unsupported encryption '{}', expected 'age'",
+                other
+            )));
+        }
+        None if !recipients.is_empty() || passphrase.is_some() => {
+            return Err(crate::ServerError::BadRequest(
+                "Recipients or passphrase given without encrypt=age".to_string(),
+            ));
+        }
+        None => {
+            let compression = query
+                .compression
+                .as_deref()
+                .unwrap_or_default()
+                .parse::<ExportCompression>()?;
+            let exported = ExportBmc::export_mailbox(
+                &ctx,
+                &state.mm,
+                &slug,
+                format,
+                scrub_mode,
+                false,
+                &ExportFilter::default(),
+                compression,
+            )
+            .await?;
+            let (content_type, ext) = content_type_and_ext(format);
+            let (body, encoding) = negotiate_encoding(exported, &headers)?;
+            return build_response(
+                content_type,
+                &format!("{}_mailbox.{}", slug, ext),
+                body,
+                encoding,
+                None,
+            );
+        }
+    }
+
+    let encryption = ExportEncryption::from_parts(recipients, passphrase)?;
+    let (encrypted, manifest) = ExportBmc::export_mailbox_encrypted(
+        &ctx,
+        &state.mm,
+        &slug,
         format,
-        ScrubMode::None,
+        scrub_mode,
         false,
+        &encryption,
+        None,
     )
     .await?;
 
-    // Determine content type and extension
-    let (content_type, ext) = match format {
+    let manifest_json = serde_json::to_vec(&manifest)
+        .map_err(|e| crate::ServerError::Internal(format!("Failed to encode manifest: {}", e)))?;
+    let (_, ext) = content_type_and_ext(format);
+
+    build_response(
+        "text/plain; charset=utf-8",
+        &format!("{}_mailbox.{}.age", slug, ext),
+        Body::from(encrypted),
+        None,
+        Some(base64::engine::general_purpose::STANDARD.encode(manifest_json)),
+    )
+}
+
+fn content_type_and_ext(format: ExportFormat) -> (&'static str, &'static str) {
+    match format {
         ExportFormat::Html => ("text/html", "html"),
+        ExportFormat::HtmlZip => ("application/zip", "zip"),
         ExportFormat::Json => ("application/json", "json"),
         ExportFormat::Markdown => ("text/markdown", "md"),
         ExportFormat::Csv => ("text/csv", "csv"),
-    };
+        ExportFormat::Tsv => ("text/tab-separated-values", "tsv"),
+        ExportFormat::Ndjson => ("application/x-ndjson", "ndjson"),
+        ExportFormat::Mbox => ("application/mbox", "mbox"),
+    }
+}
 
-    let filename = format!("{}_mailbox.{}", payload.project_slug, ext);
+/// Pick the response body for a possibly compressed export.
+///
+/// A compressed export is sent precompressed, with its `Content-Encoding`,
+/// when the client's `Accept-Encoding` allows that encoding; otherwise it is
+/// decompressed. Either way the client ends up with the content the export's
+/// manifest hashes.
+fn negotiate_encoding(
+    exported: ExportedMailbox,
+    headers: &HeaderMap,
+) -> crate::error::Result<(Body, Option<&'static str>)> {
+    let compression = exported.compression;
+    if compression.is_none() {
+        return Ok((Body::from(exported.content_bytes()?), None));
+    }
+    if accepts_encoding(headers, compression.as_str()) {
+        return Ok((
+            Body::from(exported.content_bytes()?),
+            Some(compression.as_str()),
+        ));
+    }
+    Ok((Body::from(exported.decompress()?.content_bytes()?), None))
+}
+
+/// True if `Accept-Encoding` allows `encoding` with a nonzero q-value, either
+/// by name or through `*`; a named entry overrides the wildcard.
+fn accepts_encoding(headers: &HeaderMap, encoding: &str) -> bool {
+    let mut named = None;
+    let mut wildcard = None;
+    for item in headers
+        .get_all(header::ACCEPT_ENCODING)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+    {
+        let mut parts = item.split(';').map(str::trim);
+        let token = parts.next().unwrap_or_default();
+        let q = parts
+            .find_map(|p| p.strip_prefix("q="))
+            .and_then(|q| q.parse::<f32>().ok())
+            .unwrap_or(1.0);
+        if token.eq_ignore_ascii_case(encoding) {
+            named = Some(q);
+        } else if token == "*" {
+            wildcard = Some(q);
+        }
+    }
+    named.or(wildcard).is_some_and(|q| q > 0.0)
+}
 
-    let response = Response::builder()
+fn build_response(
+    content_type: &str,
+    filename: &str,
+    body: Body,
+    content_encoding: Option<&str>,
+    manifest_b64: Option<String>,
+) -> crate::error::Result<Response> {
+    let mut builder = Response::builder()
         .header(header::CONTENT_TYPE, content_type)
         .header(
             header::CONTENT_DISPOSITION,
             format!("attachment; filename=\"{}\"", filename),
-        )
-        .body(exported.content)
+        );
+    if let Some(encoding) = content_encoding {
+        builder = builder
+            .header(header::CONTENT_ENCODING, encoding)
+            .header(header::VARY, "accept-encoding");
+    }
+    if let Some(manifest) = manifest_b64 {
+        builder = builder.header(EXPORT_MANIFEST_HEADER, manifest);
+    }
+
+    let response = builder
+        .body(body)
         .map_err(|e| crate::ServerError::Internal(format!("Failed to build response: {}", e)))?;
 
     Ok(response.into_response())
 }
+
+/// Stream NDJSON lines into a response body as they are read from the database.
+///
+/// Rows are pumped through an in-memory pipe by a background task; if the
+/// client disconnects the write fails and the task stops reading.
+fn ndjson_body(mut stream: NdjsonExportStream) -> Body {
+    let (mut writer, reader) = tokio::io::duplex(64 * 1024);
+
+    tokio::spawn(async move {
+        loop {
+            match stream.next_line().await {
+                Ok(Some(line)) => {
+                    if writer.write_all(line.as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    tracing::warn!(
+                        project = stream.project_slug(),
+                        error = %e,
+                        "NDJSON export stream failed"
+                    );
+                    break;
+                }
+            }
+        }
+        tracing::debug!(
+            project = stream.project_slug(),
+            message_count = stream.message_count(),
+            "NDJSON export stream finished"
+        );
+    });
+
+    Body::from_stream(ReaderStream::new(reader))
+}
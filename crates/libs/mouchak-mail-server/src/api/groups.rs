@@ -0,0 +1,222 @@
+//! Agent group HTTP handlers
+//!
+//! Per-project named groups of agents. Messages addressed to
+//! `group:<name>` go to the group's members at send time. Group names are
+//! case-insensitive.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+};
+use mouchak_mail_core::model::agent_group::{AgentGroup, AgentGroupBmc};
+use mouchak_mail_core::model::project::ProjectBmc;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+
+/// Request body for POST /api/project/{slug}/groups
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateGroupPayload {
+    /// Group name: 2-64 ASCII letters, digits, '_' or '-', not an agent's name
+    pub name: String,
+    /// Agent names to add right away
+    #[serde(default)]
+    pub members: Vec<String>,
+}
+
+/// Request body for POST /api/project/{slug}/groups/{name}/members
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct AddGroupMemberPayload {
+    /// Agent to add
+    pub agent_name: String,
+}
+
+/// Response for DELETE /api/project/{slug}/groups/{name}
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeleteGroupResponse {
+    pub deleted: bool,
+    pub name: String,
+}
+
+/// GET /api/project/{slug}/groups
+///
+/// Lists the project's groups and their members, ordered by name.
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/groups",
+    params(("slug" = String, Path, description = "Project slug")),
+    responses(
+        (status = 200, description = "Project groups", body = [AgentGroup]),
+        (status = 404, description = "Project not found")
+    )
+)]
+pub async fn list_groups(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path(slug): Path<String>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let groups = AgentGroupBmc::list_for_project(&ctx, mm, project.id).await?;
+
+    Ok(Json(groups).into_response())
+}
+
+/// POST /api/project/{slug}/groups
+///
+/// Creates a group, optionally with members, and returns it.
+#[utoipa::path(
+    post,
+    path = "/api/project/{slug}/groups",
+    params(("slug" = String, Path, description = "Project slug")),
+    request_body = CreateGroupPayload,
+    responses(
+        (status = 200, description = "Group created", body = AgentGroup),
+        (status = 400, description = "Invalid group name"),
+        (status = 404, description = "Project or member agent not found"),
+        (status = 409, description = "A group or agent already has this name")
+    )
+)]
+pub async fn create_group(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path(slug): Path<String>,
+    Json(payload): Json<CreateGroupPayload>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    AgentGroupBmc::create(&ctx, mm, project.id, &payload.name).await?;
+    for member in &payload.members {
+        AgentGroupBmc::add_member(&ctx, mm, project.id, &payload.name, member).await?;
+    }
+    let group = AgentGroupBmc::get_by_name(&ctx, mm, project.id, &payload.name).await?;
+
+    Ok(Json(group).into_response())
+}
+
+/// GET /api/project/{slug}/groups/{name}
+///
+/// Returns one group and its members.
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/groups/{name}",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ("name" = String, Path, description = "Group name (case-insensitive)")
+    ),
+    responses(
+        (status = 200, description = "The group", body = AgentGroup),
+        (status = 404, description = "Project or group not found")
+    )
+)]
+pub async fn get_group(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((slug, name)): Path<(String, String)>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let group = AgentGroupBmc::get_by_name(&ctx, mm, project.id, &name).await?;
+
+    Ok(Json(group).into_response())
+}
+
+/// DELETE /api/project/{slug}/groups/{name}
+///
+/// Deletes the group. Messages already sent to it are unaffected.
+#[utoipa::path(
+    delete,
+    path = "/api/project/{slug}/groups/{name}",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ("name" = String, Path, description = "Group name (case-insensitive)")
+    ),
+    responses(
+        (status = 200, description = "Group deleted", body = DeleteGroupResponse),
+        (status = 404, description = "Project or group not found")
+    )
+)]
+pub async fn delete_group(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((slug, name)): Path<(String, String)>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    AgentGroupBmc::delete(&ctx, mm, project.id, &name).await?;
+
+    Ok(Json(DeleteGroupResponse {
+        deleted: true,
+        name,
+    })
+    .into_response())
+}
+
+/// POST /api/project/{slug}/groups/{name}/members
+///
+/// Adds an agent to the group and returns the group.
+#[utoipa::path(
+    post,
+    path = "/api/project/{slug}/groups/{name}/members",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ("name" = String, Path, description = "Group name (case-insensitive)")
+    ),
+    request_body = AddGroupMemberPayload,
+    responses(
+        (status = 200, description = "The group after the change", body = AgentGroup),
+        (status = 404, description = "Project, group or agent not found")
+    )
+)]
+pub async fn add_group_member(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((slug, name)): Path<(String, String)>,
+    Json(payload): Json<AddGroupMemberPayload>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    AgentGroupBmc::add_member(&ctx, mm, project.id, &name, &payload.agent_name).await?;
+    let group = AgentGroupBmc::get_by_name(&ctx, mm, project.id, &name).await?;
+
+    Ok(Json(group).into_response())
+}
+
+/// DELETE /api/project/{slug}/groups/{name}/members/{agent_name}
+///
+/// Removes an agent from the group and returns the group. Messages the
+/// agent already received through the group are unaffected.
+#[utoipa::path(
+    delete,
+    path = "/api/project/{slug}/groups/{name}/members/{agent_name}",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ("name" = String, Path, description = "Group name (case-insensitive)"),
+        ("agent_name" = String, Path, description = "Agent to remove")
+    ),
+    responses(
+        (status = 200, description = "The group after the change", body = AgentGroup),
+        (status = 404, description = "Project, group or agent not found")
+    )
+)]
+pub async fn remove_group_member(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((slug, name, agent_name)): Path<(String, String, String)>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    AgentGroupBmc::remove_member(&ctx, mm, project.id, &name, &agent_name).await?;
+    let group = AgentGroupBmc::get_by_name(&ctx, mm, project.id, &name).await?;
+
+    Ok(Json(group).into_response())
+}
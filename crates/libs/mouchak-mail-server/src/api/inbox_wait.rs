@@ -0,0 +1,77 @@
+//! Inbox long-poll HTTP handler
+//!
+//! Lets agents block on their inbox instead of polling it. The wait parks on
+//! the same event bus that feeds the SSE stream, so a send wakes it
+//! immediately.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+};
+use mouchak_mail_core::model::agent::AgentBmc;
+use mouchak_mail_core::model::message::{MAX_INBOX_WAIT_SECS, Message, MessageBmc};
+use mouchak_mail_core::model::project::ProjectBmc;
+use serde::Deserialize;
+use std::time::Duration;
+use utoipa::IntoParams;
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+
+/// Wait used when the request doesn't set one
+const DEFAULT_TIMEOUT_SECS: u64 = 30;
+
+/// Query parameters for the inbox wait endpoint
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct InboxWaitParams {
+    /// Seconds to wait for new mail (default 30, max 120)
+    pub timeout_seconds: Option<u64>,
+    /// Only messages with a greater ID count as new (default: messages
+    /// arriving after the request)
+    pub since_message_id: Option<i64>,
+}
+
+/// GET /api/project/{slug}/agent/{name}/inbox/wait
+///
+/// Returns the agent's new messages, newest first, as soon as any arrive.
+/// Responds with an empty list once the timeout passes.
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/agent/{name}/inbox/wait",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ("name" = String, Path, description = "Agent name"),
+        InboxWaitParams
+    ),
+    responses(
+        (status = 200, description = "New inbox messages, empty on timeout", body = Vec<Message>),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
+pub async fn wait_for_inbox(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((slug, name)): Path<(String, String)>,
+    Query(params): Query<InboxWaitParams>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let agent = AgentBmc::get_by_name(&ctx, mm, project.id, &name).await?;
+    let timeout_seconds = params
+        .timeout_seconds
+        .unwrap_or(DEFAULT_TIMEOUT_SECS)
+        .min(MAX_INBOX_WAIT_SECS);
+    let messages = MessageBmc::wait_for_inbox(
+        &ctx,
+        mm,
+        project.id.get(),
+        agent.id.get(),
+        params.since_message_id,
+        Duration::from_secs(timeout_seconds),
+    )
+    .await?;
+
+    Ok(Json(messages).into_response())
+}
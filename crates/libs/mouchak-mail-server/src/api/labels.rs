@@ -0,0 +1,200 @@
+//! Message label HTTP handlers
+//!
+//! Per-project label definitions plus applying and removing labels on
+//! individual messages. Label names are case-insensitive.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+};
+use mouchak_mail_core::model::label::{Label, LabelBmc, LabelForCreate};
+use mouchak_mail_core::model::project::ProjectBmc;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+
+/// Request body for POST /api/project/{slug}/labels
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateLabelPayload {
+    /// Label name, unique within the project ignoring case
+    pub name: String,
+    /// Display color as #rrggbb (default: #6b7280)
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+/// Response for DELETE /api/project/{slug}/labels/{id}
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeleteLabelResponse {
+    pub deleted: bool,
+    pub label_id: i64,
+}
+
+/// Request body for POST /api/message/{id}/labels
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct UpdateMessageLabelsPayload {
+    /// Label names to apply
+    #[serde(default)]
+    pub add: Vec<String>,
+    /// Label names to remove
+    #[serde(default)]
+    pub remove: Vec<String>,
+    /// Create labels in `add` that the project does not have yet
+    #[serde(default)]
+    pub create_missing: bool,
+}
+
+/// GET /api/project/{slug}/labels
+///
+/// Lists the project's labels, ordered by name.
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/labels",
+    params(("slug" = String, Path, description = "Project slug")),
+    responses(
+        (status = 200, description = "Project labels", body = [Label]),
+        (status = 404, description = "Project not found")
+    )
+)]
+pub async fn list_labels(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path(slug): Path<String>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let labels = LabelBmc::list_for_project(&ctx, mm, project.id).await?;
+
+    Ok(Json(labels).into_response())
+}
+
+/// POST /api/project/{slug}/labels
+///
+/// Creates a label and returns it.
+#[utoipa::path(
+    post,
+    path = "/api/project/{slug}/labels",
+    params(("slug" = String, Path, description = "Project slug")),
+    request_body = CreateLabelPayload,
+    responses(
+        (status = 200, description = "Label created", body = Label),
+        (status = 400, description = "Empty name or invalid color"),
+        (status = 404, description = "Project not found"),
+        (status = 409, description = "A label with this name already exists")
+    )
+)]
+pub async fn create_label(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path(slug): Path<String>,
+    Json(payload): Json<CreateLabelPayload>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let label_id = LabelBmc::create(
+        &ctx,
+        mm,
+        LabelForCreate {
+            project_id: project.id,
+            name: payload.name,
+            color: payload.color,
+        },
+    )
+    .await?;
+    let label = LabelBmc::get(&ctx, mm, project.id, label_id).await?;
+
+    Ok(Json(label).into_response())
+}
+
+/// DELETE /api/project/{slug}/labels/{id}
+///
+/// Deletes the label and removes it from every message. The messages are
+/// kept.
+#[utoipa::path(
+    delete,
+    path = "/api/project/{slug}/labels/{id}",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ("id" = i64, Path, description = "Label ID")
+    ),
+    responses(
+        (status = 200, description = "Label deleted", body = DeleteLabelResponse),
+        (status = 404, description = "Project or label not found")
+    )
+)]
+pub async fn delete_label(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((slug, label_id)): Path<(String, i64)>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    LabelBmc::delete(&ctx, mm, project.id, label_id).await?;
+
+    Ok(Json(DeleteLabelResponse {
+        deleted: true,
+        label_id,
+    })
+    .into_response())
+}
+
+/// GET /api/message/{id}/labels
+///
+/// Lists the labels applied to a message.
+#[utoipa::path(
+    get,
+    path = "/api/message/{id}/labels",
+    params(("id" = i64, Path, description = "Message ID")),
+    responses(
+        (status = 200, description = "Message labels", body = [Label]),
+        (status = 404, description = "Message not found")
+    )
+)]
+pub async fn list_message_labels(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path(message_id): Path<i64>,
+) -> crate::error::Result<Response> {
+    let labels = LabelBmc::list_for_message(&ctx, &app_state.mm, message_id).await?;
+
+    Ok(Json(labels).into_response())
+}
+
+/// POST /api/message/{id}/labels
+///
+/// Adds and removes labels on a message by name and returns its labels
+/// afterwards.
+#[utoipa::path(
+    post,
+    path = "/api/message/{id}/labels",
+    params(("id" = i64, Path, description = "Message ID")),
+    request_body = UpdateMessageLabelsPayload,
+    responses(
+        (status = 200, description = "Message labels after the change", body = [Label]),
+        (status = 404, description = "Message or label not found")
+    )
+)]
+pub async fn update_message_labels(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path(message_id): Path<i64>,
+    Json(payload): Json<UpdateMessageLabelsPayload>,
+) -> crate::error::Result<Response> {
+    let labels = LabelBmc::update_message_labels(
+        &ctx,
+        &app_state.mm,
+        message_id,
+        &payload.add,
+        &payload.remove,
+        payload.create_missing,
+    )
+    .await?;
+
+    Ok(Json(labels).into_response())
+}
@@ -0,0 +1,91 @@
+//! Bulk message HTTP handlers
+//!
+//! Batch operations on a selection of messages, used by the web UI's
+//! unified inbox bulk action bar.
+
+use axum::{
+    Json,
+    extract::State,
+    response::{IntoResponse, Response},
+};
+use mouchak_mail_core::model::agent::AgentBmc;
+use mouchak_mail_core::model::message::MessageBmc;
+use mouchak_mail_core::model::project::ProjectBmc;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+
+/// Request body for POST /api/messages/mark-read
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MarkReadBatchPayload {
+    /// Messages to mark read
+    pub message_ids: Vec<i64>,
+    /// Project of the agent; required with `agent_name`
+    #[serde(default)]
+    pub project_slug: Option<String>,
+    /// Mark read for this agent only; omit to mark every recipient, which
+    /// only callers not limited to some projects may do
+    #[serde(default)]
+    pub agent_name: Option<String>,
+}
+
+/// Response for POST /api/messages/mark-read
+#[derive(Debug, Serialize, ToSchema)]
+pub struct MarkReadBatchResponse {
+    /// Messages with at least one recipient newly marked read
+    pub marked: Vec<i64>,
+    /// Number of ids requested
+    pub requested: usize,
+}
+
+/// POST /api/messages/mark-read
+///
+/// Marks a list of messages read for one agent, or for all of their
+/// recipients when no agent is given. Agent keys may only mark their own
+/// agent's rows, and every message must be in the caller's projects.
+#[utoipa::path(
+    post,
+    path = "/api/messages/mark-read",
+    request_body = MarkReadBatchPayload,
+    responses(
+        (status = 200, description = "Messages marked read", body = MarkReadBatchResponse),
+        (status = 400, description = "Too many ids, agent_name without project_slug, or no agent from a project-scoped caller"),
+        (status = 403, description = "Message outside the caller's projects, or an agent key acting as another agent"),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
+pub async fn mark_read_batch(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Json(payload): Json<MarkReadBatchPayload>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let agent_id = match (&payload.project_slug, &payload.agent_name) {
+        (Some(slug), Some(name)) => {
+            let project = ProjectBmc::get_by_identifier(&ctx, mm, slug).await?;
+            Some(
+                AgentBmc::get_by_name(&ctx, mm, project.id, name)
+                    .await?
+                    .id
+                    .get(),
+            )
+        }
+        (None, Some(_)) => {
+            return Err(crate::ServerError::BadRequest(
+                "agent_name requires project_slug".to_string(),
+            ));
+        }
+        (_, None) => None,
+    };
+
+    let marked = MessageBmc::mark_read_batch(&ctx, mm, &payload.message_ids, agent_id).await?;
+
+    Ok(Json(MarkReadBatchResponse {
+        marked,
+        requested: payload.message_ids.len(),
+    })
+    .into_response())
+}
@@ -0,0 +1,93 @@
+//! Notification center HTTP handlers
+//!
+//! Outstanding acknowledgements, unread urgent mail and reservation conflicts
+//! for the caller, plus per-actor dismissal.
+
+use axum::{
+    Json,
+    extract::State,
+    response::{IntoResponse, Response},
+};
+use mouchak_mail_core::model::notification::{Notification, NotificationBmc};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+
+/// Response for GET /api/notifications
+#[derive(Debug, Serialize, ToSchema)]
+pub struct NotificationsResponse {
+    /// Agent name the notifications were computed for
+    pub actor: String,
+    pub count: usize,
+    /// Newest first
+    pub notifications: Vec<Notification>,
+}
+
+/// Request body for POST /api/notifications/dismiss
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DismissNotificationsPayload {
+    /// Notification keys to hide
+    pub keys: Vec<String>,
+}
+
+/// Response for POST /api/notifications/dismiss
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DismissNotificationsResponse {
+    /// Keys that were not dismissed already
+    pub dismissed: usize,
+}
+
+/// GET /api/notifications
+///
+/// Lists the caller's undismissed notifications. The caller is the token's
+/// agent, or the overseer when requests are unauthenticated.
+#[utoipa::path(
+    get,
+    path = "/api/notifications",
+    responses(
+        (status = 200, description = "Notifications, newest first", body = NotificationsResponse)
+    )
+)]
+pub async fn list_notifications(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+) -> crate::error::Result<Response> {
+    let notifications = NotificationBmc::list_for_actor(&ctx, &app_state.mm).await?;
+
+    Ok(Json(NotificationsResponse {
+        actor: NotificationBmc::actor_name(&ctx),
+        count: notifications.len(),
+        notifications,
+    })
+    .into_response())
+}
+
+/// POST /api/notifications/dismiss
+///
+/// Hides notifications for the caller only.
+#[utoipa::path(
+    post,
+    path = "/api/notifications/dismiss",
+    request_body = DismissNotificationsPayload,
+    responses(
+        (status = 200, description = "Notifications dismissed", body = DismissNotificationsResponse),
+        (status = 400, description = "No keys given")
+    )
+)]
+pub async fn dismiss_notifications(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Json(payload): Json<DismissNotificationsPayload>,
+) -> crate::error::Result<Response> {
+    if payload.keys.is_empty() {
+        return Err(crate::ServerError::BadRequest(
+            "keys must not be empty".to_string(),
+        ));
+    }
+
+    let dismissed = NotificationBmc::dismiss(&ctx, &app_state.mm, &payload.keys).await?;
+
+    Ok(Json(DismissNotificationsResponse { dismissed }).into_response())
+}
@@ -0,0 +1,74 @@
+//! Outbox HTTP handlers
+//!
+//! Lists what an agent has sent, with per-recipient read and ack state.
+//! The Git archive keeps an outbox copy per sender, but the database is the
+//! source of truth here.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+};
+use mouchak_mail_core::model::agent::AgentBmc;
+use mouchak_mail_core::model::message::{MessageBmc, OutboxMessage};
+use mouchak_mail_core::model::project::ProjectBmc;
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+
+/// Default page size for the outbox endpoint
+const DEFAULT_LIMIT: i64 = 50;
+/// Upper bound on requested page size
+const MAX_LIMIT: i64 = 200;
+
+/// Query parameters for the outbox endpoint
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct OutboxParams {
+    /// Page size (default 50, max 200)
+    pub limit: Option<i64>,
+    /// ID of the last message on the previous page
+    pub cursor: Option<i64>,
+}
+
+/// GET /api/project/{slug}/agent/{name}/outbox
+///
+/// Messages sent by the agent, newest first. Page with `cursor` set to the
+/// `id` of the last message returned.
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/agent/{name}/outbox",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ("name" = String, Path, description = "Agent name"),
+        OutboxParams
+    ),
+    responses(
+        (status = 200, description = "Sent messages with recipient status", body = Vec<OutboxMessage>),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
+pub async fn agent_outbox(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((slug, name)): Path<(String, String)>,
+    Query(params): Query<OutboxParams>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let agent = AgentBmc::get_by_name(&ctx, mm, project.id, &name).await?;
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+    let messages = MessageBmc::list_outbox_for_agent(
+        &ctx,
+        mm,
+        project.id.get(),
+        agent.id.get(),
+        limit,
+        params.cursor,
+    )
+    .await?;
+
+    Ok(Json(messages).into_response())
+}
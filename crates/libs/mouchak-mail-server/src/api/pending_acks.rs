@@ -0,0 +1,47 @@
+//! Pending acknowledgements HTTP handler
+//!
+//! Lists the messages an agent still has to acknowledge, so agents and
+//! overseers can work through them oldest first.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+};
+use mouchak_mail_core::model::agent::AgentBmc;
+use mouchak_mail_core::model::message::{MessageBmc, PendingAck};
+use mouchak_mail_core::model::project::ProjectBmc;
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+
+/// GET /api/project/{slug}/agent/{name}/pending-acks
+///
+/// `ack_required` messages addressed to the agent that it has not
+/// acknowledged, oldest first, with the days each has been outstanding.
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/agent/{name}/pending-acks",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ("name" = String, Path, description = "Agent name")
+    ),
+    responses(
+        (status = 200, description = "Messages awaiting the agent's acknowledgement", body = Vec<PendingAck>),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
+pub async fn pending_acks(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((slug, name)): Path<(String, String)>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let agent = AgentBmc::get_by_name(&ctx, mm, project.id, &name).await?;
+    let pending =
+        MessageBmc::list_pending_acks_for_agent(&ctx, mm, project.id.get(), agent.id.get()).await?;
+
+    Ok(Json(pending).into_response())
+}
@@ -0,0 +1,88 @@
+//! Project statistics HTTP handlers
+//!
+//! Agent, message, thread and reservation figures for the Projects page
+//! cards and status badges.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+};
+use mouchak_mail_core::model::project::{ProjectBmc, ProjectStats};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+
+/// Stats for one project, keyed by slug
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProjectStatsEntry {
+    pub project_slug: String,
+    #[serde(flatten)]
+    pub stats: ProjectStats,
+}
+
+/// Stats for every visible project
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AllProjectStats {
+    pub projects: Vec<ProjectStatsEntry>,
+}
+
+/// GET /api/project/{slug}/stats
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/stats",
+    params(("slug" = String, Path, description = "Project slug")),
+    responses(
+        (status = 200, description = "Project statistics", body = ProjectStatsEntry),
+        (status = 404, description = "Project not found")
+    )
+)]
+pub async fn project_stats(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path(slug): Path<String>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let stats = ProjectBmc::stats(&ctx, mm, project.id).await?;
+
+    Ok(Json(ProjectStatsEntry {
+        project_slug: project.slug,
+        stats,
+    })
+    .into_response())
+}
+
+/// GET /api/projects/stats
+///
+/// Stats for all projects at once, computed with the same handful of
+/// grouped queries regardless of project count.
+#[utoipa::path(
+    get,
+    path = "/api/projects/stats",
+    responses(
+        (status = 200, description = "Statistics for all projects", body = AllProjectStats)
+    )
+)]
+pub async fn all_project_stats(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let projects = ProjectBmc::list_all(&ctx, mm).await?;
+    let stats = ProjectBmc::stats_all(&ctx, mm).await?;
+    let projects = projects
+        .into_iter()
+        .zip(stats)
+        .map(|(project, stats)| ProjectStatsEntry {
+            project_slug: project.slug,
+            stats,
+        })
+        .collect();
+
+    Ok(Json(AllProjectStats { projects }).into_response())
+}
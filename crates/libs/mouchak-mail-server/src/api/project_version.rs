@@ -0,0 +1,157 @@
+//! Project version HTTP handler and response header
+//!
+//! Structural changes (adopt, delete, agent rename/retire, prune) bump a
+//! per-project version. Clients read it from GET /api/project/{slug}/version
+//! or, without an extra request, from the `X-Project-Version` header added to
+//! responses for project-scoped paths, and refetch cached lists when it
+//! changes.
+
+use axum::{
+    Json,
+    extract::{Path, Query, Request, State},
+    http::{HeaderName, HeaderValue, Uri},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use mouchak_mail_core::Ctx;
+use mouchak_mail_core::model::project_version::ProjectVersionBmc;
+use serde::Serialize;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+
+/// Response header carrying the version of the request's project.
+pub const PROJECT_VERSION_HEADER: HeaderName = HeaderName::from_static("x-project-version");
+
+/// Segments after `/api/project/` that are routes rather than slugs.
+const NON_SLUG_SEGMENTS: &[&str] = &["ensure", "info", "siblings", "stats"];
+
+/// Response for GET /api/project/{slug}/version
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProjectVersionResponse {
+    pub project_slug: String,
+    /// Bumped by every structural change; 0 if the project never had one
+    pub version: i64,
+}
+
+/// GET /api/project/{slug}/version
+///
+/// Also answers for a deleted project, whose version was bumped by the
+/// deletion.
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/version",
+    params(("slug" = String, Path, description = "Project slug")),
+    responses(
+        (status = 200, description = "Current project version", body = ProjectVersionResponse),
+        (status = 403, description = "Project outside the caller's scope")
+    )
+)]
+pub async fn project_version(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path(slug): Path<String>,
+) -> crate::error::Result<Response> {
+    let version = ProjectVersionBmc::get(&ctx, &app_state.mm, &slug).await?;
+
+    Ok(Json(ProjectVersionResponse {
+        project_slug: slug,
+        version,
+    })
+    .into_response())
+}
+
+/// Project a request is about, from its path or `project_slug` / `project`
+/// query parameter. JSON bodies are not inspected.
+pub fn project_slug_of(uri: &Uri) -> Option<String> {
+    let mut segments = uri.path().trim_start_matches('/').split('/');
+    if segments.next() == Some("api") {
+        let scope = segments.next();
+        let slug = segments.next().filter(|s| !s.is_empty());
+        let slug = match (scope, slug) {
+            // Only nested routes; `/api/project/info` and friends have no slug
+            (Some("project"), Some(slug)) if segments.next().is_some() => Some(slug),
+            (Some("projects"), Some(slug)) => Some(slug),
+            _ => None,
+        };
+        if let Some(slug) = slug.filter(|s| !NON_SLUG_SEGMENTS.contains(s)) {
+            return Some(slug.to_string());
+        }
+    }
+
+    let Query(params) = Query::<HashMap<String, String>>::try_from_uri(uri).ok()?;
+    params
+        .get("project_slug")
+        .or_else(|| params.get("project"))
+        .filter(|s| !s.is_empty())
+        .cloned()
+}
+
+/// Adds `X-Project-Version` to responses for project-scoped requests.
+///
+/// Runs inside the auth layer so the lookup respects the caller's scope; a
+/// request the caller may not see gets no header.
+pub async fn project_version_middleware(
+    State(app_state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let slug = project_slug_of(req.uri());
+    let ctx = req
+        .extensions()
+        .get::<Ctx>()
+        .cloned()
+        .unwrap_or_else(Ctx::root_ctx);
+
+    let mut response = next.run(req).await;
+
+    if let Some(slug) = slug
+        && let Ok(version) = ProjectVersionBmc::get(&ctx, &app_state.mm, &slug).await
+    {
+        response
+            .headers_mut()
+            .insert(PROJECT_VERSION_HEADER, HeaderValue::from(version));
+    }
+    response
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn slug(uri: &str) -> Option<String> {
+        project_slug_of(&uri.parse::<Uri>().unwrap())
+    }
+
+    #[test]
+    fn test_project_slug_from_path() {
+        assert_eq!(slug("/api/project/my-proj/threads"), Some("my-proj".into()));
+        assert_eq!(slug("/api/projects/my-proj/agents"), Some("my-proj".into()));
+        assert_eq!(slug("/api/projects/my-proj"), Some("my-proj".into()));
+    }
+
+    #[test]
+    fn test_project_slug_skips_route_names() {
+        assert_eq!(slug("/api/project/info"), None);
+        assert_eq!(slug("/api/project/ensure"), None);
+        assert_eq!(slug("/api/projects/stats"), None);
+        assert_eq!(slug("/api/projects"), None);
+        assert_eq!(slug("/api/inbox"), None);
+    }
+
+    #[test]
+    fn test_project_slug_from_query() {
+        assert_eq!(
+            slug("/api/list_agents?project_slug=my-proj"),
+            Some("my-proj".into())
+        );
+        assert_eq!(
+            slug("/api/file_reservations?project=my%20proj"),
+            Some("my proj".into())
+        );
+        assert_eq!(slug("/api/unified-inbox?limit=5"), None);
+    }
+}
@@ -0,0 +1,217 @@
+//! Project reservation board HTTP handlers
+//!
+//! Lists a project's active file reservations with holder, age and time left,
+//! flagging reservations whose patterns overlap another agent's, and lets an
+//! operator force release one or hand it to another agent.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+};
+use mouchak_mail_core::model::agent::AgentBmc;
+use mouchak_mail_core::model::file_reservation::FileReservationBmc;
+use mouchak_mail_core::model::project::ProjectBmc;
+use mouchak_mail_core::utils::pathspec::paths_conflict;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+use utoipa::ToSchema;
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+use crate::tools::ForceReleaseReservationResponse;
+
+/// An unreleased, unexpired reservation
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ActiveReservation {
+    pub id: i64,
+    pub agent_name: String,
+    pub path_pattern: String,
+    pub exclusive: bool,
+    pub reason: String,
+    pub created_ts: String,
+    pub expires_ts: String,
+    /// Seconds since the reservation was taken
+    pub age_seconds: i64,
+    /// Seconds until it lapses
+    pub expires_in_seconds: i64,
+    /// Other agents' reservations whose patterns overlap this one, where
+    /// either side is exclusive
+    pub conflicts_with: Vec<i64>,
+}
+
+/// Response for GET /api/project/{slug}/reservations
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProjectReservations {
+    pub project_slug: String,
+    /// Soonest expiry first
+    pub reservations: Vec<ActiveReservation>,
+}
+
+/// GET /api/project/{slug}/reservations
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/reservations",
+    params(("slug" = String, Path, description = "Project slug")),
+    responses(
+        (status = 200, description = "Active reservations in the project", body = ProjectReservations),
+        (status = 404, description = "Project not found")
+    )
+)]
+pub async fn project_reservations(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path(slug): Path<String>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let now = chrono::Utc::now().naive_utc();
+    let mut active: Vec<_> = FileReservationBmc::list_active_for_project(&ctx, mm, project.id)
+        .await?
+        .into_iter()
+        .filter(|r| r.expires_ts > now)
+        .collect();
+    active.sort_by_key(|r| (r.expires_ts, r.id));
+
+    let mut agent_names = HashMap::new();
+    for r in &active {
+        if let Entry::Vacant(entry) = agent_names.entry(r.agent_id) {
+            entry.insert(AgentBmc::get(&ctx, mm, r.agent_id).await?.name);
+        }
+    }
+
+    let reservations = active
+        .iter()
+        .map(|r| ActiveReservation {
+            id: r.id,
+            agent_name: agent_names[&r.agent_id].clone(),
+            path_pattern: r.path_pattern.clone(),
+            exclusive: r.exclusive,
+            reason: r.reason.clone(),
+            created_ts: r.created_ts.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            expires_ts: r.expires_ts.format("%Y-%m-%dT%H:%M:%S").to_string(),
+            age_seconds: (now - r.created_ts).num_seconds().max(0),
+            expires_in_seconds: (r.expires_ts - now).num_seconds(),
+            conflicts_with: active
+                .iter()
+                .filter(|other| {
+                    other.agent_id != r.agent_id
+                        && (other.exclusive || r.exclusive)
+                        && paths_conflict(&other.path_pattern, &r.path_pattern)
+                })
+                .map(|other| other.id)
+                .collect(),
+        })
+        .collect();
+
+    Ok(Json(ProjectReservations {
+        project_slug: project.slug,
+        reservations,
+    })
+    .into_response())
+}
+
+/// DELETE /api/project/{slug}/reservations/{id}
+///
+/// Force releases a reservation in the project, whoever holds it. Recorded
+/// in the audit log.
+#[utoipa::path(
+    delete,
+    path = "/api/project/{slug}/reservations/{id}",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ("id" = i64, Path, description = "Reservation ID")
+    ),
+    responses(
+        (status = 200, description = "Reservation released", body = ForceReleaseReservationResponse),
+        (status = 404, description = "No such reservation in the project")
+    )
+)]
+pub async fn force_release_project_reservation(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((slug, id)): Path<(String, i64)>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let reservation = FileReservationBmc::get(&ctx, mm, id).await?;
+    if reservation.project_id != project.id {
+        return Err(mouchak_mail_core::Error::FileReservationNotFound(id.to_string()).into());
+    }
+
+    FileReservationBmc::force_release(&ctx, mm, id).await?;
+
+    Ok(Json(ForceReleaseReservationResponse {
+        released: reservation.released_ts.is_none(),
+        reservation_id: id,
+    })
+    .into_response())
+}
+
+/// Request body for POST /api/project/{slug}/reservations/{id}/transfer
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TransferReservationRequest {
+    /// Agent taking over the reservation
+    pub to_agent: String,
+    /// Why the reservation changes hands
+    #[serde(default)]
+    pub reason: String,
+}
+
+/// Response for POST /api/project/{slug}/reservations/{id}/transfer
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TransferReservationResponse {
+    pub reservation_id: i64,
+    pub from_agent: String,
+    pub to_agent: String,
+    /// False when the reservation already belonged to `to_agent`
+    pub transferred: bool,
+}
+
+/// POST /api/project/{slug}/reservations/{id}/transfer
+///
+/// Hands a live reservation in the project to another agent. Both agents get
+/// a system message and the transfer is recorded in the audit log.
+#[utoipa::path(
+    post,
+    path = "/api/project/{slug}/reservations/{id}/transfer",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ("id" = i64, Path, description = "Reservation ID")
+    ),
+    request_body = TransferReservationRequest,
+    responses(
+        (status = 200, description = "Reservation transferred", body = TransferReservationResponse),
+        (status = 404, description = "No such reservation or agent in the project"),
+        (status = 409, description = "Reservation was released or has expired")
+    )
+)]
+pub async fn transfer_project_reservation(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((slug, id)): Path<(String, i64)>,
+    Json(payload): Json<TransferReservationRequest>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let reservation = FileReservationBmc::get(&ctx, mm, id).await?;
+    if reservation.project_id != project.id {
+        return Err(mouchak_mail_core::Error::FileReservationNotFound(id.to_string()).into());
+    }
+    let from_agent = AgentBmc::get(&ctx, mm, reservation.agent_id).await?;
+    let to_agent = AgentBmc::get_by_name(&ctx, mm, project.id, &payload.to_agent).await?;
+
+    FileReservationBmc::transfer(&ctx, mm, id, to_agent.id, &payload.reason).await?;
+
+    Ok(Json(TransferReservationResponse {
+        reservation_id: id,
+        transferred: from_agent.id != to_agent.id,
+        from_agent: from_agent.name,
+        to_agent: to_agent.name,
+    })
+    .into_response())
+}
@@ -0,0 +1,41 @@
+//! Server info HTTP handler
+//!
+//! GET /api/server-info reports the version, enabled optional features,
+//! enforced limits and database schema version, so clients can adapt
+//! without probing. Configuration only changes on restart, so responses
+//! are cacheable for a few minutes.
+
+use axum::{
+    Json,
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+};
+use mouchak_mail_core::model::server_info::{ServerInfo, ServerInfoBmc};
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+
+/// `Cache-Control` for server info responses.
+pub const SERVER_INFO_CACHE_CONTROL: &str = "private, max-age=300";
+
+/// GET /api/server-info
+#[utoipa::path(
+    get,
+    path = "/api/server-info",
+    responses(
+        (status = 200, description = "Version, features, limits and schema version", body = ServerInfo)
+    )
+)]
+pub async fn server_info(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+) -> crate::error::Result<Response> {
+    let info = ServerInfoBmc::get(&ctx, &app_state.mm).await?;
+
+    Ok((
+        [(header::CACHE_CONTROL, SERVER_INFO_CACHE_CONTROL)],
+        Json(info),
+    )
+        .into_response())
+}
@@ -0,0 +1,165 @@
+//! Message template HTTP handlers
+//!
+//! CRUD for per-project canned directives used by the overseer composer.
+//! Placeholders in templates are stored verbatim and filled in by clients.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+};
+use mouchak_mail_core::model::project::ProjectBmc;
+use mouchak_mail_core::model::template::{MessageTemplate, TemplateBmc, TemplateForCreate};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+
+/// Request body for POST /api/project/{slug}/templates
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateTemplatePayload {
+    /// Template name, unique within the project
+    pub name: String,
+    /// Subject line; may contain {{agent_name}} and {{date}}
+    pub subject: String,
+    /// Markdown body; may contain {{agent_name}} and {{date}}
+    #[serde(default)]
+    pub body_md: String,
+    /// low, normal (default), high or urgent
+    #[serde(default)]
+    pub importance: Option<String>,
+    /// Request acknowledgment from recipients
+    #[serde(default)]
+    pub ack_required: bool,
+}
+
+/// Response for DELETE /api/project/{slug}/templates/{id}
+#[derive(Debug, Serialize, ToSchema)]
+pub struct DeleteTemplateResponse {
+    pub deleted: bool,
+    pub template_id: i64,
+}
+
+/// GET /api/project/{slug}/templates
+///
+/// Lists the project's templates, ordered by name.
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/templates",
+    params(("slug" = String, Path, description = "Project slug")),
+    responses(
+        (status = 200, description = "Project templates", body = [MessageTemplate]),
+        (status = 404, description = "Project not found")
+    )
+)]
+pub async fn list_templates(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path(slug): Path<String>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let templates = TemplateBmc::list_for_project(&ctx, mm, project.id).await?;
+
+    Ok(Json(templates).into_response())
+}
+
+/// POST /api/project/{slug}/templates
+///
+/// Creates a template and returns it.
+#[utoipa::path(
+    post,
+    path = "/api/project/{slug}/templates",
+    params(("slug" = String, Path, description = "Project slug")),
+    request_body = CreateTemplatePayload,
+    responses(
+        (status = 200, description = "Template created", body = MessageTemplate),
+        (status = 400, description = "Empty name or subject, or unknown importance"),
+        (status = 404, description = "Project not found"),
+        (status = 409, description = "A template with this name already exists")
+    )
+)]
+pub async fn create_template(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path(slug): Path<String>,
+    Json(payload): Json<CreateTemplatePayload>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let template_id = TemplateBmc::create(
+        &ctx,
+        mm,
+        TemplateForCreate {
+            project_id: project.id,
+            name: payload.name,
+            subject: payload.subject,
+            body_md: payload.body_md,
+            importance: payload.importance,
+            ack_required: payload.ack_required,
+        },
+    )
+    .await?;
+    let template = TemplateBmc::get(&ctx, mm, project.id, template_id).await?;
+
+    Ok(Json(template).into_response())
+}
+
+/// GET /api/project/{slug}/templates/{id}
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/templates/{id}",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ("id" = i64, Path, description = "Template ID")
+    ),
+    responses(
+        (status = 200, description = "Template", body = MessageTemplate),
+        (status = 404, description = "Project or template not found")
+    )
+)]
+pub async fn get_template(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((slug, template_id)): Path<(String, i64)>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let template = TemplateBmc::get(&ctx, mm, project.id, template_id).await?;
+
+    Ok(Json(template).into_response())
+}
+
+/// DELETE /api/project/{slug}/templates/{id}
+#[utoipa::path(
+    delete,
+    path = "/api/project/{slug}/templates/{id}",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ("id" = i64, Path, description = "Template ID")
+    ),
+    responses(
+        (status = 200, description = "Template deleted", body = DeleteTemplateResponse),
+        (status = 404, description = "Project or template not found")
+    )
+)]
+pub async fn delete_template(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((slug, template_id)): Path<(String, i64)>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    TemplateBmc::delete(&ctx, mm, project.id, template_id).await?;
+
+    Ok(Json(DeleteTemplateResponse {
+        deleted: true,
+        template_id,
+    })
+    .into_response())
+}
@@ -0,0 +1,260 @@
+//! Thread HTTP handlers
+//!
+//! Thread listing for browsing a project, structured thread statistics
+//! so agents and the ThreadView page don't have to pull every message to
+//! answer "what happened in this thread", and per-agent thread mutes.
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+};
+use mouchak_mail_core::model::agent::AgentBmc;
+use mouchak_mail_core::model::message::{MessageBmc, ThreadCursor, ThreadStats, ThreadSummary};
+use mouchak_mail_core::model::project::ProjectBmc;
+use mouchak_mail_core::model::thread_mute::{ThreadMute, ThreadMuteBmc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+
+/// Default page size for the thread listing
+const DEFAULT_THREADS_LIMIT: i64 = 50;
+/// Upper bound on requested thread page size
+const MAX_THREADS_LIMIT: i64 = 200;
+/// Default number of recent message snippets in a thread summary
+const DEFAULT_RECENT: i64 = 5;
+/// Upper bound on requested snippets
+const MAX_RECENT: i64 = 50;
+
+/// Query parameters for the thread listing endpoint
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ThreadListParams {
+    /// Page size (default 50, max 200)
+    pub limit: Option<i64>,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+}
+
+/// Response for GET /api/project/{slug}/threads
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ThreadPage {
+    /// Threads, most recently active first
+    pub threads: Vec<ThreadSummary>,
+    /// Pass as `cursor` for the next page; absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// GET /api/project/{slug}/threads
+///
+/// One row per thread with first subject, participant and message counts,
+/// and last activity, ordered by last activity.
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/threads",
+    operation_id = "list_project_threads",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ThreadListParams
+    ),
+    responses(
+        (status = 200, description = "Page of threads", body = ThreadPage),
+        (status = 400, description = "Malformed cursor"),
+        (status = 404, description = "Project not found")
+    )
+)]
+pub async fn list_threads(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path(slug): Path<String>,
+    Query(params): Query<ThreadListParams>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_THREADS_LIMIT)
+        .clamp(1, MAX_THREADS_LIMIT);
+    let cursor = params
+        .cursor
+        .as_deref()
+        .map(str::parse::<ThreadCursor>)
+        .transpose()?;
+
+    let threads =
+        MessageBmc::list_threads(&ctx, mm, project.id.get(), limit, cursor.as_ref()).await?;
+    let next_cursor = if threads.len() as i64 == limit {
+        threads.last().map(|t| t.cursor().to_string())
+    } else {
+        None
+    };
+
+    Ok(Json(ThreadPage {
+        threads,
+        next_cursor,
+    })
+    .into_response())
+}
+
+/// Query parameters for the thread summary endpoint
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ThreadSummaryParams {
+    /// Number of newest message snippets to include (default 5, max 50)
+    pub recent: Option<i64>,
+}
+
+/// GET /api/project/{slug}/thread/{thread_id}/summary
+///
+/// Returns participants with message counts, first/last timestamps, ack
+/// totals and the newest message snippets. No LLM is involved.
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/thread/{thread_id}/summary",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ("thread_id" = String, Path, description = "Thread ID"),
+        ThreadSummaryParams
+    ),
+    responses(
+        (status = 200, description = "Thread statistics", body = ThreadStats),
+        (status = 404, description = "Project or thread not found")
+    )
+)]
+pub async fn thread_summary(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((slug, thread_id)): Path<(String, String)>,
+    Query(params): Query<ThreadSummaryParams>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let recent = params.recent.unwrap_or(DEFAULT_RECENT).clamp(0, MAX_RECENT);
+    let summary =
+        MessageBmc::thread_summary(&ctx, mm, project.id.get(), &thread_id, recent).await?;
+
+    Ok(Json(summary).into_response())
+}
+
+/// Request body for POST /api/project/{slug}/thread/{thread_id}/mutes
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct MuteThreadPayload {
+    /// Agent muting the thread
+    pub agent_name: String,
+}
+
+/// Response for muting or unmuting a thread
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ThreadMuteResponse {
+    pub thread_id: String,
+    pub agent_name: String,
+    /// Whether the thread is now muted for the agent
+    pub muted: bool,
+    /// False if the thread was already in that state
+    pub changed: bool,
+}
+
+/// GET /api/project/{slug}/thread/{thread_id}/mutes
+///
+/// Lists the agents that muted the thread.
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/thread/{thread_id}/mutes",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ("thread_id" = String, Path, description = "Thread ID")
+    ),
+    responses(
+        (status = 200, description = "Agents that muted the thread", body = [ThreadMute]),
+        (status = 404, description = "Project not found")
+    )
+)]
+pub async fn list_thread_mutes(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((slug, thread_id)): Path<(String, String)>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let mutes = ThreadMuteBmc::list_for_thread(&ctx, mm, project.id, &thread_id).await?;
+
+    Ok(Json(mutes).into_response())
+}
+
+/// POST /api/project/{slug}/thread/{thread_id}/mutes
+///
+/// Mutes the thread for an agent. Its messages stay out of the agent's
+/// inbox unless urgent or @-mentioning the agent in the subject.
+#[utoipa::path(
+    post,
+    path = "/api/project/{slug}/thread/{thread_id}/mutes",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ("thread_id" = String, Path, description = "Thread ID")
+    ),
+    request_body = MuteThreadPayload,
+    responses(
+        (status = 200, description = "Thread muted", body = ThreadMuteResponse),
+        (status = 404, description = "Project, agent or thread not found")
+    )
+)]
+pub async fn mute_thread(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((slug, thread_id)): Path<(String, String)>,
+    Json(payload): Json<MuteThreadPayload>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let agent = AgentBmc::get_by_name(&ctx, mm, project.id, &payload.agent_name).await?;
+    let changed = ThreadMuteBmc::mute(&ctx, mm, agent.id, &thread_id).await?;
+
+    Ok(Json(ThreadMuteResponse {
+        thread_id,
+        agent_name: agent.name,
+        muted: true,
+        changed,
+    })
+    .into_response())
+}
+
+/// DELETE /api/project/{slug}/thread/{thread_id}/mutes/{agent_name}
+///
+/// Unmutes the thread for an agent; messages sent while it was muted show
+/// up in the inbox again.
+#[utoipa::path(
+    delete,
+    path = "/api/project/{slug}/thread/{thread_id}/mutes/{agent_name}",
+    params(
+        ("slug" = String, Path, description = "Project slug"),
+        ("thread_id" = String, Path, description = "Thread ID"),
+        ("agent_name" = String, Path, description = "Agent name")
+    ),
+    responses(
+        (status = 200, description = "Thread unmuted", body = ThreadMuteResponse),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
+pub async fn unmute_thread(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((slug, thread_id, agent_name)): Path<(String, String, String)>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let agent = AgentBmc::get_by_name(&ctx, mm, project.id, &agent_name).await?;
+    let changed = ThreadMuteBmc::unmute(&ctx, mm, agent.id, &thread_id).await?;
+
+    Ok(Json(ThreadMuteResponse {
+        thread_id,
+        agent_name: agent.name,
+        muted: false,
+        changed,
+    })
+    .into_response())
+}
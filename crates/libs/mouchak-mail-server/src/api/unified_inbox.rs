@@ -7,23 +7,47 @@ use axum::{
     extract::{Query, State},
     response::{IntoResponse, Response},
 };
-use mouchak_mail_core::Ctx;
-use mouchak_mail_core::model::message::{ImportanceFilter, MessageBmc};
+use mouchak_mail_core::model::message::{
+    ImportanceFilter, InboxCursor, MessageBmc, UnifiedInboxFilter,
+};
 use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
 
 use crate::AppState;
+use crate::auth::RequestCtx;
+use crate::tools::{message_permalink, parse_inbox_cursor, parse_inbox_sort};
 
 /// Query parameters for unified inbox endpoint
-#[derive(Debug, Deserialize)]
+///
+/// All filters are optional and applied in SQL; with none set the endpoint
+/// returns the latest messages across every project.
+#[derive(Debug, Deserialize, IntoParams)]
 pub struct UnifiedInboxParams {
+    /// Comma-separated project slugs to include
+    pub projects: Option<String>,
+    /// Filter by sender agent name
+    pub sender: Option<String>,
     /// Filter by importance: "high", "normal", or omit for all
     pub importance: Option<String>,
+    /// Case-insensitive subject substring
+    pub q: Option<String>,
+    /// Only messages created at or after this RFC 3339 timestamp
+    pub since: Option<String>,
+    /// Only messages created before this RFC 3339 timestamp
+    pub until: Option<String>,
     /// Maximum messages to return (default: 50, max: 200)
     pub limit: Option<i32>,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+    /// Only messages carrying this label (case-insensitive)
+    pub label: Option<String>,
+    /// `created_desc` (default), `created_asc`, `importance_desc` or
+    /// `ack_pending_first`
+    pub sort: Option<String>,
 }
 
 /// Single message in unified inbox response
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UnifiedInboxMessage {
     pub id: i64,
     pub project_id: i64,
@@ -31,56 +55,138 @@ pub struct UnifiedInboxMessage {
     pub sender_id: i64,
     pub sender_name: String,
     pub subject: String,
-    pub body_md: String,
+    /// Start of the body; fetch the message for the full text
+    pub snippet: String,
     pub excerpt: String,
     pub importance: String,
+    /// Recipients must acknowledge the message
+    pub ack_required: bool,
+    /// A recipient has muted this message's thread
+    pub muted: bool,
     pub created_ts: chrono::NaiveDateTime,
     pub thread_id: Option<String>,
+    /// Position in the thread, counting from 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_seq: Option<i64>,
+    /// Web UI link to the message; absolute when `server.public_url` is set
+    pub permalink: String,
 }
 
 /// Response wrapper for unified inbox
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UnifiedInboxResponse {
     pub messages: Vec<UnifiedInboxMessage>,
     pub total_count: usize,
+    /// Pass as `cursor` with the same `sort` to fetch the next page; absent
+    /// on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
 }
 
 /// GET /api/unified-inbox
 ///
-/// Returns messages from all projects, optionally filtered by importance.
+/// Returns messages from all projects, optionally filtered by project,
+/// sender, importance, subject, label and creation time, in `sort` order.
+/// Page with `cursor`.
+#[utoipa::path(
+    get,
+    path = "/api/unified-inbox",
+    params(UnifiedInboxParams),
+    responses(
+        (status = 200, description = "Messages across all projects, newest first by default", body = UnifiedInboxResponse),
+        (status = 400, description = "Invalid since/until timestamp, until before since, unknown sort, or a cursor from another sort")
+    )
+)]
 pub async fn unified_inbox_json(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Query(params): Query<UnifiedInboxParams>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
-    let importance = ImportanceFilter::from_str_opt(params.importance.as_deref());
-    let limit = params.limit.unwrap_or(50).clamp(1, 200);
+    let since = parse_timestamp("since", params.since.as_deref())?;
+    let until = parse_timestamp("until", params.until.as_deref())?;
+    if let (Some(since), Some(until)) = (since, until)
+        && until < since
+    {
+        return Err(crate::ServerError::BadRequest(
+            "until must not be before since".to_string(),
+        ));
+    }
+    let non_empty = |value: Option<String>| value.filter(|v| !v.trim().is_empty());
 
-    let items = MessageBmc::list_unified_inbox(&ctx, mm, importance, limit).await?;
+    let filter = UnifiedInboxFilter {
+        projects: params
+            .projects
+            .as_deref()
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|slug| !slug.is_empty())
+            .map(String::from)
+            .collect(),
+        sender: non_empty(params.sender),
+        importance: ImportanceFilter::from_str_opt(params.importance.as_deref()),
+        query: non_empty(params.q),
+        since,
+        until,
+        limit: params.limit.unwrap_or(50).clamp(1, 200) as i64,
+        cursor: parse_inbox_cursor(params.cursor.as_deref())?,
+        label: non_empty(params.label),
+        sort: parse_inbox_sort(params.sort.as_deref())?,
+    };
+
+    let items = MessageBmc::list_unified_inbox_filtered(&ctx, mm, &filter).await?;
+    let next_cursor = if items.len() as i64 == filter.limit {
+        items
+            .last()
+            .map(|m| InboxCursor::new(filter.sort, m.id).to_string())
+    } else {
+        None
+    };
 
     let messages: Vec<UnifiedInboxMessage> = items
         .into_iter()
         .map(|m| UnifiedInboxMessage {
+            permalink: message_permalink(mm, &m.project_slug, m.id),
             id: m.id,
             project_id: m.project_id,
             project_slug: m.project_slug,
             sender_id: m.sender_id,
             sender_name: m.sender_name,
             subject: m.subject,
-            body_md: m.body_md,
+            snippet: m.snippet,
             excerpt: m.excerpt,
             importance: m.importance,
+            ack_required: m.ack_required,
+            muted: m.muted,
             created_ts: m.created_ts,
             thread_id: m.thread_id,
+            thread_seq: m.thread_seq,
         })
         .collect();
 
     let response = UnifiedInboxResponse {
         total_count: messages.len(),
         messages,
+        next_cursor,
     };
 
     Ok(Json(response).into_response())
 }
+
+/// Parse an optional RFC 3339 query parameter into UTC.
+///
+/// The offset is required, so the caller's timezone is always explicit;
+/// comparisons against `created_ts` happen in UTC.
+fn parse_timestamp(
+    name: &str,
+    value: Option<&str>,
+) -> crate::error::Result<Option<chrono::NaiveDateTime>> {
+    let Some(value) = value.filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|ts| Some(ts.naive_utc()))
+        .map_err(|e| crate::ServerError::BadRequest(format!("Invalid {} timestamp: {}", name, e)))
+}
@@ -0,0 +1,123 @@
+//! Unread counts HTTP handlers
+//!
+//! Per-agent unread message counts for sidebar and project card badges.
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+};
+use mouchak_mail_core::Ctx;
+use mouchak_mail_core::model::ModelManager;
+use mouchak_mail_core::model::agent::AgentBmc;
+use mouchak_mail_core::model::message::MessageBmc;
+use mouchak_mail_core::model::project::{Project, ProjectBmc};
+use serde::Serialize;
+use std::collections::HashMap;
+use utoipa::ToSchema;
+
+use crate::AppState;
+use crate::auth::RequestCtx;
+
+/// Unread count for a single agent
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AgentUnreadCount {
+    pub agent_id: i64,
+    pub agent_name: String,
+    pub unread: i64,
+}
+
+/// Unread counts for every agent in a project
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ProjectUnreadCounts {
+    pub project_slug: String,
+    pub total: i64,
+    pub agents: Vec<AgentUnreadCount>,
+}
+
+/// Unread counts across all projects
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AllUnreadCounts {
+    pub total: i64,
+    pub projects: Vec<ProjectUnreadCounts>,
+}
+
+/// GET /api/project/{slug}/unread-counts
+///
+/// Returns unread counts for every agent in the project (agents with nothing
+/// unread report 0).
+#[utoipa::path(
+    get,
+    path = "/api/project/{slug}/unread-counts",
+    params(("slug" = String, Path, description = "Project slug")),
+    responses(
+        (status = 200, description = "Per-agent unread counts", body = ProjectUnreadCounts),
+        (status = 404, description = "Project not found")
+    )
+)]
+pub async fn project_unread_counts(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path(slug): Path<String>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = ProjectBmc::get_by_identifier(&ctx, mm, &slug).await?;
+    let counts = MessageBmc::unread_counts(&ctx, mm, project.id).await?;
+    let response = build_project_counts(&ctx, mm, project, &counts).await?;
+
+    Ok(Json(response).into_response())
+}
+
+/// GET /api/unread-counts
+///
+/// Returns unread counts for all projects, for the unified inbox view.
+#[utoipa::path(
+    get,
+    path = "/api/unread-counts",
+    responses(
+        (status = 200, description = "Unread counts for all projects", body = AllUnreadCounts)
+    )
+)]
+pub async fn all_unread_counts(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let mut counts = MessageBmc::unread_counts_all(&ctx, mm).await?;
+    let mut projects = Vec::new();
+    for project in ProjectBmc::list_all(&ctx, mm).await? {
+        let project_counts = counts.remove(&project.id.get()).unwrap_or_default();
+        projects.push(build_project_counts(&ctx, mm, project, &project_counts).await?);
+    }
+
+    let total = projects.iter().map(|p| p.total).sum();
+    Ok(Json(AllUnreadCounts { total, projects }).into_response())
+}
+
+async fn build_project_counts(
+    ctx: &Ctx,
+    mm: &ModelManager,
+    project: Project,
+    counts: &HashMap<i64, i64>,
+) -> crate::error::Result<ProjectUnreadCounts> {
+    let agents: Vec<AgentUnreadCount> = AgentBmc::list_all_for_project(ctx, mm, project.id)
+        .await?
+        .into_iter()
+        .map(|agent| {
+            let agent_id = agent.id.get();
+            AgentUnreadCount {
+                agent_id,
+                agent_name: agent.name,
+                unread: counts.get(&agent_id).copied().unwrap_or(0),
+            }
+        })
+        .collect();
+
+    Ok(ProjectUnreadCounts {
+        project_slug: project.slug,
+        total: agents.iter().map(|a| a.unread).sum(),
+        agents,
+    })
+}
@@ -0,0 +1,115 @@
+//! CORS for dashboards served from another origin.
+//!
+//! Built from the optional `server.cors` config section. Without it no layer
+//! is installed and browsers keep the API same-origin only. Preflights are
+//! answered by the layer itself, ahead of auth, so authenticated `/api`
+//! routes can be called cross-origin with an `Authorization` header.
+
+use axum::http::{HeaderName, HeaderValue, Method, Uri, header};
+use mouchak_mail_common::config::CorsConfig;
+use std::time::Duration;
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+use crate::ServerError;
+use crate::api::project_version::PROJECT_VERSION_HEADER;
+
+/// Origin entry that allows any origin.
+pub const ANY_ORIGIN: &str = "*";
+
+/// Methods cross-origin callers may use.
+const ALLOWED_METHODS: [Method; 6] = [
+    Method::GET,
+    Method::POST,
+    Method::PUT,
+    Method::PATCH,
+    Method::DELETE,
+    Method::OPTIONS,
+];
+
+/// Build the CORS layer for `config`, rejecting origins and headers a
+/// browser could never match rather than silently ignoring them.
+///
+/// # Errors
+/// [`ServerError::ConfigError`] for an empty origin list, an origin that is
+/// not `http(s)://host[:port]`, `"*"` mixed with other origins or combined
+/// with credentials, or an invalid header name.
+pub fn cors_layer(config: &CorsConfig) -> Result<CorsLayer, ServerError> {
+    let origin = allowed_origin(config)?;
+
+    // Authenticated routes need Authorization whatever the config lists
+    let mut headers = vec![header::AUTHORIZATION, header::CONTENT_TYPE];
+    for name in &config.allowed_headers {
+        let name = HeaderName::from_bytes(name.trim().as_bytes()).map_err(|_| {
+            ServerError::ConfigError(format!("Invalid CORS allowed header '{}'", name))
+        })?;
+        if !headers.contains(&name) {
+            headers.push(name);
+        }
+    }
+
+    Ok(CorsLayer::new()
+        .allow_origin(origin)
+        .allow_methods(ALLOWED_METHODS)
+        .allow_headers(headers)
+        .allow_credentials(config.allow_credentials)
+        .expose_headers([PROJECT_VERSION_HEADER])
+        .max_age(Duration::from_secs(config.max_age_secs)))
+}
+
+fn allowed_origin(config: &CorsConfig) -> Result<AllowOrigin, ServerError> {
+    let origins = &config.allowed_origins;
+    if origins.is_empty() {
+        return Err(ServerError::ConfigError(
+            "CORS is configured but allowed_origins is empty".to_string(),
+        ));
+    }
+
+    if origins.iter().any(|o| o.trim() == ANY_ORIGIN) {
+        if origins.len() > 1 {
+            return Err(ServerError::ConfigError(
+                "CORS allowed_origins '*' cannot be combined with other origins".to_string(),
+            ));
+        }
+        // Browsers refuse credentialed responses to a wildcard origin
+        if config.allow_credentials {
+            return Err(ServerError::ConfigError(
+                "CORS allowed_origins '*' cannot be used with allow_credentials".to_string(),
+            ));
+        }
+        return Ok(AllowOrigin::any());
+    }
+
+    let origins = origins
+        .iter()
+        .map(|o| parse_origin(o.trim()))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(AllowOrigin::list(origins))
+}
+
+/// Parse an exact origin as browsers send it: `http(s)://host[:port]`, with
+/// no path, query or trailing slash.
+fn parse_origin(origin: &str) -> Result<HeaderValue, ServerError> {
+    let invalid = |reason: &str| {
+        ServerError::ConfigError(format!("Invalid CORS origin '{}': {}", origin, reason))
+    };
+
+    let uri: Uri = origin
+        .parse()
+        .map_err(|_| invalid("expected scheme://host[:port]"))?;
+    match uri.scheme_str() {
+        Some("http" | "https") => {}
+        Some(_) => return Err(invalid("scheme must be http or https")),
+        None => return Err(invalid("expected scheme://host[:port]")),
+    }
+    match uri.authority() {
+        Some(authority) if !authority.host().is_empty() && !authority.as_str().contains('@') => {}
+        _ => return Err(invalid("expected a host")),
+    }
+    // Uri reports "/" for a bare authority, so look at the text itself
+    let after_scheme = origin.split_once("://").map_or("", |(_, rest)| rest);
+    if after_scheme.contains(['/', '?', '#']) {
+        return Err(invalid("origins have no path"));
+    }
+
+    HeaderValue::from_str(origin).map_err(|_| invalid("not a valid header value"))
+}
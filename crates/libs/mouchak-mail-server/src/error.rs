@@ -11,26 +11,38 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
 };
+use mouchak_mail_core::model::message::RecipientFailure;
 use serde::Serialize;
 use thiserror::Error;
+use utoipa::ToSchema;
 
 /// Error codes for machine-readable error classification.
 /// These codes are stable and can be used for client-side error handling.
-#[derive(Debug, Clone, Copy, Serialize)]
+#[derive(Debug, Clone, Copy, Serialize, ToSchema)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ErrorCode {
     // 4xx Client Errors
     BadRequest,
     Unauthorized,
     Forbidden,
+    /// The sender's project may not message agents in other projects
+    ForbiddenCrossProject,
     NotFound,
+    /// The message existed but was removed by the retention policy
+    NotFoundPruned,
     Conflict,
     ValidationError,
+    RateLimited,
+    NotMessageSender,
+    RecallWindowExpired,
+    /// The message was recalled by its sender
+    MessageRecalled,
+    /// An agent token was used to act as a different agent
+    AgentIdentityMismatch,
 
     // 5xx Server Errors
     InternalError,
     DatabaseError,
-    #[allow(dead_code)]
     ServiceUnavailable,
     ConfigError,
 }
@@ -42,9 +54,16 @@ impl ErrorCode {
             ErrorCode::BadRequest => "BAD_REQUEST",
             ErrorCode::Unauthorized => "UNAUTHORIZED",
             ErrorCode::Forbidden => "FORBIDDEN",
+            ErrorCode::ForbiddenCrossProject => "FORBIDDEN_CROSS_PROJECT",
             ErrorCode::NotFound => "NOT_FOUND",
+            ErrorCode::NotFoundPruned => "NOT_FOUND_PRUNED",
             ErrorCode::Conflict => "CONFLICT",
             ErrorCode::ValidationError => "VALIDATION_ERROR",
+            ErrorCode::RateLimited => "RATE_LIMITED",
+            ErrorCode::NotMessageSender => "NOT_MESSAGE_SENDER",
+            ErrorCode::RecallWindowExpired => "RECALL_WINDOW_EXPIRED",
+            ErrorCode::MessageRecalled => "MESSAGE_RECALLED",
+            ErrorCode::AgentIdentityMismatch => "AGENT_IDENTITY_MISMATCH",
             ErrorCode::InternalError => "INTERNAL_ERROR",
             ErrorCode::DatabaseError => "DATABASE_ERROR",
             ErrorCode::ServiceUnavailable => "SERVICE_UNAVAILABLE",
@@ -54,9 +73,10 @@ impl ErrorCode {
 }
 
 /// Structured error response following RFC 7807 Problem Details pattern.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct ErrorResponse {
     /// Machine-readable error code for client-side handling.
+    #[schema(value_type = ErrorCode)]
     pub code: &'static str,
     /// Human-readable error message (safe for display).
     pub error: String,
@@ -66,6 +86,9 @@ pub struct ErrorResponse {
     /// Optional suggestions for similar entities (for NotFound errors).
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub suggestions: Vec<String>,
+    /// Recipients that could not be resolved (for rejected sends).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub failed: Vec<RecipientFailure>,
 }
 
 impl ErrorResponse {
@@ -75,6 +98,7 @@ impl ErrorResponse {
             error: message.into(),
             details: None,
             suggestions: vec![],
+            failed: vec![],
         }
     }
 
@@ -88,6 +112,11 @@ impl ErrorResponse {
         self.suggestions = suggestions;
         self
     }
+
+    pub fn with_failed(mut self, failed: Vec<RecipientFailure>) -> Self {
+        self.failed = failed;
+        self
+    }
 }
 
 /// Server error type with production-hardened error handling.
@@ -97,6 +126,14 @@ pub enum ServerError {
     #[error("Database error")]
     Database(#[from] mouchak_mail_core::Error),
 
+    /// A send rejected because some recipients did not resolve; `source` is
+    /// the error of the first one and sets the status and code.
+    #[error("Recipients rejected")]
+    Recipients {
+        source: mouchak_mail_core::Error,
+        failed: Vec<RecipientFailure>,
+    },
+
     #[error("IO error")]
     Io(#[from] std::io::Error),
 
@@ -195,15 +232,71 @@ fn sanitize_error_message(error: &mouchak_mail_core::Error) -> String {
             format!("Agent not found: {}", name)
         }
         mouchak_mail_core::Error::MessageNotFound(id) => format!("Message not found: {}", id),
+        mouchak_mail_core::Error::MessagePruned(id) => format!(
+            "Message {} was pruned by the retention policy; it remains in the Git archive",
+            id
+        ),
+        mouchak_mail_core::Error::ThreadNotFound(id) => format!("Thread not found: {}", id),
+        mouchak_mail_core::Error::NotMessageSender(id) => {
+            format!("Only the sender can recall message {}", id)
+        }
+        mouchak_mail_core::Error::RecallWindowExpired {
+            message_id,
+            window_seconds,
+        } => format!(
+            "Recall window of {}s has passed for message {}",
+            window_seconds, message_id
+        ),
+        mouchak_mail_core::Error::MessageRecalled(id) => {
+            format!("Message {} was recalled by its sender", id)
+        }
         mouchak_mail_core::Error::FileReservationNotFound(id) => {
             format!("File reservation not found: {}", id)
         }
+        mouchak_mail_core::Error::FileReservationInactive(msg) => {
+            format!("File reservation no longer active: {}", msg)
+        }
         mouchak_mail_core::Error::ProductNotFound(id) => format!("Product not found: {}", id),
         mouchak_mail_core::Error::MacroNotFound(name) => format!("Macro not found: {}", name),
         mouchak_mail_core::Error::BuildSlotNotFound(id) => format!("Build slot not found: {}", id),
+        mouchak_mail_core::Error::TemplateNotFound(id) => format!("Template not found: {}", id),
+        mouchak_mail_core::Error::TemplateNameTaken(name) => format!(
+            "A template named '{}' already exists in this project; choose another name or delete the existing template",
+            name
+        ),
+        mouchak_mail_core::Error::SlugConflict(slug) => format!(
+            "Project slug '{}' is already taken by another project; choose a different slug",
+            slug
+        ),
+        mouchak_mail_core::Error::LabelNotFound(label) => format!("Label not found: {}", label),
+        mouchak_mail_core::Error::LabelNameTaken(name) => format!(
+            "A label named '{}' already exists in this project; names are case-insensitive",
+            name
+        ),
+        mouchak_mail_core::Error::GroupNotFound(name) => format!("Group not found: {}", name),
+        mouchak_mail_core::Error::GroupNameTaken { name, taken_by } => format!(
+            "'{}' is already the name of {} in this project; names are case-insensitive",
+            name, taken_by
+        ),
+        mouchak_mail_core::Error::GroupEmpty(name) => format!(
+            "Group '{}' has no members; add agents to it before addressing it",
+            name
+        ),
         mouchak_mail_core::Error::NotFound => "Resource not found".to_string(),
         mouchak_mail_core::Error::InvalidInput(msg) => format!("Invalid input: {}", msg),
         mouchak_mail_core::Error::AuthError => "Authentication failed".to_string(),
+        mouchak_mail_core::Error::Forbidden(slug) => format!("Access to project denied: {}", slug),
+        mouchak_mail_core::Error::CrossProjectForbidden(slug) => format!(
+            "Project '{}' may not message agents in other projects; add it to projects.allowed_peers",
+            slug
+        ),
+        mouchak_mail_core::Error::AgentIdentityMismatch {
+            authenticated,
+            claimed,
+        } => format!(
+            "Authenticated as agent '{}' and may not act as '{}'",
+            authenticated, claimed
+        ),
         // For database errors, check if it's a unique constraint
         mouchak_mail_core::Error::Libsql(e) => {
             let msg = e.to_string();
@@ -214,9 +307,14 @@ fn sanitize_error_message(error: &mouchak_mail_core::Error) -> String {
                 "Database operation failed".to_string()
             }
         }
+        mouchak_mail_core::Error::DatabaseBusy { .. } => {
+            "Database is busy, please retry".to_string()
+        }
         mouchak_mail_core::Error::Git2(_) => "Version control operation failed".to_string(),
         mouchak_mail_core::Error::SerdeJson(_) => "Invalid JSON format".to_string(),
-        mouchak_mail_core::Error::Io(_) => "File operation failed".to_string(),
+        mouchak_mail_core::Error::Io(_) | mouchak_mail_core::Error::ArchiveWrite { .. } => {
+            "File operation failed".to_string()
+        }
         mouchak_mail_core::Error::LockTimeout { .. } => "Lock acquisition timed out".to_string(),
         mouchak_mail_core::Error::Validation(ve) => ve.to_string(),
         mouchak_mail_core::Error::Image(_) => "Image processing failed".to_string(),
@@ -232,14 +330,31 @@ fn map_core_error_to_status(error: &mouchak_mail_core::Error) -> StatusCode {
         mouchak_mail_core::Error::ProjectNotFound { .. }
         | mouchak_mail_core::Error::AgentNotFound { .. }
         | mouchak_mail_core::Error::MessageNotFound(_)
+        | mouchak_mail_core::Error::MessagePruned(_)
+        | mouchak_mail_core::Error::ThreadNotFound(_)
         | mouchak_mail_core::Error::FileReservationNotFound(_)
         | mouchak_mail_core::Error::ProductNotFound(_)
         | mouchak_mail_core::Error::MacroNotFound(_)
         | mouchak_mail_core::Error::BuildSlotNotFound(_)
+        | mouchak_mail_core::Error::TemplateNotFound(_)
+        | mouchak_mail_core::Error::LabelNotFound(_)
+        | mouchak_mail_core::Error::GroupNotFound(_)
         | mouchak_mail_core::Error::NotFound => StatusCode::NOT_FOUND,
 
         mouchak_mail_core::Error::InvalidInput(_) => StatusCode::BAD_REQUEST,
+        mouchak_mail_core::Error::GroupEmpty(_) => StatusCode::BAD_REQUEST,
         mouchak_mail_core::Error::AuthError => StatusCode::UNAUTHORIZED,
+        mouchak_mail_core::Error::Forbidden(_) => StatusCode::FORBIDDEN,
+        mouchak_mail_core::Error::CrossProjectForbidden(_) => StatusCode::FORBIDDEN,
+        mouchak_mail_core::Error::NotMessageSender(_) => StatusCode::FORBIDDEN,
+        mouchak_mail_core::Error::AgentIdentityMismatch { .. } => StatusCode::FORBIDDEN,
+        mouchak_mail_core::Error::RecallWindowExpired { .. } => StatusCode::CONFLICT,
+        mouchak_mail_core::Error::MessageRecalled(_) => StatusCode::CONFLICT,
+        mouchak_mail_core::Error::FileReservationInactive(_) => StatusCode::CONFLICT,
+        mouchak_mail_core::Error::TemplateNameTaken(_) => StatusCode::CONFLICT,
+        mouchak_mail_core::Error::LabelNameTaken(_) => StatusCode::CONFLICT,
+        mouchak_mail_core::Error::GroupNameTaken { .. } => StatusCode::CONFLICT,
+        mouchak_mail_core::Error::SlugConflict(_) => StatusCode::CONFLICT,
         mouchak_mail_core::Error::SerdeJson(_) => StatusCode::BAD_REQUEST,
 
         mouchak_mail_core::Error::Libsql(e) => {
@@ -250,8 +365,11 @@ fn map_core_error_to_status(error: &mouchak_mail_core::Error) -> StatusCode {
             }
         }
 
+        mouchak_mail_core::Error::DatabaseBusy { .. } => StatusCode::SERVICE_UNAVAILABLE,
+
         mouchak_mail_core::Error::Git2(_)
         | mouchak_mail_core::Error::Io(_)
+        | mouchak_mail_core::Error::ArchiveWrite { .. }
         | mouchak_mail_core::Error::LockTimeout { .. } => StatusCode::INTERNAL_SERVER_ERROR,
 
         mouchak_mail_core::Error::Validation(_) => StatusCode::BAD_REQUEST,
@@ -268,17 +386,35 @@ fn map_core_error_to_code(error: &mouchak_mail_core::Error) -> ErrorCode {
         mouchak_mail_core::Error::ProjectNotFound { .. }
         | mouchak_mail_core::Error::AgentNotFound { .. }
         | mouchak_mail_core::Error::MessageNotFound(_)
+        | mouchak_mail_core::Error::ThreadNotFound(_)
         | mouchak_mail_core::Error::FileReservationNotFound(_)
         | mouchak_mail_core::Error::ProductNotFound(_)
         | mouchak_mail_core::Error::MacroNotFound(_)
         | mouchak_mail_core::Error::BuildSlotNotFound(_)
+        | mouchak_mail_core::Error::TemplateNotFound(_)
+        | mouchak_mail_core::Error::LabelNotFound(_)
+        | mouchak_mail_core::Error::GroupNotFound(_)
         | mouchak_mail_core::Error::NotFound => ErrorCode::NotFound,
 
+        mouchak_mail_core::Error::MessagePruned(_) => ErrorCode::NotFoundPruned,
+
         mouchak_mail_core::Error::InvalidInput(_)
+        | mouchak_mail_core::Error::GroupEmpty(_)
         | mouchak_mail_core::Error::SerdeJson(_)
         | mouchak_mail_core::Error::Validation(_) => ErrorCode::ValidationError,
 
         mouchak_mail_core::Error::AuthError => ErrorCode::Unauthorized,
+        mouchak_mail_core::Error::Forbidden(_) => ErrorCode::Forbidden,
+        mouchak_mail_core::Error::CrossProjectForbidden(_) => ErrorCode::ForbiddenCrossProject,
+        mouchak_mail_core::Error::NotMessageSender(_) => ErrorCode::NotMessageSender,
+        mouchak_mail_core::Error::AgentIdentityMismatch { .. } => ErrorCode::AgentIdentityMismatch,
+        mouchak_mail_core::Error::RecallWindowExpired { .. } => ErrorCode::RecallWindowExpired,
+        mouchak_mail_core::Error::MessageRecalled(_) => ErrorCode::MessageRecalled,
+        mouchak_mail_core::Error::FileReservationInactive(_) => ErrorCode::Conflict,
+        mouchak_mail_core::Error::TemplateNameTaken(_) => ErrorCode::Conflict,
+        mouchak_mail_core::Error::LabelNameTaken(_) => ErrorCode::Conflict,
+        mouchak_mail_core::Error::GroupNameTaken { .. } => ErrorCode::Conflict,
+        mouchak_mail_core::Error::SlugConflict(_) => ErrorCode::Conflict,
 
         mouchak_mail_core::Error::Libsql(e) => {
             if is_unique_constraint_error(&e.to_string()) {
@@ -288,8 +424,11 @@ fn map_core_error_to_code(error: &mouchak_mail_core::Error) -> ErrorCode {
             }
         }
 
+        mouchak_mail_core::Error::DatabaseBusy { .. } => ErrorCode::ServiceUnavailable,
+
         mouchak_mail_core::Error::Git2(_)
         | mouchak_mail_core::Error::Io(_)
+        | mouchak_mail_core::Error::ArchiveWrite { .. }
         | mouchak_mail_core::Error::LockTimeout { .. } => ErrorCode::InternalError,
 
         mouchak_mail_core::Error::Image(_) => ErrorCode::ValidationError,
@@ -316,6 +455,18 @@ impl IntoResponse for ServerError {
                 )
             }
 
+            ServerError::Recipients {
+                ref source,
+                ref failed,
+            } => (
+                map_core_error_to_status(source),
+                ErrorResponse::new(
+                    map_core_error_to_code(source),
+                    sanitize_error_message(source),
+                )
+                .with_failed(failed.clone()),
+            ),
+
             ServerError::NotFound(ref msg) => (
                 StatusCode::NOT_FOUND,
                 ErrorResponse::new(ErrorCode::NotFound, msg.clone()),
@@ -410,4 +561,13 @@ mod tests {
         assert!(json.contains("claude_1"));
         assert!(json.contains("claude_2"));
     }
+    #[test]
+    fn test_pruned_message_has_distinct_code() {
+        let pruned = mouchak_mail_core::Error::MessagePruned(7);
+        assert_eq!(map_core_error_to_status(&pruned), StatusCode::NOT_FOUND);
+        assert_eq!(map_core_error_to_code(&pruned).as_str(), "NOT_FOUND_PRUNED");
+
+        let missing = mouchak_mail_core::Error::MessageNotFound(7);
+        assert_eq!(map_core_error_to_code(&missing).as_str(), "NOT_FOUND");
+    }
 }
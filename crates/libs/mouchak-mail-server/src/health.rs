@@ -0,0 +1,162 @@
+//! Component health checks
+//!
+//! Backs `/health` and `/health/ready`: the database, the Git archive and
+//! free disk space for the data directory are checked on every call, and the
+//! report is `degraded` (HTTP 503) if any of them fails.
+
+use mouchak_mail_core::model::archive_queue::ArchiveQueueStats;
+use serde::Serialize;
+use std::path::Path;
+use std::time::Instant;
+use utoipa::ToSchema;
+
+use crate::AppState;
+
+/// Default minimum free space for the data directory (100 MB)
+const DEFAULT_MIN_FREE_DISK_MB: u64 = 100;
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Overall health report
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HealthReport {
+    /// `healthy` or `degraded`
+    pub status: &'static str,
+    pub version: &'static str,
+    #[schema(example = 120)]
+    pub uptime_seconds: u64,
+    pub components: HealthComponents,
+    /// Write-behind archive queue depth and commit lag (informational)
+    pub archive_queue: ArchiveQueueStats,
+}
+
+impl HealthReport {
+    pub fn is_healthy(&self) -> bool {
+        self.components.database.ok && self.components.git_archive.ok && self.components.disk.ok
+    }
+}
+
+/// Per-component results
+#[derive(Debug, Serialize, ToSchema)]
+pub struct HealthComponents {
+    pub database: ComponentHealth,
+    pub git_archive: ComponentHealth,
+    pub disk: ComponentHealth,
+}
+
+/// Result of a single component check
+#[derive(Debug, Default, Serialize, ToSchema)]
+pub struct ComponentHealth {
+    pub ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+    /// Free bytes on the data volume (disk check only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub free_bytes: Option<u64>,
+    /// Total bytes on the data volume (disk check only)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl ComponentHealth {
+    fn failed(error: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            error: Some(error.into()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Run every component check.
+pub async fn check_components(state: &AppState) -> HealthReport {
+    let components = HealthComponents {
+        database: check_database(state).await,
+        git_archive: check_git_archive(state),
+        disk: check_disk(&state.mm.repo_root, min_free_disk_bytes()),
+    };
+
+    let mut report = HealthReport {
+        status: "healthy",
+        version: env!("CARGO_PKG_VERSION"),
+        uptime_seconds: state.start_time.elapsed().as_secs(),
+        components,
+        archive_queue: state.mm.archive_queue.stats(),
+    };
+    if !report.is_healthy() {
+        report.status = "degraded";
+    }
+    report
+}
+
+async fn check_database(state: &AppState) -> ComponentHealth {
+    let start = Instant::now();
+    match state.mm.health_check().await {
+        Ok(true) => ComponentHealth {
+            ok: true,
+            latency_ms: Some(start.elapsed().as_millis() as u64),
+            ..Default::default()
+        },
+        Ok(false) => ComponentHealth::failed("Database query returned no rows"),
+        Err(e) => ComponentHealth::failed(e.to_string()),
+    }
+}
+
+fn check_git_archive(state: &AppState) -> ComponentHealth {
+    let start = Instant::now();
+    match state.mm.archive_health_check() {
+        Ok(()) => ComponentHealth {
+            ok: true,
+            latency_ms: Some(start.elapsed().as_millis() as u64),
+            ..Default::default()
+        },
+        Err(e) => ComponentHealth::failed(e.to_string()),
+    }
+}
+
+/// Free-space check for the volume holding `path`.
+pub fn check_disk(path: &Path, min_free_bytes: u64) -> ComponentHealth {
+    match disk_space(path) {
+        Ok(Some((free, total))) => ComponentHealth {
+            ok: free >= min_free_bytes,
+            free_bytes: Some(free),
+            total_bytes: Some(total),
+            error: (free < min_free_bytes)
+                .then(|| format!("Less than {} bytes free", min_free_bytes)),
+            ..Default::default()
+        },
+        // Platform without statvfs: nothing to report, don't fail readiness
+        Ok(None) => ComponentHealth {
+            ok: true,
+            ..Default::default()
+        },
+        Err(e) => ComponentHealth::failed(e.to_string()),
+    }
+}
+
+#[cfg(unix)]
+fn disk_space(path: &Path) -> std::io::Result<Option<(u64, u64)>> {
+    let stat = rustix::fs::statvfs(path)?;
+    let block = stat.f_frsize;
+    Ok(Some((
+        stat.f_bavail.saturating_mul(block),
+        stat.f_blocks.saturating_mul(block),
+    )))
+}
+
+#[cfg(not(unix))]
+#[allow(clippy::unnecessary_wraps)] // Mirrors the unix signature
+fn disk_space(_path: &Path) -> std::io::Result<Option<(u64, u64)>> {
+    Ok(None)
+}
+
+/// Minimum free disk space before the disk check degrades.
+/// Set HEALTH_MIN_FREE_DISK_MB environment variable to override.
+pub fn min_free_disk_bytes() -> u64 {
+    std::env::var("HEALTH_MIN_FREE_DISK_MB")
+        .ok()
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(DEFAULT_MIN_FREE_DISK_MB)
+        .saturating_mul(BYTES_PER_MB)
+}
@@ -0,0 +1,222 @@
+//! Event hooks
+//!
+//! Runs the local commands configured under `[[hooks.on]]` when a matching
+//! mailbox event is published, much like Git hooks: each command gets the
+//! event as JSON on stdin. Commands run off the request path with a
+//! concurrency cap and a per-hook timeout; a failing hook is logged and never
+//! affects the request that caused the event.
+
+use futures::future::join_all;
+use mouchak_mail_common::config::{HookConfig, HookEvent, HooksConfig};
+use mouchak_mail_core::events::{EventBus, MailEvent, MailEventKind};
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+
+/// How one hook command run ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HookOutcome {
+    Succeeded,
+    /// Could not start, or exited unsuccessfully
+    Failed(String),
+    /// Killed after its `timeout_secs`
+    TimedOut,
+}
+
+/// A hook command and how its run ended.
+#[derive(Debug, Clone)]
+pub struct HookRun {
+    pub command: String,
+    pub outcome: HookOutcome,
+}
+
+/// Runs configured hooks for matching events.
+pub struct HookRunner {
+    hooks: Vec<HookConfig>,
+    permits: Semaphore,
+}
+
+impl HookRunner {
+    pub fn new(config: &HooksConfig) -> Self {
+        Self {
+            hooks: config.on.clone(),
+            permits: Semaphore::new(config.max_concurrent.max(1)),
+        }
+    }
+
+    /// Hooks that run for `event`.
+    pub fn matching<'a>(&'a self, event: &'a MailEvent) -> impl Iterator<Item = &'a HookConfig> {
+        self.hooks.iter().filter(move |hook| matches(hook, event))
+    }
+
+    /// Runs every hook matching `event` and waits for them, logging
+    /// failures.
+    pub async fn fire(&self, event: &MailEvent) -> Vec<HookRun> {
+        let payload = match serde_json::to_vec(event) {
+            Ok(payload) => payload,
+            Err(e) => {
+                tracing::warn!(
+                    event = event.kind.as_str(),
+                    "Cannot encode hook payload: {}",
+                    e
+                );
+                return Vec::new();
+            }
+        };
+
+        join_all(self.matching(event).map(|hook| async {
+            let outcome = match self.permits.acquire().await {
+                Ok(_permit) => run_hook(hook, &payload).await,
+                Err(_) => HookOutcome::Failed("hook runner closed".to_string()),
+            };
+            let command = hook.command.join(" ");
+            match &outcome {
+                HookOutcome::Succeeded => {}
+                HookOutcome::Failed(reason) => tracing::warn!(
+                    event = event.kind.as_str(),
+                    command = %command,
+                    "Hook failed: {}",
+                    reason
+                ),
+                HookOutcome::TimedOut => tracing::warn!(
+                    event = event.kind.as_str(),
+                    command = %command,
+                    "Hook killed after {}s",
+                    hook.timeout_secs
+                ),
+            }
+            HookRun { command, outcome }
+        }))
+        .await
+    }
+
+    /// Fires hooks for events published on `bus` until `cancel` fires or
+    /// the bus closes. Each event's hooks run in their own task so a slow
+    /// hook never holds up later events.
+    pub async fn run(self: Arc<Self>, bus: Arc<EventBus>, cancel: CancellationToken) {
+        let mut receiver = bus.subscribe();
+        loop {
+            let event = tokio::select! {
+                _ = cancel.cancelled() => break,
+                received = receiver.recv() => match received {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Event hooks fell behind and skipped {} events", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                },
+            };
+            if self.matching(&event).next().is_none() {
+                continue;
+            }
+            let runner = self.clone();
+            tokio::spawn(async move {
+                runner.fire(&event).await;
+            });
+        }
+    }
+}
+
+/// Whether `hook` runs for `event`.
+fn matches(hook: &HookConfig, event: &MailEvent) -> bool {
+    let filter = &hook.filter;
+    hook.event.as_str() == event.kind.as_str()
+        && filter
+            .project
+            .as_ref()
+            .is_none_or(|project| *project == event.project_slug)
+        && filter.importance.as_ref().is_none_or(|wanted| {
+            event.data["importance"]
+                .as_str()
+                .is_some_and(|importance| importance.eq_ignore_ascii_case(wanted))
+        })
+}
+
+/// Runs one hook command with `payload` on stdin.
+///
+/// The child is killed when the timeout drops its future.
+async fn run_hook(hook: &HookConfig, payload: &[u8]) -> HookOutcome {
+    let Some((program, args)) = hook.command.split_first() else {
+        return HookOutcome::Failed("empty command".to_string());
+    };
+
+    let run = async {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()?;
+        if let Some(mut stdin) = child.stdin.take() {
+            // A hook that ignores its input may exit before reading it
+            let _ = stdin.write_all(payload).await;
+        }
+        child.wait_with_output().await
+    };
+
+    match tokio::time::timeout(Duration::from_secs(hook.timeout_secs), run).await {
+        Err(_) => HookOutcome::TimedOut,
+        Ok(Err(e)) => HookOutcome::Failed(format!("cannot run {}: {}", program, e)),
+        Ok(Ok(output)) if output.status.success() => HookOutcome::Succeeded,
+        Ok(Ok(output)) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            match stderr.trim().lines().last() {
+                Some(line) => HookOutcome::Failed(format!("{}: {}", output.status, line)),
+                None => HookOutcome::Failed(output.status.to_string()),
+            }
+        }
+    }
+}
+
+/// A made-up event for `hooks test`, shaped like the real one.
+pub fn synthetic_event(event: HookEvent, project_slug: &str, importance: &str) -> MailEvent {
+    let (kind, data) = match event {
+        HookEvent::MessageCreated => (
+            MailEventKind::MessageCreated,
+            serde_json::json!({
+                "id": 0,
+                "project_slug": project_slug,
+                "sender_name": "HookTest",
+                "recipients": ["HookTest"],
+                "subject": "Hook test",
+                "importance": importance,
+                "thread_id": null,
+                "ack_required": false,
+            }),
+        ),
+        HookEvent::MessageAcked => (
+            MailEventKind::MessageAcked,
+            serde_json::json!({
+                "message_id": 0,
+                "agent_name": "HookTest",
+                "subject": "Hook test",
+                "importance": importance,
+                "thread_id": null,
+            }),
+        ),
+        HookEvent::ReservationConflict => (
+            MailEventKind::ReservationConflict,
+            serde_json::json!({
+                "id": 0,
+                "agent_name": "HookTest",
+                "path_pattern": "src/**",
+                "exclusive": true,
+                "conflicts": [],
+            }),
+        ),
+    };
+    MailEvent {
+        id: 0,
+        kind,
+        project_slug: project_slug.to_string(),
+        data,
+        ts: chrono::Utc::now().to_rfc3339(),
+    }
+}
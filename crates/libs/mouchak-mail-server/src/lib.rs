@@ -5,7 +5,6 @@ use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
 use std::net::SocketAddr;
 use std::sync::OnceLock;
 use std::time::Instant;
-use tower_http::cors::{Any, CorsLayer};
 use tower_http::limit::RequestBodyLimitLayer;
 use tower_http::set_header::SetResponseHeaderLayer;
 use tower_http::trace::TraceLayer;
@@ -13,10 +12,15 @@ use tower_http::trace::TraceLayer;
 // Modules
 pub mod api;
 pub mod auth;
+pub mod cors;
 pub mod error;
+pub mod health;
+pub mod hooks;
 pub mod mcp;
 pub mod openapi;
 pub mod ratelimit;
+pub mod shutdown;
+pub mod telemetry;
 pub mod tools;
 
 #[cfg(feature = "with-web-ui")]
@@ -24,7 +28,8 @@ pub mod embedded;
 #[cfg(feature = "with-web-ui")]
 pub mod static_files;
 
-use utoipa::{OpenApi, ToSchema};
+use utoipa::ToSchema;
+use utoipa_swagger_ui::SwaggerUi;
 
 use auth::{AuthConfig, JwksClient, auth_middleware};
 pub use error::ServerError;
@@ -52,8 +57,9 @@ pub fn setup_metrics() -> PrometheusHandle {
             ];
 
             PrometheusBuilder::new()
+                // Covers http_request_duration_seconds and mcp_tool_duration_seconds
                 .set_buckets_for_metric(
-                    Matcher::Full("http_request_duration_seconds".to_string()),
+                    Matcher::Suffix("_duration_seconds".to_string()),
                     EXPONENTIAL_SECONDS,
                 )
                 .expect("Failed to set buckets")
@@ -74,17 +80,21 @@ pub async fn run(
     // Initialize ModelManager
     let mm = ModelManager::new(std::sync::Arc::new(config.clone())).await?;
 
+    let hooks = shutdown::ShutdownHooks::new();
+
     // Start Escalation Background Service
     if config.escalation.escalation_enabled {
         let mm_clone = mm.clone();
         let config_clone = config.escalation.clone();
-        tokio::spawn(async move {
+        hooks.spawn("escalation", |cancel| async move {
             tracing::info!("Starting Escalation Background Service");
             loop {
-                tokio::time::sleep(tokio::time::Duration::from_secs(
-                    config_clone.scan_interval_seconds,
-                ))
-                .await;
+                tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = tokio::time::sleep(tokio::time::Duration::from_secs(
+                        config_clone.scan_interval_seconds,
+                    )) => {}
+                }
 
                 // Use root context for background tasks
                 let ctx = mouchak_mail_core::ctx::Ctx::root_ctx();
@@ -114,6 +124,145 @@ pub async fn run(
         });
     }
 
+    // Start Scheduled Message Delivery
+    // Runs once immediately so messages that fell due while the server was
+    // down are delivered at startup, then polls at the configured interval.
+    {
+        let mm_clone = mm.clone();
+        let interval_secs = config.messages.scheduler_interval_seconds.max(1);
+        hooks.spawn("scheduler", move |cancel| async move {
+            tracing::info!("Starting Scheduled Message Delivery Service");
+            loop {
+                let ctx = mouchak_mail_core::ctx::Ctx::root_ctx();
+                match mouchak_mail_core::model::message::MessageBmc::deliver_due_scheduled(
+                    &ctx, &mm_clone,
+                )
+                .await
+                {
+                    Ok(delivered) => {
+                        if !delivered.is_empty() {
+                            tracing::info!(
+                                "Scheduler: Delivered {} scheduled messages",
+                                delivered.len()
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("Scheduler Error: {}", e);
+                    }
+                }
+
+                tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = tokio::time::sleep(tokio::time::Duration::from_secs(interval_secs)) => {}
+                }
+            }
+        });
+    }
+
+    // Start Reservation Queue Sweeper
+    // Releases promote waiters immediately; this grants the ones whose
+    // blockers expired and drops requests that waited too long.
+    {
+        let mm_clone = mm.clone();
+        let interval_secs = config.reservations.queue_sweep_interval_seconds.max(1);
+        hooks.spawn("reservation_queue", move |cancel| async move {
+            tracing::info!("Starting Reservation Queue Sweeper");
+            loop {
+                tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = tokio::time::sleep(tokio::time::Duration::from_secs(interval_secs)) => {}
+                }
+
+                let ctx = mouchak_mail_core::ctx::Ctx::root_ctx();
+                match mouchak_mail_core::model::reservation_queue::ReservationQueueBmc::sweep(
+                    &ctx, &mm_clone,
+                )
+                .await
+                {
+                    Ok(granted) => {
+                        if !granted.is_empty() {
+                            tracing::info!(
+                                "Reservation Queue: Granted {} queued reservations",
+                                granted.len()
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("Reservation Queue Error: {}", e);
+                    }
+                }
+            }
+        });
+    }
+
+    // Start Retention Sweeper
+    // Prunes messages past each project's retention policy; projects without
+    // a policy are never touched.
+    {
+        let mm_clone = mm.clone();
+        let interval_secs = config.messages.retention_sweep_interval_seconds.max(1);
+        hooks.spawn("retention", move |cancel| async move {
+            tracing::info!("Starting Retention Sweeper");
+            loop {
+                tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = tokio::time::sleep(tokio::time::Duration::from_secs(interval_secs)) => {}
+                }
+
+                let ctx = mouchak_mail_core::ctx::Ctx::root_ctx();
+                match mouchak_mail_core::model::retention::RetentionBmc::prune_all(&ctx, &mm_clone)
+                    .await
+                {
+                    Ok(reports) => {
+                        let pruned: usize = reports.iter().map(|r| r.pruned).sum();
+                        if pruned > 0 {
+                            tracing::info!("Retention: Pruned {} messages", pruned);
+                        }
+                    }
+                    Err(e) => {
+                        tracing::error!("Retention Error: {}", e);
+                    }
+                }
+            }
+        });
+    }
+
+    // Start Backup Scheduler
+    // Snapshots the database into the backup directory and rotates old ones.
+    if config.backup.interval_seconds > 0 {
+        let mm_clone = mm.clone();
+        let interval_secs = config.backup.interval_seconds;
+        hooks.spawn("backup", move |cancel| async move {
+            tracing::info!("Starting Backup Scheduler (every {}s)", interval_secs);
+            loop {
+                tokio::select! {
+                    _ = cancel.cancelled() => break,
+                    _ = tokio::time::sleep(tokio::time::Duration::from_secs(interval_secs)) => {}
+                }
+
+                let ctx = mouchak_mail_core::ctx::Ctx::root_ctx();
+                if let Err(e) =
+                    mouchak_mail_core::model::backup::BackupBmc::create(&ctx, &mm_clone).await
+                {
+                    tracing::error!("Backup Error: {}", e);
+                }
+            }
+        });
+    }
+
+    // Start Event Hooks
+    // Runs configured commands for matching events, off the request path.
+    if !config.hooks.on.is_empty() {
+        let runner = std::sync::Arc::new(crate::hooks::HookRunner::new(&config.hooks));
+        let bus = mm.events.clone();
+        tracing::info!(
+            "Starting Event Hooks ({} configured)",
+            config.hooks.on.len()
+        );
+        hooks.spawn("event_hooks", move |cancel| runner.run(bus, cancel));
+    }
+
     // Create MCP routes with shared ModelManager (clone before move)
     let mcp_routes = mcp::mcp_routes(mm.clone());
 
@@ -124,48 +273,64 @@ pub async fn run(
     let jwks_client = auth_config
         .jwks_url
         .as_ref()
-        .map(|url| JwksClient::new(url.clone()));
+        .map(|url| JwksClient::from_env(url.clone()));
 
     let app_state = AppState {
-        mm,
+        mm: mm.clone(),
         metrics_handle,
         start_time: Instant::now(),
         auth_config,
         jwks_client,
-        ratelimit_config: ratelimit::RateLimitConfig::new(),
+        ratelimit_config: ratelimit::RateLimitConfig::from_config(&config.rate_limit),
     };
 
     // Build our application with routes
-    let cors = CorsLayer::new()
-        .allow_origin(Any)
-        .allow_methods(Any)
-        .allow_headers(Any);
-
     let mut app = Router::new()
         .merge(api::routes())
         .merge(mcp_routes)
+        // Inside auth so the version lookup sees the caller's scope
+        .route_layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            api::project_version::project_version_middleware,
+        ))
+        // 4. Rate Limiting (Hardening 577.13), inside auth so buckets follow
+        // the authenticated caller rather than names in the request
+        .route_layer(axum::middleware::from_fn_with_state(
+            app_state.clone(),
+            ratelimit::rate_limit_middleware,
+        ))
         .route_layer(axum::middleware::from_fn_with_state(
             app_state.clone(),
             auth_middleware,
         ))
         // Public routes (no auth)
-        // .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", openapi::ApiDoc::openapi()))
-        .route("/api-docs/openapi.json", get(openapi_json))
+        .merge(api_docs_routes(
+            config.server.api_docs,
+            &app_state.auth_config.mode,
+        ))
         .route("/health", get(health_handler))
         .route("/ready", get(ready_handler))
         .route("/metrics", get(metrics_handler))
         // Prod Hardening: Liveness/Readiness probes (k8s style)
-        .route("/healthz", get(health_handler))
+        .route("/health/live", get(liveness_handler))
+        .route("/health/ready", get(ready_handler))
+        .route("/healthz", get(liveness_handler))
         // MCP health endpoint (NTM compatibility)
         .route("/mcp/health", get(mcp_health_handler))
-        .layer(TraceLayer::new_for_http())
-        // 4. Rate Limiting (Hardening 577.13)
-        // Global middleware using Axum 0.8 middleware::from_fn_with_state
-        .route_layer(axum::middleware::from_fn_with_state(
+        .layer(axum::middleware::from_fn_with_state(
             app_state.clone(),
-            ratelimit::rate_limit_middleware,
+            telemetry::http_metrics_middleware,
         ))
-        .layer(cors) // Enable CORS
+        .layer(TraceLayer::new_for_http());
+
+    // CORS only when configured; outside auth so preflights are answered
+    // before credentials are checked
+    if let Some(cors) = &config.server.cors {
+        tracing::info!("CORS enabled for {:?}", cors.allowed_origins);
+        app = app.layer(cors::cors_layer(cors)?);
+    }
+
+    app = app
         // 5. Security Headers (Hardening CSP/XSS Protection)
         .layer(SetResponseHeaderLayer::overriding(
             HeaderName::from_static("content-security-policy"),
@@ -197,13 +362,17 @@ pub async fn run(
             HeaderValue::from_static("camera=(), microphone=(), geolocation=()"),
         ))
         // 9. Request Body Size Limit (NIST SC-5 DoS Protection)
-        .layer(RequestBodyLimitLayer::new(get_request_body_limit()));
+        .layer(RequestBodyLimitLayer::new(get_request_body_limit(
+            &config.messages,
+        )));
 
     // Conditionally add embedded web UI routes
     #[cfg(feature = "with-web-ui")]
     if config.server.serve_ui {
         tracing::info!("Web UI enabled at /");
-        app = app.fallback(static_files::serve_embedded_file);
+        app = app
+            .route("/api/ui-version", get(static_files::ui_version))
+            .fallback_service(static_files::ui_router());
     } else {
         app = app.route("/", get(root_handler));
     }
@@ -219,64 +388,64 @@ pub async fn run(
     tracing::info!("Mouchak Mail Server starting on {}", addr);
     tracing::info!("Health check: http://{}/health", addr);
 
-    // Axum 0.8+ serve with ConnectInfo for localhost bypass
     let listener = tokio::net::TcpListener::bind(addr).await?;
+    let drain_timeout = std::time::Duration::from_secs(config.server.shutdown_timeout_secs);
+
+    // Closing the event bus on the signal ends SSE streams, which would
+    // otherwise hold their connections open for the whole drain timeout.
+    let events = mm.events.clone();
+    let signal = async move {
+        shutdown::shutdown_signal().await;
+        events.close();
+    };
 
-    // Graceful shutdown - use into_make_service_with_connect_info to enable
-    // ConnectInfo<SocketAddr> extraction in middleware for localhost bypass
-    axum::serve(
-        listener,
-        app.into_make_service_with_connect_info::<SocketAddr>(),
-    )
-    .with_graceful_shutdown(shutdown_signal())
-    .await?;
+    // ConnectInfo<SocketAddr> is enabled inside serve_with_drain for the localhost bypass
+    shutdown::serve_with_drain(listener, app, signal, drain_timeout).await?;
 
-    Ok(())
-}
+    // Background tasks stop before the final checkpoint so nothing writes after it.
+    hooks.shutdown(drain_timeout).await;
+    mm.shutdown().await?;
+    tracing::info!("Shutdown complete");
 
-async fn openapi_json() -> impl IntoResponse {
-    axum::Json(openapi::ApiDoc::openapi())
+    Ok(())
 }
 
-#[allow(clippy::expect_used)] // Signal handler setup is infallible in practice; panic is acceptable
-async fn shutdown_signal() {
-    let ctrl_c = async {
-        tokio::signal::ctrl_c()
-            .await
-            .expect("failed to install Ctrl+C handler");
-    };
-
-    #[cfg(unix)]
-    let terminate = async {
-        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
-            .expect("failed to install signal handler")
-            .recv()
-            .await;
-    };
-
-    #[cfg(not(unix))]
-    let terminate = std::future::pending::<()>();
-
-    tokio::select! {
-        _ = ctrl_c => {},
-        _ = terminate => {},
+/// OpenAPI document at `/api/openapi.json` (and the legacy
+/// `/api-docs/openapi.json`) plus Swagger UI at `/api/docs`, or no routes
+/// when `server.api_docs` is off.
+fn api_docs_routes(enabled: bool, auth_mode: &auth::AuthMode) -> Router<AppState> {
+    if !enabled {
+        return Router::new();
     }
-
-    tracing::info!("Signal received, starting graceful shutdown");
+    tracing::info!("API docs enabled at /api/docs");
+    let doc = openapi::ApiDoc::for_auth_mode(auth_mode);
+    let legacy = doc.clone();
+    Router::new()
+        .merge(SwaggerUi::new("/api/docs").url("/api/openapi.json", doc))
+        .route(
+            "/api-docs/openapi.json",
+            get(move || async move { axum::Json(legacy) }),
+        )
 }
 
 /// Get the request body size limit from environment variable or use default
-/// Default: 1MB (1048576 bytes)
+/// Default: 1MB (1048576 bytes), raised to twice the message body limit if that
+/// is larger, so a maximum-size body still fits after JSON escaping and is
+/// rejected with a VALIDATION_ERROR naming the limit rather than a bare 413
 /// Set MAX_REQUEST_SIZE_MB environment variable to override
-fn get_request_body_limit() -> usize {
+pub(crate) fn get_request_body_limit(
+    messages: &mouchak_mail_common::config::MessageConfig,
+) -> usize {
     const DEFAULT_LIMIT_MB: usize = 1;
     const BYTES_PER_MB: usize = 1024 * 1024;
 
     std::env::var("MAX_REQUEST_SIZE_MB")
         .ok()
         .and_then(|s| s.parse::<usize>().ok())
-        .unwrap_or(DEFAULT_LIMIT_MB)
-        * BYTES_PER_MB
+        .map(|mb| mb * BYTES_PER_MB)
+        .unwrap_or_else(|| {
+            (DEFAULT_LIMIT_MB * BYTES_PER_MB).max(messages.max_body_bytes.saturating_mul(2))
+        })
 }
 
 async fn root_handler() -> &'static str {
@@ -284,64 +453,64 @@ async fn root_handler() -> &'static str {
 }
 
 #[derive(serde::Serialize, ToSchema)]
-struct HealthResponse {
+struct LivenessResponse {
     status: &'static str,
     #[schema(example = 120)]
     uptime_seconds: u64,
 }
 
+/// Component health report: 200 when healthy, 503 with details when degraded
 #[utoipa::path(
     get,
-    path = "/api/health",
+    path = "/health",
     responses(
-        (status = 200, description = "Server Health", body = HealthResponse)
+        (status = 200, description = "All components healthy", body = health::HealthReport),
+        (status = 503, description = "One or more components degraded", body = health::HealthReport)
     )
 )]
 pub async fn health_handler(State(state): State<AppState>) -> impl IntoResponse {
-    let uptime = state.start_time.elapsed().as_secs();
-    let response = HealthResponse {
-        status: "healthy",
-        uptime_seconds: uptime,
+    let report = health::check_components(&state).await;
+    let status_code = if report.is_healthy() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
     };
-    (StatusCode::OK, axum::Json(response))
-}
-
-#[derive(serde::Serialize, ToSchema)]
-struct ReadyResponse {
-    status: &'static str,
-    database: &'static str,
+    (status_code, axum::Json(report))
 }
 
+/// Liveness probe: the process is up and serving; no dependencies checked
 #[utoipa::path(
     get,
-    path = "/api/ready",
+    path = "/health/live",
     responses(
-        (status = 200, description = "Readiness Check", body = ReadyResponse)
+        (status = 200, description = "Process is alive", body = LivenessResponse)
     )
 )]
-pub async fn ready_handler(State(state): State<AppState>) -> impl IntoResponse {
-    // Check database connectivity
-    let db_status = match state.mm.health_check().await {
-        Ok(true) => "connected",
-        _ => "disconnected",
-    };
-
-    let is_ready = db_status == "connected";
-    let response = ReadyResponse {
-        status: if is_ready { "ready" } else { "not_ready" },
-        database: db_status,
-    };
-
-    let status_code = if is_ready {
-        StatusCode::OK
-    } else {
-        StatusCode::SERVICE_UNAVAILABLE
+pub async fn liveness_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let response = LivenessResponse {
+        status: "alive",
+        uptime_seconds: state.start_time.elapsed().as_secs(),
     };
+    (StatusCode::OK, axum::Json(response))
+}
 
-    (status_code, axum::Json(response))
+/// Readiness probe: same component checks as `/health`
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    responses(
+        (status = 200, description = "Ready to serve traffic", body = health::HealthReport),
+        (status = 503, description = "Not ready", body = health::HealthReport)
+    )
+)]
+pub async fn ready_handler(state: State<AppState>) -> impl IntoResponse {
+    health_handler(state).await
 }
 
 async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
+    // Counted in lib-core, which has no metrics recorder of its own
+    metrics::counter!("db_busy_retries_total")
+        .absolute(mouchak_mail_core::store::retry::busy_retry_count());
     state.metrics_handle.render()
 }
 
@@ -6,11 +6,16 @@
 use axum::{
     Router,
     body::Body,
-    http::{Request, Response},
+    http::{Method, Request, Response},
+    response::IntoResponse,
     routing::any_service,
 };
-use mouchak_mail_core::ModelManager;
-use mouchak_mail_mcp::tools::MouchakMailService;
+use mouchak_mail_core::{
+    ModelManager,
+    ctx::Ctx,
+    model::{agent::AgentBmc, project::ProjectBmc},
+};
+use mouchak_mail_mcp::tools::{MouchakMailService, acting_agent};
 use rmcp::transport::streamable_http_server::{
     session::local::LocalSessionManager,
     tower::{StreamableHttpServerConfig, StreamableHttpService},
@@ -19,6 +24,7 @@ use std::sync::Arc;
 use tower::ServiceExt;
 
 use crate::AppState;
+use crate::error::ServerError;
 
 /// Create the MCP service for the /mcp route
 ///
@@ -64,17 +70,73 @@ fn create_mcp_service(mm: ModelManager) -> StreamableHttpService<MouchakMailServ
     StreamableHttpService::new(service_factory, session_manager, config)
 }
 
+/// Reject a `tools/call` that acts as an agent other than the one the
+/// request's agent key authenticates.
+///
+/// The tools run the same check against the request [`Ctx`]; doing it here
+/// as well answers with the 403 `AGENT_IDENTITY_MISMATCH` the REST routes
+/// use, instead of a JSON-RPC error. Calls whose project or agent does not
+/// resolve pass through so the tool reports them.
+async fn check_acting_agent(
+    mm: &ModelManager,
+    req: Request<Body>,
+) -> Result<Request<Body>, Response<Body>> {
+    let Some(ctx) = req
+        .extensions()
+        .get::<Ctx>()
+        .filter(|ctx| ctx.authenticated_agent().is_some())
+        .cloned()
+    else {
+        return Ok(req);
+    };
+    if req.method() != Method::POST {
+        return Ok(req);
+    }
+
+    let (parts, body) = req.into_parts();
+    let limit = crate::get_request_body_limit(&mm.app_config.messages);
+    let bytes = axum::body::to_bytes(body, limit)
+        .await
+        .map_err(|_| axum::http::StatusCode::PAYLOAD_TOO_LARGE.into_response())?;
+
+    let call: Option<serde_json::Value> = serde_json::from_slice(&bytes).ok();
+    let acting = call
+        .as_ref()
+        .filter(|call| call.get("method").and_then(|m| m.as_str()) == Some("tools/call"))
+        .and_then(|call| call.get("params"))
+        .and_then(|params| {
+            acting_agent(
+                params.get("name")?.as_str()?,
+                params.get("arguments").and_then(|a| a.as_object()),
+            )
+        });
+    if let Some((project_slug, agent_name)) = acting
+        && let Ok(project) = ProjectBmc::get_by_identifier(&ctx, mm, &project_slug).await
+        && let Ok(agent) = AgentBmc::get_by_name(&ctx, mm, project.id, &agent_name).await
+    {
+        ctx.ensure_acting_as(agent.id, &agent.name)
+            .map_err(|e| ServerError::from(e).into_response())?;
+    }
+
+    Ok(Request::from_parts(parts, Body::from(bytes)))
+}
+
 /// Get the MCP route for integration into the main router
 ///
 /// This returns an Axum Router that handles both GET (SSE stream) and POST (tool calls)
 /// on the /mcp endpoint. Uses the ModelManager from AppState to share database connection.
 pub fn mcp_routes(mm: ModelManager) -> Router<AppState> {
-    let mcp_service = create_mcp_service(mm);
+    let mcp_service = create_mcp_service(mm.clone());
 
     // Wrap the MCP service to convert body types
     let wrapped_service = tower::service_fn(move |req: Request<Body>| {
         let svc = mcp_service.clone();
+        let mm = mm.clone();
         async move {
+            let req = match check_acting_agent(&mm, req).await {
+                Ok(req) => req,
+                Err(response) => return Ok(response),
+            };
             // Call the MCP service
             let response = svc.oneshot(req).await?;
             // Convert BoxBody to axum::body::Body
@@ -1,25 +1,487 @@
-use utoipa::OpenApi;
+//! OpenAPI document for the HTTP API
+//!
+//! Served at `/api/openapi.json` (with Swagger UI at `/api/docs`) when
+//! `server.api_docs` is enabled, and written by `mouchak-mail schema --format
+//! openapi`. Use [`ApiDoc::for_auth_mode`] rather than `ApiDoc::openapi()` so
+//! the security scheme matches the server's `HTTP_AUTH_MODE`.
+
+use utoipa::openapi::security::{HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme};
+use utoipa::openapi::{Content, PathItem, RefOr, Response, path::ParameterIn};
+use utoipa::{Modify, OpenApi};
+
+use crate::auth::AuthMode;
+use crate::error::{ErrorCode, ErrorResponse};
+
+/// Name of the security scheme in `components.securitySchemes`
+const SECURITY_SCHEME: &str = "bearer_auth";
+
+/// Routes registered under a second path for Python client compatibility,
+/// as (alias, canonical). Each alias documents the canonical operation.
+const ROUTE_ALIASES: &[(&str, &str)] = &[
+    ("/ready", "/health/ready"),
+    ("/healthz", "/health/live"),
+    ("/api/health_check", "/api/health"),
+    ("/api/readiness", "/api/ready"),
+    ("/api/get_server_info", "/api/server-info"),
+    ("/api/ensure_project", "/api/project/ensure"),
+    ("/api/list_projects", "/api/projects"),
+    ("/api/list_all_projects", "/api/projects"),
+    ("/api/list_agents", "/api/projects/{project_slug}/agents"),
+    ("/api/register_agent", "/api/agent/register"),
+    ("/api/whois", "/api/agent/whois"),
+    ("/api/create_agent_identity", "/api/agent/create_identity"),
+    ("/api/send_message", "/api/message/send"),
+    ("/api/reply_message", "/api/message/reply"),
+    ("/api/mark_message_read", "/api/message/read"),
+    ("/api/acknowledge_message", "/api/message/acknowledge"),
+    ("/api/recall_message", "/api/message/recall"),
+    ("/api/search_messages", "/api/messages/search"),
+    ("/api/pending_reviews", "/api/messages/pending-reviews"),
+    ("/api/fetch_inbox", "/api/inbox"),
+    ("/api/list_inbox", "/api/inbox"),
+    ("/api/get_inbox", "/api/inbox"),
+    ("/api/fetch_outbox", "/api/outbox"),
+    ("/api/list_outbox", "/api/outbox"),
+    ("/api/get_outbox", "/api/outbox"),
+    (
+        "/api/get_message/{message_id}",
+        "/api/messages/{message_id}",
+    ),
+    ("/api/get_thread", "/api/thread"),
+    ("/api/list_threads", "/api/threads"),
+    (
+        "/api/file_reservation_paths",
+        "/api/file_reservations/paths",
+    ),
+    ("/api/list_file_reservations", "/api/file_reservations/list"),
+    ("/api/reservations", "/api/file_reservations/list"),
+    ("/mail/api/locks", "/api/locks"),
+    (
+        "/api/release_file_reservation",
+        "/api/file_reservations/release",
+    ),
+    (
+        "/api/release_file_reservations",
+        "/api/file_reservations/release",
+    ),
+    (
+        "/api/force_release_file_reservation",
+        "/api/file_reservations/force_release",
+    ),
+    (
+        "/api/force_release_reservation",
+        "/api/file_reservations/force_release",
+    ),
+    (
+        "/api/renew_file_reservation",
+        "/api/file_reservations/renew",
+    ),
+    ("/api/get_project_info", "/api/project/info"),
+    ("/api/project_info", "/api/project/info"),
+    ("/api/get_quota_status", "/api/quota/status"),
+    ("/api/get_agent_profile", "/api/agent/profile"),
+    ("/api/agent_profile", "/api/agent/profile"),
+    ("/api/update_agent_profile", "/api/agent/profile/update"),
+    ("/api/request_contact", "/api/contacts/request"),
+    ("/api/respond_contact", "/api/contacts/respond"),
+    ("/api/list_contacts", "/api/contacts/list"),
+    ("/api/set_contact_policy", "/api/contacts/policy"),
+    ("/api/acquire_build_slot", "/api/build_slots/acquire"),
+    ("/api/renew_build_slot", "/api/build_slots/renew"),
+    ("/api/release_build_slot", "/api/build_slots/release"),
+    ("/api/send_overseer_message", "/api/overseer/send"),
+    ("/api/list_macros", "/api/macros/list"),
+    ("/api/register_macro", "/api/macros/register"),
+    ("/api/unregister_macro", "/api/macros/unregister"),
+    ("/api/invoke_macro", "/api/macros/invoke"),
+    ("/api/macro_start_session", "/api/macros/start_session"),
+    (
+        "/api/macro_file_reservation_cycle",
+        "/api/macros/file_reservation_cycle",
+    ),
+    (
+        "/api/macro_contact_handshake",
+        "/api/macros/contact_handshake",
+    ),
+    ("/api/summarize_thread", "/api/thread/summarize"),
+    ("/api/summarize_threads", "/api/threads/summarize"),
+    ("/api/install_precommit_guard", "/api/setup/install_guard"),
+    ("/api/install_guard", "/api/setup/install_guard"),
+    (
+        "/api/uninstall_precommit_guard",
+        "/api/setup/uninstall_guard",
+    ),
+    ("/api/uninstall_guard", "/api/setup/uninstall_guard"),
+    ("/api/add_attachment", "/api/attachments/add"),
+    ("/api/attachments/get", "/api/attachments/{id}"),
+    ("/api/get_attachment/{id}", "/api/attachments/{id}"),
+    ("/api/get_attachment", "/api/attachments/{id}"),
+    ("/api/list_tool_metrics", "/api/metrics/tools"),
+    ("/api/get_tool_stats", "/api/metrics/tools/stats"),
+    ("/api/tool_stats", "/api/metrics/tools/stats"),
+    ("/api/list_activity", "/api/activity"),
+    ("/api/commit_archive", "/api/archive/commit"),
+    ("/api/verify_archive", "/api/archive/verify"),
+    ("/api/list_project_siblings", "/api/project/siblings"),
+];
 
 #[derive(OpenApi)]
 #[openapi(
+    info(title = "Mouchak Mail", description = "Mouchak Mail HTTP API"),
     paths(
         // Health
         crate::health_handler,
+        crate::liveness_handler,
         crate::ready_handler,
+        crate::mcp_health_handler,
+        // Core tools
+        crate::tools::health_check,
+        crate::tools::readiness_check,
+        crate::api::server_info::server_info,
+        crate::api::backup::create_backup,
+        crate::tools::ensure_project,
+        crate::tools::register_agent,
+        crate::tools::send_message,
+        crate::tools::list_inbox,
+        crate::tools::list_outbox,
+        crate::tools::list_all_projects,
+        crate::tools::delete_project,
+        crate::tools::delete_agent,
+        crate::tools::update_agent,
+        crate::tools::retire_agent,
+        crate::tools::list_all_agents_for_project,
+        crate::tools::get_message,
+        crate::tools::file_reservation_paths,
+        crate::tools::list_reservation_queue,
+        crate::tools::create_agent_identity,
+        crate::tools::whois,
+        crate::tools::list_file_reservations,
+        crate::tools::list_all_locks,
+        crate::tools::release_file_reservation,
+        crate::tools::get_thread,
+        crate::tools::reply_message,
+        crate::tools::search_messages,
+        crate::tools::force_release_reservation,
+        crate::tools::renew_file_reservation,
+        crate::tools::get_project_info,
+        crate::tools::get_quota_status,
+        crate::tools::get_agent_profile,
+        crate::tools::mark_message_read,
+        crate::tools::acknowledge_message,
+        crate::tools::recall_message,
+        crate::tools::list_scheduled,
+        crate::tools::cancel_scheduled,
+        crate::tools::snooze_message,
+        crate::tools::list_snoozed,
+        crate::tools::list_threads,
+        crate::tools::update_agent_profile,
+        crate::tools::request_contact,
+        crate::tools::respond_contact,
+        crate::tools::list_contacts,
+        crate::tools::set_contact_policy,
+        crate::tools::acquire_build_slot,
+        crate::tools::renew_build_slot,
+        crate::tools::release_build_slot,
+        crate::tools::send_overseer_message,
+        crate::tools::list_macros,
+        crate::tools::register_macro,
+        crate::tools::unregister_macro,
+        crate::tools::invoke_macro,
+        crate::tools::macro_start_session,
+        crate::tools::macro_file_reservation_cycle,
+        crate::tools::macro_contact_handshake,
+        crate::tools::summarize_thread,
+        crate::tools::summarize_threads,
+        crate::tools::install_precommit_guard,
+        crate::tools::uninstall_precommit_guard,
+        crate::tools::list_tool_metrics,
+        crate::tools::get_tool_stats,
+        crate::tools::get_tool_metrics_summary,
+        crate::tools::list_activity,
+        crate::tools::commit_archive,
+        crate::tools::verify_archive,
+        crate::tools::list_project_siblings,
+        crate::tools::list_pending_reviews,
+        crate::tools::list_archive_commits,
+        crate::tools::get_archive_commit,
+        crate::tools::list_archive_files,
+        crate::tools::get_archive_file_content,
+        crate::tools::get_archive_activity,
+        // Unified inbox
+        crate::api::unified_inbox::unified_inbox_json,
         // Attachments
         crate::api::attachments::add_attachment,
         crate::api::attachments::list_attachments,
         crate::api::attachments::get_attachment,
         // Export
         crate::api::export::export_mailbox,
+        crate::api::export::export_project,
+        crate::api::export::export_messages,
+        crate::api::export::import_mailbox,
+        // Bulk message actions
+        crate::api::messages::mark_read_batch,
+        // Unread counts
+        crate::api::unread_counts::project_unread_counts,
+        crate::api::unread_counts::all_unread_counts,
+        // Notifications
+        crate::api::notifications::list_notifications,
+        crate::api::notifications::dismiss_notifications,
+        // Project stats
+        crate::api::project_stats::project_stats,
+        crate::api::project_stats::all_project_stats,
+        crate::api::project_version::project_version,
+        crate::api::reservations::project_reservations,
+        crate::api::reservations::force_release_project_reservation,
+        crate::api::reservations::transfer_project_reservation,
+        // Threads
+        crate::api::threads::list_threads,
+        crate::api::threads::thread_summary,
+        crate::api::threads::list_thread_mutes,
+        crate::api::threads::mute_thread,
+        crate::api::threads::unmute_thread,
+        // Outbox
+        crate::api::outbox::agent_outbox,
+        crate::api::inbox_wait::wait_for_inbox,
+        crate::api::agent_activity::agent_activity,
+        crate::api::pending_acks::pending_acks,
+        crate::api::audit::project_audit,
+        // Message templates
+        crate::api::templates::list_templates,
+        crate::api::templates::create_template,
+        crate::api::templates::get_template,
+        crate::api::templates::delete_template,
+        // Agent groups
+        crate::api::groups::list_groups,
+        crate::api::groups::create_group,
+        crate::api::groups::get_group,
+        crate::api::groups::delete_group,
+        crate::api::groups::add_group_member,
+        crate::api::groups::remove_group_member,
+        // Message labels
+        crate::api::labels::list_labels,
+        crate::api::labels::create_label,
+        crate::api::labels::delete_label,
+        crate::api::labels::list_message_labels,
+        crate::api::labels::update_message_labels,
+        // Events
+        crate::api::events::event_stream,
     ),
-    components(
-        schemas(
-            // Auto-discovery might need explicit import if not working
-        )
-    ),
+    components(schemas(ErrorResponse, ErrorCode)),
+    modifiers(&ErrorEnvelope, &RouteAliases),
     tags(
         (name = "mouchak-mail", description = "Mouchak Mail API")
     )
 )]
 pub struct ApiDoc;
+
+impl ApiDoc {
+    /// The document with the security scheme for `mode`.
+    ///
+    /// With authentication enabled every `/api/` operation requires it and
+    /// may answer 401; health and MCP probe routes stay public.
+    pub fn for_auth_mode(mode: &AuthMode) -> utoipa::openapi::OpenApi {
+        let mut doc = Self::openapi();
+        let scheme = match mode {
+            AuthMode::None => return doc,
+            AuthMode::Bearer => HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build(),
+            AuthMode::Jwt => HttpBuilder::new()
+                .scheme(HttpAuthScheme::Bearer)
+                .bearer_format("JWT")
+                .build(),
+        };
+        doc.components
+            .get_or_insert_with(Default::default)
+            .add_security_scheme(SECURITY_SCHEME, SecurityScheme::Http(scheme));
+
+        for (path, item) in doc.paths.paths.iter_mut() {
+            if !path.starts_with("/api/") {
+                continue;
+            }
+            for op in operations_mut(item) {
+                op.security = Some(vec![SecurityRequirement::new(
+                    SECURITY_SCHEME,
+                    Vec::<String>::new(),
+                )]);
+                op.responses.responses.insert(
+                    "401".to_string(),
+                    RefOr::T(Response::new("Missing or invalid credentials")),
+                );
+            }
+        }
+        doc
+    }
+}
+
+/// Gives every 4xx/5xx response the `ErrorResponse` body the handlers
+/// actually send, and documents the rate limiter's 429.
+struct ErrorEnvelope;
+
+impl Modify for ErrorEnvelope {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for item in openapi.paths.paths.values_mut() {
+            for op in operations_mut(item) {
+                op.responses
+                    .responses
+                    .entry("429".to_string())
+                    .or_insert_with(|| RefOr::T(Response::new("Rate limit exceeded")));
+                for (status, response) in op.responses.responses.iter_mut() {
+                    if let RefOr::T(response) = response
+                        && (status.starts_with('4') || status.starts_with('5'))
+                        && response.content.is_empty()
+                    {
+                        response.content.insert(
+                            "application/json".to_string(),
+                            Content::new(Some(RefOr::Ref(utoipa::openapi::Ref::from_schema_name(
+                                "ErrorResponse",
+                            )))),
+                        );
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Documents each alias in [`ROUTE_ALIASES`] as a copy of its canonical
+/// operations, with a unique operation ID and without path parameters the
+/// alias doesn't have.
+struct RouteAliases;
+
+impl Modify for RouteAliases {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        for (alias, canonical) in ROUTE_ALIASES {
+            let Some(mut item) = openapi.paths.paths.get(*canonical).cloned() else {
+                continue;
+            };
+            for (method, op) in methods_mut(&mut item) {
+                op.operation_id = Some(format!(
+                    "{}_{}",
+                    method,
+                    alias
+                        .trim_start_matches('/')
+                        .replace(['/', '{', '}', '-'], "_")
+                ));
+                op.description = Some(format!(
+                    "Alias of `{} {}`.",
+                    method.to_uppercase(),
+                    canonical
+                ));
+                if let Some(params) = op.parameters.as_mut() {
+                    params.retain(|p| {
+                        !matches!(p.parameter_in, ParameterIn::Path)
+                            || alias.contains(&format!("{{{}}}", p.name))
+                    });
+                }
+            }
+            openapi.paths.paths.insert(alias.to_string(), item);
+        }
+    }
+}
+
+fn methods_mut(
+    item: &mut PathItem,
+) -> impl Iterator<Item = (&'static str, &mut utoipa::openapi::path::Operation)> {
+    [
+        ("get", &mut item.get),
+        ("put", &mut item.put),
+        ("post", &mut item.post),
+        ("delete", &mut item.delete),
+        ("patch", &mut item.patch),
+    ]
+    .into_iter()
+    .filter_map(|(method, op)| op.as_mut().map(|op| (method, op)))
+}
+
+fn operations_mut(
+    item: &mut PathItem,
+) -> impl Iterator<Item = &mut utoipa::openapi::path::Operation> {
+    methods_mut(item).map(|(_, op)| op)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    /// Every path registered with `.route("...")` in `api.rs`
+    fn registered_paths() -> Vec<&'static str> {
+        include_str!("api.rs")
+            .split(".route(")
+            .skip(1)
+            .filter_map(|rest| rest.trim_start().strip_prefix('"')?.split('"').next())
+            .collect()
+    }
+
+    fn document(mode: &AuthMode) -> serde_json::Value {
+        let json = ApiDoc::for_auth_mode(mode).to_json().unwrap();
+        serde_json::from_str(&json).expect("document is valid JSON")
+    }
+
+    #[test]
+    fn test_document_covers_every_route() {
+        let doc = document(&AuthMode::None);
+        assert_eq!(doc["info"]["title"], "Mouchak Mail");
+        let paths = doc["paths"].as_object().expect("paths object");
+
+        let registered = registered_paths();
+        assert!(registered.len() > 100, "route extraction broke");
+        let missing: Vec<_> = registered
+            .iter()
+            .filter(|path| !paths.contains_key(**path))
+            .collect();
+        assert!(missing.is_empty(), "undocumented routes: {:?}", missing);
+
+        let mut operation_ids: Vec<&str> = paths
+            .values()
+            .flat_map(|item| item.as_object().unwrap().values())
+            .filter_map(|op| op["operationId"].as_str())
+            .collect();
+        let total = operation_ids.len();
+        operation_ids.sort_unstable();
+        operation_ids.dedup();
+        assert_eq!(operation_ids.len(), total, "operation IDs must be unique");
+    }
+
+    #[test]
+    fn test_errors_use_envelope() {
+        let doc = document(&AuthMode::None);
+        assert!(doc["components"]["schemas"]["ErrorResponse"].is_object());
+
+        let not_found = &doc["paths"]["/api/messages/{message_id}"]["get"]["responses"]["404"];
+        assert_eq!(
+            not_found["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/ErrorResponse"
+        );
+        let limited = &doc["paths"]["/api/inbox"]["post"]["responses"]["429"];
+        assert!(limited["content"]["application/json"].is_object());
+
+        // Aliases drop path parameters they don't have
+        let alias = &doc["paths"]["/api/get_attachment"]["get"];
+        assert_eq!(alias["operationId"], "get_api_get_attachment");
+        let params = alias["parameters"].as_array().cloned().unwrap_or_default();
+        assert!(params.iter().all(|p| p["in"] != "path"));
+    }
+
+    #[test]
+    fn test_security_scheme_follows_auth_mode() {
+        let open = document(&AuthMode::None);
+        assert!(open["components"]["securitySchemes"].is_null());
+        assert!(open["paths"]["/api/inbox"]["post"]["security"].is_null());
+
+        let bearer = document(&AuthMode::Bearer);
+        let scheme = &bearer["components"]["securitySchemes"][SECURITY_SCHEME];
+        assert_eq!(scheme["scheme"], "bearer");
+        assert!(scheme["bearerFormat"].is_null());
+        let inbox = &bearer["paths"]["/api/inbox"]["post"];
+        assert!(inbox["security"][0][SECURITY_SCHEME].is_array());
+        assert!(inbox["responses"]["401"].is_object());
+        // Probes stay public
+        assert!(bearer["paths"]["/health"]["get"]["security"].is_null());
+
+        let jwt = document(&AuthMode::Jwt);
+        assert_eq!(
+            jwt["components"]["securitySchemes"][SECURITY_SCHEME]["bearerFormat"],
+            "JWT"
+        );
+    }
+}
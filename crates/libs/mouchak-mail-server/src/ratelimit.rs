@@ -1,25 +1,38 @@
-use axum::extract::ConnectInfo;
 use axum::{
+    Json,
     extract::{Request, State},
-    http::StatusCode,
+    http::{HeaderValue, StatusCode, header},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
-use base64::{Engine as _, engine::general_purpose::URL_SAFE_NO_PAD};
+use governor::clock::Clock;
 use governor::{Quota, RateLimiter, clock::DefaultClock, state::keyed::DashMapStateStore};
-use std::net::SocketAddr;
+use mouchak_mail_common::config::RateLimitConfig as RateLimitSettings;
+use mouchak_mail_core::Ctx;
+use std::net::{IpAddr, Ipv4Addr};
 use std::num::NonZeroU32;
 use std::sync::Arc;
 use tracing::{debug, warn};
 
-/// Rate limiter keyed by composite identity string.
-///
-/// Key format: `{jwt_subject}:{ip}` for authenticated requests,
-/// or just `{ip}` for unauthenticated requests.
+use crate::auth::{AuthConfig, AuthenticatedUser, client_ip};
+use crate::error::{ErrorCode, ErrorResponse};
+
+/// Rate limiter keyed by caller identity; see [`get_bucket_key`].
 ///
 /// NIST Control: SC-5 (DoS Protection)
 type KeyedRateLimiter = RateLimiter<String, DashMapStateStore<String>, DefaultClock>;
 
+/// Probe and scrape endpoints that are never rate limited.
+const EXEMPT_PATHS: &[&str] = &[
+    "/health",
+    "/health/live",
+    "/health/ready",
+    "/healthz",
+    "/ready",
+    "/metrics",
+    "/mcp/health",
+];
+
 #[derive(Clone)]
 pub struct RateLimitConfig {
     pub limiter: Arc<KeyedRateLimiter>,
@@ -33,37 +46,31 @@ impl Default for RateLimitConfig {
 }
 
 impl RateLimitConfig {
-    #[allow(clippy::expect_used)] // NonZeroU32 from parsed u32 with fallback defaults; always valid
+    /// Create a limiter from `RATE_LIMIT_*` environment variables.
     pub fn new() -> Self {
-        let enabled =
-            std::env::var("RATE_LIMIT_ENABLED").unwrap_or_else(|_| "true".into()) == "true";
-
-        // Defaults sized for 100 concurrent agents:
-        // - 1000 RPS allows 10 requests/second per agent
-        // - 2000 burst handles initial connection spikes
-        let rps = std::env::var("RATE_LIMIT_RPS")
-            .unwrap_or_else(|_| "1000".into())
-            .parse::<u32>()
-            .unwrap_or(1000);
-
-        let burst = std::env::var("RATE_LIMIT_BURST")
-            .unwrap_or_else(|_| "2000".into())
-            .parse::<u32>()
-            .unwrap_or(2000);
+        Self::from_config(&RateLimitSettings::from_env())
+    }
 
-        let quota = Quota::per_second(NonZeroU32::new(rps).expect("RPS should be non-zero"))
-            .allow_burst(NonZeroU32::new(burst).expect("Burst should be non-zero"));
+    /// Create a limiter from the `[rate_limit]` section of `AppConfig`.
+    pub fn from_config(settings: &RateLimitSettings) -> Self {
+        // Zero would disable the bucket entirely; treat it as the minimum instead.
+        let rps = NonZeroU32::new(settings.requests_per_second).unwrap_or(NonZeroU32::MIN);
+        let burst = NonZeroU32::new(settings.burst).unwrap_or(NonZeroU32::MIN);
 
+        let quota = Quota::per_second(rps).allow_burst(burst);
         let limiter = Arc::new(RateLimiter::keyed(quota));
 
         tracing::info!(
             "Rate Limiting: enabled={}, rps={}, burst={}",
-            enabled,
+            settings.enabled,
             rps,
             burst
         );
 
-        Self { limiter, enabled }
+        Self {
+            limiter,
+            enabled: settings.enabled,
+        }
     }
 }
 
@@ -100,19 +107,28 @@ impl ToolCategory {
         const WRITE_TOOLS: &[&str] = &[
             "send_message",
             "reply_message",
+            "forward_message",
             "file_reservation_paths",
             "reserve_file",
             "release_reservation",
+            "release_reservations",
             "force_release_reservation",
+            "transfer_reservation",
             "renew_file_reservation",
             "acquire_build_slot",
             "release_build_slot",
             "renew_build_slot",
             "register_agent",
             "update_agent_profile",
+            "update_agent",
             "create_agent_identity",
+            "manage_agent_group",
             "mark_message_read",
             "acknowledge_message",
+            "label_message",
+            "mute_thread",
+            "unmute_thread",
+            "snooze_message",
             "request_contact",
             "respond_contact",
             "set_contact_policy",
@@ -132,6 +148,7 @@ impl ToolCategory {
         const READ_TOOLS: &[&str] = &[
             "fetch_inbox",
             "check_inbox",
+            "wait_for_messages",
             "list_outbox",
             "get_message",
             "search_messages",
@@ -143,7 +160,10 @@ impl ToolCategory {
             "summarize_thread",
             "summarize_threads",
             "list_file_reservations",
+            "list_reservation_queue",
+            "list_my_reservations",
             "list_contacts",
+            "get_contact_policy",
             "list_macros",
             "list_projects",
             "get_project_info",
@@ -155,6 +175,7 @@ impl ToolCategory {
             "list_tool_metrics",
             "get_tool_stats",
             "list_activity",
+            "get_server_info",
         ];
 
         if WRITE_TOOLS.contains(&tool_name) {
@@ -288,98 +309,77 @@ impl ToolRateLimits {
     }
 }
 
-/// Extract JWT subject from Authorization header without full verification.
-///
-/// This only decodes the JWT payload to extract the `sub` claim for rate limiting.
-/// Authentication and signature verification should be done by the auth middleware.
-///
-/// # Arguments
-/// * `auth_header` - The Authorization header value (e.g., "Bearer eyJ...")
-///
-/// # Returns
-/// The `sub` claim if present and valid, or `None`
-fn extract_jwt_subject(auth_header: &str) -> Option<String> {
-    // Extract token from "Bearer <token>"
-    let token = auth_header.strip_prefix("Bearer ")?.trim();
-
-    // JWT has 3 parts: header.payload.signature
-    let parts: Vec<&str> = token.split('.').collect();
-    if parts.len() != 3 {
-        return None;
-    }
-
-    // Decode the payload (second part)
-    let payload = URL_SAFE_NO_PAD.decode(parts[1]).ok().or_else(|| {
-        // Try with padding
-        let padded = format!("{}{}", parts[1], "=".repeat((4 - parts[1].len() % 4) % 4));
-        base64::engine::general_purpose::URL_SAFE
-            .decode(&padded)
-            .ok()
-    })?;
-
-    // Parse as JSON and extract "sub"
-    let claims: serde_json::Value = serde_json::from_slice(&payload).ok()?;
-    claims.get("sub")?.as_str().map(|s| s.to_string())
-}
-
-/// Construct the rate limit bucket key.
+/// Construct the rate limit bucket key from the identity `auth_middleware`
+/// established.
 ///
 /// Key format:
-/// - `{jwt_subject}:{ip}` for authenticated requests
-/// - `{ip}` for unauthenticated requests
+/// - `agent:{agent_id}` for requests made with an agent key
+/// - `user:{jwt_subject}` for requests with a validated JWT
+/// - `{ip}` otherwise
+///
+/// Names a client could pick freely, such as an agent name in the body or
+/// an unverified token, never select the bucket.
 ///
 /// NIST Control: SC-5 (DoS Protection)
-pub fn get_bucket_key(req: &Request, client_ip: std::net::IpAddr) -> String {
-    // Try to extract JWT subject from Authorization header
-    if let Some(auth_header) = req.headers().get("authorization") {
-        if let Ok(auth_str) = auth_header.to_str() {
-            if let Some(subject) = extract_jwt_subject(auth_str) {
-                debug!(
-                    subject = %subject,
-                    ip = %client_ip,
-                    "Rate limit key includes JWT subject"
-                );
-                return format!("{}:{}", subject, client_ip);
-            }
-        }
+pub fn get_bucket_key(req: &Request, client_ip: IpAddr) -> String {
+    if let Some(agent_id) = req
+        .extensions()
+        .get::<Ctx>()
+        .and_then(Ctx::authenticated_agent)
+    {
+        return format!("agent:{}", agent_id);
+    }
+    if let Some(user) = req.extensions().get::<AuthenticatedUser>() {
+        debug!(subject = %user.subject, "Rate limit key uses JWT subject");
+        return format!("user:{}", user.subject);
     }
-
-    // Fallback to IP-only for unauthenticated requests
     client_ip.to_string()
 }
 
+/// 429 response with `Retry-After` and the structured `RATE_LIMITED` code.
+fn rate_limited_response(retry_after_secs: u64) -> Response {
+    let mut response = (
+        StatusCode::TOO_MANY_REQUESTS,
+        Json(ErrorResponse::new(
+            ErrorCode::RateLimited,
+            "Rate limit exceeded",
+        )),
+    )
+        .into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+    response
+}
+
+/// Rate limit middleware.
+///
+/// Runs inside `auth_middleware` so the bucket can follow the authenticated
+/// caller; see [`get_bucket_key`]. Unauthenticated callers share a bucket per
+/// client address, resolved with `trusted_proxies` like the auth layer does.
 pub async fn rate_limit_middleware(
     State(config): State<RateLimitConfig>,
-    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    State(auth_config): State<AuthConfig>,
     req: Request,
     next: Next,
-) -> Result<Response, StatusCode> {
-    if !config.enabled {
-        return Ok(next.run(req).await);
+) -> Response {
+    if !config.enabled || EXEMPT_PATHS.contains(&req.uri().path()) {
+        return next.run(req).await;
     }
 
-    // Determine Client IP
-    // Prefer X-Forwarded-For header if present (standard for reverse proxies)
-    // Fallback to direct peer address (ConnectInfo)
-    let ip = if let Some(forwarded) = req.headers().get("x-forwarded-for") {
-        forwarded
-            .to_str()
-            .ok()
-            .and_then(|s| s.split(',').next()) // Take the first IP in the list
-            .and_then(|s| s.trim().parse::<std::net::IpAddr>().ok())
-            .unwrap_or(peer.ip())
-    } else {
-        peer.ip()
-    };
-
-    // Get bucket key (includes JWT subject if present)
+    // The peer address is absent when the router is driven without a socket
+    // (e.g. tests)
+    let ip = client_ip(&req, &auth_config).unwrap_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED));
     let bucket_key = get_bucket_key(&req, ip);
 
     match config.limiter.check_key(&bucket_key) {
-        Ok(_) => Ok(next.run(req).await),
-        Err(_) => {
-            warn!(bucket_key = %bucket_key, "RateLimit: exceeded quota");
-            Err(StatusCode::TOO_MANY_REQUESTS)
+        Ok(_) => next.run(req).await,
+        Err(not_until) => {
+            let wait = not_until.wait_time_from(DefaultClock::default().now());
+            // Round up so clients never retry before a token is available.
+            let retry_after = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+            warn!(bucket_key = %bucket_key, retry_after, "RateLimit: exceeded quota");
+            rate_limited_response(retry_after.max(1))
         }
     }
 }
@@ -389,98 +389,42 @@ pub async fn rate_limit_middleware(
 mod tests {
     use super::*;
     use axum::http::Request as HttpRequest;
+    use mouchak_mail_core::types::AgentId;
     use std::net::IpAddr;
 
-    /// Create a test JWT token with given subject.
-    /// Format: header.payload.signature (signature is fake for testing)
-    fn create_test_jwt(subject: &str) -> String {
-        use base64::engine::general_purpose::URL_SAFE_NO_PAD;
-
-        // Minimal header
-        let header = r#"{"alg":"HS256","typ":"JWT"}"#;
-        let header_b64 = URL_SAFE_NO_PAD.encode(header);
-
-        // Payload with sub claim
-        let payload = format!(r#"{{"sub":"{}","iat":1234567890}}"#, subject);
-        let payload_b64 = URL_SAFE_NO_PAD.encode(payload);
-
-        // Fake signature (not verified in rate limiting)
-        let signature = "fake_signature_for_testing";
-
-        format!("{}.{}.{}", header_b64, payload_b64, signature)
-    }
-
-    #[test]
-    fn test_extract_jwt_subject_valid() {
-        let token = create_test_jwt("user123");
-        let auth_header = format!("Bearer {}", token);
-
-        let subject = extract_jwt_subject(&auth_header);
-        assert_eq!(subject, Some("user123".to_string()));
-    }
-
-    #[test]
-    fn test_extract_jwt_subject_with_email() {
-        let token = create_test_jwt("alice@example.com");
-        let auth_header = format!("Bearer {}", token);
-
-        let subject = extract_jwt_subject(&auth_header);
-        assert_eq!(subject, Some("alice@example.com".to_string()));
-    }
-
-    #[test]
-    fn test_extract_jwt_subject_missing_bearer() {
-        let token = create_test_jwt("user123");
-        // No "Bearer " prefix
-        let subject = extract_jwt_subject(&token);
-        assert_eq!(subject, None);
-    }
-
-    #[test]
-    fn test_extract_jwt_subject_invalid_token_format() {
-        // Only 2 parts instead of 3
-        let subject = extract_jwt_subject("Bearer header.payload");
-        assert_eq!(subject, None);
+    fn jwt_user(subject: &str) -> AuthenticatedUser {
+        AuthenticatedUser {
+            subject: subject.to_string(),
+            agent_name: None,
+            project_slug: None,
+            allowed_projects: vec!["*".to_string()],
+        }
     }
 
     #[test]
-    fn test_extract_jwt_subject_invalid_base64() {
-        let subject = extract_jwt_subject("Bearer !!invalid!!.!!base64!!.signature");
-        assert_eq!(subject, None);
-    }
+    fn test_get_bucket_key_with_agent_key() {
+        let ip: IpAddr = "192.168.1.100".parse().unwrap();
 
-    #[test]
-    fn test_extract_jwt_subject_no_sub_claim() {
-        use base64::engine::general_purpose::URL_SAFE_NO_PAD;
-
-        // JWT without sub claim
-        let header = r#"{"alg":"HS256"}"#;
-        let payload = r#"{"iat":1234567890}"#;
-        let token = format!(
-            "{}.{}.sig",
-            URL_SAFE_NO_PAD.encode(header),
-            URL_SAFE_NO_PAD.encode(payload)
-        );
+        let mut req: Request = HttpRequest::builder()
+            .body(axum::body::Body::empty())
+            .unwrap();
+        req.extensions_mut()
+            .insert(Ctx::for_agent(AgentId::new(7), "BlueLake", "backend"));
+        req.extensions_mut().insert(jwt_user("BlueLake"));
 
-        let subject = extract_jwt_subject(&format!("Bearer {}", token));
-        assert_eq!(subject, None);
+        assert_eq!(get_bucket_key(&req, ip), "agent:7");
     }
 
     #[test]
     fn test_get_bucket_key_with_jwt() {
-        let token = create_test_jwt("agent-001");
         let ip: IpAddr = "192.168.1.100".parse().unwrap();
 
-        let req = HttpRequest::builder()
-            .header("authorization", format!("Bearer {}", token))
-            .body(())
+        let mut req: Request = HttpRequest::builder()
+            .body(axum::body::Body::empty())
             .unwrap();
+        req.extensions_mut().insert(jwt_user("agent-001"));
 
-        // Convert to axum Request type
-        let axum_req: Request = req.map(|_| axum::body::Body::empty());
-
-        let key = get_bucket_key(&axum_req, ip);
-        assert_eq!(key, "agent-001:192.168.1.100");
+        assert_eq!(get_bucket_key(&req, ip), "user:agent-001");
     }
 
     #[test]
@@ -495,11 +439,13 @@ mod tests {
     }
 
     #[test]
-    fn test_get_bucket_key_with_invalid_jwt_falls_back_to_ip() {
+    fn test_get_bucket_key_ignores_unverified_credentials() {
         let ip: IpAddr = "172.16.0.1".parse().unwrap();
 
+        // Neither a raw token nor a claimed agent name picks the bucket
         let req = HttpRequest::builder()
-            .header("authorization", "Bearer invalid.token")
+            .header("authorization", "Bearer a.b.c")
+            .header("x-agent-name", "SomeoneElse")
             .body(())
             .unwrap();
         let axum_req: Request = req.map(|_| axum::body::Body::empty());
@@ -510,17 +456,13 @@ mod tests {
 
     #[test]
     fn test_get_bucket_key_ipv6() {
-        let token = create_test_jwt("ipv6-user");
         let ip: IpAddr = "2001:db8::1".parse().unwrap();
 
-        let req = HttpRequest::builder()
-            .header("authorization", format!("Bearer {}", token))
-            .body(())
-            .unwrap();
+        let req = HttpRequest::builder().body(()).unwrap();
         let axum_req: Request = req.map(|_| axum::body::Body::empty());
 
         let key = get_bucket_key(&axum_req, ip);
-        assert_eq!(key, "ipv6-user:2001:db8::1");
+        assert_eq!(key, "2001:db8::1");
     }
 
     #[test]
@@ -0,0 +1,132 @@
+//! Graceful shutdown
+//!
+//! On SIGINT/SIGTERM the server stops accepting connections, lets in-flight
+//! requests drain for a bounded time, then cancels background tasks in the
+//! order they were registered before the database is checkpointed.
+
+use axum::Router;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// A registered task and the name used when logging its shutdown.
+type NamedTask = (&'static str, JoinHandle<()>);
+
+/// Registry of background tasks to cancel on shutdown.
+///
+/// Tasks receive a [`CancellationToken`] and should return promptly once it
+/// fires. [`ShutdownHooks::shutdown`] cancels them and awaits each one in
+/// registration order.
+#[derive(Clone, Default)]
+pub struct ShutdownHooks {
+    token: CancellationToken,
+    tasks: Arc<Mutex<Vec<NamedTask>>>,
+}
+
+impl ShutdownHooks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Token cancelled when shutdown begins.
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// Spawns a named background task tied to the shutdown token.
+    pub fn spawn<F, Fut>(&self, name: &'static str, task: F)
+    where
+        F: FnOnce(CancellationToken) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handle = tokio::spawn(task(self.token.clone()));
+        self.tasks
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push((name, handle));
+    }
+
+    /// Cancels all tasks and waits up to `timeout` for each to finish.
+    ///
+    /// Tasks still running after their timeout are aborted.
+    pub async fn shutdown(&self, timeout: Duration) {
+        self.token.cancel();
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap_or_else(|e| e.into_inner()));
+        for (name, mut handle) in tasks {
+            match tokio::time::timeout(timeout, &mut handle).await {
+                Ok(_) => tracing::info!("Background task '{}' stopped", name),
+                Err(_) => {
+                    tracing::warn!("Background task '{}' did not stop in time; aborting", name);
+                    handle.abort();
+                }
+            }
+        }
+    }
+}
+
+/// Serves `app` until `signal` resolves, then drains in-flight requests.
+///
+/// New connections are refused as soon as the signal fires. Requests still
+/// running after `drain_timeout` are abandoned so a stuck client can't block
+/// shutdown forever.
+pub async fn serve_with_drain(
+    listener: TcpListener,
+    app: Router,
+    signal: impl Future<Output = ()> + Send + 'static,
+    drain_timeout: Duration,
+) -> std::io::Result<()> {
+    let drain = CancellationToken::new();
+    let server = axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(drain.clone().cancelled_owned());
+    let mut server = tokio::spawn(async move { server.await });
+
+    tokio::select! {
+        result = &mut server => return result.map_err(std::io::Error::other)?,
+        _ = signal => drain.cancel(),
+    }
+
+    tracing::info!("Draining in-flight requests (timeout {:?})", drain_timeout);
+    match tokio::time::timeout(drain_timeout, &mut server).await {
+        Ok(result) => result.map_err(std::io::Error::other)?,
+        Err(_) => {
+            tracing::warn!("Drain timeout elapsed; dropping remaining connections");
+            server.abort();
+            Ok(())
+        }
+    }
+}
+
+/// Resolves on Ctrl+C or SIGTERM.
+#[allow(clippy::expect_used)] // Signal handler setup is infallible in practice; panic is acceptable
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        tokio::signal::ctrl_c()
+            .await
+            .expect("failed to install Ctrl+C handler");
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("failed to install signal handler")
+            .recv()
+            .await;
+    };
+
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+
+    tracing::info!("Signal received, starting graceful shutdown");
+}
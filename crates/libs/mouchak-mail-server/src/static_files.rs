@@ -1,45 +1,141 @@
 //! Static file serving for embedded web UI assets.
 //!
-//! This module provides handlers for serving the embedded SvelteKit frontend
-//! with proper MIME type detection and SPA routing support.
+//! This module provides handlers for serving the embedded frontend with
+//! proper MIME type detection, SPA routing, cache headers and compression.
 
 use axum::{
+    Json, Router,
     body::Body,
     http::{StatusCode, Uri, header},
     response::{IntoResponse, Response},
 };
+use serde::Serialize;
+use tower_http::compression::CompressionLayer;
 
 use crate::embedded::Assets;
 
+/// Cache policy for content-hashed bundles (SvelteKit `_app/immutable/`,
+/// Trunk `name-<hash>.js` / `name-<hash>_bg.wasm`).
+const CACHE_IMMUTABLE: &str = "public, max-age=31536000, immutable";
+/// Cache policy for the SPA shell and version probes: always revalidate.
+const CACHE_NO_CACHE: &str = "no-cache";
+/// Cache policy for other unhashed assets (images, fonts, ...).
+const CACHE_DEFAULT: &str = "public, max-age=86400";
+
+/// Router serving the embedded UI, meant to be mounted as the app fallback.
+///
+/// Responses are gzip/brotli compressed when the client accepts it, which
+/// matters mostly for the multi-megabyte WASM bundle.
+pub fn ui_router() -> Router {
+    Router::new()
+        .fallback(serve_embedded_file)
+        .layer(CompressionLayer::new())
+}
+
 /// Serve embedded static files with SPA routing support.
 ///
-/// For paths without file extensions (SPA routes), falls back to index.html.
-/// Returns 404 for API paths that should be handled by the backend.
-/// Returns 404 for missing files with extensions.
+/// Existing assets are served as-is. Backend paths and missing files that
+/// look like assets (known extension) get a 404; anything else is a client
+/// side route and gets index.html, so deep links survive a reload.
 pub async fn serve_embedded_file(uri: Uri) -> impl IntoResponse {
     let path = uri.path().trim_start_matches('/');
 
-    // API paths should NOT be handled by the SPA - return 404 so the error is clear
-    // These paths should be handled by explicit routes in the backend
-    if path.starts_with("api/")
+    match resolve(path, |p| Assets::get(p).is_some()) {
+        Resolved::Asset => serve_file(path),
+        Resolved::Index => serve_file("index.html"),
+        Resolved::NotFound => not_found_response(),
+    }
+}
+
+/// Deployed UI build, returned by `GET /api/ui-version`.
+#[derive(Debug, Serialize)]
+pub struct UiVersion {
+    /// SHA-256 of the embedded index.html; it names every hashed bundle,
+    /// so it changes whenever the build does. `None` if no UI is embedded.
+    pub build_hash: Option<String>,
+    /// Server crate version
+    pub server_version: &'static str,
+}
+
+/// Report the embedded UI build hash so deploys can be verified.
+pub async fn ui_version() -> Json<UiVersion> {
+    let build_hash = Assets::get("index.html").map(|file| {
+        file.metadata
+            .sha256_hash()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    });
+    Json(UiVersion {
+        build_hash,
+        server_version: env!("CARGO_PKG_VERSION"),
+    })
+}
+
+/// What a request path maps to.
+#[derive(Debug, PartialEq, Eq)]
+enum Resolved {
+    /// An embedded file with this path exists
+    Asset,
+    /// Client-side route, serve index.html
+    Index,
+    NotFound,
+}
+
+fn resolve(path: &str, exists: impl Fn(&str) -> bool) -> Resolved {
+    // Backend paths should NOT be handled by the SPA - return 404 so the error is clear
+    if is_backend_path(path) {
+        return Resolved::NotFound;
+    }
+    if path.is_empty() {
+        return Resolved::Index;
+    }
+    if exists(path) {
+        return Resolved::Asset;
+    }
+    // A missing bundle must not come back as HTML, or the browser reports
+    // a confusing MIME error instead of a 404
+    if mime_guess::from_path(path).first().is_some() {
+        return Resolved::NotFound;
+    }
+    Resolved::Index
+}
+
+/// Paths owned by explicit backend routes.
+fn is_backend_path(path: &str) -> bool {
+    path == "api"
+        || path.starts_with("api/")
+        || path.starts_with("api-docs/")
         || path.starts_with("mcp/")
         || path == "health"
+        || path.starts_with("health/")
         || path == "healthz"
         || path == "ready"
         || path == "metrics"
         || path == "mcp"
-    {
-        return not_found_response();
-    }
+}
 
-    // SPA routing: paths without extensions serve index.html
-    let path = if path.is_empty() || !path.contains('.') {
-        "index.html"
+/// Cache-Control value for an embedded file.
+fn cache_control(path: &str) -> &'static str {
+    if path == "index.html" || path == "_app/version.json" {
+        CACHE_NO_CACHE
+    } else if is_hashed_asset(path) {
+        CACHE_IMMUTABLE
     } else {
-        path
-    };
+        CACHE_DEFAULT
+    }
+}
 
-    serve_file(path)
+/// Whether the file name carries a content hash, so it can be cached forever.
+fn is_hashed_asset(path: &str) -> bool {
+    if path.starts_with("_app/immutable/") {
+        return true;
+    }
+    let file = path.rsplit('/').next().unwrap_or(path);
+    let stem = file.split('.').next().unwrap_or(file);
+    let stem = stem.strip_suffix("_bg").unwrap_or(stem);
+    stem.rsplit_once('-')
+        .is_some_and(|(_, hash)| hash.len() >= 16 && hash.chars().all(|c| c.is_ascii_hexdigit()))
 }
 
 /// Serve a specific file from embedded assets.
@@ -50,29 +146,10 @@ fn serve_file(path: &str) -> Response {
                 .first_or_octet_stream()
                 .to_string();
 
-            // Use different cache strategies based on file type
-            // SvelteKit puts hashed assets in /_app/immutable/
-            let cache_control = if path.contains("/_app/immutable/") {
-                // SvelteKit immutable assets have content hashes, cache forever
-                "public, max-age=31536000, immutable"
-            } else if path.contains(".wasm") || path.contains(".js") {
-                // Other JS/WASM files with hashes - cache aggressively
-                "public, max-age=31536000, immutable"
-            } else if path == "index.html" {
-                // HTML should be revalidated
-                "public, max-age=0, must-revalidate"
-            } else if path == "_app/version.json" {
-                // Version file should be checked frequently
-                "public, max-age=0, must-revalidate"
-            } else {
-                // Other assets (CSS, images, static files) - cache for a day
-                "public, max-age=86400"
-            };
-
             Response::builder()
                 .status(StatusCode::OK)
                 .header(header::CONTENT_TYPE, mime)
-                .header(header::CACHE_CONTROL, cache_control)
+                .header(header::CACHE_CONTROL, cache_control(path))
                 .body(Body::from(content.data.into_owned()))
                 .unwrap_or_else(|_| internal_server_error())
         }
@@ -110,68 +187,89 @@ fn internal_server_error() -> Response {
 
 #[cfg(test)]
 mod tests {
-    fn is_api_path(path: &str) -> bool {
-        path.starts_with("api/")
-            || path.starts_with("mcp/")
-            || path == "health"
-            || path == "healthz"
-            || path == "ready"
-            || path == "metrics"
-            || path == "mcp"
-    }
+    #![allow(clippy::unwrap_used)]
 
-    #[test]
-    fn test_spa_routing_empty_path() {
-        let path = "";
-        let result = if path.is_empty() || !path.contains('.') {
-            "index.html"
-        } else {
-            path
-        };
-        assert_eq!(result, "index.html");
+    use super::*;
+    use tower::ServiceExt;
+
+    fn resolve_with(path: &str, files: &[&str]) -> Resolved {
+        resolve(path, |p| files.contains(&p))
     }
 
     #[test]
-    fn test_spa_routing_no_extension() {
-        let path = "mail/inbox";
-        let result = if path.is_empty() || !path.contains('.') {
-            "index.html"
-        } else {
-            path
-        };
-        assert_eq!(result, "index.html");
+    fn test_spa_routing() {
+        let files = ["index.html", "web-ui-leptos-3c2f7a1b9e0d4f56.js"];
+        assert_eq!(resolve_with("", &files), Resolved::Index);
+        assert_eq!(resolve_with("projects/whatever", &files), Resolved::Index);
+        // Dots in a route segment do not make it an asset
+        assert_eq!(resolve_with("thread/TKT-1.2", &files), Resolved::Index);
+        assert_eq!(
+            resolve_with("web-ui-leptos-3c2f7a1b9e0d4f56.js", &files),
+            Resolved::Asset
+        );
+        assert_eq!(resolve_with("missing.js", &files), Resolved::NotFound);
+        assert_eq!(resolve_with("missing_bg.wasm", &files), Resolved::NotFound);
     }
 
     #[test]
-    fn test_static_file_with_extension() {
-        let path = "assets/app.js";
-        let result = if path.is_empty() || !path.contains('.') {
-            "index.html"
-        } else {
-            path
-        };
-        assert_eq!(result, "assets/app.js");
+    fn test_backend_paths_excluded_from_spa() {
+        for path in [
+            "api",
+            "api/unknown",
+            "api/projects",
+            "api-docs/openapi.json",
+            "mcp/health",
+            "mcp",
+            "health",
+            "health/live",
+            "healthz",
+            "ready",
+            "metrics",
+        ] {
+            assert!(is_backend_path(path), "{}", path);
+            assert_eq!(resolve_with(path, &[path]), Resolved::NotFound);
+        }
+        for path in ["", "mail/inbox", "projects", "agents", "apiary"] {
+            assert!(!is_backend_path(path), "{}", path);
+        }
     }
 
     #[test]
-    fn test_api_paths_excluded_from_spa() {
-        assert!(is_api_path("api/health"));
-        assert!(is_api_path("api/projects"));
-        assert!(is_api_path("mcp/health"));
-        assert!(is_api_path("mcp/"));
-        assert!(is_api_path("health"));
-        assert!(is_api_path("healthz"));
-        assert!(is_api_path("ready"));
-        assert!(is_api_path("metrics"));
-        assert!(is_api_path("mcp"));
+    fn test_cache_control() {
+        assert_eq!(cache_control("index.html"), CACHE_NO_CACHE);
+        assert_eq!(cache_control("_app/version.json"), CACHE_NO_CACHE);
+        assert_eq!(cache_control("_app/immutable/entry.js"), CACHE_IMMUTABLE);
+        assert_eq!(
+            cache_control("web-ui-leptos-3c2f7a1b9e0d4f56_bg.wasm"),
+            CACHE_IMMUTABLE
+        );
+        assert_eq!(
+            cache_control("output-9f86d081884c7d65.css"),
+            CACHE_IMMUTABLE
+        );
+        // Unhashed bundles must not be pinned forever
+        assert_eq!(cache_control("app.js"), CACHE_DEFAULT);
+        assert_eq!(cache_control("favicon-dark.svg"), CACHE_DEFAULT);
     }
 
-    #[test]
-    fn test_spa_paths_not_api() {
-        assert!(!is_api_path(""));
-        assert!(!is_api_path("mail/inbox"));
-        assert!(!is_api_path("projects"));
-        assert!(!is_api_path("agents"));
-        assert!(!is_api_path("_app/immutable/file.js"));
+    #[tokio::test]
+    async fn test_ui_router_deep_link_and_api_404() {
+        let request = |uri: &str| {
+            axum::http::Request::builder()
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = ui_router()
+            .oneshot(request("/projects/whatever"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "text/html");
+        assert_eq!(response.headers()[header::CACHE_CONTROL], CACHE_NO_CACHE);
+
+        let response = ui_router().oneshot(request("/api/unknown")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
     }
 }
@@ -0,0 +1,77 @@
+//! HTTP request metrics middleware
+//!
+//! Records a Prometheus counter and latency histogram for every request,
+//! labelled by method, matched route and status. POST calls to the REST tool
+//! endpoints (`/api/...`) are also persisted to `tool_metrics`, mirroring
+//! what the MCP dispatcher records for tool invocations.
+
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use mouchak_mail_core::Ctx;
+use mouchak_mail_core::model::tool_metric::{ToolMetricBmc, ToolMetricForCreate};
+use std::time::Instant;
+
+use crate::AppState;
+
+/// Label used for requests that did not match any route (keeps cardinality bounded).
+const UNMATCHED_PATH: &str = "unmatched";
+
+pub async fn http_metrics_middleware(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let start = Instant::now();
+    let method = req.method().clone();
+    // Use the route template, not the raw URI, so IDs don't explode label cardinality.
+    let path = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_PATH.to_string());
+
+    let response = next.run(req).await;
+
+    let duration = start.elapsed();
+    let status = response.status();
+
+    metrics::counter!(
+        "http_requests_total",
+        "method" => method.to_string(),
+        "path" => path.clone(),
+        "status" => status.as_u16().to_string()
+    )
+    .increment(1);
+    metrics::histogram!(
+        "http_request_duration_seconds",
+        "method" => method.to_string(),
+        "path" => path.clone()
+    )
+    .record(duration.as_secs_f64());
+
+    if method == Method::POST && path.starts_with("/api/") {
+        let failed = status.is_client_error() || status.is_server_error();
+        let metric = ToolMetricForCreate {
+            project_id: None,
+            agent_id: None,
+            tool_name: path,
+            args_json: None,
+            status: if failed { "error" } else { "success" }.to_string(),
+            error_code: failed.then(|| format!("HTTP_{}", status.as_u16())),
+            duration_ms: duration.as_millis() as i64,
+        };
+        // Spawned so the DB write never adds latency to the response.
+        let mm = state.mm.clone();
+        tokio::spawn(async move {
+            if let Err(e) = ToolMetricBmc::create(&Ctx::root_ctx(), &mm, metric).await {
+                tracing::error!("Failed to record HTTP tool metric: {}", e);
+            }
+        });
+    }
+
+    response
+}
@@ -5,20 +5,37 @@ use axum::{
     response::{IntoResponse, Response},
 };
 use chrono::Utc;
+use mouchak_mail_common::permalink;
+use mouchak_mail_core::ctx::Actor;
 use mouchak_mail_core::model::file_reservation::{FileReservationBmc, FileReservationForCreate};
-use mouchak_mail_core::{self, Ctx};
+use mouchak_mail_core::model::message::{InboxCursor, InboxQuery, InboxSort, RecipientFailure};
+use mouchak_mail_core::model::reservation_queue::{
+    DEFAULT_QUEUE_WAIT_SECONDS, QueuedReservation, ReservationQueueBmc, ReservationQueueForCreate,
+    ReservationRequestOutcome,
+};
+use mouchak_mail_core::types::ProjectId;
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
+use utoipa::{IntoParams, ToSchema};
 
 use crate::AppState;
+use crate::auth::RequestCtx;
 
 // --- health_check ---
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct HealthCheckResponse {
     status: String,
     timestamp: String,
 }
 
+/// Liveness check for API clients
+#[utoipa::path(
+    get,
+    path = "/api/health",
+    responses(
+        (status = 200, description = "Server is up", body = HealthCheckResponse)
+    )
+)]
 pub async fn health_check(_state: State<AppState>) -> crate::error::Result<Response> {
     Ok(Json(HealthCheckResponse {
         status: "ok".to_string(),
@@ -28,7 +45,7 @@ pub async fn health_check(_state: State<AppState>) -> crate::error::Result<Respo
 }
 
 // --- readiness_check ---
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ReadinessResponse {
     status: &'static str,
     version: &'static str,
@@ -36,12 +53,12 @@ pub struct ReadinessResponse {
     checks: ReadinessChecks,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ReadinessChecks {
     database: DatabaseCheckResult,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct DatabaseCheckResult {
     ok: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -52,6 +69,14 @@ pub struct DatabaseCheckResult {
 
 /// Readiness probe - checks if the service can handle requests
 /// Returns 200 OK when ready, 503 Service Unavailable when not ready
+#[utoipa::path(
+    get,
+    path = "/api/ready",
+    responses(
+        (status = 200, description = "Ready to serve requests", body = ReadinessResponse),
+        (status = 503, description = "Database unavailable", body = ReadinessResponse)
+    )
+)]
 pub async fn readiness_check(State(app_state): State<AppState>) -> impl IntoResponse {
     let start = Instant::now();
 
@@ -93,24 +118,35 @@ pub async fn readiness_check(State(app_state): State<AppState>) -> impl IntoResp
 }
 
 // --- ensure_project ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct EnsureProjectPayload {
     /// Human-readable project name (e.g., "My Project")
     pub human_key: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct EnsureProjectResponse {
     pub id: i64,
     pub slug: String,
     pub human_key: String,
 }
 
+/// Create the project for a human key, or return the existing one
+#[utoipa::path(
+    post,
+    path = "/api/project/ensure",
+    request_body = EnsureProjectPayload,
+    responses(
+        (status = 200, description = "Project for the human key", body = EnsureProjectResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 409, description = "No free slug for the project")
+    )
+)]
 pub async fn ensure_project(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<EnsureProjectPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = match mouchak_mail_core::model::project::ProjectBmc::get_by_human_key(
@@ -129,19 +165,16 @@ pub async fn ensure_project(
                     mcp_config.project_identity_mode,
                     &mcp_config.project_identity_remote,
                 );
-                let _id = mouchak_mail_core::model::project::ProjectBmc::create(
-                    &ctx,
-                    mm,
-                    &slug,
-                    &payload.human_key,
-                )
-                .await?;
-                mouchak_mail_core::model::project::ProjectBmc::get_by_human_key(
-                    &ctx,
-                    mm,
-                    &payload.human_key,
-                )
-                .await?
+                // Another human_key may already have this slug; take the next free one
+                let (id, _slug) =
+                    mouchak_mail_core::model::project::ProjectBmc::create_with_unique_slug(
+                        &ctx,
+                        mm,
+                        &slug,
+                        &payload.human_key,
+                    )
+                    .await?;
+                mouchak_mail_core::model::project::ProjectBmc::get(&ctx, mm, id).await?
             } else {
                 return Err(e.into());
             }
@@ -165,18 +198,23 @@ pub async fn ensure_project(
 }
 
 // --- register_agent ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct RegisterAgentPayload {
     pub project_slug: String,
-    /// Agent name
+    /// Agent name: 2-64 ASCII letters, digits, '_' or '-', unique per
+    /// project ignoring case. Surrounding whitespace is trimmed.
+    #[schema(pattern = "^[A-Za-z0-9_-]{2,64}$", min_length = 2, max_length = 64)]
     pub name: String,
     pub program: String,
     pub model: String,
     #[serde(default)]
     pub task_description: Option<String>,
+    /// Return a conflict instead of updating an already registered name
+    #[serde(default)]
+    pub strict: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct RegisterAgentResponse {
     pub id: i64,
     pub name: String,
@@ -187,13 +225,27 @@ pub struct RegisterAgentResponse {
     pub task_description: String,
     pub inception_ts: chrono::NaiveDateTime,
     pub last_active_ts: chrono::NaiveDateTime,
-}
-
+    /// False when an existing agent was updated instead of created
+    pub created: bool,
+}
+
+/// Register an agent, or update it if the name is already registered
+#[utoipa::path(
+    post,
+    path = "/api/agent/register",
+    request_body = RegisterAgentPayload,
+    responses(
+        (status = 200, description = "Registered agent", body = RegisterAgentResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 404, description = "Project not found"),
+        (status = 409, description = "Name already registered (strict mode)")
+    )
+)]
 pub async fn register_agent(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<RegisterAgentPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -211,10 +263,12 @@ pub async fn register_agent(
         task_description: payload.task_description.clone().unwrap_or_default(),
     };
 
-    let agent_id = mouchak_mail_core::model::agent::AgentBmc::create(&ctx, mm, agent_c).await?;
+    let registration =
+        mouchak_mail_core::model::agent::AgentBmc::register(&ctx, mm, agent_c, payload.strict)
+            .await?;
 
     // Fetch the full agent to return
-    let agent = mouchak_mail_core::model::agent::AgentBmc::get(&ctx, mm, agent_id).await?;
+    let agent = mouchak_mail_core::model::agent::AgentBmc::get(&ctx, mm, registration.id).await?;
 
     Ok(Json(RegisterAgentResponse {
         id: agent.id.get(),
@@ -225,18 +279,21 @@ pub async fn register_agent(
         task_description: agent.task_description,
         inception_ts: agent.inception_ts,
         last_active_ts: agent.last_active_ts,
+        created: registration.created,
     })
     .into_response())
 }
 
 // --- send_message ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct SendMessagePayload {
     pub project_slug: String,
     // Support both naming conventions for compatibility
     #[serde(alias = "from_agent_name")]
     pub sender_name: String,
-    #[serde(alias = "to_agent_names")]
+    /// Recipient names; `slug::agent-name` addresses an agent in another
+    /// project, which requires the sender's project in `projects.allowed_peers`
+    #[serde(alias = "to_agent_names", default)]
     pub recipient_names: Vec<String>,
     /// CC recipients (optional)
     #[serde(default)]
@@ -246,32 +303,78 @@ pub struct SendMessagePayload {
     pub bcc_names: Option<Vec<String>>,
     pub subject: String,
     pub body_md: String,
+    /// Existing thread to continue; IDs are compared case-sensitively
     pub thread_id: Option<String>,
     pub importance: Option<String>,
     /// Whether recipients must acknowledge this message (default: false)
     #[serde(default)]
     pub ack_required: bool,
+    /// Hold the message until this UTC time (delivered immediately if omitted)
+    #[serde(default)]
+    pub deliver_at: Option<chrono::NaiveDateTime>,
+    /// Send to every active agent in the project; recipient lists must be empty
+    #[serde(default)]
+    pub broadcast: bool,
+    /// Deliver to the recipients that resolve even if others don't
+    /// (default: false, nothing is sent unless every recipient resolves)
+    #[serde(default)]
+    pub allow_partial: bool,
+    /// Start a new thread named `thread_id` if the project has none
+    /// (default: false, an unknown `thread_id` is rejected with 404)
+    #[serde(default)]
+    pub allow_new_thread: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct SendMessageResponse {
     pub id: i64,
+    /// Same as `id`
+    pub message_id: i64,
     pub project_id: i64,
     pub sender_id: i64,
     pub sender_name: String,
     pub thread_id: Option<String>,
+    /// Position in the thread, counting from 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_seq: Option<i64>,
     pub subject: String,
     pub body_md: String,
     pub importance: String,
     pub ack_required: bool,
     pub created_ts: chrono::NaiveDateTime,
-}
-
+    /// Set while the message is waiting for scheduled delivery
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deliver_at: Option<chrono::NaiveDateTime>,
+    /// True until the message's Git archive commit has been written
+    pub archive_pending: bool,
+    /// Recipient addresses the message was delivered to
+    pub delivered: Vec<String>,
+    /// Recipient addresses that did not resolve (only with `allow_partial`)
+    pub failed: Vec<RecipientFailure>,
+    /// True when the message went out but some recipients failed
+    pub partial_success: bool,
+    /// Members each `group:<name>` recipient expanded to
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<mouchak_mail_core::model::agent_group::GroupExpansion>,
+}
+
+/// Send a message, optionally scheduled or broadcast to the project
+#[utoipa::path(
+    post,
+    path = "/api/message/send",
+    request_body = SendMessagePayload,
+    responses(
+        (status = 200, description = "Stored message", body = SendMessageResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 403, description = "Contact policy or quota rejected the message, or a cross-project recipient is not allowed"),
+        (status = 404, description = "Project, agent or thread not found; `failed` lists every unresolved recipient")
+    )
+)]
 pub async fn send_message(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<SendMessagePayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -287,103 +390,168 @@ pub async fn send_message(
         &payload.sender_name,
     )
     .await?;
+    ctx.ensure_acting_as(sender.id, &sender.name)?;
+    // Archive commits are authored by the sender
+    let ctx = ctx.with_actor(Actor::agent(&sender.name, &project.slug));
 
-    // Resolve "to" recipients
-    let mut recipient_ids = Vec::new();
-    for name in payload.recipient_names {
-        let agent =
-            mouchak_mail_core::model::agent::AgentBmc::get_by_name(&ctx, mm, project.id, &name)
-                .await?;
-        recipient_ids.push(agent.id.get());
+    // Resolve every address before writing anything
+    let recipients = mouchak_mail_core::model::message::MessageBmc::resolve_recipients(
+        &ctx,
+        mm,
+        project.id,
+        &payload.recipient_names,
+        payload.cc_names.as_deref(),
+        payload.bcc_names.as_deref(),
+    )
+    .await?;
+    if !recipients.can_send(payload.allow_partial) {
+        let failed = recipients.failed;
+        return Err(match recipients.first_error {
+            Some(source) => crate::ServerError::Recipients { source, failed },
+            None => crate::ServerError::BadRequest("no recipients resolved".to_string()),
+        });
     }
 
-    // Resolve "cc" recipients
-    let cc_ids = if let Some(cc_names) = payload.cc_names {
-        let mut ids = Vec::new();
-        for name in cc_names {
-            let agent =
-                mouchak_mail_core::model::agent::AgentBmc::get_by_name(&ctx, mm, project.id, &name)
-                    .await?;
-            ids.push(agent.id.get());
-        }
-        Some(ids)
-    } else {
-        None
-    };
-
-    // Resolve "bcc" recipients
-    let bcc_ids = if let Some(bcc_names) = payload.bcc_names {
-        let mut ids = Vec::new();
-        for name in bcc_names {
-            let agent =
-                mouchak_mail_core::model::agent::AgentBmc::get_by_name(&ctx, mm, project.id, &name)
-                    .await?;
-            ids.push(agent.id.get());
-        }
-        Some(ids)
-    } else {
-        None
-    };
-
     let msg_c = mouchak_mail_core::model::message::MessageForCreate {
         project_id: project.id.get(),
         sender_id: sender.id.get(),
-        recipient_ids,
-        cc_ids,
-        bcc_ids,
+        recipient_ids: recipients.recipient_ids,
+        cc_ids: recipients.cc_ids,
+        bcc_ids: recipients.bcc_ids,
         subject: payload.subject,
         body_md: payload.body_md,
         thread_id: payload.thread_id,
         importance: payload.importance,
         ack_required: payload.ack_required,
+        deliver_at: payload.deliver_at,
+        broadcast: payload.broadcast,
+        allow_new_thread: payload.allow_new_thread,
     };
 
     let message_id = mouchak_mail_core::model::message::MessageBmc::create(&ctx, mm, msg_c).await?;
+    mouchak_mail_core::model::agent_group::AgentGroupBmc::record_expansions(
+        mm,
+        message_id,
+        &recipients.groups,
+    )
+    .await?;
+
+    // A broadcast names no one up front; report whom it reached
+    let delivered = if payload.broadcast {
+        mouchak_mail_core::model::message::MessageBmc::get_recipients(&ctx, mm, message_id).await?
+    } else {
+        recipients.delivered
+    };
+
+    // Scheduled messages are hidden from `get` until delivered; read them
+    // back from the sender's schedule instead.
+    let scheduled = if payload.deliver_at.is_some() {
+        mouchak_mail_core::model::message::MessageBmc::list_scheduled(
+            &ctx,
+            mm,
+            project.id.get(),
+            sender.id.get(),
+        )
+        .await?
+        .into_iter()
+        .find(|s| s.message.id == message_id)
+    } else {
+        None
+    };
 
     // Fetch the full message to return
-    let message = mouchak_mail_core::model::message::MessageBmc::get(&ctx, mm, message_id).await?;
+    let (message, deliver_at) = match scheduled {
+        Some(s) => (s.message, Some(s.deliver_at)),
+        None => (
+            mouchak_mail_core::model::message::MessageBmc::get(&ctx, mm, message_id).await?,
+            None,
+        ),
+    };
 
     Ok(Json(SendMessageResponse {
         id: message.id,
+        message_id: message.id,
         project_id: message.project_id,
         sender_id: message.sender_id,
         sender_name: message.sender_name,
         thread_id: message.thread_id,
+        thread_seq: message.thread_seq,
         subject: message.subject,
         body_md: message.body_md,
         importance: message.importance,
         ack_required: message.ack_required,
         created_ts: message.created_ts,
+        archive_pending: deliver_at.is_some() || !mm.archive_queue.is_sync(),
+        deliver_at,
+        delivered,
+        partial_success: !recipients.failed.is_empty(),
+        failed: recipients.failed,
+        groups: recipients.groups,
     })
     .into_response())
 }
 
 // --- list_inbox ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct ListInboxPayload {
     pub project_slug: String,
     pub agent_name: String,
     #[serde(default = "default_limit")]
     pub limit: i64,
+    /// Only messages carrying this label (case-insensitive)
+    #[serde(default)]
+    pub label: Option<String>,
+    /// `created_desc` (default), `created_asc`, `importance_desc` or
+    /// `ack_pending_first`
+    #[serde(default)]
+    pub sort: Option<String>,
+    /// Resume after a message: `<sort>:<message_id>` for the last message of
+    /// the previous page
+    #[serde(default)]
+    pub cursor: Option<String>,
 }
 
 fn default_limit() -> i64 {
     20
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct InboxMessage {
     pub id: i64,
     pub subject: String,
     pub sender_name: String,
+    pub ack_required: bool,
     pub created_ts: chrono::NaiveDateTime,
-}
-
+    /// Sender's project, set on messages from another project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_slug: Option<String>,
+    /// Position in the thread, counting from 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_seq: Option<i64>,
+    /// Set once a snooze on this message has run out
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub returned_from_snooze: bool,
+    /// Set while the agent still owes an acknowledgement
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub needs_ack: bool,
+}
+
+/// List an agent's inbox, newest first unless `sort` says otherwise
+#[utoipa::path(
+    post,
+    path = "/api/inbox",
+    request_body = ListInboxPayload,
+    responses(
+        (status = 200, description = "Inbox messages", body = Vec<InboxMessage>),
+        (status = 400, description = "Unknown sort, or a cursor from another sort"),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
 pub async fn list_inbox(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<ListInboxPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -400,12 +568,18 @@ pub async fn list_inbox(
     )
     .await?;
 
-    let messages = mouchak_mail_core::model::message::MessageBmc::list_inbox_for_agent(
+    let query = InboxQuery {
+        label: payload.label,
+        sort: parse_inbox_sort(payload.sort.as_deref())?,
+        cursor: parse_inbox_cursor(payload.cursor.as_deref())?,
+        limit: payload.limit,
+    };
+    let messages = mouchak_mail_core::model::message::MessageBmc::list_inbox_for_agent_sorted(
         &ctx,
         mm,
         project.id.get(),
         agent.id.get(),
-        payload.limit,
+        &query,
     )
     .await?;
 
@@ -415,27 +589,61 @@ pub async fn list_inbox(
             id: msg.id,
             subject: msg.subject,
             sender_name: msg.sender_name,
+            ack_required: msg.ack_required,
             created_ts: msg.created_ts,
+            project_slug: msg.project_slug,
+            thread_seq: msg.thread_seq,
+            returned_from_snooze: msg.returned_from_snooze,
+            needs_ack: msg.needs_ack,
         })
         .collect();
 
     Ok(Json(inbox_msgs).into_response())
 }
 
+/// Parse an optional `sort` parameter; empty means the default order.
+pub(crate) fn parse_inbox_sort(value: Option<&str>) -> crate::error::Result<InboxSort> {
+    Ok(value
+        .filter(|v| !v.is_empty())
+        .map(str::parse)
+        .transpose()?
+        .unwrap_or_default())
+}
+
+/// Parse an optional inbox `cursor` parameter; empty means the first page.
+pub(crate) fn parse_inbox_cursor(value: Option<&str>) -> crate::error::Result<Option<InboxCursor>> {
+    Ok(value
+        .filter(|v| !v.is_empty())
+        .map(str::parse)
+        .transpose()?)
+}
+
 // --- list_outbox ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct ListOutboxPayload {
     pub project_slug: String,
     pub agent_name: String,
     #[serde(default = "default_limit")]
     pub limit: i64,
-}
-
+    /// Resume after this message ID (from the previous page)
+    pub cursor: Option<i64>,
+}
+
+/// List messages sent by an agent, newest first
+#[utoipa::path(
+    post,
+    path = "/api/outbox",
+    request_body = ListOutboxPayload,
+    responses(
+        (status = 200, description = "Sent messages", body = Vec<InboxMessage>),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
 pub async fn list_outbox(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<ListOutboxPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -458,16 +666,23 @@ pub async fn list_outbox(
         project.id.get(),
         agent.id.get(),
         payload.limit,
+        payload.cursor,
     )
     .await?;
 
     let outbox_msgs: Vec<InboxMessage> = messages
         .into_iter()
+        .map(|outbox| outbox.message)
         .map(|msg| InboxMessage {
             id: msg.id,
             subject: msg.subject,
             sender_name: msg.sender_name,
+            ack_required: msg.ack_required,
             created_ts: msg.created_ts,
+            project_slug: msg.project_slug,
+            thread_seq: msg.thread_seq,
+            returned_from_snooze: msg.returned_from_snooze,
+            needs_ack: msg.needs_ack,
         })
         .collect();
 
@@ -475,7 +690,7 @@ pub async fn list_outbox(
 }
 
 // --- list_all_projects ---
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ProjectResponse {
     pub id: i64,
     pub slug: String,
@@ -483,10 +698,18 @@ pub struct ProjectResponse {
     pub created_at: chrono::NaiveDateTime,
 }
 
+/// List all projects
+#[utoipa::path(
+    get,
+    path = "/api/projects",
+    responses(
+        (status = 200, description = "Projects", body = Vec<ProjectResponse>)
+    )
+)]
 pub async fn list_all_projects(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let projects = mouchak_mail_core::model::project::ProjectBmc::list_all(&ctx, mm).await?;
@@ -505,17 +728,27 @@ pub async fn list_all_projects(
 }
 
 // --- delete_project ---
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct DeleteResponse {
     pub success: bool,
     pub message: String,
 }
 
+/// Delete a project with its agents and messages
+#[utoipa::path(
+    delete,
+    path = "/api/projects/{project_slug}",
+    params(("project_slug" = String, Path, description = "Project slug")),
+    responses(
+        (status = 200, description = "Project deleted", body = DeleteResponse),
+        (status = 404, description = "Project not found")
+    )
+)]
 pub async fn delete_project(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Path(project_slug): Path<String>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project =
@@ -532,11 +765,24 @@ pub async fn delete_project(
 }
 
 // --- delete_agent ---
+/// Delete an agent
+#[utoipa::path(
+    delete,
+    path = "/api/projects/{project_slug}/agents/{agent_name}",
+    params(
+        ("project_slug" = String, Path, description = "Project slug"),
+        ("agent_name" = String, Path, description = "Agent name")
+    ),
+    responses(
+        (status = 200, description = "Agent deleted", body = DeleteResponse),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
 pub async fn delete_agent(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Path((project_slug, agent_name)): Path<(String, String)>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project =
@@ -555,6 +801,97 @@ pub async fn delete_agent(
     .into_response())
 }
 
+// --- update_agent ---
+#[derive(Deserialize, ToSchema)]
+pub struct UpdateAgentPayload {
+    /// New name, following the same rules as registration
+    #[serde(default)]
+    #[schema(pattern = "^[A-Za-z0-9_-]{2,64}$", min_length = 2, max_length = 64)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub task_description: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub program: Option<String>,
+}
+
+/// Rename an agent or change its program, model or task
+#[utoipa::path(
+    patch,
+    path = "/api/project/{project_slug}/agent/{agent_name}",
+    params(
+        ("project_slug" = String, Path, description = "Project slug"),
+        ("agent_name" = String, Path, description = "Agent name")
+    ),
+    request_body = UpdateAgentPayload,
+    responses(
+        (status = 200, description = "Updated agent", body = AgentResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 404, description = "Project or agent not found"),
+        (status = 409, description = "New name already taken")
+    )
+)]
+pub async fn update_agent(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((project_slug, agent_name)): Path<(String, String)>,
+    Json(payload): Json<UpdateAgentPayload>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project =
+        mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(&ctx, mm, &project_slug)
+            .await?;
+    let agent =
+        mouchak_mail_core::model::agent::AgentBmc::get_by_name(&ctx, mm, project.id, &agent_name)
+            .await?;
+
+    let update = mouchak_mail_core::model::agent::AgentForUpdate {
+        name: payload.name,
+        task_description: payload.task_description,
+        model: payload.model,
+        program: payload.program,
+    };
+    let agent =
+        mouchak_mail_core::model::agent::AgentBmc::update(&ctx, mm, agent.id, update).await?;
+
+    Ok(Json(AgentResponse::from(agent)).into_response())
+}
+
+// --- retire_agent ---
+/// Retire an agent; its message history is kept
+#[utoipa::path(
+    delete,
+    path = "/api/project/{project_slug}/agent/{agent_name}",
+    params(
+        ("project_slug" = String, Path, description = "Project slug"),
+        ("agent_name" = String, Path, description = "Agent name")
+    ),
+    responses(
+        (status = 200, description = "Retired agent", body = AgentResponse),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
+pub async fn retire_agent(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Path((project_slug, agent_name)): Path<(String, String)>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project =
+        mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(&ctx, mm, &project_slug)
+            .await?;
+    let agent =
+        mouchak_mail_core::model::agent::AgentBmc::get_by_name(&ctx, mm, project.id, &agent_name)
+            .await?;
+
+    let agent = mouchak_mail_core::model::agent::AgentBmc::retire(&ctx, mm, agent.id).await?;
+
+    Ok(Json(AgentResponse::from(agent)).into_response())
+}
+
 // --- list_all_agents_for_project ---
 // Keep for backwards compatibility with JSON body requests
 #[derive(Deserialize)]
@@ -563,7 +900,7 @@ pub struct ListAgentsPayload {
     pub project_slug: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct AgentResponse {
     pub id: i64,
     pub name: String,
@@ -572,13 +909,44 @@ pub struct AgentResponse {
     pub task_description: String,
     pub inception_ts: chrono::NaiveDateTime,
     pub last_active_ts: chrono::NaiveDateTime,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retired_ts: Option<chrono::NaiveDateTime>,
+    /// Do-not-disturb policy, present only while DND is on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dnd: Option<mouchak_mail_core::model::agent::DndPolicy>,
 }
 
+impl From<mouchak_mail_core::model::agent::Agent> for AgentResponse {
+    fn from(a: mouchak_mail_core::model::agent::Agent) -> Self {
+        Self {
+            id: a.id.get(),
+            name: a.name,
+            program: a.program,
+            model: a.model,
+            task_description: a.task_description,
+            inception_ts: a.inception_ts,
+            last_active_ts: a.last_active_ts,
+            retired_ts: a.retired_ts,
+            dnd: None,
+        }
+    }
+}
+
+/// List the agents of a project
+#[utoipa::path(
+    get,
+    path = "/api/projects/{project_slug}/agents",
+    params(("project_slug" = String, Path, description = "Project slug")),
+    responses(
+        (status = 200, description = "Agents", body = Vec<AgentResponse>),
+        (status = 404, description = "Project not found")
+    )
+)]
 pub async fn list_all_agents_for_project(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Path(project_slug): Path<String>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project =
@@ -588,16 +956,18 @@ pub async fn list_all_agents_for_project(
         mouchak_mail_core::model::agent::AgentBmc::list_all_for_project(&ctx, mm, project.id)
             .await?;
 
+    let mut dnd =
+        mouchak_mail_core::model::agent::AgentBmc::list_active_dnd_policies(&ctx, mm, project.id)
+            .await?;
+
     let agent_responses: Vec<AgentResponse> = agents
         .into_iter()
-        .map(|a| AgentResponse {
-            id: a.id.get(),
-            name: a.name,
-            program: a.program,
-            model: a.model,
-            task_description: a.task_description,
-            inception_ts: a.inception_ts,
-            last_active_ts: a.last_active_ts,
+        .map(|a| {
+            let policy = dnd.remove(&a.id);
+            AgentResponse {
+                dnd: policy,
+                ..AgentResponse::from(a)
+            }
         })
         .collect();
 
@@ -612,27 +982,63 @@ pub struct GetMessagePayload {
     pub message_id: i64,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct MessageResponse {
     pub id: i64,
     pub project_id: i64,
     pub sender_id: i64,
     pub sender_name: String,
     pub thread_id: Option<String>,
+    /// Position in the thread, counting from 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_seq: Option<i64>,
     pub subject: String,
     pub body_md: String,
     pub importance: String,
     pub ack_required: bool,
     pub created_ts: chrono::NaiveDateTime,
+    #[schema(value_type = Vec<Object>)]
     pub attachments: Vec<serde_json::Value>,
     pub recipients: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recalled_ts: Option<chrono::NaiveDateTime>,
+    /// Sender's project, set on thread messages from another project
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub project_slug: Option<String>,
+    /// Message this one forwards, if it is a forward
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub forwarded_from_id: Option<i64>,
+    /// Web UI link to the message; absolute when `server.public_url` is set
+    pub permalink: String,
+}
+
+/// Permalink of a message, absolute under `server.public_url` when set.
+pub(crate) fn message_permalink(
+    mm: &mouchak_mail_core::model::ModelManager,
+    project_slug: &str,
+    message_id: i64,
+) -> String {
+    permalink::resolve(
+        mm.app_config.server.public_url.as_deref(),
+        &permalink::message_path(project_slug, message_id),
+    )
 }
 
+/// Get a message with its recipients
+#[utoipa::path(
+    get,
+    path = "/api/messages/{message_id}",
+    params(("message_id" = i64, Path, description = "Message ID")),
+    responses(
+        (status = 200, description = "Message", body = MessageResponse),
+        (status = 404, description = "Message not found")
+    )
+)]
 pub async fn get_message(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Path(message_id): Path<i64>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let message = mouchak_mail_core::model::message::MessageBmc::get(&ctx, mm, message_id).await?;
@@ -640,13 +1046,23 @@ pub async fn get_message(
         mouchak_mail_core::model::message::MessageBmc::get_recipients(&ctx, mm, message_id)
             .await
             .unwrap_or_default();
+    let recall =
+        mouchak_mail_core::model::message::MessageBmc::get_recall(&ctx, mm, message_id).await?;
+    let project = mouchak_mail_core::model::project::ProjectBmc::get(
+        &ctx,
+        mm,
+        ProjectId::new(message.project_id),
+    )
+    .await?;
 
     Ok(Json(MessageResponse {
+        permalink: message_permalink(mm, &project.slug, message.id),
         id: message.id,
         project_id: message.project_id,
         sender_id: message.sender_id,
         sender_name: message.sender_name,
         thread_id: message.thread_id,
+        thread_seq: message.thread_seq,
         subject: message.subject,
         body_md: message.body_md,
         importance: message.importance,
@@ -654,12 +1070,15 @@ pub async fn get_message(
         created_ts: message.created_ts,
         attachments: message.attachments,
         recipients,
+        recalled_ts: recall.map(|r| r.recalled_ts),
+        project_slug: message.project_slug,
+        forwarded_from_id: message.forwarded_from_id,
     })
     .into_response())
 }
 
 // --- file_reservation_paths ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct FileReservationPathsPayload {
     pub project_slug: String,
     pub agent_name: String,
@@ -668,13 +1087,18 @@ pub struct FileReservationPathsPayload {
     pub exclusive: bool,
     pub reason: Option<String>,
     pub ttl_seconds: Option<i64>,
+    /// Queue paths held by other agents instead of granting them anyway
+    #[serde(default)]
+    pub wait: bool,
+    #[serde(default)]
+    pub wait_timeout_seconds: Option<i64>,
 }
 
 fn default_exclusive() -> bool {
     true
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct FileReservationGranted {
     pub id: i64,
     pub path_pattern: String,
@@ -683,7 +1107,7 @@ pub struct FileReservationGranted {
     pub expires_ts: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct FileReservationConflict {
     pub path_pattern: String,
     pub exclusive: bool,
@@ -692,10 +1116,13 @@ pub struct FileReservationConflict {
     pub message: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct FileReservationPathsResponse {
     pub granted: Vec<FileReservationGranted>,
     pub conflicts: Vec<FileReservationConflict>,
+    /// Paths waiting in the reservation queue (only with `wait`)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub queued: Vec<QueuedReservation>,
 }
 
 fn find_path_conflicts(
@@ -724,11 +1151,22 @@ fn find_path_conflicts(
         .collect()
 }
 
+/// Reserve file paths for an agent, optionally queueing contended ones
+#[utoipa::path(
+    post,
+    path = "/api/file_reservations/paths",
+    request_body = FileReservationPathsPayload,
+    responses(
+        (status = 200, description = "Granted, conflicting and queued paths", body = FileReservationPathsResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
 pub async fn file_reservation_paths(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<FileReservationPathsPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -744,6 +1182,7 @@ pub async fn file_reservation_paths(
         &payload.agent_name,
     )
     .await?;
+    ctx.ensure_acting_as(agent.id, &agent.name)?;
 
     let active_reservations =
         FileReservationBmc::list_active_for_project(&ctx, mm, project.id).await?;
@@ -754,6 +1193,47 @@ pub async fn file_reservation_paths(
 
     let mut granted = Vec::new();
     let mut conflicts = Vec::new();
+    let mut queued = Vec::new();
+
+    if payload.wait {
+        for path in payload.paths {
+            let outcome = ReservationQueueBmc::reserve_or_enqueue(
+                &ctx,
+                mm,
+                ReservationQueueForCreate {
+                    project_id: project.id,
+                    agent_id: agent.id,
+                    path_pattern: path.clone(),
+                    exclusive: payload.exclusive,
+                    reason: payload.reason.clone().unwrap_or_default(),
+                    ttl_seconds: ttl,
+                    wait_timeout_seconds: payload
+                        .wait_timeout_seconds
+                        .unwrap_or(DEFAULT_QUEUE_WAIT_SECONDS),
+                },
+            )
+            .await?;
+            match outcome {
+                ReservationRequestOutcome::Granted { reservation_id } => {
+                    granted.push(FileReservationGranted {
+                        id: reservation_id,
+                        path_pattern: path,
+                        exclusive: payload.exclusive,
+                        reason: payload.reason.clone().unwrap_or_default(),
+                        expires_ts: expires_ts.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                    });
+                }
+                ReservationRequestOutcome::Queued(entry) => queued.push(entry),
+            }
+        }
+
+        return Ok(Json(FileReservationPathsResponse {
+            granted,
+            conflicts,
+            queued,
+        })
+        .into_response());
+    }
 
     for path in payload.paths {
         conflicts.extend(find_path_conflicts(
@@ -783,7 +1263,54 @@ pub async fn file_reservation_paths(
         });
     }
 
-    Ok(Json(FileReservationPathsResponse { granted, conflicts }).into_response())
+    Ok(Json(FileReservationPathsResponse {
+        granted,
+        conflicts,
+        queued,
+    })
+    .into_response())
+}
+
+// --- list_reservation_queue ---
+#[derive(Deserialize, ToSchema)]
+pub struct ListReservationQueuePayload {
+    pub project_slug: String,
+    /// Only requests overlapping this path pattern
+    #[serde(default)]
+    pub path_pattern: Option<String>,
+}
+
+/// List reservation requests waiting for their paths
+#[utoipa::path(
+    post,
+    path = "/api/file_reservations/queue",
+    request_body = ListReservationQueuePayload,
+    responses(
+        (status = 200, description = "Queued requests in grant order", body = Vec<QueuedReservation>),
+        (status = 404, description = "Project not found")
+    )
+)]
+pub async fn list_reservation_queue(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Json(payload): Json<ListReservationQueuePayload>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
+        &ctx,
+        mm,
+        &payload.project_slug,
+    )
+    .await?;
+    let mut queue = ReservationQueueBmc::list_for_project(&ctx, mm, project.id).await?;
+    if let Some(filter) = &payload.path_pattern {
+        queue.retain(|q| {
+            mouchak_mail_core::utils::pathspec::paths_conflict(&q.path_pattern, filter)
+        });
+    }
+
+    Ok(Json(queue).into_response())
 }
 
 // --- create_agent_identity ---
@@ -851,24 +1378,34 @@ fn find_hint_match(
     None
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CreateAgentIdentityPayload {
     pub project_slug: String,
     #[serde(default)]
     pub hint: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct CreateAgentIdentityResponse {
     pub suggested_name: String,
     pub alternatives: Vec<String>,
 }
 
+/// Suggest an unused agent name
+#[utoipa::path(
+    post,
+    path = "/api/agent/create_identity",
+    request_body = CreateAgentIdentityPayload,
+    responses(
+        (status = 200, description = "Suggested names", body = CreateAgentIdentityResponse),
+        (status = 404, description = "Project not found")
+    )
+)]
 pub async fn create_agent_identity(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<CreateAgentIdentityPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -905,13 +1442,13 @@ pub async fn create_agent_identity(
 }
 
 // --- whois ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct WhoisPayload {
     pub project_slug: String,
     pub agent_name: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct WhoisResponse {
     pub id: i64,
     pub name: String,
@@ -926,11 +1463,21 @@ pub struct WhoisResponse {
     pub project_human_key: String,
 }
 
+/// Look up an agent and its policies
+#[utoipa::path(
+    post,
+    path = "/api/agent/whois",
+    request_body = WhoisPayload,
+    responses(
+        (status = 200, description = "Agent details", body = WhoisResponse),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
 pub async fn whois(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<WhoisPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -964,7 +1511,7 @@ pub async fn whois(
 }
 
 // --- list_file_reservations ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct ListFileReservationsPayload {
     pub project_slug: String,
     #[serde(default)]
@@ -973,7 +1520,7 @@ pub struct ListFileReservationsPayload {
     pub active_only: Option<bool>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct FileReservationResponse {
     pub id: i64,
     pub agent_id: i64,
@@ -986,11 +1533,21 @@ pub struct FileReservationResponse {
     pub is_active: bool,
 }
 
+/// List file reservations in a project
+#[utoipa::path(
+    post,
+    path = "/api/file_reservations/list",
+    request_body = ListFileReservationsPayload,
+    responses(
+        (status = 200, description = "File reservations", body = Vec<FileReservationResponse>),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
 pub async fn list_file_reservations(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<ListFileReservationsPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -1040,7 +1597,7 @@ pub async fn list_file_reservations(
 
 // --- list_all_locks ---
 // Returns all active file reservations across all projects (for web UI dashboard)
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct LockResponse {
     pub id: i64,
     pub project_id: i64,
@@ -1055,8 +1612,18 @@ pub struct LockResponse {
     pub is_expired: bool,
 }
 
-pub async fn list_all_locks(State(app_state): State<AppState>) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
+/// List file reservations across all projects
+#[utoipa::path(
+    get,
+    path = "/api/locks",
+    responses(
+        (status = 200, description = "File reservations", body = Vec<LockResponse>)
+    )
+)]
+pub async fn list_all_locks(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+) -> crate::error::Result<Response> {
     let mm = &app_state.mm;
 
     let reservations = FileReservationBmc::list_all_active(&ctx, mm).await?;
@@ -1099,24 +1666,34 @@ pub async fn list_all_locks(State(app_state): State<AppState>) -> crate::error::
 }
 
 // --- release_file_reservation ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct ReleaseFileReservationPayload {
     pub project_slug: String,
     pub agent_name: String,
     pub paths: Vec<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ReleaseFileReservationResponse {
     pub released_count: usize,
     pub released_ids: Vec<i64>,
 }
 
+/// Release an agent's reservations on the given paths
+#[utoipa::path(
+    post,
+    path = "/api/file_reservations/release",
+    request_body = ReleaseFileReservationPayload,
+    responses(
+        (status = 200, description = "Released reservations", body = ReleaseFileReservationResponse),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
 pub async fn release_file_reservation(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<ReleaseFileReservationPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -1132,6 +1709,7 @@ pub async fn release_file_reservation(
         &payload.agent_name,
     )
     .await?;
+    ctx.ensure_acting_as(agent.id, &agent.name)?;
 
     let mut released_ids = Vec::new();
 
@@ -1152,17 +1730,27 @@ pub async fn release_file_reservation(
 }
 
 // --- get_thread ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct GetThreadPayload {
     pub project_slug: String,
     pub thread_id: String,
 }
 
+/// Get all messages in a thread, oldest first
+#[utoipa::path(
+    post,
+    path = "/api/thread",
+    request_body = GetThreadPayload,
+    responses(
+        (status = 200, description = "Thread messages", body = Vec<MessageResponse>),
+        (status = 404, description = "Project not found")
+    )
+)]
 pub async fn get_thread(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<GetThreadPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -1183,12 +1771,15 @@ pub async fn get_thread(
     for msg in messages {
         let recipients =
             mouchak_mail_core::model::message::MessageBmc::get_recipients(&ctx, mm, msg.id).await?;
+        let slug = msg.project_slug.as_deref().unwrap_or(&project.slug);
         responses.push(MessageResponse {
+            permalink: message_permalink(mm, slug, msg.id),
             id: msg.id,
             project_id: msg.project_id,
             sender_id: msg.sender_id,
             sender_name: msg.sender_name,
             thread_id: msg.thread_id,
+            thread_seq: msg.thread_seq,
             subject: msg.subject,
             body_md: msg.body_md,
             importance: msg.importance,
@@ -1196,6 +1787,9 @@ pub async fn get_thread(
             created_ts: msg.created_ts,
             attachments: msg.attachments,
             recipients,
+            recalled_ts: None,
+            project_slug: msg.project_slug,
+            forwarded_from_id: msg.forwarded_from_id,
         });
     }
 
@@ -1203,7 +1797,7 @@ pub async fn get_thread(
 }
 
 // --- reply_message ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct ReplyMessagePayload {
     pub project_slug: String,
     pub sender_name: String,
@@ -1212,11 +1806,22 @@ pub struct ReplyMessagePayload {
     pub importance: Option<String>,
 }
 
+/// Reply to a message in its thread
+#[utoipa::path(
+    post,
+    path = "/api/message/reply",
+    request_body = ReplyMessagePayload,
+    responses(
+        (status = 200, description = "Stored reply", body = SendMessageResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 404, description = "Project, agent or message not found")
+    )
+)]
 pub async fn reply_message(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<ReplyMessagePayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -1232,6 +1837,9 @@ pub async fn reply_message(
         &payload.sender_name,
     )
     .await?;
+    ctx.ensure_acting_as(sender.id, &sender.name)?;
+    // Archive commits are authored by the sender
+    let ctx = ctx.with_actor(Actor::agent(&sender.name, &project.slug));
 
     // Get original message to extract thread_id and original sender as recipient
     let original_msg =
@@ -1240,8 +1848,20 @@ pub async fn reply_message(
     // Reply goes to the original sender
     let recipient_ids = vec![original_msg.sender_id];
 
-    // Use existing thread_id or create reference to original
-    let thread_id = original_msg.thread_id.clone();
+    // Continue the original's thread; a message from before threads were
+    // always assigned continues the thread its subject started
+    let thread_id = match original_msg.thread_id.clone() {
+        Some(thread_id) => thread_id,
+        None => {
+            mouchak_mail_core::model::message::MessageBmc::ensure_thread(
+                &ctx,
+                mm,
+                project.id.get(),
+                &original_msg.subject,
+            )
+            .await?
+        }
+    };
 
     // Create subject with Re: prefix if not already present
     let subject = if original_msg.subject.starts_with("Re: ") {
@@ -1258,9 +1878,13 @@ pub async fn reply_message(
         bcc_ids: None,
         subject,
         body_md: payload.body_md,
-        thread_id,
+        thread_id: Some(thread_id),
         importance: payload.importance,
         ack_required: false, // Replies don't require ack by default
+        deliver_at: None,
+        broadcast: false,
+        // The thread may live in the original sender's project
+        allow_new_thread: true,
     };
 
     let message_id = mouchak_mail_core::model::message::MessageBmc::create(&ctx, mm, msg_c).await?;
@@ -1270,55 +1894,97 @@ pub async fn reply_message(
 
     Ok(Json(SendMessageResponse {
         id: message.id,
+        message_id: message.id,
         project_id: message.project_id,
         sender_id: message.sender_id,
         sender_name: message.sender_name,
         thread_id: message.thread_id,
+        thread_seq: message.thread_seq,
         subject: message.subject,
         body_md: message.body_md,
         importance: message.importance,
         ack_required: message.ack_required,
         created_ts: message.created_ts,
+        deliver_at: None,
+        archive_pending: !mm.archive_queue.is_sync(),
+        delivered: vec![original_msg.sender_name],
+        failed: Vec::new(),
+        partial_success: false,
+        groups: Vec::new(),
     })
     .into_response())
 }
 
 // --- search_messages ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct SearchMessagesPayload {
     pub project_slug: String,
+    /// Terms plus optional `from:`, `subject:`, `after:`, `before:`,
+    /// `importance:` and `thread:` fields
     pub query: String,
+    /// Page size (default 50, max 200)
     #[serde(default = "default_search_limit")]
     pub limit: i64,
+    /// `next_cursor` from the previous page
+    #[serde(default)]
+    pub cursor: Option<i64>,
 }
 
 fn default_search_limit() -> i64 {
     50
 }
 
-#[derive(Serialize)]
+/// Upper bound on requested search page size
+const MAX_SEARCH_LIMIT: i64 = 200;
+
+#[derive(Serialize, ToSchema)]
 pub struct SearchMessageResult {
     pub id: i64,
     pub subject: String,
     pub sender_name: String,
     pub thread_id: Option<String>,
-    pub body_md: String,
+    /// Position in the thread, counting from 1
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_seq: Option<i64>,
+    /// Start of the body; fetch the message for the full text
+    pub snippet: String,
     pub importance: String,
     pub created_ts: chrono::NaiveDateTime,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct SearchMessagesResponse {
     pub query: String,
-    pub results: Vec<SearchMessageResult>,
+    /// Matches on this page, newest first
+    pub items: Vec<SearchMessageResult>,
+    /// Number of items on this page
     pub count: usize,
-}
-
+    /// Pass as `cursor` for the next page; null on the last page
+    pub next_cursor: Option<i64>,
+    /// Total matches across all pages
+    pub total_estimate: i64,
+}
+
+/// Search project messages with field-scoped queries
+///
+/// `from:BlueStone subject:deploy after:2025-01-01 urgent` matches messages
+/// sent by BlueStone whose subject contains "deploy", created since 2025-01-01,
+/// with "urgent" in the body. Unknown fields are searched as plain terms.
+#[utoipa::path(
+    post,
+    path = "/api/messages/search",
+    request_body = SearchMessagesPayload,
+    responses(
+        (status = 200, description = "Page of matching messages", body = SearchMessagesResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 404, description = "Project not found")
+    )
+)]
 pub async fn search_messages(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<SearchMessagesPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -1328,55 +1994,71 @@ pub async fn search_messages(
     )
     .await?;
 
-    let messages = mouchak_mail_core::model::message::MessageBmc::search(
+    let query = mouchak_mail_core::utils::search_query::SearchQuery::parse(&payload.query);
+    let page = mouchak_mail_core::model::message::MessageBmc::search_page(
         &ctx,
         mm,
         project.id.get(),
-        &payload.query,
-        payload.limit,
+        &query,
+        payload.limit.clamp(1, MAX_SEARCH_LIMIT),
+        payload.cursor,
     )
     .await?;
 
-    let results: Vec<SearchMessageResult> = messages
+    let items: Vec<SearchMessageResult> = page
+        .items
         .into_iter()
         .map(|msg| SearchMessageResult {
             id: msg.id,
             subject: msg.subject,
             sender_name: msg.sender_name,
             thread_id: msg.thread_id,
-            body_md: msg.body_md,
+            thread_seq: msg.thread_seq,
+            snippet: msg.snippet,
             importance: msg.importance,
             created_ts: msg.created_ts,
         })
         .collect();
 
-    let count = results.len();
+    let count = items.len();
 
     Ok(Json(SearchMessagesResponse {
         query: payload.query,
-        results,
+        items,
         count,
+        next_cursor: page.next_cursor,
+        total_estimate: page.total_estimate,
     })
     .into_response())
 }
 
 // --- force_release_reservation ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct ForceReleaseReservationPayload {
     pub reservation_id: i64,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ForceReleaseReservationResponse {
     pub released: bool,
     pub reservation_id: i64,
 }
 
+/// Release any agent's reservation by ID
+#[utoipa::path(
+    post,
+    path = "/api/file_reservations/force_release",
+    request_body = ForceReleaseReservationPayload,
+    responses(
+        (status = 200, description = "Reservation released", body = ForceReleaseReservationResponse),
+        (status = 404, description = "Reservation not found")
+    )
+)]
 pub async fn force_release_reservation(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<ForceReleaseReservationPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     FileReservationBmc::force_release(&ctx, mm, payload.reservation_id).await?;
@@ -1389,46 +2071,104 @@ pub async fn force_release_reservation(
 }
 
 // --- renew_file_reservation ---
-#[derive(Deserialize)]
+/// Identifies a reservation either by `reservation_id` or by
+/// `project_slug` + `agent_name` + `path_pattern`.
+#[derive(Deserialize, ToSchema)]
 pub struct RenewFileReservationPayload {
-    pub reservation_id: i64,
+    pub reservation_id: Option<i64>,
+    pub project_slug: Option<String>,
+    pub agent_name: Option<String>,
+    pub path_pattern: Option<String>,
     pub ttl_seconds: Option<i64>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct RenewFileReservationResponse {
     pub renewed: bool,
     pub reservation_id: i64,
+    pub path_pattern: String,
+    /// New expiry in RFC3339 (UTC).
     pub new_expires_ts: String,
 }
 
+/// Extend the expiry of a file reservation
+#[utoipa::path(
+    post,
+    path = "/api/file_reservations/renew",
+    request_body = RenewFileReservationPayload,
+    responses(
+        (status = 200, description = "New expiry", body = RenewFileReservationResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 404, description = "Reservation not found"),
+        (status = 409, description = "Reservation already released or expired")
+    )
+)]
 pub async fn renew_file_reservation(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<RenewFileReservationPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
-    let ttl = payload.ttl_seconds.unwrap_or(3600);
-    let new_expires = chrono::Utc::now().naive_utc() + chrono::Duration::seconds(ttl);
+    let reservation_id = match (
+        payload.reservation_id,
+        &payload.project_slug,
+        &payload.agent_name,
+        &payload.path_pattern,
+    ) {
+        (Some(id), _, _, _) => id,
+        (None, Some(project_slug), Some(agent_name), Some(path_pattern)) => {
+            let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
+                &ctx,
+                mm,
+                project_slug,
+            )
+            .await?;
+            let agent = mouchak_mail_core::model::agent::AgentBmc::get_by_name(
+                &ctx, mm, project.id, agent_name,
+            )
+            .await?;
+            FileReservationBmc::get_latest_by_path(&ctx, mm, project.id, agent.id, path_pattern)
+                .await?
+                .map(|r| r.id)
+                .ok_or_else(|| {
+                    mouchak_mail_core::Error::FileReservationNotFound(path_pattern.clone())
+                })?
+        }
+        _ => {
+            return Err(mouchak_mail_core::Error::InvalidInput(
+                "Provide reservation_id or project_slug, agent_name and path_pattern".to_string(),
+            )
+            .into());
+        }
+    };
+
+    if ctx.authenticated_agent().is_some() {
+        let reservation = FileReservationBmc::get(&ctx, mm, reservation_id).await?;
+        let holder =
+            mouchak_mail_core::model::agent::AgentBmc::get(&ctx, mm, reservation.agent_id).await?;
+        ctx.ensure_acting_as(holder.id, &holder.name)?;
+    }
 
-    FileReservationBmc::renew(&ctx, mm, payload.reservation_id, new_expires).await?;
+    let ttl = payload.ttl_seconds.unwrap_or(3600);
+    let renewed = FileReservationBmc::renew_active(&ctx, mm, reservation_id, ttl).await?;
 
     Ok(Json(RenewFileReservationResponse {
         renewed: true,
-        reservation_id: payload.reservation_id,
-        new_expires_ts: new_expires.format("%Y-%m-%dT%H:%M:%S").to_string(),
+        reservation_id: renewed.id,
+        path_pattern: renewed.path_pattern,
+        new_expires_ts: renewed.expires_ts.and_utc().to_rfc3339(),
     })
     .into_response())
 }
 
 // --- get_project_info ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct GetProjectInfoPayload {
     pub project_slug: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ProjectInfoResponse {
     pub id: i64,
     pub slug: String,
@@ -1438,11 +2178,21 @@ pub struct ProjectInfoResponse {
     pub message_count: usize,
 }
 
+/// Project details with agent and message counts
+#[utoipa::path(
+    post,
+    path = "/api/project/info",
+    request_body = GetProjectInfoPayload,
+    responses(
+        (status = 200, description = "Project details", body = ProjectInfoResponse),
+        (status = 404, description = "Project not found")
+    )
+)]
 pub async fn get_project_info(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<GetProjectInfoPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -1473,13 +2223,13 @@ pub async fn get_project_info(
     .into_response())
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct GetQuotaStatusPayload {
     pub project_slug: String,
     pub agent_name: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct QuotaStatusResponse {
     pub project_slug: String,
     pub quota_enabled: bool,
@@ -1489,80 +2239,348 @@ pub struct QuotaStatusResponse {
     pub agent_inbox_usage: Option<i64>,
 }
 
+/// Attachment and inbox quota usage
+#[utoipa::path(
+    post,
+    path = "/api/quota/status",
+    request_body = GetQuotaStatusPayload,
+    responses(
+        (status = 200, description = "Quota usage", body = QuotaStatusResponse),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
 pub async fn get_quota_status(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<GetQuotaStatusPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
     let config = &mm.app_config.quota;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
         &ctx,
         mm,
-        &payload.project_slug,
+        &payload.project_slug,
+    )
+    .await?;
+
+    let attachments_usage =
+        mouchak_mail_core::model::attachment::AttachmentBmc::get_total_project_usage(
+            &ctx,
+            mm,
+            project.id.get(),
+        )
+        .await?;
+
+    let mut agent_usage = None;
+    if let Some(agent_name) = &payload.agent_name {
+        let agent = mouchak_mail_core::model::agent::AgentBmc::get_by_name(
+            &ctx, mm, project.id, agent_name,
+        )
+        .await?;
+        let count = mouchak_mail_core::model::message::MessageBmc::get_inbox_count(
+            &ctx,
+            mm,
+            agent.id.get(),
+        )
+        .await?;
+        agent_usage = Some(count);
+    }
+
+    Ok(Json(QuotaStatusResponse {
+        project_slug: project.slug,
+        quota_enabled: config.enabled,
+        attachments_limit_bytes: config.attachments_limit_bytes as i64,
+        attachments_usage_bytes: attachments_usage,
+        inbox_limit_count: config.inbox_limit_count as i64,
+        agent_inbox_usage: agent_usage,
+    })
+    .into_response())
+}
+
+// --- get_agent_profile ---
+// Extended profile info compared to basic whois
+#[derive(Serialize, ToSchema)]
+pub struct AgentProfileResponse {
+    pub id: i64,
+    pub name: String,
+    pub program: String,
+    pub model: String,
+    pub task_description: String,
+    pub inception_ts: chrono::NaiveDateTime,
+    pub last_active_ts: chrono::NaiveDateTime,
+    pub attachments_policy: String,
+    pub contact_policy: String,
+    pub project_slug: String,
+    pub project_human_key: String,
+    pub message_count_sent: usize,
+    pub message_count_received: usize,
+    pub active_reservations: usize,
+}
+
+/// Agent details with message and reservation counts
+#[utoipa::path(
+    post,
+    path = "/api/agent/profile",
+    request_body = WhoisPayload,
+    responses(
+        (status = 200, description = "Agent profile", body = AgentProfileResponse),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
+pub async fn get_agent_profile(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Json(payload): Json<WhoisPayload>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
+        &ctx,
+        mm,
+        &payload.project_slug,
+    )
+    .await?;
+    let agent = mouchak_mail_core::model::agent::AgentBmc::get_by_name(
+        &ctx,
+        mm,
+        project.id,
+        &payload.agent_name,
+    )
+    .await?;
+
+    // Count sent and received messages
+    let sent_count =
+        mouchak_mail_core::model::agent::AgentBmc::count_messages_sent(&ctx, mm, agent.id).await?;
+    let received_count =
+        mouchak_mail_core::model::agent::AgentBmc::count_messages_received(&ctx, mm, agent.id)
+            .await?;
+
+    // Count active reservations
+    let reservations = FileReservationBmc::list_active_for_project(&ctx, mm, project.id).await?;
+    let active_reservations = reservations
+        .iter()
+        .filter(|r| r.agent_id == agent.id)
+        .count();
+
+    Ok(Json(AgentProfileResponse {
+        id: agent.id.get(),
+        name: agent.name,
+        program: agent.program,
+        model: agent.model,
+        task_description: agent.task_description,
+        inception_ts: agent.inception_ts,
+        last_active_ts: agent.last_active_ts,
+        attachments_policy: agent.attachments_policy,
+        contact_policy: agent.contact_policy,
+        project_slug: project.slug,
+        project_human_key: project.human_key,
+        message_count_sent: sent_count as usize,
+        message_count_received: received_count as usize,
+        active_reservations,
+    })
+    .into_response())
+}
+
+// --- mark_message_read ---
+#[derive(Deserialize, ToSchema)]
+pub struct MarkMessageReadPayload {
+    pub project_slug: String,
+    pub agent_name: String,
+    pub message_id: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MarkMessageReadResponse {
+    pub marked: bool,
+    pub message_id: i64,
+}
+
+/// Mark a message read for a recipient
+#[utoipa::path(
+    post,
+    path = "/api/message/read",
+    request_body = MarkMessageReadPayload,
+    responses(
+        (status = 200, description = "Message marked read", body = MarkMessageReadResponse),
+        (status = 404, description = "Project, agent or message not found")
+    )
+)]
+pub async fn mark_message_read(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Json(payload): Json<MarkMessageReadPayload>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
+        &ctx,
+        mm,
+        &payload.project_slug,
+    )
+    .await?;
+    let agent = mouchak_mail_core::model::agent::AgentBmc::get_by_name(
+        &ctx,
+        mm,
+        project.id,
+        &payload.agent_name,
+    )
+    .await?;
+
+    mouchak_mail_core::model::message::MessageBmc::mark_read(
+        &ctx,
+        mm,
+        payload.message_id,
+        agent.id.get(),
+    )
+    .await?;
+
+    Ok(Json(MarkMessageReadResponse {
+        marked: true,
+        message_id: payload.message_id,
+    })
+    .into_response())
+}
+
+// --- acknowledge_message ---
+#[derive(Deserialize, ToSchema)]
+pub struct AcknowledgeMessagePayload {
+    pub project_slug: String,
+    pub agent_name: String,
+    pub message_id: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct AcknowledgeMessageResponse {
+    pub acknowledged: bool,
+    pub message_id: i64,
+}
+
+/// Acknowledge a message for a recipient
+#[utoipa::path(
+    post,
+    path = "/api/message/acknowledge",
+    request_body = AcknowledgeMessagePayload,
+    responses(
+        (status = 200, description = "Message acknowledged", body = AcknowledgeMessageResponse),
+        (status = 404, description = "Project, agent or message not found")
+    )
+)]
+pub async fn acknowledge_message(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Json(payload): Json<AcknowledgeMessagePayload>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
+        &ctx,
+        mm,
+        &payload.project_slug,
+    )
+    .await?;
+    let agent = mouchak_mail_core::model::agent::AgentBmc::get_by_name(
+        &ctx,
+        mm,
+        project.id,
+        &payload.agent_name,
+    )
+    .await?;
+
+    mouchak_mail_core::model::message::MessageBmc::acknowledge(
+        &ctx,
+        mm,
+        payload.message_id,
+        agent.id.get(),
+    )
+    .await?;
+
+    Ok(Json(AcknowledgeMessageResponse {
+        acknowledged: true,
+        message_id: payload.message_id,
+    })
+    .into_response())
+}
+
+// --- recall_message ---
+#[derive(Deserialize, ToSchema)]
+pub struct RecallMessagePayload {
+    pub project_slug: String,
+    /// Must be the original sender
+    pub agent_name: String,
+    pub message_id: i64,
+    pub reason: String,
+}
+
+/// Recall a sent message within the recall window
+#[utoipa::path(
+    post,
+    path = "/api/message/recall",
+    request_body = RecallMessagePayload,
+    responses(
+        (status = 200, description = "Recall tombstone", body = mouchak_mail_core::model::message::MessageRecall),
+        (status = 403, description = "Agent is not the sender"),
+        (status = 404, description = "Project, agent or message not found"),
+        (status = 409, description = "Recall window has passed")
+    )
+)]
+pub async fn recall_message(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Json(payload): Json<RecallMessagePayload>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
+        &ctx,
+        mm,
+        &payload.project_slug,
+    )
+    .await?;
+    let agent = mouchak_mail_core::model::agent::AgentBmc::get_by_name(
+        &ctx,
+        mm,
+        project.id,
+        &payload.agent_name,
     )
     .await?;
+    let ctx = ctx.with_actor(Actor::agent(&agent.name, &project.slug));
 
-    let attachments_usage =
-        mouchak_mail_core::model::attachment::AttachmentBmc::get_total_project_usage(
-            &ctx,
-            mm,
-            project.id.get(),
-        )
-        .await?;
-
-    let mut agent_usage = None;
-    if let Some(agent_name) = &payload.agent_name {
-        let agent = mouchak_mail_core::model::agent::AgentBmc::get_by_name(
-            &ctx, mm, project.id, agent_name,
-        )
-        .await?;
-        let count = mouchak_mail_core::model::message::MessageBmc::get_inbox_count(
-            &ctx,
-            mm,
-            agent.id.get(),
-        )
-        .await?;
-        agent_usage = Some(count);
-    }
+    let recall = mouchak_mail_core::model::message::MessageBmc::recall(
+        &ctx,
+        mm,
+        payload.message_id,
+        agent.id.get(),
+        &payload.reason,
+    )
+    .await?;
 
-    Ok(Json(QuotaStatusResponse {
-        project_slug: project.slug,
-        quota_enabled: config.enabled,
-        attachments_limit_bytes: config.attachments_limit_bytes as i64,
-        attachments_usage_bytes: attachments_usage,
-        inbox_limit_count: config.inbox_limit_count as i64,
-        agent_inbox_usage: agent_usage,
-    })
-    .into_response())
+    Ok(Json(recall).into_response())
 }
 
-// --- get_agent_profile ---
-// Extended profile info compared to basic whois
-#[derive(Serialize)]
-pub struct AgentProfileResponse {
-    pub id: i64,
-    pub name: String,
-    pub program: String,
-    pub model: String,
-    pub task_description: String,
-    pub inception_ts: chrono::NaiveDateTime,
-    pub last_active_ts: chrono::NaiveDateTime,
-    pub attachments_policy: String,
-    pub contact_policy: String,
+// --- list_scheduled ---
+#[derive(Deserialize, ToSchema)]
+pub struct ListScheduledPayload {
     pub project_slug: String,
-    pub project_human_key: String,
-    pub message_count_sent: usize,
-    pub message_count_received: usize,
-    pub active_reservations: usize,
+    /// Sender whose pending messages to list
+    pub agent_name: String,
 }
 
-pub async fn get_agent_profile(
+/// List messages an agent has scheduled for later delivery
+#[utoipa::path(
+    post,
+    path = "/api/message/scheduled",
+    request_body = ListScheduledPayload,
+    responses(
+        (status = 200, description = "Scheduled messages", body = Vec<mouchak_mail_core::model::message::ScheduledMessage>),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
+pub async fn list_scheduled(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
-    Json(payload): Json<WhoisPayload>,
+    Json(payload): Json<ListScheduledPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -1579,58 +2597,49 @@ pub async fn get_agent_profile(
     )
     .await?;
 
-    // Count sent and received messages
-    let sent_count =
-        mouchak_mail_core::model::agent::AgentBmc::count_messages_sent(&ctx, mm, agent.id).await?;
-    let received_count =
-        mouchak_mail_core::model::agent::AgentBmc::count_messages_received(&ctx, mm, agent.id)
-            .await?;
-
-    // Count active reservations
-    let reservations = FileReservationBmc::list_active_for_project(&ctx, mm, project.id).await?;
-    let active_reservations = reservations
-        .iter()
-        .filter(|r| r.agent_id == agent.id)
-        .count();
+    let scheduled = mouchak_mail_core::model::message::MessageBmc::list_scheduled(
+        &ctx,
+        mm,
+        project.id.get(),
+        agent.id.get(),
+    )
+    .await?;
 
-    Ok(Json(AgentProfileResponse {
-        id: agent.id.get(),
-        name: agent.name,
-        program: agent.program,
-        model: agent.model,
-        task_description: agent.task_description,
-        inception_ts: agent.inception_ts,
-        last_active_ts: agent.last_active_ts,
-        attachments_policy: agent.attachments_policy,
-        contact_policy: agent.contact_policy,
-        project_slug: project.slug,
-        project_human_key: project.human_key,
-        message_count_sent: sent_count as usize,
-        message_count_received: received_count as usize,
-        active_reservations,
-    })
-    .into_response())
+    Ok(Json(scheduled).into_response())
 }
 
-// --- mark_message_read ---
-#[derive(Deserialize)]
-pub struct MarkMessageReadPayload {
+// --- cancel_scheduled ---
+#[derive(Deserialize, ToSchema)]
+pub struct CancelScheduledPayload {
     pub project_slug: String,
+    /// Must be the original sender
     pub agent_name: String,
     pub message_id: i64,
 }
 
-#[derive(Serialize)]
-pub struct MarkMessageReadResponse {
-    pub marked: bool,
+#[derive(Serialize, ToSchema)]
+pub struct CancelScheduledResponse {
+    pub cancelled: bool,
     pub message_id: i64,
 }
 
-pub async fn mark_message_read(
+/// Cancel a scheduled message before it is delivered
+#[utoipa::path(
+    post,
+    path = "/api/message/cancel_scheduled",
+    request_body = CancelScheduledPayload,
+    responses(
+        (status = 200, description = "Scheduled message cancelled", body = CancelScheduledResponse),
+        (status = 403, description = "Agent is not the sender"),
+        (status = 404, description = "Project, agent or message not found"),
+        (status = 409, description = "Message already delivered")
+    )
+)]
+pub async fn cancel_scheduled(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
-    Json(payload): Json<MarkMessageReadPayload>,
+    Json(payload): Json<CancelScheduledPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -1647,7 +2656,7 @@ pub async fn mark_message_read(
     )
     .await?;
 
-    mouchak_mail_core::model::message::MessageBmc::mark_read(
+    mouchak_mail_core::model::message::MessageBmc::cancel_scheduled(
         &ctx,
         mm,
         payload.message_id,
@@ -1655,32 +2664,120 @@ pub async fn mark_message_read(
     )
     .await?;
 
-    Ok(Json(MarkMessageReadResponse {
-        marked: true,
+    Ok(Json(CancelScheduledResponse {
+        cancelled: true,
         message_id: payload.message_id,
     })
     .into_response())
 }
 
-// --- acknowledge_message ---
-#[derive(Deserialize)]
-pub struct AcknowledgeMessagePayload {
+// --- snooze_message ---
+#[derive(Deserialize, ToSchema)]
+pub struct SnoozeMessagePayload {
     pub project_slug: String,
+    /// Recipient snoozing the message
     pub agent_name: String,
     pub message_id: i64,
+    /// When the message returns to the inbox (UTC); omit to unsnooze
+    #[serde(default)]
+    pub until: Option<chrono::NaiveDateTime>,
 }
 
-#[derive(Serialize)]
-pub struct AcknowledgeMessageResponse {
-    pub acknowledged: bool,
+#[derive(Serialize, ToSchema)]
+pub struct SnoozeMessageResponse {
     pub message_id: i64,
+    /// When the message returns, or `null` after an unsnooze
+    pub snoozed_until: Option<chrono::NaiveDateTime>,
+    /// Whether anything changed; `false` when unsnoozing a message that was not snoozed
+    pub changed: bool,
+}
+
+/// Snooze a message for one recipient, or unsnooze it when `until` is omitted
+#[utoipa::path(
+    post,
+    path = "/api/message/snooze",
+    request_body = SnoozeMessagePayload,
+    responses(
+        (status = 200, description = "Snooze updated", body = SnoozeMessageResponse),
+        (status = 400, description = "Snooze time is not in the future"),
+        (status = 404, description = "Project or agent not found, or agent is not a recipient")
+    )
+)]
+pub async fn snooze_message(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Json(payload): Json<SnoozeMessagePayload>,
+) -> crate::error::Result<Response> {
+    let mm = &app_state.mm;
+
+    let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
+        &ctx,
+        mm,
+        &payload.project_slug,
+    )
+    .await?;
+    let agent = mouchak_mail_core::model::agent::AgentBmc::get_by_name(
+        &ctx,
+        mm,
+        project.id,
+        &payload.agent_name,
+    )
+    .await?;
+
+    let changed = match payload.until {
+        Some(until) => {
+            mouchak_mail_core::model::message::MessageBmc::snooze(
+                &ctx,
+                mm,
+                payload.message_id,
+                agent.id.get(),
+                until,
+            )
+            .await?;
+            true
+        }
+        None => {
+            mouchak_mail_core::model::message::MessageBmc::unsnooze(
+                &ctx,
+                mm,
+                payload.message_id,
+                agent.id.get(),
+            )
+            .await?
+        }
+    };
+
+    Ok(Json(SnoozeMessageResponse {
+        message_id: payload.message_id,
+        snoozed_until: payload.until,
+        changed,
+    })
+    .into_response())
 }
 
-pub async fn acknowledge_message(
+// --- list_snoozed ---
+#[derive(Deserialize, ToSchema)]
+pub struct ListSnoozedPayload {
+    pub project_slug: String,
+    /// Agent whose snoozed messages to list
+    pub agent_name: String,
+}
+
+/// List an agent's snoozed messages, soonest to return first
+#[utoipa::path(
+    post,
+    path = "/api/message/snoozed",
+    request_body = ListSnoozedPayload,
+    responses(
+        (status = 200, description = "Snoozed messages", body = Vec<mouchak_mail_core::model::message::SnoozedMessage>),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
+pub async fn list_snoozed(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
-    Json(payload): Json<AcknowledgeMessagePayload>,
+    Json(payload): Json<ListSnoozedPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -1697,23 +2794,19 @@ pub async fn acknowledge_message(
     )
     .await?;
 
-    mouchak_mail_core::model::message::MessageBmc::acknowledge(
+    let snoozed = mouchak_mail_core::model::message::MessageBmc::list_snoozed(
         &ctx,
         mm,
-        payload.message_id,
+        project.id.get(),
         agent.id.get(),
     )
     .await?;
 
-    Ok(Json(AcknowledgeMessageResponse {
-        acknowledged: true,
-        message_id: payload.message_id,
-    })
-    .into_response())
+    Ok(Json(snoozed).into_response())
 }
 
 // --- list_threads ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct ListThreadsPayload {
     pub project_slug: String,
     #[serde(default = "default_threads_limit")]
@@ -1724,7 +2817,7 @@ fn default_threads_limit() -> i64 {
     50
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ThreadSummaryResponse {
     pub thread_id: String,
     pub subject: String,
@@ -1732,11 +2825,21 @@ pub struct ThreadSummaryResponse {
     pub last_message_ts: chrono::NaiveDateTime,
 }
 
+/// List threads in a project, most recent first
+#[utoipa::path(
+    post,
+    path = "/api/threads",
+    request_body = ListThreadsPayload,
+    responses(
+        (status = 200, description = "Threads", body = Vec<ThreadSummaryResponse>),
+        (status = 404, description = "Project not found")
+    )
+)]
 pub async fn list_threads(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<ListThreadsPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -1750,6 +2853,7 @@ pub async fn list_threads(
         mm,
         project.id.get(),
         payload.limit,
+        None,
     )
     .await?;
 
@@ -1767,7 +2871,7 @@ pub async fn list_threads(
 }
 
 // --- update_agent_profile ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct UpdateAgentProfilePayload {
     pub project_slug: String,
     pub agent_name: String,
@@ -1776,17 +2880,28 @@ pub struct UpdateAgentProfilePayload {
     pub contact_policy: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct UpdateAgentProfileResponse {
     pub updated: bool,
     pub agent_name: String,
 }
 
+/// Update an agent's task and policies
+#[utoipa::path(
+    post,
+    path = "/api/agent/profile/update",
+    request_body = UpdateAgentProfilePayload,
+    responses(
+        (status = 200, description = "Profile updated", body = UpdateAgentProfileResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
 pub async fn update_agent_profile(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<UpdateAgentProfilePayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -1819,7 +2934,7 @@ pub async fn update_agent_profile(
 }
 
 // --- request_contact ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct RequestContactPayload {
     pub from_project_slug: String,
     pub from_agent_name: String,
@@ -1828,17 +2943,27 @@ pub struct RequestContactPayload {
     pub reason: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct RequestContactResponse {
     pub link_id: i64,
     pub status: String,
 }
 
+/// Ask another agent for permission to message it
+#[utoipa::path(
+    post,
+    path = "/api/contacts/request",
+    request_body = RequestContactPayload,
+    responses(
+        (status = 200, description = "Contact request", body = RequestContactResponse),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
 pub async fn request_contact(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<RequestContactPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let from_project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -1889,23 +3014,33 @@ pub async fn request_contact(
 }
 
 // --- respond_contact ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct RespondContactPayload {
     pub link_id: i64,
     pub accept: bool,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct RespondContactResponse {
     pub link_id: i64,
     pub status: String,
 }
 
+/// Accept or reject a contact request
+#[utoipa::path(
+    post,
+    path = "/api/contacts/respond",
+    request_body = RespondContactPayload,
+    responses(
+        (status = 200, description = "Contact status", body = RespondContactResponse),
+        (status = 404, description = "Contact request not found")
+    )
+)]
 pub async fn respond_contact(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<RespondContactPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     mouchak_mail_core::model::agent_link::AgentLinkBmc::respond_contact(
@@ -1929,13 +3064,13 @@ pub async fn respond_contact(
 }
 
 // --- list_contacts ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct ListContactsPayload {
     pub project_slug: String,
     pub agent_name: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ContactResponse {
     pub id: i64,
     pub other_project_id: i64,
@@ -1945,11 +3080,21 @@ pub struct ContactResponse {
     pub created_ts: chrono::NaiveDateTime,
 }
 
+/// List an agent's contacts
+#[utoipa::path(
+    post,
+    path = "/api/contacts/list",
+    request_body = ListContactsPayload,
+    responses(
+        (status = 200, description = "Contacts", body = Vec<ContactResponse>),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
 pub async fn list_contacts(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<ListContactsPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -1999,24 +3144,35 @@ pub async fn list_contacts(
 
 // --- set_contact_policy ---
 // This reuses update_agent_profile with just contact_policy field
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct SetContactPolicyPayload {
     pub project_slug: String,
     pub agent_name: String,
     pub contact_policy: String, // "auto", "manual", "deny"
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct SetContactPolicyResponse {
     pub updated: bool,
     pub contact_policy: String,
 }
 
+/// Set an agent's contact policy
+#[utoipa::path(
+    post,
+    path = "/api/contacts/policy",
+    request_body = SetContactPolicyPayload,
+    responses(
+        (status = 200, description = "Policy updated", body = SetContactPolicyResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
 pub async fn set_contact_policy(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<SetContactPolicyPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -2049,7 +3205,7 @@ pub async fn set_contact_policy(
 }
 
 // --- acquire_build_slot ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct AcquireBuildSlotPayload {
     pub project_slug: String,
     pub agent_name: String,
@@ -2062,18 +3218,29 @@ fn default_build_slot_ttl() -> i64 {
     1800 // 30 minutes default
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct AcquireBuildSlotResponse {
     pub slot_id: i64,
     pub slot_name: String,
     pub expires_ts: String,
 }
 
+/// Acquire a named build slot
+#[utoipa::path(
+    post,
+    path = "/api/build_slots/acquire",
+    request_body = AcquireBuildSlotPayload,
+    responses(
+        (status = 200, description = "Slot acquired", body = AcquireBuildSlotResponse),
+        (status = 404, description = "Project or agent not found"),
+        (status = 409, description = "Slot held by another agent")
+    )
+)]
 pub async fn acquire_build_slot(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<AcquireBuildSlotPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -2110,25 +3277,35 @@ pub async fn acquire_build_slot(
 }
 
 // --- renew_build_slot ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct RenewBuildSlotPayload {
     pub slot_id: i64,
     #[serde(default = "default_build_slot_ttl")]
     pub ttl_seconds: i64,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct RenewBuildSlotResponse {
     pub renewed: bool,
     pub slot_id: i64,
     pub new_expires_ts: String,
 }
 
+/// Extend the expiry of a build slot
+#[utoipa::path(
+    post,
+    path = "/api/build_slots/renew",
+    request_body = RenewBuildSlotPayload,
+    responses(
+        (status = 200, description = "New expiry", body = RenewBuildSlotResponse),
+        (status = 404, description = "Build slot not found")
+    )
+)]
 pub async fn renew_build_slot(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<RenewBuildSlotPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let new_expires = mouchak_mail_core::model::build_slot::BuildSlotBmc::renew(
@@ -2148,22 +3325,32 @@ pub async fn renew_build_slot(
 }
 
 // --- release_build_slot ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct ReleaseBuildSlotPayload {
     pub slot_id: i64,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ReleaseBuildSlotResponse {
     pub released: bool,
     pub slot_id: i64,
 }
 
+/// Release a build slot
+#[utoipa::path(
+    post,
+    path = "/api/build_slots/release",
+    request_body = ReleaseBuildSlotPayload,
+    responses(
+        (status = 200, description = "Slot released", body = ReleaseBuildSlotResponse),
+        (status = 404, description = "Build slot not found")
+    )
+)]
 pub async fn release_build_slot(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<ReleaseBuildSlotPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     mouchak_mail_core::model::build_slot::BuildSlotBmc::release(&ctx, mm, payload.slot_id).await?;
@@ -2176,7 +3363,7 @@ pub async fn release_build_slot(
 }
 
 // --- send_overseer_message ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct SendOverseerMessagePayload {
     pub project_slug: String,
     pub agent_name: String,
@@ -2186,17 +3373,28 @@ pub struct SendOverseerMessagePayload {
     pub importance: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct SendOverseerMessageResponse {
     pub sent: bool,
     pub message_id: i64,
 }
 
+/// Send a message to an agent as the human overseer
+#[utoipa::path(
+    post,
+    path = "/api/overseer/send",
+    request_body = SendOverseerMessagePayload,
+    responses(
+        (status = 200, description = "Message sent", body = SendOverseerMessageResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
 pub async fn send_overseer_message(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<SendOverseerMessagePayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -2233,12 +3431,12 @@ pub async fn send_overseer_message(
 }
 
 // --- list_macros ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct ListMacrosPayload {
     pub project_slug: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct MacroResponse {
     pub id: i64,
     pub name: String,
@@ -2246,11 +3444,21 @@ pub struct MacroResponse {
     pub step_count: usize,
 }
 
+/// List macros registered in a project
+#[utoipa::path(
+    post,
+    path = "/api/macros/list",
+    request_body = ListMacrosPayload,
+    responses(
+        (status = 200, description = "Macros", body = Vec<MacroResponse>),
+        (status = 404, description = "Project not found")
+    )
+)]
 pub async fn list_macros(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<ListMacrosPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -2276,25 +3484,37 @@ pub async fn list_macros(
 }
 
 // --- register_macro ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct RegisterMacroPayload {
     pub project_slug: String,
     pub name: String,
     pub description: String,
+    #[schema(value_type = Vec<Object>)]
     pub steps: Vec<serde_json::Value>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct RegisterMacroResponse {
     pub macro_id: i64,
     pub name: String,
 }
 
+/// Register a macro
+#[utoipa::path(
+    post,
+    path = "/api/macros/register",
+    request_body = RegisterMacroPayload,
+    responses(
+        (status = 200, description = "Macro registered", body = RegisterMacroResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 404, description = "Project not found")
+    )
+)]
 pub async fn register_macro(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<RegisterMacroPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -2322,23 +3542,33 @@ pub async fn register_macro(
 }
 
 // --- unregister_macro ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct UnregisterMacroPayload {
     pub project_slug: String,
     pub name: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct UnregisterMacroResponse {
     pub deleted: bool,
     pub name: String,
 }
 
+/// Remove a macro
+#[utoipa::path(
+    post,
+    path = "/api/macros/unregister",
+    request_body = UnregisterMacroPayload,
+    responses(
+        (status = 200, description = "Macro removed", body = UnregisterMacroResponse),
+        (status = 404, description = "Project or macro not found")
+    )
+)]
 pub async fn unregister_macro(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<UnregisterMacroPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -2363,26 +3593,38 @@ pub async fn unregister_macro(
 }
 
 // --- invoke_macro ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct InvokeMacroPayload {
     pub project_slug: String,
     pub name: String,
     #[allow(dead_code)]
+    #[schema(value_type = Option<Object>)]
     pub params: Option<serde_json::Value>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct InvokeMacroResponse {
     pub name: String,
+    #[schema(value_type = Vec<Object>)]
     pub steps: Vec<serde_json::Value>,
     pub message: String,
 }
 
+/// Return a macro's steps for the caller to run
+#[utoipa::path(
+    post,
+    path = "/api/macros/invoke",
+    request_body = InvokeMacroPayload,
+    responses(
+        (status = 200, description = "Macro steps", body = InvokeMacroResponse),
+        (status = 404, description = "Project or macro not found")
+    )
+)]
 pub async fn invoke_macro(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<InvokeMacroPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -2418,7 +3660,7 @@ pub async fn invoke_macro(
 
 // --- macro_start_session ---
 // Combines: register_agent + file_reservation_paths
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct MacroStartSessionPayload {
     pub project_slug: String,
     pub name: String,
@@ -2433,7 +3675,7 @@ fn default_reservation_ttl() -> i64 {
     3600
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct MacroStartSessionResponse {
     pub agent_id: i64,
     pub agent_name: String,
@@ -2441,11 +3683,22 @@ pub struct MacroStartSessionResponse {
     pub message: String,
 }
 
+/// Register an agent and reserve its paths in one call
+#[utoipa::path(
+    post,
+    path = "/api/macros/start_session",
+    request_body = MacroStartSessionPayload,
+    responses(
+        (status = 200, description = "Session started", body = MacroStartSessionResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 404, description = "Project not found")
+    )
+)]
 pub async fn macro_start_session(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<MacroStartSessionPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     // Step 1: Ensure project exists
@@ -2495,7 +3748,7 @@ pub async fn macro_start_session(
 
 // --- macro_file_reservation_cycle ---
 // Reserve or release files
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct MacroFileReservationCyclePayload {
     pub project_slug: String,
     pub agent_name: String,
@@ -2505,18 +3758,29 @@ pub struct MacroFileReservationCyclePayload {
     pub ttl_seconds: i64,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct MacroFileReservationCycleResponse {
     pub action: String,
     pub affected_count: usize,
     pub ids: Vec<i64>,
 }
 
+/// Reserve or release a set of paths
+#[utoipa::path(
+    post,
+    path = "/api/macros/file_reservation_cycle",
+    request_body = MacroFileReservationCyclePayload,
+    responses(
+        (status = 200, description = "Affected reservations", body = MacroFileReservationCycleResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
 pub async fn macro_file_reservation_cycle(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<MacroFileReservationCyclePayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -2579,24 +3843,34 @@ pub async fn macro_file_reservation_cycle(
 
 // --- macro_contact_handshake ---
 // Create bidirectional contact between two agents
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct MacroContactHandshakePayload {
     pub project_slug: String,
     pub requester: String,
     pub target: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct MacroContactHandshakeResponse {
     pub contacts_created: i32,
     pub link_ids: Vec<i64>,
 }
 
+/// Link two agents as contacts in both directions
+#[utoipa::path(
+    post,
+    path = "/api/macros/contact_handshake",
+    request_body = MacroContactHandshakePayload,
+    responses(
+        (status = 200, description = "Contacts created", body = MacroContactHandshakeResponse),
+        (status = 404, description = "Project or agent not found")
+    )
+)]
 pub async fn macro_contact_handshake(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<MacroContactHandshakePayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -2645,7 +3919,7 @@ pub async fn macro_contact_handshake(
 
 // --- summarize_thread ---
 // Note: Real summarization would use LLM, this returns a simple summary
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct SummarizeThreadPayload {
     pub project_slug: String,
     pub thread_id: String,
@@ -2659,7 +3933,7 @@ fn default_per_thread_limit() -> i64 {
     100
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct SummarizeThreadResponse {
     pub thread_id: String,
     pub message_count: usize,
@@ -2717,11 +3991,21 @@ async fn call_openai_summarize(
     Ok(summary)
 }
 
+/// Summarize a thread
+#[utoipa::path(
+    post,
+    path = "/api/thread/summarize",
+    request_body = SummarizeThreadPayload,
+    responses(
+        (status = 200, description = "Thread summary", body = SummarizeThreadResponse),
+        (status = 404, description = "Project or thread not found")
+    )
+)]
 pub async fn summarize_thread(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<SummarizeThreadPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -2790,14 +4074,14 @@ pub async fn summarize_thread(
 }
 
 // --- summarize_threads (batch) ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct SummarizeThreadsPayload {
     pub project_slug: String,
     #[serde(default = "default_threads_limit")]
     pub limit: i64,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ThreadSummaryBrief {
     pub thread_id: String,
     pub subject: String,
@@ -2805,11 +4089,21 @@ pub struct ThreadSummaryBrief {
     pub last_message_ts: chrono::NaiveDateTime,
 }
 
+/// Summarize the most recent threads of a project
+#[utoipa::path(
+    post,
+    path = "/api/threads/summarize",
+    request_body = SummarizeThreadsPayload,
+    responses(
+        (status = 200, description = "Thread summaries", body = Vec<ThreadSummaryBrief>),
+        (status = 404, description = "Project not found")
+    )
+)]
 pub async fn summarize_threads(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<SummarizeThreadsPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -2823,6 +4117,7 @@ pub async fn summarize_threads(
         mm,
         project.id.get(),
         payload.limit,
+        None,
     )
     .await?;
 
@@ -2840,24 +4135,35 @@ pub async fn summarize_threads(
 }
 
 // --- install_precommit_guard ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct InstallPrecommitGuardPayload {
     pub project_slug: String,
     pub target_repo_path: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct InstallPrecommitGuardResponse {
     pub installed: bool,
     pub hook_path: String,
     pub message: String,
 }
 
+/// Install the reservation pre-commit hook into a repository
+#[utoipa::path(
+    post,
+    path = "/api/setup/install_guard",
+    request_body = InstallPrecommitGuardPayload,
+    responses(
+        (status = 200, description = "Hook installed", body = InstallPrecommitGuardResponse),
+        (status = 400, description = "Invalid input"),
+        (status = 404, description = "Project not found")
+    )
+)]
 pub async fn install_precommit_guard(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<InstallPrecommitGuardPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     // Verify project exists
@@ -2923,17 +4229,26 @@ exit 0
 }
 
 // --- uninstall_precommit_guard ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct UninstallPrecommitGuardPayload {
     pub target_repo_path: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct UninstallPrecommitGuardResponse {
     pub uninstalled: bool,
     pub message: String,
 }
 
+/// Remove the reservation pre-commit hook from a repository
+#[utoipa::path(
+    post,
+    path = "/api/setup/uninstall_guard",
+    request_body = UninstallPrecommitGuardPayload,
+    responses(
+        (status = 200, description = "Hook removed, or nothing to remove", body = UninstallPrecommitGuardResponse)
+    )
+)]
 pub async fn uninstall_precommit_guard(
     State(_app_state): State<AppState>,
     Json(payload): Json<UninstallPrecommitGuardPayload>,
@@ -2969,52 +4284,143 @@ pub async fn uninstall_precommit_guard(
 
 // --- Metrics ---
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 pub struct ListMetricsParams {
     pub project_id: Option<i64>,
     pub limit: Option<i64>,
+    /// RFC3339 start of a usage window; switches to per-tool aggregates
+    pub since: Option<String>,
 }
 
+/// Body of `/api/metrics/tools`: raw invocations, or usage aggregates when
+/// `since` is given.
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+pub enum ToolMetricsResponse {
+    Recent(Vec<mouchak_mail_core::model::tool_metric::ToolMetric>),
+    Usage(mouchak_mail_core::model::tool_metric::ToolUsageReport),
+}
+
+fn parse_metrics_since(since: &str) -> crate::error::Result<chrono::NaiveDateTime> {
+    Ok(chrono::DateTime::parse_from_rfc3339(since)
+        .map_err(|e| crate::ServerError::BadRequest(format!("Invalid since timestamp: {}", e)))?
+        .naive_utc())
+}
+
+/// Recent MCP tool invocations, or per-tool usage since a point in time
+///
+/// With `since`, returns call counts, error rates, latency percentiles and
+/// bucketed call counts per tool (at most 50 tools, busiest first).
+#[utoipa::path(
+    get,
+    path = "/api/metrics/tools",
+    params(ListMetricsParams),
+    responses(
+        (status = 200, description = "Tool invocations, newest first, or a usage report when `since` is set", body = ToolMetricsResponse),
+        (status = 400, description = "Invalid input")
+    )
+)]
 pub async fn list_tool_metrics(
+    RequestCtx(ctx): RequestCtx,
     State(state): State<AppState>,
     Query(params): Query<ListMetricsParams>,
 ) -> crate::error::Result<Response> {
     use mouchak_mail_core::model::tool_metric::ToolMetricBmc;
 
-    let ctx = Ctx::root_ctx();
-    let limit = params.limit.unwrap_or(50);
-    let metrics = ToolMetricBmc::list_recent(&ctx, &state.mm, params.project_id, limit).await?;
+    let body = match params.since {
+        Some(since) => {
+            let since = parse_metrics_since(&since)?;
+            ToolMetricsResponse::Usage(ToolMetricBmc::usage(&ctx, &state.mm, since).await?)
+        }
+        None => {
+            let limit = params.limit.unwrap_or(50);
+            ToolMetricsResponse::Recent(
+                ToolMetricBmc::list_recent(&ctx, &state.mm, params.project_id, limit).await?,
+            )
+        }
+    };
 
-    Ok(Json(metrics).into_response())
+    Ok(Json(body).into_response())
 }
 
+/// Per-tool call counts, latency and errors
+#[utoipa::path(
+    get,
+    path = "/api/metrics/tools/stats",
+    params(ListMetricsParams),
+    responses(
+        (status = 200, description = "Per-tool statistics", body = Vec<mouchak_mail_core::model::tool_metric::ToolStat>)
+    )
+)]
 pub async fn get_tool_stats(
+    RequestCtx(ctx): RequestCtx,
     State(state): State<AppState>,
     Query(params): Query<ListMetricsParams>,
 ) -> crate::error::Result<Response> {
     use mouchak_mail_core::model::tool_metric::ToolMetricBmc;
 
-    let ctx = Ctx::root_ctx();
     let stats = ToolMetricBmc::get_stats(&ctx, &state.mm, params.project_id).await?;
 
     Ok(Json(stats).into_response())
 }
 
+#[derive(Deserialize, IntoParams)]
+pub struct ToolMetricsSummaryParams {
+    /// RFC3339 start of the window (defaults to 24 hours ago)
+    pub since: Option<String>,
+}
+
+/// Tool call totals over a time window
+#[utoipa::path(
+    get,
+    path = "/api/metrics/tools/summary",
+    params(ToolMetricsSummaryParams),
+    responses(
+        (status = 200, description = "Tool metrics summary", body = mouchak_mail_core::model::tool_metric::ToolMetricsSummary),
+        (status = 400, description = "Invalid input")
+    )
+)]
+pub async fn get_tool_metrics_summary(
+    RequestCtx(ctx): RequestCtx,
+    State(state): State<AppState>,
+    Query(params): Query<ToolMetricsSummaryParams>,
+) -> crate::error::Result<Response> {
+    use mouchak_mail_core::model::tool_metric::ToolMetricBmc;
+
+    let since = match params.since {
+        Some(since) => parse_metrics_since(&since)?,
+        None => chrono::Utc::now().naive_utc() - chrono::Duration::hours(24),
+    };
+
+    let summary = ToolMetricBmc::summary(&ctx, &state.mm, since).await?;
+
+    Ok(Json(summary).into_response())
+}
+
 // --- Activity ---
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 pub struct ListActivityParams {
     pub project_id: i64,
     pub limit: Option<i64>,
 }
 
+/// Recent messages, tool calls and registrations in a project
+#[utoipa::path(
+    get,
+    path = "/api/activity",
+    params(ListActivityParams),
+    responses(
+        (status = 200, description = "Activity, newest first", body = Vec<mouchak_mail_core::model::activity::ActivityItem>)
+    )
+)]
 pub async fn list_activity(
+    RequestCtx(ctx): RequestCtx,
     State(state): State<AppState>,
     Query(params): Query<ListActivityParams>,
 ) -> crate::error::Result<Response> {
     use mouchak_mail_core::model::activity::ActivityBmc;
 
-    let ctx = Ctx::root_ctx();
     let limit = params.limit.unwrap_or(50);
     let items = ActivityBmc::list_recent(&ctx, &state.mm, params.project_id, limit).await?;
 
@@ -3022,30 +4428,49 @@ pub async fn list_activity(
 }
 
 // --- commit_archive ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct CommitArchivePayload {
     pub project_slug: String,
     pub message: String,
+    /// Compress the committed mailbox snapshot: "gzip" or "zstd"
+    #[serde(default)]
+    pub compression: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct CommitArchiveResponse {
     pub commit_id: String,
     pub project_slug: String,
 }
 
+/// Commit pending changes in a project's Git archive
+#[utoipa::path(
+    post,
+    path = "/api/archive/commit",
+    request_body = CommitArchivePayload,
+    responses(
+        (status = 200, description = "Archive committed", body = CommitArchiveResponse),
+        (status = 404, description = "Project not found")
+    )
+)]
 pub async fn commit_archive(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<CommitArchivePayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
+    let compression = payload
+        .compression
+        .as_deref()
+        .unwrap_or_default()
+        .parse::<mouchak_mail_core::model::export::ExportCompression>()?;
 
     let commit_id = mouchak_mail_core::model::export::ExportBmc::commit_archive(
         &ctx,
         mm,
         &payload.project_slug,
         &payload.message,
+        compression,
     )
     .await?;
 
@@ -3056,13 +4481,48 @@ pub async fn commit_archive(
     .into_response())
 }
 
+// --- verify_archive ---
+#[derive(Deserialize, ToSchema)]
+pub struct VerifyArchivePayload {
+    pub project_slug: String,
+    #[serde(default)]
+    pub repair: bool,
+}
+
+/// Check a project's Git archive against the database, optionally repairing it
+#[utoipa::path(
+    post,
+    path = "/api/archive/verify",
+    request_body = VerifyArchivePayload,
+    responses(
+        (status = 200, description = "Integrity report", body = mouchak_mail_core::model::archive_integrity::ArchiveReport),
+        (status = 404, description = "Project not found")
+    )
+)]
+pub async fn verify_archive(
+    RequestCtx(ctx): RequestCtx,
+    State(app_state): State<AppState>,
+    Json(payload): Json<VerifyArchivePayload>,
+) -> crate::error::Result<Response> {
+    use mouchak_mail_core::model::archive_integrity::ArchiveIntegrityBmc;
+    let mm = &app_state.mm;
+
+    let report = if payload.repair {
+        ArchiveIntegrityBmc::repair_archive(&ctx, mm, &payload.project_slug).await?
+    } else {
+        ArchiveIntegrityBmc::verify_archive(&ctx, mm, &payload.project_slug).await?
+    };
+
+    Ok(Json(report).into_response())
+}
+
 // --- list_project_siblings ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 pub struct ListProjectSiblingsPayload {
     pub project_slug: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ProjectSiblingResponse {
     pub id: i64,
     pub other_project_id: i64,
@@ -3071,11 +4531,21 @@ pub struct ProjectSiblingResponse {
     pub rationale: String,
 }
 
+/// List projects suggested as related to this one
+#[utoipa::path(
+    post,
+    path = "/api/project/siblings",
+    request_body = ListProjectSiblingsPayload,
+    responses(
+        (status = 200, description = "Sibling suggestions", body = Vec<ProjectSiblingResponse>),
+        (status = 404, description = "Project not found")
+    )
+)]
 pub async fn list_project_siblings(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Json(payload): Json<ListProjectSiblingsPayload>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let project = mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(
@@ -3116,7 +4586,7 @@ pub async fn list_project_siblings(
 // --- list_pending_reviews ---
 // Single-call API for LLM agents to retrieve messages awaiting acknowledgment
 
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 pub struct ListPendingReviewsQuery {
     /// Filter by project slug (optional)
     pub project: Option<String>,
@@ -3131,26 +4601,26 @@ fn default_pending_reviews_limit() -> i64 {
     5
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct SenderInfo {
     pub id: i64,
     pub name: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ProjectInfo {
     pub id: i64,
     pub slug: String,
     pub name: String,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct ThreadInfo {
     pub id: String,
     pub message_count: i64,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct RecipientStatus {
     pub agent_id: i64,
     pub agent_name: String,
@@ -3161,13 +4631,14 @@ pub struct RecipientStatus {
     pub ack_ts: Option<String>,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct PendingReview {
     pub message_id: i64,
     pub subject: String,
     pub body_md: String,
     pub importance: String,
     pub created_ts: String,
+    #[schema(value_type = Vec<Object>)]
     pub attachments: Vec<serde_json::Value>,
     pub sender: SenderInfo,
     pub project: ProjectInfo,
@@ -3177,17 +4648,26 @@ pub struct PendingReview {
     pub read_count: usize,
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, ToSchema)]
 pub struct PendingReviewsResponse {
     pub pending_reviews: Vec<PendingReview>,
     pub total_count: usize,
 }
 
+/// Messages with acknowledgement required that some recipients have not acknowledged
+#[utoipa::path(
+    get,
+    path = "/api/messages/pending-reviews",
+    params(ListPendingReviewsQuery),
+    responses(
+        (status = 200, description = "Messages awaiting acknowledgement", body = PendingReviewsResponse)
+    )
+)]
 pub async fn list_pending_reviews(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Query(params): Query<ListPendingReviewsQuery>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     // Resolve project_id from slug if provided
@@ -3284,7 +4764,7 @@ pub async fn list_pending_reviews(
 // =============================================================================
 
 // --- list_archive_commits ---
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 pub struct ListArchiveCommitsQuery {
     #[serde(default)]
     pub author: Option<String>,
@@ -3302,11 +4782,21 @@ fn default_commits_limit() -> usize {
     50
 }
 
+/// List commits in the mailbox Git archive
+#[utoipa::path(
+    get,
+    path = "/api/archive/commits",
+    params(ListArchiveCommitsQuery),
+    responses(
+        (status = 200, description = "Commits, newest first", body = Vec<mouchak_mail_core::model::archive_browser::CommitSummary>),
+        (status = 400, description = "Invalid input")
+    )
+)]
 pub async fn list_archive_commits(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Query(params): Query<ListArchiveCommitsQuery>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let filter = if params.author.is_some() || params.path.is_some() {
@@ -3341,11 +4831,21 @@ pub async fn list_archive_commits(
 }
 
 // --- get_archive_commit ---
+/// Get a commit with the files it changed
+#[utoipa::path(
+    get,
+    path = "/api/archive/commits/{sha}",
+    params(("sha" = String, Path, description = "Commit SHA")),
+    responses(
+        (status = 200, description = "Commit details", body = mouchak_mail_core::model::archive_browser::CommitDetails),
+        (status = 404, description = "Commit not found")
+    )
+)]
 pub async fn get_archive_commit(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Path(sha): Path<String>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let details = mouchak_mail_core::model::archive_browser::ArchiveBrowserBmc::commit_details(
@@ -3357,18 +4857,31 @@ pub async fn get_archive_commit(
 }
 
 // --- list_archive_files ---
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 pub struct ListArchiveFilesQuery {
     #[serde(default)]
     pub path: Option<String>,
 }
 
+/// List files at a commit, optionally under a directory
+#[utoipa::path(
+    get,
+    path = "/api/archive/files/{sha}",
+    params(
+        ("sha" = String, Path, description = "Commit SHA"),
+        ListArchiveFilesQuery
+    ),
+    responses(
+        (status = 200, description = "Directory listing", body = Vec<mouchak_mail_core::model::archive_browser::FileEntry>),
+        (status = 404, description = "Commit or path not found")
+    )
+)]
 pub async fn list_archive_files(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Path(sha): Path<String>,
     Query(params): Query<ListArchiveFilesQuery>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let dir_path = params.path.unwrap_or_default();
@@ -3381,17 +4894,30 @@ pub async fn list_archive_files(
 }
 
 // --- get_archive_file_content ---
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 pub struct GetArchiveFileContentQuery {
     pub path: String,
 }
 
+/// Get the content of a file at a commit
+#[utoipa::path(
+    get,
+    path = "/api/archive/file/{sha}",
+    params(
+        ("sha" = String, Path, description = "Commit SHA"),
+        GetArchiveFileContentQuery
+    ),
+    responses(
+        (status = 200, description = "File content", body = mouchak_mail_core::model::archive_browser::FileContent),
+        (status = 404, description = "Commit or file not found")
+    )
+)]
 pub async fn get_archive_file_content(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Path(sha): Path<String>,
     Query(params): Query<GetArchiveFileContentQuery>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let content = mouchak_mail_core::model::archive_browser::ArchiveBrowserBmc::file_content_at(
@@ -3406,7 +4932,7 @@ pub async fn get_archive_file_content(
 }
 
 // --- get_archive_activity ---
-#[derive(Deserialize)]
+#[derive(Deserialize, IntoParams)]
 pub struct GetArchiveActivityQuery {
     #[serde(default = "default_since")]
     pub since: String,
@@ -3422,11 +4948,21 @@ fn default_until() -> String {
     chrono::Utc::now().to_rfc3339()
 }
 
+/// Commit counts per day, author and file over a period
+#[utoipa::path(
+    get,
+    path = "/api/archive/activity",
+    params(GetArchiveActivityQuery),
+    responses(
+        (status = 200, description = "Commit activity", body = mouchak_mail_core::model::archive_browser::ActivitySummary),
+        (status = 400, description = "Invalid input")
+    )
+)]
 pub async fn get_archive_activity(
+    RequestCtx(ctx): RequestCtx,
     State(app_state): State<AppState>,
     Query(params): Query<GetArchiveActivityQuery>,
 ) -> crate::error::Result<Response> {
-    let ctx = Ctx::root_ctx();
     let mm = &app_state.mm;
 
     let since = chrono::DateTime::parse_from_rfc3339(&params.since)
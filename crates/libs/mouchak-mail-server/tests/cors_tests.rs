@@ -0,0 +1,263 @@
+//! CORS configuration tests
+//!
+//! Drives preflight and cross-origin requests through the layer built from
+//! `server.cors`, in front of bearer auth as in `run`.
+
+// Tests are allowed to use unwrap()/expect() for clearer failure messages
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Method, Request, StatusCode, header},
+    middleware,
+    routing::get,
+};
+use mouchak_mail_common::config::{AppConfig, CorsConfig, ServerConfig};
+use mouchak_mail_core::ModelManager;
+use mouchak_mail_server::auth::{AuthConfig, AuthMode, auth_middleware};
+use mouchak_mail_server::cors::cors_layer;
+use mouchak_mail_server::ratelimit::RateLimitConfig;
+use mouchak_mail_server::{AppState, ServerError, tools};
+use std::sync::Arc;
+use tempfile::TempDir;
+use tower::ServiceExt;
+
+const DASHBOARD: &str = "https://dashboard.example.com";
+const TOKEN: &str = "cors-test-token";
+
+fn cors_config(origins: &[&str]) -> CorsConfig {
+    CorsConfig {
+        allowed_origins: origins.iter().map(|o| o.to_string()).collect(),
+        ..CorsConfig::default()
+    }
+}
+
+/// `/api/health` behind bearer auth, wrapped in the CORS layer for `cors`
+async fn app(cors: &CorsConfig) -> (Router, TempDir) {
+    let temp_dir = TempDir::new().unwrap();
+    let db = libsql::Builder::new_local(temp_dir.path().join("test.db"))
+        .build()
+        .await
+        .unwrap();
+    let mm = ModelManager::new_for_test(
+        db.connect().unwrap(),
+        temp_dir.path().join("archive"),
+        Arc::new(AppConfig::default()),
+    );
+    let state = AppState {
+        mm,
+        metrics_handle: mouchak_mail_server::setup_metrics(),
+        start_time: std::time::Instant::now(),
+        auth_config: AuthConfig {
+            mode: AuthMode::Bearer,
+            bearer_token: Some(TOKEN.to_string()),
+            jwks_url: None,
+            jwt_audience: None,
+            jwt_issuer: None,
+            allow_localhost: false,
+            trusted_proxies: vec![],
+        },
+        jwks_client: None,
+        ratelimit_config: RateLimitConfig::new(),
+    };
+
+    let app = Router::new()
+        .route("/api/health", get(tools::health_check))
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth_middleware,
+        ))
+        .layer(cors_layer(cors).unwrap())
+        .with_state(state);
+    (app, temp_dir)
+}
+
+fn preflight(origin: &str) -> Request<Body> {
+    Request::builder()
+        .method(Method::OPTIONS)
+        .uri("/api/health")
+        .header(header::ORIGIN, origin)
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+        .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization")
+        .body(Body::empty())
+        .unwrap()
+}
+
+fn header_str(response: &axum::response::Response, name: header::HeaderName) -> &str {
+    response
+        .headers()
+        .get(name)
+        .map(|v| v.to_str().unwrap())
+        .unwrap_or("")
+}
+
+/// Test a preflight for an authenticated route succeeds without credentials
+#[tokio::test]
+async fn test_preflight_allows_authorization_header() {
+    let mut config = cors_config(&[DASHBOARD]);
+    config.allowed_headers = vec!["x-agent-name".to_string()];
+    config.max_age_secs = 120;
+    let (app, _temp) = app(&config).await;
+
+    let response = app.oneshot(preflight(DASHBOARD)).await.unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        header_str(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+        DASHBOARD
+    );
+    let allowed_headers = header_str(&response, header::ACCESS_CONTROL_ALLOW_HEADERS);
+    assert!(allowed_headers.contains("authorization"));
+    assert!(allowed_headers.contains("x-agent-name"));
+    assert!(header_str(&response, header::ACCESS_CONTROL_ALLOW_METHODS).contains("GET"));
+    assert_eq!(header_str(&response, header::ACCESS_CONTROL_MAX_AGE), "120");
+    assert!(
+        response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_CREDENTIALS)
+            .is_none()
+    );
+}
+
+/// Test cross-origin GETs: allowed origin, other origin, and credentials
+#[tokio::test]
+async fn test_cross_origin_get() {
+    let get_with = |origin: &str| {
+        Request::builder()
+            .uri("/api/health")
+            .header(header::ORIGIN, origin)
+            .header(header::AUTHORIZATION, format!("Bearer {}", TOKEN))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    let mut config = cors_config(&[DASHBOARD, "http://localhost:3000"]);
+    config.allow_credentials = true;
+    let (app, _temp) = app(&config).await;
+
+    let response = app.clone().oneshot(get_with(DASHBOARD)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        header_str(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+        DASHBOARD
+    );
+    assert_eq!(
+        header_str(&response, header::ACCESS_CONTROL_ALLOW_CREDENTIALS),
+        "true"
+    );
+    assert!(header_str(&response, header::VARY).contains("origin"));
+    assert!(
+        header_str(&response, header::ACCESS_CONTROL_EXPOSE_HEADERS).contains("x-project-version")
+    );
+
+    // Not listed: the request is served but the browser gets no grant
+    let response = app
+        .clone()
+        .oneshot(get_with("https://evil.example.com"))
+        .await
+        .unwrap();
+    assert!(
+        response
+            .headers()
+            .get(header::ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_none()
+    );
+
+    // Auth still applies to cross-origin calls
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/health")
+                .header(header::ORIGIN, DASHBOARD)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(
+        header_str(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+        DASHBOARD
+    );
+}
+
+/// Test the wildcard opt-in answers any origin
+#[tokio::test]
+async fn test_wildcard_origin() {
+    let (app, _temp) = app(&cors_config(&["*"])).await;
+
+    let response = app
+        .oneshot(preflight("https://anything.example.org"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        header_str(&response, header::ACCESS_CONTROL_ALLOW_ORIGIN),
+        "*"
+    );
+}
+
+/// Test the cors section parses with defaults and is absent by default
+#[test]
+fn test_cors_config_parsing() {
+    assert!(AppConfig::default().server.cors.is_none());
+
+    let parsed: ServerConfig = serde_json::from_value(serde_json::json!({
+        "host": "0.0.0.0",
+        "port": 8765,
+        "auth_hmac": null,
+        "cors": { "allowed_origins": [DASHBOARD] }
+    }))
+    .unwrap();
+    let cors = parsed.cors.unwrap();
+    assert_eq!(cors.allowed_origins, [DASHBOARD]);
+    assert!(cors.allowed_headers.is_empty());
+    assert_eq!(cors.max_age_secs, 600);
+    assert!(!cors.allow_credentials);
+    assert!(cors_layer(&cors).is_ok());
+}
+
+/// Test origins a browser could never send are rejected up front
+#[test]
+fn test_invalid_cors_origins() {
+    for origin in [
+        "dashboard.example.com",
+        "https://dashboard.example.com/",
+        "https://dashboard.example.com/app",
+        "https://dashboard.example.com?x=1",
+        "ftp://dashboard.example.com",
+        "https://user@dashboard.example.com",
+        "https://",
+        "not an origin",
+        "",
+    ] {
+        let result = cors_layer(&cors_config(&[origin]));
+        assert!(
+            matches!(result, Err(ServerError::ConfigError(ref m)) if m.contains("Invalid CORS origin")),
+            "{:?} should be rejected",
+            origin
+        );
+    }
+
+    assert!(
+        cors_layer(&cors_config(&[
+            "http://localhost:3000",
+            "https://[::1]:8443"
+        ]))
+        .is_ok()
+    );
+    assert!(cors_layer(&cors_config(&[])).is_err());
+    assert!(cors_layer(&cors_config(&["*", DASHBOARD])).is_err());
+
+    let mut config = cors_config(&["*"]);
+    config.allow_credentials = true;
+    assert!(cors_layer(&config).is_err());
+
+    let mut config = cors_config(&[DASHBOARD]);
+    config.allowed_headers = vec!["bad header".to_string()];
+    assert!(matches!(
+        cors_layer(&config),
+        Err(ServerError::ConfigError(ref m)) if m.contains("allowed header")
+    ));
+}
@@ -0,0 +1,3 @@
+#!/bin/sh
+# Hook fixture: copies the event payload from stdin to the file named by $1.
+cat > "$1"
@@ -0,0 +1,178 @@
+//! Event hook tests
+//!
+//! Hooks run `tests/fixtures/capture_hook.sh`, which copies the event JSON
+//! from stdin to the file named by its argument.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use mouchak_mail_common::config::{HookConfig, HookEvent, HookFilter, HooksConfig};
+use mouchak_mail_core::events::EventBus;
+use mouchak_mail_server::hooks::{HookOutcome, HookRunner, synthetic_event};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+use tokio_util::sync::CancellationToken;
+
+fn fixture() -> String {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/capture_hook.sh")
+        .display()
+        .to_string()
+}
+
+/// Hook that writes the payload to `out`.
+fn capture(event: HookEvent, filter: HookFilter, out: &Path) -> HookConfig {
+    HookConfig {
+        event,
+        command: vec!["sh".into(), fixture(), out.display().to_string()],
+        filter,
+        timeout_secs: 5,
+    }
+}
+
+fn shell(script: &str, timeout_secs: u64) -> HookConfig {
+    HookConfig {
+        event: HookEvent::MessageCreated,
+        command: vec!["sh".into(), "-c".into(), script.into()],
+        filter: HookFilter::default(),
+        timeout_secs,
+    }
+}
+
+fn runner(hooks: Vec<HookConfig>) -> HookRunner {
+    HookRunner::new(&HooksConfig {
+        max_concurrent: 2,
+        on: hooks,
+    })
+}
+
+fn read_payload(path: &PathBuf) -> serde_json::Value {
+    serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_hooks_run_only_for_matching_events() {
+    let tmp = TempDir::new().unwrap();
+    let urgent = tmp.path().join("urgent.json");
+    let other_project = tmp.path().join("other.json");
+    let acked = tmp.path().join("acked.json");
+
+    let runner = runner(vec![
+        capture(
+            HookEvent::MessageCreated,
+            HookFilter {
+                importance: Some("urgent".into()),
+                project: None,
+            },
+            &urgent,
+        ),
+        capture(
+            HookEvent::MessageCreated,
+            HookFilter {
+                importance: None,
+                project: Some("other".into()),
+            },
+            &other_project,
+        ),
+        capture(HookEvent::MessageAcked, HookFilter::default(), &acked),
+    ]);
+
+    let normal = synthetic_event(HookEvent::MessageCreated, "ops", "normal");
+    assert!(runner.fire(&normal).await.is_empty());
+
+    let event = synthetic_event(HookEvent::MessageCreated, "ops", "URGENT");
+    let runs = runner.fire(&event).await;
+    assert_eq!(runs.len(), 1);
+    assert_eq!(runs[0].outcome, HookOutcome::Succeeded);
+
+    let payload = read_payload(&urgent);
+    assert_eq!(payload["type"], "message.created");
+    assert_eq!(payload["project_slug"], "ops");
+    assert_eq!(payload["data"]["subject"], "Hook test");
+    assert!(!other_project.exists());
+    assert!(!acked.exists());
+
+    // Events without an importance never match an importance filter
+    let conflict = synthetic_event(HookEvent::ReservationConflict, "ops", "urgent");
+    assert!(runner.fire(&conflict).await.is_empty());
+
+    runner
+        .fire(&synthetic_event(HookEvent::MessageAcked, "ops", "low"))
+        .await;
+    assert_eq!(read_payload(&acked)["type"], "message.acked");
+}
+
+#[tokio::test]
+async fn test_slow_hook_is_killed_at_timeout() {
+    let runner = runner(vec![shell("sleep 30", 1)]);
+
+    let start = Instant::now();
+    let runs = runner
+        .fire(&synthetic_event(HookEvent::MessageCreated, "ops", "normal"))
+        .await;
+    assert_eq!(runs[0].outcome, HookOutcome::TimedOut);
+    assert!(start.elapsed() < Duration::from_secs(10));
+}
+
+#[tokio::test]
+async fn test_failing_hooks_are_reported() {
+    let runner = runner(vec![
+        shell("echo boom >&2; exit 3", 5),
+        HookConfig {
+            command: vec!["/nonexistent/hook".into()],
+            ..shell("", 5)
+        },
+        HookConfig {
+            command: Vec::new(),
+            ..shell("", 5)
+        },
+    ]);
+
+    let runs = runner
+        .fire(&synthetic_event(HookEvent::MessageCreated, "ops", "normal"))
+        .await;
+    let reasons: Vec<String> = runs
+        .iter()
+        .map(|run| match &run.outcome {
+            HookOutcome::Failed(reason) => reason.clone(),
+            other => panic!("{} should fail, got {:?}", run.command, other),
+        })
+        .collect();
+    assert!(reasons[0].ends_with("boom"), "{}", reasons[0]);
+    assert!(reasons[1].contains("cannot run /nonexistent/hook"));
+    assert_eq!(reasons[2], "empty command");
+}
+
+#[tokio::test]
+async fn test_runner_fires_hooks_for_bus_events() {
+    let tmp = TempDir::new().unwrap();
+    let out = tmp.path().join("bus.json");
+    let runner = Arc::new(runner(vec![capture(
+        HookEvent::ReservationConflict,
+        HookFilter::default(),
+        &out,
+    )]));
+    let bus = Arc::new(EventBus::default());
+    let cancel = CancellationToken::new();
+    let task = tokio::spawn(runner.run(bus.clone(), cancel.clone()));
+
+    // Wait for the runner to subscribe
+    while bus.receiver_count() == 0 {
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    let event = synthetic_event(HookEvent::ReservationConflict, "ops", "normal");
+    bus.publish(event.kind, "ops", event.data);
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while std::fs::read_to_string(&out).map_or(true, |s| s.is_empty()) {
+        assert!(Instant::now() < deadline, "hook did not run");
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let payload = read_payload(&out);
+    assert_eq!(payload["type"], "reservation.conflict");
+    assert_eq!(payload["data"]["path_pattern"], "src/**");
+
+    cancel.cancel();
+    task.await.unwrap();
+}
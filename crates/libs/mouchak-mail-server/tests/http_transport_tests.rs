@@ -257,6 +257,7 @@ async fn test_jwt_without_kid_in_header() {
         jwt_audience: None,
         jwt_issuer: None,
         allow_localhost: false,
+        trusted_proxies: vec![],
     };
     let jwks_client = Some(JwksClient::new(jwks_url));
 
@@ -384,6 +385,7 @@ async fn test_jwt_with_wrong_issuer() {
         jwt_audience: None,
         jwt_issuer: Some("https://expected-issuer.example.com".to_string()), // Expecting different issuer
         allow_localhost: false,
+        trusted_proxies: vec![],
     };
     let jwks_client = Some(JwksClient::new(jwks_url));
 
@@ -448,6 +450,7 @@ async fn test_jwt_empty_authorization_header() {
         jwt_audience: None,
         jwt_issuer: None,
         allow_localhost: false,
+        trusted_proxies: vec![],
     };
 
     let app_state = AppState {
@@ -524,6 +527,7 @@ async fn test_jwt_wrong_algorithm() {
         jwt_audience: None,
         jwt_issuer: None,
         allow_localhost: false,
+        trusted_proxies: vec![],
     };
     let jwks_client = Some(JwksClient::new(jwks_url));
 
@@ -572,6 +576,27 @@ async fn test_jwt_wrong_algorithm() {
 // Rate Limiting Tests
 // ============================================================================
 
+/// State for driving `rate_limit_middleware` without a full `AppState`
+#[derive(Clone, axum::extract::FromRef)]
+struct LimitState {
+    limits: mouchak_mail_server::ratelimit::RateLimitConfig,
+    auth: mouchak_mail_server::auth::AuthConfig,
+}
+
+fn no_auth() -> mouchak_mail_server::auth::AuthConfig {
+    use mouchak_mail_server::auth::{AuthConfig, AuthMode};
+
+    AuthConfig {
+        mode: AuthMode::None,
+        bearer_token: None,
+        jwks_url: None,
+        jwt_audience: None,
+        jwt_issuer: None,
+        allow_localhost: false,
+        trusted_proxies: vec![],
+    }
+}
+
 #[tokio::test]
 async fn test_rate_limit_x_forwarded_for_header() {
     use axum::{Router, middleware, routing::get};
@@ -582,13 +607,13 @@ async fn test_rate_limit_x_forwarded_for_header() {
         "OK"
     }
 
-    let config = RateLimitConfig::new();
+    let state = LimitState {
+        limits: RateLimitConfig::new(),
+        auth: no_auth(),
+    };
     let app = Router::new()
         .route("/", get(handler))
-        .layer(middleware::from_fn_with_state(
-            config,
-            rate_limit_middleware,
-        ))
+        .layer(middleware::from_fn_with_state(state, rate_limit_middleware))
         .into_make_service_with_connect_info::<SocketAddr>();
 
     // Start test server
@@ -624,13 +649,13 @@ async fn test_rate_limit_multiple_ips_in_x_forwarded_for() {
         "OK"
     }
 
-    let config = RateLimitConfig::new();
+    let state = LimitState {
+        limits: RateLimitConfig::new(),
+        auth: no_auth(),
+    };
     let app = Router::new()
         .route("/", get(handler))
-        .layer(middleware::from_fn_with_state(
-            config,
-            rate_limit_middleware,
-        ))
+        .layer(middleware::from_fn_with_state(state, rate_limit_middleware))
         .into_make_service_with_connect_info::<SocketAddr>();
 
     let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -655,6 +680,122 @@ async fn test_rate_limit_multiple_ips_in_x_forwarded_for() {
     assert_eq!(response.status().as_u16(), 200);
 }
 
+/// Router with a 1 rps / burst 2 limiter in front of `/api/ping` and `/health`
+fn rate_limited_app() -> axum::Router {
+    use axum::{Router, middleware, routing::get};
+    use mouchak_mail_common::config::RateLimitConfig as RateLimitSettings;
+    use mouchak_mail_server::ratelimit::{RateLimitConfig, rate_limit_middleware};
+
+    async fn handler() -> &'static str {
+        "OK"
+    }
+
+    let state = LimitState {
+        limits: RateLimitConfig::from_config(&RateLimitSettings {
+            enabled: true,
+            requests_per_second: 1,
+            burst: 2,
+        }),
+        auth: no_auth(),
+    };
+    Router::new()
+        .route("/api/ping", get(handler).post(handler))
+        .route("/health", get(handler))
+        .layer(middleware::from_fn_with_state(state, rate_limit_middleware))
+}
+
+/// GET /api/ping as the agent `auth_middleware` resolved from an agent key
+async fn ping(app: &axum::Router, agent_id: i64) -> axum::response::Response {
+    use mouchak_mail_core::Ctx;
+    use mouchak_mail_core::types::AgentId;
+
+    app.clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/ping")
+                .extension(Ctx::for_agent(AgentId::new(agent_id), "Agent", "project"))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_rate_limit_returns_429_and_recovers() {
+    use http_body_util::BodyExt;
+
+    let app = rate_limited_app();
+
+    // Burst of 2 is allowed
+    for _ in 0..2 {
+        assert_eq!(ping(&app, 1).await.status(), StatusCode::OK);
+    }
+
+    let response = ping(&app, 1).await;
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = response.headers()["retry-after"]
+        .to_str()
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert!(retry_after >= 1);
+
+    let body = response.into_body().collect().await.unwrap().to_bytes();
+    let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["code"], "RATE_LIMITED");
+
+    // Another agent has its own bucket
+    assert_eq!(ping(&app, 2).await.status(), StatusCode::OK);
+
+    // One token is replenished after the 1s window
+    tokio::time::sleep(std::time::Duration::from_millis(1100)).await;
+    assert_eq!(ping(&app, 1).await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_rate_limit_ignores_claimed_agent_names() {
+    let app = rate_limited_app();
+
+    // Unauthenticated callers share their address's bucket whatever agent
+    // they claim to be
+    let post = |agent: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/api/ping")
+            .header("Content-Type", "application/json")
+            .header("X-Agent-Name", agent)
+            .body(Body::from(json!({ "sender_name": agent }).to_string()))
+            .unwrap()
+    };
+
+    for agent in ["BodyAgent", "OtherAgent"] {
+        let response = app.clone().oneshot(post(agent)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+    let response = app.clone().oneshot(post("ThirdAgent")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+async fn test_rate_limit_skips_health() {
+    let app = rate_limited_app();
+
+    for _ in 0..10 {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .uri("/health")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
+
 #[tokio::test]
 async fn test_per_tool_rate_limit_category_errors() {
     use mouchak_mail_server::ratelimit::{ToolCategory, ToolRateLimits};
@@ -812,6 +953,69 @@ async fn test_jwks_server_unavailable_uses_cached_keys() {
     );
 }
 
+#[tokio::test]
+async fn test_jwks_concurrent_misses_share_one_fetch() {
+    use mouchak_mail_server::auth::JwksClient;
+    use std::time::Duration;
+    use wiremock::{Mock, MockServer, ResponseTemplate, matchers::path};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(path("/.well-known/jwks.json"))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_string(r#"{"keys":[]}"#)
+                .set_delay(Duration::from_millis(100)),
+        )
+        .expect(1)
+        .named("single JWKS fetch")
+        .mount(&mock_server)
+        .await;
+
+    let jwks_url = format!("{}/.well-known/jwks.json", mock_server.uri());
+    let client = JwksClient::new_with_ttl(jwks_url, Duration::from_secs(600))
+        .with_min_refresh_interval(Duration::from_secs(60));
+
+    // A burst of requests with bogus kids while the first fetch is in flight
+    let lookups = (0..20).map(|i| {
+        let client = client.clone();
+        tokio::spawn(async move { client.get_verifying_key(&format!("bogus-{}", i)).await })
+    });
+    for handle in lookups {
+        assert!(handle.await.unwrap().is_none());
+    }
+
+    // Later misses inside the min refresh interval are negatively cached
+    assert!(client.get_verifying_key("bogus-late").await.is_none());
+
+    mock_server.verify().await;
+}
+
+#[tokio::test]
+async fn test_jwks_unknown_kid_refetches_after_min_interval() {
+    use mouchak_mail_server::auth::JwksClient;
+    use std::time::Duration;
+    use wiremock::{Mock, MockServer, ResponseTemplate, matchers::path};
+
+    let mock_server = MockServer::start().await;
+    Mock::given(path("/.well-known/jwks.json"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(r#"{"keys":[]}"#))
+        .expect(2)
+        .mount(&mock_server)
+        .await;
+
+    let jwks_url = format!("{}/.well-known/jwks.json", mock_server.uri());
+    let client = JwksClient::new_with_ttl(jwks_url, Duration::from_secs(600))
+        .with_min_refresh_interval(Duration::from_millis(50));
+
+    assert!(client.get_verifying_key("unknown").await.is_none());
+    assert!(client.get_verifying_key("unknown").await.is_none());
+
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    assert!(client.get_verifying_key("unknown").await.is_none());
+
+    mock_server.verify().await;
+}
+
 #[tokio::test]
 async fn test_rps_for_category_returns_correct_values() {
     use mouchak_mail_server::ratelimit::{ToolCategory, ToolRateLimits};
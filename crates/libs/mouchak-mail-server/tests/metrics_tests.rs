@@ -0,0 +1,115 @@
+//! Prometheus metrics tests
+//!
+//! Runs in its own test binary so the global recorder installed by
+//! `setup_metrics()` is the one rendered here.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use axum::{
+    Router,
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+};
+use mouchak_mail_common::config::AppConfig;
+use mouchak_mail_core::ModelManager;
+use mouchak_mail_mcp::tools::MouchakMailService;
+use mouchak_mail_server::auth::{AuthConfig, AuthMode};
+use mouchak_mail_server::ratelimit::RateLimitConfig;
+use mouchak_mail_server::{AppState, setup_metrics, telemetry, tools};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+use tower::ServiceExt;
+
+async fn create_test_state() -> (AppState, TempDir) {
+    let temp_dir = TempDir::new().expect("Failed to create temp dir");
+    let db_path = temp_dir.path().join("metrics.db");
+    let archive_root = temp_dir.path().join("archive");
+    std::fs::create_dir_all(&archive_root).unwrap();
+
+    let db = libsql::Builder::new_local(&db_path).build().await.unwrap();
+    let conn = db.connect().unwrap();
+    mouchak_mail_core::store::apply_migrations(&conn)
+        .await
+        .unwrap();
+
+    let app_config = Arc::new(AppConfig::default());
+    let mm = ModelManager::new_for_test(conn, archive_root, app_config);
+
+    let state = AppState {
+        mm,
+        metrics_handle: setup_metrics(),
+        start_time: Instant::now(),
+        auth_config: AuthConfig {
+            mode: AuthMode::None,
+            bearer_token: None,
+            jwks_url: None,
+            jwt_audience: None,
+            jwt_issuer: None,
+            allow_localhost: true,
+            trusted_proxies: vec![],
+        },
+        jwks_client: None,
+        ratelimit_config: RateLimitConfig::new(),
+    };
+
+    (state, temp_dir)
+}
+
+#[tokio::test]
+async fn test_prometheus_output_contains_metric_families() {
+    let (state, _temp) = create_test_state().await;
+
+    let app = Router::new()
+        .route("/api/health", get(tools::health_check))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            telemetry::http_metrics_middleware,
+        ))
+        .with_state(state.clone());
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/health")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // A failed MCP tool call goes through the same recording path as call_tool
+    let service = MouchakMailService::new_with_mm(Arc::new(state.mm.clone()), false);
+    let failed: Result<rmcp::model::CallToolResult, rmcp::ErrorData> =
+        Err(rmcp::ErrorData::invalid_params("Agent not found", None));
+    service
+        .record_tool_metric("send_message", &None, Duration::from_millis(12), &failed)
+        .await;
+
+    let output = state.metrics_handle.render();
+
+    assert!(output.contains("http_requests_total"), "{output}");
+    assert!(
+        output.contains("http_request_duration_seconds_bucket"),
+        "{output}"
+    );
+    assert!(output.contains("mcp_tool_calls_total"), "{output}");
+    assert!(
+        output.contains("mcp_tool_duration_seconds_bucket"),
+        "{output}"
+    );
+
+    let http_line = output
+        .lines()
+        .find(|l| l.starts_with("http_requests_total{") && l.contains("path=\"/api/health\""))
+        .expect("Health request should be counted");
+    assert!(http_line.contains("status=\"200\""), "{http_line}");
+
+    let tool_line = output
+        .lines()
+        .find(|l| l.starts_with("mcp_tool_calls_total{") && l.contains("tool=\"send_message\""))
+        .expect("Failed tool call should be counted");
+    assert!(tool_line.contains("status=\"error\""), "{tool_line}");
+}
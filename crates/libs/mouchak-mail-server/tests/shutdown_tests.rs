@@ -0,0 +1,107 @@
+//! Graceful shutdown tests
+//!
+//! Starts a real listener on an ephemeral port so connection draining is
+//! exercised end to end.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use axum::{Router, routing::get};
+use mouchak_mail_server::shutdown::{ShutdownHooks, serve_with_drain};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+async fn slow_handler() -> &'static str {
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    "done"
+}
+
+async fn start_server(
+    drain_timeout: Duration,
+) -> (
+    String,
+    oneshot::Sender<()>,
+    tokio::task::JoinHandle<std::io::Result<()>>,
+) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+
+    let app = Router::new().route("/slow", get(slow_handler));
+    let (signal_tx, signal_rx) = oneshot::channel::<()>();
+    let server = tokio::spawn(serve_with_drain(
+        listener,
+        app,
+        async move {
+            let _ = signal_rx.await;
+        },
+        drain_timeout,
+    ));
+
+    (base_url, signal_tx, server)
+}
+
+#[tokio::test]
+async fn test_in_flight_request_completes_after_shutdown_signal() {
+    let (base_url, signal_tx, server) = start_server(Duration::from_secs(5)).await;
+
+    let request = tokio::spawn(reqwest::get(format!("{}/slow", base_url)));
+
+    // Let the request reach the handler, then ask the server to stop
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    signal_tx.send(()).unwrap();
+
+    let response = request
+        .await
+        .unwrap()
+        .expect("in-flight request should complete");
+    assert_eq!(response.status(), reqwest::StatusCode::OK);
+    assert_eq!(response.text().await.unwrap(), "done");
+
+    tokio::time::timeout(Duration::from_secs(2), server)
+        .await
+        .expect("server should stop once drained")
+        .unwrap()
+        .unwrap();
+
+    // The listener is closed: new connections are refused
+    assert!(reqwest::get(format!("{}/slow", base_url)).await.is_err());
+}
+
+#[tokio::test]
+async fn test_drain_timeout_bounds_shutdown() {
+    let (base_url, signal_tx, server) = start_server(Duration::from_millis(50)).await;
+
+    let _request = tokio::spawn(reqwest::get(format!("{}/slow", base_url)));
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    signal_tx.send(()).unwrap();
+
+    // Returns after the drain timeout instead of waiting for the slow handler
+    tokio::time::timeout(Duration::from_millis(250), server)
+        .await
+        .expect("shutdown should not wait past the drain timeout")
+        .unwrap()
+        .unwrap();
+}
+
+#[tokio::test]
+async fn test_shutdown_hooks_cancel_background_tasks() {
+    let hooks = ShutdownHooks::new();
+    let stopped = Arc::new(AtomicBool::new(false));
+
+    let flag = stopped.clone();
+    hooks.spawn("ticker", |cancel| async move {
+        loop {
+            tokio::select! {
+                _ = cancel.cancelled() => break,
+                _ = tokio::time::sleep(Duration::from_millis(10)) => {}
+            }
+        }
+        flag.store(true, Ordering::SeqCst);
+    });
+
+    hooks.shutdown(Duration::from_secs(1)).await;
+    assert!(stopped.load(Ordering::SeqCst));
+    assert!(hooks.token().is_cancelled());
+}
@@ -39,18 +39,9 @@ async fn create_test_state() -> (AppState, TempDir) {
 
     // Apply migrations
     let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
-    let schema = include_str!("../../../../migrations/001_initial_schema.sql");
-    conn.execute_batch(schema).await.unwrap();
-    let schema002 = include_str!("../../../../migrations/002_agent_capabilities.sql");
-    conn.execute_batch(schema002).await.unwrap();
-    let schema3 = include_str!("../../../../migrations/003_tool_metrics.sql");
-    conn.execute_batch(schema3).await.unwrap();
-    let schema4 = include_str!("../../../../migrations/004_attachments.sql");
-    conn.execute_batch(schema4).await.unwrap();
-    let schema5 = include_str!("../../../../migrations/005_attachments_agent.sql");
-    conn.execute_batch(schema5).await.unwrap();
-    let schema6 = include_str!("../../../../migrations/006_query_indexes.sql");
-    conn.execute_batch(schema6).await.unwrap();
+    mouchak_mail_core::store::apply_migrations(&conn)
+        .await
+        .unwrap();
 
     let app_config = Arc::new(AppConfig::default());
     let mm = ModelManager::new_for_test(conn, archive_root, app_config);
@@ -70,6 +61,7 @@ async fn create_test_state() -> (AppState, TempDir) {
         jwt_audience: None,
         jwt_issuer: None,
         allow_localhost: true,
+        trusted_proxies: vec![],
     };
 
     let state = AppState {
@@ -158,6 +150,158 @@ mod health_tests {
         assert_eq!(body["status"], "ready");
         assert!(body["checks"]["database"]["ok"].as_bool().unwrap());
     }
+
+    fn create_probe_app(state: AppState) -> Router {
+        Router::new()
+            .route("/health", get(mouchak_mail_server::health_handler))
+            .route("/health/live", get(mouchak_mail_server::liveness_handler))
+            .route("/health/ready", get(mouchak_mail_server::ready_handler))
+            .with_state(state)
+    }
+
+    #[tokio::test]
+    async fn test_component_health_all_healthy() {
+        let (state, _temp) = create_test_state().await;
+        mouchak_mail_core::store::git_store::init_or_open_repo(&state.mm.repo_root).unwrap();
+        let app = create_probe_app(state);
+
+        let (status, body) = get_json(app.clone(), "/health").await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        assert_eq!(body["status"], "healthy");
+        assert!(body["version"].is_string());
+        assert!(body["uptime_seconds"].is_u64());
+        assert!(body["components"]["database"]["ok"].as_bool().unwrap());
+        assert!(body["components"]["database"]["latency_ms"].is_u64());
+        assert!(body["components"]["git_archive"]["ok"].as_bool().unwrap());
+        assert!(body["components"]["disk"]["ok"].as_bool().unwrap());
+
+        let (status, body) = get_json(app, "/health/ready").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "healthy");
+    }
+
+    #[tokio::test]
+    async fn test_component_health_degraded_without_archive() {
+        let (state, _temp) = create_test_state().await;
+        // Archive directory exists but was never initialized as a Git repo
+        let app = create_probe_app(state);
+
+        let (status, body) = get_json(app.clone(), "/health").await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(body["status"], "degraded");
+        assert!(body["components"]["database"]["ok"].as_bool().unwrap());
+        assert!(!body["components"]["git_archive"]["ok"].as_bool().unwrap());
+        assert!(body["components"]["git_archive"]["error"].is_string());
+
+        // Liveness doesn't depend on components
+        let (status, body) = get_json(app, "/health/live").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["status"], "alive");
+    }
+
+    #[test]
+    fn test_disk_check_threshold() {
+        let temp = TempDir::new().unwrap();
+
+        let ok = mouchak_mail_server::health::check_disk(temp.path(), 0);
+        assert!(ok.ok);
+
+        let starved = mouchak_mail_server::health::check_disk(temp.path(), u64::MAX);
+        if starved.total_bytes.is_some() {
+            assert!(!starved.ok);
+            assert!(starved.error.is_some());
+        }
+    }
+}
+
+// =============================================================================
+// Server Info Tests
+// =============================================================================
+
+mod server_info_tests {
+    use super::*;
+    use mouchak_mail_server::api::server_info;
+
+    fn create_app(state: AppState) -> Router {
+        Router::new()
+            .route("/api/server-info", get(server_info::server_info))
+            .with_state(state)
+    }
+
+    #[tokio::test]
+    async fn test_server_info_is_cacheable() {
+        let (state, _temp) = create_test_state().await;
+        let request = Request::builder()
+            .uri("/api/server-info")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = create_app(state).oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[axum::http::header::CACHE_CONTROL],
+            server_info::SERVER_INFO_CACHE_CONTROL
+        );
+    }
+
+    #[tokio::test]
+    async fn test_server_info_reflects_config() {
+        let (mut state, _temp) = create_test_state().await;
+
+        let (status, body) = get_json(create_app(state.clone()), "/api/server-info").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+        assert!(body["schema_version"].is_i64());
+        assert_eq!(body["features"]["worktrees"], false);
+        assert_eq!(body["features"]["read_tracking"], true);
+        assert!(body["limits"]["max_inbox_messages"].is_null());
+
+        let mut config = AppConfig::default();
+        config.mcp.git_identity_enabled = true;
+        config.quota.enabled = true;
+        config.quota.inbox_limit_count = 25;
+        config.messages.max_recipients = 2;
+        config.reservations.max_ttl_seconds = 120;
+        state.mm.app_config = Arc::new(config);
+
+        let (status, body) = get_json(create_app(state), "/api/server-info").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["features"]["worktrees"], true);
+        assert_eq!(body["features"]["git_identity"], true);
+        assert_eq!(body["features"]["quota"], true);
+        assert_eq!(body["limits"]["max_inbox_messages"], 25);
+        assert_eq!(body["limits"]["max_recipients"], 2);
+        assert_eq!(body["limits"]["max_reservation_ttl_seconds"], 120);
+    }
+}
+
+mod backup_tests {
+    use super::*;
+    use mouchak_mail_server::api::backup;
+
+    #[tokio::test]
+    async fn test_admin_backup_returns_path_and_size() {
+        let (mut state, temp) = create_test_state().await;
+        let backups = temp.path().join("backups");
+        let mut config = AppConfig::default();
+        config.backup.directory = Some(backups.clone());
+        state.mm.app_config = Arc::new(config);
+        let app = Router::new()
+            .route("/api/admin/backup", post(backup::create_backup))
+            .with_state(state);
+
+        let (status, body) = post_json(app, "/api/admin/backup", json!({})).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let path = body["path"].as_str().unwrap();
+        assert!(path.starts_with(&backups.display().to_string()));
+        assert!(body["size_bytes"].as_u64().unwrap() > 0);
+        assert_eq!(
+            std::fs::metadata(path).unwrap().len(),
+            body["size_bytes"].as_u64().unwrap()
+        );
+    }
 }
 
 // =============================================================================
@@ -251,7 +395,7 @@ mod project_tests {
         let (status, body) = get_json(app2, "/api/projects").await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body.as_array().unwrap().len() >= 1);
+        assert!(!body.as_array().unwrap().is_empty());
     }
 
     #[tokio::test]
@@ -336,6 +480,82 @@ mod agent_tests {
         assert_eq!(body["program"], "claude-code");
     }
 
+    #[tokio::test]
+    async fn test_register_agent_twice_updates_existing() {
+        let (state, _temp) = create_test_state().await;
+        let project_slug = setup_with_project(&state).await;
+
+        let app = Router::new()
+            .route("/api/agent/register", post(tools::register_agent))
+            .with_state(state);
+
+        let register = |model: &str, strict: bool| {
+            json!({
+                "project_slug": project_slug,
+                "name": "RestartAgent",
+                "program": "claude-code",
+                "model": model,
+                "strict": strict
+            })
+        };
+
+        let (status, first) =
+            post_json(app.clone(), "/api/agent/register", register("v1", false)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(first["created"], true);
+
+        let (status, second) =
+            post_json(app.clone(), "/api/agent/register", register("v2", false)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(second["created"], false);
+        assert_eq!(second["id"], first["id"]);
+        assert_eq!(second["model"], "v2");
+
+        let (status, _) = post_json(app, "/api/agent/register", register("v3", true)).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn test_register_agent_rejects_bad_names() {
+        let (state, _temp) = create_test_state().await;
+        let project_slug = setup_with_project(&state).await;
+
+        let app = Router::new()
+            .route("/api/agent/register", post(tools::register_agent))
+            .with_state(state);
+
+        let register = |name: &str| {
+            json!({
+                "project_slug": project_slug,
+                "name": name,
+                "program": "claude-code",
+                "model": "opus"
+            })
+        };
+
+        let (status, body) =
+            post_json(app.clone(), "/api/agent/register", register("../../etc")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "VALIDATION_ERROR");
+        assert!(
+            body["error"]
+                .as_str()
+                .unwrap()
+                .contains("'.' is not allowed")
+        );
+
+        let (status, _) = post_json(app.clone(), "/api/agent/register", register("Builder")).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, body) = post_json(app, "/api/agent/register", register("BUILDER")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(
+            body["error"]
+                .as_str()
+                .unwrap()
+                .contains("taken by 'Builder'")
+        );
+    }
+
     #[tokio::test]
     async fn test_whois_agent() {
         let (state, _temp) = create_test_state().await;
@@ -401,7 +621,7 @@ mod agent_tests {
 
         assert_eq!(status, StatusCode::OK);
         assert!(body["suggested_name"].is_string());
-        assert!(body["alternatives"].as_array().unwrap().len() > 0);
+        assert!(!body["alternatives"].as_array().unwrap().is_empty());
     }
 
     #[tokio::test]
@@ -438,9 +658,77 @@ mod agent_tests {
 
         assert_eq!(status, StatusCode::OK);
         let agents = body.as_array().unwrap();
-        assert!(agents.len() >= 1);
+        assert!(!agents.is_empty());
         assert!(agents.iter().any(|a| a["name"] == "ListTestAgent"));
     }
+
+    #[tokio::test]
+    async fn test_update_and_retire_agent() {
+        let (state, _temp) = create_test_state().await;
+        let project_slug = setup_with_project(&state).await;
+
+        let app = Router::new()
+            .route("/api/agent/register", post(tools::register_agent))
+            .with_state(state.clone());
+        post_json(
+            app,
+            "/api/agent/register",
+            json!({
+                "project_slug": project_slug,
+                "name": "Before",
+                "program": "test",
+                "model": "test"
+            }),
+        )
+        .await;
+
+        let app = Router::new()
+            .route(
+                "/api/project/{project_slug}/agent/{agent_name}",
+                axum::routing::patch(tools::update_agent).delete(tools::retire_agent),
+            )
+            .route(
+                "/api/projects/{project_slug}/agents",
+                get(tools::list_all_agents_for_project),
+            )
+            .with_state(state);
+
+        let request = Request::builder()
+            .method("PATCH")
+            .uri(format!("/api/project/{}/agent/Before", project_slug))
+            .header("Content-Type", "application/json")
+            .body(Body::from(
+                json!({"name": "After", "task_description": "Renamed"}).to_string(),
+            ))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body_bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body: Value = serde_json::from_slice(&body_bytes).unwrap();
+        assert_eq!(body["name"], "After");
+        assert_eq!(body["task_description"], "Renamed");
+
+        let request = Request::builder()
+            .method("DELETE")
+            .uri(format!("/api/project/{}/agent/After", project_slug))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body_bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body: Value = serde_json::from_slice(&body_bytes).unwrap();
+        assert!(body["retired_ts"].is_string());
+
+        let (status, body) = get_json(app, &format!("/api/projects/{}/agents", project_slug)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(
+            !body
+                .as_array()
+                .unwrap()
+                .iter()
+                .any(|a| a["name"] == "After")
+        );
+    }
 }
 
 // =============================================================================
@@ -529,199 +817,331 @@ mod message_tests {
         assert!(body["id"].as_i64().unwrap() > 0);
         assert_eq!(body["subject"], "Test Subject");
         assert_eq!(body["sender_name"], sender);
+        assert_eq!(body["archive_pending"], true);
     }
 
     #[tokio::test]
-    async fn test_send_message_with_cc_bcc() {
+    async fn test_send_message_ack_required_round_trip() {
         let (state, _temp) = create_test_state().await;
         let (project_slug, sender, recipient) = setup_with_agents(&state).await;
 
-        // Create a CC agent
-        let app = Router::new()
-            .route("/api/agent/register", post(tools::register_agent))
-            .with_state(state.clone());
-        post_json(
-            app,
-            "/api/agent/register",
-            json!({
-                "project_slug": project_slug,
-                "name": "CcAgent",
-                "program": "test",
-                "model": "test"
-            }),
-        )
-        .await;
-
         let app = Router::new()
             .route("/api/message/send", post(tools::send_message))
+            .route("/api/messages/{message_id}", get(tools::get_message))
+            .route("/api/inbox", post(tools::list_inbox))
             .with_state(state);
 
+        let mut ids = Vec::new();
+        for (subject, ack) in [("Needs ack", Some(true)), ("No ack", None)] {
+            let mut payload = json!({
+                "project_slug": project_slug,
+                "sender_name": sender,
+                "recipient_names": [recipient],
+                "subject": subject,
+                "body_md": "Body"
+            });
+            if let Some(ack) = ack {
+                payload["ack_required"] = json!(ack);
+            }
+            let (status, body) = post_json(app.clone(), "/api/message/send", payload).await;
+            assert_eq!(status, StatusCode::OK);
+            ids.push(body["id"].as_i64().unwrap());
+        }
+
+        let (status, body) = get_json(app.clone(), &format!("/api/messages/{}", ids[0])).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["ack_required"], true);
+        let (_, body) = get_json(app.clone(), &format!("/api/messages/{}", ids[1])).await;
+        assert_eq!(body["ack_required"], false);
+
         let (status, body) = post_json(
             app,
-            "/api/message/send",
+            "/api/inbox",
             json!({
                 "project_slug": project_slug,
-                "sender_name": sender,
-                "recipient_names": [recipient],
-                "cc_names": ["CcAgent"],
-                "subject": "CC Test",
-                "body_md": "Test with CC"
+                "agent_name": recipient,
+                "limit": 10
             }),
         )
         .await;
-
         assert_eq!(status, StatusCode::OK);
-        assert!(body["id"].as_i64().unwrap() > 0);
+        let inbox = body.as_array().unwrap();
+        let ack_of = |id: i64| {
+            inbox
+                .iter()
+                .find(|m| m["id"] == id)
+                .map(|m| m["ack_required"].clone())
+        };
+        assert_eq!(ack_of(ids[0]), Some(json!(true)));
+        assert_eq!(ack_of(ids[1]), Some(json!(false)));
     }
 
     #[tokio::test]
-    async fn test_list_inbox() {
+    async fn test_send_message_size_limits() {
         let (state, _temp) = create_test_state().await;
         let (project_slug, sender, recipient) = setup_with_agents(&state).await;
 
-        // Send a message first
         let app = Router::new()
             .route("/api/message/send", post(tools::send_message))
-            .with_state(state.clone());
-        post_json(
-            app,
-            "/api/message/send",
-            json!({
-                "project_slug": project_slug,
-                "sender_name": sender,
-                "recipient_names": [recipient],
-                "subject": "Inbox Test",
-                "body_md": "Check inbox"
-            }),
-        )
-        .await;
+            .with_state(state);
+        let send = |subject: String, body_md: String| {
+            post_json(
+                app.clone(),
+                "/api/message/send",
+                json!({
+                    "project_slug": project_slug,
+                    "sender_name": sender,
+                    "recipient_names": [recipient],
+                    "subject": subject,
+                    "body_md": body_md
+                }),
+            )
+        };
+
+        // At the default limits: 500 characters, 256 KB
+        let (status, _) = send("s".repeat(500), "b".repeat(256 * 1024)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, body) = send("s".repeat(501), "Body".to_string()).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "VALIDATION_ERROR");
+        assert!(
+            body["error"]
+                .as_str()
+                .unwrap()
+                .contains("subject exceeds the limit of 500 characters")
+        );
+
+        let (status, body) = send("Big".to_string(), "b".repeat(256 * 1024 + 1)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "VALIDATION_ERROR");
+        assert!(
+            body["error"]
+                .as_str()
+                .unwrap()
+                .contains("body_md exceeds the limit of 262144 bytes")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unread_counts() {
+        let (state, _temp) = create_test_state().await;
+        let (project_slug, sender, recipient) = setup_with_agents(&state).await;
 
-        // Check inbox
         let app = Router::new()
-            .route("/api/inbox", post(tools::list_inbox))
+            .route("/api/message/send", post(tools::send_message))
+            .route(
+                "/api/project/{slug}/unread-counts",
+                get(mouchak_mail_server::api::unread_counts::project_unread_counts),
+            )
+            .route(
+                "/api/unread-counts",
+                get(mouchak_mail_server::api::unread_counts::all_unread_counts),
+            )
             .with_state(state);
 
-        let (status, body) = post_json(
-            app,
-            "/api/inbox",
-            json!({
-                "project_slug": project_slug,
-                "agent_name": recipient,
-                "limit": 10
-            }),
+        for subject in ["One", "Two"] {
+            let (status, _) = post_json(
+                app.clone(),
+                "/api/message/send",
+                json!({
+                    "project_slug": project_slug,
+                    "sender_name": sender,
+                    "recipient_names": [recipient],
+                    "subject": subject,
+                    "body_md": "Unread body"
+                }),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        let (status, body) = get_json(
+            app.clone(),
+            &format!("/api/project/{}/unread-counts", project_slug),
         )
         .await;
-
         assert_eq!(status, StatusCode::OK);
-        let messages = body.as_array().unwrap();
-        assert!(messages.len() >= 1);
-        assert!(messages.iter().any(|m| m["subject"] == "Inbox Test"));
+        assert_eq!(body["total"], 2);
+        let agents = body["agents"].as_array().unwrap();
+        let unread_for = |name: &str| {
+            agents
+                .iter()
+                .find(|a| a["agent_name"] == name)
+                .map(|a| a["unread"].as_i64().unwrap())
+        };
+        assert_eq!(unread_for(recipient.as_str()), Some(2));
+        assert_eq!(unread_for(sender.as_str()), Some(0));
+
+        let (status, body) = get_json(app.clone(), "/api/unread-counts").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total"], 2);
+        assert_eq!(body["projects"][0]["project_slug"], project_slug);
+
+        let (status, _) = get_json(app, "/api/project/no-such-project/unread-counts").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
     }
 
     #[tokio::test]
-    async fn test_list_outbox() {
+    async fn test_unified_inbox_date_range() {
         let (state, _temp) = create_test_state().await;
         let (project_slug, sender, recipient) = setup_with_agents(&state).await;
 
-        // Send a message
         let app = Router::new()
             .route("/api/message/send", post(tools::send_message))
-            .with_state(state.clone());
-        post_json(
-            app,
+            .route(
+                "/api/unified-inbox",
+                get(mouchak_mail_server::api::unified_inbox::unified_inbox_json),
+            )
+            .with_state(state);
+
+        let (status, _) = post_json(
+            app.clone(),
             "/api/message/send",
             json!({
                 "project_slug": project_slug,
                 "sender_name": sender,
                 "recipient_names": [recipient],
-                "subject": "Outbox Test",
-                "body_md": "Check outbox"
-            }),
-        )
-        .await;
-
-        // Check outbox
-        let app = Router::new()
-            .route("/api/outbox", post(tools::list_outbox))
-            .with_state(state);
-
-        let (status, body) = post_json(
-            app,
-            "/api/outbox",
-            json!({
-                "project_slug": project_slug,
-                "agent_name": sender,
-                "limit": 10
+                "subject": "Dated",
+                "body_md": "body"
             }),
         )
         .await;
+        assert_eq!(status, StatusCode::OK);
 
+        let now = chrono::Utc::now();
+        let ts = |t: chrono::DateTime<chrono::Utc>, offset_secs: i32| {
+            let offset = chrono::FixedOffset::east_opt(offset_secs).unwrap();
+            t.with_timezone(&offset).to_rfc3339().replace('+', "%2B")
+        };
+        let hour = chrono::Duration::hours(1);
+
+        // The same instants expressed in different offsets select the message
+        let uri = format!(
+            "/api/unified-inbox?since={}&until={}",
+            ts(now - hour, 19800),
+            ts(now + hour, -18000)
+        );
+        let (status, body) = get_json(app.clone(), &uri).await;
         assert_eq!(status, StatusCode::OK);
-        let messages = body.as_array().unwrap();
-        assert!(messages.len() >= 1);
+        assert_eq!(body["total_count"], 1);
+
+        let uri = format!("/api/unified-inbox?until={}", ts(now - hour, 0));
+        let (_, body) = get_json(app.clone(), &uri).await;
+        assert_eq!(body["total_count"], 0);
+
+        let uri = format!(
+            "/api/unified-inbox?since={}&until={}",
+            ts(now + hour, 0),
+            ts(now - hour, 0)
+        );
+        let (status, body) = get_json(app.clone(), &uri).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("until"));
+
+        // A timestamp without an offset is ambiguous and rejected
+        let (status, _) = get_json(app, "/api/unified-inbox?until=2026-01-01T00:00:00").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
     }
 
     #[tokio::test]
-    async fn test_search_messages() {
+    async fn test_inbox_sort_and_cursor() {
         let (state, _temp) = create_test_state().await;
         let (project_slug, sender, recipient) = setup_with_agents(&state).await;
 
-        // Send a message with unique content
         let app = Router::new()
             .route("/api/message/send", post(tools::send_message))
-            .with_state(state.clone());
-        post_json(
-            app,
-            "/api/message/send",
+            .route("/api/inbox", post(tools::list_inbox))
+            .route(
+                "/api/unified-inbox",
+                get(mouchak_mail_server::api::unified_inbox::unified_inbox_json),
+            )
+            .with_state(state);
+
+        let mut ids = Vec::new();
+        for importance in ["high", "normal", "urgent"] {
+            let (status, body) = post_json(
+                app.clone(),
+                "/api/message/send",
+                json!({
+                    "project_slug": project_slug,
+                    "sender_name": sender,
+                    "recipient_names": [recipient],
+                    "subject": importance,
+                    "body_md": "body",
+                    "importance": importance
+                }),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+            ids.push(body["id"].as_i64().unwrap());
+        }
+
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/inbox",
             json!({
                 "project_slug": project_slug,
-                "sender_name": sender,
-                "recipient_names": [recipient],
-                "subject": "Searchable Subject",
-                "body_md": "UniqueSearchKeyword123"
+                "agent_name": recipient,
+                "sort": "importance_desc"
             }),
         )
         .await;
+        assert_eq!(status, StatusCode::OK);
+        let subjects: Vec<&str> = body
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["subject"].as_str().unwrap())
+            .collect();
+        assert_eq!(subjects, ["urgent", "high", "normal"]);
+
+        // next_cursor carries the sort and continues it
+        let (status, body) =
+            get_json(app.clone(), "/api/unified-inbox?sort=created_asc&limit=2").await;
+        assert_eq!(status, StatusCode::OK);
+        let cursor = body["next_cursor"].as_str().unwrap().to_string();
+        assert_eq!(cursor, format!("created_asc:{}", ids[1]));
+        let (_, body) = get_json(
+            app.clone(),
+            &format!("/api/unified-inbox?sort=created_asc&cursor={}", cursor),
+        )
+        .await;
+        assert_eq!(body["messages"][0]["id"], ids[2]);
 
-        // Search for it
-        let app = Router::new()
-            .route("/api/messages/search", post(tools::search_messages))
-            .with_state(state);
+        // Switching sorts with a stale cursor is a validation error
+        let (status, body) = get_json(
+            app.clone(),
+            &format!("/api/unified-inbox?sort=importance_desc&cursor={}", cursor),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "VALIDATION_ERROR");
 
         let (status, body) = post_json(
-            app,
-            "/api/messages/search",
+            app.clone(),
+            "/api/inbox",
             json!({
                 "project_slug": project_slug,
-                "query": "UniqueSearchKeyword123"
+                "agent_name": recipient,
+                "cursor": cursor
             }),
         )
         .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "VALIDATION_ERROR");
 
-        assert_eq!(status, StatusCode::OK);
-        assert!(body["count"].as_i64().unwrap() >= 1);
+        let (status, body) = get_json(app, "/api/unified-inbox?sort=newest").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "VALIDATION_ERROR");
     }
-}
-
-// =============================================================================
-// File Reservation Tests
-// =============================================================================
 
-mod file_reservation_tests {
-    use super::*;
-
-    async fn setup_with_agent(state: &AppState) -> (String, String) {
-        let app = Router::new()
-            .route("/api/project/ensure", post(tools::ensure_project))
-            .with_state(state.clone());
-        let (_, proj) = post_json(
-            app,
-            "/api/project/ensure",
-            json!({"human_key": "res-test-proj"}),
-        )
-        .await;
-        let project_slug = proj["slug"].as_str().unwrap().to_string();
+    #[tokio::test]
+    async fn test_send_message_with_cc_bcc() {
+        let (state, _temp) = create_test_state().await;
+        let (project_slug, sender, recipient) = setup_with_agents(&state).await;
 
+        // Create a CC agent
         let app = Router::new()
             .route("/api/agent/register", post(tools::register_agent))
             .with_state(state.clone());
@@ -730,691 +1150,1157 @@ mod file_reservation_tests {
             "/api/agent/register",
             json!({
                 "project_slug": project_slug,
-                "name": "ResAgent",
+                "name": "CcAgent",
                 "program": "test",
                 "model": "test"
             }),
         )
         .await;
 
-        (project_slug, "ResAgent".to_string())
-    }
-
-    #[tokio::test]
-    async fn test_file_reservation_paths() {
-        let (state, _temp) = create_test_state().await;
-        let (project_slug, agent_name) = setup_with_agent(&state).await;
-
         let app = Router::new()
-            .route(
-                "/api/file_reservations/paths",
-                post(tools::file_reservation_paths),
-            )
+            .route("/api/message/send", post(tools::send_message))
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/file_reservations/paths",
+            "/api/message/send",
             json!({
                 "project_slug": project_slug,
-                "agent_name": agent_name,
-                "paths": ["src/**/*.rs", "Cargo.toml"],
-                "exclusive": true,
-                "ttl_seconds": 3600
+                "sender_name": sender,
+                "recipient_names": [recipient],
+                "cc_names": ["CcAgent"],
+                "subject": "CC Test",
+                "body_md": "Test with CC"
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert_eq!(body["granted"].as_array().unwrap().len(), 2);
-        assert!(body["conflicts"].as_array().unwrap().is_empty());
+        assert!(body["id"].as_i64().unwrap() > 0);
     }
 
     #[tokio::test]
-    async fn test_list_file_reservations() {
+    async fn test_send_message_delivery_report() {
         let (state, _temp) = create_test_state().await;
-        let (project_slug, agent_name) = setup_with_agent(&state).await;
+        let (project_slug, sender, recipient) = setup_with_agents(&state).await;
 
-        // Create a reservation
         let app = Router::new()
-            .route(
-                "/api/file_reservations/paths",
-                post(tools::file_reservation_paths),
+            .route("/api/message/send", post(tools::send_message))
+            .route("/api/inbox", post(tools::list_inbox))
+            .with_state(state);
+        let send = |recipients: Value, cc: Value, allow_partial: bool| {
+            json!({
+                "project_slug": project_slug,
+                "sender_name": sender,
+                "recipient_names": recipients,
+                "cc_names": cc,
+                "subject": "Delivery report",
+                "body_md": "Body",
+                "allow_partial": allow_partial
+            })
+        };
+        let inbox_len = |app: Router| {
+            let project_slug = project_slug.clone();
+            let recipient = recipient.clone();
+            async move {
+                let (_, body) = post_json(
+                    app,
+                    "/api/inbox",
+                    json!({"project_slug": project_slug, "agent_name": recipient}),
+                )
+                .await;
+                body.as_array().unwrap().len()
+            }
+        };
+
+        // Every recipient resolves
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/message/send",
+            send(json!([recipient]), json!(null), false),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["message_id"], body["id"]);
+        assert_eq!(body["delivered"], json!([recipient]));
+        assert_eq!(body["failed"], json!([]));
+        assert_eq!(body["partial_success"], false);
+        assert_eq!(inbox_len(app.clone()).await, 1);
+
+        // None resolves: rejected either way, with every failure listed
+        for allow_partial in [false, true] {
+            let (status, body) = post_json(
+                app.clone(),
+                "/api/message/send",
+                send(json!(["Ghost"]), json!(["other::Nobody"]), allow_partial),
             )
+            .await;
+            assert_eq!(status, StatusCode::NOT_FOUND);
+            assert_eq!(body["code"], "NOT_FOUND");
+            assert_eq!(
+                body["failed"],
+                json!([
+                    {"name": "Ghost", "code": "AGENT_NOT_FOUND"},
+                    {"name": "other::Nobody", "code": "PROJECT_NOT_FOUND"}
+                ])
+            );
+        }
+
+        // Some resolve: atomic by default, nothing is sent
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/message/send",
+            send(json!([recipient, "Ghost"]), json!(null), false),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(
+            body["failed"],
+            json!([{"name": "Ghost", "code": "AGENT_NOT_FOUND"}])
+        );
+        assert_eq!(inbox_len(app.clone()).await, 1);
+
+        // ...or delivered to the rest with allow_partial
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/message/send",
+            send(json!([recipient, "Ghost"]), json!(null), true),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["delivered"], json!([recipient]));
+        assert_eq!(
+            body["failed"],
+            json!([{"name": "Ghost", "code": "AGENT_NOT_FOUND"}])
+        );
+        assert_eq!(body["partial_success"], true);
+        assert_eq!(inbox_len(app).await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_list_inbox() {
+        let (state, _temp) = create_test_state().await;
+        let (project_slug, sender, recipient) = setup_with_agents(&state).await;
+
+        // Send a message first
+        let app = Router::new()
+            .route("/api/message/send", post(tools::send_message))
             .with_state(state.clone());
         post_json(
             app,
-            "/api/file_reservations/paths",
+            "/api/message/send",
             json!({
                 "project_slug": project_slug,
-                "agent_name": agent_name,
-                "paths": ["test/**"],
-                "exclusive": true
+                "sender_name": sender,
+                "recipient_names": [recipient],
+                "subject": "Inbox Test",
+                "body_md": "Check inbox"
             }),
         )
         .await;
 
-        // List reservations
+        // Check inbox
         let app = Router::new()
-            .route(
-                "/api/file_reservations/list",
-                post(tools::list_file_reservations),
-            )
+            .route("/api/inbox", post(tools::list_inbox))
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/file_reservations/list",
+            "/api/inbox",
             json!({
-                "project_slug": project_slug
+                "project_slug": project_slug,
+                "agent_name": recipient,
+                "limit": 10
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body.as_array().unwrap().len() >= 1);
+        let messages = body.as_array().unwrap();
+        assert!(!messages.is_empty());
+        assert!(messages.iter().any(|m| m["subject"] == "Inbox Test"));
     }
 
     #[tokio::test]
-    async fn test_release_file_reservation() {
+    async fn test_send_message_to_other_project() {
         let (state, _temp) = create_test_state().await;
-        let (project_slug, agent_name) = setup_with_agent(&state).await;
+        let (project_slug, sender, _) = setup_with_agents(&state).await;
 
-        // Create reservation
         let app = Router::new()
-            .route(
-                "/api/file_reservations/paths",
-                post(tools::file_reservation_paths),
-            )
+            .route("/api/project/ensure", post(tools::ensure_project))
+            .with_state(state.clone());
+        let (_, peer) = post_json(
+            app,
+            "/api/project/ensure",
+            json!({"human_key": "msg-peer-proj"}),
+        )
+        .await;
+        let peer_slug = peer["slug"].as_str().unwrap().to_string();
+        let app = Router::new()
+            .route("/api/agent/register", post(tools::register_agent))
             .with_state(state.clone());
         post_json(
             app,
-            "/api/file_reservations/paths",
+            "/api/agent/register",
             json!({
-                "project_slug": project_slug,
-                "agent_name": agent_name,
-                "paths": ["release-test/**"],
-                "exclusive": true
+                "project_slug": peer_slug,
+                "name": "PeerAgent",
+                "program": "test",
+                "model": "test"
             }),
         )
         .await;
 
-        // Release it
+        let send = json!({
+            "project_slug": project_slug,
+            "sender_name": sender,
+            "recipient_names": [format!("{}::PeerAgent", peer_slug)],
+            "subject": "Across projects",
+            "body_md": "Hello from next door"
+        });
+
+        // A caller scoped to the sender's project needs allowed_peers
+        let scoped = mouchak_mail_core::Ctx::scoped(0, None, vec![project_slug.clone()]);
         let app = Router::new()
-            .route(
-                "/api/file_reservations/release",
-                post(tools::release_file_reservation),
-            )
-            .with_state(state);
+            .route("/api/message/send", post(tools::send_message))
+            .layer(axum::Extension(scoped))
+            .with_state(state.clone());
+        let (status, body) = post_json(app, "/api/message/send", send.clone()).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["code"], "FORBIDDEN_CROSS_PROJECT");
+
+        let app = Router::new()
+            .route("/api/message/send", post(tools::send_message))
+            .with_state(state.clone());
+        let (status, _) = post_json(app, "/api/message/send", send).await;
+        assert_eq!(status, StatusCode::OK);
 
+        let app = Router::new()
+            .route("/api/inbox", post(tools::list_inbox))
+            .with_state(state);
         let (status, body) = post_json(
             app,
-            "/api/file_reservations/release",
+            "/api/inbox",
             json!({
-                "project_slug": project_slug,
-                "agent_name": agent_name,
-                "paths": ["release-test/**"]
+                "project_slug": peer_slug,
+                "agent_name": "PeerAgent",
+                "limit": 10
             }),
         )
         .await;
-
         assert_eq!(status, StatusCode::OK);
-        assert_eq!(body["released_count"], 1);
+        let messages = body.as_array().unwrap();
+        assert_eq!(messages.len(), 1);
+        assert_eq!(messages[0]["subject"], "Across projects");
+        assert_eq!(messages[0]["project_slug"], project_slug);
     }
 
     #[tokio::test]
-    async fn test_list_all_locks() {
+    async fn test_list_outbox() {
         let (state, _temp) = create_test_state().await;
-        let (project_slug, agent_name) = setup_with_agent(&state).await;
+        let (project_slug, sender, recipient) = setup_with_agents(&state).await;
 
-        // Create a lock
+        // Send a message
         let app = Router::new()
-            .route(
-                "/api/file_reservations/paths",
-                post(tools::file_reservation_paths),
-            )
+            .route("/api/message/send", post(tools::send_message))
             .with_state(state.clone());
         post_json(
             app,
-            "/api/file_reservations/paths",
+            "/api/message/send",
             json!({
                 "project_slug": project_slug,
-                "agent_name": agent_name,
-                "paths": ["all-locks-test/**"],
-                "exclusive": true
+                "sender_name": sender,
+                "recipient_names": [recipient],
+                "subject": "Outbox Test",
+                "body_md": "Check outbox"
             }),
         )
         .await;
 
-        // List all locks
+        // Check outbox
         let app = Router::new()
-            .route("/api/locks", get(tools::list_all_locks))
+            .route("/api/outbox", post(tools::list_outbox))
             .with_state(state);
 
-        let (status, body) = get_json(app, "/api/locks").await;
+        let (status, body) = post_json(
+            app,
+            "/api/outbox",
+            json!({
+                "project_slug": project_slug,
+                "agent_name": sender,
+                "limit": 10
+            }),
+        )
+        .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body.as_array().unwrap().len() >= 1);
+        let messages = body.as_array().unwrap();
+        assert!(!messages.is_empty());
     }
-}
 
-// =============================================================================
-// Thread Tests
-// =============================================================================
+    #[tokio::test]
+    async fn test_agent_outbox_endpoint() {
+        let (state, _temp) = create_test_state().await;
+        let (project_slug, sender, recipient) = setup_with_agents(&state).await;
 
-mod thread_tests {
-    use super::*;
+        for subject in ["First", "Second"] {
+            let app = Router::new()
+                .route("/api/message/send", post(tools::send_message))
+                .with_state(state.clone());
+            post_json(
+                app,
+                "/api/message/send",
+                json!({
+                    "project_slug": project_slug,
+                    "sender_name": sender,
+                    "recipient_names": [recipient],
+                    "subject": subject,
+                    "body_md": "Outbox body"
+                }),
+            )
+            .await;
+        }
 
-    async fn setup_with_thread(state: &AppState) -> (String, String) {
-        // Create project
         let app = Router::new()
-            .route("/api/project/ensure", post(tools::ensure_project))
-            .with_state(state.clone());
-        let (_, proj) = post_json(
+            .route(
+                "/api/project/{slug}/agent/{name}/outbox",
+                get(mouchak_mail_server::api::outbox::agent_outbox),
+            )
+            .with_state(state);
+
+        let (status, body) = get_json(
+            app.clone(),
+            &format!(
+                "/api/project/{}/agent/{}/outbox?limit=1",
+                project_slug, sender
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let page = body.as_array().unwrap();
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0]["subject"], "Second");
+        assert_eq!(page[0]["recipients"][0]["name"], recipient.as_str());
+        assert_eq!(page[0]["recipients"][0]["recipient_type"], "to");
+        assert!(page[0]["recipients"][0]["ack_ts"].is_null());
+
+        let cursor = page[0]["id"].as_i64().unwrap();
+        let (status, body) = get_json(
+            app.clone(),
+            &format!(
+                "/api/project/{}/agent/{}/outbox?limit=1&cursor={}",
+                project_slug, sender, cursor
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body[0]["subject"], "First");
+
+        let (status, _) = get_json(
             app,
-            "/api/project/ensure",
-            json!({"human_key": "thread-test-proj"}),
+            &format!("/api/project/{}/agent/NoSuchAgent/outbox", project_slug),
         )
         .await;
-        let project_slug = proj["slug"].as_str().unwrap().to_string();
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
 
-        // Create agents
-        for name in ["ThreadSender", "ThreadRecipient"] {
+    #[tokio::test]
+    async fn test_agent_activity_endpoint() {
+        let (state, _temp) = create_test_state().await;
+        let (project_slug, sender, recipient) = setup_with_agents(&state).await;
+
+        for (from, to) in [(&sender, &recipient), (&recipient, &sender)] {
             let app = Router::new()
-                .route("/api/agent/register", post(tools::register_agent))
+                .route("/api/message/send", post(tools::send_message))
                 .with_state(state.clone());
             post_json(
                 app,
-                "/api/agent/register",
+                "/api/message/send",
                 json!({
                     "project_slug": project_slug,
-                    "name": name,
-                    "program": "test",
-                    "model": "test"
+                    "sender_name": from,
+                    "recipient_names": [to],
+                    "subject": format!("From {}", from),
+                    "body_md": "Activity body"
                 }),
             )
             .await;
         }
 
-        // Send message with thread_id
+        let app = Router::new()
+            .route(
+                "/api/project/{slug}/agent/{name}/activity",
+                get(mouchak_mail_server::api::agent_activity::agent_activity),
+            )
+            .with_state(state);
+
+        let (status, body) = get_json(
+            app.clone(),
+            &format!("/api/project/{}/agent/{}/activity", project_slug, sender),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["agent"]["name"], sender.as_str());
+        assert!(body["capabilities"].is_array());
+        assert!(body["file_reservations"].as_array().unwrap().is_empty());
+        let timeline = body["timeline"].as_array().unwrap();
+        assert_eq!(timeline.len(), 2);
+        let directions: Vec<&str> = timeline
+            .iter()
+            .map(|e| e["direction"].as_str().unwrap())
+            .collect();
+        assert!(directions.contains(&"sent") && directions.contains(&"received"));
+        assert!(
+            timeline
+                .iter()
+                .all(|e| e["counterpart"] == recipient.as_str())
+        );
+
+        let (status, body) = get_json(
+            app.clone(),
+            &format!(
+                "/api/project/{}/agent/{}/activity?limit=1",
+                project_slug, sender
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["timeline"].as_array().unwrap().len(), 1);
+
+        let (status, _) = get_json(
+            app,
+            &format!("/api/project/{}/agent/NoSuchAgent/activity", project_slug),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_agent_pending_acks_endpoint() {
+        let (state, _temp) = create_test_state().await;
+        let (project_slug, sender, recipient) = setup_with_agents(&state).await;
+
         let app = Router::new()
             .route("/api/message/send", post(tools::send_message))
             .with_state(state.clone());
-        post_json(
+        let (_, sent) = post_json(
             app,
             "/api/message/send",
             json!({
                 "project_slug": project_slug,
-                "sender_name": "ThreadSender",
-                "recipient_names": ["ThreadRecipient"],
-                "subject": "Thread Test",
-                "body_md": "Message in thread",
-                "thread_id": "TEST-THREAD-001"
+                "sender_name": sender,
+                "recipient_names": [recipient],
+                "subject": "Confirm the rollout",
+                "body_md": "Ack please",
+                "ack_required": true
             }),
         )
         .await;
-
-        (project_slug, "TEST-THREAD-001".to_string())
-    }
-
-    #[tokio::test]
-    async fn test_get_thread() {
-        let (state, _temp) = create_test_state().await;
-        let (project_slug, thread_id) = setup_with_thread(&state).await;
+        let message_id = sent["id"].as_i64().unwrap();
 
         let app = Router::new()
-            .route("/api/thread", post(tools::get_thread))
+            .route(
+                "/api/project/{slug}/agent/{name}/pending-acks",
+                get(mouchak_mail_server::api::pending_acks::pending_acks),
+            )
+            .route("/api/inbox", post(tools::list_inbox))
+            .route("/api/message/acknowledge", post(tools::acknowledge_message))
             .with_state(state);
+        let pending_uri = format!(
+            "/api/project/{}/agent/{}/pending-acks",
+            project_slug, recipient
+        );
 
-        let (status, body) = post_json(
-            app,
-            "/api/thread",
+        let (status, body) = get_json(app.clone(), &pending_uri).await;
+        assert_eq!(status, StatusCode::OK);
+        let pending = body.as_array().unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0]["message_id"], message_id);
+        assert_eq!(pending[0]["sender_name"], sender.as_str());
+        assert_eq!(pending[0]["days_outstanding"], 0);
+
+        let inbox = json!({
+            "project_slug": project_slug,
+            "agent_name": recipient,
+            "limit": 10
+        });
+        let (_, body) = post_json(app.clone(), "/api/inbox", inbox.clone()).await;
+        assert_eq!(body[0]["needs_ack"], true);
+
+        post_json(
+            app.clone(),
+            "/api/message/acknowledge",
             json!({
                 "project_slug": project_slug,
-                "thread_id": thread_id
+                "agent_name": recipient,
+                "message_id": message_id
             }),
         )
         .await;
+        let (_, body) = get_json(app.clone(), &pending_uri).await;
+        assert!(body.as_array().unwrap().is_empty());
+        let (_, body) = post_json(app.clone(), "/api/inbox", inbox).await;
+        assert!(body[0].get("needs_ack").is_none());
 
-        assert_eq!(status, StatusCode::OK);
-        assert!(body.as_array().unwrap().len() >= 1);
+        let (status, _) = get_json(
+            app,
+            &format!(
+                "/api/project/{}/agent/NoSuchAgent/pending-acks",
+                project_slug
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
     }
 
     #[tokio::test]
-    async fn test_list_threads() {
+    async fn test_project_audit_log() {
         let (state, _temp) = create_test_state().await;
-        let (project_slug, _) = setup_with_thread(&state).await;
+        let (project_slug, agent_name, _) = setup_with_agents(&state).await;
 
         let app = Router::new()
-            .route("/api/threads", post(tools::list_threads))
+            .route(
+                "/api/file_reservations/paths",
+                post(tools::file_reservation_paths),
+            )
+            .route(
+                "/api/file_reservations/force_release",
+                post(tools::force_release_reservation),
+            )
+            .route(
+                "/api/project/{slug}/audit",
+                get(mouchak_mail_server::api::audit::project_audit),
+            )
             .with_state(state);
 
-        let (status, body) = post_json(
-            app,
-            "/api/threads",
+        let (_, body) = post_json(
+            app.clone(),
+            "/api/file_reservations/paths",
             json!({
-                "project_slug": project_slug
+                "project_slug": project_slug,
+                "agent_name": agent_name,
+                "paths": ["a.rs", "b.rs"],
+                "exclusive": true,
+                "ttl_seconds": 3600
             }),
         )
         .await;
+        let ids: Vec<i64> = body["granted"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|g| g["id"].as_i64().unwrap())
+            .collect();
+        for id in &ids {
+            let (status, _) = post_json(
+                app.clone(),
+                "/api/file_reservations/force_release",
+                json!({ "reservation_id": id }),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+        }
 
+        let uri = format!("/api/project/{}/audit", project_slug);
+        let (status, body) = get_json(
+            app.clone(),
+            &format!("{}?action=reservation.force_release&limit=1", uri),
+        )
+        .await;
         assert_eq!(status, StatusCode::OK);
-        assert!(body.as_array().unwrap().len() >= 1);
+        assert_eq!(body["items"].as_array().unwrap().len(), 1);
+        assert_eq!(body["items"][0]["action"], "reservation.force_release");
+        assert_eq!(body["items"][0]["entity_id"], ids[1]);
+        let cursor = body["next_cursor"].as_i64().unwrap();
+
+        let (_, body) = get_json(app.clone(), &format!("{}?limit=1&cursor={}", uri, cursor)).await;
+        assert_eq!(body["items"][0]["entity_id"], ids[0]);
+
+        let (_, body) = get_json(app.clone(), &format!("{}?entity_type=agent", uri)).await;
+        assert!(body["items"].as_array().unwrap().is_empty());
+        assert!(body["next_cursor"].is_null());
+
+        let (status, _) = get_json(app.clone(), &format!("{}?action=bogus", uri)).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = get_json(app, "/api/project/no-such-project/audit").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
     }
 
     #[tokio::test]
-    async fn test_summarize_thread() {
+    async fn test_bulk_mark_read_and_export() {
         let (state, _temp) = create_test_state().await;
-        let (project_slug, thread_id) = setup_with_thread(&state).await;
+        let (project_slug, sender, recipient) = setup_with_agents(&state).await;
+
+        let mut ids = Vec::new();
+        for subject in ["Bulk one", "Bulk two", "Bulk three"] {
+            let app = Router::new()
+                .route("/api/message/send", post(tools::send_message))
+                .with_state(state.clone());
+            let (_, body) = post_json(
+                app,
+                "/api/message/send",
+                json!({
+                    "project_slug": project_slug,
+                    "sender_name": sender,
+                    "recipient_names": [recipient],
+                    "subject": subject,
+                    "body_md": "Bulk body"
+                }),
+            )
+            .await;
+            ids.push(body["id"].as_i64().unwrap());
+        }
 
         let app = Router::new()
-            .route("/api/thread/summarize", post(tools::summarize_thread))
+            .route(
+                "/api/messages/mark-read",
+                post(mouchak_mail_server::api::messages::mark_read_batch),
+            )
+            .route(
+                "/api/export/messages",
+                post(mouchak_mail_server::api::export::export_messages),
+            )
             .with_state(state);
 
         let (status, body) = post_json(
-            app,
-            "/api/thread/summarize",
+            app.clone(),
+            "/api/messages/mark-read",
             json!({
                 "project_slug": project_slug,
-                "thread_id": thread_id,
-                "no_llm": true
+                "agent_name": recipient,
+                "message_ids": [ids[0], ids[1]]
             }),
         )
         .await;
-
         assert_eq!(status, StatusCode::OK);
-        assert_eq!(body["thread_id"], thread_id);
-        assert!(body["message_count"].as_i64().unwrap() >= 1);
-        assert!(body["summary"].is_string());
-    }
-}
+        assert_eq!(body["marked"], json!([ids[0], ids[1]]));
+        assert_eq!(body["requested"], 2);
 
-// =============================================================================
-// Build Slot Tests
-// =============================================================================
+        // Without an agent every recipient is marked; already-read ids are skipped
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/messages/mark-read",
+            json!({ "message_ids": ids }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["marked"], json!([ids[2]]));
 
-mod build_slot_tests {
-    use super::*;
+        let (status, _) = post_json(
+            app.clone(),
+            "/api/messages/mark-read",
+            json!({ "agent_name": recipient, "message_ids": ids }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
 
-    async fn setup_with_agent(state: &AppState) -> (String, String) {
-        let app = Router::new()
-            .route("/api/project/ensure", post(tools::ensure_project))
-            .with_state(state.clone());
-        let (_, proj) = post_json(
-            app,
-            "/api/project/ensure",
-            json!({"human_key": "build-test-proj"}),
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/export/messages",
+            json!({ "message_ids": [ids[0], ids[2]], "format": "json" }),
         )
         .await;
-        let project_slug = proj["slug"].as_str().unwrap().to_string();
+        assert_eq!(status, StatusCode::OK);
+        let exported = body.as_array().unwrap();
+        assert_eq!(exported.len(), 2);
+        assert!(exported.iter().all(|m| m["subject"] != "Bulk two"));
 
-        let app = Router::new()
-            .route("/api/agent/register", post(tools::register_agent))
-            .with_state(state.clone());
-        post_json(
+        let (status, _) = post_json(
             app,
-            "/api/agent/register",
-            json!({
-                "project_slug": project_slug,
-                "name": "BuildAgent",
-                "program": "test",
-                "model": "test"
-            }),
+            "/api/export/messages",
+            json!({ "message_ids": [], "format": "json" }),
         )
         .await;
-
-        (project_slug, "BuildAgent".to_string())
+        assert_eq!(status, StatusCode::BAD_REQUEST);
     }
 
     #[tokio::test]
-    async fn test_acquire_build_slot() {
+    async fn test_export_negotiates_content_encoding() {
+        use mouchak_mail_core::model::export::ExportCompression;
+
         let (state, _temp) = create_test_state().await;
-        let (project_slug, agent_name) = setup_with_agent(&state).await;
+        let (project_slug, sender, recipient) = setup_with_agents(&state).await;
 
         let app = Router::new()
-            .route("/api/build_slots/acquire", post(tools::acquire_build_slot))
+            .route("/api/message/send", post(tools::send_message))
+            .route(
+                "/api/export",
+                post(mouchak_mail_server::api::export::export_mailbox),
+            )
             .with_state(state);
-
-        let (status, body) = post_json(
-            app,
-            "/api/build_slots/acquire",
+        post_json(
+            app.clone(),
+            "/api/message/send",
             json!({
                 "project_slug": project_slug,
-                "agent_name": agent_name,
-                "slot_name": "test-slot",
-                "ttl_seconds": 600
+                "sender_name": sender,
+                "recipient_names": [recipient],
+                "subject": "Compressed export",
+                "body_md": "Export body"
             }),
         )
         .await;
 
-        assert_eq!(status, StatusCode::OK);
-        assert!(body["slot_id"].as_i64().unwrap() > 0);
-        assert_eq!(body["slot_name"], "test-slot");
+        let export = |accept: &'static str| {
+            let request = Request::builder()
+                .method("POST")
+                .uri("/api/export")
+                .header("Content-Type", "application/json")
+                .header("Accept-Encoding", accept)
+                .body(Body::from(
+                    json!({
+                        "project_slug": project_slug,
+                        "format": "json",
+                        "compression": "gzip"
+                    })
+                    .to_string(),
+                ))
+                .unwrap();
+            app.clone().oneshot(request)
+        };
+
+        // Client accepts gzip: served precompressed
+        let response = export("br, gzip;q=0.8").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()["content-encoding"], "gzip");
+        assert_eq!(response.headers()["vary"], "accept-encoding");
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let decoded = ExportCompression::Gzip.decompress(&body).unwrap();
+        let messages: Value = serde_json::from_slice(&decoded).unwrap();
+        assert_eq!(messages[0]["subject"], "Compressed export");
+
+        // Client refuses gzip: decompressed on the server
+        let response = export("gzip;q=0, identity").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get("content-encoding").is_none());
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        let messages: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(messages[0]["subject"], "Compressed export");
     }
 
     #[tokio::test]
-    async fn test_renew_build_slot() {
+    async fn test_search_messages() {
         let (state, _temp) = create_test_state().await;
-        let (project_slug, agent_name) = setup_with_agent(&state).await;
+        let (project_slug, sender, recipient) = setup_with_agents(&state).await;
 
-        // Acquire first
+        // Send a message with unique content
         let app = Router::new()
-            .route("/api/build_slots/acquire", post(tools::acquire_build_slot))
+            .route("/api/message/send", post(tools::send_message))
             .with_state(state.clone());
-        let (_, slot) = post_json(
+        post_json(
             app,
-            "/api/build_slots/acquire",
+            "/api/message/send",
             json!({
                 "project_slug": project_slug,
-                "agent_name": agent_name,
-                "slot_name": "renew-slot",
-                "ttl_seconds": 300
+                "sender_name": sender,
+                "recipient_names": [recipient],
+                "subject": "Searchable Subject",
+                "body_md": "UniqueSearchKeyword123"
             }),
         )
         .await;
 
-        // Renew
+        // Search for it
         let app = Router::new()
-            .route("/api/build_slots/renew", post(tools::renew_build_slot))
+            .route("/api/messages/search", post(tools::search_messages))
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/build_slots/renew",
+            "/api/messages/search",
             json!({
-                "slot_id": slot["slot_id"],
-                "ttl_seconds": 600
+                "project_slug": project_slug,
+                "query": "UniqueSearchKeyword123"
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body["renewed"].as_bool().unwrap());
+        assert!(body["count"].as_i64().unwrap() >= 1);
     }
 
     #[tokio::test]
-    async fn test_release_build_slot() {
+    async fn test_search_messages_field_query_pages() {
         let (state, _temp) = create_test_state().await;
-        let (project_slug, agent_name) = setup_with_agent(&state).await;
-
-        // Acquire first
-        let app = Router::new()
-            .route("/api/build_slots/acquire", post(tools::acquire_build_slot))
-            .with_state(state.clone());
-        let (_, slot) = post_json(
-            app,
-            "/api/build_slots/acquire",
-            json!({
-                "project_slug": project_slug,
-                "agent_name": agent_name,
-                "slot_name": "release-slot"
-            }),
-        )
-        .await;
+        let (project_slug, sender, recipient) = setup_with_agents(&state).await;
 
-        // Release
-        let app = Router::new()
-            .route("/api/build_slots/release", post(tools::release_build_slot))
-            .with_state(state);
+        for (subject, body) in [
+            ("Deploy 1", "rollout notes"),
+            ("Deploy 2", "rollout notes"),
+            ("Unrelated", "rollout notes"),
+            ("Deploy 3", "rollout notes"),
+        ] {
+            let app = Router::new()
+                .route("/api/message/send", post(tools::send_message))
+                .with_state(state.clone());
+            post_json(
+                app,
+                "/api/message/send",
+                json!({
+                    "project_slug": project_slug,
+                    "sender_name": sender,
+                    "recipient_names": [recipient],
+                    "subject": subject,
+                    "body_md": body
+                }),
+            )
+            .await;
+        }
 
-        let (status, body) = post_json(
-            app,
-            "/api/build_slots/release",
-            json!({
-                "slot_id": slot["slot_id"]
-            }),
-        )
-        .await;
+        let search = |cursor: Option<i64>| {
+            let app = Router::new()
+                .route("/api/messages/search", post(tools::search_messages))
+                .with_state(state.clone());
+            post_json(
+                app,
+                "/api/messages/search",
+                json!({
+                    "project_slug": project_slug,
+                    "query": format!("from:{} subject:deploy rollout", sender),
+                    "limit": 2,
+                    "cursor": cursor
+                }),
+            )
+        };
 
+        let (status, first) = search(None).await;
         assert_eq!(status, StatusCode::OK);
-        assert!(body["released"].as_bool().unwrap());
+        assert_eq!(first["total_estimate"], 3);
+        let subjects: Vec<_> = first["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["subject"].as_str().unwrap().to_string())
+            .collect();
+        assert_eq!(subjects, vec!["Deploy 3", "Deploy 2"]);
+        let cursor = first["next_cursor"].as_i64().unwrap();
+
+        let (status, second) = search(Some(cursor)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(second["count"], 1);
+        assert_eq!(second["items"][0]["subject"], "Deploy 1");
+        assert!(second["next_cursor"].is_null());
     }
 }
 
 // =============================================================================
-// Contact Tests
+// File Reservation Tests
 // =============================================================================
 
-mod contact_tests {
+mod file_reservation_tests {
     use super::*;
 
-    async fn setup_with_agents(state: &AppState) -> (String, String, String) {
+    async fn setup_with_agent(state: &AppState) -> (String, String) {
         let app = Router::new()
             .route("/api/project/ensure", post(tools::ensure_project))
             .with_state(state.clone());
         let (_, proj) = post_json(
             app,
             "/api/project/ensure",
-            json!({"human_key": "contact-test-proj"}),
+            json!({"human_key": "res-test-proj"}),
         )
         .await;
         let project_slug = proj["slug"].as_str().unwrap().to_string();
 
-        for name in ["ContactRequester", "ContactTarget"] {
-            let app = Router::new()
-                .route("/api/agent/register", post(tools::register_agent))
-                .with_state(state.clone());
-            post_json(
-                app,
-                "/api/agent/register",
-                json!({
-                    "project_slug": project_slug,
-                    "name": name,
-                    "program": "test",
-                    "model": "test"
-                }),
-            )
-            .await;
-        }
-
-        (
-            project_slug,
-            "ContactRequester".to_string(),
-            "ContactTarget".to_string(),
+        let app = Router::new()
+            .route("/api/agent/register", post(tools::register_agent))
+            .with_state(state.clone());
+        post_json(
+            app,
+            "/api/agent/register",
+            json!({
+                "project_slug": project_slug,
+                "name": "ResAgent",
+                "program": "test",
+                "model": "test"
+            }),
         )
+        .await;
+
+        (project_slug, "ResAgent".to_string())
     }
 
     #[tokio::test]
-    async fn test_request_contact() {
+    async fn test_file_reservation_paths() {
         let (state, _temp) = create_test_state().await;
-        let (project_slug, requester, target) = setup_with_agents(&state).await;
+        let (project_slug, agent_name) = setup_with_agent(&state).await;
 
         let app = Router::new()
-            .route("/api/contact/request", post(tools::request_contact))
+            .route(
+                "/api/file_reservations/paths",
+                post(tools::file_reservation_paths),
+            )
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/contact/request",
+            "/api/file_reservations/paths",
             json!({
-                "from_project_slug": project_slug,
-                "from_agent_name": requester,
-                "to_project_slug": project_slug,
-                "to_agent_name": target,
-                "reason": "Test contact request"
+                "project_slug": project_slug,
+                "agent_name": agent_name,
+                "paths": ["src/**/*.rs", "Cargo.toml"],
+                "exclusive": true,
+                "ttl_seconds": 3600
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body["link_id"].as_i64().unwrap() > 0);
-        assert_eq!(body["status"], "pending");
+        assert_eq!(body["granted"].as_array().unwrap().len(), 2);
+        assert!(body["conflicts"].as_array().unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn test_respond_contact() {
+    async fn test_list_file_reservations() {
         let (state, _temp) = create_test_state().await;
-        let (project_slug, requester, target) = setup_with_agents(&state).await;
+        let (project_slug, agent_name) = setup_with_agent(&state).await;
 
-        // Request first
+        // Create a reservation
         let app = Router::new()
-            .route("/api/contact/request", post(tools::request_contact))
+            .route(
+                "/api/file_reservations/paths",
+                post(tools::file_reservation_paths),
+            )
             .with_state(state.clone());
-        let (_, req) = post_json(
+        post_json(
             app,
-            "/api/contact/request",
+            "/api/file_reservations/paths",
             json!({
-                "from_project_slug": project_slug,
-                "from_agent_name": requester,
-                "to_project_slug": project_slug,
-                "to_agent_name": target,
-                "reason": "Accept test"
+                "project_slug": project_slug,
+                "agent_name": agent_name,
+                "paths": ["test/**"],
+                "exclusive": true
             }),
         )
         .await;
 
-        // Accept
+        // List reservations
         let app = Router::new()
-            .route("/api/contact/respond", post(tools::respond_contact))
+            .route(
+                "/api/file_reservations/list",
+                post(tools::list_file_reservations),
+            )
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/contact/respond",
+            "/api/file_reservations/list",
             json!({
-                "link_id": req["link_id"],
-                "accept": true
+                "project_slug": project_slug
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert_eq!(body["status"], "accepted");
+        assert!(!body.as_array().unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn test_list_contacts() {
+    async fn test_release_file_reservation() {
         let (state, _temp) = create_test_state().await;
-        let (project_slug, requester, target) = setup_with_agents(&state).await;
+        let (project_slug, agent_name) = setup_with_agent(&state).await;
 
-        // Create and accept a contact
+        // Create reservation
         let app = Router::new()
-            .route("/api/contact/request", post(tools::request_contact))
+            .route(
+                "/api/file_reservations/paths",
+                post(tools::file_reservation_paths),
+            )
             .with_state(state.clone());
-        let (_, req) = post_json(
+        post_json(
             app,
-            "/api/contact/request",
+            "/api/file_reservations/paths",
             json!({
-                "from_project_slug": project_slug,
-                "from_agent_name": requester,
-                "to_project_slug": project_slug,
-                "to_agent_name": target,
-                "reason": "List test"
+                "project_slug": project_slug,
+                "agent_name": agent_name,
+                "paths": ["release-test/**"],
+                "exclusive": true
             }),
         )
         .await;
 
+        // Release it
         let app = Router::new()
-            .route("/api/contact/respond", post(tools::respond_contact))
-            .with_state(state.clone());
-        post_json(
+            .route(
+                "/api/file_reservations/release",
+                post(tools::release_file_reservation),
+            )
+            .with_state(state);
+
+        let (status, body) = post_json(
             app,
-            "/api/contact/respond",
+            "/api/file_reservations/release",
             json!({
-                "link_id": req["link_id"],
-                "accept": true
+                "project_slug": project_slug,
+                "agent_name": agent_name,
+                "paths": ["release-test/**"]
             }),
         )
         .await;
 
-        // List contacts
-        let app = Router::new()
-            .route("/api/contacts", post(tools::list_contacts))
-            .with_state(state);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["released_count"], 1);
+    }
 
-        let (status, body) = post_json(
+    #[tokio::test]
+    async fn test_list_all_locks() {
+        let (state, _temp) = create_test_state().await;
+        let (project_slug, agent_name) = setup_with_agent(&state).await;
+
+        // Create a lock
+        let app = Router::new()
+            .route(
+                "/api/file_reservations/paths",
+                post(tools::file_reservation_paths),
+            )
+            .with_state(state.clone());
+        post_json(
             app,
-            "/api/contacts",
+            "/api/file_reservations/paths",
             json!({
                 "project_slug": project_slug,
-                "agent_name": requester
+                "agent_name": agent_name,
+                "paths": ["all-locks-test/**"],
+                "exclusive": true
             }),
         )
         .await;
 
+        // List all locks
+        let app = Router::new()
+            .route("/api/locks", get(tools::list_all_locks))
+            .with_state(state);
+
+        let (status, body) = get_json(app, "/api/locks").await;
+
         assert_eq!(status, StatusCode::OK);
-        assert!(body.as_array().unwrap().len() >= 1);
+        assert!(!body.as_array().unwrap().is_empty());
     }
 }
 
 // =============================================================================
-// Macro Tests
+// Thread Tests
 // =============================================================================
 
-mod macro_tests {
+mod thread_tests {
     use super::*;
 
-    async fn setup_project(state: &AppState) -> String {
+    async fn setup_with_thread(state: &AppState) -> (String, String) {
+        // Create project
         let app = Router::new()
             .route("/api/project/ensure", post(tools::ensure_project))
             .with_state(state.clone());
         let (_, proj) = post_json(
             app,
             "/api/project/ensure",
-            json!({"human_key": "macro-test-proj"}),
+            json!({"human_key": "thread-test-proj"}),
         )
         .await;
-        proj["slug"].as_str().unwrap().to_string()
-    }
-
-    #[tokio::test]
-    async fn test_register_macro() {
-        let (state, _temp) = create_test_state().await;
-        let project_slug = setup_project(&state).await;
-
-        let app = Router::new()
-            .route("/api/macro/register", post(tools::register_macro))
-            .with_state(state);
+        let project_slug = proj["slug"].as_str().unwrap().to_string();
+
+        // Create agents
+        for name in ["ThreadSender", "ThreadRecipient"] {
+            let app = Router::new()
+                .route("/api/agent/register", post(tools::register_agent))
+                .with_state(state.clone());
+            post_json(
+                app,
+                "/api/agent/register",
+                json!({
+                    "project_slug": project_slug,
+                    "name": name,
+                    "program": "test",
+                    "model": "test"
+                }),
+            )
+            .await;
+        }
+
+        // Send message with thread_id
+        let app = Router::new()
+            .route("/api/message/send", post(tools::send_message))
+            .with_state(state.clone());
+        post_json(
+            app,
+            "/api/message/send",
+            json!({
+                "project_slug": project_slug,
+                "sender_name": "ThreadSender",
+                "recipient_names": ["ThreadRecipient"],
+                "subject": "Thread Test",
+                "body_md": "Message in thread",
+                "thread_id": "TEST-THREAD-001",
+                "allow_new_thread": true
+            }),
+        )
+        .await;
+
+        (project_slug, "TEST-THREAD-001".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_get_thread() {
+        let (state, _temp) = create_test_state().await;
+        let (project_slug, thread_id) = setup_with_thread(&state).await;
+
+        let app = Router::new()
+            .route("/api/thread", post(tools::get_thread))
+            .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/macro/register",
+            "/api/thread",
             json!({
                 "project_slug": project_slug,
-                "name": "test-macro",
-                "description": "A test macro",
-                "steps": [
-                    {"action": "step1", "params": {}},
-                    {"action": "step2", "params": {}}
-                ]
+                "thread_id": thread_id
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body["macro_id"].as_i64().unwrap() > 0);
-        assert_eq!(body["name"], "test-macro");
+        assert!(!body.as_array().unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn test_list_macros() {
+    async fn test_send_message_unknown_thread() {
         let (state, _temp) = create_test_state().await;
-        let project_slug = setup_project(&state).await;
+        let (project_slug, _) = setup_with_thread(&state).await;
 
-        // Register a macro first
         let app = Router::new()
-            .route("/api/macro/register", post(tools::register_macro))
-            .with_state(state.clone());
-        post_json(
+            .route("/api/message/send", post(tools::send_message))
+            .with_state(state);
+
+        let (status, body) = post_json(
             app,
-            "/api/macro/register",
+            "/api/message/send",
             json!({
                 "project_slug": project_slug,
-                "name": "list-test-macro",
-                "description": "For list test",
-                "steps": [{"action": "test"}]
+                "sender_name": "ThreadSender",
+                "recipient_names": ["ThreadRecipient"],
+                "subject": "Thread Test",
+                "body_md": "Mistyped thread",
+                "thread_id": "test-thread-001"
             }),
         )
         .await;
 
-        // List macros
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn test_list_threads() {
+        let (state, _temp) = create_test_state().await;
+        let (project_slug, _) = setup_with_thread(&state).await;
+
         let app = Router::new()
-            .route("/api/macros", post(tools::list_macros))
+            .route("/api/threads", post(tools::list_threads))
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/macros",
+            "/api/threads",
             json!({
                 "project_slug": project_slug
             }),
@@ -1422,57 +2308,146 @@ mod macro_tests {
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        // Should have built-in macros plus our test macro
-        assert!(body.as_array().unwrap().len() >= 1);
+        assert!(!body.as_array().unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn test_invoke_macro() {
+    async fn test_summarize_thread() {
         let (state, _temp) = create_test_state().await;
-        let project_slug = setup_project(&state).await;
+        let (project_slug, thread_id) = setup_with_thread(&state).await;
 
-        // Register a macro
         let app = Router::new()
-            .route("/api/macro/register", post(tools::register_macro))
-            .with_state(state.clone());
-        post_json(
+            .route("/api/thread/summarize", post(tools::summarize_thread))
+            .with_state(state);
+
+        let (status, body) = post_json(
             app,
-            "/api/macro/register",
+            "/api/thread/summarize",
             json!({
                 "project_slug": project_slug,
-                "name": "invoke-test",
-                "description": "Invoke test",
-                "steps": [{"action": "step1"}, {"action": "step2"}]
+                "thread_id": thread_id,
+                "no_llm": true
             }),
         )
         .await;
 
-        // Invoke it
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["thread_id"], thread_id);
+        assert!(body["message_count"].as_i64().unwrap() >= 1);
+        assert!(body["summary"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_thread_summary_endpoint() {
+        let (state, _temp) = create_test_state().await;
+        let (project_slug, thread_id) = setup_with_thread(&state).await;
+
         let app = Router::new()
-            .route("/api/macro/invoke", post(tools::invoke_macro))
+            .route(
+                "/api/project/{slug}/thread/{thread_id}/summary",
+                get(mouchak_mail_server::api::threads::thread_summary),
+            )
             .with_state(state);
 
-        let (status, body) = post_json(
+        let (status, body) = get_json(
+            app.clone(),
+            &format!("/api/project/{}/thread/{}/summary", project_slug, thread_id),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["subject"], "Thread Test");
+        assert_eq!(body["message_count"], 1);
+        assert_eq!(body["participants"][0]["name"], "ThreadSender");
+        assert_eq!(body["participants"][0]["message_count"], 1);
+        assert_eq!(body["recent"][0]["snippet"], "Message in thread");
+
+        let (status, body) = get_json(
             app,
-            "/api/macro/invoke",
-            json!({
-                "project_slug": project_slug,
-                "name": "invoke-test"
-            }),
+            &format!(
+                "/api/project/{}/thread/NO-SUCH-THREAD/summary",
+                project_slug
+            ),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "NOT_FOUND");
+    }
+
+    #[tokio::test]
+    async fn test_thread_mute_endpoints() {
+        let (state, _temp) = create_test_state().await;
+        let (project_slug, thread_id) = setup_with_thread(&state).await;
+
+        let app = Router::new()
+            .route(
+                "/api/project/{slug}/thread/{thread_id}/mutes",
+                get(mouchak_mail_server::api::threads::list_thread_mutes)
+                    .post(mouchak_mail_server::api::threads::mute_thread),
+            )
+            .route(
+                "/api/project/{slug}/thread/{thread_id}/mutes/{agent_name}",
+                axum::routing::delete(mouchak_mail_server::api::threads::unmute_thread),
+            )
+            .route("/api/inbox", post(tools::list_inbox))
+            .with_state(state);
+        let mutes_uri = format!("/api/project/{}/thread/{}/mutes", project_slug, thread_id);
+        let inbox_len = |app: Router| {
+            let slug = project_slug.clone();
+            async move {
+                let (_, body) = post_json(
+                    app,
+                    "/api/inbox",
+                    json!({"project_slug": slug, "agent_name": "ThreadRecipient"}),
+                )
+                .await;
+                body.as_array().unwrap().len()
+            }
+        };
+        assert_eq!(inbox_len(app.clone()).await, 1);
+
+        let (status, body) = post_json(
+            app.clone(),
+            &mutes_uri,
+            json!({"agent_name": "ThreadRecipient"}),
         )
         .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["muted"], true);
+        assert_eq!(body["changed"], true);
+        assert_eq!(inbox_len(app.clone()).await, 0);
 
+        let (status, body) = get_json(app.clone(), &mutes_uri).await;
         assert_eq!(status, StatusCode::OK);
-        assert_eq!(body["name"], "invoke-test");
-        assert_eq!(body["steps"].as_array().unwrap().len(), 2);
+        assert_eq!(body[0]["agent_name"], "ThreadRecipient");
+
+        let request = Request::builder()
+            .method("DELETE")
+            .uri(format!("{}/ThreadRecipient", mutes_uri))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body_bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body: Value = serde_json::from_slice(&body_bytes).unwrap();
+        assert_eq!(body["muted"], false);
+        assert_eq!(body["changed"], true);
+        assert_eq!(inbox_len(app.clone()).await, 1);
+
+        let (status, _) = post_json(
+            app,
+            &format!("/api/project/{}/thread/NO-SUCH-THREAD/mutes", project_slug),
+            json!({"agent_name": "ThreadRecipient"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
     }
 }
 
 // =============================================================================
-// Agent Profile Tests
+// Build Slot Tests
 // =============================================================================
 
-mod profile_tests {
+mod build_slot_tests {
     use super::*;
 
     async fn setup_with_agent(state: &AppState) -> (String, String) {
@@ -1482,7 +2457,7 @@ mod profile_tests {
         let (_, proj) = post_json(
             app,
             "/api/project/ensure",
-            json!({"human_key": "profile-test-proj"}),
+            json!({"human_key": "build-test-proj"}),
         )
         .await;
         let project_slug = proj["slug"].as_str().unwrap().to_string();
@@ -1495,118 +2470,141 @@ mod profile_tests {
             "/api/agent/register",
             json!({
                 "project_slug": project_slug,
-                "name": "ProfileAgent",
+                "name": "BuildAgent",
                 "program": "test",
-                "model": "test",
-                "task_description": "Profile test"
+                "model": "test"
             }),
         )
         .await;
 
-        (project_slug, "ProfileAgent".to_string())
+        (project_slug, "BuildAgent".to_string())
     }
 
     #[tokio::test]
-    async fn test_get_agent_profile() {
+    async fn test_acquire_build_slot() {
         let (state, _temp) = create_test_state().await;
         let (project_slug, agent_name) = setup_with_agent(&state).await;
 
         let app = Router::new()
-            .route("/api/agent/profile", post(tools::get_agent_profile))
+            .route("/api/build_slots/acquire", post(tools::acquire_build_slot))
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/agent/profile",
+            "/api/build_slots/acquire",
             json!({
                 "project_slug": project_slug,
-                "agent_name": agent_name
+                "agent_name": agent_name,
+                "slot_name": "test-slot",
+                "ttl_seconds": 600
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert_eq!(body["name"], "ProfileAgent");
-        assert!(body["message_count_sent"].as_i64().is_some());
-        assert!(body["message_count_received"].as_i64().is_some());
+        assert!(body["slot_id"].as_i64().unwrap() > 0);
+        assert_eq!(body["slot_name"], "test-slot");
     }
 
     #[tokio::test]
-    async fn test_update_agent_profile() {
+    async fn test_renew_build_slot() {
         let (state, _temp) = create_test_state().await;
         let (project_slug, agent_name) = setup_with_agent(&state).await;
 
+        // Acquire first
         let app = Router::new()
-            .route(
-                "/api/agent/profile/update",
-                post(tools::update_agent_profile),
-            )
+            .route("/api/build_slots/acquire", post(tools::acquire_build_slot))
+            .with_state(state.clone());
+        let (_, slot) = post_json(
+            app,
+            "/api/build_slots/acquire",
+            json!({
+                "project_slug": project_slug,
+                "agent_name": agent_name,
+                "slot_name": "renew-slot",
+                "ttl_seconds": 300
+            }),
+        )
+        .await;
+
+        // Renew
+        let app = Router::new()
+            .route("/api/build_slots/renew", post(tools::renew_build_slot))
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/agent/profile/update",
+            "/api/build_slots/renew",
             json!({
-                "project_slug": project_slug,
-                "agent_name": agent_name,
-                "task_description": "Updated task description",
-                "contact_policy": "auto"
+                "slot_id": slot["slot_id"],
+                "ttl_seconds": 600
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body["updated"].as_bool().unwrap());
+        assert!(body["renewed"].as_bool().unwrap());
     }
 
     #[tokio::test]
-    async fn test_set_contact_policy() {
+    async fn test_release_build_slot() {
         let (state, _temp) = create_test_state().await;
         let (project_slug, agent_name) = setup_with_agent(&state).await;
 
+        // Acquire first
         let app = Router::new()
-            .route("/api/agent/contact_policy", post(tools::set_contact_policy))
+            .route("/api/build_slots/acquire", post(tools::acquire_build_slot))
+            .with_state(state.clone());
+        let (_, slot) = post_json(
+            app,
+            "/api/build_slots/acquire",
+            json!({
+                "project_slug": project_slug,
+                "agent_name": agent_name,
+                "slot_name": "release-slot"
+            }),
+        )
+        .await;
+
+        // Release
+        let app = Router::new()
+            .route("/api/build_slots/release", post(tools::release_build_slot))
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/agent/contact_policy",
+            "/api/build_slots/release",
             json!({
-                "project_slug": project_slug,
-                "agent_name": agent_name,
-                "contact_policy": "manual"
+                "slot_id": slot["slot_id"]
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body["updated"].as_bool().unwrap());
-        assert_eq!(body["contact_policy"], "manual");
+        assert!(body["released"].as_bool().unwrap());
     }
 }
 
 // =============================================================================
-// Message Acknowledgment Tests
+// Contact Tests
 // =============================================================================
 
-mod ack_tests {
+mod contact_tests {
     use super::*;
 
-    async fn setup_with_message(state: &AppState) -> (String, String, i64) {
-        // Create project
+    async fn setup_with_agents(state: &AppState) -> (String, String, String) {
         let app = Router::new()
             .route("/api/project/ensure", post(tools::ensure_project))
             .with_state(state.clone());
         let (_, proj) = post_json(
             app,
             "/api/project/ensure",
-            json!({"human_key": "ack-test-proj"}),
+            json!({"human_key": "contact-test-proj"}),
         )
         .await;
         let project_slug = proj["slug"].as_str().unwrap().to_string();
 
-        // Create agents
-        for name in ["AckSender", "AckRecipient"] {
+        for name in ["ContactRequester", "ContactTarget"] {
             let app = Router::new()
                 .route("/api/agent/register", post(tools::register_agent))
                 .with_state(state.clone());
@@ -1623,295 +2621,286 @@ mod ack_tests {
             .await;
         }
 
-        // Send message
-        let app = Router::new()
-            .route("/api/message/send", post(tools::send_message))
-            .with_state(state.clone());
-        let (_, msg) = post_json(
-            app,
-            "/api/message/send",
-            json!({
-                "project_slug": project_slug,
-                "sender_name": "AckSender",
-                "recipient_names": ["AckRecipient"],
-                "subject": "Ack Test",
-                "body_md": "Test ack",
-                "ack_required": true
-            }),
-        )
-        .await;
-
         (
             project_slug,
-            "AckRecipient".to_string(),
-            msg["id"].as_i64().unwrap(),
+            "ContactRequester".to_string(),
+            "ContactTarget".to_string(),
         )
     }
 
     #[tokio::test]
-    async fn test_mark_message_read() {
+    async fn test_request_contact() {
         let (state, _temp) = create_test_state().await;
-        let (project_slug, agent_name, message_id) = setup_with_message(&state).await;
+        let (project_slug, requester, target) = setup_with_agents(&state).await;
 
         let app = Router::new()
-            .route("/api/message/read", post(tools::mark_message_read))
+            .route("/api/contact/request", post(tools::request_contact))
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/message/read",
+            "/api/contact/request",
             json!({
-                "project_slug": project_slug,
-                "agent_name": agent_name,
-                "message_id": message_id
+                "from_project_slug": project_slug,
+                "from_agent_name": requester,
+                "to_project_slug": project_slug,
+                "to_agent_name": target,
+                "reason": "Test contact request"
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body["marked"].as_bool().unwrap());
+        assert!(body["link_id"].as_i64().unwrap() > 0);
+        assert_eq!(body["status"], "pending");
     }
 
     #[tokio::test]
-    async fn test_acknowledge_message() {
+    async fn test_respond_contact() {
         let (state, _temp) = create_test_state().await;
-        let (project_slug, agent_name, message_id) = setup_with_message(&state).await;
+        let (project_slug, requester, target) = setup_with_agents(&state).await;
 
+        // Request first
         let app = Router::new()
-            .route("/api/message/acknowledge", post(tools::acknowledge_message))
+            .route("/api/contact/request", post(tools::request_contact))
+            .with_state(state.clone());
+        let (_, req) = post_json(
+            app,
+            "/api/contact/request",
+            json!({
+                "from_project_slug": project_slug,
+                "from_agent_name": requester,
+                "to_project_slug": project_slug,
+                "to_agent_name": target,
+                "reason": "Accept test"
+            }),
+        )
+        .await;
+
+        // Accept
+        let app = Router::new()
+            .route("/api/contact/respond", post(tools::respond_contact))
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/message/acknowledge",
+            "/api/contact/respond",
             json!({
-                "project_slug": project_slug,
-                "agent_name": agent_name,
-                "message_id": message_id
+                "link_id": req["link_id"],
+                "accept": true
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body["acknowledged"].as_bool().unwrap());
+        assert_eq!(body["status"], "accepted");
     }
-}
-
-// =============================================================================
-// Delete Operations Tests
-// =============================================================================
-
-mod delete_tests {
-    use super::*;
 
     #[tokio::test]
-    async fn test_delete_agent() {
+    async fn test_list_contacts() {
         let (state, _temp) = create_test_state().await;
+        let (project_slug, requester, target) = setup_with_agents(&state).await;
 
-        // Create project
+        // Create and accept a contact
         let app = Router::new()
-            .route("/api/project/ensure", post(tools::ensure_project))
+            .route("/api/contact/request", post(tools::request_contact))
             .with_state(state.clone());
-        let (_, proj) = post_json(
+        let (_, req) = post_json(
             app,
-            "/api/project/ensure",
-            json!({"human_key": "delete-agent-proj"}),
+            "/api/contact/request",
+            json!({
+                "from_project_slug": project_slug,
+                "from_agent_name": requester,
+                "to_project_slug": project_slug,
+                "to_agent_name": target,
+                "reason": "List test"
+            }),
         )
         .await;
-        let project_slug = proj["slug"].as_str().unwrap().to_string();
 
-        // Create agent
         let app = Router::new()
-            .route("/api/agent/register", post(tools::register_agent))
+            .route("/api/contact/respond", post(tools::respond_contact))
             .with_state(state.clone());
         post_json(
             app,
-            "/api/agent/register",
+            "/api/contact/respond",
             json!({
-                "project_slug": project_slug,
-                "name": "ToDelete",
-                "program": "test",
-                "model": "test"
+                "link_id": req["link_id"],
+                "accept": true
             }),
         )
         .await;
 
-        // Delete agent
+        // List contacts
         let app = Router::new()
-            .route(
-                "/api/projects/{project_slug}/agents/{agent_name}",
-                axum::routing::delete(tools::delete_agent),
-            )
+            .route("/api/contacts", post(tools::list_contacts))
             .with_state(state);
 
-        let request = Request::builder()
-            .method("DELETE")
-            .uri(format!("/api/projects/{}/agents/ToDelete", project_slug))
-            .body(Body::empty())
-            .unwrap();
-
-        let response = app.oneshot(request).await.unwrap();
-        assert_eq!(response.status(), StatusCode::OK);
-    }
-
-    #[tokio::test]
-    async fn test_delete_project() {
-        let (state, _temp) = create_test_state().await;
-
-        // Create project
-        let app = Router::new()
-            .route("/api/project/ensure", post(tools::ensure_project))
-            .with_state(state.clone());
-        let (_, proj) = post_json(
+        let (status, body) = post_json(
             app,
-            "/api/project/ensure",
-            json!({"human_key": "delete-proj-test"}),
+            "/api/contacts",
+            json!({
+                "project_slug": project_slug,
+                "agent_name": requester
+            }),
         )
         .await;
-        let project_slug = proj["slug"].as_str().unwrap().to_string();
-
-        // Delete project
-        let app = Router::new()
-            .route(
-                "/api/projects/{project_slug}",
-                axum::routing::delete(tools::delete_project),
-            )
-            .with_state(state);
-
-        let request = Request::builder()
-            .method("DELETE")
-            .uri(format!("/api/projects/{}", project_slug))
-            .body(Body::empty())
-            .unwrap();
 
-        let response = app.oneshot(request).await.unwrap();
-        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(status, StatusCode::OK);
+        assert!(!body.as_array().unwrap().is_empty());
     }
 }
 
 // =============================================================================
-// Message Extended Tests
+// Macro Tests
 // =============================================================================
 
-mod message_extended_tests {
+mod macro_tests {
     use super::*;
 
-    async fn setup_with_message(state: &AppState) -> (String, i64) {
-        // Create project
+    async fn setup_project(state: &AppState) -> String {
         let app = Router::new()
             .route("/api/project/ensure", post(tools::ensure_project))
             .with_state(state.clone());
         let (_, proj) = post_json(
             app,
             "/api/project/ensure",
-            json!({"human_key": "msg-ext-proj"}),
+            json!({"human_key": "macro-test-proj"}),
         )
         .await;
-        let project_slug = proj["slug"].as_str().unwrap().to_string();
+        proj["slug"].as_str().unwrap().to_string()
+    }
 
-        // Create agents
-        for name in ["ExtSender", "ExtRecipient"] {
-            let app = Router::new()
-                .route("/api/agent/register", post(tools::register_agent))
-                .with_state(state.clone());
-            post_json(
-                app,
-                "/api/agent/register",
-                json!({
-                    "project_slug": project_slug,
-                    "name": name,
-                    "program": "test",
-                    "model": "test"
-                }),
-            )
-            .await;
-        }
+    #[tokio::test]
+    async fn test_register_macro() {
+        let (state, _temp) = create_test_state().await;
+        let project_slug = setup_project(&state).await;
 
-        // Send message
         let app = Router::new()
-            .route("/api/message/send", post(tools::send_message))
-            .with_state(state.clone());
-        let (_, msg) = post_json(
+            .route("/api/macro/register", post(tools::register_macro))
+            .with_state(state);
+
+        let (status, body) = post_json(
             app,
-            "/api/message/send",
+            "/api/macro/register",
             json!({
                 "project_slug": project_slug,
-                "sender_name": "ExtSender",
-                "recipient_names": ["ExtRecipient"],
-                "subject": "Extended Test",
-                "body_md": "Extended message body",
-                "thread_id": "EXT-THREAD-001"
+                "name": "test-macro",
+                "description": "A test macro",
+                "steps": [
+                    {"action": "step1", "params": {}},
+                    {"action": "step2", "params": {}}
+                ]
             }),
         )
         .await;
 
-        (project_slug, msg["id"].as_i64().unwrap())
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["macro_id"].as_i64().unwrap() > 0);
+        assert_eq!(body["name"], "test-macro");
     }
 
     #[tokio::test]
-    async fn test_get_message() {
+    async fn test_list_macros() {
         let (state, _temp) = create_test_state().await;
-        let (_project_slug, message_id) = setup_with_message(&state).await;
+        let project_slug = setup_project(&state).await;
 
+        // Register a macro first
         let app = Router::new()
-            .route("/api/messages/{message_id}", get(tools::get_message))
-            .with_state(state);
+            .route("/api/macro/register", post(tools::register_macro))
+            .with_state(state.clone());
+        post_json(
+            app,
+            "/api/macro/register",
+            json!({
+                "project_slug": project_slug,
+                "name": "list-test-macro",
+                "description": "For list test",
+                "steps": [{"action": "test"}]
+            }),
+        )
+        .await;
 
-        let (status, body) = get_json(app, &format!("/api/messages/{}", message_id)).await;
+        // List macros
+        let app = Router::new()
+            .route("/api/macros", post(tools::list_macros))
+            .with_state(state);
+
+        let (status, body) = post_json(
+            app,
+            "/api/macros",
+            json!({
+                "project_slug": project_slug
+            }),
+        )
+        .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert_eq!(body["id"], message_id);
-        assert_eq!(body["subject"], "Extended Test");
+        // Should have built-in macros plus our test macro
+        assert!(!body.as_array().unwrap().is_empty());
     }
 
     #[tokio::test]
-    async fn test_reply_message() {
+    async fn test_invoke_macro() {
         let (state, _temp) = create_test_state().await;
-        let (project_slug, message_id) = setup_with_message(&state).await;
+        let project_slug = setup_project(&state).await;
 
+        // Register a macro
         let app = Router::new()
-            .route("/api/message/reply", post(tools::reply_message))
+            .route("/api/macro/register", post(tools::register_macro))
+            .with_state(state.clone());
+        post_json(
+            app,
+            "/api/macro/register",
+            json!({
+                "project_slug": project_slug,
+                "name": "invoke-test",
+                "description": "Invoke test",
+                "steps": [{"action": "step1"}, {"action": "step2"}]
+            }),
+        )
+        .await;
+
+        // Invoke it
+        let app = Router::new()
+            .route("/api/macro/invoke", post(tools::invoke_macro))
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/message/reply",
+            "/api/macro/invoke",
             json!({
                 "project_slug": project_slug,
-                "sender_name": "ExtRecipient",
-                "message_id": message_id,
-                "body_md": "This is a reply"
+                "name": "invoke-test"
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body["id"].as_i64().unwrap() > 0);
-        assert_eq!(body["thread_id"], "EXT-THREAD-001");
+        assert_eq!(body["name"], "invoke-test");
+        assert_eq!(body["steps"].as_array().unwrap().len(), 2);
     }
 }
 
 // =============================================================================
-// File Reservation Extended Tests
+// Agent Profile Tests
 // =============================================================================
 
-mod file_reservation_extended_tests {
+mod profile_tests {
     use super::*;
 
-    async fn setup_with_reservation(state: &AppState) -> (String, String, i64) {
-        // Create project
+    async fn setup_with_agent(state: &AppState) -> (String, String) {
         let app = Router::new()
             .route("/api/project/ensure", post(tools::ensure_project))
             .with_state(state.clone());
         let (_, proj) = post_json(
             app,
             "/api/project/ensure",
-            json!({"human_key": "file-res-ext-proj"}),
+            json!({"human_key": "profile-test-proj"}),
         )
         .await;
         let project_slug = proj["slug"].as_str().unwrap().to_string();
 
-        // Create agent
         let app = Router::new()
             .route("/api/agent/register", post(tools::register_agent))
             .with_state(state.clone());
@@ -1920,102 +2909,104 @@ mod file_reservation_extended_tests {
             "/api/agent/register",
             json!({
                 "project_slug": project_slug,
-                "name": "FileResAgent",
+                "name": "ProfileAgent",
                 "program": "test",
-                "model": "test"
+                "model": "test",
+                "task_description": "Profile test"
             }),
         )
         .await;
 
-        // Create reservation
+        (project_slug, "ProfileAgent".to_string())
+    }
+
+    #[tokio::test]
+    async fn test_get_agent_profile() {
+        let (state, _temp) = create_test_state().await;
+        let (project_slug, agent_name) = setup_with_agent(&state).await;
+
         let app = Router::new()
-            .route(
-                "/api/file_reservations/paths",
-                post(tools::file_reservation_paths),
-            )
-            .with_state(state.clone());
-        let (_, res) = post_json(
+            .route("/api/agent/profile", post(tools::get_agent_profile))
+            .with_state(state);
+
+        let (status, body) = post_json(
             app,
-            "/api/file_reservations/paths",
+            "/api/agent/profile",
             json!({
                 "project_slug": project_slug,
-                "agent_name": "FileResAgent",
-                "paths": ["ext-test/**"],
-                "exclusive": true,
-                "ttl_seconds": 3600
+                "agent_name": agent_name
             }),
         )
         .await;
 
-        let res_id = res["granted"].as_array().unwrap()[0]["id"]
-            .as_i64()
-            .unwrap();
-        (project_slug, "FileResAgent".to_string(), res_id)
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["name"], "ProfileAgent");
+        assert!(body["message_count_sent"].as_i64().is_some());
+        assert!(body["message_count_received"].as_i64().is_some());
     }
 
     #[tokio::test]
-    async fn test_renew_file_reservation() {
+    async fn test_update_agent_profile() {
         let (state, _temp) = create_test_state().await;
-        let (_project_slug, _agent_name, reservation_id) = setup_with_reservation(&state).await;
+        let (project_slug, agent_name) = setup_with_agent(&state).await;
 
         let app = Router::new()
             .route(
-                "/api/file_reservations/renew",
-                post(tools::renew_file_reservation),
+                "/api/agent/profile/update",
+                post(tools::update_agent_profile),
             )
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/file_reservations/renew",
+            "/api/agent/profile/update",
             json!({
-                "reservation_id": reservation_id,
-                "ttl_seconds": 7200
+                "project_slug": project_slug,
+                "agent_name": agent_name,
+                "task_description": "Updated task description",
+                "contact_policy": "auto"
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body["renewed"].as_bool().unwrap());
+        assert!(body["updated"].as_bool().unwrap());
     }
 
     #[tokio::test]
-    async fn test_force_release_reservation() {
+    async fn test_set_contact_policy() {
         let (state, _temp) = create_test_state().await;
-        let (_project_slug, _agent_name, reservation_id) = setup_with_reservation(&state).await;
+        let (project_slug, agent_name) = setup_with_agent(&state).await;
 
         let app = Router::new()
-            .route(
-                "/api/file_reservations/force_release",
-                post(tools::force_release_reservation),
-            )
+            .route("/api/agent/contact_policy", post(tools::set_contact_policy))
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/file_reservations/force_release",
+            "/api/agent/contact_policy",
             json!({
-                "reservation_id": reservation_id
+                "project_slug": project_slug,
+                "agent_name": agent_name,
+                "contact_policy": "manual"
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body["released"].as_bool().unwrap());
+        assert!(body["updated"].as_bool().unwrap());
+        assert_eq!(body["contact_policy"], "manual");
     }
 }
 
 // =============================================================================
-// Quota Tests
+// Message Acknowledgment Tests
 // =============================================================================
 
-mod quota_tests {
+mod ack_tests {
     use super::*;
 
-    #[tokio::test]
-    async fn test_get_quota_status() {
-        let (state, _temp) = create_test_state().await;
-
+    async fn setup_with_message(state: &AppState) -> (String, String, i64) {
         // Create project
         let app = Router::new()
             .route("/api/project/ensure", post(tools::ensure_project))
@@ -2023,57 +3014,112 @@ mod quota_tests {
         let (_, proj) = post_json(
             app,
             "/api/project/ensure",
-            json!({"human_key": "quota-test-proj"}),
+            json!({"human_key": "ack-test-proj"}),
         )
         .await;
         let project_slug = proj["slug"].as_str().unwrap().to_string();
 
-        // Create agent
+        // Create agents
+        for name in ["AckSender", "AckRecipient"] {
+            let app = Router::new()
+                .route("/api/agent/register", post(tools::register_agent))
+                .with_state(state.clone());
+            post_json(
+                app,
+                "/api/agent/register",
+                json!({
+                    "project_slug": project_slug,
+                    "name": name,
+                    "program": "test",
+                    "model": "test"
+                }),
+            )
+            .await;
+        }
+
+        // Send message
         let app = Router::new()
-            .route("/api/agent/register", post(tools::register_agent))
+            .route("/api/message/send", post(tools::send_message))
             .with_state(state.clone());
-        post_json(
+        let (_, msg) = post_json(
             app,
-            "/api/agent/register",
+            "/api/message/send",
             json!({
                 "project_slug": project_slug,
-                "name": "QuotaAgent",
-                "program": "test",
-                "model": "test"
+                "sender_name": "AckSender",
+                "recipient_names": ["AckRecipient"],
+                "subject": "Ack Test",
+                "body_md": "Test ack",
+                "ack_required": true
             }),
         )
         .await;
 
+        (
+            project_slug,
+            "AckRecipient".to_string(),
+            msg["id"].as_i64().unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_mark_message_read() {
+        let (state, _temp) = create_test_state().await;
+        let (project_slug, agent_name, message_id) = setup_with_message(&state).await;
+
         let app = Router::new()
-            .route("/api/quota/status", post(tools::get_quota_status))
+            .route("/api/message/read", post(tools::mark_message_read))
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/quota/status",
+            "/api/message/read",
             json!({
                 "project_slug": project_slug,
-                "agent_name": "QuotaAgent"
+                "agent_name": agent_name,
+                "message_id": message_id
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body["quota_enabled"].is_boolean());
-        assert!(body["attachments_limit_bytes"].as_i64().is_some());
-        assert!(body["attachments_usage_bytes"].as_i64().is_some());
+        assert!(body["marked"].as_bool().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_acknowledge_message() {
+        let (state, _temp) = create_test_state().await;
+        let (project_slug, agent_name, message_id) = setup_with_message(&state).await;
+
+        let app = Router::new()
+            .route("/api/message/acknowledge", post(tools::acknowledge_message))
+            .with_state(state);
+
+        let (status, body) = post_json(
+            app,
+            "/api/message/acknowledge",
+            json!({
+                "project_slug": project_slug,
+                "agent_name": agent_name,
+                "message_id": message_id
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["acknowledged"].as_bool().unwrap());
     }
 }
 
 // =============================================================================
-// Overseer Tests
+// Delete Operations Tests
 // =============================================================================
 
-mod overseer_tests {
+mod delete_tests {
     use super::*;
 
     #[tokio::test]
-    async fn test_send_overseer_message() {
+    async fn test_delete_agent() {
         let (state, _temp) = create_test_state().await;
 
         // Create project
@@ -2083,7 +3129,7 @@ mod overseer_tests {
         let (_, proj) = post_json(
             app,
             "/api/project/ensure",
-            json!({"human_key": "overseer-test-proj"}),
+            json!({"human_key": "delete-agent-proj"}),
         )
         .await;
         let project_slug = proj["slug"].as_str().unwrap().to_string();
@@ -2097,312 +3143,421 @@ mod overseer_tests {
             "/api/agent/register",
             json!({
                 "project_slug": project_slug,
-                "name": "OverseerTarget",
+                "name": "ToDelete",
                 "program": "test",
                 "model": "test"
             }),
         )
         .await;
 
+        // Delete agent
         let app = Router::new()
-            .route("/api/overseer/send", post(tools::send_overseer_message))
+            .route(
+                "/api/projects/{project_slug}/agents/{agent_name}",
+                axum::routing::delete(tools::delete_agent),
+            )
             .with_state(state);
 
-        let (status, body) = post_json(
-            app,
-            "/api/overseer/send",
-            json!({
-                "project_slug": project_slug,
-                "agent_name": "OverseerTarget",
-                "subject": "Human Guidance",
-                "body_md": "Please review this code"
-            }),
-        )
-        .await;
+        let request = Request::builder()
+            .method("DELETE")
+            .uri(format!("/api/projects/{}/agents/ToDelete", project_slug))
+            .body(Body::empty())
+            .unwrap();
 
-        assert_eq!(status, StatusCode::OK);
-        assert!(body["sent"].as_bool().unwrap());
-        assert!(body["message_id"].as_i64().unwrap() > 0);
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_delete_project() {
+        let (state, _temp) = create_test_state().await;
+
+        // Create project
+        let app = Router::new()
+            .route("/api/project/ensure", post(tools::ensure_project))
+            .with_state(state.clone());
+        let (_, proj) = post_json(
+            app,
+            "/api/project/ensure",
+            json!({"human_key": "delete-proj-test"}),
+        )
+        .await;
+        let project_slug = proj["slug"].as_str().unwrap().to_string();
+
+        // Delete project
+        let app = Router::new()
+            .route(
+                "/api/projects/{project_slug}",
+                axum::routing::delete(tools::delete_project),
+            )
+            .with_state(state);
+
+        let request = Request::builder()
+            .method("DELETE")
+            .uri(format!("/api/projects/{}", project_slug))
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
     }
 }
 
 // =============================================================================
-// Macro Extended Tests
+// Message Extended Tests
 // =============================================================================
 
-mod macro_extended_tests {
+mod message_extended_tests {
     use super::*;
 
-    async fn setup_project(state: &AppState) -> String {
+    async fn setup_with_message(state: &AppState) -> (String, i64) {
+        // Create project
         let app = Router::new()
             .route("/api/project/ensure", post(tools::ensure_project))
             .with_state(state.clone());
         let (_, proj) = post_json(
             app,
             "/api/project/ensure",
-            json!({"human_key": "macro-ext-proj"}),
+            json!({"human_key": "msg-ext-proj"}),
         )
         .await;
-        proj["slug"].as_str().unwrap().to_string()
-    }
+        let project_slug = proj["slug"].as_str().unwrap().to_string();
 
-    #[tokio::test]
-    async fn test_unregister_macro() {
-        let (state, _temp) = create_test_state().await;
-        let project_slug = setup_project(&state).await;
+        // Create agents
+        for name in ["ExtSender", "ExtRecipient"] {
+            let app = Router::new()
+                .route("/api/agent/register", post(tools::register_agent))
+                .with_state(state.clone());
+            post_json(
+                app,
+                "/api/agent/register",
+                json!({
+                    "project_slug": project_slug,
+                    "name": name,
+                    "program": "test",
+                    "model": "test"
+                }),
+            )
+            .await;
+        }
 
-        // Register macro first
+        // Send message
         let app = Router::new()
-            .route("/api/macros/register", post(tools::register_macro))
+            .route("/api/message/send", post(tools::send_message))
             .with_state(state.clone());
-        post_json(
+        let (_, msg) = post_json(
             app,
-            "/api/macros/register",
+            "/api/message/send",
             json!({
                 "project_slug": project_slug,
-                "name": "test-unregister-macro",
-                "description": "Macro to unregister",
-                "steps": [{"action": "test"}]
+                "sender_name": "ExtSender",
+                "recipient_names": ["ExtRecipient"],
+                "subject": "Extended Test",
+                "body_md": "Extended message body",
+                "thread_id": "EXT-THREAD-001",
+                "allow_new_thread": true
             }),
         )
         .await;
 
-        // Unregister it
+        (project_slug, msg["id"].as_i64().unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_get_message() {
+        let (state, _temp) = create_test_state().await;
+        let (project_slug, message_id) = setup_with_message(&state).await;
+
         let app = Router::new()
-            .route("/api/macros/unregister", post(tools::unregister_macro))
+            .route("/api/messages/{message_id}", get(tools::get_message))
             .with_state(state);
 
-        let (status, body) = post_json(
-            app,
-            "/api/macros/unregister",
-            json!({
-                "project_slug": project_slug,
-                "name": "test-unregister-macro"
-            }),
-        )
-        .await;
+        let (status, body) = get_json(app, &format!("/api/messages/{}", message_id)).await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body["deleted"].as_bool().unwrap());
+        assert_eq!(body["id"], message_id);
+        assert_eq!(body["subject"], "Extended Test");
+        assert_eq!(
+            body["permalink"],
+            format!("/p/{}/m/{}", project_slug, message_id)
+        );
     }
 
     #[tokio::test]
-    async fn test_macro_start_session() {
-        let (state, _temp) = create_test_state().await;
-        let project_slug = setup_project(&state).await;
+    async fn test_get_message_permalink_with_public_url() {
+        let (mut state, _temp) = create_test_state().await;
+        let (project_slug, message_id) = setup_with_message(&state).await;
+
+        let mut config = AppConfig::default();
+        config.server.public_url = Some("https://mail.example.com/".to_string());
+        state.mm.app_config = Arc::new(config);
 
         let app = Router::new()
-            .route(
-                "/api/macros/start_session",
-                post(tools::macro_start_session),
+            .route("/api/messages/{message_id}", get(tools::get_message))
+            .with_state(state);
+
+        let (status, body) = get_json(app, &format!("/api/messages/{}", message_id)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body["permalink"],
+            format!(
+                "https://mail.example.com/p/{}/m/{}",
+                project_slug, message_id
             )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reply_message() {
+        let (state, _temp) = create_test_state().await;
+        let (project_slug, message_id) = setup_with_message(&state).await;
+
+        let app = Router::new()
+            .route("/api/message/reply", post(tools::reply_message))
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/macros/start_session",
+            "/api/message/reply",
             json!({
                 "project_slug": project_slug,
-                "name": "MacroSessionAgent",
-                "program": "claude-code",
-                "model": "claude-opus-4",
-                "patterns": ["macro-session/**"]
+                "sender_name": "ExtRecipient",
+                "message_id": message_id,
+                "body_md": "This is a reply"
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body["agent_id"].as_i64().unwrap() > 0);
-        assert_eq!(body["agent_name"], "MacroSessionAgent");
+        assert!(body["id"].as_i64().unwrap() > 0);
+        assert_eq!(body["thread_id"], "EXT-THREAD-001");
     }
 
     #[tokio::test]
-    async fn test_macro_file_reservation_cycle() {
+    async fn test_recall_message() {
         let (state, _temp) = create_test_state().await;
-        let project_slug = setup_project(&state).await;
+        let (project_slug, message_id) = setup_with_message(&state).await;
 
-        // Create agent
         let app = Router::new()
-            .route("/api/agent/register", post(tools::register_agent))
-            .with_state(state.clone());
-        post_json(
-            app,
-            "/api/agent/register",
+            .route("/api/message/recall", post(tools::recall_message))
+            .route("/api/messages/{message_id}", get(tools::get_message))
+            .with_state(state);
+
+        // Only the sender may recall
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/message/recall",
             json!({
                 "project_slug": project_slug,
-                "name": "FileCycleAgent",
-                "program": "test",
-                "model": "test"
+                "agent_name": "ExtRecipient",
+                "message_id": message_id,
+                "reason": "Not mine to recall"
             }),
         )
         .await;
-
-        let app = Router::new()
-            .route(
-                "/api/macros/file_reservation_cycle",
-                post(tools::macro_file_reservation_cycle),
-            )
-            .with_state(state);
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["code"], "NOT_MESSAGE_SENDER");
 
         let (status, body) = post_json(
-            app,
-            "/api/macros/file_reservation_cycle",
+            app.clone(),
+            "/api/message/recall",
             json!({
                 "project_slug": project_slug,
-                "agent_name": "FileCycleAgent",
-                "patterns": ["cycle-test/**"],
-                "action": "reserve"
+                "agent_name": "ExtSender",
+                "message_id": message_id,
+                "reason": "Sent to the wrong thread"
             }),
         )
         .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["message_id"], message_id);
+        assert_eq!(body["recall_reason"], "Sent to the wrong thread");
 
+        let (status, body) = get_json(app, &format!("/api/messages/{}", message_id)).await;
         assert_eq!(status, StatusCode::OK);
-        assert_eq!(body["action"], "reserve");
-        assert!(body["affected_count"].as_i64().unwrap() >= 0);
+        assert_eq!(body["subject"], "[Recalled] Extended Test");
+        assert_eq!(body["body_md"], "Sent to the wrong thread");
+        assert!(body["recalled_ts"].is_string());
     }
 
     #[tokio::test]
-    async fn test_macro_contact_handshake() {
+    async fn test_scheduled_message_endpoints() {
         let (state, _temp) = create_test_state().await;
-        let project_slug = setup_project(&state).await;
-
-        for name in ["HandshakeRequester", "HandshakeTarget"] {
-            let app = Router::new()
-                .route("/api/agent/register", post(tools::register_agent))
-                .with_state(state.clone());
-            post_json(
-                app,
-                "/api/agent/register",
-                json!({
-                    "project_slug": project_slug,
-                    "name": name,
-                    "program": "test",
-                    "model": "test"
-                }),
-            )
-            .await;
-        }
+        let (project_slug, _message_id) = setup_with_message(&state).await;
 
         let app = Router::new()
+            .route("/api/message/send", post(tools::send_message))
+            .route("/api/message/scheduled", post(tools::list_scheduled))
             .route(
-                "/api/macros/contact_handshake",
-                post(tools::macro_contact_handshake),
+                "/api/message/cancel_scheduled",
+                post(tools::cancel_scheduled),
             )
             .with_state(state);
 
+        let deliver_at = (chrono::Utc::now().naive_utc() + chrono::Duration::hours(1))
+            .format("%Y-%m-%dT%H:%M:%S")
+            .to_string();
         let (status, body) = post_json(
-            app,
-            "/api/macros/contact_handshake",
+            app.clone(),
+            "/api/message/send",
             json!({
                 "project_slug": project_slug,
-                "requester": "HandshakeRequester",
-                "target": "HandshakeTarget"
+                "sender_name": "ExtSender",
+                "recipient_names": ["ExtRecipient"],
+                "subject": "Later",
+                "body_md": "Remind me",
+                "deliver_at": deliver_at
             }),
         )
         .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["deliver_at"], deliver_at);
+        let scheduled_id = body["id"].as_i64().unwrap();
 
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/message/scheduled",
+            json!({"project_slug": project_slug, "agent_name": "ExtSender"}),
+        )
+        .await;
         assert_eq!(status, StatusCode::OK);
-        assert_eq!(body["contacts_created"], 1);
-        assert!(body["link_ids"].as_array().is_some());
-    }
-}
+        assert_eq!(body.as_array().unwrap().len(), 1);
+        assert_eq!(body[0]["id"], scheduled_id);
 
-// =============================================================================
-// Thread Extended Tests
-// =============================================================================
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/message/cancel_scheduled",
+            json!({
+                "project_slug": project_slug,
+                "agent_name": "ExtRecipient",
+                "message_id": scheduled_id
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        assert_eq!(body["code"], "NOT_MESSAGE_SENDER");
 
-mod thread_extended_tests {
-    use super::*;
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/message/cancel_scheduled",
+            json!({
+                "project_slug": project_slug,
+                "agent_name": "ExtSender",
+                "message_id": scheduled_id
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["cancelled"].as_bool().unwrap());
 
-    async fn setup_with_threads(state: &AppState) -> String {
-        // Create project
-        let app = Router::new()
-            .route("/api/project/ensure", post(tools::ensure_project))
-            .with_state(state.clone());
-        let (_, proj) = post_json(
+        let (_, body) = post_json(
             app,
-            "/api/project/ensure",
-            json!({"human_key": "thread-ext-proj"}),
+            "/api/message/scheduled",
+            json!({"project_slug": project_slug, "agent_name": "ExtSender"}),
         )
         .await;
-        let project_slug = proj["slug"].as_str().unwrap().to_string();
-
-        // Create agents
-        for name in ["ThreadExtSender", "ThreadExtRecipient"] {
-            let app = Router::new()
-                .route("/api/agent/register", post(tools::register_agent))
-                .with_state(state.clone());
-            post_json(
-                app,
-                "/api/agent/register",
-                json!({
-                    "project_slug": project_slug,
-                    "name": name,
-                    "program": "test",
-                    "model": "test"
-                }),
-            )
-            .await;
-        }
-
-        // Create messages in multiple threads
-        for i in 1..=2 {
-            let app = Router::new()
-                .route("/api/message/send", post(tools::send_message))
-                .with_state(state.clone());
-            post_json(
-                app,
-                "/api/message/send",
-                json!({
-                    "project_slug": project_slug,
-                    "sender_name": "ThreadExtSender",
-                    "recipient_names": ["ThreadExtRecipient"],
-                    "subject": format!("Thread {} Message", i),
-                    "body_md": format!("Message body {}", i),
-                    "thread_id": format!("MULTI-THREAD-{}", i)
-                }),
-            )
-            .await;
-        }
-
-        project_slug
-    }
+        assert!(body.as_array().unwrap().is_empty());
+    }
 
     #[tokio::test]
-    async fn test_summarize_threads() {
+    async fn test_snooze_message_endpoints() {
         let (state, _temp) = create_test_state().await;
-        let project_slug = setup_with_threads(&state).await;
+        let (project_slug, message_id) = setup_with_message(&state).await;
 
         let app = Router::new()
-            .route("/api/threads/summarize", post(tools::summarize_threads))
+            .route("/api/inbox", post(tools::list_inbox))
+            .route("/api/message/snooze", post(tools::snooze_message))
+            .route("/api/message/snoozed", post(tools::list_snoozed))
             .with_state(state);
+        let inbox = json!({"project_slug": project_slug, "agent_name": "ExtRecipient"});
 
+        let until = (chrono::Utc::now().naive_utc() + chrono::Duration::hours(4))
+            .format("%Y-%m-%dT%H:%M:%S")
+            .to_string();
         let (status, body) = post_json(
-            app,
-            "/api/threads/summarize",
+            app.clone(),
+            "/api/message/snooze",
             json!({
                 "project_slug": project_slug,
-                "limit": 10
+                "agent_name": "ExtRecipient",
+                "message_id": message_id,
+                "until": until
             }),
         )
         .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["snoozed_until"], until);
+        assert!(body["changed"].as_bool().unwrap());
+
+        let (_, body) = post_json(app.clone(), "/api/inbox", inbox.clone()).await;
+        assert!(body.as_array().unwrap().is_empty());
 
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/message/snoozed",
+            json!({"project_slug": project_slug, "agent_name": "ExtRecipient"}),
+        )
+        .await;
         assert_eq!(status, StatusCode::OK);
-        assert!(body.is_array());
+        assert_eq!(body[0]["id"], message_id);
+        assert_eq!(body[0]["snoozed_until"], until);
+
+        // Only recipients can snooze
+        let (status, _) = post_json(
+            app.clone(),
+            "/api/message/snooze",
+            json!({
+                "project_slug": project_slug,
+                "agent_name": "ExtSender",
+                "message_id": message_id,
+                "until": until
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = post_json(
+            app.clone(),
+            "/api/message/snooze",
+            json!({
+                "project_slug": project_slug,
+                "agent_name": "ExtRecipient",
+                "message_id": message_id,
+                "until": "2000-01-01T00:00:00"
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/message/snooze",
+            json!({
+                "project_slug": project_slug,
+                "agent_name": "ExtRecipient",
+                "message_id": message_id
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["changed"].as_bool().unwrap());
+        assert!(body["snoozed_until"].is_null());
+
+        let (_, body) = post_json(app, "/api/inbox", inbox).await;
+        assert_eq!(body[0]["id"], message_id);
+        assert!(body[0].get("returned_from_snooze").is_none());
     }
 }
 
 // =============================================================================
-// Precommit Guard Tests
+// File Reservation Extended Tests
 // =============================================================================
 
-mod precommit_guard_tests {
+mod file_reservation_extended_tests {
     use super::*;
 
-    #[tokio::test]
-    async fn test_install_precommit_guard() {
-        let (state, _temp) = create_test_state().await;
-
+    async fn setup_with_reservation(state: &AppState) -> (String, String, i64) {
         // Create project
         let app = Router::new()
             .route("/api/project/ensure", post(tools::ensure_project))
@@ -2410,144 +3565,211 @@ mod precommit_guard_tests {
         let (_, proj) = post_json(
             app,
             "/api/project/ensure",
-            json!({"human_key": "guard-test-proj"}),
+            json!({"human_key": "file-res-ext-proj"}),
         )
         .await;
         let project_slug = proj["slug"].as_str().unwrap().to_string();
 
-        // Create a temp directory for the repo
-        let temp_repo = tempfile::tempdir().unwrap();
-        let repo_path = temp_repo.path().to_string_lossy().to_string();
-        std::fs::create_dir_all(temp_repo.path().join(".git/hooks")).unwrap();
+        // Create agent
+        let app = Router::new()
+            .route("/api/agent/register", post(tools::register_agent))
+            .with_state(state.clone());
+        post_json(
+            app,
+            "/api/agent/register",
+            json!({
+                "project_slug": project_slug,
+                "name": "FileResAgent",
+                "program": "test",
+                "model": "test"
+            }),
+        )
+        .await;
 
+        // Create reservation
         let app = Router::new()
             .route(
-                "/api/setup/install_guard",
-                post(tools::install_precommit_guard),
+                "/api/file_reservations/paths",
+                post(tools::file_reservation_paths),
             )
-            .with_state(state);
-
-        let (status, body) = post_json(
+            .with_state(state.clone());
+        let (_, res) = post_json(
             app,
-            "/api/setup/install_guard",
+            "/api/file_reservations/paths",
             json!({
                 "project_slug": project_slug,
-                "target_repo_path": repo_path
+                "agent_name": "FileResAgent",
+                "paths": ["ext-test/**"],
+                "exclusive": true,
+                "ttl_seconds": 3600
             }),
         )
         .await;
 
-        assert_eq!(status, StatusCode::OK);
-        assert!(body["installed"].as_bool().unwrap());
+        let res_id = res["granted"].as_array().unwrap()[0]["id"]
+            .as_i64()
+            .unwrap();
+        (project_slug, "FileResAgent".to_string(), res_id)
     }
 
     #[tokio::test]
-    async fn test_uninstall_precommit_guard() {
+    async fn test_renew_file_reservation() {
         let (state, _temp) = create_test_state().await;
-
-        let temp_repo = tempfile::tempdir().unwrap();
-        let hooks_dir = temp_repo.path().join(".git/hooks");
-        std::fs::create_dir_all(&hooks_dir).unwrap();
-        std::fs::write(
-            hooks_dir.join("pre-commit"),
-            "#!/bin/bash\n# Mouchak Mail Pre-commit Guard\necho 'checking'",
-        )
-        .unwrap();
-
-        let repo_path = temp_repo.path().to_string_lossy().to_string();
+        let (_project_slug, _agent_name, reservation_id) = setup_with_reservation(&state).await;
 
         let app = Router::new()
             .route(
-                "/api/setup/uninstall_guard",
-                post(tools::uninstall_precommit_guard),
+                "/api/file_reservations/renew",
+                post(tools::renew_file_reservation),
             )
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/setup/uninstall_guard",
+            "/api/file_reservations/renew",
             json!({
-                "target_repo_path": repo_path
+                "reservation_id": reservation_id,
+                "ttl_seconds": 7200
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body["uninstalled"].as_bool().unwrap());
+        assert!(body["renewed"].as_bool().unwrap());
     }
-}
-
-// =============================================================================
-// Metrics Tests
-// =============================================================================
-
-mod metrics_tests {
-    use super::*;
 
     #[tokio::test]
-    async fn test_list_tool_metrics() {
+    async fn test_renew_file_reservation_released_conflict() {
         let (state, _temp) = create_test_state().await;
+        let (_project_slug, _agent_name, reservation_id) = setup_with_reservation(&state).await;
 
         let app = Router::new()
-            .route("/api/metrics/tools", get(tools::list_tool_metrics))
+            .route(
+                "/api/file_reservations/force_release",
+                post(tools::force_release_reservation),
+            )
+            .route(
+                "/api/file_reservations/renew",
+                post(tools::renew_file_reservation),
+            )
             .with_state(state);
 
-        let (status, body) = get_json(app, "/api/metrics/tools").await;
-
+        let (status, _) = post_json(
+            app.clone(),
+            "/api/file_reservations/force_release",
+            json!({ "reservation_id": reservation_id }),
+        )
+        .await;
         assert_eq!(status, StatusCode::OK);
-        assert!(body.is_array() || body.is_object());
+
+        let (status, _) = post_json(
+            app,
+            "/api/file_reservations/renew",
+            json!({
+                "reservation_id": reservation_id,
+                "ttl_seconds": 600
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::CONFLICT);
     }
 
     #[tokio::test]
-    async fn test_get_tool_stats() {
+    async fn test_force_release_reservation() {
         let (state, _temp) = create_test_state().await;
+        let (_project_slug, _agent_name, reservation_id) = setup_with_reservation(&state).await;
 
         let app = Router::new()
-            .route("/api/metrics/stats", get(tools::get_tool_stats))
+            .route(
+                "/api/file_reservations/force_release",
+                post(tools::force_release_reservation),
+            )
             .with_state(state);
 
-        let (status, body) = get_json(app, "/api/metrics/stats").await;
+        let (status, body) = post_json(
+            app,
+            "/api/file_reservations/force_release",
+            json!({
+                "reservation_id": reservation_id
+            }),
+        )
+        .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body.is_array() || body.is_object());
+        assert!(body["released"].as_bool().unwrap());
     }
+}
+
+// =============================================================================
+// Quota Tests
+// =============================================================================
+
+mod quota_tests {
+    use super::*;
 
     #[tokio::test]
-    async fn test_list_activity() {
+    async fn test_get_quota_status() {
         let (state, _temp) = create_test_state().await;
 
+        // Create project
         let app = Router::new()
             .route("/api/project/ensure", post(tools::ensure_project))
             .with_state(state.clone());
         let (_, proj) = post_json(
             app,
             "/api/project/ensure",
-            json!({"human_key": "activity-test-proj"}),
+            json!({"human_key": "quota-test-proj"}),
         )
         .await;
-        let project_id = proj["id"].as_i64().unwrap();
+        let project_slug = proj["slug"].as_str().unwrap().to_string();
 
+        // Create agent
         let app = Router::new()
-            .route("/api/activity", get(tools::list_activity))
+            .route("/api/agent/register", post(tools::register_agent))
+            .with_state(state.clone());
+        post_json(
+            app,
+            "/api/agent/register",
+            json!({
+                "project_slug": project_slug,
+                "name": "QuotaAgent",
+                "program": "test",
+                "model": "test"
+            }),
+        )
+        .await;
+
+        let app = Router::new()
+            .route("/api/quota/status", post(tools::get_quota_status))
             .with_state(state);
 
-        let (status, body) =
-            get_json(app, &format!("/api/activity?project_id={}", project_id)).await;
+        let (status, body) = post_json(
+            app,
+            "/api/quota/status",
+            json!({
+                "project_slug": project_slug,
+                "agent_name": "QuotaAgent"
+            }),
+        )
+        .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body.is_array());
+        assert!(body["quota_enabled"].is_boolean());
+        assert!(body["attachments_limit_bytes"].as_i64().is_some());
+        assert!(body["attachments_usage_bytes"].as_i64().is_some());
     }
 }
 
 // =============================================================================
-// Pending Reviews Tests
+// Overseer Tests
 // =============================================================================
 
-mod pending_reviews_tests {
+mod overseer_tests {
     use super::*;
 
     #[tokio::test]
-    async fn test_list_pending_reviews() {
+    async fn test_send_overseer_message() {
         let (state, _temp) = create_test_state().await;
 
         // Create project
@@ -2557,18 +3779,76 @@ mod pending_reviews_tests {
         let (_, proj) = post_json(
             app,
             "/api/project/ensure",
-            json!({"human_key": "pending-rev-proj"}),
+            json!({"human_key": "overseer-test-proj"}),
         )
         .await;
         let project_slug = proj["slug"].as_str().unwrap().to_string();
 
-        // Create agents
-        for name in ["ReviewSender", "ReviewRecipient"] {
-            let app = Router::new()
-                .route("/api/agent/register", post(tools::register_agent))
-                .with_state(state.clone());
+        // Create agent
+        let app = Router::new()
+            .route("/api/agent/register", post(tools::register_agent))
+            .with_state(state.clone());
+        post_json(
+            app,
+            "/api/agent/register",
+            json!({
+                "project_slug": project_slug,
+                "name": "OverseerTarget",
+                "program": "test",
+                "model": "test"
+            }),
+        )
+        .await;
+
+        let app = Router::new()
+            .route("/api/overseer/send", post(tools::send_overseer_message))
+            .with_state(state);
+
+        let (status, body) = post_json(
+            app,
+            "/api/overseer/send",
+            json!({
+                "project_slug": project_slug,
+                "agent_name": "OverseerTarget",
+                "subject": "Human Guidance",
+                "body_md": "Please review this code"
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["sent"].as_bool().unwrap());
+        assert!(body["message_id"].as_i64().unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_notifications_for_overseer() {
+        let (state, _temp) = create_test_state().await;
+
+        let app = Router::new()
+            .route("/api/project/ensure", post(tools::ensure_project))
+            .route("/api/agent/register", post(tools::register_agent))
+            .route("/api/message/send", post(tools::send_message))
+            .route(
+                "/api/notifications",
+                get(mouchak_mail_server::api::notifications::list_notifications),
+            )
+            .route(
+                "/api/notifications/dismiss",
+                post(mouchak_mail_server::api::notifications::dismiss_notifications),
+            )
+            .with_state(state);
+
+        let (_, proj) = post_json(
+            app.clone(),
+            "/api/project/ensure",
+            json!({"human_key": "notify-test-proj"}),
+        )
+        .await;
+        let project_slug = proj["slug"].as_str().unwrap().to_string();
+        for name in ["Overseer", "Worker"] {
             post_json(
-                app,
+                app.clone(),
                 "/api/agent/register",
                 json!({
                     "project_slug": project_slug,
@@ -2580,76 +3860,1274 @@ mod pending_reviews_tests {
             .await;
         }
 
-        // Send message with ack_required
+        let (status, sent) = post_json(
+            app.clone(),
+            "/api/message/send",
+            json!({
+                "project_slug": project_slug,
+                "sender_name": "Overseer",
+                "recipient_names": ["Worker"],
+                "subject": "Ship it",
+                "body_md": "Confirm when done",
+                "ack_required": true
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        // Unauthenticated requests act as the overseer
+        let (status, body) = get_json(app.clone(), "/api/notifications").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["actor"], "Overseer");
+        assert_eq!(body["count"], 1);
+        let notification = &body["notifications"][0];
+        assert_eq!(notification["kind"], "ack_pending");
+        assert_eq!(notification["message_id"], sent["id"]);
+        assert_eq!(notification["project_slug"], project_slug.as_str());
+
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/notifications/dismiss",
+            json!({"keys": [notification["key"]]}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["dismissed"], 1);
+
+        let (_, body) = get_json(app.clone(), "/api/notifications").await;
+        assert_eq!(body["count"], 0);
+
+        let (status, _) = post_json(app, "/api/notifications/dismiss", json!({"keys": []})).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
+
+// =============================================================================
+// Macro Extended Tests
+// =============================================================================
+
+mod macro_extended_tests {
+    use super::*;
+
+    async fn setup_project(state: &AppState) -> String {
         let app = Router::new()
-            .route("/api/message/send", post(tools::send_message))
+            .route("/api/project/ensure", post(tools::ensure_project))
+            .with_state(state.clone());
+        let (_, proj) = post_json(
+            app,
+            "/api/project/ensure",
+            json!({"human_key": "macro-ext-proj"}),
+        )
+        .await;
+        proj["slug"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_unregister_macro() {
+        let (state, _temp) = create_test_state().await;
+        let project_slug = setup_project(&state).await;
+
+        // Register macro first
+        let app = Router::new()
+            .route("/api/macros/register", post(tools::register_macro))
             .with_state(state.clone());
         post_json(
             app,
-            "/api/message/send",
+            "/api/macros/register",
             json!({
                 "project_slug": project_slug,
-                "sender_name": "ReviewSender",
-                "recipient_names": ["ReviewRecipient"],
-                "subject": "Needs Review",
-                "body_md": "Please review this",
-                "ack_required": true
+                "name": "test-unregister-macro",
+                "description": "Macro to unregister",
+                "steps": [{"action": "test"}]
             }),
         )
         .await;
 
+        // Unregister it
         let app = Router::new()
-            .route(
-                "/api/messages/pending-reviews",
-                get(tools::list_pending_reviews),
-            )
+            .route("/api/macros/unregister", post(tools::unregister_macro))
             .with_state(state);
 
-        let (status, body) = get_json(app, "/api/messages/pending-reviews").await;
+        let (status, body) = post_json(
+            app,
+            "/api/macros/unregister",
+            json!({
+                "project_slug": project_slug,
+                "name": "test-unregister-macro"
+            }),
+        )
+        .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body["pending_reviews"].is_array());
-        assert!(body["total_count"].as_i64().is_some());
+        assert!(body["deleted"].as_bool().unwrap());
     }
-}
 
-// =============================================================================
-// Project Siblings Tests
-// =============================================================================
+    #[tokio::test]
+    async fn test_macro_start_session() {
+        let (state, _temp) = create_test_state().await;
+        let project_slug = setup_project(&state).await;
 
-mod siblings_tests {
-    use super::*;
+        let app = Router::new()
+            .route(
+                "/api/macros/start_session",
+                post(tools::macro_start_session),
+            )
+            .with_state(state);
+
+        let (status, body) = post_json(
+            app,
+            "/api/macros/start_session",
+            json!({
+                "project_slug": project_slug,
+                "name": "MacroSessionAgent",
+                "program": "claude-code",
+                "model": "claude-opus-4",
+                "patterns": ["macro-session/**"]
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["agent_id"].as_i64().unwrap() > 0);
+        assert_eq!(body["agent_name"], "MacroSessionAgent");
+    }
 
     #[tokio::test]
-    async fn test_list_project_siblings() {
+    async fn test_macro_file_reservation_cycle() {
         let (state, _temp) = create_test_state().await;
+        let project_slug = setup_project(&state).await;
 
-        // Create project
+        // Create agent
         let app = Router::new()
-            .route("/api/project/ensure", post(tools::ensure_project))
+            .route("/api/agent/register", post(tools::register_agent))
             .with_state(state.clone());
-        let (_, proj) = post_json(
+        post_json(
             app,
-            "/api/project/ensure",
-            json!({"human_key": "sibling-test-proj"}),
+            "/api/agent/register",
+            json!({
+                "project_slug": project_slug,
+                "name": "FileCycleAgent",
+                "program": "test",
+                "model": "test"
+            }),
         )
         .await;
-        let project_slug = proj["slug"].as_str().unwrap().to_string();
 
         let app = Router::new()
-            .route("/api/project/siblings", post(tools::list_project_siblings))
+            .route(
+                "/api/macros/file_reservation_cycle",
+                post(tools::macro_file_reservation_cycle),
+            )
             .with_state(state);
 
         let (status, body) = post_json(
             app,
-            "/api/project/siblings",
+            "/api/macros/file_reservation_cycle",
             json!({
-                "project_slug": project_slug
+                "project_slug": project_slug,
+                "agent_name": "FileCycleAgent",
+                "patterns": ["cycle-test/**"],
+                "action": "reserve"
             }),
         )
         .await;
 
         assert_eq!(status, StatusCode::OK);
-        assert!(body.is_array());
+        assert_eq!(body["action"], "reserve");
+        assert!(body["affected_count"].as_i64().unwrap() >= 0);
+    }
+
+    #[tokio::test]
+    async fn test_macro_contact_handshake() {
+        let (state, _temp) = create_test_state().await;
+        let project_slug = setup_project(&state).await;
+
+        for name in ["HandshakeRequester", "HandshakeTarget"] {
+            let app = Router::new()
+                .route("/api/agent/register", post(tools::register_agent))
+                .with_state(state.clone());
+            post_json(
+                app,
+                "/api/agent/register",
+                json!({
+                    "project_slug": project_slug,
+                    "name": name,
+                    "program": "test",
+                    "model": "test"
+                }),
+            )
+            .await;
+        }
+
+        let app = Router::new()
+            .route(
+                "/api/macros/contact_handshake",
+                post(tools::macro_contact_handshake),
+            )
+            .with_state(state);
+
+        let (status, body) = post_json(
+            app,
+            "/api/macros/contact_handshake",
+            json!({
+                "project_slug": project_slug,
+                "requester": "HandshakeRequester",
+                "target": "HandshakeTarget"
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["contacts_created"], 1);
+        assert!(body["link_ids"].as_array().is_some());
+    }
+}
+
+// =============================================================================
+// Thread Extended Tests
+// =============================================================================
+
+mod thread_extended_tests {
+    use super::*;
+
+    async fn setup_with_threads(state: &AppState) -> String {
+        // Create project
+        let app = Router::new()
+            .route("/api/project/ensure", post(tools::ensure_project))
+            .with_state(state.clone());
+        let (_, proj) = post_json(
+            app,
+            "/api/project/ensure",
+            json!({"human_key": "thread-ext-proj"}),
+        )
+        .await;
+        let project_slug = proj["slug"].as_str().unwrap().to_string();
+
+        // Create agents
+        for name in ["ThreadExtSender", "ThreadExtRecipient"] {
+            let app = Router::new()
+                .route("/api/agent/register", post(tools::register_agent))
+                .with_state(state.clone());
+            post_json(
+                app,
+                "/api/agent/register",
+                json!({
+                    "project_slug": project_slug,
+                    "name": name,
+                    "program": "test",
+                    "model": "test"
+                }),
+            )
+            .await;
+        }
+
+        // Create messages in multiple threads
+        for i in 1..=2 {
+            let app = Router::new()
+                .route("/api/message/send", post(tools::send_message))
+                .with_state(state.clone());
+            post_json(
+                app,
+                "/api/message/send",
+                json!({
+                    "project_slug": project_slug,
+                    "sender_name": "ThreadExtSender",
+                    "recipient_names": ["ThreadExtRecipient"],
+                    "subject": format!("Thread {} Message", i),
+                    "body_md": format!("Message body {}", i),
+                    "thread_id": format!("MULTI-THREAD-{}", i),
+                    "allow_new_thread": true
+                }),
+            )
+            .await;
+        }
+
+        project_slug
+    }
+
+    #[tokio::test]
+    async fn test_summarize_threads() {
+        let (state, _temp) = create_test_state().await;
+        let project_slug = setup_with_threads(&state).await;
+
+        let app = Router::new()
+            .route("/api/threads/summarize", post(tools::summarize_threads))
+            .with_state(state);
+
+        let (status, body) = post_json(
+            app,
+            "/api/threads/summarize",
+            json!({
+                "project_slug": project_slug,
+                "limit": 10
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.is_array());
+    }
+}
+
+// =============================================================================
+// Precommit Guard Tests
+// =============================================================================
+
+mod precommit_guard_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_install_precommit_guard() {
+        let (state, _temp) = create_test_state().await;
+
+        // Create project
+        let app = Router::new()
+            .route("/api/project/ensure", post(tools::ensure_project))
+            .with_state(state.clone());
+        let (_, proj) = post_json(
+            app,
+            "/api/project/ensure",
+            json!({"human_key": "guard-test-proj"}),
+        )
+        .await;
+        let project_slug = proj["slug"].as_str().unwrap().to_string();
+
+        // Create a temp directory for the repo
+        let temp_repo = tempfile::tempdir().unwrap();
+        let repo_path = temp_repo.path().to_string_lossy().to_string();
+        std::fs::create_dir_all(temp_repo.path().join(".git/hooks")).unwrap();
+
+        let app = Router::new()
+            .route(
+                "/api/setup/install_guard",
+                post(tools::install_precommit_guard),
+            )
+            .with_state(state);
+
+        let (status, body) = post_json(
+            app,
+            "/api/setup/install_guard",
+            json!({
+                "project_slug": project_slug,
+                "target_repo_path": repo_path
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["installed"].as_bool().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_uninstall_precommit_guard() {
+        let (state, _temp) = create_test_state().await;
+
+        let temp_repo = tempfile::tempdir().unwrap();
+        let hooks_dir = temp_repo.path().join(".git/hooks");
+        std::fs::create_dir_all(&hooks_dir).unwrap();
+        std::fs::write(
+            hooks_dir.join("pre-commit"),
+            "#!/bin/bash\n# Mouchak Mail Pre-commit Guard\necho 'checking'",
+        )
+        .unwrap();
+
+        let repo_path = temp_repo.path().to_string_lossy().to_string();
+
+        let app = Router::new()
+            .route(
+                "/api/setup/uninstall_guard",
+                post(tools::uninstall_precommit_guard),
+            )
+            .with_state(state);
+
+        let (status, body) = post_json(
+            app,
+            "/api/setup/uninstall_guard",
+            json!({
+                "target_repo_path": repo_path
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["uninstalled"].as_bool().unwrap());
+    }
+}
+
+// =============================================================================
+// Metrics Tests
+// =============================================================================
+
+mod metrics_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_tool_metrics() {
+        let (state, _temp) = create_test_state().await;
+
+        let app = Router::new()
+            .route("/api/metrics/tools", get(tools::list_tool_metrics))
+            .with_state(state);
+
+        let (status, body) = get_json(app, "/api/metrics/tools").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.is_array() || body.is_object());
+    }
+
+    #[tokio::test]
+    async fn test_get_tool_stats() {
+        let (state, _temp) = create_test_state().await;
+
+        let app = Router::new()
+            .route("/api/metrics/stats", get(tools::get_tool_stats))
+            .with_state(state);
+
+        let (status, body) = get_json(app, "/api/metrics/stats").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.is_array() || body.is_object());
+    }
+
+    #[tokio::test]
+    async fn test_get_tool_metrics_summary() {
+        let (state, _temp) = create_test_state().await;
+
+        let app = Router::new()
+            .route(
+                "/api/metrics/tools/summary",
+                get(tools::get_tool_metrics_summary),
+            )
+            .with_state(state);
+
+        let (status, body) = get_json(app.clone(), "/api/metrics/tools/summary").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total_calls"], 0);
+        assert!(body["tools"].is_array());
+
+        let (status, _) = get_json(app, "/api/metrics/tools/summary?since=yesterday").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_list_tool_metrics_usage() {
+        use mouchak_mail_core::model::tool_metric::{ToolMetricBmc, ToolMetricForCreate};
+
+        let (state, _temp) = create_test_state().await;
+        for (status, duration_ms) in [("success", 10), ("error", 30)] {
+            ToolMetricBmc::create(
+                &mouchak_mail_core::Ctx::root_ctx(),
+                &state.mm,
+                ToolMetricForCreate {
+                    project_id: None,
+                    agent_id: None,
+                    tool_name: "send_message".to_string(),
+                    args_json: None,
+                    status: status.to_string(),
+                    error_code: None,
+                    duration_ms,
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let app = Router::new()
+            .route("/api/metrics/tools", get(tools::list_tool_metrics))
+            .with_state(state);
+
+        let since = (chrono::Utc::now() - chrono::Duration::hours(1))
+            .format("%Y-%m-%dT%H:%M:%SZ")
+            .to_string();
+        let (status, body) =
+            get_json(app.clone(), &format!("/api/metrics/tools?since={}", since)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["total_calls"], 2);
+        assert_eq!(body["tool_count"], 1);
+        assert_eq!(body["tools"][0]["tool_name"], "send_message");
+        assert_eq!(body["tools"][0]["errors"], 1);
+        assert_eq!(body["tools"][0]["p95_ms"], 30);
+        assert_eq!(body["tools"][0]["buckets"].as_array().unwrap().len(), 24);
+
+        // Without since it still lists raw invocations
+        let (_, body) = get_json(app.clone(), "/api/metrics/tools").await;
+        assert_eq!(body.as_array().unwrap().len(), 2);
+
+        let (status, _) = get_json(app, "/api/metrics/tools?since=yesterday").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_list_activity() {
+        let (state, _temp) = create_test_state().await;
+
+        let app = Router::new()
+            .route("/api/project/ensure", post(tools::ensure_project))
+            .with_state(state.clone());
+        let (_, proj) = post_json(
+            app,
+            "/api/project/ensure",
+            json!({"human_key": "activity-test-proj"}),
+        )
+        .await;
+        let project_id = proj["id"].as_i64().unwrap();
+
+        let app = Router::new()
+            .route("/api/activity", get(tools::list_activity))
+            .with_state(state);
+
+        let (status, body) =
+            get_json(app, &format!("/api/activity?project_id={}", project_id)).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.is_array());
+    }
+}
+
+// =============================================================================
+// Pending Reviews Tests
+// =============================================================================
+
+mod pending_reviews_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_pending_reviews() {
+        let (state, _temp) = create_test_state().await;
+
+        // Create project
+        let app = Router::new()
+            .route("/api/project/ensure", post(tools::ensure_project))
+            .with_state(state.clone());
+        let (_, proj) = post_json(
+            app,
+            "/api/project/ensure",
+            json!({"human_key": "pending-rev-proj"}),
+        )
+        .await;
+        let project_slug = proj["slug"].as_str().unwrap().to_string();
+
+        // Create agents
+        for name in ["ReviewSender", "ReviewRecipient"] {
+            let app = Router::new()
+                .route("/api/agent/register", post(tools::register_agent))
+                .with_state(state.clone());
+            post_json(
+                app,
+                "/api/agent/register",
+                json!({
+                    "project_slug": project_slug,
+                    "name": name,
+                    "program": "test",
+                    "model": "test"
+                }),
+            )
+            .await;
+        }
+
+        // Send message with ack_required
+        let app = Router::new()
+            .route("/api/message/send", post(tools::send_message))
+            .with_state(state.clone());
+        post_json(
+            app,
+            "/api/message/send",
+            json!({
+                "project_slug": project_slug,
+                "sender_name": "ReviewSender",
+                "recipient_names": ["ReviewRecipient"],
+                "subject": "Needs Review",
+                "body_md": "Please review this",
+                "ack_required": true
+            }),
+        )
+        .await;
+
+        let app = Router::new()
+            .route(
+                "/api/messages/pending-reviews",
+                get(tools::list_pending_reviews),
+            )
+            .with_state(state);
+
+        let (status, body) = get_json(app, "/api/messages/pending-reviews").await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body["pending_reviews"].is_array());
+        assert!(body["total_count"].as_i64().is_some());
+    }
+}
+
+// =============================================================================
+// Project Siblings Tests
+// =============================================================================
+
+mod siblings_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_list_project_siblings() {
+        let (state, _temp) = create_test_state().await;
+
+        // Create project
+        let app = Router::new()
+            .route("/api/project/ensure", post(tools::ensure_project))
+            .with_state(state.clone());
+        let (_, proj) = post_json(
+            app,
+            "/api/project/ensure",
+            json!({"human_key": "sibling-test-proj"}),
+        )
+        .await;
+        let project_slug = proj["slug"].as_str().unwrap().to_string();
+
+        let app = Router::new()
+            .route("/api/project/siblings", post(tools::list_project_siblings))
+            .with_state(state);
+
+        let (status, body) = post_json(
+            app,
+            "/api/project/siblings",
+            json!({
+                "project_slug": project_slug
+            }),
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.is_array());
+    }
+}
+
+// =============================================================================
+// Archive Integrity Tests
+// =============================================================================
+
+mod archive_integrity_tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_verify_archive() {
+        let (state, _temp) = create_test_state().await;
+
+        let app = Router::new()
+            .route("/api/project/ensure", post(tools::ensure_project))
+            .with_state(state.clone());
+        let (_, proj) = post_json(
+            app,
+            "/api/project/ensure",
+            json!({"human_key": "verify-archive-proj"}),
+        )
+        .await;
+        let project_slug = proj["slug"].as_str().unwrap().to_string();
+
+        let app = Router::new()
+            .route("/api/archive/verify", post(tools::verify_archive))
+            .with_state(state);
+
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/archive/verify",
+            json!({"project_slug": project_slug}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["project_slug"], project_slug.as_str());
+        assert_eq!(body["missing_count"], 0);
+        assert_eq!(body["repaired_count"], 0);
+
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/archive/verify",
+            json!({"project_slug": project_slug, "repair": true}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["repaired_count"], 0);
+
+        let (status, _) = post_json(
+            app,
+            "/api/archive/verify",
+            json!({"project_slug": "no-such-project"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
+
+// =============================================================================
+// Event Stream Tests
+// =============================================================================
+
+mod event_stream_tests {
+    use super::*;
+    use mouchak_mail_core::events::MailEventKind;
+    use mouchak_mail_server::api::events;
+
+    #[tokio::test]
+    async fn test_event_stream_replays_after_last_event_id() {
+        let (state, _temp) = create_test_state().await;
+        let bus = state.mm.events.clone();
+
+        bus.publish(MailEventKind::MessageCreated, "alpha", json!({ "id": 1 }));
+        bus.publish(MailEventKind::MessageCreated, "beta", json!({ "id": 2 }));
+        bus.publish(
+            MailEventKind::MessageRead,
+            "alpha",
+            json!({ "message_id": 1 }),
+        );
+
+        let app = Router::new()
+            .route("/api/events", get(events::event_stream))
+            .with_state(state);
+
+        let request = Request::builder()
+            .method("GET")
+            .uri("/api/events?project=alpha")
+            .header("Last-Event-ID", "1")
+            .body(Body::empty())
+            .unwrap();
+
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(
+            response.headers()["content-type"]
+                .to_str()
+                .unwrap()
+                .starts_with("text/event-stream")
+        );
+        assert_eq!(bus.receiver_count(), 1);
+
+        let mut body = response.into_body();
+        let mut received = String::new();
+        while !received.contains("id: 3") {
+            let frame = tokio::time::timeout(std::time::Duration::from_secs(2), body.frame())
+                .await
+                .expect("Timed out waiting for replayed event")
+                .unwrap()
+                .unwrap();
+            if let Ok(data) = frame.into_data() {
+                received.push_str(&String::from_utf8_lossy(&data));
+            }
+        }
+
+        assert!(received.contains("event: message.read"));
+        assert!(!received.contains("id: 1\n"), "Already-seen event replayed");
+        assert!(!received.contains("beta"), "Other project's event leaked");
+
+        // Disconnecting drops the subscription
+        drop(body);
+        assert_eq!(bus.receiver_count(), 0);
+    }
+}
+
+// =============================================================================
+// Project Version Tests
+// =============================================================================
+
+mod project_version_tests {
+    use super::*;
+    use mouchak_mail_core::Ctx;
+    use mouchak_mail_core::model::project::{AgentConflictPolicy, ProjectBmc, RetentionPolicy};
+    use mouchak_mail_core::model::retention::RetentionBmc;
+    use mouchak_mail_server::api::project_version;
+
+    fn create_app(state: AppState) -> Router {
+        Router::new()
+            .route("/api/project/ensure", post(tools::ensure_project))
+            .route("/api/agent/register", post(tools::register_agent))
+            .route("/api/message/send", post(tools::send_message))
+            .route(
+                "/api/project/{slug}/version",
+                get(project_version::project_version),
+            )
+            .route(
+                "/api/projects/{project_slug}/agents",
+                get(tools::list_all_agents_for_project),
+            )
+            .route(
+                "/api/projects/{project_slug}",
+                axum::routing::delete(tools::delete_project),
+            )
+            .route(
+                "/api/project/{project_slug}/agent/{agent_name}",
+                axum::routing::patch(tools::update_agent).delete(tools::retire_agent),
+            )
+            .route_layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                project_version::project_version_middleware,
+            ))
+            .with_state(state)
+    }
+
+    async fn version(app: &Router, slug: &str) -> i64 {
+        let (status, body) = get_json(app.clone(), &format!("/api/project/{}/version", slug)).await;
+        assert_eq!(status, StatusCode::OK);
+        body["version"].as_i64().unwrap()
+    }
+
+    async fn call(app: &Router, method: &str, uri: &str, body: Option<Value>) -> StatusCode {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("Content-Type", "application/json")
+            .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+            .unwrap();
+        app.clone().oneshot(request).await.unwrap().status()
+    }
+
+    async fn project_with_agent(app: &Router, human_key: &str, agent: &str) -> String {
+        let (_, body) = post_json(
+            app.clone(),
+            "/api/project/ensure",
+            json!({ "human_key": human_key }),
+        )
+        .await;
+        let slug = body["slug"].as_str().unwrap().to_string();
+        post_json(
+            app.clone(),
+            "/api/agent/register",
+            json!({ "project_slug": slug, "name": agent, "program": "test", "model": "test" }),
+        )
+        .await;
+        slug
+    }
+
+    #[tokio::test]
+    async fn test_structural_changes_bump_version() {
+        let (state, _temp) = create_test_state().await;
+        let app = create_app(state.clone());
+        let ctx = Ctx::root_ctx();
+
+        let alpha = project_with_agent(&app, "/ver/alpha", "Worker").await;
+        let beta = project_with_agent(&app, "/ver/beta", "Visitor").await;
+        assert_eq!(version(&app, &alpha).await, 0);
+
+        // Editing the task leaves the version alone; renaming bumps it
+        let agent_uri = |name: &str| format!("/api/project/{}/agent/{}", alpha, name);
+        let status = call(
+            &app,
+            "PATCH",
+            &agent_uri("Worker"),
+            Some(json!({ "task_description": "busy" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(version(&app, &alpha).await, 0);
+
+        let status = call(
+            &app,
+            "PATCH",
+            &agent_uri("Worker"),
+            Some(json!({ "name": "Builder" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(version(&app, &alpha).await, 1);
+
+        // Retire
+        let (status, _) = post_json(
+            app.clone(),
+            "/api/message/send",
+            json!({
+                "project_slug": alpha,
+                "sender_name": "Builder",
+                "recipient_names": ["Builder"],
+                "subject": "old news",
+                "body_md": "prune me"
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            call(&app, "DELETE", &agent_uri("Builder"), None).await,
+            StatusCode::OK
+        );
+        assert_eq!(version(&app, &alpha).await, 2);
+
+        // Prune
+        let alpha_id = ProjectBmc::get_by_slug(&ctx, &state.mm, &alpha)
+            .await
+            .unwrap()
+            .id;
+        let policy = RetentionPolicy {
+            keep_unread: false,
+            ..RetentionPolicy::days(1)
+        };
+        ProjectBmc::set_retention(&ctx, &state.mm, alpha_id, policy)
+            .await
+            .unwrap();
+        state
+            .mm
+            .db_for_test()
+            .execute(
+                "UPDATE messages SET created_ts = '2020-01-01 00:00:00' WHERE project_id = ?",
+                [alpha_id.get()],
+            )
+            .await
+            .unwrap();
+        let report = RetentionBmc::prune(&ctx, &state.mm, alpha_id, false)
+            .await
+            .unwrap();
+        assert_eq!(report.pruned, 1);
+        assert_eq!(version(&app, &alpha).await, 3);
+
+        // Adopt bumps both sides
+        let beta_id = ProjectBmc::get_by_slug(&ctx, &state.mm, &beta)
+            .await
+            .unwrap()
+            .id;
+        ProjectBmc::adopt(
+            &ctx,
+            &state.mm,
+            beta_id,
+            alpha_id,
+            &AgentConflictPolicy::Report,
+        )
+        .await
+        .unwrap();
+        assert_eq!(version(&app, &alpha).await, 4);
+        assert_eq!(version(&app, &beta).await, 1);
+
+        // Responses for project-scoped paths carry the version
+        let request = Request::builder()
+            .uri(format!("/api/projects/{}/agents", alpha))
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()["x-project-version"], "4");
+
+        // Delete; the version outlives the project
+        assert_eq!(
+            call(&app, "DELETE", &format!("/api/projects/{}", alpha), None).await,
+            StatusCode::OK
+        );
+        assert_eq!(version(&app, &alpha).await, 5);
+    }
+}
+
+mod reservation_board_tests {
+    use super::*;
+    use mouchak_mail_server::api::reservations;
+
+    fn create_app(state: AppState) -> Router {
+        Router::new()
+            .route("/api/project/ensure", post(tools::ensure_project))
+            .route("/api/agent/register", post(tools::register_agent))
+            .route(
+                "/api/file_reservations/paths",
+                post(tools::file_reservation_paths),
+            )
+            .route(
+                "/api/project/{slug}/reservations",
+                get(reservations::project_reservations),
+            )
+            .route(
+                "/api/project/{slug}/reservations/{id}",
+                axum::routing::delete(reservations::force_release_project_reservation),
+            )
+            .route(
+                "/api/project/{slug}/reservations/{id}/transfer",
+                post(reservations::transfer_project_reservation),
+            )
+            .with_state(state)
+    }
+
+    async fn reserve(app: &Router, slug: &str, agent: &str, path: &str, exclusive: bool) -> i64 {
+        let (status, body) = post_json(
+            app.clone(),
+            "/api/file_reservations/paths",
+            json!({
+                "project_slug": slug,
+                "agent_name": agent,
+                "paths": [path],
+                "exclusive": exclusive,
+                "ttl_seconds": 3600
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        body["granted"][0]["id"].as_i64().unwrap()
+    }
+
+    async fn delete(app: &Router, uri: &str) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method("DELETE")
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_reservation_board_and_force_release() {
+        let (state, _temp) = create_test_state().await;
+        let app = create_app(state);
+
+        let mut slugs = Vec::new();
+        for key in ["/board/alpha", "/board/beta"] {
+            let (_, body) = post_json(
+                app.clone(),
+                "/api/project/ensure",
+                json!({ "human_key": key }),
+            )
+            .await;
+            let slug = body["slug"].as_str().unwrap().to_string();
+            for agent in ["BlueLake", "GreenCastle"] {
+                post_json(
+                    app.clone(),
+                    "/api/agent/register",
+                    json!({ "project_slug": slug, "name": agent, "program": "t", "model": "t" }),
+                )
+                .await;
+            }
+            slugs.push(slug);
+        }
+        let (alpha, beta) = (&slugs[0], &slugs[1]);
+
+        let src = reserve(&app, alpha, "BlueLake", "src/**", true).await;
+        let lib = reserve(&app, alpha, "GreenCastle", "src/lib.rs", false).await;
+        let docs = reserve(&app, alpha, "GreenCastle", "docs/*.md", false).await;
+        let other = reserve(&app, beta, "BlueLake", "src/**", true).await;
+
+        let uri = format!("/api/project/{}/reservations", alpha);
+        let (status, body) = get_json(app.clone(), &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["project_slug"], alpha.as_str());
+        let board = body["reservations"].as_array().unwrap();
+        assert_eq!(board.len(), 3);
+
+        let entry = |id: i64| board.iter().find(|r| r["id"] == id).unwrap();
+        assert_eq!(entry(src)["agent_name"], "BlueLake");
+        assert_eq!(entry(src)["exclusive"], true);
+        assert!(entry(src)["age_seconds"].as_i64().unwrap() >= 0);
+        let left = entry(src)["expires_in_seconds"].as_i64().unwrap();
+        assert!(left > 3500 && left <= 3600, "expires in {}", left);
+        // The exclusive glob overlaps the other agent's file but not their docs
+        assert_eq!(entry(src)["conflicts_with"], json!([lib]));
+        assert_eq!(entry(lib)["conflicts_with"], json!([src]));
+        assert_eq!(entry(docs)["conflicts_with"], json!([]));
+
+        // A reservation from another project is not this project's to release
+        let (status, _) = delete(&app, &format!("{}/{}", uri, other)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, body) = delete(&app, &format!("{}/{}", uri, src)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["released"], true);
+        assert_eq!(body["reservation_id"], src);
+
+        let (_, body) = get_json(app.clone(), &uri).await;
+        let board = body["reservations"].as_array().unwrap();
+        assert_eq!(board.len(), 2);
+        assert!(board.iter().all(|r| r["conflicts_with"] == json!([])));
+
+        // Releasing again is a no-op
+        let (status, body) = delete(&app, &format!("{}/{}", uri, src)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["released"], false);
+    }
+
+    #[tokio::test]
+    async fn test_transfer_project_reservation() {
+        let (state, _temp) = create_test_state().await;
+        let app = create_app(state);
+
+        let (_, body) = post_json(
+            app.clone(),
+            "/api/project/ensure",
+            json!({ "human_key": "/board/transfer" }),
+        )
+        .await;
+        let slug = body["slug"].as_str().unwrap().to_string();
+        for agent in ["BlueLake", "GreenCastle"] {
+            post_json(
+                app.clone(),
+                "/api/agent/register",
+                json!({ "project_slug": slug, "name": agent, "program": "t", "model": "t" }),
+            )
+            .await;
+        }
+        let id = reserve(&app, &slug, "BlueLake", "src/**", true).await;
+        let uri = format!("/api/project/{}/reservations/{}/transfer", slug, id);
+
+        // An unknown target leaves the reservation where it was
+        let (status, body) = post_json(
+            app.clone(),
+            &uri,
+            json!({ "to_agent": "NoSuchAgent", "reason": "crashed" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(body["code"], "NOT_FOUND");
+
+        let (status, body) = post_json(
+            app.clone(),
+            &uri,
+            json!({ "to_agent": "GreenCastle", "reason": "crashed" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["from_agent"], "BlueLake");
+        assert_eq!(body["to_agent"], "GreenCastle");
+        assert_eq!(body["transferred"], true);
+
+        let (_, board) =
+            get_json(app.clone(), &format!("/api/project/{}/reservations", slug)).await;
+        assert_eq!(board["reservations"][0]["agent_name"], "GreenCastle");
+
+        // Transferring to the holder changes nothing
+        let (status, body) =
+            post_json(app.clone(), &uri, json!({ "to_agent": "GreenCastle" })).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["transferred"], false);
+
+        // Released reservations cannot change hands
+        let (status, _) = delete(&app, &format!("/api/project/{}/reservations/{}", slug, id)).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = post_json(app.clone(), &uri, json!({ "to_agent": "BlueLake" })).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+}
+
+// =============================================================================
+// Agent Group Tests
+// =============================================================================
+
+mod agent_group_tests {
+    use super::*;
+    use mouchak_mail_server::api::groups;
+
+    fn create_app(state: AppState) -> Router {
+        Router::new()
+            .route("/api/project/ensure", post(tools::ensure_project))
+            .route("/api/agent/register", post(tools::register_agent))
+            .route("/api/message/send", post(tools::send_message))
+            .route(
+                "/api/project/{slug}/groups",
+                get(groups::list_groups).post(groups::create_group),
+            )
+            .route(
+                "/api/project/{slug}/groups/{name}",
+                get(groups::get_group).delete(groups::delete_group),
+            )
+            .route(
+                "/api/project/{slug}/groups/{name}/members",
+                post(groups::add_group_member),
+            )
+            .route(
+                "/api/project/{slug}/groups/{name}/members/{agent_name}",
+                axum::routing::delete(groups::remove_group_member),
+            )
+            .with_state(state)
+    }
+
+    async fn delete(app: &Router, uri: &str) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method("DELETE")
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_group_endpoints_and_group_send() {
+        let (state, _temp) = create_test_state().await;
+        let app = create_app(state);
+
+        let (_, proj) = post_json(
+            app.clone(),
+            "/api/project/ensure",
+            json!({"human_key": "/groups/http"}),
+        )
+        .await;
+        let slug = proj["slug"].as_str().unwrap().to_string();
+        for name in ["Lead", "BlueLake", "GreenCastle"] {
+            let (status, _) = post_json(
+                app.clone(),
+                "/api/agent/register",
+                json!({"project_slug": slug, "name": name, "program": "test", "model": "test"}),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+        let groups_uri = format!("/api/project/{}/groups", slug);
+
+        let (status, body) = post_json(
+            app.clone(),
+            &groups_uri,
+            json!({"name": "frontend", "members": ["BlueLake"]}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["members"], json!(["BlueLake"]));
+
+        let (status, body) =
+            post_json(app.clone(), &groups_uri, json!({"name": "greencastle"})).await;
+        assert_eq!(status, StatusCode::CONFLICT, "{}", body);
+
+        let (status, body) = post_json(
+            app.clone(),
+            &format!("{}/Frontend/members", groups_uri),
+            json!({"agent_name": "GreenCastle"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["members"], json!(["BlueLake", "GreenCastle"]));
+
+        let send = |to: &str| {
+            json!({
+                "project_slug": slug,
+                "sender_name": "Lead",
+                "recipient_names": [to],
+                "subject": "Standup",
+                "body_md": "Ten minutes"
+            })
+        };
+        let (status, body) =
+            post_json(app.clone(), "/api/message/send", send("group:frontend")).await;
+        assert_eq!(status, StatusCode::OK, "{}", body);
+        assert_eq!(body["groups"][0]["group"], "frontend");
+        assert_eq!(
+            body["groups"][0]["members"],
+            json!(["BlueLake", "GreenCastle"])
+        );
+
+        let (status, body) =
+            delete(&app, &format!("{}/frontend/members/BlueLake", groups_uri)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["members"], json!(["GreenCastle"]));
+        delete(
+            &app,
+            &format!("{}/frontend/members/GreenCastle", groups_uri),
+        )
+        .await;
+
+        let (status, body) =
+            post_json(app.clone(), "/api/message/send", send("group:frontend")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", body);
+        assert_eq!(body["failed"][0]["code"], "GROUP_EMPTY");
+
+        let (status, body) = delete(&app, &format!("{}/frontend", groups_uri)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["deleted"], true);
+        let (status, body) = get_json(app.clone(), &groups_uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(body.as_array().unwrap().is_empty());
+        let (status, _) = get_json(app, &format!("{}/frontend", groups_uri)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
     }
 }
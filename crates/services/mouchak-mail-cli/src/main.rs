@@ -1,6 +1,9 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
+use mouchak_mail_common::output::{CommandOutput, OutputMode};
+use mouchak_mail_common::permalink;
 use mouchak_mail_core::{Ctx, ModelManager};
+use serde::Serialize;
 use std::io::Write;
 use std::str::FromStr;
 use tracing_subscriber::EnvFilter;
@@ -11,6 +14,10 @@ use tracing_subscriber::EnvFilter;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    /// Output mode: human or json
+    #[arg(long, global = true, env = "AM_OUTPUT", default_value_t = OutputMode::Human)]
+    output: OutputMode,
 }
 
 #[derive(Subcommand, Debug)]
@@ -25,7 +32,16 @@ enum Commands {
     /// Run migrations
     Migrate,
     /// Create a new project
-    CreateProject { slug: String, human_key: String },
+    ///
+    /// If another project already has the slug, `-2`, `-3`, ... is appended
+    /// unless `--strict` is given.
+    CreateProject {
+        slug: String,
+        human_key: String,
+        /// Fail instead of suffixing a slug that is already taken
+        #[arg(long, default_value_t = false)]
+        strict: bool,
+    },
     /// Create a new agent
     CreateAgent { project_slug: String, name: String },
     /// Send a message
@@ -63,15 +79,30 @@ enum Commands {
     Export {
         /// Project slug
         project: String,
-        /// Format (json, html, markdown, csv)
+        /// Format (json, html, html-zip, markdown, csv, tsv, ndjson, mbox)
         #[arg(long, default_value = "json")]
         format: String,
-        /// Scrub mode (none, standard, aggressive)
+        /// Scrub mode (none, standard, aggressive, emails, secrets, all)
         #[arg(long, default_value = "none")]
         scrub: String,
         /// Output file (default: stdout)
-        #[arg(short, long)]
-        output: Option<String>,
+        #[arg(short = 'o', long)]
+        out_file: Option<String>,
+        /// Only export messages created at or after this date (RFC3339 or YYYY-MM-DD)
+        #[arg(long)]
+        since: Option<String>,
+        /// Only export messages created at or before this date (RFC3339 or YYYY-MM-DD)
+        #[arg(long)]
+        until: Option<String>,
+        /// Only export messages sent or received by this agent
+        #[arg(long)]
+        agent: Option<String>,
+        /// Only export messages in this thread
+        #[arg(long)]
+        thread: Option<String>,
+        /// Compress the output file (gzip, zstd)
+        #[arg(long, requires = "out_file")]
+        compress: Option<String>,
     },
     /// Archive management (disaster recovery)
     Archive {
@@ -146,6 +177,17 @@ enum ArchiveCommands {
         #[arg(long)]
         yes: bool,
     },
+    /// Check a project's message archive against the database
+    Verify {
+        /// Project slug
+        project: String,
+        /// Re-write missing message files from the database and commit them
+        #[arg(long)]
+        repair: bool,
+        /// Output as JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
 async fn handle_guard_command(cmd: GuardCommands) -> Result<()> {
@@ -194,6 +236,9 @@ enum ProjectsCommands {
         /// Product name
         #[arg(long)]
         product: Option<String>,
+        /// Project slug that MCP tools fall back to when project_slug is omitted
+        #[arg(long)]
+        project: Option<String>,
     },
     /// Status of project
     Status {
@@ -206,9 +251,79 @@ enum ProjectsCommands {
         from: String,
         /// Destination project identifier
         to: String,
+        /// Print the adoption report without changing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Merge agents that exist in both projects into the destination agent
+        #[arg(long, conflicts_with = "rename_suffix")]
+        merge_agents: bool,
+        /// Rename agents that exist in both projects by appending this suffix
+        #[arg(long)]
+        rename_suffix: Option<String>,
+    },
+    /// Show or set how long a project keeps messages in the database
+    ///
+    /// Without options, prints the current policy.
+    Retention {
+        /// Project identifier (slug/key)
+        project: String,
+        /// Prune messages older than this many days
+        #[arg(long, value_parser = clap::value_parser!(i64).range(1..))]
+        days: Option<i64>,
+        /// Also prune messages some recipient hasn't read
+        #[arg(long, requires = "days")]
+        prune_unread: bool,
+        /// Also prune ack-required messages some recipient hasn't acknowledged
+        #[arg(long, requires = "days")]
+        prune_ack_pending: bool,
+        /// Remove the policy and keep messages forever
+        #[arg(long, conflicts_with = "days")]
+        off: bool,
+    },
+    /// Delete messages past the project's retention policy from the database
+    ///
+    /// Messages stay in the Git archive; any without an archive file are kept
+    /// and reported.
+    Prune {
+        /// Project identifier (slug/key)
+        project: String,
+        /// Report what would be pruned without deleting anything
         #[arg(long)]
         dry_run: bool,
     },
+    /// Show the project's audit log of administrative actions, newest first
+    Audit {
+        /// Project identifier (slug/key)
+        project: String,
+        /// Only this action, e.g. agent.delete
+        #[arg(long, value_parser = clap::value_parser!(mouchak_mail_core::model::audit::AuditAction))]
+        action: Option<mouchak_mail_core::model::audit::AuditAction>,
+        /// Only this entity type (project, agent, message, reservation)
+        #[arg(long)]
+        entity_type: Option<String>,
+        /// Only this entity ID
+        #[arg(long, requires = "entity_type")]
+        entity_id: Option<i64>,
+        /// Maximum entries to show
+        #[arg(long, default_value_t = 50, value_parser = clap::value_parser!(i64).range(1..))]
+        limit: i64,
+        /// Show entries older than this ID (a previous page's next_cursor)
+        #[arg(long)]
+        cursor: Option<i64>,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct ProjectCreated {
+    id: i64,
+    slug: String,
+    human_key: String,
+}
+
+impl CommandOutput for ProjectCreated {
+    fn human(&self) -> String {
+        format!("Created project '{}' with ID {}", self.slug, self.id)
+    }
 }
 
 async fn handle_create_project(
@@ -216,11 +331,39 @@ async fn handle_create_project(
     mm: &ModelManager,
     slug: &str,
     human_key: &str,
-) -> Result<()> {
-    let id =
-        mouchak_mail_core::model::project::ProjectBmc::create(ctx, mm, slug, human_key).await?;
-    println!("Created project '{}' with ID {}", slug, id);
-    Ok(())
+    strict: bool,
+) -> Result<ProjectCreated> {
+    use mouchak_mail_core::model::project::ProjectBmc;
+
+    let (id, slug) = if strict {
+        (
+            ProjectBmc::create(ctx, mm, slug, human_key).await?,
+            slug.to_string(),
+        )
+    } else {
+        ProjectBmc::create_with_unique_slug(ctx, mm, slug, human_key).await?
+    };
+    Ok(ProjectCreated {
+        id: id.get(),
+        slug,
+        human_key: human_key.to_string(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct AgentCreated {
+    id: i64,
+    name: String,
+    project_slug: String,
+}
+
+impl CommandOutput for AgentCreated {
+    fn human(&self) -> String {
+        format!(
+            "Created agent '{}' in project '{}' with ID {}",
+            self.name, self.project_slug, self.id
+        )
+    }
 }
 
 async fn handle_create_agent(
@@ -228,7 +371,7 @@ async fn handle_create_agent(
     mm: &ModelManager,
     project_slug: &str,
     name: String,
-) -> Result<()> {
+) -> Result<AgentCreated> {
     let project =
         mouchak_mail_core::model::project::ProjectBmc::get_by_slug(ctx, mm, project_slug).await?;
     let agent_c = mouchak_mail_core::model::agent::AgentForCreate {
@@ -239,11 +382,32 @@ async fn handle_create_agent(
         task_description: "Created via CLI".to_string(),
     };
     let id = mouchak_mail_core::model::agent::AgentBmc::create(ctx, mm, agent_c).await?;
-    println!(
-        "Created agent '{}' in project '{}' with ID {}",
-        name, project_slug, id
-    );
-    Ok(())
+    Ok(AgentCreated {
+        id: id.get(),
+        name,
+        project_slug: project_slug.to_string(),
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct MessageSent {
+    id: i64,
+    project_slug: String,
+    from: String,
+    to: Vec<String>,
+    subject: String,
+    /// Web UI permalink; only set when `server.public_url` is configured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    link: Option<String>,
+}
+
+impl CommandOutput for MessageSent {
+    fn human(&self) -> String {
+        match &self.link {
+            Some(link) => format!("Sent message ID {}\nLink: {}", self.id, link),
+            None => format!("Sent message ID {}", self.id),
+        }
+    }
 }
 
 async fn handle_send_message(
@@ -254,19 +418,19 @@ async fn handle_send_message(
     to: Vec<String>,
     subject: String,
     body: String,
-) -> Result<()> {
+) -> Result<MessageSent> {
     let project =
         mouchak_mail_core::model::project::ProjectBmc::get_by_slug(ctx, mm, project_slug).await?;
     let sender =
         mouchak_mail_core::model::agent::AgentBmc::get_by_name(ctx, mm, project.id, from).await?;
 
     let mut recipient_ids = Vec::new();
-    for recipient_name in to {
+    for recipient_name in &to {
         let recipient = mouchak_mail_core::model::agent::AgentBmc::get_by_name(
             ctx,
             mm,
             project.id,
-            &recipient_name,
+            recipient_name,
         )
         .await?;
         recipient_ids.push(recipient.id.get());
@@ -278,22 +442,155 @@ async fn handle_send_message(
         recipient_ids,
         cc_ids: None,
         bcc_ids: None,
-        subject,
+        subject: subject.clone(),
         body_md: body,
         thread_id: None,
         importance: None,
         ack_required: false,
+        deliver_at: None,
+        broadcast: false,
+        allow_new_thread: false,
     };
 
     let id = mouchak_mail_core::model::message::MessageBmc::create(ctx, mm, msg_c).await?;
-    println!("Sent message ID {}", id);
-    Ok(())
+    Ok(MessageSent {
+        id,
+        link: permalink::absolute(
+            mm.app_config.server.public_url.as_deref(),
+            &permalink::message_path(&project.slug, id),
+        ),
+        project_slug: project_slug.to_string(),
+        from: from.to_string(),
+        to,
+        subject,
+    })
+}
+
+#[derive(Debug, Serialize)]
+struct ProjectStatus {
+    id: i64,
+    slug: String,
+    human_key: String,
+    created_at: String,
+    link: String,
+}
+
+impl CommandOutput for ProjectStatus {
+    fn human(&self) -> String {
+        format!(
+            "Project: {} ({})\nID: {}\nCreated: {}\nLink: {}",
+            self.human_key, self.slug, self.id, self.created_at, self.link
+        )
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct RetentionStatus {
+    project_slug: String,
+    policy: Option<mouchak_mail_core::model::project::RetentionPolicy>,
+}
+
+impl CommandOutput for RetentionStatus {
+    fn human(&self) -> String {
+        match &self.policy {
+            None => format!("Project '{}' keeps messages forever", self.project_slug),
+            Some(p) => format!(
+                "Project '{}' prunes messages after {} days\nKeep unread: {}\nKeep ack-pending: {}",
+                self.project_slug, p.retention_days, p.keep_unread, p.keep_ack_pending
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+struct PruneResult(mouchak_mail_core::model::retention::PruneReport);
+
+impl CommandOutput for PruneResult {
+    fn human(&self) -> String {
+        let r = &self.0;
+        let mut out = format!(
+            "{}Project '{}', cutoff {}\n{:<22} {}\n{:<22} {}\n{:<22} {}\n{:<22} {}",
+            if r.dry_run { "Dry run: " } else { "" },
+            r.project_slug,
+            r.cutoff,
+            "Eligible",
+            r.eligible,
+            if r.dry_run { "Would prune" } else { "Pruned" },
+            r.pruned,
+            "Recipient rows",
+            r.recipients_pruned,
+            "Missing archive",
+            r.missing_archive,
+        );
+        if !r.missing_archive_ids.is_empty() {
+            let ids: Vec<String> = r.missing_archive_ids.iter().map(i64::to_string).collect();
+            out.push_str(&format!("\nKept (no archive file): {}", ids.join(", ")));
+        }
+        out
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(transparent)]
+struct AuditLog(mouchak_mail_core::model::audit::AuditPage);
+
+impl CommandOutput for AuditLog {
+    fn human(&self) -> String {
+        if self.0.items.is_empty() {
+            return "No audit entries".to_string();
+        }
+        let mut lines: Vec<String> = self
+            .0
+            .items
+            .iter()
+            .map(|e| {
+                format!(
+                    "{:<6} {} {:<26} {} {:<8} {}  {}",
+                    e.id,
+                    e.created_ts,
+                    e.action,
+                    e.entity_type,
+                    e.entity_id,
+                    e.actor.as_deref().unwrap_or("-"),
+                    e.detail
+                )
+            })
+            .collect();
+        if let Some(cursor) = self.0.next_cursor {
+            lines.push(format!("More entries: --cursor {}", cursor));
+        }
+        lines.join("\n")
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ExportResult {
+    project_slug: String,
+    format: String,
+    message_count: usize,
+    /// File written, when exporting with --out-file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<String>,
+    /// Rendered export, when writing to stdout
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+}
+
+impl CommandOutput for ExportResult {
+    fn human(&self) -> String {
+        match (&self.path, &self.content) {
+            (Some(path), _) => format!("Exported to {}", path),
+            (None, content) => content.clone().unwrap_or_default(),
+        }
+    }
 }
 
 async fn handle_projects_command(
     cmd: ProjectsCommands,
     ctx: &Ctx,
     mm: &ModelManager,
+    output: OutputMode,
 ) -> Result<()> {
     match cmd {
         ProjectsCommands::MarkIdentity { project, commit } => {
@@ -310,11 +607,14 @@ async fn handle_projects_command(
                 println!("Committed to git.");
             }
         }
-        ProjectsCommands::DiscoveryInit { product } => {
-            let content = format!(
+        ProjectsCommands::DiscoveryInit { product, project } => {
+            let mut content = format!(
                 "product: {}\nprojects: []\n",
                 product.as_deref().unwrap_or("default")
             );
+            if let Some(project) = project {
+                content.push_str(&format!("project: {}\n", project));
+            }
             let mut file = std::fs::File::create("discovery.yaml")?;
             file.write_all(content.as_bytes())?;
             println!("Initialized discovery.yaml");
@@ -323,18 +623,34 @@ async fn handle_projects_command(
             let p =
                 mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(ctx, mm, &project)
                     .await?;
-            println!("Project: {} ({})", p.human_key, p.slug);
-            println!("ID: {}", p.id);
-            println!("Created: {}", p.created_at);
-            println!("Link: mouchak-mail://project/{}", p.slug);
-        }
-        ProjectsCommands::Adopt { from, to, dry_run } => {
-            let src =
-                mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(ctx, mm, &from)
-                    .await?;
-            let dest =
-                mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(ctx, mm, &to)
-                    .await?;
+            output.emit(&ProjectStatus {
+                id: p.id.get(),
+                link: permalink::absolute(
+                    mm.app_config.server.public_url.as_deref(),
+                    &permalink::project_path(&p.slug),
+                )
+                .unwrap_or_else(|| format!("mouchak-mail://project/{}", p.slug)),
+                slug: p.slug,
+                human_key: p.human_key,
+                created_at: p.created_at.to_string(),
+            })?;
+        }
+        ProjectsCommands::Adopt {
+            from,
+            to,
+            dry_run,
+            merge_agents,
+            rename_suffix,
+        } => {
+            use mouchak_mail_core::model::project::{AgentConflictPolicy, ProjectBmc};
+
+            let src = ProjectBmc::get_by_identifier(ctx, mm, &from).await?;
+            let dest = ProjectBmc::get_by_identifier(ctx, mm, &to).await?;
+            let policy = match (merge_agents, rename_suffix) {
+                (true, _) => AgentConflictPolicy::Merge,
+                (false, Some(suffix)) => AgentConflictPolicy::RenameSuffix(suffix),
+                (false, None) => AgentConflictPolicy::Report,
+            };
 
             println!(
                 "Adopting from '{}' ({}) -> '{}' ({})",
@@ -343,28 +659,121 @@ async fn handle_projects_command(
                 dest.human_key,
                 dest.id.get()
             );
+            let plan = ProjectBmc::plan_adopt(ctx, mm, src.id, dest.id, &policy).await?;
+            print_adopt_report(&plan);
+            if !plan.conflicts.is_empty() {
+                anyhow::bail!(
+                    "{} conflict(s); rerun with --merge-agents or --rename-suffix",
+                    plan.conflicts.len()
+                );
+            }
             if dry_run {
                 println!("Dry run: No changes made.");
             } else {
-                mouchak_mail_core::model::project::ProjectBmc::adopt(ctx, mm, src.id, dest.id)
-                    .await?;
+                ProjectBmc::adopt(ctx, mm, src.id, dest.id, &policy).await?;
                 println!("Adoption complete.");
             }
         }
+        ProjectsCommands::Retention {
+            project,
+            days,
+            prune_unread,
+            prune_ack_pending,
+            off,
+        } => {
+            use mouchak_mail_core::model::project::{ProjectBmc, RetentionPolicy};
+
+            let p = ProjectBmc::get_by_identifier(ctx, mm, &project).await?;
+            if off {
+                ProjectBmc::clear_retention(ctx, mm, p.id).await?;
+            } else if let Some(retention_days) = days {
+                let policy = RetentionPolicy {
+                    retention_days,
+                    keep_unread: !prune_unread,
+                    keep_ack_pending: !prune_ack_pending,
+                };
+                ProjectBmc::set_retention(ctx, mm, p.id, policy).await?;
+            }
+            output.emit(&RetentionStatus {
+                policy: ProjectBmc::get_retention(ctx, mm, p.id).await?,
+                project_slug: p.slug,
+            })?;
+        }
+        ProjectsCommands::Prune { project, dry_run } => {
+            let p =
+                mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(ctx, mm, &project)
+                    .await?;
+            let report =
+                mouchak_mail_core::model::retention::RetentionBmc::prune(ctx, mm, p.id, dry_run)
+                    .await?;
+            output.emit(&PruneResult(report))?;
+        }
+        ProjectsCommands::Audit {
+            project,
+            action,
+            entity_type,
+            entity_id,
+            limit,
+            cursor,
+        } => {
+            use mouchak_mail_core::model::audit::{AuditBmc, AuditFilter};
+
+            let p =
+                mouchak_mail_core::model::project::ProjectBmc::get_by_identifier(ctx, mm, &project)
+                    .await?;
+            let filter = AuditFilter {
+                action,
+                entity_type,
+                entity_id,
+            };
+            let page = AuditBmc::list(ctx, mm, p.id, &filter, limit, cursor).await?;
+            output.emit(&AuditLog(page))?;
+        }
     }
     Ok(())
 }
 
+fn print_adopt_report(report: &mouchak_mail_core::model::project::AdoptReport) {
+    println!("{:<22} {}", "Agents moved", report.agents_moved);
+    println!("{:<22} {}", "Agents merged", report.agents_merged.len());
+    for name in &report.agents_merged {
+        println!("  {}", name);
+    }
+    println!("{:<22} {}", "Agents renamed", report.agents_renamed.len());
+    for (old, new) in &report.agents_renamed {
+        println!("  {} -> {}", old, new);
+    }
+    println!("{:<22} {}", "Messages moved", report.messages_moved);
+    println!("{:<22} {}", "Reservations moved", report.reservations_moved);
+    println!(
+        "{:<22} {}",
+        "Archive files moved", report.archive_files_moved
+    );
+    for conflict in &report.conflicts {
+        println!("Conflict: {}", conflict);
+    }
+}
+
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
+    // Logs go to stderr so stdout stays parseable in --output json
     tracing_subscriber::fmt()
         .with_env_filter(
             EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
         )
+        .with_writer(std::io::stderr)
         .init();
 
     let cli = Cli::parse();
+    let output = cli.output;
+    if let Err(e) = run(cli).await {
+        output.exit_with_error(&e);
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
     let ctx = Ctx::root_ctx();
+    let output = cli.output;
 
     match cli.command {
         Commands::Start { port } => {
@@ -386,19 +795,23 @@ async fn main() -> Result<()> {
             tracing::info!("Running database migrations");
             println!("Migrations completed successfully.");
         }
-        Commands::CreateProject { slug, human_key } => {
+        Commands::CreateProject {
+            slug,
+            human_key,
+            strict,
+        } => {
             let mm = ModelManager::new(std::sync::Arc::new(
                 mouchak_mail_common::config::AppConfig::load().unwrap_or_default(),
             ))
             .await?;
-            handle_create_project(&ctx, &mm, &slug, &human_key).await?;
+            output.emit(&handle_create_project(&ctx, &mm, &slug, &human_key, strict).await?)?;
         }
         Commands::CreateAgent { project_slug, name } => {
             let mm = ModelManager::new(std::sync::Arc::new(
                 mouchak_mail_common::config::AppConfig::load().unwrap_or_default(),
             ))
             .await?;
-            handle_create_agent(&ctx, &mm, &project_slug, name).await?;
+            output.emit(&handle_create_agent(&ctx, &mm, &project_slug, name).await?)?;
         }
         Commands::SendMessage {
             project_slug,
@@ -411,14 +824,16 @@ async fn main() -> Result<()> {
                 mouchak_mail_common::config::AppConfig::load().unwrap_or_default(),
             ))
             .await?;
-            handle_send_message(&ctx, &mm, &project_slug, &from, to, subject, body).await?;
+            let sent =
+                handle_send_message(&ctx, &mm, &project_slug, &from, to, subject, body).await?;
+            output.emit(&sent)?;
         }
         Commands::Projects { command } => {
             let mm = ModelManager::new(std::sync::Arc::new(
                 mouchak_mail_common::config::AppConfig::load().unwrap_or_default(),
             ))
             .await?;
-            handle_projects_command(command, &ctx, &mm).await?;
+            handle_projects_command(command, &ctx, &mm, output).await?;
         }
         Commands::Guard { command } => {
             handle_guard_command(command).await?;
@@ -477,7 +892,12 @@ async fn main() -> Result<()> {
             project,
             format,
             scrub,
-            output,
+            out_file,
+            since,
+            until,
+            agent,
+            thread,
+            compress,
         } => {
             let mm = ModelManager::new(std::sync::Arc::new(
                 mouchak_mail_common::config::AppConfig::load().unwrap_or_default(),
@@ -489,6 +909,26 @@ async fn main() -> Result<()> {
                 .map_err(|_| anyhow::anyhow!("Invalid format"))?;
             let scrub_enum = mouchak_mail_core::model::export::ScrubMode::from_str(&scrub)
                 .map_err(|_| anyhow::anyhow!("Invalid scrub mode"))?;
+            let compression = mouchak_mail_core::model::export::ExportCompression::from_str(
+                compress.as_deref().unwrap_or_default(),
+            )?;
+            let filter = mouchak_mail_core::model::export::ExportFilter {
+                since: since
+                    .as_deref()
+                    .map(|s| {
+                        mouchak_mail_core::model::export::ExportFilter::parse_date_bound(s, false)
+                    })
+                    .transpose()?,
+                until: until
+                    .as_deref()
+                    .map(|s| {
+                        mouchak_mail_core::model::export::ExportFilter::parse_date_bound(s, true)
+                    })
+                    .transpose()?,
+                agent_name: agent,
+                thread_id: thread,
+                importance: None,
+            };
 
             let exported = mouchak_mail_core::model::export::ExportBmc::export_mailbox(
                 &ctx,
@@ -497,15 +937,21 @@ async fn main() -> Result<()> {
                 format_enum,
                 scrub_enum,
                 false,
+                &filter,
+                compression,
             )
             .await?;
 
-            if let Some(path) = output {
-                std::fs::write(&path, &exported.content)?;
-                println!("Exported to {}", path);
-            } else {
-                println!("{}", exported.content);
+            if let Some(path) = &out_file {
+                std::fs::write(path, exported.content_bytes()?)?;
             }
+            output.emit(&ExportResult {
+                project_slug: exported.project_slug,
+                format: exported.format,
+                message_count: exported.message_count,
+                content: out_file.is_none().then_some(exported.content),
+                path: out_file,
+            })?;
         }
         Commands::Archive { command } => {
             handle_archive_command(command).await?;
@@ -769,9 +1215,59 @@ async fn handle_archive_command(cmd: ArchiveCommands) -> Result<()> {
             label,
             yes,
         } => handle_archive_clear_and_reset(archives_dir, archive, label, yes).await,
+        ArchiveCommands::Verify {
+            project,
+            repair,
+            json,
+        } => handle_archive_verify(&project, repair, json).await,
     }
 }
 
+/// Handle archive verify command
+async fn handle_archive_verify(project: &str, repair: bool, json: bool) -> Result<()> {
+    use mouchak_mail_core::model::archive_integrity::ArchiveIntegrityBmc;
+
+    let mm = ModelManager::new(std::sync::Arc::new(
+        mouchak_mail_common::config::AppConfig::load().unwrap_or_default(),
+    ))
+    .await?;
+    let ctx = Ctx::root_ctx();
+
+    let report = if repair {
+        ArchiveIntegrityBmc::repair_archive(&ctx, &mm, project).await?
+    } else {
+        ArchiveIntegrityBmc::verify_archive(&ctx, &mm, project).await?
+    };
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!("Archive integrity for '{}':", report.project_slug);
+    println!("{:<22} {}", "Messages checked", report.messages_checked);
+    println!("{:<22} {}", "Files scanned", report.files_scanned);
+    for (label, count, paths) in [
+        ("Missing", report.missing_count, &report.missing),
+        ("Orphans", report.orphan_count, &report.orphans),
+        ("Mismatched", report.mismatched_count, &report.mismatched),
+    ] {
+        println!("{:<22} {}", label, count);
+        for path in paths {
+            println!("  {}", path);
+        }
+        if count > paths.len() {
+            println!("  ... and {} more", count - paths.len());
+        }
+    }
+    if repair {
+        println!("{:<22} {}", "Repaired", report.repaired_count);
+    } else if report.missing_count > 0 {
+        println!("Run with --repair to restore missing files.");
+    }
+    Ok(())
+}
+
 /// Helper to add a directory recursively to a ZIP archive
 fn add_directory_to_zip<W: Write + std::io::Seek>(
     zip: &mut zip::ZipWriter<W>,
@@ -0,0 +1,209 @@
+//! `--output` mode tests
+//!
+//! Each command runs against a fresh database in a temp directory, so IDs
+//! are deterministic and the output can be compared verbatim.
+
+#![allow(clippy::unwrap_used, clippy::expect_used, deprecated)]
+
+use assert_cmd::Command;
+use tempfile::TempDir;
+
+fn cli(dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("mouchak-mail-cli").expect("Binary not found");
+    cmd.current_dir(dir)
+        .env("DATABASE_PATH", dir.path().join("mail.db"))
+        .env("RUST_LOG", "off")
+        .env_remove("AM_OUTPUT")
+        .env_remove("PUBLIC_URL");
+    cmd
+}
+
+fn stdout(cmd: &mut Command) -> String {
+    String::from_utf8(cmd.assert().success().get_output().stdout.clone()).unwrap()
+}
+
+/// Project "demo" with agents "alice" and "bob"
+fn seed(dir: &TempDir) {
+    cli(dir)
+        .args(["create-project", "demo", "/repo/demo"])
+        .assert()
+        .success();
+    for name in ["alice", "bob"] {
+        cli(dir)
+            .args(["create-agent", "demo", name])
+            .assert()
+            .success();
+    }
+}
+
+#[test]
+fn test_create_project_output() {
+    let dir = TempDir::new().unwrap();
+    assert_eq!(
+        stdout(cli(&dir).args(["create-project", "demo", "/repo/demo"])),
+        "Created project 'demo' with ID 1\n"
+    );
+    assert_eq!(
+        stdout(cli(&dir).args(["--output", "json", "create-project", "other", "/repo/other"])),
+        "{\"id\":2,\"slug\":\"other\",\"human_key\":\"/repo/other\"}\n"
+    );
+}
+
+#[test]
+fn test_create_project_suffixes_taken_slug() {
+    let dir = TempDir::new().unwrap();
+    seed(&dir);
+
+    assert_eq!(
+        stdout(cli(&dir).args(["create-project", "demo", "/repo/Demo"])),
+        "Created project 'demo-2' with ID 2\n"
+    );
+    cli(&dir)
+        .args(["create-project", "--strict", "demo", "/repo/DEMO"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_create_agent_output() {
+    let dir = TempDir::new().unwrap();
+    cli(&dir)
+        .args(["create-project", "demo", "/repo/demo"])
+        .assert()
+        .success();
+
+    assert_eq!(
+        stdout(cli(&dir).args(["create-agent", "demo", "alice"])),
+        "Created agent 'alice' in project 'demo' with ID 1\n"
+    );
+    // The env var selects the mode too
+    assert_eq!(
+        stdout(
+            cli(&dir)
+                .env("AM_OUTPUT", "json")
+                .args(["create-agent", "demo", "bob"])
+        ),
+        "{\"id\":2,\"name\":\"bob\",\"project_slug\":\"demo\"}\n"
+    );
+}
+
+#[test]
+fn test_send_message_output() {
+    let dir = TempDir::new().unwrap();
+    seed(&dir);
+
+    assert_eq!(
+        stdout(cli(&dir).args(["send-message", "demo", "alice", "-t", "bob", "Hi", "Body"])),
+        "Sent message ID 1\n"
+    );
+    assert_eq!(
+        stdout(cli(&dir).args([
+            "send-message",
+            "demo",
+            "alice",
+            "-t",
+            "bob",
+            "Re: Hi",
+            "Body",
+            "--output",
+            "json",
+        ])),
+        "{\"id\":2,\"project_slug\":\"demo\",\"from\":\"alice\",\"to\":[\"bob\"],\"subject\":\"Re: Hi\"}\n"
+    );
+}
+
+#[test]
+fn test_projects_status_output() {
+    let dir = TempDir::new().unwrap();
+    seed(&dir);
+
+    let human = stdout(cli(&dir).args(["projects", "status", "demo"]));
+    let lines: Vec<&str> = human.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert_eq!(lines[0], "Project: /repo/demo (demo)");
+    assert_eq!(lines[1], "ID: 1");
+    assert!(lines[2].starts_with("Created: "));
+    assert_eq!(lines[3], "Link: mouchak-mail://project/demo");
+
+    let json = stdout(cli(&dir).args(["--output", "json", "projects", "status", "demo"]));
+    assert_eq!(json.lines().count(), 1);
+    let status: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(status["id"], 1);
+    assert_eq!(status["slug"], "demo");
+    assert_eq!(status["link"], "mouchak-mail://project/demo");
+    assert!(status["created_at"].is_string());
+}
+
+#[test]
+fn test_links_with_public_url() {
+    let dir = TempDir::new().unwrap();
+    seed(&dir);
+
+    let status = stdout(
+        cli(&dir)
+            .env("PUBLIC_URL", "https://mail.example.com/")
+            .args(["projects", "status", "demo"]),
+    );
+    assert_eq!(
+        status.lines().last(),
+        Some("Link: https://mail.example.com/projects/demo")
+    );
+
+    let sent = stdout(
+        cli(&dir)
+            .env("PUBLIC_URL", "https://mail.example.com")
+            .args(["send-message", "demo", "alice", "-t", "bob", "Hi", "Body"]),
+    );
+    assert_eq!(
+        sent,
+        "Sent message ID 1\nLink: https://mail.example.com/p/demo/m/1\n"
+    );
+
+    let json = stdout(
+        cli(&dir)
+            .env("PUBLIC_URL", "https://mail.example.com")
+            .args([
+                "--output",
+                "json",
+                "send-message",
+                "demo",
+                "alice",
+                "-t",
+                "bob",
+                "Re: Hi",
+                "Body",
+            ]),
+    );
+    let sent: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(sent["link"], "https://mail.example.com/p/demo/m/2");
+}
+
+#[test]
+fn test_errors_in_both_modes() {
+    let dir = TempDir::new().unwrap();
+
+    let human = cli(&dir)
+        .args(["create-agent", "missing", "alice"])
+        .assert()
+        .failure()
+        .code(1)
+        .get_output()
+        .clone();
+    assert!(human.stdout.is_empty());
+    assert!(
+        String::from_utf8(human.stderr)
+            .unwrap()
+            .starts_with("Error: ")
+    );
+
+    let json = cli(&dir)
+        .args(["--output", "json", "create-agent", "missing", "alice"])
+        .assert()
+        .failure()
+        .code(1)
+        .get_output()
+        .clone();
+    assert!(json.stdout.is_empty());
+    let err: serde_json::Value = serde_json::from_slice(&json.stderr).unwrap();
+    assert!(err["error"].as_str().unwrap().contains("missing"));
+}
@@ -16,7 +16,8 @@ async fn main() -> anyhow::Result<()> {
     // If they set `PORT`, we might lose it unless AppConfig checks it.
     // For now, let's just use AppConfig.
 
-    let config = AppConfig::load()?;
+    // Refuses to start on a broken config file; see `mouchak-mail config check`
+    let config = AppConfig::check(None).for_serve()?;
     tracing::info!("Loaded config: {:?}", config.server);
 
     // 3. Run Server
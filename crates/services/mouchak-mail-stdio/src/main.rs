@@ -1,7 +1,8 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use mouchak_mail_common::config::{AppConfig, McpConfig};
-use mouchak_mail_mcp::{run_sse, run_stdio, tools::get_tool_schemas};
+use mouchak_mail_mcp::docs::{render_schema, render_tool_list};
+use mouchak_mail_mcp::{run_sse, run_stdio};
 use tracing_subscriber::{EnvFilter, fmt, prelude::*};
 
 #[derive(Parser)]
@@ -21,8 +22,12 @@ enum Commands {
         transport: String,
         #[arg(short, long, env = "MOUCHAK_MCP__PORT", default_value = "3000")]
         port: u16,
-        #[arg(long, env = "MOUCHAK_MAIL_HOST", default_value = "127.0.0.1")]
-        host: String,
+        /// Address to bind for the SSE transport [default: 127.0.0.1, or MOUCHAK_MCP__HOST]
+        #[arg(long, env = "MOUCHAK_MAIL_HOST")]
+        host: Option<String>,
+        /// Expose the worktree build slot tools
+        #[arg(long, env = "WORKTREES_ENABLED")]
+        worktrees: bool,
     },
     /// Export JSON schemas for all tools
     Schema {
@@ -30,9 +35,16 @@ enum Commands {
         format: String,
         #[arg(short, long)]
         output: Option<String>,
+        /// Include the worktree build slot tools
+        #[arg(long, env = "WORKTREES_ENABLED")]
+        worktrees: bool,
     },
     /// List all available tools
-    Tools,
+    Tools {
+        /// Include the worktree build slot tools
+        #[arg(long, env = "WORKTREES_ENABLED")]
+        worktrees: bool,
+    },
 }
 
 fn setup_logging() -> Result<()> {
@@ -43,12 +55,21 @@ fn setup_logging() -> Result<()> {
     Ok(())
 }
 
-async fn handle_serve(transport: String, port: u16) -> Result<()> {
+async fn handle_serve(
+    transport: String,
+    port: u16,
+    host: Option<String>,
+    worktrees: bool,
+) -> Result<()> {
     setup_logging()?;
 
     let mut mcp_config = McpConfig::from_env();
     mcp_config.transport = transport.clone();
     mcp_config.port = port;
+    if let Some(host) = host {
+        mcp_config.host = host;
+    }
+    mcp_config.worktrees_enabled |= worktrees;
 
     let config = AppConfig {
         mcp: mcp_config,
@@ -62,14 +83,8 @@ async fn handle_serve(transport: String, port: u16) -> Result<()> {
     }
 }
 
-fn handle_schema(format: String, output: Option<String>) -> Result<()> {
-    // Show all tools in documentation (worktrees_enabled=true)
-    let schemas = get_tool_schemas(true);
-    let content = if format == "markdown" || format == "md" {
-        mouchak_mail_mcp::docs::generate_markdown_docs(&schemas)
-    } else {
-        serde_json::to_string_pretty(&schemas)?
-    };
+fn handle_schema(format: String, output: Option<String>, worktrees: bool) -> Result<()> {
+    let content = render_schema(&format, worktrees)?;
     if let Some(path) = output {
         std::fs::write(&path, &content)?;
         eprintln!("Schema written to {}", path);
@@ -79,15 +94,8 @@ fn handle_schema(format: String, output: Option<String>) -> Result<()> {
     Ok(())
 }
 
-fn handle_tools() {
-    // Show all tools in documentation (worktrees_enabled=true)
-    let schemas = get_tool_schemas(true);
-    println!("Mouchak Mail Tools ({} total)\n", schemas.len());
-    println!("{:<30} DESCRIPTION", "TOOL");
-    println!("{}", "-".repeat(80));
-    for schema in schemas {
-        println!("{:<30} {}", schema.name, schema.description);
-    }
+fn handle_tools(worktrees: bool) {
+    print!("{}", render_tool_list(worktrees));
 }
 
 #[tokio::main]
@@ -96,16 +104,24 @@ async fn main() -> Result<()> {
     let cmd = cli.command.unwrap_or(Commands::Serve {
         transport: "stdio".to_string(),
         port: 3000,
-        host: "127.0.0.1".to_string(),
+        host: None,
+        worktrees: false,
     });
 
     match cmd {
         Commands::Serve {
-            transport, port, ..
-        } => handle_serve(transport, port).await,
-        Commands::Schema { format, output } => handle_schema(format, output),
-        Commands::Tools => {
-            handle_tools();
+            transport,
+            port,
+            host,
+            worktrees,
+        } => handle_serve(transport, port, host, worktrees).await,
+        Commands::Schema {
+            format,
+            output,
+            worktrees,
+        } => handle_schema(format, output, worktrees),
+        Commands::Tools { worktrees } => {
+            handle_tools(worktrees);
             Ok(())
         }
     }
@@ -426,7 +426,9 @@ impl MouchakMailService {
                     .map_err(|_| McpError::invalid_params(format!("Agent not found: {}", agent_name), None))?;
                 
                 // Default limit 20
-                let messages = MessageBmc::list_inbox_for_agent(&ctx, mm, project_id, agent.id, 20).await
+                let mut messages = MessageBmc::list_inbox_for_agent(&ctx, mm, project_id, agent.id, 20).await
+                    .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+                MessageBmc::load_bodies(mm, &mut messages).await
                     .map_err(|e| McpError::internal_error(e.to_string(), None))?;
                 serde_json::to_string_pretty(&messages)
                     .map_err(|e| McpError::internal_error(e.to_string(), None))?
@@ -436,7 +438,7 @@ impl MouchakMailService {
                 let agent = AgentBmc::get_by_name(&ctx, mm, project_id, agent_name).await
                     .map_err(|_| McpError::invalid_params(format!("Agent not found: {}", agent_name), None))?;
                 
-                let messages = MessageBmc::list_outbox_for_agent(&ctx, mm, project_id, agent.id, 20).await
+                let messages = MessageBmc::list_outbox_for_agent(&ctx, mm, project_id, agent.id, 20, None).await
                     .map_err(|e| McpError::internal_error(e.to_string(), None))?;
                 serde_json::to_string_pretty(&messages)
                     .map_err(|e| McpError::internal_error(e.to_string(), None))?
@@ -817,7 +819,7 @@ pub struct ForceReleaseReservationParams {
 pub struct RenewFileReservationParams {
     /// Reservation ID to renew
     pub reservation_id: i64,
-    /// New TTL in seconds (default 3600)
+    /// New TTL in seconds, counted from now (default 3600, capped by server config)
     pub ttl_seconds: Option<i64>,
 }
 
@@ -1218,6 +1220,8 @@ impl MouchakMailService {
             body_md: p.body_md,
             thread_id: p.thread_id,
             importance: p.importance,
+            deliver_at: None,
+            broadcast: false,
         };
 
         let msg_id = MessageBmc::create(&ctx, &self.mm, msg_c).await
@@ -1556,12 +1560,19 @@ impl MouchakMailService {
         let p = params.0;
 
         let ttl = p.ttl_seconds.unwrap_or(3600);
-        let new_expires = chrono::Utc::now().naive_utc() + chrono::Duration::seconds(ttl);
-
-        FileReservationBmc::renew(&ctx, &self.mm, p.reservation_id, new_expires).await
-            .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        let renewed = FileReservationBmc::renew_active(&ctx, &self.mm, p.reservation_id, ttl).await
+            .map_err(|e| match e {
+                mouchak_mail_core::Error::FileReservationInactive(_)
+                | mouchak_mail_core::Error::Validation(_) => McpError::invalid_params(e.to_string(), None),
+                _ => McpError::internal_error(e.to_string(), None),
+            })?;
 
-        let msg = format!("Renewed reservation {} until {}", p.reservation_id, new_expires);
+        let msg = format!(
+            "Renewed reservation {} ({}) until {}",
+            renewed.id,
+            renewed.path_pattern,
+            renewed.expires_ts.and_utc().to_rfc3339()
+        );
         Ok(CallToolResult::success(vec![Content::text(msg)]))
     }
 
@@ -1608,6 +1619,8 @@ impl MouchakMailService {
             body_md: p.body_md,
             thread_id: original_msg.thread_id.clone(),
             importance: p.importance,
+            deliver_at: None,
+            broadcast: false,
         };
 
         let msg_id = MessageBmc::create(&ctx, &self.mm, msg_c).await
@@ -1844,7 +1857,7 @@ impl MouchakMailService {
         let project = ProjectBmc::get_by_identifier(&ctx, &self.mm, &p.project_slug).await
             .map_err(|e| McpError::invalid_params(format!("Project not found: {}", e), None))?;
 
-        let threads = MessageBmc::list_threads(&ctx, &self.mm, project.id, p.limit.unwrap_or(50)).await
+        let threads = MessageBmc::list_threads(&ctx, &self.mm, project.id, p.limit.unwrap_or(50), None).await
             .map_err(|e| McpError::internal_error(e.to_string(), None))?;
 
         let mut output = format!("Threads in '{}' ({}):\n\n", p.project_slug, threads.len());
@@ -2446,7 +2459,7 @@ impl MouchakMailService {
         let messages = MessageBmc::list_recent(&ctx, &self.mm, project.id, 1000).await
             .map_err(|e| McpError::internal_error(e.to_string(), None))?;
 
-        let threads = MessageBmc::list_threads(&ctx, &self.mm, project.id, 100).await
+        let threads = MessageBmc::list_threads(&ctx, &self.mm, project.id, 100, None).await
             .map_err(|e| McpError::internal_error(e.to_string(), None))?;
 
         match format.as_str() {
@@ -2594,14 +2607,7 @@ mod tests {
         let conn = db.connect().unwrap();
         let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
 
-        let schema1 = include_str!("../../../../migrations/001_initial_schema.sql");
-        conn.execute_batch(schema1).await.unwrap();
-        let schema2 = include_str!("../../../../migrations/002_agent_capabilities.sql");
-        conn.execute_batch(schema2).await.unwrap();
-        let schema3 = include_str!("../../../../migrations/003_tool_metrics.sql");
-        conn.execute_batch(schema3).await.unwrap();
-        let schema4 = include_str!("../../../../migrations/004_add_recipient_type.sql");
-        conn.execute_batch(schema4).await.unwrap();
+        mouchak_mail_core::store::apply_migrations(&conn).await.unwrap();
 
         let app_config = Arc::new(AppConfig::default());
         let mm = ModelManager::new_for_test(conn, archive_root, app_config);
@@ -2803,6 +2809,8 @@ mod tests {
             body_md: "Body".into(),
             thread_id: None,
             importance: None,
+            deliver_at: None,
+            broadcast: false,
         };
         MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
 
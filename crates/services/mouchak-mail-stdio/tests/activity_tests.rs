@@ -25,15 +25,9 @@ mod tests {
         let conn = db.connect().unwrap();
 
         let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
-        // Apply all schemas
-        let schema1 = include_str!("../../../../migrations/001_initial_schema.sql");
-        conn.execute_batch(schema1).await.unwrap();
-        let schema2 = include_str!("../../../../migrations/002_agent_capabilities.sql");
-        conn.execute_batch(schema2).await.unwrap();
-        let schema3 = include_str!("../../../../migrations/003_tool_metrics.sql");
-        conn.execute_batch(schema3).await.unwrap();
-        let schema4 = include_str!("../../../../migrations/004_attachments.sql");
-        conn.execute_batch(schema4).await.unwrap();
+        mouchak_mail_core::store::apply_migrations(&conn)
+            .await
+            .unwrap();
 
         let app_config = Arc::new(AppConfig::default());
         let mm = ModelManager::new_for_test(conn, archive_root, app_config);
@@ -75,6 +69,9 @@ mod tests {
             thread_id: None,
             importance: None,
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
         };
         MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
 
@@ -28,14 +28,9 @@ mod tools_tests {
 
         // Apply migrations
         let _ = conn.execute("PRAGMA journal_mode=WAL;", ()).await;
-        let schema = include_str!("../../../../migrations/001_initial_schema.sql");
-        conn.execute_batch(schema).await.unwrap();
-        let schema002 = include_str!("../../../../migrations/002_agent_capabilities.sql");
-        conn.execute_batch(schema002).await.unwrap();
-        let schema3 = include_str!("../../../../migrations/003_tool_metrics.sql");
-        conn.execute_batch(schema3).await.unwrap();
-        let schema4 = include_str!("../../../../migrations/004_attachments.sql");
-        conn.execute_batch(schema4).await.unwrap();
+        mouchak_mail_core::store::apply_migrations(&conn)
+            .await
+            .unwrap();
 
         let app_config = Arc::new(AppConfig::default());
         let mm = ModelManager::new_for_test(conn, archive_root, app_config);
@@ -482,7 +477,9 @@ mod tools_tests {
     async fn test_export_mailbox() {
         use mouchak_mail_core::Ctx;
         use mouchak_mail_core::model::agent::{AgentBmc, AgentForCreate};
-        use mouchak_mail_core::model::export::{ExportBmc, ExportFormat, ScrubMode};
+        use mouchak_mail_core::model::export::{
+            ExportBmc, ExportCompression, ExportFilter, ExportFormat, ScrubMode,
+        };
         use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
         use mouchak_mail_core::model::project::ProjectBmc;
 
@@ -524,6 +521,9 @@ mod tools_tests {
             thread_id: None,
             importance: None,
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
         };
         MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
 
@@ -535,6 +535,8 @@ mod tools_tests {
             ExportFormat::Json,
             ScrubMode::None,
             false,
+            &ExportFilter::default(),
+            ExportCompression::None,
         )
         .await
         .expect("JSON export should succeed");
@@ -550,6 +552,8 @@ mod tools_tests {
             ExportFormat::Html,
             ScrubMode::None,
             false,
+            &ExportFilter::default(),
+            ExportCompression::None,
         )
         .await
         .expect("HTML export should succeed");
@@ -565,6 +569,8 @@ mod tools_tests {
             ExportFormat::Markdown,
             ScrubMode::None,
             false,
+            &ExportFilter::default(),
+            ExportCompression::None,
         )
         .await
         .expect("Markdown export should succeed");
@@ -609,6 +615,9 @@ mod tools_tests {
             thread_id: None,
             importance: None,
             ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            allow_new_thread: false,
         };
         MessageBmc::create(&ctx, &mm, msg_c).await.unwrap();
 
@@ -0,0 +1,491 @@
+//! Environment self-checks
+//!
+//! `mouchak-mail doctor` runs every check and prints a pass/warn/fail table
+//! with remediation hints. `serve http` runs the [`critical`] subset first
+//! and refuses to start, with the same messages, when one of them fails.
+
+use mouchak_mail_common::config::AppConfig;
+use mouchak_mail_common::output::CommandOutput;
+use mouchak_mail_core::model::ModelManager;
+use mouchak_mail_core::store::{self, AgentNameIssue, SCHEMA_VERSION};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// A write-ahead log this large means checkpoints are not keeping up
+const STALE_WAL_BYTES: u64 = 64 * 1024 * 1024;
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum Status {
+    Pass,
+    /// Works, but needs attention
+    Warn,
+    /// The server cannot run like this
+    Fail,
+}
+
+/// One row of the doctor report.
+#[derive(Debug, Serialize)]
+pub(crate) struct Check {
+    pub(crate) name: &'static str,
+    pub(crate) status: Status,
+    pub(crate) detail: String,
+    /// How to fix a warning or failure
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) hint: Option<String>,
+}
+
+impl Check {
+    fn pass(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Pass,
+            detail: detail.into(),
+            hint: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Warn,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, hint: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: Status::Fail,
+            detail: detail.into(),
+            hint: Some(hint.into()),
+        }
+    }
+}
+
+/// Result of `mouchak-mail doctor`.
+#[derive(Debug, Serialize)]
+pub(crate) struct DoctorReport {
+    /// False if any check failed
+    pub(crate) ok: bool,
+    pub(crate) checks: Vec<Check>,
+}
+
+impl DoctorReport {
+    fn new(checks: Vec<Check>) -> Self {
+        Self {
+            ok: checks.iter().all(|c| c.status != Status::Fail),
+            checks,
+        }
+    }
+
+    /// Table of the failed checks only, as printed when startup is refused.
+    pub(crate) fn failures(&self) -> String {
+        render(self.checks.iter().filter(|c| c.status == Status::Fail))
+    }
+}
+
+impl CommandOutput for DoctorReport {
+    fn human(&self) -> String {
+        render(self.checks.iter())
+    }
+}
+
+fn render<'a>(checks: impl Iterator<Item = &'a Check>) -> String {
+    let mut out = format!(
+        "{:<10} {:<6} DETAILS\n{}\n",
+        "CHECK",
+        "STATUS",
+        "-".repeat(60)
+    );
+    for check in checks {
+        let status = match check.status {
+            Status::Pass => "pass",
+            Status::Warn => "warn",
+            Status::Fail => "FAIL",
+        };
+        out.push_str(&format!(
+            "{:<10} {:<6} {}\n",
+            check.name, status, check.detail
+        ));
+        if let Some(hint) = &check.hint {
+            for line in hint.lines() {
+                out.push_str(&format!("{:<17} {}\n", "", line));
+            }
+        }
+    }
+    out
+}
+
+/// What the checks look at.
+pub(crate) struct Environment {
+    /// Why the config files failed to parse, if they did
+    pub(crate) config_error: Option<String>,
+    pub(crate) db_path: PathBuf,
+    pub(crate) archive_root: PathBuf,
+    pub(crate) port: u16,
+    pub(crate) serve_ui: bool,
+}
+
+impl Environment {
+    /// The environment the HTTP server would run in with `config`.
+    pub(crate) fn detect(config: &AppConfig) -> Self {
+        Self {
+            config_error: AppConfig::load().err().map(|e| e.to_string()),
+            db_path: store::resolve_db_path(),
+            archive_root: ModelManager::default_repo_root()
+                .unwrap_or_else(|_| PathBuf::from("data").join("archive")),
+            port: config.server.port,
+            serve_ui: config.server.serve_ui,
+        }
+    }
+
+    fn data_dir(&self) -> &Path {
+        self.db_path.parent().unwrap_or(Path::new("."))
+    }
+}
+
+/// Run every check.
+pub(crate) async fn run_all(env: &Environment) -> DoctorReport {
+    let mut checks = vec![check_config(env.config_error.as_deref())];
+    checks.extend(critical_checks(env).await);
+    checks.push(check_wal(&env.db_path));
+    checks.push(check_agent_names(&env.db_path).await);
+    checks.push(check_web_ui(env.serve_ui));
+    DoctorReport::new(checks)
+}
+
+/// Run the checks whose failure keeps the server from working.
+///
+/// A broken config file is not among them: the server falls back to
+/// defaults and logs a warning.
+pub(crate) async fn critical(env: &Environment) -> DoctorReport {
+    DoctorReport::new(critical_checks(env).await)
+}
+
+async fn critical_checks(env: &Environment) -> Vec<Check> {
+    vec![
+        check_data_dir(env.data_dir()),
+        check_database(&env.db_path).await,
+        check_archive(&env.archive_root),
+        check_port(env.port),
+        check_disk(env.data_dir()),
+    ]
+}
+
+pub(crate) fn check_config(error: Option<&str>) -> Check {
+    match error {
+        None => Check::pass("config", "Config files are valid"),
+        Some(e) => Check::fail(
+            "config",
+            format!("Config files are invalid: {}", e),
+            "Fix or remove config/default.toml, config/$RUN_MODE.toml and ~/.mouchak-mail/config.toml; `mouchak-mail config check` shows where each problem comes from",
+        ),
+    }
+}
+
+/// The data directory must be writable, or creatable if missing.
+pub(crate) fn check_data_dir(dir: &Path) -> Check {
+    let existing = dir
+        .ancestors()
+        .find(|d| d.is_dir())
+        .unwrap_or(Path::new("."));
+    if let Err(e) = probe_writable(existing) {
+        return Check::fail(
+            "data_dir",
+            format!("{} is not writable: {}", existing.display(), e),
+            "Fix the directory permissions, or set DATABASE_PATH to a writable location",
+        );
+    }
+    if existing == dir {
+        Check::pass("data_dir", format!("{} is writable", dir.display()))
+    } else {
+        Check::warn(
+            "data_dir",
+            format!("{} does not exist", dir.display()),
+            "It is created on first start",
+        )
+    }
+}
+
+fn probe_writable(dir: &Path) -> std::io::Result<()> {
+    let probe = dir.join(format!(".mouchak-mail-doctor-{}", std::process::id()));
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)
+}
+
+/// The database must open and not be newer than this binary's migrations.
+pub(crate) async fn check_database(db_path: &Path) -> Check {
+    if !db_path.exists() {
+        return Check::warn(
+            "database",
+            format!("{} does not exist", db_path.display()),
+            "It is created and migrated on first start",
+        );
+    }
+    match store::read_schema_version(db_path).await {
+        Ok(version) => check_schema_version(version, SCHEMA_VERSION),
+        Err(e) => Check::fail(
+            "database",
+            format!("Cannot open {}: {}", db_path.display(), e),
+            "Check the file permissions, or restore a snapshot with `mouchak-mail archive restore`",
+        ),
+    }
+}
+
+/// Compare the database's schema version with the embedded migrations.
+pub(crate) fn check_schema_version(found: i64, embedded: i64) -> Check {
+    if found > embedded {
+        Check::fail(
+            "database",
+            format!(
+                "Schema v{} is newer than this binary's v{}",
+                found, embedded
+            ),
+            "Upgrade mouchak-mail, or point DATABASE_PATH at another database",
+        )
+    } else if found < embedded {
+        let current = if found == 0 {
+            "Unversioned schema".to_string()
+        } else {
+            format!("Schema v{}", found)
+        };
+        Check::warn(
+            "database",
+            format!("{}, migrations up to v{} pending", current, embedded),
+            "Pending migrations are applied on next start",
+        )
+    } else {
+        Check::pass("database", format!("Schema v{} is current", found))
+    }
+}
+
+/// Agents registered before name validation may still carry names that
+/// registration now rejects.
+pub(crate) async fn check_agent_names(db_path: &Path) -> Check {
+    if !db_path.exists() {
+        return Check::pass("agent names", "No database yet");
+    }
+    match store::read_agent_name_issues(db_path).await {
+        Ok(issues) => check_agent_name_issues(&issues),
+        Err(e) => Check::warn(
+            "agent names",
+            format!("Cannot scan agent names: {}", e),
+            "Start the server once to apply migrations, then run doctor again",
+        ),
+    }
+}
+
+pub(crate) fn check_agent_name_issues(issues: &[AgentNameIssue]) -> Check {
+    if issues.is_empty() {
+        return Check::pass("agent names", "All agent names are valid");
+    }
+    let listed: Vec<String> = issues
+        .iter()
+        .map(|i| format!("{}/{} ({})", i.project_slug, i.name, i.problem))
+        .collect();
+    Check::warn(
+        "agent names",
+        format!(
+            "{} agent name(s) break the naming rules: {}",
+            issues.len(),
+            listed.join("; ")
+        ),
+        "Names must be 2-64 ASCII letters, digits, '_' or '-', unique per project ignoring case; rename with the `update_agent` tool",
+    )
+}
+
+/// A large write-ahead log is left behind by a crashed or stuck writer.
+pub(crate) fn check_wal(db_path: &Path) -> Check {
+    let mut wal = db_path.as_os_str().to_owned();
+    wal.push("-wal");
+    let wal = PathBuf::from(wal);
+    match std::fs::metadata(&wal) {
+        Ok(meta) if meta.len() >= STALE_WAL_BYTES => Check::warn(
+            "wal",
+            format!("{} is {} MB", wal.display(), meta.len() / BYTES_PER_MB),
+            format!(
+                "Stop every server, then run: sqlite3 {} 'PRAGMA wal_checkpoint(TRUNCATE)'",
+                db_path.display()
+            ),
+        ),
+        Ok(meta) => Check::pass("wal", format!("{} MB", meta.len() / BYTES_PER_MB)),
+        Err(_) => Check::pass("wal", "No write-ahead log"),
+    }
+}
+
+/// The archive must be a Git repository, or not exist yet.
+pub(crate) fn check_archive(root: &Path) -> Check {
+    if !root.join(".git").exists() {
+        return Check::warn(
+            "archive",
+            format!("Git is not initialized at {}", root.display()),
+            "The archive repository is initialized on first start",
+        );
+    }
+    match store::git_store::open_repo(root) {
+        Ok(_) => Check::pass("archive", format!("Git repository at {}", root.display())),
+        Err(e) => Check::fail(
+            "archive",
+            format!(
+                "Cannot open the Git repository at {}: {}",
+                root.display(),
+                e
+            ),
+            "Move the broken archive aside and restore a snapshot with `mouchak-mail archive restore`",
+        ),
+    }
+}
+
+pub(crate) fn check_port(port: u16) -> Check {
+    match crate::validate_port(port) {
+        Ok(()) => Check::pass("port", format!("Port {} is free", port)),
+        Err(e) => Check::fail(
+            "port",
+            format!("Port {} is not available", e.port),
+            e.suggestion,
+        ),
+    }
+}
+
+pub(crate) fn check_disk(dir: &Path) -> Check {
+    let existing = dir
+        .ancestors()
+        .find(|d| d.is_dir())
+        .unwrap_or(Path::new("."));
+    let min_free = mouchak_mail_server::health::min_free_disk_bytes();
+    let health = mouchak_mail_server::health::check_disk(existing, min_free);
+    let free = health
+        .free_bytes
+        .map(|free| format!("{} MB free", free / BYTES_PER_MB));
+    if health.ok {
+        Check::pass("disk", free.unwrap_or_else(|| "Not measured".to_string()))
+    } else {
+        Check::fail(
+            "disk",
+            free.or(health.error)
+                .unwrap_or_else(|| "Disk check failed".to_string()),
+            format!(
+                "Free space on {}, or lower HEALTH_MIN_FREE_DISK_MB (now {} MB)",
+                existing.display(),
+                min_free / BYTES_PER_MB
+            ),
+        )
+    }
+}
+
+pub(crate) fn check_web_ui(serve_ui: bool) -> Check {
+    #[cfg(feature = "with-web-ui")]
+    let embedded = mouchak_mail_server::embedded::Assets::get("index.html").is_some();
+    #[cfg(not(feature = "with-web-ui"))]
+    let embedded = false;
+
+    match (embedded, serve_ui) {
+        (true, _) => Check::pass("web_ui", "Assets embedded"),
+        (false, false) => Check::pass("web_ui", "Not embedded, serving disabled"),
+        (false, true) => Check::warn(
+            "web_ui",
+            "Assets are not embedded in this build",
+            "Rebuild with `--features with-web-ui`, or start with `serve http --no-ui`",
+        ),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schema_version_comparison() {
+        assert_eq!(check_schema_version(22, 22).status, Status::Pass);
+        assert_eq!(check_schema_version(0, 22).status, Status::Warn);
+        assert!(check_schema_version(0, 22).detail.contains("Unversioned"));
+        assert_eq!(check_schema_version(21, 22).status, Status::Warn);
+        assert_eq!(check_schema_version(23, 22).status, Status::Fail);
+    }
+
+    #[test]
+    fn test_data_dir_writable_or_creatable() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert_eq!(check_data_dir(tmp.path()).status, Status::Pass);
+        assert_eq!(
+            check_data_dir(&tmp.path().join("data")).status,
+            Status::Warn
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_database_warns() {
+        let tmp = tempfile::tempdir().unwrap();
+        let check = check_database(&tmp.path().join("mouchak_mail.db")).await;
+        assert_eq!(check.status, Status::Warn);
+        assert_eq!(
+            check_wal(&tmp.path().join("mouchak_mail.db")).status,
+            Status::Pass
+        );
+    }
+
+    #[test]
+    fn test_agent_name_issues_warn_with_each_offender() {
+        assert_eq!(check_agent_name_issues(&[]).status, Status::Pass);
+
+        let issues = [
+            AgentNameIssue {
+                project_slug: "ops".to_string(),
+                name: "../etc".to_string(),
+                problem: "'.' is not allowed".to_string(),
+            },
+            AgentNameIssue {
+                project_slug: "ops".to_string(),
+                name: "Worker".to_string(),
+                problem: "differs only in case from 'worker'".to_string(),
+            },
+        ];
+        let check = check_agent_name_issues(&issues);
+        assert_eq!(check.status, Status::Warn);
+        assert!(check.detail.starts_with("2 agent name(s)"));
+        assert!(check.detail.contains("ops/../etc"));
+        assert!(check.detail.contains("ops/Worker"));
+    }
+
+    #[test]
+    fn test_archive_needs_git() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert_eq!(check_archive(tmp.path()).status, Status::Warn);
+
+        store::git_store::init_or_open_repo(tmp.path()).unwrap();
+        assert_eq!(check_archive(tmp.path()).status, Status::Pass);
+    }
+
+    #[test]
+    fn test_port_in_use_fails_with_hint() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let check = check_port(port);
+        assert_eq!(check.status, Status::Fail);
+        assert!(check.hint.unwrap().contains("lsof"));
+    }
+
+    #[test]
+    fn test_report_lists_failures_with_hints() {
+        let report = DoctorReport::new(vec![
+            check_config(None),
+            check_config(Some("expected `=`")),
+            check_web_ui(false),
+        ]);
+        assert!(!report.ok);
+
+        let table = report.human();
+        assert!(table.contains("pass"));
+        assert!(table.contains("FAIL"));
+
+        let failures = report.failures();
+        assert!(failures.contains("expected `=`"));
+        assert!(failures.contains("Fix or remove"));
+        assert!(!failures.contains("web_ui"));
+    }
+}
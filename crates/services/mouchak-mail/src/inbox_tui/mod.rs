@@ -0,0 +1,365 @@
+//! Terminal inbox: `mouchak-mail inbox --project <slug> --agent <name>`.
+//!
+//! Reads and replies through the lib-core BMCs directly, no HTTP server
+//! needed. When stdout is not a TTY (piped, cron, CI) it prints the inbox
+//! as plain text instead.
+//!
+//! Keys: `j`/`k` move, Enter opens, `r` replies inline (Ctrl+S sends),
+//! `e` replies in `$EDITOR`, `a` acknowledges, Esc goes back, `q` quits.
+
+mod state;
+
+use anyhow::Context;
+use mouchak_mail_core::model::agent::{Agent, AgentBmc};
+use mouchak_mail_core::model::message::{MessageBmc, MessageForCreate};
+use mouchak_mail_core::model::project::{Project, ProjectBmc};
+use mouchak_mail_core::{Ctx, ModelManager};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, List, ListItem, ListState, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use state::{Action, InboxItem, InboxState, Key, Mode, reply_subject};
+use std::io::IsTerminal;
+use std::time::{Duration, Instant};
+
+/// Messages loaded per refresh.
+const INBOX_LIMIT: i64 = 100;
+
+/// Open the inbox of `agent_name` in `project_slug`.
+pub(crate) async fn run(
+    mm: ModelManager,
+    project_slug: &str,
+    agent_name: &str,
+    refresh: Duration,
+) -> anyhow::Result<()> {
+    let ctx = Ctx::root_ctx();
+    let project = ProjectBmc::get_by_identifier(&ctx, &mm, project_slug).await?;
+    let agent = AgentBmc::get_by_name(&ctx, &mm, project.id, agent_name).await?;
+    let session = Session {
+        ctx,
+        mm,
+        project,
+        agent,
+    };
+
+    let items = session.load().await?;
+    if !std::io::stdout().is_terminal() {
+        print!("{}", render_plain(&items));
+        return Ok(());
+    }
+
+    let mut terminal = ratatui::init();
+    let result = session
+        .event_loop(&mut terminal, InboxState::new(items), refresh)
+        .await;
+    ratatui::restore();
+    result
+}
+
+/// Everything the event loop needs to talk to the database.
+struct Session {
+    ctx: Ctx,
+    mm: ModelManager,
+    project: Project,
+    agent: Agent,
+}
+
+impl Session {
+    async fn load(&self) -> anyhow::Result<Vec<InboxItem>> {
+        let mut messages = MessageBmc::list_inbox_for_agent(
+            &self.ctx,
+            &self.mm,
+            self.project.id.get(),
+            self.agent.id.get(),
+            INBOX_LIMIT,
+        )
+        .await?;
+        // The detail pane shows the whole body
+        MessageBmc::load_bodies(&self.mm, &mut messages).await?;
+        let mut receipts = MessageBmc::list_recipients(&self.mm, &messages).await?;
+
+        Ok(messages
+            .into_iter()
+            .map(|msg| {
+                let receipt = receipts
+                    .remove(&msg.id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .find(|r| r.name == self.agent.name);
+                InboxItem {
+                    id: msg.id,
+                    subject: msg.subject,
+                    sender: msg.sender_name,
+                    created_ts: msg.created_ts,
+                    body: msg.body_md,
+                    unread: receipt.as_ref().is_none_or(|r| r.read_ts.is_none()),
+                    ack_required: msg.ack_required,
+                    acked: receipt.is_some_and(|r| r.ack_ts.is_some()),
+                }
+            })
+            .collect())
+    }
+
+    async fn event_loop(
+        &self,
+        terminal: &mut DefaultTerminal,
+        mut state: InboxState,
+        refresh: Duration,
+    ) -> anyhow::Result<()> {
+        let mut last_refresh = Instant::now();
+        loop {
+            terminal.draw(|frame| draw(frame, &state, &self.agent.name))?;
+
+            if last_refresh.elapsed() >= refresh {
+                state.set_items(self.load().await?);
+                last_refresh = Instant::now();
+            }
+
+            if !event::poll(Duration::from_millis(250))? {
+                continue;
+            }
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+            let key = match key.code {
+                KeyCode::Char('s') if ctrl => Key::Send,
+                KeyCode::Char(c) => Key::Char(c),
+                KeyCode::Up => Key::Up,
+                KeyCode::Down => Key::Down,
+                KeyCode::Enter => Key::Enter,
+                KeyCode::Esc => Key::Esc,
+                KeyCode::Backspace => Key::Backspace,
+                _ => continue,
+            };
+
+            state.status = None;
+            match state.handle_key(key) {
+                Action::None => {}
+                Action::Quit => return Ok(()),
+                Action::MarkRead(id) => {
+                    MessageBmc::mark_read(&self.ctx, &self.mm, id, self.agent.id.get()).await?;
+                }
+                Action::Acknowledge(id) => {
+                    MessageBmc::acknowledge(&self.ctx, &self.mm, id, self.agent.id.get()).await?;
+                    state.status = Some(format!("Acknowledged message {}", id));
+                }
+                Action::SendReply { to, body } => {
+                    state.status = Some(self.reply(to, body).await?);
+                }
+                Action::OpenEditor(to) => {
+                    ratatui::restore();
+                    let body = edit_in_editor();
+                    *terminal = ratatui::init();
+                    state.status = Some(match body? {
+                        Some(body) => self.reply(to, body).await?,
+                        None => "Reply is empty, nothing sent".to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Reply to message `to`, keeping its thread. Returns a status line.
+    async fn reply(&self, to: i64, body: String) -> anyhow::Result<String> {
+        let original = MessageBmc::get(&self.ctx, &self.mm, to).await?;
+        let msg_c = MessageForCreate {
+            project_id: self.project.id.get(),
+            sender_id: self.agent.id.get(),
+            recipient_ids: vec![original.sender_id],
+            cc_ids: None,
+            bcc_ids: None,
+            subject: reply_subject(&original.subject),
+            body_md: body,
+            thread_id: original.thread_id,
+            importance: None,
+            ack_required: false,
+            deliver_at: None,
+            broadcast: false,
+            // The thread may live in the original sender's project
+            allow_new_thread: true,
+        };
+        let id = MessageBmc::create(&self.ctx, &self.mm, msg_c).await?;
+        Ok(format!(
+            "Reply sent to {} (id: {})",
+            original.sender_name, id
+        ))
+    }
+}
+
+/// Compose a reply in `$EDITOR` (falling back to `vi`).
+///
+/// Returns `None` when the file is left empty.
+fn edit_in_editor() -> anyhow::Result<Option<String>> {
+    let editor = std::env::var("EDITOR").unwrap_or_else(|_| "vi".to_string());
+    let path = std::env::temp_dir().join(format!("mouchak-mail-reply-{}.md", std::process::id()));
+    std::fs::write(&path, "")?;
+
+    let status = std::process::Command::new(&editor)
+        .arg(&path)
+        .status()
+        .with_context(|| format!("Failed to start editor '{}'", editor))?;
+    let body = std::fs::read_to_string(&path)?;
+    let _ = std::fs::remove_file(&path);
+
+    if !status.success() {
+        anyhow::bail!("Editor '{}' exited with {}", editor, status);
+    }
+    Ok((!body.trim().is_empty()).then_some(body))
+}
+
+/// Non-interactive listing, one message per line, unread first marked `*`.
+fn render_plain(items: &[InboxItem]) -> String {
+    if items.is_empty() {
+        return "Inbox is empty.\n".to_string();
+    }
+    items
+        .iter()
+        .map(|item| {
+            format!(
+                "{} {:>6}  {}  {:<20}  {}\n",
+                if item.unread { "*" } else { " " },
+                item.id,
+                item.created_ts.format("%Y-%m-%d %H:%M"),
+                item.sender,
+                item.subject
+            )
+        })
+        .collect()
+}
+
+fn draw(frame: &mut Frame, state: &InboxState, agent_name: &str) {
+    let [main, footer] = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(1)])
+        .areas(frame.area());
+    let [list_area, detail_area] = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+        .areas(main);
+
+    let unread = state.items.iter().filter(|item| item.unread).count();
+    let rows: Vec<ListItem> = state
+        .items
+        .iter()
+        .map(|item| {
+            let style = if item.unread {
+                Style::default().add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(if item.unread { "● " } else { "  " }),
+                Span::styled(item.subject.clone(), style),
+                Span::raw(format!(
+                    "  {} · {}",
+                    item.sender,
+                    item.created_ts.format("%m-%d %H:%M")
+                )),
+            ]))
+        })
+        .collect();
+    let mut list_state = ListState::default().with_selected(Some(state.selected));
+    frame.render_stateful_widget(
+        List::new(rows)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(" {} · {} unread ", agent_name, unread)),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+        list_area,
+        &mut list_state,
+    );
+
+    let detail = match (state.mode, state.selected_item()) {
+        (Mode::Reply, Some(item)) => Paragraph::new(
+            state
+                .draft
+                .as_ref()
+                .map(|draft| format!("{}▏", draft.body))
+                .unwrap_or_default(),
+        )
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" {} ", reply_subject(&item.subject))),
+        ),
+        (_, Some(item)) => {
+            let mut header = format!(
+                "From: {}\nDate: {}\n",
+                item.sender,
+                item.created_ts.format("%Y-%m-%d %H:%M:%S")
+            );
+            if item.ack_required {
+                header.push_str(if item.acked {
+                    "Ack: done\n"
+                } else {
+                    "Ack: requested (press a)\n"
+                });
+            }
+            Paragraph::new(format!("{}\n{}", header, item.body)).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(" {} ", item.subject)),
+            )
+        }
+        (_, None) => {
+            Paragraph::new("Inbox is empty.").block(Block::default().borders(Borders::ALL))
+        }
+    };
+    frame.render_widget(detail.wrap(Wrap { trim: false }), detail_area);
+
+    let help = match state.mode {
+        Mode::Reply => "Ctrl+S send · Esc cancel",
+        _ => "j/k move · Enter open · r reply · e $EDITOR · a ack · Esc back · q quit",
+    };
+    frame.render_widget(
+        Paragraph::new(state.status.as_deref().unwrap_or(help)),
+        footer,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_plain() {
+        let created_ts = chrono::NaiveDate::from_ymd_opt(2025, 1, 31)
+            .and_then(|d| d.and_hms_opt(9, 15, 0))
+            .unwrap_or_default();
+        let items = vec![
+            InboxItem {
+                id: 12,
+                subject: "Build broken".to_string(),
+                sender: "BlueLake".to_string(),
+                created_ts,
+                body: String::new(),
+                unread: true,
+                ack_required: false,
+                acked: false,
+            },
+            InboxItem {
+                id: 7,
+                subject: "Hello".to_string(),
+                sender: "GreenCastle".to_string(),
+                created_ts,
+                body: String::new(),
+                unread: false,
+                ack_required: false,
+                acked: false,
+            },
+        ];
+        assert_eq!(
+            render_plain(&items),
+            "*     12  2025-01-31 09:15  BlueLake              Build broken\n       7  2025-01-31 09:15  GreenCastle           Hello\n"
+        );
+        assert_eq!(render_plain(&[]), "Inbox is empty.\n");
+    }
+}
@@ -0,0 +1,369 @@
+//! Inbox TUI state machine.
+//!
+//! Pure state: key presses go in, [`Action`]s for the event loop come out.
+//! Nothing here touches the terminal or the database, so navigation and
+//! reply composition are unit-testable.
+
+use chrono::NaiveDateTime;
+
+/// One inbox row as the TUI shows it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct InboxItem {
+    pub id: i64,
+    pub subject: String,
+    pub sender: String,
+    pub created_ts: NaiveDateTime,
+    pub body: String,
+    pub unread: bool,
+    pub ack_required: bool,
+    pub acked: bool,
+}
+
+/// Which pane has focus.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum Mode {
+    /// Moving through the message list
+    #[default]
+    List,
+    /// Reading the selected message
+    Detail,
+    /// Typing a reply in the inline editor
+    Reply,
+}
+
+/// Terminal-independent key events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Key {
+    Char(char),
+    Up,
+    Down,
+    Enter,
+    Esc,
+    Backspace,
+    /// Ctrl+S: send the reply being composed
+    Send,
+}
+
+/// Side effect requested by a key press.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Action {
+    None,
+    Quit,
+    MarkRead(i64),
+    Acknowledge(i64),
+    /// Send `body` as a reply to message `to`
+    SendReply {
+        to: i64,
+        body: String,
+    },
+    /// Compose the reply to this message in `$EDITOR`
+    OpenEditor(i64),
+}
+
+/// Reply being composed in the inline editor.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ReplyDraft {
+    pub to: i64,
+    pub body: String,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct InboxState {
+    pub items: Vec<InboxItem>,
+    pub selected: usize,
+    pub mode: Mode,
+    pub draft: Option<ReplyDraft>,
+    /// One-line feedback shown in the footer
+    pub status: Option<String>,
+}
+
+impl InboxState {
+    pub(crate) fn new(items: Vec<InboxItem>) -> Self {
+        Self {
+            items,
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn selected_item(&self) -> Option<&InboxItem> {
+        self.items.get(self.selected)
+    }
+
+    /// Replace the list after a refresh, keeping the cursor on the same
+    /// message when it is still there.
+    pub(crate) fn set_items(&mut self, items: Vec<InboxItem>) {
+        let selected_id = self.selected_item().map(|item| item.id);
+        self.items = items;
+        self.selected = selected_id
+            .and_then(|id| self.items.iter().position(|item| item.id == id))
+            .unwrap_or(self.selected)
+            .min(self.items.len().saturating_sub(1));
+    }
+
+    pub(crate) fn handle_key(&mut self, key: Key) -> Action {
+        match self.mode {
+            Mode::List | Mode::Detail => self.handle_browse_key(key),
+            Mode::Reply => self.handle_reply_key(key),
+        }
+    }
+
+    fn handle_browse_key(&mut self, key: Key) -> Action {
+        match key {
+            Key::Char('q') => Action::Quit,
+            Key::Char('j') | Key::Down => {
+                if self.selected + 1 < self.items.len() {
+                    self.selected += 1;
+                }
+                self.open_if_reading()
+            }
+            Key::Char('k') | Key::Up => {
+                self.selected = self.selected.saturating_sub(1);
+                self.open_if_reading()
+            }
+            Key::Enter => {
+                if self.items.is_empty() {
+                    return Action::None;
+                }
+                self.mode = Mode::Detail;
+                self.open_selected()
+            }
+            Key::Esc => {
+                self.mode = Mode::List;
+                Action::None
+            }
+            Key::Char('a') => match self.items.get_mut(self.selected) {
+                Some(item) if item.ack_required && !item.acked => {
+                    item.acked = true;
+                    item.unread = false;
+                    Action::Acknowledge(item.id)
+                }
+                Some(item) if item.acked => {
+                    self.status = Some("Already acknowledged".to_string());
+                    Action::None
+                }
+                Some(_) => {
+                    self.status = Some("No acknowledgement requested".to_string());
+                    Action::None
+                }
+                None => Action::None,
+            },
+            Key::Char('r') => {
+                let Some(to) = self.selected_item().map(|item| item.id) else {
+                    return Action::None;
+                };
+                self.draft = Some(ReplyDraft {
+                    to,
+                    body: String::new(),
+                });
+                self.mode = Mode::Reply;
+                self.status = Some("Ctrl+S to send, Esc to cancel".to_string());
+                Action::None
+            }
+            Key::Char('e') => self
+                .selected_item()
+                .map_or(Action::None, |item| Action::OpenEditor(item.id)),
+            _ => Action::None,
+        }
+    }
+
+    fn handle_reply_key(&mut self, key: Key) -> Action {
+        let Some(draft) = self.draft.as_mut() else {
+            self.mode = Mode::Detail;
+            return Action::None;
+        };
+        match key {
+            Key::Char(c) => draft.body.push(c),
+            Key::Enter => draft.body.push('\n'),
+            Key::Backspace => {
+                draft.body.pop();
+            }
+            Key::Esc => {
+                self.draft = None;
+                self.mode = Mode::Detail;
+                self.status = Some("Reply discarded".to_string());
+            }
+            Key::Send => {
+                if draft.body.trim().is_empty() {
+                    self.status = Some("Reply is empty".to_string());
+                    return Action::None;
+                }
+                let ReplyDraft { to, body } = draft.clone();
+                self.draft = None;
+                self.mode = Mode::Detail;
+                return Action::SendReply { to, body };
+            }
+            Key::Up | Key::Down => {}
+        }
+        Action::None
+    }
+
+    /// Moving while the detail pane is open opens the new selection.
+    fn open_if_reading(&mut self) -> Action {
+        if self.mode == Mode::Detail {
+            self.open_selected()
+        } else {
+            Action::None
+        }
+    }
+
+    fn open_selected(&mut self) -> Action {
+        match self.items.get_mut(self.selected) {
+            Some(item) if item.unread => {
+                item.unread = false;
+                Action::MarkRead(item.id)
+            }
+            _ => Action::None,
+        }
+    }
+}
+
+/// Subject for a reply, without stacking "Re: " prefixes.
+pub(crate) fn reply_subject(subject: &str) -> String {
+    if subject.starts_with("Re: ") {
+        subject.to_string()
+    } else {
+        format!("Re: {}", subject)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: i64, unread: bool, ack_required: bool) -> InboxItem {
+        InboxItem {
+            id,
+            subject: format!("Subject {}", id),
+            sender: "BlueLake".to_string(),
+            created_ts: NaiveDateTime::default(),
+            body: "body".to_string(),
+            unread,
+            ack_required,
+            acked: false,
+        }
+    }
+
+    fn type_text(state: &mut InboxState, text: &str) {
+        for c in text.chars() {
+            let key = if c == '\n' { Key::Enter } else { Key::Char(c) };
+            assert_eq!(state.handle_key(key), Action::None);
+        }
+    }
+
+    #[test]
+    fn test_navigation_stays_in_bounds() {
+        let mut state = InboxState::new(vec![item(3, false, false), item(2, false, false)]);
+
+        assert_eq!(state.handle_key(Key::Char('k')), Action::None);
+        assert_eq!(state.selected, 0);
+        state.handle_key(Key::Char('j'));
+        state.handle_key(Key::Down);
+        assert_eq!(state.selected, 1);
+        state.handle_key(Key::Up);
+        assert_eq!(state.selected, 0);
+        assert_eq!(state.handle_key(Key::Char('q')), Action::Quit);
+    }
+
+    #[test]
+    fn test_open_marks_read_once() {
+        let mut state = InboxState::new(vec![item(3, true, false), item(2, true, false)]);
+
+        assert_eq!(state.handle_key(Key::Enter), Action::MarkRead(3));
+        assert_eq!(state.mode, Mode::Detail);
+        assert_eq!(state.handle_key(Key::Enter), Action::None);
+
+        // Moving with the detail pane open reads the next message
+        assert_eq!(state.handle_key(Key::Char('j')), Action::MarkRead(2));
+        assert_eq!(state.handle_key(Key::Esc), Action::None);
+        assert_eq!(state.mode, Mode::List);
+        assert_eq!(state.handle_key(Key::Char('k')), Action::None);
+    }
+
+    #[test]
+    fn test_empty_inbox_ignores_actions() {
+        let mut state = InboxState::new(Vec::new());
+        for key in [Key::Enter, Key::Char('j'), Key::Char('a'), Key::Char('r')] {
+            assert_eq!(state.handle_key(key), Action::None);
+        }
+        assert_eq!(state.mode, Mode::List);
+    }
+
+    #[test]
+    fn test_acknowledge() {
+        let mut state = InboxState::new(vec![item(3, true, true), item(2, false, false)]);
+
+        assert_eq!(state.handle_key(Key::Char('a')), Action::Acknowledge(3));
+        assert!(!state.items[0].unread);
+        assert_eq!(state.handle_key(Key::Char('a')), Action::None);
+        assert_eq!(state.status.as_deref(), Some("Already acknowledged"));
+
+        state.handle_key(Key::Char('j'));
+        assert_eq!(state.handle_key(Key::Char('a')), Action::None);
+    }
+
+    #[test]
+    fn test_reply_composition() {
+        let mut state = InboxState::new(vec![item(3, false, false)]);
+
+        state.handle_key(Key::Char('r'));
+        assert_eq!(state.mode, Mode::Reply);
+        // Browse keys are plain text while replying
+        type_text(&mut state, "qjk ok\nthx!");
+        state.handle_key(Key::Backspace);
+        assert_eq!(
+            state.draft.as_ref().map(|d| d.body.as_str()),
+            Some("qjk ok\nthx")
+        );
+
+        assert_eq!(
+            state.handle_key(Key::Send),
+            Action::SendReply {
+                to: 3,
+                body: "qjk ok\nthx".to_string()
+            }
+        );
+        assert_eq!(state.mode, Mode::Detail);
+        assert!(state.draft.is_none());
+    }
+
+    #[test]
+    fn test_reply_empty_and_cancel() {
+        let mut state = InboxState::new(vec![item(3, false, false)]);
+
+        state.handle_key(Key::Char('r'));
+        type_text(&mut state, "  \n");
+        assert_eq!(state.handle_key(Key::Send), Action::None);
+        assert_eq!(state.mode, Mode::Reply);
+        assert_eq!(state.status.as_deref(), Some("Reply is empty"));
+
+        state.handle_key(Key::Esc);
+        assert_eq!(state.mode, Mode::Detail);
+        assert!(state.draft.is_none());
+
+        assert_eq!(state.handle_key(Key::Char('e')), Action::OpenEditor(3));
+    }
+
+    #[test]
+    fn test_refresh_keeps_selection() {
+        let mut state = InboxState::new(vec![item(3, false, false), item(2, false, false)]);
+        state.handle_key(Key::Char('j'));
+
+        // A new message arrives on top
+        state.set_items(vec![
+            item(4, true, false),
+            item(3, false, false),
+            item(2, false, false),
+        ]);
+        assert_eq!(state.selected_item().map(|i| i.id), Some(2));
+
+        // The selected message disappears (e.g. recalled)
+        state.set_items(vec![item(4, true, false)]);
+        assert_eq!(state.selected, 0);
+    }
+
+    #[test]
+    fn test_reply_subject() {
+        assert_eq!(reply_subject("Build broken"), "Re: Build broken");
+        assert_eq!(reply_subject("Re: Build broken"), "Re: Build broken");
+    }
+}
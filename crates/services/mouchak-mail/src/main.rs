@@ -1,13 +1,18 @@
 use clap::{Args, CommandFactory, Parser, Subcommand};
-use mouchak_mail_common::config::AppConfig;
-use mouchak_mail_mcp::{docs::generate_markdown_docs, run_sse, run_stdio, tools::get_tool_schemas};
+use mouchak_mail_common::config::{AppConfig, HookEvent};
+use mouchak_mail_common::output::{CommandOutput, OutputMode};
+use mouchak_mail_mcp::docs::{render_schema, render_tool_list};
+use mouchak_mail_mcp::{run_sse, run_stdio};
 use std::io::Write;
 use std::net::TcpListener;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use tracing::info;
 
+mod doctor;
+mod inbox_tui;
 mod panic_hook;
 mod robot_help;
+mod shell_alias;
 
 #[derive(Parser)]
 #[command(name = "mouchak-mail")]
@@ -66,23 +71,46 @@ enum Commands {
             default_value = "http://localhost:8765"
         )]
         url: String,
+        /// Output mode: human or json
+        #[arg(long, env = "AM_OUTPUT", default_value_t = OutputMode::Human)]
+        output: OutputMode,
+    },
+
+    /// Check the local environment before serving
+    Doctor {
+        /// Port to check instead of the configured one
+        #[arg(short, long)]
+        port: Option<u16>,
+        /// Output mode: human or json
+        #[arg(long, env = "AM_OUTPUT", default_value_t = OutputMode::Human)]
+        output: OutputMode,
     },
 
     /// Manage configuration
     Config(ConfigArgs),
 
-    /// Export JSON schemas for all tools
+    /// Export JSON schemas for all tools, or the HTTP API's OpenAPI document
     Schema {
-        /// Output format: json or markdown
+        /// Output format: json, markdown or openapi
         #[arg(short, long, default_value = "json")]
         format: String,
         /// Output file (stdout if not specified)
         #[arg(short, long)]
         output: Option<String>,
+        /// Include the worktree build slot tools
+        #[arg(long, env = "WORKTREES_ENABLED", value_parser = clap::builder::BoolishValueParser::new())]
+        worktrees: bool,
     },
 
-    /// List all available tools
-    Tools,
+    /// List all tools, and which the current configuration disables
+    Tools {
+        /// Enable the worktree build slot tools
+        #[arg(long, env = "WORKTREES_ENABLED", value_parser = clap::builder::BoolishValueParser::new())]
+        worktrees: bool,
+        /// Output mode: human or json
+        #[arg(long, env = "AM_OUTPUT", default_value_t = OutputMode::Human)]
+        output: OutputMode,
+    },
 
     /// Install shell alias and configuration
     Install(InstallArgs),
@@ -93,9 +121,15 @@ enum Commands {
     /// Export sharing utilities (signing, verification)
     Share(ShareArgs),
 
+    /// Create, sign, verify, and import mailbox exports
+    Export(ExportArgs),
+
     /// Archive management (disaster recovery)
     Archive(ArchiveArgs),
 
+    /// Database snapshots (agents, reservations, acks and read state included)
+    Backup(BackupArgs),
+
     /// Summarize thread(s) in a project
     Summarize(SummarizeArgs),
 
@@ -105,11 +139,20 @@ enum Commands {
     /// Product management
     Products(ProductsArgs),
 
+    /// Agent API keys
+    Agents(AgentsArgs),
+
+    /// Event hooks configured under [[hooks.on]]
+    Hooks(HooksArgs),
+
     /// Pre-commit guard management
     Guard(GuardArgs),
 
     /// Mail/project status information
     Mail(MailArgs),
+
+    /// Read and reply to an agent's inbox in the terminal
+    Inbox(InboxArgs),
 }
 
 #[derive(Args)]
@@ -127,6 +170,12 @@ enum ConfigCommands {
     },
     /// Show the current binding port
     ShowPort,
+    /// Validate config files and env overrides and show effective values
+    Check {
+        /// Check this file instead of the usual config files
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
 }
 
 #[derive(Args)]
@@ -142,6 +191,12 @@ enum InstallCommands {
         /// Force overwrite existing alias
         #[arg(long)]
         force: bool,
+        /// Remove the alias instead
+        #[arg(long, conflicts_with = "force")]
+        uninstall: bool,
+        /// Shell to configure (detected when omitted)
+        #[arg(long, value_enum)]
+        shell: Option<shell_alias::ShellKind>,
     },
 }
 
@@ -151,6 +206,69 @@ struct ServiceArgs {
     command: ServiceCommands,
 }
 
+#[derive(Args)]
+struct AgentsArgs {
+    #[command(subcommand)]
+    command: AgentsCommands,
+}
+
+#[derive(Subcommand)]
+enum AgentsCommands {
+    /// Issue an API key that lets requests act as this agent only
+    IssueToken {
+        /// Project slug or path
+        project: String,
+        /// Agent name
+        name: String,
+    },
+    /// Revoke every API key of an agent
+    RevokeToken {
+        /// Project slug or path
+        project: String,
+        /// Agent name
+        name: String,
+    },
+}
+
+#[derive(Args)]
+struct HooksArgs {
+    #[command(subcommand)]
+    command: HooksCommands,
+}
+
+#[derive(Subcommand)]
+enum HooksCommands {
+    /// Fire a made-up event through the configured hooks
+    Test {
+        /// message.created, message.acked or reservation.conflict
+        event: HookEvent,
+        /// Project slug the event claims to come from
+        #[arg(long, default_value = "hook-test")]
+        project: String,
+        /// Message importance the event carries
+        #[arg(long, default_value = "normal")]
+        importance: String,
+    },
+}
+
+#[derive(Args)]
+struct BackupArgs {
+    #[command(subcommand)]
+    command: BackupCommands,
+}
+
+#[derive(Subcommand)]
+enum BackupCommands {
+    /// Snapshot the database now
+    Now {
+        /// Write the snapshot here instead of the backup directory (no rotation)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// List snapshots in the backup directory, newest first
+    List,
+}
+
 #[derive(Args)]
 struct ProductsArgs {
     #[command(subcommand)]
@@ -314,7 +432,7 @@ enum ExportCommands {
         #[arg(short, long)]
         output: String,
 
-        /// Privacy scrubbing mode: none, standard, aggressive
+        /// Privacy scrubbing mode: none, standard, aggressive, emails, secrets, all
         #[arg(long, default_value = "none")]
         scrub: String,
 
@@ -377,6 +495,19 @@ struct MailArgs {
     command: MailCommands,
 }
 
+#[derive(Args)]
+struct InboxArgs {
+    /// Project slug or path
+    #[arg(short, long)]
+    project: String,
+    /// Agent whose inbox to open
+    #[arg(short, long)]
+    agent: String,
+    /// Seconds between inbox refreshes
+    #[arg(long, default_value_t = 5)]
+    refresh: u64,
+}
+
 #[derive(Args)]
 struct SummarizeArgs {
     /// Project slug or path
@@ -466,6 +597,145 @@ enum MailCommands {
     Status,
 }
 
+#[derive(Args)]
+struct ExportArgs {
+    #[command(subcommand)]
+    command: MailboxExportCommands,
+}
+
+#[derive(Subcommand)]
+enum MailboxExportCommands {
+    /// Export a project's mailbox and write a manifest next to it
+    Create {
+        /// Project slug to export
+        #[arg(short, long)]
+        project: String,
+
+        /// Export format: json, html, html-zip, markdown, csv, tsv, ndjson, or mbox
+        #[arg(short, long, default_value = "json")]
+        format: String,
+
+        /// Scrub mode: none, standard, aggressive, emails, secrets, or all
+        #[arg(long, default_value = "none")]
+        scrub: String,
+
+        /// Only include messages created on or after this date (YYYY-MM-DD or RFC3339)
+        #[arg(long)]
+        since: Option<String>,
+
+        /// Only include messages created on or before this date (YYYY-MM-DD or RFC3339)
+        #[arg(long)]
+        until: Option<String>,
+
+        /// Only include messages sent or received by this agent
+        #[arg(long)]
+        agent: Option<String>,
+
+        /// Only include messages from this thread
+        #[arg(long)]
+        thread: Option<String>,
+
+        /// Output file for the export (defaults to <project>-export.<ext>)
+        #[arg(short, long)]
+        output: Option<String>,
+
+        /// Output file for the manifest (defaults to <output>.manifest.json)
+        #[arg(short, long)]
+        manifest: Option<String>,
+
+        /// Ed25519 signing key (base64)
+        #[arg(long, env = "MOUCHAK_MAIL_SIGNING_KEY", hide_env_values = true)]
+        key: Option<String>,
+
+        /// Keypair file written by `share keypair --output`
+        #[arg(long, conflicts_with = "key")]
+        key_file: Option<String>,
+
+        /// Encrypt the export for this age recipient (repeatable)
+        #[arg(short, long = "recipient")]
+        recipients: Vec<String>,
+
+        /// Encrypt the export with an age passphrase
+        #[arg(long, conflicts_with = "recipients")]
+        passphrase: Option<String>,
+    },
+    /// Sign an existing manifest after checking it matches the export
+    Sign {
+        /// Export file (plain or age-encrypted)
+        export: String,
+
+        /// Manifest file to sign in place
+        manifest: String,
+
+        /// Ed25519 signing key (base64)
+        #[arg(long, env = "MOUCHAK_MAIL_SIGNING_KEY", hide_env_values = true)]
+        key: Option<String>,
+
+        /// Keypair file written by `share keypair --output`
+        #[arg(long, conflicts_with = "key")]
+        key_file: Option<String>,
+
+        /// age identity file for encrypted exports
+        #[arg(short, long)]
+        identity_file: Option<String>,
+
+        /// Passphrase for encrypted exports (prompted if needed and omitted)
+        #[arg(long, conflicts_with = "identity_file")]
+        passphrase: Option<String>,
+    },
+    /// Verify an export against its manifest
+    ///
+    /// Exits 0 when the content hash matches and any signature is valid.
+    /// Exit codes: 3 malformed manifest, 4 tampered content, 5 invalid
+    /// signature or wrong key, 6 decryption failed.
+    Verify {
+        /// Export file (plain or age-encrypted)
+        export: String,
+
+        /// Manifest file produced by `export create`
+        manifest: String,
+
+        /// Expected signer public key (base64); required to trust the signer
+        #[arg(long)]
+        public_key: Option<String>,
+
+        /// age identity file for encrypted exports
+        #[arg(short, long)]
+        identity_file: Option<String>,
+
+        /// Passphrase for encrypted exports (prompted if needed and omitted)
+        #[arg(long, conflicts_with = "identity_file")]
+        passphrase: Option<String>,
+    },
+    /// Import messages from a JSON or NDJSON export into a project
+    ///
+    /// Missing senders are created, messages already present are skipped.
+    Import {
+        /// Export file (plain or age-encrypted)
+        file: String,
+
+        /// Project slug to import into
+        #[arg(short, long)]
+        project: String,
+
+        /// Export format: json or ndjson (defaults to the manifest's, then the file extension)
+        #[arg(short, long)]
+        format: Option<String>,
+
+        /// Manifest to verify before importing
+        #[arg(short, long)]
+        manifest: Option<String>,
+
+        /// age identity file for encrypted exports
+        #[arg(short, long)]
+        identity_file: Option<String>,
+
+        /// Passphrase for encrypted exports (prompted if needed and omitted)
+        #[arg(long, conflicts_with = "identity_file")]
+        passphrase: Option<String>,
+    },
+}
+
 #[derive(Args)]
 struct ServeArgs {
     #[command(subcommand)]
@@ -491,6 +761,12 @@ enum ServeCommands {
         transport: String,
         #[arg(short, long, default_value = "3000")]
         port: u16,
+        /// Address to bind for the SSE transport (use 0.0.0.0 for all interfaces)
+        #[arg(long)]
+        host: Option<String>,
+        /// Expose the worktree build slot tools
+        #[arg(long, env = "WORKTREES_ENABLED", value_parser = clap::builder::BoolishValueParser::new())]
+        worktrees: bool,
     },
 }
 
@@ -516,10 +792,22 @@ fn setup_tracing(json_logs: bool) -> anyhow::Result<()> {
 }
 
 fn load_config() -> AppConfig {
-    AppConfig::load().unwrap_or_else(|e| {
-        tracing::warn!("Failed to load config file: {}. Using defaults.", e);
-        AppConfig::default()
-    })
+    let report = AppConfig::check(None);
+    for issue in &report.issues {
+        tracing::warn!("Ignoring invalid config: {}. Using the default.", issue);
+    }
+    report.config
+}
+
+/// Commands that start a server, which must not run on a broken config.
+fn starts_server(command: Option<&Commands>) -> bool {
+    matches!(
+        command,
+        Some(Commands::Serve(_))
+            | Some(Commands::Service(ServiceArgs {
+                command: ServiceCommands::Start { .. } | ServiceCommands::Restart { .. },
+            }))
+    )
 }
 
 // ============================================================================
@@ -605,9 +893,13 @@ async fn handle_serve_http(
     // --no-ui takes precedence, otherwise use --with-ui value
     config.server.serve_ui = !no_ui && with_ui;
 
-    // Validate port availability before starting server
-    if let Err(e) = validate_port(config.server.port) {
-        eprintln!("\n{}", e);
+    // Refuse to start in an environment `doctor` would fail
+    let report = doctor::critical(&doctor::Environment::detect(&config)).await;
+    if !report.ok {
+        eprintln!(
+            "\nRefusing to start:\n\n{}\nRun `mouchak-mail doctor` for the full report.",
+            report.failures()
+        );
         std::process::exit(1);
     }
 
@@ -619,10 +911,16 @@ async fn handle_serve_http(
 async fn handle_serve_mcp(
     transport: String,
     port: u16,
+    host: Option<String>,
+    worktrees: bool,
     mut config: AppConfig,
 ) -> anyhow::Result<()> {
     config.mcp.transport = transport.clone();
     config.mcp.port = port;
+    if let Some(host) = host {
+        config.mcp.host = host;
+    }
+    config.mcp.worktrees_enabled |= worktrees;
     info!("Starting MCP Server ({})", transport);
     if transport == "sse" {
         run_sse(config).await?;
@@ -632,208 +930,177 @@ async fn handle_serve_mcp(
     Ok(())
 }
 
-async fn handle_health(url: String) -> anyhow::Result<()> {
-    info!("Checking health at {}", url);
-    let resp = reqwest::get(format!("{}/health", url)).await?;
-    if resp.status().is_success() {
-        info!("Server is HEALTHY: {}", resp.text().await?);
-    } else {
-        tracing::error!("Server is UNHEALTHY: Status {}", resp.status());
-        std::process::exit(1);
+/// Result of `mouchak-mail health`.
+#[derive(serde::Serialize)]
+struct HealthResult {
+    url: String,
+    http_status: u16,
+    healthy: bool,
+    /// The server's `/health` body; a string if it was not JSON
+    report: serde_json::Value,
+}
+
+impl CommandOutput for HealthResult {
+    fn human(&self) -> String {
+        match &self.report {
+            // Older servers return a bare status object without components
+            report if report.get("components").is_some() => format_health_report(report).0,
+            serde_json::Value::String(body) => body.clone(),
+            report => report.to_string(),
+        }
     }
-    Ok(())
 }
 
-fn handle_schema(format: String, output: Option<String>) -> anyhow::Result<()> {
-    // Show all tools in documentation (worktrees_enabled=true)
-    let schemas = get_tool_schemas(true);
-    let content = if format == "markdown" || format == "md" {
-        generate_markdown_docs(&schemas)
-    } else {
-        serde_json::to_string_pretty(&schemas)?
-    };
-    if let Some(path) = output {
-        std::fs::write(&path, &content)?;
-        eprintln!("Schema written to {}", path);
-    } else {
-        println!("{}", content);
+async fn handle_doctor(
+    port: Option<u16>,
+    output: OutputMode,
+    mut config: AppConfig,
+) -> anyhow::Result<()> {
+    if let Some(p) = port {
+        config.server.port = p;
+    }
+    let report = doctor::run_all(&doctor::Environment::detect(&config)).await;
+    output.emit(&report)?;
+    if !report.ok {
+        std::process::exit(1);
     }
     Ok(())
 }
 
-fn handle_tools() {
-    // Show all tools in documentation (worktrees_enabled=true)
-    let schemas = get_tool_schemas(true);
-    println!("Mouchak Mail Tools ({} total)\n", schemas.len());
-    println!("{:<30} DESCRIPTION", "TOOL");
-    println!("{}", "-".repeat(80));
-    for schema in schemas {
-        println!("{:<30} {}", schema.name, schema.description);
+async fn handle_health(url: String, output: OutputMode) -> anyhow::Result<()> {
+    info!("Checking health at {}", url);
+    let resp = reqwest::get(format!("{}/health", url)).await?;
+    let status = resp.status();
+    let body = resp.text().await?;
+
+    let report =
+        serde_json::from_str::<serde_json::Value>(&body).unwrap_or(serde_json::Value::String(body));
+    let healthy = status.is_success()
+        && (report.get("components").is_none() || format_health_report(&report).1);
+
+    output.emit(&HealthResult {
+        url,
+        http_status: status.as_u16(),
+        healthy,
+        report,
+    })?;
+
+    if !healthy {
+        tracing::error!("Server is UNHEALTHY: Status {}", status);
+        std::process::exit(1);
     }
+    info!("Server is HEALTHY");
+    Ok(())
 }
 
-// ============================================================================
-// Install Command Handlers (PORT-6.1)
-// ============================================================================
-
-/// Detect user's shell and return the appropriate rc file path.
-fn detect_shell_rc() -> Option<PathBuf> {
-    let home = std::env::var("HOME").ok()?;
-    let home_path = PathBuf::from(&home);
+/// Render the `/health` component report as a table.
+///
+/// Returns the table and whether every component is ok.
+fn format_health_report(report: &serde_json::Value) -> (String, bool) {
+    let mut out = format!(
+        "Status: {}  Version: {}  Uptime: {}s\n\n{:<14} {:<10} DETAILS\n{}\n",
+        report["status"].as_str().unwrap_or("unknown"),
+        report["version"].as_str().unwrap_or("unknown"),
+        report["uptime_seconds"].as_u64().unwrap_or(0),
+        "COMPONENT",
+        "STATUS",
+        "-".repeat(60)
+    );
 
-    // Check SHELL environment variable first
-    if let Ok(shell) = std::env::var("SHELL") {
-        if shell.ends_with("zsh") {
-            return Some(home_path.join(".zshrc"));
-        } else if shell.ends_with("bash") {
-            return Some(home_path.join(".bashrc"));
-        } else if shell.ends_with("fish") {
-            return Some(home_path.join(".config/fish/config.fish"));
-        }
-    }
+    let mut healthy = report["status"].as_str() == Some("healthy");
+    if let Some(components) = report["components"].as_object() {
+        for (name, component) in components {
+            let ok = component["ok"].as_bool().unwrap_or(false);
+            healthy &= ok;
 
-    // Fallback: check which rc files exist
-    let zshrc = home_path.join(".zshrc");
-    if zshrc.exists() {
-        return Some(zshrc);
-    }
+            let mut details = Vec::new();
+            if let Some(ms) = component["latency_ms"].as_u64() {
+                details.push(format!("{}ms", ms));
+            }
+            if let (Some(free), Some(total)) = (
+                component["free_bytes"].as_u64(),
+                component["total_bytes"].as_u64(),
+            ) {
+                details.push(format!(
+                    "{} MB free of {} MB",
+                    free / (1024 * 1024),
+                    total / (1024 * 1024)
+                ));
+            }
+            if let Some(error) = component["error"].as_str() {
+                details.push(error.to_string());
+            }
 
-    let bashrc = home_path.join(".bashrc");
-    if bashrc.exists() {
-        return Some(bashrc);
+            out.push_str(&format!(
+                "{:<14} {:<10} {}\n",
+                name,
+                if ok { "ok" } else { "DEGRADED" },
+                details.join(", ")
+            ));
+        }
     }
 
-    // Default to .profile for POSIX shells
-    Some(home_path.join(".profile"))
+    (out, healthy)
 }
 
-/// Check if the 'am' alias marker already exists in the rc file.
-fn alias_marker_exists(rc_path: &PathBuf) -> bool {
-    if let Ok(contents) = std::fs::read_to_string(rc_path) {
-        contents.contains("# >>> Mouchak Mail alias")
+fn handle_schema(format: String, output: Option<String>, worktrees: bool) -> anyhow::Result<()> {
+    let content = if format == "openapi" {
+        // Same document the server serves at /api/openapi.json
+        let mode = mouchak_mail_server::auth::AuthConfig::from_env().mode;
+        mouchak_mail_server::openapi::ApiDoc::for_auth_mode(&mode).to_pretty_json()?
     } else {
-        false
+        render_schema(&format, worktrees)?
+    };
+    if let Some(path) = output {
+        std::fs::write(&path, &content)?;
+        eprintln!("Schema written to {}", path);
+    } else {
+        println!("{}", content);
     }
+    Ok(())
 }
 
-/// Check if a different 'am' alias exists (not managed by us).
-fn other_am_alias_exists(rc_path: &PathBuf) -> bool {
-    if let Ok(contents) = std::fs::read_to_string(rc_path) {
-        // Check for any 'alias am=' that isn't in our managed block
-        for line in contents.lines() {
-            let trimmed = line.trim();
-            if trimmed.starts_with("alias am=") && !contents.contains("# >>> Mouchak Mail alias") {
-                return true;
-            }
-        }
-    }
-    false
+/// Result of `mouchak-mail tools`.
+///
+/// Lists every tool; `count` is the number enabled in this configuration.
+#[derive(serde::Serialize)]
+struct ToolList {
+    count: usize,
+    tools: Vec<ToolSummary>,
+    #[serde(skip)]
+    worktrees: bool,
 }
 
-/// Generate the alias snippet for a given shell type.
-fn generate_alias_snippet(rc_path: &Path) -> &'static str {
-    let is_fish = rc_path
-        .to_string_lossy()
-        .contains(".config/fish/config.fish");
+#[derive(serde::Serialize)]
+struct ToolSummary {
+    name: String,
+    description: String,
+    enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    disabled_reason: Option<String>,
+}
 
-    if is_fish {
-        // Fish shell uses different syntax
-        r#"
-# >>> Mouchak Mail alias
-function am
-    mouchak-mail serve http
-end
-# <<< Mouchak Mail alias
-"#
-    } else {
-        // Bash/Zsh syntax
-        r#"
-# >>> Mouchak Mail alias
-alias am='mouchak-mail serve http'
-# <<< Mouchak Mail alias
-"#
+impl CommandOutput for ToolList {
+    fn human(&self) -> String {
+        render_tool_list(self.worktrees).trim_end().to_string()
     }
 }
 
-/// Handle the 'install alias' command.
-fn handle_install_alias(force: bool) -> anyhow::Result<()> {
-    // Detect shell rc file
-    let rc_path = detect_shell_rc().ok_or_else(|| {
-        anyhow::anyhow!("Could not detect shell configuration file. Set HOME environment variable.")
+fn handle_tools(worktrees: bool, output: OutputMode) -> anyhow::Result<()> {
+    let tools: Vec<ToolSummary> = mouchak_mail_mcp::get_tool_schemas(worktrees)
+        .into_iter()
+        .map(|schema| ToolSummary {
+            name: schema.name,
+            description: schema.description,
+            enabled: schema.enabled,
+            disabled_reason: schema.disabled_reason,
+        })
+        .collect();
+    output.emit(&ToolList {
+        count: tools.iter().filter(|tool| tool.enabled).count(),
+        tools,
+        worktrees,
     })?;
-
-    println!("Detected shell config: {}", rc_path.display());
-
-    // Check if our marker already exists
-    if alias_marker_exists(&rc_path) {
-        if force {
-            println!("Updating existing 'am' alias...");
-            // Remove old marker block and re-add
-            if let Ok(contents) = std::fs::read_to_string(&rc_path) {
-                let mut new_contents = String::new();
-                let mut in_block = false;
-
-                for line in contents.lines() {
-                    if line.contains("# >>> Mouchak Mail alias") {
-                        in_block = true;
-                        continue;
-                    }
-                    if line.contains("# <<< Mouchak Mail alias") {
-                        in_block = false;
-                        continue;
-                    }
-                    if !in_block {
-                        new_contents.push_str(line);
-                        new_contents.push('\n');
-                    }
-                }
-
-                // Append new alias
-                new_contents.push_str(generate_alias_snippet(&rc_path));
-
-                std::fs::write(&rc_path, new_contents)?;
-                println!("✓ Updated 'am' alias in {}", rc_path.display());
-            }
-        } else {
-            println!("✓ 'am' alias already installed in {}", rc_path.display());
-            println!("  Use --force to update the alias.");
-        }
-        return Ok(());
-    }
-
-    // Check for conflicting alias
-    if other_am_alias_exists(&rc_path) && !force {
-        println!(
-            "⚠ An existing 'am' alias was found in {}",
-            rc_path.display()
-        );
-        println!("  Use --force to overwrite it.");
-        return Ok(());
-    }
-
-    // Ensure parent directory exists (for fish)
-    if let Some(parent) = rc_path.parent() {
-        std::fs::create_dir_all(parent)?;
-    }
-
-    // Append alias to rc file
-    let mut file = std::fs::OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(&rc_path)?;
-
-    file.write_all(generate_alias_snippet(&rc_path).as_bytes())?;
-
-    println!("✓ Added 'am' alias to {}", rc_path.display());
-    println!();
-    println!("To use the alias now, run:");
-    println!("  source {}", rc_path.display());
-    println!();
-    println!("Or open a new terminal, then run:");
-    println!("  am");
-    println!();
-    println!("This starts the HTTP server on port 8765.");
-
     Ok(())
 }
 
@@ -1037,6 +1304,507 @@ fn handle_share_verify(manifest_path: &str, public_key: Option<&str>) -> anyhow:
     Ok(())
 }
 
+// --- Mailbox Export Handlers ---
+
+/// `export verify` exit code: manifest could not be parsed or is incomplete
+const EXPORT_EXIT_MALFORMED: i32 = 3;
+/// `export verify` exit code: content does not match the manifest hash
+const EXPORT_EXIT_TAMPERED: i32 = 4;
+/// `export verify` exit code: signature invalid or signed by a different key
+const EXPORT_EXIT_BAD_SIGNATURE: i32 = 5;
+/// `export verify` exit code: encrypted export could not be decrypted
+const EXPORT_EXIT_DECRYPT_FAILED: i32 = 6;
+
+const AGE_ARMOR_HEADER: &str = "-----BEGIN AGE ENCRYPTED FILE-----";
+
+fn export_fail(code: i32, message: &str) -> ! {
+    eprintln!("✗ {}", message);
+    std::process::exit(code);
+}
+
+fn export_extension(format: &mouchak_mail_core::model::export::ExportFormat) -> &'static str {
+    use mouchak_mail_core::model::export::ExportFormat;
+
+    match format {
+        ExportFormat::Html => "html",
+        ExportFormat::HtmlZip => "zip",
+        ExportFormat::Json => "json",
+        ExportFormat::Markdown => "md",
+        ExportFormat::Csv => "csv",
+        ExportFormat::Tsv => "tsv",
+        ExportFormat::Ndjson => "ndjson",
+        ExportFormat::Mbox => "mbox",
+    }
+}
+
+/// Resolve a base64 signing key from `--key` or a keypair JSON file
+fn read_signing_key(key: Option<&str>, key_file: Option<&str>) -> anyhow::Result<Option<String>> {
+    let key_b64 = match (key, key_file) {
+        (Some(k), _) => k.trim().to_string(),
+        (None, Some(path)) => {
+            let raw = std::fs::read_to_string(path)?;
+            match serde_json::from_str::<serde_json::Value>(&raw) {
+                Ok(json) => json["private_key"]
+                    .as_str()
+                    .ok_or_else(|| anyhow::anyhow!("{} has no private_key field", path))?
+                    .to_string(),
+                Err(_) => raw.trim().to_string(),
+            }
+        }
+        (None, None) => return Ok(None),
+    };
+
+    Ok(Some(key_b64))
+}
+
+/// Read the first `AGE-SECRET-KEY-` line from an age identity file
+fn read_age_identity(path: &str) -> anyhow::Result<String> {
+    let raw = std::fs::read_to_string(path)?;
+    raw.lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("AGE-SECRET-KEY-"))
+        .map(str::to_string)
+        .ok_or_else(|| anyhow::anyhow!("No AGE-SECRET-KEY found in {}", path))
+}
+
+fn prompt_passphrase() -> anyhow::Result<String> {
+    eprint!("Passphrase: ");
+    std::io::stderr().flush()?;
+    let mut line = String::new();
+    std::io::stdin().read_line(&mut line)?;
+    let passphrase = line.trim_end_matches(['\r', '\n']).to_string();
+    if passphrase.is_empty() {
+        anyhow::bail!("Export is age-encrypted; pass --identity-file or --passphrase");
+    }
+    Ok(passphrase)
+}
+
+/// Decrypt armored age data; plain exports are returned unchanged
+fn decrypt_export_bytes(
+    bytes: Vec<u8>,
+    identity_file: Option<&str>,
+    passphrase: Option<&str>,
+) -> anyhow::Result<Vec<u8>> {
+    use mouchak_mail_core::model::export::{decrypt_with_identity, decrypt_with_passphrase};
+
+    let is_armored = String::from_utf8_lossy(&bytes)
+        .trim_start()
+        .starts_with(AGE_ARMOR_HEADER);
+    if !is_armored {
+        return Ok(bytes);
+    }
+
+    let decrypted = if let Some(path) = identity_file {
+        decrypt_with_identity(&bytes, &read_age_identity(path)?)?
+    } else {
+        let passphrase = match passphrase {
+            Some(p) => p.to_string(),
+            None => prompt_passphrase()?,
+        };
+        decrypt_with_passphrase(&bytes, &passphrase)?
+    };
+
+    // Server-side encrypted exports wrap the content together with its manifest
+    let bundled_content = serde_json::from_slice::<serde_json::Value>(&decrypted)
+        .ok()
+        .filter(|bundle| bundle.get("manifest").is_some())
+        .and_then(|bundle| bundle["content"].as_str().map(str::to_string));
+
+    Ok(bundled_content.map(String::into_bytes).unwrap_or(decrypted))
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn handle_export_create(
+    project: &str,
+    format: &str,
+    scrub: &str,
+    filter: mouchak_mail_core::model::export::ExportFilter,
+    output: Option<String>,
+    manifest_path: Option<String>,
+    signing_key: Option<String>,
+    recipients: &[String],
+    passphrase: Option<&str>,
+) -> anyhow::Result<()> {
+    use mouchak_mail_core::ctx::Ctx;
+    use mouchak_mail_core::model::ModelManager;
+    use mouchak_mail_core::model::export::{
+        ExportBmc, ExportFormat, ScrubMode, encrypt_with_age, encrypt_with_passphrase,
+        signing_key_from_base64,
+    };
+
+    let format: ExportFormat = format.parse().unwrap_or(ExportFormat::Json);
+    let scrub: ScrubMode = scrub.parse().unwrap_or_default();
+    let encrypted = !recipients.is_empty() || passphrase.is_some();
+    let signing_key = signing_key
+        .as_deref()
+        .map(signing_key_from_base64)
+        .transpose()?;
+
+    let config = load_config();
+    let mm = ModelManager::new(std::sync::Arc::new(config)).await?;
+    let ctx = Ctx::root_ctx();
+
+    let (exported, manifest) = ExportBmc::export_mailbox_signed(
+        &ctx,
+        &mm,
+        project,
+        format,
+        scrub,
+        false,
+        &filter,
+        signing_key.as_ref(),
+    )
+    .await?;
+
+    let output = output.unwrap_or_else(|| {
+        let ext = export_extension(&format);
+        if encrypted {
+            format!("{}-export.{}.age", project, ext)
+        } else {
+            format!("{}-export.{}", project, ext)
+        }
+    });
+    let manifest_path = manifest_path.unwrap_or_else(|| format!("{}.manifest.json", output));
+
+    let content = exported.content_bytes()?;
+    let bytes = if !recipients.is_empty() {
+        encrypt_with_age(&content, recipients)?
+    } else if let Some(p) = passphrase {
+        encrypt_with_passphrase(&content, p)?
+    } else {
+        content
+    };
+
+    std::fs::write(&output, bytes)?;
+    std::fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+
+    println!("✓ Exported {} message(s)", manifest.message_count);
+    println!("  Export: {}", output);
+    println!("  Manifest: {}", manifest_path);
+    if manifest.signature.is_some() {
+        println!(
+            "  Signed by: {}",
+            manifest.public_key.as_deref().unwrap_or("")
+        );
+    }
+    if encrypted {
+        println!("  Encrypted: age");
+    }
+
+    Ok(())
+}
+
+fn handle_export_sign(
+    export_path: &str,
+    manifest_path: &str,
+    signing_key: Option<String>,
+    identity_file: Option<&str>,
+    passphrase: Option<&str>,
+) -> anyhow::Result<()> {
+    use mouchak_mail_core::model::export::{ExportManifest, signing_key_from_base64};
+
+    let signing_key = signing_key
+        .ok_or_else(|| anyhow::anyhow!("A signing key is required (--key or --key-file)"))?;
+    let signing_key = signing_key_from_base64(&signing_key)?;
+
+    let mut manifest: ExportManifest =
+        serde_json::from_str(&std::fs::read_to_string(manifest_path)?)
+            .map_err(|e| anyhow::anyhow!("Malformed manifest {}: {}", manifest_path, e))?;
+
+    let bytes = decrypt_export_bytes(std::fs::read(export_path)?, identity_file, passphrase)?;
+    let content = manifest_content(&manifest, bytes)
+        .map_err(|_| anyhow::anyhow!("{} is not valid UTF-8", export_path))?;
+
+    if !manifest.matches_content(&content) {
+        anyhow::bail!(
+            "Refusing to sign: {} does not match the manifest content hash",
+            export_path
+        );
+    }
+
+    manifest.sign(&signing_key);
+    std::fs::write(manifest_path, serde_json::to_string_pretty(&manifest)?)?;
+
+    println!("✓ Manifest signed");
+    println!("  Manifest: {}", manifest_path);
+    println!(
+        "  Public key: {}",
+        manifest.public_key.as_deref().unwrap_or("")
+    );
+
+    Ok(())
+}
+
+/// The text a manifest's content hash covers for an export file.
+///
+/// `html-zip` exports are binary, so their hash is taken over the base64
+/// of the archive, as carried in the export itself.
+fn manifest_content(
+    manifest: &mouchak_mail_core::model::export::ExportManifest,
+    bytes: Vec<u8>,
+) -> Result<String, std::string::FromUtf8Error> {
+    use mouchak_mail_core::model::export::ExportFormat;
+
+    if manifest.format == ExportFormat::HtmlZip.as_str() {
+        Ok(base64::Engine::encode(
+            &base64::engine::general_purpose::STANDARD,
+            bytes,
+        ))
+    } else {
+        String::from_utf8(bytes)
+    }
+}
+
+fn handle_export_verify(
+    export_path: &str,
+    manifest_path: &str,
+    public_key: Option<&str>,
+    identity_file: Option<&str>,
+    passphrase: Option<&str>,
+) -> anyhow::Result<()> {
+    use mouchak_mail_core::model::export::ExportManifest;
+
+    let manifest_raw = std::fs::read_to_string(manifest_path)?;
+    let manifest: ExportManifest = match serde_json::from_str(&manifest_raw) {
+        Ok(m) => m,
+        Err(e) => export_fail(
+            EXPORT_EXIT_MALFORMED,
+            &format!("Malformed manifest {}: {}", manifest_path, e),
+        ),
+    };
+    if manifest.content_hash.is_empty() {
+        export_fail(
+            EXPORT_EXIT_MALFORMED,
+            &format!(
+                "Malformed manifest {}: content_hash is empty",
+                manifest_path
+            ),
+        );
+    }
+
+    let bytes = match decrypt_export_bytes(std::fs::read(export_path)?, identity_file, passphrase) {
+        Ok(b) => b,
+        Err(e) => export_fail(
+            EXPORT_EXIT_DECRYPT_FAILED,
+            &format!("Could not decrypt {}: {}", export_path, e),
+        ),
+    };
+    let content = manifest_content(&manifest, bytes)
+        .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned());
+
+    let actual_hash = ExportManifest::content_hash_of(&content);
+    if actual_hash != manifest.content_hash {
+        export_fail(
+            EXPORT_EXIT_TAMPERED,
+            &format!(
+                "Content TAMPERED: manifest records {} but {} hashes to {}",
+                manifest.content_hash, export_path, actual_hash
+            ),
+        );
+    }
+
+    let signature_ok = match (public_key, manifest.signature.is_some()) {
+        (None, false) => None,
+        (Some(_), false) => export_fail(
+            EXPORT_EXIT_BAD_SIGNATURE,
+            "Manifest is unsigned but --public-key was given",
+        ),
+        (Some(pk), true) => Some(manifest.verify_with_key(pk)),
+        (None, true) => Some(manifest.verify()),
+    };
+
+    match signature_ok {
+        None => {
+            println!("✓ Content hash matches (manifest is unsigned)");
+        }
+        Some(Ok(true)) => {
+            println!("✓ Signature VALID");
+        }
+        Some(Ok(false)) => {
+            let different_key = match (public_key, manifest.public_key.as_deref()) {
+                (Some(expected), Some(embedded)) => expected.trim() != embedded,
+                _ => false,
+            };
+            if different_key {
+                export_fail(
+                    EXPORT_EXIT_BAD_SIGNATURE,
+                    "Signature INVALID: manifest was signed by a different key",
+                );
+            }
+            export_fail(
+                EXPORT_EXIT_BAD_SIGNATURE,
+                "Signature INVALID: manifest metadata was modified after signing",
+            );
+        }
+        Some(Err(e)) => export_fail(
+            EXPORT_EXIT_MALFORMED,
+            &format!("Malformed signature data: {}", e),
+        ),
+    }
+
+    println!("  Project: {}", manifest.project_slug);
+    println!("  Exported: {}", manifest.exported_at);
+    println!("  Format: {}", manifest.format);
+    println!("  Messages: {}", manifest.message_count);
+    println!("  Content Hash: {}", manifest.content_hash);
+
+    Ok(())
+}
+
+async fn handle_export_import(
+    file: &str,
+    project: &str,
+    format: Option<&str>,
+    manifest_path: Option<&str>,
+    identity_file: Option<&str>,
+    passphrase: Option<&str>,
+) -> anyhow::Result<()> {
+    use mouchak_mail_core::ctx::Ctx;
+    use mouchak_mail_core::model::ModelManager;
+    use mouchak_mail_core::model::export::{ExportBmc, ExportFormat, ExportManifest};
+
+    let manifest: Option<ExportManifest> = manifest_path
+        .map(|path| {
+            serde_json::from_str(&std::fs::read_to_string(path)?)
+                .map_err(|e| anyhow::anyhow!("Malformed manifest {}: {}", path, e))
+        })
+        .transpose()?;
+
+    let bytes = decrypt_export_bytes(std::fs::read(file)?, identity_file, passphrase)?;
+    let content =
+        String::from_utf8(bytes).map_err(|_| anyhow::anyhow!("{} is not valid UTF-8", file))?;
+
+    let format = match (format, &manifest) {
+        (Some(f), _) => f.to_string(),
+        (None, Some(m)) => m.format.clone(),
+        (None, None) if file.trim_end_matches(".age").ends_with(".ndjson") => "ndjson".to_string(),
+        (None, None) => "json".to_string(),
+    };
+    let format: ExportFormat = format.parse().unwrap_or(ExportFormat::Json);
+
+    let config = load_config();
+    let mm = ModelManager::new(std::sync::Arc::new(config)).await?;
+    let ctx = Ctx::root_ctx();
+
+    let report =
+        ExportBmc::import_mailbox(&ctx, &mm, project, &content, format, manifest.as_ref()).await?;
+
+    println!(
+        "✓ Imported {} message(s) into {}",
+        report.imported, report.project_slug
+    );
+    println!("  Skipped (already present): {}", report.skipped);
+    println!("  Failed: {}", report.failed);
+    if !report.agents_created.is_empty() {
+        println!("  Agents created: {}", report.agents_created.join(", "));
+    }
+    if report.manifest_verified == Some(true) {
+        println!("  Manifest: verified");
+    }
+    for error in &report.errors {
+        eprintln!("  ✗ {}", error);
+    }
+
+    Ok(())
+}
+
+async fn handle_export(args: ExportArgs) -> anyhow::Result<()> {
+    use mouchak_mail_core::model::export::ExportFilter;
+
+    match args.command {
+        MailboxExportCommands::Create {
+            project,
+            format,
+            scrub,
+            since,
+            until,
+            agent,
+            thread,
+            output,
+            manifest,
+            key,
+            key_file,
+            recipients,
+            passphrase,
+        } => {
+            let filter = ExportFilter {
+                since: since
+                    .as_deref()
+                    .map(|s| ExportFilter::parse_date_bound(s, false))
+                    .transpose()?,
+                until: until
+                    .as_deref()
+                    .map(|s| ExportFilter::parse_date_bound(s, true))
+                    .transpose()?,
+                agent_name: agent,
+                thread_id: thread,
+                importance: None,
+            };
+            let signing_key = read_signing_key(key.as_deref(), key_file.as_deref())?;
+            handle_export_create(
+                &project,
+                &format,
+                &scrub,
+                filter,
+                output,
+                manifest,
+                signing_key,
+                &recipients,
+                passphrase.as_deref(),
+            )
+            .await
+        }
+        MailboxExportCommands::Sign {
+            export,
+            manifest,
+            key,
+            key_file,
+            identity_file,
+            passphrase,
+        } => {
+            let signing_key = read_signing_key(key.as_deref(), key_file.as_deref())?;
+            handle_export_sign(
+                &export,
+                &manifest,
+                signing_key,
+                identity_file.as_deref(),
+                passphrase.as_deref(),
+            )
+        }
+        MailboxExportCommands::Verify {
+            export,
+            manifest,
+            public_key,
+            identity_file,
+            passphrase,
+        } => handle_export_verify(
+            &export,
+            &manifest,
+            public_key.as_deref(),
+            identity_file.as_deref(),
+            passphrase.as_deref(),
+        ),
+        MailboxExportCommands::Import {
+            file,
+            project,
+            format,
+            manifest,
+            identity_file,
+            passphrase,
+        } => {
+            handle_export_import(
+                &file,
+                &project,
+                format.as_deref(),
+                manifest.as_deref(),
+                identity_file.as_deref(),
+                passphrase.as_deref(),
+            )
+            .await
+        }
+    }
+}
+
 // --- Export Command Handlers ---
 
 /// Export data as JSON files for static GitHub Pages deployment
@@ -2001,6 +2769,39 @@ fn handle_config_command(cmd: ConfigCommands) -> anyhow::Result<()> {
             let config = load_config();
             println!("{}", config.server.port);
         }
+        ConfigCommands::Check { file } => {
+            let report = AppConfig::check(file.as_deref());
+            if report.files.is_empty() {
+                println!("Config files: none (defaults and environment only)");
+            } else {
+                println!("Config files:");
+                for path in &report.files {
+                    println!("  {}", path.display());
+                }
+            }
+
+            println!("\nEffective values:");
+            let width = report.values.iter().map(|v| v.key.len()).max().unwrap_or(0);
+            for value in &report.values {
+                println!(
+                    "  {:width$} = {}  [{}]",
+                    value.key,
+                    value.value,
+                    value.source,
+                    width = width
+                );
+            }
+
+            if report.is_ok() {
+                println!("\n✓ Configuration is valid");
+            } else {
+                eprintln!("\n✗ {} problem(s) found:", report.issues.len());
+                for issue in &report.issues {
+                    eprintln!("  {}", issue);
+                }
+                std::process::exit(1);
+            }
+        }
     }
     Ok(())
 }
@@ -2445,7 +3246,14 @@ async fn main() -> anyhow::Result<()> {
     }
 
     setup_tracing(cli.log_format == "json")?;
-    let config = load_config();
+    let config = if starts_server(cli.command.as_ref()) {
+        AppConfig::check(None).for_serve()?
+    } else if matches!(cli.command, Some(Commands::Config(_))) {
+        // `config check` reports the problems itself
+        AppConfig::check(None).config
+    } else {
+        load_config()
+    };
 
     match cli.command {
         Some(Commands::Serve(args)) => match args.command {
@@ -2454,16 +3262,40 @@ async fn main() -> anyhow::Result<()> {
                 with_ui,
                 no_ui,
             } => handle_serve_http(port, with_ui, no_ui, config).await?,
-            ServeCommands::Mcp { transport, port } => {
-                handle_serve_mcp(transport, port, config).await?
-            }
+            ServeCommands::Mcp {
+                transport,
+                port,
+                host,
+                worktrees,
+            } => handle_serve_mcp(transport, port, host, worktrees, config).await?,
         },
-        Some(Commands::Health { url }) => handle_health(url).await?,
+        Some(Commands::Doctor { port, output }) => {
+            if let Err(e) = handle_doctor(port, output, config).await {
+                output.exit_with_error(&e);
+            }
+        }
+        Some(Commands::Health { url, output }) => {
+            if let Err(e) = handle_health(url, output).await {
+                output.exit_with_error(&e);
+            }
+        }
         Some(Commands::Config(args)) => handle_config_command(args.command)?,
-        Some(Commands::Schema { format, output }) => handle_schema(format, output)?,
-        Some(Commands::Tools) => handle_tools(),
+        Some(Commands::Schema {
+            format,
+            output,
+            worktrees,
+        }) => handle_schema(format, output, worktrees)?,
+        Some(Commands::Tools { worktrees, output }) => {
+            if let Err(e) = handle_tools(worktrees, output) {
+                output.exit_with_error(&e);
+            }
+        }
         Some(Commands::Install(args)) => match args.command {
-            InstallCommands::Alias { force } => handle_install_alias(force)?,
+            InstallCommands::Alias {
+                force,
+                uninstall,
+                shell,
+            } => shell_alias::handle_install_alias(force, uninstall, shell)?,
         },
         Some(Commands::Service(args)) => match args.command {
             ServiceCommands::Start { port, background } => {
@@ -2518,11 +3350,25 @@ async fn main() -> anyhow::Result<()> {
                 } => handle_export_static_data(&output, &scrub, limit, project.as_deref()).await?,
             },
         },
+        Some(Commands::Export(args)) => handle_export(args).await?,
         Some(Commands::Archive(args)) => handle_archive_command(args.command).await?,
+        Some(Commands::Backup(args)) => handle_backup(args, config).await?,
         Some(Commands::Summarize(args)) => handle_summarize(args).await?,
         Some(Commands::Products(args)) => handle_products(args).await?,
+        Some(Commands::Agents(args)) => handle_agents(args).await?,
+        Some(Commands::Hooks(args)) => handle_hooks(args, &config).await?,
         Some(Commands::Guard(args)) => handle_guard(args).await?,
         Some(Commands::Mail(args)) => handle_mail(args).await?,
+        Some(Commands::Inbox(args)) => {
+            let mm = mouchak_mail_core::ModelManager::new(std::sync::Arc::new(config)).await?;
+            inbox_tui::run(
+                mm,
+                &args.project,
+                &args.agent,
+                std::time::Duration::from_secs(args.refresh.max(1)),
+            )
+            .await?
+        }
         Some(Commands::Version) => println!("mouchak-mail v{}", env!("CARGO_PKG_VERSION")),
         None => {
             Cli::command().print_help()?;
@@ -2979,7 +3825,7 @@ async fn handle_products(args: ProductsArgs) -> anyhow::Result<()> {
             }
 
             // Sort by date desc
-            all_matches.sort_by(|a, b| b.created_ts.cmp(&a.created_ts));
+            all_matches.sort_by_key(|m| std::cmp::Reverse(m.created_ts));
             all_matches.truncate(limit as usize);
 
             println!(
@@ -3040,7 +3886,7 @@ async fn handle_products(args: ProductsArgs) -> anyhow::Result<()> {
                 .await
                 {
                     if let Some(agent_obj) = agents.into_iter().find(|a| a.name == agent) {
-                        if let Ok(messages) =
+                        if let Ok(mut messages) =
                             mouchak_mail_core::model::message::MessageBmc::list_inbox_for_agent(
                                 &ctx,
                                 &mm,
@@ -3050,6 +3896,13 @@ async fn handle_products(args: ProductsArgs) -> anyhow::Result<()> {
                             )
                             .await
                         {
+                            if include_bodies {
+                                mouchak_mail_core::model::message::MessageBmc::load_bodies(
+                                    &mm,
+                                    &mut messages,
+                                )
+                                .await?;
+                            }
                             for msg in messages {
                                 if urgent_only
                                     && msg.importance != "high"
@@ -3118,7 +3971,7 @@ async fn handle_products(args: ProductsArgs) -> anyhow::Result<()> {
                     thread_id, product.name
                 );
             } else {
-                all_messages.sort_by(|a, b| a.created_ts.cmp(&b.created_ts));
+                all_messages.sort_by_key(|m| m.created_ts);
                 all_messages.truncate(per_thread_limit as usize);
 
                 let mut participants: Vec<String> =
@@ -3252,6 +4105,121 @@ async fn handle_mail_status() -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn handle_agents(args: AgentsArgs) -> anyhow::Result<()> {
+    use mouchak_mail_core::ctx::Ctx;
+    use mouchak_mail_core::model::ModelManager;
+    use mouchak_mail_core::model::agent::AgentBmc;
+    use mouchak_mail_core::model::project::ProjectBmc;
+
+    let config = load_config();
+    let mm = ModelManager::new(std::sync::Arc::new(config)).await?;
+    let ctx = Ctx::root_ctx();
+
+    match args.command {
+        AgentsCommands::IssueToken { project, name } => {
+            let project = ProjectBmc::get_by_identifier(&ctx, &mm, &project).await?;
+            let agent = AgentBmc::get_by_name(&ctx, &mm, project.id, &name).await?;
+            let token = AgentBmc::issue_token(&ctx, &mm, agent.id).await?;
+            eprintln!(
+                "Issued API key for {} in {}; it is shown only once.",
+                agent.name, project.slug
+            );
+            eprintln!("Send it as: Authorization: AgentKey <token>");
+            println!("{}", token);
+        }
+        AgentsCommands::RevokeToken { project, name } => {
+            let project = ProjectBmc::get_by_identifier(&ctx, &mm, &project).await?;
+            let agent = AgentBmc::get_by_name(&ctx, &mm, project.id, &name).await?;
+            let revoked = AgentBmc::revoke_token(&ctx, &mm, agent.id).await?;
+            println!("Revoked {} API key(s) for {}", revoked, agent.name);
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_hooks(args: HooksArgs, config: &AppConfig) -> anyhow::Result<()> {
+    use mouchak_mail_server::hooks::{HookOutcome, HookRunner, synthetic_event};
+
+    match args.command {
+        HooksCommands::Test {
+            event,
+            project,
+            importance,
+        } => {
+            let runner = HookRunner::new(&config.hooks);
+            let event_name = event.as_str();
+            let event = synthetic_event(event, &project, &importance);
+            if runner.matching(&event).next().is_none() {
+                println!(
+                    "No hooks run for {} in {} at {} importance",
+                    event_name, project, importance
+                );
+                return Ok(());
+            }
+
+            let runs = runner.fire(&event).await;
+            let mut failed = 0;
+            for run in &runs {
+                match &run.outcome {
+                    HookOutcome::Succeeded => println!("ok       {}", run.command),
+                    HookOutcome::Failed(reason) => {
+                        failed += 1;
+                        println!("failed   {} ({})", run.command, reason);
+                    }
+                    HookOutcome::TimedOut => {
+                        failed += 1;
+                        println!("timeout  {}", run.command);
+                    }
+                }
+            }
+            if failed > 0 {
+                anyhow::bail!("{} of {} hooks failed", failed, runs.len());
+            }
+        }
+    }
+
+    Ok(())
+}
+
+async fn handle_backup(args: BackupArgs, config: AppConfig) -> anyhow::Result<()> {
+    use mouchak_mail_core::ctx::Ctx;
+    use mouchak_mail_core::model::ModelManager;
+    use mouchak_mail_core::model::backup::BackupBmc;
+
+    let mm = ModelManager::new(std::sync::Arc::new(config)).await?;
+    let ctx = Ctx::root_ctx();
+
+    match args.command {
+        BackupCommands::Now { output } => {
+            let backup = match output {
+                Some(path) => BackupBmc::create_at(&ctx, &mm, &path).await?,
+                None => BackupBmc::create(&ctx, &mm).await?,
+            };
+            println!(
+                "✓ Backup written to {} ({} bytes)",
+                backup.path, backup.size_bytes
+            );
+        }
+        BackupCommands::List => {
+            let backups = BackupBmc::list(&ctx, &mm)?;
+            if backups.is_empty() {
+                println!("No backups in {}", BackupBmc::directory(&mm)?.display());
+            }
+            for backup in backups {
+                println!(
+                    "{}  {:>12} bytes  {}",
+                    backup.created_ts.format("%Y-%m-%d %H:%M:%S"),
+                    backup.size_bytes,
+                    backup.path
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
 async fn handle_mail(args: MailArgs) -> anyhow::Result<()> {
     match args.command {
         MailCommands::Status => handle_mail_status().await,
@@ -3337,6 +4305,49 @@ mod guard_pattern_tests {
 // Tests for robot-* flag handlers (TDD - mouchak-mail-rs-vgs4)
 // =============================================================================
 
+#[cfg(test)]
+mod health_report_tests {
+    use super::format_health_report;
+
+    #[test]
+    fn test_healthy_report() {
+        let report = serde_json::json!({
+            "status": "healthy",
+            "version": "0.2.7",
+            "uptime_seconds": 42,
+            "components": {
+                "database": { "ok": true, "latency_ms": 1 },
+                "disk": { "ok": true, "free_bytes": 2_097_152u64, "total_bytes": 4_194_304u64 },
+                "git_archive": { "ok": true, "latency_ms": 0 }
+            }
+        });
+
+        let (table, healthy) = format_health_report(&report);
+        assert!(healthy);
+        assert!(table.contains("Uptime: 42s"));
+        assert!(table.contains("2 MB free of 4 MB"));
+        assert!(!table.contains("DEGRADED"));
+    }
+
+    #[test]
+    fn test_degraded_component_fails() {
+        let report = serde_json::json!({
+            "status": "degraded",
+            "version": "0.2.7",
+            "uptime_seconds": 1,
+            "components": {
+                "database": { "ok": true, "latency_ms": 1 },
+                "git_archive": { "ok": false, "error": "archive root /data is missing" }
+            }
+        });
+
+        let (table, healthy) = format_health_report(&report);
+        assert!(!healthy);
+        assert!(table.contains("DEGRADED"));
+        assert!(table.contains("archive root /data is missing"));
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod robot_handler_tests {
@@ -3524,12 +4535,15 @@ mod robot_handler_tests {
             "install",
             "service",
             "share",
+            "export",
             "archive",
             "summarize",
             "version",
             "products",
+            "agents",
             "guard",
             "mail",
+            "inbox",
         ];
 
         for cmd in core_commands {
@@ -197,6 +197,31 @@ pub(crate) static EXAMPLE_REGISTRY: Lazy<HashMap<&'static str, ExampleEntry>> =
                     "For Claude Desktop",
                 ),
                 example("mouchak-mail serve mcp --transport sse", "For web clients"),
+                example(
+                    "mouchak-mail serve mcp --transport sse --host 0.0.0.0",
+                    "Listen on all interfaces (e.g. behind a reverse proxy)",
+                ),
+            ],
+        },
+    );
+
+    m.insert(
+        "doctor",
+        ExampleEntry {
+            description: "Check the local environment before serving",
+            target_type: "subcommand",
+            param_type: None,
+            default: None,
+            examples: vec![
+                example("mouchak-mail doctor", "Run every check and print the table"),
+                example(
+                    "mouchak-mail doctor --port 9000",
+                    "Check another port instead of the configured one",
+                ),
+                example(
+                    "mouchak-mail doctor --output json",
+                    "Print the checks as one JSON object",
+                ),
             ],
         },
     );
@@ -214,6 +239,10 @@ pub(crate) static EXAMPLE_REGISTRY: Lazy<HashMap<&'static str, ExampleEntry>> =
                     "mouchak-mail health --url http://prod.example.com",
                     "Check remote server",
                 ),
+                example(
+                    "mouchak-mail health --output json",
+                    "Print the health report as one JSON object",
+                ),
             ],
         },
     );
@@ -263,7 +292,7 @@ pub(crate) static EXAMPLE_REGISTRY: Lazy<HashMap<&'static str, ExampleEntry>> =
     m.insert(
         "schema",
         ExampleEntry {
-            description: "Export JSON schemas for all tools",
+            description: "Export JSON schemas for all tools or the HTTP API",
             target_type: "subcommand",
             param_type: None,
             default: None,
@@ -273,6 +302,10 @@ pub(crate) static EXAMPLE_REGISTRY: Lazy<HashMap<&'static str, ExampleEntry>> =
                     "mouchak-mail schema --format markdown --output docs/tools.md",
                     "Generate markdown docs",
                 ),
+                example(
+                    "mouchak-mail schema --format openapi --output openapi.json",
+                    "Write the HTTP API's OpenAPI document",
+                ),
             ],
         },
     );
@@ -280,11 +313,21 @@ pub(crate) static EXAMPLE_REGISTRY: Lazy<HashMap<&'static str, ExampleEntry>> =
     m.insert(
         "tools",
         ExampleEntry {
-            description: "List all available MCP tools",
+            description: "List all MCP tools, and which are disabled and why",
             target_type: "subcommand",
             param_type: None,
             default: None,
-            examples: vec![example("mouchak-mail tools", "List all 45 MCP tools")],
+            examples: vec![
+                example("mouchak-mail tools", "List all 45 MCP tools"),
+                example(
+                    "mouchak-mail tools --worktrees",
+                    "Enable the worktree build slot tools",
+                ),
+                example(
+                    "AM_OUTPUT=json mouchak-mail tools",
+                    "List tools as JSON for scripting",
+                ),
+            ],
         },
     );
 
@@ -309,10 +352,17 @@ pub(crate) static EXAMPLE_REGISTRY: Lazy<HashMap<&'static str, ExampleEntry>> =
             target_type: "subcommand",
             param_type: None,
             default: None,
-            examples: vec![example(
-                "mouchak-mail install alias",
-                "Create 'am' command alias",
-            )],
+            examples: vec![
+                example("mouchak-mail install alias", "Create 'am' command alias"),
+                example(
+                    "mouchak-mail install alias --shell powershell",
+                    "Add 'am' to the PowerShell profile",
+                ),
+                example(
+                    "mouchak-mail install alias --uninstall",
+                    "Remove the 'am' alias from every shell",
+                ),
+            ],
         },
     );
 
@@ -651,6 +701,46 @@ pub(crate) static EXAMPLE_REGISTRY: Lazy<HashMap<&'static str, ExampleEntry>> =
         },
     );
 
+    m.insert(
+        "agents",
+        ExampleEntry {
+            description: "Per-agent API keys",
+            target_type: "subcommand",
+            param_type: None,
+            default: None,
+            examples: vec![
+                example(
+                    "mouchak-mail agents issue-token my-repo BlueLake",
+                    "Issue a key; requests sending 'Authorization: AgentKey <key>' act as BlueLake only",
+                ),
+                example(
+                    "mouchak-mail agents revoke-token my-repo BlueLake",
+                    "Revoke all of BlueLake's keys",
+                ),
+            ],
+        },
+    );
+
+    m.insert(
+        "hooks test",
+        ExampleEntry {
+            description: "Fire a made-up event through the configured [[hooks.on]] commands",
+            target_type: "subcommand",
+            param_type: None,
+            default: None,
+            examples: vec![
+                example(
+                    "mouchak-mail hooks test message.created --importance urgent",
+                    "Run the hooks an urgent message would trigger and report each outcome",
+                ),
+                example(
+                    "mouchak-mail hooks test reservation.conflict --project my-repo",
+                    "Run the conflict hooks as if my-repo had a clashing reservation",
+                ),
+            ],
+        },
+    );
+
     m.insert(
         "products ensure",
         ExampleEntry {
@@ -811,6 +901,26 @@ pub(crate) static EXAMPLE_REGISTRY: Lazy<HashMap<&'static str, ExampleEntry>> =
         },
     );
 
+    m.insert(
+        "inbox",
+        ExampleEntry {
+            description: "Read and reply to an agent's inbox in the terminal",
+            target_type: "subcommand",
+            param_type: None,
+            default: None,
+            examples: vec![
+                example(
+                    "mouchak-mail inbox --project my-repo --agent BlueLake",
+                    "Open the interactive inbox",
+                ),
+                example(
+                    "mouchak-mail inbox -p my-repo -a BlueLake | grep '^\\*'",
+                    "Print unread messages (plain list when piped)",
+                ),
+            ],
+        },
+    );
+
     m.insert(
         "mail status",
         ExampleEntry {
@@ -822,5 +932,93 @@ pub(crate) static EXAMPLE_REGISTRY: Lazy<HashMap<&'static str, ExampleEntry>> =
         },
     );
 
+    m.insert(
+        "export",
+        ExampleEntry {
+            description: "Create, sign, verify, and import mailbox exports",
+            target_type: "subcommand",
+            param_type: None,
+            default: None,
+            examples: vec![
+                example(
+                    "mouchak-mail export create --project my-project --key-file keys.json",
+                    "Export and sign a mailbox",
+                ),
+                example(
+                    "mouchak-mail export verify export.json export.json.manifest.json",
+                    "Verify an export",
+                ),
+            ],
+        },
+    );
+
+    m.insert(
+        "export create",
+        ExampleEntry {
+            description: "Export a mailbox with a manifest",
+            target_type: "subcommand",
+            param_type: None,
+            default: None,
+            examples: vec![
+                example(
+                    "mouchak-mail export create --project my-project --format ndjson",
+                    "Plain NDJSON export",
+                ),
+                example(
+                    "mouchak-mail export create --project my-project --recipient age1...",
+                    "Encrypt for an age recipient",
+                ),
+            ],
+        },
+    );
+
+    m.insert(
+        "export sign",
+        ExampleEntry {
+            description: "Sign an existing export manifest",
+            target_type: "subcommand",
+            param_type: None,
+            default: None,
+            examples: vec![example(
+                "mouchak-mail export sign export.json export.json.manifest.json --key-file keys.json",
+                "Sign with a keypair file",
+            )],
+        },
+    );
+
+    m.insert(
+        "export verify",
+        ExampleEntry {
+            description: "Verify an export against its manifest",
+            target_type: "subcommand",
+            param_type: None,
+            default: None,
+            examples: vec![
+                example(
+                    "mouchak-mail export verify export.json export.json.manifest.json --public-key BASE64",
+                    "Verify against a known signer",
+                ),
+                example(
+                    "mouchak-mail export verify export.json.age manifest.json --identity-file key.txt",
+                    "Verify an encrypted export",
+                ),
+            ],
+        },
+    );
+
+    m.insert(
+        "export import",
+        ExampleEntry {
+            description: "Import messages from a JSON or NDJSON export",
+            target_type: "subcommand",
+            param_type: None,
+            default: None,
+            examples: vec![example(
+                "mouchak-mail export import export.json --project my-project --manifest export.json.manifest.json",
+                "Verify and restore an export",
+            )],
+        },
+    );
+
     m
 });
@@ -0,0 +1,509 @@
+//! The `am` shell alias
+//!
+//! `install alias` adds an `am` command that starts the HTTP server to the
+//! user's shell startup file, between marker comments so `--force` can
+//! replace it and `--uninstall` can remove it without touching the rest of
+//! the file. POSIX shells, fish and PowerShell get a block in their startup
+//! file. cmd has no startup file, so it gets a doskey macro file of its own
+//! and the user is told how to load it.
+
+use clap::ValueEnum;
+use std::path::{Path, PathBuf};
+
+const BEGIN_MARKER: &str = "# >>> Mouchak Mail alias";
+const END_MARKER: &str = "# <<< Mouchak Mail alias";
+
+/// Shells `install alias` knows how to configure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ShellKind {
+    Zsh,
+    Bash,
+    Fish,
+    /// Any other POSIX shell, configured through `~/.profile`
+    Sh,
+    #[value(name = "powershell")]
+    PowerShell,
+    /// cmd.exe, through a doskey macro file
+    Cmd,
+}
+
+impl ShellKind {
+    const ALL: [ShellKind; 6] = [
+        ShellKind::Zsh,
+        ShellKind::Bash,
+        ShellKind::Fish,
+        ShellKind::Sh,
+        ShellKind::PowerShell,
+        ShellKind::Cmd,
+    ];
+
+    /// The user's shell, from `SHELL` first, then PowerShell markers, then
+    /// whichever startup file exists.
+    ///
+    /// `SHELL` wins so Git Bash and WSL on Windows count as bash. Without it,
+    /// Windows (or `PSModulePath`/`PROFILE`, set by PowerShell elsewhere)
+    /// means PowerShell.
+    pub(crate) fn detect(env: &dyn Fn(&str) -> Option<String>, home: &Path) -> Self {
+        if let Some(shell) = env("SHELL") {
+            let shell = shell.trim_end_matches(".exe");
+            if shell.ends_with("zsh") {
+                return ShellKind::Zsh;
+            } else if shell.ends_with("bash") {
+                return ShellKind::Bash;
+            } else if shell.ends_with("fish") {
+                return ShellKind::Fish;
+            } else if shell.ends_with("pwsh") {
+                return ShellKind::PowerShell;
+            }
+        }
+
+        if cfg!(windows) || env("PSModulePath").is_some() || env("PROFILE").is_some() {
+            return ShellKind::PowerShell;
+        }
+
+        if home.join(".zshrc").exists() {
+            ShellKind::Zsh
+        } else if home.join(".bashrc").exists() {
+            ShellKind::Bash
+        } else {
+            ShellKind::Sh
+        }
+    }
+
+    /// File the alias is written to.
+    ///
+    /// For PowerShell this is `PROFILE` when set, else the current-user
+    /// profile of PowerShell 7.
+    pub(crate) fn rc_path(self, env: &dyn Fn(&str) -> Option<String>, home: &Path) -> PathBuf {
+        match self {
+            ShellKind::Zsh => home.join(".zshrc"),
+            ShellKind::Bash => home.join(".bashrc"),
+            ShellKind::Fish => home.join(".config").join("fish").join("config.fish"),
+            ShellKind::Sh => home.join(".profile"),
+            ShellKind::PowerShell => match env("PROFILE") {
+                Some(profile) if !profile.is_empty() => PathBuf::from(profile),
+                _ if cfg!(windows) => home
+                    .join("Documents")
+                    .join("PowerShell")
+                    .join("Microsoft.PowerShell_profile.ps1"),
+                _ => home
+                    .join(".config")
+                    .join("powershell")
+                    .join("Microsoft.PowerShell_profile.ps1"),
+            },
+            ShellKind::Cmd => home.join(".mouchak-mail").join("am.doskey"),
+        }
+    }
+
+    /// Definition of `am` in this shell's syntax.
+    fn definition(self) -> &'static str {
+        match self {
+            ShellKind::Zsh | ShellKind::Bash | ShellKind::Sh => {
+                "alias am='mouchak-mail serve http'"
+            }
+            ShellKind::Fish => "function am\n    mouchak-mail serve http\nend",
+            ShellKind::PowerShell => "function am { mouchak-mail serve http @args }",
+            ShellKind::Cmd => "am=mouchak-mail serve http $*",
+        }
+    }
+
+    /// Text appended to the startup file.
+    ///
+    /// doskey reads every line of a macro file as a macro, so cmd's file
+    /// holds the bare definition and is owned by us as a whole.
+    pub(crate) fn snippet(self) -> String {
+        match self {
+            ShellKind::Cmd => format!("{}\n", self.definition()),
+            _ => format!("\n{BEGIN_MARKER}\n{}\n{END_MARKER}\n", self.definition()),
+        }
+    }
+
+    /// Whether `line` defines an `am` we did not write.
+    fn defines_am(self, line: &str) -> bool {
+        let line = line.trim();
+        match self {
+            ShellKind::Zsh | ShellKind::Bash | ShellKind::Sh => line.starts_with("alias am="),
+            ShellKind::Fish => line.starts_with("alias am ") || line == "function am",
+            ShellKind::PowerShell => {
+                let lower = line.to_ascii_lowercase();
+                lower.starts_with("function am ")
+                    || lower.starts_with("function am{")
+                    || lower.starts_with("set-alias am ")
+                    || lower.starts_with("new-alias am ")
+            }
+            ShellKind::Cmd => line.starts_with("am="),
+        }
+    }
+
+    /// How to start using the alias without a new terminal.
+    fn activation_hint(self, rc_path: &Path) -> Vec<String> {
+        let path = rc_path.display();
+        match self {
+            ShellKind::PowerShell => vec![
+                "To use the alias now, run:".to_string(),
+                format!("  . \"{path}\""),
+            ],
+            ShellKind::Cmd => vec![
+                "To use the alias in this cmd window, run:".to_string(),
+                format!("  doskey /macrofile=\"{path}\""),
+                "To load it in every cmd window without editing the registry,".to_string(),
+                "start cmd from a shortcut whose target is:".to_string(),
+                format!("  cmd.exe /k doskey /macrofile=\"{path}\""),
+            ],
+            _ => vec![
+                "To use the alias now, run:".to_string(),
+                format!("  source {path}"),
+            ],
+        }
+    }
+}
+
+/// What `install` did.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum InstallOutcome {
+    Installed,
+    /// Replaced our block (`--force`)
+    Updated,
+    AlreadyInstalled,
+    /// Another `am` exists and `--force` was not given
+    Conflict,
+}
+
+/// Whether `contents` has our marker block.
+fn has_block(contents: &str) -> bool {
+    contents.contains(BEGIN_MARKER)
+}
+
+/// `contents` without our marker block.
+fn remove_block(contents: &str) -> String {
+    let mut kept = String::new();
+    let mut in_block = false;
+    for line in contents.lines() {
+        if line.contains(BEGIN_MARKER) {
+            // Drop the blank line `snippet` puts before the block
+            if kept == "\n" || kept.ends_with("\n\n") {
+                kept.pop();
+            }
+            in_block = true;
+            continue;
+        }
+        if line.contains(END_MARKER) {
+            in_block = false;
+            continue;
+        }
+        if !in_block {
+            kept.push_str(line);
+            kept.push('\n');
+        }
+    }
+    kept
+}
+
+/// Write the alias for `kind` to `rc_path`, creating the file and its
+/// directory if missing.
+pub(crate) fn install(
+    kind: ShellKind,
+    rc_path: &Path,
+    force: bool,
+) -> std::io::Result<InstallOutcome> {
+    let contents = match std::fs::read_to_string(rc_path) {
+        Ok(contents) => Some(contents),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+
+    let installed = match (&contents, kind) {
+        (Some(_), ShellKind::Cmd) => true,
+        (Some(contents), _) => has_block(contents),
+        (None, _) => false,
+    };
+    if installed && !force {
+        return Ok(InstallOutcome::AlreadyInstalled);
+    }
+
+    let existing = match (contents, kind) {
+        (_, ShellKind::Cmd) | (None, _) => String::new(),
+        (Some(contents), _) => remove_block(&contents),
+    };
+    if !installed && !force && existing.lines().any(|line| kind.defines_am(line)) {
+        return Ok(InstallOutcome::Conflict);
+    }
+
+    if let Some(parent) = rc_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(rc_path, existing + &kind.snippet())?;
+
+    Ok(if installed {
+        InstallOutcome::Updated
+    } else {
+        InstallOutcome::Installed
+    })
+}
+
+/// Remove the alias for `kind` from `rc_path`. Returns whether there was one.
+pub(crate) fn uninstall(kind: ShellKind, rc_path: &Path) -> std::io::Result<bool> {
+    let contents = match std::fs::read_to_string(rc_path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e),
+    };
+
+    if kind == ShellKind::Cmd {
+        std::fs::remove_file(rc_path)?;
+        return Ok(true);
+    }
+    if !has_block(&contents) {
+        return Ok(false);
+    }
+    std::fs::write(rc_path, remove_block(&contents))?;
+    Ok(true)
+}
+
+/// The user's home directory: `HOME`, or `USERPROFILE` on Windows.
+fn home_dir(env: &dyn Fn(&str) -> Option<String>) -> Option<PathBuf> {
+    env("HOME")
+        .or_else(|| env("USERPROFILE"))
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
+
+fn process_env(name: &str) -> Option<String> {
+    std::env::var(name).ok()
+}
+
+/// Handle `install alias`.
+pub(crate) fn handle_install_alias(
+    force: bool,
+    uninstall_alias: bool,
+    shell: Option<ShellKind>,
+) -> anyhow::Result<()> {
+    let home = home_dir(&process_env).ok_or_else(|| {
+        anyhow::anyhow!("Could not find your home directory. Set HOME (or USERPROFILE on Windows).")
+    })?;
+
+    if uninstall_alias {
+        // Remove from every shell we know, in case the user switched since
+        let kinds = shell.map_or(ShellKind::ALL.to_vec(), |kind| vec![kind]);
+        let mut removed = false;
+        for kind in kinds {
+            let rc_path = kind.rc_path(&process_env, &home);
+            if uninstall(kind, &rc_path)? {
+                println!("✓ Removed 'am' alias from {}", rc_path.display());
+                removed = true;
+            }
+        }
+        if !removed {
+            println!("No 'am' alias installed by Mouchak Mail was found.");
+        }
+        return Ok(());
+    }
+
+    let kind = shell.unwrap_or_else(|| ShellKind::detect(&process_env, &home));
+    let rc_path = kind.rc_path(&process_env, &home);
+    println!("Detected shell config: {}", rc_path.display());
+
+    match install(kind, &rc_path, force)? {
+        InstallOutcome::AlreadyInstalled => {
+            println!("✓ 'am' alias already installed in {}", rc_path.display());
+            println!("  Use --force to update the alias.");
+            return Ok(());
+        }
+        InstallOutcome::Conflict => {
+            println!(
+                "⚠ An existing 'am' alias was found in {}",
+                rc_path.display()
+            );
+            println!("  Use --force to overwrite it.");
+            return Ok(());
+        }
+        InstallOutcome::Updated => {
+            println!("✓ Updated 'am' alias in {}", rc_path.display());
+        }
+        InstallOutcome::Installed => {
+            println!("✓ Added 'am' alias to {}", rc_path.display());
+        }
+    }
+
+    println!();
+    for line in kind.activation_hint(&rc_path) {
+        println!("{line}");
+    }
+    println!();
+    println!("Or open a new terminal, then run:");
+    println!("  am");
+    println!();
+    println!("This starts the HTTP server on port 8765.");
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn env_of(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> + use<> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn test_detect_from_shell_variable() {
+        let home = tempfile::tempdir().unwrap();
+        for (shell, kind) in [
+            ("/bin/zsh", ShellKind::Zsh),
+            ("/usr/bin/bash", ShellKind::Bash),
+            ("C:\\Program Files\\Git\\bin\\bash.exe", ShellKind::Bash),
+            ("/usr/local/bin/fish", ShellKind::Fish),
+            ("/usr/bin/pwsh", ShellKind::PowerShell),
+        ] {
+            let env = env_of(&[("SHELL", shell)]);
+            assert_eq!(ShellKind::detect(&env, home.path()), kind, "{shell}");
+        }
+    }
+
+    #[test]
+    fn test_detect_powershell_without_shell() {
+        let home = tempfile::tempdir().unwrap();
+        let env = env_of(&[("PSModulePath", "C:\\Modules")]);
+        assert_eq!(ShellKind::detect(&env, home.path()), ShellKind::PowerShell);
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_detect_falls_back_to_rc_files() {
+        let home = tempfile::tempdir().unwrap();
+        let env = env_of(&[]);
+        assert_eq!(ShellKind::detect(&env, home.path()), ShellKind::Sh);
+
+        std::fs::write(home.path().join(".bashrc"), "").unwrap();
+        assert_eq!(ShellKind::detect(&env, home.path()), ShellKind::Bash);
+
+        std::fs::write(home.path().join(".zshrc"), "").unwrap();
+        assert_eq!(ShellKind::detect(&env, home.path()), ShellKind::Zsh);
+    }
+
+    #[test]
+    fn test_home_dir_falls_back_to_userprofile() {
+        let env = env_of(&[("USERPROFILE", "C:\\Users\\dev")]);
+        assert_eq!(home_dir(&env), Some(PathBuf::from("C:\\Users\\dev")));
+        assert_eq!(home_dir(&env_of(&[])), None);
+    }
+
+    #[test]
+    fn test_powershell_profile_path() {
+        let home = tempfile::tempdir().unwrap();
+        let env = env_of(&[("PROFILE", "/custom/profile.ps1")]);
+        assert_eq!(
+            ShellKind::PowerShell.rc_path(&env, home.path()),
+            PathBuf::from("/custom/profile.ps1")
+        );
+
+        let default = ShellKind::PowerShell.rc_path(&env_of(&[]), home.path());
+        assert!(default.starts_with(home.path()));
+        assert!(default.ends_with("Microsoft.PowerShell_profile.ps1"));
+    }
+
+    /// Install, keep, force-update and uninstall, for every marker-block shell
+    #[test]
+    fn test_install_roundtrip_each_kind() {
+        for kind in [
+            ShellKind::Zsh,
+            ShellKind::Bash,
+            ShellKind::Fish,
+            ShellKind::Sh,
+            ShellKind::PowerShell,
+        ] {
+            let home = tempfile::tempdir().unwrap();
+            let rc_path = kind.rc_path(&env_of(&[]), home.path());
+            if kind == ShellKind::Zsh {
+                std::fs::write(&rc_path, "export EDITOR=vim\n").unwrap();
+            }
+
+            assert_eq!(
+                install(kind, &rc_path, false).unwrap(),
+                InstallOutcome::Installed,
+                "{kind:?}"
+            );
+            let contents = std::fs::read_to_string(&rc_path).unwrap();
+            assert!(contents.contains(kind.definition()), "{kind:?}");
+            assert_eq!(contents.matches(BEGIN_MARKER).count(), 1);
+
+            assert_eq!(
+                install(kind, &rc_path, false).unwrap(),
+                InstallOutcome::AlreadyInstalled
+            );
+            assert_eq!(
+                install(kind, &rc_path, true).unwrap(),
+                InstallOutcome::Updated
+            );
+            let contents = std::fs::read_to_string(&rc_path).unwrap();
+            assert_eq!(contents.matches(BEGIN_MARKER).count(), 1, "{kind:?}");
+
+            assert!(uninstall(kind, &rc_path).unwrap());
+            let contents = std::fs::read_to_string(&rc_path).unwrap();
+            assert!(!contents.contains(BEGIN_MARKER), "{kind:?}");
+            assert!(!contents.contains(kind.definition()), "{kind:?}");
+            if kind == ShellKind::Zsh {
+                assert_eq!(contents, "export EDITOR=vim\n");
+            }
+            assert!(!uninstall(kind, &rc_path).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_install_cmd_macro_file() {
+        let home = tempfile::tempdir().unwrap();
+        let rc_path = ShellKind::Cmd.rc_path(&env_of(&[]), home.path());
+
+        assert_eq!(
+            install(ShellKind::Cmd, &rc_path, false).unwrap(),
+            InstallOutcome::Installed
+        );
+        assert_eq!(
+            std::fs::read_to_string(&rc_path).unwrap(),
+            "am=mouchak-mail serve http $*\n"
+        );
+        assert_eq!(
+            install(ShellKind::Cmd, &rc_path, false).unwrap(),
+            InstallOutcome::AlreadyInstalled
+        );
+
+        assert!(uninstall(ShellKind::Cmd, &rc_path).unwrap());
+        assert!(!rc_path.exists());
+    }
+
+    #[test]
+    fn test_foreign_alias_needs_force() {
+        let home = tempfile::tempdir().unwrap();
+        for (kind, line) in [
+            (ShellKind::Bash, "alias am='git am'\n"),
+            (ShellKind::PowerShell, "Set-Alias am Get-Something\n"),
+        ] {
+            let rc_path = kind.rc_path(&env_of(&[]), home.path());
+            std::fs::create_dir_all(rc_path.parent().unwrap()).unwrap();
+            std::fs::write(&rc_path, line).unwrap();
+
+            assert_eq!(
+                install(kind, &rc_path, false).unwrap(),
+                InstallOutcome::Conflict
+            );
+            assert_eq!(std::fs::read_to_string(&rc_path).unwrap(), line);
+
+            assert_eq!(
+                install(kind, &rc_path, true).unwrap(),
+                InstallOutcome::Installed
+            );
+            assert!(
+                std::fs::read_to_string(&rc_path)
+                    .unwrap()
+                    .contains(BEGIN_MARKER)
+            );
+        }
+    }
+}
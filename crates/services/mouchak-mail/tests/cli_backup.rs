@@ -0,0 +1,43 @@
+#![allow(clippy::unwrap_used)]
+#![allow(deprecated)] // cargo_bin is still valid for our use case
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+use std::path::Path;
+
+fn backup_cmd(cwd: &Path, db: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("mouchak-mail").unwrap();
+    cmd.current_dir(cwd)
+        .env("DATABASE_PATH", db)
+        .env("RUST_LOG", "off")
+        .env_remove("BACKUP_DIRECTORY")
+        .arg("backup");
+    cmd
+}
+
+/// Test snapshots default to `backups` beside the database, whatever the
+/// working directory
+#[test]
+fn test_backup_directory_follows_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = dir.path().join("db").join("mail.db");
+    let (first, second) = (dir.path().join("first"), dir.path().join("second"));
+    for cwd in [&first, &second] {
+        std::fs::create_dir_all(cwd).unwrap();
+    }
+    let backups = dir.path().join("db").join("backups");
+
+    backup_cmd(&first, &db)
+        .arg("now")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(backups.display().to_string()));
+
+    backup_cmd(&second, &db)
+        .arg("list")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(backups.display().to_string()))
+        .stdout(predicate::str::contains("No backups").not());
+    assert!(!first.join("data").join("backups").exists());
+}
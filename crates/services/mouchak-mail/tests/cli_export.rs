@@ -0,0 +1,178 @@
+#![allow(clippy::unwrap_used)]
+#![allow(deprecated)] // cargo_bin is still valid for our use case
+
+use assert_cmd::Command;
+use mouchak_mail_core::model::export::{
+    ExportCompression, ExportManifest, ExportedMailbox, ScrubMode, encrypt_with_passphrase,
+    generate_signing_keypair, signing_key_to_base64, verifying_key_to_base64,
+};
+use predicates::prelude::*;
+use std::path::Path;
+
+const CONTENT: &str = r#"{"messages":[{"id":1,"subject":"hello"}]}"#;
+
+fn sample_manifest() -> ExportManifest {
+    ExportManifest::new(&ExportedMailbox {
+        project_slug: "demo".to_string(),
+        project_name: "Demo".to_string(),
+        message_count: 1,
+        exported_at: "2026-01-01T00:00:00Z".to_string(),
+        content: CONTENT.to_string(),
+        format: "json".to_string(),
+        filter: None,
+        scrub_mode: ScrubMode::None,
+        compression: ExportCompression::None,
+        original_size: None,
+    })
+}
+
+fn write_manifest(path: &Path, manifest: &ExportManifest) {
+    std::fs::write(path, serde_json::to_string_pretty(manifest).unwrap()).unwrap();
+}
+
+fn verify_cmd(export: &Path, manifest: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("mouchak-mail").unwrap();
+    cmd.arg("export").arg("verify").arg(export).arg(manifest);
+    cmd
+}
+
+/// Test export help lists the create/sign/verify subcommands
+#[test]
+fn test_export_help() {
+    let mut cmd = Command::cargo_bin("mouchak-mail").unwrap();
+    cmd.arg("export")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("create"))
+        .stdout(predicate::str::contains("sign"))
+        .stdout(predicate::str::contains("verify"));
+}
+
+/// Test a signed export verifies with the signer's public key
+#[test]
+fn test_export_verify_signed() {
+    let dir = tempfile::tempdir().unwrap();
+    let export = dir.path().join("export.json");
+    let manifest_path = dir.path().join("manifest.json");
+
+    let (signing_key, verifying_key) = generate_signing_keypair();
+    let mut manifest = sample_manifest();
+    manifest.sign(&signing_key);
+    std::fs::write(&export, CONTENT).unwrap();
+    write_manifest(&manifest_path, &manifest);
+
+    verify_cmd(&export, &manifest_path)
+        .arg("--public-key")
+        .arg(verifying_key_to_base64(&verifying_key))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Signature VALID"));
+}
+
+/// Test modified content is reported as tampered
+#[test]
+fn test_export_verify_tampered_content() {
+    let dir = tempfile::tempdir().unwrap();
+    let export = dir.path().join("export.json");
+    let manifest_path = dir.path().join("manifest.json");
+
+    std::fs::write(&export, CONTENT.replace("hello", "goodbye")).unwrap();
+    write_manifest(&manifest_path, &sample_manifest());
+
+    verify_cmd(&export, &manifest_path)
+        .assert()
+        .code(4)
+        .stderr(predicate::str::contains("TAMPERED"));
+}
+
+/// Test a signature from a different key is rejected
+#[test]
+fn test_export_verify_wrong_key() {
+    let dir = tempfile::tempdir().unwrap();
+    let export = dir.path().join("export.json");
+    let manifest_path = dir.path().join("manifest.json");
+
+    let (signing_key, _) = generate_signing_keypair();
+    let (_, other_key) = generate_signing_keypair();
+    let mut manifest = sample_manifest();
+    manifest.sign(&signing_key);
+    std::fs::write(&export, CONTENT).unwrap();
+    write_manifest(&manifest_path, &manifest);
+
+    verify_cmd(&export, &manifest_path)
+        .arg("--public-key")
+        .arg(verifying_key_to_base64(&other_key))
+        .assert()
+        .code(5)
+        .stderr(predicate::str::contains("different key"));
+}
+
+/// Test an unparseable manifest is reported as malformed
+#[test]
+fn test_export_verify_malformed_manifest() {
+    let dir = tempfile::tempdir().unwrap();
+    let export = dir.path().join("export.json");
+    let manifest_path = dir.path().join("manifest.json");
+
+    std::fs::write(&export, CONTENT).unwrap();
+    std::fs::write(&manifest_path, "{ not json").unwrap();
+
+    verify_cmd(&export, &manifest_path)
+        .assert()
+        .code(3)
+        .stderr(predicate::str::contains("Malformed manifest"));
+}
+
+/// Test an age passphrase export is decrypted before verification
+#[test]
+fn test_export_verify_passphrase_encrypted() {
+    let dir = tempfile::tempdir().unwrap();
+    let export = dir.path().join("export.json.age");
+    let manifest_path = dir.path().join("manifest.json");
+
+    let encrypted = encrypt_with_passphrase(CONTENT.as_bytes(), "hunter2").unwrap();
+    std::fs::write(&export, encrypted).unwrap();
+    write_manifest(&manifest_path, &sample_manifest());
+
+    verify_cmd(&export, &manifest_path)
+        .write_stdin("hunter2\n")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Content hash matches"));
+
+    verify_cmd(&export, &manifest_path)
+        .arg("--passphrase")
+        .arg("wrong")
+        .assert()
+        .code(6);
+}
+
+/// Test signing an unsigned manifest makes it verifiable
+#[test]
+fn test_export_sign_then_verify() {
+    let dir = tempfile::tempdir().unwrap();
+    let export = dir.path().join("export.json");
+    let manifest_path = dir.path().join("manifest.json");
+
+    let (signing_key, verifying_key) = generate_signing_keypair();
+    std::fs::write(&export, CONTENT).unwrap();
+    write_manifest(&manifest_path, &sample_manifest());
+
+    let mut cmd = Command::cargo_bin("mouchak-mail").unwrap();
+    cmd.arg("export")
+        .arg("sign")
+        .arg(&export)
+        .arg(&manifest_path)
+        .arg("--key")
+        .arg(signing_key_to_base64(&signing_key))
+        .assert()
+        .success();
+
+    verify_cmd(&export, &manifest_path)
+        .arg("--public-key")
+        .arg(verifying_key_to_base64(&verifying_key))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Signature VALID"));
+}
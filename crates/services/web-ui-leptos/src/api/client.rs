@@ -1,44 +1,68 @@
 //! HTTP client for Mouchak Mail API.
 
+use super::fetch;
 use gloo_net::http::Request;
 use serde::{Deserialize, Serialize};
 
-/// Get the API base URL.
-///
-/// In WASM (browser), uses the current window origin so the API is on the same host.
-/// This allows the frontend to work correctly when deployed behind a reverse proxy.
-///
-/// Build-time configuration via `API_BASE_URL` env var is also supported for development.
-pub fn api_base_url() -> String {
-    // Check for build-time env var first (for development overrides)
-    if let Some(url) = option_env!("API_BASE_URL") {
-        if !url.is_empty() {
-            return url.to_string();
-        }
-    }
-
-    // In WASM, use the current window origin
-    #[cfg(target_arch = "wasm32")]
-    {
-        if let Some(window) = web_sys::window() {
-            if let Ok(origin) = window.location().origin() {
-                return origin;
-            }
-        }
-    }
-
-    // Fallback for non-WASM or if window is unavailable
-    "http://127.0.0.1:8080".to_string()
-}
+pub use super::fetch::api_base_url;
 
 /// Legacy constant for backwards compatibility - prefer api_base_url() function
 #[deprecated(since = "0.2.0", note = "Use api_base_url() function instead")]
 pub const API_BASE_URL: &str = "http://127.0.0.1:8080";
 
+/// What went wrong with an API call.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiErrorKind {
+    /// The server answered with an error, or its answer couldn't be read
+    #[default]
+    Server,
+    /// The request never reached the server
+    Network,
+    /// The server didn't answer within [`fetch::REQUEST_TIMEOUT`]
+    Timeout,
+}
+
 /// API error type.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiError {
     pub message: String,
+    #[serde(default)]
+    pub kind: ApiErrorKind,
+}
+
+impl ApiError {
+    /// An error reported by the server.
+    pub fn new(message: impl Into<String>) -> Self {
+        ApiError {
+            message: message.into(),
+            kind: ApiErrorKind::Server,
+        }
+    }
+
+    /// The request failed before reaching the server.
+    pub fn network(detail: &str) -> Self {
+        ApiError {
+            message: format!("Server unreachable: {}", detail),
+            kind: ApiErrorKind::Network,
+        }
+    }
+
+    /// The server didn't answer in time.
+    pub fn timeout() -> Self {
+        ApiError {
+            message: format!(
+                "Server unreachable: no response within {}s",
+                fetch::REQUEST_TIMEOUT.as_secs()
+            ),
+            kind: ApiErrorKind::Timeout,
+        }
+    }
+
+    /// Whether the server couldn't be reached at all.
+    pub fn is_unreachable(&self) -> bool {
+        matches!(self.kind, ApiErrorKind::Network | ApiErrorKind::Timeout)
+    }
 }
 
 impl std::fmt::Display for ApiError {
@@ -49,9 +73,7 @@ impl std::fmt::Display for ApiError {
 
 impl From<gloo_net::Error> for ApiError {
     fn from(e: gloo_net::Error) -> Self {
-        ApiError {
-            message: e.to_string(),
-        }
+        ApiError::new(e.to_string())
     }
 }
 
@@ -97,6 +119,19 @@ pub struct Agent {
     pub last_active_ts: Option<String>,
     #[serde(default)]
     pub created_at: Option<String>,
+    /// Present only while the agent is in "do not disturb"
+    #[serde(default)]
+    pub dnd: Option<DndPolicy>,
+}
+
+/// An agent's "do not disturb" window.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DndPolicy {
+    #[serde(default)]
+    pub dnd_until: Option<String>,
+    /// Lowest importance still delivered; `None` means urgent only
+    #[serde(default)]
+    pub min_importance: Option<String>,
 }
 
 /// Inbox message response (from POST /api/inbox).
@@ -105,7 +140,21 @@ pub struct InboxMessage {
     pub id: i64,
     pub subject: String,
     pub sender_name: String,
+    #[serde(default)]
+    pub ack_required: bool,
     pub created_ts: String,
+    /// Sender's project, set on messages from another project.
+    #[serde(default)]
+    pub project_slug: Option<String>,
+    /// Position in the thread, counting from 1.
+    #[serde(default)]
+    pub thread_seq: Option<i64>,
+    /// Set once a snooze on this message has run out.
+    #[serde(default)]
+    pub returned_from_snooze: bool,
+    /// Set while the agent still owes an acknowledgement.
+    #[serde(default)]
+    pub needs_ack: bool,
 }
 
 /// Full message response (from GET /api/messages/:id).
@@ -127,33 +176,50 @@ pub struct Message {
     pub attachments: Vec<serde_json::Value>,
     #[serde(default)]
     pub recipients: Vec<String>,
+    /// Set when the sender has recalled the message.
+    #[serde(default)]
+    pub recalled_ts: Option<String>,
+    /// Sender's project, set on messages from another project.
+    #[serde(default)]
+    pub project_slug: Option<String>,
+    /// Position in the thread, counting from 1.
+    #[serde(default)]
+    pub thread_seq: Option<i64>,
+    /// Message this one forwards, if it is a forward.
+    #[serde(default)]
+    pub forwarded_from_id: Option<i64>,
+    /// Canonical link, absolute when the server has a public URL.
+    #[serde(default)]
+    pub permalink: Option<String>,
 }
 
 /// Check API health.
 pub async fn check_health() -> Result<HealthResponse, ApiError> {
     let url = format!("{}/api/health", api_base_url());
-    let response = Request::get(&url).send().await?;
+    let response = fetch::get(&url).await?;
 
     if response.ok() {
         Ok(response.json().await?)
     } else {
-        Err(ApiError {
-            message: format!("Health check failed: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Health check failed: {}",
+            response.status()
+        )))
     }
 }
 
 /// Get all projects.
 pub async fn get_projects() -> Result<Vec<Project>, ApiError> {
     let url = format!("{}/api/projects", api_base_url());
-    let response = Request::get(&url).send().await?;
+    let response = fetch::get(&url).await?;
 
     if response.ok() {
         Ok(response.json().await?)
     } else {
-        Err(ApiError {
-            message: format!("Failed to get projects: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to get projects: {}",
+            response.status()
+        )))
     }
 }
 
@@ -168,46 +234,48 @@ pub async fn ensure_project(human_key: &str) -> Result<Project, ApiError> {
 
     let payload = CreateProjectPayload { human_key };
 
-    let response = Request::post(&url)
+    let request = Request::post(&url)
         .header("Content-Type", "application/json")
-        .json(&payload)?
-        .send()
-        .await?;
+        .json(&payload)?;
+    let response = fetch::send(request).await?;
 
     if response.ok() {
         Ok(response.json().await?)
     } else {
-        Err(ApiError {
-            message: format!("Failed to create project: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to create project: {}",
+            response.status()
+        )))
     }
 }
 
 /// Get project by slug.
 pub async fn get_project(slug: &str) -> Result<Project, ApiError> {
     let url = format!("{}/api/projects/{}", api_base_url(), slug);
-    let response = Request::get(&url).send().await?;
+    let response = fetch::get(&url).await?;
 
     if response.ok() {
         Ok(response.json().await?)
     } else {
-        Err(ApiError {
-            message: format!("Failed to get project: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to get project: {}",
+            response.status()
+        )))
     }
 }
 
 /// Get agents for a project.
 pub async fn get_agents(project_slug: &str) -> Result<Vec<Agent>, ApiError> {
     let url = format!("{}/api/projects/{}/agents", api_base_url(), project_slug);
-    let response = Request::get(&url).send().await?;
+    let response = fetch::get(&url).await?;
 
     if response.ok() {
         Ok(response.json().await?)
     } else {
-        Err(ApiError {
-            message: format!("Failed to get agents: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to get agents: {}",
+            response.status()
+        )))
     }
 }
 
@@ -220,6 +288,9 @@ struct BackendError {
     /// Human-readable error message.
     #[serde(default)]
     error: Option<String>,
+    /// Recipients that could not be resolved, for a rejected send.
+    #[serde(default)]
+    failed: Vec<RecipientFailure>,
 }
 
 impl BackendError {
@@ -261,11 +332,10 @@ pub async fn register_agent(
         task_description,
     };
 
-    let response = Request::post(&url)
+    let request = Request::post(&url)
         .header("Content-Type", "application/json")
-        .json(&payload)?
-        .send()
-        .await?;
+        .json(&payload)?;
+    let response = fetch::send(request).await?;
 
     if response.ok() {
         Ok(response.json().await?)
@@ -276,23 +346,92 @@ pub async fn register_agent(
             Ok(err) => err.message(),
             Err(_) => format!("HTTP {}", status),
         };
-        Err(ApiError {
-            message: format!("Failed to register agent: {}", error_msg),
-        })
+        Err(ApiError::new(format!(
+            "Failed to register agent: {}",
+            error_msg
+        )))
+    }
+}
+
+/// Fields to change on an agent; `None` leaves the current value.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AgentUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub task_description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub program: Option<String>,
+}
+
+/// Rename an agent or update its task, model or program.
+pub async fn update_agent(
+    project_slug: &str,
+    agent_name: &str,
+    update: &AgentUpdate,
+) -> Result<Agent, ApiError> {
+    let url = format!(
+        "{}/api/project/{}/agent/{}",
+        api_base_url(),
+        project_slug,
+        agent_name
+    );
+
+    let request = Request::patch(&url)
+        .header("Content-Type", "application/json")
+        .json(update)?;
+    let response = fetch::send(request).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        // Surface the backend reason (e.g. name already taken)
+        let status = response.status();
+        let error_msg = match response.json::<BackendError>().await {
+            Ok(err) => err.message(),
+            Err(_) => format!("HTTP {}", status),
+        };
+        Err(ApiError::new(format!(
+            "Failed to update agent: {}",
+            error_msg
+        )))
+    }
+}
+
+/// Retire an agent: it disappears from listings but keeps its history.
+pub async fn retire_agent(project_slug: &str, agent_name: &str) -> Result<Agent, ApiError> {
+    let url = format!(
+        "{}/api/project/{}/agent/{}",
+        api_base_url(),
+        project_slug,
+        agent_name
+    );
+    let response = fetch::send(Request::delete(&url).build()?).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to retire agent: {}",
+            response.status()
+        )))
     }
 }
 
 /// Get all agents.
 pub async fn get_all_agents() -> Result<Vec<Agent>, ApiError> {
     let url = format!("{}/api/agents", api_base_url());
-    let response = Request::get(&url).send().await?;
+    let response = fetch::get(&url).await?;
 
     if response.ok() {
         Ok(response.json().await?)
     } else {
-        Err(ApiError {
-            message: format!("Failed to get agents: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to get agents: {}",
+            response.status()
+        )))
     }
 }
 
@@ -309,39 +448,83 @@ pub async fn get_inbox(
         "limit": 50
     });
 
-    let response = Request::post(&url)
+    let request = Request::post(&url)
         .header("Content-Type", "application/json")
         .body(payload.to_string())
-        .map_err(|e| ApiError {
-            message: e.to_string(),
-        })?
-        .send()
-        .await?;
+        .map_err(|e| ApiError::new(e.to_string()))?;
+    let response = fetch::send(request).await?;
 
     if response.ok() {
         Ok(response.json().await?)
     } else {
-        Err(ApiError {
-            message: format!("Failed to get inbox: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to get inbox: {}",
+            response.status()
+        )))
     }
 }
 
 /// Get a single message by ID.
 pub async fn get_message(id: &str) -> Result<Message, ApiError> {
     let url = format!("{}/api/messages/{}", api_base_url(), id);
-    let response = Request::get(&url).send().await?;
+    let response = fetch::get(&url).await?;
 
     if response.ok() {
         Ok(response.json().await?)
     } else {
-        Err(ApiError {
-            message: format!("Failed to get message: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to get message: {}",
+            response.status()
+        )))
+    }
+}
+
+/// A recipient the server could not resolve, with its error code
+/// (`AGENT_NOT_FOUND`, `PROJECT_NOT_FOUND` or `FORBIDDEN_CROSS_PROJECT`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecipientFailure {
+    pub name: String,
+    pub code: String,
+}
+
+/// Delivery report returned by POST /api/message/send.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SendMessageResult {
+    pub message_id: i64,
+    #[serde(default)]
+    pub delivered: Vec<String>,
+    #[serde(default)]
+    pub failed: Vec<RecipientFailure>,
+    #[serde(default)]
+    pub partial_success: bool,
+}
+
+/// A failed send; `failed` names the recipients that caused it, if any.
+#[derive(Debug, Clone)]
+pub struct SendMessageError {
+    pub error: ApiError,
+    pub failed: Vec<RecipientFailure>,
+}
+
+impl From<ApiError> for SendMessageError {
+    fn from(error: ApiError) -> Self {
+        SendMessageError {
+            error,
+            failed: Vec::new(),
+        }
+    }
+}
+
+impl From<gloo_net::Error> for SendMessageError {
+    fn from(e: gloo_net::Error) -> Self {
+        ApiError::from(e).into()
     }
 }
 
 /// Send a message.
+///
+/// Nothing is sent unless every recipient resolves; when some don't, the
+/// error lists them so the composer can point at the right names.
 #[allow(clippy::too_many_arguments)]
 pub async fn send_message(
     project_slug: &str,
@@ -351,8 +534,9 @@ pub async fn send_message(
     body: &str,
     thread_id: Option<&str>,
     importance: &str,
-    _ack_required: bool,
-) -> Result<Message, ApiError> {
+    ack_required: bool,
+    broadcast: bool,
+) -> Result<SendMessageResult, SendMessageError> {
     let url = format!("{}/api/message/send", api_base_url());
 
     #[derive(Serialize)]
@@ -366,6 +550,8 @@ pub async fn send_message(
         thread_id: Option<&'a str>,
         #[serde(skip_serializing_if = "Option::is_none")]
         importance: Option<&'a str>,
+        ack_required: bool,
+        broadcast: bool,
     }
 
     let payload = SendMessagePayload {
@@ -376,20 +562,79 @@ pub async fn send_message(
         body_md: body,
         thread_id,
         importance: Some(importance),
+        ack_required,
+        broadcast,
     };
 
-    let response = Request::post(&url)
+    let request = Request::post(&url)
         .header("Content-Type", "application/json")
-        .json(&payload)?
-        .send()
-        .await?;
+        .json(&payload)?;
+    let response = fetch::send(request).await?;
 
     if response.ok() {
         Ok(response.json().await?)
     } else {
-        Err(ApiError {
-            message: format!("Failed to send message: {}", response.status()),
-        })
+        let status = response.status();
+        match response.json::<BackendError>().await {
+            Ok(err) => Err(SendMessageError {
+                error: ApiError::new(format!("Failed to send message: {}", err.message())),
+                failed: err.failed,
+            }),
+            Err(_) => Err(ApiError::new(format!("Failed to send message: {}", status)).into()),
+        }
+    }
+}
+
+/// Recall record returned by POST /api/message/recall.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageRecall {
+    pub message_id: i64,
+    pub recalled_ts: String,
+    pub recall_reason: String,
+}
+
+/// Recall a message sent by `agent_name`, replacing it with a tombstone.
+pub async fn recall_message(
+    project_slug: &str,
+    agent_name: &str,
+    message_id: i64,
+    reason: &str,
+) -> Result<MessageRecall, ApiError> {
+    let url = format!("{}/api/message/recall", api_base_url());
+
+    #[derive(Serialize)]
+    struct RecallMessagePayload<'a> {
+        project_slug: &'a str,
+        agent_name: &'a str,
+        message_id: i64,
+        reason: &'a str,
+    }
+
+    let payload = RecallMessagePayload {
+        project_slug,
+        agent_name,
+        message_id,
+        reason,
+    };
+
+    let request = Request::post(&url)
+        .header("Content-Type", "application/json")
+        .json(&payload)?;
+    let response = fetch::send(request).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        // Surface the backend reason (e.g. recall window expired)
+        let status = response.status();
+        let error_msg = match response.json::<BackendError>().await {
+            Ok(err) => err.message(),
+            Err(_) => format!("HTTP {}", status),
+        };
+        Err(ApiError::new(format!(
+            "Failed to recall message: {}",
+            error_msg
+        )))
     }
 }
 
@@ -403,136 +648,949 @@ pub struct UnifiedInboxMessage {
     pub sender_name: String,
     pub subject: String,
     pub importance: String,
+    #[serde(default)]
+    pub ack_required: bool,
     pub created_ts: String,
     #[serde(default)]
     pub thread_id: Option<String>,
+    /// A recipient has muted the message's thread
+    #[serde(default)]
+    pub muted: bool,
+    /// Canonical link, absolute when the server has a public URL
+    #[serde(default)]
+    pub permalink: Option<String>,
 }
 
-/// Get unified inbox (all messages across all projects).
-pub async fn get_unified_inbox(
-    importance: Option<&str>,
-    limit: Option<i32>,
-) -> Result<Vec<UnifiedInboxMessage>, ApiError> {
-    let mut url = format!("{}/api/unified-inbox", api_base_url());
+/// Server-side filters for [`get_unified_inbox`].
+///
+/// The default (no filters) returns the latest messages across all projects.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UnifiedInboxQuery {
+    /// Project slugs to include (empty = all)
+    pub projects: Vec<String>,
+    pub sender: Option<String>,
+    pub importance: Option<String>,
+    /// Subject substring
+    pub q: Option<String>,
+    /// RFC 3339 lower bound on created time (inclusive)
+    pub since: Option<String>,
+    /// RFC 3339 upper bound on created time (exclusive)
+    pub until: Option<String>,
+    pub limit: Option<i32>,
+    /// `next_cursor` from the previous page
+    pub cursor: Option<String>,
+    /// Label name (case-insensitive)
+    pub label: Option<String>,
+    /// Sort order (None = newest first)
+    pub sort: Option<String>,
+}
 
-    let mut params = Vec::new();
-    if let Some(imp) = importance {
-        params.push(format!("importance={}", imp));
+impl UnifiedInboxQuery {
+    /// Whether any filter beyond paging is set.
+    pub fn is_filtered(&self) -> bool {
+        !self.projects.is_empty()
+            || self.sender.is_some()
+            || self.importance.is_some()
+            || self.q.is_some()
+            || self.since.is_some()
+            || self.until.is_some()
+            || self.label.is_some()
     }
-    if let Some(lim) = limit {
-        params.push(format!("limit={}", lim));
+
+    fn to_query_string(&self) -> String {
+        let mut params = Vec::new();
+        if !self.projects.is_empty() {
+            params.push(format!(
+                "projects={}",
+                urlencoding::encode(&self.projects.join(","))
+            ));
+        }
+        let optional = [
+            ("sender", &self.sender),
+            ("importance", &self.importance),
+            ("q", &self.q),
+            ("since", &self.since),
+            ("until", &self.until),
+            ("label", &self.label),
+            ("sort", &self.sort),
+            ("cursor", &self.cursor),
+        ];
+        for (key, value) in optional {
+            if let Some(value) = value {
+                params.push(format!("{}={}", key, urlencoding::encode(value)));
+            }
+        }
+        if let Some(limit) = self.limit {
+            params.push(format!("limit={}", limit));
+        }
+        params.join("&")
     }
-    if !params.is_empty() {
-        url = format!("{}?{}", url, params.join("&"));
+}
+
+/// Unified inbox response wrapper (from GET /api/unified-inbox).
+#[derive(Debug, Clone, Deserialize)]
+struct UnifiedInboxResponse {
+    messages: Vec<UnifiedInboxMessage>,
+}
+
+/// Get unified inbox (all messages across all projects), filtered server-side.
+pub async fn get_unified_inbox(
+    query: &UnifiedInboxQuery,
+) -> Result<Vec<UnifiedInboxMessage>, ApiError> {
+    let mut url = format!("{}/api/unified-inbox", api_base_url());
+    let query_string = query.to_query_string();
+    if !query_string.is_empty() {
+        url = format!("{}?{}", url, query_string);
     }
 
-    let response = Request::get(&url).send().await?;
+    let response = fetch::get(&url).await?;
 
     if response.ok() {
-        Ok(response.json().await?)
+        let body: UnifiedInboxResponse = response.json().await?;
+        Ok(body.messages)
     } else {
-        Err(ApiError {
-            message: format!("Failed to get unified inbox: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to get unified inbox: {}",
+            response.status()
+        )))
     }
 }
 
 /// Get messages in a thread.
 pub async fn get_thread(project_slug: &str, thread_id: &str) -> Result<Vec<Message>, ApiError> {
-    let url = format!(
-        "{}/api/projects/{}/threads/{}",
-        api_base_url(),
-        project_slug,
-        thread_id
-    );
-    let response = Request::get(&url).send().await?;
+    let url = format!("{}/api/thread", api_base_url());
+
+    #[derive(Serialize)]
+    struct GetThreadPayload<'a> {
+        project_slug: &'a str,
+        thread_id: &'a str,
+    }
+
+    let request = Request::post(&url)
+        .header("Content-Type", "application/json")
+        .json(&GetThreadPayload {
+            project_slug,
+            thread_id,
+        })?;
+    let response = fetch::send(request).await?;
 
     if response.ok() {
         Ok(response.json().await?)
     } else {
-        Err(ApiError {
-            message: format!("Failed to get thread: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to get thread: {}",
+            response.status()
+        )))
     }
 }
 
-/// Search messages.
-pub async fn search_messages(project_slug: &str, query: &str) -> Result<Vec<Message>, ApiError> {
+/// Thread participant with the number of messages they sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadParticipant {
+    pub name: String,
+    pub message_count: i64,
+}
+
+/// Structured thread statistics (from GET /api/project/{slug}/thread/{id}/summary).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadStats {
+    pub thread_id: String,
+    pub subject: String,
+    #[serde(default)]
+    pub participants: Vec<ThreadParticipant>,
+    pub message_count: i64,
+    pub first_ts: String,
+    pub last_ts: String,
+    #[serde(default)]
+    pub ack_required_count: i64,
+    #[serde(default)]
+    pub acked_count: i64,
+}
+
+/// Get participant and message stats for a thread.
+pub async fn get_thread_summary(
+    project_slug: &str,
+    thread_id: &str,
+) -> Result<ThreadStats, ApiError> {
     let url = format!(
-        "{}/api/projects/{}/search?q={}",
+        "{}/api/project/{}/thread/{}/summary?recent=0",
         api_base_url(),
-        project_slug,
-        query
+        urlencoding::encode(project_slug),
+        urlencoding::encode(thread_id)
     );
-    let response = Request::get(&url).send().await?;
+    let response = fetch::get(&url).await?;
 
     if response.ok() {
         Ok(response.json().await?)
     } else {
-        Err(ApiError {
-            message: format!("Failed to search: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to get thread summary: {}",
+            response.status()
+        )))
     }
 }
 
-/// File reservation response from API.
+/// Agent that muted a thread (from GET /api/project/{slug}/thread/{id}/mutes).
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct FileReservationResponse {
-    pub id: i64,
+pub struct ThreadMute {
+    pub thread_id: String,
     pub agent_name: String,
-    pub path_pattern: String,
-    pub exclusive: bool,
-    pub reason: Option<String>,
-    pub created_ts: String,
-    pub expires_ts: Option<String>,
-    #[serde(default)]
-    pub expired: bool,
+    pub muted_ts: String,
 }
 
-/// Get file reservations for a project.
-pub async fn get_file_reservations(
+fn thread_mutes_url(project_slug: &str, thread_id: &str) -> String {
+    format!(
+        "{}/api/project/{}/thread/{}/mutes",
+        api_base_url(),
+        urlencoding::encode(project_slug),
+        urlencoding::encode(thread_id)
+    )
+}
+
+/// Get the agents that muted a thread.
+pub async fn get_thread_mutes(
     project_slug: &str,
-) -> Result<Vec<FileReservationResponse>, ApiError> {
-    let url = format!("{}/api/file_reservations/list", api_base_url());
+    thread_id: &str,
+) -> Result<Vec<ThreadMute>, ApiError> {
+    let response = fetch::get(&thread_mutes_url(project_slug, thread_id)).await?;
 
-    #[derive(Serialize)]
-    struct Payload<'a> {
-        project_slug: &'a str,
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to get thread mutes: {}",
+            response.status()
+        )))
     }
+}
 
-    let response = Request::post(&url)
-        .header("Content-Type", "application/json")
-        .json(&Payload { project_slug })?
-        .send()
-        .await?;
+/// Mute or unmute a thread for an agent.
+pub async fn set_thread_muted(
+    project_slug: &str,
+    thread_id: &str,
+    agent_name: &str,
+    muted: bool,
+) -> Result<(), ApiError> {
+    let url = thread_mutes_url(project_slug, thread_id);
+    let request = if muted {
+        Request::post(&url)
+            .header("Content-Type", "application/json")
+            .json(&serde_json::json!({ "agent_name": agent_name }))?
+    } else {
+        Request::delete(&format!("{}/{}", url, urlencoding::encode(agent_name))).build()?
+    };
+    let response = fetch::send(request).await?;
 
     if response.ok() {
-        Ok(response.json().await?)
+        Ok(())
     } else {
-        Err(ApiError {
-            message: format!("Failed to get reservations: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to {} thread: {}",
+            if muted { "mute" } else { "unmute" },
+            response.status()
+        )))
     }
 }
 
-/// Mark read response.
+/// Thread row (from GET /api/project/{slug}/threads).
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct MarkReadResponse {
-    pub success: bool,
+pub struct ThreadListItem {
+    pub thread_id: String,
+    pub subject: String,
+    pub message_count: i64,
     #[serde(default)]
-    pub message: Option<String>,
+    pub participant_count: i64,
+    pub last_message_ts: String,
+    #[serde(default)]
+    pub last_sender_name: String,
 }
 
-/// Mark a message as read/unread.
+/// Page of threads, most recently active first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThreadPage {
+    #[serde(default)]
+    pub threads: Vec<ThreadListItem>,
+    #[serde(default)]
+    pub next_cursor: Option<String>,
+}
+
+/// List a project's threads by last activity.
 ///
-/// # Arguments
-/// * `message_id` - The message ID to mark
-/// * `project_slug` - Project context
-/// * `agent_name` - Agent marking the message
-/// * `is_read` - True to mark as read, false to mark as unread
-pub async fn mark_read(
-    message_id: i64,
-    project_slug: &str,
+/// Pass the previous page's `next_cursor` as `cursor` for the next page.
+pub async fn get_threads(project_slug: &str, cursor: Option<&str>) -> Result<ThreadPage, ApiError> {
+    let mut url = format!(
+        "{}/api/project/{}/threads?limit=50",
+        api_base_url(),
+        urlencoding::encode(project_slug)
+    );
+    if let Some(cursor) = cursor {
+        url.push_str(&format!("&cursor={}", urlencoding::encode(cursor)));
+    }
+    let response = fetch::get(&url).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to get threads: {}",
+            response.status()
+        )))
+    }
+}
+
+/// Canned message template (from GET /api/project/{slug}/templates).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageTemplate {
+    pub id: i64,
+    pub name: String,
+    pub subject: String,
+    #[serde(default)]
+    pub body_md: String,
+    pub importance: String,
+    #[serde(default)]
+    pub ack_required: bool,
+}
+
+/// List a project's message templates, ordered by name.
+pub async fn get_templates(project_slug: &str) -> Result<Vec<MessageTemplate>, ApiError> {
+    let url = format!(
+        "{}/api/project/{}/templates",
+        api_base_url(),
+        urlencoding::encode(project_slug)
+    );
+    let response = fetch::get(&url).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to get templates: {}",
+            response.status()
+        )))
+    }
+}
+
+/// Named agent group (from GET /api/project/{slug}/groups). Addressed as
+/// `group:<name>`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AgentGroup {
+    pub id: i64,
+    pub name: String,
+    /// Member agent names, ordered by name
+    #[serde(default)]
+    pub members: Vec<String>,
+}
+
+/// List a project's agent groups, ordered by name.
+pub async fn get_groups(project_slug: &str) -> Result<Vec<AgentGroup>, ApiError> {
+    let url = format!(
+        "{}/api/project/{}/groups",
+        api_base_url(),
+        urlencoding::encode(project_slug)
+    );
+    let response = fetch::get(&url).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to get groups: {}",
+            response.status()
+        )))
+    }
+}
+
+/// Message label (from GET /api/project/{slug}/labels).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Label {
+    pub id: i64,
+    pub name: String,
+    /// `#rrggbb`
+    pub color: String,
+}
+
+/// List a project's labels, ordered by name.
+pub async fn get_labels(project_slug: &str) -> Result<Vec<Label>, ApiError> {
+    let url = format!(
+        "{}/api/project/{}/labels",
+        api_base_url(),
+        urlencoding::encode(project_slug)
+    );
+    let response = fetch::get(&url).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to get labels: {}",
+            response.status()
+        )))
+    }
+}
+
+/// List the labels applied to a message.
+pub async fn get_message_labels(message_id: i64) -> Result<Vec<Label>, ApiError> {
+    let url = format!("{}/api/message/{}/labels", api_base_url(), message_id);
+    let response = fetch::get(&url).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to get message labels: {}",
+            response.status()
+        )))
+    }
+}
+
+/// Add and remove labels on a message by name. Labels the project does not
+/// have yet are created. Returns the message's labels afterwards.
+pub async fn update_message_labels(
+    message_id: i64,
+    add: &[String],
+    remove: &[String],
+) -> Result<Vec<Label>, ApiError> {
+    let url = format!("{}/api/message/{}/labels", api_base_url(), message_id);
+
+    #[derive(Serialize)]
+    struct UpdateMessageLabelsPayload<'a> {
+        add: &'a [String],
+        remove: &'a [String],
+        create_missing: bool,
+    }
+
+    let payload = UpdateMessageLabelsPayload {
+        add,
+        remove,
+        create_missing: true,
+    };
+
+    let request = Request::post(&url)
+        .header("Content-Type", "application/json")
+        .json(&payload)?;
+    let response = fetch::send(request).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        let status = response.status();
+        let error_msg = match response.json::<BackendError>().await {
+            Ok(err) => err.message(),
+            Err(_) => format!("HTTP {}", status),
+        };
+        Err(ApiError::new(format!(
+            "Failed to update labels: {}",
+            error_msg
+        )))
+    }
+}
+
+/// Recipient of a sent message with read/ack state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxRecipient {
+    pub name: String,
+    pub recipient_type: String,
+    #[serde(default)]
+    pub read_ts: Option<String>,
+    #[serde(default)]
+    pub ack_ts: Option<String>,
+}
+
+/// Sent message (from GET /api/project/{slug}/agent/{name}/outbox).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxMessage {
+    pub id: i64,
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    pub subject: String,
+    pub importance: String,
+    #[serde(default)]
+    pub ack_required: bool,
+    pub created_ts: String,
+    #[serde(default)]
+    pub recipients: Vec<OutboxRecipient>,
+}
+
+/// Get messages sent by an agent, newest first.
+///
+/// Pass the `id` of the last message of a page as `cursor` for the next page.
+pub async fn get_outbox(
+    project_slug: &str,
+    agent_name: &str,
+    cursor: Option<i64>,
+) -> Result<Vec<OutboxMessage>, ApiError> {
+    let mut url = format!(
+        "{}/api/project/{}/agent/{}/outbox?limit=50",
+        api_base_url(),
+        urlencoding::encode(project_slug),
+        urlencoding::encode(agent_name)
+    );
+    if let Some(cursor) = cursor {
+        url.push_str(&format!("&cursor={}", cursor));
+    }
+    let response = fetch::get(&url).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to get outbox: {}",
+            response.status()
+        )))
+    }
+}
+
+/// Capability held by an agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentCapability {
+    pub id: i64,
+    pub capability: String,
+    pub granted_at: String,
+    #[serde(default)]
+    pub expires_at: Option<String>,
+}
+
+/// Active file reservation held by an agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentReservation {
+    pub id: i64,
+    pub path_pattern: String,
+    pub exclusive: bool,
+    #[serde(default)]
+    pub reason: String,
+    pub created_ts: String,
+    pub expires_ts: String,
+}
+
+/// Sent or received message in an agent's timeline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentTimelineEntry {
+    pub message_id: i64,
+    /// `sent` or `received`
+    pub direction: String,
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    pub subject: String,
+    pub importance: String,
+    /// Sender of a received message, recipients of a sent one
+    pub counterpart: String,
+    pub created_ts: String,
+    #[serde(default)]
+    pub read_ts: Option<String>,
+}
+
+/// Received message still waiting on the agent's acknowledgement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingAck {
+    pub message_id: i64,
+    /// Sender's project, set on messages from another project
+    #[serde(default)]
+    pub project_slug: Option<String>,
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    pub subject: String,
+    pub sender_name: String,
+    pub importance: String,
+    pub created_ts: String,
+    pub days_outstanding: i64,
+}
+
+/// Agent detail (from GET /api/project/{slug}/agent/{name}/activity).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentActivity {
+    pub agent: Agent,
+    #[serde(default)]
+    pub capabilities: Vec<AgentCapability>,
+    #[serde(default)]
+    pub file_reservations: Vec<AgentReservation>,
+    /// Oldest first
+    #[serde(default)]
+    pub pending_acks: Vec<PendingAck>,
+    #[serde(default)]
+    pub timeline: Vec<AgentTimelineEntry>,
+}
+
+/// Get an agent's profile, capabilities, reservations and message timeline.
+pub async fn get_agent_activity(
+    project_slug: &str,
+    agent_name: &str,
+    limit: usize,
+) -> Result<AgentActivity, ApiError> {
+    let url = format!(
+        "{}/api/project/{}/agent/{}/activity?limit={}",
+        api_base_url(),
+        urlencoding::encode(project_slug),
+        urlencoding::encode(agent_name),
+        limit
+    );
+    let response = fetch::get(&url).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to get agent activity: {}",
+            response.status()
+        )))
+    }
+}
+
+/// Response from POST /api/messages/mark-read.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkReadBatchResponse {
+    /// Messages with at least one recipient newly marked read
+    #[serde(default)]
+    pub marked: Vec<i64>,
+    pub requested: usize,
+}
+
+/// Mark several messages read.
+///
+/// With `agent` (project slug, agent name) only that agent's copies are
+/// marked; without it every recipient's copy is.
+pub async fn mark_read_batch(
+    message_ids: &[i64],
+    agent: Option<(&str, &str)>,
+) -> Result<MarkReadBatchResponse, ApiError> {
+    let url = format!("{}/api/messages/mark-read", api_base_url());
+
+    #[derive(Serialize)]
+    struct Payload<'a> {
+        message_ids: &'a [i64],
+        #[serde(skip_serializing_if = "Option::is_none")]
+        project_slug: Option<&'a str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        agent_name: Option<&'a str>,
+    }
+
+    let request = Request::post(&url)
+        .header("Content-Type", "application/json")
+        .json(&Payload {
+            message_ids,
+            project_slug: agent.map(|(project, _)| project),
+            agent_name: agent.map(|(_, name)| name),
+        })?;
+    let response = fetch::send(request).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to mark messages read: {}",
+            response.status()
+        )))
+    }
+}
+
+/// Export specific messages; returns the rendered file content.
+///
+/// `format` is one of json, html, md, csv, tsv, ndjson or mbox.
+pub async fn export_messages(message_ids: &[i64], format: &str) -> Result<String, ApiError> {
+    let url = format!("{}/api/export/messages", api_base_url());
+
+    #[derive(Serialize)]
+    struct Payload<'a> {
+        message_ids: &'a [i64],
+        format: &'a str,
+    }
+
+    let request = Request::post(&url)
+        .header("Content-Type", "application/json")
+        .json(&Payload {
+            message_ids,
+            format,
+        })?;
+    let response = fetch::send(request).await?;
+
+    if response.ok() {
+        Ok(response.text().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to export messages: {}",
+            response.status()
+        )))
+    }
+}
+
+/// Search hit (from POST /api/messages/search).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub id: i64,
+    pub subject: String,
+    pub sender_name: String,
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    pub snippet: String,
+    pub importance: String,
+    pub created_ts: String,
+}
+
+/// Page of search hits, newest first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchPage {
+    #[serde(default)]
+    pub items: Vec<SearchHit>,
+    #[serde(default)]
+    pub next_cursor: Option<i64>,
+    /// Matches across all pages
+    #[serde(default)]
+    pub total_estimate: i64,
+}
+
+/// Search a project's messages.
+///
+/// `query` is passed through as typed; the server parses field syntax such
+/// as `from:NAME subject:"TEXT" after:YYYY-MM-DD`. Pass the previous page's
+/// `next_cursor` as `cursor` for the next page.
+pub async fn search_messages(
+    project_slug: &str,
+    query: &str,
+    cursor: Option<i64>,
+) -> Result<SearchPage, ApiError> {
+    let url = format!("{}/api/messages/search", api_base_url());
+
+    #[derive(Serialize)]
+    struct Payload<'a> {
+        project_slug: &'a str,
+        query: &'a str,
+        limit: i64,
+        cursor: Option<i64>,
+    }
+
+    let request = Request::post(&url)
+        .header("Content-Type", "application/json")
+        .json(&Payload {
+            project_slug,
+            query,
+            limit: 50,
+            cursor,
+        })?;
+    let response = fetch::send(request).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to search: {}",
+            response.status()
+        )))
+    }
+}
+
+/// File reservation response from API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileReservationResponse {
+    pub id: i64,
+    pub agent_name: String,
+    pub path_pattern: String,
+    pub exclusive: bool,
+    pub reason: Option<String>,
+    pub created_ts: String,
+    pub expires_ts: Option<String>,
+    #[serde(default)]
+    pub expired: bool,
+}
+
+/// Get file reservations for a project.
+pub async fn get_file_reservations(
+    project_slug: &str,
+) -> Result<Vec<FileReservationResponse>, ApiError> {
+    let url = format!("{}/api/file_reservations/list", api_base_url());
+
+    #[derive(Serialize)]
+    struct Payload<'a> {
+        project_slug: &'a str,
+    }
+
+    let request = Request::post(&url)
+        .header("Content-Type", "application/json")
+        .json(&Payload { project_slug })?;
+    let response = fetch::send(request).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to get reservations: {}",
+            response.status()
+        )))
+    }
+}
+
+/// Active reservation on a project's reservation board.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActiveReservation {
+    pub id: i64,
+    pub agent_name: String,
+    pub path_pattern: String,
+    pub exclusive: bool,
+    #[serde(default)]
+    pub reason: String,
+    pub created_ts: String,
+    pub expires_ts: String,
+    pub age_seconds: i64,
+    pub expires_in_seconds: i64,
+    /// Other agents' reservations that overlap this one
+    #[serde(default)]
+    pub conflicts_with: Vec<i64>,
+}
+
+/// Response from `/api/project/{slug}/reservations`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectReservations {
+    pub project_slug: String,
+    pub reservations: Vec<ActiveReservation>,
+}
+
+/// Get a project's active reservations, soonest expiry first.
+pub async fn get_project_reservations(project_slug: &str) -> Result<ProjectReservations, ApiError> {
+    let url = format!(
+        "{}/api/project/{}/reservations",
+        api_base_url(),
+        urlencoding::encode(project_slug)
+    );
+    let response = fetch::get(&url).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to get reservations: {}",
+            response.status()
+        )))
+    }
+}
+
+/// Force release any agent's reservation in a project.
+pub async fn force_release_reservation(
+    project_slug: &str,
+    reservation_id: i64,
+) -> Result<(), ApiError> {
+    let url = format!(
+        "{}/api/project/{}/reservations/{}",
+        api_base_url(),
+        urlencoding::encode(project_slug),
+        reservation_id
+    );
+    let response = fetch::send(Request::delete(&url).build()?).await?;
+
+    if response.ok() {
+        Ok(())
+    } else {
+        let status = response.status();
+        let error_msg = match response.json::<BackendError>().await {
+            Ok(err) => err.message(),
+            Err(_) => format!("HTTP {}", status),
+        };
+        Err(ApiError::new(format!(
+            "Failed to release reservation: {}",
+            error_msg
+        )))
+    }
+}
+
+/// Payload for handing a reservation to another agent.
+#[derive(Debug, Clone, Serialize)]
+struct TransferReservationPayload<'a> {
+    to_agent: &'a str,
+    reason: &'a str,
+}
+
+/// Hand a live reservation in a project to another agent.
+pub async fn transfer_reservation(
+    project_slug: &str,
+    reservation_id: i64,
+    to_agent: &str,
+    reason: &str,
+) -> Result<(), ApiError> {
+    let url = format!(
+        "{}/api/project/{}/reservations/{}/transfer",
+        api_base_url(),
+        urlencoding::encode(project_slug),
+        reservation_id
+    );
+    let request = Request::post(&url)
+        .header("Content-Type", "application/json")
+        .json(&TransferReservationPayload { to_agent, reason })?;
+    let response = fetch::send(request).await?;
+
+    if response.ok() {
+        Ok(())
+    } else {
+        let status = response.status();
+        let error_msg = match response.json::<BackendError>().await {
+            Ok(err) => err.message(),
+            Err(_) => format!("HTTP {}", status),
+        };
+        Err(ApiError::new(format!(
+            "Failed to transfer reservation: {}",
+            error_msg
+        )))
+    }
+}
+
+/// Payload for snoozing a message for one recipient.
+#[derive(Debug, Clone, Serialize)]
+struct SnoozeMessagePayload<'a> {
+    project_slug: &'a str,
+    agent_name: &'a str,
+    message_id: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    until: Option<&'a str>,
+}
+
+/// Hide a message from an agent's inbox until `until` (UTC, no offset), or
+/// unsnooze it when `until` is `None`.
+pub async fn snooze_message(
+    project_slug: &str,
+    agent_name: &str,
+    message_id: i64,
+    until: Option<&str>,
+) -> Result<(), ApiError> {
+    let url = format!("{}/api/message/snooze", api_base_url());
+    let request = Request::post(&url)
+        .header("Content-Type", "application/json")
+        .json(&SnoozeMessagePayload {
+            project_slug,
+            agent_name,
+            message_id,
+            until,
+        })?;
+    let response = fetch::send(request).await?;
+
+    if response.ok() {
+        Ok(())
+    } else {
+        let status = response.status();
+        let error_msg = match response.json::<BackendError>().await {
+            Ok(err) => err.message(),
+            Err(_) => format!("HTTP {}", status),
+        };
+        Err(ApiError::new(format!(
+            "Failed to snooze message: {}",
+            error_msg
+        )))
+    }
+}
+
+/// Mark read response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkReadResponse {
+    pub success: bool,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Mark a message as read/unread.
+///
+/// # Arguments
+/// * `message_id` - The message ID to mark
+/// * `project_slug` - Project context
+/// * `agent_name` - Agent marking the message
+/// * `is_read` - True to mark as read, false to mark as unread
+pub async fn mark_read(
+    message_id: i64,
+    project_slug: &str,
     agent_name: &str,
     is_read: bool,
 ) -> Result<MarkReadResponse, ApiError> {
@@ -545,15 +1603,14 @@ pub async fn mark_read(
         is_read: bool,
     }
 
-    let response = Request::post(&url)
+    let request = Request::post(&url)
         .header("Content-Type", "application/json")
         .json(&Payload {
             project_slug,
             agent_name,
             is_read,
-        })?
-        .send()
-        .await?;
+        })?;
+    let response = fetch::send(request).await?;
 
     if response.ok() {
         Ok(MarkReadResponse {
@@ -561,12 +1618,327 @@ pub async fn mark_read(
             message: Some("Message read status updated".to_string()),
         })
     } else {
-        Err(ApiError {
-            message: format!("Failed to update read status: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to update read status: {}",
+            response.status()
+        )))
     }
 }
 
+// -- Unread Counts API --
+
+/// Unread count for one agent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentUnreadCount {
+    pub agent_id: i64,
+    pub agent_name: String,
+    pub unread: i64,
+}
+
+/// Unread counts for every agent in a project.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectUnreadCounts {
+    pub project_slug: String,
+    pub total: i64,
+    #[serde(default)]
+    pub agents: Vec<AgentUnreadCount>,
+}
+
+/// Unread counts across all projects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllUnreadCounts {
+    pub total: i64,
+    #[serde(default)]
+    pub projects: Vec<ProjectUnreadCounts>,
+}
+
+/// Get per-agent unread counts for a project.
+pub async fn get_unread_counts(project_slug: &str) -> Result<ProjectUnreadCounts, ApiError> {
+    let url = format!(
+        "{}/api/project/{}/unread-counts",
+        api_base_url(),
+        urlencoding::encode(project_slug)
+    );
+    let response = fetch::get(&url).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to get unread counts: {}",
+            response.status()
+        )))
+    }
+}
+
+/// Get unread counts for all projects (unified view).
+pub async fn get_all_unread_counts() -> Result<AllUnreadCounts, ApiError> {
+    let url = format!("{}/api/unread-counts", api_base_url());
+    let response = fetch::get(&url).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to get unread counts: {}",
+            response.status()
+        )))
+    }
+}
+
+// -- Notifications API --
+
+/// Notification center entry (from GET /api/notifications).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Notification {
+    /// Stable key, used for dismissal
+    pub key: String,
+    /// "ack_pending", "ack_requested", "urgent_unread" or "reservation_conflict"
+    pub kind: String,
+    pub project_slug: String,
+    pub title: String,
+    #[serde(default)]
+    pub detail: String,
+    #[serde(default)]
+    pub message_id: Option<i64>,
+    #[serde(default)]
+    pub thread_id: Option<String>,
+    #[serde(default)]
+    pub reservation_id: Option<i64>,
+    pub created_ts: String,
+}
+
+/// Response from GET /api/notifications.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationsResponse {
+    /// Agent name the notifications were computed for
+    pub actor: String,
+    #[serde(default)]
+    pub count: usize,
+    #[serde(default)]
+    pub notifications: Vec<Notification>,
+}
+
+/// Get the caller's undismissed notifications, newest first.
+pub async fn get_notifications() -> Result<NotificationsResponse, ApiError> {
+    let url = format!("{}/api/notifications", api_base_url());
+    let response = fetch::get(&url).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to get notifications: {}",
+            response.status()
+        )))
+    }
+}
+
+/// Hide notifications for the caller.
+pub async fn dismiss_notifications(keys: &[String]) -> Result<(), ApiError> {
+    let url = format!("{}/api/notifications/dismiss", api_base_url());
+    let request = Request::post(&url)
+        .header("Content-Type", "application/json")
+        .json(&serde_json::json!({ "keys": keys }))?;
+    let response = fetch::send(request).await?;
+
+    if response.ok() {
+        Ok(())
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to dismiss notifications: {}",
+            response.status()
+        )))
+    }
+}
+
+// -- Project Stats API --
+
+/// Activity figures for one project.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProjectStats {
+    pub project_slug: String,
+    #[serde(default)]
+    pub agent_count: i64,
+    #[serde(default)]
+    pub message_count: i64,
+    #[serde(default)]
+    pub messages_last_24h: i64,
+    #[serde(default)]
+    pub messages_last_7d: i64,
+    #[serde(default)]
+    pub active_reservations: i64,
+    #[serde(default)]
+    pub thread_count: i64,
+    #[serde(default)]
+    pub last_activity_ts: Option<String>,
+}
+
+/// Stats for all projects.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllProjectStats {
+    #[serde(default)]
+    pub projects: Vec<ProjectStats>,
+}
+
+/// Get stats for all projects (Projects page cards).
+pub async fn get_all_project_stats() -> Result<AllProjectStats, ApiError> {
+    let url = format!("{}/api/projects/stats", api_base_url());
+    let response = fetch::get(&url).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to get project stats: {}",
+            response.status()
+        )))
+    }
+}
+
+// -- Tool Metrics API --
+
+/// One tool's usage over a window.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolUsage {
+    pub tool_name: String,
+    #[serde(default)]
+    pub calls: i64,
+    #[serde(default)]
+    pub errors: i64,
+    #[serde(default)]
+    pub error_rate: f64,
+    #[serde(default)]
+    pub avg_duration_ms: f64,
+    #[serde(default)]
+    pub p50_ms: i64,
+    #[serde(default)]
+    pub p95_ms: i64,
+    #[serde(default)]
+    pub p99_ms: i64,
+    /// Calls per time bucket, oldest first
+    #[serde(default)]
+    pub buckets: Vec<i64>,
+}
+
+/// Per-tool usage since a point in time.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ToolUsageReport {
+    pub since: String,
+    #[serde(default)]
+    pub bucket_seconds: i64,
+    #[serde(default)]
+    pub total_calls: i64,
+    #[serde(default)]
+    pub total_errors: i64,
+    #[serde(default)]
+    pub error_rate: f64,
+    #[serde(default)]
+    pub tool_count: i64,
+    /// More tools were called than are listed
+    #[serde(default)]
+    pub truncated: bool,
+    #[serde(default)]
+    pub tools: Vec<ToolUsage>,
+}
+
+/// Get per-tool usage since an RFC 3339 timestamp (Stats page).
+pub async fn get_tool_usage(since: &str) -> Result<ToolUsageReport, ApiError> {
+    let url = format!(
+        "{}/api/metrics/tools?since={}",
+        api_base_url(),
+        urlencoding::encode(since)
+    );
+    let response = fetch::get(&url).await?;
+
+    if response.ok() {
+        Ok(response.json().await?)
+    } else {
+        Err(ApiError::new(format!(
+            "Failed to get tool usage: {}",
+            response.status()
+        )))
+    }
+}
+
+// -- Live Events API --
+
+/// Live mailbox event (from the GET /api/events SSE stream).
+///
+/// `kind` is one of `message.created`, `message.read`, `reservation.created`,
+/// `reservation.released`, or `resync` when the client fell behind and should
+/// refetch.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MailEvent {
+    #[serde(default)]
+    pub id: u64,
+    #[serde(rename = "type", default)]
+    pub kind: String,
+    #[serde(default)]
+    pub project_slug: String,
+    #[serde(default)]
+    pub data: serde_json::Value,
+}
+
+/// SSE event names sent by the server.
+const MAIL_EVENT_TYPES: [&str; 5] = [
+    "message.created",
+    "message.read",
+    "reservation.created",
+    "reservation.released",
+    "resync",
+];
+
+/// Get the live event stream URL, optionally filtered to one project.
+pub fn events_url(project_slug: Option<&str>) -> String {
+    match project_slug {
+        Some(slug) => format!(
+            "{}/api/events?project={}",
+            api_base_url(),
+            urlencoding::encode(slug)
+        ),
+        None => format!("{}/api/events", api_base_url()),
+    }
+}
+
+/// Subscribe to live mailbox events.
+///
+/// The browser reconnects on its own and resends `Last-Event-ID`, so missed
+/// events are replayed by the server. Call `close()` on the returned source
+/// when the subscriber unmounts.
+pub fn subscribe_events(
+    project_slug: Option<&str>,
+    on_event: impl Fn(MailEvent) + 'static,
+) -> Result<web_sys::EventSource, ApiError> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen::closure::Closure;
+
+    let source = web_sys::EventSource::new(&events_url(project_slug))
+        .map_err(|_| ApiError::new("Failed to open event stream".to_string()))?;
+
+    let on_event = std::rc::Rc::new(on_event);
+    for event_type in MAIL_EVENT_TYPES {
+        let on_event = on_event.clone();
+        let closure =
+            Closure::<dyn Fn(web_sys::MessageEvent)>::new(move |e: web_sys::MessageEvent| {
+                let raw = e.data().as_string().unwrap_or_default();
+                let mut event: MailEvent = serde_json::from_str(&raw).unwrap_or(MailEvent {
+                    id: 0,
+                    kind: String::new(),
+                    project_slug: String::new(),
+                    data: serde_json::Value::Null,
+                });
+                event.kind = e.type_();
+                on_event(event);
+            });
+        let _ =
+            source.add_event_listener_with_callback(event_type, closure.as_ref().unchecked_ref());
+        closure.forget(); // Lives as long as the EventSource
+    }
+
+    Ok(source)
+}
+
 // -- Attachments API --
 
 /// Attachment response from listing.
@@ -655,14 +2027,15 @@ pub async fn list_attachments(
         url.push_str(&format!("&agent_name={}", urlencoding::encode(agent)));
     }
 
-    let response = Request::get(&url).send().await?;
+    let response = fetch::get(&url).await?;
 
     if response.ok() {
         Ok(response.json().await?)
     } else {
-        Err(ApiError {
-            message: format!("Failed to list attachments: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to list attachments: {}",
+            response.status()
+        )))
     }
 }
 
@@ -748,28 +2121,30 @@ pub async fn get_archive_commits(limit: Option<usize>) -> Result<Vec<CommitSumma
         url.push_str(&format!("?limit={}", lim));
     }
 
-    let response = Request::get(&url).send().await?;
+    let response = fetch::get(&url).await?;
 
     if response.ok() {
         Ok(response.json().await?)
     } else {
-        Err(ApiError {
-            message: format!("Failed to get commits: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to get commits: {}",
+            response.status()
+        )))
     }
 }
 
 /// Get archive commit details.
 pub async fn get_archive_commit(sha: &str) -> Result<CommitDetails, ApiError> {
     let url = format!("{}/api/archive/commits/{}", api_base_url(), sha);
-    let response = Request::get(&url).send().await?;
+    let response = fetch::get(&url).await?;
 
     if response.ok() {
         Ok(response.json().await?)
     } else {
-        Err(ApiError {
-            message: format!("Failed to get commit: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to get commit: {}",
+            response.status()
+        )))
     }
 }
 
@@ -780,14 +2155,15 @@ pub async fn get_archive_files(sha: &str, path: Option<&str>) -> Result<Vec<File
         url.push_str(&format!("?path={}", urlencoding::encode(p)));
     }
 
-    let response = Request::get(&url).send().await?;
+    let response = fetch::get(&url).await?;
 
     if response.ok() {
         Ok(response.json().await?)
     } else {
-        Err(ApiError {
-            message: format!("Failed to list files: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to list files: {}",
+            response.status()
+        )))
     }
 }
 
@@ -799,27 +2175,29 @@ pub async fn get_archive_file_content(sha: &str, path: &str) -> Result<FileConte
         sha,
         urlencoding::encode(path)
     );
-    let response = Request::get(&url).send().await?;
+    let response = fetch::get(&url).await?;
 
     if response.ok() {
         Ok(response.json().await?)
     } else {
-        Err(ApiError {
-            message: format!("Failed to get file content: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to get file content: {}",
+            response.status()
+        )))
     }
 }
 
 /// Get archive activity summary.
 pub async fn get_archive_activity() -> Result<ActivitySummary, ApiError> {
     let url = format!("{}/api/archive/activity", api_base_url());
-    let response = Request::get(&url).send().await?;
+    let response = fetch::get(&url).await?;
 
     if response.ok() {
         Ok(response.json().await?)
     } else {
-        Err(ApiError {
-            message: format!("Failed to get activity: {}", response.status()),
-        })
+        Err(ApiError::new(format!(
+            "Failed to get activity: {}",
+            response.status()
+        )))
     }
 }
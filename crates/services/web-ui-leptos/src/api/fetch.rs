@@ -0,0 +1,362 @@
+//! Request plumbing shared by every API call.
+//!
+//! - [`api_base_url`] resolves the backend origin: a `window.MOUCHAK_API_BASE`
+//!   variable or `<meta name="mouchak-api-base">` tag injected by the server,
+//!   then the build-time `API_BASE_URL`, then the page's own origin.
+//! - [`send`] aborts a request after [`REQUEST_TIMEOUT`]; [`get`] also
+//!   retries once, after [`RETRY_BACKOFF`], when a GET fails on the network.
+//! - [`is_online`] tracks whether the last request reached the server, for
+//!   the layout's offline banner.
+//! - Each response's `X-Project-Version` header goes to
+//!   [`project_version::observe`].
+
+use std::future::Future;
+use std::time::Duration;
+
+use gloo_net::http::{Request, Response};
+use leptos::prelude::*;
+
+use super::client::{ApiError, ApiErrorKind};
+use super::project_version::{self, PROJECT_VERSION_HEADER};
+
+/// Window property holding the API origin.
+pub const API_BASE_GLOBAL: &str = "MOUCHAK_API_BASE";
+
+/// `name` of the `<meta>` tag holding the API origin.
+pub const API_BASE_META: &str = "mouchak-api-base";
+
+/// How long a request may take before it is aborted.
+pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Wait before retrying a GET that failed on the network.
+pub const RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Attempts per idempotent request, including the first.
+const MAX_ATTEMPTS: u32 = 2;
+
+/// Fallback when there is no window (non-WASM builds and tests).
+const DEFAULT_BASE_URL: &str = "http://127.0.0.1:8080";
+
+thread_local! {
+    static ONLINE: RwSignal<bool> = RwSignal::new(true);
+}
+
+/// Get the API base URL, without a trailing slash.
+pub fn api_base_url() -> String {
+    #[cfg(target_arch = "wasm32")]
+    {
+        let window = web_sys::window();
+        let global = window.as_ref().and_then(|w| {
+            web_sys::js_sys::Reflect::get(w, &API_BASE_GLOBAL.into())
+                .ok()
+                .and_then(|v| v.as_string())
+        });
+        let meta = window
+            .as_ref()
+            .and_then(|w| w.document())
+            .and_then(|d| {
+                d.query_selector(&format!("meta[name=\"{}\"]", API_BASE_META))
+                    .ok()
+                    .flatten()
+            })
+            .and_then(|el| el.get_attribute("content"));
+        let origin = window.and_then(|w| w.location().origin().ok());
+        resolve_base_url([
+            global,
+            meta,
+            option_env!("API_BASE_URL").map(str::to_string),
+            origin,
+        ])
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        resolve_base_url([option_env!("API_BASE_URL").map(str::to_string)])
+    }
+}
+
+/// First non-blank candidate, trailing slashes removed.
+fn resolve_base_url<const N: usize>(candidates: [Option<String>; N]) -> String {
+    candidates
+        .into_iter()
+        .flatten()
+        .map(|url| url.trim().trim_end_matches('/').to_string())
+        .find(|url| !url.is_empty())
+        .unwrap_or_else(|| DEFAULT_BASE_URL.to_string())
+}
+
+/// Whether the last request reached the server.
+///
+/// Starts `true`; turns `false` when a request fails with a network error
+/// or timeout, and back to `true` on the next response of any status.
+pub fn is_online() -> Signal<bool> {
+    ONLINE.with(|online| (*online).into())
+}
+
+/// Update [`is_online`] from a request outcome.
+fn record_connectivity<T>(result: &Result<T, ApiError>) {
+    let reached = !matches!(result, Err(e) if e.is_unreachable());
+    ONLINE.with(|online| {
+        if online.get_untracked() != reached {
+            online.set(reached);
+        }
+    });
+}
+
+/// GET `url`, retrying once on a network error.
+pub async fn get(url: &str) -> Result<Response, ApiError> {
+    let result = with_retry(
+        true,
+        || async { send_once(Request::get(url).build()?).await },
+        gloo_timers::future::sleep,
+    )
+    .await;
+    record_connectivity(&result);
+    record_project_version(&result);
+    result
+}
+
+/// Send `request` once, with the request timeout.
+pub async fn send(request: Request) -> Result<Response, ApiError> {
+    let result = send_once(request).await;
+    record_connectivity(&result);
+    record_project_version(&result);
+    result
+}
+
+/// Pass a response's project version, if any, on to cache invalidation.
+fn record_project_version(result: &Result<Response, ApiError>) {
+    if let Ok(response) = result {
+        project_version::observe(
+            &response.url(),
+            response.headers().get(PROJECT_VERSION_HEADER),
+        );
+    }
+}
+
+/// Whether a request that failed with `err` on attempt number `attempt`
+/// (counting from 1) should be tried again.
+///
+/// Only idempotent requests are retried, only once, and only when the
+/// request never reached the server; a timeout has already waited long
+/// enough and an error response would just repeat.
+fn should_retry(idempotent: bool, attempt: u32, err: &ApiError) -> bool {
+    idempotent && attempt < MAX_ATTEMPTS && err.kind == ApiErrorKind::Network
+}
+
+/// Backoff before retry number `attempt` (counting from 1), doubling each time.
+fn backoff(attempt: u32) -> Duration {
+    RETRY_BACKOFF * 2u32.saturating_pow(attempt.saturating_sub(1))
+}
+
+/// Run `attempt` until it succeeds or [`should_retry`] gives up, calling
+/// `sleep` between tries.
+async fn with_retry<T, A, AF, S, SF>(
+    idempotent: bool,
+    mut attempt: A,
+    mut sleep: S,
+) -> Result<T, ApiError>
+where
+    A: FnMut() -> AF,
+    AF: Future<Output = Result<T, ApiError>>,
+    S: FnMut(Duration) -> SF,
+    SF: Future<Output = ()>,
+{
+    let mut n = 1;
+    loop {
+        match attempt().await {
+            Err(e) if should_retry(idempotent, n, &e) => {
+                sleep(backoff(n)).await;
+                n += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Kind of error for a failed `fetch`, from the JavaScript error name.
+fn fetch_error_kind(name: &str) -> ApiErrorKind {
+    match name {
+        "AbortError" | "TimeoutError" => ApiErrorKind::Timeout,
+        _ => ApiErrorKind::Network,
+    }
+}
+
+/// Map an error from sending a request (rather than reading its response).
+fn send_error(err: gloo_net::Error) -> ApiError {
+    match err {
+        gloo_net::Error::JsError(js) => match fetch_error_kind(&js.name) {
+            ApiErrorKind::Timeout => ApiError::timeout(),
+            _ => ApiError::network(&js.message),
+        },
+        other => other.into(),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+async fn send_once(request: Request) -> Result<Response, ApiError> {
+    use futures::future::{Either, select};
+
+    let controller = web_sys::AbortController::new()
+        .map_err(|_| ApiError::new("Failed to create AbortController"))?;
+    let init = web_sys::RequestInit::new();
+    init.set_signal(Some(&controller.signal()));
+    let raw: web_sys::Request = request.into();
+    let request: Request = web_sys::Request::new_with_request_and_init(&raw, &init)
+        .map_err(|_| ApiError::new("Failed to prepare request"))?
+        .into();
+
+    let response = std::pin::pin!(request.send());
+    let timeout = std::pin::pin!(gloo_timers::future::sleep(REQUEST_TIMEOUT));
+    match select(response, timeout).await {
+        Either::Left((result, _)) => result.map_err(send_error),
+        Either::Right(((), _)) => {
+            controller.abort();
+            Err(ApiError::timeout())
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+async fn send_once(request: Request) -> Result<Response, ApiError> {
+    request.send().await.map_err(send_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::VecDeque;
+
+    /// A fetch layer that replays canned outcomes and records its calls.
+    struct MockFetch {
+        outcomes: RefCell<VecDeque<Result<&'static str, ApiError>>>,
+        calls: RefCell<u32>,
+        sleeps: RefCell<Vec<Duration>>,
+    }
+
+    impl MockFetch {
+        fn new(outcomes: Vec<Result<&'static str, ApiError>>) -> Self {
+            MockFetch {
+                outcomes: RefCell::new(outcomes.into()),
+                calls: RefCell::new(0),
+                sleeps: RefCell::new(Vec::new()),
+            }
+        }
+
+        fn run(&self, idempotent: bool) -> Result<&'static str, ApiError> {
+            futures::executor::block_on(with_retry(
+                idempotent,
+                || {
+                    *self.calls.borrow_mut() += 1;
+                    let outcome = self
+                        .outcomes
+                        .borrow_mut()
+                        .pop_front()
+                        .unwrap_or(Ok("fallback"));
+                    async move { outcome }
+                },
+                |delay| {
+                    self.sleeps.borrow_mut().push(delay);
+                    async {}
+                },
+            ))
+        }
+    }
+
+    #[test]
+    fn test_get_retries_once_after_network_error() {
+        let fetch = MockFetch::new(vec![Err(ApiError::network("refused")), Ok("body")]);
+        assert_eq!(fetch.run(true).ok(), Some("body"));
+        assert_eq!(*fetch.calls.borrow(), 2);
+        assert_eq!(*fetch.sleeps.borrow(), vec![RETRY_BACKOFF]);
+    }
+
+    #[test]
+    fn test_retry_gives_up_after_second_failure() {
+        let fetch = MockFetch::new(vec![
+            Err(ApiError::network("refused")),
+            Err(ApiError::network("refused again")),
+        ]);
+        let err = fetch.run(true).err();
+        assert_eq!(err.map(|e| e.kind), Some(ApiErrorKind::Network));
+        assert_eq!(*fetch.calls.borrow(), 2);
+    }
+
+    #[test]
+    fn test_no_retry_for_writes_timeouts_or_error_responses() {
+        for (idempotent, err) in [
+            (false, ApiError::network("refused")),
+            (true, ApiError::timeout()),
+            (true, ApiError::new("Failed to get projects: 500")),
+        ] {
+            let fetch = MockFetch::new(vec![Err(err.clone()), Ok("body")]);
+            assert_eq!(fetch.run(idempotent).err().map(|e| e.kind), Some(err.kind));
+            assert_eq!(*fetch.calls.borrow(), 1);
+            assert!(fetch.sleeps.borrow().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_should_retry_and_backoff() {
+        let network = ApiError::network("refused");
+        assert!(should_retry(true, 1, &network));
+        assert!(!should_retry(true, MAX_ATTEMPTS, &network));
+        assert!(!should_retry(false, 1, &network));
+        assert_eq!(backoff(1), RETRY_BACKOFF);
+        assert_eq!(backoff(2), RETRY_BACKOFF * 2);
+    }
+
+    #[test]
+    fn test_error_mapping() {
+        assert_eq!(fetch_error_kind("AbortError"), ApiErrorKind::Timeout);
+        assert_eq!(fetch_error_kind("TimeoutError"), ApiErrorKind::Timeout);
+        assert_eq!(fetch_error_kind("TypeError"), ApiErrorKind::Network);
+
+        let err = send_error(gloo_net::Error::GlooError("bad header".to_string()));
+        assert_eq!(err.kind, ApiErrorKind::Server);
+        assert!(!err.is_unreachable());
+
+        let serde_err = serde_json::from_str::<u32>("nope").unwrap_err();
+        let err: ApiError = gloo_net::Error::SerdeError(serde_err).into();
+        assert_eq!(err.kind, ApiErrorKind::Server);
+
+        assert!(ApiError::timeout().is_unreachable());
+        assert!(ApiError::timeout().message.contains("unreachable"));
+        assert!(ApiError::network("refused").is_unreachable());
+    }
+
+    #[test]
+    fn test_connectivity_follows_outcomes() {
+        assert!(is_online().get_untracked());
+        record_connectivity::<()>(&Err(ApiError::timeout()));
+        assert!(!is_online().get_untracked());
+        // Any answer from the server, even an error, means it is back
+        record_connectivity::<()>(&Err(ApiError::new("Failed: 404")));
+        assert!(is_online().get_untracked());
+        record_connectivity::<()>(&Err(ApiError::network("refused")));
+        assert!(!is_online().get_untracked());
+        record_connectivity(&Ok(()));
+        assert!(is_online().get_untracked());
+    }
+
+    #[test]
+    fn test_resolve_base_url() {
+        assert_eq!(
+            resolve_base_url([
+                None,
+                Some(" https://mail.example.com/ ".to_string()),
+                Some("https://origin.example.com".to_string()),
+            ]),
+            "https://mail.example.com"
+        );
+        assert_eq!(
+            resolve_base_url([
+                Some(String::new()),
+                Some("http://localhost:8765".to_string())
+            ]),
+            "http://localhost:8765"
+        );
+        assert_eq!(resolve_base_url::<1>([None]), DEFAULT_BASE_URL);
+    }
+}
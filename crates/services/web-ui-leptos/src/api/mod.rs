@@ -2,3 +2,5 @@
 //! Will be implemented in task mouchak-mail-rs-l0o.
 
 pub mod client;
+pub mod fetch;
+pub mod project_version;
@@ -0,0 +1,154 @@
+//! Cache invalidation from the `X-Project-Version` response header.
+//!
+//! The server bumps a project's version when the project is adopted, deleted
+//! or pruned, or one of its agents is renamed or retired, and reports it on
+//! responses for project-scoped paths. [`super::fetch`] hands every response
+//! to [`observe`]; pages that cache a project's lists track
+//! [`project_changes`] and reload when it moves.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use leptos::prelude::*;
+
+/// Response header carrying the version of the request's project.
+pub const PROJECT_VERSION_HEADER: &str = "x-project-version";
+
+/// Path segments after `/api/project/` or `/api/projects/` that name a route
+/// rather than a project.
+const NON_SLUG_SEGMENTS: &[&str] = &["ensure", "info", "siblings", "stats"];
+
+thread_local! {
+    /// Last version seen per project; not reactive, only compared against.
+    static VERSIONS: RefCell<HashMap<String, i64>> = RefCell::new(HashMap::new());
+    /// Times each project's version changed since the app loaded.
+    static CHANGES: RwSignal<HashMap<String, u64>> = RwSignal::new(HashMap::new());
+}
+
+/// Counter that moves whenever `slug`'s version changes.
+///
+/// Starts at 0. Read it in the effect that loads a project's lists so they
+/// are fetched again after a structural change.
+pub fn project_changes(slug: impl Into<String>) -> Signal<u64> {
+    let slug = slug.into();
+    let changes = CHANGES.with(|c| *c);
+    Signal::derive(move || changes.with(|c| c.get(&slug).copied().unwrap_or(0)))
+}
+
+/// Record the version header of a response to `url`.
+pub fn observe(url: &str, header: Option<String>) {
+    let (Some(slug), Some(version)) = (project_slug_from_url(url), parse_version(header)) else {
+        return;
+    };
+    let changed = VERSIONS.with(|v| is_invalidated(&mut v.borrow_mut(), &slug, version));
+    if changed {
+        CHANGES.with(|c| c.update(|c| *c.entry(slug).or_default() += 1));
+    }
+}
+
+/// Record `version` for `slug`; true if it replaces a different version, so
+/// anything cached for the project is stale.
+///
+/// The first version seen for a project only sets the baseline.
+fn is_invalidated(known: &mut HashMap<String, i64>, slug: &str, version: i64) -> bool {
+    match known.insert(slug.to_string(), version) {
+        Some(previous) => previous != version,
+        None => false,
+    }
+}
+
+fn parse_version(header: Option<String>) -> Option<i64> {
+    header?.trim().parse().ok()
+}
+
+/// Project a request URL is about, matching the server's rules: the slug in
+/// `/api/project/{slug}/...` or `/api/projects/{slug}[/...]`, else a
+/// `project_slug` or `project` query parameter.
+fn project_slug_from_url(url: &str) -> Option<String> {
+    let rest = url.split_once("/api/").map(|(_, rest)| rest)?;
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+    let mut segments = path.split('/');
+    let scope = segments.next();
+    let slug = segments.next().filter(|s| !s.is_empty());
+    let slug = match (scope, slug) {
+        (Some("project"), Some(slug)) if segments.next().is_some() => Some(slug),
+        (Some("projects"), Some(slug)) => Some(slug),
+        _ => None,
+    };
+    if let Some(slug) = slug.filter(|s| !NON_SLUG_SEGMENTS.contains(s)) {
+        return urlencoding::decode(slug).ok().map(|s| s.into_owned());
+    }
+
+    let param = |name: &str| {
+        query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find(|(key, value)| *key == name && !value.is_empty())
+            .and_then(|(_, value)| urlencoding::decode(value).ok())
+            .map(|s| s.into_owned())
+    };
+    param("project_slug").or_else(|| param("project"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_first_version_is_baseline() {
+        let mut known = HashMap::new();
+        assert!(!is_invalidated(&mut known, "alpha", 3));
+        assert!(!is_invalidated(&mut known, "alpha", 3));
+        assert!(is_invalidated(&mut known, "alpha", 4));
+        assert!(!is_invalidated(&mut known, "alpha", 4));
+        // Other projects are tracked separately
+        assert!(!is_invalidated(&mut known, "beta", 9));
+        assert_eq!(known.get("alpha"), Some(&4));
+    }
+
+    #[test]
+    fn test_observe_bumps_changes_on_new_version() {
+        let url = "http://localhost:8765/api/projects/gamma/agents";
+        let changes = project_changes("gamma");
+        observe(url, Some("1".to_string()));
+        assert_eq!(changes.get_untracked(), 0);
+        observe(url, Some("1".to_string()));
+        observe(url, None);
+        assert_eq!(changes.get_untracked(), 0);
+        observe(
+            "http://localhost:8765/api/project/gamma/threads",
+            Some("2".to_string()),
+        );
+        assert_eq!(changes.get_untracked(), 1);
+        assert_eq!(project_changes("other").get_untracked(), 0);
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version(Some("7".to_string())), Some(7));
+        assert_eq!(parse_version(Some(" 7 ".to_string())), Some(7));
+        assert_eq!(parse_version(Some("seven".to_string())), None);
+        assert_eq!(parse_version(None), None);
+    }
+
+    #[test]
+    fn test_project_slug_from_url() {
+        let base = "http://localhost:8765";
+        let slug = |path: &str| project_slug_from_url(&format!("{}{}", base, path));
+        assert_eq!(slug("/api/projects/my-proj/agents"), Some("my-proj".into()));
+        assert_eq!(slug("/api/projects/my-proj"), Some("my-proj".into()));
+        assert_eq!(
+            slug("/api/project/my-proj/threads?cursor=abc"),
+            Some("my-proj".into())
+        );
+        assert_eq!(slug("/api/project/info"), None);
+        assert_eq!(slug("/api/projects/stats"), None);
+        assert_eq!(slug("/api/projects"), None);
+        assert_eq!(
+            slug("/api/events?project=my%20proj"),
+            Some("my proj".into())
+        );
+        assert_eq!(slug("/api/unified-inbox?limit=5"), None);
+    }
+}
@@ -4,34 +4,46 @@ use leptos::prelude::*;
 use leptos_router::components::*;
 use leptos_router::path;
 
-use crate::components::Layout;
+use crate::components::{Layout, ThemeProvider, Toaster};
 use crate::pages::*;
+use crate::utils::permalink::{message_route, thread_route};
 
 /// Root application component with all routes.
 #[component]
 pub fn App() -> impl IntoView {
     view! {
-        <Router>
-            <Routes fallback=|| view! { <NotFound /> }>
-                <ParentRoute path=path!("") view=Layout>
-                    <Route path=path!("") view=Dashboard />
-                    <Route path=path!("projects") view=Projects />
-                    <Route path=path!("projects/:slug") view=ProjectDetail />
-                    <Route path=path!("projects/:slug/file-reservations") view=FileReservations />
-                    <Route path=path!("agents") view=Agents />
-                    <Route path=path!("attachments") view=Attachments />
-                    <Route path=path!("inbox") view=Inbox />
-                    <Route path=path!("inbox/:id") view=MessageDetail />
-                    <Route path=path!("mail") view=UnifiedInbox />
-                    <Route path=path!("mail/unified") view=UnifiedInbox />
-                    <Route path=path!("mail/unified-inbox") view=UnifiedInbox />
-                    <Route path=path!("thread/:id") view=ThreadView />
-                    <Route path=path!("search") view=Search />
-                    <Route path=path!("archive") view=ArchiveBrowser />
-                </ParentRoute>
+        <ThemeProvider>
+            <Toaster>
+                <Router>
+                    <Routes fallback=|| view! { <NotFound /> }>
+                        <ParentRoute path=path!("") view=Layout>
+                            <Route path=path!("") view=Dashboard />
+                            <Route path=path!("projects") view=Projects />
+                            <Route path=path!("projects/:slug") view=ProjectDetail />
+                            <Route path=path!("projects/:slug/file-reservations") view=FileReservations />
+                            <Route path=path!("projects/:slug/agents/:name") view=AgentDetail />
+                            <Route path=path!("agents") view=Agents />
+                            <Route path=path!("attachments") view=Attachments />
+                            <Route path=path!("inbox") view=Inbox />
+                            <Route path=path!("inbox/:id") view=LegacyMessageDetail />
+                            <Route path=message_route() view=MessageDetail />
+                            <Route path=path!("sent") view=Sent />
+                            <Route path=path!("mail") view=UnifiedInbox />
+                            <Route path=path!("mail/unified") view=UnifiedInbox />
+                            <Route path=path!("mail/unified-inbox") view=UnifiedInbox />
+                            <Route path=path!("threads") view=Threads />
+                            <Route path=path!("thread/:id") view=LegacyThreadView />
+                            <Route path=thread_route() view=ThreadView />
+                            <Route path=path!("search") view=Search />
+                            <Route path=path!("archive") view=ArchiveBrowser />
+                            <Route path=path!("reservations") view=Reservations />
+                            <Route path=path!("stats") view=Stats />
+                        </ParentRoute>
 
-            </Routes>
-        </Router>
+                    </Routes>
+                </Router>
+            </Toaster>
+        </ThemeProvider>
     }
 }
 
@@ -69,6 +81,8 @@ mod tests {
             "mail/unified-inbox",
             "thread/:id",
             "search",
+            "reservations",
+            "stats",
         ];
 
         assert!(routes.contains(&"mail"));
@@ -76,5 +90,7 @@ mod tests {
         assert!(routes.contains(&"mail/unified-inbox"));
         assert!(routes.contains(&"attachments"));
         assert!(routes.contains(&"search"));
+        assert!(routes.contains(&"reservations"));
+        assert!(routes.contains(&"stats"));
     }
 }
@@ -0,0 +1,393 @@
+//! Bulk actions for a multi-selected message list.
+//!
+//! Selection helpers used by [`SplitViewLayout`](super::SplitViewLayout)
+//! checkboxes (single toggle, Shift-click ranges, select all visible) and the
+//! [`BulkActionBar`] shown while anything is selected: mark read, export the
+//! selection, and copy links to the selected threads.
+
+use crate::api::client::{self, UnifiedInboxMessage};
+use crate::components::{Button, ButtonSize, ButtonVariant, Select, SelectIcon, SelectOption};
+use leptos::prelude::*;
+
+/// Export formats offered for a selection: (value, label, extension, MIME type).
+const EXPORT_FORMATS: &[(&str, &str, &str, &str)] = &[
+    ("md", "Markdown", "md", "text/markdown"),
+    ("json", "JSON", "json", "application/json"),
+    ("csv", "CSV", "csv", "text/csv"),
+    ("tsv", "TSV", "tsv", "text/tab-separated-values"),
+    ("html", "HTML", "html", "text/html"),
+    ("ndjson", "NDJSON", "ndjson", "application/x-ndjson"),
+    ("mbox", "mbox", "mbox", "application/mbox"),
+];
+
+/// Selection after checking or unchecking `id`.
+///
+/// With an `anchor` (the previously clicked row, for Shift-click) every
+/// visible message between the two takes the new state; otherwise only `id`
+/// changes. The result keeps list order.
+pub fn apply_check(
+    selected: &[i64],
+    visible: &[i64],
+    anchor: Option<i64>,
+    id: i64,
+    checked: bool,
+) -> Vec<i64> {
+    let target = visible.iter().position(|v| *v == id);
+    let anchor = anchor.and_then(|a| visible.iter().position(|v| *v == a));
+    let range: Vec<i64> = match (anchor, target) {
+        (Some(a), Some(t)) => visible[a.min(t)..=a.max(t)].to_vec(),
+        _ => vec![id],
+    };
+    let keep = |v: &i64| {
+        if range.contains(v) {
+            checked
+        } else {
+            selected.contains(v)
+        }
+    };
+    let mut next: Vec<i64> = visible.iter().copied().filter(keep).collect();
+    // Selected ids that are not visible (e.g. just scrolled away) survive
+    next.extend(selected.iter().filter(|v| !visible.contains(v)));
+    if checked && target.is_none() && !next.contains(&id) {
+        next.push(id);
+    }
+    next
+}
+
+/// Select or clear every visible message.
+pub fn select_all_visible(visible: &[i64], checked: bool) -> Vec<i64> {
+    if checked {
+        visible.to_vec()
+    } else {
+        Vec::new()
+    }
+}
+
+/// Whether (all, some) of the visible messages are selected.
+pub fn selection_state(selected: &[i64], visible: &[i64]) -> (bool, bool) {
+    let count = visible.iter().filter(|v| selected.contains(v)).count();
+    (count > 0 && count == visible.len(), count > 0)
+}
+
+/// Drop selected ids that are no longer in the list.
+pub fn retain_visible(selected: &[i64], visible: &[i64]) -> Vec<i64> {
+    selected
+        .iter()
+        .copied()
+        .filter(|id| visible.contains(id))
+        .collect()
+}
+
+/// Links to the selected messages' threads, one per thread.
+///
+/// Messages without a thread link to the message itself.
+pub fn thread_links(
+    origin: &str,
+    messages: &[UnifiedInboxMessage],
+    selected: &[i64],
+) -> Vec<String> {
+    let mut links = Vec::new();
+    for msg in messages.iter().filter(|m| selected.contains(&m.id)) {
+        let project = urlencoding::encode(&msg.project_slug);
+        let link = match &msg.thread_id {
+            Some(thread) if !thread.is_empty() => format!(
+                "{}/thread/{}?project={}",
+                origin,
+                urlencoding::encode(thread),
+                project
+            ),
+            _ => format!("{}/inbox/{}?project={}", origin, msg.id, project),
+        };
+        if !links.contains(&link) {
+            links.push(link);
+        }
+    }
+    links
+}
+
+/// File extension and MIME type for an export format value.
+fn export_file_type(format: &str) -> (&'static str, &'static str) {
+    EXPORT_FORMATS
+        .iter()
+        .find(|(value, ..)| *value == format)
+        .map(|(_, _, ext, mime)| (*ext, *mime))
+        .unwrap_or(("json", "application/json"))
+}
+
+/// Window origin for building absolute links
+#[cfg(target_arch = "wasm32")]
+fn window_origin() -> String {
+    web_sys::window()
+        .and_then(|w| w.location().origin().ok())
+        .unwrap_or_default()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn window_origin() -> String {
+    String::new()
+}
+
+/// Copy text to the clipboard
+#[cfg(target_arch = "wasm32")]
+fn copy_to_clipboard(text: &str) {
+    if let Some(window) = web_sys::window() {
+        let _ = window.navigator().clipboard().write_text(text);
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn copy_to_clipboard(_text: &str) {}
+
+/// Save `content` as a file via a `data:` URL on a temporary link
+#[cfg(target_arch = "wasm32")]
+fn download_text(filename: &str, mime: &str, content: &str) {
+    use wasm_bindgen::JsCast;
+
+    if let Some(anchor) = web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.create_element("a").ok())
+        .and_then(|el| el.dyn_into::<web_sys::HtmlAnchorElement>().ok())
+    {
+        anchor.set_href(&format!(
+            "data:{};charset=utf-8,{}",
+            mime,
+            urlencoding::encode(content)
+        ));
+        anchor.set_download(filename);
+        anchor.click();
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn download_text(_filename: &str, _mime: &str, _content: &str) {}
+
+/// Action bar for the current selection.
+///
+/// # Props
+/// - `selection`: Selected message ids (cleared by the bar's Clear button)
+/// - `messages`: Loaded messages, used to build thread links
+#[component]
+pub fn BulkActionBar(
+    /// Selected message ids
+    selection: RwSignal<Vec<i64>>,
+    /// Messages currently loaded
+    #[prop(into)]
+    messages: Signal<Vec<UnifiedInboxMessage>>,
+) -> impl IntoView {
+    let busy = RwSignal::new(false);
+    let status = RwSignal::new(Option::<(bool, String)>::None);
+    let export_format = RwSignal::new("md".to_string());
+
+    // Show a result for a few seconds
+    let report = move |ok: bool, text: String| {
+        status.set(Some((ok, text)));
+        leptos::task::spawn_local(async move {
+            gloo_timers::future::TimeoutFuture::new(4000).await;
+            status.set(None);
+        });
+    };
+
+    let mark_read = Callback::new(move |_| {
+        let ids = selection.get_untracked();
+        busy.set(true);
+        leptos::task::spawn_local(async move {
+            match client::mark_read_batch(&ids, None).await {
+                Ok(res) => report(
+                    true,
+                    format!("Marked {} of {} read", res.marked.len(), ids.len()),
+                ),
+                Err(e) => report(false, e.message),
+            }
+            busy.set(false);
+        });
+    });
+
+    let export = Callback::new(move |_| {
+        let ids = selection.get_untracked();
+        let format = export_format.get_untracked();
+        busy.set(true);
+        leptos::task::spawn_local(async move {
+            match client::export_messages(&ids, &format).await {
+                Ok(content) => {
+                    let (ext, mime) = export_file_type(&format);
+                    download_text(&format!("selected_messages.{}", ext), mime, &content);
+                    report(true, format!("Exported {} messages", ids.len()));
+                }
+                Err(e) => report(false, e.message),
+            }
+            busy.set(false);
+        });
+    });
+
+    let copy_links = Callback::new(move |_| {
+        let links = messages.with_untracked(|msgs| {
+            thread_links(&window_origin(), msgs, &selection.get_untracked())
+        });
+        copy_to_clipboard(&links.join("\n"));
+        report(
+            true,
+            format!(
+                "Copied {} link{}",
+                links.len(),
+                if links.len() == 1 { "" } else { "s" }
+            ),
+        );
+    });
+
+    let format_options: Vec<SelectOption> = EXPORT_FORMATS
+        .iter()
+        .map(|(value, label, ..)| SelectOption::new(*value, *label))
+        .collect();
+    let disabled = Signal::derive(move || busy.get());
+
+    view! {
+        <div
+            class="flex flex-wrap items-center gap-3 p-3 rounded-lg border border-primary/30 bg-primary/5 animate-slide-up"
+            role="toolbar"
+            aria-label="Bulk actions"
+        >
+            <span class="text-sm font-medium text-foreground tabular-nums">
+                {move || format!("{} selected", selection.get().len())}
+            </span>
+
+            <Button variant=ButtonVariant::Secondary size=ButtonSize::Sm on_click=mark_read disabled=disabled>
+                <i data-lucide="mail-open" class="icon-sm"></i>
+                <span>"Mark read"</span>
+            </Button>
+
+            <div class="flex items-center gap-2">
+                <div class="w-36">
+                    <Select
+                        id="bulkExportFormat".to_string()
+                        options=format_options
+                        value=export_format
+                        placeholder="Format".to_string()
+                        icon=SelectIcon::Tag
+                    />
+                </div>
+                <Button variant=ButtonVariant::Secondary size=ButtonSize::Sm on_click=export disabled=disabled>
+                    <i data-lucide="download" class="icon-sm"></i>
+                    <span>"Export selected"</span>
+                </Button>
+            </div>
+
+            <Button variant=ButtonVariant::Secondary size=ButtonSize::Sm on_click=copy_links>
+                <i data-lucide="link" class="icon-sm"></i>
+                <span>"Copy thread links"</span>
+            </Button>
+
+            {move || status.get().map(|(ok, text)| view! {
+                <span
+                    class=if ok { "text-sm text-teal-600 dark:text-teal-400" } else { "text-sm text-destructive" }
+                    role="status"
+                >
+                    {text}
+                </span>
+            })}
+
+            <Button
+                variant=ButtonVariant::Ghost
+                size=ButtonSize::Sm
+                class="ml-auto".to_string()
+                on_click=Callback::new(move |_| selection.set(Vec::new()))
+                aria_label="Clear selection".to_string()
+            >
+                <i data-lucide="x" class="icon-sm"></i>
+                <span>"Clear"</span>
+            </Button>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn msg(id: i64, project: &str, thread: Option<&str>) -> UnifiedInboxMessage {
+        UnifiedInboxMessage {
+            id,
+            project_id: 1,
+            project_slug: project.to_string(),
+            sender_id: 1,
+            sender_name: "BlueLake".to_string(),
+            subject: "Subject".to_string(),
+            importance: "normal".to_string(),
+            ack_required: false,
+            created_ts: "2026-01-01T00:00:00".to_string(),
+            thread_id: thread.map(str::to_string),
+            muted: false,
+            permalink: None,
+        }
+    }
+
+    #[test]
+    fn test_single_toggle() {
+        let visible = [1, 2, 3];
+        assert_eq!(apply_check(&[], &visible, None, 2, true), vec![2]);
+        assert_eq!(apply_check(&[2, 3], &visible, None, 2, false), vec![3]);
+    }
+
+    #[test]
+    fn test_shift_click_range_in_either_direction() {
+        let visible = [10, 20, 30, 40, 50];
+        assert_eq!(
+            apply_check(&[20], &visible, Some(20), 40, true),
+            vec![20, 30, 40]
+        );
+        assert_eq!(
+            apply_check(&[], &visible, Some(50), 30, true),
+            vec![30, 40, 50]
+        );
+        // Unchecking a range leaves the rest alone
+        assert_eq!(
+            apply_check(&[10, 20, 30, 40, 50], &visible, Some(20), 40, false),
+            vec![10, 50]
+        );
+    }
+
+    #[test]
+    fn test_stale_anchor_falls_back_to_single() {
+        assert_eq!(apply_check(&[], &[1, 2, 3], Some(99), 3, true), vec![3]);
+    }
+
+    #[test]
+    fn test_select_all_and_state() {
+        let visible = [1, 2, 3];
+        let all = select_all_visible(&visible, true);
+        assert_eq!(selection_state(&all, &visible), (true, true));
+        assert_eq!(selection_state(&[2], &visible), (false, true));
+        assert_eq!(selection_state(&[], &visible), (false, false));
+        assert_eq!(selection_state(&[], &[]), (false, false));
+        assert!(select_all_visible(&visible, false).is_empty());
+    }
+
+    #[test]
+    fn test_retain_visible() {
+        assert_eq!(retain_visible(&[1, 4, 3], &[1, 2, 3]), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_thread_links_dedupe_threads() {
+        let messages = vec![
+            msg(1, "proj", Some("t-1")),
+            msg(2, "proj", Some("t-1")),
+            msg(3, "other proj", None),
+            msg(4, "proj", Some("t-2")),
+        ];
+        let links = thread_links("https://mail.test", &messages, &[1, 2, 3]);
+        assert_eq!(
+            links,
+            vec![
+                "https://mail.test/thread/t-1?project=proj".to_string(),
+                "https://mail.test/inbox/3?project=other%20proj".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_export_file_type() {
+        assert_eq!(export_file_type("md"), ("md", "text/markdown"));
+        assert_eq!(export_file_type("csv"), ("csv", "text/csv"));
+        assert_eq!(export_file_type("unknown"), ("json", "application/json"));
+    }
+}
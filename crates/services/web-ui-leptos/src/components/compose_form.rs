@@ -0,0 +1,340 @@
+//! Compose form state shared by every composer.
+//!
+//! [`use_compose_form`] owns the form signals, prefills replies and restores
+//! and autosaves the draft; [`ComposeForm::submit`] validates and sends. The
+//! compose dialog and the inline reply both build on it, so they validate,
+//! persist drafts and report rejected recipients the same way.
+
+use super::{ForwardOf, ReplyTo};
+use crate::api::client::{self, RecipientFailure};
+use crate::utils::{DraftFields, DraftHandle, use_compose_draft};
+use leptos::prelude::*;
+
+/// Subject of a reply to `subject`, without stacking `Re:` prefixes.
+pub fn reply_subject(subject: &str) -> String {
+    let mut rest = subject.trim();
+    while rest
+        .get(..3)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("re:"))
+    {
+        rest = rest[3..].trim_start();
+    }
+    format!("Re: {}", rest)
+}
+
+/// Subject of a forward of `subject`, without stacking `Fwd:` prefixes.
+pub fn forward_subject(subject: &str) -> String {
+    let mut rest = subject.trim();
+    while rest
+        .get(..4)
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case("fwd:"))
+    {
+        rest = rest[4..].trim_start();
+    }
+    format!("Fwd: {}", rest)
+}
+
+/// Body of a forward: room for a note, then the message quoted under its
+/// sender, date and subject.
+///
+/// Recipients are left out since the detail view can't tell BCC from To.
+pub fn forward_body(forward: &ForwardOf) -> String {
+    let mut body = String::from("\n\n> ---------- Forwarded message ----------\n");
+    body.push_str(&format!("> **From:** {}\n", forward.sender_name));
+    body.push_str(&format!(
+        "> **Date:** {} UTC\n",
+        forward.created_ts.replacen('T', " ", 1)
+    ));
+    body.push_str(&format!("> **Subject:** {}\n>\n", forward.subject));
+    for line in forward.body_md.lines() {
+        if line.is_empty() {
+            body.push_str(">\n");
+        } else {
+            body.push_str("> ");
+            body.push_str(line);
+            body.push('\n');
+        }
+    }
+    body
+}
+
+/// Draft context for a composer: new messages per sender, replies per sender
+/// and thread, forwards per sender and message.
+pub fn draft_context(
+    sender_name: &str,
+    reply_to: Option<&ReplyTo>,
+    forward: Option<&ForwardOf>,
+) -> String {
+    match (reply_to, forward) {
+        (Some(reply), _) => format!(
+            "reply:{}:{}",
+            sender_name,
+            reply.thread_id.clone().unwrap_or_default()
+        ),
+        (None, Some(forward)) => format!("forward:{}:{}", sender_name, forward.message_id),
+        (None, None) => format!("compose:{}", sender_name),
+    }
+}
+
+/// Why a form with these values can't be sent yet.
+pub fn validate_compose(
+    recipients: &[String],
+    recipients_valid: bool,
+    subject: &str,
+    body: &str,
+) -> Result<(), &'static str> {
+    if recipients.is_empty() {
+        return Err("Please select at least one recipient");
+    }
+    if !recipients_valid {
+        return Err("Remove unknown recipients before sending");
+    }
+    if subject.trim().is_empty() {
+        return Err("Please enter a subject");
+    }
+    if body.trim().is_empty() {
+        return Err("Please enter a message body");
+    }
+    Ok(())
+}
+
+/// Signals and draft of a mounted composer.
+#[derive(Clone)]
+pub struct ComposeForm {
+    pub project_slug: String,
+    pub sender_name: String,
+    pub recipients: RwSignal<Vec<String>>,
+    pub subject: RwSignal<String>,
+    pub body: RwSignal<String>,
+    pub importance: RwSignal<String>,
+    pub ack_required: RwSignal<bool>,
+    pub thread_id: RwSignal<String>,
+    /// Set by the recipient picker while every recipient is a known agent
+    pub recipients_valid: RwSignal<bool>,
+    /// Recipients the server refused on the last attempt, flagged on their chips
+    pub rejected: RwSignal<Vec<RecipientFailure>>,
+    pub sending: RwSignal<bool>,
+    pub error: RwSignal<Option<String>>,
+    pub draft: DraftHandle,
+}
+
+impl ComposeForm {
+    /// Whether the send button should be disabled.
+    pub fn cannot_send(&self) -> bool {
+        self.sending.get() || self.recipients.get().is_empty() || !self.recipients_valid.get()
+    }
+
+    /// Validate and send; on success the draft is removed and `on_sent` runs.
+    pub fn submit(&self, on_sent: Callback<()>) {
+        if self.sending.get_untracked() {
+            return;
+        }
+        let recipients = self.recipients.get_untracked();
+        let subject = self.subject.get_untracked();
+        let body = self.body.get_untracked();
+        if let Err(e) = validate_compose(
+            &recipients,
+            self.recipients_valid.get_untracked(),
+            &subject,
+            &body,
+        ) {
+            self.error.set(Some(e.to_string()));
+            return;
+        }
+
+        self.sending.set(true);
+        self.error.set(None);
+        self.rejected.set(Vec::new());
+
+        let form = self.clone();
+        let thread_id = self.thread_id.get_untracked();
+        let importance = self.importance.get_untracked();
+        let ack_required = self.ack_required.get_untracked();
+
+        leptos::task::spawn_local(async move {
+            match client::send_message(
+                &form.project_slug,
+                &form.sender_name,
+                &recipients,
+                &subject,
+                &body,
+                (!thread_id.is_empty()).then_some(thread_id.as_str()),
+                &importance,
+                ack_required,
+                false,
+            )
+            .await
+            {
+                Ok(_) => {
+                    form.draft.finish();
+                    on_sent.run(());
+                }
+                Err(e) if !e.failed.is_empty() => {
+                    form.rejected.set(e.failed);
+                    form.sending.set(false);
+                }
+                Err(e) => {
+                    form.error.set(Some(e.error.message));
+                    form.sending.set(false);
+                }
+            }
+        });
+    }
+}
+
+/// Create the form for `sender_name` in `project_slug`, prefilled from
+/// `reply_to` or `forward`, and restore or keep saving its draft.
+pub fn use_compose_form(
+    project_slug: &str,
+    sender_name: &str,
+    reply_to: Option<&ReplyTo>,
+    forward: Option<&ForwardOf>,
+) -> ComposeForm {
+    let recipients = RwSignal::new(Vec::<String>::new());
+    let subject = RwSignal::new(String::new());
+    let thread_id = RwSignal::new(String::new());
+    if let Some(reply) = reply_to {
+        recipients.set(reply.recipient_names.clone());
+        subject.set(reply_subject(&reply.subject));
+        if let Some(ref tid) = reply.thread_id {
+            thread_id.set(tid.clone());
+        }
+    }
+    let body = RwSignal::new(String::new());
+    if let Some(forward) = forward {
+        subject.set(forward_subject(&forward.subject));
+        body.set(forward_body(forward));
+    }
+    let importance = RwSignal::new("normal".to_string());
+
+    let draft = use_compose_draft(
+        project_slug,
+        &draft_context(sender_name, reply_to, forward),
+        DraftFields {
+            recipients,
+            subject,
+            body,
+            importance,
+            thread_id,
+        },
+    );
+
+    ComposeForm {
+        project_slug: project_slug.to_string(),
+        sender_name: sender_name.to_string(),
+        recipients,
+        subject,
+        body,
+        importance,
+        ack_required: RwSignal::new(false),
+        thread_id,
+        recipients_valid: RwSignal::new(false),
+        rejected: RwSignal::new(Vec::new()),
+        sending: RwSignal::new(false),
+        error: RwSignal::new(None),
+        draft,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reply_subject() {
+        assert_eq!(reply_subject("Status"), "Re: Status");
+        assert_eq!(reply_subject("Re: Status"), "Re: Status");
+        assert_eq!(reply_subject("re: RE:Status "), "Re: Status");
+        assert_eq!(
+            reply_subject("Regarding the build"),
+            "Re: Regarding the build"
+        );
+        assert_eq!(reply_subject("日本語"), "Re: 日本語");
+    }
+
+    #[test]
+    fn test_draft_context() {
+        let reply = ReplyTo {
+            thread_id: Some("TKT-1".to_string()),
+            subject: "Status".to_string(),
+            recipient_names: vec!["BlueLake".to_string()],
+        };
+        let forward = forward_of("BlueLake", "Status", "Green");
+        assert_eq!(
+            draft_context("GreenCastle", None, None),
+            "compose:GreenCastle"
+        );
+        assert_eq!(
+            draft_context("GreenCastle", Some(&reply), None),
+            "reply:GreenCastle:TKT-1"
+        );
+        assert_eq!(
+            draft_context("GreenCastle", None, Some(&forward)),
+            "forward:GreenCastle:42"
+        );
+    }
+
+    fn forward_of(sender: &str, subject: &str, body: &str) -> ForwardOf {
+        ForwardOf {
+            message_id: 42,
+            sender_name: sender.to_string(),
+            created_ts: "2025-12-18T10:30:00".to_string(),
+            subject: subject.to_string(),
+            body_md: body.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_forward_subject() {
+        assert_eq!(forward_subject("Status"), "Fwd: Status");
+        assert_eq!(forward_subject("Fwd: Status"), "Fwd: Status");
+        assert_eq!(forward_subject("FWD: fwd:Status "), "Fwd: Status");
+        assert_eq!(forward_subject("Re: Status"), "Fwd: Re: Status");
+    }
+
+    #[test]
+    fn test_forward_body() {
+        let body = forward_body(&forward_of(
+            "BlueLake",
+            "Status",
+            "Build is green.\n\nShip it.",
+        ));
+        assert_eq!(
+            body,
+            "\n\n> ---------- Forwarded message ----------\n\
+             > **From:** BlueLake\n\
+             > **Date:** 2025-12-18 10:30:00 UTC\n\
+             > **Subject:** Status\n\
+             >\n\
+             > Build is green.\n\
+             >\n\
+             > Ship it.\n"
+        );
+
+        // Forwarding a forward nests the earlier quote
+        let nested = forward_body(&forward_of("GreenCastle", "Fwd: Status", &body));
+        assert!(nested.contains("> > **From:** BlueLake\n"));
+    }
+
+    #[test]
+    fn test_validate_compose() {
+        let to = vec!["BlueLake".to_string()];
+        assert_eq!(validate_compose(&to, true, "Hi", "Body"), Ok(()));
+        assert_eq!(
+            validate_compose(&[], true, "Hi", "Body"),
+            Err("Please select at least one recipient")
+        );
+        assert_eq!(
+            validate_compose(&to, false, "Hi", "Body"),
+            Err("Remove unknown recipients before sending")
+        );
+        assert_eq!(
+            validate_compose(&to, true, "  ", "Body"),
+            Err("Please enter a subject")
+        );
+        assert_eq!(
+            validate_compose(&to, true, "Hi", "\n"),
+            Err("Please enter a message body")
+        );
+    }
+}
@@ -1,7 +1,8 @@
 //! ComposeMessage modal component.
 
-use super::{Button, ButtonVariant, Input, Select, SelectOption};
-use crate::api::client::{self, Agent};
+use super::compose_form::{ComposeForm, use_compose_form};
+use super::{Button, ButtonVariant, Input, RecipientPicker, Select, SelectOption};
+use crate::api::client::{Agent, Message};
 use leptos::prelude::*;
 
 /// Props for ComposeMessage component.
@@ -11,13 +12,37 @@ pub struct ComposeProps {
     pub sender_name: String,
     pub agents: Vec<Agent>,
     pub reply_to: Option<ReplyTo>,
+    pub forward: Option<ForwardOf>,
 }
 
+/// Reply context; several `recipient_names` make it a reply-all.
 #[derive(Clone)]
 pub struct ReplyTo {
     pub thread_id: Option<String>,
     pub subject: String,
-    pub recipient_name: Option<String>,
+    pub recipient_names: Vec<String>,
+}
+
+/// Forward context: the message quoted into a new one's body.
+#[derive(Clone)]
+pub struct ForwardOf {
+    pub message_id: i64,
+    pub sender_name: String,
+    pub created_ts: String,
+    pub subject: String,
+    pub body_md: String,
+}
+
+impl From<&Message> for ForwardOf {
+    fn from(msg: &Message) -> Self {
+        Self {
+            message_id: msg.id,
+            sender_name: msg.sender_name.clone(),
+            created_ts: msg.created_ts.clone(),
+            subject: msg.subject.clone(),
+            body_md: msg.body_md.clone(),
+        }
+    }
 }
 
 /// ComposeMessage modal component.
@@ -27,118 +52,55 @@ pub fn ComposeMessage(
     on_close: Callback<()>,
     on_sent: Callback<()>,
 ) -> impl IntoView {
-    // Form state
-    let recipients = RwSignal::new(Vec::<String>::new());
-    let subject = RwSignal::new(String::new());
-    let body = RwSignal::new(String::new());
-    let importance = RwSignal::new("normal".to_string());
-    let ack_required = RwSignal::new(false);
-    let thread_id = RwSignal::new(String::new());
-
-    let sending = RwSignal::new(false);
-    let error = RwSignal::new(Option::<String>::None);
-
-    // Initialize from reply_to if present
     let is_reply = props.reply_to.is_some();
-    if let Some(ref reply) = props.reply_to {
-        if let Some(ref recipient) = reply.recipient_name {
-            recipients.set(vec![recipient.clone()]);
-        }
-        subject.set(format!("Re: {}", reply.subject.trim_start_matches("Re: ")));
-        if let Some(ref tid) = reply.thread_id {
-            thread_id.set(tid.clone());
-        }
-    }
-
+    let is_forward = props.forward.is_some();
     let project_slug = props.project_slug.clone();
     let sender_name = props.sender_name.clone();
 
-    // Available recipients (exclude sender)
-    let available_recipients: Vec<Agent> = props
-        .agents
-        .iter()
-        .filter(|a| a.name != sender_name)
-        .cloned()
-        .collect();
+    // Form state, prefilled for replies, with the draft restored and autosaved
+    let form = use_compose_form(
+        &project_slug,
+        &sender_name,
+        props.reply_to.as_ref(),
+        props.forward.as_ref(),
+    );
+    let ComposeForm {
+        recipients,
+        subject,
+        body,
+        importance,
+        ack_required,
+        thread_id,
+        recipients_valid,
+        rejected,
+        sending,
+        error,
+        ..
+    } = form.clone();
+    let draft = form.draft.clone();
+    let draft_restored = draft.restored;
 
-    // Toggle recipient selection
-    let toggle_recipient = move |name: String| {
-        let mut current = recipients.get();
-        if current.contains(&name) {
-            current.retain(|r| r != &name);
-        } else {
-            current.push(name);
-        }
-        recipients.set(current);
-    };
+    let agents: Vec<Agent> = props.agents.clone();
 
     // Send message handler
     let handle_submit = {
-        let project_slug = project_slug.clone();
-        let sender_name = sender_name.clone();
-        move |_| {
-            let recips = recipients.get();
-            let subj = subject.get();
-            let bod = body.get();
-
-            if recips.is_empty() {
-                error.set(Some("Please select at least one recipient".to_string()));
-                return;
-            }
-            if subj.trim().is_empty() {
-                error.set(Some("Please enter a subject".to_string()));
-                return;
-            }
-            if bod.trim().is_empty() {
-                error.set(Some("Please enter a message body".to_string()));
-                return;
-            }
-
-            sending.set(true);
-            error.set(None);
-
-            let project = project_slug.clone();
-            let sender = sender_name.clone();
-            let tid = thread_id.get();
-            let imp = importance.get();
-            let ack = ack_required.get();
-            let on_sent = on_sent;
-
-            leptos::task::spawn_local(async move {
-                match client::send_message(
-                    &project,
-                    &sender,
-                    &recips,
-                    &subj,
-                    &bod,
-                    if tid.is_empty() {
-                        None
-                    } else {
-                        Some(tid.as_str())
-                    },
-                    &imp,
-                    ack,
-                )
-                .await
-                {
-                    Ok(_) => {
-                        on_sent.run(());
-                    }
-                    Err(e) => {
-                        error.set(Some(e.message));
-                        sending.set(false);
-                    }
-                }
-            });
-        }
+        let form = form.clone();
+        move |_| form.submit(on_sent)
     };
+    let cannot_send = Signal::derive(move || form.cannot_send());
 
     view! {
         <div class="flex flex-col h-full max-h-[90vh]">
             // Header
             <div class="p-4 border-b border-cream-200 dark:border-charcoal-700 flex items-center justify-between">
                 <h2 class="text-lg font-semibold text-charcoal-900 dark:text-cream-100">
-                    {if is_reply { "Reply" } else { "New Message" }}
+                    {if is_reply {
+                        "Reply"
+                    } else if is_forward {
+                        "Forward"
+                    } else {
+                        "New Message"
+                    }}
                 </h2>
                 <Button
                     variant=ButtonVariant::Ghost
@@ -151,6 +113,26 @@ pub fn ComposeMessage(
 
             // Form
             <div class="flex-1 overflow-y-auto p-4 space-y-4">
+                // Restored draft notice
+                {move || draft_restored.get().then(|| {
+                    let draft = draft.clone();
+                    view! {
+                        <div class="flex items-center justify-between gap-3 px-3 py-2 rounded-lg bg-amber-50 dark:bg-amber-900/20 border border-amber-200 dark:border-amber-800 text-sm text-amber-800 dark:text-amber-300">
+                            <span class="flex items-center gap-2">
+                                <i data-lucide="file-clock" class="icon-sm"></i>
+                                "Draft restored"
+                            </span>
+                            <button
+                                type="button"
+                                class="font-medium hover:underline underline-offset-4"
+                                on:click=move |_| draft.discard()
+                            >
+                                "Discard draft"
+                            </button>
+                        </div>
+                    }
+                })}
+
                 // From (readonly)
                 <div>
                     <span class="block text-sm font-medium text-charcoal-700 dark:text-charcoal-300 mb-1">
@@ -166,53 +148,17 @@ pub fn ComposeMessage(
 
                 // Recipients
                 <div>
-                    <span class="block text-sm font-medium text-charcoal-700 dark:text-charcoal-300 mb-1">
+                    <label for="recipients" class="block text-sm font-medium text-charcoal-700 dark:text-charcoal-300 mb-1">
                         "To *"
-                    </span>
-                    {if available_recipients.is_empty() {
-                        view! {
-                            <p class="text-sm text-charcoal-500 dark:text-charcoal-400 italic">
-                                "No other agents in this project. Register more agents to send messages."
-                            </p>
-                        }.into_any()
-                    } else {
-                        view! {
-                            <div class="flex flex-wrap gap-2">
-                                {available_recipients.iter().map(|agent| {
-                                    let name = agent.name.clone();
-                                    let name_display = name.clone();
-                                    let toggle = toggle_recipient;
-                                    view! {
-                                        <button
-                                            type="button"
-                                            on:click=move |_| toggle(name.clone())
-                                            class=move || {
-                                                if recipients.get().contains(&name_display) {
-                                                    "px-3 py-1.5 rounded-full text-sm transition-colors bg-amber-600 text-white"
-                                                } else {
-                                                    "px-3 py-1.5 rounded-full text-sm transition-colors bg-charcoal-100 dark:bg-charcoal-700 text-charcoal-700 dark:text-charcoal-300 hover:bg-charcoal-200 dark:hover:bg-charcoal-600"
-                                                }
-                                            }
-                                        >
-                                            {name_display.clone()}
-                                        </button>
-                                    }
-                                }).collect::<Vec<_>>()}
-                            </div>
-                            {move || {
-                                let recips = recipients.get();
-                                if !recips.is_empty() {
-                                    Some(view! {
-                                        <p class="mt-2 text-sm text-charcoal-500 dark:text-charcoal-400">
-                                            "Selected: " {recips.join(", ")}
-                                        </p>
-                                    })
-                                } else {
-                                    None
-                                }
-                            }}
-                        }.into_any()
-                    }}
+                    </label>
+                    <RecipientPicker
+                        project_slug=project_slug.clone()
+                        selected=recipients
+                        valid=recipients_valid
+                        agents=agents
+                        rejected=rejected
+                        exclude=vec![sender_name.clone()]
+                    />
                 </div>
 
                 // Subject
@@ -320,7 +266,7 @@ pub fn ComposeMessage(
                 <Button
                     variant=ButtonVariant::Default
                     on_click=Callback::new(move |_| handle_submit(()))
-                    disabled=sending.get() || recipients.get().is_empty()
+                    disabled=cannot_send
                 >
                     {move || {
                         if sending.get() {
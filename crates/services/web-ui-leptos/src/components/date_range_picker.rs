@@ -0,0 +1,184 @@
+//! Date range picker for the inbox filters.
+//!
+//! Two native date inputs plus quick presets. Bounds are RFC 3339 timestamps
+//! carrying the browser's UTC offset: `since` is inclusive, `until` exclusive.
+//! Picking a "to" date covers that whole day, so `until` is the following
+//! local midnight.
+
+use super::{Button, ButtonSize, ButtonVariant, cva::input_class};
+use crate::utils::{local_day_start, local_hours_ago, local_today, previous_day};
+use leptos::prelude::*;
+
+/// Quick ranges shown next to the date inputs. All are open-ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DatePreset {
+    Today,
+    Last24Hours,
+    Last7Days,
+}
+
+impl DatePreset {
+    pub const ALL: [DatePreset; 3] = [Self::Today, Self::Last24Hours, Self::Last7Days];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Today => "Today",
+            Self::Last24Hours => "Last 24h",
+            Self::Last7Days => "Last 7 days",
+        }
+    }
+
+    /// Lower bound for this preset, measured from now.
+    fn since(self) -> Option<String> {
+        match self {
+            Self::Today => local_day_start(&local_today(), 0),
+            Self::Last24Hours => Some(local_hours_ago(24.0)),
+            Self::Last7Days => Some(local_hours_ago(24.0 * 7.0)),
+        }
+    }
+}
+
+/// Value for the "from" date input.
+fn from_input_value(since: Option<&str>) -> String {
+    since
+        .and_then(|s| s.get(..10))
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Value for the "to" date input: the last day included before `until`.
+fn to_input_value(until: Option<&str>) -> String {
+    let Some(until) = until else {
+        return String::new();
+    };
+    if until.get(10..19) == Some("T00:00:00") {
+        previous_day(until).unwrap_or_default()
+    } else {
+        from_input_value(Some(until))
+    }
+}
+
+/// Date range picker with from/to inputs and presets.
+///
+/// # Props
+/// - `since`: Inclusive lower bound (RFC 3339), `None` = open
+/// - `until`: Exclusive upper bound (RFC 3339), `None` = open
+/// - `on_change`: Called with the new `(since, until)` pair
+///
+/// # Example
+/// ```rust,ignore
+/// view! {
+///     <DateRangePicker
+///         since=Signal::derive(move || state.with(|s| s.since.clone()))
+///         until=Signal::derive(move || state.with(|s| s.until.clone()))
+///         on_change=Callback::new(move |(since, until)| { /* ... */ })
+///     />
+/// }
+/// ```
+#[component]
+pub fn DateRangePicker(
+    /// Inclusive lower bound
+    #[prop(into)]
+    since: Signal<Option<String>>,
+    /// Exclusive upper bound
+    #[prop(into)]
+    until: Signal<Option<String>>,
+    /// Range change callback
+    on_change: Callback<(Option<String>, Option<String>)>,
+) -> impl IntoView {
+    let set_from = move |ev: leptos::ev::Event| {
+        let date = event_target_value(&ev);
+        let since = local_day_start(&date, 0);
+        // Drop an upper bound that now ends before the range starts
+        let until = until
+            .get_untracked()
+            .filter(|u| to_input_value(Some(u)) >= date || date.is_empty());
+        on_change.run((since, until));
+    };
+    let set_to = move |ev: leptos::ev::Event| {
+        let date = event_target_value(&ev);
+        let until = local_day_start(&date, 1);
+        let since = since
+            .get_untracked()
+            .filter(|s| from_input_value(Some(s)) <= date || date.is_empty());
+        on_change.run((since, until));
+    };
+
+    let date_class = input_class(false, Some("w-36"));
+
+    view! {
+        <div class="flex flex-wrap items-center gap-2" role="group" aria-label="Date range">
+            <input
+                type="date"
+                class=date_class.clone()
+                aria-label="From date"
+                prop:value=move || from_input_value(since.get().as_deref())
+                on:change=set_from
+            />
+            <span class="text-muted-foreground text-sm">"–"</span>
+            <input
+                type="date"
+                class=date_class
+                aria-label="To date"
+                prop:value=move || to_input_value(until.get().as_deref())
+                on:change=set_to
+            />
+            {DatePreset::ALL
+                .into_iter()
+                .map(|preset| {
+                    view! {
+                        <Button
+                            variant=ButtonVariant::Ghost
+                            size=ButtonSize::Sm
+                            on_click=Callback::new(move |_| on_change.run((preset.since(), None)))
+                        >
+                            {preset.label()}
+                        </Button>
+                    }
+                })
+                .collect_view()}
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_input_value() {
+        assert_eq!(from_input_value(None), "");
+        assert_eq!(
+            from_input_value(Some("2026-03-01T00:00:00+02:00")),
+            "2026-03-01"
+        );
+        // Presets keep their time of day; the input shows the date
+        assert_eq!(
+            from_input_value(Some("2026-03-01T14:25:10-05:00")),
+            "2026-03-01"
+        );
+    }
+
+    #[test]
+    fn test_to_input_value_shows_last_included_day() {
+        assert_eq!(to_input_value(None), "");
+        assert_eq!(
+            to_input_value(Some("2026-03-02T00:00:00+02:00")),
+            "2026-03-01"
+        );
+        assert_eq!(
+            to_input_value(Some("2026-03-01T00:00:00+00:00")),
+            "2026-02-28"
+        );
+        assert_eq!(
+            to_input_value(Some("2026-03-02T12:00:00+02:00")),
+            "2026-03-02"
+        );
+    }
+
+    #[test]
+    fn test_preset_labels() {
+        let labels: Vec<_> = DatePreset::ALL.iter().map(|p| p.label()).collect();
+        assert_eq!(labels, vec!["Today", "Last 24h", "Last 7 days"]);
+    }
+}
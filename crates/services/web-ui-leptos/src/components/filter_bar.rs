@@ -1,10 +1,16 @@
 //! Comprehensive Filter Bar component for inbox views.
 //!
-//! Provides search, filter dropdowns, view controls, and message count.
+//! Provides search, filter dropdowns, a date range, view controls, and
+//! message count.
 //! Responsive design with mobile bottom sheet support.
 
-use super::{Badge, BadgeVariant, Button, ButtonVariant, Input, Select, SelectIcon, SelectOption};
+use super::{
+    Badge, BadgeVariant, Button, ButtonVariant, DateRangePicker, Input, Select, SelectIcon,
+    SelectOption,
+};
 use leptos::prelude::*;
+use leptos_router::NavigateOptions;
+use leptos_router::hooks::{use_location, use_navigate, use_query_map};
 use leptos_router::params::ParamsMap;
 use leptos_use::use_debounce_fn;
 
@@ -19,6 +25,14 @@ pub struct FilterState {
     pub sender: Option<String>,
     /// Importance filter (None = all)
     pub importance: Option<String>,
+    /// Selected label (None = all)
+    pub label: Option<String>,
+    /// Created at or after this RFC 3339 timestamp (None = open)
+    pub since: Option<String>,
+    /// Created before this RFC 3339 timestamp (None = open)
+    pub until: Option<String>,
+    /// Server-side sort order (None = newest first)
+    pub sort: Option<String>,
     /// Show threaded view
     pub threaded: bool,
     /// View mode: "list" or "grid"
@@ -33,31 +47,46 @@ impl FilterState {
         }
     }
 
-    /// Create FilterState from URL query parameters
-    pub fn from_query_params(params: &std::collections::HashMap<String, String>) -> Self {
+    /// Build a FilterState from a query parameter lookup
+    fn from_lookup(get: impl Fn(&str) -> Option<String>) -> Self {
         Self {
-            query: params.get("q").cloned().unwrap_or_default(),
-            project: params.get("project").filter(|s| !s.is_empty()).cloned(),
-            sender: params.get("sender").filter(|s| !s.is_empty()).cloned(),
-            importance: params.get("importance").filter(|s| !s.is_empty()).cloned(),
-            threaded: params.get("threaded").is_some_and(|v| v == "true"),
-            view_mode: params
-                .get("view")
-                .cloned()
+            query: get("q").unwrap_or_default(),
+            project: get("project").filter(|s| !s.is_empty()),
+            sender: get("sender").filter(|s| !s.is_empty()),
+            importance: get("importance").filter(|s| !s.is_empty()),
+            label: get("label").filter(|s| !s.is_empty()),
+            since: get("since").filter(|s| !s.is_empty()),
+            until: get("until").filter(|s| !s.is_empty()),
+            sort: get("sort").filter(|s| !s.is_empty()),
+            threaded: get("threaded").is_some_and(|v| v == "true"),
+            view_mode: get("view")
+                .filter(|s| !s.is_empty())
                 .unwrap_or_else(|| "list".to_string()),
         }
     }
 
+    /// Create FilterState from URL query parameters
+    pub fn from_query_params(params: &std::collections::HashMap<String, String>) -> Self {
+        Self::from_lookup(|key| params.get(key).cloned())
+    }
+
     /// Create FilterState from a Leptos ParamsMap
     pub fn from_params_map(params: &ParamsMap) -> Self {
-        Self {
-            query: params.get("q").unwrap_or_default(),
-            project: params.get("project").filter(|s| !s.is_empty()),
-            sender: params.get("sender").filter(|s| !s.is_empty()),
-            importance: params.get("importance").filter(|s| !s.is_empty()),
-            threaded: params.get("threaded").is_some_and(|v| v == "true"),
-            view_mode: params.get("view").unwrap_or_else(|| "list".to_string()),
-        }
+        Self::from_lookup(|key| params.get(key))
+    }
+
+    /// Create FilterState from a URL query string (with or without leading ?)
+    pub fn from_query_string(qs: &str) -> Self {
+        let params: std::collections::HashMap<String, String> = qs
+            .trim_start_matches('?')
+            .split('&')
+            .filter_map(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                let value = urlencoding::decode(value).ok()?;
+                Some((key.to_string(), value.into_owned()))
+            })
+            .collect();
+        Self::from_query_params(&params)
     }
 
     /// Convert FilterState to URL query string (without leading ?)
@@ -76,11 +105,23 @@ impl FilterState {
         if let Some(ref i) = self.importance {
             params.push(format!("importance={}", urlencoding::encode(i)));
         }
+        if let Some(ref l) = self.label {
+            params.push(format!("label={}", urlencoding::encode(l)));
+        }
+        if let Some(ref since) = self.since {
+            params.push(format!("since={}", urlencoding::encode(since)));
+        }
+        if let Some(ref until) = self.until {
+            params.push(format!("until={}", urlencoding::encode(until)));
+        }
+        if let Some(ref sort) = self.sort {
+            params.push(format!("sort={}", urlencoding::encode(sort)));
+        }
         if self.threaded {
             params.push("threaded=true".to_string());
         }
         if self.view_mode != "list" {
-            params.push(format!("view={}", &self.view_mode));
+            params.push(format!("view={}", urlencoding::encode(&self.view_mode)));
         }
 
         params.join("&")
@@ -92,6 +133,9 @@ impl FilterState {
             || self.project.is_some()
             || self.sender.is_some()
             || self.importance.is_some()
+            || self.label.is_some()
+            || self.since.is_some()
+            || self.until.is_some()
     }
 
     /// Clear all filters
@@ -100,6 +144,9 @@ impl FilterState {
         self.project = None;
         self.sender = None;
         self.importance = None;
+        self.label = None;
+        self.since = None;
+        self.until = None;
     }
 }
 
@@ -111,6 +158,57 @@ const IMPORTANCE_OPTIONS: &[(&str, &str)] = &[
     ("low", "Low"),
 ];
 
+/// Sort options, matching the server's `sort` values
+const SORT_OPTIONS: &[(&str, &str)] = &[
+    ("", "Newest first"),
+    ("created_asc", "Oldest first"),
+    ("importance_desc", "Importance"),
+    ("ack_pending_first", "Awaiting ack"),
+];
+
+/// Keep `filter_state` and the URL query string in sync.
+///
+/// Filter changes replace the current history entry (no history spam), and
+/// URL changes such as browser back/forward update the signal. Call this
+/// from the page that owns `filter_state`, after initializing it with
+/// [`FilterState::from_params_map`].
+pub fn use_filter_url_sync(filter_state: RwSignal<FilterState>) {
+    let query = use_query_map();
+    let location = use_location();
+    let navigate = use_navigate();
+
+    // URL -> state (back/forward, links to the same page)
+    Effect::new(move |_| {
+        let from_url = query.with(FilterState::from_params_map);
+        if filter_state.get_untracked() != from_url {
+            filter_state.set(from_url);
+        }
+    });
+
+    // State -> URL
+    Effect::new(move |_| {
+        let state = filter_state.get();
+        if query.with_untracked(FilterState::from_params_map) == state {
+            return;
+        }
+        let qs = state.to_query_string();
+        let path = location.pathname.get_untracked();
+        let url = if qs.is_empty() {
+            path
+        } else {
+            format!("{}?{}", path, qs)
+        };
+        navigate(
+            &url,
+            NavigateOptions {
+                replace: true,
+                scroll: false,
+                ..Default::default()
+            },
+        );
+    });
+}
+
 /// Comprehensive filter bar component.
 ///
 /// # Props
@@ -118,6 +216,7 @@ const IMPORTANCE_OPTIONS: &[(&str, &str)] = &[
 /// - `message_count`: Number of messages to display
 /// - `projects`: Available project options
 /// - `senders`: Available sender options
+/// - `labels`: Available label names; the label dropdown is hidden when empty
 ///
 /// # Example
 /// ```rust,ignore
@@ -144,6 +243,9 @@ pub fn FilterBar(
     /// Available senders for dropdown
     #[prop(default = vec![])]
     senders: Vec<String>,
+    /// Available labels for dropdown
+    #[prop(default = vec![])]
+    labels: Vec<String>,
 ) -> impl IntoView {
     // Mobile filters sheet visibility
     let show_filters_sheet = RwSignal::new(false);
@@ -152,18 +254,44 @@ pub fn FilterBar(
     let project_value = RwSignal::new(String::new());
     let sender_value = RwSignal::new(String::new());
     let importance_value = RwSignal::new(String::new());
+    let label_value = RwSignal::new(String::new());
+    let sort_value = RwSignal::new(String::new());
     let search_value = RwSignal::new(String::new());
 
-    // Sync from filter_state on mount ONLY (use get_untracked to avoid re-running on changes)
-    Effect::new(move |prev: Option<()>| {
-        // Only run once on initial mount
-        if prev.is_none() {
-            let state = filter_state.get_untracked();
-            project_value.set(state.project.clone().unwrap_or_default());
-            sender_value.set(state.sender.clone().unwrap_or_default());
-            importance_value.set(state.importance.clone().unwrap_or_default());
-            search_value.set(state.query.clone());
+    // Sync from filter_state on mount and whenever a field changes outside
+    // the bar (e.g. browser back/forward). Only changed fields are copied, so
+    // a pending debounced search is not clobbered by another filter change.
+    Effect::new(move |prev: Option<FilterState>| {
+        let state = filter_state.get();
+        let first = prev.is_none();
+        let prev = prev.unwrap_or_default();
+        let sync = |local: RwSignal<String>, value: &str| {
+            if local.get_untracked() != value {
+                local.set(value.to_string());
+            }
+        };
+        if first || prev.project != state.project {
+            sync(project_value, state.project.as_deref().unwrap_or_default());
+        }
+        if first || prev.sender != state.sender {
+            sync(sender_value, state.sender.as_deref().unwrap_or_default());
+        }
+        if first || prev.importance != state.importance {
+            sync(
+                importance_value,
+                state.importance.as_deref().unwrap_or_default(),
+            );
         }
+        if first || prev.label != state.label {
+            sync(label_value, state.label.as_deref().unwrap_or_default());
+        }
+        if first || prev.sort != state.sort {
+            sync(sort_value, state.sort.as_deref().unwrap_or_default());
+        }
+        if first || prev.query != state.query {
+            sync(search_value, &state.query);
+        }
+        state
     });
 
     // Sync project changes to filter_state
@@ -211,6 +339,36 @@ pub fn FilterBar(
         val
     });
 
+    // Sync label changes to filter_state
+    Effect::new(move |prev: Option<String>| {
+        let val = label_value.get();
+        if prev.is_some() {
+            filter_state.update(|s| {
+                s.label = if val.is_empty() {
+                    None
+                } else {
+                    Some(val.clone())
+                };
+            });
+        }
+        val
+    });
+
+    // Sync sort changes to filter_state
+    Effect::new(move |prev: Option<String>| {
+        let val = sort_value.get();
+        if prev.is_some() {
+            filter_state.update(|s| {
+                s.sort = if val.is_empty() {
+                    None
+                } else {
+                    Some(val.clone())
+                };
+            });
+        }
+        val
+    });
+
     // Sync search changes to filter_state with debounce (300ms) to prevent UI freeze
     let debounced_search_update = use_debounce_fn(
         move || {
@@ -228,6 +386,16 @@ pub fn FilterBar(
         val
     });
 
+    // Date range goes straight to filter_state; the picker reads it back
+    let date_since = Signal::derive(move || filter_state.with(|s| s.since.clone()));
+    let date_until = Signal::derive(move || filter_state.with(|s| s.until.clone()));
+    let set_date_range = Callback::new(move |(since, until)| {
+        filter_state.update(|s| {
+            s.since = since;
+            s.until = until;
+        });
+    });
+
     // View mode toggle
     let set_list_view = move |_| {
         filter_state.update(|s| s.view_mode = "list".to_string());
@@ -243,6 +411,7 @@ pub fn FilterBar(
         project_value.set(String::new());
         sender_value.set(String::new());
         importance_value.set(String::new());
+        label_value.set(String::new());
         search_value.set(String::new());
     });
 
@@ -263,6 +432,15 @@ pub fn FilterBar(
         )
         .collect();
 
+    let has_labels = !labels.is_empty();
+    let label_options: Vec<SelectOption> = std::iter::once(SelectOption::new("", "All Labels"))
+        .chain(
+            labels
+                .iter()
+                .map(|l| SelectOption::new(l.clone(), l.clone())),
+        )
+        .collect();
+
     // Debug: Log filter options
     leptos::logging::log!(
         "FilterBar: {} projects, {} senders",
@@ -275,10 +453,15 @@ pub fn FilterBar(
         .map(|(v, l)| SelectOption::new(*v, *l))
         .collect();
 
+    let sort_options: Vec<SelectOption> = SORT_OPTIONS
+        .iter()
+        .map(|(v, l)| SelectOption::new(*v, *l))
+        .collect();
+
     view! {
         <div class="flex flex-col gap-4">
             // Desktop: Single row layout - improved spacing and padding
-            <div class="hidden md:flex items-center gap-4 p-4 bg-muted/50 rounded-lg border border-border">
+            <div class="hidden md:flex flex-wrap items-center gap-4 p-4 bg-muted/50 rounded-lg border border-border">
                 // Search Input with icon - using inline SVG for reliability
                 <div class="relative flex-1">
                     <svg class="absolute left-3 top-1/2 -translate-y-1/2 h-4 w-4 text-muted-foreground pointer-events-none z-10" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
@@ -328,6 +511,33 @@ pub fn FilterBar(
                     />
                 </div>
 
+                {has_labels.then(|| {
+                    let label_options = label_options.clone();
+                    view! {
+                        <div class="w-40">
+                            <Select
+                                id="labelFilter".to_string()
+                                options=label_options
+                                value=label_value
+                                placeholder="All Labels".to_string()
+                                icon=SelectIcon::Tag
+                            />
+                        </div>
+                    }
+                })}
+
+                <DateRangePicker since=date_since until=date_until on_change=set_date_range />
+
+                <div class="w-40">
+                    <Select
+                        id="sortOrder".to_string()
+                        options=sort_options.clone()
+                        value=sort_value
+                        placeholder="Newest first".to_string()
+                        icon=SelectIcon::Sort
+                    />
+                </div>
+
                 // Clear Filters Button (shown when filters active)
                 {move || {
                     if filter_state.get().has_filters() {
@@ -485,6 +695,27 @@ pub fn FilterBar(
                                          placeholder="Importance".to_string()
                                          icon=SelectIcon::AlertCircle
                                      />
+                                     {has_labels.then(|| view! {
+                                         <Select
+                                             id="labelFilterMobile".to_string()
+                                             options=label_options.clone()
+                                             value=label_value
+                                             placeholder="All Labels".to_string()
+                                             icon=SelectIcon::Tag
+                                         />
+                                     })}
+                                     <DateRangePicker
+                                         since=date_since
+                                         until=date_until
+                                         on_change=set_date_range
+                                     />
+                                     <Select
+                                         id="sortOrderMobile".to_string()
+                                         options=sort_options.clone()
+                                         value=sort_value
+                                         placeholder="Newest first".to_string()
+                                         icon=SelectIcon::Sort
+                                     />
                                 </div>
 
                                 <Button
@@ -535,6 +766,14 @@ mod tests {
         state.project = None;
         state.importance = Some("high".to_string());
         assert!(state.has_filters());
+
+        state.importance = None;
+        state.label = Some("blocked".to_string());
+        assert!(state.has_filters());
+
+        state.label = None;
+        state.until = Some("2026-03-02T00:00:00+02:00".to_string());
+        assert!(state.has_filters());
     }
 
     #[test]
@@ -544,6 +783,9 @@ mod tests {
         state.project = Some("proj".to_string());
         state.sender = Some("agent".to_string());
         state.importance = Some("high".to_string());
+        state.label = Some("blocked".to_string());
+        state.since = Some("2026-03-01T00:00:00+02:00".to_string());
+        state.until = Some("2026-03-02T00:00:00+02:00".to_string());
 
         state.clear();
 
@@ -551,6 +793,9 @@ mod tests {
         assert_eq!(state.project, None);
         assert_eq!(state.sender, None);
         assert_eq!(state.importance, None);
+        assert_eq!(state.label, None);
+        assert_eq!(state.since, None);
+        assert_eq!(state.until, None);
     }
 
     #[test]
@@ -669,6 +914,29 @@ mod tests {
         assert_eq!(state.view_mode, "grid");
     }
 
+    #[test]
+    fn test_sort_is_not_a_filter() {
+        let mut state = FilterState::new();
+        state.sort = Some("created_asc".to_string());
+        assert!(!state.has_filters());
+
+        // clear() keeps the chosen order, like the view mode
+        state.query = "test".to_string();
+        state.clear();
+        assert_eq!(state.sort.as_deref(), Some("created_asc"));
+        assert_eq!(state.to_query_string(), "sort=created_asc");
+    }
+
+    #[test]
+    fn test_sort_options_default_first() {
+        assert_eq!(SORT_OPTIONS[0].0, "");
+        let values: Vec<&str> = SORT_OPTIONS[1..].iter().map(|(v, _)| *v).collect();
+        assert_eq!(
+            values,
+            ["created_asc", "importance_desc", "ack_pending_first"]
+        );
+    }
+
     #[test]
     fn test_clear_preserves_threaded() {
         let mut state = FilterState::new();
@@ -713,6 +981,71 @@ mod tests {
         assert_eq!(original.threaded, restored.threaded);
     }
 
+    #[test]
+    fn test_query_string_roundtrip_every_field() {
+        let mut original = FilterState::new();
+        original.query = "deploy failed".to_string();
+        original.project = Some("backend-api".to_string());
+        original.sender = Some("BlueLake".to_string());
+        original.importance = Some("high".to_string());
+        original.label = Some("needs-review".to_string());
+        original.since = Some("2026-03-01T00:00:00-05:30".to_string());
+        original.until = Some("2026-03-08T00:00:00-05:30".to_string());
+        original.sort = Some("importance_desc".to_string());
+        original.threaded = true;
+        original.view_mode = "grid".to_string();
+
+        let restored = FilterState::from_query_string(&original.to_query_string());
+        assert_eq!(restored, original);
+
+        // Leading ? is accepted
+        let restored = FilterState::from_query_string(&format!("?{}", original.to_query_string()));
+        assert_eq!(restored, original);
+    }
+
+    #[test]
+    fn test_query_string_date_range() {
+        let mut state = FilterState::new();
+        state.since = Some("2026-03-01T09:30:00+02:00".to_string());
+        state.until = Some("2026-03-02T00:00:00+02:00".to_string());
+
+        // The offset's + must survive the URL, or it would decode as a space
+        assert_eq!(
+            state.to_query_string(),
+            "since=2026-03-01T09%3A30%3A00%2B02%3A00&until=2026-03-02T00%3A00%3A00%2B02%3A00"
+        );
+        assert_eq!(
+            FilterState::from_query_string(&state.to_query_string()),
+            state
+        );
+
+        // Empty bounds are the same as no bound
+        assert_eq!(
+            FilterState::from_query_string("since=&until="),
+            FilterState::new()
+        );
+    }
+
+    #[test]
+    fn test_query_string_roundtrip_defaults() {
+        let state = FilterState::new();
+        assert_eq!(state.to_query_string(), "");
+        assert_eq!(FilterState::from_query_string(""), state);
+    }
+
+    #[test]
+    fn test_query_string_roundtrip_special_chars() {
+        let mut original = FilterState::new();
+        original.query = "a&b=c #1 100% +fix /path?x 日本".to_string();
+        original.sender = Some("agent & co".to_string());
+
+        let qs = original.to_query_string();
+        assert!(!qs.contains(' '));
+        assert!(!qs.contains('#'));
+        assert_eq!(qs.matches('&').count(), 1);
+        assert_eq!(FilterState::from_query_string(&qs), original);
+    }
+
     #[test]
     fn test_query_string_special_chars() {
         let mut state = FilterState::new();
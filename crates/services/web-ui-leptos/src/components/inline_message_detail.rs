@@ -5,9 +5,10 @@
 
 use crate::api::client::{self, Message};
 use crate::components::{
-    Alert, AlertDescription, AlertTitle, AlertVariant, Badge, BadgeVariant, MessageDetailHeader,
-    Skeleton,
+    Alert, AlertDescription, AlertTitle, AlertVariant, Badge, BadgeVariant, Button, ButtonSize,
+    ButtonVariant, InlineReply, Input, MessageDetailHeader, Skeleton, ThreadMuteToggle,
 };
+use crate::utils::render_markdown;
 use leptos::prelude::*;
 
 /// Inline message detail component for embedding in split view.
@@ -15,6 +16,7 @@ use leptos::prelude::*;
 /// # Props
 /// - `message_id`: ID of the message to display
 /// - `project_slug`: Project context for the message
+/// - `on_reply_sent`: Called after a reply is sent from the inline composer
 ///
 /// # Example
 /// ```rust,ignore
@@ -22,6 +24,7 @@ use leptos::prelude::*;
 ///     <InlineMessageDetail
 ///         message_id=selected_id
 ///         project_slug="my-project".to_string()
+///         on_reply_sent=refresh
 ///     />
 /// }
 /// ```
@@ -33,11 +36,17 @@ pub fn InlineMessageDetail(
     /// Project slug for context
     #[prop(into)]
     project_slug: Signal<String>,
+    /// Called after a reply is sent, e.g. to refresh the message list
+    #[prop(optional)]
+    on_reply_sent: Option<Callback<()>>,
 ) -> impl IntoView {
     // State
     let message = RwSignal::new(Option::<Message>::None);
     let loading = RwSignal::new(true);
     let error = RwSignal::new(Option::<String>::None);
+    let labels = RwSignal::new(Vec::<client::Label>::new());
+    let new_label = RwSignal::new(String::new());
+    let label_error = RwSignal::new(Option::<String>::None);
 
     // Load message when ID changes
     Effect::new(move |_| {
@@ -46,6 +55,8 @@ pub fn InlineMessageDetail(
         leptos::task::spawn_local(async move {
             loading.set(true);
             error.set(None);
+            label_error.set(None);
+            labels.set(client::get_message_labels(id).await.unwrap_or_default());
 
             match client::get_message(&id.to_string()).await {
                 Ok(m) => {
@@ -60,6 +71,27 @@ pub fn InlineMessageDetail(
         });
     });
 
+    // Add or remove one label, then show the message's labels as returned
+    let update_labels = move |add: Vec<String>, remove: Vec<String>| {
+        let id = message_id.get_untracked();
+        leptos::task::spawn_local(async move {
+            match client::update_message_labels(id, &add, &remove).await {
+                Ok(updated) => {
+                    label_error.set(None);
+                    labels.set(updated);
+                }
+                Err(e) => label_error.set(Some(e.message)),
+            }
+        });
+    };
+    let add_label = move || {
+        let name = new_label.get_untracked().trim().to_string();
+        if !name.is_empty() {
+            new_label.set(String::new());
+            update_labels(vec![name], Vec::new());
+        }
+    };
+
     view! {
         <div class="h-full flex flex-col">
             // Error
@@ -104,13 +136,14 @@ pub fn InlineMessageDetail(
                 if !loading.get() {
                     if let Some(msg) = message.get() {
                         let subject = msg.subject.clone();
-                        let body = msg.body_md.clone();
+                        let body_html = render_markdown(&msg.body_md);
                         let created = msg.created_ts.clone();
                         let importance = msg.importance.clone();
                         let ack_required = msg.ack_required;
                         let thread_id = msg.thread_id.clone();
                         let msg_id = msg.id;
                         let sender = msg.sender_name.clone();
+                        let recipients = msg.recipients.clone();
 
                         Some(view! {
                             <div class="flex-1 overflow-y-auto">
@@ -122,6 +155,7 @@ pub fn InlineMessageDetail(
                                     project_slug={project.clone()}
                                     sent_at={created.clone()}
                                     message_id={msg_id}
+                                    permalink={msg.permalink.clone()}
                                 />
 
                                 // Badges
@@ -155,15 +189,87 @@ pub fn InlineMessageDetail(
                                     })}
                                 </div>
 
+                                // Mute the thread for a recipient
+                                {thread_id.clone().filter(|_| !recipients.is_empty()).map(|tid| view! {
+                                    <div class="px-6 py-3 border-b border-border">
+                                        <ThreadMuteToggle
+                                            project_slug={project.clone()}
+                                            thread_id=tid
+                                            agents=recipients
+                                        />
+                                    </div>
+                                })}
+
+                                // Labels with add/remove
+                                <div class="px-6 py-3 border-b border-border flex flex-wrap items-center gap-2">
+                                    <i data-lucide="tag" class="icon-xs text-muted-foreground"></i>
+                                    {move || {
+                                        labels.get().into_iter().map(|label| {
+                                            let name = label.name.clone();
+                                            let style = format!(
+                                                "background-color: {0}1a; border-color: {0}; color: {0}",
+                                                label.color
+                                            );
+                                            view! {
+                                                <span
+                                                    class="inline-flex items-center gap-1 rounded-full border px-2 py-0.5 text-xs font-medium"
+                                                    style=style
+                                                >
+                                                    {label.name.clone()}
+                                                    <button
+                                                        type="button"
+                                                        class="hover:opacity-70"
+                                                        aria-label=format!("Remove label {}", label.name)
+                                                        on:click=move |_| update_labels(Vec::new(), vec![name.clone()])
+                                                    >
+                                                        "×"
+                                                    </button>
+                                                </span>
+                                            }
+                                        }).collect_view()
+                                    }}
+                                    <form
+                                        class="flex items-center gap-1"
+                                        on:submit=move |ev| { ev.prevent_default(); add_label(); }
+                                    >
+                                        <Input
+                                            value=new_label
+                                            placeholder="Add label".to_string()
+                                            aria_label="Add label".to_string()
+                                            class="h-7 w-32 text-xs".to_string()
+                                        />
+                                        <Button
+                                            variant=ButtonVariant::Ghost
+                                            size=ButtonSize::Sm
+                                            button_type="submit"
+                                        >
+                                            "Add"
+                                        </Button>
+                                    </form>
+                                    {move || label_error.get().map(|e| view! {
+                                        <span class="text-xs text-destructive">{e}</span>
+                                    })}
+                                </div>
+
                                 // Message Body
                                 <div class="p-6">
-                                    <div class="prose dark:prose-invert max-w-none">
-                                        <pre class="whitespace-pre-wrap font-sans text-foreground bg-transparent p-0 overflow-visible text-sm">
-                                            {body}
-                                        </pre>
-                                    </div>
+                                    <div
+                                        class="markdown-body text-sm text-foreground break-words"
+                                        inner_html=body_html
+                                    ></div>
                                 </div>
 
+                                // Reply without leaving the split view
+                                <InlineReply
+                                    message=msg.clone()
+                                    project_slug=project.clone()
+                                    on_sent=Callback::new(move |_| {
+                                        if let Some(on_reply_sent) = on_reply_sent {
+                                            on_reply_sent.run(());
+                                        }
+                                    })
+                                />
+
                                 // Open in full view link - shadcn link pattern
                                 <div class="px-6 py-4 border-t border-border bg-muted/50">
                                     <a
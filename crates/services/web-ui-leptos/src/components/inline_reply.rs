@@ -0,0 +1,329 @@
+//! Inline reply composer for InlineMessageDetail.
+//!
+//! A "Reply" button below the message body expands a compact composer
+//! prefilled to answer the sender in the same thread. It shares form state,
+//! validation and drafts with the compose dialog through
+//! [`use_compose_form`], so collapsing it (Esc) keeps the draft for next
+//! time. Ctrl/Cmd+Enter sends.
+
+use super::compose_form::{ComposeForm, use_compose_form};
+use super::{
+    Button, ButtonSize, ButtonVariant, Input, RecipientPicker, ReplyTo, Toast,
+    reply_all_recipients, use_toaster,
+};
+use crate::api::client::Message;
+use leptos::prelude::*;
+
+/// Agents a reply to `msg` can be sent as: its recipients, in order.
+pub fn reply_identities(msg: &Message) -> Vec<String> {
+    let mut out: Vec<String> = Vec::new();
+    for name in &msg.recipients {
+        if !out.contains(name) {
+            out.push(name.clone());
+        }
+    }
+    out
+}
+
+/// Reply context for answering `msg` as `me`: to the sender (or, on a
+/// message `me` sent, to its other recipients), in the message's thread.
+pub fn reply_prefill(msg: &Message, me: &str) -> ReplyTo {
+    let recipient_names = if msg.sender_name == me {
+        reply_all_recipients(&msg.sender_name, &msg.recipients, me)
+    } else {
+        vec![msg.sender_name.clone()]
+    };
+    ReplyTo {
+        thread_id: msg
+            .thread_id
+            .clone()
+            .or_else(|| Some(format!("thread-{}", msg.id))),
+        subject: msg.subject.clone(),
+        recipient_names,
+    }
+}
+
+/// A composer key binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplyKey {
+    /// Ctrl+Enter or Cmd+Enter
+    Send,
+    /// Escape
+    Collapse,
+}
+
+impl ReplyKey {
+    /// Map a `KeyboardEvent.key` value and its modifiers to a binding.
+    pub fn from_key(key: &str, ctrl: bool, meta: bool) -> Option<Self> {
+        match key {
+            "Enter" if ctrl || meta => Some(Self::Send),
+            "Escape" => Some(Self::Collapse),
+            _ => None,
+        }
+    }
+}
+
+/// Reply affordance and collapsible composer for a message.
+///
+/// # Props
+/// - `message`: Message being answered
+/// - `project_slug`: Project the reply is sent in
+/// - `on_sent`: Called after a reply is sent, e.g. to refresh the list
+#[component]
+pub fn InlineReply(
+    /// Message being answered
+    message: Message,
+    /// Project the reply is sent in
+    #[prop(into)]
+    project_slug: String,
+    /// Called after a reply is sent
+    on_sent: Callback<()>,
+) -> impl IntoView {
+    let identities = reply_identities(&message);
+    let expanded = RwSignal::new(false);
+    let reply_as = RwSignal::new(identities.first().cloned().unwrap_or_default());
+    let message = StoredValue::new(message);
+    let project_slug = StoredValue::new(project_slug);
+    let toaster = use_toaster();
+
+    let sent = Callback::new(move |_| {
+        expanded.set(false);
+        toaster.toast(Toast::success("Reply sent"));
+        on_sent.run(());
+    });
+    let collapse = Callback::new(move |_| expanded.set(false));
+
+    view! {
+        <div class="px-6 py-4 border-t border-border">
+            {move || {
+                if identities.is_empty() {
+                    return None;
+                }
+                if !expanded.get() {
+                    return Some(view! {
+                        <Button
+                            variant=ButtonVariant::Outline
+                            size=ButtonSize::Sm
+                            on_click=Callback::new(move |_| expanded.set(true))
+                        >
+                            <i data-lucide="reply" class="icon-sm"></i>
+                            <span>"Reply"</span>
+                        </Button>
+                    }.into_any());
+                }
+
+                let select_as = (identities.len() > 1).then(|| {
+                    let options = identities.clone();
+                    view! {
+                        <label class="flex items-center gap-2 text-xs text-muted-foreground">
+                            "Reply as"
+                            <select
+                                class="input h-7 py-0 text-xs w-auto"
+                                prop:value=move || reply_as.get()
+                                on:change=move |ev| reply_as.set(event_target_value(&ev))
+                            >
+                                {options.into_iter().map(|name| view! {
+                                    <option value=name.clone()>{name.clone()}</option>
+                                }).collect_view()}
+                            </select>
+                        </label>
+                    }
+                });
+
+                Some(view! {
+                    <div class="space-y-2">
+                        {select_as}
+                        // Remount per identity: drafts are kept per sender
+                        {move || {
+                            let me = reply_as.get();
+                            let reply_to = message.with_value(|msg| reply_prefill(msg, &me));
+                            view! {
+                                <ReplyForm
+                                    project_slug=project_slug.get_value()
+                                    sender_name=me
+                                    reply_to=reply_to
+                                    on_sent=sent
+                                    on_collapse=collapse
+                                />
+                            }
+                        }}
+                    </div>
+                }.into_any())
+            }}
+        </div>
+    }
+}
+
+/// The expanded composer for one sending identity.
+#[component]
+fn ReplyForm(
+    project_slug: String,
+    sender_name: String,
+    reply_to: ReplyTo,
+    on_sent: Callback<()>,
+    on_collapse: Callback<()>,
+) -> impl IntoView {
+    let form = use_compose_form(&project_slug, &sender_name, Some(&reply_to), None);
+    let ComposeForm {
+        recipients,
+        subject,
+        body,
+        recipients_valid,
+        rejected,
+        sending,
+        error,
+        ..
+    } = form.clone();
+    let draft_restored = form.draft.restored;
+
+    let send = {
+        let form = form.clone();
+        move || form.submit(on_sent)
+    };
+    let on_keydown = {
+        let send = send.clone();
+        move |ev: web_sys::KeyboardEvent| {
+            match ReplyKey::from_key(&ev.key(), ev.ctrl_key(), ev.meta_key()) {
+                Some(ReplyKey::Send) => send(),
+                Some(ReplyKey::Collapse) => on_collapse.run(()),
+                None => return,
+            }
+            ev.prevent_default();
+            ev.stop_propagation();
+        }
+    };
+    let cannot_send = {
+        let form = form.clone();
+        Signal::derive(move || form.cannot_send())
+    };
+
+    view! {
+        <div class="space-y-2" on:keydown=on_keydown>
+            {move || draft_restored.get().then(|| view! {
+                <p class="flex items-center gap-1 text-xs text-amber-700 dark:text-amber-400">
+                    <i data-lucide="file-clock" class="icon-xs"></i>
+                    "Draft restored"
+                </p>
+            })}
+            <div class="flex items-center gap-2 text-xs text-muted-foreground">
+                <span>"From " <span class="font-medium text-foreground">{sender_name.clone()}</span></span>
+            </div>
+            <RecipientPicker
+                project_slug=project_slug.clone()
+                selected=recipients
+                valid=recipients_valid
+                rejected=rejected
+                exclude=vec![sender_name.clone()]
+                id="inline-reply-recipients"
+            />
+            <Input
+                value=subject
+                placeholder="Subject".to_string()
+                aria_label="Reply subject".to_string()
+                class="h-8 text-sm".to_string()
+            />
+            <textarea
+                prop:value=move || body.get()
+                on:input=move |ev| body.set(event_target_value(&ev))
+                rows="4"
+                placeholder="Write a reply... (Ctrl+Enter to send, Esc to collapse)"
+                aria-label="Reply body"
+                class="input resize-y font-mono text-sm"
+                style="height: auto; min-height: 6rem;"
+                autofocus=true
+            ></textarea>
+            {move || error.get().map(|e| view! {
+                <p class="text-xs text-destructive">{e}</p>
+            })}
+            <div class="flex justify-end gap-2">
+                <Button
+                    variant=ButtonVariant::Ghost
+                    size=ButtonSize::Sm
+                    on_click=Callback::new(move |_| on_collapse.run(()))
+                >
+                    "Cancel"
+                </Button>
+                <Button
+                    size=ButtonSize::Sm
+                    on_click=Callback::new(move |_| send())
+                    disabled=cannot_send
+                >
+                    {move || if sending.get() {
+                        view! {
+                            <i data-lucide="loader-2" class="icon-sm animate-spin"></i>
+                            <span>"Sending..."</span>
+                        }.into_any()
+                    } else {
+                        view! {
+                            <i data-lucide="send" class="icon-sm"></i>
+                            <span>"Send"</span>
+                        }.into_any()
+                    }}
+                </Button>
+            </div>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(sender: &str, recipients: &[&str], thread_id: Option<&str>) -> Message {
+        serde_json::from_value(serde_json::json!({
+            "id": 42,
+            "project_id": 1,
+            "sender_id": 1,
+            "sender_name": sender,
+            "thread_id": thread_id,
+            "subject": "Re: Build status",
+            "body_md": "Green.",
+            "importance": "normal",
+            "created_ts": "2026-01-01T00:00:00",
+            "recipients": recipients,
+        }))
+        .expect("valid message")
+    }
+
+    #[test]
+    fn test_reply_prefill_answers_sender_in_thread() {
+        let msg = message("BlueLake", &["GreenCastle", "RedStone"], Some("TKT-7"));
+        let reply = reply_prefill(&msg, "GreenCastle");
+        assert_eq!(reply.recipient_names, vec!["BlueLake"]);
+        assert_eq!(reply.thread_id.as_deref(), Some("TKT-7"));
+        assert_eq!(reply.subject, "Re: Build status");
+    }
+
+    #[test]
+    fn test_reply_prefill_own_message_and_threadless() {
+        let msg = message("BlueLake", &["GreenCastle", "BlueLake", "RedStone"], None);
+        let reply = reply_prefill(&msg, "BlueLake");
+        assert_eq!(reply.recipient_names, vec!["GreenCastle", "RedStone"]);
+        assert_eq!(reply.thread_id.as_deref(), Some("thread-42"));
+    }
+
+    #[test]
+    fn test_reply_identities_dedupes_in_order() {
+        let msg = message("BlueLake", &["RedStone", "GreenCastle", "RedStone"], None);
+        assert_eq!(reply_identities(&msg), vec!["RedStone", "GreenCastle"]);
+        assert!(reply_identities(&message("BlueLake", &[], None)).is_empty());
+    }
+
+    #[test]
+    fn test_reply_key_bindings() {
+        assert_eq!(
+            ReplyKey::from_key("Enter", true, false),
+            Some(ReplyKey::Send)
+        );
+        assert_eq!(
+            ReplyKey::from_key("Enter", false, true),
+            Some(ReplyKey::Send)
+        );
+        assert_eq!(ReplyKey::from_key("Enter", false, false), None);
+        assert_eq!(
+            ReplyKey::from_key("Escape", false, false),
+            Some(ReplyKey::Collapse)
+        );
+        assert_eq!(ReplyKey::from_key("a", true, false), None);
+    }
+}
@@ -1,7 +1,8 @@
 //! Main layout component with navigation.
 //! Digital Correspondence design - postal aesthetics meets terminal precision.
 
-use super::{Button, ButtonSize, ButtonVariant};
+use super::{Button, ButtonSize, ButtonVariant, NotificationCenter, use_theme};
+use crate::api::fetch::is_online;
 use leptos::prelude::*;
 use leptos_router::components::Outlet;
 use leptos_router::hooks::use_location;
@@ -9,46 +10,18 @@ use leptos_router::hooks::use_location;
 /// Main layout wrapper with navigation and content outlet.
 #[component]
 pub fn Layout() -> impl IntoView {
-    // Dark mode signal - persisted to localStorage
-    let (dark_mode, set_dark_mode) = signal(false);
+    // Theme preference (Light/Dark/System), applied by ThemeProvider
+    let theme = use_theme();
 
     // Mobile navigation state
     let mobile_nav_open = RwSignal::new(false);
 
+    // False while the backend is unreachable
+    let online = is_online();
+
     // Get current location for aria-current
     let location = use_location();
 
-    // Initialize from localStorage and watch for changes
-    Effect::new(move |_| {
-        if let Some(window) = web_sys::window()
-            && let Ok(Some(storage)) = window.local_storage()
-            && let Ok(Some(saved)) = storage.get_item("darkMode")
-            && saved == "true"
-        {
-            set_dark_mode.set(true);
-        }
-    });
-
-    // Toggle dark mode class on document and save preference
-    Effect::new(move |_| {
-        if let Some(window) = web_sys::window() {
-            if let Some(document) = window.document()
-                && let Some(html) = document.document_element()
-            {
-                if dark_mode.get() {
-                    let _ = html.class_list().add_1("dark");
-                } else {
-                    let _ = html.class_list().remove_1("dark");
-                }
-            }
-            // Save to localStorage
-            if let Ok(Some(storage)) = window.local_storage() {
-                let _ =
-                    storage.set_item("darkMode", if dark_mode.get() { "true" } else { "false" });
-            }
-        }
-    });
-
     // Close mobile nav when clicking outside or navigating
     Effect::new(move |_| {
         // Close mobile nav on route change
@@ -88,32 +61,45 @@ pub fn Layout() -> impl IntoView {
                                 <NavLink href="/projects" label="Projects" icon="folder-open" />
                                 <NavLink href="/agents" label="Agents" icon="bot" />
                                 <NavLink href="/inbox" label="Inbox" icon="inbox" />
+                                <NavLink href="/sent" label="Sent" icon="send" />
+                                <NavLink href="/threads" label="Threads" icon="messages-square" />
                                 <NavLink href="/mail/unified" label="All Mail" icon="layers" />
                                 <NavLink href="/attachments" label="Files" icon="paperclip" />
+                                <NavLink href="/reservations" label="Locks" icon="lock" />
+                                <NavLink href="/stats" label="Stats" icon="chart-column" />
                             </div>
                         </div>
 
                         // Right side actions
                         <div class="flex items-center space-x-3">
                             // Status indicator
-                            <div class="hidden sm:flex status-online">
-                                <span class="text-xs font-medium">"Online"</span>
+                            <div class=move || {
+                                if online.get() { "hidden sm:flex status-online" } else { "hidden sm:flex status-offline" }
+                            }>
+                                <span class="text-xs font-medium">
+                                    {move || if online.get() { "Online" } else { "Offline" }}
+                                </span>
                             </div>
 
-                            // Dark mode toggle
-                            <Button
-                                variant=ButtonVariant::Ghost
-                                size=ButtonSize::Icon
-                                on_click=Callback::new(move |_| set_dark_mode.update(|v| *v = !*v))
-                                title={if dark_mode.get() { "Switch to light mode".to_string() } else { "Switch to dark mode".to_string() }}
-                                class="border border-border rounded-full hover:bg-accent".to_string()
-                            >
-                                {move || if dark_mode.get() {
-                                    view! { <i data-lucide="sun" class="icon-lg text-primary"></i> }.into_any()
-                                } else {
-                                    view! { <i data-lucide="moon" class="icon-lg text-muted-foreground"></i> }.into_any()
-                                }}
-                            </Button>
+                            // Outstanding acks, urgent mail and reservation conflicts
+                            <NotificationCenter />
+
+                            // Theme toggle: Light -> Dark -> System
+                            {move || {
+                                let pref = theme.preference();
+                                view! {
+                                    <Button
+                                        variant=ButtonVariant::Ghost
+                                        size=ButtonSize::Icon
+                                        on_click=Callback::new(move |_| theme.cycle())
+                                        title=format!("Theme: {} (switch to {})", pref.label(), pref.next().label())
+                                        aria_label=format!("Theme: {}", pref.label())
+                                        class="border border-border rounded-full hover:bg-accent".to_string()
+                                    >
+                                        <i data-lucide=pref.icon() class="icon-lg text-muted-foreground"></i>
+                                    </Button>
+                                }
+                            }}
 
                             // Mobile hamburger menu button
                             <Button
@@ -176,6 +162,20 @@ pub fn Layout() -> impl IntoView {
                                     current_path=Signal::derive(move || location.pathname.get())
                                     on_click=Callback::new(move |_| mobile_nav_open.set(false))
                                 />
+                                <MobileNavLink
+                                    href="/sent"
+                                    label="Sent"
+                                    icon="send"
+                                    current_path=Signal::derive(move || location.pathname.get())
+                                    on_click=Callback::new(move |_| mobile_nav_open.set(false))
+                                />
+                                <MobileNavLink
+                                    href="/threads"
+                                    label="Threads"
+                                    icon="messages-square"
+                                    current_path=Signal::derive(move || location.pathname.get())
+                                    on_click=Callback::new(move |_| mobile_nav_open.set(false))
+                                />
                                 <MobileNavLink
                                     href="/mail/unified"
                                     label="All Mail"
@@ -190,6 +190,20 @@ pub fn Layout() -> impl IntoView {
                                     current_path=Signal::derive(move || location.pathname.get())
                                     on_click=Callback::new(move |_| mobile_nav_open.set(false))
                                 />
+                                <MobileNavLink
+                                    href="/reservations"
+                                    label="Locks"
+                                    icon="lock"
+                                    current_path=Signal::derive(move || location.pathname.get())
+                                    on_click=Callback::new(move |_| mobile_nav_open.set(false))
+                                />
+                                <MobileNavLink
+                                    href="/stats"
+                                    label="Stats"
+                                    icon="chart-column"
+                                    current_path=Signal::derive(move || location.pathname.get())
+                                    on_click=Callback::new(move |_| mobile_nav_open.set(false))
+                                />
                             </nav>
                         </div>
                     })
@@ -213,6 +227,19 @@ pub fn Layout() -> impl IntoView {
                 }
             }}
 
+            // Offline banner, shown until a request reaches the backend again
+            <Show when=move || !online.get()>
+                <div
+                    class="relative bg-amber-50 dark:bg-amber-900/20 border-b border-amber-200 dark:border-amber-800 text-amber-800 dark:text-amber-300"
+                    role="alert"
+                >
+                    <div class="max-w-7xl mx-auto px-4 py-2 flex items-center gap-2 text-sm">
+                        <i data-lucide="wifi-off" class="icon-sm"></i>
+                        <span>"Server unreachable. Showing the last loaded data until it responds again."</span>
+                    </div>
+                </div>
+            </Show>
+
             // Main content area
             <main id="main-content" tabindex="-1" class="relative max-w-7xl mx-auto py-8 px-4 sm:px-6 lg:px-8 flex-1 w-full" role="main">
                 <div class="animate-fade-in">
@@ -400,6 +427,8 @@ mod tests {
             ("Projects", "folder-open"),
             ("Agents", "bot"),
             ("Inbox", "inbox"),
+            ("Sent", "send"),
+            ("Threads", "messages-square"),
             ("All Mail", "layers"),
             ("Files", "paperclip"),
         ];
@@ -418,6 +447,8 @@ mod tests {
             "/projects",
             "/agents",
             "/inbox",
+            "/sent",
+            "/threads",
             "/mail/unified",
             "/attachments",
         ];
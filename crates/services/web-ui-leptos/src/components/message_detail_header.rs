@@ -3,7 +3,8 @@
 //! Displays message subject, sender/recipient info with avatars,
 //! project and timestamp, plus action buttons.
 
-use crate::components::{AgentAvatar, AvatarSize, Button, ButtonVariant};
+use crate::components::{AgentAvatar, AvatarSize, Button, ButtonVariant, Toast, use_toaster};
+use crate::utils::permalink::{message_path, share_url};
 use leptos::prelude::*;
 
 /// Format a timestamp for display
@@ -24,7 +25,7 @@ fn format_timestamp(ts: &str) -> String {
 
 /// Copy text to clipboard using Web API
 #[cfg(target_arch = "wasm32")]
-fn copy_to_clipboard(text: &str) {
+pub(crate) fn copy_to_clipboard(text: &str) {
     if let Some(window) = web_sys::window() {
         let navigator = window.navigator();
         // clipboard() returns Clipboard directly in current web-sys
@@ -34,20 +35,20 @@ fn copy_to_clipboard(text: &str) {
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn copy_to_clipboard(_text: &str) {
+pub(crate) fn copy_to_clipboard(_text: &str) {
     // No-op for non-WASM builds
 }
 
 /// Get window origin for building URLs
 #[cfg(target_arch = "wasm32")]
-fn window_origin() -> String {
+pub(crate) fn window_origin() -> String {
     web_sys::window()
         .and_then(|w| w.location().origin().ok())
         .unwrap_or_else(|| crate::api::client::api_base_url())
 }
 
 #[cfg(not(target_arch = "wasm32"))]
-fn window_origin() -> String {
+pub(crate) fn window_origin() -> String {
     crate::api::client::api_base_url()
 }
 
@@ -110,15 +111,26 @@ pub fn MessageDetailHeader(
     sent_at: String,
     /// Message ID for building links
     message_id: i64,
+    /// Server-provided permalink; built from the slug and ID when absent
+    #[prop(default = None)]
+    permalink: Option<String>,
+    /// Shows a "Recall" action when set (only the sender may recall)
+    #[prop(default = None)]
+    on_recall: Option<Callback<()>>,
+    /// Shows a "Forward" action when set
+    #[prop(default = None)]
+    on_forward: Option<Callback<()>>,
 ) -> impl IntoView {
     // State for copy button feedback
     let copied = RwSignal::new(false);
 
-    let message_id_for_copy = message_id;
+    let toaster = use_toaster();
+    let link_path = message_path(&project_slug, message_id);
     let copy_link = move |_| {
-        let url = format!("{}/inbox/{}", window_origin(), message_id_for_copy);
+        let url = share_url(permalink.as_deref(), &link_path, &window_origin());
         copy_to_clipboard(&url);
         copied.set(true);
+        toaster.toast(Toast::success("Link copied to clipboard"));
 
         // Reset after 2 seconds
         leptos::task::spawn_local(async move {
@@ -192,6 +204,26 @@ pub fn MessageDetailHeader(
                     <i data-lucide="external-link" class="icon-sm"></i>
                     "Open in Project"
                 </a>
+
+                {on_forward.map(|on_forward| view! {
+                    <Button
+                        variant=ButtonVariant::Secondary
+                        on_click=on_forward
+                    >
+                        <i data-lucide="forward" class="icon-sm"></i>
+                        "Forward"
+                    </Button>
+                })}
+
+                {on_recall.map(|on_recall| view! {
+                    <Button
+                        variant=ButtonVariant::Destructive
+                        on_click=on_recall
+                    >
+                        <i data-lucide="undo-2" class="icon-sm"></i>
+                        "Recall"
+                    </Button>
+                })}
             </div>
         </div>
     }
@@ -4,31 +4,40 @@ pub mod alert;
 pub mod avatar;
 pub mod badge;
 pub mod breadcrumb;
+pub mod bulk_actions;
 pub mod button;
 pub mod card;
 pub mod checkbox;
+pub mod compose_form;
 pub mod compose_message;
 pub mod cva;
+pub mod date_range_picker;
 pub mod dialog;
 pub mod filter_bar;
 pub mod inline_message_detail;
+pub mod inline_reply;
 pub mod input;
 pub mod label;
 pub mod layout;
 pub mod mark_read_button;
 pub mod message_detail_header;
+pub mod notification_center;
 pub mod overseer_composer;
 pub mod pagination;
 pub mod progress;
 pub mod project_card;
+pub mod recipient_picker;
 pub mod select;
 pub mod separator;
 pub mod skeleton;
+pub mod snooze_menu;
 pub mod spinner;
 pub mod split_view;
 pub mod switch;
 pub mod tabs;
 pub mod textarea;
+pub mod theme;
+pub mod thread_mute_toggle;
 pub mod toast;
 pub mod tooltip;
 
@@ -36,16 +45,20 @@ pub use alert::{Alert, AlertDescription, AlertTitle, AlertVariant};
 pub use avatar::{AgentAvatar, AvatarSize};
 pub use badge::{Badge, BadgeVariant};
 pub use breadcrumb::{Breadcrumb, BreadcrumbItem};
+pub use bulk_actions::BulkActionBar;
 pub use button::{Button, ButtonSize, ButtonVariant};
 pub use card::{Card, CardContent, CardDescription, CardFooter, CardHeader, CardTitle};
-pub use compose_message::{ComposeMessage, ComposeProps, ReplyTo};
+pub use compose_form::{ComposeForm, reply_subject, use_compose_form};
+pub use compose_message::{ComposeMessage, ComposeProps, ForwardOf, ReplyTo};
+pub use date_range_picker::{DatePreset, DateRangePicker};
 pub use dialog::{
     Dialog, DialogContent, DialogDescription, DialogFooter, DialogHeader, DialogTitle,
     DialogTrigger,
 };
 
-pub use filter_bar::{FilterBar, FilterState};
+pub use filter_bar::{FilterBar, FilterState, use_filter_url_sync};
 pub use inline_message_detail::InlineMessageDetail;
+pub use inline_reply::InlineReply;
 pub use input::Input;
 pub use layout::Layout;
 pub use mark_read_button::MarkReadButton;
@@ -53,23 +66,28 @@ pub use message_detail_header::MessageDetailHeader;
 pub use overseer_composer::{OverseerComposeProps, OverseerComposer};
 pub use pagination::Pagination;
 pub use project_card::{ProjectCard, ProjectStatus, determine_project_status};
+pub use recipient_picker::{RecipientPicker, reply_all_recipients};
 pub use select::{Select, SelectIcon, SelectOption};
 pub use separator::{Orientation, Separator};
 pub use skeleton::{
     AttachmentCardSkeleton, AttachmentGridSkeleton, CardSkeleton, MessageDetailSkeleton,
     MessageItemSkeleton, MessageListSkeleton, Skeleton, TableRowSkeleton,
 };
+pub use snooze_menu::{SnoozeMenu, SnoozePreset};
 pub use split_view::{EmptyDetailPanel, MessageListItem, SplitViewLayout};
 pub use toast::{Toast, ToastVariant, Toaster, ToasterContext, use_toaster};
 
 // New shadcn-ui components
 pub use checkbox::{Checkbox, CheckboxState};
 pub use label::Label;
+pub use notification_center::NotificationCenter;
 pub use progress::{Progress, ProgressIndeterminate};
 pub use spinner::{Spinner, SpinnerSize};
 pub use switch::Switch;
 pub use tabs::{TabItem, Tabs, TabsContent, TabsContext, TabsList, TabsTrigger};
 pub use textarea::Textarea;
+pub use theme::{ThemeContext, ThemePreference, ThemeProvider, use_theme};
+pub use thread_mute_toggle::ThreadMuteToggle;
 pub use tooltip::{SimpleTooltip, Tooltip, TooltipSide};
 
 // Magic UI - animated components
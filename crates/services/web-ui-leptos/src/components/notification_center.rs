@@ -0,0 +1,318 @@
+//! Notification center for the header.
+//!
+//! A bell with a count badge that opens a drawer listing outstanding
+//! acknowledgements, acknowledgements the actor owes, unread urgent mail and
+//! reservation conflicts from
+//! `GET /api/notifications`. The list is polled every 30 seconds; entries
+//! that arrive between polls are flagged until the drawer is opened.
+
+use super::{Button, ButtonSize, ButtonVariant, ShimmerBadge};
+use crate::api::client::{self, Notification};
+use leptos::prelude::*;
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// How often the notification list is refreshed, in milliseconds.
+const POLL_INTERVAL_MS: u32 = 30_000;
+
+/// Where a notification leads.
+///
+/// Pending acks open the thread (or the message if it has none), requested
+/// acks and unread mail open the message in the actor's inbox, and
+/// reservation conflicts open the project's reservation list.
+fn notification_href(notification: &Notification, actor: &str) -> String {
+    let project = urlencoding::encode(&notification.project_slug);
+    match (notification.kind.as_str(), notification.message_id) {
+        ("reservation_conflict", _) | (_, None) => {
+            format!("/projects/{}/file-reservations", project)
+        }
+        ("ack_pending", Some(id)) => match &notification.thread_id {
+            Some(thread_id) => format!(
+                "/thread/{}?project={}",
+                urlencoding::encode(thread_id),
+                project
+            ),
+            None => format!("/inbox/{}?project={}", id, project),
+        },
+        (_, Some(id)) => format!(
+            "/inbox/{}?project={}&agent={}",
+            id,
+            project,
+            urlencoding::encode(actor)
+        ),
+    }
+}
+
+/// Lucide icon for a notification kind.
+fn notification_icon(kind: &str) -> &'static str {
+    match kind {
+        "ack_pending" => "check-check",
+        "ack_requested" => "mail-check",
+        "urgent_unread" => "alert-triangle",
+        "reservation_conflict" => "lock",
+        _ => "bell",
+    }
+}
+
+/// Count shown on the bell; large counts are capped.
+fn badge_label(count: usize) -> String {
+    if count > 99 {
+        "99+".to_string()
+    } else {
+        count.to_string()
+    }
+}
+
+/// Keys in `latest` that were not in `seen`.
+fn new_keys(seen: &HashSet<String>, latest: &[Notification]) -> Vec<String> {
+    latest
+        .iter()
+        .filter(|n| !seen.contains(&n.key))
+        .map(|n| n.key.clone())
+        .collect()
+}
+
+/// Bell button and notification drawer.
+///
+/// # Accessibility
+/// - The bell's accessible name includes the count; `aria-expanded` tracks the drawer
+/// - The drawer is a labelled `region`; each entry is a link plus a dismiss button
+///
+/// # Example
+/// ```rust,ignore
+/// view! { <NotificationCenter /> }
+/// ```
+#[component]
+pub fn NotificationCenter() -> impl IntoView {
+    let notifications = RwSignal::new(Vec::<Notification>::new());
+    let actor = RwSignal::new(String::new());
+    let open = RwSignal::new(false);
+    // Arrived since the drawer was last opened
+    let has_new = RwSignal::new(false);
+    let seen = StoredValue::new(HashSet::<String>::new());
+
+    let refresh = move || {
+        leptos::task::spawn_local(async move {
+            match client::get_notifications().await {
+                Ok(response) => {
+                    let first_load =
+                        seen.with_value(|s| s.is_empty()) && actor.get_untracked().is_empty();
+                    let fresh = seen.with_value(|s| new_keys(s, &response.notifications));
+                    if !fresh.is_empty() && !first_load && !open.get_untracked() {
+                        has_new.set(true);
+                    }
+                    seen.update_value(|s| s.extend(fresh));
+                    actor.set(response.actor);
+                    notifications.set(response.notifications);
+                }
+                Err(e) => leptos::logging::warn!("NotificationCenter: {}", e),
+            }
+        });
+    };
+
+    // Poll until the header unmounts
+    let alive = Arc::new(AtomicBool::new(true));
+    {
+        let alive = alive.clone();
+        leptos::task::spawn_local(async move {
+            while alive.load(Ordering::Relaxed) {
+                refresh();
+                gloo_timers::future::TimeoutFuture::new(POLL_INTERVAL_MS).await;
+            }
+        });
+    }
+    on_cleanup(move || alive.store(false, Ordering::Relaxed));
+
+    let toggle = Callback::new(move |_| {
+        open.update(|v| *v = !*v);
+        has_new.set(false);
+    });
+
+    let dismiss = move |keys: Vec<String>| {
+        notifications.update(|all| all.retain(|n| !keys.contains(&n.key)));
+        leptos::task::spawn_local(async move {
+            if let Err(e) = client::dismiss_notifications(&keys).await {
+                leptos::logging::warn!("NotificationCenter: {}", e);
+                refresh();
+            }
+        });
+    };
+    let dismiss_all = move |_| {
+        let keys = notifications.with_untracked(|all| all.iter().map(|n| n.key.clone()).collect());
+        dismiss(keys);
+    };
+
+    let count = Signal::derive(move || notifications.with(Vec::len));
+
+    view! {
+        <div class="relative">
+            <Button
+                variant=ButtonVariant::Ghost
+                size=ButtonSize::Icon
+                on_click=toggle
+                aria_expanded=Signal::derive(move || open.get().to_string())
+                aria_controls="notification-drawer".to_string()
+                class="relative border border-border rounded-full hover:bg-accent".to_string()
+            >
+                <i data-lucide="bell" class="icon-lg text-muted-foreground"></i>
+                <span class="sr-only">"Notifications"</span>
+                <Show when=move || { count.get() > 0 }>
+                    <span class="absolute -top-1 -right-1 min-w-[1.25rem] h-5 px-1 rounded-full bg-destructive text-destructive-foreground text-xs font-semibold flex items-center justify-center">
+                        {move || badge_label(count.get())}
+                    </span>
+                </Show>
+            </Button>
+
+            <Show when=move || has_new.get()>
+                <ShimmerBadge class="absolute top-full right-0 mt-1 text-xs whitespace-nowrap">
+                    "New"
+                </ShimmerBadge>
+            </Show>
+
+            <Show when=move || open.get()>
+                <div
+                    id="notification-drawer"
+                    class="absolute right-0 top-full mt-2 w-80 sm:w-96 max-h-[70vh] overflow-y-auto z-50 rounded-lg border border-border bg-background shadow-lg animate-slide-down"
+                    role="region"
+                    aria-label="Notifications"
+                >
+                    <div class="flex items-center justify-between px-4 py-3 border-b border-border">
+                        <span class="font-display font-semibold text-sm">"Notifications"</span>
+                        <Show when=move || { count.get() > 0 }>
+                            <Button variant=ButtonVariant::Ghost size=ButtonSize::Sm on_click=Callback::new(dismiss_all)>
+                                "Dismiss all"
+                            </Button>
+                        </Show>
+                    </div>
+                    <Show
+                        when=move || { count.get() > 0 }
+                        fallback=|| view! {
+                            <p class="px-4 py-6 text-sm text-muted-foreground text-center">"Nothing needs your attention."</p>
+                        }
+                    >
+                        <ul class="divide-y divide-border">
+                            <For
+                                each=move || notifications.get()
+                                key=|n| n.key.clone()
+                                children=move |n| {
+                                    let href = notification_href(&n, &actor.get_untracked());
+                                    let key = n.key.clone();
+                                    view! {
+                                        <li class="flex items-start gap-3 px-4 py-3 hover:bg-muted/50">
+                                            <i data-lucide=notification_icon(&n.kind) class="icon-sm mt-0.5 text-primary"></i>
+                                            <a
+                                                href=href
+                                                class="flex-1 min-w-0"
+                                                on:click=move |_| open.set(false)
+                                            >
+                                                <p class="text-sm font-medium text-foreground">{n.title.clone()}</p>
+                                                <p class="text-xs text-muted-foreground truncate">{n.detail.clone()}</p>
+                                                <p class="text-xs text-muted-foreground font-mono">
+                                                    {format!("{} · {}", n.project_slug, n.created_ts.get(..16).unwrap_or(&n.created_ts).replace('T', " "))}
+                                                </p>
+                                            </a>
+                                            <button
+                                                type="button"
+                                                class="text-muted-foreground hover:text-foreground"
+                                                aria-label="Dismiss notification"
+                                                on:click=move |_| dismiss(vec![key.clone()])
+                                            >
+                                                <i data-lucide="x" class="icon-sm"></i>
+                                            </button>
+                                        </li>
+                                    }
+                                }
+                            />
+                        </ul>
+                    </Show>
+                </div>
+            </Show>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn notification(kind: &str, message_id: Option<i64>, thread_id: Option<&str>) -> Notification {
+        Notification {
+            key: format!("{}:1", kind),
+            kind: kind.to_string(),
+            project_slug: "my project".to_string(),
+            title: "title".to_string(),
+            detail: String::new(),
+            message_id,
+            thread_id: thread_id.map(str::to_string),
+            reservation_id: None,
+            created_ts: "2026-03-01T10:00:00".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_href_ack_pending_opens_thread() {
+        let n = notification("ack_pending", Some(7), Some("TKT-1/a"));
+        assert_eq!(
+            notification_href(&n, "Overseer"),
+            "/thread/TKT-1%2Fa?project=my%20project"
+        );
+
+        let n = notification("ack_pending", Some(7), None);
+        assert_eq!(
+            notification_href(&n, "Overseer"),
+            "/inbox/7?project=my%20project"
+        );
+    }
+
+    #[test]
+    fn test_href_urgent_unread_opens_actor_inbox() {
+        let n = notification("urgent_unread", Some(9), Some("t"));
+        assert_eq!(
+            notification_href(&n, "Blue Lake"),
+            "/inbox/9?project=my%20project&agent=Blue%20Lake"
+        );
+    }
+
+    #[test]
+    fn test_href_ack_requested_opens_actor_inbox() {
+        let n = notification("ack_requested", Some(4), Some("TKT-2"));
+        assert_eq!(
+            notification_href(&n, "Overseer"),
+            "/inbox/4?project=my%20project&agent=Overseer"
+        );
+        assert_eq!(notification_icon("ack_requested"), "mail-check");
+    }
+
+    #[test]
+    fn test_href_reservation_conflict_opens_reservations() {
+        let n = notification("reservation_conflict", None, None);
+        assert_eq!(
+            notification_href(&n, "Overseer"),
+            "/projects/my%20project/file-reservations"
+        );
+    }
+
+    #[test]
+    fn test_badge_label_caps_at_99() {
+        assert_eq!(badge_label(3), "3");
+        assert_eq!(badge_label(99), "99");
+        assert_eq!(badge_label(150), "99+");
+    }
+
+    #[test]
+    fn test_new_keys() {
+        let latest = vec![
+            notification("ack_pending", Some(1), None),
+            notification("urgent_unread", Some(2), None),
+        ];
+        let mut seen = HashSet::new();
+        assert_eq!(new_keys(&seen, &latest).len(), 2);
+
+        seen.insert("ack_pending:1".to_string());
+        assert_eq!(
+            new_keys(&seen, &latest),
+            vec!["urgent_unread:1".to_string()]
+        );
+    }
+}
@@ -3,8 +3,11 @@
 //!
 //! Follows shadcn/ui Dialog anatomy with destructive theme variant.
 
-use super::{Button, ButtonSize, ButtonVariant, Input, Select, SelectOption};
-use crate::api::client::{self, Agent};
+use super::{
+    Button, ButtonSize, ButtonVariant, Input, RecipientPicker, Select, SelectOption, Switch,
+};
+use crate::api::client::{self, Agent, MessageTemplate, RecipientFailure};
+use crate::utils::{DraftFields, agent_name_label, fill_placeholders, today, use_compose_draft};
 use leptos::prelude::*;
 
 /// Props for OverseerComposer component.
@@ -14,7 +17,7 @@ pub struct OverseerComposeProps {
     pub agents: Vec<Agent>,
     // Reply context (optional)
     pub reply_to_thread_id: Option<String>,
-    pub reply_to_recipient: Option<String>,
+    pub reply_to_recipients: Vec<String>,
     pub reply_subject: Option<String>,
 }
 
@@ -32,13 +35,21 @@ pub fn OverseerComposer(
     let importance = RwSignal::new("high".to_string()); // Default to High for Overseer
     let ack_required = RwSignal::new(true); // Default to True for Overseer
     let thread_id = RwSignal::new(String::new());
+    let recipients_valid = RwSignal::new(false);
+    // Recipients the server refused on the last attempt, flagged on their chips
+    let rejected = RwSignal::new(Vec::<RecipientFailure>::new());
+    // Send to every active agent, resolved by the server at send time
+    let broadcast = RwSignal::new(false);
+    // Canned directives; choosing one pre-fills the editable fields below
+    let templates = RwSignal::new(Vec::<MessageTemplate>::new());
+    let template_choice = RwSignal::new(String::new());
 
     let sending = RwSignal::new(false);
     let error = RwSignal::new(Option::<String>::None);
 
     // Initialize from props
-    if let Some(ref r) = props.reply_to_recipient {
-        recipients.set(vec![r.clone()]);
+    if !props.reply_to_recipients.is_empty() {
+        recipients.set(props.reply_to_recipients.clone());
     }
     if let Some(ref s) = props.reply_subject {
         subject.set(format!("OVERSEER: {}", s.trim_start_matches("re: ")));
@@ -51,43 +62,72 @@ pub fn OverseerComposer(
     // Hardcoded sender for Overseer Mode
     let sender_name = "Overseer".to_string();
 
+    // Restore and autosave the directive draft (per thread when replying)
+    let draft_context = format!(
+        "overseer:{}",
+        props.reply_to_thread_id.clone().unwrap_or_default()
+    );
+    let draft = use_compose_draft(
+        &project_slug,
+        &draft_context,
+        DraftFields {
+            recipients,
+            subject,
+            body,
+            importance,
+            thread_id,
+        },
+    );
+    let draft_restored = draft.restored;
+
     let all_agents = props.agents.clone();
 
-    // Toggle recipient selection
-    let toggle_recipient = move |name: String| {
-        let mut current = recipients.get();
-        if current.contains(&name) {
-            current.retain(|r| r != &name);
-        } else {
-            current.push(name);
-        }
-        recipients.set(current);
-    };
+    {
+        let project_slug = project_slug.clone();
+        leptos::task::spawn_local(async move {
+            // No templates is a normal state; the picker just stays hidden
+            if let Ok(list) = client::get_templates(&project_slug).await {
+                templates.set(list);
+            }
+        });
+    }
 
-    // Toggle All Candidates
-    let all_agents_clone = all_agents.clone();
-    let toggle_all = move |_| {
-        let current_len = recipients.get().len();
-        if current_len == all_agents_clone.len() {
-            recipients.set(vec![]);
-        } else {
-            recipients.set(all_agents_clone.iter().map(|a| a.name.clone()).collect());
-        }
-    };
+    Effect::new(move |_| {
+        let choice = template_choice.get();
+        let Some(template) = templates
+            .with_untracked(|list| list.iter().find(|t| t.id.to_string() == choice).cloned())
+        else {
+            return;
+        };
+        subject.set(template.subject);
+        body.set(template.body_md);
+        importance.set(template.importance);
+        ack_required.set(template.ack_required);
+    });
 
     // Send message handler
     let handle_submit = {
         let project_slug = project_slug.clone();
         let sender_name = sender_name.clone();
+        let draft = draft.clone();
         move |_| {
-            let recips = recipients.get();
-            let subj = subject.get();
-            let bod = body.get();
+            let to_all = broadcast.get();
+            let recips = if to_all { vec![] } else { recipients.get() };
+            let agent_label = agent_name_label(&recips, to_all);
+            let date = today();
+            let subj = fill_placeholders(&subject.get(), &agent_label, &date);
+            let bod = fill_placeholders(&body.get(), &agent_label, &date);
 
-            if recips.is_empty() {
+            if !to_all && recips.is_empty() {
                 error.set(Some("Target at least one agent.".to_string()));
                 return;
             }
+            if !to_all && !recipients_valid.get() {
+                error.set(Some(
+                    "Remove unknown agents before broadcasting.".to_string(),
+                ));
+                return;
+            }
             if subj.trim().is_empty() {
                 error.set(Some("Command subject required.".to_string()));
                 return;
@@ -99,6 +139,7 @@ pub fn OverseerComposer(
 
             sending.set(true);
             error.set(None);
+            rejected.set(Vec::new());
 
             let project = project_slug.clone();
             let sender = sender_name.clone();
@@ -106,6 +147,7 @@ pub fn OverseerComposer(
             let imp = importance.get();
             let ack = ack_required.get();
             let on_sent = on_sent;
+            let draft = draft.clone();
 
             leptos::task::spawn_local(async move {
                 match client::send_message(
@@ -121,14 +163,20 @@ pub fn OverseerComposer(
                     },
                     &imp,
                     ack,
+                    to_all,
                 )
                 .await
                 {
                     Ok(_) => {
+                        draft.finish();
                         on_sent.run(());
                     }
+                    Err(e) if !e.failed.is_empty() => {
+                        rejected.set(e.failed);
+                        sending.set(false);
+                    }
                     Err(e) => {
-                        error.set(Some(e.message));
+                        error.set(Some(e.error.message));
                         sending.set(false);
                     }
                 }
@@ -172,65 +220,86 @@ pub fn OverseerComposer(
             </div>
 
             <div class="flex-1 min-h-0 overflow-y-auto p-6 space-y-6 max-h-[60vh]">
+                // Restored draft notice
+                {move || draft_restored.get().then(|| {
+                    let draft = draft.clone();
+                    view! {
+                        <div class="flex items-center justify-between gap-3 rounded-md border border-amber-500/30 bg-amber-500/10 px-4 py-2 text-sm text-foreground">
+                            <span>"Draft restored"</span>
+                            <button
+                                type="button"
+                                class="text-amber-500 hover:text-amber-400 hover:underline underline-offset-4 font-medium"
+                                on:click=move |_| draft.discard()
+                            >
+                                "Discard draft"
+                            </button>
+                        </div>
+                    }
+                })}
+
+                // Template picker - only shown when the project has templates
+                {move || {
+                    let list = templates.get();
+                    (!list.is_empty()).then(|| {
+                        let options = list
+                            .iter()
+                            .map(|t| SelectOption::new(t.id.to_string(), t.name.clone()))
+                            .collect::<Vec<_>>();
+                        view! {
+                            <div class="space-y-2">
+                                <label class="text-sm font-medium text-foreground">
+                                    "Template"
+                                </label>
+                                <Select
+                                    id="overseerTemplate".to_string()
+                                    options=options
+                                    value=template_choice
+                                    placeholder="Start from a template...".to_string()
+                                    disabled=false
+                                />
+                                <p class="text-xs text-muted-foreground">
+                                    "{{agent_name}} and {{date}} are filled in when the directive is sent."
+                                </p>
+                            </div>
+                        }
+                    })
+                }}
+
                 // Target Agent Selection - improved spacing and alignment
                 <div class="space-y-4">
                     <div class="flex items-center justify-between">
-                        <label class="text-sm font-medium leading-none text-foreground">
+                        <label for="overseerRecipients" class="text-sm font-medium leading-none text-foreground">
                             "Target Agents"
                         </label>
-                        <button
-                            type="button"
-                            class="text-sm text-amber-500 hover:text-amber-400 hover:underline underline-offset-4 font-medium transition-colors focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring focus-visible:ring-offset-2 rounded-sm px-2 py-1"
-                            on:click=toggle_all
-                        >
-                            {
-                                let total_len = all_agents.len();
-                                move || if recipients.get().len() == total_len { "Deselect All" } else { "Select All" }
-                            }
-                        </button>
+                        <div class="flex items-center gap-2">
+                            <label for="overseerBroadcast" class="text-sm text-muted-foreground">
+                                "Send to all agents"
+                            </label>
+                            <Switch
+                                id="overseerBroadcast"
+                                checked=broadcast
+                                on_change=Callback::new(move |v| broadcast.set(v))
+                            />
+                        </div>
                     </div>
 
-                    {if all_agents.is_empty() {
-                        view! { <p class="text-sm text-muted-foreground italic">"No agents available."</p> }.into_any()
-                    } else {
-                        view! {
-                            <div class="flex flex-wrap gap-2">
-                                {all_agents.iter().map(|agent| {
-                                    let name = agent.name.clone();
-                                    let name_display = name.clone();
-                                    let toggle = toggle_recipient;
-                                    // shadcn toggle button pattern - outline variant when unselected, destructive when selected
-                                    view! {
-                                        <button
-                                            type="button"
-                                            on:click=move |_| toggle(name.clone())
-                                            class=move || {
-                                                // Enhanced button styling with better contrast
-                                                let base = "inline-flex items-center justify-center gap-2 whitespace-nowrap rounded-md text-sm font-medium ring-offset-background transition-all duration-200 focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring focus-visible:ring-offset-2 disabled:pointer-events-none disabled:opacity-50 h-9 px-3 min-w-[120px]";
-                                                if recipients.get().contains(&name_display) {
-                                                    // Selected: amber accent for overseer theme
-                                                    format!("{} bg-amber-500 text-white hover:bg-amber-600 shadow-sm border-0", base)
-                                                } else {
-                                                    // Unselected: subtle dark variant
-                                                    format!("{} border border-border/50 bg-muted/30 hover:bg-muted/50 hover:border-border text-foreground", base)
-                                                }
-                                            }
-                                        >
-                                            <svg class="h-4 w-4 shrink-0" viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
-                                                <path d="M12 8V4H8"></path>
-                                                <rect width="16" height="12" x="4" y="8" rx="2"></rect>
-                                                <path d="M2 14h2"></path>
-                                                <path d="M20 14h2"></path>
-                                                <path d="M15 13v2"></path>
-                                                <path d="M9 13v2"></path>
-                                            </svg>
-                                            <span class="truncate">{name_display.clone()}</span>
-                                        </button>
-                                    }
-                                }).collect::<Vec<_>>()}
-                            </div>
-                        }.into_any()
-                    }}
+                    <Show
+                        when=move || !broadcast.get()
+                        fallback=|| view! {
+                            <p class="rounded-md border border-amber-500/30 bg-amber-500/10 px-4 py-3 text-sm text-foreground">
+                                "Every active agent in the project receives this directive when it is sent."
+                            </p>
+                        }
+                    >
+                        <RecipientPicker
+                            project_slug=project_slug.clone()
+                            selected=recipients
+                            valid=recipients_valid
+                            agents=all_agents.clone()
+                            rejected=rejected
+                            id="overseerRecipients"
+                        />
+                    </Show>
                 </div>
 
                 // Subject / Directive - improved label styling
@@ -349,7 +418,9 @@ pub fn OverseerComposer(
                     <Button
                         variant=ButtonVariant::Destructive
                         on_click=Callback::new(move |_| handle_submit(()))
-                        disabled=Signal::derive(move || sending.get() || recipients.get().is_empty())
+                        disabled=Signal::derive(move || {
+                            sending.get() || (!broadcast.get() && (recipients.get().is_empty() || !recipients_valid.get()))
+                        })
                     >
                         {move || {
                             if sending.get() {
@@ -4,9 +4,13 @@
 
 use crate::components::{
     Badge, BadgeVariant, Card, CardContent, CardDescription, CardFooter, CardHeader, CardTitle,
+    NumberCounter,
 };
 use leptos::prelude::*;
 
+/// How recently a project must have seen activity to count as active.
+pub const ACTIVE_WINDOW_SECS: i64 = 7 * 24 * 60 * 60;
+
 /// Project status enum
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ProjectStatus {
@@ -44,6 +48,10 @@ impl ProjectStatus {
 ///         status=ProjectStatus::Active
 ///         agent_count=3
 ///         message_count=42
+///         thread_count=12
+///         messages_last_24h=5
+///         active_reservations=2
+///         unread_count=7
 ///     />
 /// }
 /// ```
@@ -63,10 +71,22 @@ pub fn ProjectCard(
     status: ProjectStatus,
     /// Number of agents
     #[prop(default = 0)]
-    agent_count: usize,
+    agent_count: i64,
     /// Number of messages
     #[prop(default = 0)]
-    message_count: usize,
+    message_count: i64,
+    /// Number of message threads
+    #[prop(default = 0)]
+    thread_count: i64,
+    /// Messages sent in the last 24 hours
+    #[prop(default = 0)]
+    messages_last_24h: i64,
+    /// Unreleased, unexpired file reservations
+    #[prop(default = 0)]
+    active_reservations: i64,
+    /// Number of unread messages across the project's agents
+    #[prop(default = 0)]
+    unread_count: i64,
 ) -> impl IntoView {
     let href = format!("/projects/{}", slug);
     let formatted_date = format_date(&created_at);
@@ -90,20 +110,41 @@ pub fn ProjectCard(
                 </CardHeader>
 
                 <CardContent>
-                    <Badge variant={badge_variant}>
-                        {status.label()}
-                    </Badge>
+                    <div class="flex items-center gap-2">
+                        <Badge variant={badge_variant}>
+                            {status.label()}
+                        </Badge>
+                        {(unread_count > 0).then(|| view! {
+                            <Badge variant=BadgeVariant::Default>
+                                {unread_label(unread_count)}
+                            </Badge>
+                        })}
+                    </div>
+                    <div class="grid grid-cols-3 gap-2 mt-4 text-center">
+                        <div title="Messages in the last 24 hours">
+                            <NumberCounter value=messages_last_24h class="block text-lg font-semibold text-charcoal-800 dark:text-cream-100" />
+                            <span class="text-xs text-charcoal-500 dark:text-charcoal-400">"Today"</span>
+                        </div>
+                        <div title="Threads">
+                            <NumberCounter value=thread_count class="block text-lg font-semibold text-charcoal-800 dark:text-cream-100" />
+                            <span class="text-xs text-charcoal-500 dark:text-charcoal-400">"Threads"</span>
+                        </div>
+                        <div title="Active file reservations">
+                            <NumberCounter value=active_reservations class="block text-lg font-semibold text-charcoal-800 dark:text-cream-100" />
+                            <span class="text-xs text-charcoal-500 dark:text-charcoal-400">"Reserved"</span>
+                        </div>
+                    </div>
                 </CardContent>
 
                 <CardFooter class="pt-0 border-t border-cream-200 dark:border-charcoal-700 mt-auto">
                    <div class="flex items-center gap-4 text-sm text-charcoal-500 dark:text-charcoal-400 w-full pt-4">
                         <span class="flex items-center gap-1" title="Agents">
                             <i data-lucide="bot" class="icon-xs"></i>
-                            {agent_count}
+                            <NumberCounter value=agent_count />
                         </span>
                         <span class="flex items-center gap-1" title="Messages">
                             <i data-lucide="mail" class="icon-xs"></i>
-                            {message_count}
+                            <NumberCounter value=message_count />
                         </span>
                         <span class="flex items-center gap-1 ml-auto" title="Created">
                             <i data-lucide="calendar" class="icon-xs"></i>
@@ -116,6 +157,11 @@ pub fn ProjectCard(
     }
 }
 
+/// Label for the unread badge, e.g. "7 unread"
+fn unread_label(count: i64) -> String {
+    format!("{} unread", count)
+}
+
 /// Format date string for display
 fn format_date(date_str: &str) -> String {
     if date_str.is_empty() {
@@ -125,21 +171,15 @@ fn format_date(date_str: &str) -> String {
     date_str.split('T').next().unwrap_or(date_str).to_string()
 }
 
-/// Determine project status based on activity
-pub fn determine_project_status(last_active: Option<&str>, agent_count: usize) -> ProjectStatus {
-    // If has agents, consider active
-    if agent_count > 0 {
-        return ProjectStatus::Active;
-    }
-
-    // Check last activity timestamp (simplified)
-    if let Some(ts) = last_active {
-        if !ts.is_empty() {
-            return ProjectStatus::Active;
-        }
+/// Determine project status from how long ago it last saw activity
+///
+/// Active when a message was sent or an agent was active within
+/// [`ACTIVE_WINDOW_SECS`]; projects with no activity at all are inactive.
+pub fn determine_project_status(last_activity_age_secs: Option<i64>) -> ProjectStatus {
+    match last_activity_age_secs {
+        Some(age) if age <= ACTIVE_WINDOW_SECS => ProjectStatus::Active,
+        _ => ProjectStatus::Inactive,
     }
-
-    ProjectStatus::Inactive
 }
 
 #[cfg(test)]
@@ -164,6 +204,25 @@ mod tests {
         assert_eq!(ProjectStatus::Inactive.label(), "Inactive");
     }
 
+    #[test]
+    fn test_status_from_last_activity() {
+        assert_eq!(determine_project_status(Some(60)), ProjectStatus::Active);
+        assert_eq!(
+            determine_project_status(Some(ACTIVE_WINDOW_SECS)),
+            ProjectStatus::Active
+        );
+        assert_eq!(
+            determine_project_status(Some(ACTIVE_WINDOW_SECS + 1)),
+            ProjectStatus::Inactive
+        );
+        assert_eq!(determine_project_status(None), ProjectStatus::Inactive);
+    }
+
+    #[test]
+    fn test_unread_label() {
+        assert_eq!(unread_label(7), "7 unread");
+    }
+
     #[test]
     fn test_format_date() {
         assert_eq!(format_date("2025-10-26T10:30:00"), "2025-10-26");
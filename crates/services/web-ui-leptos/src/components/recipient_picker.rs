@@ -0,0 +1,609 @@
+//! RecipientPicker component: searchable, validated recipient chips.
+//!
+//! Loads the project's agents and agent groups, suggests matches as you type
+//! and renders each selected recipient as a removable chip. Groups are offered
+//! as `group:<name>` with their members shown on hover. Names that are not
+//! registered in the project are flagged so the composer can keep Send
+//! disabled, as are names the server refused on the last send attempt.
+//!
+//! Keyboard: ArrowDown/ArrowUp move the highlighted suggestion, Enter or Tab
+//! adds it, Backspace on an empty query removes the last chip, Escape closes
+//! the suggestion list.
+
+use crate::api::client::{self, Agent, AgentGroup, RecipientFailure};
+use leptos::prelude::*;
+
+/// Maximum number of suggestions shown at once.
+const MAX_SUGGESTIONS: usize = 8;
+
+/// Prefix of a group address, as in `group:frontend`.
+const GROUP_PREFIX: &str = "group:";
+
+/// The group name of a `group:<name>` address.
+fn group_name(address: &str) -> Option<&str> {
+    address
+        .get(..GROUP_PREFIX.len())
+        .filter(|p| p.eq_ignore_ascii_case(GROUP_PREFIX))
+        .map(|_| address[GROUP_PREFIX.len()..].trim())
+}
+
+fn find_group<'a>(groups: &'a [AgentGroup], address: &str) -> Option<&'a AgentGroup> {
+    let name = group_name(address)?;
+    groups.iter().find(|g| g.name.eq_ignore_ascii_case(name))
+}
+
+/// Addresses of the groups that can receive mail, i.e. those with members.
+pub fn group_addresses(groups: &[AgentGroup]) -> Vec<String> {
+    groups
+        .iter()
+        .filter(|g| !g.members.is_empty())
+        .map(|g| format!("{}{}", GROUP_PREFIX, g.name))
+        .collect()
+}
+
+/// Hover text listing a group address's members; `None` for agent names.
+pub fn group_preview(groups: &[AgentGroup], address: &str) -> Option<String> {
+    group_name(address)?;
+    Some(match find_group(groups, address) {
+        Some(group) if !group.members.is_empty() => {
+            format!("Members: {}", group.members.join(", "))
+        }
+        Some(_) => "No members".to_string(),
+        None => "Unknown group".to_string(),
+    })
+}
+
+/// Why a selected name that is not a known recipient cannot be sent to.
+pub fn unknown_label(groups: &[AgentGroup], name: &str) -> &'static str {
+    match (group_name(name), find_group(groups, name)) {
+        (None, _) => "Unknown agent",
+        (Some(_), Some(_)) => "Group has no members",
+        (Some(_), None) => "Unknown group",
+    }
+}
+
+/// Agents matching `query`, excluding `selected` and `exclude`.
+///
+/// Matching is case-insensitive; names starting with the query come before
+/// names that merely contain it. An empty query lists every candidate.
+pub fn filter_suggestions(
+    agents: &[String],
+    selected: &[String],
+    exclude: &[String],
+    query: &str,
+) -> Vec<String> {
+    let needle = query.trim().to_lowercase();
+    let mut prefix = Vec::new();
+    let mut contains = Vec::new();
+    for name in agents {
+        if selected.contains(name) || exclude.contains(name) {
+            continue;
+        }
+        let lower = name.to_lowercase();
+        if lower.starts_with(&needle) {
+            prefix.push(name.clone());
+        } else if lower.contains(&needle) {
+            contains.push(name.clone());
+        }
+    }
+    prefix.extend(contains);
+    prefix.truncate(MAX_SUGGESTIONS);
+    prefix
+}
+
+/// Canonical agent name for `input`, matched case-insensitively.
+pub fn resolve_name(agents: &[String], input: &str) -> Option<String> {
+    let input = input.trim();
+    agents
+        .iter()
+        .find(|name| name.eq_ignore_ascii_case(input))
+        .cloned()
+}
+
+/// Append `name` unless it is blank or already selected.
+///
+/// Returns `true` when the selection changed.
+pub fn add_recipient(selected: &mut Vec<String>, name: &str) -> bool {
+    let name = name.trim();
+    if name.is_empty() || selected.iter().any(|s| s == name) {
+        return false;
+    }
+    selected.push(name.to_string());
+    true
+}
+
+/// Backspace handling: drop the last chip when the query is empty.
+///
+/// Returns `true` when a chip was removed.
+pub fn remove_last_on_backspace(selected: &mut Vec<String>, query: &str) -> bool {
+    query.is_empty() && selected.pop().is_some()
+}
+
+/// Selected names that are not agents of the project or are excluded.
+pub fn invalid_recipients(
+    selected: &[String],
+    agents: &[String],
+    exclude: &[String],
+) -> Vec<String> {
+    selected
+        .iter()
+        .filter(|name| !agents.contains(name) || exclude.contains(name))
+        .cloned()
+        .collect()
+}
+
+/// Short explanation for a recipient failure code from the server.
+pub fn failure_label(code: &str) -> &'static str {
+    match code {
+        "AGENT_NOT_FOUND" => "Unknown agent",
+        "PROJECT_NOT_FOUND" => "Unknown project",
+        "FORBIDDEN_CROSS_PROJECT" => "Cross-project messages not allowed",
+        "GROUP_NOT_FOUND" => "Unknown group",
+        "GROUP_EMPTY" => "Group has no members",
+        _ => "Not deliverable",
+    }
+}
+
+/// The server's reason for refusing `name`, if it did.
+pub fn rejection_for(rejected: &[RecipientFailure], name: &str) -> Option<&'static str> {
+    rejected
+        .iter()
+        .find(|f| f.name == name)
+        .map(|f| failure_label(&f.code))
+}
+
+/// Reply-all recipients: the original sender followed by the other
+/// recipients, without `me` and without duplicates.
+pub fn reply_all_recipients(sender: &str, recipients: &[String], me: &str) -> Vec<String> {
+    let mut out = Vec::new();
+    for name in std::iter::once(sender).chain(recipients.iter().map(String::as_str)) {
+        if name != me {
+            add_recipient(&mut out, name);
+        }
+    }
+    out
+}
+
+/// Searchable recipient chips validated against the project's agents and
+/// groups.
+///
+/// # Props
+/// - `project_slug`: Project whose agents and groups are offered (loaded via
+///   `get_agents` and `get_groups`)
+/// - `selected`: Selected recipient names
+/// - `valid`: Set to `true` while every selected name is a known agent or a
+///   group with members
+/// - `agents`: Already-loaded agents, shown until the fetch completes
+/// - `exclude`: Names that cannot be picked (e.g. the sender)
+/// - `rejected`: Recipients the server refused on the last send; removing the chip clears it
+/// - `id`: Input ID for label association
+#[component]
+pub fn RecipientPicker(
+    /// Project whose agents are offered
+    #[prop(into)]
+    project_slug: String,
+    /// Selected recipient names
+    selected: RwSignal<Vec<String>>,
+    /// Whether every selected recipient is a known agent
+    valid: RwSignal<bool>,
+    /// Agents already loaded by the caller
+    #[prop(optional)]
+    agents: Vec<Agent>,
+    /// Names that cannot be picked
+    #[prop(optional)]
+    exclude: Vec<String>,
+    /// Recipients the server refused on the last send
+    #[prop(optional)]
+    rejected: Option<RwSignal<Vec<RecipientFailure>>>,
+    /// Input ID for label association
+    #[prop(default = "recipients".to_string(), into)]
+    id: String,
+) -> impl IntoView {
+    let seed: Vec<String> = agents.into_iter().map(|a| a.name).collect();
+    let known = RwSignal::new(Option::<Vec<String>>::None);
+    let groups = RwSignal::new(Vec::<AgentGroup>::new());
+    let load_error = RwSignal::new(Option::<String>::None);
+    if !seed.is_empty() {
+        known.set(Some(seed.clone()));
+    }
+
+    let query = RwSignal::new(String::new());
+    let open = RwSignal::new(false);
+    let highlighted = RwSignal::new(0usize);
+    let exclude = StoredValue::new(exclude);
+
+    // Refresh the agent and group lists from the server. Groups are
+    // optional: without them only agents are offered.
+    leptos::task::spawn_local(async move {
+        let group_list = client::get_groups(&project_slug).await.unwrap_or_default();
+        match client::get_agents(&project_slug).await {
+            Ok(list) => {
+                let mut names: Vec<String> = list.into_iter().map(|a| a.name).collect();
+                names.extend(group_addresses(&group_list));
+                groups.set(group_list);
+                known.set(Some(names));
+                load_error.set(None);
+            }
+            Err(e) => {
+                known.set(Some(seed));
+                load_error.set(Some(e.message));
+            }
+        }
+    });
+
+    let invalid = Memo::new(move |_| match known.get() {
+        Some(agents) => exclude.with_value(|ex| invalid_recipients(&selected.get(), &agents, ex)),
+        None => selected.get(),
+    });
+    let rejected = rejected.unwrap_or_else(|| RwSignal::new(Vec::new()));
+    // Refused names still selected, with the reason
+    let refused = Memo::new(move |_| {
+        let rejected = rejected.get();
+        selected
+            .get()
+            .into_iter()
+            .filter_map(|name| {
+                rejection_for(&rejected, &name).map(|reason| format!("{} ({})", name, reason))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    Effect::new(move |_| {
+        valid.set(
+            known.with(Option::is_some)
+                && invalid.with(Vec::is_empty)
+                && refused.with(Vec::is_empty),
+        );
+    });
+
+    let suggestions = Memo::new(move |_| {
+        let agents = known.get().unwrap_or_default();
+        exclude.with_value(|ex| filter_suggestions(&agents, &selected.get(), ex, &query.get()))
+    });
+
+    let pick = move |name: String| {
+        selected.update(|s| {
+            add_recipient(s, &name);
+        });
+        query.set(String::new());
+        highlighted.set(0);
+    };
+
+    let on_keydown = move |ev: web_sys::KeyboardEvent| match ev.key().as_str() {
+        "ArrowDown" => {
+            ev.prevent_default();
+            open.set(true);
+            let len = suggestions.with(Vec::len);
+            highlighted.update(|i| *i = (*i + 1).min(len.saturating_sub(1)));
+        }
+        "ArrowUp" => {
+            ev.prevent_default();
+            highlighted.update(|i| *i = i.saturating_sub(1));
+        }
+        "Enter" | "Tab" => {
+            let typed = query.get_untracked();
+            if typed.trim().is_empty() {
+                return;
+            }
+            ev.prevent_default();
+            let exact = known
+                .get_untracked()
+                .and_then(|agents| resolve_name(&agents, &typed));
+            let choice = exact
+                .or_else(|| {
+                    suggestions.with_untracked(|s| s.get(highlighted.get_untracked()).cloned())
+                })
+                .unwrap_or(typed);
+            pick(choice);
+        }
+        "Backspace" => {
+            let typed = query.get_untracked();
+            selected.update(|s| {
+                remove_last_on_backspace(s, &typed);
+            });
+        }
+        "Escape" if open.get_untracked() => {
+            ev.stop_propagation();
+            open.set(false);
+        }
+        _ => {}
+    };
+
+    let listbox_id = format!("{}-suggestions", id);
+
+    view! {
+        <div class="relative">
+            <div class=move || {
+                let base = "flex flex-wrap items-center gap-2 min-h-10 w-full rounded-md border bg-background px-2 py-1.5 text-sm focus-within:ring-2 focus-within:ring-ring focus-within:ring-offset-2";
+                if invalid.with(Vec::is_empty) && refused.with(Vec::is_empty) {
+                    format!("{} border-input", base)
+                } else {
+                    format!("{} border-destructive", base)
+                }
+            }>
+                {move || {
+                    let bad = invalid.get();
+                    let refusals = rejected.get();
+                    let group_list = groups.get();
+                    selected.get().into_iter().map(|name| {
+                        let reason = rejection_for(&refusals, &name).or_else(|| {
+                            bad.contains(&name).then(|| unknown_label(&group_list, &name))
+                        });
+                        let is_invalid = reason.is_some();
+                        let preview = group_preview(&group_list, &name);
+                        let is_group = preview.is_some();
+                        let title = reason.map(str::to_string).or(preview).unwrap_or_default();
+                        let remove_name = name.clone();
+                        view! {
+                            <span
+                                class=if is_invalid {
+                                    "inline-flex items-center gap-1 rounded-full px-2.5 py-0.5 text-sm bg-red-100 dark:bg-red-900/30 text-red-700 dark:text-red-400 line-through"
+                                } else {
+                                    "inline-flex items-center gap-1 rounded-full px-2.5 py-0.5 text-sm bg-amber-600 text-white"
+                                }
+                                title=title
+                            >
+                                {is_group.then(|| view! { <i data-lucide="users" class="icon-xs"></i> })}
+                                {name.clone()}
+                                <button
+                                    type="button"
+                                    class="rounded-full opacity-70 hover:opacity-100 focus:outline-none"
+                                    aria-label=format!("Remove {}", name)
+                                    on:click=move |_| {
+                                        rejected.update(|r| r.retain(|f| f.name != remove_name));
+                                        selected.update(|s| s.retain(|r| r != &remove_name));
+                                    }
+                                >
+                                    <i data-lucide="x" class="icon-xs"></i>
+                                </button>
+                            </span>
+                        }
+                    }).collect::<Vec<_>>()
+                }}
+                <input
+                    id=id.clone()
+                    type="text"
+                    role="combobox"
+                    autocomplete="off"
+                    aria-autocomplete="list"
+                    aria-controls=listbox_id.clone()
+                    aria-expanded=move || open.get().to_string()
+                    placeholder=move || {
+                        if selected.with(Vec::is_empty) { "Search agents or groups..." } else { "" }
+                    }
+                    prop:value=move || query.get()
+                    on:input=move |ev| {
+                        query.set(event_target_value(&ev));
+                        highlighted.set(0);
+                        open.set(true);
+                    }
+                    on:focus=move |_| open.set(true)
+                    on:blur=move |_| open.set(false)
+                    on:keydown=on_keydown
+                    class="flex-1 min-w-[8rem] bg-transparent py-1 outline-none placeholder:text-muted-foreground"
+                />
+            </div>
+
+            // Suggestions
+            <Show when=move || open.get() && !suggestions.with(Vec::is_empty)>
+                <ul
+                    id=listbox_id.clone()
+                    role="listbox"
+                    class="absolute z-20 mt-1 w-full max-h-60 overflow-y-auto rounded-md border border-border bg-background shadow-lg py-1"
+                >
+                    {move || suggestions.get().into_iter().enumerate().map(|(i, name)| {
+                        let pick_name = name.clone();
+                        let preview = groups.with(|g| group_preview(g, &name));
+                        let is_group = preview.is_some();
+                        view! {
+                            <li
+                                role="option"
+                                aria-selected=move || (highlighted.get() == i).to_string()
+                                // mousedown keeps focus in the input, unlike click
+                                on:mousedown=move |ev| {
+                                    ev.prevent_default();
+                                    pick(pick_name.clone());
+                                }
+                                on:mouseenter=move |_| highlighted.set(i)
+                                title=preview.unwrap_or_default()
+                                class=move || {
+                                    if highlighted.get() == i {
+                                        "flex items-center gap-2 px-3 py-1.5 text-sm cursor-pointer bg-amber-100 dark:bg-amber-900/30 text-charcoal-900 dark:text-cream-100"
+                                    } else {
+                                        "flex items-center gap-2 px-3 py-1.5 text-sm cursor-pointer text-charcoal-700 dark:text-charcoal-300"
+                                    }
+                                }
+                            >
+                                {is_group.then(|| view! { <i data-lucide="users" class="icon-xs"></i> })}
+                                {name}
+                            </li>
+                        }
+                    }).collect::<Vec<_>>()}
+                </ul>
+            </Show>
+
+            // Status
+            {move || {
+                let bad = invalid.get();
+                let refused = refused.get();
+                if known.with(Option::is_none) {
+                    Some(view! {
+                        <p class="mt-1 text-xs text-charcoal-500 dark:text-charcoal-400">"Loading agents..."</p>
+                    }.into_any())
+                } else if !bad.is_empty() {
+                    Some(view! {
+                        <p class="mt-1 text-xs text-red-600 dark:text-red-400">
+                            "Unknown recipients: " {bad.join(", ")}
+                        </p>
+                    }.into_any())
+                } else if !refused.is_empty() {
+                    Some(view! {
+                        <p class="mt-1 text-xs text-red-600 dark:text-red-400" role="alert">
+                            "Could not deliver to: " {refused.join(", ")}
+                        </p>
+                    }.into_any())
+                } else {
+                    load_error.get().map(|e| view! {
+                        <p class="mt-1 text-xs text-charcoal-500 dark:text-charcoal-400">
+                            "Could not refresh agents: " {e}
+                        </p>
+                    }.into_any())
+                }
+            }}
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_filter_suggestions_prefix_first() {
+        let agents = names(&["BlueLake", "GreenCastle", "LakeShore", "RedStone"]);
+        let result = filter_suggestions(&agents, &[], &[], "lake");
+        assert_eq!(result, names(&["LakeShore", "BlueLake"]));
+    }
+
+    #[test]
+    fn test_filter_suggestions_skips_selected_and_excluded() {
+        let agents = names(&["BlueLake", "GreenCastle", "RedStone"]);
+        let selected = names(&["GreenCastle"]);
+        let exclude = names(&["RedStone"]);
+        let result = filter_suggestions(&agents, &selected, &exclude, "");
+        assert_eq!(result, names(&["BlueLake"]));
+    }
+
+    #[test]
+    fn test_filter_suggestions_limit() {
+        let agents: Vec<String> = (0..20).map(|i| format!("Agent{}", i)).collect();
+        assert_eq!(
+            filter_suggestions(&agents, &[], &[], "agent").len(),
+            MAX_SUGGESTIONS
+        );
+    }
+
+    #[test]
+    fn test_resolve_name_case_insensitive() {
+        let agents = names(&["BlueLake", "GreenCastle"]);
+        assert_eq!(
+            resolve_name(&agents, " bluelake "),
+            Some("BlueLake".to_string())
+        );
+        assert_eq!(resolve_name(&agents, "blue"), None);
+    }
+
+    #[test]
+    fn test_add_recipient_dedupes_and_trims() {
+        let mut selected = Vec::new();
+        assert!(add_recipient(&mut selected, " BlueLake "));
+        assert!(!add_recipient(&mut selected, "BlueLake"));
+        assert!(!add_recipient(&mut selected, "   "));
+        assert_eq!(selected, names(&["BlueLake"]));
+    }
+
+    #[test]
+    fn test_backspace_only_removes_with_empty_query() {
+        let mut selected = names(&["BlueLake", "GreenCastle"]);
+        assert!(!remove_last_on_backspace(&mut selected, "gr"));
+        assert_eq!(selected.len(), 2);
+        assert!(remove_last_on_backspace(&mut selected, ""));
+        assert_eq!(selected, names(&["BlueLake"]));
+        selected.clear();
+        assert!(!remove_last_on_backspace(&mut selected, ""));
+    }
+
+    #[test]
+    fn test_invalid_recipients() {
+        let agents = names(&["BlueLake", "GreenCastle", "RedStone"]);
+        let selected = names(&["BlueLake", "BlueLak", "RedStone"]);
+        let exclude = names(&["RedStone"]);
+        assert_eq!(
+            invalid_recipients(&selected, &agents, &exclude),
+            names(&["BlueLak", "RedStone"])
+        );
+        assert!(invalid_recipients(&[], &agents, &exclude).is_empty());
+    }
+
+    #[test]
+    fn test_rejection_for() {
+        let rejected = vec![
+            RecipientFailure {
+                name: "Ghost".to_string(),
+                code: "AGENT_NOT_FOUND".to_string(),
+            },
+            RecipientFailure {
+                name: "other::BlueLake".to_string(),
+                code: "FORBIDDEN_CROSS_PROJECT".to_string(),
+            },
+        ];
+        assert_eq!(rejection_for(&rejected, "Ghost"), Some("Unknown agent"));
+        assert_eq!(
+            rejection_for(&rejected, "other::BlueLake"),
+            Some("Cross-project messages not allowed")
+        );
+        assert_eq!(rejection_for(&rejected, "BlueLake"), None);
+        assert_eq!(failure_label("SOMETHING_ELSE"), "Not deliverable");
+    }
+
+    fn group(name: &str, members: &[&str]) -> AgentGroup {
+        AgentGroup {
+            id: 1,
+            name: name.to_string(),
+            members: names(members),
+        }
+    }
+
+    #[test]
+    fn test_group_addresses_skip_empty_groups() {
+        let groups = vec![
+            group("frontend", &["BlueLake", "GreenCastle"]),
+            group("idle", &[]),
+        ];
+        let addresses = group_addresses(&groups);
+        assert_eq!(addresses, names(&["group:frontend"]));
+
+        // Groups are found by their bare name too
+        let mut candidates = names(&["BlueLake", "FrontDesk"]);
+        candidates.extend(addresses);
+        assert_eq!(
+            filter_suggestions(&candidates, &[], &[], "front"),
+            names(&["FrontDesk", "group:frontend"])
+        );
+    }
+
+    #[test]
+    fn test_group_preview_and_unknown_label() {
+        let groups = vec![
+            group("frontend", &["BlueLake", "GreenCastle"]),
+            group("idle", &[]),
+        ];
+        assert_eq!(
+            group_preview(&groups, "group:Frontend"),
+            Some("Members: BlueLake, GreenCastle".to_string())
+        );
+        assert_eq!(
+            group_preview(&groups, "GROUP:idle"),
+            Some("No members".to_string())
+        );
+        assert_eq!(group_preview(&groups, "BlueLake"), None);
+
+        assert_eq!(unknown_label(&groups, "Ghost"), "Unknown agent");
+        assert_eq!(unknown_label(&groups, "group:idle"), "Group has no members");
+        assert_eq!(unknown_label(&groups, "group:backend"), "Unknown group");
+        assert_eq!(failure_label("GROUP_EMPTY"), "Group has no members");
+    }
+
+    #[test]
+    fn test_reply_all_recipients() {
+        let recipients = names(&["GreenCastle", "RedStone", "BlueLake"]);
+        assert_eq!(
+            reply_all_recipients("BlueLake", &recipients, "RedStone"),
+            names(&["BlueLake", "GreenCastle"])
+        );
+    }
+}
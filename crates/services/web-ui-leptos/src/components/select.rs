@@ -68,6 +68,7 @@ pub enum SelectIcon {
     Send,
     Archive,
     Star,
+    Sort,
 }
 
 impl SelectIcon {
@@ -87,6 +88,7 @@ impl SelectIcon {
             Self::Send => "send",
             Self::Archive => "archive",
             Self::Star => "star",
+            Self::Sort => "arrow-up-down",
         }
     }
 }
@@ -0,0 +1,160 @@
+//! Snooze Menu component.
+//!
+//! Hides a message from one agent's inbox until a preset time. Presets are
+//! picked in the browser's local time and sent to the server as UTC.
+
+use crate::api::client;
+use crate::components::{Button, ButtonSize, ButtonVariant};
+use crate::utils::time;
+use leptos::prelude::*;
+
+/// When a snoozed message comes back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnoozePreset {
+    OneHour,
+    FourHours,
+    TomorrowMorning,
+}
+
+impl SnoozePreset {
+    /// Presets in menu order.
+    pub const ALL: [SnoozePreset; 3] = [Self::OneHour, Self::FourHours, Self::TomorrowMorning];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::OneHour => "1 hour",
+            Self::FourHours => "4 hours",
+            Self::TomorrowMorning => "Tomorrow 9am",
+        }
+    }
+
+    /// End of the snooze as an offset-less UTC timestamp.
+    pub fn until(self) -> String {
+        match self {
+            Self::OneHour => time::utc_hours_from_now(1.0),
+            Self::FourHours => time::utc_hours_from_now(4.0),
+            Self::TomorrowMorning => time::utc_tomorrow_at(9),
+        }
+    }
+}
+
+/// Snooze button with a preset menu for one inbox message.
+///
+/// # Props
+/// - `message_id`: Message to snooze
+/// - `project_slug`: Project context
+/// - `agent_name`: Recipient snoozing the message
+/// - `on_snoozed`: Called with the message ID once the server confirms
+///
+/// # Accessibility
+/// - `aria-expanded` on the trigger reflects whether the menu is open
+/// - Presets are a `menu` of `menuitem` buttons
+///
+/// # Example
+/// ```rust,ignore
+/// view! {
+///     <SnoozeMenu
+///         message_id=123
+///         project_slug="my-project".to_string()
+///         agent_name="worker-1".to_string()
+///         on_snoozed=Callback::new(move |id| remove_from_list(id))
+///     />
+/// }
+/// ```
+#[component]
+pub fn SnoozeMenu(
+    /// Message ID to snooze
+    message_id: i64,
+    /// Project slug for context
+    #[prop(into)]
+    project_slug: String,
+    /// Recipient snoozing the message
+    #[prop(into)]
+    agent_name: String,
+    /// Called with the message ID after a successful snooze
+    on_snoozed: Callback<i64>,
+) -> impl IntoView {
+    let open = RwSignal::new(false);
+    let pending = RwSignal::new(false);
+    let error = RwSignal::new(Option::<String>::None);
+
+    let snooze = Callback::new(move |preset: SnoozePreset| {
+        if pending.get_untracked() {
+            return;
+        }
+        open.set(false);
+        pending.set(true);
+        error.set(None);
+
+        let (project, agent) = (project_slug.clone(), agent_name.clone());
+        leptos::task::spawn_local(async move {
+            let until = preset.until();
+            match client::snooze_message(&project, &agent, message_id, Some(&until)).await {
+                Ok(()) => on_snoozed.run(message_id),
+                Err(e) => error.set(Some(e.message)),
+            }
+            pending.set(false);
+        });
+    });
+
+    view! {
+        <div class="relative inline-flex">
+            <Button
+                variant=ButtonVariant::Ghost
+                size=ButtonSize::Icon
+                on_click=Callback::new(move |_| open.update(|o| *o = !*o))
+                disabled=pending
+                title="Snooze".to_string()
+                aria_label="Snooze message".to_string()
+                aria_expanded=Signal::derive(move || open.get().to_string())
+                class="min-w-[44px] min-h-[44px]".to_string()
+            >
+                {move || if pending.get() {
+                    view! { <i data-lucide="loader-2" class="icon-sm animate-spin text-charcoal-500"></i> }.into_any()
+                } else {
+                    view! { <i data-lucide="alarm-clock" class="icon-sm text-charcoal-400"></i> }.into_any()
+                }}
+            </Button>
+
+            {move || open.get().then(|| view! {
+                <div
+                    class="absolute right-0 top-full mt-1 w-40 rounded-lg border border-cream-200 dark:border-charcoal-700 bg-white dark:bg-charcoal-800 shadow-lg z-50 py-1 animate-scale-in"
+                    role="menu"
+                    aria-label="Snooze until"
+                >
+                    {SnoozePreset::ALL.into_iter().map(|preset| view! {
+                        <button
+                            type="button"
+                            role="menuitem"
+                            class="w-full text-left px-3 py-2 text-sm text-charcoal-700 dark:text-cream-200 hover:bg-cream-100 dark:hover:bg-charcoal-700"
+                            on:click=move |_| snooze.run(preset)
+                        >
+                            {preset.label()}
+                        </button>
+                    }).collect_view()}
+                </div>
+            })}
+
+            // Error tooltip
+            {move || error.get().map(|err| view! {
+                <div
+                    class="absolute bottom-full right-0 mb-2 px-3 py-2 bg-red-100 dark:bg-red-900/50 text-red-700 dark:text-red-300 text-xs rounded-lg shadow-lg whitespace-nowrap z-50 animate-slide-up"
+                    role="alert"
+                >
+                    {err}
+                </div>
+            })}
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_in_menu_order() {
+        let labels: Vec<&str> = SnoozePreset::ALL.iter().map(|p| p.label()).collect();
+        assert_eq!(labels, ["1 hour", "4 hours", "Tomorrow 9am"]);
+    }
+}
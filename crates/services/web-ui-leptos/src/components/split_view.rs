@@ -2,8 +2,16 @@
 //!
 //! Provides a two-column layout with message list (35%) and detail panel (65%).
 //! Responsive design with mobile single-column fallback.
+//!
+//! Keyboard: ArrowDown/j and ArrowUp/k move the selection, Home/End jump to
+//! the ends, Enter selects, Escape clears. Keys typed into inputs, textareas
+//! or open dialogs are left alone.
+//!
+//! With a `selection` signal, rows get checkboxes for bulk actions:
+//! Shift-click checks a range, and the list header selects all visible rows.
 
-use crate::components::{AgentAvatar, AvatarSize};
+use crate::components::bulk_actions::{apply_check, select_all_visible, selection_state};
+use crate::components::{AgentAvatar, AvatarSize, Checkbox};
 use leptos::prelude::*;
 
 /// Props for message list items
@@ -21,10 +29,82 @@ pub struct MessageListItem {
     pub unread: bool,
     /// Importance level
     pub importance: String,
+    /// Whether recipients must acknowledge the message
+    pub ack_required: bool,
+    /// Whether a recipient has muted the message's thread
+    pub muted: bool,
     /// Project slug
     pub project_slug: String,
 }
 
+/// A message list key binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListNavKey {
+    /// ArrowDown or j
+    Next,
+    /// ArrowUp or k
+    Previous,
+    /// Home
+    First,
+    /// End
+    Last,
+    /// Enter
+    Open,
+    /// Escape
+    Clear,
+}
+
+impl ListNavKey {
+    /// Map a `KeyboardEvent.key` value to a binding.
+    pub fn from_key(key: &str) -> Option<Self> {
+        match key {
+            "ArrowDown" | "j" => Some(Self::Next),
+            "ArrowUp" | "k" => Some(Self::Previous),
+            "Home" => Some(Self::First),
+            "End" => Some(Self::Last),
+            "Enter" => Some(Self::Open),
+            "Escape" => Some(Self::Clear),
+            _ => None,
+        }
+    }
+}
+
+/// Index to select after `key`, given the `current` index in a list of `len`.
+///
+/// Movement clamps at the ends of the list. With nothing selected, Next and
+/// Open pick the first item and Previous picks the last.
+pub fn navigate_index(current: Option<usize>, len: usize, key: ListNavKey) -> Option<usize> {
+    if len == 0 || key == ListNavKey::Clear {
+        return None;
+    }
+    let last = len - 1;
+    let current = current.map(|idx| idx.min(last));
+    Some(match (key, current) {
+        (ListNavKey::Next, Some(idx)) => (idx + 1).min(last),
+        (ListNavKey::Previous, Some(idx)) => idx.saturating_sub(1),
+        (ListNavKey::Open, Some(idx)) => idx,
+        (ListNavKey::Next | ListNavKey::Open | ListNavKey::First, _) => 0,
+        (ListNavKey::Previous | ListNavKey::Last, _) => last,
+        (ListNavKey::Clear, _) => return None,
+    })
+}
+
+/// Whether a key event comes from somewhere that owns its own keys: form
+/// fields, editable content, or an open dialog.
+fn is_typing_target(ev: &web_sys::KeyboardEvent) -> bool {
+    use wasm_bindgen::JsCast;
+
+    let Some(el) = ev
+        .target()
+        .and_then(|target| target.dyn_into::<web_sys::HtmlElement>().ok())
+    else {
+        return false;
+    };
+    matches!(el.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT")
+        || el.is_content_editable()
+        || el.closest("[role='dialog']").ok().flatten().is_some()
+}
+
 /// Empty state placeholder for the detail panel
 #[component]
 pub fn EmptyDetailPanel() -> impl IntoView {
@@ -47,6 +127,13 @@ pub fn MessageListItemView(
     selected: Signal<bool>,
     /// Callback when item is clicked
     on_click: Callback<i64>,
+    /// Whether the item's bulk-selection checkbox is checked
+    #[prop(optional, into)]
+    checked: Option<Signal<bool>>,
+    /// Callback with (id, checked, shift held) when the checkbox is toggled;
+    /// the checkbox is only shown when set
+    #[prop(default = None)]
+    on_check: Option<Callback<(i64, bool, bool)>>,
 ) -> impl IntoView {
     let id = item.id;
     let sender = item.sender.clone();
@@ -54,16 +141,18 @@ pub fn MessageListItemView(
     let timestamp = item.timestamp.clone();
     let unread = item.unread;
     let importance = item.importance.clone();
+    let ack_required = item.ack_required;
+    let muted = item.muted;
 
     // 2025 Magic UI list item with enhanced hover and selection states
     // Uses role="option" for proper listbox semantics
-    view! {
+    let row = view! {
         <button
             id={format!("message-{}", id)}
             role="option"
             aria-selected=move || selected.get().to_string()
             class={move || format!(
-                "w-full text-left p-4 border-b border-border/50 \
+                "w-full min-w-0 text-left p-4 border-b border-border/50 \
                  message-item transition-all duration-200 \
                  focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-inset focus-visible:ring-ring \
                  {} {}",
@@ -101,6 +190,20 @@ pub fn MessageListItemView(
                             } else {
                                 None
                             }}
+                            {if ack_required {
+                                Some(view! {
+                                    <i data-lucide="check-circle" class="h-3 w-3 text-amber-500 flex-shrink-0 ml-1" title="Acknowledgment required"></i>
+                                })
+                            } else {
+                                None
+                            }}
+                            {if muted {
+                                Some(view! {
+                                    <i data-lucide="bell-off" class="h-3 w-3 text-muted-foreground flex-shrink-0 ml-1" title="Thread muted"></i>
+                                })
+                            } else {
+                                None
+                            }}
                         </div>
                         <span class="text-xs text-muted-foreground whitespace-nowrap flex-shrink-0">
                             {timestamp}
@@ -112,7 +215,31 @@ pub fn MessageListItemView(
                 </div>
             </div>
         </button>
+    };
+
+    let Some(on_check) = on_check else {
+        return row.into_any();
+    };
+    let checked = checked.unwrap_or_else(|| Signal::derive(|| false));
+
+    // The checkbox sits beside the row button rather than inside it, and its
+    // clicks stop here so they never open the message
+    view! {
+        <div class="flex items-stretch">
+            <span
+                class="flex items-center pl-4 border-b border-border/50"
+                title="Select message (Shift-click for a range)"
+                on:click=move |ev| {
+                    ev.stop_propagation();
+                    on_check.run((id, !checked.get_untracked(), ev.shift_key()));
+                }
+            >
+                <Checkbox checked=checked />
+            </span>
+            {row}
+        </div>
     }
+    .into_any()
 }
 
 /// Split view layout container component.
@@ -121,6 +248,8 @@ pub fn MessageListItemView(
 /// - `messages`: List of messages to display
 /// - `selected_id`: Signal for currently selected message ID
 /// - `on_select`: Callback when a message is selected
+/// - `on_clear`: Callback when the selection is cleared (Escape, mobile back)
+/// - `selection`: Checked message IDs; enables row checkboxes when set
 /// - `detail_content`: Content to show in detail panel (slot)
 ///
 /// # Example
@@ -148,86 +277,93 @@ pub fn SplitViewLayout(
     selected_id: Signal<Option<i64>>,
     /// Callback when a message is selected
     on_select: Callback<i64>,
+    /// Callback when the selection is cleared
+    #[prop(optional)]
+    on_clear: Option<Callback<()>>,
+    /// Message IDs checked for bulk actions
+    #[prop(optional)]
+    selection: Option<RwSignal<Vec<i64>>>,
     /// Content for the detail panel
     children: Children,
 ) -> impl IntoView {
-    // Clone for keyboard handler
-    let messages_for_keyboard = messages.clone();
-    let on_select_keyboard = on_select;
-
-    // Keyboard navigation handler
-    // Supports: ArrowDown/j, ArrowUp/k, Home, End, Enter, Escape
-    let on_keydown = move |ev: web_sys::KeyboardEvent| {
-        let key = ev.key();
-
-        // Handle Escape to deselect (no prevent_default needed for Escape)
-        if key == "Escape" {
-            // Clear selection - parent component should handle None case
-            // We can't call on_select with None, so we use a special value
-            // For now, just blur the container
-            use wasm_bindgen::JsCast;
-            if let Some(target) = ev.target() {
-                if let Some(el) = target.dyn_ref::<web_sys::HtmlElement>() {
-                    let _ = el.blur();
-                }
-            }
-            return;
+    let message_ids: Vec<i64> = messages.iter().map(|m| m.id).collect();
+
+    // Bulk selection: the last checked row anchors Shift-click ranges
+    let check_anchor = StoredValue::new(None::<i64>);
+    let on_check = selection.map(|selection| {
+        let visible = message_ids.clone();
+        Callback::new(move |(id, checked, shift): (i64, bool, bool)| {
+            let anchor = if shift {
+                check_anchor.get_value()
+            } else {
+                None
+            };
+            selection.update(|sel| *sel = apply_check(sel, &visible, anchor, id, checked));
+            check_anchor.set_value(Some(id));
+        })
+    });
+    let select_all_header = selection.filter(|_| !messages.is_empty()).map(|selection| {
+        let visible = message_ids.clone();
+        let visible_for_state = message_ids.clone();
+        let state = Memo::new(move |_| {
+            selection.with(|sel| selection_state(sel, &visible_for_state))
+        });
+        view! {
+            <div class="sticky top-0 z-10 flex items-center gap-3 px-4 py-2 border-b border-border bg-background/95 backdrop-blur">
+                <Checkbox
+                    id="selectAllMessages"
+                    checked=Signal::derive(move || state.get().0)
+                    indeterminate=Signal::derive(move || {
+                        let (all, some) = state.get();
+                        some && !all
+                    })
+                    on_change=Callback::new(move |checked| {
+                        selection.set(select_all_visible(&visible, checked));
+                        check_anchor.set_value(None);
+                    })
+                />
+                <label for="selectAllMessages" class="text-xs text-muted-foreground cursor-pointer">
+                    "Select all"
+                </label>
+            </div>
         }
+    });
 
-        // Navigate down: ArrowDown or j (Vim-style)
-        // Navigate up: ArrowUp or k (Vim-style)
-        let is_nav_key = key == "ArrowDown"
-            || key == "ArrowUp"
-            || key == "j"
-            || key == "k"
-            || key == "Home"
-            || key == "End"
-            || key == "Enter";
-
-        if is_nav_key {
-            ev.prevent_default();
-
-            if messages_for_keyboard.is_empty() {
-                return;
-            }
-
-            let current_id = selected_id.get();
-            let current_idx =
-                current_id.and_then(|id| messages_for_keyboard.iter().position(|m| m.id == id));
-
-            match key.as_str() {
-                "ArrowDown" | "j" => {
-                    let next_idx = match current_idx {
-                        Some(idx) if idx + 1 < messages_for_keyboard.len() => idx + 1,
-                        None => 0,
-                        _ => return,
-                    };
-                    on_select_keyboard.run(messages_for_keyboard[next_idx].id);
-                }
-                "ArrowUp" | "k" => {
-                    let prev_idx = match current_idx {
-                        Some(idx) if idx > 0 => idx - 1,
-                        None => messages_for_keyboard.len() - 1,
-                        _ => return,
-                    };
-                    on_select_keyboard.run(messages_for_keyboard[prev_idx].id);
-                }
-                "Home" => {
-                    // Jump to first message
-                    on_select_keyboard.run(messages_for_keyboard[0].id);
-                }
-                "End" => {
-                    // Jump to last message
-                    let last_idx = messages_for_keyboard.len() - 1;
-                    on_select_keyboard.run(messages_for_keyboard[last_idx].id);
+    // Keyboard navigation, listened for on the window so it works without
+    // first focusing the list
+    let keydown = window_event_listener(leptos::ev::keydown, move |ev| {
+        if ev.default_prevented()
+            || ev.ctrl_key()
+            || ev.meta_key()
+            || ev.alt_key()
+            || is_typing_target(&ev)
+        {
+            return;
+        }
+        let Some(key) = ListNavKey::from_key(&ev.key()) else {
+            return;
+        };
+        ev.prevent_default();
+
+        let current = selected_id
+            .get_untracked()
+            .and_then(|id| message_ids.iter().position(|m| *m == id));
+        match navigate_index(current, message_ids.len(), key) {
+            Some(idx) => {
+                let id = message_ids[idx];
+                if Some(idx) != current {
+                    on_select.run(id);
                 }
-                "Enter" => {
-                    // Enter already handled by selection
+                scroll_into_view(id);
+            }
+            None => {
+                if let Some(on_clear) = on_clear {
+                    on_clear.run(());
                 }
-                _ => {}
             }
         }
-    };
+    });
+    on_cleanup(move || keydown.remove());
 
     // Track viewport size for conditional rendering (avoids duplicate DOM)
     let is_desktop = RwSignal::new(true);
@@ -270,8 +406,6 @@ pub fn SplitViewLayout(
         // Single unified layout - responsive with CSS Grid
         <div
             class="h-[calc(100vh-12rem)] rounded-lg border bg-card text-card-foreground shadow-sm overflow-hidden"
-            tabindex="0"
-            on:keydown=on_keydown
         >
             // CSS Grid: 1 column on mobile, 2 columns on md+
             <div class="grid grid-cols-1 md:grid-cols-[minmax(280px,35%)_1fr] h-full">
@@ -283,6 +417,7 @@ pub fn SplitViewLayout(
                     aria-label="Message list"
                     aria-activedescendant=move || selected_id.get().map(|id| format!("message-{}", id)).unwrap_or_default()
                 >
+                    {select_all_header}
                     {if messages.is_empty() {
                         view! {
                             <div class="p-8 text-center text-muted-foreground">
@@ -296,11 +431,16 @@ pub fn SplitViewLayout(
                                 {messages.iter().map(|msg| {
                                     let msg_id = msg.id;
                                     let is_selected = Signal::derive(move || selected_id.get() == Some(msg_id));
+                                    let is_checked = Signal::derive(move || {
+                                        selection.is_some_and(|sel| sel.with(|ids| ids.contains(&msg_id)))
+                                    });
                                     view! {
                                         <MessageListItemView
                                             item=msg.clone()
                                             selected=is_selected
                                             on_click=on_select
+                                            checked=is_checked
+                                            on_check=on_check
                                         />
                                     }
                                 }).collect::<Vec<_>>()}
@@ -327,8 +467,9 @@ pub fn SplitViewLayout(
                                         class="inline-flex items-center gap-2 text-sm font-medium text-muted-foreground hover:text-foreground transition-colors focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring rounded-md px-2 py-1"
                                         on:click=move |_| {
                                             // Clear selection to go back to list
-                                            // Note: This is a workaround since we can't set None directly
-                                            // The parent should handle this by detecting the back action
+                                            if let Some(on_clear) = on_clear {
+                                                on_clear.run(());
+                                            }
                                         }
                                     >
                                         <i data-lucide="arrow-left" class="h-4 w-4"></i>
@@ -347,6 +488,18 @@ pub fn SplitViewLayout(
     }
 }
 
+/// Scroll the list row for message `id` into view if it is off screen.
+fn scroll_into_view(id: i64) {
+    if let Some(row) = web_sys::window()
+        .and_then(|w| w.document())
+        .and_then(|d| d.get_element_by_id(&format!("message-{}", id)))
+    {
+        let options = web_sys::ScrollIntoViewOptions::new();
+        options.set_block(web_sys::ScrollLogicalPosition::Nearest);
+        row.scroll_into_view_with_scroll_into_view_options(&options);
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -362,6 +515,8 @@ mod tests {
             timestamp: "10:30 AM".to_string(),
             unread: true,
             importance: "normal".to_string(),
+            ack_required: false,
+            muted: false,
             project_slug: "my-project".to_string(),
         };
 
@@ -379,10 +534,14 @@ mod tests {
             timestamp: "Now".to_string(),
             unread: false,
             importance: "high".to_string(),
+            ack_required: true,
+            muted: true,
             project_slug: "proj".to_string(),
         };
 
         assert_eq!(item.importance, "high");
+        assert!(item.ack_required);
+        assert!(item.muted);
     }
 
     #[test]
@@ -400,6 +559,8 @@ mod tests {
             timestamp: "Now".to_string(),
             unread: false,
             importance: "normal".to_string(),
+            ack_required: false,
+            muted: false,
             project_slug: "proj".to_string(),
         };
         let item2 = item1.clone();
@@ -409,126 +570,67 @@ mod tests {
     // === Keyboard navigation logic tests ===
 
     #[test]
-    fn test_keyboard_navigation_supported_keys() {
-        // These are the keys that should trigger navigation (including Vim-style j/k)
-        let supported_keys = [
-            "ArrowDown",
-            "ArrowUp",
-            "Home",
-            "End",
-            "Enter",
-            "j",
-            "k",
-            "Escape",
-        ];
-        for key in supported_keys {
-            assert!(
-                key == "ArrowDown"
-                    || key == "ArrowUp"
-                    || key == "Home"
-                    || key == "End"
-                    || key == "Enter"
-                    || key == "j"
-                    || key == "k"
-                    || key == "Escape",
-                "Key {} should be supported",
-                key
-            );
-        }
+    fn test_nav_key_mapping() {
+        assert_eq!(ListNavKey::from_key("ArrowDown"), Some(ListNavKey::Next));
+        assert_eq!(ListNavKey::from_key("j"), Some(ListNavKey::Next));
+        assert_eq!(ListNavKey::from_key("ArrowUp"), Some(ListNavKey::Previous));
+        assert_eq!(ListNavKey::from_key("k"), Some(ListNavKey::Previous));
+        assert_eq!(ListNavKey::from_key("Home"), Some(ListNavKey::First));
+        assert_eq!(ListNavKey::from_key("End"), Some(ListNavKey::Last));
+        assert_eq!(ListNavKey::from_key("Enter"), Some(ListNavKey::Open));
+        assert_eq!(ListNavKey::from_key("Escape"), Some(ListNavKey::Clear));
+        assert_eq!(ListNavKey::from_key("J"), None);
+        assert_eq!(ListNavKey::from_key("a"), None);
     }
 
     #[test]
-    fn test_vim_j_navigates_down() {
-        // j key should behave like ArrowDown
-        let key = "j";
-        assert!(key == "j" || key == "ArrowDown");
+    fn test_next_and_previous_move_one() {
+        assert_eq!(navigate_index(Some(2), 5, ListNavKey::Next), Some(3));
+        assert_eq!(navigate_index(Some(2), 5, ListNavKey::Previous), Some(1));
     }
 
     #[test]
-    fn test_vim_k_navigates_up() {
-        // k key should behave like ArrowUp
-        let key = "k";
-        assert!(key == "k" || key == "ArrowUp");
+    fn test_movement_clamps_at_boundaries() {
+        assert_eq!(navigate_index(Some(4), 5, ListNavKey::Next), Some(4));
+        assert_eq!(navigate_index(Some(0), 5, ListNavKey::Previous), Some(0));
+        assert_eq!(navigate_index(Some(0), 1, ListNavKey::Next), Some(0));
     }
 
     #[test]
-    fn test_escape_key_supported() {
-        // Escape should deselect/blur
-        let key = "Escape";
-        assert_eq!(key, "Escape");
+    fn test_no_selection_starts_at_an_end() {
+        assert_eq!(navigate_index(None, 5, ListNavKey::Next), Some(0));
+        assert_eq!(navigate_index(None, 5, ListNavKey::Open), Some(0));
+        assert_eq!(navigate_index(None, 5, ListNavKey::Previous), Some(4));
     }
 
     #[test]
-    fn test_arrow_down_navigation_logic() {
-        // Simulate arrow down navigation logic
-        let messages = vec![1, 2, 3, 4, 5];
-        let current_idx = Some(2);
-
-        let next_idx = match current_idx {
-            Some(idx) if idx + 1 < messages.len() => idx + 1,
-            None => 0,
-            _ => usize::MAX, // Would return early
-        };
-
-        assert_eq!(next_idx, 3);
+    fn test_home_end_jump() {
+        assert_eq!(navigate_index(Some(2), 5, ListNavKey::First), Some(0));
+        assert_eq!(navigate_index(Some(2), 5, ListNavKey::Last), Some(4));
     }
 
     #[test]
-    fn test_arrow_up_navigation_logic() {
-        // Simulate arrow up navigation logic
-        let messages = vec![1, 2, 3, 4, 5];
-        let current_idx = Some(2);
-
-        let prev_idx = match current_idx {
-            Some(idx) if idx > 0 => idx - 1,
-            None => messages.len() - 1,
-            _ => usize::MAX,
-        };
-
-        assert_eq!(prev_idx, 1);
+    fn test_enter_keeps_selection() {
+        assert_eq!(navigate_index(Some(3), 5, ListNavKey::Open), Some(3));
     }
 
     #[test]
-    fn test_home_key_navigation_logic() {
-        // Home should always go to index 0
-        let messages = vec![1, 2, 3, 4, 5];
-        let first_idx = 0;
-        assert_eq!(messages[first_idx], 1);
+    fn test_escape_clears() {
+        assert_eq!(navigate_index(Some(3), 5, ListNavKey::Clear), None);
     }
 
     #[test]
-    fn test_end_key_navigation_logic() {
-        // End should always go to last index
-        let messages = vec![1, 2, 3, 4, 5];
-        let last_idx = messages.len() - 1;
-        assert_eq!(messages[last_idx], 5);
-    }
-
-    #[test]
-    fn test_arrow_down_at_end_stays() {
-        // At the end of the list, arrow down shouldn't move
-        let messages = vec![1, 2, 3];
-        let current_idx = Some(2); // Last item
-
-        let next_idx = match current_idx {
-            Some(idx) if idx + 1 < messages.len() => Some(idx + 1),
-            _ => None, // Stay in place
-        };
-
-        assert!(next_idx.is_none());
+    fn test_empty_list_selects_nothing() {
+        for key in [ListNavKey::Next, ListNavKey::Previous, ListNavKey::Open] {
+            assert_eq!(navigate_index(None, 0, key), None);
+        }
     }
 
     #[test]
-    fn test_arrow_up_at_start_stays() {
-        // At the start of the list, arrow up shouldn't move
-        let current_idx = Some(0);
-
-        let prev_idx = match current_idx {
-            Some(idx) if idx > 0 => Some(idx - 1),
-            _ => None, // Stay in place
-        };
-
-        assert!(prev_idx.is_none());
+    fn test_stale_index_is_clamped() {
+        // List shrank under the selection (e.g. a refetch with filters)
+        assert_eq!(navigate_index(Some(9), 3, ListNavKey::Next), Some(2));
+        assert_eq!(navigate_index(Some(9), 3, ListNavKey::Previous), Some(1));
     }
 
     // === ARIA attributes tests ===
@@ -0,0 +1,258 @@
+//! Theme provider: Light, Dark or System preference.
+//!
+//! The preference is persisted to localStorage and resolved to the `dark`
+//! class on the document root. In System mode the OS color scheme is tracked
+//! through a `prefers-color-scheme` media query, so switching the OS theme
+//! updates the page without a reload.
+
+use leptos::prelude::*;
+
+/// localStorage key holding the theme preference.
+pub const THEME_STORAGE_KEY: &str = "theme";
+
+/// Previous storage key ("true"/"false"), read once for migration.
+const LEGACY_DARK_MODE_KEY: &str = "darkMode";
+
+/// User's theme choice.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ThemePreference {
+    Light,
+    Dark,
+    /// Follow the OS color scheme
+    #[default]
+    System,
+}
+
+impl ThemePreference {
+    /// Stored representation.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Light => "light",
+            Self::Dark => "dark",
+            Self::System => "system",
+        }
+    }
+
+    /// Parse a stored value.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "light" => Some(Self::Light),
+            "dark" => Some(Self::Dark),
+            "system" => Some(Self::System),
+            _ => None,
+        }
+    }
+
+    /// Next preference for the toggle button: Light → Dark → System.
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Light => Self::Dark,
+            Self::Dark => Self::System,
+            Self::System => Self::Light,
+        }
+    }
+
+    /// Human-readable name.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Light => "Light",
+            Self::Dark => "Dark",
+            Self::System => "System",
+        }
+    }
+
+    /// Lucide icon name.
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Self::Light => "sun",
+            Self::Dark => "moon",
+            Self::System => "monitor",
+        }
+    }
+}
+
+/// Preference from storage: the `theme` value wins, then the legacy
+/// `darkMode` flag, then System.
+pub fn stored_preference(theme: Option<&str>, legacy_dark_mode: Option<&str>) -> ThemePreference {
+    if let Some(pref) = theme.and_then(ThemePreference::parse) {
+        return pref;
+    }
+    match legacy_dark_mode {
+        Some("true") => ThemePreference::Dark,
+        Some("false") => ThemePreference::Light,
+        _ => ThemePreference::System,
+    }
+}
+
+/// Whether the `dark` class applies for `preference` given the OS scheme.
+pub fn resolve_dark(preference: ThemePreference, system_prefers_dark: bool) -> bool {
+    match preference {
+        ThemePreference::Light => false,
+        ThemePreference::Dark => true,
+        ThemePreference::System => system_prefers_dark,
+    }
+}
+
+/// Theme state shared through context.
+#[derive(Clone, Copy)]
+pub struct ThemeContext {
+    preference: RwSignal<ThemePreference>,
+    system_dark: Signal<bool>,
+}
+
+impl ThemeContext {
+    /// Current preference.
+    pub fn preference(&self) -> ThemePreference {
+        self.preference.get()
+    }
+
+    /// Change the preference (persisted by the provider).
+    pub fn set_preference(&self, preference: ThemePreference) {
+        self.preference.set(preference);
+    }
+
+    /// Advance to the next preference.
+    pub fn cycle(&self) {
+        self.preference.update(|p| *p = p.next());
+    }
+
+    /// Whether dark mode is in effect.
+    pub fn is_dark(&self) -> bool {
+        resolve_dark(self.preference.get(), self.system_dark.get())
+    }
+}
+
+/// Hook to access the theme context.
+/// Panics if used outside of a ThemeProvider.
+#[allow(clippy::expect_used)]
+pub fn use_theme() -> ThemeContext {
+    use_context::<ThemeContext>().expect("use_theme must be used within a ThemeProvider component")
+}
+
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+/// Whether the OS prefers a dark color scheme, updated when it changes.
+fn use_system_dark() -> Signal<bool> {
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen::prelude::*;
+
+    let system_dark = RwSignal::new(false);
+    let query = web_sys::window()
+        .and_then(|w| w.match_media("(prefers-color-scheme: dark)").ok())
+        .flatten();
+    if let Some(query) = query {
+        system_dark.set(query.matches());
+        let listener = Closure::<dyn Fn()>::new({
+            let query = query.clone();
+            move || system_dark.set(query.matches())
+        });
+        let _ = query.add_event_listener_with_callback("change", listener.as_ref().unchecked_ref());
+        listener.forget(); // Lives as long as the page
+    }
+    system_dark.into()
+}
+
+/// Provides [`ThemeContext`] and keeps the `dark` class on `<html>` in sync.
+#[component]
+pub fn ThemeProvider(children: Children) -> impl IntoView {
+    let initial = storage()
+        .map(|s| {
+            let theme = s.get_item(THEME_STORAGE_KEY).ok().flatten();
+            let legacy = s.get_item(LEGACY_DARK_MODE_KEY).ok().flatten();
+            stored_preference(theme.as_deref(), legacy.as_deref())
+        })
+        .unwrap_or_default();
+
+    let ctx = ThemeContext {
+        preference: RwSignal::new(initial),
+        system_dark: use_system_dark(),
+    };
+    provide_context(ctx);
+
+    // Persist the preference
+    Effect::new(move |_| {
+        let preference = ctx.preference.get();
+        if let Some(storage) = storage() {
+            let _ = storage.set_item(THEME_STORAGE_KEY, preference.as_str());
+            let _ = storage.remove_item(LEGACY_DARK_MODE_KEY);
+        }
+    });
+
+    // Apply the resolved theme to the document root
+    Effect::new(move |_| {
+        let dark = ctx.is_dark();
+        if let Some(html) = web_sys::window()
+            .and_then(|w| w.document())
+            .and_then(|d| d.document_element())
+        {
+            let _ = if dark {
+                html.class_list().add_1("dark")
+            } else {
+                html.class_list().remove_1("dark")
+            };
+        }
+    });
+
+    children()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stored_value_beats_system() {
+        assert_eq!(
+            stored_preference(Some("light"), None),
+            ThemePreference::Light
+        );
+        assert!(!resolve_dark(stored_preference(Some("light"), None), true));
+        assert!(resolve_dark(stored_preference(Some("dark"), None), false));
+    }
+
+    #[test]
+    fn test_system_tracks_media_query() {
+        let pref = stored_preference(Some("system"), None);
+        assert_eq!(pref, ThemePreference::System);
+        assert!(resolve_dark(pref, true));
+        assert!(!resolve_dark(pref, false));
+    }
+
+    #[test]
+    fn test_missing_or_invalid_defaults_to_system() {
+        assert_eq!(stored_preference(None, None), ThemePreference::System);
+        assert_eq!(
+            stored_preference(Some("purple"), None),
+            ThemePreference::System
+        );
+    }
+
+    #[test]
+    fn test_legacy_dark_mode_flag() {
+        assert_eq!(stored_preference(None, Some("true")), ThemePreference::Dark);
+        assert_eq!(
+            stored_preference(None, Some("false")),
+            ThemePreference::Light
+        );
+        // The new key wins over the legacy one
+        assert_eq!(
+            stored_preference(Some("system"), Some("true")),
+            ThemePreference::System
+        );
+    }
+
+    #[test]
+    fn test_round_trip_and_cycle() {
+        for pref in [
+            ThemePreference::Light,
+            ThemePreference::Dark,
+            ThemePreference::System,
+        ] {
+            assert_eq!(ThemePreference::parse(pref.as_str()), Some(pref));
+            assert_ne!(pref.next(), pref);
+            assert_eq!(pref.next().next().next(), pref);
+        }
+    }
+}
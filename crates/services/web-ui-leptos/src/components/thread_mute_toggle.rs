@@ -0,0 +1,141 @@
+//! Thread Mute Toggle component.
+//!
+//! One chip per agent that mutes or unmutes a thread for that agent. A muted
+//! thread stays out of the agent's inbox except for urgent messages and
+//! ones that @-mention the agent.
+
+use crate::api::client;
+use leptos::prelude::*;
+
+/// Per-agent mute toggles for a thread, with optimistic UI updates.
+///
+/// # Props
+/// - `project_slug`: Project the thread belongs to
+/// - `thread_id`: Thread to mute
+/// - `agents`: Agents offered a toggle, usually the thread's participants
+///
+/// # Accessibility
+/// - `aria-pressed` reflects whether the agent muted the thread
+/// - `aria-label` names the agent and the action
+///
+/// # Example
+/// ```rust,ignore
+/// view! {
+///     <ThreadMuteToggle
+///         project_slug="my-project".to_string()
+///         thread_id="FEAT-123".to_string()
+///         agents=vec!["worker-1".to_string()]
+///     />
+/// }
+/// ```
+#[component]
+pub fn ThreadMuteToggle(
+    /// Project slug for context
+    #[prop(into)]
+    project_slug: String,
+    /// Thread to mute
+    #[prop(into)]
+    thread_id: String,
+    /// Agents offered a toggle
+    agents: Vec<String>,
+) -> impl IntoView {
+    let muted = RwSignal::new(Vec::<String>::new());
+    let pending = RwSignal::new(false);
+    let error = RwSignal::new(Option::<String>::None);
+
+    let (load_project, load_thread) = (project_slug.clone(), thread_id.clone());
+    leptos::task::spawn_local(async move {
+        if let Ok(mutes) = client::get_thread_mutes(&load_project, &load_thread).await {
+            muted.set(mutes.into_iter().map(|m| m.agent_name).collect());
+        }
+    });
+
+    let toggle = Callback::new(move |agent: String| {
+        if pending.get_untracked() {
+            return;
+        }
+        let mute = !muted.with_untracked(|names| names.contains(&agent));
+        muted.update(|names| set_muted(names, &agent, mute));
+        pending.set(true);
+        error.set(None);
+
+        let (project, thread) = (project_slug.clone(), thread_id.clone());
+        leptos::task::spawn_local(async move {
+            if let Err(e) = client::set_thread_muted(&project, &thread, &agent, mute).await {
+                // Rollback on error
+                muted.update(|names| set_muted(names, &agent, !mute));
+                error.set(Some(e.message));
+            }
+            pending.set(false);
+        });
+    });
+
+    view! {
+        <div class="flex items-center gap-2 flex-wrap" aria-label="Thread mutes">
+            <span class="text-xs text-muted-foreground">"Mute for:"</span>
+            {agents.into_iter().map(|agent| {
+                let (check, label_agent, click_agent, name) =
+                    (agent.clone(), agent.clone(), agent.clone(), agent);
+                let is_muted = Signal::derive(move || muted.with(|names| names.contains(&check)));
+                view! {
+                    <button
+                        type="button"
+                        class=move || format!(
+                            "inline-flex items-center gap-1 rounded-full border px-2 py-0.5 text-xs transition-colors {}",
+                            if is_muted.get() {
+                                "border-border bg-muted text-muted-foreground"
+                            } else {
+                                "border-border/50 text-foreground hover:bg-muted/50"
+                            }
+                        )
+                        aria-pressed=move || is_muted.get().to_string()
+                        aria-label=move || format!(
+                            "{} thread for {}",
+                            if is_muted.get() { "Unmute" } else { "Mute" },
+                            label_agent
+                        )
+                        disabled=move || pending.get()
+                        on:click=move |_| toggle.run(click_agent.clone())
+                    >
+                        <i
+                            data-lucide=move || if is_muted.get() { "bell-off" } else { "bell" }
+                            class="icon-xs"
+                        ></i>
+                        {name}
+                    </button>
+                }
+            }).collect_view()}
+            {move || error.get().map(|e| view! {
+                <span class="text-xs text-destructive" role="alert">{e}</span>
+            })}
+        </div>
+    }
+}
+
+/// Add `agent` to or remove it from a sorted list of muting agents.
+fn set_muted(names: &mut Vec<String>, agent: &str, muted: bool) {
+    match (names.binary_search_by(|n| n.as_str().cmp(agent)), muted) {
+        (Err(idx), true) => names.insert(idx, agent.to_string()),
+        (Ok(idx), false) => {
+            names.remove(idx);
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_muted_keeps_names_sorted_and_unique() {
+        let mut names = vec!["Alpha".to_string(), "Gamma".to_string()];
+        set_muted(&mut names, "Beta", true);
+        set_muted(&mut names, "Beta", true);
+        assert_eq!(names, ["Alpha", "Beta", "Gamma"]);
+
+        set_muted(&mut names, "Alpha", false);
+        set_muted(&mut names, "Delta", false);
+        assert_eq!(names, ["Beta", "Gamma"]);
+    }
+}
@@ -0,0 +1,418 @@
+//! Agent detail page - profile, capabilities, reservations, pending acks and
+//! message timeline.
+
+use crate::api::client::{self, AgentActivity};
+use crate::components::{Breadcrumb, BreadcrumbItem, CardSkeleton, Skeleton};
+use leptos::prelude::*;
+use leptos_router::hooks::use_params_map;
+
+/// Number of timeline entries requested from the server.
+const TIMELINE_LIMIT: usize = 50;
+
+/// Agent detail page component.
+#[component]
+pub fn AgentDetail() -> impl IntoView {
+    let params = use_params_map();
+    let project_slug = params.with_untracked(|p| p.get("slug").unwrap_or_default());
+    let agent_name = params.with_untracked(|p| p.get("name").unwrap_or_default());
+
+    // State
+    let activity = RwSignal::new(Option::<AgentActivity>::None);
+    let loading = RwSignal::new(true);
+    let error = RwSignal::new(Option::<String>::None);
+
+    // Fetch activity on mount
+    {
+        let slug = project_slug.clone();
+        let name = agent_name.clone();
+        Effect::new(move |_| {
+            let slug = slug.clone();
+            let name = name.clone();
+            leptos::task::spawn_local(async move {
+                loading.set(true);
+                match client::get_agent_activity(&slug, &name, TIMELINE_LIMIT).await {
+                    Ok(data) => {
+                        activity.set(Some(data));
+                        error.set(None);
+                    }
+                    Err(e) => error.set(Some(e.message)),
+                }
+                loading.set(false);
+            });
+        });
+    }
+
+    let breadcrumb = vec![
+        BreadcrumbItem::new("Projects", "/projects"),
+        BreadcrumbItem::new(project_slug.clone(), format!("/projects/{}", project_slug)),
+        BreadcrumbItem::new(agent_name.clone(), ""),
+    ];
+
+    view! {
+        <div class="space-y-6">
+            <Breadcrumb items=breadcrumb />
+
+            {move || {
+                if loading.get() {
+                    view! { <AgentDetailSkeleton /> }.into_any()
+                } else if let Some(err) = error.get() {
+                    view! {
+                        <div class="card-elevated p-6 text-center text-rose-600 dark:text-rose-400">
+                            <i data-lucide="alert-circle" class="icon-lg mx-auto mb-2"></i>
+                            <p>{err}</p>
+                        </div>
+                    }.into_any()
+                } else if let Some(data) = activity.get() {
+                    let slug = project_slug.clone();
+                    view! {
+                        <div class="space-y-6">
+                            <AgentHeader data=data.clone() project_slug=slug.clone() />
+                            <div class="grid grid-cols-1 lg:grid-cols-3 gap-6">
+                                <div class="space-y-6">
+                                    <CapabilitiesSection data=data.clone() />
+                                    <ReservationsSection data=data.clone() project_slug=slug.clone() />
+                                    <PendingAcksSection data=data.clone() project_slug=slug.clone() />
+                                </div>
+                                <div class="lg:col-span-2">
+                                    <TimelineSection data=data project_slug=slug />
+                                </div>
+                            </div>
+                        </div>
+                    }.into_any()
+                } else {
+                    ().into_any()
+                }
+            }}
+        </div>
+    }
+}
+
+/// Agent name, metadata and last activity.
+#[component]
+fn AgentHeader(data: AgentActivity, project_slug: String) -> impl IntoView {
+    let agent = data.agent;
+    let program = agent.program.unwrap_or_else(|| "unknown".to_string());
+    let model = agent.model.unwrap_or_else(|| "unknown".to_string());
+    let task = agent.task_description.filter(|t| !t.trim().is_empty());
+    let inception = agent.inception_ts.unwrap_or_default();
+    let last_active = agent.last_active_ts.unwrap_or_default();
+    let inbox_href = format!("/inbox?project={}&agent={}", project_slug, agent.name);
+
+    view! {
+        <div class="card-elevated p-6">
+            <div class="flex items-start justify-between gap-4">
+                <div class="flex items-center gap-3">
+                    <div class="w-12 h-12 bg-violet-100 dark:bg-violet-900/50 rounded-xl flex items-center justify-center">
+                        <i data-lucide="bot" class="icon-lg text-violet-600 dark:text-violet-400"></i>
+                    </div>
+                    <div>
+                        <h1 class="font-display text-2xl font-bold text-charcoal-800 dark:text-cream-100">
+                            {agent.name}
+                        </h1>
+                        <p class="text-sm text-charcoal-500 dark:text-charcoal-400">
+                            {program}" · "<span class="font-mono text-xs">{model}</span>
+                        </p>
+                    </div>
+                </div>
+                <a
+                    href=inbox_href
+                    class="flex items-center gap-2 text-amber-600 dark:text-amber-400 hover:text-amber-700 dark:hover:text-amber-300 text-sm font-medium"
+                >
+                    <i data-lucide="inbox" class="icon-sm"></i>
+                    "View Inbox"
+                </a>
+            </div>
+
+            {task.map(|t| view! {
+                <p class="mt-4 text-charcoal-700 dark:text-charcoal-300">{t}</p>
+            })}
+
+            <dl class="mt-4 pt-4 border-t border-cream-200 dark:border-charcoal-700 grid grid-cols-2 gap-4 text-sm">
+                <div>
+                    <dt class="text-charcoal-500 dark:text-charcoal-400 flex items-center gap-1">
+                        <i data-lucide="calendar" class="icon-xs"></i>
+                        "Registered"
+                    </dt>
+                    <dd class="font-mono text-xs text-charcoal-600 dark:text-charcoal-400 mt-1">{format_date(&inception)}</dd>
+                </div>
+                <div>
+                    <dt class="text-charcoal-500 dark:text-charcoal-400 flex items-center gap-1">
+                        <i data-lucide="clock" class="icon-xs"></i>
+                        "Last Active"
+                    </dt>
+                    <dd class="font-mono text-xs text-charcoal-600 dark:text-charcoal-400 mt-1">{format_date(&last_active)}</dd>
+                </div>
+            </dl>
+        </div>
+    }
+}
+
+/// Capabilities currently granted to the agent.
+#[component]
+fn CapabilitiesSection(data: AgentActivity) -> impl IntoView {
+    let capabilities = data.capabilities;
+
+    view! {
+        <section class="card-elevated p-6">
+            <SectionTitle icon="key-round" title="Capabilities" count=capabilities.len() />
+            {if capabilities.is_empty() {
+                view! { <EmptySection icon="key" message="No capabilities granted" /> }.into_any()
+            } else {
+                view! {
+                    <ul class="flex flex-wrap gap-2">
+                        {capabilities.into_iter().map(|c| {
+                            let title = match &c.expires_at {
+                                Some(exp) => format!("Granted {} · expires {}", format_date(&c.granted_at), format_date(exp)),
+                                None => format!("Granted {}", format_date(&c.granted_at)),
+                            };
+                            view! {
+                                <li
+                                    class="px-2 py-1 rounded-md bg-violet-100 dark:bg-violet-900/40 text-violet-700 dark:text-violet-300 font-mono text-xs"
+                                    title=title
+                                >
+                                    {c.capability}
+                                </li>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </ul>
+                }.into_any()
+            }}
+        </section>
+    }
+}
+
+/// Active file reservations held by the agent.
+#[component]
+fn ReservationsSection(data: AgentActivity, project_slug: String) -> impl IntoView {
+    let reservations = data.file_reservations;
+    let all_href = format!("/projects/{}/file-reservations", project_slug);
+
+    view! {
+        <section class="card-elevated p-6">
+            <SectionTitle icon="shield" title="File Reservations" count=reservations.len() />
+            {if reservations.is_empty() {
+                view! { <EmptySection icon="file-check" message="No active file reservations" /> }.into_any()
+            } else {
+                view! {
+                    <ul class="space-y-3">
+                        {reservations.into_iter().map(|r| view! {
+                            <li class="text-sm">
+                                <div class="flex items-center justify-between gap-2">
+                                    <code class="bg-cream-100 dark:bg-charcoal-800 px-2 py-1 rounded font-mono text-xs truncate">
+                                        {r.path_pattern}
+                                    </code>
+                                    {if r.exclusive {
+                                        view! {
+                                            <span class="text-xs px-2 py-0.5 rounded-full bg-amber-100 dark:bg-amber-900/30 text-amber-700 dark:text-amber-400">"Exclusive"</span>
+                                        }.into_any()
+                                    } else {
+                                        view! {
+                                            <span class="text-xs px-2 py-0.5 rounded-full bg-sky-100 dark:bg-sky-900/30 text-sky-700 dark:text-sky-400">"Shared"</span>
+                                        }.into_any()
+                                    }}
+                                </div>
+                                <p class="mt-1 text-xs text-charcoal-500 dark:text-charcoal-400">
+                                    {format!("Expires {}", format_date(&r.expires_ts))}
+                                </p>
+                            </li>
+                        }).collect::<Vec<_>>()}
+                    </ul>
+                }.into_any()
+            }}
+            <a href=all_href class="mt-4 inline-block text-sm text-amber-600 dark:text-amber-400 hover:underline">
+                "All project reservations"
+            </a>
+        </section>
+    }
+}
+
+/// Received messages the agent has yet to acknowledge, oldest first.
+#[component]
+fn PendingAcksSection(data: AgentActivity, project_slug: String) -> impl IntoView {
+    let agent_name = data.agent.name;
+    let pending = data.pending_acks;
+
+    view! {
+        <section class="card-elevated p-6">
+            <SectionTitle icon="mail-check" title="Pending Acks" count=pending.len() />
+            {if pending.is_empty() {
+                view! { <EmptySection icon="check-check" message="Nothing waiting on an acknowledgement" /> }.into_any()
+            } else {
+                view! {
+                    <ul class="space-y-3">
+                        {pending.into_iter().map(|ack| {
+                            let href = format!(
+                                "/inbox/{}?project={}&agent={}",
+                                ack.message_id,
+                                urlencoding::encode(&project_slug),
+                                urlencoding::encode(&agent_name)
+                            );
+                            let sender = match &ack.project_slug {
+                                Some(slug) => format!("{}::{}", slug, ack.sender_name),
+                                None => ack.sender_name.clone(),
+                            };
+                            let age = match ack.days_outstanding {
+                                0 => "today".to_string(),
+                                1 => "1 day".to_string(),
+                                days => format!("{} days", days),
+                            };
+                            view! {
+                                <li class="text-sm">
+                                    <a href=href class="flex items-center justify-between gap-2 hover:underline">
+                                        <span class="truncate text-charcoal-700 dark:text-charcoal-300">{ack.subject}</span>
+                                        <span class="text-xs px-2 py-0.5 rounded-full bg-amber-100 dark:bg-amber-900/30 text-amber-700 dark:text-amber-400 whitespace-nowrap">
+                                            {age}
+                                        </span>
+                                    </a>
+                                    <p class="mt-1 text-xs text-charcoal-500 dark:text-charcoal-400">
+                                        {format!("From {}", sender)}
+                                    </p>
+                                </li>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </ul>
+                }.into_any()
+            }}
+        </section>
+    }
+}
+
+/// Sent and received messages, newest first.
+#[component]
+fn TimelineSection(data: AgentActivity, project_slug: String) -> impl IntoView {
+    let agent_name = data.agent.name;
+    let timeline = data.timeline;
+
+    view! {
+        <section class="card-elevated p-6">
+            <SectionTitle icon="activity" title="Recent Messages" count=timeline.len() />
+            {if timeline.is_empty() {
+                view! { <EmptySection icon="mail" message="No messages sent or received yet" /> }.into_any()
+            } else {
+                view! {
+                    <ol class="divide-y divide-cream-200 dark:divide-charcoal-700">
+                        {timeline.into_iter().map(|entry| {
+                            let sent = entry.direction == "sent";
+                            let (icon, icon_class, label) = if sent {
+                                ("arrow-up-right", "text-sky-600 dark:text-sky-400", "To")
+                            } else {
+                                ("arrow-down-left", "text-emerald-600 dark:text-emerald-400", "From")
+                            };
+                            let href = format!(
+                                "/inbox/{}?project={}&agent={}",
+                                entry.message_id,
+                                urlencoding::encode(&project_slug),
+                                urlencoding::encode(&agent_name)
+                            );
+                            let unread = !sent && entry.read_ts.is_none();
+                            let high = entry.importance == "high" || entry.importance == "urgent";
+                            let subject = if entry.subject.trim().is_empty() {
+                                "(no subject)".to_string()
+                            } else {
+                                entry.subject
+                            };
+
+                            view! {
+                                <li>
+                                    <a href=href class="flex items-start gap-3 py-3 hover:bg-cream-50 dark:hover:bg-charcoal-800/50 -mx-2 px-2 rounded-lg transition-colors">
+                                        <span class="mt-0.5" title={if sent { "Sent" } else { "Received" }}>
+                                            <i data-lucide=icon class=format!("icon-sm {}", icon_class)></i>
+                                        </span>
+                                        <div class="flex-1 min-w-0">
+                                            <div class="flex items-center gap-2">
+                                                <span class={if unread {
+                                                    "font-semibold text-charcoal-800 dark:text-cream-100 truncate"
+                                                } else {
+                                                    "text-charcoal-700 dark:text-charcoal-300 truncate"
+                                                }}>
+                                                    {subject}
+                                                </span>
+                                                {high.then(|| view! {
+                                                    <span class="text-xs px-1.5 py-0.5 rounded bg-rose-100 dark:bg-rose-900/30 text-rose-700 dark:text-rose-400">
+                                                        {entry.importance.clone()}
+                                                    </span>
+                                                })}
+                                            </div>
+                                            <p class="text-xs text-charcoal-500 dark:text-charcoal-400 truncate">
+                                                {format!("{} {}", label, entry.counterpart)}
+                                            </p>
+                                        </div>
+                                        <span class="font-mono text-xs text-charcoal-500 dark:text-charcoal-400 whitespace-nowrap">
+                                            {format_date(&entry.created_ts)}
+                                        </span>
+                                    </a>
+                                </li>
+                            }
+                        }).collect::<Vec<_>>()}
+                    </ol>
+                }.into_any()
+            }}
+        </section>
+    }
+}
+
+#[component]
+fn SectionTitle(icon: &'static str, title: &'static str, count: usize) -> impl IntoView {
+    view! {
+        <h2 class="flex items-center gap-2 font-display font-semibold text-charcoal-800 dark:text-cream-100 mb-4">
+            <i data-lucide=icon class="icon-sm text-charcoal-500"></i>
+            {title}
+            <span class="text-xs font-normal text-charcoal-500 dark:text-charcoal-400">{format!("({})", count)}</span>
+        </h2>
+    }
+}
+
+#[component]
+fn EmptySection(icon: &'static str, message: &'static str) -> impl IntoView {
+    view! {
+        <div class="py-6 text-center text-charcoal-400">
+            <i data-lucide=icon class="icon-lg mx-auto mb-2 opacity-50"></i>
+            <p class="text-sm">{message}</p>
+        </div>
+    }
+}
+
+/// Placeholder layout shown while the activity loads.
+#[component]
+fn AgentDetailSkeleton() -> impl IntoView {
+    view! {
+        <div class="space-y-6">
+            <div class="card-elevated p-6 space-y-4">
+                <div class="flex items-center gap-3">
+                    <Skeleton class="h-12 w-12 rounded-xl".to_string() />
+                    <div class="space-y-2 flex-1">
+                        <Skeleton class="h-6 w-1/3".to_string() />
+                        <Skeleton class="h-3 w-1/4".to_string() />
+                    </div>
+                </div>
+                <Skeleton class="h-4 w-2/3".to_string() />
+            </div>
+            <div class="grid grid-cols-1 lg:grid-cols-3 gap-6">
+                <div class="space-y-6">
+                    <CardSkeleton />
+                    <CardSkeleton />
+                </div>
+                <div class="lg:col-span-2 rounded-lg border border-border p-6 space-y-4">
+                    <Skeleton class="h-5 w-1/4".to_string() />
+                    {(0..5).map(|_| view! {
+                        <div class="flex items-center gap-3">
+                            <Skeleton class="h-4 w-4 rounded-full".to_string() />
+                            <div class="flex-1 space-y-2">
+                                <Skeleton class="h-4 w-3/4".to_string() />
+                                <Skeleton class="h-3 w-1/3".to_string() />
+                            </div>
+                        </div>
+                    }).collect::<Vec<_>>()}
+                </div>
+            </div>
+        </div>
+    }
+}
+
+/// Trim an ISO timestamp to minutes for display.
+fn format_date(ts: &str) -> String {
+    if ts.is_empty() {
+        return "—".to_string();
+    }
+    ts.get(..16).unwrap_or(ts).replace('T', " ")
+}
@@ -10,6 +10,7 @@ use crate::components::{
 };
 use leptos::prelude::*;
 use leptos_router::hooks::use_query_map;
+use std::cmp::Reverse;
 
 /// Sort options for attachments
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
@@ -73,12 +74,12 @@ impl AttachmentSort {
         match self {
             Self::DateDesc => attachments.sort_by(|a, b| b.created_ts.cmp(&a.created_ts)),
             Self::DateAsc => attachments.sort_by(|a, b| a.created_ts.cmp(&b.created_ts)),
-            Self::NameAsc => attachments
-                .sort_by(|a, b| a.filename.to_lowercase().cmp(&b.filename.to_lowercase())),
-            Self::NameDesc => attachments
-                .sort_by(|a, b| b.filename.to_lowercase().cmp(&a.filename.to_lowercase())),
-            Self::SizeDesc => attachments.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes)),
-            Self::SizeAsc => attachments.sort_by(|a, b| a.size_bytes.cmp(&b.size_bytes)),
+            Self::NameAsc => attachments.sort_by_cached_key(|a| a.filename.to_lowercase()),
+            Self::NameDesc => {
+                attachments.sort_by_cached_key(|a| Reverse(a.filename.to_lowercase()))
+            }
+            Self::SizeDesc => attachments.sort_by_key(|a| Reverse(a.size_bytes)),
+            Self::SizeAsc => attachments.sort_by_key(|a| a.size_bytes),
         }
     }
 }
@@ -4,7 +4,7 @@
 use crate::api::client::{self, Agent, InboxMessage, Project};
 use crate::components::{
     AgentAvatar, Alert, AlertDescription, AlertVariant, AvatarSize, Badge, BadgeVariant, Button,
-    ButtonVariant, Select, SelectIcon, SelectOption, Spinner, SpinnerSize,
+    ButtonVariant, Select, SelectIcon, SelectOption, SnoozeMenu, Spinner, SpinnerSize,
 };
 use leptos::prelude::*;
 use leptos_router::hooks::use_query_map;
@@ -175,6 +175,7 @@ pub fn Inbox() -> impl IntoView {
                     if !project.is_empty() && !agent.is_empty() {
                          Some(view! {
                             <div class="fixed inset-0 z-50 flex items-center justify-center p-4 sm:p-6">
+                                // Backdrop click closes; the composer saves its draft on close
                                 <div
                                     class="fixed inset-0 bg-charcoal-900/50 backdrop-blur-sm transition-opacity"
                                     on:click=move |_| show_compose.set(false)
@@ -186,6 +187,7 @@ pub fn Inbox() -> impl IntoView {
                                             sender_name: agent,
                                             agents: agent_list,
                                             reply_to: None,
+                                            forward: None,
                                         }
                                         on_close=Callback::new(move |_| show_compose.set(false))
                                         on_sent=Callback::new(move |_| {
@@ -409,13 +411,21 @@ pub fn Inbox() -> impl IntoView {
                                         let subject = msg.subject.clone();
                                         let sender = msg.sender_name.clone();
                                         let created = msg.created_ts.clone();
+                                        // Senders in other projects show their address
+                                        let sender_label = match &msg.project_slug {
+                                            Some(slug) => format!("{}::{}", slug, sender),
+                                            None => sender.clone(),
+                                        };
 
                                         let sender_for_avatar = sender.clone();
+                                        let returned_from_snooze = msg.returned_from_snooze;
+                                        let needs_ack = msg.needs_ack;
+                                        let (snooze_project, snooze_agent) = (project.clone(), agent.clone());
                                         view! {
-                                            <li class="group">
+                                            <li class="group flex items-center hover:bg-cream-50 dark:hover:bg-charcoal-800/50 transition-colors">
                                                 <a
                                                     href=href
-                                                    class="flex items-start gap-4 px-6 py-4 hover:bg-cream-50 dark:hover:bg-charcoal-800/50 transition-colors"
+                                                    class="flex-1 min-w-0 flex items-start gap-4 pl-6 pr-2 py-4"
                                                 >
                                                     // Sender Avatar
                                                     <div class="flex-shrink-0 group-hover:scale-105 transition-transform">
@@ -432,14 +442,36 @@ pub fn Inbox() -> impl IntoView {
                                                                 {format_date(&created)}
                                                             </span>
                                                         </div>
-                                                        <p class="text-sm text-charcoal-500 dark:text-charcoal-400">
-                                                            <span>{sender}</span>
+                                                        <p class="flex items-center gap-2 text-sm text-charcoal-500 dark:text-charcoal-400">
+                                                            <span>{sender_label}</span>
+                                                            {returned_from_snooze.then(|| view! {
+                                                                <Badge variant=BadgeVariant::Secondary class="flex items-center gap-1">
+                                                                    <i data-lucide="alarm-clock" class="h-3 w-3"></i>
+                                                                    "Back from snooze"
+                                                                </Badge>
+                                                            })}
+                                                            {needs_ack.then(|| view! {
+                                                                <Badge variant=BadgeVariant::Warning class="flex items-center gap-1">
+                                                                    <i data-lucide="mail-check" class="h-3 w-3"></i>
+                                                                    "Needs ack"
+                                                                </Badge>
+                                                            })}
                                                         </p>
                                                     </div>
 
                                                     // Arrow
                                                     <i data-lucide="chevron-right" class="icon-sm flex-shrink-0 text-charcoal-300 dark:text-charcoal-600 group-hover:text-amber-500 group-hover:translate-x-1 transition-all"></i>
                                                 </a>
+                                                <div class="flex-shrink-0 pr-4">
+                                                    <SnoozeMenu
+                                                        message_id=id
+                                                        project_slug=snooze_project
+                                                        agent_name=snooze_agent
+                                                        on_snoozed=Callback::new(move |snoozed: i64| {
+                                                            messages.update(|list| list.retain(|m| m.id != snoozed));
+                                                        })
+                                                    />
+                                                </div>
                                             </li>
                                         }
                                     }).collect::<Vec<_>>()}
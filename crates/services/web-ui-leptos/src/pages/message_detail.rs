@@ -3,11 +3,38 @@
 
 use crate::api::client::{self, Agent, Message};
 use crate::components::{
-    Button, ButtonVariant, ComposeMessage, ComposeProps, MessageDetailHeader, ReplyTo,
+    Button, ButtonVariant, ComposeMessage, ComposeProps, ForwardOf, Input, MessageDetailHeader,
+    ReplyTo, reply_all_recipients,
 };
+use crate::utils::permalink::legacy_message_redirect;
 use leptos::prelude::*;
+use leptos_router::NavigateOptions;
+use leptos_router::components::Redirect;
 use leptos_router::hooks::{use_params_map, use_query_map};
 
+/// Legacy `/inbox/{id}` route: redirects to the permalink when the project
+/// is known, otherwise shows the message in place.
+#[component]
+pub fn LegacyMessageDetail() -> impl IntoView {
+    let params = use_params_map();
+    let query = use_query_map();
+    let message_id = params.with_untracked(|p| p.get("id").unwrap_or_default());
+    let (project, agent) = query.with_untracked(|q| {
+        (
+            q.get("project").unwrap_or_default(),
+            q.get("agent").unwrap_or_default(),
+        )
+    });
+
+    match legacy_message_redirect(&message_id, &project, &agent) {
+        Some(path) => view! {
+            <Redirect path=path options=NavigateOptions { replace: true, ..Default::default() } />
+        }
+        .into_any(),
+        None => view! { <MessageDetail /> }.into_any(),
+    }
+}
+
 /// Message detail page component.
 #[component]
 pub fn MessageDetail() -> impl IntoView {
@@ -17,7 +44,11 @@ pub fn MessageDetail() -> impl IntoView {
     // Use with_untracked since route params don't change without navigation
     // This avoids reactive tracking warnings while still getting the values
     let message_id = params.with_untracked(|p| p.get("id").unwrap_or_default());
-    let project_slug = query.with_untracked(|q| q.get("project").unwrap_or_default());
+    // Permalinks carry the project in the path, legacy links in the query
+    let project_slug = params
+        .with_untracked(|p| p.get("slug"))
+        .or_else(|| query.with_untracked(|q| q.get("project")))
+        .unwrap_or_default();
     let agent_name = query.with_untracked(|q| q.get("agent").unwrap_or_default());
 
     // State
@@ -26,6 +57,11 @@ pub fn MessageDetail() -> impl IntoView {
     let loading = RwSignal::new(true);
     let error = RwSignal::new(Option::<String>::None);
     let show_reply = RwSignal::new(false);
+    let reply_all = RwSignal::new(false);
+    let forwarding = RwSignal::new(false);
+    let show_recall = RwSignal::new(false);
+    let recall_reason = RwSignal::new(String::new());
+    let recalling = RwSignal::new(false);
 
     // Clone values for use in Effect
     let id_for_effect = message_id.clone();
@@ -58,6 +94,42 @@ pub fn MessageDetail() -> impl IntoView {
         });
     });
 
+    // Recall the message (sender only), then reload it to show the tombstone
+    let recall_project = project_slug.clone();
+    let recall_agent = agent_name.clone();
+    let recall_id = message_id.clone();
+    let submit_recall = move || {
+        let reason = recall_reason.get_untracked();
+        if reason.trim().is_empty() {
+            error.set(Some(
+                "Enter a reason for recalling this message".to_string(),
+            ));
+            return;
+        }
+        let Ok(msg_id) = recall_id.parse::<i64>() else {
+            return;
+        };
+        let project = recall_project.clone();
+        let agent = recall_agent.clone();
+        let id = recall_id.clone();
+
+        recalling.set(true);
+        error.set(None);
+        leptos::task::spawn_local(async move {
+            match client::recall_message(&project, &agent, msg_id, &reason).await {
+                Ok(_) => {
+                    show_recall.set(false);
+                    recall_reason.set(String::new());
+                    if let Ok(m) = client::get_message(&id).await {
+                        message.set(Some(m));
+                    }
+                }
+                Err(e) => error.set(Some(e.message)),
+            }
+            recalling.set(false);
+        });
+    };
+
     // Back to unified inbox URL
     let back_url = "/mail/unified".to_string();
 
@@ -101,9 +173,12 @@ pub fn MessageDetail() -> impl IntoView {
             {
                 let back_url_for_content = back_url.clone();
                 let project_slug_for_detail = project_slug.clone();
+                let agent_for_detail = agent_name.clone();
+                let submit_recall = submit_recall.clone();
                 move || {
                 let back_url = back_url_for_content.clone();
                 let project_slug = project_slug_for_detail.clone();
+                let submit_recall = submit_recall.clone();
                 if loading.get() {
                     view! {
                         <div class="flex items-center justify-center py-16">
@@ -123,6 +198,10 @@ pub fn MessageDetail() -> impl IntoView {
                     let msg_id = msg.id;
                     let sender = msg.sender_name.clone();
                     let can_reply = !agents.get().is_empty();
+                    let can_reply_all = reply_all_recipients(&sender, &msg.recipients, &agent_for_detail).len() > 1;
+                    let recalled = msg.recalled_ts.is_some();
+                    let can_recall = !recalled && !agent_for_detail.is_empty() && agent_for_detail == sender;
+                    let can_forward = can_reply && !recalled && !agent_for_detail.is_empty();
 
                     view! {
                         <div class="card-elevated overflow-hidden">
@@ -134,8 +213,47 @@ pub fn MessageDetail() -> impl IntoView {
                                 project_slug={project_slug.clone()}
                                 sent_at={created.clone()}
                                 message_id={msg_id}
+                                permalink={msg.permalink.clone()}
+                                on_recall={can_recall.then(|| Callback::new(move |_| show_recall.set(true)))}
+                                on_forward={can_forward.then(|| Callback::new(move |_| {
+                                    forwarding.set(true);
+                                    show_reply.set(true);
+                                }))}
                             />
 
+                            // Recall reason form
+                            {move || show_recall.get().then(|| {
+                                let submit_recall = submit_recall.clone();
+                                view! {
+                                    <div class="px-6 py-4 border-b border-cream-200 dark:border-charcoal-700 bg-red-50/50 dark:bg-red-900/10 space-y-3">
+                                        <p class="text-sm text-charcoal-600 dark:text-charcoal-300">
+                                            "Recipients will see this reason instead of the original message."
+                                        </p>
+                                        <Input
+                                            id="recallReason".to_string()
+                                            value=recall_reason
+                                            placeholder="Reason for recalling".to_string()
+                                            disabled=recalling.get()
+                                        />
+                                        <div class="flex gap-2">
+                                            <Button
+                                                variant=ButtonVariant::Destructive
+                                                disabled=recalling.get()
+                                                on_click=Callback::new(move |_| submit_recall())
+                                            >
+                                                "Recall Message"
+                                            </Button>
+                                            <Button
+                                                variant=ButtonVariant::Secondary
+                                                on_click=Callback::new(move |_| show_recall.set(false))
+                                            >
+                                                "Cancel"
+                                            </Button>
+                                        </div>
+                                    </div>
+                                }
+                            })}
+
                             // Badges and Reply button
                             <div class="px-6 py-3 border-b border-cream-200 dark:border-charcoal-700 flex flex-wrap items-center justify-between gap-2">
                                 <div class="flex flex-wrap items-center gap-2 text-sm">
@@ -160,6 +278,12 @@ pub fn MessageDetail() -> impl IntoView {
                                     } else {
                                         None
                                     }}
+                                    {recalled.then(|| view! {
+                                        <span class="badge badge-red flex items-center gap-1">
+                                            <i data-lucide="undo-2" class="icon-xs"></i>
+                                            "Recalled"
+                                        </span>
+                                    })}
                                     {thread_id.as_ref().map(|tid| view! {
                                         <span class="badge badge-violet flex items-center gap-1">
                                             <i data-lucide="git-branch" class="icon-xs"></i>
@@ -169,13 +293,32 @@ pub fn MessageDetail() -> impl IntoView {
                                 </div>
                                 {if can_reply {
                                     Some(view! {
-                                        <Button
-                                            variant=ButtonVariant::Default
-                                            on_click=Callback::new(move |_| show_reply.set(true))
-                                        >
-                                            <i data-lucide="reply" class="icon-sm"></i>
-                                            <span>"Reply"</span>
-                                        </Button>
+                                        <div class="flex items-center gap-2">
+                                            {can_reply_all.then(|| view! {
+                                                <Button
+                                                    variant=ButtonVariant::Secondary
+                                                    on_click=Callback::new(move |_| {
+                                                        forwarding.set(false);
+                                                        reply_all.set(true);
+                                                        show_reply.set(true);
+                                                    })
+                                                >
+                                                    <i data-lucide="reply-all" class="icon-sm"></i>
+                                                    <span>"Reply All"</span>
+                                                </Button>
+                                            })}
+                                            <Button
+                                                variant=ButtonVariant::Default
+                                                on_click=Callback::new(move |_| {
+                                                    forwarding.set(false);
+                                                    reply_all.set(false);
+                                                    show_reply.set(true);
+                                                })
+                                            >
+                                                <i data-lucide="reply" class="icon-sm"></i>
+                                                <span>"Reply"</span>
+                                            </Button>
+                                        </div>
                                     })
                                 } else {
                                     None
@@ -225,7 +368,11 @@ pub fn MessageDetail() -> impl IntoView {
                                 Some(view! {
                                     <Button
                                         variant=ButtonVariant::Default
-                                        on_click=Callback::new(move |_| show_reply.set(true))
+                                        on_click=Callback::new(move |_| {
+                                            forwarding.set(false);
+                                            reply_all.set(false);
+                                            show_reply.set(true);
+                                        })
                                     >
                                         <i data-lucide="reply" class="icon-sm"></i>
                                         "Reply to Message"
@@ -259,22 +406,29 @@ pub fn MessageDetail() -> impl IntoView {
                 }
             }}
 
-            // Reply Modal
+            // Reply / Forward Modal
             {
                 let project_for_modal = project_slug.clone();
                 let agent_for_modal = agent_name.clone();
                 move || {
                 if show_reply.get() {
                     if let Some(msg) = message.get() {
+                        let recipient_names = if reply_all.get() {
+                            reply_all_recipients(&msg.sender_name, &msg.recipients, &agent_for_modal)
+                        } else {
+                            vec![msg.sender_name.clone()]
+                        };
+                        let forward = forwarding.get().then(|| ForwardOf::from(&msg));
                         let props = ComposeProps {
                             project_slug: project_for_modal.clone(),
                             sender_name: agent_for_modal.clone(),
                             agents: agents.get(),
-                            reply_to: Some(ReplyTo {
+                            reply_to: forward.is_none().then(|| ReplyTo {
                                 thread_id: msg.thread_id.clone().or_else(|| Some(format!("thread-{}", msg.id))),
                                 subject: msg.subject.clone(),
-                                recipient_name: Some(msg.sender_name.clone()),
+                                recipient_names,
                             }),
+                            forward,
                         };
 
                         Some(view! {
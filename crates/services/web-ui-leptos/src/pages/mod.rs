@@ -4,6 +4,7 @@
 //! This pattern triggers `unreachable_pub` lint, but is intentional for Leptos components.
 #![allow(unreachable_pub)]
 
+mod agent_detail;
 mod agents;
 mod archive;
 mod attachments;
@@ -13,19 +14,28 @@ mod inbox;
 mod message_detail;
 mod project_detail;
 mod projects;
+mod reservations;
 mod search;
+mod sent;
+mod stats;
 mod thread;
+mod threads;
 mod unified_inbox;
 
+pub use agent_detail::AgentDetail;
 pub use agents::Agents;
 pub use archive::ArchiveBrowser;
 pub use attachments::Attachments;
 pub use dashboard::Dashboard;
 pub use file_reservations::FileReservations;
 pub use inbox::Inbox;
-pub use message_detail::MessageDetail;
+pub use message_detail::{LegacyMessageDetail, MessageDetail};
 pub use project_detail::ProjectDetail;
 pub use projects::Projects;
+pub use reservations::Reservations;
 pub use search::Search;
-pub use thread::ThreadView;
+pub use sent::Sent;
+pub use stats::Stats;
+pub use thread::{LegacyThreadView, ThreadView};
+pub use threads::Threads;
 pub use unified_inbox::UnifiedInbox;
@@ -1,8 +1,12 @@
 //! Project detail page - view project info and manage agents.
 //! Digital Correspondence design with Lucide icons.
 
-use crate::api::client::{self, Agent};
-use crate::components::{Breadcrumb, BreadcrumbItem, Button, ButtonVariant, Input};
+use crate::api::client::{self, Agent, DndPolicy};
+use crate::api::project_version::project_changes;
+use crate::components::{
+    Badge, BadgeVariant, Breadcrumb, BreadcrumbItem, Button, ButtonVariant, Input,
+};
+use crate::utils::validation::{AGENT_NAME_HINT, agent_name_error};
 use leptos::prelude::*;
 use leptos_router::hooks::use_params_map;
 
@@ -25,6 +29,14 @@ pub fn ProjectDetail() -> impl IntoView {
     let new_model = RwSignal::new(String::new());
     let new_task = RwSignal::new(String::new());
 
+    // Edit form: name of the agent being edited, plus its editable fields
+    let editing = RwSignal::new(Option::<String>::None);
+    let saving = RwSignal::new(false);
+    let edit_name = RwSignal::new(String::new());
+    let edit_program = RwSignal::new(String::new());
+    let edit_model = RwSignal::new(String::new());
+    let edit_task = RwSignal::new(String::new());
+
     // Load agents
     let load_agents = {
         move || {
@@ -46,9 +58,10 @@ pub fn ProjectDetail() -> impl IntoView {
         }
     };
 
-    // Initial load
+    // Initial load, and again after adopt, rename, retire or prune
     Effect::new({
         move |_| {
+            project_changes(slug()).track();
             load_agents();
         }
     });
@@ -56,8 +69,8 @@ pub fn ProjectDetail() -> impl IntoView {
     // Create agent handler
     let create_agent = {
         move |_| {
-            let name = new_name.get();
-            if name.trim().is_empty() {
+            let name = new_name.get().trim().to_string();
+            if name.is_empty() || agent_name_error(&name).is_some() {
                 return;
             }
 
@@ -109,6 +122,73 @@ pub fn ProjectDetail() -> impl IntoView {
         }
     };
 
+    // Save edits: only send fields that changed
+    let save_agent = {
+        move |_| {
+            let Some(agent_name) = editing.get() else {
+                return;
+            };
+            let Some(current) = agents.get().into_iter().find(|a| a.name == agent_name) else {
+                return;
+            };
+            let name = edit_name.get().trim().to_string();
+            if name.is_empty() || agent_name_error(&name).is_some() {
+                return;
+            }
+
+            let changed = |value: String, old: Option<String>| {
+                (Some(&value) != old.as_ref()).then_some(value)
+            };
+            let update = client::AgentUpdate {
+                name: (name != current.name).then_some(name),
+                task_description: changed(edit_task.get(), current.task_description),
+                model: changed(edit_model.get(), current.model),
+                program: changed(edit_program.get(), current.program),
+            };
+
+            let project_slug = slug();
+            saving.set(true);
+            error.set(None);
+
+            leptos::task::spawn_local(async move {
+                match client::update_agent(&project_slug, &agent_name, &update).await {
+                    Ok(_) => {
+                        match client::get_agents(&project_slug).await {
+                            Ok(a) => agents.set(a),
+                            Err(e) => error.set(Some(e.message)),
+                        }
+                        editing.set(None);
+                    }
+                    Err(e) => error.set(Some(e.message)),
+                }
+                saving.set(false);
+            });
+        }
+    };
+
+    // Retire the agent being edited; its messages stay in the archive
+    let retire_agent = {
+        move |_| {
+            let Some(agent_name) = editing.get() else {
+                return;
+            };
+            let project_slug = slug();
+            saving.set(true);
+            error.set(None);
+
+            leptos::task::spawn_local(async move {
+                match client::retire_agent(&project_slug, &agent_name).await {
+                    Ok(_) => {
+                        agents.update(|list| list.retain(|a| a.name != agent_name));
+                        editing.set(None);
+                    }
+                    Err(e) => error.set(Some(e.message)),
+                }
+                saving.set(false);
+            });
+        }
+    };
+
     view! {
         <div class="space-y-6">
             // Breadcrumb using reusable component
@@ -127,6 +207,18 @@ pub fn ProjectDetail() -> impl IntoView {
                     <p class="text-charcoal-500 dark:text-charcoal-400">"Agents in this project"</p>
                 </div>
                 <div class="flex items-center gap-3">
+                    <Button
+                        variant=ButtonVariant::Secondary
+                        disabled=Signal::derive(move || loading.get())
+                        on_click=Callback::new(move |_| load_agents())
+                    >
+                        {move || if loading.get() {
+                            view! { <i data-lucide="loader-2" class="icon-sm animate-spin"></i> }
+                        } else {
+                            view! { <i data-lucide="refresh-cw" class="icon-sm"></i> }
+                        }}
+                        <span>"Refresh"</span>
+                    </Button>
                     <a
                         href={move || format!("/projects/{}/file-reservations", slug())}
                         class="btn-secondary flex items-center gap-2"
@@ -164,6 +256,14 @@ pub fn ProjectDetail() -> impl IntoView {
                                             value=new_name
                                             placeholder="BlueStone".to_string()
                                         />
+                                        {move || match agent_name_error(&new_name.get()) {
+                                            Some(err) => view! {
+                                                <p class="mt-1 text-xs text-red-600 dark:text-red-400" role="alert">{err.message}</p>
+                                            }.into_any(),
+                                            None => view! {
+                                                <p class="mt-1 text-xs text-charcoal-500 dark:text-charcoal-400">{AGENT_NAME_HINT}</p>
+                                            }.into_any(),
+                                        }}
                                     </div>
                                     <div>
                                         <label for="agentProgram" class="block text-sm font-medium text-charcoal-700 dark:text-charcoal-300 mb-2">
@@ -200,7 +300,10 @@ pub fn ProjectDetail() -> impl IntoView {
                                     <Button
                                         variant=ButtonVariant::Default
                                         button_type="submit"
-                                        disabled=creating.get() || new_name.get().trim().is_empty()
+                                        disabled=Signal::derive(move || {
+                                            let name = new_name.get();
+                                            creating.get() || name.trim().is_empty() || agent_name_error(&name).is_some()
+                                        })
                                     >
                                         {move || if creating.get() {
                                             view! { <i data-lucide="loader-2" class="icon-sm animate-spin"></i> }
@@ -230,6 +333,81 @@ pub fn ProjectDetail() -> impl IntoView {
                 }
             }}
 
+            // Edit Agent Form
+            {move || {
+                editing.get().map(|agent_name| view! {
+                    <div class="card-elevated p-6 animate-slide-up">
+                        <h2 class="font-display text-lg font-semibold text-charcoal-800 dark:text-cream-100 mb-4 flex items-center gap-2">
+                            <i data-lucide="pencil" class="icon-lg text-violet-500"></i>
+                            {format!("Edit {}", agent_name)}
+                        </h2>
+                        <form on:submit=move |ev| { ev.prevent_default(); save_agent(()); } class="space-y-4">
+                            <div class="grid grid-cols-1 md:grid-cols-2 gap-4">
+                                <div>
+                                    <label for="editAgentName" class="block text-sm font-medium text-charcoal-700 dark:text-charcoal-300 mb-2">
+                                        "Agent Name *"
+                                    </label>
+                                    <Input id="editAgentName".to_string() value=edit_name />
+                                    {move || match agent_name_error(&edit_name.get()) {
+                                        Some(err) => view! {
+                                            <p class="mt-1 text-xs text-red-600 dark:text-red-400" role="alert">{err.message}</p>
+                                        }.into_any(),
+                                        None => view! {
+                                            <p class="mt-1 text-xs text-charcoal-500 dark:text-charcoal-400">{AGENT_NAME_HINT}</p>
+                                        }.into_any(),
+                                    }}
+                                </div>
+                                <div>
+                                    <label for="editAgentProgram" class="block text-sm font-medium text-charcoal-700 dark:text-charcoal-300 mb-2">
+                                        "Program"
+                                    </label>
+                                    <Input id="editAgentProgram".to_string() value=edit_program />
+                                </div>
+                                <div>
+                                    <label for="editAgentModel" class="block text-sm font-medium text-charcoal-700 dark:text-charcoal-300 mb-2">
+                                        "Model"
+                                    </label>
+                                    <Input id="editAgentModel".to_string() value=edit_model />
+                                </div>
+                                <div>
+                                    <label for="editAgentTask" class="block text-sm font-medium text-charcoal-700 dark:text-charcoal-300 mb-2">
+                                        "Task Description"
+                                    </label>
+                                    <Input id="editAgentTask".to_string() value=edit_task />
+                                </div>
+                            </div>
+                            <div class="flex gap-3">
+                                <Button
+                                    variant=ButtonVariant::Default
+                                    button_type="submit"
+                                    disabled=Signal::derive(move || {
+                                        let name = edit_name.get();
+                                        saving.get() || name.trim().is_empty() || agent_name_error(&name).is_some()
+                                    })
+                                >
+                                    <i data-lucide="save" class="icon-sm"></i>
+                                    {move || if saving.get() { "Saving..." } else { "Save Changes" }}
+                                </Button>
+                                <Button
+                                    variant=ButtonVariant::Secondary
+                                    on_click=Callback::new(move |_| editing.set(None))
+                                >
+                                    "Cancel"
+                                </Button>
+                                <Button
+                                    variant=ButtonVariant::Destructive
+                                    disabled=saving.get()
+                                    on_click=Callback::new(move |_| retire_agent(()))
+                                >
+                                    <i data-lucide="archive" class="icon-sm"></i>
+                                    "Retire Agent"
+                                </Button>
+                            </div>
+                        </form>
+                    </div>
+                })
+            }}
+
             // Error Message
             {move || {
                 error.get().map(|e| view! {
@@ -284,7 +462,20 @@ pub fn ProjectDetail() -> impl IntoView {
                                     let model = agent.model.clone().unwrap_or_else(|| "unknown".to_string());
                                     let task = agent.task_description.clone();
                                     let last_active = agent.last_active_ts.clone().unwrap_or_default();
+                                    let dnd_title = agent.dnd.as_ref().map(dnd_title);
                                     let inbox_href = format!("/inbox?project={}&agent={}", project_slug, name);
+                                    let detail_href = format!("/projects/{}/agents/{}", project_slug, name);
+                                    let start_edit = {
+                                        let agent = agent.clone();
+                                        move |_| {
+                                            edit_name.set(agent.name.clone());
+                                            edit_program.set(agent.program.clone().unwrap_or_default());
+                                            edit_model.set(agent.model.clone().unwrap_or_default());
+                                            edit_task.set(agent.task_description.clone().unwrap_or_default());
+                                            show_new_form.set(false);
+                                            editing.set(Some(agent.name.clone()));
+                                        }
+                                    };
 
                                     view! {
                                         <div class="card-elevated p-6 group hover:border-violet-300 dark:hover:border-violet-700 transition-all">
@@ -294,10 +485,28 @@ pub fn ProjectDetail() -> impl IntoView {
                                                         <i data-lucide="bot" class="icon-lg text-violet-600 dark:text-violet-400"></i>
                                                     </div>
                                                     <div>
-                                                        <h3 class="font-display font-semibold text-charcoal-800 dark:text-cream-100">{name.clone()}</h3>
+                                                        <h3 class="font-display font-semibold text-charcoal-800 dark:text-cream-100 flex items-center gap-2">
+                                                            {name.clone()}
+                                                            {dnd_title.map(|title| view! {
+                                                                <span title=title>
+                                                                    <Badge variant=BadgeVariant::Warning>
+                                                                        <i data-lucide="bell-off" class="icon-xs mr-1"></i>
+                                                                        "DND"
+                                                                    </Badge>
+                                                                </span>
+                                                            })}
+                                                        </h3>
                                                         <p class="text-sm text-charcoal-500 dark:text-charcoal-400">{program}</p>
                                                     </div>
                                                 </div>
+                                                <button
+                                                    type="button"
+                                                    class="text-charcoal-400 hover:text-violet-600 dark:hover:text-violet-400 transition-colors"
+                                                    title="Edit agent"
+                                                    on:click=start_edit
+                                                >
+                                                    <i data-lucide="pencil" class="icon-sm"></i>
+                                                </button>
                                             </div>
 
                                             <div class="space-y-2 text-sm">
@@ -320,7 +529,7 @@ pub fn ProjectDetail() -> impl IntoView {
                                                 </div>
                                             </div>
 
-                                            <div class="mt-4 pt-4 border-t border-cream-200 dark:border-charcoal-700">
+                                            <div class="mt-4 pt-4 border-t border-cream-200 dark:border-charcoal-700 flex items-center justify-between">
                                                 <a
                                                     href=inbox_href
                                                     class="flex items-center gap-2 text-amber-600 dark:text-amber-400 hover:text-amber-700 dark:hover:text-amber-300 text-sm font-medium group/link"
@@ -329,6 +538,13 @@ pub fn ProjectDetail() -> impl IntoView {
                                                     "View Inbox"
                                                     <i data-lucide="arrow-right" class="icon-xs group-hover/link:translate-x-1 transition-transform"></i>
                                                 </a>
+                                                <a
+                                                    href=detail_href
+                                                    class="flex items-center gap-2 text-violet-600 dark:text-violet-400 hover:text-violet-700 dark:hover:text-violet-300 text-sm font-medium"
+                                                >
+                                                    <i data-lucide="activity" class="icon-sm"></i>
+                                                    "Details"
+                                                </a>
                                             </div>
                                         </div>
                                     }
@@ -348,3 +564,17 @@ fn format_date(date_str: &str) -> String {
     }
     date_str.split('T').next().unwrap_or(date_str).to_string()
 }
+
+/// Tooltip for the DND badge, e.g. "Do not disturb until 2025-01-31 09:15:00 (high and above)".
+fn dnd_title(policy: &DndPolicy) -> String {
+    let until = policy
+        .dnd_until
+        .as_deref()
+        .map(|ts| ts.replace('T', " "))
+        .unwrap_or_default();
+    let delivers = match policy.min_importance.as_deref() {
+        None | Some("urgent") => "urgent only".to_string(),
+        Some(level) => format!("{} and above", level),
+    };
+    format!("Do not disturb until {} ({})", until, delivers)
+}
@@ -1,9 +1,14 @@
 //! Projects page - list and create projects.
 //! Digital Correspondence design with Lucide icons.
 
-use crate::api::client::{self, Project};
-use crate::components::{Button, ButtonVariant, Input, ProjectCard, ProjectStatus};
+use crate::api::client::{self, Project, ProjectStats};
+use crate::components::{
+    Badge, Button, ButtonVariant, Input, NumberCounter, ProjectCard, ProjectStatus,
+    determine_project_status,
+};
+use crate::utils::seconds_since;
 use leptos::prelude::*;
+use std::collections::HashMap;
 
 /// Projects page component.
 #[component]
@@ -34,9 +39,39 @@ pub fn Projects() -> impl IntoView {
         });
     };
 
+    // Unread totals per project slug, for card badges
+    let unread_counts = RwSignal::new(HashMap::<String, i64>::new());
+
+    // Activity stats per project slug, for card numbers and status badges
+    let project_stats = RwSignal::new(HashMap::<String, ProjectStats>::new());
+    let load_stats = move || {
+        leptos::task::spawn_local(async move {
+            if let Ok(all) = client::get_all_project_stats().await {
+                project_stats.set(
+                    all.projects
+                        .into_iter()
+                        .map(|s| (s.project_slug.clone(), s))
+                        .collect(),
+                );
+            }
+        });
+    };
+
     // Initial load
     Effect::new(move |_| {
         load_projects();
+        load_stats();
+        leptos::task::spawn_local(async move {
+            if let Ok(counts) = client::get_all_unread_counts().await {
+                unread_counts.set(
+                    counts
+                        .projects
+                        .into_iter()
+                        .map(|p| (p.project_slug, p.total))
+                        .collect(),
+                );
+            }
+        });
     });
 
     // Create project handler
@@ -56,6 +91,7 @@ pub fn Projects() -> impl IntoView {
                     match client::get_projects().await {
                         Ok(p) => {
                             projects.set(p);
+                            load_stats();
                         }
                         Err(e) => {
                             error.set(Some(e.message));
@@ -200,14 +236,20 @@ pub fn Projects() -> impl IntoView {
                                         let slug = project.slug.clone();
                                         let human_key = project.human_key.clone().unwrap_or_default();
                                         let created = project.created_at.clone().unwrap_or_default();
+                                        let unread = unread_counts.with(|c| c.get(&slug).copied().unwrap_or(0));
+                                        let stats = stats_for(project_stats, &slug);
                                         view! {
                                             <ProjectCard
                                                 slug={slug}
                                                 human_key={human_key}
                                                 created_at={created}
-                                                status={ProjectStatus::Active}
-                                                agent_count=0
-                                                message_count=0
+                                                status={project_status(&stats)}
+                                                agent_count={stats.agent_count}
+                                                message_count={stats.message_count}
+                                                thread_count={stats.thread_count}
+                                                messages_last_24h={stats.messages_last_24h}
+                                                active_reservations={stats.active_reservations}
+                                                unread_count=unread
                                             />
                                         }
                                     }).collect::<Vec<_>>()}
@@ -224,6 +266,24 @@ pub fn Projects() -> impl IntoView {
                                                 <th class="px-6 py-3 text-left text-xs font-medium text-charcoal-500 dark:text-charcoal-400 uppercase tracking-wider">
                                                     "Path"
                                                 </th>
+                                                <th class="px-6 py-3 text-left text-xs font-medium text-charcoal-500 dark:text-charcoal-400 uppercase tracking-wider">
+                                                    "Status"
+                                                </th>
+                                                <th class="px-6 py-3 text-right text-xs font-medium text-charcoal-500 dark:text-charcoal-400 uppercase tracking-wider">
+                                                    "Agents"
+                                                </th>
+                                                <th class="px-6 py-3 text-right text-xs font-medium text-charcoal-500 dark:text-charcoal-400 uppercase tracking-wider">
+                                                    "Messages"
+                                                </th>
+                                                <th class="px-6 py-3 text-right text-xs font-medium text-charcoal-500 dark:text-charcoal-400 uppercase tracking-wider">
+                                                    "Last 24h"
+                                                </th>
+                                                <th class="px-6 py-3 text-right text-xs font-medium text-charcoal-500 dark:text-charcoal-400 uppercase tracking-wider">
+                                                    "Threads"
+                                                </th>
+                                                <th class="px-6 py-3 text-left text-xs font-medium text-charcoal-500 dark:text-charcoal-400 uppercase tracking-wider">
+                                                    "Last Activity"
+                                                </th>
                                                 <th class="px-6 py-3 text-left text-xs font-medium text-charcoal-500 dark:text-charcoal-400 uppercase tracking-wider">
                                                     "Created"
                                                 </th>
@@ -239,6 +299,9 @@ pub fn Projects() -> impl IntoView {
                                                 let href2 = href.clone();
                                                 let human_key = project.human_key.clone().unwrap_or_default();
                                                 let created = project.created_at.clone().unwrap_or_default();
+                                                let stats = stats_for(project_stats, &slug);
+                                                let status = project_status(&stats);
+                                                let last_activity = stats.last_activity_ts.clone().unwrap_or_default();
                                                 view! {
                                                     <tr class="hover:bg-cream-50 dark:hover:bg-charcoal-800/50 transition-colors group">
                                                         <td class="px-6 py-4">
@@ -255,6 +318,24 @@ pub fn Projects() -> impl IntoView {
                                                                 {human_key}
                                                             </span>
                                                         </td>
+                                                        <td class="px-6 py-4 whitespace-nowrap">
+                                                            <Badge variant={status.to_badge_variant()}>{status.label()}</Badge>
+                                                        </td>
+                                                        <td class="px-6 py-4 whitespace-nowrap text-right text-sm text-charcoal-700 dark:text-charcoal-300">
+                                                            <NumberCounter value={stats.agent_count} />
+                                                        </td>
+                                                        <td class="px-6 py-4 whitespace-nowrap text-right text-sm text-charcoal-700 dark:text-charcoal-300">
+                                                            <NumberCounter value={stats.message_count} />
+                                                        </td>
+                                                        <td class="px-6 py-4 whitespace-nowrap text-right text-sm text-charcoal-700 dark:text-charcoal-300">
+                                                            <NumberCounter value={stats.messages_last_24h} />
+                                                        </td>
+                                                        <td class="px-6 py-4 whitespace-nowrap text-right text-sm text-charcoal-700 dark:text-charcoal-300">
+                                                            <NumberCounter value={stats.thread_count} />
+                                                        </td>
+                                                        <td class="px-6 py-4 whitespace-nowrap text-sm text-charcoal-500 dark:text-charcoal-400 font-mono">
+                                                            {format_date(&last_activity)}
+                                                        </td>
                                                         <td class="px-6 py-4 whitespace-nowrap text-sm text-charcoal-500 dark:text-charcoal-400 font-mono">
                                                             {format_date(&created)}
                                                         </td>
@@ -282,6 +363,16 @@ pub fn Projects() -> impl IntoView {
     }
 }
 
+/// Stats for a project, or zeros while they load.
+fn stats_for(stats: RwSignal<HashMap<String, ProjectStats>>, slug: &str) -> ProjectStats {
+    stats.with(|s| s.get(slug).cloned().unwrap_or_default())
+}
+
+/// Status badge from the project's last activity.
+fn project_status(stats: &ProjectStats) -> ProjectStatus {
+    determine_project_status(stats.last_activity_ts.as_deref().and_then(seconds_since))
+}
+
 /// Format date string for display.
 fn format_date(date_str: &str) -> String {
     // Simple date formatting - just show the date part
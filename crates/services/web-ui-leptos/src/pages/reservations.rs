@@ -0,0 +1,581 @@
+//! Reservations page - which files are locked right now, and by whom.
+//!
+//! Shows a project's active reservations grouped by agent, with a live
+//! countdown to each one's expiry and a marker on reservations that overlap
+//! another agent's. Operators can force release a stuck reservation or hand
+//! it to another agent. The board refreshes every 15 seconds.
+
+use crate::api::client::{self, ActiveReservation, Agent, Project};
+use crate::api::project_version::project_changes;
+use crate::components::{
+    AgentAvatar, Alert, AlertDescription, AlertVariant, AvatarSize, Badge, BadgeVariant, Button,
+    ButtonSize, ButtonVariant, Dialog, DialogContent, DialogDescription, DialogFooter,
+    DialogHeader, DialogTitle, Input, Select, SelectIcon, SelectOption, Spinner, SpinnerSize,
+};
+use leptos::prelude::*;
+use leptos_router::hooks::use_query_map;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// How often the board is refetched, in seconds.
+const REFRESH_INTERVAL_SECS: i64 = 15;
+
+/// One agent's reservations on the board.
+#[derive(Debug, Clone, PartialEq)]
+struct AgentReservations {
+    agent_name: String,
+    /// Soonest expiry first
+    reservations: Vec<ActiveReservation>,
+    /// How many of them overlap another agent's
+    conflicts: usize,
+}
+
+/// Group reservations by holder, agents in name order. Each agent keeps the
+/// server's soonest-expiry-first order.
+fn group_by_agent(reservations: Vec<ActiveReservation>) -> Vec<AgentReservations> {
+    let mut groups: Vec<AgentReservations> = Vec::new();
+    for reservation in reservations {
+        let conflicting = !reservation.conflicts_with.is_empty();
+        let index = match groups
+            .iter()
+            .position(|g| g.agent_name == reservation.agent_name)
+        {
+            Some(i) => i,
+            None => {
+                groups.push(AgentReservations {
+                    agent_name: reservation.agent_name.clone(),
+                    reservations: Vec::new(),
+                    conflicts: 0,
+                });
+                groups.len() - 1
+            }
+        };
+        let group = &mut groups[index];
+        group.conflicts += usize::from(conflicting);
+        group.reservations.push(reservation);
+    }
+    groups.sort_by(|a, b| a.agent_name.cmp(&b.agent_name));
+    groups
+}
+
+/// Time left as a countdown: `45s`, `12m 05s`, `2h 05m` or `1d 3h`.
+fn format_countdown(seconds: i64) -> String {
+    match seconds {
+        s if s <= 0 => "expired".to_string(),
+        s if s < 60 => format!("{}s", s),
+        s if s < 3_600 => format!("{}m {:02}s", s / 60, s % 60),
+        s if s < 86_400 => format!("{}h {:02}m", s / 3_600, (s % 3_600) / 60),
+        s => format!("{}d {}h", s / 86_400, (s % 86_400) / 3_600),
+    }
+}
+
+/// How long a reservation has been held: `just now`, `5m`, `2h` or `3d`.
+fn format_age(seconds: i64) -> String {
+    match seconds {
+        s if s < 60 => "just now".to_string(),
+        s if s < 3_600 => format!("{}m", s / 60),
+        s if s < 86_400 => format!("{}h", s / 3_600),
+        s => format!("{}d", s / 86_400),
+    }
+}
+
+/// Reservations page component.
+#[component]
+pub fn Reservations() -> impl IntoView {
+    let query = use_query_map();
+
+    // State
+    let projects = RwSignal::new(Vec::<Project>::new());
+    let reservations = RwSignal::new(Vec::<ActiveReservation>::new());
+    let loading = RwSignal::new(true);
+    let loading_board = RwSignal::new(false);
+    let error = RwSignal::new(Option::<String>::None);
+    // Seconds since the board was fetched, for the countdowns
+    let elapsed = RwSignal::new(0_i64);
+    let confirm_release = RwSignal::new(Option::<ActiveReservation>::None);
+    let releasing = RwSignal::new(false);
+    let agents = RwSignal::new(Vec::<Agent>::new());
+    let confirm_transfer = RwSignal::new(Option::<ActiveReservation>::None);
+    let transfer_to = RwSignal::new(String::new());
+    let transfer_reason = RwSignal::new(String::new());
+    let transferring = RwSignal::new(false);
+
+    let selected_project =
+        RwSignal::new(query.with_untracked(|params| params.get("project").unwrap_or_default()));
+
+    let load_board = move || {
+        let project = selected_project.get_untracked();
+        if project.is_empty() {
+            return;
+        }
+
+        loading_board.set(true);
+        leptos::task::spawn_local(async move {
+            match client::get_project_reservations(&project).await {
+                Ok(board) => {
+                    // Ignore a slow response for a project no longer selected
+                    if board.project_slug == selected_project.get_untracked() {
+                        reservations.set(board.reservations);
+                        elapsed.set(0);
+                        error.set(None);
+                    }
+                }
+                Err(e) => error.set(Some(e.message)),
+            }
+            loading_board.set(false);
+        });
+    };
+
+    // Load projects once
+    Effect::new(move |_| {
+        leptos::task::spawn_local(async move {
+            match client::get_projects().await {
+                Ok(p) => projects.set(p),
+                Err(e) => error.set(Some(e.message)),
+            }
+            loading.set(false);
+        });
+    });
+
+    // Reload when another project is picked or this one is reshaped
+    Effect::new(move |_| {
+        project_changes(selected_project.get()).track();
+        reservations.set(Vec::new());
+        load_board();
+    });
+
+    // Transfer targets for the selected project
+    Effect::new(move |_| {
+        let project = selected_project.get();
+        agents.set(Vec::new());
+        if project.is_empty() {
+            return;
+        }
+        leptos::task::spawn_local(async move {
+            if let Ok(list) = client::get_agents(&project).await
+                && project == selected_project.get_untracked()
+            {
+                agents.set(list);
+            }
+        });
+    });
+
+    // Tick the countdowns every second and refetch every 15 until unmounted
+    let alive = Arc::new(AtomicBool::new(true));
+    {
+        let alive = alive.clone();
+        leptos::task::spawn_local(async move {
+            loop {
+                gloo_timers::future::TimeoutFuture::new(1_000).await;
+                if !alive.load(Ordering::Relaxed) {
+                    break;
+                }
+                elapsed.update(|e| *e += 1);
+                if elapsed.get_untracked() % REFRESH_INTERVAL_SECS == 0 {
+                    load_board();
+                }
+            }
+        });
+    }
+    on_cleanup(move || alive.store(false, Ordering::Relaxed));
+
+    let release = move |_| {
+        let Some(reservation) = confirm_release.get_untracked() else {
+            return;
+        };
+        let project = selected_project.get_untracked();
+        releasing.set(true);
+        leptos::task::spawn_local(async move {
+            match client::force_release_reservation(&project, reservation.id).await {
+                Ok(()) => {
+                    reservations.update(|list| list.retain(|r| r.id != reservation.id));
+                    load_board();
+                }
+                Err(e) => error.set(Some(e.message)),
+            }
+            releasing.set(false);
+            confirm_release.set(None);
+        });
+    };
+
+    // Each transfer starts with a blank form
+    Effect::new(move |_| {
+        if confirm_transfer.with(Option::is_some) {
+            transfer_to.set(String::new());
+            transfer_reason.set(String::new());
+        }
+    });
+
+    let transfer = move |_| {
+        let Some(reservation) = confirm_transfer.get_untracked() else {
+            return;
+        };
+        let to_agent = transfer_to.get_untracked();
+        if to_agent.is_empty() {
+            return;
+        }
+        let reason = transfer_reason.get_untracked();
+        let project = selected_project.get_untracked();
+        transferring.set(true);
+        leptos::task::spawn_local(async move {
+            match client::transfer_reservation(&project, reservation.id, &to_agent, &reason).await {
+                Ok(()) => load_board(),
+                Err(e) => error.set(Some(e.message)),
+            }
+            transferring.set(false);
+            confirm_transfer.set(None);
+        });
+    };
+
+    view! {
+        <div class="space-y-6">
+            // Header
+            <div class="flex items-start justify-between gap-4">
+                <div>
+                    <h1 class="font-display text-2xl font-bold text-charcoal-800 dark:text-cream-100 flex items-center gap-2">
+                        <i data-lucide="lock" class="icon-xl text-amber-500"></i>
+                        "Reservations"
+                    </h1>
+                    <p class="text-charcoal-500 dark:text-charcoal-400">
+                        "Files agents have reserved right now, grouped by agent"
+                    </p>
+                </div>
+                <Button
+                    variant=ButtonVariant::Secondary
+                    disabled=Signal::derive(move || loading_board.get() || selected_project.get().is_empty())
+                    on_click=Callback::new(move |_| load_board())
+                >
+                    {move || if loading_board.get() {
+                        view! { <i data-lucide="loader-2" class="icon-sm animate-spin"></i> }.into_any()
+                    } else {
+                        view! { <i data-lucide="refresh-cw" class="icon-sm"></i> }.into_any()
+                    }}
+                    "Refresh"
+                </Button>
+            </div>
+
+            // Project picker
+            <div class="card-elevated p-5">
+                <label class="flex items-center gap-2 text-sm font-medium text-charcoal-700 dark:text-charcoal-300 mb-2">
+                    <i data-lucide="folder" class="icon-sm text-charcoal-400"></i>
+                    "Project"
+                </label>
+                {move || {
+                    let options: Vec<SelectOption> = projects.get()
+                        .into_iter()
+                        .map(|p| SelectOption::new(p.slug.clone(), p.slug.clone()))
+                        .collect();
+                    view! {
+                        <Select
+                            id="reservationsProjectSelect".to_string()
+                            options=options
+                            value=selected_project
+                            placeholder="Select a project...".to_string()
+                            disabled=false
+                            icon=SelectIcon::Folder
+                        />
+                    }
+                }}
+            </div>
+
+            // Error Message
+            {move || {
+                error.get().map(|e| view! {
+                    <Alert variant=AlertVariant::Destructive class="animate-slide-up">
+                        <i data-lucide="triangle-alert" class="h-4 w-4"></i>
+                        <AlertDescription>{e}</AlertDescription>
+                    </Alert>
+                })
+            }}
+
+            // Board
+            {move || {
+                let board = reservations.get();
+                let project = selected_project.get();
+                if loading.get() || (loading_board.get() && board.is_empty()) {
+                    view! {
+                        <div class="flex items-center justify-center py-16">
+                            <Spinner size=SpinnerSize::Lg class="text-primary" />
+                        </div>
+                    }.into_any()
+                } else if project.is_empty() {
+                    view! {
+                        <div class="card-elevated p-12 text-center">
+                            <h3 class="font-display text-xl font-semibold text-charcoal-800 dark:text-cream-100 mb-2">"Select a Project"</h3>
+                            <p class="text-charcoal-500 dark:text-charcoal-400 max-w-sm mx-auto">
+                                "Choose a project to see its reservations."
+                            </p>
+                        </div>
+                    }.into_any()
+                } else if board.is_empty() {
+                    view! {
+                        <div class="card-elevated p-12 text-center">
+                            <i data-lucide="file-check" class="icon-xl mx-auto mb-3 text-charcoal-400 opacity-50"></i>
+                            <h3 class="font-display text-xl font-semibold text-charcoal-800 dark:text-cream-100 mb-2">"Nothing reserved"</h3>
+                            <p class="text-charcoal-500 dark:text-charcoal-400">
+                                <span class="font-medium text-charcoal-700 dark:text-cream-200">{project}</span>
+                                " has no active reservations."
+                            </p>
+                        </div>
+                    }.into_any()
+                } else {
+                    view! {
+                        <div class="space-y-4">
+                            {group_by_agent(board).into_iter().map(|group| view! {
+                                <AgentGroup
+                                    group=group
+                                    elapsed=elapsed
+                                    confirm_release=confirm_release
+                                    confirm_transfer=confirm_transfer
+                                />
+                            }).collect::<Vec<_>>()}
+                        </div>
+                    }.into_any()
+                }
+            }}
+
+            // Force release confirmation
+            {move || confirm_release.get().map(|reservation| {
+                let description = StoredValue::new(format!(
+                    "{} will lose its {} reservation on {}. The release is recorded in the audit log.",
+                    reservation.agent_name,
+                    if reservation.exclusive { "exclusive" } else { "shared" },
+                    reservation.path_pattern
+                ));
+                view! {
+                <Dialog open=true on_open_change=Callback::new(move |open: bool| if !open { confirm_release.set(None) })>
+                    <DialogContent>
+                        <DialogHeader>
+                            <DialogTitle>"Force release this reservation?"</DialogTitle>
+                            <DialogDescription>
+                                {description.get_value()}
+                            </DialogDescription>
+                        </DialogHeader>
+                        <DialogFooter class="gap-2">
+                            <Button
+                                variant=ButtonVariant::Secondary
+                                on_click=Callback::new(move |_| confirm_release.set(None))
+                            >
+                                "Cancel"
+                            </Button>
+                            <Button
+                                variant=ButtonVariant::Destructive
+                                disabled=Signal::derive(move || releasing.get())
+                                on_click=Callback::new(release)
+                            >
+                                <i data-lucide="unlock" class="icon-sm"></i>
+                                {move || if releasing.get() { "Releasing..." } else { "Force Release" }}
+                            </Button>
+                        </DialogFooter>
+                    </DialogContent>
+                </Dialog>
+                }
+            })}
+
+            // Transfer dialog
+            {move || confirm_transfer.get().map(|reservation| {
+                let description = StoredValue::new(format!(
+                    "Hand {}'s {} reservation on {} to another agent. Both agents are notified and the transfer is recorded in the audit log.",
+                    reservation.agent_name,
+                    if reservation.exclusive { "exclusive" } else { "shared" },
+                    reservation.path_pattern
+                ));
+                let options = StoredValue::new(
+                    agents
+                        .get()
+                        .into_iter()
+                        .filter(|a| a.name != reservation.agent_name)
+                        .map(|a| SelectOption::new(a.name.clone(), a.name))
+                        .collect::<Vec<_>>(),
+                );
+                view! {
+                <Dialog open=true on_open_change=Callback::new(move |open: bool| if !open { confirm_transfer.set(None) })>
+                    <DialogContent>
+                        <DialogHeader>
+                            <DialogTitle>"Transfer this reservation?"</DialogTitle>
+                            <DialogDescription>
+                                {description.get_value()}
+                            </DialogDescription>
+                        </DialogHeader>
+                        <div class="space-y-3">
+                            <Select
+                                id="transferAgentSelect".to_string()
+                                options=options.get_value()
+                                value=transfer_to
+                                placeholder="Select an agent...".to_string()
+                                icon=SelectIcon::Bot
+                            />
+                            <Input
+                                id="transferReason".to_string()
+                                value=transfer_reason
+                                placeholder="Reason, e.g. holder crashed".to_string()
+                            />
+                        </div>
+                        <DialogFooter class="gap-2">
+                            <Button
+                                variant=ButtonVariant::Secondary
+                                on_click=Callback::new(move |_| confirm_transfer.set(None))
+                            >
+                                "Cancel"
+                            </Button>
+                            <Button
+                                disabled=Signal::derive(move || transferring.get() || transfer_to.get().is_empty())
+                                on_click=Callback::new(transfer)
+                            >
+                                <i data-lucide="arrow-right-left" class="icon-sm"></i>
+                                {move || if transferring.get() { "Transferring..." } else { "Transfer" }}
+                            </Button>
+                        </DialogFooter>
+                    </DialogContent>
+                </Dialog>
+                }
+            })}
+        </div>
+    }
+}
+
+/// One agent's card on the board.
+#[component]
+fn AgentGroup(
+    group: AgentReservations,
+    elapsed: RwSignal<i64>,
+    confirm_release: RwSignal<Option<ActiveReservation>>,
+    confirm_transfer: RwSignal<Option<ActiveReservation>>,
+) -> impl IntoView {
+    let count = group.reservations.len();
+    let summary = format!("{} reservation{}", count, if count == 1 { "" } else { "s" });
+
+    view! {
+        <div class="card-elevated overflow-hidden">
+            <div class="flex items-center justify-between gap-3 px-5 py-3 bg-cream-50 dark:bg-charcoal-800 border-b border-cream-200 dark:border-charcoal-700">
+                <div class="flex items-center gap-2">
+                    <AgentAvatar name={group.agent_name.clone()} size=AvatarSize::Sm />
+                    <span class="font-medium text-charcoal-800 dark:text-cream-100">{group.agent_name.clone()}</span>
+                    <span class="text-sm text-charcoal-500 dark:text-charcoal-400">{summary}</span>
+                </div>
+                {(group.conflicts > 0).then(|| view! {
+                    <Badge variant=BadgeVariant::Destructive>
+                        {format!("{} overlapping", group.conflicts)}
+                    </Badge>
+                })}
+            </div>
+            <ul class="divide-y divide-cream-200 dark:divide-charcoal-700">
+                {group.reservations.into_iter().map(|reservation| {
+                    let expires_in = reservation.expires_in_seconds;
+                    let overlaps = (!reservation.conflicts_with.is_empty()).then(|| {
+                        let ids: Vec<String> = reservation.conflicts_with.iter().map(|id| format!("#{}", id)).collect();
+                        format!("Overlaps {}", ids.join(", "))
+                    });
+                    let for_dialog = reservation.clone();
+                    let for_transfer = reservation.clone();
+                    view! {
+                        <li class="flex flex-wrap items-center gap-3 px-5 py-3">
+                            <span class="text-xs font-mono text-charcoal-400">{format!("#{}", reservation.id)}</span>
+                            <code class="text-sm bg-cream-100 dark:bg-charcoal-800 px-2 py-1 rounded font-mono">
+                                {reservation.path_pattern.clone()}
+                            </code>
+                            {if reservation.exclusive {
+                                view! { <Badge variant=BadgeVariant::Secondary>"Exclusive"</Badge> }.into_any()
+                            } else {
+                                view! { <Badge variant=BadgeVariant::Outline>"Shared"</Badge> }.into_any()
+                            }}
+                            {overlaps.map(|text| view! {
+                                <span class="inline-flex items-center gap-1 text-xs text-rose-600 dark:text-rose-400">
+                                    <i data-lucide="triangle-alert" class="icon-xs"></i>
+                                    {text}
+                                </span>
+                            })}
+                            <span class="flex-1 text-sm text-charcoal-500 dark:text-charcoal-400 truncate">
+                                {reservation.reason.clone()}
+                            </span>
+                            <span class="text-xs text-charcoal-400" title={reservation.created_ts.clone()}>
+                                {format!("held {}", format_age(reservation.age_seconds))}
+                            </span>
+                            <span class="text-sm font-mono text-charcoal-700 dark:text-cream-200 whitespace-nowrap" title={reservation.expires_ts.clone()}>
+                                <i data-lucide="timer" class="icon-xs mr-1"></i>
+                                {move || format_countdown(expires_in - elapsed.get())}
+                            </span>
+                            <Button
+                                variant=ButtonVariant::Secondary
+                                size=ButtonSize::Sm
+                                title="Transfer to another agent"
+                                on_click=Callback::new(move |_| confirm_transfer.set(Some(for_transfer.clone())))
+                            >
+                                <i data-lucide="arrow-right-left" class="icon-xs"></i>
+                                "Transfer"
+                            </Button>
+                            <Button
+                                variant=ButtonVariant::Destructive
+                                size=ButtonSize::Sm
+                                title="Force release"
+                                on_click=Callback::new(move |_| confirm_release.set(Some(for_dialog.clone())))
+                            >
+                                <i data-lucide="unlock" class="icon-xs"></i>
+                                "Release"
+                            </Button>
+                        </li>
+                    }
+                }).collect::<Vec<_>>()}
+            </ul>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reservation(id: i64, agent: &str, conflicts_with: Vec<i64>) -> ActiveReservation {
+        ActiveReservation {
+            id,
+            agent_name: agent.to_string(),
+            path_pattern: format!("src/{}.rs", id),
+            exclusive: true,
+            reason: String::new(),
+            created_ts: "2026-01-01T10:00:00".to_string(),
+            expires_ts: "2026-01-01T11:00:00".to_string(),
+            age_seconds: 60,
+            expires_in_seconds: 3_540,
+            conflicts_with,
+        }
+    }
+
+    #[test]
+    fn test_group_by_agent() {
+        let groups = group_by_agent(vec![
+            reservation(1, "GreenCastle", vec![]),
+            reservation(2, "BlueLake", vec![3]),
+            reservation(3, "GreenCastle", vec![2]),
+            reservation(4, "BlueLake", vec![]),
+        ]);
+
+        let names: Vec<&str> = groups.iter().map(|g| g.agent_name.as_str()).collect();
+        assert_eq!(names, vec!["BlueLake", "GreenCastle"]);
+        let ids = |g: &AgentReservations| g.reservations.iter().map(|r| r.id).collect::<Vec<_>>();
+        // Server order (soonest expiry first) is kept within a group
+        assert_eq!(ids(&groups[0]), vec![2, 4]);
+        assert_eq!(ids(&groups[1]), vec![1, 3]);
+        assert_eq!(groups[0].conflicts, 1);
+        assert_eq!(groups[1].conflicts, 1);
+        assert!(group_by_agent(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn test_format_countdown() {
+        assert_eq!(format_countdown(-5), "expired");
+        assert_eq!(format_countdown(0), "expired");
+        assert_eq!(format_countdown(45), "45s");
+        assert_eq!(format_countdown(725), "12m 05s");
+        assert_eq!(format_countdown(7_500), "2h 05m");
+        assert_eq!(format_countdown(97_200), "1d 3h");
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(0), "just now");
+        assert_eq!(format_age(59), "just now");
+        assert_eq!(format_age(300), "5m");
+        assert_eq!(format_age(7_200), "2h");
+        assert_eq!(format_age(3 * 86_400), "3d");
+    }
+}
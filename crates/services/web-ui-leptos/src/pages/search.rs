@@ -1,10 +1,12 @@
 //! Search results page with FTS5 highlighting.
 //!
 //! Displays search results with query term highlighting,
-//! filter chips, and debounced search-as-you-type.
+//! filter chips, and debounced search-as-you-type. The query is sent as
+//! typed; the server understands field syntax such as `from:` and `after:`.
 
-use crate::api::client::{self, Message, Project};
+use crate::api::client::{self, Project, SearchHit};
 use crate::components::{Badge, BadgeVariant, Card, CardContent, Input, Pagination, Skeleton};
+use crate::utils::render_markdown_highlighted;
 use leptos::prelude::*;
 use leptos_router::hooks::use_query_map;
 
@@ -25,7 +27,9 @@ pub fn Search() -> impl IntoView {
     let search_query = RwSignal::new(initial_query);
     let selected_project = RwSignal::new(initial_project);
     let projects = RwSignal::new(Vec::<Project>::new());
-    let results = RwSignal::new(Vec::<Message>::new());
+    let results = RwSignal::new(Vec::<SearchHit>::new());
+    // Project the current results came from, for result links
+    let results_project = RwSignal::new(String::new());
     let loading = RwSignal::new(false);
     let error = RwSignal::new(Option::<String>::None);
     let has_searched = RwSignal::new(false);
@@ -33,6 +37,10 @@ pub fn Search() -> impl IntoView {
     // Pagination state
     let has_more = RwSignal::new(false);
     let total_count = RwSignal::new(0i64);
+    let next_cursor = RwSignal::new(Option::<i64>::None);
+    let loading_more = RwSignal::new(false);
+
+    let show_syntax = RwSignal::new(false);
 
     // Load projects for filter
     Effect::new(move |_| {
@@ -90,11 +98,13 @@ pub fn Search() -> impl IntoView {
                 project
             };
 
-            match client::search_messages(&search_project, &query).await {
-                Ok(msgs) => {
-                    total_count.set(msgs.len() as i64);
-                    has_more.set(false); // API doesn't support pagination yet
-                    results.set(msgs);
+            match client::search_messages(&search_project, &query, None).await {
+                Ok(page) => {
+                    total_count.set(page.total_estimate);
+                    has_more.set(page.next_cursor.is_some());
+                    next_cursor.set(page.next_cursor);
+                    results_project.set(search_project);
+                    results.set(page.items);
                     loading.set(false);
                 }
                 Err(e) => {
@@ -105,8 +115,29 @@ pub fn Search() -> impl IntoView {
         });
     });
 
+    // Append the next page of the current search
+    let load_more = Callback::new(move |_| {
+        let Some(cursor) = next_cursor.get_untracked() else {
+            return;
+        };
+        let query = search_query.get_untracked();
+        let project = results_project.get_untracked();
+        loading_more.set(true);
+        leptos::task::spawn_local(async move {
+            match client::search_messages(&project, &query, Some(cursor)).await {
+                Ok(page) => {
+                    has_more.set(page.next_cursor.is_some());
+                    next_cursor.set(page.next_cursor);
+                    results.update(|r| r.extend(page.items));
+                }
+                Err(e) => error.set(Some(e.message)),
+            }
+            loading_more.set(false);
+        });
+    });
+
     // Result count for screen readers
-    let result_count = Signal::derive(move || results.get().len());
+    let result_count = Signal::derive(move || total_count.get());
 
     view! {
         <div class="space-y-6">
@@ -125,8 +156,19 @@ pub fn Search() -> impl IntoView {
                             id="search-input".to_string()
                             value=search_input
                             placeholder="Search messages...".to_string()
-                            class="pl-10".to_string()
+                            class="pl-10 pr-10".to_string()
                         />
+                        <button
+                            type="button"
+                            class="absolute right-2 top-1/2 -translate-y-1/2 p-1 rounded text-muted-foreground hover:text-foreground"
+                            on:click=move |_| show_syntax.update(|s| *s = !*s)
+                            aria-label="Search syntax"
+                            aria-expanded=move || show_syntax.get().to_string()
+                            aria-controls="search-syntax"
+                        >
+                            <i data-lucide="info" class="icon-sm"></i>
+                        </button>
+                        {move || show_syntax.get().then(|| view! { <SearchSyntaxHint /> })}
                     </div>
 
                     // Project filter
@@ -239,7 +281,8 @@ pub fn Search() -> impl IntoView {
             // Results list
             {move || {
                 let msgs = results.get();
-                let query = search_query.get();
+                let highlight = highlight_text(&search_query.get());
+                let project = results_project.get();
                 let searched = has_searched.get();
                 let is_loading = loading.get();
 
@@ -272,15 +315,24 @@ pub fn Search() -> impl IntoView {
                     Some(view! {
                         <div class="space-y-4">
                             // Result count
-                            <p class="text-sm text-muted-foreground">
-                                {format!("{} result{}", msgs.len(), if msgs.len() == 1 { "" } else { "s" })}
-                            </p>
+                            {move || {
+                                let total = total_count.get();
+                                view! {
+                                    <p class="text-sm text-muted-foreground">
+                                        {format!("{} result{}", total, if total == 1 { "" } else { "s" })}
+                                    </p>
+                                }
+                            }}
 
                             // Results
                             <div class="space-y-3" role="list" aria-label="Search results">
                                 {msgs.into_iter().map(|msg| {
                                     view! {
-                                        <SearchResultItem message=msg query=query.clone() />
+                                        <SearchResultItem
+                                            message=msg
+                                            project=project.clone()
+                                            highlight=highlight.clone()
+                                        />
                                     }
                                 }).collect::<Vec<_>>()}
                             </div>
@@ -290,10 +342,8 @@ pub fn Search() -> impl IntoView {
                                 has_more=Signal::derive(move || has_more.get())
                                 total=Signal::derive(move || total_count.get())
                                 current_count=Signal::derive(move || results.get().len())
-                                loading=Signal::derive(move || loading.get())
-                                on_load_more=Callback::new(|_| {
-                                    // TODO: Implement cursor-based pagination
-                                })
+                                loading=Signal::derive(move || loading_more.get())
+                                on_load_more=load_more
                             />
                         </div>
                     }.into_any())
@@ -311,21 +361,70 @@ pub fn Search() -> impl IntoView {
     }
 }
 
+/// Popover documenting the field syntax understood by the search endpoint.
+#[component]
+fn SearchSyntaxHint() -> impl IntoView {
+    const FIELDS: [(&str, &str); 6] = [
+        ("from:BlueStone", "Sent by an agent"),
+        ("subject:\"release plan\"", "Subject contains the text"),
+        ("after:2025-01-01", "Sent on or after a date"),
+        ("before:2025-02-01T12:00", "Sent before a date or time"),
+        ("importance:urgent", "low, normal, high or urgent"),
+        ("thread:T-42", "In a thread"),
+    ];
+
+    view! {
+        <div
+            id="search-syntax"
+            role="note"
+            class="absolute right-0 top-full mt-2 z-20 w-80 rounded-lg border border-border bg-popover p-4 text-sm shadow-lg"
+        >
+            <p class="font-medium text-foreground mb-2">"Search syntax"</p>
+            <p class="text-muted-foreground mb-3">
+                "Words search message bodies; \"quoted phrases\" match exactly. Combine with fields:"
+            </p>
+            <dl class="grid grid-cols-[auto_1fr] gap-x-3 gap-y-1">
+                {FIELDS.into_iter().map(|(example, meaning)| view! {
+                    <dt><code class="text-xs font-mono text-foreground">{example}</code></dt>
+                    <dd class="text-muted-foreground">{meaning}</dd>
+                }).collect::<Vec<_>>()}
+            </dl>
+            <p class="text-xs text-muted-foreground mt-3">
+                "Unknown fields are searched as plain words."
+            </p>
+        </div>
+    }
+}
+
+/// Free-text part of a search query, for highlighting: `field:value` tokens
+/// are dropped and phrase quotes removed.
+fn highlight_text(query: &str) -> String {
+    query
+        .split_whitespace()
+        .filter(|word| !word.contains(':'))
+        .map(|word| word.trim_matches('"'))
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 /// Individual search result item with highlighting.
 #[component]
-fn SearchResultItem(message: Message, query: String) -> impl IntoView {
+fn SearchResultItem(message: SearchHit, project: String, highlight: String) -> impl IntoView {
+    let query = highlight;
     let subject = message.subject.clone();
     let sender = message.sender_name.clone();
-    let body = message.body_md.clone();
+    let body = message.snippet.clone();
     let created = message.created_ts.clone();
     let message_id = message.id;
 
-    // Create highlighted snippet
+    // Create highlighted snippet (rendered and sanitized before highlighting)
     let snippet = create_highlighted_snippet(&body, &query, 200);
+    let snippet_html = render_markdown_highlighted(&snippet, &query);
 
     view! {
         <a
-            href=format!("/inbox/{}?project={}", message_id, message.project_id)
+            href=format!("/inbox/{}?project={}", message_id, project)
             class="block"
         >
             <Card>
@@ -347,9 +446,10 @@ fn SearchResultItem(message: Message, query: String) -> impl IntoView {
                         </div>
 
                         // Body snippet with highlights
-                        <p class="text-sm text-muted-foreground line-clamp-2">
-                            {snippet}
-                        </p>
+                        <div
+                            class="markdown-body text-sm text-muted-foreground line-clamp-2 break-words"
+                            inner_html=snippet_html
+                        ></div>
                     </div>
                 </CardContent>
             </Card>
@@ -471,6 +571,15 @@ mod tests {
         assert_eq!(snippet, "This is a ");
     }
 
+    #[test]
+    fn test_highlight_text_drops_fields() {
+        assert_eq!(
+            highlight_text(r#"from:BlueStone subject:deploy "release plan" urgent"#),
+            "release plan urgent"
+        );
+        assert_eq!(highlight_text("after:2025-01-01"), "");
+    }
+
     #[test]
     fn test_highlight_uses_mark_element() {
         // Verify we use <mark> for accessibility
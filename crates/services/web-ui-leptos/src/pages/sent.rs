@@ -0,0 +1,280 @@
+//! Sent page - messages an agent has sent, with per-recipient read/ack status.
+
+use crate::api::client::{self, Agent, OutboxMessage, OutboxRecipient, Project};
+use crate::components::{
+    Alert, AlertDescription, AlertVariant, Badge, BadgeVariant, Button, ButtonVariant, Select,
+    SelectIcon, SelectOption, Spinner, SpinnerSize,
+};
+use leptos::prelude::*;
+use leptos_router::hooks::use_query_map;
+
+/// Page size requested from the outbox endpoint.
+const PAGE_SIZE: usize = 50;
+
+/// Sent page component.
+#[component]
+pub fn Sent() -> impl IntoView {
+    let query = use_query_map();
+
+    // State
+    let projects = RwSignal::new(Vec::<Project>::new());
+    let agents = RwSignal::new(Vec::<Agent>::new());
+    let messages = RwSignal::new(Vec::<OutboxMessage>::new());
+    let has_more = RwSignal::new(false);
+    let loading = RwSignal::new(true);
+    let loading_messages = RwSignal::new(false);
+    let error = RwSignal::new(Option::<String>::None);
+
+    // Selections, initialized from URL params
+    let selected_project =
+        RwSignal::new(query.with_untracked(|params| params.get("project").unwrap_or_default()));
+    let selected_agent =
+        RwSignal::new(query.with_untracked(|params| params.get("agent").unwrap_or_default()));
+
+    // Fetch a page; `cursor` None replaces the list, Some appends to it
+    let load_page = move |cursor: Option<i64>| {
+        let project = selected_project.get_untracked();
+        let agent = selected_agent.get_untracked();
+        if project.is_empty() || agent.is_empty() {
+            return;
+        }
+
+        loading_messages.set(true);
+        error.set(None);
+        leptos::task::spawn_local(async move {
+            match client::get_outbox(&project, &agent, cursor).await {
+                Ok(page) => {
+                    has_more.set(page.len() == PAGE_SIZE);
+                    if cursor.is_some() {
+                        messages.update(|m| m.extend(page));
+                    } else {
+                        messages.set(page);
+                    }
+                }
+                Err(e) => error.set(Some(e.message)),
+            }
+            loading_messages.set(false);
+        });
+    };
+
+    // Load projects once
+    Effect::new(move |_| {
+        leptos::task::spawn_local(async move {
+            match client::get_projects().await {
+                Ok(p) => projects.set(p),
+                Err(e) => error.set(Some(e.message)),
+            }
+            loading.set(false);
+        });
+    });
+
+    // Reload agents when the project changes
+    Effect::new(move |prev: Option<String>| {
+        let project = selected_project.get();
+        if prev.as_ref().is_some_and(|p| *p != project) {
+            selected_agent.set(String::new());
+        }
+        if project.is_empty() {
+            agents.set(Vec::new());
+        } else {
+            let slug = project.clone();
+            leptos::task::spawn_local(async move {
+                match client::get_agents(&slug).await {
+                    Ok(a) => agents.set(a),
+                    Err(e) => error.set(Some(e.message)),
+                }
+            });
+        }
+        project
+    });
+
+    // Reload sent messages when the agent changes
+    Effect::new(move |_| {
+        if selected_agent.get().is_empty() {
+            messages.set(Vec::new());
+            has_more.set(false);
+        } else {
+            load_page(None);
+        }
+    });
+
+    let load_more = move || {
+        let cursor = messages.with_untracked(|m| m.last().map(|msg| msg.id));
+        load_page(cursor);
+    };
+
+    view! {
+        <div class="space-y-6">
+            // Header
+            <div>
+                <h1 class="font-display text-2xl font-bold text-charcoal-800 dark:text-cream-100 flex items-center gap-2">
+                    <i data-lucide="send" class="icon-xl text-amber-500"></i>
+                    "Sent"
+                </h1>
+                <p class="text-charcoal-500 dark:text-charcoal-400">"Messages your agents have sent, with delivery status"</p>
+            </div>
+
+            // Filters Card
+            <div class="card-elevated p-5">
+                <div class="flex flex-col md:flex-row gap-4">
+                    <div class="flex-1">
+                        <label class="flex items-center gap-2 text-sm font-medium text-charcoal-700 dark:text-charcoal-300 mb-2">
+                            <i data-lucide="folder" class="icon-sm text-charcoal-400"></i>
+                            "Project"
+                        </label>
+                        {move || {
+                            let options: Vec<SelectOption> = projects.get()
+                                .into_iter()
+                                .map(|p| SelectOption::new(p.slug.clone(), p.slug.clone()))
+                                .collect();
+                            view! {
+                                <Select
+                                    id="sentProjectSelect".to_string()
+                                    options=options
+                                    value=selected_project
+                                    placeholder="Select a project...".to_string()
+                                    disabled=false
+                                    icon=SelectIcon::Folder
+                                />
+                            }
+                        }}
+                    </div>
+
+                    <div class="flex-1">
+                        <label class="flex items-center gap-2 text-sm font-medium text-charcoal-700 dark:text-charcoal-300 mb-2">
+                            <i data-lucide="bot" class="icon-sm text-charcoal-400"></i>
+                            "Agent"
+                        </label>
+                        {move || {
+                            let options: Vec<SelectOption> = agents.get()
+                                .into_iter()
+                                .map(|a| SelectOption::new(a.name.clone(), a.name.clone()))
+                                .collect();
+                            let is_disabled = selected_project.get().is_empty() || options.is_empty();
+                            view! {
+                                <Select
+                                    id="sentAgentSelect".to_string()
+                                    options=options
+                                    value=selected_agent
+                                    placeholder="Select an agent...".to_string()
+                                    disabled=is_disabled
+                                    icon=SelectIcon::Bot
+                                />
+                            }
+                        }}
+                    </div>
+                </div>
+            </div>
+
+            // Error Message
+            {move || {
+                error.get().map(|e| view! {
+                    <Alert variant=AlertVariant::Destructive class="animate-slide-up">
+                        <i data-lucide="triangle-alert" class="h-4 w-4"></i>
+                        <AlertDescription>{e}</AlertDescription>
+                    </Alert>
+                })
+            }}
+
+            // Content
+            {move || {
+                let msg_list = messages.get();
+                if loading.get() || (loading_messages.get() && msg_list.is_empty()) {
+                    view! {
+                        <div class="flex items-center justify-center py-16">
+                            <Spinner size=SpinnerSize::Lg class="text-primary" />
+                        </div>
+                    }.into_any()
+                } else if selected_project.get().is_empty() || selected_agent.get().is_empty() {
+                    view! {
+                        <div class="card-elevated p-12 text-center">
+                            <h3 class="font-display text-xl font-semibold text-charcoal-800 dark:text-cream-100 mb-2">"Select an Agent"</h3>
+                            <p class="text-charcoal-500 dark:text-charcoal-400 max-w-sm mx-auto">
+                                "Choose a project and agent to see what they have sent."
+                            </p>
+                        </div>
+                    }.into_any()
+                } else if msg_list.is_empty() {
+                    view! {
+                        <div class="card-elevated p-12 text-center">
+                            <h3 class="font-display text-xl font-semibold text-charcoal-800 dark:text-cream-100 mb-2">"Nothing sent yet"</h3>
+                            <p class="text-charcoal-500 dark:text-charcoal-400">
+                                <span class="font-medium text-charcoal-700 dark:text-cream-200">{selected_agent.get()}</span>
+                                " hasn't sent any messages."
+                            </p>
+                        </div>
+                    }.into_any()
+                } else {
+                    view! {
+                        <div class="card-elevated overflow-hidden">
+                            <ul class="divide-y divide-cream-200 dark:divide-charcoal-700">
+                                {msg_list.into_iter().map(|msg| {
+                                    let thread_href = msg.thread_id.clone().map(|t| format!("/thread/{}", t));
+                                    view! {
+                                        <li class="px-6 py-4">
+                                            <div class="flex items-baseline justify-between gap-4 mb-2">
+                                                <h4 class="font-medium text-charcoal-800 dark:text-cream-100 truncate">
+                                                    {match thread_href {
+                                                        Some(href) => view! { <a href=href class="hover:text-amber-600">{msg.subject.clone()}</a> }.into_any(),
+                                                        None => view! { <span>{msg.subject.clone()}</span> }.into_any(),
+                                                    }}
+                                                </h4>
+                                                <span class="flex-shrink-0 text-xs font-mono text-charcoal-400 dark:text-charcoal-500">
+                                                    {format_date(&msg.created_ts)}
+                                                </span>
+                                            </div>
+                                            <div class="flex flex-wrap gap-2">
+                                                {msg.recipients.iter().map(|r| {
+                                                    let label = format!("{}: {} · {}", r.recipient_type, r.name, delivery_status(r));
+                                                    view! { <Badge variant=status_variant(r)>{label}</Badge> }
+                                                }).collect::<Vec<_>>()}
+                                            </div>
+                                        </li>
+                                    }
+                                }).collect::<Vec<_>>()}
+                            </ul>
+                            {move || has_more.get().then(|| view! {
+                                <div class="px-6 py-4 border-t border-cream-200 dark:border-charcoal-700 text-center">
+                                    <Button
+                                        variant=ButtonVariant::Secondary
+                                        disabled=loading_messages.get()
+                                        on_click=Callback::new(move |_| load_more())
+                                    >
+                                        "Load more"
+                                    </Button>
+                                </div>
+                            })}
+                        </div>
+                    }.into_any()
+                }
+            }}
+        </div>
+    }
+}
+
+fn delivery_status(recipient: &OutboxRecipient) -> &'static str {
+    if recipient.ack_ts.is_some() {
+        "acked"
+    } else if recipient.read_ts.is_some() {
+        "read"
+    } else {
+        "unread"
+    }
+}
+
+fn status_variant(recipient: &OutboxRecipient) -> BadgeVariant {
+    if recipient.ack_ts.is_some() {
+        BadgeVariant::Success
+    } else if recipient.read_ts.is_some() {
+        BadgeVariant::Secondary
+    } else {
+        BadgeVariant::Outline
+    }
+}
+
+fn format_date(date_str: &str) -> String {
+    if date_str.is_empty() {
+        return "—".to_string();
+    }
+    date_str.split('T').next().unwrap_or(date_str).to_string()
+}
@@ -0,0 +1,327 @@
+//! Stats page - how agents use the MCP tools.
+//!
+//! Per-tool call counts, error rates and latency percentiles over the last
+//! hour, day or week, from `/api/metrics/tools`. Tools are compared with
+//! plain bars: call volume against the busiest tool, and calls over time
+//! as a strip of bucket bars.
+
+use crate::api::client::{self, ToolUsage, ToolUsageReport};
+use crate::components::{
+    Alert, AlertDescription, AlertVariant, Button, ButtonVariant, NumberCounter, Progress, Spinner,
+    SpinnerSize,
+};
+use crate::utils::local_hours_ago;
+use leptos::prelude::*;
+use leptos_router::hooks::use_query_map;
+
+/// Time window the page covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageWindow {
+    Hour,
+    Day,
+    Week,
+}
+
+impl UsageWindow {
+    pub const ALL: [UsageWindow; 3] = [UsageWindow::Hour, UsageWindow::Day, UsageWindow::Week];
+
+    /// Short label, also used as the `window` query value.
+    pub fn label(self) -> &'static str {
+        match self {
+            UsageWindow::Hour => "1h",
+            UsageWindow::Day => "24h",
+            UsageWindow::Week => "7d",
+        }
+    }
+
+    pub fn hours(self) -> f64 {
+        match self {
+            UsageWindow::Hour => 1.0,
+            UsageWindow::Day => 24.0,
+            UsageWindow::Week => 168.0,
+        }
+    }
+
+    /// Window for a `window` query value; the last 24 hours by default.
+    pub fn from_query(value: &str) -> Self {
+        Self::ALL
+            .into_iter()
+            .find(|w| w.label() == value)
+            .unwrap_or(UsageWindow::Day)
+    }
+}
+
+/// A latency as `850 ms` or `1.2 s`.
+pub fn format_latency(ms: f64) -> String {
+    if ms < 1_000.0 {
+        format!("{} ms", ms.round() as i64)
+    } else {
+        format!("{:.1} s", ms / 1_000.0)
+    }
+}
+
+/// An error rate (0.0 to 1.0) as a percentage with one decimal.
+pub fn format_rate(rate: f64) -> String {
+    format!("{:.1}%", rate * 100.0)
+}
+
+/// Bar heights for a bucket strip, as percentages of the busiest bucket.
+/// Buckets with any calls get at least a sliver so they stay visible.
+pub fn bucket_heights(buckets: &[i64]) -> Vec<u32> {
+    let max = buckets.iter().copied().max().unwrap_or(0);
+    buckets
+        .iter()
+        .map(|&calls| match calls {
+            0 => 0,
+            c => ((c as f64 / max as f64) * 100.0).round().max(4.0) as u32,
+        })
+        .collect()
+}
+
+/// Stats page component.
+#[component]
+pub fn Stats() -> impl IntoView {
+    let query = use_query_map();
+    let window = RwSignal::new(UsageWindow::from_query(
+        &query.with_untracked(|params| params.get("window").unwrap_or_default()),
+    ));
+    let report = RwSignal::new(Option::<ToolUsageReport>::None);
+    let loading = RwSignal::new(true);
+    let error = RwSignal::new(Option::<String>::None);
+
+    let load = move || {
+        let selected = window.get_untracked();
+        loading.set(true);
+        leptos::task::spawn_local(async move {
+            match client::get_tool_usage(&local_hours_ago(selected.hours())).await {
+                Ok(r) => {
+                    // Ignore a slow response for a window no longer selected
+                    if window.get_untracked() == selected {
+                        report.set(Some(r));
+                        error.set(None);
+                    }
+                }
+                Err(e) => error.set(Some(e.message)),
+            }
+            loading.set(false);
+        });
+    };
+
+    Effect::new(move |_| {
+        window.track();
+        load();
+    });
+
+    view! {
+        <div class="space-y-6">
+            // Header
+            <div class="flex flex-wrap items-start justify-between gap-4">
+                <div>
+                    <h1 class="font-display text-2xl font-bold text-charcoal-800 dark:text-cream-100 flex items-center gap-2">
+                        <i data-lucide="chart-column" class="icon-xl text-amber-500"></i>
+                        "Tool Stats"
+                    </h1>
+                    <p class="text-charcoal-500 dark:text-charcoal-400">
+                        "How often agents call each tool, how often it fails and how long it takes"
+                    </p>
+                </div>
+                <div class="flex items-center gap-2">
+                    <div class="inline-flex rounded-lg border border-cream-200 dark:border-charcoal-700 p-0.5" role="group" aria-label="Time window">
+                        {UsageWindow::ALL.into_iter().map(|w| view! {
+                            <button
+                                type="button"
+                                class=move || format!(
+                                    "px-3 py-1 text-sm rounded-md transition-colors {}",
+                                    if window.get() == w {
+                                        "bg-primary text-primary-foreground"
+                                    } else {
+                                        "text-charcoal-600 dark:text-charcoal-300 hover:bg-cream-100 dark:hover:bg-charcoal-700"
+                                    }
+                                )
+                                aria-pressed=move || (window.get() == w).to_string()
+                                on:click=move |_| window.set(w)
+                            >
+                                {w.label()}
+                            </button>
+                        }).collect_view()}
+                    </div>
+                    <Button
+                        variant=ButtonVariant::Secondary
+                        disabled=Signal::derive(move || loading.get())
+                        on_click=Callback::new(move |_| load())
+                    >
+                        {move || if loading.get() {
+                            view! { <i data-lucide="loader-2" class="icon-sm animate-spin"></i> }.into_any()
+                        } else {
+                            view! { <i data-lucide="refresh-cw" class="icon-sm"></i> }.into_any()
+                        }}
+                        "Refresh"
+                    </Button>
+                </div>
+            </div>
+
+            // Error Message
+            {move || {
+                error.get().map(|e| view! {
+                    <Alert variant=AlertVariant::Destructive class="animate-slide-up">
+                        <i data-lucide="triangle-alert" class="h-4 w-4"></i>
+                        <AlertDescription>{e}</AlertDescription>
+                    </Alert>
+                })
+            }}
+
+            {move || match report.get() {
+                None if loading.get() => view! {
+                    <div class="flex items-center justify-center py-16">
+                        <Spinner size=SpinnerSize::Lg class="text-primary" />
+                    </div>
+                }.into_any(),
+                None => ().into_any(),
+                Some(r) => view! { <UsageReport report=r window=window.get() /> }.into_any(),
+            }}
+        </div>
+    }
+}
+
+/// Summary cards and the per-tool comparison for one report.
+#[component]
+fn UsageReport(report: ToolUsageReport, window: UsageWindow) -> impl IntoView {
+    let max_calls = report.tools.iter().map(|t| t.calls).max().unwrap_or(0);
+    let bucket_minutes = (report.bucket_seconds / 60).max(1);
+    let error_percent = report.error_rate * 100.0;
+
+    view! {
+        <div class="space-y-6">
+            <div class="grid grid-cols-1 sm:grid-cols-3 gap-4">
+                <div class="card-elevated p-5">
+                    <p class="text-sm text-charcoal-500 dark:text-charcoal-400">{format!("Calls ({})", window.label())}</p>
+                    <NumberCounter value=report.total_calls class="block text-3xl font-semibold text-charcoal-800 dark:text-cream-100" />
+                </div>
+                <div class="card-elevated p-5 space-y-2">
+                    <p class="text-sm text-charcoal-500 dark:text-charcoal-400">"Error rate"</p>
+                    <p class="text-3xl font-semibold text-charcoal-800 dark:text-cream-100">
+                        {format_rate(report.error_rate)}
+                    </p>
+                    <Progress value=error_percent class="h-2" indicator_class="bg-rose-500" />
+                    <p class="text-xs text-charcoal-400">
+                        <NumberCounter value=report.total_errors />
+                        " failed calls"
+                    </p>
+                </div>
+                <div class="card-elevated p-5">
+                    <p class="text-sm text-charcoal-500 dark:text-charcoal-400">"Tools used"</p>
+                    <NumberCounter value=report.tool_count class="block text-3xl font-semibold text-charcoal-800 dark:text-cream-100" />
+                </div>
+            </div>
+
+            {if report.tools.is_empty() {
+                view! {
+                    <div class="card-elevated p-12 text-center">
+                        <i data-lucide="chart-column" class="icon-xl mx-auto mb-3 text-charcoal-400 opacity-50"></i>
+                        <h3 class="font-display text-xl font-semibold text-charcoal-800 dark:text-cream-100 mb-2">"No tool calls"</h3>
+                        <p class="text-charcoal-500 dark:text-charcoal-400">
+                            {format!("No tools were called in the last {}.", window.label())}
+                        </p>
+                    </div>
+                }.into_any()
+            } else {
+                let shown = report.tools.len();
+                let truncated = report.truncated.then(|| view! {
+                    <p class="px-5 py-3 text-xs text-charcoal-400">
+                        {format!("Showing the {} busiest of {} tools; totals include all of them.", shown, report.tool_count)}
+                    </p>
+                });
+                view! {
+                    <div class="card-elevated overflow-hidden">
+                        <div class="hidden md:grid grid-cols-12 gap-4 px-5 py-3 text-xs font-medium uppercase tracking-wide text-charcoal-500 dark:text-charcoal-400 bg-cream-50 dark:bg-charcoal-800 border-b border-cream-200 dark:border-charcoal-700">
+                            <span class="col-span-4">"Tool"</span>
+                            <span class="col-span-2 text-right">"Errors"</span>
+                            <span class="col-span-3 text-right">"p50 / p95 / p99"</span>
+                            <span class="col-span-3">{format!("Calls per {} min", bucket_minutes)}</span>
+                        </div>
+                        <ul class="divide-y divide-cream-200 dark:divide-charcoal-700">
+                            {report.tools.into_iter().map(|tool| view! {
+                                <ToolRow tool=tool max_calls=max_calls />
+                            }).collect_view()}
+                        </ul>
+                        {truncated}
+                    </div>
+                }.into_any()
+            }}
+        </div>
+    }
+}
+
+/// One tool's row in the comparison.
+#[component]
+fn ToolRow(tool: ToolUsage, max_calls: i64) -> impl IntoView {
+    let heights = bucket_heights(&tool.buckets);
+    let latency = format!(
+        "{} / {} / {}",
+        format_latency(tool.p50_ms as f64),
+        format_latency(tool.p95_ms as f64),
+        format_latency(tool.p99_ms as f64)
+    );
+    let error_class = if tool.errors > 0 {
+        "text-rose-600 dark:text-rose-400"
+    } else {
+        "text-charcoal-500 dark:text-charcoal-400"
+    };
+
+    view! {
+        <li class="grid grid-cols-1 md:grid-cols-12 gap-2 md:gap-4 items-center px-5 py-3">
+            <div class="md:col-span-4 space-y-1">
+                <div class="flex items-baseline justify-between gap-2">
+                    <code class="text-sm font-mono text-charcoal-800 dark:text-cream-100 truncate">{tool.tool_name.clone()}</code>
+                    <span class="text-sm font-medium text-charcoal-700 dark:text-cream-200">{tool.calls}</span>
+                </div>
+                <Progress value=tool.calls as f64 max=max_calls.max(1) as f64 class="h-1.5" />
+            </div>
+            <div class=format!("md:col-span-2 md:text-right text-sm {}", error_class)>
+                {format!("{} ({})", format_rate(tool.error_rate), tool.errors)}
+            </div>
+            <div class="md:col-span-3 md:text-right text-sm font-mono text-charcoal-600 dark:text-charcoal-300" title=format!("avg {}", format_latency(tool.avg_duration_ms))>
+                {latency}
+            </div>
+            <div class="md:col-span-3 flex items-end gap-px h-8" aria-hidden="true">
+                {heights.into_iter().map(|h| view! {
+                    <div
+                        class="flex-1 rounded-sm bg-amber-400/80 dark:bg-amber-500/70"
+                        style=format!("height: {}%", h)
+                    ></div>
+                }).collect_view()}
+            </div>
+        </li>
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_window_from_query() {
+        assert_eq!(UsageWindow::from_query("1h"), UsageWindow::Hour);
+        assert_eq!(UsageWindow::from_query("7d"), UsageWindow::Week);
+        assert_eq!(UsageWindow::from_query(""), UsageWindow::Day);
+        assert_eq!(UsageWindow::from_query("30d"), UsageWindow::Day);
+        assert_eq!(UsageWindow::Week.hours(), 168.0);
+    }
+
+    #[test]
+    fn test_format_latency_and_rate() {
+        assert_eq!(format_latency(0.0), "0 ms");
+        assert_eq!(format_latency(849.6), "850 ms");
+        assert_eq!(format_latency(1_250.0), "1.2 s");
+        assert_eq!(format_rate(0.0), "0.0%");
+        assert_eq!(format_rate(0.125), "12.5%");
+    }
+
+    #[test]
+    fn test_bucket_heights() {
+        assert_eq!(bucket_heights(&[0, 10, 5, 1]), vec![0, 100, 50, 10]);
+        assert_eq!(bucket_heights(&[100, 1]), vec![100, 4]);
+        assert_eq!(bucket_heights(&[0, 0]), vec![0, 0]);
+        assert!(bucket_heights(&[]).is_empty());
+    }
+}
@@ -3,9 +3,17 @@
 //! Shows hierarchical view of message threads with expand/collapse,
 //! reply functionality, and keyboard navigation.
 
-use crate::api::client::{self, Message};
-use crate::components::{Button, ButtonVariant, Card, CardContent};
+use crate::api::client::{self, Message, ThreadStats};
+use crate::components::message_detail_header::{copy_to_clipboard, window_origin};
+use crate::components::{
+    Badge, BadgeVariant, Button, ButtonVariant, Card, CardContent, ThreadMuteToggle, Toast,
+    use_toaster,
+};
+use crate::utils::permalink::{legacy_thread_redirect, share_url, thread_path};
+use crate::utils::render_markdown;
 use leptos::prelude::*;
+use leptos_router::NavigateOptions;
+use leptos_router::components::Redirect;
 use leptos_router::hooks::{use_params_map, use_query_map};
 
 /// Maximum indentation depth for visual hierarchy.
@@ -19,18 +27,47 @@ struct ThreadNode {
     expanded: RwSignal<bool>,
 }
 
+/// Legacy `/thread/{id}` route: redirects to the permalink when the project
+/// is known, otherwise shows the thread in place.
+#[component]
+pub fn LegacyThreadView() -> impl IntoView {
+    let params = use_params_map();
+    let query = use_query_map();
+    let thread_id = params.with_untracked(|p| p.get("id").unwrap_or_default());
+    let project = query.with_untracked(|q| q.get("project").unwrap_or_default());
+
+    match legacy_thread_redirect(&thread_id, &project) {
+        Some(path) => view! {
+            <Redirect path=path options=NavigateOptions { replace: true, ..Default::default() } />
+        }
+        .into_any(),
+        None => view! { <ThreadView /> }.into_any(),
+    }
+}
+
 /// Thread view page component.
 #[component]
 pub fn ThreadView() -> impl IntoView {
     let params = use_params_map();
     let query = use_query_map();
 
-    // Route params
+    // Route params; permalinks carry the project in the path, legacy links in the query
     let thread_id = params.with_untracked(|p| p.get("id").unwrap_or_default());
-    let project_slug = query.with_untracked(|q| q.get("project").unwrap_or_default());
+    let project_slug = params
+        .with_untracked(|p| p.get("slug"))
+        .or_else(|| query.with_untracked(|q| q.get("project")))
+        .unwrap_or_default();
+
+    let toaster = use_toaster();
+    let link_path = thread_path(&project_slug, &thread_id);
+    let copy_link = move |_| {
+        copy_to_clipboard(&share_url(None, &link_path, &window_origin()));
+        toaster.toast(Toast::success("Link copied to clipboard"));
+    };
 
     // State
     let messages = RwSignal::new(Vec::<Message>::new());
+    let summary = RwSignal::new(Option::<ThreadStats>::None);
     let loading = RwSignal::new(true);
     let error = RwSignal::new(Option::<String>::None);
     let focused_index = RwSignal::new(0usize);
@@ -48,6 +85,14 @@ pub fn ThreadView() -> impl IntoView {
             return;
         }
 
+        let (summary_proj, summary_tid) = (proj.clone(), tid.clone());
+        leptos::task::spawn_local(async move {
+            // Participant chips are optional; the thread still renders without them
+            if let Ok(stats) = client::get_thread_summary(&summary_proj, &summary_tid).await {
+                summary.set(Some(stats));
+            }
+        });
+
         leptos::task::spawn_local(async move {
             match client::get_thread(&proj, &tid).await {
                 Ok(msgs) => {
@@ -121,6 +166,11 @@ pub fn ThreadView() -> impl IntoView {
                     </a>
                 </nav>
 
+                <Button variant=ButtonVariant::Secondary on_click=Callback::new(copy_link)>
+                    <i data-lucide="link" class="icon-sm"></i>
+                    "Copy link"
+                </Button>
+
                 <div class="text-sm text-charcoal-400 dark:text-charcoal-500">
                     <kbd class="px-1.5 py-0.5 rounded bg-charcoal-100 dark:bg-charcoal-800 text-xs">"↑↓"</kbd>
                     " navigate "
@@ -131,6 +181,36 @@ pub fn ThreadView() -> impl IntoView {
                 </div>
             </div>
 
+            // Participant chips
+            {move || summary.get().map(|stats| view! {
+                <div class="flex items-center gap-2 flex-wrap" aria-label="Thread participants">
+                    {stats.participants.into_iter().map(|p| view! {
+                        <Badge variant=BadgeVariant::Secondary>
+                            {p.name}" · "{p.message_count}
+                        </Badge>
+                    }).collect::<Vec<_>>()}
+                    <span class="text-sm text-charcoal-500 dark:text-charcoal-400">
+                        {format!("{} messages", stats.message_count)}
+                        {(stats.ack_required_count > 0).then(|| format!(
+                            " · {}/{} acknowledged",
+                            stats.acked_count, stats.ack_required_count
+                        ))}
+                    </span>
+                </div>
+            })}
+
+            // Per-participant mute toggles
+            {
+                let (mute_project, mute_thread) = (project_slug.clone(), thread_id.clone());
+                move || summary.get().map(|stats| view! {
+                    <ThreadMuteToggle
+                        project_slug={mute_project.clone()}
+                        thread_id={mute_thread.clone()}
+                        agents=stats.participants.into_iter().map(|p| p.name).collect()
+                    />
+                })
+            }
+
             // Error display
             {move || error.get().map(|e| view! {
                 <div class="rounded-xl border border-red-200 dark:border-red-800 bg-red-50 dark:bg-red-900/20 p-4">
@@ -213,8 +293,12 @@ fn ThreadMessageNode(node: ThreadNode, focused: bool, project_slug: String) -> i
         message.subject.clone()
     };
     let from = message.sender_name.clone();
+    // Set only on messages sent from another project
+    let origin = message.project_slug.clone();
     let body_preview = message.body_md.chars().take(200).collect::<String>();
+    let body_html = render_markdown(&message.body_md);
     let created = message.created_ts.clone();
+    let position = message.thread_seq.map(|seq| format!("#{} in thread", seq));
 
     view! {
         <div
@@ -260,8 +344,17 @@ fn ThreadMessageNode(node: ThreadNode, focused: bool, project_slug: String) -> i
                                     <i data-lucide="user" class="icon-xs"></i>
                                     {from}
                                 </span>
+                                {origin.map(|slug| view! {
+                                    <span class="badge badge-charcoal text-xs" title="Sent from another project">
+                                        {slug}
+                                    </span>
+                                })}
                                 <span>"·"</span>
                                 <span>{created}</span>
+                                {position.map(|position| view! {
+                                    <span>"·"</span>
+                                    <span>{position}</span>
+                                })}
                             </div>
 
                             // Body preview (when collapsed) or full body (when expanded)
@@ -269,7 +362,10 @@ fn ThreadMessageNode(node: ThreadNode, focused: bool, project_slug: String) -> i
                                 {move || {
                                     if expanded.get() {
                                         view! {
-                                            <div class="whitespace-pre-wrap">{message.body_md.clone()}</div>
+                                            <div
+                                                class="markdown-body text-sm break-words"
+                                                inner_html=body_html.clone()
+                                            ></div>
                                         }.into_any()
                                     } else {
                                         view! {
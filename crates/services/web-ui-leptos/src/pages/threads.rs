@@ -0,0 +1,237 @@
+//! Threads page - browse a project's conversations by last activity.
+
+use crate::api::client::{self, Project, ThreadListItem};
+use crate::api::project_version::project_changes;
+use crate::components::{
+    Alert, AlertDescription, AlertVariant, Badge, BadgeVariant, Button, ButtonVariant, Select,
+    SelectIcon, SelectOption, Spinner, SpinnerSize,
+};
+use leptos::prelude::*;
+use leptos_router::hooks::use_query_map;
+
+/// Threads page component.
+#[component]
+pub fn Threads() -> impl IntoView {
+    let query = use_query_map();
+
+    // State
+    let projects = RwSignal::new(Vec::<Project>::new());
+    let threads = RwSignal::new(Vec::<ThreadListItem>::new());
+    let next_cursor = RwSignal::new(Option::<String>::None);
+    let loading = RwSignal::new(true);
+    let loading_threads = RwSignal::new(false);
+    let error = RwSignal::new(Option::<String>::None);
+
+    let selected_project =
+        RwSignal::new(query.with_untracked(|params| params.get("project").unwrap_or_default()));
+
+    // Fetch a page; `cursor` None replaces the list, Some appends to it
+    let load_page = move |cursor: Option<String>| {
+        let project = selected_project.get_untracked();
+        if project.is_empty() {
+            return;
+        }
+
+        loading_threads.set(true);
+        error.set(None);
+        leptos::task::spawn_local(async move {
+            match client::get_threads(&project, cursor.as_deref()).await {
+                Ok(page) => {
+                    next_cursor.set(page.next_cursor);
+                    if cursor.is_some() {
+                        threads.update(|t| t.extend(page.threads));
+                    } else {
+                        threads.set(page.threads);
+                    }
+                }
+                Err(e) => error.set(Some(e.message)),
+            }
+            loading_threads.set(false);
+        });
+    };
+
+    // Load projects once
+    Effect::new(move |_| {
+        leptos::task::spawn_local(async move {
+            match client::get_projects().await {
+                Ok(p) => projects.set(p),
+                Err(e) => error.set(Some(e.message)),
+            }
+            loading.set(false);
+        });
+    });
+
+    // Reload threads when another project is picked or this one is reshaped
+    Effect::new(move |_| {
+        project_changes(selected_project.get()).track();
+        if selected_project.get().is_empty() {
+            threads.set(Vec::new());
+            next_cursor.set(None);
+        } else {
+            load_page(None);
+        }
+    });
+
+    let load_more = move || {
+        if let Some(cursor) = next_cursor.get_untracked() {
+            load_page(Some(cursor));
+        }
+    };
+
+    view! {
+        <div class="space-y-6">
+            // Header
+            <div>
+                <h1 class="font-display text-2xl font-bold text-charcoal-800 dark:text-cream-100 flex items-center gap-2">
+                    <i data-lucide="messages-square" class="icon-xl text-amber-500"></i>
+                    "Threads"
+                </h1>
+                <p class="text-charcoal-500 dark:text-charcoal-400">"Conversations in a project, most recently active first"</p>
+            </div>
+
+            // Filters Card
+            <div class="card-elevated p-5">
+                <label class="flex items-center gap-2 text-sm font-medium text-charcoal-700 dark:text-charcoal-300 mb-2">
+                    <i data-lucide="folder" class="icon-sm text-charcoal-400"></i>
+                    "Project"
+                </label>
+                {move || {
+                    let options: Vec<SelectOption> = projects.get()
+                        .into_iter()
+                        .map(|p| SelectOption::new(p.slug.clone(), p.slug.clone()))
+                        .collect();
+                    view! {
+                        <Select
+                            id="threadsProjectSelect".to_string()
+                            options=options
+                            value=selected_project
+                            placeholder="Select a project...".to_string()
+                            disabled=false
+                            icon=SelectIcon::Folder
+                        />
+                    }
+                }}
+            </div>
+
+            // Error Message
+            {move || {
+                error.get().map(|e| view! {
+                    <Alert variant=AlertVariant::Destructive class="animate-slide-up">
+                        <i data-lucide="triangle-alert" class="h-4 w-4"></i>
+                        <AlertDescription>{e}</AlertDescription>
+                    </Alert>
+                })
+            }}
+
+            // Content
+            {move || {
+                let thread_list = threads.get();
+                let project = selected_project.get();
+                if loading.get() || (loading_threads.get() && thread_list.is_empty()) {
+                    view! {
+                        <div class="flex items-center justify-center py-16">
+                            <Spinner size=SpinnerSize::Lg class="text-primary" />
+                        </div>
+                    }.into_any()
+                } else if project.is_empty() {
+                    view! {
+                        <div class="card-elevated p-12 text-center">
+                            <h3 class="font-display text-xl font-semibold text-charcoal-800 dark:text-cream-100 mb-2">"Select a Project"</h3>
+                            <p class="text-charcoal-500 dark:text-charcoal-400 max-w-sm mx-auto">
+                                "Choose a project to browse its threads."
+                            </p>
+                        </div>
+                    }.into_any()
+                } else if thread_list.is_empty() {
+                    view! {
+                        <div class="card-elevated p-12 text-center">
+                            <h3 class="font-display text-xl font-semibold text-charcoal-800 dark:text-cream-100 mb-2">"No threads yet"</h3>
+                            <p class="text-charcoal-500 dark:text-charcoal-400">
+                                <span class="font-medium text-charcoal-700 dark:text-cream-200">{project}</span>
+                                " has no conversations."
+                            </p>
+                        </div>
+                    }.into_any()
+                } else {
+                    view! {
+                        <div class="card-elevated overflow-hidden">
+                            <ul class="divide-y divide-cream-200 dark:divide-charcoal-700">
+                                {thread_list.into_iter().map(|thread| {
+                                    let href = thread_href(&project, &thread.thread_id);
+                                    let counts = format!(
+                                        "{} msgs · {} participants",
+                                        thread.message_count, thread.participant_count
+                                    );
+                                    view! {
+                                        <li class="px-6 py-4">
+                                            <div class="flex items-baseline justify-between gap-4 mb-2">
+                                                <h4 class="font-medium text-charcoal-800 dark:text-cream-100 truncate">
+                                                    <a href=href class="hover:text-amber-600">{thread.subject.clone()}</a>
+                                                </h4>
+                                                <span class="flex-shrink-0 text-xs font-mono text-charcoal-400 dark:text-charcoal-500">
+                                                    {format_date(&thread.last_message_ts)}
+                                                </span>
+                                            </div>
+                                            <div class="flex flex-wrap items-center gap-2 text-sm text-charcoal-500 dark:text-charcoal-400">
+                                                <Badge variant=BadgeVariant::Outline>{thread.thread_id.clone()}</Badge>
+                                                <span>{counts}</span>
+                                                <span>"· last from "</span>
+                                                <span class="font-medium text-charcoal-700 dark:text-cream-200">{thread.last_sender_name.clone()}</span>
+                                            </div>
+                                        </li>
+                                    }
+                                }).collect::<Vec<_>>()}
+                            </ul>
+                            {move || next_cursor.get().is_some().then(|| view! {
+                                <div class="px-6 py-4 border-t border-cream-200 dark:border-charcoal-700 text-center">
+                                    <Button
+                                        variant=ButtonVariant::Secondary
+                                        disabled=loading_threads.get()
+                                        on_click=Callback::new(move |_| load_more())
+                                    >
+                                        "Load more"
+                                    </Button>
+                                </div>
+                            })}
+                        </div>
+                    }.into_any()
+                }
+            }}
+        </div>
+    }
+}
+
+/// Link into the ThreadView page, which needs the project as a query param.
+fn thread_href(project_slug: &str, thread_id: &str) -> String {
+    format!(
+        "/thread/{}?project={}",
+        urlencoding::encode(thread_id),
+        urlencoding::encode(project_slug)
+    )
+}
+
+fn format_date(date_str: &str) -> String {
+    if date_str.is_empty() {
+        return "—".to_string();
+    }
+    date_str.replace('T', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_thread_href_encodes_parts() {
+        assert_eq!(
+            thread_href("my project", "TKT-1/a"),
+            "/thread/TKT-1%2Fa?project=my%20project"
+        );
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(format_date("2025-01-31T09:15:00"), "2025-01-31 09:15:00");
+        assert_eq!(format_date(""), "—");
+    }
+}
@@ -3,18 +3,26 @@
 //!
 //! Features:
 //! - SplitViewLayout for Gmail-style two-column view on desktop
-//! - FilterBar with search, project, sender, importance filters
+//! - FilterBar with search, project, sender, importance, label and date range filters
+//!   and sort order (applied server-side)
 //! - InlineMessageDetail for viewing messages without navigation
+//! - Checkbox selection with bulk mark read, export and copy thread links
 //! - Mobile fallback with card-based list
+//! - Live updates via the /api/events SSE stream
 
-use crate::api::client::{self, Agent, UnifiedInboxMessage};
+use crate::api::client::{self, Agent, UnifiedInboxMessage, UnifiedInboxQuery};
+use crate::components::bulk_actions::retain_visible;
 use crate::components::{
-    Alert, AlertDescription, AlertTitle, AlertVariant, Badge, BadgeVariant, Button, ButtonSize,
-    ButtonVariant, FilterBar, FilterState, InlineMessageDetail, MessageListItem,
-    OverseerComposeProps, OverseerComposer, SplitViewLayout,
+    Alert, AlertDescription, AlertTitle, AlertVariant, Badge, BadgeVariant, BulkActionBar, Button,
+    ButtonSize, ButtonVariant, FilterBar, FilterState, InlineMessageDetail, MessageListItem,
+    OverseerComposeProps, OverseerComposer, SplitViewLayout, use_filter_url_sync,
 };
 use leptos::prelude::*;
 use leptos_router::hooks::use_query_map;
+use leptos_use::use_debounce_fn;
+
+/// Page size requested from the server.
+const PAGE_SIZE: i32 = 100;
 
 /// Unified Inbox page component.
 #[component]
@@ -23,32 +31,48 @@ pub fn UnifiedInbox() -> impl IntoView {
 
     // State
     let messages = RwSignal::new(Vec::<UnifiedInboxMessage>::new());
-    let all_messages = RwSignal::new(Vec::<UnifiedInboxMessage>::new()); // Unfiltered for extracting options
+    // Dropdown options, kept across fetches so narrowing a filter doesn't hide the others
+    let project_options = RwSignal::new(Vec::<String>::new());
+    let sender_options = RwSignal::new(Vec::<String>::new());
+    let label_options = RwSignal::new(Vec::<String>::new());
     let loading = RwSignal::new(true);
     let error = RwSignal::new(Option::<String>::None);
     let filter_state = RwSignal::new(query.with_untracked(FilterState::from_params_map));
+    use_filter_url_sync(filter_state);
     let selected_id = RwSignal::new(Option::<i64>::None);
+    // Messages checked for bulk actions
+    let selection = RwSignal::new(Vec::<i64>::new());
 
     // Overseer Composer state
     let show_overseer = RwSignal::new(false);
     let overseer_agents = RwSignal::new(Vec::<Agent>::new());
     let overseer_project = RwSignal::new(String::new());
 
-    // Load all messages once on mount
-    Effect::new(move |_| {
+    // Fetch messages matching the current filters; the server applies them
+    let fetch_messages = move || {
+        let inbox_query = to_inbox_query(&filter_state.get_untracked());
         leptos::task::spawn_local(async move {
-            loading.set(true);
             error.set(None);
 
-            match client::get_unified_inbox(None, Some(100)).await {
+            match client::get_unified_inbox(&inbox_query).await {
                 Ok(msgs) => {
-                    // Auto-select first message if nothing selected
-                    if selected_id.get_untracked().is_none() {
-                        if let Some(first) = msgs.first() {
-                            selected_id.set(Some(first.id));
-                        }
+                    sender_options.update(|senders| {
+                        senders.extend(msgs.iter().map(|m| m.sender_name.clone()));
+                        senders.sort();
+                        senders.dedup();
+                    });
+
+                    // Keep the selection if it is still visible, else select the first message
+                    let current = selected_id.get_untracked();
+                    if !current.is_some_and(|id| msgs.iter().any(|m| m.id == id)) {
+                        selected_id.set(msgs.first().map(|m| m.id));
+                    }
+                    // Drop checked messages that are no longer listed
+                    let visible: Vec<i64> = msgs.iter().map(|m| m.id).collect();
+                    let kept = selection.with_untracked(|sel| retain_visible(sel, &visible));
+                    if selection.with_untracked(Vec::len) != kept.len() {
+                        selection.set(kept);
                     }
-                    all_messages.set(msgs.clone());
                     messages.set(msgs);
                     loading.set(false);
                 }
@@ -58,96 +82,52 @@ pub fn UnifiedInbox() -> impl IntoView {
                 }
             }
         });
-    });
-
-    // Apply filters reactively
-    Effect::new(move |_| {
-        let filter = filter_state.get();
-        let all = all_messages.get();
-
-        let filtered: Vec<UnifiedInboxMessage> = all
-            .into_iter()
-            .filter(|msg| {
-                // Search query filter
-                if !filter.query.is_empty() {
-                    let q = filter.query.to_lowercase();
-                    let matches = msg.subject.to_lowercase().contains(&q)
-                        || msg.sender_name.to_lowercase().contains(&q)
-                        || msg
-                            .thread_id
-                            .as_ref()
-                            .is_some_and(|t| t.to_lowercase().contains(&q));
-                    if !matches {
-                        return false;
-                    }
-                }
+    };
+    let debounced_fetch = use_debounce_fn(fetch_messages, 250.0);
 
-                // Importance filter
-                if let Some(ref imp) = filter.importance {
-                    if msg.importance != *imp {
-                        return false;
-                    }
+    // Fetch on mount, then refetch (debounced) whenever a server-side filter changes
+    Effect::new({
+        let debounced_fetch = debounced_fetch.clone();
+        move |prev: Option<UnifiedInboxQuery>| {
+            let inbox_query = to_inbox_query(&filter_state.get());
+            match &prev {
+                None => fetch_messages(),
+                Some(prev) if *prev != inbox_query => {
+                    selection.set(Vec::new());
+                    debounced_fetch();
                 }
+                Some(_) => {}
+            }
+            inbox_query
+        }
+    });
 
-                // Sender filter
-                if let Some(ref sender) = filter.sender {
-                    if msg.sender_name != *sender {
-                        return false;
-                    }
-                }
+    // Project dropdown lists every project, not just those on the current page;
+    // the label dropdown lists every label name across them
+    Effect::new(move |_| {
+        leptos::task::spawn_local(async move {
+            if let Ok(projects) = client::get_projects().await {
+                let mut slugs: Vec<String> = projects.into_iter().map(|p| p.slug).collect();
+                slugs.sort();
+                project_options.set(slugs.clone());
 
-                // Project filter (uses project_slug for display-friendly matching)
-                if let Some(ref project) = filter.project {
-                    if msg.project_slug != *project {
-                        return false;
+                let mut names = Vec::new();
+                for slug in &slugs {
+                    if let Ok(labels) = client::get_labels(slug).await {
+                        names.extend(labels.into_iter().map(|l| l.name));
                     }
                 }
-
-                true
-            })
-            .collect();
-
-        // If current selection is no longer visible, select first filtered message
-        if let Some(current_id) = selected_id.get_untracked() {
-            let still_visible = filtered.iter().any(|m| m.id == current_id);
-            if !still_visible {
-                if let Some(first) = filtered.first() {
-                    selected_id.set(Some(first.id));
-                }
+                // Names are case-insensitive on the server
+                names.sort_by_key(|n| n.to_lowercase());
+                names.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+                label_options.set(names);
             }
-        } else if let Some(first) = filtered.first() {
-            // No selection, select first
-            selected_id.set(Some(first.id));
-        }
-
-        messages.set(filtered);
-    });
-
-    // Extract unique senders for filter dropdown
-    let senders = Signal::derive(move || {
-        let mut senders: Vec<String> = all_messages
-            .get()
-            .iter()
-            .map(|m| m.sender_name.clone())
-            .collect();
-        senders.sort();
-        senders.dedup();
-        leptos::logging::log!("UnifiedInbox: Found {} unique senders", senders.len());
-        senders
+        });
     });
 
-    // Extract unique project slugs for filter dropdown
-    let projects = Signal::derive(move || {
-        let mut projects: Vec<String> = all_messages
-            .get()
-            .iter()
-            .map(|m| m.project_slug.clone())
-            .collect();
-        projects.sort();
-        projects.dedup();
-        leptos::logging::log!("UnifiedInbox: Found {} unique projects", projects.len());
-        projects
-    });
+    let senders = Signal::derive(move || sender_options.get());
+    let projects = Signal::derive(move || project_options.get());
+    let labels = Signal::derive(move || label_options.get());
 
     // Message count for FilterBar
     let message_count = Signal::derive(move || messages.get().len());
@@ -164,6 +144,8 @@ pub fn UnifiedInbox() -> impl IntoView {
                 timestamp: format_date(&msg.created_ts),
                 unread: false, // Read state not yet tracked.
                 importance: msg.importance.clone(),
+                ack_required: msg.ack_required,
+                muted: msg.muted,
                 project_slug: msg.project_slug.clone(),
             })
             .collect::<Vec<_>>()
@@ -187,6 +169,9 @@ pub fn UnifiedInbox() -> impl IntoView {
     let on_select = Callback::new(move |id: i64| {
         selected_id.set(Some(id));
     });
+    let on_clear = Callback::new(move |_| {
+        selected_id.set(None);
+    });
 
     // Handle Overseer button click
     let open_overseer = move |_| {
@@ -213,14 +198,41 @@ pub fn UnifiedInbox() -> impl IntoView {
     };
 
     // Refresh messages after sending
-    let refresh_messages = move || {
-        leptos::task::spawn_local(async move {
-            if let Ok(msgs) = client::get_unified_inbox(None, Some(100)).await {
-                all_messages.set(msgs.clone());
-                messages.set(msgs);
+    let refresh_messages = fetch_messages;
+
+    // Live updates: prepend new messages as the server streams them. When
+    // filters are active the server decides what matches, so refetch instead.
+    let event_source = StoredValue::new_local(None::<web_sys::EventSource>);
+    Effect::new(move |_| {
+        let debounced_fetch = debounced_fetch.clone();
+        let source = client::subscribe_events(None, move |event| match event.kind.as_str() {
+            "message.created" => {
+                if to_inbox_query(&filter_state.get_untracked()).is_filtered() {
+                    debounced_fetch();
+                } else if let Ok(msg) = serde_json::from_value::<UnifiedInboxMessage>(event.data) {
+                    messages.update(|all| {
+                        if !all.iter().any(|m| m.id == msg.id) {
+                            all.insert(0, msg);
+                        }
+                    });
+                }
             }
+            // Fell behind the server's buffer; reload everything
+            "resync" => refresh_messages(),
+            _ => {}
         });
-    };
+        match source {
+            Ok(source) => event_source.set_value(Some(source)),
+            Err(e) => leptos::logging::warn!("UnifiedInbox: live updates unavailable: {}", e),
+        }
+    });
+    on_cleanup(move || {
+        event_source.try_with_value(|source| {
+            if let Some(source) = source {
+                source.close();
+            }
+        });
+    });
 
     view! {
         <div class="space-y-6">
@@ -237,7 +249,8 @@ pub fn UnifiedInbox() -> impl IntoView {
                             aria-modal="true"
                             aria-labelledby="overseer-dialog-title"
                         >
-                            // Backdrop overlay - completely opaque to block all page content
+                            // Backdrop overlay - completely opaque to block all page content.
+                            // Clicking it closes the dialog; the composer saves its draft on close.
                             <div
                                 class="fixed inset-0 z-[1050] bg-black/95 backdrop-blur-sm animate-fade-in"
                                 on:click=move |_| show_overseer.set(false)
@@ -250,7 +263,7 @@ pub fn UnifiedInbox() -> impl IntoView {
                                     project_slug: project,
                                     agents,
                                     reply_to_thread_id: None,
-                                    reply_to_recipient: None,
+                                    reply_to_recipients: Vec::new(),
                                     reply_subject: None,
                                 }
                                 on_close=Callback::new(move |_| show_overseer.set(false))
@@ -336,6 +349,7 @@ pub fn UnifiedInbox() -> impl IntoView {
                             message_count=message_count
                             projects=projects.get()
                             senders=senders.get()
+                            labels=labels.get()
                         />
                     }
                 }}
@@ -387,6 +401,13 @@ pub fn UnifiedInbox() -> impl IntoView {
                 }
             }}
 
+            // Bulk actions for checked messages
+            {move || {
+                (!loading.get() && !selection.with(Vec::is_empty)).then(|| view! {
+                    <BulkActionBar selection=selection messages=messages />
+                })
+            }}
+
             // SplitViewLayout - Gmail-style two-column view with 2025 design
             {move || {
                 if !loading.get() {
@@ -398,6 +419,8 @@ pub fn UnifiedInbox() -> impl IntoView {
                                 messages=items
                                 selected_id=selected_signal
                                 on_select=on_select
+                                on_clear=on_clear
+                                selection=selection
                             >
                                 {move || {
                                     if let Some(id) = selected_id.get() {
@@ -406,6 +429,7 @@ pub fn UnifiedInbox() -> impl IntoView {
                                                 <InlineMessageDetail
                                                     message_id=Signal::derive(move || id)
                                                     project_slug=selected_project
+                                                    on_reply_sent=Callback::new(move |_| refresh_messages())
                                                 />
                                             </div>
                                         }.into_any()
@@ -422,6 +446,8 @@ pub fn UnifiedInbox() -> impl IntoView {
                                                 <div class="mt-4 flex gap-2 text-xs text-muted-foreground/60">
                                                     <kbd class="kbd">"↑"</kbd>
                                                     <kbd class="kbd">"↓"</kbd>
+                                                    <kbd class="kbd">"j"</kbd>
+                                                    <kbd class="kbd">"k"</kbd>
                                                     <span>"to navigate"</span>
                                                 </div>
                                             </div>
@@ -439,6 +465,23 @@ pub fn UnifiedInbox() -> impl IntoView {
     }
 }
 
+/// Map the FilterBar state onto the server-side unified inbox filters.
+fn to_inbox_query(filter: &FilterState) -> UnifiedInboxQuery {
+    let query = filter.query.trim();
+    UnifiedInboxQuery {
+        projects: filter.project.iter().cloned().collect(),
+        sender: filter.sender.clone(),
+        importance: filter.importance.clone(),
+        q: (!query.is_empty()).then(|| query.to_string()),
+        label: filter.label.clone(),
+        since: filter.since.clone(),
+        until: filter.until.clone(),
+        sort: filter.sort.clone(),
+        limit: Some(PAGE_SIZE),
+        ..Default::default()
+    }
+}
+
 fn format_date(date_str: &str) -> String {
     if date_str.is_empty() {
         return "—".to_string();
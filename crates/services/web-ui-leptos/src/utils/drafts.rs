@@ -0,0 +1,346 @@
+//! Compose drafts persisted to localStorage.
+//!
+//! Drafts live in one JSON array under [`DRAFTS_STORAGE_KEY`], most recent
+//! first and capped at [`MAX_DRAFTS`]. Each draft is keyed by project and
+//! compose context (a new message from an agent, a reply in a thread, an
+//! overseer directive), so reopening the same composer restores what was
+//! typed. Storage failures (private mode, quota) are ignored: a draft is a
+//! convenience, never a reason to block sending.
+
+use leptos::prelude::*;
+use leptos_use::use_debounce_fn;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+/// localStorage key holding every draft.
+pub const DRAFTS_STORAGE_KEY: &str = "composeDrafts";
+
+/// Maximum number of drafts kept; the least recently edited are evicted.
+pub const MAX_DRAFTS: usize = 10;
+
+/// Delay before a change is written to storage, in milliseconds.
+const SAVE_DEBOUNCE_MS: f64 = 500.0;
+
+/// Saved compose form state.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Draft {
+    pub project: String,
+    pub context: String,
+    #[serde(default)]
+    pub recipients: Vec<String>,
+    #[serde(default)]
+    pub subject: String,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub importance: String,
+    #[serde(default)]
+    pub thread_id: String,
+}
+
+impl Draft {
+    /// Storage key of this draft.
+    pub fn key(&self) -> String {
+        draft_key(&self.project, &self.context)
+    }
+
+    /// Whether there is nothing worth restoring.
+    pub fn is_blank(&self) -> bool {
+        self.recipients.is_empty() && self.subject.trim().is_empty() && self.body.trim().is_empty()
+    }
+}
+
+/// Storage key for a project and compose context.
+pub fn draft_key(project: &str, context: &str) -> String {
+    format!("{}::{}", project, context)
+}
+
+/// Encode drafts for storage.
+pub fn serialize_drafts(drafts: &[Draft]) -> String {
+    serde_json::to_string(drafts).unwrap_or_else(|_| "[]".to_string())
+}
+
+/// Decode stored drafts; corrupt data yields no drafts.
+pub fn deserialize_drafts(raw: &str) -> Vec<Draft> {
+    serde_json::from_str(raw).unwrap_or_default()
+}
+
+/// Put `draft` first, replacing any draft with the same key, and keep at
+/// most `cap` drafts.
+pub fn upsert_draft(mut drafts: Vec<Draft>, draft: Draft, cap: usize) -> Vec<Draft> {
+    let key = draft.key();
+    drafts.retain(|d| d.key() != key);
+    drafts.insert(0, draft);
+    drafts.truncate(cap);
+    drafts
+}
+
+/// Drop the draft for a project and compose context.
+pub fn remove_draft(mut drafts: Vec<Draft>, project: &str, context: &str) -> Vec<Draft> {
+    let key = draft_key(project, context);
+    drafts.retain(|d| d.key() != key);
+    drafts
+}
+
+/// Find the draft for a project and compose context.
+pub fn find_draft(drafts: &[Draft], project: &str, context: &str) -> Option<Draft> {
+    let key = draft_key(project, context);
+    drafts.iter().find(|d| d.key() == key).cloned()
+}
+
+fn storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+fn read_drafts() -> Vec<Draft> {
+    storage()
+        .and_then(|s| s.get_item(DRAFTS_STORAGE_KEY).ok().flatten())
+        .map(|raw| deserialize_drafts(&raw))
+        .unwrap_or_default()
+}
+
+fn write_drafts(drafts: &[Draft]) {
+    if let Some(storage) = storage() {
+        let _ = if drafts.is_empty() {
+            storage.remove_item(DRAFTS_STORAGE_KEY)
+        } else {
+            storage.set_item(DRAFTS_STORAGE_KEY, &serialize_drafts(drafts))
+        };
+    }
+}
+
+/// Load the stored draft for a project and compose context.
+pub fn load_draft(project: &str, context: &str) -> Option<Draft> {
+    find_draft(&read_drafts(), project, context)
+}
+
+/// Store `draft`, or remove it when it is blank.
+pub fn save_draft(draft: Draft) {
+    if draft.is_blank() {
+        clear_draft(&draft.project, &draft.context);
+    } else {
+        write_drafts(&upsert_draft(read_drafts(), draft, MAX_DRAFTS));
+    }
+}
+
+/// Remove the stored draft for a project and compose context.
+pub fn clear_draft(project: &str, context: &str) {
+    let drafts = read_drafts();
+    if find_draft(&drafts, project, context).is_some() {
+        write_drafts(&remove_draft(drafts, project, context));
+    }
+}
+
+/// Compose form signals persisted by [`use_compose_draft`].
+#[derive(Clone, Copy)]
+pub struct DraftFields {
+    pub recipients: RwSignal<Vec<String>>,
+    pub subject: RwSignal<String>,
+    pub body: RwSignal<String>,
+    pub importance: RwSignal<String>,
+    pub thread_id: RwSignal<String>,
+}
+
+impl DraftFields {
+    fn snapshot(&self, project: &str, context: &str) -> Draft {
+        Draft {
+            project: project.to_string(),
+            context: context.to_string(),
+            recipients: self.recipients.get(),
+            subject: self.subject.get(),
+            body: self.body.get(),
+            importance: self.importance.get(),
+            thread_id: self.thread_id.get(),
+        }
+    }
+
+    fn apply(&self, draft: &Draft) {
+        self.recipients.set(draft.recipients.clone());
+        self.subject.set(draft.subject.clone());
+        self.body.set(draft.body.clone());
+        if !draft.importance.is_empty() {
+            self.importance.set(draft.importance.clone());
+        }
+        self.thread_id.set(draft.thread_id.clone());
+    }
+}
+
+#[derive(Default)]
+struct PendingDraft {
+    draft: Option<Draft>,
+    finished: bool,
+}
+
+/// Draft state of a mounted composer.
+#[derive(Clone)]
+pub struct DraftHandle {
+    /// Set when the form was filled from a stored draft.
+    pub restored: RwSignal<bool>,
+    initial: Draft,
+    fields: DraftFields,
+    pending: Arc<Mutex<PendingDraft>>,
+}
+
+impl DraftHandle {
+    /// Forget the stored draft and reset the form to its initial values.
+    pub fn discard(&self) {
+        clear_draft(&self.initial.project, &self.initial.context);
+        self.fields.apply(&self.initial);
+        self.restored.set(false);
+    }
+
+    /// Remove the draft after a successful send; later edits are not saved.
+    pub fn finish(&self) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.draft = None;
+            pending.finished = true;
+        }
+        clear_draft(&self.initial.project, &self.initial.context);
+    }
+}
+
+/// Restore the draft for `project`/`context` into `fields` and keep saving
+/// edits (debounced, and once more when the composer unmounts).
+///
+/// A form still matching its initial values is not stored, so opening and
+/// closing a composer never leaves an empty draft behind.
+pub fn use_compose_draft(project: &str, context: &str, fields: DraftFields) -> DraftHandle {
+    let initial = Draft {
+        project: project.to_string(),
+        context: context.to_string(),
+        recipients: fields.recipients.get_untracked(),
+        subject: fields.subject.get_untracked(),
+        body: fields.body.get_untracked(),
+        importance: fields.importance.get_untracked(),
+        thread_id: fields.thread_id.get_untracked(),
+    };
+    let restored = RwSignal::new(false);
+    if let Some(draft) = load_draft(project, context) {
+        fields.apply(&draft);
+        restored.set(true);
+    }
+
+    let pending = Arc::new(Mutex::new(PendingDraft::default()));
+    let flush = {
+        let pending = pending.clone();
+        let initial = initial.clone();
+        move || {
+            let draft = pending.lock().ok().and_then(|mut p| p.draft.take());
+            match draft {
+                Some(draft) if draft == initial => clear_draft(&draft.project, &draft.context),
+                Some(draft) => save_draft(draft),
+                None => {}
+            }
+        }
+    };
+    let debounced_flush = use_debounce_fn(flush.clone(), SAVE_DEBOUNCE_MS);
+
+    Effect::new({
+        let pending = pending.clone();
+        let project = project.to_string();
+        let context = context.to_string();
+        move |_| {
+            let draft = fields.snapshot(&project, &context);
+            if let Ok(mut p) = pending.lock() {
+                if p.finished {
+                    return;
+                }
+                p.draft = Some(draft);
+            }
+            debounced_flush();
+        }
+    });
+
+    // Closing the dialog (Cancel, backdrop click) must not lose a pending edit
+    on_cleanup(flush);
+
+    DraftHandle {
+        restored,
+        initial,
+        fields,
+        pending,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn draft(context: &str, body: &str) -> Draft {
+        Draft {
+            project: "proj".to_string(),
+            context: context.to_string(),
+            body: body.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let drafts = vec![Draft {
+            project: "proj".to_string(),
+            context: "compose:BlueLake".to_string(),
+            recipients: vec!["GreenCastle".to_string(), "RedStone".to_string()],
+            subject: "Status \"update\"".to_string(),
+            body: "Line one\nLine two ✓".to_string(),
+            importance: "high".to_string(),
+            thread_id: "thread-7".to_string(),
+        }];
+        assert_eq!(deserialize_drafts(&serialize_drafts(&drafts)), drafts);
+    }
+
+    #[test]
+    fn test_deserialize_corrupt_or_partial() {
+        assert!(deserialize_drafts("not json").is_empty());
+        assert!(deserialize_drafts("").is_empty());
+        let partial = deserialize_drafts(r#"[{"project":"p","context":"c","body":"hi"}]"#);
+        assert_eq!(partial.len(), 1);
+        assert_eq!(partial[0].body, "hi");
+        assert!(partial[0].recipients.is_empty());
+    }
+
+    #[test]
+    fn test_upsert_replaces_and_moves_to_front() {
+        let drafts = vec![draft("a", "1"), draft("b", "2")];
+        let drafts = upsert_draft(drafts, draft("b", "updated"), MAX_DRAFTS);
+        assert_eq!(drafts.len(), 2);
+        assert_eq!(drafts[0].context, "b");
+        assert_eq!(drafts[0].body, "updated");
+        assert_eq!(drafts[1].context, "a");
+    }
+
+    #[test]
+    fn test_upsert_evicts_least_recent() {
+        let mut drafts = Vec::new();
+        for i in 0..(MAX_DRAFTS + 3) {
+            drafts = upsert_draft(drafts, draft(&format!("c{}", i), "x"), MAX_DRAFTS);
+        }
+        assert_eq!(drafts.len(), MAX_DRAFTS);
+        assert_eq!(drafts[0].context, format!("c{}", MAX_DRAFTS + 2));
+        assert!(find_draft(&drafts, "proj", "c0").is_none());
+        assert!(find_draft(&drafts, "proj", "c3").is_some());
+    }
+
+    #[test]
+    fn test_keys_separate_projects_and_contexts() {
+        let mut other = draft("a", "other project");
+        other.project = "other".to_string();
+        let drafts = vec![draft("a", "mine"), other];
+        assert_eq!(
+            find_draft(&drafts, "proj", "a").map(|d| d.body),
+            Some("mine".to_string())
+        );
+        let drafts = remove_draft(drafts, "proj", "a");
+        assert_eq!(drafts.len(), 1);
+        assert_eq!(drafts[0].project, "other");
+    }
+
+    #[test]
+    fn test_is_blank() {
+        assert!(draft("a", "  \n").is_blank());
+        assert!(!draft("a", "text").is_blank());
+        let mut with_recipient = draft("a", "");
+        with_recipient.recipients.push("BlueLake".to_string());
+        assert!(!with_recipient.is_blank());
+    }
+}
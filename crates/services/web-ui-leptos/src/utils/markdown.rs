@@ -0,0 +1,308 @@
+//! Markdown rendering for message bodies.
+//!
+//! Converts `body_md` to HTML with pulldown-cmark and sanitizes the event
+//! stream before it is serialized:
+//! - raw HTML (blocks and inline tags) is escaped and shown as text
+//! - link and image targets are limited to http(s), mailto and relative URLs
+//! - links open with `rel="noopener noreferrer"`; images become links
+//! - code spans and blocks get the theme's monospace classes
+//!
+//! The output is safe to pass to `inner_html`.
+
+use pulldown_cmark::{CowStr, Event, LinkType, Options, Parser, Tag, TagEnd, html};
+
+/// Classes for fenced and indented code blocks.
+const CODE_BLOCK_CLASS: &str = "my-3 overflow-x-auto rounded-md bg-charcoal-100 dark:bg-charcoal-800 p-3 font-mono text-xs text-charcoal-800 dark:text-cream-100";
+
+/// Classes for inline code spans.
+const INLINE_CODE_CLASS: &str =
+    "rounded bg-charcoal-100 dark:bg-charcoal-800 px-1 py-0.5 font-mono text-[0.85em]";
+
+/// Classes for links.
+const LINK_CLASS: &str = "text-amber-600 dark:text-amber-400 underline underline-offset-2";
+
+/// Classes for search highlights (matches `HighlightedText`).
+const MARK_CLASS: &str = "bg-yellow-200 dark:bg-yellow-800 px-0.5 rounded";
+
+/// Render Markdown to sanitized HTML.
+pub fn render_markdown(md: &str) -> String {
+    let mut out = String::with_capacity(md.len() * 3 / 2);
+    html::push_html(&mut out, parse(md).map(sanitize));
+    out
+}
+
+/// Render Markdown to sanitized HTML with `query` wrapped in `<mark>`.
+///
+/// Highlighting runs on the already-sanitized event stream and only touches
+/// text, so a query can never inject markup or alter URLs. Matching is
+/// ASCII case-insensitive.
+pub fn render_markdown_highlighted(md: &str, query: &str) -> String {
+    let query = query.trim();
+    if query.is_empty() {
+        return render_markdown(md);
+    }
+    let mut out = String::with_capacity(md.len() * 3 / 2);
+    html::push_html(
+        &mut out,
+        parse(md)
+            .map(sanitize)
+            .flat_map(|event| highlight(event, query)),
+    );
+    out
+}
+
+/// Whether `url` may be used as a link target.
+///
+/// Allows http, https, mailto and scheme-less (relative or fragment) URLs.
+/// Whitespace and control characters are ignored when detecting the scheme,
+/// matching how browsers parse `java\tscript:` and similar tricks.
+pub fn is_safe_url(url: &str) -> bool {
+    let cleaned: String = url
+        .chars()
+        .filter(|c| !c.is_ascii_control() && !c.is_whitespace())
+        .collect::<String>()
+        .to_ascii_lowercase();
+    match cleaned.find(':') {
+        None => true,
+        // A colon after a path, query or fragment delimiter is not a scheme
+        Some(colon) if cleaned[..colon].contains(['/', '?', '#']) => true,
+        Some(colon) => matches!(&cleaned[..colon], "http" | "https" | "mailto"),
+    }
+}
+
+fn parse(md: &str) -> Parser<'_> {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+    options.insert(Options::ENABLE_TASKLISTS);
+    Parser::new_ext(md, options)
+}
+
+/// Replace every event that could emit untrusted markup.
+fn sanitize(event: Event<'_>) -> Event<'_> {
+    match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            ..
+        })
+        | Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            ..
+        }) => Event::InlineHtml(open_link(link_type, &dest_url, &title).into()),
+        Event::End(TagEnd::Link) | Event::End(TagEnd::Image) => Event::InlineHtml("</a>".into()),
+        Event::Start(Tag::CodeBlock(_)) => {
+            Event::InlineHtml(format!("<pre class=\"{}\"><code>", CODE_BLOCK_CLASS).into())
+        }
+        Event::End(TagEnd::CodeBlock) => Event::InlineHtml("</code></pre>".into()),
+        Event::Code(code) => Event::InlineHtml(
+            format!(
+                "<code class=\"{}\">{}</code>",
+                INLINE_CODE_CLASS,
+                escape_html(&code)
+            )
+            .into(),
+        ),
+        other => other,
+    }
+}
+
+fn open_link(link_type: LinkType, dest_url: &str, title: &str) -> String {
+    let href = if link_type == LinkType::Email {
+        format!("mailto:{}", dest_url)
+    } else {
+        dest_url.to_string()
+    };
+    let href = if is_safe_url(&href) {
+        href
+    } else {
+        "#".to_string()
+    };
+
+    let mut tag = format!(
+        "<a href=\"{}\" class=\"{}\" rel=\"noopener noreferrer\" target=\"_blank\"",
+        escape_html(&href),
+        LINK_CLASS
+    );
+    if !title.is_empty() {
+        tag.push_str(&format!(" title=\"{}\"", escape_html(title)));
+    }
+    tag.push('>');
+    tag
+}
+
+/// Split a text event around case-insensitive matches of `query`.
+fn highlight<'a>(event: Event<'a>, query: &str) -> Vec<Event<'a>> {
+    let Event::Text(text) = event else {
+        return vec![event];
+    };
+    let haystack = text.to_ascii_lowercase();
+    let needle = query.to_ascii_lowercase();
+
+    let mut events = Vec::new();
+    let mut last = 0;
+    for (start, _) in haystack.match_indices(&needle) {
+        let end = start + needle.len();
+        if start > last {
+            events.push(Event::Text(CowStr::from(text[last..start].to_string())));
+        }
+        events.push(Event::InlineHtml(
+            format!("<mark class=\"{}\">", MARK_CLASS).into(),
+        ));
+        events.push(Event::Text(CowStr::from(text[start..end].to_string())));
+        events.push(Event::InlineHtml("</mark>".into()));
+        last = end;
+    }
+    if last == 0 {
+        return vec![Event::Text(text)];
+    }
+    if last < text.len() {
+        events.push(Event::Text(CowStr::from(text[last..].to_string())));
+    }
+    events
+}
+
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_renders_basic_markdown() {
+        let html = render_markdown("# Title\n\n- one\n- **two**\n");
+        assert!(html.contains("<h1>Title</h1>"));
+        assert!(html.contains("<li>one</li>"));
+        assert!(html.contains("<strong>two</strong>"));
+    }
+
+    #[test]
+    fn test_code_blocks_are_styled_and_escaped() {
+        let html = render_markdown("```rust\nlet x = \"<b>\";\n```\n\nUse `<i>` here");
+        assert!(html.contains(&format!("<pre class=\"{}\"><code>", CODE_BLOCK_CLASS)));
+        assert!(html.contains("&lt;b&gt;"));
+        assert!(html.contains(&format!(
+            "<code class=\"{}\">&lt;i&gt;</code>",
+            INLINE_CODE_CLASS
+        )));
+        assert!(!html.contains("<b>"));
+        assert!(!html.contains("<i>"));
+    }
+
+    #[test]
+    fn test_script_tags_are_escaped() {
+        let html = render_markdown("<script>alert(1)</script>\n\nhi <script>alert(2)</script>");
+        assert!(!html.contains("<script"));
+        assert!(html.contains("&lt;script&gt;"));
+    }
+
+    #[test]
+    fn test_event_handler_attributes_are_escaped() {
+        let html = render_markdown("<img src=x onerror=alert(1)>\n\ntext <svg onload=alert(1)>");
+        assert!(!html.contains("<img"));
+        assert!(!html.contains("<svg"));
+        assert!(html.contains("&lt;img src=x onerror=alert(1)&gt;"));
+    }
+
+    #[test]
+    fn test_javascript_links_are_neutralized() {
+        for md in [
+            "[x](javascript:alert(1))",
+            "[x](JaVaScRiPt:alert(1))",
+            "[x](data:text/html;base64,PHNjcmlwdD4=)",
+            "[x](vbscript:msgbox)",
+            "<javascript:alert(1)>",
+        ] {
+            let html = render_markdown(md);
+            assert_eq!(
+                html.matches("href=\"").count(),
+                html.matches("href=\"#\"").count(),
+                "{} rendered {}",
+                md,
+                html
+            );
+            assert!(html.contains("href=\"#\""), "{} rendered {}", md, html);
+        }
+    }
+
+    #[test]
+    fn test_images_become_safe_links() {
+        let html = render_markdown("![alt text](javascript:alert(1))");
+        assert!(!html.contains("<img"));
+        assert!(html.contains("href=\"#\""));
+        assert!(html.contains("alt text</a>"));
+    }
+
+    #[test]
+    fn test_links_get_noopener_and_escaped_title() {
+        let html =
+            render_markdown("[site](https://example.com \"a\\\" onmouseover=\\\"alert(1)\")");
+        assert!(html.contains("href=\"https://example.com\""));
+        assert!(html.contains("rel=\"noopener noreferrer\""));
+        assert!(html.contains("&quot; onmouseover=&quot;"));
+        assert!(!html.contains("\" onmouseover=\""));
+    }
+
+    #[test]
+    fn test_email_autolinks_use_mailto() {
+        let html = render_markdown("<agent@example.com>");
+        assert!(html.contains("href=\"mailto:agent@example.com\""));
+    }
+
+    #[test]
+    fn test_is_safe_url() {
+        assert!(is_safe_url("https://example.com"));
+        assert!(is_safe_url("http://example.com/a:b"));
+        assert!(is_safe_url("mailto:a@b.c"));
+        assert!(is_safe_url("/inbox/1?project=x"));
+        assert!(is_safe_url("#section"));
+        assert!(is_safe_url("docs/page:1"));
+        assert!(!is_safe_url("javascript:alert(1)"));
+        assert!(!is_safe_url(" java\tscript:alert(1)"));
+        assert!(!is_safe_url("java\nscript:alert(1)"));
+        assert!(!is_safe_url("\u{1}javascript:alert(1)"));
+        assert!(!is_safe_url("data:text/html,x"));
+    }
+
+    #[test]
+    fn test_highlight_wraps_text_matches() {
+        let html = render_markdown_highlighted("Deploy the **deploy** script", "deploy");
+        assert_eq!(html.matches("<mark").count(), 2);
+        assert!(html.contains(&format!("<mark class=\"{}\">Deploy</mark>", MARK_CLASS)));
+    }
+
+    #[test]
+    fn test_highlight_does_not_touch_urls_or_markup() {
+        let html = render_markdown_highlighted("[test](https://test.example)", "test");
+        assert!(html.contains("href=\"https://test.example\""));
+        assert_eq!(html.matches("<mark").count(), 1);
+    }
+
+    #[test]
+    fn test_highlight_runs_after_sanitization() {
+        let html = render_markdown_highlighted("<script>alert(1)</script>", "script");
+        assert!(!html.contains("<script"));
+        assert!(html.contains("&lt;<mark"));
+
+        let html = render_markdown_highlighted("plain text", "\"><img src=x onerror=alert(1)>");
+        assert!(!html.contains("<img"));
+        assert!(!html.contains("<mark"));
+    }
+}
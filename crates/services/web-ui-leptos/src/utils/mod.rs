@@ -1,5 +1,14 @@
 //! Utility modules for web-ui-leptos.
 
+pub mod drafts;
+pub mod markdown;
+pub mod permalink;
+pub mod templates;
+pub mod time;
 pub mod validation;
 
+pub use drafts::*;
+pub use markdown::*;
+pub use templates::*;
+pub use time::*;
 pub use validation::*;
@@ -0,0 +1,143 @@
+//! Canonical message and thread links.
+//!
+//! Messages live at `/p/{slug}/m/{id}` and threads at `/p/{slug}/t/{id}`,
+//! matching the links the server puts in `permalink`. The older
+//! `/inbox/{id}?project=…` and `/thread/{id}?project=…` routes redirect here.
+
+use leptos_router::{PossibleRouteMatch, path};
+
+/// Router path of a message permalink.
+pub fn message_route() -> impl PossibleRouteMatch + Clone + Send + 'static {
+    path!("p/:slug/m/:id")
+}
+
+/// Router path of a thread permalink.
+pub fn thread_route() -> impl PossibleRouteMatch + Clone + Send + 'static {
+    path!("p/:slug/t/:id")
+}
+
+/// Path of a message's permalink.
+pub fn message_path(project_slug: &str, message_id: impl std::fmt::Display) -> String {
+    format!(
+        "/p/{}/m/{}",
+        urlencoding::encode(project_slug),
+        urlencoding::encode(&message_id.to_string())
+    )
+}
+
+/// Path of a thread's permalink.
+pub fn thread_path(project_slug: &str, thread_id: &str) -> String {
+    format!(
+        "/p/{}/t/{}",
+        urlencoding::encode(project_slug),
+        urlencoding::encode(thread_id)
+    )
+}
+
+/// Permalink for a legacy `/inbox/{id}` link, keeping its `agent`; `None`
+/// without a project, which the old route still serves itself.
+pub fn legacy_message_redirect(message_id: &str, project: &str, agent: &str) -> Option<String> {
+    if project.is_empty() || message_id.is_empty() {
+        return None;
+    }
+    let path = message_path(project, message_id);
+    Some(if agent.is_empty() {
+        path
+    } else {
+        format!("{}?agent={}", path, urlencoding::encode(agent))
+    })
+}
+
+/// Permalink for a legacy `/thread/{id}` link; `None` without a project.
+pub fn legacy_thread_redirect(thread_id: &str, project: &str) -> Option<String> {
+    (!project.is_empty() && !thread_id.is_empty()).then(|| thread_path(project, thread_id))
+}
+
+/// Absolute URL to share: the server's `permalink` when it is already
+/// absolute, otherwise the path under this page's origin.
+pub fn share_url(permalink: Option<&str>, path: &str, origin: &str) -> String {
+    match permalink {
+        Some(link) if link.starts_with("http://") || link.starts_with("https://") => {
+            link.to_string()
+        }
+        Some(link) if link.starts_with('/') => format!("{}{}", origin, link),
+        _ => format!("{}{}", origin, path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route_params(route: impl PossibleRouteMatch, path: &str) -> Option<Vec<(String, String)>> {
+        let matched = route.test(path)?;
+        if !matched.remaining().is_empty() {
+            return None;
+        }
+        Some(
+            matched
+                .params()
+                .into_iter()
+                .map(|(k, v)| (k.to_string(), v))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_message_route_matches_permalink() {
+        let params = route_params(message_route(), &message_path("backend", 42)).unwrap();
+        assert_eq!(
+            params,
+            vec![
+                ("slug".to_string(), "backend".to_string()),
+                ("id".to_string(), "42".to_string())
+            ]
+        );
+        assert!(route_params(message_route(), "/p/backend/t/42").is_none());
+        assert!(route_params(message_route(), "/inbox/42").is_none());
+    }
+
+    #[test]
+    fn test_thread_route_matches_encoded_id() {
+        let path = thread_path("backend", "review/auth flow");
+        assert_eq!(path, "/p/backend/t/review%2Fauth%20flow");
+        let params = route_params(thread_route(), &path).unwrap();
+        assert_eq!(params[0].1, "backend");
+        assert!(route_params(thread_route(), "/p/backend/m/1").is_none());
+    }
+
+    #[test]
+    fn test_legacy_redirects() {
+        assert_eq!(
+            legacy_message_redirect("7", "backend", "BlueLake").as_deref(),
+            Some("/p/backend/m/7?agent=BlueLake")
+        );
+        assert_eq!(
+            legacy_message_redirect("7", "backend", "").as_deref(),
+            Some("/p/backend/m/7")
+        );
+        assert_eq!(legacy_message_redirect("7", "", "BlueLake"), None);
+        assert_eq!(
+            legacy_thread_redirect("TKT-1", "my project").as_deref(),
+            Some("/p/my%20project/t/TKT-1")
+        );
+        assert_eq!(legacy_thread_redirect("TKT-1", ""), None);
+    }
+
+    #[test]
+    fn test_share_url() {
+        let origin = "http://localhost:8765";
+        assert_eq!(
+            share_url(Some("https://mail.example.com/p/a/m/1"), "/p/a/m/1", origin),
+            "https://mail.example.com/p/a/m/1"
+        );
+        assert_eq!(
+            share_url(Some("/p/a/m/1"), "/ignored", origin),
+            "http://localhost:8765/p/a/m/1"
+        );
+        assert_eq!(
+            share_url(None, "/p/a/t/T-1", origin),
+            "http://localhost:8765/p/a/t/T-1"
+        );
+    }
+}
@@ -0,0 +1,74 @@
+//! Placeholder substitution for message templates.
+//!
+//! Templates are stored with `{{agent_name}}` and `{{date}}` left in place;
+//! the composer fills them in right before sending so the text stays
+//! editable until then.
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    type Date;
+
+    #[wasm_bindgen(constructor)]
+    fn new() -> Date;
+
+    #[wasm_bindgen(method, js_name = toISOString)]
+    fn to_iso_string(this: &Date) -> String;
+}
+
+/// Today's date as `YYYY-MM-DD` (UTC), for the `{{date}}` placeholder.
+pub fn today() -> String {
+    Date::new().to_iso_string().chars().take(10).collect()
+}
+
+/// Label used for `{{agent_name}}`: the recipients joined with ", ", or
+/// "all agents" for a broadcast.
+pub fn agent_name_label(recipients: &[String], broadcast: bool) -> String {
+    if broadcast {
+        "all agents".to_string()
+    } else {
+        recipients.join(", ")
+    }
+}
+
+/// Replace `{{agent_name}}` and `{{date}}` in `text`.
+pub fn fill_placeholders(text: &str, agent_name: &str, date: &str) -> String {
+    text.replace("{{agent_name}}", agent_name)
+        .replace("{{date}}", date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_placeholders() {
+        assert_eq!(
+            fill_placeholders(
+                "STATUS REPORT for {{agent_name}} ({{date}}) - {{agent_name}}",
+                "BlueLake",
+                "2025-01-31"
+            ),
+            "STATUS REPORT for BlueLake (2025-01-31) - BlueLake"
+        );
+    }
+
+    #[test]
+    fn test_fill_placeholders_leaves_other_text() {
+        assert_eq!(
+            fill_placeholders("STOP {{unknown}} {agent_name}", "BlueLake", "2025-01-31"),
+            "STOP {{unknown}} {agent_name}"
+        );
+    }
+
+    #[test]
+    fn test_agent_name_label() {
+        let recipients = vec!["BlueLake".to_string(), "GreenCastle".to_string()];
+        assert_eq!(
+            agent_name_label(&recipients, false),
+            "BlueLake, GreenCastle"
+        );
+        assert_eq!(agent_name_label(&recipients, true), "all agents");
+    }
+}
@@ -0,0 +1,263 @@
+//! Timestamp helpers backed by the browser clock.
+
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    type Date;
+
+    #[wasm_bindgen(static_method_of = Date)]
+    fn now() -> f64;
+
+    #[wasm_bindgen(static_method_of = Date)]
+    fn parse(s: &str) -> f64;
+
+    #[wasm_bindgen(constructor)]
+    fn from_ms(ms: f64) -> Date;
+
+    /// Local midnight; out-of-range days roll over into the next month.
+    #[wasm_bindgen(constructor)]
+    fn local_midnight(year: i32, month_index: i32, day: i32) -> Date;
+
+    /// Local time on the hour; out-of-range days roll over like `local_midnight`.
+    #[wasm_bindgen(constructor)]
+    fn local_hour(year: i32, month_index: i32, day: i32, hours: i32) -> Date;
+
+    #[wasm_bindgen(method, js_name = getFullYear)]
+    fn full_year(this: &Date) -> i32;
+
+    #[wasm_bindgen(method, js_name = getMonth)]
+    fn month_index(this: &Date) -> u32;
+
+    #[wasm_bindgen(method, js_name = getDate)]
+    fn day(this: &Date) -> u32;
+
+    #[wasm_bindgen(method, js_name = getHours)]
+    fn hours(this: &Date) -> u32;
+
+    #[wasm_bindgen(method, js_name = getMinutes)]
+    fn minutes(this: &Date) -> u32;
+
+    #[wasm_bindgen(method, js_name = getSeconds)]
+    fn seconds(this: &Date) -> u32;
+
+    #[wasm_bindgen(method, js_name = getTimezoneOffset)]
+    fn timezone_offset(this: &Date) -> f64;
+
+    #[wasm_bindgen(method, js_name = getUTCFullYear)]
+    fn utc_full_year(this: &Date) -> i32;
+
+    #[wasm_bindgen(method, js_name = getUTCMonth)]
+    fn utc_month_index(this: &Date) -> u32;
+
+    #[wasm_bindgen(method, js_name = getUTCDate)]
+    fn utc_day(this: &Date) -> u32;
+
+    #[wasm_bindgen(method, js_name = getUTCHours)]
+    fn utc_hours(this: &Date) -> u32;
+
+    #[wasm_bindgen(method, js_name = getUTCMinutes)]
+    fn utc_minutes(this: &Date) -> u32;
+
+    #[wasm_bindgen(method, js_name = getUTCSeconds)]
+    fn utc_seconds(this: &Date) -> u32;
+}
+
+/// Seconds elapsed since a server timestamp such as `2025-10-26T10:30:00`.
+///
+/// Server timestamps are UTC without an offset, so one is added before
+/// parsing. Returns `None` if the timestamp can't be parsed.
+pub fn seconds_since(ts: &str) -> Option<i64> {
+    let parsed = Date::parse(&utc_timestamp(ts));
+    if parsed.is_nan() {
+        return None;
+    }
+    Some(((Date::now() - parsed) / 1000.0) as i64)
+}
+
+/// Mark an offset-less timestamp as UTC (`Z`) so the browser doesn't read it
+/// as local time.
+fn utc_timestamp(ts: &str) -> String {
+    let ts = ts.trim().replacen(' ', "T", 1);
+    let has_offset = ts.ends_with('Z') || ts.rfind(['+', '-']).is_some_and(|i| i > 10);
+    if has_offset { ts } else { format!("{}Z", ts) }
+}
+
+/// The browser's local time `hours` ago, as RFC 3339 with its UTC offset.
+pub fn local_hours_ago(hours: f64) -> String {
+    local_rfc3339(&Date::from_ms(Date::now() - hours * 3_600_000.0))
+}
+
+/// `hours` from now as an offset-less UTC timestamp, the form the server
+/// expects.
+pub fn utc_hours_from_now(hours: f64) -> String {
+    utc_naive(&Date::from_ms(Date::now() + hours * 3_600_000.0))
+}
+
+/// `hour`:00 local time tomorrow as an offset-less UTC timestamp.
+pub fn utc_tomorrow_at(hour: i32) -> String {
+    let today = Date::from_ms(Date::now());
+    utc_naive(&Date::local_hour(
+        today.full_year(),
+        today.month_index() as i32,
+        today.day() as i32 + 1,
+        hour,
+    ))
+}
+
+/// Today's local date as `YYYY-MM-DD`.
+pub fn local_today() -> String {
+    local_hours_ago(0.0)[..10].to_string()
+}
+
+/// Local midnight `days` after the start of `date` (`YYYY-MM-DD`), as RFC
+/// 3339 with the UTC offset in effect on that day.
+pub fn local_day_start(date: &str, days: i32) -> Option<String> {
+    let (year, month, day) = parse_date(date)?;
+    Some(local_rfc3339(&Date::local_midnight(
+        year,
+        month as i32 - 1,
+        day as i32 + days,
+    )))
+}
+
+/// The calendar day before `date` (`YYYY-MM-DD`).
+pub fn previous_day(date: &str) -> Option<String> {
+    let (mut year, mut month, mut day) = parse_date(date)?;
+    if day > 1 {
+        day -= 1;
+    } else {
+        if month == 1 {
+            year -= 1;
+            month = 12;
+        } else {
+            month -= 1;
+        }
+        day = days_in_month(year, month);
+    }
+    Some(format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+fn local_rfc3339(date: &Date) -> String {
+    format_rfc3339(
+        (date.full_year(), date.month_index() + 1, date.day()),
+        (date.hours(), date.minutes(), date.seconds()),
+        // getTimezoneOffset() is UTC minus local time
+        -(date.timezone_offset() as i32),
+    )
+}
+
+fn utc_naive(date: &Date) -> String {
+    format_naive(
+        (
+            date.utc_full_year(),
+            date.utc_month_index() + 1,
+            date.utc_day(),
+        ),
+        (date.utc_hours(), date.utc_minutes(), date.utc_seconds()),
+    )
+}
+
+/// Format a wall-clock time with an explicit offset (minutes east of UTC).
+fn format_rfc3339(date: (i32, u32, u32), time: (u32, u32, u32), offset_minutes: i32) -> String {
+    let sign = if offset_minutes < 0 { '-' } else { '+' };
+    let offset = offset_minutes.unsigned_abs();
+    format!(
+        "{}{}{:02}:{:02}",
+        format_naive(date, time),
+        sign,
+        offset / 60,
+        offset % 60
+    )
+}
+
+/// Format a wall-clock time as `YYYY-MM-DDTHH:MM:SS`.
+fn format_naive(
+    (year, month, day): (i32, u32, u32),
+    (hour, minute, second): (u32, u32, u32),
+) -> String {
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+        year, month, day, hour, minute, second
+    )
+}
+
+fn parse_date(date: &str) -> Option<(i32, u32, u32)> {
+    let mut parts = date.get(..10)?.splitn(3, '-');
+    let year = parts.next()?.parse().ok()?;
+    let month = parts
+        .next()?
+        .parse()
+        .ok()
+        .filter(|m| (1..=12).contains(m))?;
+    let day = parts.next()?.parse().ok().filter(|d| *d >= 1)?;
+    (day <= days_in_month(year, month)).then_some((year, month, day))
+}
+
+fn days_in_month(year: i32, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utc_timestamp() {
+        assert_eq!(utc_timestamp("2025-10-26T10:30:00"), "2025-10-26T10:30:00Z");
+        assert_eq!(utc_timestamp("2025-10-26 10:30:00"), "2025-10-26T10:30:00Z");
+        assert_eq!(
+            utc_timestamp("2025-10-26T10:30:00Z"),
+            "2025-10-26T10:30:00Z"
+        );
+        assert_eq!(
+            utc_timestamp("2025-10-26T10:30:00+02:00"),
+            "2025-10-26T10:30:00+02:00"
+        );
+    }
+
+    #[test]
+    fn test_format_rfc3339_offsets() {
+        assert_eq!(
+            format_rfc3339((2026, 3, 1), (9, 5, 0), 120),
+            "2026-03-01T09:05:00+02:00"
+        );
+        assert_eq!(
+            format_rfc3339((2026, 3, 1), (0, 0, 0), -330),
+            "2026-03-01T00:00:00-05:30"
+        );
+        assert_eq!(
+            format_rfc3339((2026, 12, 31), (23, 59, 59), 0),
+            "2026-12-31T23:59:59+00:00"
+        );
+    }
+
+    #[test]
+    fn test_format_naive_pads_fields() {
+        assert_eq!(format_naive((2026, 3, 1), (9, 5, 0)), "2026-03-01T09:05:00");
+        assert_eq!(
+            format_naive((999, 12, 31), (23, 59, 59)),
+            "0999-12-31T23:59:59"
+        );
+    }
+
+    #[test]
+    fn test_previous_day() {
+        assert_eq!(previous_day("2026-03-02").as_deref(), Some("2026-03-01"));
+        assert_eq!(previous_day("2026-03-01").as_deref(), Some("2026-02-28"));
+        assert_eq!(previous_day("2024-03-01").as_deref(), Some("2024-02-29"));
+        assert_eq!(previous_day("2026-01-01").as_deref(), Some("2025-12-31"));
+        // Timestamps are accepted; only the date part is read
+        assert_eq!(
+            previous_day("2026-05-01T00:00:00+02:00").as_deref(),
+            Some("2026-04-30")
+        );
+        assert_eq!(previous_day("2026-02-30"), None);
+        assert_eq!(previous_day("yesterday"), None);
+    }
+}
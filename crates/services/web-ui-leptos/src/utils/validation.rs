@@ -124,6 +124,36 @@ pub fn validate_all(value: &str, rules: &[ValidationRule]) -> Vec<ValidationErro
         .collect()
 }
 
+/// Naming rules for agents, shown under agent name fields.
+pub const AGENT_NAME_HINT: &str = "2-64 characters: letters, digits, _ and -";
+
+/// Check an agent name against the server's naming rules.
+///
+/// Mirrors `validate_agent_name` in the core crate so forms can explain the
+/// problem before submitting; the server still enforces the rules and
+/// case-insensitive uniqueness. Surrounding whitespace is ignored, as the
+/// server trims it.
+pub fn agent_name_error(name: &str) -> Option<ValidationError> {
+    let name = name.trim();
+    if let Some(c) = name
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || *c == '_' || *c == '-'))
+    {
+        let shown = if c.is_ascii_graphic() || c == ' ' {
+            format!("'{}'", c)
+        } else {
+            format!("U+{:04X}", c as u32)
+        };
+        return Some(ValidationError::new(format!("{} is not allowed", shown)));
+    }
+    match name.len() {
+        0 => None,
+        1 => Some(ValidationError::new("Must be at least 2 characters")),
+        n if n > 64 => Some(ValidationError::new("Must be at most 64 characters")),
+        _ => None,
+    }
+}
+
 /// Field validation state for reactive forms.
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct FieldState {
@@ -622,4 +652,30 @@ mod tests {
             );
         }
     }
+
+    // ========================================================================
+    // Agent name tests
+    // ========================================================================
+
+    #[test]
+    fn test_agent_name_accepts_valid_names() {
+        assert_eq!(agent_name_error("BlueStone"), None);
+        assert_eq!(agent_name_error("cc-1_a"), None);
+        assert_eq!(agent_name_error("  Trimmed "), None);
+        assert_eq!(agent_name_error(""), None);
+    }
+
+    #[test]
+    fn test_agent_name_rejects_traversal_and_lookalikes() {
+        assert_eq!(
+            agent_name_error("../etc").unwrap().message,
+            "'.' is not allowed"
+        );
+        assert_eq!(
+            agent_name_error("\u{0430}lice").unwrap().message,
+            "U+0430 is not allowed"
+        );
+        assert!(agent_name_error("x").is_some());
+        assert!(agent_name_error(&"a".repeat(65)).is_some());
+    }
 }
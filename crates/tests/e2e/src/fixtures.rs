@@ -58,6 +58,43 @@ impl TestFixtures {
             "body_md": body
         })
     }
+
+    /// Key presses for the unified inbox split view, starting from a list of
+    /// `KEY_NAV_LIST_LEN` messages with the first one selected.
+    ///
+    /// Each step gives the index expected to be selected afterwards (`None`
+    /// once Escape clears the selection).
+    pub fn keyboard_navigation_steps() -> Vec<KeyStep> {
+        [
+            ("j", Some(1)),
+            ("ArrowDown", Some(2)),
+            ("j", Some(2)), // clamps at the end
+            ("k", Some(1)),
+            ("ArrowUp", Some(0)),
+            ("k", Some(0)), // clamps at the start
+            ("End", Some(2)),
+            ("Home", Some(0)),
+            ("Enter", Some(0)),
+            ("Escape", None),
+            ("j", Some(0)), // restarts from the top
+        ]
+        .into_iter()
+        .map(|(key, expected_index)| KeyStep {
+            key,
+            expected_index,
+        })
+        .collect()
+    }
+}
+
+/// Number of messages [`TestFixtures::keyboard_navigation_steps`] assumes.
+pub const KEY_NAV_LIST_LEN: usize = 3;
+
+/// One key press and the list index expected to be selected afterwards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyStep {
+    pub key: &'static str,
+    pub expected_index: Option<usize>,
 }
 
 /// Response from ensure_project endpoint
@@ -1,6 +1,5 @@
 #![allow(clippy::unwrap_used, clippy::expect_used)] // expect/unwrap is fine in tests
 #![allow(deprecated)] // cargo_bin is still valid for our use case
-#![allow(clippy::unwrap_used)]
 
 use assert_cmd::Command;
 use predicates::prelude::*;
@@ -45,6 +44,17 @@ fn test_schema_command() {
         .stdout(predicate::str::contains("send_message")); // Expect at least one known tool
 }
 
+#[test]
+fn test_schema_openapi_command() {
+    let mut cmd = Command::cargo_bin("mouchak-mail").unwrap();
+    cmd.args(["schema", "--format", "openapi"])
+        .env("HTTP_AUTH_MODE", "bearer")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"/api/message/send\""))
+        .stdout(predicate::str::contains("\"bearer_auth\""));
+}
+
 #[test]
 fn test_tools_command() {
     let mut cmd = Command::cargo_bin("mouchak-mail").unwrap();
@@ -491,6 +491,7 @@ async fn test_prepush_blocks_pending_reviews() {
             "subject": "[COMPLETION] task-123: Feature X",
             "body_md": "Ready for review",
             "thread_id": "TASK-123",
+            "allow_new_thread": true,
             "importance": "high",
             "ack_required": true
         }))
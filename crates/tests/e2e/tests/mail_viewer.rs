@@ -7,8 +7,9 @@
 //! - Data Binding (6 tests)
 //! - Thread Navigation (3 tests)
 //! - Compose/Reply (3 tests)
+//! - Keyboard Navigation (2 tests)
 //!
-//! Total: 26 tests
+//! Total: 28 tests
 //!
 //! Prerequisites:
 //! - Web UI running: `cd crates/services/web-ui && bun run dev`
@@ -23,6 +24,7 @@
 
 #![allow(clippy::unwrap_used, clippy::expect_used)] // expect/unwrap is fine in tests
 
+use e2e_tests::fixtures::KEY_NAV_LIST_LEN;
 use e2e_tests::{TestConfig, TestFixtures};
 use jugar_probar::Assertion;
 
@@ -76,6 +78,12 @@ mod locators {
         pub(crate) const THREAD_NAV_PREV: &str = "[data-testid='thread-nav-prev']";
         pub(crate) const THREAD_NAV_NEXT: &str = "[data-testid='thread-nav-next']";
     }
+
+    pub(crate) mod split_view {
+        pub(crate) const MESSAGE_LIST: &str = "[role='listbox'][aria-label='Message list']";
+        pub(crate) const SELECTED_ROW: &str = "[role='option'][aria-selected='true']";
+        pub(crate) const FILTER_SEARCH: &str = "#filterSearch";
+    }
 }
 
 // ============================================================================
@@ -608,3 +616,71 @@ fn test_config_for_mail_viewer_tests() {
 
     println!("✓ Mail viewer URL configuration validated");
 }
+
+// ============================================================================
+// KEYBOARD NAVIGATION TESTS (2 tests)
+// ============================================================================
+
+/// Test K-001: j/k, arrows, Home/End, Enter and Escape drive the selection
+#[test]
+fn test_split_view_keyboard_navigation_steps() {
+    let steps = TestFixtures::keyboard_navigation_steps();
+
+    for key in [
+        "j",
+        "k",
+        "ArrowDown",
+        "ArrowUp",
+        "Home",
+        "End",
+        "Enter",
+        "Escape",
+    ] {
+        assert!(
+            steps.iter().any(|step| step.key == key),
+            "Key {} should be exercised",
+            key
+        );
+    }
+
+    // Selection always stays inside the list, and moves at most one row for j/k
+    let mut previous = Some(0);
+    for step in &steps {
+        if let Some(idx) = step.expected_index {
+            assert!(idx < KEY_NAV_LIST_LEN, "{} selects past the list", step.key);
+            if let (Some(prev), "j" | "k" | "ArrowDown" | "ArrowUp") = (previous, step.key) {
+                assert!(
+                    idx.abs_diff(prev) <= 1,
+                    "{} moved more than one row",
+                    step.key
+                );
+            }
+        }
+        previous = step.expected_index;
+    }
+    assert_eq!(steps.last().unwrap().expected_index, Some(0));
+
+    let list = Assertion::is_true(
+        locators::split_view::MESSAGE_LIST.contains("listbox"),
+        "Message list uses listbox role",
+    );
+    assert!(list.passed);
+    let selected = Assertion::is_true(
+        locators::split_view::SELECTED_ROW.contains("aria-selected"),
+        "Selected row is marked with aria-selected",
+    );
+    assert!(selected.passed);
+
+    println!("✓ Split view keyboard navigation steps validated");
+}
+
+/// Test K-002: keys typed into the filter search box don't move the selection
+#[test]
+fn test_split_view_keys_ignored_in_inputs() {
+    // The guard keys off the focused element, so the locator must hit the input itself
+    let search = locators::split_view::FILTER_SEARCH;
+    let valid = Assertion::is_true(search.starts_with('#'), "Search box is an ID selector");
+    assert!(valid.passed);
+
+    println!("✓ Split view input focus guard locator validated");
+}
@@ -1125,7 +1125,7 @@ async fn test_agent_reconnect_preserves_inbox() {
     let inbox_before = fetch_inbox(&client, &config, &project.slug, reconnect_agent).await;
     let before_count = inbox_before.map(|m| m.len()).unwrap_or(0);
 
-    // Agent already exists (API returns CONFLICT on re-register, which is expected behavior).
+    // Re-registering updates the existing agent (pass strict=true to get CONFLICT instead).
     // The important thing is that the inbox remains accessible.
 
     // Check inbox after reconnect
@@ -0,0 +1,7 @@
+-- Index for per-agent unread counts
+-- Partial index keeps only unread rows, so GROUP BY agent_id scans
+-- exactly the rows being counted even with 100k+ messages.
+
+CREATE INDEX IF NOT EXISTS idx_message_recipients_unread
+    ON message_recipients(agent_id, message_id)
+    WHERE read_ts IS NULL;
@@ -0,0 +1,6 @@
+-- Index for per-agent outbox listing
+-- Covers WHERE project_id = ? AND sender_id = ? ORDER BY created_ts DESC, id DESC
+-- so paging through a busy sender's outbox never scans other agents' mail.
+
+CREATE INDEX IF NOT EXISTS idx_messages_project_sender_created
+    ON messages(project_id, sender_id, created_ts DESC, id DESC);
@@ -0,0 +1,27 @@
+-- Message recall (tombstones)
+-- A recalled message keeps its original row for the audit trail; reads go
+-- through visible_messages, which swaps in the recall reason for the body.
+
+CREATE TABLE IF NOT EXISTS message_recalls (
+    message_id INTEGER PRIMARY KEY,
+    recalled_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    recall_reason TEXT NOT NULL,
+    FOREIGN KEY (message_id) REFERENCES messages(id)
+);
+
+CREATE VIEW IF NOT EXISTS visible_messages AS
+SELECT
+    m.id,
+    m.project_id,
+    m.sender_id,
+    m.thread_id,
+    CASE WHEN r.message_id IS NULL THEN m.subject ELSE '[Recalled] ' || m.subject END AS subject,
+    CASE WHEN r.message_id IS NULL THEN m.body_md ELSE r.recall_reason END AS body_md,
+    m.importance,
+    m.ack_required,
+    m.created_ts,
+    CASE WHEN r.message_id IS NULL THEN m.attachments ELSE '[]' END AS attachments,
+    r.recalled_ts,
+    r.recall_reason
+FROM messages AS m
+LEFT JOIN message_recalls AS r ON r.message_id = m.id;
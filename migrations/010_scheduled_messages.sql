@@ -0,0 +1,40 @@
+-- Scheduled (delayed) message delivery
+-- A message sent with a future deliver_at gets a row here in 'scheduled'
+-- state. The server's scheduler flips due rows to 'delivered'; until then
+-- the message stays out of visible_messages and every inbox query.
+
+CREATE TABLE IF NOT EXISTS message_schedules (
+    message_id INTEGER PRIMARY KEY,
+    deliver_at DATETIME NOT NULL,
+    status TEXT NOT NULL DEFAULT 'scheduled'
+        CHECK (status IN ('scheduled', 'delivered', 'cancelled')),
+    delivered_ts DATETIME,
+    FOREIGN KEY (message_id) REFERENCES messages(id)
+);
+
+-- Scheduler scan: WHERE status = 'scheduled' AND deliver_at <= now
+CREATE INDEX IF NOT EXISTS idx_message_schedules_due
+    ON message_schedules(status, deliver_at);
+
+-- Rebuild visible_messages (from 009) so undelivered messages are hidden.
+-- Dropping first keeps this idempotent across restarts.
+DROP VIEW IF EXISTS visible_messages;
+
+CREATE VIEW visible_messages AS
+SELECT
+    m.id,
+    m.project_id,
+    m.sender_id,
+    m.thread_id,
+    CASE WHEN r.message_id IS NULL THEN m.subject ELSE '[Recalled] ' || m.subject END AS subject,
+    CASE WHEN r.message_id IS NULL THEN m.body_md ELSE r.recall_reason END AS body_md,
+    m.importance,
+    m.ack_required,
+    m.created_ts,
+    CASE WHEN r.message_id IS NULL THEN m.attachments ELSE '[]' END AS attachments,
+    r.recalled_ts,
+    r.recall_reason
+FROM messages AS m
+LEFT JOIN message_recalls AS r ON r.message_id = m.id
+LEFT JOIN message_schedules AS s ON s.message_id = m.id
+WHERE s.message_id IS NULL OR s.status = 'delivered';
@@ -0,0 +1,10 @@
+-- Agent retirement
+-- A retired agent keeps its agents row, so messages it sent or received stay
+-- attributed to it, but it drops out of default listings, broadcast fan-out
+-- and capability checks.
+
+CREATE TABLE IF NOT EXISTS agent_retirements (
+    agent_id INTEGER PRIMARY KEY,
+    retired_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (agent_id) REFERENCES agents(id)
+);
@@ -0,0 +1,9 @@
+-- Indexes for the filtered unified inbox
+-- Covers ORDER BY created_ts DESC, id DESC across all projects, optionally
+-- narrowed by importance, so filtered pages don't sort the whole table.
+
+CREATE INDEX IF NOT EXISTS idx_messages_created
+    ON messages(created_ts DESC, id DESC);
+
+CREATE INDEX IF NOT EXISTS idx_messages_importance_created
+    ON messages(importance, created_ts DESC, id DESC);
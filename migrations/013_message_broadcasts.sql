@@ -0,0 +1,9 @@
+-- Broadcast messages
+-- A message sent with broadcast set is fanned out to every active agent at
+-- send time and flagged here, so agents that register later can still find
+-- the project's earlier announcements.
+
+CREATE TABLE IF NOT EXISTS message_broadcasts (
+    message_id INTEGER PRIMARY KEY,
+    FOREIGN KEY (message_id) REFERENCES messages(id)
+);
@@ -0,0 +1,16 @@
+-- Message templates (canned directives)
+-- Per-project subject/body presets the overseer composer can pre-fill from.
+-- {{agent_name}} and {{date}} placeholders are substituted client-side.
+
+CREATE TABLE IF NOT EXISTS message_templates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project_id INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    body_md TEXT NOT NULL,
+    importance TEXT NOT NULL DEFAULT 'normal',
+    ack_required INTEGER NOT NULL DEFAULT 0,
+    created_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_id) REFERENCES projects(id),
+    UNIQUE (project_id, name)
+);
@@ -0,0 +1,8 @@
+-- Thread listing
+-- MessageBmc::list_threads groups a project's messages by thread_id and
+-- takes MAX(created_ts) and COUNT(DISTINCT sender_id). This index covers
+-- that aggregate so listing threads stays an index scan on projects with
+-- tens of thousands of messages.
+
+CREATE INDEX IF NOT EXISTS idx_messages_project_thread_created
+    ON messages(project_id, thread_id, created_ts, sender_id);
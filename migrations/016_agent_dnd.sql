@@ -0,0 +1,27 @@
+-- Agent "do not disturb" settings and message deferral
+-- While an agent's dnd_until is in the future, messages sent to it below
+-- min_importance (default: everything but urgent) get a deferral row here.
+-- The message and recipient rows are written as usual; the inbox just skips
+-- them until deferred_until passes. Urgent and ack_required messages are
+-- never deferred.
+
+CREATE TABLE IF NOT EXISTS agent_settings (
+    agent_id INTEGER PRIMARY KEY,
+    dnd_until DATETIME,
+    min_importance TEXT,
+    updated_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (agent_id) REFERENCES agents(id)
+);
+
+CREATE TABLE IF NOT EXISTS message_deferrals (
+    message_id INTEGER NOT NULL,
+    agent_id INTEGER NOT NULL,
+    deferred_until DATETIME NOT NULL,
+    PRIMARY KEY (message_id, agent_id),
+    FOREIGN KEY (message_id) REFERENCES messages(id),
+    FOREIGN KEY (agent_id) REFERENCES agents(id)
+);
+
+-- Inbox filter and policy updates look deferrals up by agent
+CREATE INDEX IF NOT EXISTS idx_message_deferrals_agent
+    ON message_deferrals(agent_id, deferred_until);
@@ -0,0 +1,26 @@
+-- Message labels ("needs-review", "blocked", "done", ...)
+-- Labels are defined per project; names are unique per project ignoring
+-- case. Deleting a label removes its message_labels rows, never messages.
+
+CREATE TABLE IF NOT EXISTS labels (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project_id INTEGER NOT NULL,
+    name TEXT NOT NULL COLLATE NOCASE,
+    color TEXT NOT NULL DEFAULT '#6b7280',
+    created_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_id) REFERENCES projects(id),
+    UNIQUE (project_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS message_labels (
+    message_id INTEGER NOT NULL,
+    label_id INTEGER NOT NULL,
+    created_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (message_id, label_id),
+    FOREIGN KEY (message_id) REFERENCES messages(id),
+    FOREIGN KEY (label_id) REFERENCES labels(id) ON DELETE CASCADE
+);
+
+-- Label filters look messages up by label
+CREATE INDEX IF NOT EXISTS idx_message_labels_label
+    ON message_labels(label_id, message_id);
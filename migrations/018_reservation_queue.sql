@@ -0,0 +1,22 @@
+-- Waiting list for contended file reservations
+-- Agents that ask to wait for a path held by someone else are queued here
+-- and granted the reservation, first come first served, once it frees up.
+-- Rows are deleted when granted or when wait_until passes.
+
+CREATE TABLE IF NOT EXISTS reservation_queue (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project_id INTEGER NOT NULL,
+    agent_id INTEGER NOT NULL,
+    path_pattern TEXT NOT NULL,
+    exclusive INTEGER NOT NULL DEFAULT 1,
+    reason TEXT NOT NULL DEFAULT '',
+    ttl_seconds INTEGER NOT NULL,
+    created_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    wait_until DATETIME NOT NULL,
+    FOREIGN KEY (project_id) REFERENCES projects(id),
+    FOREIGN KEY (agent_id) REFERENCES agents(id)
+);
+
+-- Promotion walks a project's queue in arrival order
+CREATE INDEX IF NOT EXISTS idx_reservation_queue_project
+    ON reservation_queue(project_id, id);
@@ -0,0 +1,21 @@
+-- Per-project message retention
+-- Messages older than retention_days are pruned from the database once their
+-- canonical archive file is confirmed; the Git archive stays the permanent
+-- record. A tombstone per pruned message lets lookups report "pruned" rather
+-- than "never existed".
+
+CREATE TABLE IF NOT EXISTS project_retention (
+    project_id INTEGER PRIMARY KEY,
+    retention_days INTEGER NOT NULL,
+    keep_unread INTEGER NOT NULL DEFAULT 1,
+    keep_ack_pending INTEGER NOT NULL DEFAULT 1,
+    updated_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_id) REFERENCES projects(id)
+);
+
+CREATE TABLE IF NOT EXISTS pruned_messages (
+    message_id INTEGER PRIMARY KEY,
+    project_id INTEGER NOT NULL,
+    pruned_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_id) REFERENCES projects(id)
+);
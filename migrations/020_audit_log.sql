@@ -0,0 +1,21 @@
+-- Audit log of administrative actions
+-- Force-releasing reservations, adopting or deleting projects, deleting or
+-- retiring agents, recalling messages and retention prunes each append a row
+-- here, written in the same transaction as the action where possible.
+-- No foreign keys: entries outlive the projects and agents they describe.
+
+CREATE TABLE IF NOT EXISTS audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project_id INTEGER NOT NULL,
+    action TEXT NOT NULL,
+    entity_type TEXT NOT NULL,
+    entity_id INTEGER NOT NULL,
+    actor TEXT,
+    user_id INTEGER NOT NULL DEFAULT 0,
+    detail TEXT NOT NULL DEFAULT '{}',
+    created_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Listing walks a project's log newest first
+CREATE INDEX IF NOT EXISTS idx_audit_log_project
+    ON audit_log(project_id, id);
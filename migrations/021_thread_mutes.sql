@@ -0,0 +1,18 @@
+-- Thread mutes
+-- An agent that mutes a thread stops seeing the thread's messages in its
+-- inbox, except urgent ones and ones that @-mention it in the subject.
+-- Messages are still delivered; unmuting brings them back.
+
+CREATE TABLE IF NOT EXISTS thread_mutes (
+    project_id INTEGER NOT NULL,
+    thread_id TEXT NOT NULL,
+    agent_id INTEGER NOT NULL,
+    muted_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (project_id, thread_id, agent_id),
+    FOREIGN KEY (project_id) REFERENCES projects(id),
+    FOREIGN KEY (agent_id) REFERENCES agents(id)
+);
+
+-- Inbox filtering looks mutes up by agent
+CREATE INDEX IF NOT EXISTS idx_thread_mutes_agent
+    ON thread_mutes(agent_id, project_id, thread_id);
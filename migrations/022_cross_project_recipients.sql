@@ -0,0 +1,19 @@
+-- Cross-project recipients
+-- A message addressed to "slug::agent-name" is stored in the sender's project
+-- and delivered to an agent of another project. Each such recipient is
+-- recorded here with its own project, so that project's inbox and thread
+-- views can find the message.
+
+CREATE TABLE IF NOT EXISTS cross_project_recipients (
+    message_id INTEGER NOT NULL,
+    agent_id INTEGER NOT NULL,
+    project_id INTEGER NOT NULL,
+    PRIMARY KEY (message_id, agent_id),
+    FOREIGN KEY (message_id) REFERENCES messages(id),
+    FOREIGN KEY (agent_id) REFERENCES agents(id),
+    FOREIGN KEY (project_id) REFERENCES projects(id)
+);
+
+-- Thread views look messages up by the receiving project
+CREATE INDEX IF NOT EXISTS idx_cross_project_recipients_project
+    ON cross_project_recipients(project_id, message_id);
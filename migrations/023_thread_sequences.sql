@@ -0,0 +1,54 @@
+-- Per-thread sequence numbers
+-- Every message gets the next number of its thread when it is created, so
+-- messages created within the same second keep a stable order. Threads are
+-- numbered across projects: a thread spanning projects has one sequence.
+
+CREATE TABLE IF NOT EXISTS thread_counters (
+    thread_id TEXT PRIMARY KEY,
+    last_seq INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS message_thread_seqs (
+    message_id INTEGER PRIMARY KEY,
+    seq INTEGER NOT NULL,
+    FOREIGN KEY (message_id) REFERENCES messages(id)
+);
+
+-- Number messages that predate this migration in (created_ts, id) order.
+-- Runs only while no message has a number, so restarts leave it alone.
+INSERT INTO message_thread_seqs (message_id, seq)
+SELECT id, ROW_NUMBER() OVER (PARTITION BY thread_id ORDER BY created_ts, id)
+FROM messages
+WHERE thread_id IS NOT NULL
+  AND NOT EXISTS (SELECT 1 FROM message_thread_seqs);
+
+INSERT OR IGNORE INTO thread_counters (thread_id, last_seq)
+SELECT m.thread_id, MAX(s.seq)
+FROM message_thread_seqs AS s
+JOIN messages AS m ON m.id = s.message_id
+GROUP BY m.thread_id;
+
+-- Rebuild visible_messages (from 010) with the sequence number.
+-- Dropping first keeps this idempotent across restarts.
+DROP VIEW IF EXISTS visible_messages;
+
+CREATE VIEW visible_messages AS
+SELECT
+    m.id,
+    m.project_id,
+    m.sender_id,
+    m.thread_id,
+    CASE WHEN r.message_id IS NULL THEN m.subject ELSE '[Recalled] ' || m.subject END AS subject,
+    CASE WHEN r.message_id IS NULL THEN m.body_md ELSE r.recall_reason END AS body_md,
+    m.importance,
+    m.ack_required,
+    m.created_ts,
+    CASE WHEN r.message_id IS NULL THEN m.attachments ELSE '[]' END AS attachments,
+    r.recalled_ts,
+    r.recall_reason,
+    ts.seq AS thread_seq
+FROM messages AS m
+LEFT JOIN message_recalls AS r ON r.message_id = m.id
+LEFT JOIN message_schedules AS s ON s.message_id = m.id
+LEFT JOIN message_thread_seqs AS ts ON ts.message_id = m.id
+WHERE s.message_id IS NULL OR s.status = 'delivered';
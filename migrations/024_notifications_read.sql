@@ -0,0 +1,11 @@
+-- Dismissed notifications
+-- The notification center derives its entries from messages and
+-- reservations on every request. Dismissing one records its key here so
+-- it stays hidden for that actor; nothing else changes.
+
+CREATE TABLE IF NOT EXISTS notifications_read (
+    actor TEXT NOT NULL,
+    notification_key TEXT NOT NULL,
+    read_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (actor, notification_key)
+);
@@ -0,0 +1,11 @@
+-- Per-project structure versions
+-- Adopting or deleting a project, renaming or retiring an agent and
+-- pruning messages bump the project's version so clients holding cached
+-- lists know to refetch. Keyed by slug so a deleted project's version
+-- outlives the project row.
+
+CREATE TABLE IF NOT EXISTS project_versions (
+    slug TEXT PRIMARY KEY,
+    version INTEGER NOT NULL DEFAULT 0,
+    updated_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
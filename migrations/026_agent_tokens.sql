@@ -0,0 +1,15 @@
+-- Agent identity tokens
+-- Per-agent API keys. A request carrying `Authorization: AgentKey <token>`
+-- acts as the token's agent and can't send or reserve as anyone else.
+-- Only the SHA-256 of the secret is stored; revoking sets revoked_ts.
+
+CREATE TABLE IF NOT EXISTS agent_tokens (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    agent_id INTEGER NOT NULL REFERENCES agents(id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    created_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_used_ts DATETIME,
+    revoked_ts DATETIME
+);
+
+CREATE INDEX IF NOT EXISTS idx_agent_tokens_agent ON agent_tokens(agent_id);
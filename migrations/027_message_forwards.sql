@@ -0,0 +1,39 @@
+-- Message forwarding provenance
+-- A forwarded message is a new message quoting the original; this table
+-- links it back. forwarded_from_id has no foreign key so a forward keeps its
+-- provenance after the original is pruned or deleted.
+
+CREATE TABLE IF NOT EXISTS message_forwards (
+    message_id INTEGER PRIMARY KEY,
+    forwarded_from_id INTEGER NOT NULL,
+    FOREIGN KEY (message_id) REFERENCES messages(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_message_forwards_from ON message_forwards(forwarded_from_id);
+
+-- Rebuild visible_messages (from 023) with the forward link.
+-- Dropping first keeps this idempotent across restarts.
+DROP VIEW IF EXISTS visible_messages;
+
+CREATE VIEW visible_messages AS
+SELECT
+    m.id,
+    m.project_id,
+    m.sender_id,
+    m.thread_id,
+    CASE WHEN r.message_id IS NULL THEN m.subject ELSE '[Recalled] ' || m.subject END AS subject,
+    CASE WHEN r.message_id IS NULL THEN m.body_md ELSE r.recall_reason END AS body_md,
+    m.importance,
+    m.ack_required,
+    m.created_ts,
+    CASE WHEN r.message_id IS NULL THEN m.attachments ELSE '[]' END AS attachments,
+    r.recalled_ts,
+    r.recall_reason,
+    ts.seq AS thread_seq,
+    fw.forwarded_from_id
+FROM messages AS m
+LEFT JOIN message_recalls AS r ON r.message_id = m.id
+LEFT JOIN message_schedules AS s ON s.message_id = m.id
+LEFT JOIN message_thread_seqs AS ts ON ts.message_id = m.id
+LEFT JOIN message_forwards AS fw ON fw.message_id = m.id
+WHERE s.message_id IS NULL OR s.status = 'delivered';
@@ -0,0 +1,13 @@
+-- Reservation ownership transfers
+-- Handing a reservation to another agent (e.g. when its holder crashed)
+-- reassigns file_reservations.agent_id and records the previous holder here.
+-- One row per reservation: a later transfer replaces it, and the audit log
+-- keeps the full history.
+
+CREATE TABLE IF NOT EXISTS reservation_transfers (
+    reservation_id INTEGER PRIMARY KEY,
+    transferred_from INTEGER NOT NULL,
+    transfer_reason TEXT NOT NULL DEFAULT '',
+    transferred_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (reservation_id) REFERENCES file_reservations(id)
+);
@@ -0,0 +1,16 @@
+-- Indexes for the unified inbox sort orders
+-- The per-project index carries the id tiebreak so a single-project page is
+-- read in order. The importance index uses the same rank expression as the
+-- importance_desc sort, so that sort walks the index instead of sorting
+-- every message. message_recipients(agent_id, message_id) is already
+-- covered by idx_message_recipients_agent (006).
+
+CREATE INDEX IF NOT EXISTS idx_messages_project_created_id
+    ON messages(project_id, created_ts DESC, id DESC);
+
+CREATE INDEX IF NOT EXISTS idx_messages_importance_rank
+    ON messages(
+        (CASE importance WHEN 'urgent' THEN 3 WHEN 'high' THEN 2 WHEN 'normal' THEN 1 ELSE 0 END) DESC,
+        created_ts DESC,
+        id DESC
+    );
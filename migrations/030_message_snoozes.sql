@@ -0,0 +1,19 @@
+-- Per-recipient inbox snooze
+-- A snoozed message leaves that recipient's inbox until snoozed_until, then
+-- comes back flagged as returned from snooze. The row goes when the
+-- recipient unsnoozes or reads the message. Other recipients and the thread
+-- view never look at this table.
+
+CREATE TABLE IF NOT EXISTS message_snoozes (
+    message_id INTEGER NOT NULL,
+    agent_id INTEGER NOT NULL,
+    snoozed_until DATETIME NOT NULL,
+    snoozed_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (message_id, agent_id),
+    FOREIGN KEY (message_id) REFERENCES messages(id),
+    FOREIGN KEY (agent_id) REFERENCES agents(id)
+);
+
+-- Listing an agent's snoozed messages
+CREATE INDEX IF NOT EXISTS idx_message_snoozes_agent
+    ON message_snoozes(agent_id, snoozed_until);
@@ -0,0 +1,37 @@
+-- Agent groups ("frontend", "reviewers", ...)
+-- Groups are defined per project; names are unique per project ignoring
+-- case. A message addressed to "group:<name>" goes to the group's members
+-- at send time, and message_group_expansions records who that was, so
+-- later membership changes never rewrite history.
+
+CREATE TABLE IF NOT EXISTS agent_groups (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    project_id INTEGER NOT NULL,
+    name TEXT NOT NULL COLLATE NOCASE,
+    created_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (project_id) REFERENCES projects(id),
+    UNIQUE (project_id, name)
+);
+
+CREATE TABLE IF NOT EXISTS agent_group_members (
+    group_id INTEGER NOT NULL,
+    agent_id INTEGER NOT NULL,
+    added_ts DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (group_id, agent_id),
+    FOREIGN KEY (group_id) REFERENCES agent_groups(id) ON DELETE CASCADE,
+    FOREIGN KEY (agent_id) REFERENCES agents(id)
+);
+
+-- Removing an agent from every group it belongs to
+CREATE INDEX IF NOT EXISTS idx_agent_group_members_agent
+    ON agent_group_members(agent_id);
+
+-- Keyed by group name rather than ID so the record outlives the group
+CREATE TABLE IF NOT EXISTS message_group_expansions (
+    message_id INTEGER NOT NULL,
+    group_name TEXT NOT NULL,
+    agent_id INTEGER NOT NULL,
+    PRIMARY KEY (message_id, group_name, agent_id),
+    FOREIGN KEY (message_id) REFERENCES messages(id),
+    FOREIGN KEY (agent_id) REFERENCES agents(id)
+);
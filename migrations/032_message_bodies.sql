@@ -0,0 +1,87 @@
+-- Message bodies move out of the hot messages table
+-- Inbox, unified inbox and search listings only need a preview, so messages
+-- keeps a 200-character snippet and the full body lives in message_bodies,
+-- which also backs the full-text index. body_hash is the hex SHA-256 of the
+-- body; apply_migrations fills it in for the rows backfilled here.
+--
+-- Runs once: apply_migrations skips migrations the database has already
+-- recorded in user_version, and runs each one in a transaction.
+
+-- Everything that reads messages.body_md goes first
+DROP TRIGGER IF EXISTS messages_ai;
+DROP TRIGGER IF EXISTS messages_ad;
+DROP TRIGGER IF EXISTS messages_au;
+DROP VIEW IF EXISTS visible_messages;
+
+CREATE TABLE message_bodies (
+    message_id INTEGER PRIMARY KEY,
+    body_md TEXT NOT NULL,
+    body_hash TEXT NOT NULL DEFAULT '',
+    FOREIGN KEY (message_id) REFERENCES messages(id)
+);
+
+INSERT INTO message_bodies (message_id, body_md)
+SELECT id, body_md FROM messages;
+
+-- substr counts characters, so a snippet never ends mid-codepoint
+ALTER TABLE messages ADD COLUMN snippet TEXT NOT NULL DEFAULT '';
+UPDATE messages SET snippet = substr(body_md, 1, 200);
+
+ALTER TABLE messages DROP COLUMN body_md;
+
+-- Re-point the full-text index at message_bodies
+DROP TABLE IF EXISTS messages_fts;
+
+CREATE VIRTUAL TABLE messages_fts USING fts5(
+    body_md,
+    content='message_bodies',
+    content_rowid='message_id'
+);
+
+INSERT INTO messages_fts(messages_fts) VALUES ('rebuild');
+
+CREATE TRIGGER message_bodies_ai AFTER INSERT ON message_bodies BEGIN
+  INSERT INTO messages_fts(rowid, body_md) VALUES (new.message_id, new.body_md);
+END;
+
+CREATE TRIGGER message_bodies_ad AFTER DELETE ON message_bodies BEGIN
+  INSERT INTO messages_fts(messages_fts, rowid, body_md) VALUES('delete', old.message_id, old.body_md);
+END;
+
+CREATE TRIGGER message_bodies_au AFTER UPDATE ON message_bodies BEGIN
+  INSERT INTO messages_fts(messages_fts, rowid, body_md) VALUES('delete', old.message_id, old.body_md);
+  INSERT INTO messages_fts(rowid, body_md) VALUES (new.message_id, new.body_md);
+END;
+
+-- Deleting a message deletes its body, and with it the index entry
+CREATE TRIGGER messages_body_ad AFTER DELETE ON messages BEGIN
+  DELETE FROM message_bodies WHERE message_id = old.id;
+END;
+
+-- Rebuild visible_messages (from 027) with the body from message_bodies.
+-- A query that reads only the snippet never touches message_bodies: the
+-- LEFT JOIN on its primary key is dropped when unused.
+CREATE VIEW visible_messages AS
+SELECT
+    m.id,
+    m.project_id,
+    m.sender_id,
+    m.thread_id,
+    CASE WHEN r.message_id IS NULL THEN m.subject ELSE '[Recalled] ' || m.subject END AS subject,
+    CASE WHEN r.message_id IS NULL THEN b.body_md ELSE r.recall_reason END AS body_md,
+    CASE WHEN r.message_id IS NULL THEN m.snippet ELSE substr(r.recall_reason, 1, 200) END AS snippet,
+    m.importance,
+    m.ack_required,
+    m.created_ts,
+    CASE WHEN r.message_id IS NULL THEN m.attachments ELSE '[]' END AS attachments,
+    r.recalled_ts,
+    r.recall_reason,
+    ts.seq AS thread_seq,
+    fw.forwarded_from_id
+FROM messages AS m
+LEFT JOIN message_bodies AS b ON b.message_id = m.id
+LEFT JOIN message_recalls AS r ON r.message_id = m.id
+LEFT JOIN message_schedules AS s ON s.message_id = m.id
+LEFT JOIN message_thread_seqs AS ts ON ts.message_id = m.id
+LEFT JOIN message_forwards AS fw ON fw.message_id = m.id
+WHERE s.message_id IS NULL OR s.status = 'delivered';
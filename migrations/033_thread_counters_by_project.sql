@@ -0,0 +1,25 @@
+-- Thread counters per project
+-- 023 keyed thread_counters on thread_id alone, so two projects using the
+-- same thread id (e.g. one derived from the same subject) shared a counter
+-- and each saw gaps in its numbering. Counters are now kept per project and
+-- seeded from the highest number each project's messages already carry.
+--
+-- Runs once: apply_migrations skips migrations the database has already
+-- recorded in user_version, and runs each one in a transaction.
+
+CREATE TABLE thread_counters_by_project (
+    project_id INTEGER NOT NULL,
+    thread_id TEXT NOT NULL,
+    last_seq INTEGER NOT NULL,
+    PRIMARY KEY (project_id, thread_id)
+);
+
+INSERT INTO thread_counters_by_project (project_id, thread_id, last_seq)
+SELECT m.project_id, m.thread_id, MAX(s.seq)
+FROM message_thread_seqs AS s
+JOIN messages AS m ON m.id = s.message_id
+WHERE m.thread_id IS NOT NULL
+GROUP BY m.project_id, m.thread_id;
+
+DROP TABLE thread_counters;
+ALTER TABLE thread_counters_by_project RENAME TO thread_counters;